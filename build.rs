@@ -0,0 +1,47 @@
+//! 构建信息：git 提交与构建时间（供 `build_info` 模块使用）
+//!
+//! CI 可通过 `FLARE_GIT_SHA` / `FLARE_BUILD_TIMESTAMP`（Unix 秒）覆盖，
+//! 否则从本地 git 仓库与当前时间获取
+
+use std::env;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=FLARE_GIT_SHA");
+    println!("cargo:rerun-if-env-changed=FLARE_BUILD_TIMESTAMP");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+
+    let git_sha = env::var("FLARE_GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| {
+            let sha = git(&["rev-parse", "--short=12", "HEAD"])?;
+            let dirty = git(&["status", "--porcelain", "--untracked-files=no"])
+                .is_some_and(|status| !status.is_empty());
+            Some(if dirty { format!("{sha}-dirty") } else { sha })
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let build_timestamp = env::var("FLARE_BUILD_TIMESTAMP")
+        .ok()
+        .and_then(|ts| ts.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        });
+
+    println!("cargo:rustc-env=FLARE_GIT_SHA={git_sha}");
+    println!("cargo:rustc-env=FLARE_BUILD_TIMESTAMP={build_timestamp}");
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
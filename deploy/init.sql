@@ -594,34 +594,28 @@ CALL add_columnstore_policy('messages', after => INTERVAL '30 days');
 -- COMMENT: ACK归档记录表，用于审计和分析的ACK日志归档
 DROP TABLE IF EXISTS ack_archive_records CASCADE;
 CREATE TABLE ack_archive_records (
-    id BIGSERIAL PRIMARY KEY,
     message_id TEXT NOT NULL,
     user_id TEXT NOT NULL,
-    ack_type TEXT NOT NULL,
+    ack_type TEXT,
     ack_status TEXT NOT NULL,
-    timestamp BIGINT NOT NULL,
-    importance_level SMALLINT DEFAULT 1 CHECK (importance_level BETWEEN 1 AND 3),
-    metadata JSONB,
-    archived_at BIGINT NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())
-);
+    importance TEXT NOT NULL,
+    acked_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (message_id, user_id, ack_status, acked_at)
+) PARTITION BY RANGE (acked_at);
 
-COMMENT ON TABLE ack_archive_records IS 'ACK归档记录表（用于审计和分析的ACK日志归档）';
-COMMENT ON COLUMN ack_archive_records.id IS '记录ID（自增主键）';
+COMMENT ON TABLE ack_archive_records IS 'ACK归档记录表（终态ACK，按 acked_at 按天分区，用于合规查询）';
 COMMENT ON COLUMN ack_archive_records.message_id IS '消息ID';
 COMMENT ON COLUMN ack_archive_records.user_id IS '用户ID';
-COMMENT ON COLUMN ack_archive_records.ack_type IS 'ACK类型';
-COMMENT ON COLUMN ack_archive_records.ack_status IS 'ACK状态';
-COMMENT ON COLUMN ack_archive_records.timestamp IS '时间戳';
-COMMENT ON COLUMN ack_archive_records.importance_level IS '重要性等级：1-低，2-中，3-高';
-COMMENT ON COLUMN ack_archive_records.metadata IS '元数据';
-COMMENT ON COLUMN ack_archive_records.archived_at IS '归档时间';
+COMMENT ON COLUMN ack_archive_records.ack_type IS 'ACK类型（transport, server, delivery, storage）';
+COMMENT ON COLUMN ack_archive_records.ack_status IS 'ACK状态（received, processed, failed）';
+COMMENT ON COLUMN ack_archive_records.importance IS '重要性等级（high, medium, low）';
+COMMENT ON COLUMN ack_archive_records.acked_at IS '确认时间（分区键，日分区 ack_archive_records_YYYYMMDD 由归档任务按需创建）';
+COMMENT ON COLUMN ack_archive_records.archived_at IS '归档写入时间';
 
 -- ACK归档记录表索引
-CREATE INDEX IF NOT EXISTS idx_ack_archive_message_id ON ack_archive_records (message_id);
-CREATE INDEX IF NOT EXISTS idx_ack_archive_user_id ON ack_archive_records (user_id);
-CREATE INDEX IF NOT EXISTS idx_ack_archive_timestamp_desc ON ack_archive_records (timestamp DESC);
-CREATE INDEX IF NOT EXISTS idx_ack_archive_importance_level ON ack_archive_records (importance_level);
-CREATE INDEX IF NOT EXISTS idx_ack_archive_message_user_type ON ack_archive_records (message_id, user_id, ack_type);
+CREATE INDEX IF NOT EXISTS idx_ack_archive_user_acked_at ON ack_archive_records (user_id, acked_at DESC);
+CREATE INDEX IF NOT EXISTS idx_ack_archive_acked_at ON ack_archive_records (acked_at DESC);
 
 -- 消息可靠性保障表（MessageReliability）
 -- COMMENT: 消息可靠性保障表，用于跟踪消息的发送、确认和重试状态
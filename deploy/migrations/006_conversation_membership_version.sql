@@ -0,0 +1,30 @@
+-- 迁移：会话成员版本与变更日志
+-- 日期: 2025-01-XX
+-- 说明: 为会话成员添加单调递增的版本号，并记录每次成员变更，
+--       支持成员快照导出（GetParticipantsSnapshot）与增量同步（GetParticipantsDiff）
+
+-- 会话成员版本号（每次成员变更递增）
+ALTER TABLE conversations ADD COLUMN IF NOT EXISTS membership_version BIGINT NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN conversations.membership_version IS '成员版本号（每次成员变更递增，用于增量同步）';
+
+-- 会话成员变更日志
+-- COMMENT: 记录每个成员版本中发生的成员变更（加入/移除/更新）
+DROP TABLE IF EXISTS conversation_membership_changes CASCADE;
+CREATE TABLE conversation_membership_changes (
+    tenant_id TEXT NOT NULL,                   -- 租户ID（多租户支持）
+    conversation_id TEXT NOT NULL,             -- 会话ID
+    version BIGINT NOT NULL,                   -- 变更后的成员版本号
+    user_id TEXT NOT NULL,                     -- 变更的用户ID
+    change_type TEXT NOT NULL,                 -- 变更类型（added, removed, updated）
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (tenant_id, conversation_id, version, user_id),
+    FOREIGN KEY (tenant_id, conversation_id) REFERENCES conversations(tenant_id, conversation_id) ON DELETE CASCADE
+);
+
+COMMENT ON TABLE conversation_membership_changes IS '会话成员变更日志（用于成员增量同步）';
+COMMENT ON COLUMN conversation_membership_changes.version IS '变更后的成员版本号';
+COMMENT ON COLUMN conversation_membership_changes.change_type IS '变更类型（added: 加入, removed: 移除, updated: 角色/属性更新）';
+
+CREATE INDEX IF NOT EXISTS idx_conversation_membership_changes_created_at ON conversation_membership_changes(created_at);
@@ -0,0 +1,8 @@
+-- 迁移：Hook结果缓存配置
+-- 日期: 2025-01-XX
+-- 说明: 为幂等的PreSend校验类Hook（如敏感词检测）启用结果缓存，
+--       相同租户、相同消息内容的请求复用上一次的执行结果
+
+ALTER TABLE hook_configs ADD COLUMN IF NOT EXISTS cache_config JSONB;
+
+COMMENT ON COLUMN hook_configs.cache_config IS '结果缓存配置（JSON: {"ttl_ms": 60000, "max_entries": 10000}），为空表示不缓存，仅对PreSend Hook生效';
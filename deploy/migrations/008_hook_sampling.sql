@@ -0,0 +1,8 @@
+-- 迁移：Hook调用采样配置
+-- 日期: 2025-01-XX
+-- 说明: 按租户限速采集gRPC/WebHook Hook的请求与响应（脱敏后），
+--       用于排查Hook响应被拒绝、解析失败等接入问题
+
+ALTER TABLE hook_configs ADD COLUMN IF NOT EXISTS sampling_config JSONB;
+
+COMMENT ON COLUMN hook_configs.sampling_config IS '采样配置（JSON: {"per_minute": 10, "tenants": []}），为空表示不采样，仅对gRPC/WebHook Hook生效';
@@ -0,0 +1,7 @@
+-- 迁移：PostSend/Delivery Hook失败重试配置
+-- 日期: 2025-01-XX
+-- 说明: 按Hook配置退避重试（固定/线性/指数 + 抖动），重试耗尽后写入死信队列供后续重放
+
+ALTER TABLE hook_configs ADD COLUMN IF NOT EXISTS retry_config JSONB;
+
+COMMENT ON COLUMN hook_configs.retry_config IS '重试配置（JSON: {"max_retries": 3, "retry_interval_ms": 1000, "backoff_strategy": "exponential", "max_interval_ms": 30000, "jitter": 0.2}），为空表示不重试，仅对PostSend/Delivery Hook生效';
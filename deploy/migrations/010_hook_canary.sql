@@ -0,0 +1,7 @@
+-- 迁移：Hook金丝雀发布配置
+-- 日期: 2025-01-XX
+-- 说明: 新版本Hook按租户白名单/流量百分比灰度，两个版本的调用统计分开记录，对比错误率后再全量切换
+
+ALTER TABLE hook_configs ADD COLUMN IF NOT EXISTS canary_config JSONB;
+
+COMMENT ON COLUMN hook_configs.canary_config IS '金丝雀发布配置（JSON: {"version": "v2", "transport": {...}, "percentage": 10, "tenants": ["tenant-a"]}），为空表示不灰度，仅对gRPC/WebHook Hook生效';
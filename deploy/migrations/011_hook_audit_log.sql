@@ -0,0 +1,66 @@
+-- 迁移：Hook执行审计日志
+-- 日期: 2025-01-XX
+-- 说明: 记录每次Hook执行（Hook、租户、消息ID、决策、耗时、错误），用于排查"消息为什么被拒绝"
+--       表按天分区，Hook引擎后台任务提前创建分区并删除超出保留期的分区
+
+CREATE TABLE IF NOT EXISTS hook_audit_logs (
+    id BIGSERIAL,
+    executed_at TIMESTAMPTZ NOT NULL,
+    tenant_id VARCHAR(64),
+    hook_type VARCHAR(64) NOT NULL,
+    hook_name VARCHAR(255) NOT NULL,
+    message_id VARCHAR(255),
+    request_id VARCHAR(255) NOT NULL DEFAULT '',
+    decision VARCHAR(16) NOT NULL,
+    reason TEXT,
+    latency_ms BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (id, executed_at)
+) PARTITION BY RANGE (executed_at);
+
+-- 兜底分区：分区维护失败时写入不报错（存在落入某天的数据时无法再创建当天分区，需手工迁移）
+CREATE TABLE IF NOT EXISTS hook_audit_logs_default PARTITION OF hook_audit_logs DEFAULT;
+
+CREATE INDEX IF NOT EXISTS idx_hook_audit_logs_message ON hook_audit_logs (message_id, executed_at DESC);
+CREATE INDEX IF NOT EXISTS idx_hook_audit_logs_tenant ON hook_audit_logs (tenant_id, executed_at DESC);
+CREATE INDEX IF NOT EXISTS idx_hook_audit_logs_hook ON hook_audit_logs (hook_type, hook_name, executed_at DESC);
+
+COMMENT ON TABLE hook_audit_logs IS 'Hook执行审计日志（按天分区，分区名 hook_audit_logs_YYYYMMDD）';
+COMMENT ON COLUMN hook_audit_logs.hook_type IS 'Hook类型（pre_send, post_send, delivery, recall）';
+COMMENT ON COLUMN hook_audit_logs.decision IS '执行决策（continue: 放行/成功, reject: 拒绝, error: 执行出错）';
+COMMENT ON COLUMN hook_audit_logs.reason IS '拒绝原因或错误信息';
+
+-- 创建指定日期的分区（已存在时跳过）
+CREATE OR REPLACE FUNCTION create_hook_audit_partition(day DATE) RETURNS VOID AS $$
+BEGIN
+    EXECUTE format(
+        'CREATE TABLE IF NOT EXISTS %I PARTITION OF hook_audit_logs FOR VALUES FROM (%L) TO (%L)',
+        'hook_audit_logs_' || to_char(day, 'YYYYMMDD'),
+        day,
+        day + 1
+    );
+END;
+$$ LANGUAGE plpgsql;
+
+-- 删除早于指定日期的分区，返回删除的分区数
+CREATE OR REPLACE FUNCTION drop_hook_audit_partitions(before DATE) RETURNS INTEGER AS $$
+DECLARE
+    partition RECORD;
+    dropped INTEGER := 0;
+BEGIN
+    FOR partition IN
+        SELECT child.relname
+        FROM pg_inherits
+        JOIN pg_class parent ON parent.oid = pg_inherits.inhparent
+        JOIN pg_class child ON child.oid = pg_inherits.inhrelid
+        WHERE parent.relname = 'hook_audit_logs'
+          AND child.relname ~ '^hook_audit_logs_[0-9]{8}$'
+          AND child.relname < 'hook_audit_logs_' || to_char(before, 'YYYYMMDD')
+    LOOP
+        EXECUTE format('DROP TABLE IF EXISTS %I', partition.relname);
+        dropped := dropped + 1;
+    END LOOP;
+    RETURN dropped;
+END;
+$$ LANGUAGE plpgsql;
+
+SELECT create_hook_audit_partition(CURRENT_DATE + day) FROM generate_series(0, 3) AS day;
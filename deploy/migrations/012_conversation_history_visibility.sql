@@ -0,0 +1,18 @@
+-- 迁移：新成员历史消息可见性
+-- 日期: 2025-01-XX
+-- 说明: 会话通过属性 history_visibility 配置新成员能否查看入群前的历史消息（none / days:N / all），
+--       成员加入时计算可见边界 history_visible_from，Reader 查询消息时以该边界裁剪时间范围
+
+-- 先添加不带默认值的列，避免已有成员被填充为迁移执行时间；回填后再设置默认值
+ALTER TABLE conversation_participants ADD COLUMN IF NOT EXISTS joined_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE conversation_participants ADD COLUMN IF NOT EXISTS history_visible_from TIMESTAMP WITH TIME ZONE;
+
+-- 已有成员的加入时间以创建时间回填，历史消息保持全部可见
+UPDATE conversation_participants SET joined_at = created_at WHERE joined_at IS NULL;
+
+ALTER TABLE conversation_participants ALTER COLUMN joined_at SET DEFAULT CURRENT_TIMESTAMP;
+
+COMMENT ON COLUMN conversation_participants.joined_at IS '成员加入时间';
+COMMENT ON COLUMN conversation_participants.history_visible_from IS '历史消息可见起始时间（NULL 表示全部可见）';
+
+-- Reader 按 (tenant_id, conversation_id, user_id) 查询可见边界，直接命中成员表主键，无需额外索引
@@ -0,0 +1,46 @@
+-- 迁移：用户账号合并任务
+-- 日期: 2025-01-XX
+-- 说明: 两个用户账号合并（手机号/邮箱统一）时，由会话服务后台任务将源用户的会话参与关系改写到目标用户，
+--       合并同步游标与未读状态，并为下游系统记录会话映射。任务按会话ID游标分批推进，中断后可续跑。
+--       管理工具插入 status = 'pending' 的任务记录即可提交合并。
+
+-- 用户合并任务
+-- COMMENT: 记录每个合并任务的状态与进度（以最后处理的会话ID作为游标）
+DROP TABLE IF EXISTS user_merge_jobs CASCADE;
+CREATE TABLE user_merge_jobs (
+    job_id TEXT PRIMARY KEY,
+    tenant_id TEXT NOT NULL,                   -- 租户ID（多租户支持）
+    source_user_id TEXT NOT NULL,              -- 被合并的用户ID
+    target_user_id TEXT NOT NULL,              -- 合并后保留的用户ID
+    status TEXT NOT NULL DEFAULT 'pending',    -- 任务状态（pending, running, completed, failed）
+    last_conversation_id TEXT,                 -- 最后处理的会话ID（续跑起点）
+    processed_count BIGINT NOT NULL DEFAULT 0, -- 已处理的会话数
+    last_error TEXT,                           -- 最近一次失败原因
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMENT ON TABLE user_merge_jobs IS '用户账号合并任务（会话参与关系迁移）';
+COMMENT ON COLUMN user_merge_jobs.status IS '任务状态（pending: 待执行, running: 执行中, completed: 已完成, failed: 失败）';
+COMMENT ON COLUMN user_merge_jobs.last_conversation_id IS '最后处理的会话ID（任务中断后从此处续跑）';
+
+CREATE INDEX IF NOT EXISTS idx_user_merge_jobs_status ON user_merge_jobs(status, created_at);
+
+-- 用户合并会话映射
+-- COMMENT: 合并过程中每个会话的处理结果，供下游系统（消息存储、搜索等）按映射迁移数据
+DROP TABLE IF EXISTS user_merge_mappings CASCADE;
+CREATE TABLE user_merge_mappings (
+    job_id TEXT NOT NULL,
+    tenant_id TEXT NOT NULL,
+    conversation_id TEXT NOT NULL,             -- 源用户参与的会话ID
+    action TEXT NOT NULL,                      -- 处理方式（rekeyed, combined, merged）
+    merged_into TEXT,                          -- 单聊合并时并入的会话ID
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (job_id, conversation_id),
+    FOREIGN KEY (job_id) REFERENCES user_merge_jobs(job_id) ON DELETE CASCADE
+);
+
+COMMENT ON TABLE user_merge_mappings IS '用户合并会话映射（供下游系统迁移数据）';
+COMMENT ON COLUMN user_merge_mappings.action IS '处理方式（rekeyed: 参与关系改写为目标用户, combined: 目标用户已在会话中并合并状态, merged: 单聊并入目标用户已有单聊）';
+COMMENT ON COLUMN user_merge_mappings.merged_into IS '单聊合并时并入的会话ID';
@@ -0,0 +1,30 @@
+-- 迁移：Hook配置版本历史与回滚
+-- 日期: 2025-01-XX
+-- 说明: 每次创建/更新/启停/回滚Hook配置时递增 config_revision 并保存完整配置快照，
+--       支持按版本回滚；审计日志记录执行时使用的配置版本，便于将故障与配置变更关联
+
+ALTER TABLE hook_configs ADD COLUMN IF NOT EXISTS config_revision BIGINT NOT NULL DEFAULT 1;
+
+CREATE TABLE IF NOT EXISTS hook_config_versions (
+    id BIGSERIAL PRIMARY KEY,
+    hook_config_id BIGINT NOT NULL REFERENCES hook_configs(id) ON DELETE CASCADE,
+    revision BIGINT NOT NULL,
+    tenant_id VARCHAR(64),
+    hook_type VARCHAR(64) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    config JSONB NOT NULL,
+    change_type VARCHAR(16) NOT NULL,
+    created_by VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (hook_config_id, revision)
+);
+
+CREATE INDEX IF NOT EXISTS idx_hook_config_versions_hook ON hook_config_versions (hook_config_id, revision DESC);
+
+ALTER TABLE hook_audit_logs ADD COLUMN IF NOT EXISTS config_revision BIGINT;
+
+COMMENT ON COLUMN hook_configs.config_revision IS '配置版本号，每次变更递增';
+COMMENT ON TABLE hook_config_versions IS 'Hook配置版本历史（每个版本保存完整配置快照）';
+COMMENT ON COLUMN hook_config_versions.config IS '配置快照（HookConfigItem JSON）';
+COMMENT ON COLUMN hook_config_versions.change_type IS '变更类型（create, update, status, rollback）';
+COMMENT ON COLUMN hook_audit_logs.config_revision IS '执行时使用的Hook配置版本（文件配置为空）';
@@ -0,0 +1,20 @@
+-- 迁移：推送受众人群包
+-- 日期: 2025-01-XX
+-- 说明: Push Proxy 支持按受众选择器（标签、人群包、会话角色）推送。标签存储于 Redis Set
+--       （{prefix}:{tenant_id}:tag:{tag}），人群包成员存储于本表，由租户业务系统批量维护；
+--       Push Proxy 入队前按 user_id 键集分页读取并展开为具体用户列表。
+
+-- 受众人群包成员
+-- COMMENT: 每行表示某租户人群包中的一个用户
+DROP TABLE IF EXISTS push_audience_segments CASCADE;
+CREATE TABLE push_audience_segments (
+    tenant_id TEXT NOT NULL,                   -- 租户ID（多租户支持）
+    segment TEXT NOT NULL,                     -- 人群包名称
+    user_id TEXT NOT NULL,                     -- 用户ID
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (tenant_id, segment, user_id)
+);
+
+COMMENT ON TABLE push_audience_segments IS '推送受众人群包成员（Push Proxy 受众选择器）';
+COMMENT ON COLUMN push_audience_segments.segment IS '人群包名称（对应 AudienceSelector.segments）';
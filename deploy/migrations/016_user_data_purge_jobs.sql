@@ -0,0 +1,54 @@
+-- 迁移：用户数据删除（被遗忘权）任务
+-- 日期: 2025-01-XX
+-- 说明: 用户行使被遗忘权时，由 Storage Reader 后台任务按步骤清除该用户在各存储中的数据：
+--       发送的消息（完全删除或匿名化发送者）、已读记录与私有消息状态、同步游标、ACK 历史、媒体引用，
+--       并同步失效 Redis 中的消息缓存。每步分批执行并持久化进度，中断后可续跑；
+--       每个步骤的处理结果写入审计表，任务完成后 report 字段即为完成报告。
+
+-- 用户数据删除任务
+-- COMMENT: 记录每个删除任务的状态、当前步骤与各存储的处理计数
+DROP TABLE IF EXISTS user_purge_jobs CASCADE;
+CREATE TABLE user_purge_jobs (
+    job_id TEXT PRIMARY KEY,
+    tenant_id TEXT NOT NULL,                   -- 租户ID（多租户支持）
+    user_id TEXT NOT NULL,                     -- 被删除数据的用户ID
+    mode TEXT NOT NULL,                        -- 消息处理方式（delete, anonymize）
+    status TEXT NOT NULL DEFAULT 'pending',    -- 任务状态（pending, running, completed, failed）
+    current_step TEXT NOT NULL,                -- 当前步骤（续跑起点）
+    report JSONB NOT NULL DEFAULT '{}'::jsonb, -- 各存储已处理的记录数（完成报告）
+    requested_by TEXT,                         -- 发起人（管理员ID或工单号）
+    last_error TEXT,                           -- 最近一次失败原因
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP WITH TIME ZONE      -- 完成时间
+);
+
+COMMENT ON TABLE user_purge_jobs IS '用户数据删除任务（被遗忘权）';
+COMMENT ON COLUMN user_purge_jobs.mode IS '消息处理方式（delete: 完全删除用户发送的消息, anonymize: 保留消息内容并匿名化发送者）';
+COMMENT ON COLUMN user_purge_jobs.status IS '任务状态（pending: 待执行, running: 执行中, completed: 已完成, failed: 失败）';
+COMMENT ON COLUMN user_purge_jobs.current_step IS '当前步骤（messages, read_records, cursors, ack_records, media_references, done）';
+COMMENT ON COLUMN user_purge_jobs.report IS '各存储已处理的记录数（JSON，键为步骤名）';
+
+CREATE INDEX IF NOT EXISTS idx_user_purge_jobs_status ON user_purge_jobs(status, created_at);
+CREATE INDEX IF NOT EXISTS idx_user_purge_jobs_tenant_user ON user_purge_jobs(tenant_id, user_id);
+
+-- 用户数据删除审计日志
+-- COMMENT: 任务提交、每个步骤完成、任务完成或失败时各记录一条，任务记录删除后仍保留
+DROP TABLE IF EXISTS user_purge_audit_logs CASCADE;
+CREATE TABLE user_purge_audit_logs (
+    id BIGSERIAL PRIMARY KEY,
+    job_id TEXT NOT NULL,
+    tenant_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    action TEXT NOT NULL,                      -- 审计动作（submitted, step_completed, completed, failed, retried）
+    step TEXT,                                 -- 关联的步骤（步骤完成时）
+    affected_count BIGINT NOT NULL DEFAULT 0,  -- 该步骤处理的记录数
+    detail TEXT,                               -- 附加说明（发起人、失败原因等）
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMENT ON TABLE user_purge_audit_logs IS '用户数据删除审计日志';
+COMMENT ON COLUMN user_purge_audit_logs.action IS '审计动作（submitted: 提交, step_completed: 步骤完成, completed: 任务完成, failed: 任务失败, retried: 重新执行）';
+
+CREATE INDEX IF NOT EXISTS idx_user_purge_audit_logs_job ON user_purge_audit_logs(job_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_user_purge_audit_logs_tenant_user ON user_purge_audit_logs(tenant_id, user_id, created_at DESC);
@@ -0,0 +1,7 @@
+-- 迁移：Hook并发上限
+-- 日期: 2025-01-XX
+-- 说明: 单个Hook的最大在途执行数，防止单个慢Hook或单租户突发流量占满Hook引擎的执行容量
+
+ALTER TABLE hook_configs ADD COLUMN IF NOT EXISTS max_concurrency INTEGER;
+
+COMMENT ON COLUMN hook_configs.max_concurrency IS '最大在途执行数，为空表示使用Hook引擎的默认单Hook上限（HOOK_ENGINE_HOOK_MAX_IN_FLIGHT）';
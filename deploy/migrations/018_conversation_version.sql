@@ -0,0 +1,7 @@
+-- 迁移：会话版本号
+-- 日期: 2025-01-XX
+-- 说明: 会话更新采用乐观并发控制，每次更新递增版本号，携带的期望版本与当前版本不一致时拒绝更新
+
+ALTER TABLE conversations ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN conversations.version IS '会话版本号（每次更新或软删除时递增，用于乐观并发控制）';
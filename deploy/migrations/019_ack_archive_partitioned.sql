@@ -0,0 +1,63 @@
+-- 迁移：ACK归档表按天分区
+-- 日期: 2025-01-XX
+-- 说明: 终态ACK由 ACK 模块异步批量写入 ack_archive_records，按确认时间（acked_at）按天分区，
+--       日分区由归档任务写入前按需创建（ack_archive_records_YYYYMMDD），过期数据可直接 DROP 分区清理。
+--       原非分区表重命名为 ack_archive_records_legacy，其中的记录按天创建分区后回填到新表
+--       （timestamp / archived_at 为 Unix 秒，importance_level 3/2/1 对应 high/medium/low），
+--       回填后旧表仅用于核对，确认无误后可 DROP。
+
+ALTER TABLE IF EXISTS ack_archive_records RENAME TO ack_archive_records_legacy;
+ALTER INDEX IF EXISTS ack_archive_records_pkey RENAME TO ack_archive_records_legacy_pkey;
+
+CREATE TABLE IF NOT EXISTS ack_archive_records (
+    message_id TEXT NOT NULL,                  -- 消息ID
+    user_id TEXT NOT NULL,                     -- 用户ID
+    ack_type TEXT,                             -- ACK类型（transport, server, delivery, storage）
+    ack_status TEXT NOT NULL,                  -- ACK状态（received, processed, failed）
+    importance TEXT NOT NULL,                  -- 重要性等级（high, medium, low）
+    acked_at TIMESTAMP WITH TIME ZONE NOT NULL,  -- 确认时间（分区键）
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (message_id, user_id, ack_status, acked_at)
+) PARTITION BY RANGE (acked_at);
+
+COMMENT ON TABLE ack_archive_records IS 'ACK归档记录表（终态ACK，按 acked_at 按天分区，用于合规查询）';
+COMMENT ON COLUMN ack_archive_records.acked_at IS '确认时间（分区键，日分区 ack_archive_records_YYYYMMDD 由归档任务按需创建）';
+COMMENT ON COLUMN ack_archive_records.archived_at IS '归档写入时间';
+
+CREATE INDEX IF NOT EXISTS idx_ack_archive_user_acked_at ON ack_archive_records (user_id, acked_at DESC);
+CREATE INDEX IF NOT EXISTS idx_ack_archive_acked_at ON ack_archive_records (acked_at DESC);
+
+-- 回填旧表记录：先为旧记录涉及的日期创建日分区，再写入新表
+DO $$
+DECLARE
+    day DATE;
+BEGIN
+    IF to_regclass('ack_archive_records_legacy') IS NULL THEN
+        RETURN;
+    END IF;
+
+    FOR day IN
+        SELECT DISTINCT (to_timestamp("timestamp") AT TIME ZONE 'UTC')::DATE
+        FROM ack_archive_records_legacy
+    LOOP
+        EXECUTE format(
+            'CREATE TABLE IF NOT EXISTS %I PARTITION OF ack_archive_records FOR VALUES FROM (%L) TO (%L)',
+            'ack_archive_records_' || to_char(day, 'YYYYMMDD'),
+            day::TEXT || ' 00:00:00+00',
+            (day + 1)::TEXT || ' 00:00:00+00'
+        );
+    END LOOP;
+
+    INSERT INTO ack_archive_records
+        (message_id, user_id, ack_type, ack_status, importance, acked_at, archived_at)
+    SELECT
+        message_id,
+        user_id,
+        ack_type,
+        ack_status,
+        CASE importance_level WHEN 3 THEN 'high' WHEN 2 THEN 'medium' ELSE 'low' END,
+        to_timestamp("timestamp"),
+        to_timestamp(archived_at)
+    FROM ack_archive_records_legacy
+    ON CONFLICT DO NOTHING;
+END $$;
@@ -0,0 +1,25 @@
+-- 迁移：Hook资源用量日统计
+-- 日期: 2025-01-XX
+-- 说明: 按天、租户、Hook聚合调用次数、出错次数、累计耗时和发往WebHook/gRPC目标的请求字节数，
+--       用于向租户分摊租户专属Hook的处理成本。Hook引擎定期将内存中聚合的增量累加到对应统计行
+
+CREATE TABLE IF NOT EXISTS hook_usage_daily (
+    usage_date DATE NOT NULL,
+    tenant_id VARCHAR(64) NOT NULL DEFAULT '0',
+    hook_type VARCHAR(64) NOT NULL,
+    hook_name VARCHAR(255) NOT NULL,
+    invocations BIGINT NOT NULL DEFAULT 0,
+    failures BIGINT NOT NULL DEFAULT 0,
+    total_latency_ms BIGINT NOT NULL DEFAULT 0,
+    egress_bytes BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (usage_date, tenant_id, hook_type, hook_name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_hook_usage_daily_tenant ON hook_usage_daily (tenant_id, usage_date DESC);
+
+COMMENT ON TABLE hook_usage_daily IS 'Hook资源用量日统计（UTC日期）';
+COMMENT ON COLUMN hook_usage_daily.tenant_id IS '租户ID（未携带租户的请求记为 0）';
+COMMENT ON COLUMN hook_usage_daily.hook_type IS 'Hook类型（pre_send, post_send, delivery, recall）';
+COMMENT ON COLUMN hook_usage_daily.failures IS '执行出错次数（含超时、熔断、并发受限）';
+COMMENT ON COLUMN hook_usage_daily.egress_bytes IS '发往WebHook/gRPC目标的请求字节数';
@@ -0,0 +1,18 @@
+-- 迁移：超大会话的近似消息计数
+-- 日期: 2025-01-XX
+-- 说明: 消息数超过精确计数阈值（STORAGE_MESSAGE_COUNT_EXACT_THRESHOLD）的会话由 Storage Reader 维护计数快照，
+--       每次查询只统计 max_seq 之后的新消息并增量累加；后台按校准间隔精确重算，消除删除、撤回造成的偏差。
+--       消息数回落到阈值以内的会话删除快照，恢复精确计数
+
+CREATE TABLE IF NOT EXISTS conversation_message_counts (
+    conversation_id TEXT PRIMARY KEY,
+    message_count BIGINT NOT NULL DEFAULT 0,       -- 消息数（估算值）
+    max_seq BIGINT NOT NULL DEFAULT 0,             -- 已计入 message_count 的最大 seq
+    reconciled_at TIMESTAMP WITH TIME ZONE NOT NULL, -- 最近一次精确校准时间
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+COMMENT ON TABLE conversation_message_counts IS '超大会话的消息计数快照（增量维护，定期校准）';
+COMMENT ON COLUMN conversation_message_counts.reconciled_at IS '最近一次精确校准时间（首次越过阈值时为 1970-01-01，等待后台校准）';
+
+CREATE INDEX IF NOT EXISTS idx_conversation_message_counts_reconciled_at ON conversation_message_counts(reconciled_at);
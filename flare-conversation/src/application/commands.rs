@@ -70,4 +70,19 @@ pub struct UpdateConversationCommand {
     pub attributes: Option<HashMap<String, String>>,
     pub visibility: Option<ConversationVisibility>,
     pub lifecycle_state: Option<ConversationLifecycleState>,
+    /// 期望的会话版本号（乐观并发控制，None 表示按最新版本更新）
+    pub expected_version: Option<i64>,
+}
+
+/// 用户账号合并命令（将 source 用户的会话参与关系迁移到 target 用户）
+#[derive(Debug, Clone)]
+pub struct MergeUsersCommand {
+    pub source_user_id: String,
+    pub target_user_id: String,
+}
+
+/// 重试失败的用户合并任务命令
+#[derive(Debug, Clone)]
+pub struct RetryUserMergeJobCommand {
+    pub job_id: String,
 }
@@ -6,14 +6,18 @@ use tracing::{debug, info};
 
 use crate::application::commands::{
     BatchAcknowledgeCommand, CreateConversationCommand, DeleteConversationCommand, ForceConversationSyncCommand,
-    ManageParticipantsCommand, UpdateCursorCommand, UpdatePresenceCommand, UpdateConversationCommand,
+    ManageParticipantsCommand, MergeUsersCommand, RetryUserMergeJobCommand, UpdateCursorCommand,
+    UpdatePresenceCommand, UpdateConversationCommand,
 };
 use crate::application::queries::{
-    ListConversationsQuery, SearchConversationsQuery, ConversationBootstrapQuery, SyncMessagesQuery,
+    GetParticipantsDiffQuery, GetParticipantsSnapshotQuery, ListConversationsQuery,
+    SearchConversationsQuery, ConversationBootstrapQuery, SyncMessagesQuery,
 };
+use crate::domain::model::UserMergeJob;
 use crate::domain::service::conversation_domain_service::{
-    ConversationBootstrapOutput, ConversationDomainService,
+    ConversationBootstrapOutput, ConversationDomainService, ConversationUpdate,
 };
+use crate::domain::service::UserMergeDomainService;
 
 /// 会话命令处理器
 pub struct ConversationCommandHandler {
@@ -221,10 +225,13 @@ impl ConversationCommandHandler {
             .update_conversation(
                 ctx,
                 &command.conversation_id,
-                command.display_name,
-                command.attributes,
-                command.visibility,
-                command.lifecycle_state,
+                ConversationUpdate {
+                    display_name: command.display_name,
+                    attributes: command.attributes,
+                    visibility: command.visibility,
+                    lifecycle_state: command.lifecycle_state,
+                },
+                command.expected_version,
             )
             .await?;
 
@@ -233,6 +240,61 @@ impl ConversationCommandHandler {
     }
 }
 
+/// 用户合并命令处理器（管理操作，合并任务由后台执行）
+pub struct UserMergeCommandHandler {
+    domain_service: Arc<UserMergeDomainService>,
+}
+
+impl UserMergeCommandHandler {
+    pub fn new(domain_service: Arc<UserMergeDomainService>) -> Self {
+        Self { domain_service }
+    }
+
+    /// 处理用户合并命令：创建合并任务，由后台任务执行
+    pub async fn handle_merge_users(
+        &self,
+        ctx: &Context,
+        command: MergeUsersCommand,
+    ) -> Result<UserMergeJob> {
+        let tenant_id = ctx.tenant_id().unwrap_or("0");
+
+        debug!(
+            tenant_id = %tenant_id,
+            source_user_id = %command.source_user_id,
+            target_user_id = %command.target_user_id,
+            "Handling merge users command"
+        );
+
+        self.domain_service
+            .submit(tenant_id, &command.source_user_id, &command.target_user_id)
+            .await
+    }
+
+    /// 处理重试用户合并任务命令
+    pub async fn handle_retry_user_merge_job(
+        &self,
+        _ctx: &Context,
+        command: RetryUserMergeJobCommand,
+    ) -> Result<()> {
+        self.domain_service.retry(&command.job_id).await?;
+
+        info!(job_id = %command.job_id, "User merge job scheduled for retry");
+        Ok(())
+    }
+
+    /// 后台轮询执行待执行或中断的合并任务
+    pub async fn run_pending_jobs(self: Arc<Self>, poll_interval: std::time::Duration) {
+        let mut interval = tokio::time::interval(poll_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.domain_service.resume_pending().await {
+                tracing::warn!(error = %err, "Failed to resume user merge jobs");
+            }
+        }
+    }
+}
+
 /// 会话查询处理器
 pub struct ConversationQueryHandler {
     domain_service: Arc<ConversationDomainService>,
@@ -359,4 +421,45 @@ impl ConversationQueryHandler {
 
         Ok(result)
     }
+
+    /// 处理成员快照查询
+    pub async fn handle_get_participants_snapshot(
+        &self,
+        ctx: &Context,
+        query: GetParticipantsSnapshotQuery,
+    ) -> Result<crate::domain::model::ParticipantsSnapshot> {
+        debug!(
+            conversation_id = %query.conversation_id,
+            cursor = ?query.cursor,
+            limit = query.limit,
+            "Handling get participants snapshot query"
+        );
+
+        self.domain_service
+            .get_participants_snapshot(
+                ctx,
+                &query.conversation_id,
+                query.cursor.as_deref(),
+                query.limit,
+            )
+            .await
+    }
+
+    /// 处理成员增量查询
+    pub async fn handle_get_participants_diff(
+        &self,
+        ctx: &Context,
+        query: GetParticipantsDiffQuery,
+    ) -> Result<crate::domain::model::ParticipantsDiff> {
+        debug!(
+            conversation_id = %query.conversation_id,
+            since_version = query.since_version,
+            limit = query.limit,
+            "Handling get participants diff query"
+        );
+
+        self.domain_service
+            .get_participants_diff(ctx, &query.conversation_id, query.since_version, query.limit)
+            .await
+    }
 }
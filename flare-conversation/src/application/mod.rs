@@ -2,4 +2,4 @@ pub mod commands;
 pub mod handlers;
 pub mod queries;
 
-pub use handlers::{ConversationCommandHandler, ConversationQueryHandler};
+pub use handlers::{ConversationCommandHandler, ConversationQueryHandler, UserMergeCommandHandler};
@@ -34,3 +34,19 @@ pub struct SyncMessagesQuery {
     pub cursor: Option<String>,
     pub limit: i32,
 }
+
+/// 成员快照查询
+#[derive(Debug, Clone)]
+pub struct GetParticipantsSnapshotQuery {
+    pub conversation_id: String,
+    pub cursor: Option<String>,
+    pub limit: i32,
+}
+
+/// 成员增量查询
+#[derive(Debug, Clone)]
+pub struct GetParticipantsDiffQuery {
+    pub conversation_id: String,
+    pub since_version: i64,
+    pub limit: i32,
+}
@@ -1,9 +1,11 @@
 use anyhow::Result;
 use flare_im_core::config::FlareAppConfig;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
 
-use crate::domain::model::{ConflictResolutionPolicy, ConversationPolicy};
+use crate::domain::model::{
+    ConflictResolutionPolicy, ConversationEventKind, ConversationPolicy, ReceiptsPolicy,
+};
 
 #[derive(Clone, Debug)]
 pub struct ConversationConfig {
@@ -16,6 +18,55 @@ pub struct ConversationConfig {
     pub storage_reader_service: Option<String>,
     pub recent_message_limit: i32,
     pub default_policy: ConversationPolicy,
+    /// Kafka 地址（可选，配置后发布用户合并映射事件）
+    pub kafka_bootstrap: Option<String>,
+    pub kafka_timeout_ms: u64,
+    /// 用户合并映射事件 Topic
+    pub user_merge_topic: String,
+    /// 用户合并任务轮询间隔（毫秒）
+    pub user_merge_poll_interval_ms: u64,
+    /// 会话生命周期事件 Webhook（可选）
+    pub webhook: Option<ConversationWebhookConfig>,
+    /// 多语言会话的消息翻译 Hook（可选）
+    pub translation: Option<TranslationHookConfig>,
+    /// Hook 配置（未配置时使用默认的 config/hooks.toml、config/hooks.d）
+    pub hook_config: Option<String>,
+    /// Hook 配置目录
+    pub hook_config_dir: Option<String>,
+    /// 更新会话时是否必须携带期望版本号（`x-expected-version`）
+    pub require_expected_version: bool,
+}
+
+/// 会话生命周期事件 Webhook 配置
+#[derive(Clone, Debug)]
+pub struct ConversationWebhookConfig {
+    pub url: String,
+    /// 签名密钥（HMAC-SHA256 签名请求体，见 `flare_im_core::hooks::signature`）
+    pub secret: Option<String>,
+    /// 轮换中的旧签名密钥（轮换期间同时携带新旧两个签名）
+    pub previous_secret: Option<String>,
+    /// 只推送这些业务类型的会话事件（为空表示全部）
+    pub business_types: HashSet<String>,
+    /// 只推送这些类型的事件（为空表示全部）
+    pub events: HashSet<ConversationEventKind>,
+    pub timeout_ms: u64,
+}
+
+/// 消息翻译 Hook 配置
+#[derive(Clone, Debug)]
+pub struct TranslationHookConfig {
+    pub url: String,
+    /// 签名密钥（HMAC-SHA256 签名请求体，见 `flare_im_core::hooks::signature`）
+    pub secret: Option<String>,
+    /// 轮换中的旧签名密钥（轮换期间同时携带新旧两个签名）
+    pub previous_secret: Option<String>,
+    pub timeout_ms: u64,
+    /// 单次同步最多翻译的消息数（其余消息在后续同步中补齐）
+    pub max_messages_per_sync: usize,
+    /// 单次同步同时调用翻译服务的最大请求数
+    pub concurrency: usize,
+    /// 单次同步等待译文的总时长（毫秒），超时未完成的消息不阻塞同步
+    pub sync_budget_ms: u64,
 }
 
 impl ConversationConfig {
@@ -112,6 +163,16 @@ impl ConversationConfig {
             .or_else(|| policy_cfg.and_then(|p| p.allow_history_sync))
             .unwrap_or(true);
 
+        let receipts = env::var("CONVERSATION_POLICY_RECEIPTS")
+            .ok()
+            .and_then(|s| ReceiptsPolicy::from_str(&s))
+            .or_else(|| {
+                policy_cfg
+                    .and_then(|p| p.receipts.as_ref())
+                    .and_then(|s| ReceiptsPolicy::from_str(s))
+            })
+            .unwrap_or_default();
+
         let mut policy_metadata = HashMap::new();
         if let Ok(raw) = env::var("CONVERSATION_POLICY_METADATA") {
             for kv in raw.split(',') {
@@ -126,9 +187,112 @@ impl ConversationConfig {
             max_devices,
             allow_anonymous,
             allow_history_sync,
+            receipts,
             metadata: policy_metadata,
         };
 
+        // 用户合并任务配置
+        let kafka_profile = service_config
+            .kafka
+            .as_ref()
+            .and_then(|kafka_name| app.kafka_profile(kafka_name));
+
+        let kafka_bootstrap = env::var("CONVERSATION_KAFKA_BOOTSTRAP_SERVERS")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .or_else(|| kafka_profile.map(|profile| profile.bootstrap_servers.clone()));
+
+        let kafka_timeout_ms = kafka_profile
+            .and_then(|profile| profile.timeout_ms)
+            .unwrap_or(5000);
+
+        let user_merge_topic = env::var("CONVERSATION_USER_MERGE_TOPIC")
+            .ok()
+            .or_else(|| service_config.user_merge_topic.clone())
+            .unwrap_or_else(|| "conversation-user-merge-events".to_string());
+
+        let user_merge_poll_interval_ms = env::var("CONVERSATION_USER_MERGE_POLL_INTERVAL_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(10_000);
+
+        // 会话生命周期事件 Webhook（未配置 URL 时不启用）
+        let webhook = env::var("CONVERSATION_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .map(|url| -> Result<ConversationWebhookConfig> {
+                let events = split_list("CONVERSATION_WEBHOOK_EVENTS")
+                    .into_iter()
+                    .map(|event| {
+                        ConversationEventKind::from_str(&event).ok_or_else(|| {
+                            anyhow::anyhow!("Unknown conversation webhook event: {}", event)
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(ConversationWebhookConfig {
+                    url,
+                    secret: env::var("CONVERSATION_WEBHOOK_SECRET")
+                        .ok()
+                        .filter(|s| !s.is_empty()),
+                    previous_secret: env::var("CONVERSATION_WEBHOOK_PREVIOUS_SECRET")
+                        .ok()
+                        .filter(|s| !s.is_empty()),
+                    business_types: split_list("CONVERSATION_WEBHOOK_BUSINESS_TYPES")
+                        .into_iter()
+                        .collect(),
+                    events,
+                    timeout_ms: env::var("CONVERSATION_WEBHOOK_TIMEOUT_MS")
+                        .ok()
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .unwrap_or(5000),
+                })
+            })
+            .transpose()?;
+
+        // 多语言会话的消息翻译 Hook（未配置 URL 时不翻译）
+        let translation = env::var("CONVERSATION_TRANSLATION_HOOK_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .map(|url| TranslationHookConfig {
+                url,
+                secret: env::var("CONVERSATION_TRANSLATION_HOOK_SECRET")
+                    .ok()
+                    .filter(|s| !s.is_empty()),
+                previous_secret: env::var("CONVERSATION_TRANSLATION_HOOK_PREVIOUS_SECRET")
+                    .ok()
+                    .filter(|s| !s.is_empty()),
+                timeout_ms: env::var("CONVERSATION_TRANSLATION_HOOK_TIMEOUT_MS")
+                    .ok()
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .unwrap_or(3000),
+                max_messages_per_sync: env::var("CONVERSATION_TRANSLATION_MAX_PER_SYNC")
+                    .ok()
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .unwrap_or(20),
+                concurrency: env::var("CONVERSATION_TRANSLATION_CONCURRENCY")
+                    .ok()
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .unwrap_or(4)
+                    .max(1),
+                sync_budget_ms: env::var("CONVERSATION_TRANSLATION_SYNC_BUDGET_MS")
+                    .ok()
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .unwrap_or(1000),
+            });
+
+        let hook_config = env::var("CONVERSATION_HOOKS_CONFIG")
+            .ok()
+            .or_else(|| service_config.hook_config.clone());
+
+        let hook_config_dir = env::var("CONVERSATION_HOOKS_CONFIG_DIR")
+            .ok()
+            .or_else(|| service_config.hook_config_dir.clone());
+
+        let require_expected_version = env::var("CONVERSATION_REQUIRE_EXPECTED_VERSION")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok())
+            .unwrap_or(false);
+
         Ok(Self {
             redis_url,
             postgres_url,
@@ -139,6 +303,27 @@ impl ConversationConfig {
             storage_reader_service,
             recent_message_limit,
             default_policy,
+            kafka_bootstrap,
+            kafka_timeout_ms,
+            user_merge_topic,
+            user_merge_poll_interval_ms,
+            webhook,
+            translation,
+            hook_config,
+            hook_config_dir,
+            require_expected_version,
         })
     }
 }
+
+/// 读取逗号分隔的环境变量列表（忽略空项）
+fn split_list(key: &str) -> Vec<String> {
+    env::var(key)
+        .map(|raw| {
+            raw.split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -1,12 +1,14 @@
 use chrono::{DateTime, TimeZone, Utc};
 use std::collections::HashMap;
 
+use flare_im_core::utils::translation::normalize_locale;
 use flare_proto::common::Message;
 use flare_proto::common::{
     ConflictResolution as ProtoConflictResolution, DeviceState as ProtoDeviceState,
     ConversationLifecycleState as ProtoConversationLifecycleState,
     ConversationVisibility as ProtoConversationVisibility,
 };
+use flare_proto::conversation::MembershipChangeType as ProtoMembershipChangeType;
 
 #[derive(Clone, Debug)]
 pub struct ConversationSummary {
@@ -22,6 +24,8 @@ pub struct ConversationSummary {
     pub metadata: HashMap<String, String>,
     pub server_cursor_ts: Option<i64>,
     pub display_name: Option<String>,
+    /// 会话当前版本号（更新会话时作为期望版本号，存储不支持时为 None）
+    pub version: Option<i64>,
 }
 
 #[derive(Clone, Debug)]
@@ -141,9 +145,123 @@ pub struct ConversationPolicy {
     pub max_devices: i32,
     pub allow_anonymous: bool,
     pub allow_history_sync: bool,
+    /// 默认回执策略（会话属性 `receipts_policy` 可覆盖）
+    pub receipts: ReceiptsPolicy,
     pub metadata: HashMap<String, String>,
 }
 
+/// 会话属性中配置回执策略的键（bootstrap 时会话 metadata 携带生效值）
+pub const RECEIPTS_POLICY_ATTRIBUTE: &str = "receipts_policy";
+
+/// 会话回执策略
+///
+/// 控制送达/已读回执是否记录并推送给发送者：`full`（默认）、`delivery_only`、`none`。
+/// 客户端据此隐藏已读（或送达）标识，例如匿名咨询会话配置为 `none`。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReceiptsPolicy {
+    /// 送达与已读回执
+    #[default]
+    Full,
+    /// 仅送达回执
+    DeliveryOnly,
+    /// 不产生回执
+    None,
+}
+
+impl ReceiptsPolicy {
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "full" => Some(Self::Full),
+            "delivery_only" | "delivery-only" => Some(Self::DeliveryOnly),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReceiptsPolicy::Full => "full",
+            ReceiptsPolicy::DeliveryOnly => "delivery_only",
+            ReceiptsPolicy::None => "none",
+        }
+    }
+
+    /// 是否允许送达回执
+    pub fn allows_delivery(&self) -> bool {
+        !matches!(self, ReceiptsPolicy::None)
+    }
+
+    /// 是否允许已读回执
+    pub fn allows_read(&self) -> bool {
+        matches!(self, ReceiptsPolicy::Full)
+    }
+
+    /// 从会话属性读取配置，未配置或无法解析时使用 `default`
+    pub fn from_attributes(attributes: &HashMap<String, String>, default: Self) -> Self {
+        attributes
+            .get(RECEIPTS_POLICY_ATTRIBUTE)
+            .and_then(|value| Self::from_str(value))
+            .unwrap_or(default)
+    }
+}
+
+/// 会话属性中配置新成员历史可见性的键
+pub const HISTORY_VISIBILITY_ATTRIBUTE: &str = "history_visibility";
+
+/// 新成员历史消息可见性
+///
+/// 通过会话属性 `history_visibility` 配置：`none`、`days:N`、`all`（默认）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryVisibility {
+    /// 只能看到加入之后的消息
+    None,
+    /// 可以看到加入前 N 天内的消息
+    LastDays(u32),
+    /// 可以看到全部历史消息
+    All,
+}
+
+impl HistoryVisibility {
+    pub fn from_str(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        match value.as_str() {
+            "none" => Some(Self::None),
+            "all" => Some(Self::All),
+            _ => value
+                .strip_prefix("days:")
+                .and_then(|days| days.parse::<u32>().ok())
+                .map(Self::LastDays),
+        }
+    }
+
+    pub fn as_str(&self) -> String {
+        match self {
+            HistoryVisibility::None => "none".to_string(),
+            HistoryVisibility::LastDays(days) => format!("days:{}", days),
+            HistoryVisibility::All => "all".to_string(),
+        }
+    }
+
+    /// 从会话属性读取配置，未配置或无法解析时为 `All`
+    pub fn from_attributes(attributes: &HashMap<String, String>) -> Self {
+        attributes
+            .get(HISTORY_VISIBILITY_ATTRIBUTE)
+            .and_then(|value| Self::from_str(value))
+            .unwrap_or(Self::All)
+    }
+
+    /// 计算成员在 `joined_at` 加入时的历史消息可见起始时间（`None` 表示全部可见）
+    pub fn visible_from(&self, joined_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            HistoryVisibility::None => Some(joined_at),
+            HistoryVisibility::LastDays(days) => {
+                Some(joined_at - chrono::Duration::days(i64::from(*days)))
+            }
+            HistoryVisibility::All => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Conversation {
     pub tenant_id: String,
@@ -158,8 +276,207 @@ pub struct Conversation {
     pub policy: Option<ConversationPolicy>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// 会话版本号（每次更新递增，用于乐观并发控制）
+    pub version: i64,
+}
+
+/// 会话属性中绑定表情包（贴纸）集合的键，值为逗号分隔的集合ID
+pub const STICKER_SETS_ATTRIBUTE: &str = "sticker_sets";
+
+/// 单个会话最多绑定的表情包集合数
+pub const MAX_STICKER_SETS: usize = 32;
+
+/// 表情包集合ID最大长度
+const MAX_STICKER_SET_ID_LEN: usize = 64;
+
+/// 会话绑定的表情包（贴纸）集合
+///
+/// 表情包集合由租户上传到 flare-media（集合内的贴纸文件携带 `sticker_set_id` 元数据），
+/// 会话通过属性 `sticker_sets` 绑定集合，bootstrap 时随会话 metadata 下发给客户端；
+/// 发送贴纸消息时由消息编排服务校验引用的集合已绑定到该会话。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StickerSetBindings {
+    set_ids: Vec<String>,
+}
+
+impl StickerSetBindings {
+    /// 解析逗号分隔的集合ID（去除空白与重复项，保留绑定顺序）
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut set_ids: Vec<String> = Vec::new();
+        for set_id in value.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+            if set_id.len() > MAX_STICKER_SET_ID_LEN
+                || !set_id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+            {
+                return Err(format!("invalid sticker set id: {}", set_id));
+            }
+            if !set_ids.iter().any(|existing| existing == set_id) {
+                set_ids.push(set_id.to_string());
+            }
+        }
+        if set_ids.len() > MAX_STICKER_SETS {
+            return Err(format!(
+                "too many sticker sets: {} (max {})",
+                set_ids.len(),
+                MAX_STICKER_SETS
+            ));
+        }
+        Ok(Self { set_ids })
+    }
+
+    /// 从会话属性读取绑定，未配置或无法解析时为空
+    pub fn from_attributes(attributes: &HashMap<String, String>) -> Self {
+        attributes
+            .get(STICKER_SETS_ATTRIBUTE)
+            .and_then(|value| Self::parse(value).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn set_ids(&self) -> &[String] {
+        &self.set_ids
+    }
+
+    pub fn contains(&self, set_id: &str) -> bool {
+        self.set_ids.iter().any(|id| id == set_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set_ids.is_empty()
+    }
+
+    /// 规范化后的属性值
+    pub fn to_attribute_value(&self) -> String {
+        self.set_ids.join(",")
+    }
 }
 
+/// 会话属性中标记多语言会话的键（`true` 时启用消息翻译）
+pub const MULTILINGUAL_ATTRIBUTE: &str = "multilingual";
+
+/// 会话属性中限定翻译目标语言的键，值为逗号分隔的语言标签（未配置时不限制）
+pub const TRANSLATION_LOCALES_ATTRIBUTE: &str = "translation_locales";
+
+/// 单个会话最多配置的翻译目标语言数
+pub const MAX_TRANSLATION_LOCALES: usize = 16;
+
+/// 会话的消息翻译配置
+///
+/// 多语言会话同步消息时，会话服务按请求的目标语言调用翻译 Hook，
+/// 译文以 `translation:<locale>` 属性写回消息（格式见 `flare_im_core::utils::translation`）。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TranslationSettings {
+    pub enabled: bool,
+    /// 允许的目标语言（规范化后的语言标签，为空表示不限制）
+    pub locales: Vec<String>,
+}
+
+impl TranslationSettings {
+    /// 解析逗号分隔的目标语言（规范化、去重，保留配置顺序）
+    pub fn parse_locales(value: &str) -> Result<Vec<String>, String> {
+        let mut locales: Vec<String> = Vec::new();
+        for locale in value.split(',').map(str::trim).filter(|l| !l.is_empty()) {
+            let normalized = normalize_locale(locale)
+                .ok_or_else(|| format!("invalid translation locale: {}", locale))?;
+            if !locales.contains(&normalized) {
+                locales.push(normalized);
+            }
+        }
+        if locales.len() > MAX_TRANSLATION_LOCALES {
+            return Err(format!(
+                "too many translation locales: {} (max {})",
+                locales.len(),
+                MAX_TRANSLATION_LOCALES
+            ));
+        }
+        Ok(locales)
+    }
+
+    /// 从会话属性读取配置，未配置或无法解析时不启用
+    pub fn from_attributes(attributes: &HashMap<String, String>) -> Self {
+        let enabled = attributes
+            .get(MULTILINGUAL_ATTRIBUTE)
+            .and_then(|value| value.trim().parse::<bool>().ok())
+            .unwrap_or(false);
+        let locales = attributes
+            .get(TRANSLATION_LOCALES_ATTRIBUTE)
+            .and_then(|value| Self::parse_locales(value).ok())
+            .unwrap_or_default();
+        Self { enabled, locales }
+    }
+
+    /// 是否为该目标语言生成译文
+    pub fn accepts(&self, locale: &str) -> bool {
+        self.enabled && (self.locales.is_empty() || self.locales.iter().any(|l| l == locale))
+    }
+}
+
+/// 同步消息时调用翻译服务的限制
+#[derive(Clone, Copy, Debug)]
+pub struct TranslationLimits {
+    /// 单次同步最多翻译的消息数
+    pub max_per_sync: usize,
+    /// 同时调用翻译服务的最大请求数
+    pub concurrency: usize,
+    /// 单次同步等待译文的总时长，超时未完成的消息在后续同步中补齐
+    pub budget: std::time::Duration,
+}
+
+impl Conversation {
+    /// 新成员历史消息可见性
+    pub fn history_visibility(&self) -> HistoryVisibility {
+        HistoryVisibility::from_attributes(&self.attributes)
+    }
+
+    /// 会话绑定的表情包集合
+    pub fn sticker_sets(&self) -> StickerSetBindings {
+        StickerSetBindings::from_attributes(&self.attributes)
+    }
+
+    /// 消息翻译配置
+    pub fn translation_settings(&self) -> TranslationSettings {
+        TranslationSettings::from_attributes(&self.attributes)
+    }
+}
+
+/// 会话版本冲突（更新时期望版本与当前版本不一致）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConversationVersionConflict {
+    pub conversation_id: String,
+    pub expected_version: i64,
+    pub current_version: i64,
+}
+
+impl std::fmt::Display for ConversationVersionConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "conversation {} version conflict: expected {}, current {}",
+            self.conversation_id, self.expected_version, self.current_version
+        )
+    }
+}
+
+impl std::error::Error for ConversationVersionConflict {}
+
+/// 配置要求携带期望版本号，但更新请求未携带
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConversationVersionRequired {
+    pub conversation_id: String,
+}
+
+impl std::fmt::Display for ConversationVersionRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected_version is required to update conversation {}",
+            self.conversation_id
+        )
+    }
+}
+
+impl std::error::Error for ConversationVersionRequired {}
+
 #[derive(Clone, Debug)]
 pub struct ConversationParticipant {
     pub user_id: String,
@@ -169,6 +486,169 @@ pub struct ConversationParticipant {
     pub attributes: HashMap<String, String>,
 }
 
+/// 成员变更类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MembershipChangeType {
+    Added,
+    Removed,
+    Updated,
+}
+
+impl MembershipChangeType {
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "added" => Some(Self::Added),
+            "removed" => Some(Self::Removed),
+            "updated" => Some(Self::Updated),
+            _ => None,
+        }
+    }
+
+    pub fn as_proto(&self) -> i32 {
+        match self {
+            MembershipChangeType::Added => ProtoMembershipChangeType::Added as i32,
+            MembershipChangeType::Removed => ProtoMembershipChangeType::Removed as i32,
+            MembershipChangeType::Updated => ProtoMembershipChangeType::Updated as i32,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MembershipChangeType::Added => "added",
+            MembershipChangeType::Removed => "removed",
+            MembershipChangeType::Updated => "updated",
+        }
+    }
+}
+
+/// 成员变更记录
+#[derive(Clone, Debug)]
+pub struct MembershipChange {
+    pub user_id: String,
+    pub change_type: MembershipChangeType,
+    /// 变更后的成员版本号
+    pub version: i64,
+    /// 变更后的成员信息（移除时为 None）
+    pub participant: Option<ConversationParticipant>,
+}
+
+/// 成员快照（分页）
+#[derive(Clone, Debug)]
+pub struct ParticipantsSnapshot {
+    pub participants: Vec<ConversationParticipant>,
+    /// 快照对应的成员版本号（首页读取，后续分页沿用）
+    pub version: i64,
+    /// 下一页游标（见 [`ParticipantsSnapshotCursor`]），None 表示已到末尾
+    pub next_cursor: Option<String>,
+    pub total: i64,
+}
+
+impl ParticipantsSnapshot {
+    /// 由一页成员（按 user_id 升序，最多 `limit + 1` 条）构建快照，多出的一条表示还有下一页
+    pub fn from_page(
+        mut participants: Vec<ConversationParticipant>,
+        limit: usize,
+        version: i64,
+        total: i64,
+    ) -> Self {
+        let has_more = participants.len() > limit;
+        participants.truncate(limit);
+        let next_cursor = if has_more {
+            participants.last().map(|p| {
+                ParticipantsSnapshotCursor {
+                    version,
+                    after_user_id: p.user_id.clone(),
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+        Self {
+            participants,
+            version,
+            next_cursor,
+            total,
+        }
+    }
+}
+
+/// 成员快照分页游标：`<首页成员版本号>:<上一页最后一个 user_id>`
+///
+/// 后续分页沿用首页版本号，成员版本变化时返回 [`ParticipantsSnapshotExpired`]，
+/// 避免拼出跨版本、缺漏或重复成员的快照
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParticipantsSnapshotCursor {
+    pub version: i64,
+    pub after_user_id: String,
+}
+
+impl ParticipantsSnapshotCursor {
+    pub fn encode(&self) -> String {
+        format!("{}:{}", self.version, self.after_user_id)
+    }
+
+    pub fn parse(cursor: &str) -> anyhow::Result<Self> {
+        let (version, after_user_id) = cursor
+            .split_once(':')
+            .and_then(|(version, user_id)| Some((version.parse::<i64>().ok()?, user_id)))
+            .ok_or_else(|| anyhow::anyhow!("Invalid participants snapshot cursor: {}", cursor))?;
+        Ok(Self {
+            version,
+            after_user_id: after_user_id.to_string(),
+        })
+    }
+
+    /// 校验当前成员版本与游标中的快照版本一致
+    pub fn ensure_version(
+        &self,
+        conversation_id: &str,
+        current_version: i64,
+    ) -> anyhow::Result<()> {
+        if self.version != current_version {
+            return Err(ParticipantsSnapshotExpired {
+                conversation_id: conversation_id.to_string(),
+                snapshot_version: self.version,
+                current_version,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// 成员快照已过期（分页期间成员发生变化），调用方需从首页重新拉取
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParticipantsSnapshotExpired {
+    pub conversation_id: String,
+    pub snapshot_version: i64,
+    pub current_version: i64,
+}
+
+impl std::fmt::Display for ParticipantsSnapshotExpired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "conversation {} participants snapshot expired: snapshot version {}, current {}",
+            self.conversation_id, self.snapshot_version, self.current_version
+        )
+    }
+}
+
+impl std::error::Error for ParticipantsSnapshotExpired {}
+
+/// 成员增量
+#[derive(Clone, Debug)]
+pub struct ParticipantsDiff {
+    /// 按用户合并后的变更（每个用户只保留最终状态）
+    pub changes: Vec<MembershipChange>,
+    pub since_version: i64,
+    /// 本次增量覆盖到的版本号，下次以此作为 since_version
+    pub current_version: i64,
+    /// 变更日志已被清理或超出上限，需要重新拉取快照
+    pub full_resync_required: bool,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ConversationVisibility {
     Unspecified,
@@ -257,6 +737,7 @@ impl ConversationLifecycleState {
 
 #[derive(Clone, Debug)]
 pub struct ConversationFilter {
+    pub conversation_id: Option<String>,
     pub conversation_type: Option<String>,
     pub business_type: Option<String>,
     pub lifecycle_state: Option<ConversationLifecycleState>,
@@ -306,6 +787,8 @@ pub struct ConversationDomainConfig {
     pub recent_message_limit: i32,
     /// Bootstrap 最大会话数（默认 100，避免响应过大）
     pub max_bootstrap_conversations: Option<usize>,
+    /// 更新会话时是否必须携带期望版本号（默认 false，未携带时按最新版本合并更新）
+    pub require_expected_version: bool,
 }
 
 impl ConversationDomainConfig {
@@ -313,6 +796,7 @@ impl ConversationDomainConfig {
         Self {
             recent_message_limit,
             max_bootstrap_conversations: Some(100),
+            require_expected_version: false,
         }
     }
 
@@ -320,6 +804,359 @@ impl ConversationDomainConfig {
         Self {
             recent_message_limit: 20,
             max_bootstrap_conversations: Some(100),
+            require_expected_version: false,
+        }
+    }
+
+    /// 设置更新会话时是否必须携带期望版本号
+    pub fn with_require_expected_version(mut self, required: bool) -> Self {
+        self.require_expected_version = required;
+        self
+    }
+}
+
+/// 用户合并任务状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserMergeJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl UserMergeJobStatus {
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "running" => Some(Self::Running),
+            "completed" => Some(Self::Completed),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserMergeJobStatus::Pending => "pending",
+            UserMergeJobStatus::Running => "running",
+            UserMergeJobStatus::Completed => "completed",
+            UserMergeJobStatus::Failed => "failed",
+        }
+    }
+}
+
+/// 用户账号合并任务（将 source 用户的会话参与关系迁移到 target 用户）
+#[derive(Clone, Debug)]
+pub struct UserMergeJob {
+    pub job_id: String,
+    pub tenant_id: String,
+    pub source_user_id: String,
+    pub target_user_id: String,
+    pub status: UserMergeJobStatus,
+    /// 最后处理的会话ID，任务中断后从其之后续跑
+    pub last_conversation_id: Option<String>,
+    pub processed_count: i64,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 源用户参与的待合并会话
+#[derive(Clone, Debug)]
+pub struct UserMergeCandidate {
+    pub conversation_id: String,
+    pub conversation_type: String,
+    /// 目标用户是否已在该会话中
+    pub target_is_participant: bool,
+    /// 单聊的对端用户（非单聊为空）
+    pub peers: Vec<String>,
+}
+
+/// 单个会话的合并方式
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UserMergeAction {
+    /// 参与关系直接改写为目标用户
+    Rekeyed,
+    /// 目标用户已在会话中：合并两者的已读/同步状态后移除源用户
+    Combined,
+    /// 单聊：并入目标用户与同一对端的单聊会话（会话ID由双方用户ID决定），源会话归档
+    Merged { into_conversation_id: String },
+}
+
+impl UserMergeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserMergeAction::Rekeyed => "rekeyed",
+            UserMergeAction::Combined => "combined",
+            UserMergeAction::Merged { .. } => "merged",
+        }
+    }
+
+    pub fn merged_into(&self) -> Option<&str> {
+        match self {
+            UserMergeAction::Merged {
+                into_conversation_id,
+            } => Some(into_conversation_id),
+            _ => None,
         }
     }
 }
+
+/// 会话映射事件（供下游系统按映射迁移消息、索引等数据）
+#[derive(Clone, Debug)]
+pub struct UserMergeMapping {
+    pub job_id: String,
+    pub tenant_id: String,
+    pub source_user_id: String,
+    pub target_user_id: String,
+    pub conversation_id: String,
+    pub action: UserMergeAction,
+}
+
+/// 会话生命周期事件类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConversationEventKind {
+    /// 会话创建
+    Created,
+    /// 会话归档
+    Archived,
+    /// 成员变更（加入、移除、角色更新）
+    MembershipChanged,
+    /// 访问策略变更（可见性、新成员历史消息可见性）
+    PolicyChanged,
+}
+
+impl ConversationEventKind {
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "created" => Some(Self::Created),
+            "archived" => Some(Self::Archived),
+            "membership_changed" => Some(Self::MembershipChanged),
+            "policy_changed" => Some(Self::PolicyChanged),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConversationEventKind::Created => "created",
+            ConversationEventKind::Archived => "archived",
+            ConversationEventKind::MembershipChanged => "membership_changed",
+            ConversationEventKind::PolicyChanged => "policy_changed",
+        }
+    }
+}
+
+/// 会话生命周期事件（推送到租户 Webhook，供 CRM 等外部系统同步会话状态）
+#[derive(Clone, Debug)]
+pub struct ConversationLifecycleEvent {
+    pub event_id: String,
+    pub kind: ConversationEventKind,
+    pub tenant_id: String,
+    pub conversation_id: String,
+    pub conversation_type: String,
+    pub business_type: String,
+    /// 事件发生后的会话状态
+    pub lifecycle_state: ConversationLifecycleState,
+    pub visibility: ConversationVisibility,
+    pub history_visibility: HistoryVisibility,
+    /// 新加入的成员（created 为初始成员，membership_changed 为新加入成员）
+    pub added: Vec<String>,
+    /// 被移除的成员（仅 membership_changed）
+    pub removed: Vec<String>,
+    /// 角色被更新的成员（仅 membership_changed）
+    pub updated: Vec<String>,
+    /// 触发变更的用户
+    pub operator_id: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl ConversationLifecycleEvent {
+    pub fn new(
+        kind: ConversationEventKind,
+        conversation: &Conversation,
+        operator_id: Option<String>,
+    ) -> Self {
+        Self {
+            event_id: ulid::Ulid::new().to_string(),
+            kind,
+            tenant_id: conversation.tenant_id.clone(),
+            conversation_id: conversation.conversation_id.clone(),
+            conversation_type: conversation.conversation_type.clone(),
+            business_type: conversation.business_type.clone(),
+            lifecycle_state: conversation.lifecycle_state,
+            visibility: conversation.visibility,
+            history_visibility: conversation.history_visibility(),
+            added: Vec::new(),
+            removed: Vec::new(),
+            updated: Vec::new(),
+            operator_id,
+            occurred_at: Utc::now(),
+        }
+    }
+
+    /// 会话创建事件（携带初始成员）
+    pub fn created(conversation: &Conversation, operator_id: Option<String>) -> Self {
+        Self {
+            added: conversation
+                .participants
+                .iter()
+                .map(|p| p.user_id.clone())
+                .collect(),
+            ..Self::new(ConversationEventKind::Created, conversation, operator_id)
+        }
+    }
+
+    /// 成员变更事件
+    pub fn membership_changed(
+        conversation: &Conversation,
+        operator_id: Option<String>,
+        added: Vec<String>,
+        removed: Vec<String>,
+        updated: Vec<String>,
+    ) -> Self {
+        Self {
+            added,
+            removed,
+            updated,
+            ..Self::new(
+                ConversationEventKind::MembershipChanged,
+                conversation,
+                operator_id,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn participant(user_id: &str) -> ConversationParticipant {
+        ConversationParticipant {
+            user_id: user_id.to_string(),
+            roles: Vec::new(),
+            muted: false,
+            pinned: false,
+            attributes: HashMap::new(),
+        }
+    }
+
+    /// 与 PostgreSQL 仓储相同的分页流程：校验游标版本后按 user_id 做 keyset 分页
+    fn snapshot_page(
+        members: &[&str],
+        version: i64,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> anyhow::Result<ParticipantsSnapshot> {
+        let cursor = cursor.map(ParticipantsSnapshotCursor::parse).transpose()?;
+        if let Some(ref cursor) = cursor {
+            cursor.ensure_version("conv-1", version)?;
+        }
+        let after = cursor.as_ref().map_or("", |c| c.after_user_id.as_str());
+        let mut members = members.to_vec();
+        members.sort();
+        let page = members
+            .into_iter()
+            .filter(|user_id| *user_id > after)
+            .take(limit + 1)
+            .map(participant)
+            .collect();
+        Ok(ParticipantsSnapshot::from_page(page, limit, version, 0))
+    }
+
+    #[test]
+    fn test_snapshot_pages_share_first_page_version() {
+        let members = ["u1", "u2", "u3"];
+        let first = snapshot_page(&members, 7, None, 2).unwrap();
+        assert_eq!(first.participants.len(), 2);
+        let cursor = first.next_cursor.unwrap();
+
+        let second = snapshot_page(&members, 7, Some(&cursor), 2).unwrap();
+        assert_eq!(second.version, 7);
+        assert_eq!(second.participants[0].user_id, "u3");
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_snapshot_membership_change_between_pages_expires() {
+        let first = snapshot_page(&["u1", "u2", "u3"], 7, None, 2).unwrap();
+        let cursor = first.next_cursor.unwrap();
+
+        // 翻页之间 u0 加入、u3 被移除，成员版本递增
+        let err = snapshot_page(&["u0", "u1", "u2"], 9, Some(&cursor), 2).unwrap_err();
+        let expired = err.downcast_ref::<ParticipantsSnapshotExpired>().unwrap();
+        assert_eq!(expired.snapshot_version, 7);
+        assert_eq!(expired.current_version, 9);
+    }
+
+    #[test]
+    fn test_snapshot_cursor_keeps_user_id_with_colon() {
+        let cursor = ParticipantsSnapshotCursor {
+            version: 3,
+            after_user_id: "tenant:u1".to_string(),
+        };
+        let parsed = ParticipantsSnapshotCursor::parse(&cursor.encode()).unwrap();
+        assert_eq!(parsed, cursor);
+        assert!(ParticipantsSnapshotCursor::parse("u1").is_err());
+    }
+
+    #[test]
+    fn test_history_visibility_boundary() {
+        let joined_at = Utc.with_ymd_and_hms(2025, 3, 10, 8, 30, 0).unwrap();
+
+        // none：只能看到加入之后的消息
+        assert_eq!(
+            HistoryVisibility::from_str("none")
+                .unwrap()
+                .visible_from(joined_at),
+            Some(joined_at)
+        );
+        // days:N：可以看到加入前 N 天内的消息
+        assert_eq!(
+            HistoryVisibility::from_str("days:7")
+                .unwrap()
+                .visible_from(joined_at),
+            Some(Utc.with_ymd_and_hms(2025, 3, 3, 8, 30, 0).unwrap())
+        );
+        assert_eq!(
+            HistoryVisibility::from_str("days:0")
+                .unwrap()
+                .visible_from(joined_at),
+            Some(joined_at)
+        );
+        // all：全部可见
+        assert_eq!(
+            HistoryVisibility::from_str("all")
+                .unwrap()
+                .visible_from(joined_at),
+            None
+        );
+    }
+
+    #[test]
+    fn test_history_visibility_parse() {
+        assert_eq!(
+            HistoryVisibility::from_str(" Days:30 "),
+            Some(HistoryVisibility::LastDays(30))
+        );
+        assert_eq!(HistoryVisibility::LastDays(30).as_str(), "days:30");
+        for invalid in ["days:", "days:-1", "days:abc", "week:1", ""] {
+            assert_eq!(HistoryVisibility::from_str(invalid), None, "{}", invalid);
+        }
+
+        // 未配置或无法解析时全部可见
+        assert_eq!(
+            HistoryVisibility::from_attributes(&HashMap::new()),
+            HistoryVisibility::All
+        );
+        let attributes = HashMap::from([(
+            HISTORY_VISIBILITY_ATTRIBUTE.to_string(),
+            "sometimes".to_string(),
+        )]);
+        assert_eq!(
+            HistoryVisibility::from_attributes(&attributes),
+            HistoryVisibility::All
+        );
+    }
+}
@@ -2,13 +2,26 @@ use std::collections::HashMap;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use flare_im_core::utils::translation::MessageTranslation;
 use flare_proto::common::Message;
 
 use crate::domain::model::{
     ConflictResolutionPolicy, DevicePresence, DeviceState, MessageSyncResult, Conversation,
-    ConversationBootstrapResult, ConversationParticipant, ConversationSummary,
+    ConversationBootstrapResult, ConversationLifecycleEvent, ConversationParticipant,
+    ConversationSummary, ParticipantsDiff, ParticipantsSnapshot, UserMergeCandidate, UserMergeJob,
+    UserMergeJobStatus, UserMergeMapping,
 };
 
+/// 单条消息的翻译请求
+#[derive(Clone, Debug)]
+pub struct TranslationRequest {
+    pub conversation_id: String,
+    pub message_id: String,
+    pub text: String,
+    /// 规范化后的目标语言
+    pub target_locale: String,
+}
+
 #[derive(Clone, Debug)]
 pub struct PresenceUpdate {
     pub user_id: String,
@@ -33,6 +46,8 @@ pub trait ConversationRepository: Send + Sync {
 
     async fn create_conversation(&self, ctx: &flare_server_core::context::Context, conversation: &Conversation) -> Result<()>;
     async fn get_conversation(&self, ctx: &flare_server_core::context::Context, conversation_id: &str) -> Result<Option<Conversation>>;
+    /// 按版本号比较并更新（`conversation.version` 为读取时的版本），成功后版本号加一；
+    /// 版本不一致时返回 [`ConversationVersionConflict`](crate::domain::model::ConversationVersionConflict)
     async fn update_conversation(&self, ctx: &flare_server_core::context::Context, conversation: &Conversation) -> Result<()>;
     async fn delete_conversation(&self, ctx: &flare_server_core::context::Context, conversation_id: &str, hard_delete: bool) -> Result<()>;
     async fn manage_participants(
@@ -56,6 +71,28 @@ pub trait ConversationRepository: Send + Sync {
     async fn mark_as_read(&self, ctx: &flare_server_core::context::Context, conversation_id: &str, seq: i64) -> Result<()>;
 
     async fn get_unread_count(&self, ctx: &flare_server_core::context::Context, conversation_id: &str) -> Result<i32>;
+
+    /// 分页导出成员快照（按 user_id 游标分页，附带成员版本号）
+    ///
+    /// 游标为 [`ParticipantsSnapshotCursor`](crate::domain::model::ParticipantsSnapshotCursor)，
+    /// 携带首页版本号；后续分页时成员版本已变化返回
+    /// [`ParticipantsSnapshotExpired`](crate::domain::model::ParticipantsSnapshotExpired)
+    async fn get_participants_snapshot(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        conversation_id: &str,
+        cursor: Option<&str>,
+        limit: i32,
+    ) -> Result<ParticipantsSnapshot>;
+
+    /// 获取自 `since_version` 以来的成员增量
+    async fn get_participants_diff(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        conversation_id: &str,
+        since_version: i64,
+        limit: i32,
+    ) -> Result<ParticipantsDiff>;
 }
 
 /// Presence 仓储接口（需要作为 trait 对象使用，保留 async-trait）
@@ -111,6 +148,18 @@ pub trait MessageProvider: Send + Sync {
             "sync_messages_by_seq not implemented, use sync_messages instead"
         ))
     }
+
+    /// 合并写入消息属性（可选，用于回写译文等扩展数据）
+    ///
+    /// 只覆盖 `attributes` 中出现的键，其他属性与标签保持不变
+    async fn attach_attributes(
+        &self,
+        _ctx: &flare_server_core::context::Context,
+        _message_id: &str,
+        _attributes: HashMap<String, String>,
+    ) -> Result<()> {
+        Err(anyhow::anyhow!("attach_attributes not implemented"))
+    }
 }
 
 /// Thread 仓储接口（话题管理）
@@ -165,3 +214,69 @@ pub trait ThreadRepository: Send + Sync {
     /// 获取话题参与者列表
     async fn get_participants(&self, thread_id: &str) -> Result<Vec<String>>;
 }
+
+/// 用户合并仓储接口（合并任务与会话参与关系迁移）
+#[async_trait]
+pub trait UserMergeRepository: Send + Sync {
+    /// 创建合并任务
+    async fn create_job(&self, job: &UserMergeJob) -> Result<()>;
+
+    async fn get_job(&self, job_id: &str) -> Result<Option<UserMergeJob>>;
+
+    /// 列出待执行或执行中断的任务（按创建时间升序）
+    async fn list_resumable_jobs(&self, limit: i64) -> Result<Vec<UserMergeJob>>;
+
+    /// 更新任务状态与进度
+    async fn update_job(
+        &self,
+        job_id: &str,
+        status: UserMergeJobStatus,
+        last_conversation_id: Option<&str>,
+        processed_count: i64,
+        last_error: Option<&str>,
+    ) -> Result<()>;
+
+    /// 按会话ID升序分页列出源用户参与的会话（`after` 为上一批最后的会话ID）
+    async fn list_candidates(
+        &self,
+        tenant_id: &str,
+        source_user_id: &str,
+        target_user_id: &str,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<UserMergeCandidate>>;
+
+    /// 在单个事务中应用会话的合并方式并记录映射（单聊并入的目标会话不存在时自动创建）
+    async fn apply_merge(&self, mapping: &UserMergeMapping) -> Result<()>;
+
+    /// 合并源用户的同步游标到目标用户（取两者较大值）
+    async fn merge_cursors(
+        &self,
+        tenant_id: &str,
+        source_user_id: &str,
+        target_user_id: &str,
+    ) -> Result<u64>;
+}
+
+/// 用户合并映射事件发布接口
+#[async_trait]
+pub trait UserMergeEventPublisher: Send + Sync {
+    async fn publish(&self, mapping: &UserMergeMapping) -> Result<()>;
+}
+
+/// 消息翻译接口（多语言会话的翻译 Hook）
+#[async_trait]
+pub trait MessageTranslator: Send + Sync {
+    /// 翻译单条消息，翻译服务跳过该消息时返回 `Ok(None)`
+    async fn translate(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        request: &TranslationRequest,
+    ) -> Result<Option<MessageTranslation>>;
+}
+
+/// 会话生命周期事件发布接口
+#[async_trait]
+pub trait ConversationEventPublisher: Send + Sync {
+    async fn publish(&self, event: &ConversationLifecycleEvent) -> Result<()>;
+}
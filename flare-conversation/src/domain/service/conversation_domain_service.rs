@@ -9,26 +9,78 @@ use flare_core::common::conversation::{
     generate_ai_conversation_id, generate_customer_conversation_id, generate_system_conversation_id,
     generate_temp_conversation_id, validate_conversation_id,
 };
+use flare_im_core::utils::translation::{
+    TRANSLATION_LOCALE_EXTRA, requested_locale, select_translation_locale,
+    translation_attribute_key,
+};
 use flare_proto::common::Message;
 use flare_server_core::context::Context;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::domain::model::{
     ConflictResolutionPolicy, DevicePresence, DeviceState, MessageSyncResult, Conversation,
-    ConversationDomainConfig, ConversationFilter, ConversationLifecycleState, ConversationParticipant, ConversationPolicy,
-    ConversationSort, ConversationSummary, ConversationVisibility,
+    ConversationDomainConfig, ConversationEventKind, ConversationFilter,
+    ConversationLifecycleEvent, ConversationLifecycleState, ConversationParticipant,
+    ConversationPolicy, ConversationSort, ConversationSummary, ConversationVersionConflict,
+    ConversationVersionRequired, ConversationVisibility, HISTORY_VISIBILITY_ATTRIBUTE,
+    HistoryVisibility, MULTILINGUAL_ATTRIBUTE, ParticipantsDiff, ParticipantsSnapshot,
+    ParticipantsSnapshotCursor,
+    RECEIPTS_POLICY_ATTRIBUTE, ReceiptsPolicy, STICKER_SETS_ATTRIBUTE, StickerSetBindings,
+    TRANSLATION_LOCALES_ATTRIBUTE, TranslationLimits, TranslationSettings,
 };
 use crate::domain::repository::{
-    MessageProvider, PresenceRepository, PresenceUpdate, ConversationRepository,
+    ConversationEventPublisher, MessageProvider, MessageTranslator, PresenceRepository,
+    PresenceUpdate, ConversationRepository, TranslationRequest,
 };
 
+/// 未携带期望版本时，更新因并发修改失败的最大尝试次数
+const MAX_UPDATE_ATTEMPTS: u32 = 3;
+
+/// 会话更新内容（字段为 None 表示不修改）
+#[derive(Clone, Debug, Default)]
+pub struct ConversationUpdate {
+    pub display_name: Option<String>,
+    pub attributes: Option<HashMap<String, String>>,
+    pub visibility: Option<ConversationVisibility>,
+    pub lifecycle_state: Option<ConversationLifecycleState>,
+}
+
+impl ConversationUpdate {
+    fn apply(&self, conversation: &mut Conversation) -> Result<()> {
+        if let Some(name) = &self.display_name {
+            conversation.display_name = Some(name.clone());
+        }
+        if let Some(attrs) = &self.attributes {
+            validate_history_visibility(attrs)?;
+            validate_receipts_policy(attrs)?;
+            let mut attrs = attrs.clone();
+            normalize_sticker_sets(&mut attrs)?;
+            normalize_translation_settings(&mut attrs)?;
+            conversation.attributes = attrs;
+        }
+        if let Some(vis) = self.visibility {
+            conversation.visibility = vis;
+        }
+        if let Some(state) = self.lifecycle_state {
+            conversation.lifecycle_state = state;
+        }
+        Ok(())
+    }
+}
+
 /// 会话领域服务 - 包含所有业务逻辑
 pub struct ConversationDomainService {
     conversation_repo: Arc<dyn ConversationRepository>,
     presence_repo: Arc<dyn PresenceRepository>,
     message_provider: Option<Arc<dyn MessageProvider>>,
     config: ConversationDomainConfig,
+    /// 生命周期事件发布者（Webhook、Hook 等，未配置时不推送事件）
+    event_publishers: Vec<Arc<dyn ConversationEventPublisher>>,
+    /// 多语言会话的消息翻译器及调用限制（未配置时不生成译文）
+    translator: Option<(Arc<dyn MessageTranslator>, TranslationLimits)>,
 }
 
 /// 会话引导输出
@@ -52,6 +104,76 @@ impl ConversationDomainService {
             presence_repo,
             message_provider,
             config,
+            event_publishers: Vec::new(),
+            translator: None,
+        }
+    }
+
+    /// 添加生命周期事件发布者（可多次调用，每个事件发布到所有发布者）
+    pub fn with_event_publisher(mut self, publisher: Arc<dyn ConversationEventPublisher>) -> Self {
+        self.event_publishers.push(publisher);
+        self
+    }
+
+    /// 配置消息翻译器（多语言会话同步消息时按请求语言补齐译文）
+    pub fn with_translator(
+        mut self,
+        translator: Arc<dyn MessageTranslator>,
+        limits: TranslationLimits,
+    ) -> Self {
+        self.translator = Some((translator, limits));
+        self
+    }
+
+    /// 后台发布生命周期事件，发布失败只记录日志，不影响主流程
+    fn emit(&self, event: ConversationLifecycleEvent) {
+        for publisher in self.event_publishers.iter().cloned() {
+            let event = event.clone();
+            tokio::spawn(async move {
+                if let Err(e) = publisher.publish(&event).await {
+                    warn!(
+                        conversation_id = %event.conversation_id,
+                        event = event.kind.as_str(),
+                        error = %e,
+                        "Failed to publish conversation lifecycle event"
+                    );
+                }
+            });
+        }
+    }
+
+    /// 成员变更后发布事件（需要读取会话以获取业务类型等信息）
+    async fn emit_membership_changed(
+        &self,
+        ctx: &Context,
+        conversation_id: &str,
+        added: Vec<String>,
+        removed: Vec<String>,
+        updated: Vec<String>,
+    ) {
+        if self.event_publishers.is_empty()
+            || (added.is_empty() && removed.is_empty() && updated.is_empty())
+        {
+            return;
+        }
+        match self
+            .conversation_repo
+            .get_conversation(ctx, conversation_id)
+            .await
+        {
+            Ok(Some(conversation)) => self.emit(ConversationLifecycleEvent::membership_changed(
+                &conversation,
+                operator_id(ctx),
+                added,
+                removed,
+                updated,
+            )),
+            Ok(None) => {}
+            Err(e) => warn!(
+                conversation_id = %conversation_id,
+                error = %e,
+                "Failed to load conversation for membership event"
+            ),
         }
     }
 
@@ -209,6 +331,16 @@ impl ConversationDomainService {
             }
         }
 
+        // 下发每个会话生效的回执策略，客户端据此隐藏已读/送达标识
+        for summary in &mut summaries {
+            let receipts =
+                ReceiptsPolicy::from_attributes(&summary.metadata, bootstrap.policy.receipts);
+            summary.metadata.insert(
+                RECEIPTS_POLICY_ATTRIBUTE.to_string(),
+                receipts.as_str().to_string(),
+            );
+        }
+
         let user_id = ctx.user_id().ok_or_else(|| anyhow::anyhow!("user_id is required in context"))?;
         let devices = self
             .presence_repo
@@ -263,6 +395,8 @@ impl ConversationDomainService {
     }
 
     /// 同步消息（业务逻辑）
+    ///
+    /// Reader 按 `ctx` 中的用户裁剪新成员不可见的历史消息，Bootstrap 最近消息同理。
     pub async fn sync_messages(
         &self,
         ctx: &Context,
@@ -275,9 +409,165 @@ impl ConversationDomainService {
             .message_provider
             .as_ref()
             .ok_or_else(|| anyhow!("message provider not configured"))?;
-        provider
+        let mut result = provider
             .sync_messages(ctx, conversation_id, since_ts, cursor, limit)
+            .await?;
+        self.translate_messages(ctx, provider, conversation_id, &mut result.messages)
+            .await;
+        Ok(result)
+    }
+
+    /// 多语言会话按请求语言补齐译文
+    ///
+    /// 只翻译文本消息中缺少目标语言译文的部分（每次最多 `max_per_sync` 条），并发调用翻译服务
+    /// （最多 `concurrency` 个请求），总等待时长不超过 `budget`，超时未完成的请求直接取消，
+    /// 在后续同步中补齐。译文写入返回的消息并在后台回写到 Reader；翻译或回写失败只记录日志，不影响同步
+    async fn translate_messages(
+        &self,
+        ctx: &Context,
+        provider: &Arc<dyn MessageProvider>,
+        conversation_id: &str,
+        messages: &mut [Message],
+    ) {
+        let Some((translator, limits)) = &self.translator else {
+            return;
+        };
+        let Some(locale) = ctx
+            .request()
+            .cloned()
+            .map(|req_ctx| -> flare_proto::common::RequestContext { req_ctx.into() })
+            .and_then(|req_ctx| requested_locale(&req_ctx))
+        else {
+            return;
+        };
+        if messages.is_empty() || limits.max_per_sync == 0 {
+            return;
+        }
+        let settings = match self
+            .conversation_repo
+            .get_conversation(ctx, conversation_id)
             .await
+        {
+            Ok(Some(conversation)) => conversation.translation_settings(),
+            Ok(None) => return,
+            Err(e) => {
+                warn!(
+                    conversation_id,
+                    error = %e,
+                    "Failed to load conversation for translation"
+                );
+                return;
+            }
+        };
+        if !settings.accepts(&locale) {
+            return;
+        }
+
+        let pending: Vec<(usize, TranslationRequest)> = messages
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| {
+                select_translation_locale(&message.attributes, &locale).is_none()
+            })
+            .filter_map(|(index, message)| {
+                match message.content.as_ref().and_then(|c| c.content.as_ref()) {
+                    Some(flare_proto::common::message_content::Content::Text(t))
+                        if !t.text.trim().is_empty() =>
+                    {
+                        Some((
+                            index,
+                            TranslationRequest {
+                                conversation_id: conversation_id.to_string(),
+                                message_id: message.server_id.clone(),
+                                text: t.text.clone(),
+                                target_locale: locale.clone(),
+                            },
+                        ))
+                    }
+                    _ => None,
+                }
+            })
+            .take(limits.max_per_sync)
+            .collect();
+        if pending.is_empty() {
+            return;
+        }
+
+        let semaphore = Arc::new(Semaphore::new(limits.concurrency.max(1)));
+        let mut tasks = JoinSet::new();
+        for (index, request) in pending {
+            let translator = Arc::clone(translator);
+            let semaphore = Arc::clone(&semaphore);
+            let ctx = ctx.clone();
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                let result = translator.translate(&ctx, &request).await;
+                (index, request, result)
+            });
+        }
+
+        let key = translation_attribute_key(&locale);
+        let deadline = tokio::time::Instant::now() + limits.budget;
+        let mut translated = 0;
+        loop {
+            let (index, request, result) =
+                match tokio::time::timeout_at(deadline, tasks.join_next()).await {
+                    Ok(Some(Ok(joined))) => joined,
+                    Ok(Some(Err(_))) => continue,
+                    Ok(None) => break,
+                    Err(_) => {
+                        warn!(
+                            conversation_id,
+                            locale = %locale,
+                            remaining = tasks.len(),
+                            "Translation budget exceeded, remaining messages deferred"
+                        );
+                        tasks.abort_all();
+                        break;
+                    }
+                };
+            let translation = match result {
+                Ok(Some(translation)) => translation,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!(
+                        conversation_id,
+                        message_id = %request.message_id,
+                        locale = %locale,
+                        error = %e,
+                        "Failed to translate message"
+                    );
+                    continue;
+                }
+            };
+            translated += 1;
+            let value = translation.to_attribute_value();
+            let message = &mut messages[index];
+            message.attributes.insert(key.clone(), value.clone());
+            message
+                .extra
+                .insert(TRANSLATION_LOCALE_EXTRA.to_string(), locale.clone());
+
+            let provider = Arc::clone(provider);
+            let ctx = ctx.clone();
+            let attributes = HashMap::from([(key.clone(), value)]);
+            tokio::spawn(async move {
+                if let Err(e) = provider
+                    .attach_attributes(&ctx, &request.message_id, attributes)
+                    .await
+                {
+                    warn!(
+                        conversation_id = %request.conversation_id,
+                        message_id = %request.message_id,
+                        error = %e,
+                        "Failed to persist message translation"
+                    );
+                }
+            });
+        }
+        if translated > 0 {
+            debug!(conversation_id, locale = %locale, translated, "Translated messages");
+        }
     }
 
     /// 更新游标（业务逻辑）
@@ -380,6 +670,10 @@ impl ConversationDomainService {
         visibility: ConversationVisibility,
     ) -> Result<Conversation> {
         let tenant_id = ctx.tenant_id().unwrap_or("0");
+        validate_history_visibility(&attributes)?;
+        validate_receipts_policy(&attributes)?;
+        normalize_sticker_sets(&mut attributes)?;
+        normalize_translation_settings(&mut attributes)?;
         // 尝试从 attributes 中提取指定的 conversation_id
         if let Some(requested_conversation_id) = attributes.remove("conversation_id") {
             // 验证会话ID格式（如果格式不正确，记录警告但继续处理，保持向后兼容）
@@ -423,6 +717,13 @@ impl ConversationDomainService {
                     self.conversation_repo
                         .manage_participants(ctx, &requested_conversation_id, &participants_to_add, &[], &[])
                         .await?;
+                    self.emit(ConversationLifecycleEvent::membership_changed(
+                        &existing_session,
+                        operator_id(ctx),
+                        participants_to_add.into_iter().map(|p| p.user_id).collect(),
+                        Vec::new(),
+                        Vec::new(),
+                    ));
                 }
 
                 // 返回现有会话
@@ -446,10 +747,15 @@ impl ConversationDomainService {
                     policy: None,
                     created_at: chrono::Utc::now(),
                     updated_at: chrono::Utc::now(),
+                    version: 0,
                 };
 
                 self.conversation_repo.create_conversation(ctx, &session).await?;
                 info!(conversation_id = %requested_conversation_id, "Conversation created with provided conversation_id");
+                self.emit(ConversationLifecycleEvent::created(
+                    &session,
+                    operator_id(ctx),
+                ));
                 Ok(session)
             }
         } else {
@@ -534,6 +840,7 @@ impl ConversationDomainService {
                 policy: None,
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
+                version: 0,
             };
 
             self.conversation_repo.create_conversation(ctx, &session).await?;
@@ -541,6 +848,10 @@ impl ConversationDomainService {
                 conversation_id = %conversation_id,
                 "Conversation created with generated conversation_id"
             );
+            self.emit(ConversationLifecycleEvent::created(
+                &session,
+                operator_id(ctx),
+            ));
             Ok(session)
         }
     }
@@ -551,37 +862,88 @@ impl ConversationDomainService {
     }
 
     /// 更新会话（业务逻辑）
+    ///
+    /// 携带 `expected_version` 时，与当前版本不一致返回 [`ConversationVersionConflict`]；
+    /// 配置要求携带而未携带时返回 [`ConversationVersionRequired`]；
+    /// 未携带时基于最新版本合并更新，写入期间被并发修改则重新读取后重试
     pub async fn update_conversation(
         &self,
         ctx: &Context,
         conversation_id: &str,
-        display_name: Option<String>,
-        attributes: Option<HashMap<String, String>>,
-        visibility: Option<ConversationVisibility>,
-        lifecycle_state: Option<ConversationLifecycleState>,
+        update: ConversationUpdate,
+        expected_version: Option<i64>,
     ) -> Result<Conversation> {
-        let mut conversation = self
-            .conversation_repo
-            .get_conversation(ctx, conversation_id)
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
-
-        if let Some(name) = display_name {
-            conversation.display_name = Some(name);
-        }
-        if let Some(attrs) = attributes {
-            conversation.attributes = attrs;
+        if expected_version.is_none() && self.config.require_expected_version {
+            return Err(ConversationVersionRequired {
+                conversation_id: conversation_id.to_string(),
+            }
+            .into());
         }
-        if let Some(vis) = visibility {
-            conversation.visibility = vis;
+
+        let mut attempt = 0;
+        let (conversation, previous_state, previous_policy) = loop {
+            attempt += 1;
+            let mut conversation = self
+                .conversation_repo
+                .get_conversation(ctx, conversation_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+            if let Some(expected) = expected_version {
+                if expected != conversation.version {
+                    return Err(ConversationVersionConflict {
+                        conversation_id: conversation_id.to_string(),
+                        expected_version: expected,
+                        current_version: conversation.version,
+                    }
+                    .into());
+                }
+            }
+            let previous_state = conversation.lifecycle_state;
+            let previous_policy = (conversation.visibility, conversation.history_visibility());
+            update.apply(&mut conversation)?;
+            conversation.updated_at = chrono::Utc::now();
+
+            match self.conversation_repo.update_conversation(ctx, &conversation).await {
+                Ok(()) => {
+                    conversation.version += 1;
+                    break (conversation, previous_state, previous_policy);
+                }
+                Err(e)
+                    if expected_version.is_none()
+                        && attempt < MAX_UPDATE_ATTEMPTS
+                        && e.downcast_ref::<ConversationVersionConflict>().is_some() =>
+                {
+                    debug!(
+                        conversation_id = %conversation_id,
+                        attempt,
+                        "Conversation modified concurrently, retrying update"
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        info!(
+            conversation_id = %conversation_id,
+            version = conversation.version,
+            "Conversation updated"
+        );
+
+        if conversation.lifecycle_state == ConversationLifecycleState::Archived
+            && previous_state != ConversationLifecycleState::Archived
+        {
+            self.emit(ConversationLifecycleEvent::new(
+                ConversationEventKind::Archived,
+                &conversation,
+                operator_id(ctx),
+            ));
         }
-        if let Some(state) = lifecycle_state {
-            conversation.lifecycle_state = state;
+        if (conversation.visibility, conversation.history_visibility()) != previous_policy {
+            self.emit(ConversationLifecycleEvent::new(
+                ConversationEventKind::PolicyChanged,
+                &conversation,
+                operator_id(ctx),
+            ));
         }
-        conversation.updated_at = chrono::Utc::now();
-
-        self.conversation_repo.update_conversation(ctx, &conversation).await?;
-        info!(conversation_id = %conversation_id, "Conversation updated");
         Ok(conversation)
     }
 
@@ -619,9 +981,75 @@ impl ConversationDomainService {
             role_updates = role_updates.len(),
             "Participants managed"
         );
+        self.emit_membership_changed(
+            ctx,
+            conversation_id,
+            to_add.into_iter().map(|p| p.user_id).collect(),
+            to_remove,
+            role_updates
+                .into_iter()
+                .map(|(user_id, _)| user_id)
+                .collect(),
+        )
+        .await;
         Ok(participants)
     }
 
+    /// 导出成员快照（业务逻辑）
+    pub async fn get_participants_snapshot(
+        &self,
+        ctx: &Context,
+        conversation_id: &str,
+        cursor: Option<&str>,
+        limit: i32,
+    ) -> Result<ParticipantsSnapshot> {
+        let limit = normalize_membership_page_limit(limit);
+        let snapshot_cursor = cursor.map(ParticipantsSnapshotCursor::parse).transpose()?;
+        let snapshot = self
+            .conversation_repo
+            .get_participants_snapshot(ctx, conversation_id, cursor, limit)
+            .await?;
+        // 仓储未校验游标版本时兜底：不同版本的分页不能拼成同一份快照
+        if let Some(snapshot_cursor) = snapshot_cursor {
+            snapshot_cursor.ensure_version(conversation_id, snapshot.version)?;
+        }
+        debug!(
+            conversation_id = %conversation_id,
+            version = snapshot.version,
+            count = snapshot.participants.len(),
+            has_more = snapshot.next_cursor.is_some(),
+            "Participants snapshot exported"
+        );
+        Ok(snapshot)
+    }
+
+    /// 获取成员增量（业务逻辑）
+    pub async fn get_participants_diff(
+        &self,
+        ctx: &Context,
+        conversation_id: &str,
+        since_version: i64,
+        limit: i32,
+    ) -> Result<ParticipantsDiff> {
+        if since_version < 0 {
+            return Err(anyhow!("since_version must be non-negative"));
+        }
+        let limit = normalize_membership_page_limit(limit);
+        let diff = self
+            .conversation_repo
+            .get_participants_diff(ctx, conversation_id, since_version, limit)
+            .await?;
+        debug!(
+            conversation_id = %conversation_id,
+            since_version,
+            current_version = diff.current_version,
+            changes = diff.changes.len(),
+            full_resync_required = diff.full_resync_required,
+            "Participants diff computed"
+        );
+        Ok(diff)
+    }
+
     /// 批量确认（业务逻辑）
     pub async fn batch_acknowledge(
         &self,
@@ -684,3 +1112,87 @@ fn parse_cursor(cursor: Option<&str>) -> (Option<i64>, String) {
     }
     (None, String::new())
 }
+
+/// 成员快照/增量单页默认条数与上限
+const DEFAULT_MEMBERSHIP_PAGE_LIMIT: i32 = 500;
+const MAX_MEMBERSHIP_PAGE_LIMIT: i32 = 2000;
+
+fn normalize_membership_page_limit(limit: i32) -> i32 {
+    if limit <= 0 {
+        DEFAULT_MEMBERSHIP_PAGE_LIMIT
+    } else {
+        limit.min(MAX_MEMBERSHIP_PAGE_LIMIT)
+    }
+}
+
+/// 校验会话属性中的历史可见性配置（未配置时视为全部可见）
+fn validate_history_visibility(attributes: &HashMap<String, String>) -> Result<()> {
+    match attributes.get(HISTORY_VISIBILITY_ATTRIBUTE) {
+        Some(value) if HistoryVisibility::from_str(value).is_none() => Err(anyhow!(
+            "invalid {}: {} (expected none, days:N or all)",
+            HISTORY_VISIBILITY_ATTRIBUTE,
+            value
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 校验会话属性中的回执策略配置（未配置时使用默认策略）
+fn validate_receipts_policy(attributes: &HashMap<String, String>) -> Result<()> {
+    match attributes.get(RECEIPTS_POLICY_ATTRIBUTE) {
+        Some(value) if ReceiptsPolicy::from_str(value).is_none() => Err(anyhow!(
+            "invalid {}: {} (expected full, delivery_only or none)",
+            RECEIPTS_POLICY_ATTRIBUTE,
+            value
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 校验并规范化会话属性中的表情包集合绑定（去重、去空白；绑定为空时移除属性）
+fn normalize_sticker_sets(attributes: &mut HashMap<String, String>) -> Result<()> {
+    let Some(value) = attributes.get(STICKER_SETS_ATTRIBUTE) else {
+        return Ok(());
+    };
+    let bindings = StickerSetBindings::parse(value)
+        .map_err(|e| anyhow!("invalid {}: {}", STICKER_SETS_ATTRIBUTE, e))?;
+    if bindings.is_empty() {
+        attributes.remove(STICKER_SETS_ATTRIBUTE);
+    } else {
+        attributes.insert(
+            STICKER_SETS_ATTRIBUTE.to_string(),
+            bindings.to_attribute_value(),
+        );
+    }
+    Ok(())
+}
+
+/// 校验并规范化会话属性中的翻译配置（`multilingual` 为布尔值，目标语言规范化、去重）
+fn normalize_translation_settings(attributes: &mut HashMap<String, String>) -> Result<()> {
+    if let Some(value) = attributes.get(MULTILINGUAL_ATTRIBUTE) {
+        let enabled = value.trim().parse::<bool>().map_err(|_| {
+            anyhow!(
+                "invalid {}: {} (expected true or false)",
+                MULTILINGUAL_ATTRIBUTE,
+                value
+            )
+        })?;
+        attributes.insert(MULTILINGUAL_ATTRIBUTE.to_string(), enabled.to_string());
+    }
+    let Some(value) = attributes.get(TRANSLATION_LOCALES_ATTRIBUTE) else {
+        return Ok(());
+    };
+    let locales = TranslationSettings::parse_locales(value)
+        .map_err(|e| anyhow!("invalid {}: {}", TRANSLATION_LOCALES_ATTRIBUTE, e))?;
+    if locales.is_empty() {
+        attributes.remove(TRANSLATION_LOCALES_ATTRIBUTE);
+    } else {
+        attributes.insert(TRANSLATION_LOCALES_ATTRIBUTE.to_string(), locales.join(","));
+    }
+    Ok(())
+}
+
+/// 触发变更的用户（来自请求上下文）
+fn operator_id(ctx: &Context) -> Option<String> {
+    ctx.user_id().map(|user_id| user_id.to_string())
+}
@@ -1,5 +1,7 @@
 pub mod conversation_domain_service;
 pub mod thread_domain_service;
+pub mod user_merge_domain_service;
 
-pub use conversation_domain_service::ConversationDomainService;
+pub use conversation_domain_service::{ConversationDomainService, ConversationUpdate};
 pub use thread_domain_service::ThreadDomainService;
+pub use user_merge_domain_service::UserMergeDomainService;
@@ -0,0 +1,654 @@
+//! 用户合并领域服务 - 账号合并时迁移会话参与关系
+//!
+//! 任务按会话ID升序分批处理源用户参与的会话，每批处理完成后持久化进度；
+//! 已迁移的会话不再出现在源用户的参与列表中，因此任务中断后重新执行是幂等的。
+
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use flare_core::common::conversation::generate_single_chat_conversation_id;
+use tracing::{info, instrument, warn};
+
+use crate::domain::model::{
+    UserMergeAction, UserMergeCandidate, UserMergeJob, UserMergeJobStatus, UserMergeMapping,
+};
+use crate::domain::repository::{UserMergeEventPublisher, UserMergeRepository};
+
+/// 每批处理的会话数（默认值）
+const DEFAULT_BATCH_SIZE: i64 = 100;
+
+/// 用户合并领域服务
+pub struct UserMergeDomainService {
+    repo: Arc<dyn UserMergeRepository>,
+    publisher: Option<Arc<dyn UserMergeEventPublisher>>,
+    batch_size: i64,
+}
+
+impl UserMergeDomainService {
+    pub fn new(
+        repo: Arc<dyn UserMergeRepository>,
+        publisher: Option<Arc<dyn UserMergeEventPublisher>>,
+    ) -> Self {
+        Self {
+            repo,
+            publisher,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// 提交合并任务（由后台执行）
+    #[instrument(skip(self))]
+    pub async fn submit(
+        &self,
+        tenant_id: &str,
+        source_user_id: &str,
+        target_user_id: &str,
+    ) -> Result<UserMergeJob> {
+        if source_user_id.is_empty() || target_user_id.is_empty() {
+            return Err(anyhow!("source_user_id and target_user_id are required"));
+        }
+        if source_user_id == target_user_id {
+            return Err(anyhow!("cannot merge a user into itself"));
+        }
+
+        let now = Utc::now();
+        let job = UserMergeJob {
+            job_id: ulid::Ulid::new().to_string(),
+            tenant_id: tenant_id.to_string(),
+            source_user_id: source_user_id.to_string(),
+            target_user_id: target_user_id.to_string(),
+            status: UserMergeJobStatus::Pending,
+            last_conversation_id: None,
+            processed_count: 0,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.repo.create_job(&job).await?;
+
+        info!(job_id = %job.job_id, "User merge job submitted");
+        Ok(job)
+    }
+
+    /// 将失败的任务重新置为待执行（保留进度，从中断处续跑）
+    pub async fn retry(&self, job_id: &str) -> Result<()> {
+        let job = self
+            .repo
+            .get_job(job_id)
+            .await?
+            .ok_or_else(|| anyhow!("user merge job {} not found", job_id))?;
+        if job.status != UserMergeJobStatus::Failed {
+            return Err(anyhow!(
+                "user merge job {} is {}, only failed jobs can be retried",
+                job_id,
+                job.status.as_str()
+            ));
+        }
+
+        self.repo
+            .update_job(
+                job_id,
+                UserMergeJobStatus::Pending,
+                job.last_conversation_id.as_deref(),
+                job.processed_count,
+                None,
+            )
+            .await
+    }
+
+    /// 执行所有待执行或中断的任务
+    pub async fn resume_pending(&self) -> Result<usize> {
+        let jobs = self.repo.list_resumable_jobs(10).await?;
+        let count = jobs.len();
+        for job in jobs {
+            let job_id = job.job_id.clone();
+            if let Err(err) = self.run_job(job).await {
+                warn!(job_id = %job_id, error = %err, "User merge job failed");
+            }
+        }
+        Ok(count)
+    }
+
+    /// 执行单个合并任务，失败时记录错误并标记为失败
+    #[instrument(skip(self, job), fields(job_id = %job.job_id))]
+    pub async fn run_job(&self, mut job: UserMergeJob) -> Result<UserMergeJob> {
+        let result = self.process(&mut job).await;
+
+        match result {
+            Ok(()) => {
+                job.status = UserMergeJobStatus::Completed;
+                job.last_error = None;
+            }
+            Err(ref err) => {
+                job.status = UserMergeJobStatus::Failed;
+                job.last_error = Some(err.to_string());
+            }
+        }
+        self.repo
+            .update_job(
+                &job.job_id,
+                job.status,
+                job.last_conversation_id.as_deref(),
+                job.processed_count,
+                job.last_error.as_deref(),
+            )
+            .await?;
+
+        result.map(|_| job)
+    }
+
+    async fn process(&self, job: &mut UserMergeJob) -> Result<()> {
+        job.status = UserMergeJobStatus::Running;
+        self.repo
+            .update_job(
+                &job.job_id,
+                job.status,
+                job.last_conversation_id.as_deref(),
+                job.processed_count,
+                None,
+            )
+            .await?;
+
+        loop {
+            let candidates = self
+                .repo
+                .list_candidates(
+                    &job.tenant_id,
+                    &job.source_user_id,
+                    &job.target_user_id,
+                    job.last_conversation_id.as_deref(),
+                    self.batch_size,
+                )
+                .await?;
+            if candidates.is_empty() {
+                break;
+            }
+
+            for candidate in &candidates {
+                self.merge_conversation(job, candidate).await?;
+                job.last_conversation_id = Some(candidate.conversation_id.clone());
+                job.processed_count += 1;
+            }
+
+            // 每批持久化进度，便于中断后续跑
+            self.repo
+                .update_job(
+                    &job.job_id,
+                    UserMergeJobStatus::Running,
+                    job.last_conversation_id.as_deref(),
+                    job.processed_count,
+                    None,
+                )
+                .await?;
+        }
+
+        let cursors = self
+            .repo
+            .merge_cursors(&job.tenant_id, &job.source_user_id, &job.target_user_id)
+            .await?;
+
+        info!(
+            job_id = %job.job_id,
+            conversations = job.processed_count,
+            cursors,
+            "User merge job completed"
+        );
+        Ok(())
+    }
+
+    async fn merge_conversation(
+        &self,
+        job: &UserMergeJob,
+        candidate: &UserMergeCandidate,
+    ) -> Result<()> {
+        let mapping = UserMergeMapping {
+            job_id: job.job_id.clone(),
+            tenant_id: job.tenant_id.clone(),
+            source_user_id: job.source_user_id.clone(),
+            target_user_id: job.target_user_id.clone(),
+            conversation_id: candidate.conversation_id.clone(),
+            action: plan_merge_action(candidate, &job.target_user_id),
+        };
+        self.repo.apply_merge(&mapping).await?;
+
+        if let Some(publisher) = &self.publisher {
+            if let Err(err) = publisher.publish(&mapping).await {
+                // 映射已持久化，下游可从映射表补偿
+                warn!(
+                    job_id = %job.job_id,
+                    conversation_id = %candidate.conversation_id,
+                    error = %err,
+                    "Failed to publish user merge mapping event"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 决定会话的合并方式
+///
+/// 单聊会话ID由双方用户ID生成，合并后目标用户与对端的消息会写入 (target, peer) 对应的会话，
+/// 因此源单聊统一并入该会话，而不是原地改写参与者。
+pub fn plan_merge_action(candidate: &UserMergeCandidate, target_user_id: &str) -> UserMergeAction {
+    if candidate.target_is_participant {
+        return UserMergeAction::Combined;
+    }
+
+    match (
+        candidate.conversation_type.as_str(),
+        candidate.peers.as_slice(),
+    ) {
+        ("single", [peer]) => UserMergeAction::Merged {
+            into_conversation_id: generate_single_chat_conversation_id(target_user_id, peer),
+        },
+        _ => UserMergeAction::Rekeyed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    const TENANT: &str = "tenant-a";
+
+    /// 内存合并仓储：(租户, 会话ID) -> (会话类型, 参与者)
+    #[derive(Default)]
+    struct MemoryMergeRepo {
+        jobs: Mutex<HashMap<String, UserMergeJob>>,
+        conversations: Mutex<BTreeMap<(String, String), (String, Vec<String>)>>,
+        applied: Mutex<Vec<UserMergeMapping>>,
+        /// 每次进度持久化的 (状态, 最后处理的会话, 已处理数)
+        progress: Mutex<Vec<(UserMergeJobStatus, Option<String>, i64)>>,
+        /// 处理到该会话时失败
+        fail_on: Mutex<Option<String>>,
+    }
+
+    impl MemoryMergeRepo {
+        fn with_conversation(
+            self,
+            tenant_id: &str,
+            conversation_id: &str,
+            conversation_type: &str,
+            participants: &[&str],
+        ) -> Self {
+            self.conversations.lock().unwrap().insert(
+                (tenant_id.to_string(), conversation_id.to_string()),
+                (
+                    conversation_type.to_string(),
+                    participants.iter().map(|p| p.to_string()).collect(),
+                ),
+            );
+            self
+        }
+
+        fn participants(&self, tenant_id: &str, conversation_id: &str) -> Vec<String> {
+            self.conversations
+                .lock()
+                .unwrap()
+                .get(&(tenant_id.to_string(), conversation_id.to_string()))
+                .map(|(_, participants)| participants.clone())
+                .unwrap_or_default()
+        }
+
+        fn job(&self, job_id: &str) -> UserMergeJob {
+            self.jobs.lock().unwrap()[job_id].clone()
+        }
+
+        fn applied_conversations(&self) -> Vec<String> {
+            self.applied
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|mapping| mapping.conversation_id.clone())
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl UserMergeRepository for MemoryMergeRepo {
+        async fn create_job(&self, job: &UserMergeJob) -> Result<()> {
+            self.jobs
+                .lock()
+                .unwrap()
+                .insert(job.job_id.clone(), job.clone());
+            Ok(())
+        }
+
+        async fn get_job(&self, job_id: &str) -> Result<Option<UserMergeJob>> {
+            Ok(self.jobs.lock().unwrap().get(job_id).cloned())
+        }
+
+        async fn list_resumable_jobs(&self, limit: i64) -> Result<Vec<UserMergeJob>> {
+            let mut jobs: Vec<UserMergeJob> = self
+                .jobs
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|job| {
+                    matches!(
+                        job.status,
+                        UserMergeJobStatus::Pending | UserMergeJobStatus::Running
+                    )
+                })
+                .cloned()
+                .collect();
+            jobs.sort_by_key(|job| job.created_at);
+            jobs.truncate(limit as usize);
+            Ok(jobs)
+        }
+
+        async fn update_job(
+            &self,
+            job_id: &str,
+            status: UserMergeJobStatus,
+            last_conversation_id: Option<&str>,
+            processed_count: i64,
+            last_error: Option<&str>,
+        ) -> Result<()> {
+            let mut jobs = self.jobs.lock().unwrap();
+            let job = jobs.get_mut(job_id).ok_or_else(|| anyhow!("no job"))?;
+            job.status = status;
+            job.last_conversation_id = last_conversation_id.map(str::to_string);
+            job.processed_count = processed_count;
+            job.last_error = last_error.map(str::to_string);
+            self.progress.lock().unwrap().push((
+                status,
+                job.last_conversation_id.clone(),
+                processed_count,
+            ));
+            Ok(())
+        }
+
+        async fn list_candidates(
+            &self,
+            tenant_id: &str,
+            source_user_id: &str,
+            target_user_id: &str,
+            after: Option<&str>,
+            limit: i64,
+        ) -> Result<Vec<UserMergeCandidate>> {
+            Ok(self
+                .conversations
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|((tenant, conversation_id), (_, participants))| {
+                    tenant == tenant_id
+                        && after.is_none_or(|after| conversation_id.as_str() > after)
+                        && participants.iter().any(|p| p == source_user_id)
+                })
+                .take(limit as usize)
+                .map(
+                    |((_, conversation_id), (conversation_type, participants))| {
+                        UserMergeCandidate {
+                            conversation_id: conversation_id.clone(),
+                            conversation_type: conversation_type.clone(),
+                            target_is_participant: participants.iter().any(|p| p == target_user_id),
+                            peers: if conversation_type == "single" {
+                                participants
+                                    .iter()
+                                    .filter(|p| *p != source_user_id)
+                                    .cloned()
+                                    .collect()
+                            } else {
+                                Vec::new()
+                            },
+                        }
+                    },
+                )
+                .collect())
+        }
+
+        async fn apply_merge(&self, mapping: &UserMergeMapping) -> Result<()> {
+            if self.fail_on.lock().unwrap().as_deref() == Some(mapping.conversation_id.as_str()) {
+                return Err(anyhow!("database unavailable"));
+            }
+            let mut conversations = self.conversations.lock().unwrap();
+            let key = (mapping.tenant_id.clone(), mapping.conversation_id.clone());
+            let (_, participants) = conversations
+                .get_mut(&key)
+                .ok_or_else(|| anyhow!("no conversation"))?;
+            let peers: Vec<String> = participants
+                .iter()
+                .filter(|p| **p != mapping.source_user_id)
+                .cloned()
+                .collect();
+            participants.retain(|p| *p != mapping.source_user_id);
+            match &mapping.action {
+                UserMergeAction::Rekeyed => participants.push(mapping.target_user_id.clone()),
+                UserMergeAction::Combined => {}
+                UserMergeAction::Merged {
+                    into_conversation_id,
+                } => {
+                    let mut merged = peers;
+                    merged.push(mapping.target_user_id.clone());
+                    conversations
+                        .entry((mapping.tenant_id.clone(), into_conversation_id.clone()))
+                        .or_insert(("single".to_string(), merged));
+                }
+            }
+            self.applied.lock().unwrap().push(mapping.clone());
+            Ok(())
+        }
+
+        async fn merge_cursors(
+            &self,
+            _tenant_id: &str,
+            _source_user_id: &str,
+            _target_user_id: &str,
+        ) -> Result<u64> {
+            Ok(0)
+        }
+    }
+
+    /// 记录映射事件的发布器（`fail` 为真时发布总是失败）
+    #[derive(Default)]
+    struct MemoryPublisher {
+        published: Mutex<Vec<String>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl UserMergeEventPublisher for MemoryPublisher {
+        async fn publish(&self, mapping: &UserMergeMapping) -> Result<()> {
+            if self.fail {
+                return Err(anyhow!("broker unavailable"));
+            }
+            self.published
+                .lock()
+                .unwrap()
+                .push(mapping.conversation_id.clone());
+            Ok(())
+        }
+    }
+
+    fn group_repo(count: usize) -> MemoryMergeRepo {
+        (1..=count).fold(MemoryMergeRepo::default(), |repo, i| {
+            repo.with_conversation(TENANT, &format!("group-{}", i), "group", &["old", "peer"])
+        })
+    }
+
+    fn candidate(
+        conversation_type: &str,
+        target_is_participant: bool,
+        peers: &[&str],
+    ) -> UserMergeCandidate {
+        UserMergeCandidate {
+            conversation_id: "conv-1".to_string(),
+            conversation_type: conversation_type.to_string(),
+            target_is_participant,
+            peers: peers.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_plan_merge_action() {
+        assert_eq!(
+            plan_merge_action(&candidate("single", false, &["peer"]), "new"),
+            UserMergeAction::Merged {
+                into_conversation_id: generate_single_chat_conversation_id("new", "peer"),
+            }
+        );
+        // 目标用户已在会话中时合并状态，其余会话原地改写
+        assert_eq!(
+            plan_merge_action(&candidate("single", true, &["new"]), "new"),
+            UserMergeAction::Combined
+        );
+        assert_eq!(
+            plan_merge_action(&candidate("group", true, &[]), "new"),
+            UserMergeAction::Combined
+        );
+        assert_eq!(
+            plan_merge_action(&candidate("group", false, &[]), "new"),
+            UserMergeAction::Rekeyed
+        );
+        assert_eq!(
+            plan_merge_action(&candidate("single", false, &[]), "new"),
+            UserMergeAction::Rekeyed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_poll_runs_pending_job_in_batches_and_persists_progress() {
+        let repo = Arc::new(
+            group_repo(5)
+                .with_conversation(TENANT, "single-1", "single", &["old", "peer"])
+                .with_conversation(TENANT, "shared", "group", &["old", "new"]),
+        );
+        let publisher = Arc::new(MemoryPublisher::default());
+        let service =
+            UserMergeDomainService::new(repo.clone(), Some(publisher.clone())).with_batch_size(3);
+
+        // 没有任务时轮询为空操作
+        assert_eq!(service.resume_pending().await.unwrap(), 0);
+
+        let job = service.submit(TENANT, "old", "new").await.unwrap();
+        assert_eq!(service.resume_pending().await.unwrap(), 1);
+
+        let job = repo.job(&job.job_id);
+        assert_eq!(job.status, UserMergeJobStatus::Completed);
+        assert_eq!(job.processed_count, 7);
+        assert_eq!(job.last_conversation_id.as_deref(), Some("single-1"));
+        // 按会话ID升序每批持久化一次进度
+        let progress = repo.progress.lock().unwrap().clone();
+        assert_eq!(
+            progress,
+            vec![
+                (UserMergeJobStatus::Running, None, 0),
+                (UserMergeJobStatus::Running, Some("group-3".to_string()), 3),
+                (UserMergeJobStatus::Running, Some("shared".to_string()), 6),
+                (UserMergeJobStatus::Running, Some("single-1".to_string()), 7),
+                (
+                    UserMergeJobStatus::Completed,
+                    Some("single-1".to_string()),
+                    7
+                ),
+            ]
+        );
+
+        assert_eq!(repo.participants(TENANT, "group-1"), ["peer", "new"]);
+        assert_eq!(repo.participants(TENANT, "shared"), ["new"]);
+        // 单聊并入目标用户与对端的单聊会话
+        assert_eq!(repo.participants(TENANT, "single-1"), ["peer"]);
+        assert_eq!(
+            repo.participants(TENANT, &generate_single_chat_conversation_id("new", "peer")),
+            ["peer", "new"]
+        );
+        assert_eq!(publisher.published.lock().unwrap().len(), 7);
+
+        // 已完成的任务不再被轮询
+        assert_eq!(service.resume_pending().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_job_resumes_from_last_batch_after_retry() {
+        let repo = Arc::new(group_repo(5));
+        *repo.fail_on.lock().unwrap() = Some("group-4".to_string());
+        let service = UserMergeDomainService::new(repo.clone(), None).with_batch_size(2);
+
+        let job = service.submit(TENANT, "old", "new").await.unwrap();
+        assert_eq!(service.resume_pending().await.unwrap(), 1);
+
+        // 失败时保留已完成批次的进度，失败任务不会被自动轮询重试
+        let failed = repo.job(&job.job_id);
+        assert_eq!(failed.status, UserMergeJobStatus::Failed);
+        assert_eq!(failed.last_conversation_id.as_deref(), Some("group-3"));
+        assert_eq!(failed.processed_count, 3);
+        assert!(failed.last_error.unwrap().contains("database unavailable"));
+        assert_eq!(service.resume_pending().await.unwrap(), 0);
+
+        *repo.fail_on.lock().unwrap() = None;
+        service.retry(&job.job_id).await.unwrap();
+        assert_eq!(repo.job(&job.job_id).status, UserMergeJobStatus::Pending);
+        assert_eq!(service.resume_pending().await.unwrap(), 1);
+
+        let completed = repo.job(&job.job_id);
+        assert_eq!(completed.status, UserMergeJobStatus::Completed);
+        assert_eq!(completed.processed_count, 5);
+        assert!(completed.last_error.is_none());
+        // 每个会话只合并一次
+        assert_eq!(
+            repo.applied_conversations(),
+            ["group-1", "group-2", "group-3", "group-4", "group-5"]
+        );
+
+        // 只有失败的任务可以重试
+        assert!(service.retry(&job.job_id).await.is_err());
+        assert!(service.retry("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_merge_is_scoped_to_tenant() {
+        let repo = Arc::new(group_repo(2).with_conversation(
+            "tenant-b",
+            "group-1",
+            "group",
+            &["old", "peer"],
+        ));
+        let service = UserMergeDomainService::new(repo.clone(), None);
+
+        let job = service.submit(TENANT, "old", "new").await.unwrap();
+        service.resume_pending().await.unwrap();
+
+        assert_eq!(repo.job(&job.job_id).processed_count, 2);
+        assert_eq!(repo.participants("tenant-b", "group-1"), ["old", "peer"]);
+    }
+
+    #[tokio::test]
+    async fn test_publish_failure_does_not_fail_job() {
+        let repo = Arc::new(group_repo(2));
+        let publisher = Arc::new(MemoryPublisher {
+            fail: true,
+            ..Default::default()
+        });
+        let service = UserMergeDomainService::new(repo.clone(), Some(publisher));
+
+        let job = service.submit(TENANT, "old", "new").await.unwrap();
+        service.resume_pending().await.unwrap();
+
+        // 映射已持久化，下游可从映射表补偿
+        assert_eq!(repo.job(&job.job_id).status, UserMergeJobStatus::Completed);
+        assert_eq!(repo.applied_conversations(), ["group-1", "group-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_submit_rejects_invalid_users() {
+        let service = UserMergeDomainService::new(Arc::new(MemoryMergeRepo::default()), None);
+        assert!(service.submit(TENANT, "", "new").await.is_err());
+        assert!(service.submit(TENANT, "old", "").await.is_err());
+        assert!(service.submit(TENANT, "old", "old").await.is_err());
+    }
+}
@@ -0,0 +1,78 @@
+//! 会话生命周期 Hook
+//!
+//! 将会话创建、成员变更事件转交给 flare-im-core 的 SessionCreated / SessionMemberChanged Hook，
+//! 业务系统可按租户、会话类型、业务类型配置 WebHook 或本地 Hook。
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use flare_im_core::hooks::hook_context_data::{HookContextData, set_hook_context_data};
+use flare_im_core::hooks::{HookDispatcher, SessionCreatedEvent, SessionMemberChangedEvent};
+use flare_server_core::context::Context;
+
+use crate::domain::model::{ConversationEventKind, ConversationLifecycleEvent};
+use crate::domain::repository::ConversationEventPublisher;
+
+/// Hook 会话事件发布者
+pub struct HookConversationEventPublisher {
+    hooks: Arc<HookDispatcher>,
+}
+
+impl HookConversationEventPublisher {
+    pub fn new(hooks: Arc<HookDispatcher>) -> Self {
+        Self { hooks }
+    }
+
+    /// 构建 Hook 上下文（租户、会话类型、业务类型用于 Hook 选择器匹配）
+    fn hook_context(event: &ConversationLifecycleEvent) -> Context {
+        let mut data = HookContextData::new()
+            .with_conversation_id(event.conversation_id.clone())
+            .with_conversation_type(event.conversation_type.clone())
+            .with_business_type(event.business_type.clone());
+        data.occurred_at = Some(SystemTime::from(event.occurred_at));
+        set_hook_context_data(
+            Context::root().with_tenant_id(event.tenant_id.clone()),
+            data,
+        )
+    }
+}
+
+#[async_trait]
+impl ConversationEventPublisher for HookConversationEventPublisher {
+    async fn publish(&self, event: &ConversationLifecycleEvent) -> Result<()> {
+        let ctx = Self::hook_context(event);
+        let business_type = Some(event.business_type.clone()).filter(|value| !value.is_empty());
+        let result = match event.kind {
+            ConversationEventKind::Created => {
+                let hook_event = SessionCreatedEvent {
+                    conversation_id: event.conversation_id.clone(),
+                    conversation_type: event.conversation_type.clone(),
+                    business_type,
+                    creator_id: event.operator_id.clone(),
+                    participants: event.added.clone(),
+                    created_at: SystemTime::from(event.occurred_at),
+                    metadata: Default::default(),
+                };
+                self.hooks.session_created(&ctx, &hook_event).await
+            }
+            ConversationEventKind::MembershipChanged => {
+                let hook_event = SessionMemberChangedEvent {
+                    conversation_id: event.conversation_id.clone(),
+                    conversation_type: event.conversation_type.clone(),
+                    business_type,
+                    operator_id: event.operator_id.clone(),
+                    added: event.added.clone(),
+                    removed: event.removed.clone(),
+                    updated: event.updated.clone(),
+                    changed_at: SystemTime::from(event.occurred_at),
+                    metadata: Default::default(),
+                };
+                self.hooks.session_member_changed(&ctx, &hook_event).await
+            }
+            ConversationEventKind::Archived | ConversationEventKind::PolicyChanged => Ok(()),
+        };
+        result.map_err(|err| anyhow!("Conversation {} hook failed: {}", event.kind.as_str(), err))
+    }
+}
@@ -0,0 +1,97 @@
+//! 会话生命周期事件 Webhook
+//!
+//! 将会话创建、归档、成员变更、策略变更事件以 JSON POST 到租户配置的 Webhook，
+//! 可按业务类型和事件类型过滤，供 CRM 等业务系统同步会话状态而无需轮询搜索接口。
+//! 配置密钥时按 WebHook 签名规则签名请求体（`X-Hook-Timestamp` + `X-Hook-Signature`）。
+
+use std::time::Duration;
+
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use flare_im_core::hooks::WebhookSigner;
+use reqwest::Client;
+use serde_json::json;
+
+use crate::config::ConversationWebhookConfig;
+use crate::domain::model::ConversationLifecycleEvent;
+use crate::domain::repository::ConversationEventPublisher;
+
+/// Webhook 会话事件发布者
+pub struct WebhookConversationEventPublisher {
+    client: Client,
+    config: ConversationWebhookConfig,
+    signer: Option<WebhookSigner>,
+}
+
+impl WebhookConversationEventPublisher {
+    pub fn new(config: ConversationWebhookConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .context("Failed to build conversation webhook client")?;
+        let signer =
+            WebhookSigner::from_secrets(config.secret.clone(), config.previous_secret.clone());
+        Ok(Self {
+            client,
+            config,
+            signer,
+        })
+    }
+
+    /// 是否推送该事件（业务类型、事件类型未配置时不过滤）
+    fn accepts(&self, event: &ConversationLifecycleEvent) -> bool {
+        (self.config.business_types.is_empty()
+            || self.config.business_types.contains(&event.business_type))
+            && (self.config.events.is_empty() || self.config.events.contains(&event.kind))
+    }
+}
+
+#[async_trait]
+impl ConversationEventPublisher for WebhookConversationEventPublisher {
+    async fn publish(&self, event: &ConversationLifecycleEvent) -> Result<()> {
+        if !self.accepts(event) {
+            return Ok(());
+        }
+
+        let body = json!({
+            "event_id": event.event_id,
+            "event": event.kind.as_str(),
+            "tenant_id": event.tenant_id,
+            "conversation_id": event.conversation_id,
+            "conversation_type": event.conversation_type,
+            "business_type": event.business_type,
+            "lifecycle_state": event.lifecycle_state.as_str(),
+            "visibility": event.visibility.as_str(),
+            "history_visibility": event.history_visibility.as_str(),
+            "added": event.added,
+            "removed": event.removed,
+            "updated": event.updated,
+            "operator_id": event.operator_id,
+            "occurred_at": event.occurred_at.timestamp_millis(),
+        });
+
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .json(&body)
+            .build()
+            .context("Failed to build conversation webhook request")?;
+        if let Some(signer) = &self.signer {
+            signer.sign_request(&mut request);
+        }
+        let response = self
+            .client
+            .execute(request)
+            .await
+            .map_err(|e| anyhow!("Conversation webhook request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Conversation webhook returned {} for event {}",
+                response.status(),
+                event.event_id
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+pub mod conversation_hook;
+pub mod conversation_webhook;
+pub mod translation_webhook;
+pub mod user_merge_publisher;
+
+pub use conversation_hook::HookConversationEventPublisher;
+pub use conversation_webhook::WebhookConversationEventPublisher;
+pub use translation_webhook::WebhookMessageTranslator;
+pub use user_merge_publisher::KafkaUserMergeEventPublisher;
@@ -0,0 +1,109 @@
+//! 消息翻译 Webhook
+//!
+//! 多语言会话同步消息时，将缺少目标语言译文的文本消息以 JSON POST 到翻译服务：
+//! `{"conversation_id", "message_id", "text", "target_locale"}`，
+//! 翻译服务返回 `{"text", "source_locale"?, "provider"?}`；返回 204 或空文本表示不翻译该消息。
+//! 配置密钥时按 WebHook 签名规则签名请求体（`X-Hook-Timestamp` + `X-Hook-Signature`）。
+
+use std::time::Duration;
+
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use flare_im_core::hooks::WebhookSigner;
+use flare_im_core::utils::translation::MessageTranslation;
+use flare_server_core::context::Context;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::config::TranslationHookConfig;
+use crate::domain::repository::{MessageTranslator, TranslationRequest};
+
+#[derive(Deserialize)]
+struct TranslationResponse {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    source_locale: Option<String>,
+    #[serde(default)]
+    provider: Option<String>,
+}
+
+/// Webhook 消息翻译器
+pub struct WebhookMessageTranslator {
+    client: Client,
+    config: TranslationHookConfig,
+    signer: Option<WebhookSigner>,
+}
+
+impl WebhookMessageTranslator {
+    pub fn new(config: TranslationHookConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .context("Failed to build translation webhook client")?;
+        let signer =
+            WebhookSigner::from_secrets(config.secret.clone(), config.previous_secret.clone());
+        Ok(Self {
+            client,
+            config,
+            signer,
+        })
+    }
+}
+
+#[async_trait]
+impl MessageTranslator for WebhookMessageTranslator {
+    async fn translate(
+        &self,
+        ctx: &Context,
+        request: &TranslationRequest,
+    ) -> Result<Option<MessageTranslation>> {
+        let body = json!({
+            "tenant_id": ctx.tenant_id().unwrap_or_default(),
+            "conversation_id": request.conversation_id,
+            "message_id": request.message_id,
+            "text": request.text,
+            "target_locale": request.target_locale,
+        });
+
+        let mut http_request = self
+            .client
+            .post(&self.config.url)
+            .json(&body)
+            .build()
+            .context("Failed to build translation webhook request")?;
+        if let Some(signer) = &self.signer {
+            signer.sign_request(&mut http_request);
+        }
+        let response = self
+            .client
+            .execute(http_request)
+            .await
+            .map_err(|e| anyhow!("Translation webhook request failed: {}", e))?;
+
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Translation webhook returned {} for message {}",
+                response.status(),
+                request.message_id
+            ));
+        }
+        let translated: TranslationResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid translation webhook response: {}", e))?;
+        if translated.text.trim().is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(MessageTranslation {
+            text: translated.text,
+            source_locale: translated.source_locale.filter(|l| !l.is_empty()),
+            provider: translated.provider.filter(|p| !p.is_empty()),
+            translated_at: chrono::Utc::now().timestamp_millis(),
+        }))
+    }
+}
@@ -0,0 +1,77 @@
+//! 用户合并映射事件发布（Kafka）
+//!
+//! 每个会话的合并结果以 JSON 发布到配置的 Topic，按源用户ID作为 key 保证同一合并任务的事件有序。
+
+use std::time::Duration;
+
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde_json::json;
+
+use crate::domain::model::UserMergeMapping;
+use crate::domain::repository::UserMergeEventPublisher;
+
+/// Kafka 用户合并事件发布者
+pub struct KafkaUserMergeEventPublisher {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl KafkaUserMergeEventPublisher {
+    pub fn new(bootstrap: &str, topic: String, timeout_ms: u64) -> Result<Self> {
+        struct SimpleProducerConfig {
+            bootstrap: String,
+            timeout_ms: u64,
+        }
+
+        impl flare_server_core::kafka::KafkaProducerConfig for SimpleProducerConfig {
+            fn kafka_bootstrap(&self) -> &str {
+                &self.bootstrap
+            }
+
+            fn message_timeout_ms(&self) -> u64 {
+                self.timeout_ms
+            }
+
+            fn enable_idempotence(&self) -> bool {
+                true
+            }
+        }
+
+        let producer_config = SimpleProducerConfig {
+            bootstrap: bootstrap.to_string(),
+            timeout_ms,
+        };
+        let producer = flare_server_core::kafka::build_kafka_producer(
+            &producer_config as &dyn flare_server_core::kafka::KafkaProducerConfig,
+        )
+        .map_err(|e| anyhow!("Failed to create Kafka producer: {}", e))?;
+
+        Ok(Self { producer, topic })
+    }
+}
+
+#[async_trait]
+impl UserMergeEventPublisher for KafkaUserMergeEventPublisher {
+    async fn publish(&self, mapping: &UserMergeMapping) -> Result<()> {
+        let event = json!({
+            "job_id": mapping.job_id,
+            "tenant_id": mapping.tenant_id,
+            "source_user_id": mapping.source_user_id,
+            "target_user_id": mapping.target_user_id,
+            "conversation_id": mapping.conversation_id,
+            "action": mapping.action.as_str(),
+            "merged_into": mapping.action.merged_into(),
+        });
+        let payload = serde_json::to_vec(&event).context("Failed to serialize user merge event")?;
+        let record = FutureRecord::to(&self.topic)
+            .key(&mapping.source_user_id)
+            .payload(&payload);
+        self.producer
+            .send(record, Duration::from_secs(0))
+            .await
+            .map_err(|(e, _)| anyhow!("Failed to publish user merge event to {}: {}", self.topic, e))?;
+        Ok(())
+    }
+}
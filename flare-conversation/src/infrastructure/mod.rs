@@ -1,2 +1,3 @@
+pub mod messaging;
 pub mod persistence;
 pub mod transport;
@@ -2,6 +2,8 @@ pub mod postgres_repository;
 pub mod redis_presence;
 pub mod redis_repository;
 pub mod thread_repository;
+pub mod user_merge_repository;
 
 pub use postgres_repository::PostgresConversationRepository;
 pub use thread_repository::PostgresThreadRepository;
+pub use user_merge_repository::PostgresUserMergeRepository;
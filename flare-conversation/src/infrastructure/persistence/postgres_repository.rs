@@ -12,7 +12,9 @@ use tracing::info;
 
 use crate::config::ConversationConfig;
 use crate::domain::model::{
-    Conversation, ConversationBootstrapResult, ConversationFilter, ConversationParticipant, ConversationSort, ConversationSummary,
+    Conversation, ConversationBootstrapResult, ConversationFilter, ConversationParticipant, ConversationSort, ConversationSummary, ConversationVersionConflict,
+    HistoryVisibility, MembershipChange, MembershipChangeType, ParticipantsDiff, ParticipantsSnapshot,
+    ParticipantsSnapshotCursor,
 };
 use crate::domain::repository::ConversationRepository;
 use async_trait::async_trait;
@@ -94,6 +96,7 @@ impl ConversationRepository for PostgresConversationRepository {
                 s.attributes,
                 s.visibility,
                 s.lifecycle_state,
+                s.version,
                 s.updated_at,
                 s.last_message_seq,
                 COALESCE(sp.last_read_msg_seq, 0) as last_read_msg_seq,
@@ -121,6 +124,7 @@ impl ConversationRepository for PostgresConversationRepository {
             let business_type: Option<String> = row.get("business_type");
             let display_name: Option<String> = row.get("display_name");
             let attributes: Option<serde_json::Value> = row.get("attributes");
+            let version: i64 = row.get("version");
             let updated_at: DateTime<Utc> = row.get("updated_at");
 
             // 从数据库读取未读数相关字段
@@ -161,6 +165,7 @@ impl ConversationRepository for PostgresConversationRepository {
                 metadata: attributes,
                 server_cursor_ts,
                 display_name,
+                version: Some(version),
             };
 
             summaries.push(summary);
@@ -268,7 +273,7 @@ impl ConversationRepository for PostgresConversationRepository {
             r#"
             SELECT conversation_id, conversation_type, business_type, display_name,
                    attributes, visibility, lifecycle_state, metadata,
-                   created_at, updated_at
+                   version, created_at, updated_at
             FROM conversations
             WHERE tenant_id = $1 AND conversation_id = $2
             "#,
@@ -290,6 +295,7 @@ impl ConversationRepository for PostgresConversationRepository {
         let attributes: Option<serde_json::Value> = row.get("attributes");
         let visibility: String = row.get("visibility");
         let lifecycle_state: String = row.get("lifecycle_state");
+        let version: i64 = row.get("version");
         let created_at: DateTime<Utc> = row.get("created_at");
         let updated_at: DateTime<Utc> = row.get("updated_at");
 
@@ -357,6 +363,7 @@ impl ConversationRepository for PostgresConversationRepository {
             visibility,
             lifecycle_state,
             policy: None,
+            version,
             created_at,
             updated_at,
         }))
@@ -364,15 +371,17 @@ impl ConversationRepository for PostgresConversationRepository {
 
     async fn update_conversation(&self, ctx: &flare_server_core::context::Context, session: &Conversation) -> Result<()> {
         let tenant_id = ctx.tenant_id().unwrap_or("0");
-        sqlx::query(
+        // 乐观并发控制：仅当版本号未变化时更新，并递增版本号
+        let result = sqlx::query(
             r#"
             UPDATE conversations
             SET display_name = $1,
                 attributes = $2,
                 visibility = $3,
                 lifecycle_state = $4,
+                version = version + 1,
                 updated_at = CURRENT_TIMESTAMP
-            WHERE tenant_id = $5 AND conversation_id = $6
+            WHERE tenant_id = $5 AND conversation_id = $6 AND version = $7
             "#,
         )
         .bind(&session.display_name)
@@ -381,10 +390,34 @@ impl ConversationRepository for PostgresConversationRepository {
         .bind(session.lifecycle_state.as_str())
         .bind(tenant_id)
         .bind(&session.conversation_id)
+        .bind(session.version)
         .execute(&*self.pool)
         .await
         .context("Failed to update conversation")?;
 
+        if result.rows_affected() == 0 {
+            let current_version: Option<i64> = sqlx::query_scalar(
+                "SELECT version FROM conversations WHERE tenant_id = $1 AND conversation_id = $2",
+            )
+            .bind(tenant_id)
+            .bind(&session.conversation_id)
+            .fetch_optional(&*self.pool)
+            .await
+            .context("Failed to get conversation version")?;
+            return match current_version {
+                Some(current_version) => Err(ConversationVersionConflict {
+                    conversation_id: session.conversation_id.clone(),
+                    expected_version: session.version,
+                    current_version,
+                }
+                .into()),
+                None => Err(anyhow::anyhow!(
+                    "Conversation not found: {}",
+                    session.conversation_id
+                )),
+            };
+        }
+
         info!(conversation_id = %session.conversation_id, "Conversation updated");
         Ok(())
     }
@@ -404,7 +437,7 @@ impl ConversationRepository for PostgresConversationRepository {
             sqlx::query(
                 r#"
                 UPDATE conversations
-                SET lifecycle_state = 'deleted', updated_at = CURRENT_TIMESTAMP
+                SET lifecycle_state = 'deleted', version = version + 1, updated_at = CURRENT_TIMESTAMP
                 WHERE tenant_id = $1 AND conversation_id = $2
                 "#,
             )
@@ -429,15 +462,38 @@ impl ConversationRepository for PostgresConversationRepository {
     ) -> Result<Vec<ConversationParticipant>> {
         let tenant_id = ctx.tenant_id().unwrap_or("0");
         let mut tx = self.pool.begin().await?;
+        // 本次操作中每个用户的最终变更（用于成员版本与变更日志）
+        let mut changes: HashMap<String, MembershipChangeType> = HashMap::new();
+
+        // 新成员的历史消息可见边界由会话的历史可见性配置决定
+        let history_visible_from = if to_add.is_empty() {
+            None
+        } else {
+            let conversation_attributes: Option<serde_json::Value> = sqlx::query_scalar(
+                "SELECT attributes FROM conversations WHERE tenant_id = $1 AND conversation_id = $2",
+            )
+            .bind(tenant_id)
+            .bind(conversation_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .context("Failed to load conversation attributes")?
+            .flatten();
+            let attributes: HashMap<String, String> = conversation_attributes
+                .and_then(|v| serde_json::from_value(v).ok())
+                .unwrap_or_default();
+            HistoryVisibility::from_attributes(&attributes).visible_from(Utc::now())
+        };
 
         // 添加参与者
         for participant in to_add {
-            sqlx::query(
+            // xmax = 0 表示本次为插入而非冲突更新；已有成员保留原加入时间与可见边界
+            let inserted: bool = sqlx::query_scalar(
                 r#"
                 INSERT INTO conversation_participants (
-                    tenant_id, conversation_id, user_id, roles, muted, pinned, attributes, created_at, updated_at
+                    tenant_id, conversation_id, user_id, roles, muted, pinned, attributes,
+                    joined_at, history_visible_from, created_at, updated_at
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, CURRENT_TIMESTAMP, $8, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP)
                 ON CONFLICT (tenant_id, conversation_id, user_id)
                 DO UPDATE SET
                     roles = $4,
@@ -445,6 +501,7 @@ impl ConversationRepository for PostgresConversationRepository {
                     pinned = $6,
                     attributes = $7,
                     updated_at = CURRENT_TIMESTAMP
+                RETURNING (xmax = 0)
                 "#,
             )
             .bind(tenant_id)
@@ -454,25 +511,37 @@ impl ConversationRepository for PostgresConversationRepository {
             .bind(participant.muted)
             .bind(participant.pinned)
             .bind(serde_json::to_value(&participant.attributes)?)
-            .execute(&mut *tx)
+            .bind(history_visible_from)
+            .fetch_one(&mut *tx)
             .await
             .context("Failed to add participant")?;
+
+            let change_type = if inserted {
+                MembershipChangeType::Added
+            } else {
+                MembershipChangeType::Updated
+            };
+            changes.insert(participant.user_id.clone(), change_type);
         }
 
         // 删除参与者
         for user_id in to_remove {
-            sqlx::query("DELETE FROM conversation_participants WHERE tenant_id = $1 AND conversation_id = $2 AND user_id = $3")
+            let result = sqlx::query("DELETE FROM conversation_participants WHERE tenant_id = $1 AND conversation_id = $2 AND user_id = $3")
                 .bind(tenant_id)
                 .bind(conversation_id)
                 .bind(user_id)
                 .execute(&mut *tx)
                 .await
                 .context("Failed to remove participant")?;
+
+            if result.rows_affected() > 0 {
+                changes.insert(user_id.clone(), MembershipChangeType::Removed);
+            }
         }
 
         // 更新角色
         for (user_id, roles) in role_updates {
-            sqlx::query(
+            let result = sqlx::query(
                 r#"
                 UPDATE conversation_participants
                 SET roles = $1, updated_at = CURRENT_TIMESTAMP
@@ -486,6 +555,49 @@ impl ConversationRepository for PostgresConversationRepository {
             .execute(&mut *tx)
             .await
             .context("Failed to update participant roles")?;
+
+            if result.rows_affected() > 0 {
+                // 同一批次中新加入的成员仍视为加入
+                changes
+                    .entry(user_id.clone())
+                    .or_insert(MembershipChangeType::Updated);
+            }
+        }
+
+        // 递增成员版本号并记录变更日志
+        if !changes.is_empty() {
+            let version: i64 = sqlx::query_scalar(
+                r#"
+                UPDATE conversations
+                SET membership_version = membership_version + 1, updated_at = CURRENT_TIMESTAMP
+                WHERE tenant_id = $1 AND conversation_id = $2
+                RETURNING membership_version
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(conversation_id)
+            .fetch_one(&mut *tx)
+            .await
+            .context("Failed to bump membership version")?;
+
+            for (user_id, change_type) in &changes {
+                sqlx::query(
+                    r#"
+                    INSERT INTO conversation_membership_changes (
+                        tenant_id, conversation_id, version, user_id, change_type, created_at
+                    )
+                    VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP)
+                    "#,
+                )
+                .bind(tenant_id)
+                .bind(conversation_id)
+                .bind(version)
+                .bind(user_id)
+                .bind(change_type.as_str())
+                .execute(&mut *tx)
+                .await
+                .context("Failed to record membership change")?;
+            }
         }
 
         tx.commit().await?;
@@ -575,6 +687,7 @@ impl ConversationRepository for PostgresConversationRepository {
                 s.attributes,
                 s.visibility,
                 s.lifecycle_state,
+                s.version,
                 s.updated_at
             FROM conversations s
             "#,
@@ -602,6 +715,10 @@ impl ConversationRepository for PostgresConversationRepository {
 
         // 应用过滤器
         for filter in filters {
+            if filter.conversation_id.is_some() {
+                conditions.push(format!("s.conversation_id = ${}", bind_index));
+                bind_index += 1;
+            }
             if filter.conversation_type.is_some() {
                 conditions.push(format!("s.conversation_type = ${}", bind_index));
                 bind_index += 1;
@@ -679,6 +796,9 @@ impl ConversationRepository for PostgresConversationRepository {
 
         // 绑定过滤器参数
         for filter in filters {
+            if let Some(ref cid) = filter.conversation_id {
+                query_builder = query_builder.bind(cid);
+            }
             if let Some(ref st) = filter.conversation_type {
                 query_builder = query_builder.bind(st);
             }
@@ -713,6 +833,7 @@ impl ConversationRepository for PostgresConversationRepository {
                 let business_type: String = row.get("business_type");
                 let display_name: Option<String> = row.get("display_name");
                 let attributes: Option<serde_json::Value> = row.get("attributes");
+                let version: i64 = row.get("version");
                 let updated_at: DateTime<Utc> = row.get("updated_at");
 
                 let attributes: HashMap<String, String> = attributes
@@ -733,6 +854,7 @@ impl ConversationRepository for PostgresConversationRepository {
                     metadata: attributes,
                     server_cursor_ts,
                     display_name,
+                    version: Some(version),
                 }
             })
             .collect();
@@ -742,16 +864,27 @@ impl ConversationRepository for PostgresConversationRepository {
         // 1. 使用Redis缓存查询结果（TTL 5-10分钟）
         // 2. 使用近似值（如通过采样估算）
         // 3. 对于大用户，考虑使用分页而不显示总数
-        let count_query = query.replace("SELECT DISTINCT", "SELECT COUNT(DISTINCT s.conversation_id)");
-        let count_query = count_query.split("LIMIT").next().unwrap_or(&count_query);
-        let mut count_builder = sqlx::query_scalar::<_, i64>(count_query);
+        // 复用 FROM/WHERE 子句（去掉 ORDER BY 与 LIMIT/OFFSET），绑定顺序与上面的查询一致
+        let from_start = query.find("FROM conversations s").unwrap_or(0);
+        let from_end = query.find(" ORDER BY").unwrap_or(query.len());
+        let count_query = format!(
+            "SELECT COUNT(DISTINCT s.conversation_id) {}",
+            &query[from_start..from_end]
+        );
+        let mut count_builder = sqlx::query_scalar::<_, i64>(&count_query);
+
+        count_builder = count_builder.bind(tenant_id);
 
         if let Some(uid) = user_id {
-            count_builder = count_builder.bind(uid);
+            count_builder = count_builder.bind(tenant_id); // sp.tenant_id
+            count_builder = count_builder.bind(uid); // sp.user_id
         }
 
         // 绑定过滤器参数（与上面相同）
         for filter in filters {
+            if let Some(ref cid) = filter.conversation_id {
+                count_builder = count_builder.bind(cid);
+            }
             if let Some(ref st) = filter.conversation_type {
                 count_builder = count_builder.bind(st);
             }
@@ -765,11 +898,15 @@ impl ConversationRepository for PostgresConversationRepository {
                 count_builder = count_builder.bind(vis.as_str());
             }
             if let Some(ref pid) = filter.participant_user_id {
-                count_builder = count_builder.bind(pid);
+                count_builder = count_builder.bind(tenant_id); // sp2.tenant_id
+                count_builder = count_builder.bind(pid); // sp2.user_id
             }
         }
 
-        let total = count_builder.fetch_one(&*self.pool).await.unwrap_or(0) as usize;
+        let total = count_builder
+            .fetch_one(&*self.pool)
+            .await
+            .context("Failed to count conversations")? as usize;
 
         Ok((summaries, total))
     }
@@ -834,4 +971,347 @@ impl ConversationRepository for PostgresConversationRepository {
 
         Ok(unread_count)
     }
+
+    async fn get_participants_snapshot(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        conversation_id: &str,
+        cursor: Option<&str>,
+        limit: i32,
+    ) -> Result<ParticipantsSnapshot> {
+        let tenant_id = ctx.tenant_id().unwrap_or("0");
+        let cursor = cursor.map(ParticipantsSnapshotCursor::parse).transpose()?;
+
+        // 版本号与成员页在同一个 REPEATABLE READ 事务中读取，保证本页与版本号一致
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(&mut *tx)
+            .await
+            .context("Failed to set snapshot isolation level")?;
+
+        let conversation_row = sqlx::query(
+            r#"
+            SELECT membership_version,
+                   (SELECT COUNT(*) FROM conversation_participants
+                    WHERE tenant_id = $1 AND conversation_id = $2) AS total
+            FROM conversations
+            WHERE tenant_id = $1 AND conversation_id = $2
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(conversation_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("Failed to get membership version")?
+        .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+
+        let version: i64 = conversation_row.get("membership_version");
+        let total: i64 = conversation_row.get("total");
+
+        // 后续分页沿用首页版本号，期间成员发生变化则要求从首页重新拉取
+        if let Some(ref cursor) = cursor {
+            cursor.ensure_version(conversation_id, version)?;
+        }
+
+        // 按 user_id 做 keyset 分页，避免大群 OFFSET 扫描
+        let limit = limit.max(1) as i64;
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, roles, muted, pinned, attributes
+            FROM conversation_participants
+            WHERE tenant_id = $1 AND conversation_id = $2 AND user_id > $3
+            ORDER BY user_id
+            LIMIT $4
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(conversation_id)
+        .bind(cursor.as_ref().map_or("", |c| c.after_user_id.as_str()))
+        .bind(limit + 1)
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to get participants snapshot")?;
+        tx.commit().await?;
+
+        Ok(ParticipantsSnapshot::from_page(
+            rows.iter().map(participant_from_row).collect(),
+            limit as usize,
+            version,
+            total,
+        ))
+    }
+
+    async fn get_participants_diff(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        conversation_id: &str,
+        since_version: i64,
+        limit: i32,
+    ) -> Result<ParticipantsDiff> {
+        let tenant_id = ctx.tenant_id().unwrap_or("0");
+
+        let version_row = sqlx::query(
+            r#"
+            SELECT c.membership_version,
+                   (SELECT MIN(version) FROM conversation_membership_changes
+                    WHERE tenant_id = $1 AND conversation_id = $2) AS min_version
+            FROM conversations c
+            WHERE c.tenant_id = $1 AND c.conversation_id = $2
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(conversation_id)
+        .fetch_optional(&*self.pool)
+        .await
+        .context("Failed to get membership version")?
+        .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+
+        let current_version: i64 = version_row.get("membership_version");
+        let min_version: Option<i64> = version_row.get("min_version");
+
+        let resync = || ParticipantsDiff {
+            changes: Vec::new(),
+            since_version,
+            current_version,
+            full_resync_required: true,
+        };
+
+        // 客户端版本超前（数据被重建），需要重新拉取快照
+        if since_version > current_version {
+            return Ok(resync());
+        }
+        if since_version == current_version {
+            return Ok(ParticipantsDiff {
+                changes: Vec::new(),
+                since_version,
+                current_version,
+                full_resync_required: false,
+            });
+        }
+
+        // 变更日志已被清理，无法计算增量
+        if min_version.is_none_or(|min| min > since_version + 1) {
+            return Ok(resync());
+        }
+
+        let limit = limit.max(1) as i64;
+        let change_rows = sqlx::query(
+            r#"
+            SELECT version, user_id, change_type
+            FROM conversation_membership_changes
+            WHERE tenant_id = $1 AND conversation_id = $2 AND version > $3 AND version <= $4
+            ORDER BY version
+            LIMIT $5
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(conversation_id)
+        .bind(since_version)
+        .bind(current_version)
+        .bind(limit + 1)
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to get membership changes")?;
+
+        // 变更过多时增量不比快照划算
+        if change_rows.len() as i64 > limit {
+            return Ok(resync());
+        }
+
+        // 按用户合并，只保留最终状态
+        let mut merged: HashMap<String, (i64, MembershipChangeType)> = HashMap::new();
+        for row in &change_rows {
+            let version: i64 = row.get("version");
+            let user_id: String = row.get("user_id");
+            let change_type: String = row.get("change_type");
+            let Some(change_type) = MembershipChangeType::from_str(&change_type) else {
+                continue;
+            };
+            merged
+                .entry(user_id)
+                .and_modify(|(v, t)| {
+                    // 先加入后更新仍视为加入；先移除后加入视为加入
+                    let next = match (*t, change_type) {
+                        (MembershipChangeType::Added, MembershipChangeType::Updated) => {
+                            MembershipChangeType::Added
+                        }
+                        (_, next) => next,
+                    };
+                    *v = version;
+                    *t = next;
+                })
+                .or_insert((version, change_type));
+        }
+
+        // 加载仍在会话中的成员的当前信息
+        let present_ids: Vec<String> = merged
+            .iter()
+            .filter(|(_, (_, t))| *t != MembershipChangeType::Removed)
+            .map(|(user_id, _)| user_id.clone())
+            .collect();
+        let mut current: HashMap<String, ConversationParticipant> = HashMap::new();
+        if !present_ids.is_empty() {
+            let rows = sqlx::query(
+                r#"
+                SELECT user_id, roles, muted, pinned, attributes
+                FROM conversation_participants
+                WHERE tenant_id = $1 AND conversation_id = $2 AND user_id = ANY($3)
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(conversation_id)
+            .bind(&present_ids)
+            .fetch_all(&*self.pool)
+            .await
+            .context("Failed to get changed participants")?;
+            for row in &rows {
+                let participant = participant_from_row(row);
+                current.insert(participant.user_id.clone(), participant);
+            }
+        }
+
+        let mut changes: Vec<MembershipChange> = merged
+            .into_iter()
+            .map(|(user_id, (version, change_type))| {
+                let participant = current.remove(&user_id);
+                // 日志与当前表不一致（如并发删除）时以当前表为准
+                let change_type = if participant.is_none() {
+                    MembershipChangeType::Removed
+                } else {
+                    change_type
+                };
+                MembershipChange {
+                    user_id,
+                    change_type,
+                    version,
+                    participant,
+                }
+            })
+            .collect();
+        changes.sort_by(|a, b| a.version.cmp(&b.version).then_with(|| a.user_id.cmp(&b.user_id)));
+
+        Ok(ParticipantsDiff {
+            changes,
+            since_version,
+            current_version,
+            full_resync_required: false,
+        })
+    }
+}
+
+fn participant_from_row(row: &sqlx::postgres::PgRow) -> ConversationParticipant {
+    let attributes: Option<serde_json::Value> = row.get("attributes");
+    ConversationParticipant {
+        user_id: row.get("user_id"),
+        roles: row.get::<Option<Vec<String>>, _>("roles").unwrap_or_default(),
+        muted: row.get::<Option<bool>, _>("muted").unwrap_or(false),
+        pinned: row.get::<Option<bool>, _>("pinned").unwrap_or(false),
+        attributes: attributes
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{ConflictResolutionPolicy, ConversationPolicy, ReceiptsPolicy};
+    use flare_server_core::context::Context as RequestContext;
+
+    fn test_config() -> ConversationConfig {
+        ConversationConfig {
+            redis_url: "redis://127.0.0.1:6379/0".to_string(),
+            postgres_url: None,
+            conversation_state_prefix: "test:conversation:state".to_string(),
+            conversation_unread_prefix: "test:conversation:unread".to_string(),
+            user_cursor_prefix: "test:conversation:cursor".to_string(),
+            presence_prefix: "test:conversation:presence".to_string(),
+            storage_reader_service: None,
+            recent_message_limit: 20,
+            default_policy: ConversationPolicy {
+                conflict_resolution: ConflictResolutionPolicy::Coexist,
+                max_devices: 5,
+                allow_anonymous: false,
+                allow_history_sync: true,
+                receipts: ReceiptsPolicy::Full,
+                metadata: HashMap::new(),
+            },
+            kafka_bootstrap: None,
+            kafka_timeout_ms: 5000,
+            user_merge_topic: "test-user-merge".to_string(),
+            user_merge_poll_interval_ms: 1000,
+            webhook: None,
+            translation: None,
+            hook_config: None,
+            hook_config_dir: None,
+            require_expected_version: false,
+        }
+    }
+
+    /// 需要已执行 deploy/init.sql 与 deploy/migrations 的 PostgreSQL：
+    /// `CONVERSATION_TEST_POSTGRES_URL=postgres://... cargo test -p flare-conversation -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL (CONVERSATION_TEST_POSTGRES_URL)"]
+    async fn test_update_with_stale_version_returns_conflict() {
+        let url = std::env::var("CONVERSATION_TEST_POSTGRES_URL")
+            .expect("CONVERSATION_TEST_POSTGRES_URL is not set");
+        let pool = Arc::new(PgPool::connect(&url).await.unwrap());
+        let repo = PostgresConversationRepository::new(pool.clone(), Arc::new(test_config()));
+        let tenant_id = format!("test-{}", uuid::Uuid::new_v4());
+        let ctx = RequestContext::root().with_tenant_id(tenant_id.clone());
+
+        sqlx::query(
+            "INSERT INTO conversations (tenant_id, conversation_id, conversation_type, business_type, attributes)
+             VALUES ($1, 'conv-1', 'group', 'chat', '{}')",
+        )
+        .bind(&tenant_id)
+        .execute(&*pool)
+        .await
+        .unwrap();
+
+        let mut conversation = repo
+            .get_conversation(&ctx, "conv-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(conversation.version, 0);
+        conversation.display_name = Some("first".to_string());
+        repo.update_conversation(&ctx, &conversation).await.unwrap();
+
+        // 仍按读取时的版本号写入：被拒绝并返回当前版本，已写入的内容不被覆盖
+        conversation.display_name = Some("stale".to_string());
+        let err = repo
+            .update_conversation(&ctx, &conversation)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ConversationVersionConflict>(),
+            Some(&ConversationVersionConflict {
+                conversation_id: "conv-1".to_string(),
+                expected_version: 0,
+                current_version: 1,
+            })
+        );
+        let stored = repo
+            .get_conversation(&ctx, "conv-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.version, 1);
+        assert_eq!(stored.display_name.as_deref(), Some("first"));
+
+        // 不存在的会话不是版本冲突
+        conversation.conversation_id = "missing".to_string();
+        let err = repo
+            .update_conversation(&ctx, &conversation)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<ConversationVersionConflict>().is_none());
+
+        sqlx::query("DELETE FROM conversations WHERE tenant_id = $1")
+            .bind(&tenant_id)
+            .execute(&*pool)
+            .await
+            .unwrap();
+    }
 }
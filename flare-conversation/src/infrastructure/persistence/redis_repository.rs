@@ -8,10 +8,22 @@ use redis::{AsyncCommands, aio::ConnectionManager};
 use crate::config::ConversationConfig;
 use crate::domain::model::{
     Conversation, ConversationBootstrapResult, ConversationFilter, ConversationParticipant, ConversationSort, ConversationSummary,
+    ConversationVersionConflict, ParticipantsDiff, ParticipantsSnapshot,
 };
 use crate::domain::repository::ConversationRepository;
 use async_trait::async_trait;
 
+/// 会话状态 CAS 更新：version 与期望值一致时写入并递增，返回 {是否成功, 当前版本}
+const UPDATE_CONVERSATION_SCRIPT: &str = r#"
+local current = tonumber(redis.call('HGET', KEYS[1], 'version') or '0')
+if current ~= tonumber(ARGV[1]) then
+    return {0, current}
+end
+redis.call('HSET', KEYS[1], 'display_name', ARGV[2], 'attributes', ARGV[3],
+    'visibility', ARGV[4], 'lifecycle_state', ARGV[5], 'version', current + 1)
+return {1, current + 1}
+"#;
+
 pub struct RedisConversationRepository {
     client: Arc<redis::Client>,
     config: Arc<ConversationConfig>,
@@ -100,6 +112,13 @@ impl ConversationRepository for RedisConversationRepository {
                 metadata: HashMap::new(),
                 server_cursor_ts: last_ts.or_else(|| server_cursor.get(conversation_id).copied()),
                 display_name: state.get("display_name").cloned(),
+                // 未经过 CAS 更新的会话状态没有 version 字段，按 0 处理（与更新脚本一致）
+                version: Some(
+                    state
+                        .get("version")
+                        .and_then(|v| v.parse::<i64>().ok())
+                        .unwrap_or(0),
+                ),
             };
 
             summaries.push(summary);
@@ -139,10 +158,29 @@ impl ConversationRepository for RedisConversationRepository {
         ))
     }
 
-    async fn update_conversation(&self, _ctx: &flare_server_core::context::Context, _session: &Conversation) -> Result<()> {
-        Err(anyhow::anyhow!(
-            "RedisConversationRepository does not support update_conversation. Use PostgresConversationRepository instead."
-        ))
+    async fn update_conversation(&self, _ctx: &flare_server_core::context::Context, session: &Conversation) -> Result<()> {
+        let mut conn = self.connection().await?;
+        let state_key = self.session_state_key(&session.conversation_id);
+        let (updated, current_version): (i64, i64) = redis::Script::new(UPDATE_CONVERSATION_SCRIPT)
+            .key(&state_key)
+            .arg(session.version)
+            .arg(session.display_name.as_deref().unwrap_or_default())
+            .arg(serde_json::to_string(&session.attributes)?)
+            .arg(session.visibility.as_str())
+            .arg(session.lifecycle_state.as_str())
+            .invoke_async(&mut conn)
+            .await
+            .with_context(|| format!("update session state {}", session.conversation_id))?;
+
+        if updated == 0 {
+            return Err(ConversationVersionConflict {
+                conversation_id: session.conversation_id.clone(),
+                expected_version: session.version,
+                current_version,
+            }
+            .into());
+        }
+        Ok(())
     }
 
     async fn delete_conversation(&self, _ctx: &flare_server_core::context::Context, _conversation_id: &str, _hard_delete: bool) -> Result<()> {
@@ -204,4 +242,75 @@ impl ConversationRepository for RedisConversationRepository {
             .unwrap_or_default();
         Ok(unread)
     }
+
+    async fn get_participants_snapshot(
+        &self,
+        _ctx: &flare_server_core::context::Context,
+        _conversation_id: &str,
+        _cursor: Option<&str>,
+        _limit: i32,
+    ) -> Result<ParticipantsSnapshot> {
+        Err(anyhow::anyhow!(
+            "RedisConversationRepository does not support get_participants_snapshot. Use PostgresConversationRepository instead."
+        ))
+    }
+
+    async fn get_participants_diff(
+        &self,
+        _ctx: &flare_server_core::context::Context,
+        _conversation_id: &str,
+        _since_version: i64,
+        _limit: i32,
+    ) -> Result<ParticipantsDiff> {
+        Err(anyhow::anyhow!(
+            "RedisConversationRepository does not support get_participants_diff. Use PostgresConversationRepository instead."
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn invoke_update(
+        conn: &mut ConnectionManager,
+        key: &str,
+        expected_version: i64,
+        display_name: &str,
+    ) -> (i64, i64) {
+        redis::Script::new(UPDATE_CONVERSATION_SCRIPT)
+            .key(key)
+            .arg(expected_version)
+            .arg(display_name)
+            .arg("{}")
+            .arg("private")
+            .arg("active")
+            .invoke_async(conn)
+            .await
+            .unwrap()
+    }
+
+    /// `CONVERSATION_TEST_REDIS_URL=redis://... cargo test -p flare-conversation -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires Redis (CONVERSATION_TEST_REDIS_URL)"]
+    async fn test_update_script_rejects_stale_version() {
+        let url = std::env::var("CONVERSATION_TEST_REDIS_URL")
+            .expect("CONVERSATION_TEST_REDIS_URL is not set");
+        let client = redis::Client::open(url).unwrap();
+        let mut conn = ConnectionManager::new(client).await.unwrap();
+        let key = format!("test:conversation:state:{}", uuid::Uuid::new_v4());
+
+        // 未写过版本号的会话状态按 0 处理
+        assert_eq!(invoke_update(&mut conn, &key, 0, "first").await, (1, 1));
+        // 过期版本被拒绝，返回当前版本且不修改状态
+        assert_eq!(invoke_update(&mut conn, &key, 0, "stale").await, (0, 1));
+        let display_name: String = conn.hget(&key, "display_name").await.unwrap();
+        assert_eq!(display_name, "first");
+        // 携带当前版本时成功并递增
+        assert_eq!(invoke_update(&mut conn, &key, 1, "second").await, (1, 2));
+        let version: i64 = conn.hget(&key, "version").await.unwrap();
+        assert_eq!(version, 2);
+
+        let _: () = conn.del(&key).await.unwrap();
+    }
 }
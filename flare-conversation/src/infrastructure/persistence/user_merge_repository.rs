@@ -0,0 +1,510 @@
+//! # PostgreSQL User Merge Repository
+//!
+//! 用户账号合并的持久化实现：合并任务、会话参与关系迁移与会话映射
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use tracing::instrument;
+
+use crate::domain::model::{
+    MembershipChangeType, UserMergeAction, UserMergeCandidate, UserMergeJob, UserMergeJobStatus,
+    UserMergeMapping,
+};
+use crate::domain::repository::UserMergeRepository;
+use async_trait::async_trait;
+
+/// PostgreSQL User Merge Repository实现
+pub struct PostgresUserMergeRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresUserMergeRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_job(row: &sqlx::postgres::PgRow) -> Result<UserMergeJob> {
+        let status: String = row.get("status");
+        Ok(UserMergeJob {
+            job_id: row.get("job_id"),
+            tenant_id: row.get("tenant_id"),
+            source_user_id: row.get("source_user_id"),
+            target_user_id: row.get("target_user_id"),
+            status: UserMergeJobStatus::from_str(&status)
+                .with_context(|| format!("Unknown user merge job status: {}", status))?,
+            last_conversation_id: row.get("last_conversation_id"),
+            processed_count: row.get("processed_count"),
+            last_error: row.get("last_error"),
+            created_at: row.get::<DateTime<Utc>, _>("created_at"),
+            updated_at: row.get::<DateTime<Utc>, _>("updated_at"),
+        })
+    }
+
+    /// 递增成员版本号并记录成员变更
+    async fn record_membership_changes(
+        tx: &mut Transaction<'_, Postgres>,
+        tenant_id: &str,
+        conversation_id: &str,
+        changes: &[(&str, MembershipChangeType)],
+    ) -> Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let version: i64 = sqlx::query_scalar(
+            r#"
+            UPDATE conversations
+            SET membership_version = membership_version + 1, updated_at = CURRENT_TIMESTAMP
+            WHERE tenant_id = $1 AND conversation_id = $2
+            RETURNING membership_version
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(conversation_id)
+        .fetch_one(&mut **tx)
+        .await
+        .context("Failed to bump membership version")?;
+
+        for (user_id, change_type) in changes {
+            sqlx::query(
+                r#"
+                INSERT INTO conversation_membership_changes (
+                    tenant_id, conversation_id, version, user_id, change_type, created_at
+                )
+                VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP)
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(conversation_id)
+            .bind(version)
+            .bind(user_id)
+            .bind(change_type.as_str())
+            .execute(&mut **tx)
+            .await
+            .context("Failed to record membership change")?;
+        }
+
+        Ok(())
+    }
+
+    /// 参与关系直接改写为目标用户
+    async fn rekey(
+        tx: &mut Transaction<'_, Postgres>,
+        mapping: &UserMergeMapping,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE conversation_participants
+            SET user_id = $4, updated_at = CURRENT_TIMESTAMP
+            WHERE tenant_id = $1 AND conversation_id = $2 AND user_id = $3
+            "#,
+        )
+        .bind(&mapping.tenant_id)
+        .bind(&mapping.conversation_id)
+        .bind(&mapping.source_user_id)
+        .bind(&mapping.target_user_id)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to rekey participant")?;
+
+        Self::record_membership_changes(
+            tx,
+            &mapping.tenant_id,
+            &mapping.conversation_id,
+            &[
+                (&mapping.source_user_id, MembershipChangeType::Removed),
+                (&mapping.target_user_id, MembershipChangeType::Added),
+            ],
+        )
+        .await
+    }
+
+    /// 目标用户已在会话中：合并已读/同步状态后移除源用户
+    async fn combine(
+        tx: &mut Transaction<'_, Postgres>,
+        mapping: &UserMergeMapping,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE conversation_participants t
+            SET
+                last_read_msg_seq = GREATEST(t.last_read_msg_seq, s.last_read_msg_seq),
+                last_sync_msg_seq = GREATEST(t.last_sync_msg_seq, s.last_sync_msg_seq),
+                unread_count = LEAST(t.unread_count, s.unread_count),
+                pinned = t.pinned OR s.pinned,
+                updated_at = CURRENT_TIMESTAMP
+            FROM conversation_participants s
+            WHERE t.tenant_id = $1 AND t.conversation_id = $2 AND t.user_id = $4
+              AND s.tenant_id = $1 AND s.conversation_id = $2 AND s.user_id = $3
+            "#,
+        )
+        .bind(&mapping.tenant_id)
+        .bind(&mapping.conversation_id)
+        .bind(&mapping.source_user_id)
+        .bind(&mapping.target_user_id)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to combine participant state")?;
+
+        sqlx::query(
+            "DELETE FROM conversation_participants WHERE tenant_id = $1 AND conversation_id = $2 AND user_id = $3",
+        )
+        .bind(&mapping.tenant_id)
+        .bind(&mapping.conversation_id)
+        .bind(&mapping.source_user_id)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to remove merged participant")?;
+
+        Self::record_membership_changes(
+            tx,
+            &mapping.tenant_id,
+            &mapping.conversation_id,
+            &[
+                (&mapping.source_user_id, MembershipChangeType::Removed),
+                (&mapping.target_user_id, MembershipChangeType::Updated),
+            ],
+        )
+        .await
+    }
+
+    /// 单聊并入 (target, peer) 会话：不存在时以源会话为模板创建，迁移成员状态后归档源会话
+    async fn merge_single(
+        tx: &mut Transaction<'_, Postgres>,
+        mapping: &UserMergeMapping,
+        into: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO conversations (
+                tenant_id, conversation_id, conversation_type, business_type, display_name,
+                attributes, visibility, lifecycle_state, created_at, updated_at
+            )
+            SELECT tenant_id, $3, conversation_type, business_type, display_name,
+                   attributes, visibility, 'active', CURRENT_TIMESTAMP, CURRENT_TIMESTAMP
+            FROM conversations
+            WHERE tenant_id = $1 AND conversation_id = $2
+            ON CONFLICT (tenant_id, conversation_id) DO NOTHING
+            "#,
+        )
+        .bind(&mapping.tenant_id)
+        .bind(&mapping.conversation_id)
+        .bind(into)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to create merged single conversation")?;
+
+        // 源会话的成员（源用户改写为目标用户）并入目标会话；目标会话已有的成员保留自身的 seq 状态，
+        // 只累加源会话的未读数
+        let moved: Vec<(String, bool)> = sqlx::query_as(
+            r#"
+            INSERT INTO conversation_participants (
+                tenant_id, conversation_id, user_id, roles, muted, pinned, attributes,
+                last_read_msg_seq, last_sync_msg_seq, unread_count, joined_at, created_at, updated_at
+            )
+            SELECT tenant_id, $3, CASE WHEN user_id = $4 THEN $5 ELSE user_id END,
+                   roles, muted, pinned, attributes,
+                   last_read_msg_seq, last_sync_msg_seq, unread_count,
+                   CURRENT_TIMESTAMP, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP
+            FROM conversation_participants
+            WHERE tenant_id = $1 AND conversation_id = $2
+            ON CONFLICT (tenant_id, conversation_id, user_id)
+            DO UPDATE SET
+                unread_count = conversation_participants.unread_count + EXCLUDED.unread_count,
+                pinned = conversation_participants.pinned OR EXCLUDED.pinned,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING user_id, (xmax = 0)
+            "#,
+        )
+        .bind(&mapping.tenant_id)
+        .bind(&mapping.conversation_id)
+        .bind(into)
+        .bind(&mapping.source_user_id)
+        .bind(&mapping.target_user_id)
+        .fetch_all(&mut **tx)
+        .await
+        .context("Failed to move participants into merged conversation")?;
+
+        let removed: Vec<String> = sqlx::query_scalar(
+            "DELETE FROM conversation_participants WHERE tenant_id = $1 AND conversation_id = $2 RETURNING user_id",
+        )
+        .bind(&mapping.tenant_id)
+        .bind(&mapping.conversation_id)
+        .fetch_all(&mut **tx)
+        .await
+        .context("Failed to remove participants from source conversation")?;
+
+        sqlx::query(
+            r#"
+            UPDATE conversations
+            SET lifecycle_state = 'archived',
+                attributes = COALESCE(attributes, '{}'::jsonb) || jsonb_build_object('merged_into', $3::TEXT),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE tenant_id = $1 AND conversation_id = $2
+            "#,
+        )
+        .bind(&mapping.tenant_id)
+        .bind(&mapping.conversation_id)
+        .bind(into)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to archive source conversation")?;
+
+        let into_changes: Vec<(&str, MembershipChangeType)> = moved
+            .iter()
+            .map(|(user_id, inserted)| {
+                let change_type = if *inserted {
+                    MembershipChangeType::Added
+                } else {
+                    MembershipChangeType::Updated
+                };
+                (user_id.as_str(), change_type)
+            })
+            .collect();
+        Self::record_membership_changes(tx, &mapping.tenant_id, into, &into_changes).await?;
+
+        let removed_changes: Vec<(&str, MembershipChangeType)> = removed
+            .iter()
+            .map(|user_id| (user_id.as_str(), MembershipChangeType::Removed))
+            .collect();
+        Self::record_membership_changes(
+            tx,
+            &mapping.tenant_id,
+            &mapping.conversation_id,
+            &removed_changes,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl UserMergeRepository for PostgresUserMergeRepository {
+    #[instrument(skip(self, job), fields(job_id = %job.job_id))]
+    async fn create_job(&self, job: &UserMergeJob) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_merge_jobs (
+                job_id, tenant_id, source_user_id, target_user_id, status,
+                last_conversation_id, processed_count, last_error, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+        )
+        .bind(&job.job_id)
+        .bind(&job.tenant_id)
+        .bind(&job.source_user_id)
+        .bind(&job.target_user_id)
+        .bind(job.status.as_str())
+        .bind(&job.last_conversation_id)
+        .bind(job.processed_count)
+        .bind(&job.last_error)
+        .bind(job.created_at)
+        .bind(job.updated_at)
+        .execute(&*self.pool)
+        .await
+        .context("Failed to create user merge job")?;
+
+        Ok(())
+    }
+
+    async fn get_job(&self, job_id: &str) -> Result<Option<UserMergeJob>> {
+        let row = sqlx::query("SELECT * FROM user_merge_jobs WHERE job_id = $1")
+            .bind(job_id)
+            .fetch_optional(&*self.pool)
+            .await
+            .context("Failed to get user merge job")?;
+
+        row.as_ref().map(Self::row_to_job).transpose()
+    }
+
+    async fn list_resumable_jobs(&self, limit: i64) -> Result<Vec<UserMergeJob>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM user_merge_jobs
+            WHERE status IN ('pending', 'running')
+            ORDER BY created_at ASC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to list resumable user merge jobs")?;
+
+        rows.iter().map(Self::row_to_job).collect()
+    }
+
+    async fn update_job(
+        &self,
+        job_id: &str,
+        status: UserMergeJobStatus,
+        last_conversation_id: Option<&str>,
+        processed_count: i64,
+        last_error: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE user_merge_jobs
+            SET status = $2, last_conversation_id = $3, processed_count = $4,
+                last_error = $5, updated_at = CURRENT_TIMESTAMP
+            WHERE job_id = $1
+            "#,
+        )
+        .bind(job_id)
+        .bind(status.as_str())
+        .bind(last_conversation_id)
+        .bind(processed_count)
+        .bind(last_error)
+        .execute(&*self.pool)
+        .await
+        .context("Failed to update user merge job")?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn list_candidates(
+        &self,
+        tenant_id: &str,
+        source_user_id: &str,
+        target_user_id: &str,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<UserMergeCandidate>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                p.conversation_id,
+                c.conversation_type,
+                EXISTS (
+                    SELECT 1 FROM conversation_participants t
+                    WHERE t.tenant_id = p.tenant_id AND t.conversation_id = p.conversation_id
+                      AND t.user_id = $3
+                ) AS target_is_participant,
+                CASE WHEN c.conversation_type = 'single' THEN ARRAY(
+                    SELECT o.user_id FROM conversation_participants o
+                    WHERE o.tenant_id = p.tenant_id AND o.conversation_id = p.conversation_id
+                      AND o.user_id <> $2
+                    ORDER BY o.user_id
+                ) ELSE ARRAY[]::TEXT[] END AS peers
+            FROM conversation_participants p
+            INNER JOIN conversations c ON c.tenant_id = p.tenant_id AND c.conversation_id = p.conversation_id
+            WHERE p.tenant_id = $1 AND p.user_id = $2
+              AND ($4::TEXT IS NULL OR p.conversation_id > $4)
+            ORDER BY p.conversation_id ASC
+            LIMIT $5
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(source_user_id)
+        .bind(target_user_id)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .context("Failed to list user merge candidates")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| UserMergeCandidate {
+                conversation_id: row.get("conversation_id"),
+                conversation_type: row.get("conversation_type"),
+                target_is_participant: row.get("target_is_participant"),
+                peers: row.get("peers"),
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self, mapping), fields(job_id = %mapping.job_id, conversation_id = %mapping.conversation_id, action = mapping.action.as_str()))]
+    async fn apply_merge(&self, mapping: &UserMergeMapping) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        match &mapping.action {
+            UserMergeAction::Rekeyed => Self::rekey(&mut tx, mapping).await?,
+            UserMergeAction::Combined => Self::combine(&mut tx, mapping).await?,
+            UserMergeAction::Merged {
+                into_conversation_id,
+            } => Self::merge_single(&mut tx, mapping, into_conversation_id).await?,
+        }
+
+        // 会话拥有者随参与关系一起迁移
+        sqlx::query(
+            r#"
+            UPDATE conversations SET owner_id = $3, updated_at = CURRENT_TIMESTAMP
+            WHERE tenant_id = $1 AND conversation_id = $2 AND owner_id = $4
+            "#,
+        )
+        .bind(&mapping.tenant_id)
+        .bind(mapping.action.merged_into().unwrap_or(mapping.conversation_id.as_str()))
+        .bind(&mapping.target_user_id)
+        .bind(&mapping.source_user_id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to transfer conversation ownership")?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_merge_mappings (job_id, tenant_id, conversation_id, action, merged_into, created_at)
+            VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP)
+            ON CONFLICT (job_id, conversation_id) DO NOTHING
+            "#,
+        )
+        .bind(&mapping.job_id)
+        .bind(&mapping.tenant_id)
+        .bind(&mapping.conversation_id)
+        .bind(mapping.action.as_str())
+        .bind(mapping.action.merged_into())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to record user merge mapping")?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn merge_cursors(
+        &self,
+        tenant_id: &str,
+        source_user_id: &str,
+        target_user_id: &str,
+    ) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO user_sync_cursor (
+                tenant_id, user_id, conversation_id, last_synced_ts, last_synced_seq, created_at, updated_at
+            )
+            SELECT tenant_id, $3, conversation_id, last_synced_ts, last_synced_seq,
+                   CURRENT_TIMESTAMP, CURRENT_TIMESTAMP
+            FROM user_sync_cursor
+            WHERE tenant_id = $1 AND user_id = $2
+            ON CONFLICT (tenant_id, user_id, conversation_id)
+            DO UPDATE SET
+                last_synced_ts = GREATEST(user_sync_cursor.last_synced_ts, EXCLUDED.last_synced_ts),
+                last_synced_seq = GREATEST(user_sync_cursor.last_synced_seq, EXCLUDED.last_synced_seq),
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(source_user_id)
+        .bind(target_user_id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to merge user sync cursors")?;
+
+        sqlx::query("DELETE FROM user_sync_cursor WHERE tenant_id = $1 AND user_id = $2")
+            .bind(tenant_id)
+            .bind(source_user_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to remove merged user sync cursors")?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+}
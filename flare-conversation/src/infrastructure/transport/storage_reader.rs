@@ -1,6 +1,6 @@
 use anyhow::{Context as AnyhowContext, Result};
 use flare_proto::common::TenantContext;
-use flare_proto::storage::QueryMessagesRequest;
+use flare_proto::storage::{QueryMessagesRequest, SetMessageAttributesRequest};
 use flare_proto::storage::storage_reader_service_client::StorageReaderServiceClient;
 use flare_server_core::client::set_context_metadata;
 use flare_server_core::context::Context;
@@ -87,15 +87,9 @@ impl StorageReaderMessageProvider {
             .map(|ts| ts.seconds * 1_000 + (ts.nanos as i64 / 1_000_000))
     }
 
-    fn build_request(
-        ctx: &Context,
-        conversation_id: &str,
-        since_ts: i64,
-        cursor: Option<&str>,
-        limit: i32,
-    ) -> QueryMessagesRequest {
-        // 从 Context 构建 protobuf RequestContext 和 TenantContext
-        let request_context: flare_proto::common::RequestContext = ctx.request()
+    /// 从 Context 构建 protobuf RequestContext
+    fn request_context(ctx: &Context) -> flare_proto::common::RequestContext {
+        ctx.request()
             .cloned()
             .map(|req_ctx| req_ctx.into())
             .unwrap_or_else(|| {
@@ -113,7 +107,18 @@ impl StorageReaderMessageProvider {
                     user_agent: String::new(),
                     attributes: std::collections::HashMap::new(),
                 }
-            });
+            })
+    }
+
+    fn build_request(
+        ctx: &Context,
+        conversation_id: &str,
+        since_ts: i64,
+        cursor: Option<&str>,
+        limit: i32,
+    ) -> QueryMessagesRequest {
+        // 从 Context 构建 protobuf RequestContext 和 TenantContext
+        let request_context = Self::request_context(ctx);
 
         let tenant_context: flare_proto::common::TenantContext = ctx.tenant()
             .cloned()
@@ -387,4 +392,26 @@ impl MessageProvider for StorageReaderMessageProvider {
             server_cursor_seq,
         })
     }
+
+    async fn attach_attributes(
+        &self,
+        ctx: &Context,
+        message_id: &str,
+        attributes: HashMap<String, String>,
+    ) -> Result<()> {
+        let mut client = self.client().await?;
+        // tags 为空时 Reader 不修改已有标签
+        let mut request = Request::new(SetMessageAttributesRequest {
+            message_id: message_id.to_string(),
+            attributes,
+            context: Some(Self::request_context(ctx)),
+            ..Default::default()
+        });
+        set_context_metadata(&mut request, ctx);
+        client
+            .set_message_attributes(request)
+            .await
+            .context("call storage reader set_message_attributes")?;
+        Ok(())
+    }
 }
@@ -9,7 +9,9 @@ use flare_proto::conversation::conversation_service_server::ConversationService;
 use flare_proto::conversation::{
     BatchAcknowledgeRequest, BatchAcknowledgeResponse, CreateConversationRequest, CreateConversationResponse,
     DeleteConversationRequest, DeleteConversationResponse, DevicePresence as ProtoDevicePresence,
-    ForceConversationSyncRequest, ForceConversationSyncResponse, ListConversationsRequest, ListConversationsResponse,
+    ForceConversationSyncRequest, ForceConversationSyncResponse, GetParticipantsDiffRequest,
+    GetParticipantsDiffResponse, GetParticipantsSnapshotRequest, GetParticipantsSnapshotResponse,
+    ListConversationsRequest, ListConversationsResponse, ParticipantChange as ProtoParticipantChange,
     ManageParticipantsRequest, ManageParticipantsResponse, SearchConversationsRequest,
     SearchConversationsResponse, ConversationBootstrapRequest, ConversationBootstrapResponse,
     ConversationPolicy as ProtoConversationPolicy, SyncMessagesRequest, SyncMessagesResponse,
@@ -18,8 +20,10 @@ use flare_proto::conversation::{
 };
 use flare_server_core::context::Context;
 use flare_server_core::error;
+use flare_im_core::pagination::{CursorCodec, PageInfo, PageLimit};
 use flare_im_core::utils::context::require_context;
 use prost_types::Timestamp;
+use tonic::metadata::MetadataValue;
 use tonic::{Request, Response, Status};
 
 use crate::application::commands::{
@@ -28,15 +32,29 @@ use crate::application::commands::{
 };
 use crate::application::handlers::{ConversationCommandHandler, ConversationQueryHandler};
 use crate::application::queries::{
-    ListConversationsQuery, SearchConversationsQuery, ConversationBootstrapQuery, SyncMessagesQuery,
+    GetParticipantsDiffQuery, GetParticipantsSnapshotQuery, ListConversationsQuery,
+    SearchConversationsQuery, ConversationBootstrapQuery, SyncMessagesQuery,
 };
 use crate::domain::model::{
     ConflictResolutionPolicy, DevicePresence, DeviceState, Conversation, ConversationFilter,
     ConversationLifecycleState, ConversationParticipant, ConversationPolicy, ConversationSort, ConversationSummary,
-    ConversationVisibility, Thread, ThreadSortOrder,
+    ConversationVersionConflict, ConversationVersionRequired, ConversationVisibility,
+    ParticipantsSnapshotExpired,
+    RECEIPTS_POLICY_ATTRIBUTE, Thread,
+    ThreadSortOrder,
 };
 use crate::domain::service::ThreadDomainService;
 
+/// 更新会话时携带的期望版本号（乐观并发控制）
+const EXPECTED_VERSION_METADATA_KEY: &str = "x-expected-version";
+/// 响应中返回的会话当前版本号
+const CONVERSATION_VERSION_METADATA_KEY: &str = "x-conversation-version";
+/// 会话摘要 metadata 中携带的当前版本号（协议中没有版本字段，列表/bootstrap 通过该键下发）
+const SUMMARY_VERSION_METADATA_KEY: &str = "conversation_version";
+/// 会话搜索的分页游标作用域与条数限制
+const SEARCH_CURSOR_SCOPE: &str = "conversation.search";
+const SEARCH_PAGE_LIMIT: PageLimit = PageLimit::new(20, 1000);
+
 #[derive(Clone)]
 pub struct ConversationGrpcHandler {
     command_handler: Arc<ConversationCommandHandler>,
@@ -313,10 +331,16 @@ impl ConversationService for ConversationGrpcHandler {
             .await
             .map_err(internal_error)?;
 
-        Ok(Response::new(CreateConversationResponse {
+        let version = conversation.version;
+        let mut response = Response::new(CreateConversationResponse {
             conversation: Some(domain_to_proto_conversation(conversation)),
             status: Some(error::ok_status()),
-        }))
+        });
+        response.metadata_mut().insert(
+            CONVERSATION_VERSION_METADATA_KEY,
+            MetadataValue::from(version),
+        );
+        Ok(response)
     }
 
     async fn update_conversation(
@@ -324,6 +348,7 @@ impl ConversationService for ConversationGrpcHandler {
         request: Request<UpdateConversationRequest>,
     ) -> Result<Response<UpdateConversationResponse>, Status> {
         let ctx = require_context(&request)?;
+        let expected_version = expected_version(&request)?;
         let req = request.into_inner();
 
         let display_name = if req.display_name.is_empty() {
@@ -358,15 +383,22 @@ impl ConversationService for ConversationGrpcHandler {
                     },
                     visibility,
                     lifecycle_state,
+                    expected_version,
                 },
             )
             .await
-            .map_err(internal_error)?;
+            .map_err(update_error)?;
 
-        Ok(Response::new(UpdateConversationResponse {
+        let version = conversation.version;
+        let mut response = Response::new(UpdateConversationResponse {
             conversation: Some(domain_to_proto_conversation(conversation)),
             status: Some(error::ok_status()),
-        }))
+        });
+        response.metadata_mut().insert(
+            CONVERSATION_VERSION_METADATA_KEY,
+            MetadataValue::from(version),
+        );
+        Ok(response)
     }
 
     async fn delete_conversation(
@@ -446,6 +478,84 @@ impl ConversationService for ConversationGrpcHandler {
         }))
     }
 
+    async fn get_participants_snapshot(
+        &self,
+        request: Request<GetParticipantsSnapshotRequest>,
+    ) -> Result<Response<GetParticipantsSnapshotResponse>, Status> {
+        let ctx = require_context(&request)?;
+        let req = request.into_inner();
+
+        let cursor = if req.cursor.is_empty() {
+            None
+        } else {
+            Some(req.cursor)
+        };
+
+        let snapshot = self
+            .query_handler
+            .handle_get_participants_snapshot(
+                &ctx,
+                GetParticipantsSnapshotQuery {
+                    conversation_id: req.conversation_id,
+                    cursor,
+                    limit: req.limit,
+                },
+            )
+            .await
+            .map_err(snapshot_error)?;
+
+        Ok(Response::new(GetParticipantsSnapshotResponse {
+            participants: snapshot
+                .participants
+                .into_iter()
+                .map(proto_participant)
+                .collect(),
+            version: snapshot.version,
+            has_more: snapshot.next_cursor.is_some(),
+            next_cursor: snapshot.next_cursor.unwrap_or_default(),
+            total: snapshot.total,
+            status: Some(error::ok_status()),
+        }))
+    }
+
+    async fn get_participants_diff(
+        &self,
+        request: Request<GetParticipantsDiffRequest>,
+    ) -> Result<Response<GetParticipantsDiffResponse>, Status> {
+        let ctx = require_context(&request)?;
+        let req = request.into_inner();
+
+        let diff = self
+            .query_handler
+            .handle_get_participants_diff(
+                &ctx,
+                GetParticipantsDiffQuery {
+                    conversation_id: req.conversation_id,
+                    since_version: req.since_version,
+                    limit: req.limit,
+                },
+            )
+            .await
+            .map_err(internal_error)?;
+
+        Ok(Response::new(GetParticipantsDiffResponse {
+            changes: diff
+                .changes
+                .into_iter()
+                .map(|change| ProtoParticipantChange {
+                    user_id: change.user_id,
+                    change_type: change.change_type.as_proto(),
+                    version: change.version,
+                    participant: change.participant.map(proto_participant),
+                })
+                .collect(),
+            since_version: diff.since_version,
+            current_version: diff.current_version,
+            full_resync_required: diff.full_resync_required,
+            status: Some(error::ok_status()),
+        }))
+    }
+
     async fn batch_acknowledge(
         &self,
         request: Request<BatchAcknowledgeRequest>,
@@ -485,9 +595,24 @@ impl ConversationService for ConversationGrpcHandler {
         let mut filters = Vec::new();
         for filter_expr in &req.filters {
             let filter = match filter_expr.field.as_str() {
+                "conversation_id" => {
+                    if !filter_expr.values.is_empty() {
+                        Some(ConversationFilter {
+                            conversation_id: Some(filter_expr.values[0].clone()),
+                            conversation_type: None,
+                            business_type: None,
+                            lifecycle_state: None,
+                            visibility: None,
+                            participant_user_id: None,
+                        })
+                    } else {
+                        None
+                    }
+                }
                 "conversation_type" => {
                     if !filter_expr.values.is_empty() {
                         Some(ConversationFilter {
+                            conversation_id: None,
                             conversation_type: Some(filter_expr.values[0].clone()),
                             business_type: None,
                             lifecycle_state: None,
@@ -501,6 +626,7 @@ impl ConversationService for ConversationGrpcHandler {
                 "business_type" => {
                     if !filter_expr.values.is_empty() {
                         Some(ConversationFilter {
+                            conversation_id: None,
                             conversation_type: None,
                             business_type: Some(filter_expr.values[0].clone()),
                             lifecycle_state: None,
@@ -522,6 +648,7 @@ impl ConversationService for ConversationGrpcHandler {
                             _ => ConversationLifecycleState::Unspecified,
                         };
                         Some(ConversationFilter {
+                            conversation_id: None,
                             conversation_type: None,
                             business_type: None,
                             lifecycle_state: Some(state),
@@ -542,6 +669,7 @@ impl ConversationService for ConversationGrpcHandler {
                             _ => ConversationVisibility::Unspecified,
                         };
                         Some(ConversationFilter {
+                            conversation_id: None,
                             conversation_type: None,
                             business_type: None,
                             lifecycle_state: None,
@@ -555,6 +683,7 @@ impl ConversationService for ConversationGrpcHandler {
                 "participant_user_id" => {
                     if !filter_expr.values.is_empty() {
                         Some(ConversationFilter {
+                            conversation_id: None,
                             conversation_type: None,
                             business_type: None,
                             lifecycle_state: None,
@@ -582,13 +711,14 @@ impl ConversationService for ConversationGrpcHandler {
             })
             .collect();
 
-        let limit = req
-            .pagination
-            .as_ref()
-            .map(|p| p.limit as usize)
-            .unwrap_or(20)
-            .min(1000);
-        let offset = 0; // 分页使用cursor，offset暂时为0
+        // 分页：cursor 为上一页返回的不透明游标（编码下一页的起始偏移量）
+        let mut pagination = req.pagination.unwrap_or_default();
+        let limit = SEARCH_PAGE_LIMIT.clamp(pagination.limit as i64);
+        let codec = CursorCodec::global();
+        let offset = codec
+            .decode::<u64>(SEARCH_CURSOR_SCOPE, &pagination.cursor)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?
+            .unwrap_or(0);
 
         let (summaries, total) = self
             .query_handler
@@ -598,16 +728,16 @@ impl ConversationService for ConversationGrpcHandler {
                     filters,
                     sort,
                     limit,
-                    offset,
+                    offset: offset as usize,
                 },
             )
             .await
             .map_err(internal_error)?;
 
         // 更新pagination信息
-        let mut pagination = req.pagination.unwrap_or_default();
-        pagination.total_size = total as i64;
-        pagination.has_more = summaries.len() >= limit;
+        let request_cursor = std::mem::take(&mut pagination.cursor);
+        PageInfo::from_offset(codec, SEARCH_CURSOR_SCOPE, offset, summaries.len(), total as u64)
+            .apply_to(&mut pagination, request_cursor, limit);
 
         Ok(Response::new(SearchConversationsResponse {
             conversations: summaries.into_iter().map(proto_summary).collect(),
@@ -892,6 +1022,13 @@ impl ConversationService for ConversationGrpcHandler {
 
 fn proto_summary(summary: ConversationSummary) -> ProtoConversationSummary {
     let last_message_time = summary.last_message_time.and_then(timestamp_from_datetime);
+    let mut metadata = summary.metadata;
+    if let Some(version) = summary.version {
+        metadata.insert(
+            SUMMARY_VERSION_METADATA_KEY.to_string(),
+            version.to_string(),
+        );
+    }
 
     ProtoConversationSummary {
         conversation_id: summary.conversation_id,
@@ -912,7 +1049,7 @@ fn proto_summary(summary: ConversationSummary) -> ProtoConversationSummary {
         is_muted: false,
         is_pinned: false,
         updated_at: last_message_time,
-        metadata: summary.metadata,
+        metadata,
         labels: Vec::new(),
         is_muted_detail: false,
         mute_until: None,
@@ -920,6 +1057,16 @@ fn proto_summary(summary: ConversationSummary) -> ProtoConversationSummary {
     }
 }
 
+fn proto_participant(p: ConversationParticipant) -> flare_proto::conversation::ConversationParticipant {
+    flare_proto::conversation::ConversationParticipant {
+        user_id: p.user_id,
+        roles: p.roles,
+        muted: p.muted,
+        pinned: p.pinned,
+        attributes: p.attributes,
+    }
+}
+
 fn proto_device(device: DevicePresence) -> ProtoDevicePresence {
     let last_seen_at = device.last_seen_at.and_then(timestamp_from_datetime);
 
@@ -931,13 +1078,23 @@ fn proto_device(device: DevicePresence) -> ProtoDevicePresence {
     }
 }
 
+/// 策略 metadata（协议中没有回执策略字段，通过 `receipts_policy` 下发默认回执策略）
+fn policy_metadata(policy: &ConversationPolicy) -> HashMap<String, String> {
+    let mut metadata = policy.metadata.clone();
+    metadata.insert(
+        RECEIPTS_POLICY_ATTRIBUTE.to_string(),
+        policy.receipts.as_str().to_string(),
+    );
+    metadata
+}
+
 fn proto_policy(policy: ConversationPolicy) -> ProtoConversationPolicy {
     ProtoConversationPolicy {
         conflict_resolution: policy.conflict_resolution.as_proto(),
         max_devices: policy.max_devices,
         allow_anonymous: policy.allow_anonymous,
         allow_history_sync: policy.allow_history_sync,
-        metadata: policy.metadata,
+        metadata: policy_metadata(&policy),
     }
 }
 
@@ -947,7 +1104,7 @@ fn proto_common_policy(policy: ConversationPolicy) -> flare_proto::common::Conve
         max_devices: policy.max_devices,
         allow_anonymous: policy.allow_anonymous,
         allow_history_sync: policy.allow_history_sync,
-        metadata: policy.metadata,
+        metadata: policy_metadata(&policy),
         allow_message_search: false,
         allow_file_transfer: true,
     }
@@ -968,6 +1125,53 @@ fn failed_precondition(err: anyhow::Error) -> Status {
     Status::failed_precondition(err.to_string())
 }
 
+/// 更新会话的错误映射：版本冲突返回 ABORTED，调用方应重新读取后重试；
+/// 要求携带期望版本号而未携带时返回 FAILED_PRECONDITION
+fn update_error(err: anyhow::Error) -> Status {
+    if err.downcast_ref::<ConversationVersionRequired>().is_some() {
+        return failed_precondition(err);
+    }
+    match err.downcast_ref::<ConversationVersionConflict>() {
+        Some(conflict) => {
+            let mut status = Status::aborted(conflict.to_string());
+            status.metadata_mut().insert(
+                CONVERSATION_VERSION_METADATA_KEY,
+                MetadataValue::from(conflict.current_version),
+            );
+            status
+        }
+        None => internal_error(err),
+    }
+}
+
+/// 导出成员快照的错误映射：分页期间成员变化返回 ABORTED，调用方应从首页重新拉取
+fn snapshot_error(err: anyhow::Error) -> Status {
+    match err.downcast_ref::<ParticipantsSnapshotExpired>() {
+        Some(expired) => Status::aborted(expired.to_string()),
+        None => internal_error(err),
+    }
+}
+
+/// 读取请求元数据中的期望版本号（未携带时返回 None）
+fn expected_version<T>(request: &Request<T>) -> Result<Option<i64>, Status> {
+    request
+        .metadata()
+        .get(EXPECTED_VERSION_METADATA_KEY)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .ok_or_else(|| {
+                    Status::invalid_argument(format!(
+                        "invalid {} metadata",
+                        EXPECTED_VERSION_METADATA_KEY
+                    ))
+                })
+        })
+        .transpose()
+}
+
 fn thread_to_proto(thread: Thread) -> flare_proto::conversation::Thread {
     flare_proto::conversation::Thread {
         id: thread.id,
@@ -5,13 +5,20 @@
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
+use flare_im_core::hooks::{HookConfigLoader, HookDispatcher};
 
-use crate::application::handlers::{ConversationCommandHandler, ConversationQueryHandler};
+use crate::application::handlers::{
+    ConversationCommandHandler, ConversationQueryHandler, UserMergeCommandHandler,
+};
 use crate::config::ConversationConfig;
-use crate::domain::model::ConversationDomainConfig;
-use crate::domain::repository::MessageProvider;
-use crate::domain::service::ConversationDomainService;
-use crate::infrastructure::persistence::PostgresConversationRepository;
+use crate::domain::model::{ConversationDomainConfig, TranslationLimits};
+use crate::domain::repository::{MessageProvider, UserMergeEventPublisher};
+use crate::domain::service::{ConversationDomainService, UserMergeDomainService};
+use crate::infrastructure::messaging::{
+    HookConversationEventPublisher, KafkaUserMergeEventPublisher, WebhookConversationEventPublisher,
+    WebhookMessageTranslator,
+};
+use crate::infrastructure::persistence::{PostgresConversationRepository, PostgresUserMergeRepository};
 use crate::infrastructure::persistence::redis_presence::RedisPresenceRepository;
 use crate::infrastructure::persistence::redis_repository::RedisConversationRepository;
 use crate::infrastructure::transport::storage_reader::StorageReaderMessageProvider;
@@ -20,6 +27,8 @@ use crate::interface::grpc::handler::ConversationGrpcHandler;
 /// 应用上下文 - 包含所有已初始化的服务
 pub struct ApplicationContext {
     pub handler: ConversationGrpcHandler,
+    /// 用户合并命令处理器（需要 PostgreSQL）
+    pub user_merge_handler: Option<Arc<UserMergeCommandHandler>>,
 }
 
 /// 构建应用上下文
@@ -102,20 +111,54 @@ pub async fn initialize(
     };
 
     // 7. 构建领域配置
-    let domain_config = ConversationDomainConfig::new(conversation_config.recent_message_limit);
+    let domain_config = ConversationDomainConfig::new(conversation_config.recent_message_limit)
+        .with_require_expected_version(conversation_config.require_expected_version);
 
     // 8. 转换 message_provider 类型
     let message_provider_for_domain: Option<Arc<dyn MessageProvider>> = message_provider
         .clone()
         .map(|p| p as Arc<dyn MessageProvider>);
 
-    // 9. 构建领域服务
-    let domain_service = Arc::new(ConversationDomainService::new(
+    // 9. 构建领域服务（配置了 Webhook 时推送会话生命周期事件，会话创建/成员变更执行 Hook，配置翻译 Hook 时为多语言会话补齐译文）
+    let mut domain_service = ConversationDomainService::new(
         conversation_repo.clone(),
         presence_repo,
         message_provider_for_domain,
         domain_config,
+    );
+    if let Some(ref webhook) = conversation_config.webhook {
+        let publisher = WebhookConversationEventPublisher::new(webhook.clone())
+            .context("Failed to create conversation webhook publisher")?;
+        domain_service = domain_service.with_event_publisher(Arc::new(publisher));
+        tracing::info!(url = %webhook.url, "Conversation lifecycle webhook enabled");
+    }
+    if let Some(ref translation) = conversation_config.translation {
+        let translator = WebhookMessageTranslator::new(translation.clone())
+            .context("Failed to create translation webhook client")?;
+        domain_service = domain_service.with_translator(
+            Arc::new(translator),
+            TranslationLimits {
+                max_per_sync: translation.max_messages_per_sync,
+                concurrency: translation.concurrency,
+                budget: std::time::Duration::from_millis(translation.sync_budget_ms),
+            },
+        );
+        tracing::info!(url = %translation.url, "Message translation hook enabled");
+    }
+    let mut hook_loader = HookConfigLoader::new();
+    if let Some(path) = &conversation_config.hook_config {
+        hook_loader = hook_loader.add_candidate(path.clone());
+    }
+    if let Some(dir) = &conversation_config.hook_config_dir {
+        hook_loader = hook_loader.add_candidate(dir.clone());
+    }
+    let hooks = HookDispatcher::load(&hook_loader)
+        .await
+        .map_err(|err| anyhow::anyhow!("Failed to load hooks: {}", err))?;
+    domain_service = domain_service.with_event_publisher(Arc::new(
+        HookConversationEventPublisher::new(Arc::new(hooks)),
     ));
+    let domain_service = Arc::new(domain_service);
 
     // 10. 构建命令处理器
     let command_handler = Arc::new(ConversationCommandHandler::new(domain_service.clone()));
@@ -130,7 +173,46 @@ pub async fn initialize(
     // 12. 构建 gRPC 处理器
     let grpc_handler = ConversationGrpcHandler::new(command_handler, query_handler, None);
 
+    // 13. 构建用户合并任务（需要 PostgreSQL），并在后台续跑未完成的任务
+    let user_merge_handler = postgres_pool
+        .as_ref()
+        .map(|pool| build_user_merge_handler(pool.clone(), &conversation_config))
+        .transpose()?;
+    if let Some(handler) = &user_merge_handler {
+        let poll_interval =
+            std::time::Duration::from_millis(conversation_config.user_merge_poll_interval_ms.max(1));
+        tokio::spawn(handler.clone().run_pending_jobs(poll_interval));
+    }
+
     Ok(ApplicationContext {
         handler: grpc_handler,
+        user_merge_handler,
     })
 }
+
+/// 构建用户合并命令处理器
+fn build_user_merge_handler(
+    pool: Arc<sqlx::PgPool>,
+    config: &ConversationConfig,
+) -> Result<Arc<UserMergeCommandHandler>> {
+    let publisher = config
+        .kafka_bootstrap
+        .as_ref()
+        .map(|bootstrap| {
+            KafkaUserMergeEventPublisher::new(
+                bootstrap,
+                config.user_merge_topic.clone(),
+                config.kafka_timeout_ms,
+            )
+            .map(|publisher| Arc::new(publisher) as Arc<dyn UserMergeEventPublisher>)
+        })
+        .transpose()
+        .context("Failed to create user merge event publisher")?;
+
+    let domain_service = Arc::new(UserMergeDomainService::new(
+        Arc::new(PostgresUserMergeRepository::new(pool)),
+        publisher,
+    ));
+
+    Ok(Arc::new(UserMergeCommandHandler::new(domain_service)))
+}
@@ -1,10 +1,12 @@
 use anyhow::Result;
+use flare_im_core::service_names::CORE_GATEWAY;
 use flare_im_core::tracing::init_tracing_from_config;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // 从配置初始化日志系统（默认 debug 级别）
     init_tracing_from_config(None);
+    flare_im_core::build_info!(CORE_GATEWAY).install();
 
     // 创建应用并启动
     flare_core_gateway::ApplicationBootstrap::run().await
@@ -2,6 +2,9 @@ use anyhow::Result;
 use flare_im_core::config::FlareAppConfig;
 use std::env;
 
+/// 路由表在配置中心中的默认键
+const DEFAULT_ROUTES_CONFIG_KEY: &str = "/flare/core-gateway/routes";
+
 #[derive(Debug, Clone)]
 pub struct GatewayConfig {
     pub signaling_service: String,
@@ -16,6 +19,20 @@ pub struct GatewayConfig {
     pub route_service: String,
     pub use_route_service: bool,
     pub default_svid: String,
+    /// 控制台 SSE 桥接监听地址（未配置时不启动）
+    pub dashboard_address: Option<String>,
+    /// 指标快照推送间隔（毫秒）
+    pub dashboard_metrics_interval_ms: u64,
+    /// JWT 签名密钥文件（未配置时使用 JWT_SECRET_KEY 单密钥）
+    pub token_keys_file: Option<String>,
+    /// 下游路由表文件（TOML/JSON）
+    pub routes_file: Option<String>,
+    /// 下游路由表配置中心地址（etcd://host:port，优先于路由表文件）
+    pub routes_config_endpoint: Option<String>,
+    /// 路由表在配置中心中的键
+    pub routes_config_key: String,
+    /// 路由表刷新间隔（毫秒）
+    pub routes_refresh_interval_ms: u64,
 }
 
 impl GatewayConfig {
@@ -80,6 +97,20 @@ impl GatewayConfig {
                 .unwrap_or_else(|| "signaling-route".to_string()),
             use_route_service: cfg.use_route_service.unwrap_or(false),
             default_svid: cfg.default_svid.unwrap_or_else(|| "svid.im".to_string()),
+            dashboard_address: env::var("CORE_GATEWAY_DASHBOARD_ADDRESS").ok(),
+            dashboard_metrics_interval_ms: env::var("CORE_GATEWAY_DASHBOARD_METRICS_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5_000),
+            token_keys_file: env::var("JWT_KEYS_FILE").ok().or(cfg.token_keys_file),
+            routes_file: env::var("CORE_GATEWAY_ROUTES_FILE").ok(),
+            routes_config_endpoint: env::var("CORE_GATEWAY_ROUTES_CONFIG_ENDPOINT").ok(),
+            routes_config_key: env::var("CORE_GATEWAY_ROUTES_CONFIG_KEY")
+                .unwrap_or_else(|_| DEFAULT_ROUTES_CONFIG_KEY.to_string()),
+            routes_refresh_interval_ms: env::var("CORE_GATEWAY_ROUTES_REFRESH_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30_000),
         })
     }
 
@@ -108,6 +139,13 @@ impl GatewayConfig {
                 .parse()
                 .unwrap_or(false),
             default_svid: env::var("DEFAULT_SVID").unwrap_or_else(|_| "svid.im".to_string()),
+            dashboard_address: env::var("CORE_GATEWAY_DASHBOARD_ADDRESS").ok(),
+            dashboard_metrics_interval_ms: 5_000,
+            token_keys_file: env::var("JWT_KEYS_FILE").ok(),
+            routes_file: env::var("CORE_GATEWAY_ROUTES_FILE").ok(),
+            routes_config_endpoint: env::var("CORE_GATEWAY_ROUTES_CONFIG_ENDPOINT").ok(),
+            routes_config_key: DEFAULT_ROUTES_CONFIG_KEY.to_string(),
+            routes_refresh_interval_ms: 30_000,
         }
     }
 }
@@ -0,0 +1,102 @@
+//! 控制台事件总线
+//!
+//! 进程内按主题（topic）广播的事件总线，供管理控制台的 SSE 桥接订阅。
+//! 主题为固定集合（[`DashboardTopic`]），每个主题都有对应的发布方；
+//! 没有订阅者时发布直接丢弃。
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+/// 每个主题的默认缓冲区大小（订阅者落后超过该数量时丢弃旧事件）
+const DEFAULT_TOPIC_CAPACITY: usize = 256;
+
+/// 控制台主题
+///
+/// 新增主题时必须同时接入发布方，避免控制台订阅到永远没有事件的主题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DashboardTopic {
+    /// 指标快照（由 `metrics_feed` 定期发布，包含消息速率、在线数、Hook 失败等指标）
+    Metrics,
+}
+
+impl DashboardTopic {
+    /// 全部主题
+    pub const ALL: [DashboardTopic; 1] = [DashboardTopic::Metrics];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DashboardTopic::Metrics => "metrics",
+        }
+    }
+
+    /// 按名称解析主题，未知主题返回 None
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|topic| topic.as_str() == name)
+    }
+}
+
+/// 控制台事件
+#[derive(Debug, Clone, Serialize)]
+pub struct DashboardEvent {
+    /// 主题
+    pub topic: String,
+    /// 事件内容
+    pub payload: Value,
+    /// 事件时间（毫秒时间戳）
+    pub timestamp_ms: i64,
+}
+
+/// 控制台事件总线
+pub struct DashboardEventBus {
+    topics: HashMap<DashboardTopic, broadcast::Sender<DashboardEvent>>,
+}
+
+impl DashboardEventBus {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_TOPIC_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            topics: DashboardTopic::ALL
+                .into_iter()
+                .map(|topic| (topic, broadcast::channel(capacity).0))
+                .collect(),
+        }
+    }
+
+    fn sender(&self, topic: DashboardTopic) -> &broadcast::Sender<DashboardEvent> {
+        // 构造时已为全部主题创建通道
+        &self.topics[&topic]
+    }
+
+    /// 发布事件，返回收到事件的订阅者数量
+    pub fn publish(&self, topic: DashboardTopic, payload: Value) -> usize {
+        let event = DashboardEvent {
+            topic: topic.as_str().to_string(),
+            payload,
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        };
+        self.sender(topic).send(event).unwrap_or(0)
+    }
+
+    /// 订阅主题
+    pub fn subscribe(&self, topic: DashboardTopic) -> broadcast::Receiver<DashboardEvent> {
+        self.sender(topic).subscribe()
+    }
+
+    /// 主题当前的订阅者数量
+    pub fn subscriber_count(&self, topic: DashboardTopic) -> usize {
+        self.sender(topic).receiver_count()
+    }
+}
+
+impl Default for DashboardEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
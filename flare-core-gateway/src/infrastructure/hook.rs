@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tonic::metadata::MetadataMap;
 use tonic::transport::Channel;
 use tonic::{Request, Response, Status};
 
@@ -10,6 +11,8 @@ use flare_proto::hooks::*;
 
 use flare_server_core::discovery::ServiceClient;
 
+use crate::infrastructure::routing::{Downstream, RouteTable};
+
 /// gRPC Hook服务客户端
 pub struct GrpcHookClient {
     /// 服务客户端（用于服务发现）
@@ -18,6 +21,8 @@ pub struct GrpcHookClient {
     service_name: String,
     /// 直连地址（当没有服务发现时使用）
     direct_address: Option<String>,
+    /// 动态路由表（配置后优先于静态配置）
+    route_table: Option<Arc<RouteTable>>,
 }
 
 impl GrpcHookClient {
@@ -27,6 +32,7 @@ impl GrpcHookClient {
             service_client: None,
             service_name,
             direct_address: None,
+            route_table: None,
         }
     }
 
@@ -36,6 +42,7 @@ impl GrpcHookClient {
             service_client: Some(Arc::new(Mutex::new(service_client))),
             service_name,
             direct_address: None,
+            route_table: None,
         }
     }
 
@@ -45,11 +52,27 @@ impl GrpcHookClient {
             service_client: None,
             service_name,
             direct_address: Some(direct_address),
+            route_table: None,
         }
     }
 
+    /// 设置动态路由表
+    pub fn with_route_table(mut self, route_table: Arc<RouteTable>) -> Self {
+        self.route_table = Some(route_table);
+        self
+    }
+
     /// 获取gRPC客户端
-    async fn get_client(&self) -> Result<HookServiceClient<Channel>, Status> {
+    async fn get_client(
+        &self,
+        metadata: &MetadataMap,
+    ) -> Result<HookServiceClient<Channel>, Status> {
+        if let Some(route_table) = &self.route_table {
+            if let Some(channel) = route_table.channel(Downstream::Hook, metadata).await? {
+                return Ok(HookServiceClient::new(channel));
+            }
+        }
+
         if let Some(service_client) = &self.service_client {
             let mut client = service_client.lock().await;
             let channel = client.get_channel().await.map_err(|e| {
@@ -89,7 +112,7 @@ impl GrpcHookClient {
         &self,
         request: Request<CreateHookConfigRequest>,
     ) -> Result<Response<CreateHookConfigResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.create_hook_config(request).await
     }
 
@@ -98,7 +121,7 @@ impl GrpcHookClient {
         &self,
         request: Request<GetHookConfigRequest>,
     ) -> Result<Response<GetHookConfigResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.get_hook_config(request).await
     }
 
@@ -107,7 +130,7 @@ impl GrpcHookClient {
         &self,
         request: Request<UpdateHookConfigRequest>,
     ) -> Result<Response<UpdateHookConfigResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.update_hook_config(request).await
     }
 
@@ -116,7 +139,7 @@ impl GrpcHookClient {
         &self,
         request: Request<ListHookConfigsRequest>,
     ) -> Result<Response<ListHookConfigsResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.list_hook_configs(request).await
     }
 
@@ -125,7 +148,7 @@ impl GrpcHookClient {
         &self,
         request: Request<DeleteHookConfigRequest>,
     ) -> Result<Response<DeleteHookConfigResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.delete_hook_config(request).await
     }
 
@@ -134,7 +157,7 @@ impl GrpcHookClient {
         &self,
         request: Request<SetHookStatusRequest>,
     ) -> Result<Response<SetHookStatusResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.set_hook_status(request).await
     }
 
@@ -143,7 +166,7 @@ impl GrpcHookClient {
         &self,
         request: Request<GetHookStatisticsRequest>,
     ) -> Result<Response<GetHookStatisticsResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.get_hook_statistics(request).await
     }
 
@@ -152,7 +175,7 @@ impl GrpcHookClient {
         &self,
         request: Request<QueryHookExecutionsRequest>,
     ) -> Result<Response<QueryHookExecutionsResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.query_hook_executions(request).await
     }
 }
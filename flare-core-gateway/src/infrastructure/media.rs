@@ -3,6 +3,7 @@
 use futures_util::stream::StreamExt;
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tonic::metadata::MetadataMap;
 use tonic::transport::Channel;
 use tonic::{Request, Response, Status, Streaming};
 
@@ -11,6 +12,8 @@ use flare_proto::media::*;
 
 use flare_server_core::discovery::ServiceClient;
 
+use crate::infrastructure::routing::{Downstream, RouteTable};
+
 /// gRPC媒体服务客户端
 pub struct GrpcMediaClient {
     /// 服务客户端（用于服务发现）
@@ -19,6 +22,8 @@ pub struct GrpcMediaClient {
     service_name: String,
     /// 直连地址（当没有服务发现时使用）
     direct_address: Option<String>,
+    /// 动态路由表（配置后优先于静态配置）
+    route_table: Option<Arc<RouteTable>>,
 }
 
 impl GrpcMediaClient {
@@ -28,6 +33,7 @@ impl GrpcMediaClient {
             service_client: None,
             service_name,
             direct_address: None,
+            route_table: None,
         }
     }
 
@@ -37,6 +43,7 @@ impl GrpcMediaClient {
             service_client: Some(Arc::new(Mutex::new(service_client))),
             service_name,
             direct_address: None,
+            route_table: None,
         }
     }
 
@@ -46,11 +53,27 @@ impl GrpcMediaClient {
             service_client: None,
             service_name,
             direct_address: Some(direct_address),
+            route_table: None,
         }
     }
 
+    /// 设置动态路由表
+    pub fn with_route_table(mut self, route_table: Arc<RouteTable>) -> Self {
+        self.route_table = Some(route_table);
+        self
+    }
+
     /// 获取gRPC客户端
-    async fn get_client(&self) -> Result<MediaServiceClient<Channel>, Status> {
+    async fn get_client(
+        &self,
+        metadata: &MetadataMap,
+    ) -> Result<MediaServiceClient<Channel>, Status> {
+        if let Some(route_table) = &self.route_table {
+            if let Some(channel) = route_table.channel(Downstream::Media, metadata).await? {
+                return Ok(MediaServiceClient::new(channel));
+            }
+        }
+
         if let Some(service_client) = &self.service_client {
             let mut client = service_client.lock().await;
             let channel = client.get_channel().await.map_err(|e| {
@@ -90,6 +113,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<tonic::Streaming<UploadFileRequest>>,
     ) -> Result<Response<UploadFileResponse>, Status> {
+        let metadata = request.metadata().clone();
         // 收集流中的所有元素，处理Result类型
         let stream = request.into_inner();
         let items: Vec<Result<UploadFileRequest, Status>> = stream.collect().await;
@@ -101,7 +125,7 @@ impl GrpcMediaClient {
         // 创建一个新的流
         let new_stream = tokio_stream::iter(upload_requests);
 
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(&metadata).await?;
         client.upload_file(new_stream).await
     }
 
@@ -110,7 +134,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<InitiateMultipartUploadRequest>,
     ) -> Result<Response<InitiateMultipartUploadResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.initiate_multipart_upload(request).await
     }
 
@@ -119,7 +143,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<UploadMultipartChunkRequest>,
     ) -> Result<Response<UploadMultipartChunkResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.upload_multipart_chunk(request).await
     }
 
@@ -128,7 +152,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<CompleteMultipartUploadRequest>,
     ) -> Result<Response<UploadFileResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.complete_multipart_upload(request).await
     }
 
@@ -137,7 +161,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<AbortMultipartUploadRequest>,
     ) -> Result<Response<AbortMultipartUploadResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.abort_multipart_upload(request).await
     }
 
@@ -146,7 +170,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<CreateReferenceRequest>,
     ) -> Result<Response<CreateReferenceResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.create_reference(request).await
     }
 
@@ -155,7 +179,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<DeleteReferenceRequest>,
     ) -> Result<Response<DeleteReferenceResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.delete_reference(request).await
     }
 
@@ -164,7 +188,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<ListReferencesRequest>,
     ) -> Result<Response<ListReferencesResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.list_references(request).await
     }
 
@@ -173,7 +197,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<CleanupOrphanedAssetsRequest>,
     ) -> Result<Response<CleanupOrphanedAssetsResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.cleanup_orphaned_assets(request).await
     }
 
@@ -182,7 +206,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<GetFileUrlRequest>,
     ) -> Result<Response<GetFileUrlResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.get_file_url(request).await
     }
 
@@ -191,7 +215,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<GetFileInfoRequest>,
     ) -> Result<Response<GetFileInfoResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.get_file_info(request).await
     }
 
@@ -200,7 +224,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<DeleteFileRequest>,
     ) -> Result<Response<DeleteFileResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.delete_file(request).await
     }
 
@@ -209,7 +233,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<ProcessImageRequest>,
     ) -> Result<Response<ProcessImageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.process_image(request).await
     }
 
@@ -218,7 +242,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<ProcessVideoRequest>,
     ) -> Result<Response<ProcessVideoResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.process_video(request).await
     }
 
@@ -227,7 +251,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<SetObjectAclRequest>,
     ) -> Result<Response<SetObjectAclResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.set_object_acl(request).await
     }
 
@@ -236,7 +260,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<ListObjectsRequest>,
     ) -> Result<Response<ListObjectsResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.list_objects(request).await
     }
 
@@ -245,7 +269,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<GenerateUploadUrlRequest>,
     ) -> Result<Response<GenerateUploadUrlResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.generate_upload_url(request).await
     }
 
@@ -254,7 +278,7 @@ impl GrpcMediaClient {
         &self,
         request: Request<DescribeBucketRequest>,
     ) -> Result<Response<DescribeBucketResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.describe_bucket(request).await
     }
 }
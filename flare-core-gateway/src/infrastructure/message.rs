@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tonic::metadata::MetadataMap;
 use tonic::transport::Channel;
 use tonic::{Request, Response, Status};
 
@@ -10,6 +11,8 @@ use flare_proto::message::*;
 
 use flare_server_core::discovery::ServiceClient;
 
+use crate::infrastructure::routing::{Downstream, RouteTable};
+
 /// gRPC消息服务客户端
 pub struct GrpcMessageClient {
     /// 服务客户端（用于服务发现）
@@ -18,6 +21,8 @@ pub struct GrpcMessageClient {
     service_name: String,
     /// 直连地址（当没有服务发现时使用）
     direct_address: Option<String>,
+    /// 动态路由表（配置后优先于静态配置）
+    route_table: Option<Arc<RouteTable>>,
 }
 
 impl GrpcMessageClient {
@@ -27,6 +32,7 @@ impl GrpcMessageClient {
             service_client: None,
             service_name,
             direct_address: None,
+            route_table: None,
         }
     }
 
@@ -36,6 +42,7 @@ impl GrpcMessageClient {
             service_client: Some(Arc::new(Mutex::new(service_client))),
             service_name,
             direct_address: None,
+            route_table: None,
         }
     }
 
@@ -45,11 +52,27 @@ impl GrpcMessageClient {
             service_client: None,
             service_name,
             direct_address: Some(direct_address),
+            route_table: None,
         }
     }
 
+    /// 设置动态路由表
+    pub fn with_route_table(mut self, route_table: Arc<RouteTable>) -> Self {
+        self.route_table = Some(route_table);
+        self
+    }
+
     /// 获取gRPC客户端
-    async fn get_client(&self) -> Result<MessageServiceClient<Channel>, Status> {
+    async fn get_client(
+        &self,
+        metadata: &MetadataMap,
+    ) -> Result<MessageServiceClient<Channel>, Status> {
+        if let Some(route_table) = &self.route_table {
+            if let Some(channel) = route_table.channel(Downstream::Message, metadata).await? {
+                return Ok(MessageServiceClient::new(channel));
+            }
+        }
+
         if let Some(service_client) = &self.service_client {
             let mut client = service_client.lock().await;
             let channel = client.get_channel().await.map_err(|e| {
@@ -89,7 +112,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<SendMessageRequest>,
     ) -> Result<Response<SendMessageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.send_message(request).await
     }
 
@@ -98,7 +121,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<BatchSendMessageRequest>,
     ) -> Result<Response<BatchSendMessageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.batch_send_message(request).await
     }
 
@@ -107,7 +130,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<SendSystemMessageRequest>,
     ) -> Result<Response<SendSystemMessageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.send_system_message(request).await
     }
 
@@ -116,7 +139,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<QueryMessagesRequest>,
     ) -> Result<Response<QueryMessagesResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.query_messages(request).await
     }
 
@@ -125,7 +148,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<SearchMessagesRequest>,
     ) -> Result<Response<SearchMessagesResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.search_messages(request).await
     }
 
@@ -134,7 +157,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<GetMessageRequest>,
     ) -> Result<Response<GetMessageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.get_message(request).await
     }
 
@@ -143,7 +166,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<RecallMessageRequest>,
     ) -> Result<Response<RecallMessageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.recall_message(request).await
     }
 
@@ -152,7 +175,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<DeleteMessageRequest>,
     ) -> Result<Response<DeleteMessageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.delete_message(request).await
     }
 
@@ -161,7 +184,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<MarkMessageReadRequest>,
     ) -> Result<Response<MarkMessageReadResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.mark_message_read(request).await
     }
 
@@ -170,7 +193,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<EditMessageRequest>,
     ) -> Result<Response<EditMessageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.edit_message(request).await
     }
 
@@ -179,7 +202,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<AddReactionRequest>,
     ) -> Result<Response<AddReactionResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.add_reaction(request).await
     }
 
@@ -188,7 +211,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<RemoveReactionRequest>,
     ) -> Result<Response<RemoveReactionResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.remove_reaction(request).await
     }
 
@@ -197,7 +220,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<PinMessageRequest>,
     ) -> Result<Response<PinMessageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.pin_message(request).await
     }
 
@@ -206,7 +229,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<UnpinMessageRequest>,
     ) -> Result<Response<UnpinMessageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.unpin_message(request).await
     }
 
@@ -215,7 +238,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<MarkMessageRequest>,
     ) -> Result<Response<MarkMessageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.mark_message(request).await
     }
 
@@ -224,7 +247,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<BatchMarkMessageReadRequest>,
     ) -> Result<Response<BatchMarkMessageReadResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.batch_mark_message_read(request).await
     }
 
@@ -233,7 +256,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<MarkMessagesReadUntilRequest>,
     ) -> Result<Response<MarkMessagesReadUntilResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.mark_messages_read_until(request).await
     }
 
@@ -242,7 +265,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<GetPinnedMessagesRequest>,
     ) -> Result<Response<GetPinnedMessagesResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.get_pinned_messages(request).await
     }
 
@@ -251,7 +274,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<GetMarkedMessagesRequest>,
     ) -> Result<Response<GetMarkedMessagesResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.get_marked_messages(request).await
     }
 
@@ -260,7 +283,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<GetThreadsRequest>,
     ) -> Result<Response<GetThreadsResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.get_threads(request).await
     }
 
@@ -269,7 +292,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<GetThreadRepliesRequest>,
     ) -> Result<Response<GetThreadRepliesResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.get_thread_replies(request).await
     }
 
@@ -278,7 +301,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<MarkConversationReadRequest>,
     ) -> Result<Response<MarkConversationReadResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.mark_conversation_read(request).await
     }
 
@@ -287,7 +310,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<MarkAllConversationsReadRequest>,
     ) -> Result<Response<MarkAllConversationsReadResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.mark_all_conversations_read(request).await
     }
 
@@ -296,7 +319,7 @@ impl GrpcMessageClient {
         &self,
         request: Request<UnmarkMessageRequest>,
     ) -> Result<Response<UnmarkMessageResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.unmark_message(request).await
     }
 }
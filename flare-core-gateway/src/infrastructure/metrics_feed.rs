@@ -0,0 +1,148 @@
+//! 指标推送源
+//!
+//! 定期采集 Prometheus 指标注册表，将快照发布到事件总线的 `metrics` 主题，
+//! 控制台通过 SSE 订阅即可获得实时指标，无需轮询 Prometheus。
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::infrastructure::event_bus::{DashboardEventBus, DashboardTopic};
+
+/// 单个指标样本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSample {
+    pub name: String,
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+}
+
+/// 启动指标采集任务
+///
+/// 没有订阅者时跳过采集，避免无意义的开销
+pub fn spawn_metrics_feed(bus: Arc<DashboardEventBus>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval.max(Duration::from_millis(1)));
+        loop {
+            ticker.tick().await;
+            if bus.subscriber_count(DashboardTopic::Metrics) == 0 {
+                continue;
+            }
+
+            let samples = parse_metrics_text(&flare_im_core::metrics::gather_metrics());
+            let count = samples.len();
+            match serde_json::to_value(samples) {
+                Ok(payload) => {
+                    let receivers = bus.publish(DashboardTopic::Metrics, payload);
+                    debug!(samples = count, receivers, "Published metrics snapshot");
+                }
+                Err(e) => debug!(error = %e, "Failed to serialize metrics snapshot"),
+            }
+        }
+    });
+}
+
+/// 按指标名前缀过滤样本（空前缀返回全部）
+pub fn filter_samples(samples: &[MetricSample], prefix: &str) -> Vec<MetricSample> {
+    samples
+        .iter()
+        .filter(|sample| sample.name.starts_with(prefix))
+        .cloned()
+        .collect()
+}
+
+/// 解析 Prometheus 文本格式
+///
+/// 每行格式为 `name{label="value",...} value [timestamp]`，注释行和无法解析的行会被跳过
+pub fn parse_metrics_text(text: &str) -> Vec<MetricSample> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(parse_sample_line)
+        .collect()
+}
+
+fn parse_sample_line(line: &str) -> Option<MetricSample> {
+    let (name, labels, rest) = match line.find(['{', ' ']) {
+        Some(pos) if line.as_bytes()[pos] == b'{' => {
+            let end = line.rfind('}')?;
+            (&line[..pos], parse_labels(&line[pos + 1..end])?, &line[end + 1..])
+        }
+        Some(pos) => (&line[..pos], BTreeMap::new(), &line[pos..]),
+        None => return None,
+    };
+
+    let value = rest.split_whitespace().next()?.parse::<f64>().ok()?;
+    Some(MetricSample {
+        name: name.to_string(),
+        labels,
+        value,
+    })
+}
+
+fn parse_labels(input: &str) -> Option<BTreeMap<String, String>> {
+    let mut labels = BTreeMap::new();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        while matches!(chars.peek(), Some(',') | Some(' ')) {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            return Some(labels);
+        }
+
+        let key: String = chars.by_ref().take_while(|c| *c != '=').collect();
+        if chars.next()? != '"' {
+            return None;
+        }
+
+        let mut value = String::new();
+        loop {
+            match chars.next()? {
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    other => value.push(other),
+                },
+                '"' => break,
+                c => value.push(c),
+            }
+        }
+        labels.insert(key.trim().to_string(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metrics_text() {
+        let text = r#"
+# HELP push_server_online_push_success_total Online push success
+# TYPE push_server_online_push_success_total counter
+push_server_online_push_success_total{user_id="u1"} 3
+access_gateway_connections 42
+message_latency_bucket{le="0.5",path="a\"b"} 7 1700000000000
+"#;
+        let samples = parse_metrics_text(text);
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].name, "push_server_online_push_success_total");
+        assert_eq!(samples[0].labels["user_id"], "u1");
+        assert_eq!(samples[0].value, 3.0);
+        assert!(samples[1].labels.is_empty());
+        assert_eq!(samples[1].value, 42.0);
+        assert_eq!(samples[2].labels["path"], "a\"b");
+        assert_eq!(samples[2].labels["le"], "0.5");
+    }
+
+    #[test]
+    fn test_filter_samples_by_prefix() {
+        let samples = parse_metrics_text("push_a 1\npush_b 2\nhook_c 3\n");
+        assert_eq!(filter_samples(&samples, "push_").len(), 2);
+        assert_eq!(filter_samples(&samples, "").len(), 3);
+    }
+}
@@ -1,10 +1,13 @@
 pub mod database;
+pub mod event_bus;
 // Gateway Router 已移至 flare-im-core::gateway
 // pub mod gateway_router;
 pub mod hook_engine;
 pub mod messaging;
+pub mod metrics_feed;
 pub mod push;
 pub mod route;
+pub mod routing;
 pub mod signaling;
 pub mod storage;
 
@@ -16,10 +19,12 @@ pub mod online;
 pub mod session;
 
 pub use database::{create_db_pool, create_db_pool_from_env};
+pub use event_bus::{DashboardEvent, DashboardEventBus, DashboardTopic};
 // Gateway Router 已移至 flare-im-core::gateway
 // pub use gateway_router::{DeploymentMode, GatewayRouterConfig, GatewayRouterImpl};
 pub use push::GrpcPushClient;
 pub use route::RouteServiceClient;
+pub use routing::RouteTable;
 pub use signaling::GrpcSignalingClient;
 pub use storage::GrpcStorageClient;
 
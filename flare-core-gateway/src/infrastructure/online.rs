@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tonic::metadata::MetadataMap;
 use tonic::transport::Channel;
 use tonic::{Request, Response, Status, Streaming};
 
@@ -10,6 +11,8 @@ use flare_proto::signaling::online::*;
 
 use flare_server_core::discovery::ServiceClient;
 
+use crate::infrastructure::routing::{Downstream, RouteTable};
+
 /// gRPC在线状态服务客户端
 pub struct GrpcOnlineClient {
     /// 服务客户端（用于服务发现）
@@ -18,6 +21,8 @@ pub struct GrpcOnlineClient {
     service_name: String,
     /// 直连地址（当没有服务发现时使用）
     direct_address: Option<String>,
+    /// 动态路由表（配置后优先于静态配置）
+    route_table: Option<Arc<RouteTable>>,
 }
 
 impl GrpcOnlineClient {
@@ -27,6 +32,7 @@ impl GrpcOnlineClient {
             service_client: None,
             service_name,
             direct_address: None,
+            route_table: None,
         }
     }
 
@@ -36,6 +42,7 @@ impl GrpcOnlineClient {
             service_client: Some(Arc::new(Mutex::new(service_client))),
             service_name,
             direct_address: None,
+            route_table: None,
         }
     }
 
@@ -45,11 +52,27 @@ impl GrpcOnlineClient {
             service_client: None,
             service_name,
             direct_address: Some(direct_address),
+            route_table: None,
         }
     }
 
+    /// 设置动态路由表
+    pub fn with_route_table(mut self, route_table: Arc<RouteTable>) -> Self {
+        self.route_table = Some(route_table);
+        self
+    }
+
     /// 获取OnlineService gRPC客户端（统一的服务，包含 SignalingService 和 UserService 的所有功能）
-    async fn get_online_client(&self) -> Result<OnlineServiceClient<Channel>, Status> {
+    async fn get_online_client(
+        &self,
+        metadata: &MetadataMap,
+    ) -> Result<OnlineServiceClient<Channel>, Status> {
+        if let Some(route_table) = &self.route_table {
+            if let Some(channel) = route_table.channel(Downstream::Online, metadata).await? {
+                return Ok(OnlineServiceClient::new(channel));
+            }
+        }
+
         if let Some(service_client) = &self.service_client {
             let mut client = service_client.lock().await;
             let channel = client.get_channel().await.map_err(|e| {
@@ -89,7 +112,7 @@ impl GrpcOnlineClient {
         &self,
         request: Request<LoginRequest>,
     ) -> Result<Response<LoginResponse>, Status> {
-        let mut client = self.get_online_client().await?;
+        let mut client = self.get_online_client(request.metadata()).await?;
         client.login(request).await
     }
 
@@ -98,7 +121,7 @@ impl GrpcOnlineClient {
         &self,
         request: Request<LogoutRequest>,
     ) -> Result<Response<LogoutResponse>, Status> {
-        let mut client = self.get_online_client().await?;
+        let mut client = self.get_online_client(request.metadata()).await?;
         client.logout(request).await
     }
 
@@ -107,7 +130,7 @@ impl GrpcOnlineClient {
         &self,
         request: Request<HeartbeatRequest>,
     ) -> Result<Response<HeartbeatResponse>, Status> {
-        let mut client = self.get_online_client().await?;
+        let mut client = self.get_online_client(request.metadata()).await?;
         client.heartbeat(request).await
     }
 
@@ -116,7 +139,7 @@ impl GrpcOnlineClient {
         &self,
         request: Request<GetOnlineStatusRequest>,
     ) -> Result<Response<GetOnlineStatusResponse>, Status> {
-        let mut client = self.get_online_client().await?;
+        let mut client = self.get_online_client(request.metadata()).await?;
         client.get_online_status(request).await
     }
 
@@ -125,7 +148,7 @@ impl GrpcOnlineClient {
         &self,
         request: Request<WatchPresenceRequest>,
     ) -> Result<Response<Streaming<PresenceEvent>>, Status> {
-        let mut client = self.get_online_client().await?;
+        let mut client = self.get_online_client(request.metadata()).await?;
         client.watch_presence(request).await
     }
 
@@ -134,7 +157,7 @@ impl GrpcOnlineClient {
         &self,
         request: Request<GetUserPresenceRequest>,
     ) -> Result<Response<GetUserPresenceResponse>, Status> {
-        let mut client = self.get_online_client().await?;
+        let mut client = self.get_online_client(request.metadata()).await?;
         client.get_user_presence(request).await
     }
 
@@ -143,7 +166,7 @@ impl GrpcOnlineClient {
         &self,
         request: Request<BatchGetUserPresenceRequest>,
     ) -> Result<Response<BatchGetUserPresenceResponse>, Status> {
-        let mut client = self.get_online_client().await?;
+        let mut client = self.get_online_client(request.metadata()).await?;
         client.batch_get_user_presence(request).await
     }
 
@@ -152,7 +175,7 @@ impl GrpcOnlineClient {
         &self,
         request: Request<SubscribeUserPresenceRequest>,
     ) -> Result<Response<Streaming<UserPresenceEvent>>, Status> {
-        let mut client = self.get_online_client().await?;
+        let mut client = self.get_online_client(request.metadata()).await?;
         client.subscribe_user_presence(request).await
     }
 
@@ -161,7 +184,7 @@ impl GrpcOnlineClient {
         &self,
         request: Request<ListUserDevicesRequest>,
     ) -> Result<Response<ListUserDevicesResponse>, Status> {
-        let mut client = self.get_online_client().await?;
+        let mut client = self.get_online_client(request.metadata()).await?;
         client.list_user_devices(request).await
     }
 
@@ -170,7 +193,7 @@ impl GrpcOnlineClient {
         &self,
         request: Request<KickDeviceRequest>,
     ) -> Result<Response<KickDeviceResponse>, Status> {
-        let mut client = self.get_online_client().await?;
+        let mut client = self.get_online_client(request.metadata()).await?;
         client.kick_device(request).await
     }
 
@@ -179,7 +202,7 @@ impl GrpcOnlineClient {
         &self,
         request: Request<GetDeviceRequest>,
     ) -> Result<Response<GetDeviceResponse>, Status> {
-        let mut client = self.get_online_client().await?;
+        let mut client = self.get_online_client(request.metadata()).await?;
         client.get_device(request).await
     }
 }
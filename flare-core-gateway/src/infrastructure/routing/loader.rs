@@ -0,0 +1,164 @@
+//! 路由表加载与热更新
+//!
+//! 支持两种来源（内容为 TOML 或 JSON）：
+//! - **本地文件**：按刷新间隔轮询
+//! - **配置中心（etcd）**：Watch 到变更后立即重新加载，同时按刷新间隔兜底轮询
+//!
+//! 加载或校验失败时保留当前路由表，只记录告警。
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
+
+use super::{RouteTable, RouteTableConfig};
+
+/// 路由表来源
+#[derive(Debug, Clone)]
+pub enum RouteTableSource {
+    /// 本地文件
+    File(PathBuf),
+    /// etcd 配置键
+    Etcd { endpoints: Vec<String>, key: String },
+}
+
+impl RouteTableSource {
+    /// 从配置中心地址（etcd://host:port）创建
+    pub fn etcd(endpoint: &str, key: String) -> Result<Self> {
+        let addr = endpoint
+            .strip_prefix("etcd://")
+            .with_context(|| format!("Unsupported route config endpoint: {}", endpoint))?;
+        Ok(Self::Etcd {
+            endpoints: addr
+                .split(',')
+                .map(|host| format!("http://{}", host.trim()))
+                .collect(),
+            key,
+        })
+    }
+}
+
+/// 加载并校验路由表（etcd 中不存在配置键时返回空路由表，即全部使用静态配置）
+pub async fn load_route_table(source: &RouteTableSource) -> Result<RouteTableConfig> {
+    let content = match source {
+        RouteTableSource::File(path) => tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read route table file {}", path.display()))?,
+        RouteTableSource::Etcd { endpoints, key } => {
+            let mut client = etcd_client::Client::connect(endpoints, None)
+                .await
+                .context("Failed to connect to etcd")?;
+            let resp = client
+                .get(key.as_str(), None)
+                .await
+                .context("Failed to get route table from etcd")?;
+            match resp.kvs().first() {
+                Some(kv) => String::from_utf8(kv.value().to_vec())
+                    .context("Failed to parse etcd value as UTF-8 string")?,
+                None => return Ok(RouteTableConfig::default()),
+            }
+        }
+    };
+
+    let config: RouteTableConfig = if content.trim_start().starts_with('{') {
+        serde_json::from_str(&content).context("Failed to parse route table as JSON")?
+    } else {
+        toml::from_str(&content).context("Failed to parse route table as TOML")?
+    };
+    config.validate()?;
+    Ok(config)
+}
+
+/// 启动路由表热更新任务
+pub fn spawn_route_table_reload(
+    table: Arc<RouteTable>,
+    source: RouteTableSource,
+    refresh_interval: Duration,
+) {
+    let notify = Arc::new(Notify::new());
+    if let RouteTableSource::Etcd { endpoints, key } = &source {
+        spawn_etcd_watch(endpoints.clone(), key.clone(), notify.clone());
+    }
+
+    tokio::spawn(async move {
+        // 刷新间隔配置为 0 时 tokio 的 interval 会 panic，至少按 1ms 处理
+        let mut interval = tokio::time::interval(refresh_interval.max(Duration::from_millis(1)));
+        interval.tick().await;
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = notify.notified() => {}
+            }
+            match load_route_table(&source).await {
+                Ok(config) => {
+                    if table.replace(config).await {
+                        info!(source = ?source, "Downstream route table reloaded");
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        source = ?source,
+                        error = %e,
+                        "Failed to reload route table, keeping current routes"
+                    );
+                }
+            }
+        }
+    });
+}
+
+/// Watch etcd 配置键，变更时触发重新加载（断开后按指数退避重连）
+fn spawn_etcd_watch(endpoints: Vec<String>, key: String, notify: Arc<Notify>) {
+    tokio::spawn(async move {
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            match watch_etcd(&endpoints, &key, &notify).await {
+                Ok(()) => {
+                    warn!(key = %key, "etcd route watch stream closed, reconnecting");
+                    backoff = Duration::from_secs(1);
+                }
+                Err(e) => {
+                    warn!(
+                        key = %key,
+                        error = %e,
+                        retry_in_secs = backoff.as_secs(),
+                        "etcd route watch failed, retrying"
+                    );
+                }
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(Duration::from_secs(30));
+        }
+    });
+}
+
+async fn watch_etcd(endpoints: &[String], key: &str, notify: &Notify) -> Result<()> {
+    let mut client = etcd_client::Client::connect(endpoints, None)
+        .await
+        .context("Failed to connect to etcd")?;
+    // watcher 需要保持存活，drop 后 Watch 会被取消
+    let (_watcher, mut stream) = client
+        .watch(key, None)
+        .await
+        .context("Failed to watch route table in etcd")?;
+
+    info!(key = %key, "Watching downstream route table in etcd");
+
+    // 建立（或重建）Watch 期间可能错过变更，主动触发一次重新加载
+    notify.notify_one();
+
+    while let Some(resp) = stream.message().await.context("etcd watch stream error")? {
+        if resp.canceled() {
+            anyhow::bail!("etcd watch canceled by server");
+        }
+        if !resp.events().is_empty() {
+            debug!(key = %key, events = resp.events().len(), "Route table changed in etcd");
+            notify.notify_one();
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,391 @@
+//! 下游服务动态路由表
+//!
+//! 网关访问下游服务（消息编排/存储查询、在线信令、会话、媒体、Hook）时先查询路由表：
+//! 1. 租户覆盖：`tenants.<tenant_id>.<service>`（如为大租户指定独立的存储集群）
+//! 2. 默认路由：`defaults.<service>`
+//! 3. 均未配置时回退到客户端的静态配置（服务发现或直连地址）
+//!
+//! 路由表由 [`loader`] 从配置中心（etcd）或本地文件加载并热更新，加载失败时保留上一次的路由。
+
+pub mod loader;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, OnceCell};
+use tonic::Status;
+use tonic::metadata::MetadataMap;
+use tonic::transport::Channel;
+use tracing::info;
+
+use flare_server_core::discovery::ServiceClient;
+
+pub use loader::{RouteTableSource, load_route_table, spawn_route_table_reload};
+
+/// 请求元数据中的租户ID
+const TENANT_ID_METADATA_KEY: &str = "x-tenant-id";
+
+/// 网关的下游服务
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Downstream {
+    /// 消息编排（发送消息、查询存储）
+    Message,
+    /// 在线信令
+    Online,
+    /// 会话服务
+    Conversation,
+    /// 媒体服务
+    Media,
+    /// Hook 引擎
+    Hook,
+}
+
+impl Downstream {
+    /// 路由表中的服务键
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Downstream::Message => "message",
+            Downstream::Online => "online",
+            Downstream::Conversation => "conversation",
+            Downstream::Media => "media",
+            Downstream::Hook => "hook",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "message" => Some(Downstream::Message),
+            "online" => Some(Downstream::Online),
+            "conversation" => Some(Downstream::Conversation),
+            "media" => Some(Downstream::Media),
+            "hook" => Some(Downstream::Hook),
+            _ => None,
+        }
+    }
+}
+
+/// 路由目标（服务发现名称或直连地址，二选一）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RouteTarget {
+    /// 服务发现中的服务名称
+    #[serde(default)]
+    pub service_name: Option<String>,
+    /// 直连地址（如 http://storage-vip:50051）
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+/// 路由表配置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RouteTableConfig {
+    /// 默认路由（服务键 -> 目标）
+    #[serde(default)]
+    pub defaults: HashMap<String, RouteTarget>,
+    /// 租户覆盖（租户ID -> 服务键 -> 目标）
+    #[serde(default)]
+    pub tenants: HashMap<String, HashMap<String, RouteTarget>>,
+}
+
+impl RouteTableConfig {
+    /// 校验服务键和路由目标
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let routes = self
+            .defaults
+            .iter()
+            .map(|(service, target)| (None, service, target));
+        let overrides = self.tenants.iter().flat_map(|(tenant, routes)| {
+            routes
+                .iter()
+                .map(move |(service, target)| (Some(tenant), service, target))
+        });
+        for (tenant, service, target) in routes.chain(overrides) {
+            let scope = tenant.map(String::as_str).unwrap_or("defaults");
+            if Downstream::parse(service).is_none() {
+                anyhow::bail!("unknown downstream service '{}' in {}", service, scope);
+            }
+            match (&target.service_name, &target.endpoint) {
+                (Some(_), None) | (None, Some(_)) => {}
+                _ => anyhow::bail!(
+                    "route {}.{} must set exactly one of service_name or endpoint",
+                    scope,
+                    service
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    fn targets(&self) -> HashSet<RouteTarget> {
+        self.defaults
+            .values()
+            .chain(self.tenants.values().flat_map(|routes| routes.values()))
+            .cloned()
+            .collect()
+    }
+}
+
+/// 已建立的路由连接
+#[derive(Clone)]
+enum RoutedChannel {
+    /// 直连地址（Channel 内部自动重连）
+    Direct(Channel),
+    /// 服务发现（每次获取时负载均衡选择实例）
+    Discovery(Arc<Mutex<ServiceClient>>),
+}
+
+/// 为路由目标建立连接的函数（测试中可替换）
+type Connector =
+    Arc<dyn Fn(RouteTarget) -> BoxFuture<'static, Result<RoutedChannel, Status>> + Send + Sync>;
+
+/// 下游服务路由表
+///
+/// 每个目标的连接保存在独立的 `OnceCell` 中：查表只短暂持有表锁，建立连接时不持锁，
+/// 慢速或不可达的目标不会阻塞其他目标的路由；同一目标的并发请求共享一次连接，失败后下次请求重试
+pub struct RouteTable {
+    config: RwLock<RouteTableConfig>,
+    channels: Mutex<HashMap<RouteTarget, Arc<OnceCell<RoutedChannel>>>>,
+    connector: Connector,
+}
+
+impl Default for RouteTable {
+    fn default() -> Self {
+        Self::new(RouteTableConfig::default())
+    }
+}
+
+impl RouteTable {
+    pub fn new(config: RouteTableConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            channels: Mutex::new(HashMap::new()),
+            connector: Arc::new(|target| Box::pin(async move { connect(&target).await })),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_connector(mut self, connector: Connector) -> Self {
+        self.connector = connector;
+        self
+    }
+
+    /// 当前路由配置
+    pub fn config(&self) -> RouteTableConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    /// 替换路由配置（热更新），不再被引用的目标连接随之释放
+    pub async fn replace(&self, config: RouteTableConfig) -> bool {
+        let targets = config.targets();
+        {
+            let mut current = self.config.write().unwrap();
+            if *current == config {
+                return false;
+            }
+            *current = config;
+        }
+        self.channels
+            .lock()
+            .await
+            .retain(|target, _| targets.contains(target));
+        info!(targets = targets.len(), "Downstream route table updated");
+        true
+    }
+
+    /// 解析路由目标：租户覆盖 > 默认路由，均未配置时返回 None
+    pub fn resolve(&self, service: Downstream, tenant_id: Option<&str>) -> Option<RouteTarget> {
+        let config = self.config.read().unwrap();
+        tenant_id
+            .and_then(|tenant| config.tenants.get(tenant))
+            .and_then(|routes| routes.get(service.as_str()))
+            .or_else(|| config.defaults.get(service.as_str()))
+            .cloned()
+    }
+
+    /// 按请求中的租户获取路由后的 Channel
+    ///
+    /// 返回 `Ok(None)` 表示路由表中没有该服务的路由，调用方使用静态配置；
+    /// 已配置的路由不可用时返回错误，不回退到共享集群，避免租户数据写入错误的集群
+    pub async fn channel(
+        &self,
+        service: Downstream,
+        metadata: &MetadataMap,
+    ) -> Result<Option<Channel>, Status> {
+        let tenant_id = metadata
+            .get(TENANT_ID_METADATA_KEY)
+            .and_then(|v| v.to_str().ok());
+        let Some(target) = self.resolve(service, tenant_id) else {
+            return Ok(None);
+        };
+
+        let cell = self
+            .channels
+            .lock()
+            .await
+            .entry(target.clone())
+            .or_default()
+            .clone();
+        let routed = cell
+            .get_or_try_init(|| (self.connector)(target))
+            .await?
+            .clone();
+        match routed {
+            RoutedChannel::Direct(channel) => Ok(Some(channel)),
+            RoutedChannel::Discovery(client) => {
+                let channel = client.lock().await.get_channel().await.map_err(|e| {
+                    Status::unavailable(format!(
+                        "Failed to get channel for routed {} service: {}",
+                        service.as_str(),
+                        e
+                    ))
+                })?;
+                Ok(Some(channel))
+            }
+        }
+    }
+}
+
+/// 为路由目标建立连接
+async fn connect(target: &RouteTarget) -> Result<RoutedChannel, Status> {
+    if let Some(endpoint) = &target.endpoint {
+        let channel = Channel::from_shared(endpoint.clone())
+            .map_err(|e| Status::invalid_argument(format!("Invalid routed endpoint: {}", e)))?
+            .connect_lazy();
+        return Ok(RoutedChannel::Direct(channel));
+    }
+
+    let service_name = target.service_name.as_deref().unwrap_or_default();
+    let discover = flare_im_core::discovery::create_discover(service_name)
+        .await
+        .map_err(|e| {
+            Status::unavailable(format!(
+                "Failed to create service discover for {}: {}",
+                service_name, e
+            ))
+        })?
+        .ok_or_else(|| Status::unavailable("Service discovery not configured"))?;
+    Ok(RoutedChannel::Discovery(Arc::new(Mutex::new(
+        ServiceClient::new(discover),
+    ))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    fn endpoint(url: &str) -> RouteTarget {
+        RouteTarget {
+            service_name: None,
+            endpoint: Some(url.to_string()),
+        }
+    }
+
+    fn table(defaults: &[(&str, RouteTarget)]) -> RouteTableConfig {
+        RouteTableConfig {
+            defaults: defaults
+                .iter()
+                .map(|(service, target)| (service.to_string(), target.clone()))
+                .collect(),
+            tenants: HashMap::new(),
+        }
+    }
+
+    /// 统计连接次数；`slow` 目标的连接永不完成，`bad` 目标连接失败
+    fn counting_connector(connects: Arc<AtomicUsize>) -> Connector {
+        Arc::new(move |target: RouteTarget| {
+            let connects = connects.clone();
+            Box::pin(async move {
+                connects.fetch_add(1, Ordering::SeqCst);
+                let endpoint = target.endpoint.unwrap_or_default();
+                if endpoint.contains("slow") {
+                    std::future::pending::<()>().await;
+                }
+                if endpoint.contains("bad") {
+                    return Err(Status::unavailable("connect failed"));
+                }
+                let channel = Channel::from_shared(endpoint).unwrap().connect_lazy();
+                Ok(RoutedChannel::Direct(channel))
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_channel_cache_hit_and_miss() {
+        let connects = Arc::new(AtomicUsize::new(0));
+        let routes = RouteTable::new(table(&[("message", endpoint("http://storage-a:50051"))]))
+            .with_connector(counting_connector(connects.clone()));
+        let metadata = MetadataMap::new();
+
+        let unrouted = routes.channel(Downstream::Online, &metadata).await;
+        assert!(unrouted.unwrap().is_none());
+        assert_eq!(connects.load(Ordering::SeqCst), 0);
+
+        for _ in 0..3 {
+            let routed = routes.channel(Downstream::Message, &metadata).await;
+            assert!(routed.unwrap().is_some());
+        }
+        assert_eq!(connects.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_channel_connect_failure_is_retried() {
+        let connects = Arc::new(AtomicUsize::new(0));
+        let routes = RouteTable::new(table(&[("message", endpoint("http://bad:50051"))]))
+            .with_connector(counting_connector(connects.clone()));
+        let metadata = MetadataMap::new();
+
+        for _ in 0..2 {
+            let result = routes.channel(Downstream::Message, &metadata).await;
+            assert_eq!(result.unwrap_err().code(), tonic::Code::Unavailable);
+        }
+        // 失败不缓存，每次请求重新连接
+        assert_eq!(connects.load(Ordering::SeqCst), 2);
+
+        let routes = RouteTable::default();
+        routes
+            .replace(table(&[("message", endpoint("not a uri"))]))
+            .await;
+        let result = routes.channel(Downstream::Message, &metadata).await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_slow_target_does_not_block_other_targets() {
+        let connects = Arc::new(AtomicUsize::new(0));
+        let routes = Arc::new(
+            RouteTable::new(table(&[
+                ("media", endpoint("http://slow:50051")),
+                ("message", endpoint("http://storage-a:50051")),
+                ("online", endpoint("http://online:50051")),
+            ]))
+            .with_connector(counting_connector(connects.clone())),
+        );
+
+        let slow = tokio::spawn({
+            let routes = routes.clone();
+            async move { routes.channel(Downstream::Media, &MetadataMap::new()).await }
+        });
+        tokio::task::yield_now().await;
+
+        let services = [Downstream::Message, Downstream::Online];
+        let lookups = services.into_iter().cycle().take(4).map(|service| {
+            let routes = routes.clone();
+            tokio::spawn(async move { routes.channel(service, &MetadataMap::new()).await })
+        });
+        let results =
+            tokio::time::timeout(Duration::from_secs(5), futures::future::join_all(lookups))
+                .await
+                .expect("lookups blocked behind the slow target");
+        for result in results {
+            assert!(result.unwrap().unwrap().is_some());
+        }
+        // 同一目标的并发请求只连接一次：slow + storage-a + online
+        assert_eq!(connects.load(Ordering::SeqCst), 3);
+        assert!(!slow.is_finished());
+        slow.abort();
+    }
+}
@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tonic::metadata::MetadataMap;
 use tonic::transport::Channel;
 use tonic::{Request, Response, Status};
 
@@ -10,6 +11,8 @@ use flare_proto::conversation::*;
 
 use flare_server_core::discovery::ServiceClient;
 
+use crate::infrastructure::routing::{Downstream, RouteTable};
+
 /// gRPC会话服务客户端
 pub struct GrpcConversationClient {
     /// 服务客户端（用于服务发现）
@@ -18,6 +21,8 @@ pub struct GrpcConversationClient {
     service_name: String,
     /// 直连地址（当没有服务发现时使用）
     direct_address: Option<String>,
+    /// 动态路由表（配置后优先于静态配置）
+    route_table: Option<Arc<RouteTable>>,
 }
 
 impl GrpcConversationClient {
@@ -27,6 +32,7 @@ impl GrpcConversationClient {
             service_client: None,
             service_name,
             direct_address: None,
+            route_table: None,
         }
     }
 
@@ -36,6 +42,7 @@ impl GrpcConversationClient {
             service_client: Some(Arc::new(Mutex::new(service_client))),
             service_name,
             direct_address: None,
+            route_table: None,
         }
     }
 
@@ -45,11 +52,30 @@ impl GrpcConversationClient {
             service_client: None,
             service_name,
             direct_address: Some(direct_address),
+            route_table: None,
         }
     }
 
+    /// 设置动态路由表
+    pub fn with_route_table(mut self, route_table: Arc<RouteTable>) -> Self {
+        self.route_table = Some(route_table);
+        self
+    }
+
     /// 获取gRPC客户端
-    async fn get_client(&self) -> Result<ConversationServiceClient<Channel>, Status> {
+    async fn get_client(
+        &self,
+        metadata: &MetadataMap,
+    ) -> Result<ConversationServiceClient<Channel>, Status> {
+        if let Some(route_table) = &self.route_table {
+            if let Some(channel) = route_table
+                .channel(Downstream::Conversation, metadata)
+                .await?
+            {
+                return Ok(ConversationServiceClient::new(channel));
+            }
+        }
+
         if let Some(service_client) = &self.service_client {
             let mut client = service_client.lock().await;
             let channel = client.get_channel().await.map_err(|e| {
@@ -89,7 +115,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<ConversationBootstrapRequest>,
     ) -> Result<Response<ConversationBootstrapResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.conversation_bootstrap(request).await
     }
 
@@ -98,7 +124,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<ListConversationsRequest>,
     ) -> Result<Response<ListConversationsResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.list_conversations(request).await
     }
 
@@ -107,7 +133,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<SyncMessagesRequest>,
     ) -> Result<Response<SyncMessagesResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.sync_messages(request).await
     }
 
@@ -116,7 +142,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<flare_proto::common::SyncConversationsRequest>,
     ) -> Result<Response<flare_proto::common::SyncConversationsResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.sync_conversations(request).await
     }
 
@@ -125,7 +151,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<flare_proto::common::ConversationSyncAllRequest>,
     ) -> Result<Response<flare_proto::common::ConversationSyncAllResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.get_all_conversations(request).await
     }
 
@@ -134,7 +160,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<UpdateCursorRequest>,
     ) -> Result<Response<UpdateCursorResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.update_cursor(request).await
     }
 
@@ -143,7 +169,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<UpdatePresenceRequest>,
     ) -> Result<Response<UpdatePresenceResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.update_presence(request).await
     }
 
@@ -152,7 +178,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<ForceConversationSyncRequest>,
     ) -> Result<Response<ForceConversationSyncResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.force_conversation_sync(request).await
     }
 
@@ -161,7 +187,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<CreateConversationRequest>,
     ) -> Result<Response<CreateConversationResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.create_conversation(request).await
     }
 
@@ -170,7 +196,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<UpdateConversationRequest>,
     ) -> Result<Response<UpdateConversationResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.update_conversation(request).await
     }
 
@@ -179,7 +205,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<DeleteConversationRequest>,
     ) -> Result<Response<DeleteConversationResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.delete_conversation(request).await
     }
 
@@ -188,7 +214,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<ManageParticipantsRequest>,
     ) -> Result<Response<ManageParticipantsResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.manage_participants(request).await
     }
 
@@ -197,7 +223,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<BatchAcknowledgeRequest>,
     ) -> Result<Response<BatchAcknowledgeResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.batch_acknowledge(request).await
     }
 
@@ -206,7 +232,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<SearchConversationsRequest>,
     ) -> Result<Response<SearchConversationsResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.search_conversations(request).await
     }
 
@@ -215,7 +241,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<CreateThreadRequest>,
     ) -> Result<Response<CreateThreadResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.create_thread(request).await
     }
 
@@ -224,7 +250,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<ListThreadsRequest>,
     ) -> Result<Response<ListThreadsResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.list_threads(request).await
     }
 
@@ -233,7 +259,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<GetThreadRequest>,
     ) -> Result<Response<GetThreadResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.get_thread(request).await
     }
 
@@ -242,7 +268,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<UpdateThreadRequest>,
     ) -> Result<Response<UpdateThreadResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.update_thread(request).await
     }
 
@@ -251,7 +277,7 @@ impl GrpcConversationClient {
         &self,
         request: Request<DeleteThreadRequest>,
     ) -> Result<Response<DeleteThreadResponse>, Status> {
-        let mut client = self.get_client().await?;
+        let mut client = self.get_client(request.metadata()).await?;
         client.delete_thread(request).await
     }
 }
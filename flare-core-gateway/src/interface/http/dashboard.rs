@@ -0,0 +1,263 @@
+//! # 控制台 SSE 桥接
+//!
+//! 管理控制台通过 `GET /dashboard/stream?topics=metrics,metrics:hook` 订阅实时数据，
+//! 服务端以 Server-Sent Events 推送事件总线上对应主题的事件。
+//!
+//! - `metrics`：完整指标快照；`metrics:<前缀>` 只推送指定前缀的指标（如 `metrics:push_server`）
+//!
+//! 在线数、Hook 失败等视图通过 `metrics:<前缀>` 订阅对应指标；未知主题返回 400。
+//!
+//! 鉴权：`Authorization: Bearer <token>` 或 `access_token` 查询参数（浏览器 EventSource 无法设置请求头），
+//! 每个主题需要 `dashboard:*`、`dashboard:<主题>` 或其上级主题权限（如 `dashboard:metrics`）。
+//!
+//! 同一地址的 `GET /metrics` 供 Prometheus 抓取（不鉴权）：请求头 `Accept` 包含
+//! `application/openmetrics-text` 时返回带 exemplar 的 OpenMetrics 格式，否则返回文本格式。
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use futures::stream::{self, BoxStream, StreamExt};
+use serde::Deserialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+use crate::infrastructure::event_bus::{DashboardEvent, DashboardEventBus, DashboardTopic};
+use crate::infrastructure::metrics_feed::{MetricSample, filter_samples};
+use crate::interface::middleware::{AuthMiddleware, RbacMiddleware, TokenClaims};
+
+/// 单个连接最多订阅的主题数
+const MAX_TOPICS_PER_STREAM: usize = 16;
+
+/// 控制台接口共享状态
+#[derive(Clone)]
+pub struct DashboardState {
+    pub bus: Arc<DashboardEventBus>,
+    pub auth: Arc<AuthMiddleware>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamQuery {
+    /// 逗号分隔的主题列表
+    topics: String,
+    #[serde(default)]
+    access_token: Option<String>,
+}
+
+/// 构建控制台路由
+pub fn router(state: DashboardState) -> Router {
+    Router::new()
+        .route("/dashboard/stream", get(stream_events))
+        .route("/metrics", get(scrape_metrics))
+        .with_state(state)
+}
+
+/// Prometheus 抓取端点（按 `Accept` 协商文本 / OpenMetrics 格式）
+async fn scrape_metrics(headers: HeaderMap) -> Response {
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok());
+    let (content_type, body) = flare_im_core::metrics::gather_metrics_for(accept);
+    ([(header::CONTENT_TYPE, content_type)], body).into_response()
+}
+
+async fn stream_events(
+    State(state): State<DashboardState>,
+    Query(query): Query<StreamQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let claims = match authenticate(&state.auth, &headers, query.access_token.as_deref()) {
+        Ok(claims) => claims,
+        Err(e) => return (StatusCode::UNAUTHORIZED, e.to_string()).into_response(),
+    };
+
+    let topics: Vec<String> = query
+        .topics
+        .split(',')
+        .map(str::trim)
+        .filter(|topic| !topic.is_empty())
+        .map(str::to_string)
+        .collect();
+    if topics.is_empty() || topics.len() > MAX_TOPICS_PER_STREAM {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("topics must contain 1-{} entries", MAX_TOPICS_PER_STREAM),
+        )
+            .into_response();
+    }
+
+    let mut subscriptions = Vec::with_capacity(topics.len());
+    for topic in &topics {
+        match parse_topic(topic) {
+            Some(subscription) => subscriptions.push(subscription),
+            None => {
+                return (StatusCode::BAD_REQUEST, format!("Unknown topic: {}", topic))
+                    .into_response();
+            }
+        }
+        if !is_topic_allowed(&claims, topic) {
+            warn!(user_id = %claims.user_id, topic = %topic, "Dashboard topic not permitted");
+            return (
+                StatusCode::FORBIDDEN,
+                format!("Permission denied for topic: {}", topic),
+            )
+                .into_response();
+        }
+    }
+
+    info!(user_id = %claims.user_id, topics = ?topics, "Dashboard stream opened");
+
+    let streams: Vec<BoxStream<'static, Result<Event, Infallible>>> = topics
+        .into_iter()
+        .zip(subscriptions)
+        .map(|(topic, subscription)| topic_stream(&state.bus, topic, subscription))
+        .collect();
+    Sse::new(stream::select_all(streams))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+fn authenticate(
+    auth: &AuthMiddleware,
+    headers: &HeaderMap,
+    access_token: Option<&str>,
+) -> anyhow::Result<TokenClaims> {
+    let token = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .or(access_token)
+        .ok_or_else(|| anyhow::anyhow!("Missing access token"))?;
+    auth.authenticate_token(token)
+}
+
+/// 检查主题权限：`dashboard:*` 或主题及其任一上级（以 `:` 分隔）的权限
+fn is_topic_allowed(claims: &TokenClaims, topic: &str) -> bool {
+    let mut permissions = vec!["dashboard:*".to_string()];
+    let mut scope = String::new();
+    for segment in topic.split(':') {
+        if !scope.is_empty() {
+            scope.push(':');
+        }
+        scope.push_str(segment);
+        permissions.push(format!("dashboard:{}", scope));
+    }
+
+    let permissions: Vec<&str> = permissions.iter().map(String::as_str).collect();
+    RbacMiddleware::check_any_permission(claims, &permissions)
+}
+
+/// 解析订阅主题，返回总线主题和指标前缀过滤（未知主题返回 None）
+///
+/// `metrics:<前缀>` 订阅完整指标快照后按前缀过滤
+fn parse_topic(topic: &str) -> Option<(DashboardTopic, Option<String>)> {
+    match topic.split_once(':') {
+        Some((name, prefix)) => match DashboardTopic::from_name(name)? {
+            DashboardTopic::Metrics => Some((DashboardTopic::Metrics, Some(prefix.to_string()))),
+        },
+        None => Some((DashboardTopic::from_name(topic)?, None)),
+    }
+}
+
+/// 将主题订阅转换为 SSE 事件流
+fn topic_stream(
+    bus: &DashboardEventBus,
+    topic: String,
+    (bus_topic, metric_prefix): (DashboardTopic, Option<String>),
+) -> BoxStream<'static, Result<Event, Infallible>> {
+    receiver_stream(bus.subscribe(bus_topic))
+        .filter_map(move |mut event| {
+            let topic = topic.clone();
+            let metric_prefix = metric_prefix.clone();
+            async move {
+                if let Some(prefix) = metric_prefix {
+                    let samples: Vec<MetricSample> =
+                        serde_json::from_value(event.payload).ok()?;
+                    event.payload = serde_json::to_value(filter_samples(&samples, &prefix)).ok()?;
+                }
+                Event::default().event(topic).json_data(&event).ok().map(Ok)
+            }
+        })
+        .boxed()
+}
+
+fn receiver_stream(
+    receiver: broadcast::Receiver<DashboardEvent>,
+) -> BoxStream<'static, DashboardEvent> {
+    stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                // 消费过慢时丢弃积压事件，继续推送最新数据
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "Dashboard subscriber lagged, events dropped");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scrape_metrics_serves_exemplars_for_openmetrics() {
+        let metrics = flare_im_core::metrics::HookExecutionMetrics::new();
+        metrics.observe_execution(
+            &["spam-filter", "pre_send", "validation", "t1", "error"],
+            0.25,
+            Some("trace-dashboard"),
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT,
+            "application/openmetrics-text; version=1.0.0"
+                .parse()
+                .unwrap(),
+        );
+        let response = scrape_metrics(headers).await;
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            flare_im_core::metrics::OPENMETRICS_CONTENT_TYPE
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.lines().any(
+            |line| line.starts_with("hook_execution_duration_seconds_bucket")
+                && line.contains("# {trace_id=\"trace-dashboard\"}")
+        ));
+        assert!(body.ends_with("# EOF\n"));
+
+        let response = scrape_metrics(HeaderMap::new()).await;
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            flare_im_core::metrics::TEXT_CONTENT_TYPE
+        );
+    }
+
+    #[test]
+    fn test_parse_topic_rejects_unknown_topics() {
+        assert_eq!(
+            parse_topic("metrics"),
+            Some((DashboardTopic::Metrics, None))
+        );
+        assert_eq!(
+            parse_topic("metrics:push_server"),
+            Some((DashboardTopic::Metrics, Some("push_server".to_string())))
+        );
+        assert_eq!(parse_topic("hook_failures"), None);
+        assert_eq!(parse_topic("online:count"), None);
+        assert_eq!(parse_topic(""), None);
+    }
+}
@@ -2,4 +2,5 @@
 //!
 //! 提供Gateway的HTTP服务实现
 
+pub mod dashboard;
 pub mod router;
@@ -1,11 +1,14 @@
 //! # 认证中间件
 //!
 //! 提供JWT Token验证和Claims提取功能。
+//! 验证密钥来自 `TokenKeyRing`，按 JWT 头部的 `kid` 选择密钥，支持签名密钥轮换。
 
 use anyhow::Result;
-use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
+use flare_im_core::auth::{SigningKeySet, TokenKeyRing};
+use jsonwebtoken::{Validation, Algorithm};
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::sync::Arc;
 use tonic::metadata::MetadataMap;
 use tracing::debug;
 
@@ -26,20 +29,26 @@ pub struct TokenClaims {
 
 /// 认证中间件
 pub struct AuthMiddleware {
-    /// JWT密钥
-    secret_key: Vec<u8>,
+    /// JWT签名密钥集合
+    key_ring: Arc<TokenKeyRing>,
     /// 验证配置
     validation: Validation,
 }
 
 impl AuthMiddleware {
-    /// 创建认证中间件
+    /// 创建认证中间件（单密钥）
     pub fn new(secret_key: Vec<u8>) -> Self {
+        let secret = String::from_utf8_lossy(&secret_key).into_owned();
+        Self::with_key_ring(TokenKeyRing::new(SigningKeySet::single(secret)))
+    }
+
+    /// 使用签名密钥集合创建认证中间件（密钥集合可在线替换）
+    pub fn with_key_ring(key_ring: Arc<TokenKeyRing>) -> Self {
         let mut validation = Validation::new(Algorithm::HS256);
         validation.validate_exp = true;
-        
+
         Self {
-            secret_key,
+            key_ring,
             validation,
         }
     }
@@ -53,6 +62,11 @@ impl AuthMiddleware {
         Ok(Self::new(secret_key))
     }
     
+    /// 使用当前生效密钥签发Token（头部写入 `kid`）
+    pub fn issue_token(&self, claims: &TokenClaims) -> Result<String> {
+        self.key_ring.sign(claims)
+    }
+
     /// 从Metadata中提取并验证Token
     pub fn authenticate(&self, metadata: &MetadataMap) -> Result<TokenClaims> {
         // 从Authorization header提取Token
@@ -68,12 +82,13 @@ impl AuthMiddleware {
             })
             .ok_or_else(|| anyhow::anyhow!("Missing or invalid Authorization header"))?;
         
-        // 解码和验证Token
-        let decoding_key = DecodingKey::from_secret(&self.secret_key);
-        let token_data = decode::<TokenClaims>(&token, &decoding_key, &self.validation)
-            .map_err(|e| anyhow::anyhow!("Token validation failed: {}", e))?;
-        
-        let claims = token_data.claims;
+        self.authenticate_token(&token)
+    }
+    
+    /// 验证Token字符串并提取Claims
+    pub fn authenticate_token(&self, token: &str) -> Result<TokenClaims> {
+        // 按 kid 选择密钥解码和验证Token
+        let claims: TokenClaims = self.key_ring.verify(token, &self.validation)?;
         
         debug!(
             user_id = %claims.user_id,
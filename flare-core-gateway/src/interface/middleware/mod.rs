@@ -2,4 +2,11 @@
 //!
 //! 提供认证授权、租户上下文提取、权限校验、限流等中间件功能。
 
-// 轻量级网关仅作为代理层，不包含复杂的中间件逻辑
+// 轻量级网关仅作为代理层，gRPC 链路不包含复杂的中间件逻辑
+// auth/rbac 目前用于控制台 HTTP 接口（SSE 桥接）的鉴权
+
+pub mod auth;
+pub mod rbac;
+
+pub use auth::{AuthMiddleware, TokenClaims};
+pub use rbac::RbacMiddleware;
@@ -2,7 +2,6 @@
 //!
 //! 提供基于角色的访问控制（RBAC）功能。
 
-use anyhow::Result;
 use tracing::debug;
 
 use crate::interface::middleware::auth::TokenClaims;
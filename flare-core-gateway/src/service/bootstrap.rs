@@ -15,10 +15,10 @@ pub struct ApplicationBootstrap;
 impl ApplicationBootstrap {
     /// 运行应用的主入口点
     pub async fn run() -> Result<()> {
-        use flare_im_core::{ServiceHelper, load_config};
+        use flare_im_core::{ServiceHelper, load_config_with_validation, strict_mode};
 
         // 加载应用配置
-        let app_config = load_config(Some("./config"));
+        let app_config = load_config_with_validation(Some("./config"), strict_mode())?;
         let gateway_config_service = app_config.core_gateway_service();
         let runtime_config = app_config
             .compose_service_config(&gateway_config_service.runtime, "flare-core-gateway");
@@ -55,6 +55,7 @@ impl ApplicationBootstrap {
 
         let simple_handler = context.simple_handler;
         let lightweight_handler = context.lightweight_handler;
+        let dashboard = context.dashboard;
 
         info!(
             address = %address,
@@ -64,7 +65,7 @@ impl ApplicationBootstrap {
 
         // 使用 ServiceRuntime 管理服务生命周期
         let address_clone = address;
-        let runtime = ServiceRuntime::new("core-gateway", address)
+        let mut runtime = ServiceRuntime::new("core-gateway", address)
             .add_spawn_with_shutdown("core-gateway-grpc", move |shutdown_rx| async move {
                 // 使用 ContextLayer 分别包裹每个 Service
                 use flare_server_core::middleware::ContextLayer;
@@ -91,6 +92,7 @@ impl ApplicationBootstrap {
                     .layer(ConversationServiceServer::new(simple_handler.clone()));
                 
                 Server::builder()
+                    .add_service(flare_im_core::ServerInfoServer::from_global())
                     .add_service(media_service)
                     .add_service(hook_service)
                     .add_service(message_service)
@@ -117,6 +119,41 @@ impl ApplicationBootstrap {
                     .map_err(|e| format!("gRPC server error: {}", e).into())
             });
 
+        // 控制台 SSE 桥接（可选）
+        if let Some(dashboard) = dashboard {
+            let dashboard_address: SocketAddr = dashboard
+                .address
+                .parse()
+                .context("invalid dashboard address")?;
+            crate::infrastructure::metrics_feed::spawn_metrics_feed(
+                dashboard.state.bus.clone(),
+                std::time::Duration::from_millis(dashboard.metrics_interval_ms),
+            );
+            let router = crate::interface::http::dashboard::router(dashboard.state);
+
+            runtime = runtime.add_spawn_with_shutdown(
+                "core-gateway-dashboard",
+                move |shutdown_rx| async move {
+                    let listener = tokio::net::TcpListener::bind(dashboard_address)
+                        .await
+                        .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
+                            format!("Failed to bind dashboard address: {}", e).into()
+                        })?;
+                    info!(
+                        address = %dashboard_address,
+                        "✅ Core Gateway dashboard SSE bridge is listening"
+                    );
+
+                    axum::serve(listener, router)
+                        .with_graceful_shutdown(async move {
+                            let _ = shutdown_rx.await;
+                        })
+                        .await
+                        .map_err(|e| format!("dashboard server error: {}", e).into())
+                },
+            );
+        }
+
         // 运行服务（带服务注册）
         runtime
             .run_with_registration(|addr| {
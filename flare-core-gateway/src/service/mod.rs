@@ -6,4 +6,4 @@ pub mod bootstrap;
 mod wire;
 
 pub use bootstrap::ApplicationBootstrap;
-pub use wire::ApplicationContext;
+pub use wire::{ApplicationContext, DashboardContext};
@@ -9,14 +9,33 @@ use anyhow::{Context, Result};
 use crate::config::GatewayConfig;
 // use crate::interface::grpc::handler::{SimpleGatewayHandler, LightweightGatewayHandler};
 use crate::infrastructure::{
-    GrpcHookClient, GrpcMediaClient, GrpcMessageClient, GrpcOnlineClient, GrpcConversationClient,
+    DashboardEventBus, GrpcHookClient, GrpcMediaClient, GrpcMessageClient, GrpcOnlineClient,
+    GrpcConversationClient,
+};
+use crate::infrastructure::routing::{
+    RouteTable, RouteTableSource, load_route_table, spawn_route_table_reload,
 };
 use crate::interface::grpc::handler::{LightweightGatewayHandler, SimpleGatewayHandler};
+use crate::interface::http::dashboard::DashboardState;
+use crate::interface::middleware::AuthMiddleware;
+use flare_im_core::auth::{SigningKeySet, TokenKeyRing, spawn_key_file_reload};
+
+/// 签名密钥文件重新加载间隔
+const TOKEN_KEYS_RELOAD_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
 
 /// 应用上下文 - 包含所有已初始化的服务
 pub struct ApplicationContext {
     pub simple_handler: SimpleGatewayHandler,
     pub lightweight_handler: LightweightGatewayHandler,
+    /// 控制台 SSE 桥接（未配置监听地址时为 None）
+    pub dashboard: Option<DashboardContext>,
+}
+
+/// 控制台 SSE 桥接上下文
+pub struct DashboardContext {
+    pub address: String,
+    pub metrics_interval_ms: u64,
+    pub state: DashboardState,
 }
 
 /// 构建应用上下文
@@ -130,50 +149,62 @@ pub async fn initialize(
         None
     };
 
-    // 3. 创建基础设施客户端
+    // 3. 构建下游路由表，创建基础设施客户端
+    let route_table = build_route_table(&gateway_config).await;
+
     let media_client = if let Some(service_client) = media_service_client {
-        Arc::new(GrpcMediaClient::with_service_client(
-            service_client,
-            media_service.clone(),
-        ))
+        Arc::new(
+            GrpcMediaClient::with_service_client(service_client, media_service.clone())
+                .with_route_table(route_table.clone()),
+        )
     } else {
-        Arc::new(GrpcMediaClient::new(media_service.clone()))
+        Arc::new(
+            GrpcMediaClient::new(media_service.clone()).with_route_table(route_table.clone()),
+        )
     };
 
     let hook_client = if let Some(service_client) = hook_service_client {
-        Arc::new(GrpcHookClient::with_service_client(
-            service_client,
-            hook_service.clone(),
-        ))
+        Arc::new(
+            GrpcHookClient::with_service_client(service_client, hook_service.clone())
+                .with_route_table(route_table.clone()),
+        )
     } else {
-        Arc::new(GrpcHookClient::new(hook_service.clone()))
+        Arc::new(
+            GrpcHookClient::new(hook_service.clone()).with_route_table(route_table.clone()),
+        )
     };
 
     let message_client = if let Some(service_client) = message_service_client {
-        Arc::new(GrpcMessageClient::with_service_client(
-            service_client,
-            message_service.clone(),
-        ))
+        Arc::new(
+            GrpcMessageClient::with_service_client(service_client, message_service.clone())
+                .with_route_table(route_table.clone()),
+        )
     } else {
-        Arc::new(GrpcMessageClient::new(message_service.clone()))
+        Arc::new(
+            GrpcMessageClient::new(message_service.clone()).with_route_table(route_table.clone()),
+        )
     };
 
     let online_client = if let Some(service_client) = online_service_client {
-        Arc::new(GrpcOnlineClient::with_service_client(
-            service_client,
-            online_service.clone(),
-        ))
+        Arc::new(
+            GrpcOnlineClient::with_service_client(service_client, online_service.clone())
+                .with_route_table(route_table.clone()),
+        )
     } else {
-        Arc::new(GrpcOnlineClient::new(online_service.clone()))
+        Arc::new(
+            GrpcOnlineClient::new(online_service.clone()).with_route_table(route_table.clone()),
+        )
     };
 
     let conversation_client = if let Some(service_client) = conversation_service_client {
-        Arc::new(GrpcConversationClient::with_service_client(
-            service_client,
-            conversation_service.clone(),
-        ))
+        Arc::new(
+            GrpcConversationClient::with_service_client(service_client, conversation_service.clone())
+                .with_route_table(route_table.clone()),
+        )
     } else {
-        Arc::new(GrpcConversationClient::new(conversation_service.clone()))
+        Arc::new(
+            GrpcConversationClient::new(conversation_service.clone()).with_route_table(route_table.clone()),
+        )
     };
 
     // 4. 构建简单网关处理器
@@ -194,8 +225,83 @@ pub async fn initialize(
         conversation_client,
     );
 
+    // 6. 构建控制台 SSE 桥接（可选）
+    let dashboard = match gateway_config.dashboard_address.clone() {
+        Some(address) => Some(DashboardContext {
+            address,
+            metrics_interval_ms: gateway_config.dashboard_metrics_interval_ms,
+            state: DashboardState {
+                bus: Arc::new(DashboardEventBus::new()),
+                auth: Arc::new(
+                    build_auth_middleware(&gateway_config)
+                        .context("Failed to create dashboard auth")?,
+                ),
+            },
+        }),
+        None => None,
+    };
+
     Ok(ApplicationContext {
         simple_handler,
         lightweight_handler,
+        dashboard,
     })
 }
+
+/// 构建下游路由表
+///
+/// 配置了配置中心或路由表文件时加载并启动热更新；初次加载失败不阻塞启动，
+/// 此时所有请求使用静态配置，等待下一次刷新
+async fn build_route_table(config: &GatewayConfig) -> Arc<RouteTable> {
+    let route_table = Arc::new(RouteTable::default());
+    let source = match (&config.routes_config_endpoint, &config.routes_file) {
+        (Some(endpoint), _) => {
+            match RouteTableSource::etcd(endpoint, config.routes_config_key.clone()) {
+                Ok(source) => source,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Invalid route config endpoint, routing disabled");
+                    return route_table;
+                }
+            }
+        }
+        (None, Some(path)) => RouteTableSource::File(path.into()),
+        (None, None) => return route_table,
+    };
+
+    match load_route_table(&source).await {
+        Ok(routes) => {
+            route_table.replace(routes).await;
+        }
+        Err(e) => {
+            tracing::warn!(
+                source = ?source,
+                error = %e,
+                "Failed to load route table, using static downstream config"
+            );
+        }
+    }
+    spawn_route_table_reload(
+        route_table.clone(),
+        source,
+        std::time::Duration::from_millis(config.routes_refresh_interval_ms),
+    );
+    route_table
+}
+
+/// 构建认证中间件
+///
+/// 配置了签名密钥文件时使用多密钥签发与验证，并定期重新加载文件以支持密钥轮换；
+/// 密钥文件加载失败时拒绝启动，`JWT_SECRET_KEY` 保留为仅验证的历史密钥
+fn build_auth_middleware(config: &GatewayConfig) -> Result<AuthMiddleware> {
+    match &config.token_keys_file {
+        Some(path) => {
+            let keys = SigningKeySet::from_file(path)
+                .with_context(|| format!("Failed to load token signing keys from {}", path))?;
+            let legacy_secret = std::env::var("JWT_SECRET_KEY").unwrap_or_default();
+            let key_ring = TokenKeyRing::with_legacy_secret(keys, legacy_secret);
+            spawn_key_file_reload(key_ring.clone(), path, TOKEN_KEYS_RELOAD_INTERVAL);
+            Ok(AuthMiddleware::with_key_ring(key_ring))
+        }
+        None => AuthMiddleware::from_env(),
+    }
+}
@@ -3,17 +3,29 @@
 //! Hook引擎的启动入口
 
 use anyhow::Result;
-use flare_hook_engine::domain::model::ExecutionMode;
+use flare_hook_engine::domain::model::{DraftMergeStrategy, ExecutionMode};
+use flare_hook_engine::domain::service::{
+    DEFAULT_DEADLINE_RESERVE, DEFAULT_MAX_PENDING_RETRIES, DEFAULT_SLOW_HOOK_THRESHOLD,
+    parse_chain_budgets,
+};
+use flare_hook_engine::infrastructure::adapters::grpc_pool::GrpcChannelConfig;
+use flare_hook_engine::infrastructure::audit::HookAuditConfig;
+use flare_hook_engine::infrastructure::usage::HookUsageConfig;
+use flare_hook_engine::infrastructure::concurrency::ConcurrencyConfig;
+use flare_hook_engine::infrastructure::dead_letter::{DEFAULT_DEAD_LETTER_TOPIC, DeadLetterConfig};
+use flare_hook_engine::infrastructure::health::HookHealthConfig;
 use flare_hook_engine::service::bootstrap::{ApplicationBootstrap, HookEngineConfig};
-use flare_im_core::{load_config, tracing::init_tracing_from_config};
+use flare_im_core::service_names::HOOK_ENGINE;
+use flare_im_core::{load_config_with_validation, strict_mode, tracing::init_tracing_from_config};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // 加载配置（Hook Engine 可能不使用标准配置，但为了统一日志初始化，先加载）
-    let app_config = load_config(Some("config"));
+    let app_config = load_config_with_validation(Some("config"), strict_mode())?;
 
     // 从配置初始化日志系统（默认 debug 级别）
     init_tracing_from_config(Some(app_config.logging()));
+    flare_im_core::build_info!(HOOK_ENGINE).install();
 
     // 从环境变量读取配置
     let database_url = std::env::var("DATABASE_URL").ok().or_else(|| {
@@ -32,14 +44,215 @@ async fn main() -> Result<()> {
         .ok()
         .map(|s| std::path::PathBuf::from(s));
 
+    // 死信队列（未配置Kafka地址时不启用）
+    let dead_letter = std::env::var("HOOK_ENGINE_DLQ_KAFKA_BOOTSTRAP")
+        .ok()
+        .map(|kafka_bootstrap| DeadLetterConfig {
+            kafka_bootstrap,
+            topic: std::env::var("HOOK_ENGINE_DLQ_TOPIC")
+                .unwrap_or_else(|_| DEFAULT_DEAD_LETTER_TOPIC.to_string()),
+        });
+
+    // 执行审计日志（默认关闭）
+    let audit = std::env::var("HOOK_ENGINE_AUDIT_ENABLED")
+        .ok()
+        .filter(|v| v == "true" || v == "1")
+        .map(|_| {
+            let defaults = HookAuditConfig::default();
+            HookAuditConfig {
+                batch_size: std::env::var("HOOK_ENGINE_AUDIT_BATCH_SIZE")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(defaults.batch_size),
+                flush_interval: std::env::var("HOOK_ENGINE_AUDIT_FLUSH_INTERVAL_MS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .map(std::time::Duration::from_millis)
+                    .unwrap_or(defaults.flush_interval),
+                retention_days: std::env::var("HOOK_ENGINE_AUDIT_RETENTION_DAYS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(defaults.retention_days),
+                ..defaults
+            }
+        });
+
+    // Hook资源用量统计（默认关闭）
+    let usage = std::env::var("HOOK_ENGINE_USAGE_ENABLED")
+        .ok()
+        .filter(|v| v == "true" || v == "1")
+        .map(|_| {
+            let defaults = HookUsageConfig::default();
+            HookUsageConfig {
+                flush_interval: std::env::var("HOOK_ENGINE_USAGE_FLUSH_INTERVAL_MS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .map(std::time::Duration::from_millis)
+                    .unwrap_or(defaults.flush_interval),
+            }
+        });
+
+    // 动态库插件目录（可选）
+    let plugin_dir = std::env::var("HOOK_ENGINE_PLUGIN_DIR")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(std::path::PathBuf::from);
+
+    // Redis地址（可选，配置了内置限流Hook时必需）
+    let redis_url = std::env::var("HOOK_ENGINE_REDIS_URL")
+        .ok()
+        .filter(|url| !url.is_empty());
+
+    // PreSend business组执行模式（sequential / concurrent）和并发时的草稿合并策略
+    let execution_mode = std::env::var("HOOK_ENGINE_EXECUTION_MODE")
+        .ok()
+        .map(|v| v.parse::<ExecutionMode>())
+        .transpose()
+        .map_err(anyhow::Error::msg)?
+        .unwrap_or_default();
+    let draft_merge = std::env::var("HOOK_ENGINE_DRAFT_MERGE")
+        .ok()
+        .map(|v| v.parse::<DraftMergeStrategy>())
+        .transpose()
+        .map_err(anyhow::Error::msg)?
+        .unwrap_or_default();
+
+    // 请求预算保留量（毫秒）
+    let deadline_reserve = std::env::var("HOOK_ENGINE_DEADLINE_RESERVE_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(DEFAULT_DEADLINE_RESERVE);
+
+    // Hook链路总预算（如 pre_send=150,recall=100，单位毫秒）
+    let chain_budgets = std::env::var("HOOK_ENGINE_CHAIN_BUDGET_MS")
+        .ok()
+        .map(|v| parse_chain_budgets(&v))
+        .transpose()?
+        .unwrap_or_default();
+
+    // 配置了OTLP端点时为执行指标记录 trace_id exemplar
+    let trace_exemplars = std::env::var("OTLP_ENDPOINT")
+        .map(|endpoint| !endpoint.is_empty())
+        .unwrap_or(false);
+
+    // 慢Hook告警阈值（毫秒，0 表示关闭）
+    let slow_hook_threshold = match std::env::var("HOOK_ENGINE_SLOW_HOOK_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+    {
+        Some(0) => None,
+        Some(millis) => Some(std::time::Duration::from_millis(millis)),
+        None => Some(DEFAULT_SLOW_HOOK_THRESHOLD),
+    };
+
+    // 后台重试在途上限（超过后直接写入死信）
+    let max_pending_retries = std::env::var("HOOK_ENGINE_MAX_PENDING_RETRIES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_PENDING_RETRIES);
+
+    // 端点健康检查（默认开启，HOOK_ENGINE_HEALTH_CHECK_ENABLED=false 关闭）
+    let health_check = {
+        let defaults = HookHealthConfig::default();
+        HookHealthConfig {
+            enabled: std::env::var("HOOK_ENGINE_HEALTH_CHECK_ENABLED")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(defaults.enabled),
+            interval_ms: std::env::var("HOOK_ENGINE_HEALTH_CHECK_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.interval_ms),
+            timeout_ms: std::env::var("HOOK_ENGINE_HEALTH_CHECK_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.timeout_ms),
+            unhealthy_threshold: std::env::var("HOOK_ENGINE_HEALTH_UNHEALTHY_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.unhealthy_threshold),
+            healthy_threshold: std::env::var("HOOK_ENGINE_HEALTH_HEALTHY_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.healthy_threshold),
+        }
+    };
+
+    // gRPC Hook的Channel配置（连接超时、keep-alive，间隔为0表示关闭）
+    let grpc_channel = {
+        let defaults = GrpcChannelConfig::default();
+        GrpcChannelConfig {
+            connect_timeout_ms: std::env::var("HOOK_ENGINE_GRPC_CONNECT_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.connect_timeout_ms),
+            keep_alive_interval_ms: std::env::var("HOOK_ENGINE_GRPC_KEEPALIVE_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.keep_alive_interval_ms),
+            keep_alive_timeout_ms: std::env::var("HOOK_ENGINE_GRPC_KEEPALIVE_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.keep_alive_timeout_ms),
+            keep_alive_while_idle: std::env::var("HOOK_ENGINE_GRPC_KEEPALIVE_WHILE_IDLE")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(defaults.keep_alive_while_idle),
+            tcp_keepalive_ms: std::env::var("HOOK_ENGINE_GRPC_TCP_KEEPALIVE_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.tcp_keepalive_ms),
+        }
+    };
+
+    // Hook执行并发限制（上限为0表示不限制，HOOK_ENGINE_CONCURRENCY_ENABLED=false 关闭）
+    let concurrency = {
+        let defaults = ConcurrencyConfig::default();
+        ConcurrencyConfig {
+            enabled: std::env::var("HOOK_ENGINE_CONCURRENCY_ENABLED")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(defaults.enabled),
+            global_max_in_flight: std::env::var("HOOK_ENGINE_MAX_IN_FLIGHT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.global_max_in_flight),
+            hook_max_in_flight: std::env::var("HOOK_ENGINE_HOOK_MAX_IN_FLIGHT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.hook_max_in_flight),
+            tenant_max_in_flight: std::env::var("HOOK_ENGINE_TENANT_MAX_IN_FLIGHT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.tenant_max_in_flight),
+            max_queue_wait_ms: std::env::var("HOOK_ENGINE_MAX_QUEUE_WAIT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_queue_wait_ms),
+        }
+    };
+
     // 创建Hook引擎配置
     let config = HookEngineConfig {
         config_file,
         database_url,
         config_center_endpoint,
         tenant_id,
-        execution_mode: ExecutionMode::Sequential,
+        execution_mode,
+        draft_merge,
         refresh_interval_secs: 60,
+        circuit_breaker: Default::default(),
+        health_check,
+        grpc_channel,
+        concurrency,
+        dead_letter,
+        audit,
+        usage,
+        plugin_dir,
+        redis_url,
+        deadline_reserve,
+        chain_budgets,
+        trace_exemplars,
+        slow_hook_threshold,
+        max_pending_retries,
     };
 
     tracing::info!("Starting Hook Engine with config: {:?}", config);
@@ -6,7 +6,7 @@ use std::sync::Arc;
 
 use anyhow::Result;
 
-use crate::domain::model::HookExecutionPlan;
+use crate::domain::model::{HookExecutionPlan, PreSendSimulation};
 use crate::domain::service::HookOrchestrationService;
 use flare_im_core::{
     DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent,
@@ -37,6 +37,18 @@ impl HookCommandHandler {
             .await
     }
 
+    /// 处理PreSend Hook预演命令（影子模式，不修改传入的草稿）
+    pub async fn handle_simulate_pre_send(
+        &self,
+        ctx: &Context,
+        draft: &MessageDraft,
+        hooks: Vec<HookExecutionPlan>,
+    ) -> PreSendSimulation {
+        self.orchestration_service
+            .simulate_pre_send(ctx, draft, hooks)
+            .await
+    }
+
     /// 处理PostSend Hook命令
     pub async fn handle_post_send(
         &self,
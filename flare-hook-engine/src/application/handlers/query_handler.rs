@@ -17,9 +17,15 @@ impl HookQueryHandler {
         Self { metrics_collector }
     }
 
-    /// 处理获取Hook统计信息查询
-    pub async fn handle_get_statistics(&self, hook_name: &str) -> Option<HookStatistics> {
-        self.metrics_collector.get_statistics(hook_name).await
+    /// 处理获取Hook统计信息查询（按租户隔离）
+    pub async fn handle_get_statistics(
+        &self,
+        tenant_id: Option<&str>,
+        hook_name: &str,
+    ) -> Option<HookStatistics> {
+        self.metrics_collector
+            .get_statistics(tenant_id, hook_name)
+            .await
     }
 
     /// 处理获取所有Hook统计信息查询
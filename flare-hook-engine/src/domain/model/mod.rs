@@ -2,12 +2,14 @@
 //!
 //! 定义Hook引擎的核心领域模型
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
+use chrono::{NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+use flare_im_core::hooks::{HookSelector, MatchRule, TagExpr};
 use flare_im_core::{
     DeliveryEvent, HookErrorPolicy, HookGroup, HookMetadata, MessageDraft,
     MessageRecord, PreSendDecision, PreSendHook, RecallEvent,
@@ -29,6 +31,124 @@ impl Default for ExecutionMode {
     }
 }
 
+impl std::str::FromStr for ExecutionMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sequential" | "serial" => Ok(ExecutionMode::Sequential),
+            "concurrent" | "parallel" => Ok(ExecutionMode::Concurrent),
+            _ => Err(format!("Unknown execution mode: {}", s)),
+        }
+    }
+}
+
+/// 并发执行的PreSend business组Hook改写草稿后的合并策略
+///
+/// 各Hook在原始草稿的副本上执行，改动按字段计算（payload整体为一个字段，headers/metadata/extra按键计算），
+/// 不同Hook改动的不同键会合并保留
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DraftMergeStrategy {
+    /// 同一字段以执行顺序（priority）靠后的Hook为准，与串行执行的结果一致
+    #[default]
+    LastWriteWins,
+    /// 同一字段以执行顺序（priority）靠前的Hook为准
+    FirstWriteWins,
+}
+
+impl std::str::FromStr for DraftMergeStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "last_write_wins" | "last-write-wins" => Ok(DraftMergeStrategy::LastWriteWins),
+            "first_write_wins" | "first-write-wins" => Ok(DraftMergeStrategy::FirstWriteWins),
+            _ => Err(format!("Unknown draft merge strategy: {}", s)),
+        }
+    }
+}
+
+impl DraftMergeStrategy {
+    /// 将各Hook的改动（按执行顺序排列）合并到草稿
+    pub fn merge(&self, draft: &mut MessageDraft, changes: Vec<DraftChanges>) {
+        match self {
+            DraftMergeStrategy::LastWriteWins => changes.into_iter().for_each(|c| c.apply(draft)),
+            DraftMergeStrategy::FirstWriteWins => {
+                changes.into_iter().rev().for_each(|c| c.apply(draft))
+            }
+        }
+    }
+}
+
+/// 单个Hook对草稿的改动（相对于执行前的原始草稿，`None` 表示删除该键）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DraftChanges {
+    payload: Option<Vec<u8>>,
+    headers: HashMap<String, Option<String>>,
+    metadata: HashMap<String, Option<String>>,
+    extra: HashMap<String, Option<serde_json::Value>>,
+}
+
+impl DraftChanges {
+    /// 计算 `modified` 相对于 `original` 的改动
+    pub fn diff(original: &MessageDraft, modified: &MessageDraft) -> Self {
+        Self {
+            payload: (modified.payload != original.payload).then(|| modified.payload.clone()),
+            headers: diff_map(&original.headers, &modified.headers),
+            metadata: diff_map(&original.metadata, &modified.metadata),
+            extra: diff_map(&original.extra, &modified.extra),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.payload.is_none()
+            && self.headers.is_empty()
+            && self.metadata.is_empty()
+            && self.extra.is_empty()
+    }
+
+    fn apply(self, draft: &mut MessageDraft) {
+        if let Some(payload) = self.payload {
+            draft.payload = payload;
+        }
+        apply_map(&mut draft.headers, self.headers);
+        apply_map(&mut draft.metadata, self.metadata);
+        apply_map(&mut draft.extra, self.extra);
+    }
+}
+
+fn diff_map<V: Clone + PartialEq>(
+    original: &HashMap<String, V>,
+    modified: &HashMap<String, V>,
+) -> HashMap<String, Option<V>> {
+    let mut changes: HashMap<String, Option<V>> = modified
+        .iter()
+        .filter(|(key, value)| original.get(*key) != Some(*value))
+        .map(|(key, value)| (key.clone(), Some(value.clone())))
+        .collect();
+    changes.extend(
+        original
+            .keys()
+            .filter(|key| !modified.contains_key(*key))
+            .map(|key| (key.clone(), None)),
+    );
+    changes
+}
+
+fn apply_map<V>(target: &mut HashMap<String, V>, changes: HashMap<String, Option<V>>) {
+    for (key, value) in changes {
+        match value {
+            Some(value) => {
+                target.insert(key, value);
+            }
+            None => {
+                target.remove(&key);
+            }
+        }
+    }
+}
+
 /// Hook配置项
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HookConfigItem {
@@ -41,7 +161,7 @@ pub struct HookConfigItem {
     /// 是否启用
     pub enabled: bool,
     /// 优先级（0-1000，数字越小优先级越高）
-    /// 注意：priority < 100 自动归入business组，priority >= 100 自动归入validation组
+    /// 注意：未指定 group 时，priority < 100 自动归入business组，priority >= 100 自动归入validation组
     pub priority: i32,
     /// Hook分组（可选，如果不指定则根据priority自动分组）
     /// validation: 校验类Hook组（串行执行，快速失败）
@@ -67,6 +187,24 @@ pub struct HookConfigItem {
     /// 元数据
     #[serde(default)]
     pub metadata: HashMap<String, String>,
+    /// 结果缓存配置（可选，仅对PreSend生效，适用于幂等的校验类Hook）
+    #[serde(default)]
+    pub cache: Option<HookCacheConfig>,
+    /// 请求/响应采样配置（可选，默认关闭，仅对gRPC/WebHook生效）
+    #[serde(default)]
+    pub sampling: Option<HookSamplingConfig>,
+    /// 失败重试配置（可选，仅对PostSend/Delivery生效，重试耗尽后写入死信队列）
+    #[serde(default)]
+    pub retry: Option<HookRetryConfig>,
+    /// 金丝雀发布配置（可选，将部分流量路由到新版本Hook，仅对gRPC/WebHook生效）
+    #[serde(default)]
+    pub canary: Option<HookCanaryConfig>,
+    /// 最大在途执行数（可选，未配置时使用引擎的默认单Hook上限）
+    #[serde(default)]
+    pub max_concurrency: Option<u32>,
+    /// 配置版本号（由数据库维护，每次变更递增；文件配置为空）
+    #[serde(default, skip_serializing)]
+    pub config_revision: Option<u64>,
 }
 
 fn default_max_retries() -> u32 {
@@ -81,6 +219,165 @@ fn default_require_success() -> bool {
     true
 }
 
+/// Hook结果缓存配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookCacheConfig {
+    /// 缓存有效期（毫秒）
+    #[serde(default = "default_cache_ttl_ms")]
+    pub ttl_ms: u64,
+    /// 最大缓存条目数
+    #[serde(default = "default_cache_max_entries")]
+    pub max_entries: usize,
+}
+
+fn default_cache_ttl_ms() -> u64 {
+    60_000
+}
+
+fn default_cache_max_entries() -> usize {
+    10_000
+}
+
+impl Default for HookCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_ms: default_cache_ttl_ms(),
+            max_entries: default_cache_max_entries(),
+        }
+    }
+}
+
+/// Hook请求/响应采样配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookSamplingConfig {
+    /// 每个租户每分钟最多采样的调用次数
+    pub per_minute: u32,
+    /// 仅对指定租户采样（为空表示所有租户）
+    #[serde(default)]
+    pub tenants: Vec<String>,
+}
+
+/// Hook金丝雀发布配置
+///
+/// 白名单租户始终路由到金丝雀版本，其余调用按 `percentage` 随机分流；
+/// 两个版本的调用统计分别记录在 `hook_type:name#version` 下，便于全量前对比错误率
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookCanaryConfig {
+    /// 金丝雀版本号（用于区分统计指标）
+    pub version: String,
+    /// 金丝雀版本的传输配置
+    pub transport: HookTransportConfig,
+    /// 路由到金丝雀版本的流量百分比（0-100）
+    #[serde(default)]
+    pub percentage: u32,
+    /// 始终路由到金丝雀版本的租户
+    #[serde(default)]
+    pub tenants: Vec<String>,
+}
+
+/// 重试退避策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackoffStrategy {
+    /// 固定间隔
+    Fixed,
+    /// 线性增长
+    Linear,
+    /// 指数增长
+    #[default]
+    Exponential,
+}
+
+impl BackoffStrategy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "fixed" => Some(BackoffStrategy::Fixed),
+            "linear" => Some(BackoffStrategy::Linear),
+            "exponential" => Some(BackoffStrategy::Exponential),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackoffStrategy::Fixed => "fixed",
+            BackoffStrategy::Linear => "linear",
+            BackoffStrategy::Exponential => "exponential",
+        }
+    }
+}
+
+/// Hook失败重试配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookRetryConfig {
+    /// 最大重试次数（不含首次调用）
+    #[serde(default = "default_retry_max_retries")]
+    pub max_retries: u32,
+    /// 首次重试间隔（毫秒）
+    #[serde(default = "default_retry_interval_ms")]
+    pub retry_interval_ms: u64,
+    /// 退避策略
+    #[serde(default)]
+    pub backoff_strategy: BackoffStrategy,
+    /// 最大重试间隔（毫秒）
+    #[serde(default = "default_retry_max_interval_ms")]
+    pub max_interval_ms: u64,
+    /// 抖动比例（0.0-1.0），实际间隔在 `间隔 * (1 ± jitter)` 内随机
+    #[serde(default = "default_retry_jitter")]
+    pub jitter: f64,
+}
+
+fn default_retry_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_interval_ms() -> u64 {
+    1000
+}
+
+fn default_retry_max_interval_ms() -> u64 {
+    30_000
+}
+
+fn default_retry_jitter() -> f64 {
+    0.2
+}
+
+impl Default for HookRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_retry_max_retries(),
+            retry_interval_ms: default_retry_interval_ms(),
+            backoff_strategy: BackoffStrategy::default(),
+            max_interval_ms: default_retry_max_interval_ms(),
+            jitter: default_retry_jitter(),
+        }
+    }
+}
+
+impl HookRetryConfig {
+    /// 第 `retry` 次重试（从1开始）前的等待时间（不含抖动）
+    pub fn base_delay(&self, retry: u32) -> Duration {
+        let retry = retry.max(1);
+        let interval = match self.backoff_strategy {
+            BackoffStrategy::Fixed => self.retry_interval_ms,
+            BackoffStrategy::Linear => self.retry_interval_ms.saturating_mul(retry as u64),
+            BackoffStrategy::Exponential => self
+                .retry_interval_ms
+                .saturating_mul(1u64.checked_shl(retry - 1).unwrap_or(u64::MAX)),
+        };
+        Duration::from_millis(interval.min(self.max_interval_ms))
+    }
+
+    /// 第 `retry` 次重试前的等待时间（含抖动）
+    pub fn delay(&self, retry: u32) -> Duration {
+        let base = self.base_delay(retry).as_millis() as f64;
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = 1.0 + jitter * (rand::random::<f64>() * 2.0 - 1.0);
+        Duration::from_millis((base * factor).max(0.0) as u64)
+    }
+}
+
 /// Hook选择器配置
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HookSelectorConfig {
@@ -96,9 +393,37 @@ pub struct HookSelectorConfig {
     /// 用户ID列表（空表示匹配所有用户）
     #[serde(default)]
     pub user_ids: Vec<String>,
+    /// 业务类型列表（空表示匹配所有业务类型）
+    #[serde(default)]
+    pub business_types: Vec<String>,
     /// 标签匹配
     #[serde(default)]
     pub tags: HashMap<String, String>,
+    /// 标签表达式（`key`、`!key`、`key=a|b`、`key!=a|b`，全部满足才匹配）
+    #[serde(default)]
+    pub tag_expressions: Vec<String>,
+}
+
+impl HookSelectorConfig {
+    /// 编译为运行时选择器，标签表达式非法时返回错误
+    pub fn compile(&self) -> anyhow::Result<HookSelector> {
+        let mut tags: Vec<TagExpr> = self
+            .tags
+            .iter()
+            .map(|(key, value)| TagExpr::In(key.clone(), vec![value.clone()]))
+            .collect();
+        for expr in &self.tag_expressions {
+            tags.push(expr.parse().map_err(|e: String| anyhow::anyhow!(e))?);
+        }
+        Ok(HookSelector {
+            tenants: MatchRule::of_non_empty(&self.tenants),
+            conversation_types: MatchRule::of_non_empty(&self.conversation_types),
+            message_types: MatchRule::of_non_empty(&self.message_types),
+            business_types: MatchRule::of_non_empty(&self.business_types),
+            senders: MatchRule::of_non_empty(&self.user_ids),
+            tags,
+        })
+    }
 }
 
 /// 负载均衡策略
@@ -137,6 +462,108 @@ impl std::str::FromStr for LoadBalanceStrategy {
     }
 }
 
+/// gRPC Hook的TLS配置
+///
+/// 配置客户端证书和私钥时启用 mTLS；未配置CA证书时使用内置的 WebPKI 根证书校验服务端
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct GrpcTlsConfig {
+    /// CA证书路径（PEM，用于校验服务端证书）
+    #[serde(default)]
+    pub ca_cert_path: Option<String>,
+    /// 客户端证书路径（PEM，mTLS）
+    #[serde(default)]
+    pub client_cert_path: Option<String>,
+    /// 客户端私钥路径（PEM，mTLS）
+    #[serde(default)]
+    pub client_key_path: Option<String>,
+    /// 校验服务端证书时使用的域名（可选，默认取endpoint的主机名）
+    #[serde(default)]
+    pub domain_name: Option<String>,
+}
+
+/// 内置限流Hook配置（Local 传输，计数存储在 Redis）
+///
+/// 各维度独立计数（固定窗口，窗口从该维度的第一条消息开始），任一维度超限即执行 `action`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RateLimitHookConfig {
+    /// 按发送者限流
+    #[serde(default)]
+    pub per_sender: Option<RateLimitWindow>,
+    /// 按会话限流
+    #[serde(default)]
+    pub per_conversation: Option<RateLimitWindow>,
+    /// 按租户限流
+    #[serde(default)]
+    pub per_tenant: Option<RateLimitWindow>,
+    /// 超限动作（默认拒绝）
+    #[serde(default)]
+    pub action: RateLimitAction,
+}
+
+/// 限流窗口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RateLimitWindow {
+    /// 窗口内允许的消息数
+    pub limit: u64,
+    /// 窗口长度（秒）
+    pub window_secs: u64,
+}
+
+/// 限流超限动作
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RateLimitAction {
+    /// 拒绝消息
+    Reject {
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    /// 等待窗口重置后放行（最长等待 `max_delay_ms`，受Hook超时约束）
+    Delay { max_delay_ms: u64 },
+    /// 放行并在草稿 metadata 中打标（值为超限的维度），由下游决定如何处理
+    Tag {
+        #[serde(default = "default_rate_limit_tag_key")]
+        metadata_key: String,
+    },
+}
+
+impl Default for RateLimitAction {
+    fn default() -> Self {
+        RateLimitAction::Reject { reason: None }
+    }
+}
+
+fn default_rate_limit_tag_key() -> String {
+    "rate_limited".to_string()
+}
+
+impl RateLimitHookConfig {
+    /// 校验配置（至少配置一个维度，且上限和窗口均大于0）
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let windows = [
+            ("per_sender", self.per_sender),
+            ("per_conversation", self.per_conversation),
+            ("per_tenant", self.per_tenant),
+        ];
+        if windows.iter().all(|(_, window)| window.is_none()) {
+            anyhow::bail!("rate limit requires at least one of per_sender, per_conversation, per_tenant");
+        }
+        for (name, window) in windows {
+            if let Some(window) = window {
+                if window.limit == 0 || window.window_secs == 0 {
+                    anyhow::bail!("rate limit {} limit and window_secs must be greater than 0", name);
+                }
+            }
+        }
+        if let RateLimitAction::Tag { metadata_key } = &self.action {
+            if metadata_key.is_empty() {
+                anyhow::bail!("rate limit tag action requires a metadata_key");
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Hook传输配置
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -166,25 +593,121 @@ pub enum HookTransportConfig {
         /// 请求元数据
         #[serde(default)]
         metadata: HashMap<String, String>,
+
+        /// TLS/mTLS配置（可选，仅直接地址模式生效，用于可信网络之外的Hook服务）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tls: Option<GrpcTlsConfig>,
     },
     /// WebHook传输（必须使用直接URL）
     Webhook {
         /// WebHook端点（HTTP URL）
         endpoint: String,
-        /// 密钥（可选）
+        /// 签名密钥（可选）
         #[serde(default)]
         secret: Option<String>,
+        /// 轮换中的旧密钥（可选，配置后同时使用新旧密钥签名，接收方切换完成后移除）
+        #[serde(default)]
+        previous_secret: Option<String>,
         /// 请求头（可选）
         #[serde(default)]
         headers: HashMap<String, String>,
+        /// 单次请求超时（毫秒，默认3000）
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+        /// 连接失败、超时、5xx/429 时的重试次数（默认1）
+        #[serde(default)]
+        max_retries: Option<u32>,
     },
     /// Local Plugin传输
     Local {
-        /// 插件目标
+        /// 插件目标（配置脚本时作为脚本名称）
         target: String,
+        /// 内嵌 Rhai 脚本（可选，配置后在进程内执行脚本而非查找本地插件）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        script: Option<String>,
+        /// 内置限流配置（可选，配置后在进程内执行限流而非查找本地插件）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rate_limit: Option<RateLimitHookConfig>,
+    },
+    /// NATS传输（request/reply，由订阅主题的响应方处理）
+    Nats {
+        /// NATS服务器地址（如 nats://127.0.0.1:4222）
+        url: String,
+        /// 请求主题
+        subject: String,
+        /// 请求超时（毫秒，默认3000）
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+        /// 请求元数据（作为NATS消息头发送）
+        #[serde(default)]
+        metadata: HashMap<String, String>,
+    },
+    /// Kafka传输（仅用于PostSend/Delivery等异步Hook，事件发布到Topic后即返回）
+    Kafka {
+        /// Kafka集群配置档名称（引用全局 `kafka` 配置）
+        profile: String,
+        /// 目标Topic
+        topic: String,
+        /// 附加到事件中的元数据
+        #[serde(default)]
+        metadata: HashMap<String, String>,
+    },
+    /// 联邦传输（边缘部署将Hook转发到中心Hook引擎，由中心引擎执行其配置的同类Hook）
+    Federated {
+        /// 中心Hook引擎的gRPC地址（HookExtension服务）
+        endpoint: String,
+        /// TLS/mTLS配置（可选，跨区域链路建议开启）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tls: Option<GrpcTlsConfig>,
+        /// 单次转发超时（毫秒，默认1000），超时视为链路不可用
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+        /// 请求元数据
+        #[serde(default)]
+        metadata: HashMap<String, String>,
+        /// 中心决策缓存（可选，仅对PreSend生效，相同租户、相同内容直接复用中心决策）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        decision_cache: Option<HookCacheConfig>,
+        /// 链路不可用时的本地降级策略
+        #[serde(default)]
+        fallback: FederationFallback,
     },
 }
 
+/// 联邦传输的本地降级策略
+///
+/// 转发失败（连接失败、超时）后在 `link_retry_ms` 内不再转发，直接在本地执行降级策略
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FederationFallback {
+    /// PreSend/Recall 的降级决策（默认放行）
+    #[serde(default = "default_federation_decision")]
+    pub decision: DefaultDecision,
+    /// PostSend/Delivery 降级时是否返回错误（交由Hook的重试配置写入死信），默认本地丢弃
+    #[serde(default)]
+    pub fail_notifications: bool,
+    /// 链路故障后暂停转发的时长（毫秒）
+    #[serde(default = "default_link_retry_ms")]
+    pub link_retry_ms: u64,
+}
+
+fn default_federation_decision() -> DefaultDecision {
+    DefaultDecision::Allow
+}
+
+fn default_link_retry_ms() -> u64 {
+    5_000
+}
+
+impl Default for FederationFallback {
+    fn default() -> Self {
+        Self {
+            decision: default_federation_decision(),
+            fail_notifications: false,
+            link_retry_ms: default_link_retry_ms(),
+        }
+    }
+}
+
 /// Hook配置
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct HookConfig {
@@ -233,9 +756,68 @@ pub struct HookConfig {
     /// GetConversationParticipants Hook配置列表
     #[serde(default)]
     pub get_conversation_participants: Vec<HookConfigItem>,
+    /// 租户专属Hook配置（tenant_id -> 配置）
+    ///
+    /// 按名称覆盖或追加全局Hook链，`enabled = false` 可为该租户关闭同名全局Hook；
+    /// 未配置的租户使用全局Hook链
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tenants: HashMap<String, HookConfig>,
+    /// 默认策略（对应类型未配置任何Hook时生效，租户配置按类型覆盖全局配置）
+    #[serde(default, skip_serializing_if = "HookDefaultPolicies::is_empty")]
+    pub defaults: HookDefaultPolicies,
+}
+
+/// 按Hook类型配置的默认策略
+///
+/// 以内置本地Hook的形式注入执行计划，未接入外部Hook的租户也能得到基础的放行/拒绝策略
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookDefaultPolicies {
+    /// PreSend默认决策
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_send: Option<DefaultDecision>,
+    /// Recall默认策略
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recall: Option<RecallDefaultPolicy>,
+}
+
+impl HookDefaultPolicies {
+    pub fn is_empty(&self) -> bool {
+        self.pre_send.is_none() && self.recall.is_none()
+    }
+
+    /// 以 `overrides` 中已配置的类型覆盖当前策略
+    pub fn overlay(&self, overrides: &HookDefaultPolicies) -> HookDefaultPolicies {
+        HookDefaultPolicies {
+            pre_send: overrides.pre_send.clone().or_else(|| self.pre_send.clone()),
+            recall: overrides.recall.clone().or_else(|| self.recall.clone()),
+        }
+    }
+}
+
+/// 默认决策
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DefaultDecision {
+    /// 放行
+    Allow,
+    /// 拒绝
+    Deny {
+        /// 拒绝原因（返回给调用方）
+        #[serde(default)]
+        reason: Option<String>,
+    },
+}
+
+/// Recall默认策略
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecallDefaultPolicy {
+    /// 消息发送超过该时长（秒）后拒绝撤回，未配置时放行所有撤回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deny_older_than_secs: Option<u64>,
 }
 
 /// Hook执行计划
+#[derive(Clone)]
 pub struct HookExecutionPlan {
     metadata: HookMetadata,
     /// PreSend Hook处理器（可选，用于 Local Plugin）
@@ -246,6 +828,20 @@ pub struct HookExecutionPlan {
     transport_config: Option<HookTransportConfig>,
     /// Local Plugin target（用于 Local 适配器）
     local_target: Option<String>,
+    /// 熔断器（仅保护适配器调用）
+    circuit_breaker: Option<Arc<crate::infrastructure::circuit_breaker::HookCircuitBreaker>>,
+    /// 端点健康状态（仅gRPC/WebHook，降级时跳过非必需Hook）
+    health: Option<Arc<crate::infrastructure::health::HookHealth>>,
+    /// PreSend结果缓存（可选）
+    result_cache: Option<Arc<crate::infrastructure::result_cache::HookResultCache>>,
+    /// 失败重试策略（可选，仅PostSend/Delivery）
+    retry_policy: Option<HookRetryConfig>,
+    /// 最大在途执行数（可选，未配置时使用引擎默认值）
+    max_concurrency: Option<u32>,
+    /// 生成该执行计划的配置版本号
+    config_revision: Option<u64>,
+    /// 条件选择器（默认匹配所有请求）
+    selector: HookSelector,
 }
 
 impl std::fmt::Debug for HookExecutionPlan {
@@ -257,6 +853,14 @@ impl std::fmt::Debug for HookExecutionPlan {
                 &self.pre_send_handler.as_ref().map(|_| "Some(PreSendHook)"),
             )
             .field("has_adapter", &self.adapter.is_some())
+            .field("has_result_cache", &self.result_cache.is_some())
+            .field("retry_policy", &self.retry_policy)
+            .field("config_revision", &self.config_revision)
+            .field(
+                "circuit_state",
+                &self.circuit_breaker.as_ref().map(|b| b.state()),
+            )
+            .field("health_status", &self.health.as_ref().map(|h| h.status()))
             .finish()
     }
 }
@@ -270,6 +874,13 @@ impl HookExecutionPlan {
             adapter: None,
             transport_config: None,
             local_target: None,
+            circuit_breaker: None,
+            health: None,
+            result_cache: None,
+            retry_policy: None,
+            max_concurrency: None,
+            config_revision: None,
+            selector: HookSelector::default(),
         }
     }
 
@@ -281,6 +892,13 @@ impl HookExecutionPlan {
             adapter: None,
             transport_config: None,
             local_target: None,
+            circuit_breaker: None,
+            health: None,
+            result_cache: None,
+            retry_policy: None,
+            max_concurrency: None,
+            config_revision: None,
+            selector: HookSelector::default(),
         }
     }
 
@@ -293,6 +911,21 @@ impl HookExecutionPlan {
         self
     }
 
+    /// 设置熔断器
+    pub fn with_circuit_breaker(
+        mut self,
+        breaker: Arc<crate::infrastructure::circuit_breaker::HookCircuitBreaker>,
+    ) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// 设置端点健康状态
+    pub fn with_health(mut self, health: Arc<crate::infrastructure::health::HookHealth>) -> Self {
+        self.health = Some(health);
+        self
+    }
+
     /// 设置传输配置和本地目标
     pub fn with_transport(
         mut self,
@@ -304,11 +937,23 @@ impl HookExecutionPlan {
         self
     }
 
+    /// 获取端点健康状态（仅gRPC/WebHook）
+    pub fn health(&self) -> Option<&Arc<crate::infrastructure::health::HookHealth>> {
+        self.health.as_ref()
+    }
+
     /// 获取适配器（如果已设置）
     pub fn adapter(&self) -> Option<&Arc<dyn crate::infrastructure::adapters::HookAdapter>> {
         self.adapter.as_ref()
     }
 
+    /// 获取结果缓存（仅配置了缓存的PreSend Hook）
+    pub fn result_cache(
+        &self,
+    ) -> Option<&Arc<crate::infrastructure::result_cache::HookResultCache>> {
+        self.result_cache.as_ref()
+    }
+
     /// 从HookConfigItem创建HookExecutionPlan
     ///
     /// # 参数
@@ -323,12 +968,16 @@ impl HookExecutionPlan {
             _ => HookErrorPolicy::FailFast,
         };
         use flare_im_core::HookKind;
-        // 根据hook_type字符串设置HookKind（只支持核心的4种类型，其他类型使用默认值）
+        // 根据hook_type字符串设置HookKind（未识别的类型使用默认值）
         let kind = match hook_type {
             "pre_send" | "push_pre_send" => HookKind::PreSend,
             "post_send" | "push_post_send" => HookKind::PostSend,
             "delivery" | "push_delivery" => HookKind::Delivery,
             "recall" => HookKind::Recall,
+            "presence_changed" => HookKind::PresenceChanged,
+            "session_created" => HookKind::SessionCreated,
+            "session_member_changed" => HookKind::SessionMemberChanged,
+            "media_uploaded" => HookKind::MediaUploaded,
             // 其他类型（conversation_lifecycle, user_login等）使用PreSend作为默认值
             _ => HookKind::PreSend,
         };
@@ -342,6 +991,26 @@ impl HookExecutionPlan {
             max_retries: config.max_retries,
             error_policy,
             require_success: config.require_success,
+            group: config.group.as_deref().and_then(HookGroup::parse),
+        };
+        // 结果缓存仅对PreSend生效
+        let result_cache = match (hook_type, &config.cache) {
+            ("pre_send" | "push_pre_send", Some(cache_config)) => Some(Arc::new(
+                crate::infrastructure::result_cache::HookResultCache::new(cache_config),
+            )),
+            _ => None,
+        };
+        // 重试仅对PostSend/Delivery生效；未单独配置时兼容 error_policy = "retry" + max_retries
+        let retry_policy = match kind {
+            HookKind::PostSend | HookKind::Delivery => config.retry.clone().or_else(|| {
+                (config.error_policy == "retry" && config.max_retries > 0).then(|| {
+                    HookRetryConfig {
+                        max_retries: config.max_retries,
+                        ..Default::default()
+                    }
+                })
+            }),
+            _ => None,
         };
         Self {
             metadata,
@@ -349,9 +1018,16 @@ impl HookExecutionPlan {
             adapter: None,
             transport_config: Some(config.transport.clone()),
             local_target: match &config.transport {
-                HookTransportConfig::Local { target } => Some(target.clone()),
+                HookTransportConfig::Local { target, .. } => Some(target.clone()),
                 _ => None,
             },
+            circuit_breaker: None,
+            health: None,
+            result_cache,
+            retry_policy,
+            max_concurrency: config.max_concurrency,
+            config_revision: config.config_revision,
+            selector: HookSelector::default(),
         }
     }
 
@@ -372,34 +1048,176 @@ impl HookExecutionPlan {
     }
 
     pub fn group(&self) -> HookGroup {
-        // HookGroup::from_priority 会根据 priority 自动分组
-        HookGroup::from_priority(self.metadata.priority)
+        // 优先使用配置中的 group，未配置时根据 priority 自动分组
+        self.metadata.group()
     }
 
     pub fn require_success(&self) -> bool {
         self.metadata.require_success
     }
 
+    pub fn retry_policy(&self) -> Option<&HookRetryConfig> {
+        self.retry_policy.as_ref()
+    }
+
+    /// 最大在途执行数（未配置时为 None）
+    pub fn max_concurrency(&self) -> Option<u32> {
+        self.max_concurrency
+    }
+
+    /// 生成该执行计划的配置版本号（文件配置为空）
+    pub fn config_revision(&self) -> Option<u64> {
+        self.config_revision
+    }
+
+    /// 设置条件选择器
+    pub fn with_selector(mut self, selector: HookSelector) -> Self {
+        self.selector = selector;
+        self
+    }
+
+    /// 当前请求是否命中该Hook的选择器
+    pub fn matches(&self, ctx: &Context) -> bool {
+        self.selector.matches(ctx)
+    }
+
+    /// 设置重试策略
+    pub fn with_retry_policy(mut self, retry_policy: HookRetryConfig) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// 熔断器是否放行本次适配器调用（未配置熔断器时总是放行）
+    fn circuit_permit(&self) -> Option<AdapterPermit<'_>> {
+        match self.circuit_breaker {
+            Some(ref breaker) => {
+                let permit = breaker.try_acquire();
+                if permit.is_none() {
+                    tracing::debug!(
+                        hook = %self.name(),
+                        require_success = self.require_success(),
+                        "Hook circuit open"
+                    );
+                }
+                permit.map(|permit| AdapterPermit(Some(permit)))
+            }
+            None => Some(AdapterPermit(None)),
+        }
+    }
+
+    /// 端点健康检查与熔断器是否都放行本次适配器调用
+    ///
+    /// 端点降级时跳过非必需Hook，不占用熔断器的半开探测名额
+    fn adapter_permit(&self) -> Option<AdapterPermit<'_>> {
+        if let Some(ref health) = self.health {
+            if health.should_skip(self.require_success()) {
+                tracing::debug!(hook = %self.name(), "Hook endpoint degraded, skipping hook");
+                return None;
+            }
+        }
+        self.circuit_permit()
+    }
+
+    /// 适配器调用未被放行时的结果：非必需Hook直接跳过（返回 `skipped`），
+    /// 必需Hook不放行（端点降级不会跳过必需Hook，只有熔断时会走到这里）
+    fn unavailable<T>(&self, skipped: T) -> anyhow::Result<T> {
+        if self.require_success() {
+            Err(anyhow::anyhow!(
+                "Hook {} unavailable: circuit open",
+                self.name()
+            ))
+        } else {
+            Ok(skipped)
+        }
+    }
+
+    /// 写入PreSend结果缓存（未启用缓存时忽略）
+    fn cache_decision(
+        &self,
+        key: Option<String>,
+        original_payload: Option<Vec<u8>>,
+        decision: &PreSendDecision,
+        draft: &MessageDraft,
+    ) {
+        if let (Some(cache), Some(key), Some(original)) =
+            (&self.result_cache, key, original_payload)
+        {
+            cache.put(key, decision, &original, draft);
+        }
+    }
+
     /// 执行PreSend Hook
     pub async fn execute(
         &self,
         ctx: &Context,
         draft: &mut MessageDraft,
     ) -> anyhow::Result<PreSendDecision> {
+        // 命中结果缓存时直接返回，不调用下游
+        let cache_key = self.result_cache.as_ref().map(|_| {
+            crate::infrastructure::result_cache::HookResultCache::key(
+                ctx.tenant_id().unwrap_or("0"),
+                &draft.payload,
+            )
+        });
+        if let (Some(cache), Some(key)) = (&self.result_cache, &cache_key) {
+            if let Some(decision) = cache.get(key, draft) {
+                return Ok(decision);
+            }
+        }
+        let original_payload = cache_key.as_ref().map(|_| draft.payload.clone());
+
         // 优先使用适配器（gRPC/WebHook）
         if let Some(ref adapter) = self.adapter {
-            return adapter.pre_send(ctx, draft).await;
+            // 熔断中或端点降级的非必需Hook直接跳过，必需Hook熔断时返回错误（均不写入缓存）
+            let Some(permit) = self.adapter_permit() else {
+                return self.unavailable(PreSendDecision::Continue);
+            };
+            let result = adapter.pre_send(ctx, draft).await;
+            permit.record(&result);
+            if let Ok(ref decision) = result {
+                self.cache_decision(cache_key, original_payload, decision, draft);
+            }
+            return result;
         }
 
         // 回退到本地插件
         if let Some(ref handler) = self.pre_send_handler {
-            return Ok(handler.handle(ctx, draft).await);
+            let decision = handler.handle(ctx, draft).await;
+            self.cache_decision(cache_key, original_payload, &decision, draft);
+            return Ok(decision);
         }
 
         // 没有处理器，直接通过
         Ok(PreSendDecision::Continue)
     }
 
+    /// 以影子模式执行PreSend Hook（用于配置预演）
+    ///
+    /// 直接调用适配器或本地插件，不读写结果缓存、不经过熔断器也不反馈调用结果
+    pub async fn execute_shadow(
+        &self,
+        ctx: &Context,
+        draft: &mut MessageDraft,
+    ) -> anyhow::Result<PreSendDecision> {
+        if let Some(ref adapter) = self.adapter {
+            return adapter.pre_send(ctx, draft).await;
+        }
+
+        if let Some(ref handler) = self.pre_send_handler {
+            return Ok(handler.handle(ctx, draft).await);
+        }
+
+        Ok(PreSendDecision::Continue)
+    }
+
+    /// 熔断器当前是否处于打开状态（只读，不占用探测名额）
+    pub fn is_circuit_open(&self) -> bool {
+        self.circuit_breaker
+            .as_ref()
+            .map(|breaker| breaker.state() == CircuitState::Open)
+            .unwrap_or(false)
+    }
+
     /// 执行PostSend Hook
     pub async fn execute_post_send(
         &self,
@@ -409,7 +1227,12 @@ impl HookExecutionPlan {
     ) -> anyhow::Result<()> {
         // 优先使用适配器（gRPC/WebHook）
         if let Some(ref adapter) = self.adapter {
-            return adapter.post_send(ctx, record, draft).await;
+            let Some(permit) = self.adapter_permit() else {
+                return self.unavailable(());
+            };
+            let result = adapter.post_send(ctx, record, draft).await;
+            permit.record(&result);
+            return result;
         }
 
         // 本地插件不支持PostSend，直接成功
@@ -424,7 +1247,12 @@ impl HookExecutionPlan {
     ) -> anyhow::Result<()> {
         // 优先使用适配器（gRPC/WebHook）
         if let Some(ref adapter) = self.adapter {
-            return adapter.delivery(ctx, event).await;
+            let Some(permit) = self.adapter_permit() else {
+                return self.unavailable(());
+            };
+            let result = adapter.delivery(ctx, event).await;
+            permit.record(&result);
+            return result;
         }
 
         // 本地插件不支持Delivery，直接成功
@@ -439,7 +1267,12 @@ impl HookExecutionPlan {
     ) -> anyhow::Result<PreSendDecision> {
         // 优先使用适配器（gRPC/WebHook）
         if let Some(ref adapter) = self.adapter {
-            return adapter.recall(ctx, event).await;
+            let Some(permit) = self.adapter_permit() else {
+                return self.unavailable(PreSendDecision::Continue);
+            };
+            let result = adapter.recall(ctx, event).await;
+            permit.record(&result);
+            return result;
         }
 
         // 本地插件不支持Recall，直接通过
@@ -447,6 +1280,23 @@ impl HookExecutionPlan {
     }
 }
 
+/// 适配器调用许可（未配置熔断器时不持有熔断器许可）
+///
+/// 调用被取消时熔断器许可在 Drop 中归还，不计入失败
+struct AdapterPermit<'a>(Option<crate::infrastructure::circuit_breaker::CircuitPermit<'a>>);
+
+impl AdapterPermit<'_> {
+    /// 将适配器调用结果反馈给熔断器
+    fn record<T>(self, result: &anyhow::Result<T>) {
+        if let Some(permit) = self.0 {
+            match result {
+                Ok(_) => permit.success(),
+                Err(_) => permit.failure(),
+            }
+        }
+    }
+}
+
 /// Hook执行结果
 #[derive(Debug, Clone)]
 pub struct HookExecutionResult {
@@ -457,6 +1307,345 @@ pub struct HookExecutionResult {
     pub error_message: Option<String>,
 }
 
+/// 预演中单个Hook的执行结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookTraceOutcome {
+    /// 放行
+    Continue,
+    /// 拒绝
+    Reject { message: String },
+    /// 调用出错
+    Error { message: String },
+    /// 前序Hook已拒绝或出错，未执行
+    Skipped,
+}
+
+impl HookTraceOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookTraceOutcome::Continue => "continue",
+            HookTraceOutcome::Reject { .. } => "reject",
+            HookTraceOutcome::Error { .. } => "error",
+            HookTraceOutcome::Skipped => "skipped",
+        }
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            HookTraceOutcome::Reject { message } | HookTraceOutcome::Error { message } => {
+                Some(message)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 预演中单个Hook的执行记录
+#[derive(Debug, Clone)]
+pub struct HookTrace {
+    pub hook_name: String,
+    pub group: HookGroup,
+    pub priority: i32,
+    pub outcome: HookTraceOutcome,
+    pub latency_ms: u64,
+    /// Hook是否改写了消息草稿
+    pub draft_modified: bool,
+    /// 熔断器是否打开（线上流量会跳过该Hook）
+    pub circuit_open: bool,
+}
+
+/// PreSend Hook链预演结果
+#[derive(Debug, Clone)]
+pub struct PreSendSimulation {
+    /// 整条Hook链的最终结果（Continue / Reject / Error）
+    pub outcome: HookTraceOutcome,
+    /// 按执行顺序排列的Hook记录
+    pub traces: Vec<HookTrace>,
+    /// 经过Hook链处理后的消息草稿
+    pub draft: MessageDraft,
+}
+
+/// 死信中保存的原始Hook输入（用于重放）
+#[derive(Debug, Clone)]
+pub enum HookDeadLetterPayload {
+    PostSend {
+        record: MessageRecord,
+        draft: MessageDraft,
+    },
+    Delivery {
+        event: DeliveryEvent,
+    },
+}
+
+impl HookDeadLetterPayload {
+    pub fn hook_type(&self) -> &'static str {
+        match self {
+            HookDeadLetterPayload::PostSend { .. } => "post_send",
+            HookDeadLetterPayload::Delivery { .. } => "delivery",
+        }
+    }
+}
+
+/// 重试耗尽的Hook执行（发布到死信队列）
+#[derive(Clone)]
+pub struct HookDeadLetter {
+    pub hook_name: String,
+    /// 原始请求上下文
+    pub ctx: Context,
+    pub payload: HookDeadLetterPayload,
+    /// 总执行次数（含首次执行）
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: SystemTime,
+}
+
+impl std::fmt::Debug for HookDeadLetter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HookDeadLetter")
+            .field("hook_name", &self.hook_name)
+            .field("request_id", &self.ctx.request_id())
+            .field("payload", &self.payload)
+            .field("attempts", &self.attempts)
+            .field("last_error", &self.last_error)
+            .field("failed_at", &self.failed_at)
+            .finish()
+    }
+}
+
+/// Hook执行审计决策
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookAuditDecision {
+    /// 放行（PostSend/Delivery执行成功也记为放行）
+    Continue,
+    /// 拒绝
+    Reject,
+    /// 执行出错（含超时、熔断）
+    Error,
+}
+
+impl HookAuditDecision {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "continue" => Some(Self::Continue),
+            "reject" => Some(Self::Reject),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Continue => "continue",
+            Self::Reject => "reject",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Hook执行审计记录
+#[derive(Debug, Clone)]
+pub struct HookAuditEntry {
+    /// 记录ID（落库后由数据库分配，写入前为0）
+    pub id: i64,
+    pub hook_type: String,
+    pub hook_name: String,
+    pub tenant_id: Option<String>,
+    pub message_id: Option<String>,
+    pub request_id: String,
+    pub decision: HookAuditDecision,
+    /// 拒绝原因或错误信息
+    pub reason: Option<String>,
+    pub latency_ms: u64,
+    pub executed_at: SystemTime,
+    /// 执行时使用的配置版本号
+    pub config_revision: Option<u64>,
+}
+
+impl HookAuditEntry {
+    /// Hook标识（`hook_type:name`，与统计信息一致）
+    pub fn hook_id(&self) -> String {
+        format!("{}:{}", self.hook_type, self.hook_name)
+    }
+}
+
+/// Hook执行审计查询条件
+#[derive(Debug, Clone, Default)]
+pub struct HookAuditQuery {
+    pub tenant_id: Option<String>,
+    pub message_id: Option<String>,
+    /// Hook类型
+    pub hook_type: Option<String>,
+    /// Hook名称
+    pub hook_name: Option<String>,
+    pub decision: Option<HookAuditDecision>,
+    pub start_time: Option<SystemTime>,
+    pub end_time: Option<SystemTime>,
+    pub limit: usize,
+}
+
+/// Hook资源用量（按天、租户、Hook聚合，用于向租户分摊Hook处理成本）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookUsageRecord {
+    /// 统计日期（UTC）
+    pub date: NaiveDate,
+    /// 租户ID（未携带租户的请求记为 `0`，与执行指标一致）
+    pub tenant_id: String,
+    pub hook_type: String,
+    pub hook_name: String,
+    /// 调用次数
+    pub invocations: u64,
+    /// 执行出错次数（含超时、熔断、并发受限）
+    pub failures: u64,
+    /// 累计耗时
+    pub total_latency_ms: u64,
+    /// 发往WebHook/gRPC目标的请求字节数
+    pub egress_bytes: u64,
+}
+
+impl HookUsageRecord {
+    /// 单次Hook执行的用量（统计日期为当前UTC日期）
+    pub fn invocation(
+        tenant_id: Option<&str>,
+        hook_type: &str,
+        hook_name: &str,
+        failed: bool,
+        latency_ms: u64,
+        egress_bytes: u64,
+    ) -> Self {
+        Self {
+            date: Utc::now().date_naive(),
+            tenant_id: tenant_id.unwrap_or("0").to_string(),
+            hook_type: hook_type.to_string(),
+            hook_name: hook_name.to_string(),
+            invocations: 1,
+            failures: failed as u64,
+            total_latency_ms: latency_ms,
+            egress_bytes,
+        }
+    }
+
+    /// Hook标识（`hook_type:name`，与统计信息一致）
+    pub fn hook_id(&self) -> String {
+        format!("{}:{}", self.hook_type, self.hook_name)
+    }
+
+    /// 平均耗时
+    pub fn avg_latency_ms(&self) -> u64 {
+        self.total_latency_ms
+            .checked_div(self.invocations)
+            .unwrap_or_default()
+    }
+
+    /// 累加同一天、同一租户、同一Hook的用量
+    pub fn merge(&mut self, other: &HookUsageRecord) {
+        self.invocations += other.invocations;
+        self.failures += other.failures;
+        self.total_latency_ms += other.total_latency_ms;
+        self.egress_bytes += other.egress_bytes;
+    }
+}
+
+/// Hook资源用量查询条件（日期范围为闭区间）
+#[derive(Debug, Clone, Default)]
+pub struct HookUsageQuery {
+    pub tenant_id: Option<String>,
+    pub hook_type: Option<String>,
+    pub hook_name: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub limit: usize,
+}
+
+/// Hook配置变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookConfigChangeType {
+    Create,
+    Update,
+    /// 启用/禁用
+    Status,
+    Rollback,
+}
+
+impl HookConfigChangeType {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "create" => Some(Self::Create),
+            "update" => Some(Self::Update),
+            "status" => Some(Self::Status),
+            "rollback" => Some(Self::Rollback),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Status => "status",
+            Self::Rollback => "rollback",
+        }
+    }
+}
+
+/// Hook配置版本（某次变更后的完整配置快照）
+#[derive(Debug, Clone)]
+pub struct HookConfigVersion {
+    pub hook_config_id: i64,
+    pub revision: u64,
+    pub tenant_id: Option<String>,
+    pub hook_type: String,
+    pub config: HookConfigItem,
+    pub change_type: HookConfigChangeType,
+    pub created_by: Option<String>,
+    pub created_at: SystemTime,
+}
+
+/// 熔断器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// 正常放行
+    #[default]
+    Closed,
+    /// 熔断中，跳过Hook
+    Open,
+    /// 半开，放行少量探测请求
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// Hook端点健康状态（由后台健康检查维护）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookHealthStatus {
+    /// 健康（含尚未检查）
+    #[default]
+    Healthy,
+    /// 连续检查失败，非必需调用被跳过
+    Degraded,
+}
+
+impl HookHealthStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookHealthStatus::Healthy => "healthy",
+            HookHealthStatus::Degraded => "degraded",
+        }
+    }
+}
+
+/// 用于计算延迟分位数的最近样本数
+pub const LATENCY_SAMPLE_WINDOW: usize = 1024;
+
 /// Hook统计信息
 #[derive(Debug, Clone, Default)]
 pub struct HookStatistics {
@@ -466,6 +1655,24 @@ pub struct HookStatistics {
     pub avg_latency_ms: f64,
     pub max_latency_ms: u64,
     pub min_latency_ms: u64,
+    /// 熔断器当前状态
+    pub circuit_state: CircuitState,
+    /// 累计熔断次数
+    pub circuit_open_count: u64,
+    /// 因熔断被跳过的调用次数
+    pub circuit_rejected_count: u64,
+    /// 因请求预算耗尽被跳过的调用次数
+    pub deadline_skipped_count: u64,
+    /// 因链路预算耗尽被跳过的调用次数
+    pub chain_budget_skipped_count: u64,
+    /// 端点健康状态
+    pub health_status: HookHealthStatus,
+    /// 因端点降级被跳过的调用次数
+    pub health_skipped_count: u64,
+    /// 最近一次健康检查失败的原因
+    pub last_health_error: Option<String>,
+    /// 最近的执行延迟（最多 `LATENCY_SAMPLE_WINDOW` 条，用于计算P50/P99）
+    pub recent_latencies_ms: VecDeque<u64>,
 }
 
 impl HookStatistics {
@@ -476,6 +1683,26 @@ impl HookStatistics {
         self.success_count as f64 / self.total_count as f64
     }
 
+    /// 最近执行延迟的分位数（`quantile` 取值 0.0~1.0，无样本时返回 0）
+    pub fn latency_percentile(&self, quantile: f64) -> f64 {
+        if self.recent_latencies_ms.is_empty() {
+            return 0.0;
+        }
+        let mut sorted: Vec<u64> = self.recent_latencies_ms.iter().copied().collect();
+        sorted.sort_unstable();
+        // nearest-rank 法
+        let rank = (quantile.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+        sorted[rank.saturating_sub(1).min(sorted.len() - 1)] as f64
+    }
+
+    pub fn p50_latency_ms(&self) -> f64 {
+        self.latency_percentile(0.50)
+    }
+
+    pub fn p99_latency_ms(&self) -> f64 {
+        self.latency_percentile(0.99)
+    }
+
     pub fn update(&mut self, result: &HookExecutionResult) {
         self.total_count += 1;
         if result.success {
@@ -484,6 +1711,11 @@ impl HookStatistics {
             self.failure_count += 1;
         }
 
+        if self.recent_latencies_ms.len() >= LATENCY_SAMPLE_WINDOW {
+            self.recent_latencies_ms.pop_front();
+        }
+        self.recent_latencies_ms.push_back(result.latency_ms);
+
         // 更新延迟统计
         if self.total_count == 1 {
             self.avg_latency_ms = result.latency_ms as f64;
@@ -563,6 +1795,51 @@ mod tests {
         assert_eq!(stats.success_rate(), 0.5);
     }
 
+    #[test]
+    fn test_hook_statistics_latency_percentiles() {
+        let mut stats = HookStatistics::default();
+        assert_eq!(stats.p50_latency_ms(), 0.0);
+        assert_eq!(stats.p99_latency_ms(), 0.0);
+
+        for latency_ms in 1..=100 {
+            stats.update(&HookExecutionResult {
+                hook_name: "test".to_string(),
+                executed_at: SystemTime::now(),
+                success: true,
+                latency_ms,
+                error_message: None,
+            });
+        }
+        assert_eq!(stats.p50_latency_ms(), 50.0);
+        assert_eq!(stats.p99_latency_ms(), 99.0);
+    }
+
+    #[test]
+    fn test_hook_statistics_latency_window_is_bounded() {
+        let mut stats = HookStatistics::default();
+        for _ in 0..LATENCY_SAMPLE_WINDOW {
+            stats.update(&HookExecutionResult {
+                hook_name: "test".to_string(),
+                executed_at: SystemTime::now(),
+                success: true,
+                latency_ms: 1000,
+                error_message: None,
+            });
+        }
+        for _ in 0..LATENCY_SAMPLE_WINDOW {
+            stats.update(&HookExecutionResult {
+                hook_name: "test".to_string(),
+                executed_at: SystemTime::now(),
+                success: true,
+                latency_ms: 10,
+                error_message: None,
+            });
+        }
+        assert_eq!(stats.recent_latencies_ms.len(), LATENCY_SAMPLE_WINDOW);
+        assert_eq!(stats.p99_latency_ms(), 10.0);
+        assert_eq!(stats.max_latency_ms, 1000);
+    }
+
     #[test]
     fn test_hook_execution_plan_from_config() {
         let config = HookConfigItem {
@@ -584,8 +1861,15 @@ mod tests {
                 namespace: None,
                 load_balance: None,
                 metadata: HashMap::new(),
+                tls: None,
             },
             metadata: HashMap::new(),
+            cache: None,
+            sampling: None,
+            retry: None,
+            canary: None,
+            max_concurrency: None,
+            config_revision: None,
         };
 
         let plan = HookExecutionPlan::from_hook_config(config.clone(), "pre_send");
@@ -603,8 +1887,175 @@ mod tests {
         assert_eq!(plan.metadata().kind, flare_im_core::HookKind::Recall);
     }
 
+    #[test]
+    fn test_hook_execution_plan_result_cache_only_for_pre_send() {
+        let config = HookConfigItem {
+            name: "sensitive-word".to_string(),
+            version: None,
+            description: None,
+            enabled: true,
+            priority: 100,
+            group: Some("validation".to_string()),
+            timeout_ms: 1000,
+            max_retries: 0,
+            error_policy: "fail_fast".to_string(),
+            require_success: true,
+            selector: HookSelectorConfig::default(),
+            transport: HookTransportConfig::Local {
+                target: "sensitive-word".to_string(),
+                script: None,
+                rate_limit: None,
+            },
+            metadata: HashMap::new(),
+            cache: Some(HookCacheConfig::default()),
+            sampling: None,
+            retry: None,
+            canary: None,
+            max_concurrency: None,
+            config_revision: None,
+        };
+
+        let plan = HookExecutionPlan::from_hook_config(config.clone(), "pre_send");
+        assert!(plan.result_cache.is_some());
+
+        let plan = HookExecutionPlan::from_hook_config(config, "post_send");
+        assert!(plan.result_cache.is_none());
+    }
+
+    #[test]
+    fn test_retry_config_backoff() {
+        let mut retry = HookRetryConfig {
+            max_retries: 5,
+            retry_interval_ms: 100,
+            backoff_strategy: BackoffStrategy::Exponential,
+            max_interval_ms: 500,
+            jitter: 0.0,
+        };
+        assert_eq!(retry.base_delay(1), Duration::from_millis(100));
+        assert_eq!(retry.base_delay(3), Duration::from_millis(400));
+        // 超过上限时截断
+        assert_eq!(retry.base_delay(5), Duration::from_millis(500));
+        assert_eq!(retry.delay(2), Duration::from_millis(200));
+
+        retry.backoff_strategy = BackoffStrategy::Linear;
+        assert_eq!(retry.base_delay(3), Duration::from_millis(300));
+
+        retry.backoff_strategy = BackoffStrategy::Fixed;
+        retry.jitter = 0.5;
+        let delay = retry.delay(4);
+        assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_selector_config_compile() {
+        use flare_im_core::hooks::hook_context_data::{HookContextData, set_hook_context_data};
+
+        let selector = HookSelectorConfig {
+            business_types: vec!["customer_service".to_string()],
+            user_ids: vec!["u1".to_string()],
+            tags: HashMap::from([("channel".to_string(), "app".to_string())]),
+            tag_expressions: vec!["level=vip|svip".to_string()],
+            ..Default::default()
+        }
+        .compile()
+        .unwrap();
+
+        let data = HookContextData::new()
+            .with_business_type("customer_service")
+            .with_sender_id("u1")
+            .with_tags(HashMap::from([
+                ("channel".to_string(), "app".to_string()),
+                ("level".to_string(), "svip".to_string()),
+            ]));
+        assert!(selector.matches(&set_hook_context_data(Context::root(), data.clone())));
+        assert!(!selector.matches(&set_hook_context_data(
+            Context::root(),
+            data.with_sender_id("u2"),
+        )));
+
+        let invalid = HookSelectorConfig {
+            tag_expressions: vec!["=vip".to_string()],
+            ..Default::default()
+        };
+        assert!(invalid.compile().is_err());
+    }
+
+    #[test]
+    fn test_draft_merge_strategies() {
+        let pairs = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let mut original = MessageDraft::new(b"hello".to_vec());
+        original.metadata = pairs(&[("lang", "en"), ("trace", "1")]);
+
+        let mut first = original.clone();
+        first.payload = b"hi".to_vec();
+        first.metadata = pairs(&[("lang", "zh"), ("trace", "1"), ("risk", "low")]);
+        let mut second = original.clone();
+        second.payload = b"hey".to_vec();
+        second.metadata = pairs(&[("lang", "en")]);
+        second.headers = pairs(&[("x-tag", "ad")]);
+        let untouched = original.clone();
+
+        let changes = vec![
+            DraftChanges::diff(&original, &first),
+            DraftChanges::diff(&original, &second),
+            DraftChanges::diff(&original, &untouched),
+        ];
+        assert!(changes[2].is_empty());
+
+        let mut merged = original.clone();
+        DraftMergeStrategy::LastWriteWins.merge(&mut merged, changes.clone());
+        assert_eq!(merged.payload, b"hey");
+        assert_eq!(merged.metadata, pairs(&[("lang", "zh"), ("risk", "low")]));
+        assert_eq!(merged.headers, pairs(&[("x-tag", "ad")]));
+
+        let mut merged = original.clone();
+        DraftMergeStrategy::FirstWriteWins.merge(&mut merged, changes);
+        assert_eq!(merged.payload, b"hi");
+        assert_eq!(merged.metadata, pairs(&[("lang", "zh"), ("risk", "low")]));
+
+        assert_eq!(
+            ExecutionMode::from_str("concurrent").unwrap(),
+            ExecutionMode::Concurrent
+        );
+        assert!(DraftMergeStrategy::from_str("union").is_err());
+    }
+
     #[test]
     fn test_execution_mode_default() {
         assert_eq!(ExecutionMode::default(), ExecutionMode::Sequential);
     }
+
+    #[test]
+    fn test_rate_limit_config_validate() {
+        let config: RateLimitHookConfig = serde_json::from_str(
+            r#"{"per_sender": {"limit": 10, "window_secs": 60}, "action": {"type": "tag"}}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.action,
+            RateLimitAction::Tag {
+                metadata_key: "rate_limited".to_string()
+            }
+        );
+
+        let config: RateLimitHookConfig =
+            serde_json::from_str(r#"{"per_tenant": {"limit": 100, "window_secs": 1}}"#).unwrap();
+        assert_eq!(config.action, RateLimitAction::Reject { reason: None });
+
+        assert!(RateLimitHookConfig::default().validate().is_err());
+        let zero_window = RateLimitHookConfig {
+            per_conversation: Some(RateLimitWindow {
+                limit: 10,
+                window_secs: 0,
+            }),
+            ..Default::default()
+        };
+        assert!(zero_window.validate().is_err());
+    }
 }
@@ -2,7 +2,9 @@
 //!
 //! 定义Hook配置的仓储接口
 
-use crate::domain::model::HookConfig;
+use crate::domain::model::{
+    HookAuditEntry, HookAuditQuery, HookConfig, HookDeadLetter, HookUsageQuery, HookUsageRecord,
+};
 
 /// Hook配置仓储接口
 
@@ -18,3 +20,46 @@ pub trait HookConfigRepository: Send + Sync {
     where
         F: Fn(HookConfig) + Send + Sync + 'static;
 }
+
+/// Hook死信发布接口（重试耗尽的PostSend/Delivery执行）
+#[async_trait::async_trait]
+pub trait HookDeadLetterPublisher: Send + Sync {
+    /// 发布死信
+    async fn publish(&self, letter: &HookDeadLetter) -> anyhow::Result<()>;
+}
+
+/// Hook执行审计记录器
+///
+/// 在Hook执行路径上调用，实现方不得阻塞（异步批量落库，缓冲区满时丢弃）
+pub trait HookAuditRecorder: Send + Sync {
+    /// 记录一次Hook执行
+    fn record(&self, entry: HookAuditEntry);
+}
+
+/// Hook执行审计仓储接口
+#[async_trait::async_trait]
+pub trait HookAuditRepository: Send + Sync {
+    /// 批量写入审计记录
+    async fn insert_batch(&self, entries: &[HookAuditEntry]) -> anyhow::Result<()>;
+
+    /// 查询审计记录（按执行时间倒序）
+    async fn query(&self, query: &HookAuditQuery) -> anyhow::Result<Vec<HookAuditEntry>>;
+}
+
+/// Hook资源用量记录器
+///
+/// 在Hook执行路径上调用，实现方不得阻塞（内存聚合后定期落库）
+pub trait HookUsageRecorder: Send + Sync {
+    /// 记录一次（或已聚合的多次）Hook执行用量
+    fn record(&self, usage: HookUsageRecord);
+}
+
+/// Hook资源用量仓储接口（按天聚合）
+#[async_trait::async_trait]
+pub trait HookUsageRepository: Send + Sync {
+    /// 将用量增量累加到对应日期、租户、Hook的统计行
+    async fn upsert_batch(&self, records: &[HookUsageRecord]) -> anyhow::Result<()>;
+
+    /// 查询用量（按日期倒序）
+    async fn query(&self, query: &HookUsageQuery) -> anyhow::Result<Vec<HookUsageRecord>>;
+}
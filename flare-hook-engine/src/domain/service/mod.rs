@@ -2,16 +2,35 @@
 //!
 //! 定义Hook引擎的核心领域服务
 
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
 use anyhow::Result;
 use futures_util::future::join_all;
+use once_cell::sync::Lazy;
+use tokio::sync::Semaphore;
+use tracing::{Instrument, Span};
 
-use crate::domain::model::HookExecutionPlan;
+use crate::domain::model::{
+    DraftChanges, DraftMergeStrategy, ExecutionMode, HookAuditDecision, HookAuditEntry,
+    HookDeadLetter, HookDeadLetterPayload, HookExecutionPlan, HookRetryConfig, HookTrace,
+    HookTraceOutcome, HookUsageRecord, PreSendSimulation,
+};
+use crate::domain::repository::{HookAuditRecorder, HookDeadLetterPublisher, HookUsageRecorder};
+use crate::infrastructure::adapters::hook_context_data::get_hook_context_data;
+use crate::infrastructure::concurrency::{HookConcurrencyLimiter, HookPermit};
+use crate::infrastructure::monitoring::{MetricsCollector, statistics_key};
+use crate::infrastructure::usage::meter_egress;
+use flare_im_core::metrics::HookExecutionMetrics;
 use flare_im_core::{
     DeliveryEvent, HookGroup, MessageDraft, MessageRecord, PreSendDecision,
     RecallEvent,
 };
 use flare_server_core::context::Context;
 
+static METRICS: Lazy<HookExecutionMetrics> = Lazy::new(HookExecutionMetrics::new);
+
 /// Hook分组结果
 #[derive(Debug, Default)]
 pub struct GroupedHooks {
@@ -23,10 +42,502 @@ pub struct GroupedHooks {
     pub business: Vec<HookExecutionPlan>,
 }
 
+/// 默认的请求预算保留量：剩余预算不足该值时跳过剩余的business组Hook
+pub const DEFAULT_DEADLINE_RESERVE: Duration = Duration::from_millis(10);
+
+/// 默认的慢Hook告警阈值：单次执行耗时超过该值时输出告警日志
+pub const DEFAULT_SLOW_HOOK_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// 默认的后台重试在途上限：超过后新的失败直接写入死信，不再调度重试
+pub const DEFAULT_MAX_PENDING_RETRIES: usize = 1024;
+
+/// 支持配置链路预算的Hook类型
+pub const CHAIN_BUDGET_HOOK_TYPES: [&str; 4] = ["pre_send", "post_send", "delivery", "recall"];
+
+/// 解析链路预算配置，格式为 `pre_send=150,recall=100`（毫秒）
+pub fn parse_chain_budgets(raw: &str) -> Result<HashMap<String, Duration>> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (hook_type, millis) = entry.split_once('=').ok_or_else(|| {
+                anyhow::anyhow!("invalid chain budget `{entry}`, expected hook_type=ms")
+            })?;
+            let hook_type = hook_type.trim();
+            if !CHAIN_BUDGET_HOOK_TYPES.contains(&hook_type) {
+                anyhow::bail!("unsupported hook type `{hook_type}` in chain budget");
+            }
+            let millis: u64 = millis
+                .trim()
+                .parse()
+                .map_err(|e| anyhow::anyhow!("invalid chain budget `{entry}`: {e}"))?;
+            Ok((hook_type.to_string(), Duration::from_millis(millis)))
+        })
+        .collect()
+}
+
 /// Hook编排服务
-pub struct HookOrchestrationService;
+pub struct HookOrchestrationService {
+    /// 死信发布器（未配置时重试耗尽只记录错误日志）
+    dead_letter: Option<Arc<dyn HookDeadLetterPublisher>>,
+    /// 后台重试槽位（下游持续故障时限制在途重试任务数）
+    retry_slots: Arc<Semaphore>,
+    /// 执行审计记录器（未配置时不记录审计日志）
+    audit: Option<Arc<dyn HookAuditRecorder>>,
+    /// 资源用量记录器（未配置时不统计用量）
+    usage: Option<Arc<dyn HookUsageRecorder>>,
+    /// 请求预算保留量（调用方传递了截止时间时生效）
+    deadline_reserve: Duration,
+    /// 指标收集器（可选，用于统计因预算耗尽被截断的执行）
+    metrics: Option<Arc<MetricsCollector>>,
+    /// PreSend business组的执行模式（并发时各Hook在草稿副本上执行）
+    execution_mode: ExecutionMode,
+    /// 并发执行时的草稿合并策略
+    draft_merge: DraftMergeStrategy,
+    /// Hook链路总预算（按Hook类型，未配置的类型不限制）
+    chain_budgets: HashMap<String, Duration>,
+    /// 并发限制器（未配置时不限制在途执行数）
+    concurrency: Option<Arc<HookConcurrencyLimiter>>,
+    /// 执行指标与慢Hook日志配置
+    observability: HookObservability,
+}
+
+/// Hook执行的指标与日志配置
+#[derive(Debug, Clone, Copy)]
+struct HookObservability {
+    /// 是否为执行指标记录 trace_id exemplar（启用了OTLP追踪时才有意义）
+    trace_exemplars: bool,
+    /// 慢Hook告警阈值（None 表示不告警）
+    slow_threshold: Option<Duration>,
+}
+
+impl Default for HookOrchestrationService {
+    fn default() -> Self {
+        Self {
+            dead_letter: None,
+            retry_slots: Arc::new(Semaphore::new(DEFAULT_MAX_PENDING_RETRIES)),
+            audit: None,
+            usage: None,
+            deadline_reserve: DEFAULT_DEADLINE_RESERVE,
+            metrics: None,
+            execution_mode: ExecutionMode::Sequential,
+            draft_merge: DraftMergeStrategy::default(),
+            chain_budgets: HashMap::new(),
+            concurrency: None,
+            observability: HookObservability {
+                trace_exemplars: false,
+                slow_threshold: Some(DEFAULT_SLOW_HOOK_THRESHOLD),
+            },
+        }
+    }
+}
 
 impl HookOrchestrationService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置请求预算保留量
+    pub fn with_deadline_reserve(mut self, reserve: Duration) -> Self {
+        self.deadline_reserve = reserve;
+        self
+    }
+
+    /// 设置指标收集器
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// 设置PreSend business组的执行模式和草稿合并策略
+    pub fn with_execution_mode(mut self, mode: ExecutionMode, merge: DraftMergeStrategy) -> Self {
+        self.execution_mode = mode;
+        self.draft_merge = merge;
+        self
+    }
+
+    /// 设置Hook链路总预算（如 `pre_send` 整条链路150ms）
+    ///
+    /// 预算耗尽后，剩余的非必需（`require_success = false`）Hook被跳过
+    pub fn with_chain_budget(mut self, hook_type: impl Into<String>, budget: Duration) -> Self {
+        self.chain_budgets.insert(hook_type.into(), budget);
+        self
+    }
+
+    /// 设置并发限制器（租户、单Hook、全局三级在途执行数上限）
+    pub fn with_concurrency_limiter(mut self, limiter: Arc<HookConcurrencyLimiter>) -> Self {
+        self.concurrency = Some(limiter);
+        self
+    }
+
+    /// 设置是否为执行指标记录 trace_id exemplar（启用OTLP追踪时开启）
+    pub fn with_trace_exemplars(mut self, enabled: bool) -> Self {
+        self.observability.trace_exemplars = enabled;
+        self
+    }
+
+    /// 设置慢Hook告警阈值（None 表示不告警）
+    pub fn with_slow_hook_threshold(mut self, threshold: Option<Duration>) -> Self {
+        self.observability.slow_threshold = threshold;
+        self
+    }
+
+    /// 设置死信发布器
+    pub fn with_dead_letter_publisher(mut self, publisher: Arc<dyn HookDeadLetterPublisher>) -> Self {
+        self.dead_letter = Some(publisher);
+        self
+    }
+
+    /// 设置后台重试在途上限（超过后新的失败直接写入死信）
+    pub fn with_max_pending_retries(mut self, max_pending: usize) -> Self {
+        self.retry_slots = Arc::new(Semaphore::new(max_pending.max(1)));
+        self
+    }
+
+    /// 设置执行审计记录器
+    pub fn with_audit_recorder(mut self, recorder: Arc<dyn HookAuditRecorder>) -> Self {
+        self.audit = Some(recorder);
+        self
+    }
+
+    /// 设置资源用量记录器
+    pub fn with_usage_recorder(mut self, recorder: Arc<dyn HookUsageRecorder>) -> Self {
+        self.usage = Some(recorder);
+        self
+    }
+
+    /// business组可用的剩余预算（调用方未传递截止时间时返回 None）
+    ///
+    /// 返回 `Some(Duration::ZERO)` 表示预算即将耗尽，剩余的business组Hook应被跳过
+    fn business_budget(&self, ctx: &Context) -> Option<Duration> {
+        get_hook_context_data(ctx)
+            .and_then(|data| data.remaining_budget())
+            .map(|remaining| remaining.saturating_sub(self.deadline_reserve))
+    }
+
+    /// 记录一次因请求预算耗尽被截断的执行
+    async fn record_truncated(
+        &self,
+        hook_type: &str,
+        ctx: &Context,
+        skipped: &[&HookExecutionPlan],
+    ) {
+        tracing::warn!(
+            hook_type,
+            request_id = %ctx.request_id(),
+            skipped = skipped.len(),
+            "Request budget nearly exhausted, skipping remaining business hooks"
+        );
+        if let Some(ref metrics) = self.metrics {
+            let keys: Vec<String> = skipped
+                .iter()
+                .map(|hook| {
+                    statistics_key(ctx.tenant_id(), &format!("{}:{}", hook_type, hook.name()))
+                })
+                .collect();
+            metrics.record_deadline_truncated(hook_type, &keys).await;
+        }
+    }
+
+    /// 开始一次Hook链路执行，按配置的链路预算计算截止时间
+    fn start_chain(&self, hook_type: &'static str) -> ChainBudget {
+        ChainBudget {
+            hook_type,
+            deadline: self
+                .chain_budgets
+                .get(hook_type)
+                .map(|budget| Instant::now() + *budget),
+        }
+    }
+
+    /// 链路预算已耗尽时跳过非必需Hook并记录统计，返回是否跳过
+    async fn skip_over_chain_budget(
+        &self,
+        chain: &ChainBudget,
+        ctx: &Context,
+        hook: &HookExecutionPlan,
+    ) -> bool {
+        if !chain.skips(hook) {
+            return false;
+        }
+        self.record_chain_skipped(chain, ctx, &[hook]).await;
+        true
+    }
+
+    /// 记录因链路预算耗尽被跳过的Hook
+    async fn record_chain_skipped(
+        &self,
+        chain: &ChainBudget,
+        ctx: &Context,
+        skipped: &[&HookExecutionPlan],
+    ) {
+        tracing::warn!(
+            hook_type = chain.hook_type,
+            request_id = %ctx.request_id(),
+            skipped = ?skipped.iter().map(|hook| hook.name()).collect::<Vec<_>>(),
+            "Hook chain budget exhausted, skipping non-required hooks"
+        );
+        if let Some(ref metrics) = self.metrics {
+            let keys: Vec<String> = skipped
+                .iter()
+                .map(|hook| {
+                    let hook_name = format!("{}:{}", chain.hook_type, hook.name());
+                    statistics_key(ctx.tenant_id(), &hook_name)
+                })
+                .collect();
+            metrics
+                .record_chain_budget_skipped(chain.hook_type, &keys)
+                .await;
+        }
+    }
+
+    /// 并发执行business组：每个Hook在请求预算和链路预算内执行
+    ///
+    /// 返回各Hook的执行结果（`None` 表示被截断），并记录被截断的Hook
+    async fn run_business_within_budget<'a, T, F, Fut>(
+        &self,
+        chain: &ChainBudget,
+        ctx: &Context,
+        hooks: &'a [HookExecutionPlan],
+        run: F,
+    ) -> Vec<Option<T>>
+    where
+        F: Fn(&'a HookExecutionPlan) -> Fut,
+        Fut: std::future::Future<Output = T>,
+    {
+        let budget = self.business_budget(ctx);
+        // 每个Hook的可用预算；链路预算比请求预算更紧时，截断记为链路预算耗尽
+        let limits: Vec<(Option<Duration>, bool)> = hooks
+            .iter()
+            .map(|hook| {
+                let chain_limit = chain.limit_for(hook);
+                let chain_bound =
+                    chain_limit.is_some_and(|limit| budget.is_none_or(|budget| limit < budget));
+                (min_budget(budget, chain_limit), chain_bound)
+            })
+            .collect();
+        let futures: Vec<_> = hooks
+            .iter()
+            .zip(&limits)
+            .map(|(hook, (limit, _))| within_budget(*limit, run(hook)))
+            .collect();
+
+        let results = join_all(futures).await;
+        let mut truncated = Vec::new();
+        let mut chain_skipped = Vec::new();
+        for ((hook, result), (_, chain_bound)) in hooks.iter().zip(&results).zip(&limits) {
+            match (result, chain_bound) {
+                (Some(_), _) => {}
+                (None, true) => chain_skipped.push(hook),
+                (None, false) => truncated.push(hook),
+            }
+        }
+        if !truncated.is_empty() {
+            self.record_truncated(chain.hook_type, ctx, &truncated)
+                .await;
+        }
+        if !chain_skipped.is_empty() {
+            self.record_chain_skipped(chain, ctx, &chain_skipped).await;
+        }
+        results
+    }
+
+    /// 记录一次Hook执行的审计日志
+    fn audit(
+        &self,
+        hook: &HookExecutionPlan,
+        hook_type: &str,
+        ctx: &Context,
+        message_id: Option<&str>,
+        started: Instant,
+        (decision, reason): (HookAuditDecision, Option<String>),
+    ) {
+        let Some(ref recorder) = self.audit else {
+            return;
+        };
+        recorder.record(HookAuditEntry {
+            id: 0,
+            hook_type: hook_type.to_string(),
+            hook_name: hook.name().to_string(),
+            tenant_id: ctx.tenant_id().map(str::to_string),
+            message_id: message_id.filter(|id| !id.is_empty()).map(str::to_string),
+            request_id: ctx.request_id().to_string(),
+            decision,
+            reason,
+            latency_ms: started.elapsed().as_millis() as u64,
+            executed_at: SystemTime::now(),
+            config_revision: hook.config_revision(),
+        });
+    }
+
+    /// 执行单个PreSend Hook并记录审计
+    async fn run_pre_send(
+        &self,
+        hook: &HookExecutionPlan,
+        ctx: &Context,
+        draft: &mut MessageDraft,
+    ) -> Result<PreSendDecision> {
+        let span = hook_span(hook, "pre_send", ctx);
+        let started = Instant::now();
+        let permit = acquire_permit(self.concurrency.as_deref(), hook, "pre_send", ctx).await;
+        let (result, egress_bytes) = match permit {
+            Ok(_permit) => {
+                meter_egress(hook.execute(ctx, draft))
+                    .instrument(span.clone())
+                    .await
+            }
+            Err(e) => (Err(e), 0),
+        };
+        let audit = decision_audit(&result);
+        record_decision(&span, &audit);
+        record_metrics(hook, "pre_send", ctx, started, &audit.0, self.observability);
+        record_usage(self.usage.as_deref(), hook, "pre_send", ctx, started, &audit.0, egress_bytes);
+        let message_id = draft.message_id.clone();
+        self.audit(hook, "pre_send", ctx, message_id.as_deref(), started, audit);
+        result
+    }
+
+    /// 执行单个PostSend Hook并记录审计
+    async fn run_post_send(
+        &self,
+        hook: &HookExecutionPlan,
+        ctx: &Context,
+        record: &MessageRecord,
+        draft: &MessageDraft,
+    ) -> Result<()> {
+        let span = hook_span(hook, "post_send", ctx);
+        let started = Instant::now();
+        let permit = acquire_permit(self.concurrency.as_deref(), hook, "post_send", ctx).await;
+        let (result, egress_bytes) = match permit {
+            Ok(_permit) => {
+                meter_egress(hook.execute_post_send(ctx, record, draft))
+                    .instrument(span.clone())
+                    .await
+            }
+            Err(e) => (Err(e), 0),
+        };
+        let audit = result_audit(&result);
+        record_decision(&span, &audit);
+        record_metrics(hook, "post_send", ctx, started, &audit.0, self.observability);
+        record_usage(
+            self.usage.as_deref(),
+            hook,
+            "post_send",
+            ctx,
+            started,
+            &audit.0,
+            egress_bytes,
+        );
+        self.audit(hook, "post_send", ctx, Some(&record.message_id), started, audit);
+        result
+    }
+
+    /// 执行单个Delivery Hook并记录审计
+    async fn run_delivery(
+        &self,
+        hook: &HookExecutionPlan,
+        ctx: &Context,
+        event: &DeliveryEvent,
+    ) -> Result<()> {
+        let span = hook_span(hook, "delivery", ctx);
+        let started = Instant::now();
+        let permit = acquire_permit(self.concurrency.as_deref(), hook, "delivery", ctx).await;
+        let (result, egress_bytes) = match permit {
+            Ok(_permit) => {
+                meter_egress(hook.execute_delivery(ctx, event))
+                    .instrument(span.clone())
+                    .await
+            }
+            Err(e) => (Err(e), 0),
+        };
+        let audit = result_audit(&result);
+        record_decision(&span, &audit);
+        record_metrics(hook, "delivery", ctx, started, &audit.0, self.observability);
+        record_usage(self.usage.as_deref(), hook, "delivery", ctx, started, &audit.0, egress_bytes);
+        self.audit(hook, "delivery", ctx, Some(&event.message_id), started, audit);
+        result
+    }
+
+    /// 执行单个Recall Hook并记录审计
+    async fn run_recall(
+        &self,
+        hook: &HookExecutionPlan,
+        ctx: &Context,
+        event: &RecallEvent,
+    ) -> Result<PreSendDecision> {
+        let span = hook_span(hook, "recall", ctx);
+        let started = Instant::now();
+        let permit = acquire_permit(self.concurrency.as_deref(), hook, "recall", ctx).await;
+        let (result, egress_bytes) = match permit {
+            Ok(_permit) => {
+                meter_egress(hook.execute_recall(ctx, event))
+                    .instrument(span.clone())
+                    .await
+            }
+            Err(e) => (Err(e), 0),
+        };
+        let audit = decision_audit(&result);
+        record_decision(&span, &audit);
+        record_metrics(hook, "recall", ctx, started, &audit.0, self.observability);
+        record_usage(self.usage.as_deref(), hook, "recall", ctx, started, &audit.0, egress_bytes);
+        self.audit(hook, "recall", ctx, Some(&event.message_id), started, audit);
+        result
+    }
+
+    /// 对未中断主流程的失败调度后台重试（仅配置了重试策略的Hook）
+    ///
+    /// 在途重试达到上限时不再调度，直接写入死信
+    async fn schedule_retry(
+        &self,
+        hook: &HookExecutionPlan,
+        ctx: &Context,
+        payload: HookDeadLetterPayload,
+        error: &anyhow::Error,
+    ) {
+        let Some(policy) = hook.retry_policy().cloned() else {
+            return;
+        };
+        let Ok(slot) = self.retry_slots.clone().try_acquire_owned() else {
+            tracing::warn!(
+                hook = %hook.name(),
+                hook_type = payload.hook_type(),
+                "Too many pending hook retries, sending failure to dead letter queue"
+            );
+            let letter = HookDeadLetter {
+                hook_name: hook.name().to_string(),
+                ctx: ctx.clone(),
+                payload,
+                attempts: 1,
+                last_error: error.to_string(),
+                failed_at: SystemTime::now(),
+            };
+            publish_dead_letter(self.dead_letter.as_deref(), &letter).await;
+            return;
+        };
+        let hook = hook.clone();
+        let ctx = ctx.clone();
+        let last_error = error.to_string();
+        let dead_letter = self.dead_letter.clone();
+        let concurrency = self.concurrency.clone();
+        let usage = self.usage.clone();
+        let observability = self.observability;
+        tokio::spawn(async move {
+            retry_hook(
+                hook,
+                policy,
+                ctx,
+                payload,
+                last_error,
+                dead_letter,
+                concurrency,
+                usage,
+                observability,
+            )
+            .await;
+            drop(slot);
+        });
+    }
+
     /// 分组Hook
     pub fn group_hooks(&self, hooks: Vec<HookExecutionPlan>) -> GroupedHooks {
         let mut validation = Vec::new();
@@ -63,10 +574,14 @@ impl HookOrchestrationService {
         hooks: Vec<HookExecutionPlan>,
     ) -> Result<PreSendDecision> {
         let grouped = self.group_hooks(hooks);
+        let chain = self.start_chain("pre_send");
 
         // 先执行validation组（串行，快速失败）
         for hook in &grouped.validation {
-            let decision = hook.execute(ctx, draft).await?;
+            if self.skip_over_chain_budget(&chain, ctx, hook).await {
+                continue;
+            }
+            let decision = self.run_pre_send(hook, ctx, draft).await?;
             match decision {
                 PreSendDecision::Reject { .. } => return Ok(decision),
                 PreSendDecision::Continue => continue,
@@ -75,16 +590,34 @@ impl HookOrchestrationService {
 
         // 再执行critical组（串行，保证顺序）
         for hook in &grouped.critical {
-            let decision = hook.execute(ctx, draft).await?;
+            if self.skip_over_chain_budget(&chain, ctx, hook).await {
+                continue;
+            }
+            let decision = self.run_pre_send(hook, ctx, draft).await?;
             match decision {
                 PreSendDecision::Reject { .. } => return Ok(decision),
                 PreSendDecision::Continue => continue,
             }
         }
 
-        // 最后执行business组（串行执行，因为draft是&mut不能并发）
-        for hook in &grouped.business {
-            let decision = hook.execute(ctx, draft).await?;
+        // 并发模式下business组在草稿副本上并发执行，完成后合并改动
+        if self.execution_mode == ExecutionMode::Concurrent && grouped.business.len() > 1 {
+            self.execute_pre_send_business_concurrently(&chain, ctx, draft, &grouped.business)
+                .await?;
+            return Ok(PreSendDecision::Continue);
+        }
+
+        // 最后执行business组（串行执行，因为draft是&mut不能并发；请求预算即将耗尽时跳过剩余Hook）
+        for (index, hook) in grouped.business.iter().enumerate() {
+            if self.business_budget(ctx) == Some(Duration::ZERO) {
+                let skipped: Vec<_> = grouped.business[index..].iter().collect();
+                self.record_truncated("pre_send", ctx, &skipped).await;
+                break;
+            }
+            if self.skip_over_chain_budget(&chain, ctx, hook).await {
+                continue;
+            }
+            let decision = self.run_pre_send(hook, ctx, draft).await?;
             match decision {
                 PreSendDecision::Reject { .. } => {
                     // business组即使失败也不中断主流程，只记录日志
@@ -97,6 +630,132 @@ impl HookOrchestrationService {
         Ok(PreSendDecision::Continue)
     }
 
+    /// 并发执行PreSend business组
+    ///
+    /// 每个Hook在原始草稿的副本上执行，全部完成后按priority顺序计算各自的改动并按合并策略写回草稿。
+    /// 拒绝与串行模式一样只记录日志；任一Hook出错时返回错误，草稿保持不变；
+    /// 超出请求预算或链路预算的Hook改动被丢弃。
+    async fn execute_pre_send_business_concurrently(
+        &self,
+        chain: &ChainBudget,
+        ctx: &Context,
+        draft: &mut MessageDraft,
+        hooks: &[HookExecutionPlan],
+    ) -> Result<()> {
+        let original = draft.clone();
+        let results = self
+            .run_business_within_budget(chain, ctx, hooks, |hook| {
+                let mut copy = original.clone();
+                async move {
+                    let decision = self.run_pre_send(hook, ctx, &mut copy).await?;
+                    Ok::<_, anyhow::Error>((decision, copy))
+                }
+            })
+            .await;
+
+        let mut changes = Vec::new();
+        let mut first_error = None;
+        for (hook, result) in hooks.iter().zip(results) {
+            match result {
+                None => {}
+                Some(Err(e)) => {
+                    first_error.get_or_insert(e);
+                }
+                Some(Ok((decision, modified))) => {
+                    if let PreSendDecision::Reject { .. } = decision {
+                        tracing::warn!(hook = %hook.name(), "Business hook rejected but continuing");
+                    }
+                    changes.push(DraftChanges::diff(&original, &modified));
+                }
+            }
+        }
+        if let Some(e) = first_error {
+            return Err(e);
+        }
+
+        self.draft_merge.merge(draft, changes);
+        Ok(())
+    }
+
+    /// 预演PreSend Hook链（影子模式）
+    ///
+    /// 与 `execute_pre_send` 使用相同的分组、排序和中断规则，但在草稿副本上执行，
+    /// 不读写结果缓存、不影响熔断器，并记录每个Hook的决策和耗时。
+    /// business组始终按串行顺序预演，便于观察每个Hook对草稿的改写。
+    /// 注意：远程Hook（gRPC/WebHook）仍会被真实调用。
+    pub async fn simulate_pre_send(
+        &self,
+        ctx: &Context,
+        draft: &MessageDraft,
+        hooks: Vec<HookExecutionPlan>,
+    ) -> PreSendSimulation {
+        let grouped = self.group_hooks(hooks);
+        let mut draft = draft.clone();
+        let mut traces = Vec::new();
+        let mut outcome = HookTraceOutcome::Continue;
+
+        let ordered = grouped
+            .validation
+            .iter()
+            .chain(grouped.critical.iter())
+            .chain(grouped.business.iter());
+        for hook in ordered {
+            let mut trace = HookTrace {
+                hook_name: hook.name().to_string(),
+                group: hook.group(),
+                priority: hook.priority(),
+                outcome: HookTraceOutcome::Skipped,
+                latency_ms: 0,
+                draft_modified: false,
+                circuit_open: hook.is_circuit_open(),
+            };
+            if outcome != HookTraceOutcome::Continue {
+                traces.push(trace);
+                continue;
+            }
+
+            let before = draft.clone();
+            let started = Instant::now();
+            let span = hook_span(hook, "pre_send", ctx);
+            let result = hook
+                .execute_shadow(ctx, &mut draft)
+                .instrument(span.clone())
+                .await;
+            record_decision(&span, &decision_audit(&result));
+            trace.latency_ms = started.elapsed().as_millis() as u64;
+            trace.draft_modified = draft.payload != before.payload
+                || draft.headers != before.headers
+                || draft.metadata != before.metadata
+                || draft.extra != before.extra;
+            trace.outcome = match result {
+                Ok(PreSendDecision::Continue) => HookTraceOutcome::Continue,
+                Ok(PreSendDecision::Reject { error }) => HookTraceOutcome::Reject {
+                    message: error.to_string(),
+                },
+                Err(e) => HookTraceOutcome::Error {
+                    message: e.to_string(),
+                },
+            };
+
+            // business组的拒绝不中断主流程，其余拒绝和所有错误都会中断
+            let aborts = match trace.outcome {
+                HookTraceOutcome::Reject { .. } => trace.group != HookGroup::Business,
+                HookTraceOutcome::Error { .. } => true,
+                _ => false,
+            };
+            if aborts {
+                outcome = trace.outcome.clone();
+            }
+            traces.push(trace);
+        }
+
+        PreSendSimulation {
+            outcome,
+            traces,
+            draft,
+        }
+    }
+
     /// 执行PostSend Hook（领域业务逻辑）
     pub async fn execute_post_send(
         &self,
@@ -106,32 +765,38 @@ impl HookOrchestrationService {
         hooks: Vec<HookExecutionPlan>,
     ) -> Result<()> {
         let grouped = self.group_hooks(hooks);
+        let chain = self.start_chain("post_send");
 
         // 串行执行validation和critical组
         for hook in grouped.validation.iter().chain(grouped.critical.iter()) {
-            if let Err(e) = hook.execute_post_send(ctx, record, draft).await {
+            if self.skip_over_chain_budget(&chain, ctx, hook).await {
+                continue;
+            }
+            if let Err(e) = self.run_post_send(hook, ctx, record, draft).await {
                 if hook.require_success() {
                     return Err(e);
                 }
                 tracing::warn!(hook = %hook.name(), error = %e, "PostSend hook failed but continuing");
+                self.schedule_retry(hook, ctx, post_send_payload(record, draft), &e)
+                    .await;
             }
         }
 
-        // 并发执行business组
-        let business_futures: Vec<_> = grouped
-            .business
-            .iter()
-            .map(|hook| hook.execute_post_send(ctx, record, draft))
-            .collect();
-
-        let results = join_all(business_futures).await;
+        // 并发执行business组（超出请求预算或链路预算的Hook被截断）
+        let results = self
+            .run_business_within_budget(&chain, ctx, &grouped.business, |hook| {
+                self.run_post_send(hook, ctx, record, draft)
+            })
+            .await;
         for (hook, result) in grouped.business.iter().zip(results) {
-            if let Err(e) = result {
+            if let Some(Err(e)) = result {
                 if hook.require_success() {
                     tracing::warn!(hook = %hook.name(), error = %e, "PostSend hook failed");
                 } else {
                     tracing::debug!(hook = %hook.name(), error = %e, "PostSend hook failed but ignored");
                 }
+                self.schedule_retry(hook, ctx, post_send_payload(record, draft), &e)
+                    .await;
             }
         }
 
@@ -146,32 +811,38 @@ impl HookOrchestrationService {
         hooks: Vec<HookExecutionPlan>,
     ) -> Result<()> {
         let grouped = self.group_hooks(hooks);
+        let chain = self.start_chain("delivery");
 
         // 串行执行validation和critical组
         for hook in grouped.validation.iter().chain(grouped.critical.iter()) {
-            if let Err(e) = hook.execute_delivery(ctx, event).await {
+            if self.skip_over_chain_budget(&chain, ctx, hook).await {
+                continue;
+            }
+            if let Err(e) = self.run_delivery(hook, ctx, event).await {
                 if hook.require_success() {
                     return Err(e);
                 }
                 tracing::warn!(hook = %hook.name(), error = %e, "Delivery hook failed but continuing");
+                self.schedule_retry(hook, ctx, delivery_payload(event), &e)
+                    .await;
             }
         }
 
-        // 并发执行business组
-        let business_futures: Vec<_> = grouped
-            .business
-            .iter()
-            .map(|hook| hook.execute_delivery(ctx, event))
-            .collect();
-
-        let results = join_all(business_futures).await;
+        // 并发执行business组（超出请求预算或链路预算的Hook被截断）
+        let results = self
+            .run_business_within_budget(&chain, ctx, &grouped.business, |hook| {
+                self.run_delivery(hook, ctx, event)
+            })
+            .await;
         for (hook, result) in grouped.business.iter().zip(results) {
-            if let Err(e) = result {
+            if let Some(Err(e)) = result {
                 if hook.require_success() {
                     tracing::warn!(hook = %hook.name(), error = %e, "Delivery hook failed");
                 } else {
                     tracing::debug!(hook = %hook.name(), error = %e, "Delivery hook failed but ignored");
                 }
+                self.schedule_retry(hook, ctx, delivery_payload(event), &e)
+                    .await;
             }
         }
 
@@ -186,10 +857,14 @@ impl HookOrchestrationService {
         hooks: Vec<HookExecutionPlan>,
     ) -> Result<PreSendDecision> {
         let grouped = self.group_hooks(hooks);
+        let chain = self.start_chain("recall");
 
         // 先执行validation组（串行，快速失败）
         for hook in &grouped.validation {
-            let decision = hook.execute_recall(ctx, event).await?;
+            if self.skip_over_chain_budget(&chain, ctx, hook).await {
+                continue;
+            }
+            let decision = self.run_recall(hook, ctx, event).await?;
             match decision {
                 PreSendDecision::Reject { .. } => return Ok(decision),
                 PreSendDecision::Continue => continue,
@@ -198,16 +873,27 @@ impl HookOrchestrationService {
 
         // 再执行critical组（串行，保证顺序）
         for hook in &grouped.critical {
-            let decision = hook.execute_recall(ctx, event).await?;
+            if self.skip_over_chain_budget(&chain, ctx, hook).await {
+                continue;
+            }
+            let decision = self.run_recall(hook, ctx, event).await?;
             match decision {
                 PreSendDecision::Reject { .. } => return Ok(decision),
                 PreSendDecision::Continue => continue,
             }
         }
 
-        // 最后执行business组（串行执行）
-        for hook in &grouped.business {
-            let decision = hook.execute_recall(ctx, event).await?;
+        // 最后执行business组（串行执行，请求预算即将耗尽时跳过剩余Hook）
+        for (index, hook) in grouped.business.iter().enumerate() {
+            if self.business_budget(ctx) == Some(Duration::ZERO) {
+                let skipped: Vec<_> = grouped.business[index..].iter().collect();
+                self.record_truncated("recall", ctx, &skipped).await;
+                break;
+            }
+            if self.skip_over_chain_budget(&chain, ctx, hook).await {
+                continue;
+            }
+            let decision = self.run_recall(hook, ctx, event).await?;
             match decision {
                 PreSendDecision::Reject { .. } => {
                     // business组即使失败也不中断主流程，只记录日志
@@ -221,6 +907,343 @@ impl HookOrchestrationService {
     }
 }
 
+/// 一次Hook链路执行的总预算
+struct ChainBudget {
+    hook_type: &'static str,
+    /// 链路截止时间（未配置链路预算时为 None）
+    deadline: Option<Instant>,
+}
+
+impl ChainBudget {
+    fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Hook可用的链路预算（必需的Hook不受链路预算限制）
+    fn limit_for(&self, hook: &HookExecutionPlan) -> Option<Duration> {
+        if hook.require_success() {
+            None
+        } else {
+            self.remaining()
+        }
+    }
+
+    /// 链路预算已耗尽时跳过非必需Hook
+    fn skips(&self, hook: &HookExecutionPlan) -> bool {
+        self.limit_for(hook) == Some(Duration::ZERO)
+    }
+}
+
+/// 取两个可选预算中较小的一个
+fn min_budget(a: Option<Duration>, b: Option<Duration>) -> Option<Duration> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// 在剩余预算内执行（未传递预算时不限时），预算耗尽时返回 None
+async fn within_budget<T>(
+    budget: Option<Duration>,
+    future: impl std::future::Future<Output = T>,
+) -> Option<T> {
+    match budget {
+        Some(Duration::ZERO) => None,
+        Some(budget) => tokio::time::timeout(budget, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+/// PreSend/Recall执行结果对应的审计决策
+fn decision_audit(result: &Result<PreSendDecision>) -> (HookAuditDecision, Option<String>) {
+    match result {
+        Ok(PreSendDecision::Continue) => (HookAuditDecision::Continue, None),
+        Ok(PreSendDecision::Reject { error }) => (HookAuditDecision::Reject, Some(error.to_string())),
+        Err(e) => (HookAuditDecision::Error, Some(format!("{:#}", e))),
+    }
+}
+
+/// 为单个Hook执行创建追踪Span
+///
+/// 作为当前Span（消息发送链路）的子Span，携带请求上下文中的 trace_id，
+/// 执行结束后由 [`record_decision`] 写入决策
+fn hook_span(hook: &HookExecutionPlan, hook_type: &str, ctx: &Context) -> Span {
+    tracing::info_span!(
+        "hook.execute",
+        hook = %hook.name(),
+        kind = hook_type,
+        group = hook.group().as_str(),
+        tenant_id = ctx.tenant_id().unwrap_or("0"),
+        trace_id = %ctx.trace_id(),
+        request_id = %ctx.request_id(),
+        config_revision = hook.config_revision(),
+        decision = tracing::field::Empty,
+        reason = tracing::field::Empty,
+        retry = tracing::field::Empty,
+    )
+}
+
+/// 将执行决策写入Hook Span
+fn record_decision(span: &Span, (decision, reason): &(HookAuditDecision, Option<String>)) {
+    span.record("decision", decision.as_str());
+    if let Some(reason) = reason {
+        span.record("reason", reason.as_str());
+    }
+}
+
+/// 记录Hook执行的Prometheus指标（按Hook名称、类型、分组、租户和决策统计次数与耗时）
+///
+/// 开启 exemplar 时将 trace_id 关联到耗时与错误计数；耗时超过阈值时输出带 trace_id 的慢Hook告警
+fn record_metrics(
+    hook: &HookExecutionPlan,
+    hook_type: &str,
+    ctx: &Context,
+    started: Instant,
+    decision: &HookAuditDecision,
+    observability: HookObservability,
+) {
+    let elapsed = started.elapsed();
+    let trace_id = ctx.trace_id();
+    let labels = [
+        hook.name(),
+        hook_type,
+        hook.group().as_str(),
+        ctx.tenant_id().unwrap_or("0"),
+        decision.as_str(),
+    ];
+    METRICS.observe_execution(
+        &labels,
+        elapsed.as_secs_f64(),
+        observability.trace_exemplars.then_some(trace_id),
+    );
+
+    if let Some(threshold) = observability.slow_threshold.filter(|t| elapsed >= *t) {
+        tracing::warn!(
+            hook = %hook.name(),
+            hook_type,
+            tenant_id = ctx.tenant_id().unwrap_or("0"),
+            trace_id = %trace_id,
+            request_id = %ctx.request_id(),
+            decision = decision.as_str(),
+            latency_ms = elapsed.as_millis() as u64,
+            threshold_ms = threshold.as_millis() as u64,
+            "Slow hook execution"
+        );
+    }
+}
+
+/// 记录Hook执行的资源用量（调用次数、出错次数、耗时和出站字节数，按天聚合用于成本分摊）
+fn record_usage(
+    recorder: Option<&dyn HookUsageRecorder>,
+    hook: &HookExecutionPlan,
+    hook_type: &str,
+    ctx: &Context,
+    started: Instant,
+    decision: &HookAuditDecision,
+    egress_bytes: u64,
+) {
+    let Some(recorder) = recorder else {
+        return;
+    };
+    recorder.record(HookUsageRecord::invocation(
+        ctx.tenant_id(),
+        hook_type,
+        hook.name(),
+        *decision == HookAuditDecision::Error,
+        started.elapsed().as_millis() as u64,
+        egress_bytes,
+    ));
+}
+
+/// 获取Hook执行许可并记录排队等待时间（未配置限制器时直接放行）
+///
+/// 排队超时返回错误，按Hook的错误策略处理（必需Hook中断链路，非必需Hook跳过）
+async fn acquire_permit(
+    limiter: Option<&HookConcurrencyLimiter>,
+    hook: &HookExecutionPlan,
+    hook_type: &str,
+    ctx: &Context,
+) -> Result<Option<HookPermit>> {
+    let Some(limiter) = limiter else {
+        return Ok(None);
+    };
+    let key = format!("{}:{}", hook_type, hook.name());
+    let tenant_id = ctx.tenant_id();
+    match limiter
+        .acquire(&key, hook.max_concurrency(), tenant_id)
+        .await
+    {
+        Ok(permit) => {
+            METRICS
+                .queue_wait_seconds
+                .with_label_values(&[hook.name(), hook_type, tenant_id.unwrap_or("0")])
+                .observe(permit.waited.as_secs_f64());
+            Ok(Some(permit))
+        }
+        Err(e) => {
+            METRICS
+                .concurrency_rejected_total
+                .with_label_values(&[hook.name(), hook_type, e.scope.as_str()])
+                .inc();
+            tracing::warn!(
+                hook = %hook.name(),
+                hook_type,
+                tenant_id = tenant_id.unwrap_or("0"),
+                scope = e.scope.as_str(),
+                waited_ms = e.waited.as_millis() as u64,
+                "Hook concurrency limit exceeded"
+            );
+            Err(e.into())
+        }
+    }
+}
+
+/// PostSend/Delivery执行结果对应的审计决策
+fn result_audit(result: &Result<()>) -> (HookAuditDecision, Option<String>) {
+    match result {
+        Ok(()) => (HookAuditDecision::Continue, None),
+        Err(e) => (HookAuditDecision::Error, Some(format!("{:#}", e))),
+    }
+}
+
+fn post_send_payload(record: &MessageRecord, draft: &MessageDraft) -> HookDeadLetterPayload {
+    HookDeadLetterPayload::PostSend {
+        record: record.clone(),
+        draft: draft.clone(),
+    }
+}
+
+fn delivery_payload(event: &DeliveryEvent) -> HookDeadLetterPayload {
+    HookDeadLetterPayload::Delivery {
+        event: event.clone(),
+    }
+}
+
+/// 按重试策略重新执行Hook，重试耗尽后发布死信
+///
+/// 熔断打开时不调用下游，按失败计入重试次数
+#[allow(clippy::too_many_arguments)]
+async fn retry_hook(
+    hook: HookExecutionPlan,
+    policy: HookRetryConfig,
+    ctx: Context,
+    payload: HookDeadLetterPayload,
+    mut last_error: String,
+    dead_letter: Option<Arc<dyn HookDeadLetterPublisher>>,
+    concurrency: Option<Arc<HookConcurrencyLimiter>>,
+    usage: Option<Arc<dyn HookUsageRecorder>>,
+    observability: HookObservability,
+) {
+    for retry in 1..=policy.max_retries {
+        tokio::time::sleep(policy.delay(retry)).await;
+
+        if hook.is_circuit_open() {
+            last_error = "circuit open".to_string();
+            continue;
+        }
+        let hook_type = payload.hook_type();
+        let _permit = match acquire_permit(concurrency.as_deref(), &hook, hook_type, &ctx).await {
+            Ok(permit) => permit,
+            Err(e) => {
+                last_error = e.to_string();
+                continue;
+            }
+        };
+        let result = match &payload {
+            HookDeadLetterPayload::PostSend { record, draft } => {
+                let span = hook_span(&hook, "post_send", &ctx);
+                span.record("retry", retry);
+                let started = Instant::now();
+                let (result, egress_bytes) =
+                    meter_egress(hook.execute_post_send(&ctx, record, draft))
+                        .instrument(span.clone())
+                        .await;
+                let audit = result_audit(&result);
+                record_decision(&span, &audit);
+                record_metrics(&hook, "post_send", &ctx, started, &audit.0, observability);
+                record_usage(
+                    usage.as_deref(),
+                    &hook,
+                    "post_send",
+                    &ctx,
+                    started,
+                    &audit.0,
+                    egress_bytes,
+                );
+                result
+            }
+            HookDeadLetterPayload::Delivery { event } => {
+                let span = hook_span(&hook, "delivery", &ctx);
+                span.record("retry", retry);
+                let started = Instant::now();
+                let (result, egress_bytes) = meter_egress(hook.execute_delivery(&ctx, event))
+                    .instrument(span.clone())
+                    .await;
+                let audit = result_audit(&result);
+                record_decision(&span, &audit);
+                record_metrics(&hook, "delivery", &ctx, started, &audit.0, observability);
+                record_usage(
+                    usage.as_deref(),
+                    &hook,
+                    "delivery",
+                    &ctx,
+                    started,
+                    &audit.0,
+                    egress_bytes,
+                );
+                result
+            }
+        };
+        match result {
+            Ok(()) => {
+                tracing::info!(hook = %hook.name(), retry, "Hook retry succeeded");
+                return;
+            }
+            Err(e) => {
+                tracing::debug!(hook = %hook.name(), retry, error = %e, "Hook retry failed");
+                last_error = e.to_string();
+            }
+        }
+    }
+
+    let letter = HookDeadLetter {
+        hook_name: hook.name().to_string(),
+        ctx,
+        payload,
+        attempts: policy.max_retries + 1,
+        last_error,
+        failed_at: SystemTime::now(),
+    };
+    publish_dead_letter(dead_letter.as_deref(), &letter).await;
+}
+
+/// 发布死信（未配置死信队列时只记录错误日志）
+async fn publish_dead_letter(
+    dead_letter: Option<&dyn HookDeadLetterPublisher>,
+    letter: &HookDeadLetter,
+) {
+    match dead_letter {
+        Some(publisher) => {
+            if let Err(e) = publisher.publish(letter).await {
+                tracing::error!(
+                    hook = %letter.hook_name,
+                    error = %e,
+                    last_error = %letter.last_error,
+                    "Failed to publish hook dead letter"
+                );
+            }
+        }
+        None => tracing::error!(
+            hook = %letter.hook_name,
+            hook_type = letter.payload.hook_type(),
+            attempts = letter.attempts,
+            error = %letter.last_error,
+            "Hook retries exhausted and no dead letter queue configured"
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,58 +1264,59 @@ mod tests {
             max_retries: 0,
             error_policy: HookErrorPolicy::FailFast,
             require_success: true,
+            group: None,
         };
 
-        // HookGroup::from_priority 只能区分 Validation (>=100) 和 Business (<100)
-        // Critical 组无法通过 priority 区分，所以这里 Critical 和 Business 都使用 <100
-        // 注意：实际业务中，Critical 组需要通过其他方式（如配置中的 group 字段）来区分
-        let metadata = match group {
-            HookGroup::Validation => metadata.with_priority(100 + priority),
-            HookGroup::Critical => metadata.with_priority(priority), // 使用 <100，会被分到 Business
-            HookGroup::Business => metadata.with_priority(priority),
-        };
-
-        HookExecutionPlan::new(metadata)
+        HookExecutionPlan::new(metadata.with_group(Some(group)))
     }
 
     #[test]
     fn test_group_hooks() {
-        let service = HookOrchestrationService;
+        let service = HookOrchestrationService::default();
 
         let hooks = vec![
-            create_test_hook_plan("validation-hook-1", 100, HookGroup::Validation), // priority = 200
-            create_test_hook_plan("validation-hook-2", 50, HookGroup::Validation), // priority = 150
-            create_test_hook_plan("critical-hook-1", 30, HookGroup::Critical), // priority = 30 (会被分到 Business)
-            create_test_hook_plan("business-hook-1", 10, HookGroup::Business), // priority = 10
-            create_test_hook_plan("business-hook-2", 20, HookGroup::Business), // priority = 20
+            create_test_hook_plan("validation-hook-1", 200, HookGroup::Validation),
+            create_test_hook_plan("validation-hook-2", 150, HookGroup::Validation),
+            create_test_hook_plan("critical-hook-1", 30, HookGroup::Critical),
+            create_test_hook_plan("business-hook-1", 10, HookGroup::Business),
+            create_test_hook_plan("business-hook-2", 20, HookGroup::Business),
         ];
 
         let grouped = service.group_hooks(hooks);
 
         assert_eq!(grouped.validation.len(), 2, "Validation 组应该有 2 个 hook");
-        // Critical 组无法通过 priority 区分，会被分到 Business 组
-        assert_eq!(
-            grouped.critical.len(),
-            0,
-            "Critical 组无法通过 priority 区分"
-        );
-        assert_eq!(
-            grouped.business.len(),
-            3,
-            "Business 组应该有 3 个 hook（包含 1 个 Critical）"
-        );
+        assert_eq!(grouped.critical.len(), 1, "Critical 组应该有 1 个 hook");
+        assert_eq!(grouped.business.len(), 2, "Business 组应该有 2 个 hook");
 
         // 验证排序（priority越小越先执行）
         assert_eq!(grouped.validation[0].priority(), 150);
         assert_eq!(grouped.validation[1].priority(), 200);
+        assert_eq!(grouped.critical[0].priority(), 30);
         assert_eq!(grouped.business[0].priority(), 10);
         assert_eq!(grouped.business[1].priority(), 20);
-        assert_eq!(grouped.business[2].priority(), 30); // Critical hook 被分到 Business
+    }
+
+    #[test]
+    fn test_group_hooks_explicit_group_overrides_priority() {
+        let service = HookOrchestrationService::default();
+
+        // priority >= 100 但显式指定为 critical
+        let explicit = create_test_hook_plan("critical-hook", 500, HookGroup::Critical);
+        // 未指定 group 时回退到 priority 推断
+        let inferred = HookExecutionPlan::new(
+            explicit.metadata().clone().with_name("inferred-hook").with_group(None),
+        );
+
+        let grouped = service.group_hooks(vec![explicit, inferred]);
+
+        assert_eq!(grouped.critical.len(), 1);
+        assert_eq!(grouped.validation.len(), 1);
+        assert!(grouped.business.is_empty());
     }
 
     #[test]
     fn test_group_hooks_empty() {
-        let service = HookOrchestrationService;
+        let service = HookOrchestrationService::default();
         let grouped = service.group_hooks(vec![]);
 
         assert!(grouped.validation.is_empty());
@@ -302,7 +1326,7 @@ mod tests {
 
     #[test]
     fn test_group_hooks_single_group() {
-        let service = HookOrchestrationService;
+        let service = HookOrchestrationService::default();
 
         let hooks = vec![
             create_test_hook_plan("hook-1", 10, HookGroup::Business),
@@ -316,4 +1340,561 @@ mod tests {
         assert_eq!(grouped.critical.len(), 0);
         assert_eq!(grouped.business.len(), 3);
     }
+
+    struct RewriteHook;
+
+    #[async_trait::async_trait]
+    impl flare_im_core::PreSendHook for RewriteHook {
+        async fn handle(&self, _ctx: &Context, draft: &mut MessageDraft) -> PreSendDecision {
+            draft.payload = b"***".to_vec();
+            PreSendDecision::Continue
+        }
+    }
+
+    struct RejectHook;
+
+    #[async_trait::async_trait]
+    impl flare_im_core::PreSendHook for RejectHook {
+        async fn handle(&self, _ctx: &Context, _draft: &mut MessageDraft) -> PreSendDecision {
+            use flare_im_core::error::{ErrorBuilder, ErrorCode};
+            PreSendDecision::Reject {
+                error: ErrorBuilder::new(ErrorCode::PermissionDenied, "blocked").build_error(),
+            }
+        }
+    }
+
+    fn local_plan(
+        name: &str,
+        priority: i32,
+        group: HookGroup,
+        handler: Arc<dyn flare_im_core::PreSendHook>,
+    ) -> HookExecutionPlan {
+        let metadata = create_test_hook_plan(name, priority, group).metadata().clone();
+        HookExecutionPlan::new_pre_send(metadata, handler)
+    }
+
+    #[tokio::test]
+    async fn test_simulate_pre_send_stops_after_reject() {
+        let service = HookOrchestrationService::default();
+        let hooks = vec![
+            local_plan("rewrite", 10, HookGroup::Validation, Arc::new(RewriteHook)),
+            local_plan("reject", 20, HookGroup::Validation, Arc::new(RejectHook)),
+            local_plan("after", 10, HookGroup::Critical, Arc::new(RewriteHook)),
+        ];
+        let ctx = Context::with_request_id("simulate-test".to_string());
+        let draft = MessageDraft::new(b"hello".to_vec());
+
+        let simulation = service.simulate_pre_send(&ctx, &draft, hooks).await;
+
+        assert_eq!(simulation.outcome.as_str(), "reject");
+        assert_eq!(simulation.traces.len(), 3);
+        assert!(simulation.traces[0].draft_modified);
+        assert!(simulation.traces[1].outcome.message().unwrap().contains("blocked"));
+        assert_eq!(simulation.traces[2].outcome, HookTraceOutcome::Skipped);
+        assert_eq!(simulation.draft.payload, b"***");
+        // 原始草稿不受影响
+        assert_eq!(draft.payload, b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_simulate_pre_send_business_reject_continues() {
+        let service = HookOrchestrationService::default();
+        let hooks = vec![
+            local_plan("reject", 10, HookGroup::Business, Arc::new(RejectHook)),
+            local_plan("rewrite", 20, HookGroup::Business, Arc::new(RewriteHook)),
+        ];
+        let ctx = Context::with_request_id("simulate-test".to_string());
+        let draft = MessageDraft::new(Vec::new());
+
+        let simulation = service.simulate_pre_send(&ctx, &draft, hooks).await;
+
+        assert_eq!(simulation.outcome, HookTraceOutcome::Continue);
+        assert_eq!(simulation.traces[0].outcome.as_str(), "reject");
+        assert_eq!(simulation.traces[1].outcome, HookTraceOutcome::Continue);
+    }
+
+    #[tokio::test]
+    async fn test_execute_pre_send_skips_business_hooks_when_budget_exhausted() {
+        use crate::infrastructure::adapters::hook_context_data::{
+            HookContextData, set_hook_context_data,
+        };
+
+        let metrics = Arc::new(MetricsCollector::new());
+        let service = HookOrchestrationService::new()
+            .with_deadline_reserve(Duration::from_millis(50))
+            .with_metrics(metrics.clone());
+        let hooks = vec![
+            local_plan("validate", 10, HookGroup::Validation, Arc::new(RewriteHook)),
+            local_plan("enrich", 10, HookGroup::Business, Arc::new(RejectHook)),
+            local_plan("notify", 20, HookGroup::Business, Arc::new(RejectHook)),
+        ];
+        // 剩余预算低于保留量：validation组照常执行，business组整体跳过
+        let ctx = set_hook_context_data(
+            Context::with_request_id("deadline-test".to_string()),
+            HookContextData::new().with_budget(Duration::from_millis(20)),
+        );
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+
+        let decision = service
+            .execute_pre_send(&ctx, &mut draft, hooks)
+            .await
+            .unwrap();
+
+        assert!(matches!(decision, PreSendDecision::Continue));
+        assert_eq!(draft.payload, b"***");
+        assert_eq!(
+            metrics.deadline_truncated_runs().await.get("pre_send"),
+            Some(&1)
+        );
+        let stats = metrics
+            .get_statistics(None, "pre_send:notify")
+            .await
+            .unwrap();
+        assert_eq!(stats.deadline_skipped_count, 1);
+        let validate = metrics.get_statistics(None, "pre_send:validate").await;
+        assert!(validate.is_none());
+    }
+
+    /// 在metadata中写入标记并等待一段时间（用于验证并发执行）
+    struct TagHook(&'static str);
+
+    #[async_trait::async_trait]
+    impl flare_im_core::PreSendHook for TagHook {
+        async fn handle(&self, _ctx: &Context, draft: &mut MessageDraft) -> PreSendDecision {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            draft.metadata.insert(self.0.to_string(), "1".to_string());
+            PreSendDecision::Continue
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_execute_pre_send_concurrent_business_merges_drafts() {
+        let service = HookOrchestrationService::new()
+            .with_execution_mode(ExecutionMode::Concurrent, DraftMergeStrategy::LastWriteWins);
+        let hooks = vec![
+            local_plan("tag-a", 10, HookGroup::Business, Arc::new(TagHook("a"))),
+            local_plan("reject", 20, HookGroup::Business, Arc::new(RejectHook)),
+            local_plan("tag-b", 30, HookGroup::Business, Arc::new(TagHook("b"))),
+            local_plan("rewrite", 40, HookGroup::Business, Arc::new(RewriteHook)),
+        ];
+        let ctx = Context::with_request_id("concurrent-test".to_string());
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+
+        let started = tokio::time::Instant::now();
+        let decision = service
+            .execute_pre_send(&ctx, &mut draft, hooks)
+            .await
+            .unwrap();
+
+        // 两个各耗时50ms的Hook并发执行（暂停的时钟只在所有任务等待时推进，与机器负载无关）
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(50) && elapsed < Duration::from_millis(100));
+        assert!(matches!(decision, PreSendDecision::Continue));
+        assert_eq!(draft.payload, b"***");
+        assert_eq!(draft.metadata.get("a").map(String::as_str), Some("1"));
+        assert_eq!(draft.metadata.get("b").map(String::as_str), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_pre_send_skips_optional_hooks_when_chain_budget_exhausted() {
+        fn optional(name: &str, group: HookGroup) -> HookExecutionPlan {
+            let mut metadata = create_test_hook_plan(name, 10, group).metadata().clone();
+            metadata.require_success = false;
+            HookExecutionPlan::new_pre_send(metadata, Arc::new(RewriteHook))
+        }
+
+        let metrics = Arc::new(MetricsCollector::new());
+        let service = HookOrchestrationService::new()
+            .with_chain_budget("pre_send", Duration::from_millis(30))
+            .with_metrics(metrics.clone());
+        // 必需的validation Hook耗尽链路预算后，剩余的非必需Hook被跳过，必需Hook照常执行
+        let hooks = vec![
+            local_plan("slow", 10, HookGroup::Validation, Arc::new(TagHook("slow"))),
+            optional("rewrite-critical", HookGroup::Critical),
+            local_plan("must", 10, HookGroup::Business, Arc::new(TagHook("must"))),
+            optional("rewrite-business", HookGroup::Business),
+        ];
+        let ctx = Context::with_request_id("chain-budget-test".to_string());
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+
+        let decision = service
+            .execute_pre_send(&ctx, &mut draft, hooks)
+            .await
+            .unwrap();
+
+        assert!(matches!(decision, PreSendDecision::Continue));
+        assert_eq!(draft.payload, b"hello");
+        assert!(draft.metadata.contains_key("slow"));
+        assert!(draft.metadata.contains_key("must"));
+        assert_eq!(
+            metrics.chain_budget_skipped().await.get("pre_send"),
+            Some(&2)
+        );
+        let stats = metrics
+            .get_statistics(None, "pre_send:rewrite-business")
+            .await
+            .unwrap();
+        assert_eq!(stats.chain_budget_skipped_count, 1);
+    }
+
+    #[test]
+    fn test_parse_chain_budgets() {
+        let budgets = parse_chain_budgets("pre_send=150, recall=100").unwrap();
+        assert_eq!(budgets.get("pre_send"), Some(&Duration::from_millis(150)));
+        assert_eq!(budgets.get("recall"), Some(&Duration::from_millis(100)));
+        assert!(parse_chain_budgets("").unwrap().is_empty());
+        assert!(parse_chain_budgets("pre_send").is_err());
+        assert!(parse_chain_budgets("unknown=10").is_err());
+        assert!(parse_chain_budgets("pre_send=fast").is_err());
+    }
+
+    #[derive(Default)]
+    struct MemoryAuditRecorder(std::sync::Mutex<Vec<HookAuditEntry>>);
+
+    impl HookAuditRecorder for MemoryAuditRecorder {
+        fn record(&self, entry: HookAuditEntry) {
+            self.0.lock().unwrap().push(entry);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_pre_send_records_audit() {
+        let recorder = Arc::new(MemoryAuditRecorder::default());
+        let service = HookOrchestrationService::new().with_audit_recorder(recorder.clone());
+        let hooks = vec![
+            local_plan("rewrite", 10, HookGroup::Validation, Arc::new(RewriteHook)),
+            local_plan("reject", 20, HookGroup::Validation, Arc::new(RejectHook)),
+            local_plan("after", 10, HookGroup::Critical, Arc::new(RewriteHook)),
+        ];
+        let ctx = Context::with_request_id("audit-test".to_string());
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+        draft.message_id = Some("msg-1".to_string());
+
+        let decision = service.execute_pre_send(&ctx, &mut draft, hooks).await.unwrap();
+        assert!(matches!(decision, PreSendDecision::Reject { .. }));
+
+        // 被中断的Hook未执行，不产生审计记录
+        let entries = recorder.0.lock().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].hook_id(), "pre_send:rewrite");
+        assert_eq!(entries[0].decision, HookAuditDecision::Continue);
+        assert_eq!(entries[1].hook_name, "reject");
+        assert_eq!(entries[1].decision, HookAuditDecision::Reject);
+        assert!(entries[1].reason.as_deref().unwrap().contains("blocked"));
+        assert_eq!(entries[1].message_id.as_deref(), Some("msg-1"));
+        assert_eq!(entries[1].request_id, "audit-test");
+    }
+
+    #[derive(Default)]
+    struct MemoryUsageRecorder(std::sync::Mutex<Vec<HookUsageRecord>>);
+
+    impl HookUsageRecorder for MemoryUsageRecorder {
+        fn record(&self, usage: HookUsageRecord) {
+            self.0.lock().unwrap().push(usage);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_pre_send_records_usage() {
+        let recorder = Arc::new(MemoryUsageRecorder::default());
+        let service = HookOrchestrationService::new().with_usage_recorder(recorder.clone());
+        let hooks = vec![
+            local_plan("rewrite", 10, HookGroup::Validation, Arc::new(RewriteHook)),
+            local_plan("reject", 20, HookGroup::Validation, Arc::new(RejectHook)),
+        ];
+        let ctx = Context::with_request_id("usage-test".to_string());
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+
+        service.execute_pre_send(&ctx, &mut draft, hooks).await.unwrap();
+
+        // 拒绝是正常的业务结果，按调用计费但不计为出错
+        let usage = recorder.0.lock().unwrap();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[1].hook_id(), "pre_send:reject");
+        assert_eq!(usage[1].tenant_id, "0");
+        assert_eq!(usage[1].invocations, 1);
+        assert_eq!(usage[1].failures, 0);
+        assert_eq!(usage[1].egress_bytes, 0);
+    }
+
+    /// 始终失败的适配器（记录调用次数）
+    struct FailingAdapter {
+        calls: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::infrastructure::adapters::HookAdapter for FailingAdapter {
+        async fn pre_send(&self, _ctx: &Context, _draft: &mut MessageDraft) -> Result<PreSendDecision> {
+            Ok(PreSendDecision::Continue)
+        }
+
+        async fn post_send(
+            &self,
+            _ctx: &Context,
+            _record: &MessageRecord,
+            _draft: &MessageDraft,
+        ) -> Result<()> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            anyhow::bail!("downstream unavailable")
+        }
+
+        async fn delivery(&self, _ctx: &Context, _event: &DeliveryEvent) -> Result<()> {
+            Ok(())
+        }
+
+        async fn recall(&self, _ctx: &Context, _event: &RecallEvent) -> Result<PreSendDecision> {
+            Ok(PreSendDecision::Continue)
+        }
+    }
+
+    struct ChannelDeadLetter(tokio::sync::mpsc::UnboundedSender<HookDeadLetter>);
+
+    #[async_trait::async_trait]
+    impl HookDeadLetterPublisher for ChannelDeadLetter {
+        async fn publish(&self, letter: &HookDeadLetter) -> Result<()> {
+            self.0.send(letter.clone())?;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_post_send_retry_exhausted_publishes_dead_letter() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let service = HookOrchestrationService::new()
+            .with_dead_letter_publisher(Arc::new(ChannelDeadLetter(tx)));
+        let adapter = Arc::new(FailingAdapter {
+            calls: std::sync::atomic::AtomicU32::new(0),
+        });
+        let metadata = create_test_hook_plan("audit", 10, HookGroup::Business).metadata().clone();
+        let hook = HookExecutionPlan::new(metadata)
+            .with_adapter(adapter.clone())
+            .with_retry_policy(crate::domain::model::HookRetryConfig {
+                max_retries: 2,
+                retry_interval_ms: 1,
+                jitter: 0.0,
+                ..Default::default()
+            });
+
+        let ctx = Context::with_request_id("retry-test".to_string());
+        let record = MessageRecord {
+            message_id: "msg-1".to_string(),
+            client_message_id: None,
+            conversation_id: "conv-1".to_string(),
+            sender_id: "user-1".to_string(),
+            conversation_type: None,
+            message_type: None,
+            persisted_at: SystemTime::now(),
+            metadata: HashMap::new(),
+        };
+        let draft = MessageDraft::new(b"hello".to_vec());
+
+        // business组失败不影响主流程
+        service
+            .execute_post_send(&ctx, &record, &draft, vec![hook])
+            .await
+            .unwrap();
+
+        let letter = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(letter.hook_name, "audit");
+        assert_eq!(letter.attempts, 3);
+        assert!(letter.last_error.contains("downstream unavailable"));
+        assert_eq!(adapter.calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+        match letter.payload {
+            HookDeadLetterPayload::PostSend { record, .. } => assert_eq!(record.message_id, "msg-1"),
+            other => panic!("unexpected payload: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pending_retry_limit_sends_overflow_to_dead_letter() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let service = HookOrchestrationService::new()
+            .with_dead_letter_publisher(Arc::new(ChannelDeadLetter(tx)))
+            .with_max_pending_retries(1);
+        let adapter = Arc::new(FailingAdapter {
+            calls: std::sync::atomic::AtomicU32::new(0),
+        });
+        let metadata = create_test_hook_plan("audit", 10, HookGroup::Business)
+            .metadata()
+            .clone();
+        let hook = HookExecutionPlan::new(metadata)
+            .with_adapter(adapter.clone())
+            .with_retry_policy(crate::domain::model::HookRetryConfig {
+                max_retries: 1,
+                retry_interval_ms: 60_000,
+                jitter: 0.0,
+                ..Default::default()
+            });
+
+        let ctx = Context::with_request_id("retry-limit-test".to_string());
+        let draft = MessageDraft::new(b"hello".to_vec());
+        for message_id in ["msg-1", "msg-2"] {
+            let record = MessageRecord {
+                message_id: message_id.to_string(),
+                client_message_id: None,
+                conversation_id: "conv-1".to_string(),
+                sender_id: "user-1".to_string(),
+                conversation_type: None,
+                message_type: None,
+                persisted_at: SystemTime::now(),
+                metadata: HashMap::new(),
+            };
+            service
+                .execute_post_send(&ctx, &record, &draft, vec![hook.clone()])
+                .await
+                .unwrap();
+        }
+
+        // 第一条失败占用唯一的重试槽位，第二条不再调度重试，直接写入死信
+        let letter = rx.try_recv().unwrap();
+        assert_eq!(letter.attempts, 1);
+        assert!(letter.last_error.contains("downstream unavailable"));
+        match letter.payload {
+            HookDeadLetterPayload::PostSend { record, .. } => {
+                assert_eq!(record.message_id, "msg-2")
+            }
+            other => panic!("unexpected payload: {:?}", other),
+        }
+        assert!(rx.try_recv().is_err());
+        assert_eq!(adapter.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_open_circuit_fails_required_hooks_and_skips_optional_ones() {
+        use crate::infrastructure::circuit_breaker::{CircuitBreakerConfig, HookCircuitBreaker};
+
+        let service = HookOrchestrationService::new();
+        let adapter = Arc::new(FailingAdapter {
+            calls: std::sync::atomic::AtomicU32::new(0),
+        });
+        let breaker = Arc::new(HookCircuitBreaker::new(
+            "audit",
+            CircuitBreakerConfig {
+                window_size: 1,
+                min_requests: 1,
+                ..Default::default()
+            },
+        ));
+        breaker.record_failure();
+        let required = create_test_hook_plan("audit", 10, HookGroup::Validation)
+            .with_adapter(adapter.clone())
+            .with_circuit_breaker(breaker.clone());
+        let mut metadata = required.metadata().clone();
+        metadata.require_success = false;
+        let optional = HookExecutionPlan::new(metadata)
+            .with_adapter(adapter.clone())
+            .with_circuit_breaker(breaker);
+
+        let ctx = Context::with_request_id("circuit-test".to_string());
+        let record = MessageRecord {
+            message_id: "msg-1".to_string(),
+            client_message_id: None,
+            conversation_id: "conv-1".to_string(),
+            sender_id: "user-1".to_string(),
+            conversation_type: None,
+            message_type: None,
+            persisted_at: SystemTime::now(),
+            metadata: HashMap::new(),
+        };
+        let draft = MessageDraft::new(b"hello".to_vec());
+
+        let error = service
+            .execute_post_send(&ctx, &record, &draft, vec![required.clone()])
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("circuit open"));
+        let mut pre_send_draft = draft.clone();
+        assert!(
+            service
+                .execute_pre_send(&ctx, &mut pre_send_draft, vec![required])
+                .await
+                .is_err()
+        );
+
+        service
+            .execute_post_send(&ctx, &record, &draft, vec![optional])
+            .await
+            .unwrap();
+        // 熔断期间不调用下游
+        assert_eq!(adapter.calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    /// PreSend Hook 的执行次数与耗时样本数
+    fn pre_send_executions(hook: &str, group: &str, tenant_id: &str, outcome: &str) -> (u64, u64) {
+        let labels = [hook, "pre_send", group, tenant_id, outcome];
+        (
+            METRICS.executions_total.with_label_values(&labels).get(),
+            METRICS
+                .execution_duration_seconds
+                .with_label_values(&labels)
+                .get_sample_count(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_execute_pre_send_records_per_hook_metrics() {
+        // 指标为进程级全局，Hook 名称唯一以免与其他测试互相影响
+        let suffix = uuid::Uuid::new_v4().to_string();
+        let rewrite = format!("rewrite-{suffix}");
+        let reject = format!("reject-{suffix}");
+        let after = format!("after-{suffix}");
+        let service = HookOrchestrationService::new();
+        let hooks = vec![
+            local_plan(&rewrite, 10, HookGroup::Validation, Arc::new(RewriteHook)),
+            local_plan(&reject, 20, HookGroup::Validation, Arc::new(RejectHook)),
+            local_plan(&after, 10, HookGroup::Critical, Arc::new(RewriteHook)),
+        ];
+        let ctx = Context::root().with_tenant_id("tenant-metrics");
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+
+        service
+            .execute_pre_send(&ctx, &mut draft, hooks)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            pre_send_executions(&rewrite, "validation", "tenant-metrics", "continue"),
+            (1, 1)
+        );
+        assert_eq!(
+            pre_send_executions(&reject, "validation", "tenant-metrics", "reject"),
+            (1, 1)
+        );
+        // 被中断的Hook未执行，不计数
+        assert_eq!(
+            pre_send_executions(&after, "critical", "tenant-metrics", "continue"),
+            (0, 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_pre_send_metrics_default_tenant() {
+        let name = format!("rewrite-{}", uuid::Uuid::new_v4());
+        let service = HookOrchestrationService::new();
+        let hooks = vec![local_plan(
+            &name,
+            10,
+            HookGroup::Business,
+            Arc::new(RewriteHook),
+        )];
+        let ctx = Context::with_request_id("metrics-test".to_string());
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+
+        service
+            .execute_pre_send(&ctx, &mut draft, hooks.clone())
+            .await
+            .unwrap();
+        service
+            .execute_pre_send(&ctx, &mut draft, hooks)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            pre_send_executions(&name, "business", "0", "continue"),
+            (2, 2)
+        );
+    }
 }
@@ -0,0 +1,209 @@
+//! # 金丝雀适配器
+//!
+//! 同时持有稳定版本和金丝雀版本的适配器，按租户白名单和流量百分比选择版本，
+//! 并将两个版本的调用结果分别记录到指标收集器，便于全量前对比错误率。
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+
+use anyhow::Result;
+
+use flare_im_core::{DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent};
+use flare_server_core::context::Context;
+
+use crate::domain::model::{HookCanaryConfig, HookExecutionResult};
+use crate::infrastructure::adapters::HookAdapter;
+use crate::infrastructure::monitoring::MetricsCollector;
+
+/// 未配置版本号时稳定版本的统计标识
+pub const STABLE_VERSION: &str = "stable";
+
+/// 金丝雀流量路由
+#[derive(Debug, Clone)]
+pub struct CanaryRouter {
+    percentage: u32,
+    tenants: HashSet<String>,
+}
+
+impl CanaryRouter {
+    pub fn new(config: &HookCanaryConfig) -> Self {
+        Self {
+            percentage: config.percentage.min(100),
+            tenants: config.tenants.iter().cloned().collect(),
+        }
+    }
+
+    /// 本次调用是否路由到金丝雀版本
+    pub fn route_to_canary(&self, tenant_id: Option<&str>) -> bool {
+        if tenant_id.is_some_and(|tenant_id| self.tenants.contains(tenant_id)) {
+            return true;
+        }
+        match self.percentage {
+            0 => false,
+            100 => true,
+            percentage => rand::random::<f64>() * 100.0 < percentage as f64,
+        }
+    }
+}
+
+/// 单个版本的适配器及其统计键
+struct VersionedAdapter {
+    adapter: Arc<dyn HookAdapter>,
+    /// 统计键（`hook_type:name#version`）
+    metrics_key: String,
+}
+
+/// 金丝雀Hook适配器
+pub struct CanaryHookAdapter {
+    stable: VersionedAdapter,
+    canary: VersionedAdapter,
+    router: CanaryRouter,
+    metrics: Arc<MetricsCollector>,
+}
+
+impl CanaryHookAdapter {
+    pub fn new(
+        hook_key: &str,
+        stable_version: Option<&str>,
+        stable: Arc<dyn HookAdapter>,
+        canary_config: &HookCanaryConfig,
+        canary: Arc<dyn HookAdapter>,
+        metrics: Arc<MetricsCollector>,
+    ) -> Self {
+        Self {
+            stable: VersionedAdapter {
+                adapter: stable,
+                metrics_key: version_metrics_key(hook_key, stable_version.unwrap_or(STABLE_VERSION)),
+            },
+            canary: VersionedAdapter {
+                adapter: canary,
+                metrics_key: version_metrics_key(hook_key, &canary_config.version),
+            },
+            router: CanaryRouter::new(canary_config),
+            metrics,
+        }
+    }
+
+    fn select(&self, ctx: &Context) -> &VersionedAdapter {
+        if self.router.route_to_canary(ctx.tenant_id()) {
+            &self.canary
+        } else {
+            &self.stable
+        }
+    }
+
+    async fn record<T>(
+        &self,
+        ctx: &Context,
+        target: &VersionedAdapter,
+        started: Instant,
+        result: &Result<T>,
+    ) {
+        self.metrics
+            .record(
+                ctx.tenant_id(),
+                &HookExecutionResult {
+                    hook_name: target.metrics_key.clone(),
+                    executed_at: SystemTime::now(),
+                    success: result.is_ok(),
+                    latency_ms: started.elapsed().as_millis() as u64,
+                    error_message: result.as_ref().err().map(|e| format!("{:#}", e)),
+                },
+            )
+            .await;
+    }
+}
+
+/// 版本维度的统计键
+pub fn version_metrics_key(hook_key: &str, version: &str) -> String {
+    format!("{}#{}", hook_key, version)
+}
+
+#[async_trait::async_trait]
+impl HookAdapter for CanaryHookAdapter {
+    async fn pre_send(&self, ctx: &Context, draft: &mut MessageDraft) -> Result<PreSendDecision> {
+        let target = self.select(ctx);
+        let started = Instant::now();
+        let result = target.adapter.pre_send(ctx, draft).await;
+        self.record(ctx, target, started, &result).await;
+        result
+    }
+
+    async fn post_send(
+        &self,
+        ctx: &Context,
+        record: &MessageRecord,
+        draft: &MessageDraft,
+    ) -> Result<()> {
+        let target = self.select(ctx);
+        let started = Instant::now();
+        let result = target.adapter.post_send(ctx, record, draft).await;
+        self.record(ctx, target, started, &result).await;
+        result
+    }
+
+    async fn delivery(&self, ctx: &Context, event: &DeliveryEvent) -> Result<()> {
+        let target = self.select(ctx);
+        let started = Instant::now();
+        let result = target.adapter.delivery(ctx, event).await;
+        self.record(ctx, target, started, &result).await;
+        result
+    }
+
+    async fn recall(&self, ctx: &Context, event: &RecallEvent) -> Result<PreSendDecision> {
+        let target = self.select(ctx);
+        let started = Instant::now();
+        let result = target.adapter.recall(ctx, event).await;
+        self.record(ctx, target, started, &result).await;
+        result
+    }
+
+    /// 稳定版本与灰度版本都需要健康
+    async fn health_check(&self) -> Result<()> {
+        self.stable.adapter.health_check().await?;
+        self.canary.adapter.health_check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::HookTransportConfig;
+
+    fn canary_config(percentage: u32, tenants: &[&str]) -> HookCanaryConfig {
+        HookCanaryConfig {
+            version: "v2".to_string(),
+            transport: HookTransportConfig::Local {
+                target: "noop".to_string(),
+                script: None,
+                rate_limit: None,
+            },
+            percentage,
+            tenants: tenants.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_canary_router() {
+        let router = CanaryRouter::new(&canary_config(0, &["tenant-a"]));
+        assert!(router.route_to_canary(Some("tenant-a")));
+        assert!(!router.route_to_canary(Some("tenant-b")));
+        assert!(!router.route_to_canary(None));
+
+        let router = CanaryRouter::new(&canary_config(100, &[]));
+        assert!(router.route_to_canary(None));
+
+        // 百分比超过100按全量处理
+        let router = CanaryRouter::new(&canary_config(150, &[]));
+        assert!(router.route_to_canary(Some("tenant-b")));
+    }
+
+    #[test]
+    fn test_version_metrics_key() {
+        assert_eq!(
+            version_metrics_key("pre_send:filter", "v2"),
+            "pre_send:filter#v2"
+        );
+    }
+}
@@ -15,7 +15,7 @@ use flare_proto::hooks::{
 };
 use flare_server_core::context::Context;
 use crate::infrastructure::adapters::hook_context_data::{
-    HookContextData, set_hook_context_data,
+    HOOK_BUDGET_ATTRIBUTE, HookContextData, set_hook_context_data,
 };
 
 /// 将 flare_server_core::Context 转换为 HookInvocationContext
@@ -23,6 +23,14 @@ pub fn context_to_proto(ctx: &Context) -> HookInvocationContext {
     use crate::infrastructure::adapters::hook_context_data::get_hook_context_data;
 
     let hook_data = get_hook_context_data(ctx).cloned().unwrap_or_default();
+    // 向下游Hook传递的是当前剩余预算
+    let mut attributes = hook_data.attributes.clone();
+    if let Some(budget) = hook_data.remaining_budget() {
+        attributes.insert(
+            HOOK_BUDGET_ATTRIBUTE.to_string(),
+            budget.as_millis().to_string(),
+        );
+    }
 
     HookInvocationContext {
         request_context: Some(RequestContext {
@@ -36,7 +44,7 @@ pub fn context_to_proto(ctx: &Context) -> HookInvocationContext {
         }),
         tenant: ctx.tenant_id().map(|tid| TenantContext {
             tenant_id: tid.to_string(),
-            business_type: String::new(),
+            business_type: hook_data.business_type.clone().unwrap_or_default(),
             environment: String::new(),
             organization_id: String::new(),
             labels: std::collections::HashMap::new(),
@@ -50,7 +58,7 @@ pub fn context_to_proto(ctx: &Context) -> HookInvocationContext {
             .cloned()
             .unwrap_or_else(|| "messaging".to_string()),
         tags: hook_data.tags.clone(),
-        attributes: hook_data.attributes.clone(),
+        attributes,
     }
 }
 
@@ -166,7 +174,7 @@ pub fn recall_event_to_proto(event: &RecallEvent) -> HookRecallEvent {
         message_id: event.message_id.clone(),
         operator_id: event.operator_id.clone(),
         recalled_at: Some(system_time_to_timestamp(event.recalled_at)),
-        metadata: event.metadata.clone(),
+        metadata: event.wire_metadata(),
     }
 }
 
@@ -264,6 +272,34 @@ pub fn proto_to_context(proto: &HookInvocationContext) -> Context {
         ctx = ctx.with_session_id(proto.conversation_id.clone());
     }
 
+    // 选择器匹配字段：消息类型来自 attributes，业务类型优先取租户上下文，发送者取请求方
+    let non_empty = |value: &str| (!value.is_empty()).then(|| value.to_string());
+    let message_type = proto
+        .attributes
+        .get("message_type")
+        .and_then(|v| non_empty(v));
+    let business_type = proto
+        .tenant
+        .as_ref()
+        .and_then(|t| non_empty(&t.business_type))
+        .or_else(|| {
+            proto
+                .attributes
+                .get("business_type")
+                .and_then(|v| non_empty(v))
+        });
+    let sender_id = proto
+        .request_context
+        .as_ref()
+        .and_then(|r| r.actor.as_ref())
+        .and_then(|a| non_empty(&a.actor_id));
+    // 调用方剩余的请求预算（毫秒）
+    let deadline = proto
+        .attributes
+        .get(HOOK_BUDGET_ATTRIBUTE)
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|budget_ms| std::time::Instant::now() + std::time::Duration::from_millis(budget_ms));
+
     // 创建 HookContextData 并存储到 Context
     let hook_data = HookContextData {
         conversation_id: if proto.conversation_id.is_empty() {
@@ -276,12 +312,14 @@ pub fn proto_to_context(proto: &HookInvocationContext) -> Context {
         } else {
             Some(proto.conversation_type.clone())
         },
-        message_type: None,
-        sender_id: None,
+        message_type,
+        business_type,
+        sender_id,
         tags: proto.tags.clone(),
         attributes: proto.attributes.clone(),
         request_metadata: std::collections::HashMap::new(),
         occurred_at: None,
+        deadline,
     };
 
     ctx = set_hook_context_data(ctx, hook_data);
@@ -0,0 +1,145 @@
+//! # 默认策略适配器
+//!
+//! 当某类Hook未配置任何外部Hook时，由执行计划注入的内置本地Hook，在进程内执行默认策略：
+//! - PreSend：按配置放行或拒绝
+//! - Recall：消息发送时间超过 `deny_older_than_secs` 时拒绝撤回
+//!
+//! Recall 的消息发送时间取自 `RecallEvent.sent_at`（gRPC 接口从 `message_sent_at_ms` 元数据填充），
+//! 调用方未提供时无法判断消息时长，按放行处理。
+
+use std::time::Duration;
+
+use anyhow::Result;
+
+use flare_im_core::error::{ErrorBuilder, ErrorCode};
+use flare_im_core::{DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent};
+use flare_server_core::context::Context;
+
+use crate::domain::model::{DefaultDecision, RecallDefaultPolicy};
+
+/// 内置默认策略Hook名称前缀
+pub const DEFAULT_POLICY_HOOK_PREFIX: &str = "builtin:default";
+
+/// 默认策略适配器
+pub enum DefaultPolicyHookAdapter {
+    PreSend(DefaultDecision),
+    Recall(RecallDefaultPolicy),
+}
+
+impl DefaultPolicyHookAdapter {
+    fn reject(message: &str) -> PreSendDecision {
+        PreSendDecision::Reject {
+            error: ErrorBuilder::new(ErrorCode::PermissionDenied, message).build_error(),
+        }
+    }
+
+    /// 判断撤回是否超出允许的时长
+    fn recall_decision(policy: &RecallDefaultPolicy, event: &RecallEvent) -> PreSendDecision {
+        let Some(max_age_secs) = policy.deny_older_than_secs else {
+            return PreSendDecision::Continue;
+        };
+        let Some(sent_at) = event.sent_at else {
+            tracing::debug!(
+                message_id = %event.message_id,
+                "Message sent time missing from recall event, default recall policy allows"
+            );
+            return PreSendDecision::Continue;
+        };
+
+        let age = event
+            .recalled_at
+            .duration_since(sent_at)
+            .unwrap_or(Duration::ZERO);
+        if age > Duration::from_secs(max_age_secs) {
+            Self::reject(&format!(
+                "Message older than {} seconds cannot be recalled",
+                max_age_secs
+            ))
+        } else {
+            PreSendDecision::Continue
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl super::HookAdapter for DefaultPolicyHookAdapter {
+    async fn pre_send(&self, _ctx: &Context, _draft: &mut MessageDraft) -> Result<PreSendDecision> {
+        Ok(match self {
+            DefaultPolicyHookAdapter::PreSend(DefaultDecision::Deny { reason }) => Self::reject(
+                reason
+                    .as_deref()
+                    .unwrap_or("Message rejected by default policy"),
+            ),
+            _ => PreSendDecision::Continue,
+        })
+    }
+
+    async fn post_send(
+        &self,
+        _ctx: &Context,
+        _record: &MessageRecord,
+        _draft: &MessageDraft,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn delivery(&self, _ctx: &Context, _event: &DeliveryEvent) -> Result<()> {
+        Ok(())
+    }
+
+    async fn recall(&self, _ctx: &Context, event: &RecallEvent) -> Result<PreSendDecision> {
+        Ok(match self {
+            DefaultPolicyHookAdapter::Recall(policy) => Self::recall_decision(policy, event),
+            _ => PreSendDecision::Continue,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::adapters::HookAdapter;
+    use std::collections::HashMap;
+    use std::time::SystemTime;
+
+    fn recall_event(sent_secs_ago: Option<u64>) -> RecallEvent {
+        let now = SystemTime::now();
+        RecallEvent {
+            message_id: "msg-1".to_string(),
+            operator_id: "user-1".to_string(),
+            recalled_at: now,
+            sent_at: sent_secs_ago.map(|secs| now - Duration::from_secs(secs)),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recall_denied_when_older_than_limit() {
+        let adapter = DefaultPolicyHookAdapter::Recall(RecallDefaultPolicy {
+            deny_older_than_secs: Some(120),
+        });
+        let ctx = Context::with_request_id("req-1".to_string());
+
+        let decision = adapter.recall(&ctx, &recall_event(Some(300))).await.unwrap();
+        assert!(!decision.is_continue());
+
+        let decision = adapter.recall(&ctx, &recall_event(Some(30))).await.unwrap();
+        assert!(decision.is_continue());
+
+        // 缺少发送时间时放行
+        let decision = adapter.recall(&ctx, &recall_event(None)).await.unwrap();
+        assert!(decision.is_continue());
+    }
+
+    #[tokio::test]
+    async fn test_pre_send_default_decision() {
+        let ctx = Context::with_request_id("req-1".to_string());
+        let mut draft = MessageDraft::new(Vec::new());
+
+        let allow = DefaultPolicyHookAdapter::PreSend(DefaultDecision::Allow);
+        assert!(allow.pre_send(&ctx, &mut draft).await.unwrap().is_continue());
+
+        let deny = DefaultPolicyHookAdapter::PreSend(DefaultDecision::Deny { reason: None });
+        assert!(!deny.pre_send(&ctx, &mut draft).await.unwrap().is_continue());
+    }
+}
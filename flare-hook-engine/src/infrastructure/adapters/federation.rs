@@ -0,0 +1,301 @@
+//! # 联邦Hook适配器
+//!
+//! 边缘/多区域部署时，本地Hook引擎把选定的Hook转发到中心Hook引擎执行：
+//! 对延迟不敏感的Hook在中心统一执行，校验类Hook仍在本地执行。
+//!
+//! - 转发使用 HookExtension gRPC 协议，Channel 由 [`GrpcChannelPool`] 共享，首次调用时建连
+//! - 可选缓存中心的PreSend决策（相同租户、相同内容直接复用）
+//! - 转发失败（连接失败、超时）后标记链路不可用，`link_retry_ms` 内直接执行本地降级策略，
+//!   避免每条消息都等待超时
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tokio::sync::OnceCell;
+
+use flare_im_core::error::{ErrorBuilder, ErrorCode};
+use flare_im_core::{DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent};
+use flare_server_core::context::Context;
+
+use crate::domain::model::{DefaultDecision, FederationFallback, GrpcTlsConfig, HookCacheConfig};
+use crate::infrastructure::adapters::grpc::GrpcHookAdapter;
+use crate::infrastructure::adapters::grpc_pool::GrpcChannelPool;
+use crate::infrastructure::result_cache::HookResultCache;
+
+/// 默认单次转发超时
+const DEFAULT_FORWARD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// 联邦Hook适配器
+pub struct FederatedHookAdapter {
+    channel_pool: Arc<GrpcChannelPool>,
+    endpoint: String,
+    tls: Option<GrpcTlsConfig>,
+    metadata: HashMap<String, String>,
+    timeout: Duration,
+    /// 到中心引擎的适配器（首次转发时建立，建连失败时下次重试）
+    central: OnceCell<GrpcHookAdapter>,
+    decision_cache: Option<HookResultCache>,
+    fallback: FederationFallback,
+    /// 链路不可用的截止时间（None 表示链路正常）
+    link_down_until: Mutex<Option<Instant>>,
+}
+
+impl FederatedHookAdapter {
+    pub fn new(
+        channel_pool: Arc<GrpcChannelPool>,
+        endpoint: String,
+        tls: Option<GrpcTlsConfig>,
+        timeout_ms: Option<u64>,
+        metadata: HashMap<String, String>,
+        decision_cache: Option<&HookCacheConfig>,
+        fallback: FederationFallback,
+    ) -> Self {
+        tracing::info!(
+            endpoint = %endpoint,
+            cache = decision_cache.is_some(),
+            "Created federated hook adapter"
+        );
+        Self {
+            channel_pool,
+            endpoint,
+            tls,
+            metadata,
+            timeout: timeout_ms
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_FORWARD_TIMEOUT),
+            central: OnceCell::new(),
+            decision_cache: decision_cache.map(HookResultCache::new),
+            fallback,
+            link_down_until: Mutex::new(None),
+        }
+    }
+
+    /// 链路是否处于不可用窗口内
+    fn link_down(&self) -> bool {
+        let mut link_down_until = self.link_down_until.lock().unwrap();
+        match *link_down_until {
+            Some(until) if until > Instant::now() => true,
+            Some(_) => {
+                // 窗口到期后放行下一次转发作为探测
+                *link_down_until = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn mark_link_down(&self, error: &anyhow::Error) {
+        let mut link_down_until = self.link_down_until.lock().unwrap();
+        if link_down_until.is_none() {
+            tracing::warn!(
+                endpoint = %self.endpoint,
+                error = %error,
+                retry_after_ms = self.fallback.link_retry_ms,
+                "Federation link down, applying local fallback policy"
+            );
+        }
+        *link_down_until =
+            Some(Instant::now() + Duration::from_millis(self.fallback.link_retry_ms));
+    }
+
+    fn mark_link_up(&self) {
+        if self.link_down_until.lock().unwrap().take().is_some() {
+            tracing::info!(endpoint = %self.endpoint, "Federation link recovered");
+        }
+    }
+
+    /// 在超时限制内调用中心引擎，失败时标记链路不可用
+    async fn forward<'a, T, F, Fut>(&'a self, call: F) -> Option<T>
+    where
+        F: FnOnce(&'a GrpcHookAdapter) -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        if self.link_down() {
+            return None;
+        }
+        let result = tokio::time::timeout(self.timeout, async {
+            let central = self
+                .central
+                .get_or_try_init(|| {
+                    GrpcHookAdapter::new_from_endpoint(
+                        self.channel_pool.clone(),
+                        self.endpoint.clone(),
+                        self.tls.clone(),
+                        self.metadata.clone(),
+                    )
+                })
+                .await?;
+            call(central).await
+        })
+        .await
+        .unwrap_or_else(|_| {
+            Err(anyhow::anyhow!(
+                "Federated hook call timed out after {}ms",
+                self.timeout.as_millis()
+            ))
+        });
+
+        match result {
+            Ok(value) => {
+                self.mark_link_up();
+                Some(value)
+            }
+            Err(e) => {
+                self.mark_link_down(&e);
+                None
+            }
+        }
+    }
+
+    /// PreSend/Recall 的降级决策
+    fn fallback_decision(&self) -> PreSendDecision {
+        match &self.fallback.decision {
+            DefaultDecision::Allow => PreSendDecision::Continue,
+            DefaultDecision::Deny { reason } => PreSendDecision::Reject {
+                error: ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    reason
+                        .as_deref()
+                        .unwrap_or("Central hook engine unavailable"),
+                )
+                .build_error(),
+            },
+        }
+    }
+
+    /// PostSend/Delivery 的降级结果
+    fn fallback_notification(&self, hook: &str) -> Result<()> {
+        if self.fallback.fail_notifications {
+            Err(anyhow::anyhow!(
+                "Central hook engine {} unavailable for {} hook",
+                self.endpoint,
+                hook
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl super::HookAdapter for FederatedHookAdapter {
+    async fn pre_send(&self, ctx: &Context, draft: &mut MessageDraft) -> Result<PreSendDecision> {
+        let cache_key = self
+            .decision_cache
+            .as_ref()
+            .map(|_| HookResultCache::key(ctx.tenant_id().unwrap_or("0"), &draft.payload));
+        if let (Some(cache), Some(key)) = (&self.decision_cache, &cache_key) {
+            if let Some(decision) = cache.get(key, draft) {
+                return Ok(decision);
+            }
+        }
+
+        let original_payload = cache_key.as_ref().map(|_| draft.payload.clone());
+        let mut forwarded = draft.clone();
+        let Some((decision, forwarded)) = self
+            .forward(|central| async move {
+                let decision = central.pre_send(ctx, &mut forwarded).await?;
+                Ok((decision, forwarded))
+            })
+            .await
+        else {
+            return Ok(self.fallback_decision());
+        };
+        *draft = forwarded;
+
+        if let (Some(cache), Some(key), Some(original)) =
+            (&self.decision_cache, cache_key, original_payload)
+        {
+            cache.put(key, &decision, &original, draft);
+        }
+        Ok(decision)
+    }
+
+    async fn post_send(
+        &self,
+        ctx: &Context,
+        record: &MessageRecord,
+        draft: &MessageDraft,
+    ) -> Result<()> {
+        match self
+            .forward(|central| central.post_send(ctx, record, draft))
+            .await
+        {
+            Some(()) => Ok(()),
+            None => self.fallback_notification("post_send"),
+        }
+    }
+
+    async fn delivery(&self, ctx: &Context, event: &DeliveryEvent) -> Result<()> {
+        match self.forward(|central| central.delivery(ctx, event)).await {
+            Some(()) => Ok(()),
+            None => self.fallback_notification("delivery"),
+        }
+    }
+
+    async fn recall(&self, ctx: &Context, event: &RecallEvent) -> Result<PreSendDecision> {
+        Ok(self
+            .forward(|central| central.recall(ctx, event))
+            .await
+            .unwrap_or_else(|| self.fallback_decision()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::adapters::HookAdapter;
+
+    fn unreachable_adapter(fallback: FederationFallback) -> FederatedHookAdapter {
+        FederatedHookAdapter::new(
+            Arc::new(GrpcChannelPool::default()),
+            "http://127.0.0.1:1".to_string(),
+            None,
+            Some(500),
+            HashMap::new(),
+            None,
+            fallback,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_link_down_applies_fallback() {
+        let adapter = unreachable_adapter(FederationFallback {
+            decision: DefaultDecision::Deny { reason: None },
+            fail_notifications: true,
+            link_retry_ms: 60_000,
+        });
+        let ctx = Context::with_request_id("req-1".to_string());
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+
+        let decision = adapter.pre_send(&ctx, &mut draft).await.unwrap();
+        assert!(!decision.is_continue());
+        assert!(adapter.link_down());
+
+        // 链路不可用期间直接降级，不再转发
+        let record = MessageRecord {
+            message_id: "msg-1".to_string(),
+            client_message_id: None,
+            conversation_id: "conv-1".to_string(),
+            sender_id: "user-1".to_string(),
+            conversation_type: None,
+            message_type: None,
+            persisted_at: std::time::SystemTime::now(),
+            metadata: HashMap::new(),
+        };
+        assert!(adapter.post_send(&ctx, &record, &draft).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_default_fallback_allows() {
+        let adapter = unreachable_adapter(FederationFallback::default());
+        let ctx = Context::with_request_id("req-1".to_string());
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+
+        let decision = adapter.pre_send(&ctx, &mut draft).await.unwrap();
+        assert!(decision.is_continue());
+        assert_eq!(draft.payload, b"hello");
+    }
+}
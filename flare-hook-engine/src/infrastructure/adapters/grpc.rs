@@ -2,7 +2,7 @@
 //!
 //! 提供基于gRPC的Hook传输适配器实现。
 //! 支持两种模式：
-//! 1. 直接地址模式（外部系统/开发测试）
+//! 1. 直接地址模式（外部系统/开发测试），Channel 由 [`GrpcChannelPool`] 按 endpoint 共享，支持 mTLS
 //! 2. 服务发现模式（生产环境内部服务）
 
 use std::collections::HashMap;
@@ -11,8 +11,9 @@ use tokio::sync::Mutex;
 use tokio::time::Duration;
 
 use anyhow::{Context as AnyhowContext, Result};
+use prost::Message;
 use tonic::Request;
-use tonic::transport::{Channel, Endpoint};
+use tonic::transport::Channel;
 
 use flare_im_core::{
     DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent,
@@ -24,12 +25,14 @@ use flare_proto::hooks::{
 use flare_server_core::client::set_context_metadata;
 use flare_server_core::context::Context;
 
-use crate::domain::model::LoadBalanceStrategy;
+use crate::domain::model::{GrpcTlsConfig, LoadBalanceStrategy};
+use crate::infrastructure::usage::record_egress;
 use crate::infrastructure::adapters::conversion::{
     context_to_proto, delivery_event_to_proto, message_draft_to_proto,
     message_record_to_proto, proto_to_pre_send_decision, proto_to_recall_decision,
     recall_event_to_proto,
 };
+use crate::infrastructure::adapters::grpc_pool::GrpcChannelPool;
 
 // 导入服务发现相关模块
 use flare_server_core::{ServiceClient, ServiceDiscover};
@@ -38,6 +41,9 @@ use flare_server_core::{ServiceClient, ServiceDiscover};
 pub struct GrpcHookAdapter {
     // 模式1: 直接地址模式（固定客户端）
     client: Option<Arc<Mutex<HookExtensionClient<Channel>>>>,
+    endpoint: Option<String>,
+    tls: Option<GrpcTlsConfig>,
+    channel_pool: Option<Arc<GrpcChannelPool>>,
 
     // 模式2: 服务发现模式（动态选择实例）
     service_client: Option<Arc<Mutex<ServiceClient>>>,
@@ -54,22 +60,32 @@ pub struct GrpcHookAdapter {
 
 impl GrpcHookAdapter {
     /// 从直接地址创建gRPC Hook适配器（模式1: 直接地址模式）
+    ///
+    /// Channel 从池中获取，相同 endpoint 和TLS配置的Hook共享连接
     pub async fn new_from_endpoint(
+        channel_pool: Arc<GrpcChannelPool>,
         endpoint: String,
+        tls: Option<GrpcTlsConfig>,
         metadata: HashMap<String, String>,
     ) -> Result<Self> {
-        let channel = Endpoint::from_shared(endpoint.clone())?
-            .connect()
+        let channel = channel_pool
+            .channel(&endpoint, tls.as_ref())
             .await
-            .context("Failed to connect to gRPC endpoint")
-            .map_err(|e| anyhow::anyhow!("Failed to connect to gRPC endpoint: {}", e))?;
+            .context("Failed to connect to gRPC endpoint")?;
 
         let client = HookExtensionClient::new(channel);
 
-        tracing::info!(endpoint = %endpoint, "Created gRPC adapter from endpoint");
+        tracing::info!(
+            endpoint = %endpoint,
+            tls = tls.is_some(),
+            "Created gRPC adapter from endpoint"
+        );
 
         Ok(Self {
             client: Some(Arc::new(Mutex::new(client))),
+            endpoint: Some(endpoint),
+            tls,
+            channel_pool: Some(channel_pool),
             service_client: None,
             service_name: String::new(),
             load_balance_strategy: LoadBalanceStrategy::RoundRobin,
@@ -94,6 +110,9 @@ impl GrpcHookAdapter {
 
         Ok(Self {
             client: None,
+            endpoint: None,
+            tls: None,
+            channel_pool: None,
             service_client: Some(service_client),
             service_name,
             load_balance_strategy,
@@ -118,6 +137,9 @@ impl GrpcHookAdapter {
 
         Ok(Self {
             client: None,
+            endpoint: None,
+            tls: None,
+            channel_pool: None,
             service_client: None,
             service_name,
             load_balance_strategy,
@@ -157,6 +179,24 @@ impl GrpcHookAdapter {
         ))
     }
 
+    /// 健康探测
+    ///
+    /// 直接地址模式重新建立连接，服务发现模式获取一个可用实例的 Channel
+    pub async fn health_check(&self) -> Result<()> {
+        if let (Some(endpoint), Some(pool)) = (&self.endpoint, &self.channel_pool) {
+            pool.endpoint(endpoint, self.tls.as_ref())?
+                .connect_timeout(self.timeout)
+                .connect()
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to connect to gRPC endpoint {}: {}", endpoint, e)
+                })?;
+            return Ok(());
+        }
+
+        self.get_client(None).await.map(|_| ())
+    }
+
     /// 设置请求元数据（包括静态 metadata 和从 Context 提取的 Context）
     fn set_request_metadata<T>(
         &self,
@@ -199,6 +239,7 @@ impl GrpcHookAdapter {
         // 使用一致性哈希时，以 conversation_id 作为 key
         let key = ctx.session_id().and_then(|s| if s.is_empty() { None } else { Some(s) });
         let mut client = self.get_client(key).await?;
+        record_egress(request.get_ref().encoded_len());
 
         let response = client
             .invoke_pre_send(request)
@@ -229,6 +270,7 @@ impl GrpcHookAdapter {
         // 使用一致性哈希时，以 conversation_id 作为 key
         let key = ctx.session_id().and_then(|s| if s.is_empty() { None } else { Some(s) });
         let mut client = self.get_client(key).await?;
+        record_egress(request.get_ref().encoded_len());
 
         let response = client
             .invoke_post_send(request)
@@ -270,6 +312,7 @@ impl GrpcHookAdapter {
         // 使用一致性哈希时，以 user_id 作为 key
         let key = Some(event.user_id.as_str());
         let mut client = self.get_client(key).await?;
+        record_egress(request.get_ref().encoded_len());
 
         let response = client
             .notify_delivery(request)
@@ -311,6 +354,7 @@ impl GrpcHookAdapter {
         // 使用一致性哈希时，以 conversation_id 作为 key
         let key = ctx.session_id().and_then(|s| if s.is_empty() { None } else { Some(s) });
         let mut client = self.get_client(key).await?;
+        record_egress(request.get_ref().encoded_len());
 
         let response = client
             .notify_recall(request)
@@ -0,0 +1,309 @@
+//! # gRPC Channel池
+//!
+//! 直接地址模式的gRPC Hook按 endpoint + TLS配置 共享同一个 Channel（HTTP/2 多路复用），
+//! 配置刷新重建适配器时不会重复建连；所有 Channel 使用统一的连接超时和 keep-alive 设置。
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+
+use crate::domain::model::GrpcTlsConfig;
+
+/// gRPC Channel配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrpcChannelConfig {
+    /// 建连超时（毫秒）
+    pub connect_timeout_ms: u64,
+    /// HTTP/2 keep-alive PING 间隔（毫秒，0 表示关闭）
+    pub keep_alive_interval_ms: u64,
+    /// keep-alive PING 超时（毫秒）
+    pub keep_alive_timeout_ms: u64,
+    /// 空闲连接是否也发送 keep-alive PING
+    pub keep_alive_while_idle: bool,
+    /// TCP keepalive 间隔（毫秒，0 表示关闭）
+    pub tcp_keepalive_ms: u64,
+}
+
+impl Default for GrpcChannelConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_ms: 3000,
+            keep_alive_interval_ms: 30000,
+            keep_alive_timeout_ms: 10000,
+            keep_alive_while_idle: true,
+            tcp_keepalive_ms: 60000,
+        }
+    }
+}
+
+/// Channel池的键（同一 endpoint 使用不同TLS配置时分别建连）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ChannelKey {
+    endpoint: String,
+    tls: Option<GrpcTlsConfig>,
+}
+
+/// gRPC Channel池
+#[derive(Debug, Default)]
+pub struct GrpcChannelPool {
+    config: GrpcChannelConfig,
+    channels: Mutex<HashMap<ChannelKey, Channel>>,
+}
+
+impl GrpcChannelPool {
+    pub fn new(config: GrpcChannelConfig) -> Self {
+        Self {
+            config,
+            channels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 获取共享的 Channel，不存在时建连并缓存
+    pub async fn channel(&self, endpoint: &str, tls: Option<&GrpcTlsConfig>) -> Result<Channel> {
+        let key = ChannelKey {
+            endpoint: endpoint.to_string(),
+            tls: tls.cloned(),
+        };
+        let mut channels = self.channels.lock().await;
+        if let Some(channel) = channels.get(&key) {
+            return Ok(channel.clone());
+        }
+
+        let channel = self
+            .endpoint(endpoint, tls)?
+            .connect()
+            .await
+            .with_context(|| format!("Failed to connect to gRPC endpoint {}", endpoint))?;
+        tracing::info!(
+            endpoint = %endpoint,
+            tls = tls.is_some(),
+            "Created pooled gRPC channel"
+        );
+        channels.insert(key, channel.clone());
+        Ok(channel)
+    }
+
+    /// 按池配置构建 Endpoint（健康探测使用它单独建连）
+    pub fn endpoint(&self, endpoint: &str, tls: Option<&GrpcTlsConfig>) -> Result<Endpoint> {
+        let config = &self.config;
+        let mut builder = Endpoint::from_shared(endpoint.to_string())
+            .with_context(|| format!("Invalid gRPC endpoint {}", endpoint))?
+            .connect_timeout(Duration::from_millis(config.connect_timeout_ms))
+            .keep_alive_while_idle(config.keep_alive_while_idle);
+        if config.keep_alive_interval_ms > 0 {
+            builder = builder
+                .http2_keep_alive_interval(Duration::from_millis(config.keep_alive_interval_ms))
+                .keep_alive_timeout(Duration::from_millis(config.keep_alive_timeout_ms));
+        }
+        if config.tcp_keepalive_ms > 0 {
+            builder = builder.tcp_keepalive(Some(Duration::from_millis(config.tcp_keepalive_ms)));
+        }
+        if let Some(tls) = tls {
+            builder = builder
+                .tls_config(client_tls_config(tls)?)
+                .with_context(|| format!("Invalid TLS config for gRPC endpoint {}", endpoint))?;
+        }
+        Ok(builder)
+    }
+}
+
+/// 读取证书文件构建客户端TLS配置
+fn client_tls_config(tls: &GrpcTlsConfig) -> Result<ClientTlsConfig> {
+    let mut config = ClientTlsConfig::new();
+    config = match &tls.ca_cert_path {
+        Some(path) => {
+            let pem = std::fs::read(path)
+                .with_context(|| format!("Failed to read gRPC CA certificate {}", path))?;
+            config.ca_certificate(Certificate::from_pem(pem))
+        }
+        None => config.with_webpki_roots(),
+    };
+
+    match (&tls.client_cert_path, &tls.client_key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            let cert = std::fs::read(cert_path)
+                .with_context(|| format!("Failed to read gRPC client certificate {}", cert_path))?;
+            let key = std::fs::read(key_path)
+                .with_context(|| format!("Failed to read gRPC client key {}", key_path))?;
+            config = config.identity(Identity::from_pem(cert, key));
+        }
+        (None, None) => {}
+        _ => {
+            return Err(anyhow::anyhow!(
+                "client_cert_path and client_key_path must be configured together for mTLS"
+            ));
+        }
+    }
+
+    if let Some(domain_name) = &tls.domain_name {
+        config = config.domain_name(domain_name.clone());
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tokio::net::TcpListener;
+
+    /// 将仓库自带的 DER 测试证书/私钥转换为 PEM 写入临时目录，返回文件路径
+    fn pem_fixture(file: &str, label: &str) -> String {
+        use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+        let der =
+            std::fs::read(format!("{}/../certs/{}", env!("CARGO_MANIFEST_DIR"), file)).unwrap();
+        let encoded = BASE64.encode(der);
+        let body: Vec<&str> = encoded
+            .as_bytes()
+            .chunks(64)
+            .map(|line| std::str::from_utf8(line).unwrap())
+            .collect();
+        let pem = format!(
+            "-----BEGIN {label}-----\n{}\n-----END {label}-----\n",
+            body.join("\n")
+        );
+        // 测试并行执行，每次写入独立文件
+        let path = std::env::temp_dir().join(format!(
+            "flare-hook-grpc-pool-{}-{}.pem",
+            uuid::Uuid::new_v4(),
+            file
+        ));
+        std::fs::write(&path, pem).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn ca_cert() -> String {
+        pem_fixture("server.crt", "CERTIFICATE")
+    }
+
+    /// 只接受TCP连接并保持不关闭的服务端，返回地址和已接受的连接数
+    async fn accepting_server() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            let mut sockets = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                sockets.push(socket);
+            }
+        });
+        (format!("http://{}", addr), accepted)
+    }
+
+    fn tls(ca_cert_path: &str) -> GrpcTlsConfig {
+        GrpcTlsConfig {
+            ca_cert_path: Some(ca_cert_path.to_string()),
+            domain_name: Some("localhost".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_channel_is_shared_per_endpoint() {
+        let (endpoint, accepted) = accepting_server().await;
+        let pool = GrpcChannelPool::default();
+
+        pool.channel(&endpoint, None).await.unwrap();
+        pool.channel(&endpoint, None).await.unwrap();
+
+        assert_eq!(pool.channels.lock().await.len(), 1);
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_connect_is_not_cached() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let pool = GrpcChannelPool::new(GrpcChannelConfig {
+            connect_timeout_ms: 500,
+            ..Default::default()
+        });
+
+        let error = pool.channel(&endpoint, None).await.unwrap_err();
+
+        assert!(error.to_string().contains(&endpoint));
+        assert!(pool.channels.lock().await.is_empty());
+    }
+
+    #[test]
+    fn test_endpoint_rejects_invalid_uri() {
+        let pool = GrpcChannelPool::default();
+        assert!(pool.endpoint("not a uri", None).is_err());
+    }
+
+    #[test]
+    fn test_endpoint_applies_tls_config() {
+        let pool = GrpcChannelPool::default();
+        assert!(
+            pool.endpoint("https://localhost:50051", Some(&tls(&ca_cert())))
+                .is_ok()
+        );
+
+        let error = pool
+            .endpoint("https://localhost:50051", Some(&tls("/nonexistent/ca.pem")))
+            .unwrap_err();
+        assert!(format!("{:#}", error).contains("/nonexistent/ca.pem"));
+    }
+
+    #[test]
+    fn test_client_tls_config_requires_cert_and_key_together() {
+        let cert_only = GrpcTlsConfig {
+            client_cert_path: Some(ca_cert()),
+            ..tls(&ca_cert())
+        };
+        let error = client_tls_config(&cert_only).unwrap_err();
+        assert!(error.to_string().contains("configured together"));
+
+        let key_only = GrpcTlsConfig {
+            client_key_path: Some(pem_fixture("server.key", "EC PRIVATE KEY")),
+            ..tls(&ca_cert())
+        };
+        assert!(client_tls_config(&key_only).is_err());
+    }
+
+    #[test]
+    fn test_client_tls_config_reads_mtls_identity() {
+        let mtls = GrpcTlsConfig {
+            client_cert_path: Some(ca_cert()),
+            client_key_path: Some(pem_fixture("server.key", "EC PRIVATE KEY")),
+            ..tls(&ca_cert())
+        };
+        assert!(client_tls_config(&mtls).is_ok());
+        assert!(
+            GrpcChannelPool::default()
+                .endpoint("https://localhost:50051", Some(&mtls))
+                .is_ok()
+        );
+
+        let missing_key = GrpcTlsConfig {
+            client_key_path: Some("/nonexistent/client.key".to_string()),
+            ..mtls
+        };
+        let error = client_tls_config(&missing_key).unwrap_err();
+        assert!(error.to_string().contains("/nonexistent/client.key"));
+    }
+
+    #[test]
+    fn test_channel_config_defaults_missing_fields() {
+        let config: GrpcChannelConfig =
+            serde_json::from_str(r#"{"connect_timeout_ms": 500, "keep_alive_interval_ms": 0}"#)
+                .unwrap();
+
+        assert_eq!(config.connect_timeout_ms, 500);
+        assert_eq!(config.keep_alive_interval_ms, 0);
+        assert_eq!(config.keep_alive_timeout_ms, 10000);
+        assert!(config.keep_alive_while_idle);
+        assert_eq!(config.tcp_keepalive_ms, 60000);
+    }
+}
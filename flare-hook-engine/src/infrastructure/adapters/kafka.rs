@@ -0,0 +1,144 @@
+//! # Kafka适配器
+//!
+//! 异步Hook（PostSend/Delivery）的Kafka传输：将Hook事件序列化为JSON发布到配置的Topic，
+//! 不等待下游处理结果。Kafka集群复用全局 `kafka` 配置档。
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context as _, Result, anyhow};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde_json::{Value, json};
+
+use flare_im_core::hooks::hook_context_data::get_hook_context_data;
+use flare_im_core::{DeliveryEvent, KafkaClusterConfig, MessageDraft, MessageRecord};
+use flare_server_core::context::Context;
+
+/// 未在配置档中指定超时时的消息发送超时
+const DEFAULT_MESSAGE_TIMEOUT_MS: u64 = 5000;
+
+/// Kafka适配器
+pub struct KafkaHookAdapter {
+    producer: FutureProducer,
+    topic: String,
+    metadata: HashMap<String, String>,
+}
+
+impl KafkaHookAdapter {
+    /// 根据Kafka配置档创建适配器
+    pub fn new(
+        profile: &KafkaClusterConfig,
+        topic: String,
+        metadata: HashMap<String, String>,
+    ) -> Result<Self> {
+        struct SimpleProducerConfig {
+            bootstrap: String,
+            timeout_ms: u64,
+        }
+
+        impl flare_server_core::kafka::KafkaProducerConfig for SimpleProducerConfig {
+            fn kafka_bootstrap(&self) -> &str {
+                &self.bootstrap
+            }
+
+            fn message_timeout_ms(&self) -> u64 {
+                self.timeout_ms
+            }
+        }
+
+        let producer_config = SimpleProducerConfig {
+            bootstrap: profile.bootstrap_servers.clone(),
+            timeout_ms: profile.timeout_ms.unwrap_or(DEFAULT_MESSAGE_TIMEOUT_MS),
+        };
+        let producer = flare_server_core::kafka::build_kafka_producer(
+            &producer_config as &dyn flare_server_core::kafka::KafkaProducerConfig,
+        )
+        .map_err(|e| anyhow!("Failed to create Kafka producer: {}", e))?;
+
+        Ok(Self {
+            producer,
+            topic,
+            metadata,
+        })
+    }
+
+    /// 发布PostSend事件
+    pub async fn post_send(
+        &self,
+        ctx: &Context,
+        record: &MessageRecord,
+        draft: &MessageDraft,
+    ) -> Result<()> {
+        let event = hook_event(
+            ctx,
+            "post_send",
+            &self.metadata,
+            json!({ "record": record, "draft": draft }),
+        );
+        self.publish(&record.message_id, &event).await
+    }
+
+    /// 发布Delivery事件
+    pub async fn delivery(&self, ctx: &Context, event: &DeliveryEvent) -> Result<()> {
+        let hook_event = hook_event(ctx, "delivery", &self.metadata, json!({ "event": event }));
+        self.publish(&event.message_id, &hook_event).await
+    }
+
+    async fn publish(&self, message_id: &str, event: &Value) -> Result<()> {
+        let payload = serde_json::to_vec(event).context("Failed to serialize hook event")?;
+        let record = FutureRecord::to(&self.topic).key(message_id).payload(&payload);
+        self.producer
+            .send(record, Duration::from_secs(0))
+            .await
+            .map_err(|(e, _)| anyhow!("Failed to publish hook event to {}: {}", self.topic, e))?;
+        Ok(())
+    }
+}
+
+/// 构建Hook事件消息（JSON），按消息ID作为Kafka key保证同一消息的事件有序
+pub fn hook_event(
+    ctx: &Context,
+    hook_type: &str,
+    metadata: &HashMap<String, String>,
+    input: Value,
+) -> Value {
+    let hook_data = get_hook_context_data(ctx).cloned().unwrap_or_default();
+    json!({
+        "hook_type": hook_type,
+        "context": {
+            "request_id": ctx.request_id(),
+            "trace_id": ctx.trace_id(),
+            "tenant_id": ctx.tenant_id(),
+            "conversation_id": hook_data.conversation_id,
+            "conversation_type": hook_data.conversation_type,
+        },
+        "metadata": metadata,
+        "input": input,
+        "published_at": SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_event_keeps_input_and_metadata() {
+        let ctx = Context::with_request_id("req-1".to_string());
+        let metadata = HashMap::from([("source".to_string(), "im".to_string())]);
+        let event = hook_event(
+            &ctx,
+            "delivery",
+            &metadata,
+            json!({ "event": { "message_id": "msg-1" } }),
+        );
+
+        assert_eq!(event["hook_type"], "delivery");
+        assert_eq!(event["context"]["request_id"], "req-1");
+        assert_eq!(event["metadata"]["source"], "im");
+        assert_eq!(event["input"]["event"]["message_id"], "msg-1");
+    }
+}
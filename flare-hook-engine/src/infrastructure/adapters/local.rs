@@ -1,6 +1,8 @@
 //! # Local Plugin适配器
 //!
 //! 提供基于本地插件的Hook传输适配器实现。
+//!
+//! `target` 优先匹配进程内注册的 Hook，未注册时回退到插件目录中同名的动态库插件。
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -13,8 +15,12 @@ use flare_im_core::{
 };
 use flare_server_core::context::Context;
 
+use crate::infrastructure::adapters::plugin::LoadedPlugin;
+
 /// Local Plugin适配器
 pub struct LocalHookAdapter {
+    target: String,
+    plugin: Option<Arc<LoadedPlugin>>,
     pre_send_hooks: HashMap<String, Arc<dyn PreSendHook>>,
     post_send_hooks: HashMap<String, Arc<dyn PostSendHook>>,
     delivery_hooks: HashMap<String, Arc<dyn DeliveryHook>>,
@@ -23,8 +29,10 @@ pub struct LocalHookAdapter {
 
 impl LocalHookAdapter {
     /// 创建Local Plugin适配器
-    pub fn new(_target: String) -> anyhow::Result<Self> {
+    pub fn new(target: String) -> anyhow::Result<Self> {
         Ok(Self {
+            target,
+            plugin: None,
             pre_send_hooks: HashMap::new(),
             post_send_hooks: HashMap::new(),
             delivery_hooks: HashMap::new(),
@@ -32,6 +40,17 @@ impl LocalHookAdapter {
         })
     }
 
+    /// 设置动态库插件（进程内未注册 target 时使用）
+    pub fn with_plugin(mut self, plugin: Arc<LoadedPlugin>) -> Self {
+        self.plugin = Some(plugin);
+        self
+    }
+
+    /// 适配器绑定的 target
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
     /// 注册PreSend Hook
     pub fn register_pre_send(&mut self, name: String, hook: Arc<dyn PreSendHook>) {
         self.pre_send_hooks.insert(name, hook);
@@ -59,10 +78,12 @@ impl LocalHookAdapter {
         ctx: &Context,
         draft: &mut MessageDraft,
     ) -> Result<PreSendDecision> {
-        let hook = self
-            .pre_send_hooks
-            .get(target)
-            .ok_or_else(|| anyhow::anyhow!("Local PreSend hook not found: {}", target))?;
+        let Some(hook) = self.pre_send_hooks.get(target) else {
+            return match &self.plugin {
+                Some(plugin) => plugin.pre_send(ctx, draft).await,
+                None => Err(anyhow::anyhow!("Local PreSend hook not found: {}", target)),
+            };
+        };
 
         Ok(hook.handle(ctx, draft).await)
     }
@@ -75,10 +96,12 @@ impl LocalHookAdapter {
         record: &MessageRecord,
         draft: &MessageDraft,
     ) -> Result<()> {
-        let hook = self
-            .post_send_hooks
-            .get(target)
-            .ok_or_else(|| anyhow::anyhow!("Local PostSend hook not found: {}", target))?;
+        let Some(hook) = self.post_send_hooks.get(target) else {
+            return match &self.plugin {
+                Some(plugin) => plugin.post_send(ctx, record, draft).await,
+                None => Err(anyhow::anyhow!("Local PostSend hook not found: {}", target)),
+            };
+        };
 
         let outcome = hook.handle(ctx, record, draft).await;
         if outcome.is_completed() {
@@ -95,10 +118,12 @@ impl LocalHookAdapter {
         ctx: &Context,
         event: &DeliveryEvent,
     ) -> Result<()> {
-        let hook = self
-            .delivery_hooks
-            .get(target)
-            .ok_or_else(|| anyhow::anyhow!("Local Delivery hook not found: {}", target))?;
+        let Some(hook) = self.delivery_hooks.get(target) else {
+            return match &self.plugin {
+                Some(plugin) => plugin.delivery(ctx, event).await,
+                None => Err(anyhow::anyhow!("Local Delivery hook not found: {}", target)),
+            };
+        };
 
         let outcome = hook.handle(ctx, event).await;
         if outcome.is_completed() {
@@ -115,10 +140,12 @@ impl LocalHookAdapter {
         ctx: &Context,
         event: &RecallEvent,
     ) -> Result<PreSendDecision> {
-        let hook = self
-            .recall_hooks
-            .get(target)
-            .ok_or_else(|| anyhow::anyhow!("Local Recall hook not found: {}", target))?;
+        let Some(hook) = self.recall_hooks.get(target) else {
+            return match &self.plugin {
+                Some(plugin) => plugin.recall(ctx, event).await,
+                None => Err(anyhow::anyhow!("Local Recall hook not found: {}", target)),
+            };
+        };
 
         let outcome = hook.handle(ctx, event).await;
         if outcome.is_completed() {
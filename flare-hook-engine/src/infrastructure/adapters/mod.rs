@@ -2,20 +2,39 @@
 //!
 //! 提供Hook适配器的创建和管理
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use anyhow::{Context, Result};
+use flare_im_core::KafkaClusterConfig;
 
 use crate::domain::model::{HookTransportConfig, LoadBalanceStrategy};
+use crate::infrastructure::adapters::federation::FederatedHookAdapter;
 use crate::infrastructure::adapters::grpc::GrpcHookAdapter;
+use crate::infrastructure::adapters::grpc_pool::{GrpcChannelConfig, GrpcChannelPool};
+use crate::infrastructure::adapters::kafka::KafkaHookAdapter;
 use crate::infrastructure::adapters::local::LocalHookAdapter;
+use crate::infrastructure::adapters::nats::NatsHookAdapter;
+use crate::infrastructure::adapters::plugin::PluginRegistry;
+use crate::infrastructure::adapters::rate_limit::RateLimitHookAdapter;
+use crate::infrastructure::adapters::script::ScriptHookAdapter;
 use crate::infrastructure::adapters::webhook::WebhookHookAdapter;
 
+pub mod canary;
 pub mod conversion;
+pub mod default_policy;
+pub mod federation;
 pub mod grpc;
+pub mod grpc_pool;
 pub mod hook_context_data;
+pub mod kafka;
 pub mod local;
+pub mod nats;
+pub mod plugin;
+pub mod rate_limit;
+pub mod sampled;
+pub mod script;
 pub mod webhook;
 
 /// Hook适配器工厂
@@ -23,15 +42,57 @@ pub struct HookAdapterFactory {
     /// 服务注册发现（可选，用于服务发现模式）
     /// 使用新的统一服务发现接口
     service_client: Option<Arc<Mutex<flare_server_core::ServiceClient>>>,
+    /// Kafka集群配置档（Kafka传输按名称引用）
+    kafka_profiles: HashMap<String, KafkaClusterConfig>,
+    /// NATS连接（按服务器地址复用，配置刷新时不重复建连）
+    nats_clients: Mutex<HashMap<String, async_nats::Client>>,
+    /// 动态库插件（Local 传输按 target 引用）
+    plugins: Arc<PluginRegistry>,
+    /// gRPC Channel池（直接地址模式按 endpoint 复用，配置刷新时不重复建连）
+    grpc_channels: Arc<GrpcChannelPool>,
+    /// Redis客户端（内置限流Hook的计数存储）
+    redis_client: Option<redis::Client>,
+    /// Redis连接（首次创建限流Hook时建立，之后复用）
+    redis_connection: Mutex<Option<redis::aio::ConnectionManager>>,
 }
 
 impl HookAdapterFactory {
     pub fn new() -> Self {
         Self {
             service_client: None,
+            kafka_profiles: HashMap::new(),
+            nats_clients: Mutex::new(HashMap::new()),
+            plugins: Arc::new(PluginRegistry::default()),
+            grpc_channels: Arc::new(GrpcChannelPool::default()),
+            redis_client: None,
+            redis_connection: Mutex::new(None),
         }
     }
 
+    /// 设置Redis客户端（内置限流Hook使用）
+    pub fn with_redis_client(mut self, client: redis::Client) -> Self {
+        self.redis_client = Some(client);
+        self
+    }
+
+    /// 设置gRPC Channel配置（连接超时、keep-alive）
+    pub fn with_grpc_channel_config(mut self, config: GrpcChannelConfig) -> Self {
+        self.grpc_channels = Arc::new(GrpcChannelPool::new(config));
+        self
+    }
+
+    /// 设置动态库插件注册表
+    pub fn with_plugins(mut self, plugins: Arc<PluginRegistry>) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    /// 设置Kafka集群配置档
+    pub fn with_kafka_profiles(mut self, profiles: HashMap<String, KafkaClusterConfig>) -> Self {
+        self.kafka_profiles = profiles;
+        self
+    }
+
     /// 设置服务注册发现
     pub fn with_service_client(
         mut self,
@@ -56,10 +117,17 @@ impl HookAdapterFactory {
                 namespace: _,
                 load_balance,
                 metadata,
+                tls,
             } => {
                 // 优先级1: 服务发现模式（推荐，生产环境）
                 if let Some(service_name) = service_name {
                     if let Some(service_client) = &self.service_client {
+                        if tls.is_some() {
+                            tracing::warn!(
+                                service_name = %service_name,
+                                "TLS config is only applied in endpoint mode, ignored for service discovery"
+                            );
+                        }
                         let strategy = load_balance.unwrap_or(LoadBalanceStrategy::RoundRobin);
                         let adapter = GrpcHookAdapter::new_from_service_client(
                             service_client.clone(),
@@ -81,10 +149,14 @@ impl HookAdapterFactory {
 
                 // 优先级2: 直接地址模式（fallback/外部系统/开发测试）
                 if let Some(endpoint) = endpoint {
-                    let adapter =
-                        GrpcHookAdapter::new_from_endpoint(endpoint.clone(), metadata.clone())
-                            .await
-                            .context("Failed to create gRPC adapter from endpoint")?;
+                    let adapter = GrpcHookAdapter::new_from_endpoint(
+                        self.grpc_channels.clone(),
+                        endpoint.clone(),
+                        tls.clone(),
+                        metadata.clone(),
+                    )
+                    .await
+                    .context("Failed to create gRPC adapter from endpoint")?;
                     return Ok(Arc::new(adapter));
                 }
 
@@ -95,24 +167,132 @@ impl HookAdapterFactory {
             HookTransportConfig::Webhook {
                 endpoint,
                 secret,
+                previous_secret,
                 headers,
+                timeout_ms,
+                max_retries,
             } => {
                 // WebHook 必须使用直接地址
-                let adapter =
-                    WebhookHookAdapter::new(endpoint.clone(), secret.clone(), headers.clone())
-                        .await
-                        .context("Failed to create WebHook adapter")?;
+                let adapter = WebhookHookAdapter::new(
+                    endpoint.clone(),
+                    secret.clone(),
+                    previous_secret.clone(),
+                    headers.clone(),
+                    *timeout_ms,
+                    *max_retries,
+                )
+                .await
+                .context("Failed to create WebHook adapter")?;
+                Ok(Arc::new(adapter))
+            }
+            HookTransportConfig::Local {
+                target,
+                rate_limit: Some(rate_limit),
+                ..
+            } => {
+                let redis = self.redis_connection().await?;
+                let adapter = RateLimitHookAdapter::new(target.clone(), rate_limit.clone(), redis);
                 Ok(Arc::new(adapter))
             }
-            HookTransportConfig::Local { target } => {
-                let adapter = LocalHookAdapter::new(target.clone())
+            HookTransportConfig::Local {
+                target,
+                script: Some(script),
+                ..
+            } => {
+                let adapter = ScriptHookAdapter::new(target.clone(), script)
+                    .context("Failed to create script adapter")?;
+                Ok(Arc::new(adapter))
+            }
+            HookTransportConfig::Local {
+                target,
+                script: None,
+                rate_limit: None,
+            } => {
+                let mut adapter = LocalHookAdapter::new(target.clone())
                     .context("Failed to create Local Plugin adapter")?;
+                if let Some(plugin) = self.plugins.get(target) {
+                    adapter = adapter.with_plugin(plugin);
+                }
+                Ok(Arc::new(adapter))
+            }
+            HookTransportConfig::Nats {
+                url,
+                subject,
+                timeout_ms,
+                metadata,
+            } => {
+                let client = self.nats_client(url).await?;
+                let adapter = NatsHookAdapter::new(client, subject.clone(), *timeout_ms, metadata);
+                Ok(Arc::new(adapter))
+            }
+            HookTransportConfig::Kafka {
+                profile,
+                topic,
+                metadata,
+            } => {
+                let cluster = self.kafka_profiles.get(profile).ok_or_else(|| {
+                    anyhow::anyhow!("Kafka config '{}' not found for hook transport", profile)
+                })?;
+                let adapter = KafkaHookAdapter::new(cluster, topic.clone(), metadata.clone())
+                    .context("Failed to create Kafka adapter")?;
+                Ok(Arc::new(adapter))
+            }
+            HookTransportConfig::Federated {
+                endpoint,
+                tls,
+                timeout_ms,
+                metadata,
+                decision_cache,
+                fallback,
+            } => {
+                // 中心引擎在首次转发时建连，边缘节点启动时链路不可用也能加载Hook
+                let adapter = FederatedHookAdapter::new(
+                    self.grpc_channels.clone(),
+                    endpoint.clone(),
+                    tls.clone(),
+                    *timeout_ms,
+                    metadata.clone(),
+                    decision_cache.as_ref(),
+                    fallback.clone(),
+                );
                 Ok(Arc::new(adapter))
             }
         }
     }
 }
 
+impl HookAdapterFactory {
+    /// 获取（或建立）内置限流Hook使用的Redis连接
+    async fn redis_connection(&self) -> Result<redis::aio::ConnectionManager> {
+        let client = self.redis_client.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("Rate limit hook requires Redis (set HOOK_ENGINE_REDIS_URL)")
+        })?;
+        let mut connection = self.redis_connection.lock().await;
+        if let Some(connection) = connection.as_ref() {
+            return Ok(connection.clone());
+        }
+        let manager = redis::aio::ConnectionManager::new(client.clone())
+            .await
+            .context("Failed to connect to Redis for rate limit hook")?;
+        *connection = Some(manager.clone());
+        Ok(manager)
+    }
+
+    /// 获取（或建立）到指定地址的NATS连接
+    async fn nats_client(&self, url: &str) -> Result<async_nats::Client> {
+        let mut clients = self.nats_clients.lock().await;
+        if let Some(client) = clients.get(url) {
+            return Ok(client.clone());
+        }
+        let client = async_nats::connect(url)
+            .await
+            .with_context(|| format!("Failed to connect to NATS server {}", url))?;
+        tracing::info!(url = %url, "Connected to NATS server for hook transport");
+        clients.insert(url.to_string(), client.clone());
+        Ok(client)
+    }
+}
+
 /// Hook适配器接口
 #[async_trait::async_trait]
 pub trait HookAdapter: Send + Sync {
@@ -144,6 +324,11 @@ pub trait HookAdapter: Send + Sync {
         ctx: &flare_server_core::context::Context,
         event: &flare_im_core::RecallEvent,
     ) -> Result<flare_im_core::PreSendDecision>;
+
+    /// 健康探测（默认视为健康，仅远程端点需要实现）
+    async fn health_check(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -179,33 +364,148 @@ impl HookAdapter for GrpcHookAdapter {
     ) -> Result<flare_im_core::PreSendDecision> {
         GrpcHookAdapter::recall(self, ctx, event).await
     }
+
+    async fn health_check(&self) -> Result<()> {
+        GrpcHookAdapter::health_check(self).await
+    }
 }
 
 #[async_trait::async_trait]
 impl HookAdapter for WebhookHookAdapter {
+    async fn pre_send(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        draft: &mut flare_im_core::MessageDraft,
+    ) -> Result<flare_im_core::PreSendDecision> {
+        WebhookHookAdapter::pre_send(self, ctx, draft).await
+    }
+
+    async fn post_send(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        record: &flare_im_core::MessageRecord,
+        draft: &flare_im_core::MessageDraft,
+    ) -> Result<()> {
+        WebhookHookAdapter::post_send(self, ctx, record, draft).await
+    }
+
+    async fn delivery(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        event: &flare_im_core::DeliveryEvent,
+    ) -> Result<()> {
+        WebhookHookAdapter::delivery(self, ctx, event).await
+    }
+
+    async fn recall(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        event: &flare_im_core::RecallEvent,
+    ) -> Result<flare_im_core::PreSendDecision> {
+        WebhookHookAdapter::recall(self, ctx, event).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        WebhookHookAdapter::health_check(self).await
+    }
+}
+#[async_trait::async_trait]
+impl HookAdapter for LocalHookAdapter {
+    async fn pre_send(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        draft: &mut flare_im_core::MessageDraft,
+    ) -> Result<flare_im_core::PreSendDecision> {
+        LocalHookAdapter::pre_send(self, self.target(), ctx, draft).await
+    }
+
+    async fn post_send(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        record: &flare_im_core::MessageRecord,
+        draft: &flare_im_core::MessageDraft,
+    ) -> Result<()> {
+        LocalHookAdapter::post_send(self, self.target(), ctx, record, draft).await
+    }
+
+    async fn delivery(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        event: &flare_im_core::DeliveryEvent,
+    ) -> Result<()> {
+        LocalHookAdapter::delivery(self, self.target(), ctx, event).await
+    }
+
+    async fn recall(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        event: &flare_im_core::RecallEvent,
+    ) -> Result<flare_im_core::PreSendDecision> {
+        LocalHookAdapter::recall(self, self.target(), ctx, event).await
+    }
+}
+
+#[async_trait::async_trait]
+impl HookAdapter for NatsHookAdapter {
+    async fn pre_send(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        draft: &mut flare_im_core::MessageDraft,
+    ) -> Result<flare_im_core::PreSendDecision> {
+        NatsHookAdapter::pre_send(self, ctx, draft).await
+    }
+
+    async fn post_send(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        record: &flare_im_core::MessageRecord,
+        draft: &flare_im_core::MessageDraft,
+    ) -> Result<()> {
+        NatsHookAdapter::post_send(self, ctx, record, draft).await
+    }
+
+    async fn delivery(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        event: &flare_im_core::DeliveryEvent,
+    ) -> Result<()> {
+        NatsHookAdapter::delivery(self, ctx, event).await
+    }
+
+    async fn recall(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        event: &flare_im_core::RecallEvent,
+    ) -> Result<flare_im_core::PreSendDecision> {
+        NatsHookAdapter::recall(self, ctx, event).await
+    }
+}
+
+#[async_trait::async_trait]
+impl HookAdapter for KafkaHookAdapter {
     async fn pre_send(
         &self,
         _ctx: &flare_server_core::context::Context,
         _draft: &mut flare_im_core::MessageDraft,
     ) -> Result<flare_im_core::PreSendDecision> {
-        Ok(flare_im_core::PreSendDecision::Continue)
+        Err(anyhow::anyhow!("Kafka transport does not support pre_send hooks"))
     }
 
     async fn post_send(
         &self,
-        _ctx: &flare_server_core::context::Context,
-        _record: &flare_im_core::MessageRecord,
-        _draft: &flare_im_core::MessageDraft,
+        ctx: &flare_server_core::context::Context,
+        record: &flare_im_core::MessageRecord,
+        draft: &flare_im_core::MessageDraft,
     ) -> Result<()> {
-        Ok(())
+        KafkaHookAdapter::post_send(self, ctx, record, draft).await
     }
 
     async fn delivery(
         &self,
-        _ctx: &flare_server_core::context::Context,
-        _event: &flare_im_core::DeliveryEvent,
+        ctx: &flare_server_core::context::Context,
+        event: &flare_im_core::DeliveryEvent,
     ) -> Result<()> {
-        Ok(())
+        KafkaHookAdapter::delivery(self, ctx, event).await
     }
 
     async fn recall(
@@ -213,17 +513,18 @@ impl HookAdapter for WebhookHookAdapter {
         _ctx: &flare_server_core::context::Context,
         _event: &flare_im_core::RecallEvent,
     ) -> Result<flare_im_core::PreSendDecision> {
-        Ok(flare_im_core::PreSendDecision::Continue)
+        Err(anyhow::anyhow!("Kafka transport does not support recall hooks"))
     }
 }
+
 #[async_trait::async_trait]
-impl HookAdapter for LocalHookAdapter {
+impl HookAdapter for ScriptHookAdapter {
     async fn pre_send(
         &self,
         ctx: &flare_server_core::context::Context,
         draft: &mut flare_im_core::MessageDraft,
     ) -> Result<flare_im_core::PreSendDecision> {
-        LocalHookAdapter::pre_send(self, "", ctx, draft).await
+        ScriptHookAdapter::pre_send(self, ctx, draft).await
     }
 
     async fn post_send(
@@ -232,7 +533,7 @@ impl HookAdapter for LocalHookAdapter {
         record: &flare_im_core::MessageRecord,
         draft: &flare_im_core::MessageDraft,
     ) -> Result<()> {
-        LocalHookAdapter::post_send(self, "", ctx, record, draft).await
+        ScriptHookAdapter::post_send(self, ctx, record, draft).await
     }
 
     async fn delivery(
@@ -240,7 +541,7 @@ impl HookAdapter for LocalHookAdapter {
         ctx: &flare_server_core::context::Context,
         event: &flare_im_core::DeliveryEvent,
     ) -> Result<()> {
-        LocalHookAdapter::delivery(self, "", ctx, event).await
+        ScriptHookAdapter::delivery(self, ctx, event).await
     }
 
     async fn recall(
@@ -248,6 +549,6 @@ impl HookAdapter for LocalHookAdapter {
         ctx: &flare_server_core::context::Context,
         event: &flare_im_core::RecallEvent,
     ) -> Result<flare_im_core::PreSendDecision> {
-        LocalHookAdapter::recall(self, "", ctx, event).await
+        ScriptHookAdapter::recall(self, ctx, event).await
     }
 }
@@ -0,0 +1,163 @@
+//! # NATS Hook适配器
+//!
+//! 基于NATS request/reply的Hook传输适配器实现。
+//! 请求与响应沿用 HookExtension 的 protobuf 消息（如 `PreSendHookRequest` / `PreSendHookResponse`），
+//! 静态 metadata 作为 NATS 消息头发送，请求上下文随请求体中的 `context` 传递。
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::{Context as AnyhowContext, Result, anyhow};
+use async_nats::HeaderMap;
+use prost::Message as ProstMessage;
+
+use flare_im_core::{DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent};
+use flare_proto::hooks::{
+    DeliveryHookRequest, DeliveryHookResponse, PostSendHookRequest, PostSendHookResponse,
+    PreSendHookRequest, PreSendHookResponse, RecallHookRequest, RecallHookResponse,
+};
+use flare_server_core::context::Context;
+
+use crate::infrastructure::adapters::conversion::{
+    context_to_proto, delivery_event_to_proto, message_draft_to_proto, message_record_to_proto,
+    proto_to_pre_send_decision, proto_to_recall_decision, recall_event_to_proto,
+};
+
+/// 未配置超时时的默认请求超时
+pub const DEFAULT_NATS_TIMEOUT_MS: u64 = 3_000;
+
+/// NATS Hook适配器
+pub struct NatsHookAdapter {
+    client: async_nats::Client,
+    subject: String,
+    timeout: Duration,
+    headers: HeaderMap,
+}
+
+impl NatsHookAdapter {
+    /// 使用已建立的NATS连接创建适配器（同一地址的Hook共享连接）
+    pub fn new(
+        client: async_nats::Client,
+        subject: String,
+        timeout_ms: Option<u64>,
+        metadata: &HashMap<String, String>,
+    ) -> Self {
+        let mut headers = HeaderMap::new();
+        for (key, value) in metadata {
+            headers.insert(key.as_str(), value.as_str());
+        }
+
+        Self {
+            client,
+            subject,
+            timeout: Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_NATS_TIMEOUT_MS)),
+            headers,
+        }
+    }
+
+    /// 发送请求并等待响应（超时返回错误）
+    async fn request<Req, Resp>(&self, hook_type: &str, request: &Req) -> Result<Resp>
+    where
+        Req: ProstMessage,
+        Resp: ProstMessage + Default,
+    {
+        let reply = tokio::time::timeout(
+            self.timeout,
+            self.client.request_with_headers(
+                self.subject.clone(),
+                self.headers.clone(),
+                request.encode_to_vec().into(),
+            ),
+        )
+        .await
+        .map_err(|_| {
+            anyhow!(
+                "NATS {} hook request to {} timed out after {}ms",
+                hook_type,
+                self.subject,
+                self.timeout.as_millis()
+            )
+        })?
+        .map_err(|e| anyhow!("NATS {} hook request to {} failed: {}", hook_type, self.subject, e))?;
+
+        Resp::decode(reply.payload)
+            .with_context(|| format!("Failed to decode NATS {} hook response", hook_type))
+    }
+
+    /// 执行PreSend Hook
+    pub async fn pre_send(
+        &self,
+        ctx: &Context,
+        draft: &mut MessageDraft,
+    ) -> Result<PreSendDecision> {
+        let request = PreSendHookRequest {
+            context: Some(context_to_proto(ctx)),
+            draft: Some(message_draft_to_proto(draft)),
+        };
+        let response: PreSendHookResponse = self.request("pre_send", &request).await?;
+        Ok(proto_to_pre_send_decision(&response, draft))
+    }
+
+    /// 执行PostSend Hook
+    pub async fn post_send(
+        &self,
+        ctx: &Context,
+        record: &MessageRecord,
+        draft: &MessageDraft,
+    ) -> Result<()> {
+        let request = PostSendHookRequest {
+            context: Some(context_to_proto(ctx)),
+            record: Some(message_record_to_proto(record)),
+            draft: Some(message_draft_to_proto(draft)),
+        };
+        let response: PostSendHookResponse = self.request("post_send", &request).await?;
+        if response.success {
+            Ok(())
+        } else {
+            Err(response_error(
+                response.status.map(|s| (s.code as u32, s.message)),
+                "PostSend hook failed",
+            ))
+        }
+    }
+
+    /// 执行Delivery Hook
+    pub async fn delivery(&self, ctx: &Context, event: &DeliveryEvent) -> Result<()> {
+        let request = DeliveryHookRequest {
+            context: Some(context_to_proto(ctx)),
+            event: Some(delivery_event_to_proto(event)),
+        };
+        let response: DeliveryHookResponse = self.request("delivery", &request).await?;
+        if response.success {
+            Ok(())
+        } else {
+            Err(response_error(
+                response.status.map(|s| (s.code as u32, s.message)),
+                "Delivery hook failed",
+            ))
+        }
+    }
+
+    /// 执行Recall Hook
+    pub async fn recall(&self, ctx: &Context, event: &RecallEvent) -> Result<PreSendDecision> {
+        let request = RecallHookRequest {
+            context: Some(context_to_proto(ctx)),
+            event: Some(recall_event_to_proto(event)),
+        };
+        let response: RecallHookResponse = self.request("recall", &request).await?;
+        Ok(proto_to_recall_decision(&response))
+    }
+}
+
+/// 将响应中的错误状态转换为错误
+fn response_error(status: Option<(u32, String)>, default_message: &str) -> anyhow::Error {
+    use flare_im_core::error::{ErrorBuilder, ErrorCode};
+    let error = match status {
+        Some((code, message)) => {
+            ErrorBuilder::new(ErrorCode::from_u32(code).unwrap_or(ErrorCode::GeneralError), &message)
+                .build_error()
+        }
+        None => ErrorBuilder::new(ErrorCode::InternalError, default_message).build_error(),
+    };
+    error.into()
+}
@@ -0,0 +1,381 @@
+//! # 动态库插件
+//!
+//! 从插件目录加载 `.so` / `.dylib` 编译的 Hook 插件，供 Local 传输按 `target` 引用。
+//!
+//! 插件通过稳定的 C ABI 暴露版本化描述符：
+//!
+//! ```c
+//! typedef struct { uint8_t *data; size_t len; } FlareHookBuffer;
+//!
+//! typedef struct {
+//!     uint32_t abi_version;           // 必须等于 FLARE_HOOK_PLUGIN_ABI_VERSION（当前为 1）
+//!     const char *name;               // 插件名称（Local 传输的 target）
+//!     const char *version;            // 插件版本
+//!     int32_t (*invoke)(const char *hook_type, const uint8_t *input, size_t input_len,
+//!                       FlareHookBuffer *output);
+//!     void (*free_buffer)(FlareHookBuffer buffer);
+//! } FlareHookPluginDescriptor;
+//!
+//! const FlareHookPluginDescriptor *flare_hook_plugin_descriptor(void);
+//! ```
+//!
+//! - `invoke` 的输入/输出与 WebHook 的 JSON 请求/响应格式一致；返回 0 表示成功，
+//!   非 0 表示失败（此时 `output` 可携带 UTF-8 错误信息）
+//! - `output` 由插件分配，引擎使用后调用 `free_buffer` 释放
+//! - `invoke` 必须线程安全，引擎在阻塞线程池中并发调用
+//! - 插件在启动时加载，进程生命周期内不卸载
+
+use std::collections::HashMap;
+use std::ffi::{CStr, CString, c_char};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context as AnyhowContext, Result, anyhow, bail};
+use libloading::Library;
+use serde_json::Value;
+
+use flare_im_core::{DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent};
+use flare_server_core::context::Context;
+
+use crate::infrastructure::adapters::webhook::{
+    check_success, delivery_payload, parse_decision, parse_pre_send_response, post_send_payload,
+    pre_send_payload, recall_payload,
+};
+
+/// 当前支持的插件 ABI 版本
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+/// 插件导出的描述符函数名
+pub const PLUGIN_DESCRIPTOR_SYMBOL: &str = "flare_hook_plugin_descriptor";
+
+/// 插件分配的缓冲区
+#[repr(C)]
+pub struct FlareHookBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+/// 插件调用入口
+pub type PluginInvokeFn = unsafe extern "C" fn(
+    hook_type: *const c_char,
+    input: *const u8,
+    input_len: usize,
+    output: *mut FlareHookBuffer,
+) -> i32;
+
+/// 释放插件分配的缓冲区
+pub type PluginFreeBufferFn = unsafe extern "C" fn(buffer: FlareHookBuffer);
+
+/// 插件描述符
+#[repr(C)]
+pub struct FlareHookPluginDescriptor {
+    pub abi_version: u32,
+    pub name: *const c_char,
+    pub version: *const c_char,
+    pub invoke: PluginInvokeFn,
+    pub free_buffer: PluginFreeBufferFn,
+}
+
+type DescriptorFn = unsafe extern "C" fn() -> *const FlareHookPluginDescriptor;
+
+/// 已加载的插件
+pub struct LoadedPlugin {
+    name: String,
+    version: String,
+    invoke: PluginInvokeFn,
+    free_buffer: PluginFreeBufferFn,
+    /// 持有动态库句柄，保证函数指针在插件生命周期内有效
+    _library: Option<Library>,
+}
+
+// ABI 约定 `invoke` / `free_buffer` 线程安全，描述符字段已在加载时复制
+unsafe impl Send for LoadedPlugin {}
+unsafe impl Sync for LoadedPlugin {}
+
+impl LoadedPlugin {
+    /// 加载动态库插件
+    pub fn load(path: &Path) -> Result<Self> {
+        // SAFETY: 插件目录由运维配置，加载即信任其代码；描述符的有效性在下方校验
+        unsafe {
+            let library = Library::new(path)
+                .with_context(|| format!("Failed to load hook plugin {}", path.display()))?;
+            let descriptor_fn = library
+                .get::<DescriptorFn>(PLUGIN_DESCRIPTOR_SYMBOL.as_bytes())
+                .with_context(|| {
+                    format!(
+                        "Hook plugin {} does not export {}",
+                        path.display(),
+                        PLUGIN_DESCRIPTOR_SYMBOL
+                    )
+                })?;
+            let descriptor = descriptor_fn();
+            Self::from_descriptor(descriptor, Some(library))
+        }
+    }
+
+    /// 从描述符构建插件（校验 ABI 版本与名称）
+    ///
+    /// # Safety
+    /// `descriptor` 为空或指向在插件生命周期内有效的描述符
+    unsafe fn from_descriptor(
+        descriptor: *const FlareHookPluginDescriptor,
+        library: Option<Library>,
+    ) -> Result<Self> {
+        let descriptor = unsafe { descriptor.as_ref() }
+            .ok_or_else(|| anyhow!("Hook plugin returned a null descriptor"))?;
+        if descriptor.abi_version != PLUGIN_ABI_VERSION {
+            bail!(
+                "Unsupported hook plugin ABI version {} (expected {})",
+                descriptor.abi_version,
+                PLUGIN_ABI_VERSION
+            );
+        }
+        let name = unsafe { c_string(descriptor.name) }
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| anyhow!("Hook plugin descriptor has no name"))?;
+        let version = unsafe { c_string(descriptor.version) }.unwrap_or_default();
+
+        Ok(Self {
+            name,
+            version,
+            invoke: descriptor.invoke,
+            free_buffer: descriptor.free_buffer,
+            _library: library,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// 调用插件（阻塞调用，放到阻塞线程池执行），空响应视为 `null`
+    async fn call(self: &Arc<Self>, hook_type: &'static str, payload: Value) -> Result<Value> {
+        let plugin = self.clone();
+        tokio::task::spawn_blocking(move || plugin.call_blocking(hook_type, &payload))
+            .await
+            .context("Hook plugin task panicked")?
+    }
+
+    fn call_blocking(&self, hook_type: &str, payload: &Value) -> Result<Value> {
+        let input = serde_json::to_vec(payload).context("Failed to encode hook plugin payload")?;
+        let hook_type = CString::new(hook_type).expect("hook type contains no NUL");
+        let mut output = FlareHookBuffer {
+            data: std::ptr::null_mut(),
+            len: 0,
+        };
+
+        // SAFETY: 输入缓冲区在调用期间有效，输出缓冲区按 ABI 约定由插件分配、由 free_buffer 释放
+        let (code, bytes) = unsafe {
+            let code = (self.invoke)(hook_type.as_ptr(), input.as_ptr(), input.len(), &mut output);
+            let bytes = if output.data.is_null() {
+                Vec::new()
+            } else {
+                let bytes = std::slice::from_raw_parts(output.data, output.len).to_vec();
+                (self.free_buffer)(output);
+                bytes
+            };
+            (code, bytes)
+        };
+
+        if code != 0 {
+            bail!(
+                "Hook plugin {} failed with code {}: {}",
+                self.name,
+                code,
+                String::from_utf8_lossy(&bytes)
+            );
+        }
+        if bytes.is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_slice(&bytes)
+            .with_context(|| format!("Hook plugin {} returned invalid JSON", self.name))
+    }
+
+    /// 执行PreSend Hook
+    pub async fn pre_send(
+        self: &Arc<Self>,
+        ctx: &Context,
+        draft: &mut MessageDraft,
+    ) -> Result<PreSendDecision> {
+        let response = self.call("pre_send", pre_send_payload(ctx, draft)).await?;
+        Ok(parse_pre_send_response(&response, draft, "Hook plugin rejected the request"))
+    }
+
+    /// 执行PostSend Hook
+    pub async fn post_send(
+        self: &Arc<Self>,
+        ctx: &Context,
+        record: &MessageRecord,
+        draft: &MessageDraft,
+    ) -> Result<()> {
+        let response = self
+            .call("post_send", post_send_payload(ctx, record, draft))
+            .await?;
+        check_success(&response, "Plugin PostSend")
+    }
+
+    /// 执行Delivery Hook
+    pub async fn delivery(self: &Arc<Self>, ctx: &Context, event: &DeliveryEvent) -> Result<()> {
+        let response = self.call("delivery", delivery_payload(ctx, event)).await?;
+        check_success(&response, "Plugin Delivery")
+    }
+
+    /// 执行Recall Hook
+    pub async fn recall(
+        self: &Arc<Self>,
+        ctx: &Context,
+        event: &RecallEvent,
+    ) -> Result<PreSendDecision> {
+        let response = self.call("recall", recall_payload(ctx, event)).await?;
+        Ok(parse_decision(&response, "Hook plugin rejected the recall request"))
+    }
+}
+
+/// 插件注册表（插件名称 -> 插件）
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: HashMap<String, Arc<LoadedPlugin>>,
+}
+
+impl PluginRegistry {
+    /// 加载插件目录下的所有动态库
+    ///
+    /// 单个插件加载失败只记录告警，不影响其他插件；插件名称重复时保留先加载的
+    pub fn load_dir(dir: &Path) -> Result<Self> {
+        let mut registry = Self::default();
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read hook plugin directory {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|ext| ext.to_str()),
+                    Some("so" | "dylib")
+                )
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match LoadedPlugin::load(&path) {
+                Ok(plugin) => {
+                    tracing::info!(
+                        plugin = %plugin.name(),
+                        version = %plugin.version(),
+                        path = %path.display(),
+                        "Loaded hook plugin"
+                    );
+                    registry.insert(plugin);
+                }
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "Failed to load hook plugin");
+                }
+            }
+        }
+        Ok(registry)
+    }
+
+    fn insert(&mut self, plugin: LoadedPlugin) {
+        if self.plugins.contains_key(plugin.name()) {
+            tracing::warn!(plugin = %plugin.name(), "Duplicate hook plugin name, ignoring");
+            return;
+        }
+        self.plugins
+            .insert(plugin.name().to_string(), Arc::new(plugin));
+    }
+
+    /// 按名称获取插件
+    pub fn get(&self, name: &str) -> Option<Arc<LoadedPlugin>> {
+        self.plugins.get(name).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+}
+
+/// 读取 C 字符串（空指针返回 None）
+///
+/// # Safety
+/// `ptr` 为空或指向以 NUL 结尾的有效字符串
+unsafe fn c_string(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    Some(unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试插件：内容为 "blocked" 时拒绝，否则在 metadata 中打标
+    unsafe extern "C" fn test_invoke(
+        _hook_type: *const c_char,
+        input: *const u8,
+        input_len: usize,
+        output: *mut FlareHookBuffer,
+    ) -> i32 {
+        let input = unsafe { std::slice::from_raw_parts(input, input_len) };
+        let request: Value = serde_json::from_slice(input).unwrap();
+        let blocked = request["draft"]["payload"].as_str() == Some("YmxvY2tlZA=="); // "blocked"
+        let response = if blocked {
+            serde_json::json!({ "allow": false, "reason": "blocked by plugin" })
+        } else {
+            serde_json::json!({ "allow": true, "draft": { "metadata": { "plugin": "seen" } } })
+        };
+        let mut bytes = serde_json::to_vec(&response).unwrap().into_boxed_slice();
+        unsafe {
+            *output = FlareHookBuffer {
+                data: bytes.as_mut_ptr(),
+                len: bytes.len(),
+            };
+        }
+        std::mem::forget(bytes);
+        0
+    }
+
+    unsafe extern "C" fn test_free(buffer: FlareHookBuffer) {
+        drop(unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(buffer.data, buffer.len)) });
+    }
+
+    fn descriptor(abi_version: u32) -> FlareHookPluginDescriptor {
+        FlareHookPluginDescriptor {
+            abi_version,
+            name: c"test-plugin".as_ptr(),
+            version: c"1.0.0".as_ptr(),
+            invoke: test_invoke,
+            free_buffer: test_free,
+        }
+    }
+
+    #[test]
+    fn test_rejects_unsupported_abi_version() {
+        let descriptor = descriptor(PLUGIN_ABI_VERSION + 1);
+        let result = unsafe { LoadedPlugin::from_descriptor(&descriptor, None) };
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_plugin_pre_send_roundtrip() {
+        let descriptor = descriptor(PLUGIN_ABI_VERSION);
+        let plugin = Arc::new(unsafe { LoadedPlugin::from_descriptor(&descriptor, None) }.unwrap());
+        assert_eq!(plugin.name(), "test-plugin");
+        assert_eq!(plugin.version(), "1.0.0");
+
+        let ctx = Context::with_request_id("req-1".to_string());
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+        assert!(plugin.pre_send(&ctx, &mut draft).await.unwrap().is_continue());
+        assert_eq!(draft.metadata.get("plugin").map(String::as_str), Some("seen"));
+
+        let mut draft = MessageDraft::new(b"blocked".to_vec());
+        assert!(!plugin.pre_send(&ctx, &mut draft).await.unwrap().is_continue());
+    }
+}
@@ -0,0 +1,218 @@
+//! # 内置限流Hook
+//!
+//! Local 传输配置 `rate_limit` 时在进程内执行的 PreSend 限流，无需部署外部Hook服务：
+//! - 按发送者、会话、租户三个维度独立计数，计数存储在 Redis（多实例共享）
+//! - 固定窗口：维度的第一条消息开始计时，窗口过期后重新计数
+//! - 超限动作：拒绝、等待窗口重置后放行、放行并打标
+//!
+//! 缺少发送者或会话ID时跳过对应维度；Redis 不可用时放行（限流不应影响消息主链路）。
+
+use std::time::Duration;
+
+use anyhow::Result;
+use redis::Script;
+use redis::aio::ConnectionManager;
+
+use flare_im_core::error::{ErrorBuilder, ErrorCode};
+use flare_im_core::{DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent};
+use flare_server_core::context::Context;
+
+use crate::domain::model::{RateLimitAction, RateLimitHookConfig, RateLimitWindow};
+use crate::infrastructure::adapters::hook_context_data::get_hook_context_data;
+
+/// 对所有维度计数：首次计数时设置窗口过期时间，返回每个维度的 {计数, 剩余毫秒}
+const INCREMENT_SCRIPT: &str = r#"
+local result = {}
+for i, key in ipairs(KEYS) do
+    local count = redis.call('INCR', key)
+    if count == 1 then
+        redis.call('EXPIRE', key, ARGV[i])
+    end
+    table.insert(result, count)
+    table.insert(result, redis.call('PTTL', key))
+end
+return result
+"#;
+
+/// 限流维度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RateLimitScope {
+    Sender,
+    Conversation,
+    Tenant,
+}
+
+impl RateLimitScope {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitScope::Sender => "sender",
+            RateLimitScope::Conversation => "conversation",
+            RateLimitScope::Tenant => "tenant",
+        }
+    }
+}
+
+/// 超限的维度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Exceeded {
+    scope: RateLimitScope,
+    /// 窗口剩余时间
+    retry_after: Duration,
+}
+
+/// 内置限流适配器
+pub struct RateLimitHookAdapter {
+    target: String,
+    config: RateLimitHookConfig,
+    redis: ConnectionManager,
+    script: Script,
+}
+
+impl RateLimitHookAdapter {
+    pub fn new(target: String, config: RateLimitHookConfig, redis: ConnectionManager) -> Self {
+        Self {
+            target,
+            config,
+            redis,
+            script: Script::new(INCREMENT_SCRIPT),
+        }
+    }
+
+    /// 本次消息涉及的计数维度（Redis 键与窗口）
+    fn windows(
+        &self,
+        ctx: &Context,
+        draft: &MessageDraft,
+    ) -> Vec<(RateLimitScope, String, RateLimitWindow)> {
+        let tenant_id = ctx.tenant_id().unwrap_or("0");
+        let hook_data = get_hook_context_data(ctx);
+        let sender_id = hook_data
+            .and_then(|data| data.sender_id.as_deref())
+            .or_else(|| ctx.user_id());
+        let conversation_id = draft
+            .conversation_id
+            .as_deref()
+            .or_else(|| hook_data.and_then(|data| data.conversation_id.as_deref()));
+
+        let candidates = [
+            (RateLimitScope::Sender, self.config.per_sender, sender_id),
+            (
+                RateLimitScope::Conversation,
+                self.config.per_conversation,
+                conversation_id,
+            ),
+            (
+                RateLimitScope::Tenant,
+                self.config.per_tenant,
+                Some(tenant_id),
+            ),
+        ];
+        candidates
+            .into_iter()
+            .filter_map(|(scope, window, id)| {
+                let window = window?;
+                let id = id.filter(|id| !id.is_empty())?;
+                let key = format!(
+                    "hook:rate_limit:{}:{}:{}:{}",
+                    self.target,
+                    tenant_id,
+                    scope.as_str(),
+                    id
+                );
+                Some((scope, key, window))
+            })
+            .collect()
+    }
+
+    /// 计数并返回超限的维度（多个维度超限时取窗口剩余时间最长的）
+    async fn check(&self, ctx: &Context, draft: &MessageDraft) -> Result<Option<Exceeded>> {
+        let windows = self.windows(ctx, draft);
+        if windows.is_empty() {
+            return Ok(None);
+        }
+
+        let mut invocation = self.script.prepare_invoke();
+        for (_, key, window) in &windows {
+            invocation.key(key).arg(window.window_secs);
+        }
+        let mut conn = self.redis.clone();
+        let counts: Vec<(u64, i64)> = invocation.invoke_async(&mut conn).await?;
+
+        Ok(windows
+            .iter()
+            .zip(counts)
+            .filter(|((_, _, window), (count, _))| *count > window.limit)
+            .map(|((scope, _, _), (_, ttl_ms))| Exceeded {
+                scope: *scope,
+                retry_after: Duration::from_millis(ttl_ms.max(0) as u64),
+            })
+            .max_by_key(|exceeded| exceeded.retry_after))
+    }
+}
+
+#[async_trait::async_trait]
+impl super::HookAdapter for RateLimitHookAdapter {
+    async fn pre_send(&self, ctx: &Context, draft: &mut MessageDraft) -> Result<PreSendDecision> {
+        let exceeded = match self.check(ctx, draft).await {
+            Ok(Some(exceeded)) => exceeded,
+            Ok(None) => return Ok(PreSendDecision::Continue),
+            Err(e) => {
+                tracing::warn!(
+                    hook = %self.target,
+                    error = %e,
+                    "Rate limit check failed, allowing message"
+                );
+                return Ok(PreSendDecision::Continue);
+            }
+        };
+
+        tracing::debug!(
+            hook = %self.target,
+            scope = exceeded.scope.as_str(),
+            retry_after_ms = exceeded.retry_after.as_millis() as u64,
+            "Rate limit exceeded"
+        );
+        Ok(match &self.config.action {
+            RateLimitAction::Reject { reason } => {
+                let message = reason.clone().unwrap_or_else(|| {
+                    format!("Rate limit exceeded ({})", exceeded.scope.as_str())
+                });
+                PreSendDecision::Reject {
+                    error: ErrorBuilder::new(ErrorCode::PermissionDenied, &message).build_error(),
+                }
+            }
+            RateLimitAction::Delay { max_delay_ms } => {
+                tokio::time::sleep(
+                    exceeded
+                        .retry_after
+                        .min(Duration::from_millis(*max_delay_ms)),
+                )
+                .await;
+                PreSendDecision::Continue
+            }
+            RateLimitAction::Tag { metadata_key } => {
+                draft
+                    .metadata
+                    .insert(metadata_key.clone(), exceeded.scope.as_str().to_string());
+                PreSendDecision::Continue
+            }
+        })
+    }
+
+    async fn post_send(
+        &self,
+        _ctx: &Context,
+        _record: &MessageRecord,
+        _draft: &MessageDraft,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn delivery(&self, _ctx: &Context, _event: &DeliveryEvent) -> Result<()> {
+        Ok(())
+    }
+
+    async fn recall(&self, _ctx: &Context, _event: &RecallEvent) -> Result<PreSendDecision> {
+        Ok(PreSendDecision::Continue)
+    }
+}
@@ -0,0 +1,153 @@
+//! # 采样适配器
+//!
+//! 包装gRPC/WebHook适配器，对命中采样的调用记录请求与响应，未命中时直接透传。
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use flare_im_core::{DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent};
+use flare_server_core::context::Context;
+
+use crate::infrastructure::adapters::HookAdapter;
+use crate::infrastructure::sampling::{HookSampleStore, HookSampler};
+
+/// 带采样的Hook适配器
+pub struct SampledHookAdapter {
+    inner: Arc<dyn HookAdapter>,
+    sampler: HookSampler,
+    store: Arc<HookSampleStore>,
+}
+
+impl SampledHookAdapter {
+    pub fn new(
+        inner: Arc<dyn HookAdapter>,
+        sampler: HookSampler,
+        store: Arc<HookSampleStore>,
+    ) -> Self {
+        Self {
+            inner,
+            sampler,
+            store,
+        }
+    }
+
+    fn record(
+        &self,
+        ctx: &Context,
+        operation: &'static str,
+        started: Instant,
+        request: Value,
+        outcome: Result<Value, String>,
+    ) {
+        let sample = self.sampler.sample(
+            ctx.tenant_id().unwrap_or("0"),
+            operation,
+            started.elapsed().as_millis() as u64,
+            request,
+            outcome,
+        );
+        self.store.push(sample);
+    }
+}
+
+fn context_value(ctx: &Context) -> Value {
+    json!({
+        "request_id": ctx.request_id(),
+        "trace_id": ctx.trace_id(),
+        "tenant_id": ctx.tenant_id(),
+        "session_id": ctx.session_id(),
+    })
+}
+
+fn decision_value(decision: &PreSendDecision) -> Value {
+    match decision {
+        PreSendDecision::Continue => json!({ "allow": true }),
+        PreSendDecision::Reject { error } => json!({
+            "allow": false,
+            "error": error.to_string(),
+        }),
+    }
+}
+
+#[async_trait::async_trait]
+impl HookAdapter for SampledHookAdapter {
+    async fn pre_send(&self, ctx: &Context, draft: &mut MessageDraft) -> Result<PreSendDecision> {
+        if !self.sampler.try_sample(ctx.tenant_id().unwrap_or("0")) {
+            return self.inner.pre_send(ctx, draft).await;
+        }
+
+        let request = json!({ "context": context_value(ctx), "draft": &*draft });
+        let started = Instant::now();
+        let result = self.inner.pre_send(ctx, draft).await;
+        let outcome = match &result {
+            Ok(decision) => {
+                let mut response = decision_value(decision);
+                response["draft"] = json!(&*draft);
+                Ok(response)
+            }
+            Err(e) => Err(format!("{:#}", e)),
+        };
+        self.record(ctx, "pre_send", started, request, outcome);
+        result
+    }
+
+    async fn post_send(
+        &self,
+        ctx: &Context,
+        record: &MessageRecord,
+        draft: &MessageDraft,
+    ) -> Result<()> {
+        if !self.sampler.try_sample(ctx.tenant_id().unwrap_or("0")) {
+            return self.inner.post_send(ctx, record, draft).await;
+        }
+
+        let request = json!({ "context": context_value(ctx), "record": record, "draft": draft });
+        let started = Instant::now();
+        let result = self.inner.post_send(ctx, record, draft).await;
+        let outcome = match &result {
+            Ok(()) => Ok(json!({ "success": true })),
+            Err(e) => Err(format!("{:#}", e)),
+        };
+        self.record(ctx, "post_send", started, request, outcome);
+        result
+    }
+
+    async fn delivery(&self, ctx: &Context, event: &DeliveryEvent) -> Result<()> {
+        if !self.sampler.try_sample(ctx.tenant_id().unwrap_or("0")) {
+            return self.inner.delivery(ctx, event).await;
+        }
+
+        let request = json!({ "context": context_value(ctx), "event": event });
+        let started = Instant::now();
+        let result = self.inner.delivery(ctx, event).await;
+        let outcome = match &result {
+            Ok(()) => Ok(json!({ "success": true })),
+            Err(e) => Err(format!("{:#}", e)),
+        };
+        self.record(ctx, "delivery", started, request, outcome);
+        result
+    }
+
+    async fn recall(&self, ctx: &Context, event: &RecallEvent) -> Result<PreSendDecision> {
+        if !self.sampler.try_sample(ctx.tenant_id().unwrap_or("0")) {
+            return self.inner.recall(ctx, event).await;
+        }
+
+        let request = json!({ "context": context_value(ctx), "event": event });
+        let started = Instant::now();
+        let result = self.inner.recall(ctx, event).await;
+        let outcome = result
+            .as_ref()
+            .map(decision_value)
+            .map_err(|e| format!("{:#}", e));
+        self.record(ctx, "recall", started, request, outcome);
+        result
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+}
@@ -0,0 +1,309 @@
+//! # 脚本Hook适配器
+//!
+//! 在进程内执行 Hook 配置中内嵌的 Rhai 脚本，运营人员无需部署服务即可编写简单的过滤规则。
+//!
+//! 脚本可访问的变量：
+//! - `hook_type`：`pre_send` / `post_send` / `delivery` / `recall`
+//! - `tenant_id`：租户ID
+//! - `message`：消息信息（只读，`pre_send` 下包含 `message_id`、`conversation_id`、`text` 等）
+//! - `metadata`：消息元数据（`pre_send` 下对其修改会写回 `MessageDraft.metadata`）
+//!
+//! 脚本可调用 `reject(reason)` 拒绝本次操作（`pre_send` / `recall` 返回拒绝决策，其他类型返回错误）。
+//!
+//! ```rhai
+//! if message.text.contains("广告") {
+//!     reject("包含违规内容");
+//! }
+//! metadata["checked_by"] = "script";
+//! ```
+//!
+//! 脚本运行在受限引擎中：禁用 `eval`，限制操作数、调用深度和字符串/数组/Map 大小，
+//! `print` / `debug` 输出到日志。
+
+use std::collections::HashMap;
+
+use anyhow::{Result, anyhow};
+use rhai::{AST, Dynamic, Engine, EvalAltResult, Map, Position, Scope};
+
+use flare_im_core::error::{ErrorBuilder, ErrorCode};
+use flare_im_core::{DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent};
+use flare_server_core::context::Context;
+
+/// 单次执行允许的最大操作数（防止死循环）
+const MAX_OPERATIONS: u64 = 100_000;
+const MAX_CALL_LEVELS: usize = 16;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_STRING_SIZE: usize = 64 * 1024;
+const MAX_COLLECTION_SIZE: usize = 1_024;
+
+/// `reject(reason)` 的中断信号
+#[derive(Debug, Clone)]
+struct RejectSignal(String);
+
+/// 脚本执行结果
+#[derive(Debug, PartialEq)]
+enum ScriptOutcome {
+    Continue,
+    Reject(String),
+}
+
+/// 脚本Hook适配器
+pub struct ScriptHookAdapter {
+    name: String,
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptHookAdapter {
+    /// 编译脚本并创建适配器（语法错误在加载配置时即返回）
+    pub fn new(name: String, script: &str) -> Result<Self> {
+        let engine = restricted_engine(&name);
+        let ast = engine
+            .compile(script)
+            .map_err(|e| anyhow!("Failed to compile hook script {}: {}", name, e))?;
+        Ok(Self { name, engine, ast })
+    }
+
+    /// 执行脚本，返回执行结果和（可能被修改的）元数据
+    fn run(
+        &self,
+        ctx: &Context,
+        hook_type: &str,
+        message: Map,
+        metadata: &HashMap<String, String>,
+    ) -> Result<(ScriptOutcome, HashMap<String, String>)> {
+        let mut scope = Scope::new();
+        scope.push_constant("hook_type", hook_type.to_string());
+        scope.push_constant("tenant_id", ctx.tenant_id().unwrap_or("0").to_string());
+        scope.push_constant("message", message);
+        scope.push("metadata", string_map(metadata));
+
+        let outcome = match self.engine.run_ast_with_scope(&mut scope, &self.ast) {
+            Ok(()) => ScriptOutcome::Continue,
+            Err(err) => match reject_reason(&err) {
+                Some(reason) => ScriptOutcome::Reject(reason),
+                None => return Err(anyhow!("Hook script {} failed: {}", self.name, err)),
+            },
+        };
+
+        let metadata = scope
+            .get_value::<Map>("metadata")
+            .map(|map| {
+                map.into_iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect()
+            })
+            .unwrap_or_else(|| metadata.clone());
+
+        Ok((outcome, metadata))
+    }
+
+    /// 执行PreSend Hook
+    pub async fn pre_send(
+        &self,
+        ctx: &Context,
+        draft: &mut MessageDraft,
+    ) -> Result<PreSendDecision> {
+        let mut message = Map::new();
+        message.insert("message_id".into(), optional(&draft.message_id));
+        message.insert("client_message_id".into(), optional(&draft.client_message_id));
+        message.insert("conversation_id".into(), optional(&draft.conversation_id));
+        message.insert(
+            "text".into(),
+            String::from_utf8_lossy(&draft.payload).into_owned().into(),
+        );
+        message.insert("headers".into(), string_map(&draft.headers).into());
+
+        let (outcome, metadata) = self.run(ctx, "pre_send", message, &draft.metadata)?;
+        match outcome {
+            ScriptOutcome::Continue => {
+                draft.metadata = metadata;
+                Ok(PreSendDecision::Continue)
+            }
+            ScriptOutcome::Reject(reason) => Ok(reject_decision(&reason)),
+        }
+    }
+
+    /// 执行PostSend Hook
+    pub async fn post_send(
+        &self,
+        ctx: &Context,
+        record: &MessageRecord,
+        _draft: &MessageDraft,
+    ) -> Result<()> {
+        let mut message = Map::new();
+        message.insert("message_id".into(), record.message_id.clone().into());
+        message.insert("conversation_id".into(), record.conversation_id.clone().into());
+        message.insert("sender_id".into(), record.sender_id.clone().into());
+        message.insert("conversation_type".into(), optional(&record.conversation_type));
+        message.insert("message_type".into(), optional(&record.message_type));
+
+        let (outcome, _) = self.run(ctx, "post_send", message, &record.metadata)?;
+        self.ensure_continue(outcome)
+    }
+
+    /// 执行Delivery Hook
+    pub async fn delivery(&self, ctx: &Context, event: &DeliveryEvent) -> Result<()> {
+        let mut message = Map::new();
+        message.insert("message_id".into(), event.message_id.clone().into());
+        message.insert("user_id".into(), event.user_id.clone().into());
+        message.insert("channel".into(), event.channel.clone().into());
+
+        let (outcome, _) = self.run(ctx, "delivery", message, &event.metadata)?;
+        self.ensure_continue(outcome)
+    }
+
+    /// 执行Recall Hook
+    pub async fn recall(&self, ctx: &Context, event: &RecallEvent) -> Result<PreSendDecision> {
+        let mut message = Map::new();
+        message.insert("message_id".into(), event.message_id.clone().into());
+        message.insert("operator_id".into(), event.operator_id.clone().into());
+
+        let (outcome, _) = self.run(ctx, "recall", message, &event.metadata)?;
+        match outcome {
+            ScriptOutcome::Continue => Ok(PreSendDecision::Continue),
+            ScriptOutcome::Reject(reason) => Ok(reject_decision(&reason)),
+        }
+    }
+
+    fn ensure_continue(&self, outcome: ScriptOutcome) -> Result<()> {
+        match outcome {
+            ScriptOutcome::Continue => Ok(()),
+            ScriptOutcome::Reject(reason) => {
+                Err(anyhow!("Hook script {} rejected: {}", self.name, reason))
+            }
+        }
+    }
+}
+
+/// 构建受限的脚本引擎
+fn restricted_engine(name: &str) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_COLLECTION_SIZE)
+        .set_max_map_size(MAX_COLLECTION_SIZE)
+        .set_max_modules(0);
+    engine.disable_symbol("eval");
+
+    let print_name = name.to_string();
+    engine.on_print(move |text| {
+        tracing::info!(hook = %print_name, "{}", text);
+    });
+    let debug_name = name.to_string();
+    engine.on_debug(move |text, _, pos| {
+        tracing::debug!(hook = %debug_name, position = %pos, "{}", text);
+    });
+
+    engine.register_fn(
+        "reject",
+        |reason: &str| -> std::result::Result<(), Box<EvalAltResult>> {
+            Err(Box::new(EvalAltResult::ErrorRuntime(
+                Dynamic::from(RejectSignal(reason.to_string())),
+                Position::NONE,
+            )))
+        },
+    );
+    engine
+}
+
+/// 从脚本错误中提取 `reject(reason)` 信号（包括在脚本函数内调用的情况）
+fn reject_reason(err: &EvalAltResult) -> Option<String> {
+    match err {
+        EvalAltResult::ErrorRuntime(value, _) => value
+            .clone()
+            .try_cast::<RejectSignal>()
+            .map(|signal| signal.0),
+        EvalAltResult::ErrorInFunctionCall(_, _, inner, _) => reject_reason(inner),
+        _ => None,
+    }
+}
+
+fn reject_decision(reason: &str) -> PreSendDecision {
+    let error = ErrorBuilder::new(ErrorCode::OperationFailed, reason).build_error();
+    PreSendDecision::Reject { error }
+}
+
+fn optional(value: &Option<String>) -> Dynamic {
+    value
+        .clone()
+        .map(Dynamic::from)
+        .unwrap_or(Dynamic::UNIT)
+}
+
+fn string_map(values: &HashMap<String, String>) -> Map {
+    values
+        .iter()
+        .map(|(key, value)| (key.as_str().into(), value.clone().into()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft(text: &str) -> MessageDraft {
+        let mut draft = MessageDraft::new(text.as_bytes().to_vec());
+        draft.conversation_id = Some("conv-1".to_string());
+        draft
+    }
+
+    #[tokio::test]
+    async fn test_script_rejects_and_modifies_metadata() {
+        let adapter = ScriptHookAdapter::new(
+            "keyword-filter".to_string(),
+            r#"
+            if message.text.contains("spam") {
+                reject("spam is not allowed");
+            }
+            metadata["checked"] = "true";
+            "#,
+        )
+        .unwrap();
+        let ctx = Context::with_request_id("req-1".to_string());
+
+        let mut ok = draft("hello");
+        let decision = adapter.pre_send(&ctx, &mut ok).await.unwrap();
+        assert!(decision.is_continue());
+        assert_eq!(ok.metadata.get("checked").map(String::as_str), Some("true"));
+
+        let mut bad = draft("buy spam now");
+        let decision = adapter.pre_send(&ctx, &mut bad).await.unwrap();
+        assert!(!decision.is_continue());
+        assert!(bad.metadata.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reject_inside_script_function() {
+        let adapter = ScriptHookAdapter::new(
+            "fn-reject".to_string(),
+            r#"
+            fn check(text) { if text == "" { reject("empty"); } }
+            check(message.text);
+            "#,
+        )
+        .unwrap();
+        let ctx = Context::with_request_id("req-1".to_string());
+
+        let decision = adapter.pre_send(&ctx, &mut draft("")).await.unwrap();
+        assert!(!decision.is_continue());
+    }
+
+    #[tokio::test]
+    async fn test_runaway_script_is_stopped() {
+        let adapter =
+            ScriptHookAdapter::new("loop".to_string(), "loop { metadata.x = 1; }").unwrap();
+        let ctx = Context::with_request_id("req-1".to_string());
+
+        assert!(adapter.pre_send(&ctx, &mut draft("hi")).await.is_err());
+    }
+
+    #[test]
+    fn test_invalid_script_fails_to_compile() {
+        assert!(ScriptHookAdapter::new("bad".to_string(), "if {").is_err());
+        assert!(ScriptHookAdapter::new("eval".to_string(), r#"eval("1")"#).is_err());
+    }
+}
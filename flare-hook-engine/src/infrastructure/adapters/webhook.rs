@@ -1,25 +1,52 @@
 //! # WebHook适配器
 //!
 //! 提供基于HTTP WebHook的Hook传输适配器实现。
+//!
+//! 请求以 JSON 格式 POST 到配置的端点，请求头携带：
+//! - `X-Hook-Type`：Hook类型（pre_send / post_send / delivery / recall）
+//! - `X-Hook-Timestamp`：发送时间（Unix 秒）
+//! - `X-Hook-Signature`：配置密钥时的签名，`sha256=hex(HMAC-SHA256(secret, "{timestamp}.{body}"))`；
+//!   配置了 `previous_secret`（密钥轮换中）时同时携带新旧密钥的签名，逗号分隔
+//!
+//! 签名规则与 `flare_im_core::hooks::signature` 一致，接收方可直接使用其中的 `verify_signature` 校验。
+//!
+//! 连接失败、超时、5xx 和 429 响应按配置重试（指数退避），其余错误直接返回。
 
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use anyhow::{Context as AnyhowContext, Result};
+use anyhow::{Context as AnyhowContext, Result, anyhow};
 use base64::Engine;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
+use serde_json::{Value, json};
 
-use flare_im_core::{
-    DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent,
-};
-use flare_im_core::hooks::hook_context_data::{get_hook_context_data, HookContextData};
+use flare_im_core::error::{ErrorBuilder, ErrorCode};
+use flare_im_core::hooks::WebhookSigner;
+use flare_im_core::hooks::hook_context_data::get_hook_context_data;
+use flare_im_core::{DeliveryEvent, MessageDraft, MessageRecord, PreSendDecision, RecallEvent};
 use flare_server_core::context::Context;
 
+use crate::infrastructure::usage::record_egress;
+
+/// 未配置超时时的默认请求超时（单次请求）
+pub const DEFAULT_WEBHOOK_TIMEOUT_MS: u64 = 3_000;
+/// 未配置时的默认重试次数
+pub const DEFAULT_WEBHOOK_MAX_RETRIES: u32 = 1;
+/// 首次重试前的等待时间，之后每次翻倍
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+pub use flare_im_core::hooks::signature::{SIGNATURE_HEADER, TIMESTAMP_HEADER};
+/// Hook类型请求头
+pub const HOOK_TYPE_HEADER: &str = "X-Hook-Type";
+
 /// WebHook适配器
 pub struct WebhookHookAdapter {
     client: Client,
     endpoint: String,
-    secret: Option<String>,
+    signer: Option<WebhookSigner>,
     headers: HashMap<String, String>,
+    timeout: Duration,
+    max_retries: u32,
 }
 
 impl WebhookHookAdapter {
@@ -27,17 +54,24 @@ impl WebhookHookAdapter {
     pub async fn new(
         endpoint: String,
         secret: Option<String>,
+        previous_secret: Option<String>,
         headers: HashMap<String, String>,
+        timeout_ms: Option<u64>,
+        max_retries: Option<u32>,
     ) -> Result<Self> {
+        let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_WEBHOOK_TIMEOUT_MS));
         let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
+            .timeout(timeout)
+            .build()
+            .context("Failed to build WebHook HTTP client")?;
 
         Ok(Self {
             client,
             endpoint,
-            secret,
+            signer: WebhookSigner::from_secrets(secret, previous_secret),
             headers,
+            timeout,
+            max_retries: max_retries.unwrap_or(DEFAULT_WEBHOOK_MAX_RETRIES),
         })
     }
 
@@ -47,111 +81,8 @@ impl WebhookHookAdapter {
         ctx: &Context,
         draft: &mut MessageDraft,
     ) -> Result<PreSendDecision> {
-        use serde_json::json;
-        use std::time::{SystemTime, UNIX_EPOCH};
-
-        let hook_data = get_hook_context_data(ctx).cloned().unwrap_or_default();
-        let tenant_id = ctx.tenant_id().unwrap_or("0").to_string();
-
-        let payload = json!({
-            "hook_type": "pre_send",
-            "context": {
-                "tenant_id": tenant_id,
-                "conversation_id": hook_data.conversation_id,
-                "conversation_type": hook_data.conversation_type,
-            },
-            "draft": {
-                "message_id": draft.message_id,
-                "client_message_id": draft.client_message_id,
-                "conversation_id": draft.conversation_id,
-                "payload": base64::engine::general_purpose::STANDARD.encode(&draft.payload),
-                "headers": draft.headers,
-                "metadata": draft.metadata,
-            },
-            "timestamp": SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
-        });
-
-        let mut request = self
-            .client
-            .post(&self.endpoint)
-            .json(&payload)
-            .timeout(std::time::Duration::from_secs(30));
-
-        // 设置自定义请求头
-        for (key, value) in &self.headers {
-            request = request.header(key, value);
-        }
-
-        // 如果配置了密钥，生成签名
-        if let Some(ref secret) = self.secret {
-            let signature = self.generate_signature(&payload.to_string(), secret)?;
-            request = request.header("X-Hook-Signature", signature);
-        }
-
-        let response = request
-            .send()
-            .await
-            .with_context(|| "WebHook PreSend request failed")?;
-
-        if response.status().is_success() {
-            let result: serde_json::Value = response
-                .json()
-                .await
-                .with_context(|| "Failed to parse WebHook response")?;
-
-            // 检查是否允许发送
-            let allow = result
-                .get("allow")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(true);
-
-            if allow {
-                // 如果允许发送，检查是否有修改后的 draft
-                if let Some(updated_draft) = result.get("draft") {
-                    if let Some(payload_base64) =
-                        updated_draft.get("payload").and_then(|v| v.as_str())
-                    {
-                        if let Ok(payload) =
-                            base64::engine::general_purpose::STANDARD.decode(payload_base64)
-                        {
-                            draft.payload = payload;
-                        }
-                    }
-                    if let Some(headers) = updated_draft.get("headers").and_then(|v| v.as_object())
-                    {
-                        for (key, value) in headers {
-                            if let Some(value_str) = value.as_str() {
-                                draft.header(key.clone(), value_str.to_string());
-                            }
-                        }
-                    }
-                    if let Some(metadata) =
-                        updated_draft.get("metadata").and_then(|v| v.as_object())
-                    {
-                        for (key, value) in metadata {
-                            if let Some(value_str) = value.as_str() {
-                                draft.metadata(key.clone(), value_str.to_string());
-                            }
-                        }
-                    }
-                }
-                Ok(PreSendDecision::Continue)
-            } else {
-                use flare_im_core::error::{ErrorBuilder, ErrorCode};
-                let error =
-                    ErrorBuilder::new(ErrorCode::PermissionDenied, "WebHook rejected the request")
-                        .build_error();
-                Ok(PreSendDecision::Reject { error })
-            }
-        } else {
-            use flare_im_core::error::{ErrorBuilder, ErrorCode};
-            let error = ErrorBuilder::new(
-                ErrorCode::InternalError,
-                &format!("WebHook returned error status: {}", response.status()),
-            )
-            .build_error();
-            Err(error.into())
-        }
+        let response = self.post("pre_send", &pre_send_payload(ctx, draft)).await?;
+        Ok(parse_pre_send_response(&response, draft, "WebHook rejected the request"))
     }
 
     /// 执行PostSend Hook
@@ -161,183 +92,375 @@ impl WebhookHookAdapter {
         record: &MessageRecord,
         draft: &MessageDraft,
     ) -> Result<()> {
-        use serde_json::json;
-        use std::time::{SystemTime, UNIX_EPOCH};
-
-        let hook_data = get_hook_context_data(ctx).cloned().unwrap_or_default();
-        let tenant_id = ctx.tenant_id().unwrap_or("0").to_string();
-
-        let payload = json!({
-            "hook_type": "post_send",
-            "context": {
-                "tenant_id": tenant_id,
-                "conversation_id": hook_data.conversation_id,
-            },
-            "record": {
-                "message_id": record.message_id,
-                "conversation_id": record.conversation_id,
-                "sender_id": record.sender_id,
-            },
-            "timestamp": SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
-        });
-
-        let mut request = self
-            .client
-            .post(&self.endpoint)
-            .json(&payload)
-            .timeout(std::time::Duration::from_secs(30));
+        let response = self
+            .post("post_send", &post_send_payload(ctx, record, draft))
+            .await?;
+        check_success(&response, "WebHook PostSend")
+    }
+
+    /// 执行Delivery Hook
+    pub async fn delivery(&self, ctx: &Context, event: &DeliveryEvent) -> Result<()> {
+        let response = self.post("delivery", &delivery_payload(ctx, event)).await?;
+        check_success(&response, "WebHook Delivery")
+    }
+
+    /// 执行Recall Hook
+    pub async fn recall(&self, ctx: &Context, event: &RecallEvent) -> Result<PreSendDecision> {
+        let response = self.post("recall", &recall_payload(ctx, event)).await?;
+        Ok(parse_decision(&response, "WebHook rejected the recall request"))
+    }
 
+    /// 健康探测：向端点发送 `HEAD` 请求，连接失败或 5xx 响应视为不健康
+    ///
+    /// 端点不支持 `HEAD`（返回 4xx）时仍视为可达
+    pub async fn health_check(&self) -> Result<()> {
+        let mut request = self.client.head(&self.endpoint);
         for (key, value) in &self.headers {
             request = request.header(key, value);
         }
-
-        if let Some(ref secret) = self.secret {
-            let signature = self.generate_signature(&payload.to_string(), secret)?;
-            request = request.header("X-Hook-Signature", signature);
-        }
-
         let response = request
             .send()
             .await
-            .with_context(|| "WebHook PostSend request failed")?;
-
-        if response.status().is_success() {
-            Ok(())
-        } else {
-            use flare_im_core::error::{ErrorBuilder, ErrorCode};
-            let error = ErrorBuilder::new(
-                ErrorCode::InternalError,
-                &format!("WebHook returned error status: {}", response.status()),
-            )
-            .build_error();
-            Err(error.into())
+            .map_err(|e| anyhow!("WebHook {} health check failed: {}", self.endpoint, e))?;
+        if response.status().is_server_error() {
+            return Err(anyhow!(
+                "WebHook {} health check returned error status: {}",
+                self.endpoint,
+                response.status()
+            ));
         }
+        Ok(())
     }
 
-    /// 执行Delivery Hook
-    pub async fn delivery(&self, ctx: &Context, event: &DeliveryEvent) -> Result<()> {
-        use serde_json::json;
-
-        let tenant_id = ctx.tenant_id().unwrap_or("0").to_string();
-
-        let payload = json!({
-            "hook_type": "delivery",
-            "context": {
-                "tenant_id": tenant_id,
-            },
-            "event": {
-                "message_id": event.message_id,
-                "user_id": event.user_id,
-                "channel": event.channel,
-            },
-        });
-
-        let mut request = self
-            .client
-            .post(&self.endpoint)
-            .json(&payload)
-            .timeout(std::time::Duration::from_secs(30));
+    /// 发送请求并解析响应体（空响应体视为 `null`），可重试的失败按指数退避重试
+    async fn post(&self, hook_type: &str, payload: &Value) -> Result<Value> {
+        let body = serde_json::to_vec(payload).context("Failed to encode WebHook payload")?;
+
+        let mut attempt = 0;
+        loop {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let mut request = self
+                .client
+                .post(&self.endpoint)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(HOOK_TYPE_HEADER, hook_type)
+                .header(TIMESTAMP_HEADER, timestamp.to_string());
+            for (key, value) in &self.headers {
+                request = request.header(key, value);
+            }
+            if let Some(ref signer) = self.signer {
+                request = request.header(SIGNATURE_HEADER, signer.sign(timestamp, &body));
+            }
 
-        for (key, value) in &self.headers {
-            request = request.header(key, value);
-        }
+            record_egress(body.len());
+            let retryable_error = match request.body(body.clone()).send().await {
+                Ok(response) if response.status().is_success() => {
+                    let bytes = response
+                        .bytes()
+                        .await
+                        .with_context(|| format!("Failed to read WebHook {} response", hook_type))?;
+                    if bytes.iter().all(u8::is_ascii_whitespace) {
+                        return Ok(Value::Null);
+                    }
+                    return serde_json::from_slice(&bytes)
+                        .with_context(|| format!("Failed to parse WebHook {} response", hook_type));
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let error = anyhow!(
+                        "WebHook {} hook {} returned error status: {}",
+                        hook_type,
+                        self.endpoint,
+                        status
+                    );
+                    if !is_retryable_status(status) {
+                        return Err(error);
+                    }
+                    error
+                }
+                Err(e) => {
+                    let error = if e.is_timeout() {
+                        anyhow!(
+                            "WebHook {} hook {} timed out after {}ms",
+                            hook_type,
+                            self.endpoint,
+                            self.timeout.as_millis()
+                        )
+                    } else {
+                        anyhow!("WebHook {} hook {} request failed: {}", hook_type, self.endpoint, e)
+                    };
+                    if !(e.is_timeout() || e.is_connect() || e.is_request()) {
+                        return Err(error);
+                    }
+                    error
+                }
+            };
 
-        if let Some(ref secret) = self.secret {
-            let signature = self.generate_signature(&payload.to_string(), secret)?;
-            request = request.header("X-Hook-Signature", signature);
+            if attempt >= self.max_retries {
+                return Err(retryable_error);
+            }
+            let backoff = RETRY_BACKOFF * 2u32.saturating_pow(attempt);
+            tracing::debug!(
+                hook_type,
+                endpoint = %self.endpoint,
+                attempt = attempt + 1,
+                error = %retryable_error,
+                "Retrying WebHook request"
+            );
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
         }
+    }
+}
 
-        let _response = request
-            .send()
-            .await
-            .with_context(|| "WebHook Delivery request failed")?;
+/// 生成签名：`sha256=hex(HMAC-SHA256(secret, "{timestamp}.{body}"))`
+pub fn sign_payload(secret: &str, timestamp: u64, body: &[u8]) -> Result<String> {
+    Ok(flare_im_core::hooks::signature::sign_payload(
+        secret, timestamp, body,
+    ))
+}
 
-        Ok(())
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// PreSend 请求体
+///
+/// WebHook 与动态库插件共用同一套 JSON 请求/响应格式
+pub(crate) fn pre_send_payload(ctx: &Context, draft: &MessageDraft) -> Value {
+    json!({
+        "hook_type": "pre_send",
+        "context": context_json(ctx),
+        "draft": draft_json(draft),
+    })
+}
+
+/// PostSend 请求体
+pub(crate) fn post_send_payload(
+    ctx: &Context,
+    record: &MessageRecord,
+    draft: &MessageDraft,
+) -> Value {
+    json!({
+        "hook_type": "post_send",
+        "context": context_json(ctx),
+        "record": {
+            "message_id": record.message_id,
+            "client_message_id": record.client_message_id,
+            "conversation_id": record.conversation_id,
+            "sender_id": record.sender_id,
+            "conversation_type": record.conversation_type,
+            "message_type": record.message_type,
+            "persisted_at": unix_millis(record.persisted_at),
+            "metadata": record.metadata,
+        },
+        "draft": draft_json(draft),
+    })
+}
+
+/// Delivery 请求体
+pub(crate) fn delivery_payload(ctx: &Context, event: &DeliveryEvent) -> Value {
+    json!({
+        "hook_type": "delivery",
+        "context": context_json(ctx),
+        "event": {
+            "message_id": event.message_id,
+            "user_id": event.user_id,
+            "channel": event.channel,
+            "delivered_at": unix_millis(event.delivered_at),
+            "metadata": event.metadata,
+        },
+    })
+}
+
+/// Recall 请求体
+pub(crate) fn recall_payload(ctx: &Context, event: &RecallEvent) -> Value {
+    json!({
+        "hook_type": "recall",
+        "context": context_json(ctx),
+        "event": {
+            "message_id": event.message_id,
+            "operator_id": event.operator_id,
+            "recalled_at": unix_millis(event.recalled_at),
+            "sent_at": event.sent_at.map(unix_millis),
+            "metadata": event.metadata,
+        },
+    })
+}
+
+/// 请求上下文（租户、追踪信息及Hook上下文数据）
+fn context_json(ctx: &Context) -> Value {
+    let hook_data = get_hook_context_data(ctx).cloned().unwrap_or_default();
+    json!({
+        "tenant_id": ctx.tenant_id().unwrap_or("0"),
+        "request_id": ctx.request_id(),
+        "trace_id": ctx.trace_id(),
+        "conversation_id": hook_data.conversation_id,
+        "conversation_type": hook_data.conversation_type,
+        "message_type": hook_data.message_type,
+        "sender_id": hook_data.sender_id,
+        "tags": hook_data.tags,
+        "attributes": hook_data.attributes,
+    })
+}
+
+/// 消息草稿（payload 使用 base64 编码）
+fn draft_json(draft: &MessageDraft) -> Value {
+    json!({
+        "message_id": draft.message_id,
+        "client_message_id": draft.client_message_id,
+        "conversation_id": draft.conversation_id,
+        "payload": base64::engine::general_purpose::STANDARD.encode(&draft.payload),
+        "headers": draft.headers,
+        "metadata": draft.metadata,
+    })
+}
+
+/// 解析放行/拒绝决策：`{"allow": false, "reason": "...", "code": 403}`，未声明 `allow` 时放行
+pub(crate) fn parse_decision(response: &Value, default_reason: &str) -> PreSendDecision {
+    let allow = response
+        .get("allow")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    if allow {
+        return PreSendDecision::Continue;
     }
 
-    /// 执行Recall Hook
-    pub async fn recall(&self, ctx: &Context, event: &RecallEvent) -> Result<PreSendDecision> {
-        use serde_json::json;
-
-        let tenant_id = ctx.tenant_id().unwrap_or("0").to_string();
-
-        let payload = json!({
-            "hook_type": "recall",
-            "context": {
-                "tenant_id": tenant_id,
-            },
-            "event": {
-                "message_id": event.message_id,
-                "operator_id": event.operator_id,
-            },
-        });
-
-        let mut request = self
-            .client
-            .post(&self.endpoint)
-            .json(&payload)
-            .timeout(std::time::Duration::from_secs(30));
+    let reason = response
+        .get("reason")
+        .and_then(Value::as_str)
+        .filter(|reason| !reason.is_empty())
+        .unwrap_or(default_reason);
+    let code = response
+        .get("code")
+        .and_then(Value::as_u64)
+        .and_then(|code| ErrorCode::from_u32(code as u32))
+        .unwrap_or(ErrorCode::PermissionDenied);
+    PreSendDecision::Reject {
+        error: ErrorBuilder::new(code, reason).build_error(),
+    }
+}
 
-        for (key, value) in &self.headers {
-            request = request.header(key, value);
-        }
+/// 解析PreSend响应：拒绝时返回拒绝决策，放行时应用响应中 `draft` 对草稿的修改
+pub(crate) fn parse_pre_send_response(
+    response: &Value,
+    draft: &mut MessageDraft,
+    default_reason: &str,
+) -> PreSendDecision {
+    let decision = parse_decision(response, default_reason);
+    if !decision.is_continue() {
+        return decision;
+    }
 
-        if let Some(ref secret) = self.secret {
-            let signature = self.generate_signature(&payload.to_string(), secret)?;
-            request = request.header("X-Hook-Signature", signature);
+    let Some(updated_draft) = response.get("draft") else {
+        return decision;
+    };
+    if let Some(payload_base64) = updated_draft.get("payload").and_then(Value::as_str) {
+        match base64::engine::general_purpose::STANDARD.decode(payload_base64) {
+            Ok(payload) => draft.payload = payload,
+            Err(e) => tracing::warn!(error = %e, "Ignoring invalid base64 payload in hook response"),
         }
-
-        let response = request
-            .send()
-            .await
-            .with_context(|| "WebHook Recall request failed")?;
-
-        if response.status().is_success() {
-            let result: serde_json::Value = response
-                .json()
-                .await
-                .with_context(|| "Failed to parse WebHook response")?;
-
-            let allow = result
-                .get("allow")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(true);
-
-            if allow {
-                Ok(PreSendDecision::Continue)
-            } else {
-                use flare_im_core::error::{ErrorBuilder, ErrorCode};
-                let error = ErrorBuilder::new(
-                    ErrorCode::PermissionDenied,
-                    "WebHook rejected the recall request",
-                )
-                .build_error();
-                Ok(PreSendDecision::Reject { error })
+    }
+    if let Some(headers) = updated_draft.get("headers").and_then(Value::as_object) {
+        for (key, value) in headers {
+            if let Some(value) = value.as_str() {
+                draft.header(key.clone(), value.to_string());
             }
-        } else {
-            use flare_im_core::error::{ErrorBuilder, ErrorCode};
-            let error = ErrorBuilder::new(
-                ErrorCode::InternalError,
-                &format!("WebHook returned error status: {}", response.status()),
-            )
-            .build_error();
-            Err(error.into())
         }
     }
+    if let Some(metadata) = updated_draft.get("metadata").and_then(Value::as_object) {
+        for (key, value) in metadata {
+            if let Some(value) = value.as_str() {
+                draft.metadata(key.clone(), value.to_string());
+            }
+        }
+    }
+    decision
+}
 
-    /// 生成签名（使用 HMAC-SHA256）
-    fn generate_signature(&self, payload: &str, secret: &str) -> Result<String> {
-        use hmac::{Hmac, Mac};
-        use sha2::Sha256;
-
-        type HmacSha256 = Hmac<Sha256>;
+/// 异步Hook响应：`{"success": false, "reason": "..."}` 视为失败，空响应或未声明时视为成功
+pub(crate) fn check_success(response: &Value, hook_name: &str) -> Result<()> {
+    let success = response
+        .get("success")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    if success {
+        return Ok(());
+    }
+    let reason = response
+        .get("reason")
+        .and_then(Value::as_str)
+        .unwrap_or("no reason given");
+    Err(anyhow!("{} hook failed: {}", hook_name, reason))
+}
 
-        let mut mac =
-            HmacSha256::new_from_slice(secret.as_bytes()).with_context(|| "Invalid secret key")?;
-        mac.update(payload.as_bytes());
-        let result = mac.finalize();
-        let signature = hex::encode(result.into_bytes());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_payload_covers_timestamp_and_body() {
+        let signature = sign_payload("secret", 1_700_000_000, br#"{"a":1}"#).unwrap();
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+
+        assert_eq!(
+            signature,
+            sign_payload("secret", 1_700_000_000, br#"{"a":1}"#).unwrap()
+        );
+        assert_ne!(
+            signature,
+            sign_payload("secret", 1_700_000_001, br#"{"a":1}"#).unwrap()
+        );
+        assert_ne!(
+            signature,
+            sign_payload("other", 1_700_000_000, br#"{"a":1}"#).unwrap()
+        );
+    }
 
-        Ok(format!("sha256={}", signature))
+    #[test]
+    fn test_parse_pre_send_response() {
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+
+        // 拒绝时不修改草稿
+        let rejected = parse_pre_send_response(
+            &json!({
+                "allow": false,
+                "reason": "contains banned words",
+                "draft": { "payload": base64::engine::general_purpose::STANDARD.encode(b"***") },
+            }),
+            &mut draft,
+            "rejected",
+        );
+        assert!(!rejected.is_continue());
+        assert_eq!(draft.payload, b"hello");
+
+        // 放行时应用草稿修改
+        let allowed = parse_pre_send_response(
+            &json!({
+                "allow": true,
+                "draft": {
+                    "payload": base64::engine::general_purpose::STANDARD.encode(b"h***o"),
+                    "metadata": { "filtered": "true" },
+                },
+            }),
+            &mut draft,
+            "rejected",
+        );
+        assert!(allowed.is_continue());
+        assert_eq!(draft.payload, b"h***o");
+        assert_eq!(draft.metadata.get("filtered").map(String::as_str), Some("true"));
+
+        // 空响应放行
+        assert!(parse_pre_send_response(&Value::Null, &mut draft, "rejected").is_continue());
     }
 }
@@ -0,0 +1,180 @@
+//! # Hook执行审计
+//!
+//! 审计记录先写入有界通道，由后台任务按批次大小或刷新间隔批量落库，不阻塞Hook执行路径。
+//! 通道已满或落库失败时丢弃记录并输出告警（审计日志尽力而为，不影响消息主流程）。
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use crate::domain::model::HookAuditEntry;
+use crate::domain::repository::{HookAuditRecorder, HookAuditRepository};
+
+/// 审计日志配置
+#[derive(Debug, Clone)]
+pub struct HookAuditConfig {
+    /// 单批写入的最大记录数
+    pub batch_size: usize,
+    /// 未攒满一批时的刷新间隔
+    pub flush_interval: Duration,
+    /// 通道容量（超过后丢弃新记录）
+    pub buffer_size: usize,
+    /// 分区保留天数（更早的分区被删除）
+    pub retention_days: u32,
+}
+
+impl Default for HookAuditConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 500,
+            flush_interval: Duration::from_secs(1),
+            buffer_size: 10_000,
+            retention_days: 7,
+        }
+    }
+}
+
+/// 异步批量审计记录器
+pub struct BatchingHookAuditRecorder {
+    tx: mpsc::Sender<HookAuditEntry>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl BatchingHookAuditRecorder {
+    /// 创建记录器并启动后台落库任务
+    pub fn start(repository: Arc<dyn HookAuditRepository>, config: &HookAuditConfig) -> Arc<Self> {
+        let (tx, rx) = mpsc::channel(config.buffer_size.max(1));
+        let dropped = Arc::new(AtomicU64::new(0));
+        tokio::spawn(run_flush_loop(
+            rx,
+            repository,
+            config.batch_size.max(1),
+            config.flush_interval,
+            dropped.clone(),
+        ));
+        Arc::new(Self { tx, dropped })
+    }
+}
+
+impl HookAuditRecorder for BatchingHookAuditRecorder {
+    fn record(&self, entry: HookAuditEntry) {
+        if self.tx.try_send(entry).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+async fn run_flush_loop(
+    mut rx: mpsc::Receiver<HookAuditEntry>,
+    repository: Arc<dyn HookAuditRepository>,
+    batch_size: usize,
+    flush_interval: Duration,
+    dropped: Arc<AtomicU64>,
+) {
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut ticker = tokio::time::interval(flush_interval.max(Duration::from_millis(1)));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // 第一次 tick 立即完成，跳过
+    ticker.tick().await;
+
+    loop {
+        tokio::select! {
+            entry = rx.recv() => match entry {
+                Some(entry) => {
+                    batch.push(entry);
+                    if batch.len() >= batch_size {
+                        flush(repository.as_ref(), &mut batch).await;
+                    }
+                }
+                None => {
+                    // 记录器已释放，写完剩余记录后退出
+                    flush(repository.as_ref(), &mut batch).await;
+                    break;
+                }
+            },
+            _ = ticker.tick() => {
+                flush(repository.as_ref(), &mut batch).await;
+                let dropped = dropped.swap(0, Ordering::Relaxed);
+                if dropped > 0 {
+                    tracing::warn!(dropped, "Hook audit buffer full, entries dropped");
+                }
+            }
+        }
+    }
+}
+
+async fn flush(repository: &dyn HookAuditRepository, batch: &mut Vec<HookAuditEntry>) {
+    if batch.is_empty() {
+        return;
+    }
+    if let Err(e) = repository.insert_batch(batch).await {
+        tracing::warn!(count = batch.len(), error = %e, "Failed to persist hook audit entries");
+    }
+    batch.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{HookAuditDecision, HookAuditQuery};
+    use std::sync::Mutex;
+    use std::time::SystemTime;
+
+    #[derive(Default)]
+    struct MemoryAuditRepository {
+        batches: Mutex<Vec<usize>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HookAuditRepository for MemoryAuditRepository {
+        async fn insert_batch(&self, entries: &[HookAuditEntry]) -> anyhow::Result<()> {
+            self.batches.lock().unwrap().push(entries.len());
+            Ok(())
+        }
+
+        async fn query(&self, _query: &HookAuditQuery) -> anyhow::Result<Vec<HookAuditEntry>> {
+            Ok(vec![])
+        }
+    }
+
+    fn entry(hook_name: &str) -> HookAuditEntry {
+        HookAuditEntry {
+            id: 0,
+            hook_type: "pre_send".to_string(),
+            hook_name: hook_name.to_string(),
+            tenant_id: Some("tenant-a".to_string()),
+            message_id: Some("msg-1".to_string()),
+            request_id: "req-1".to_string(),
+            decision: HookAuditDecision::Continue,
+            reason: None,
+            latency_ms: 1,
+            executed_at: SystemTime::now(),
+            config_revision: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batching_recorder_flushes_by_size_and_interval() {
+        let repository = Arc::new(MemoryAuditRepository::default());
+        let recorder = BatchingHookAuditRecorder::start(
+            repository.clone(),
+            &HookAuditConfig {
+                batch_size: 2,
+                flush_interval: Duration::from_millis(20),
+                ..Default::default()
+            },
+        );
+
+        for name in ["a", "b", "c"] {
+            recorder.record(entry(name));
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // 攒满一批立即写入，不足一批的由定时刷新写入
+        let batches = repository.batches.lock().unwrap();
+        assert_eq!(batches.iter().sum::<usize>(), 3);
+        assert!(batches.iter().all(|&size| size <= 2));
+    }
+}
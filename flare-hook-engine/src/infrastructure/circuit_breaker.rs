@@ -0,0 +1,529 @@
+//! # Hook熔断器
+//!
+//! 为每个Hook适配器提供独立的熔断保护，避免下游gRPC/WebHook服务异常拖慢整条消息链路。
+//!
+//! 状态流转：
+//! - `Closed`：正常放行，按滑动窗口统计失败率，超过阈值后进入 `Open`
+//! - `Open`：直接跳过Hook，等待 `open_duration` 后进入 `HalfOpen`
+//! - `HalfOpen`：放行有限数量的探测请求，全部成功后恢复 `Closed`，任一失败重新 `Open`
+//!
+//! 放行的调用持有 [`CircuitPermit`]。调用被取消（如调用方的链路预算超时）不代表Hook异常，
+//! 许可在 Drop 中只归还半开探测名额、不计入失败率，避免名额泄漏导致熔断器永远停留在 `HalfOpen`，
+//! 也避免少量紧预算请求把健康的Hook熔断。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::domain::model::CircuitState;
+
+/// 熔断器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    /// 是否启用熔断
+    pub enabled: bool,
+    /// 滑动窗口大小（最近N次调用）
+    pub window_size: usize,
+    /// 窗口内最少调用次数（不足时不触发熔断）
+    pub min_requests: usize,
+    /// 失败率阈值（0.0-1.0）
+    pub failure_rate_threshold: f64,
+    /// 熔断持续时间（毫秒），到期后进入半开状态
+    pub open_duration_ms: u64,
+    /// 半开状态下的探测请求数（全部成功后关闭熔断）
+    pub half_open_probes: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_size: 20,
+            min_requests: 10,
+            failure_rate_threshold: 0.5,
+            open_duration_ms: 30_000, // 30秒
+            half_open_probes: 3,
+        }
+    }
+}
+
+/// 熔断器状态快照（用于统计展示）
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBreakerSnapshot {
+    pub state: CircuitState,
+    /// 当前窗口内的失败率
+    pub failure_rate: f64,
+    /// 累计熔断次数
+    pub open_count: u64,
+    /// 因熔断被跳过的调用次数
+    pub rejected_count: u64,
+}
+
+struct BreakerInner {
+    state: CircuitState,
+    /// 最近调用结果（true 表示失败）
+    window: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    /// 半开状态下已放行的探测数
+    probes_in_flight: u32,
+    /// 半开状态下已成功的探测数
+    probe_successes: u32,
+    /// 每次进入半开状态递增，用于识别被丢弃的许可是否属于当前这轮探测
+    half_open_epoch: u64,
+    open_count: u64,
+    rejected_count: u64,
+}
+
+/// 单个Hook的熔断器
+pub struct HookCircuitBreaker {
+    name: String,
+    config: CircuitBreakerConfig,
+    inner: Mutex<BreakerInner>,
+}
+
+impl HookCircuitBreaker {
+    pub fn new(name: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        Self {
+            name: name.into(),
+            inner: Mutex::new(BreakerInner {
+                state: CircuitState::Closed,
+                window: VecDeque::with_capacity(config.window_size),
+                opened_at: None,
+                probes_in_flight: 0,
+                probe_successes: 0,
+                half_open_epoch: 0,
+                open_count: 0,
+                rejected_count: 0,
+            }),
+            config,
+        }
+    }
+
+    /// 判断本次调用是否放行
+    ///
+    /// 返回 None 时调用方应跳过该Hook；放行时返回的许可须通过 [`CircuitPermit::success`] 或
+    /// [`CircuitPermit::failure`] 记录结果，未记录即被丢弃的许可不计入结果，只归还探测名额
+    pub fn try_acquire(&self) -> Option<CircuitPermit<'_>> {
+        self.acquire().map(|probe_epoch| CircuitPermit {
+            breaker: self,
+            probe_epoch,
+            completed: false,
+        })
+    }
+
+    /// 放行时返回 `Some`，内层为半开探测所属的轮次（非探测调用为 None）
+    fn acquire(&self) -> Option<Option<u64>> {
+        if !self.config.enabled {
+            return Some(None);
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        match inner.state {
+            CircuitState::Closed => Some(None),
+            CircuitState::Open => {
+                let elapsed = inner
+                    .opened_at
+                    .map(|at| at.elapsed())
+                    .unwrap_or_default();
+                if elapsed >= Duration::from_millis(self.config.open_duration_ms) {
+                    info!(hook = %self.name, "Hook circuit half-open, probing");
+                    inner.state = CircuitState::HalfOpen;
+                    inner.probes_in_flight = 1;
+                    inner.probe_successes = 0;
+                    inner.half_open_epoch += 1;
+                    Some(Some(inner.half_open_epoch))
+                } else {
+                    inner.rejected_count += 1;
+                    None
+                }
+            }
+            CircuitState::HalfOpen => {
+                if inner.probes_in_flight < self.config.half_open_probes.max(1) {
+                    inner.probes_in_flight += 1;
+                    Some(Some(inner.half_open_epoch))
+                } else {
+                    inner.rejected_count += 1;
+                    None
+                }
+            }
+        }
+    }
+
+    /// 归还未记录结果的许可（调用被取消）：不计入失败率，只释放本轮半开探测名额
+    fn release(&self, probe_epoch: Option<u64>) {
+        let Some(epoch) = probe_epoch else {
+            return;
+        };
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state == CircuitState::HalfOpen && inner.half_open_epoch == epoch {
+            inner.probes_in_flight = inner.probes_in_flight.saturating_sub(1);
+        }
+    }
+
+    /// 记录调用成功
+    pub fn record_success(&self) {
+        if !self.config.enabled {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        match inner.state {
+            CircuitState::Closed => self.push_outcome(&mut inner, false),
+            CircuitState::HalfOpen => {
+                inner.probe_successes += 1;
+                if inner.probe_successes >= self.config.half_open_probes.max(1) {
+                    info!(hook = %self.name, "Hook circuit closed, hook recovered");
+                    inner.state = CircuitState::Closed;
+                    inner.window.clear();
+                    inner.opened_at = None;
+                    inner.probes_in_flight = 0;
+                    inner.probe_successes = 0;
+                }
+            }
+            // 熔断前已发出的请求返回，不影响状态
+            CircuitState::Open => {}
+        }
+    }
+
+    /// 记录调用失败
+    pub fn record_failure(&self) {
+        if !self.config.enabled {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        match inner.state {
+            CircuitState::Closed => {
+                self.push_outcome(&mut inner, true);
+                let total = inner.window.len();
+                if total >= self.config.min_requests.max(1) {
+                    let rate = failure_rate(&inner.window);
+                    if rate >= self.config.failure_rate_threshold {
+                        warn!(
+                            hook = %self.name,
+                            failure_rate = rate,
+                            window = total,
+                            "Hook circuit opened due to high failure rate"
+                        );
+                        self.trip(&mut inner);
+                    }
+                }
+            }
+            CircuitState::HalfOpen => {
+                warn!(hook = %self.name, "Hook circuit probe failed, reopening");
+                self.trip(&mut inner);
+            }
+            CircuitState::Open => {}
+        }
+    }
+
+    /// 当前状态
+    pub fn state(&self) -> CircuitState {
+        self.inner.lock().unwrap().state
+    }
+
+    /// 状态快照
+    pub fn snapshot(&self) -> CircuitBreakerSnapshot {
+        let inner = self.inner.lock().unwrap();
+        CircuitBreakerSnapshot {
+            state: inner.state,
+            failure_rate: failure_rate(&inner.window),
+            open_count: inner.open_count,
+            rejected_count: inner.rejected_count,
+        }
+    }
+
+    fn push_outcome(&self, inner: &mut BreakerInner, failed: bool) {
+        inner.window.push_back(failed);
+        while inner.window.len() > self.config.window_size.max(1) {
+            inner.window.pop_front();
+        }
+    }
+
+    fn trip(&self, inner: &mut BreakerInner) {
+        inner.state = CircuitState::Open;
+        inner.opened_at = Some(Instant::now());
+        inner.window.clear();
+        inner.probes_in_flight = 0;
+        inner.probe_successes = 0;
+        inner.open_count += 1;
+    }
+}
+
+/// 熔断器放行许可
+///
+/// 未调用 [`success`](Self::success) / [`failure`](Self::failure) 即被丢弃时（调用被取消或超出调用方预算）
+/// 不影响熔断统计，只归还半开探测名额
+pub struct CircuitPermit<'a> {
+    breaker: &'a HookCircuitBreaker,
+    /// 半开探测所属轮次（非探测许可为 None）
+    probe_epoch: Option<u64>,
+    completed: bool,
+}
+
+impl CircuitPermit<'_> {
+    /// 记录调用成功
+    pub fn success(mut self) {
+        self.completed = true;
+        self.breaker.record_success();
+    }
+
+    /// 记录调用失败
+    pub fn failure(mut self) {
+        self.completed = true;
+        self.breaker.record_failure();
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.breaker.release(self.probe_epoch);
+        }
+    }
+}
+
+fn failure_rate(window: &VecDeque<bool>) -> f64 {
+    if window.is_empty() {
+        return 0.0;
+    }
+    window.iter().filter(|failed| **failed).count() as f64 / window.len() as f64
+}
+
+/// 熔断器注册表
+///
+/// 按 `hook_type:name` 保存熔断器，执行计划重建时复用已有熔断器，保证熔断状态不因配置刷新而丢失
+pub struct CircuitBreakerRegistry {
+    config: CircuitBreakerConfig,
+    breakers: RwLock<HashMap<String, Arc<HookCircuitBreaker>>>,
+}
+
+impl CircuitBreakerRegistry {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            breakers: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 获取或创建熔断器
+    pub fn get_or_create(&self, key: &str) -> Arc<HookCircuitBreaker> {
+        if let Some(breaker) = self.breakers.read().unwrap().get(key) {
+            return breaker.clone();
+        }
+
+        self.breakers
+            .write()
+            .unwrap()
+            .entry(key.to_string())
+            .or_insert_with(|| Arc::new(HookCircuitBreaker::new(key, self.config.clone())))
+            .clone()
+    }
+
+    /// 获取熔断器状态快照
+    pub fn snapshot(&self, key: &str) -> Option<CircuitBreakerSnapshot> {
+        self.breakers
+            .read()
+            .unwrap()
+            .get(key)
+            .map(|breaker| breaker.snapshot())
+    }
+
+    /// 获取所有熔断器状态快照
+    pub fn snapshots(&self) -> HashMap<String, CircuitBreakerSnapshot> {
+        self.breakers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(key, breaker)| (key.clone(), breaker.snapshot()))
+            .collect()
+    }
+
+    /// 只保留指定的熔断器（清理已删除Hook的熔断器）
+    pub fn retain(&self, keys: &HashSet<String>) {
+        self.breakers
+            .write()
+            .unwrap()
+            .retain(|key, _| keys.contains(key));
+    }
+}
+
+impl Default for CircuitBreakerRegistry {
+    fn default() -> Self {
+        Self::new(CircuitBreakerConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            enabled: true,
+            window_size: 4,
+            min_requests: 4,
+            failure_rate_threshold: 0.5,
+            open_duration_ms: 0,
+            half_open_probes: 2,
+        }
+    }
+
+    #[test]
+    fn test_opens_when_failure_rate_exceeds_threshold() {
+        let breaker = HookCircuitBreaker::new(
+            "pre_send:test",
+            CircuitBreakerConfig {
+                open_duration_ms: 60_000,
+                ..test_config()
+            },
+        );
+
+        breaker.record_success();
+        breaker.record_failure();
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        // 窗口满4次，失败率 0.5，触发熔断
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.try_acquire().is_none());
+
+        let snapshot = breaker.snapshot();
+        assert_eq!(snapshot.open_count, 1);
+        assert_eq!(snapshot.rejected_count, 1);
+    }
+
+    #[test]
+    fn test_half_open_probes_close_circuit() {
+        let breaker = HookCircuitBreaker::new("pre_send:test", test_config());
+        for _ in 0..4 {
+            breaker.record_failure();
+        }
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // open_duration 为0，下一次调用进入半开探测
+        let first = breaker.try_acquire().unwrap();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        let second = breaker.try_acquire().unwrap();
+        // 探测数已满，其余请求继续跳过
+        assert!(breaker.try_acquire().is_none());
+
+        first.success();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        second.success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire().is_some());
+    }
+
+    #[test]
+    fn test_half_open_probe_failure_reopens() {
+        let breaker = HookCircuitBreaker::new("pre_send:test", test_config());
+        for _ in 0..4 {
+            breaker.record_failure();
+        }
+
+        breaker.try_acquire().unwrap().failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert_eq!(breaker.snapshot().open_count, 2);
+    }
+
+    #[test]
+    fn test_dropped_probe_releases_half_open_slot() {
+        let breaker = HookCircuitBreaker::new("pre_send:test", test_config());
+        for _ in 0..4 {
+            breaker.record_failure();
+        }
+
+        // 探测被取消（如链路预算超时）：许可丢弃只归还名额，保持半开
+        let probe = breaker.try_acquire().unwrap();
+        let other = breaker.try_acquire().unwrap();
+        assert!(breaker.try_acquire().is_none());
+        drop(probe);
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        drop(other);
+
+        // 名额归还后可以再次探测并恢复
+        let first = breaker.try_acquire().unwrap();
+        let second = breaker.try_acquire().unwrap();
+        first.success();
+        second.success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_budget_timeouts_do_not_open_circuit() {
+        let breaker = HookCircuitBreaker::new("pre_send:test", test_config());
+
+        // 调用方预算耗尽取消了整个窗口的调用：Hook本身没有失败，熔断保持关闭
+        for _ in 0..8 {
+            let call = async {
+                let permit = breaker.try_acquire().unwrap();
+                std::future::pending::<()>().await;
+                permit.success();
+            };
+            let timed_out = tokio::time::timeout(Duration::from_millis(10), call).await;
+            assert!(timed_out.is_err());
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.snapshot().failure_rate, 0.0);
+
+        // 只有显式记录的失败才会触发熔断
+        for _ in 0..4 {
+            breaker.try_acquire().unwrap().failure();
+        }
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_probe_cancelled_by_timeout_releases_slot() {
+        let breaker = HookCircuitBreaker::new("pre_send:test", test_config());
+        for _ in 0..4 {
+            breaker.record_failure();
+        }
+
+        let probe = async {
+            let permit = breaker.try_acquire().unwrap();
+            std::future::pending::<()>().await;
+            permit.success();
+        };
+        let timed_out = tokio::time::timeout(Duration::from_millis(10), probe).await;
+        assert!(timed_out.is_err());
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.try_acquire().is_some());
+    }
+
+    #[test]
+    fn test_disabled_breaker_always_allows() {
+        let breaker = HookCircuitBreaker::new(
+            "pre_send:test",
+            CircuitBreakerConfig {
+                enabled: false,
+                ..test_config()
+            },
+        );
+        for _ in 0..10 {
+            breaker.record_failure();
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire().is_some());
+    }
+
+    #[test]
+    fn test_registry_reuses_and_retains_breakers() {
+        let registry = CircuitBreakerRegistry::new(test_config());
+        let a = registry.get_or_create("pre_send:a");
+        let a2 = registry.get_or_create("pre_send:a");
+        assert!(Arc::ptr_eq(&a, &a2));
+        registry.get_or_create("pre_send:b");
+
+        let keep: HashSet<String> = ["pre_send:a".to_string()].into_iter().collect();
+        registry.retain(&keep);
+        assert!(registry.snapshot("pre_send:a").is_some());
+        assert!(registry.snapshot("pre_send:b").is_none());
+    }
+}
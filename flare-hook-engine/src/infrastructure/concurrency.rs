@@ -0,0 +1,296 @@
+//! # Hook并发限制
+//!
+//! 每次Hook执行前依次获取三级许可：
+//! - 租户许可：单租户的在途执行数上限，突发流量只在本租户队列中排队，不会占满全局容量
+//! - Hook许可：单个Hook的在途执行数上限（`max_concurrency`，未配置时使用默认值）
+//! - 全局许可：整个Hook引擎的在途执行数上限
+//!
+//! 信号量按 FIFO 顺序分配许可，同一级别内先到先得；排队超过 `max_queue_wait_ms` 时放弃执行。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// 租户信号量数量超过该值时清理空闲租户
+const TENANT_PRUNE_THRESHOLD: usize = 1024;
+
+/// 并发限制配置（上限为0表示不限制）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConcurrencyConfig {
+    /// 是否启用并发限制
+    pub enabled: bool,
+    /// 全局在途执行数上限
+    pub global_max_in_flight: usize,
+    /// 单个Hook的默认在途执行数上限（Hook配置了 `max_concurrency` 时以配置为准）
+    pub hook_max_in_flight: usize,
+    /// 单个租户的在途执行数上限
+    pub tenant_max_in_flight: usize,
+    /// 最长排队等待时间（毫秒，0表示一直等待）
+    pub max_queue_wait_ms: u64,
+}
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            global_max_in_flight: 1024,
+            hook_max_in_flight: 256,
+            tenant_max_in_flight: 256,
+            max_queue_wait_ms: 1000,
+        }
+    }
+}
+
+/// 许可获取失败的限制级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConcurrencyScope {
+    Tenant,
+    Hook,
+    Global,
+}
+
+impl ConcurrencyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConcurrencyScope::Tenant => "tenant",
+            ConcurrencyScope::Hook => "hook",
+            ConcurrencyScope::Global => "global",
+        }
+    }
+}
+
+/// 排队超时错误
+#[derive(Debug, thiserror::Error)]
+#[error("hook concurrency limit exceeded ({}), waited {waited:?}", scope.as_str())]
+pub struct ConcurrencyLimitExceeded {
+    pub scope: ConcurrencyScope,
+    pub waited: Duration,
+}
+
+/// 一次Hook执行持有的许可，drop 时释放
+#[derive(Debug)]
+pub struct HookPermit {
+    _permits: Vec<OwnedSemaphorePermit>,
+    /// 排队等待时间
+    pub waited: Duration,
+}
+
+/// 带容量的信号量（Hook的 `max_concurrency` 变更后按新容量重建）
+struct SizedSemaphore {
+    capacity: usize,
+    semaphore: Arc<Semaphore>,
+}
+
+impl SizedSemaphore {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            semaphore: Arc::new(Semaphore::new(capacity)),
+        }
+    }
+
+    fn idle(&self) -> bool {
+        self.semaphore.available_permits() == self.capacity
+    }
+}
+
+/// Hook并发限制器
+pub struct HookConcurrencyLimiter {
+    config: ConcurrencyConfig,
+    global: Option<Arc<Semaphore>>,
+    hooks: Mutex<HashMap<String, SizedSemaphore>>,
+    tenants: Mutex<HashMap<String, SizedSemaphore>>,
+}
+
+impl HookConcurrencyLimiter {
+    pub fn new(config: ConcurrencyConfig) -> Self {
+        let global = (config.global_max_in_flight > 0)
+            .then(|| Arc::new(Semaphore::new(config.global_max_in_flight)));
+        Self {
+            config,
+            global,
+            hooks: Mutex::new(HashMap::new()),
+            tenants: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &ConcurrencyConfig {
+        &self.config
+    }
+
+    /// 依次获取租户、Hook、全局许可
+    ///
+    /// `hook_key` 为 `hook_type:name`，`max_concurrency` 为Hook自身配置的上限
+    pub async fn acquire(
+        &self,
+        hook_key: &str,
+        max_concurrency: Option<u32>,
+        tenant_id: Option<&str>,
+    ) -> Result<HookPermit, ConcurrencyLimitExceeded> {
+        let started = Instant::now();
+        let deadline = (self.config.max_queue_wait_ms > 0)
+            .then(|| started + Duration::from_millis(self.config.max_queue_wait_ms));
+
+        let hook_capacity = max_concurrency
+            .map(|max| max as usize)
+            .unwrap_or(self.config.hook_max_in_flight);
+        let stages = [
+            (
+                ConcurrencyScope::Tenant,
+                tenant_id.and_then(|tenant| self.tenant_semaphore(tenant)),
+            ),
+            (
+                ConcurrencyScope::Hook,
+                self.hook_semaphore(hook_key, hook_capacity),
+            ),
+            (ConcurrencyScope::Global, self.global.clone()),
+        ];
+
+        let mut permits = Vec::with_capacity(stages.len());
+        for (scope, semaphore) in stages {
+            let Some(semaphore) = semaphore else {
+                continue;
+            };
+            let acquired = match deadline {
+                Some(deadline) => {
+                    tokio::time::timeout_at(deadline.into(), semaphore.acquire_owned())
+                        .await
+                        .ok()
+                }
+                None => Some(semaphore.acquire_owned().await),
+            };
+            match acquired {
+                // 信号量从不关闭
+                Some(Ok(permit)) => permits.push(permit),
+                _ => {
+                    return Err(ConcurrencyLimitExceeded {
+                        scope,
+                        waited: started.elapsed(),
+                    });
+                }
+            }
+        }
+
+        Ok(HookPermit {
+            _permits: permits,
+            waited: started.elapsed(),
+        })
+    }
+
+    fn hook_semaphore(&self, hook_key: &str, capacity: usize) -> Option<Arc<Semaphore>> {
+        if capacity == 0 {
+            return None;
+        }
+        let mut hooks = self.hooks.lock().unwrap();
+        let entry = hooks
+            .entry(hook_key.to_string())
+            .or_insert_with(|| SizedSemaphore::new(capacity));
+        if entry.capacity != capacity {
+            // 容量变更后新请求使用新信号量，旧许可随执行结束释放
+            *entry = SizedSemaphore::new(capacity);
+        }
+        Some(entry.semaphore.clone())
+    }
+
+    fn tenant_semaphore(&self, tenant_id: &str) -> Option<Arc<Semaphore>> {
+        let capacity = self.config.tenant_max_in_flight;
+        if capacity == 0 {
+            return None;
+        }
+        let mut tenants = self.tenants.lock().unwrap();
+        if let Some(entry) = tenants.get(tenant_id) {
+            return Some(entry.semaphore.clone());
+        }
+        if tenants.len() >= TENANT_PRUNE_THRESHOLD {
+            tenants.retain(|_, entry| !entry.idle());
+        }
+        let entry = SizedSemaphore::new(capacity);
+        let semaphore = entry.semaphore.clone();
+        tenants.insert(tenant_id.to_string(), entry);
+        Some(semaphore)
+    }
+}
+
+impl Default for HookConcurrencyLimiter {
+    fn default() -> Self {
+        Self::new(ConcurrencyConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(global: usize, hook: usize, tenant: usize) -> ConcurrencyConfig {
+        ConcurrencyConfig {
+            enabled: true,
+            global_max_in_flight: global,
+            hook_max_in_flight: hook,
+            tenant_max_in_flight: tenant,
+            max_queue_wait_ms: 20,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tenant_burst_does_not_exhaust_global_capacity() {
+        let limiter = HookConcurrencyLimiter::new(config(4, 0, 2));
+        let _a = limiter
+            .acquire("pre_send:a", None, Some("t1"))
+            .await
+            .unwrap();
+        let _b = limiter
+            .acquire("pre_send:a", None, Some("t1"))
+            .await
+            .unwrap();
+
+        let err = limiter
+            .acquire("pre_send:a", None, Some("t1"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.scope, ConcurrencyScope::Tenant);
+
+        // 其他租户仍可使用剩余的全局容量
+        assert!(
+            limiter
+                .acquire("pre_send:a", None, Some("t2"))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hook_max_concurrency_overrides_default() {
+        let limiter = HookConcurrencyLimiter::new(config(0, 8, 0));
+        let permit = limiter
+            .acquire("delivery:slow", Some(1), None)
+            .await
+            .unwrap();
+
+        let err = limiter
+            .acquire("delivery:slow", Some(1), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.scope, ConcurrencyScope::Hook);
+
+        drop(permit);
+        assert!(
+            limiter
+                .acquire("delivery:slow", Some(1), None)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_global_cap() {
+        let limiter = HookConcurrencyLimiter::new(config(1, 0, 0));
+        let _permit = limiter.acquire("pre_send:a", None, None).await.unwrap();
+
+        let err = limiter.acquire("pre_send:b", None, None).await.unwrap_err();
+        assert_eq!(err.scope, ConcurrencyScope::Global);
+    }
+}
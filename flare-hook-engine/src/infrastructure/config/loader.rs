@@ -4,12 +4,14 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use base64::{Engine as _, engine::general_purpose};
+use tokio::sync::Notify;
 use tracing::{debug, error, info, warn};
 
-use crate::domain::model::HookConfig;
+use crate::domain::model::{HookConfig, HookTransportConfig};
 use crate::infrastructure::persistence::postgres_config::PostgresHookConfigRepository;
 use flare_server_core::{
     BackendType, DiscoveryConfig, DiscoveryFactory, KvBackend, KvStore, ServiceDiscover,
@@ -40,6 +42,16 @@ impl ConfigLoaderItem {
             ConfigLoaderItem::ConfigCenter(loader) => loader.load().await,
         }
     }
+
+    /// 启动推送监听，配置变更时通过 `notify` 触发重新加载
+    ///
+    /// 仅配置中心加载器支持推送，其他加载器依赖定时刷新
+    pub async fn watch(&self, notify: Arc<Notify>) -> Result<()> {
+        match self {
+            ConfigLoaderItem::ConfigCenter(loader) => loader.watch(notify).await,
+            ConfigLoaderItem::File(_) | ConfigLoaderItem::Database(_) => Ok(()),
+        }
+    }
 }
 
 /// Hook配置合并器
@@ -57,25 +69,35 @@ impl ConfigMerger {
 
         // 按顺序合并，后面的配置会覆盖前面的同名配置
         for config in configs {
-            Self::merge_hook_list(&mut merged.pre_send, config.pre_send);
-            Self::merge_hook_list(&mut merged.post_send, config.post_send);
-            Self::merge_hook_list(&mut merged.delivery, config.delivery);
-            Self::merge_hook_list(&mut merged.recall, config.recall);
-            Self::merge_hook_list(&mut merged.session_create, config.session_create);
-            Self::merge_hook_list(&mut merged.session_update, config.session_update);
-            Self::merge_hook_list(&mut merged.session_delete, config.session_delete);
-            Self::merge_hook_list(&mut merged.user_login, config.user_login);
-            Self::merge_hook_list(&mut merged.user_logout, config.user_logout);
-            Self::merge_hook_list(&mut merged.user_online, config.user_online);
-            Self::merge_hook_list(&mut merged.user_offline, config.user_offline);
-            Self::merge_hook_list(&mut merged.push_pre_send, config.push_pre_send);
-            Self::merge_hook_list(&mut merged.push_post_send, config.push_post_send);
-            Self::merge_hook_list(&mut merged.push_delivery, config.push_delivery);
+            Self::merge_into(&mut merged, config);
         }
 
         merged
     }
 
+    /// 将 `config` 合并到 `merged`（租户配置按租户分别合并）
+    fn merge_into(merged: &mut HookConfig, config: HookConfig) {
+        Self::merge_hook_list(&mut merged.pre_send, config.pre_send);
+        Self::merge_hook_list(&mut merged.post_send, config.post_send);
+        Self::merge_hook_list(&mut merged.delivery, config.delivery);
+        Self::merge_hook_list(&mut merged.recall, config.recall);
+        Self::merge_hook_list(&mut merged.session_create, config.session_create);
+        Self::merge_hook_list(&mut merged.session_update, config.session_update);
+        Self::merge_hook_list(&mut merged.session_delete, config.session_delete);
+        Self::merge_hook_list(&mut merged.user_login, config.user_login);
+        Self::merge_hook_list(&mut merged.user_logout, config.user_logout);
+        Self::merge_hook_list(&mut merged.user_online, config.user_online);
+        Self::merge_hook_list(&mut merged.user_offline, config.user_offline);
+        Self::merge_hook_list(&mut merged.push_pre_send, config.push_pre_send);
+        Self::merge_hook_list(&mut merged.push_post_send, config.push_post_send);
+        Self::merge_hook_list(&mut merged.push_delivery, config.push_delivery);
+        merged.defaults = merged.defaults.overlay(&config.defaults);
+
+        for (tenant_id, tenant_config) in config.tenants {
+            Self::merge_into(merged.tenants.entry(tenant_id).or_default(), tenant_config);
+        }
+    }
+
     fn merge_hook_list(
         target: &mut Vec<crate::domain::model::HookConfigItem>,
         source: Vec<crate::domain::model::HookConfigItem>,
@@ -119,10 +141,52 @@ impl ConfigValidator {
             Self::validate_hook(hook)?;
         }
 
+        // 需要同步返回决策的Hook不能使用Kafka传输
+        for (hook_type, hooks) in [
+            ("pre_send", &config.pre_send),
+            ("recall", &config.recall),
+            ("push_pre_send", &config.push_pre_send),
+        ] {
+            for hook in hooks {
+                Self::validate_transport(hook_type, hook)?;
+            }
+        }
+
+        for (tenant_id, tenant_config) in &config.tenants {
+            if tenant_id.is_empty() {
+                anyhow::bail!("Tenant id of tenant hook config cannot be empty");
+            }
+            Self::validate(tenant_config)
+                .with_context(|| format!("Invalid hook config for tenant {}", tenant_id))?;
+        }
+
         Ok(())
     }
 
-    fn validate_hook(hook: &crate::domain::model::HookConfigItem) -> Result<()> {
+    /// 验证Hook类型是否支持该传输方式（Kafka传输不等待结果，仅适用于异步Hook）
+    pub fn validate_transport(hook_type: &str, hook: &crate::domain::model::HookConfigItem) -> Result<()> {
+        if matches!(hook.transport, HookTransportConfig::Kafka { .. })
+            && matches!(hook_type, "pre_send" | "recall" | "push_pre_send")
+        {
+            anyhow::bail!(
+                "Hook {} uses kafka transport, which is not supported for {} hooks",
+                hook.name,
+                hook_type
+            );
+        }
+        if matches!(hook.transport, HookTransportConfig::Local { rate_limit: Some(_), .. })
+            && hook_type != "pre_send"
+        {
+            anyhow::bail!(
+                "Hook {} uses the built-in rate limit, which is only supported for pre_send hooks",
+                hook.name
+            );
+        }
+        Ok(())
+    }
+
+    /// 验证单个Hook配置
+    pub fn validate_hook(hook: &crate::domain::model::HookConfigItem) -> Result<()> {
         if hook.name.is_empty() {
             anyhow::bail!("Hook name cannot be empty");
         }
@@ -135,6 +199,135 @@ impl ConfigValidator {
             anyhow::bail!("Hook timeout must be between 1ms and 30000ms");
         }
 
+        if let Some(group) = hook.group.as_deref() {
+            if flare_im_core::HookGroup::parse(group).is_none() {
+                anyhow::bail!(
+                    "Invalid hook group '{}' for hook {}, expected validation/critical/business",
+                    group,
+                    hook.name
+                );
+            }
+        }
+
+        hook.selector
+            .compile()
+            .with_context(|| format!("Hook {} has an invalid selector", hook.name))?;
+
+        if let HookTransportConfig::Nats { url, subject, timeout_ms, .. } = &hook.transport {
+            if url.is_empty() || subject.is_empty() {
+                anyhow::bail!("Hook {} nats transport requires url and subject", hook.name);
+            }
+            if matches!(timeout_ms, Some(timeout) if *timeout == 0 || *timeout > 30000) {
+                anyhow::bail!("Hook {} nats timeout must be between 1ms and 30000ms", hook.name);
+            }
+        }
+
+        if let HookTransportConfig::Local {
+            target,
+            script: Some(script),
+            ..
+        } = &hook.transport
+        {
+            crate::infrastructure::adapters::script::ScriptHookAdapter::new(target.clone(), script)
+                .with_context(|| format!("Hook {} has an invalid script", hook.name))?;
+        }
+
+        if let HookTransportConfig::Local {
+            script,
+            rate_limit: Some(rate_limit),
+            ..
+        } = &hook.transport
+        {
+            if script.is_some() {
+                anyhow::bail!("Hook {} cannot combine script and rate_limit", hook.name);
+            }
+            rate_limit
+                .validate()
+                .with_context(|| format!("Hook {} has an invalid rate_limit", hook.name))?;
+        }
+
+        if let HookTransportConfig::Kafka { profile, topic, .. } = &hook.transport {
+            if profile.is_empty() || topic.is_empty() {
+                anyhow::bail!("Hook {} kafka transport requires profile and topic", hook.name);
+            }
+        }
+
+        if let HookTransportConfig::Federated {
+            endpoint,
+            timeout_ms,
+            decision_cache,
+            fallback,
+            ..
+        } = &hook.transport
+        {
+            if endpoint.is_empty() {
+                anyhow::bail!("Hook {} federated transport requires an endpoint", hook.name);
+            }
+            if matches!(timeout_ms, Some(timeout) if *timeout == 0 || *timeout > 30000) {
+                anyhow::bail!("Hook {} federated timeout must be between 1ms and 30000ms", hook.name);
+            }
+            if matches!(decision_cache, Some(cache) if cache.ttl_ms == 0 || cache.max_entries == 0) {
+                anyhow::bail!(
+                    "Hook {} federated decision_cache ttl_ms and max_entries must be greater than 0",
+                    hook.name
+                );
+            }
+            if fallback.link_retry_ms == 0 {
+                anyhow::bail!("Hook {} federated link_retry_ms must be greater than 0", hook.name);
+            }
+        }
+
+        if let Some(cache) = hook.cache.as_ref() {
+            if cache.ttl_ms == 0 || cache.max_entries == 0 {
+                anyhow::bail!(
+                    "Hook {} cache ttl_ms and max_entries must be greater than 0",
+                    hook.name
+                );
+            }
+        }
+
+        if let Some(sampling) = hook.sampling.as_ref() {
+            if sampling.per_minute == 0 {
+                anyhow::bail!("Hook {} sampling per_minute must be greater than 0", hook.name);
+            }
+        }
+
+        if let Some(retry) = hook.retry.as_ref() {
+            if retry.retry_interval_ms == 0 || retry.max_interval_ms < retry.retry_interval_ms {
+                anyhow::bail!(
+                    "Hook {} retry_interval_ms must be greater than 0 and not exceed max_interval_ms",
+                    hook.name
+                );
+            }
+            if !(0.0..=1.0).contains(&retry.jitter) {
+                anyhow::bail!("Hook {} retry jitter must be between 0.0 and 1.0", hook.name);
+            }
+        }
+
+        if let Some(canary) = hook.canary.as_ref() {
+            if canary.version.is_empty() || hook.version.as_deref() == Some(canary.version.as_str()) {
+                anyhow::bail!(
+                    "Hook {} canary version must be non-empty and differ from the hook version",
+                    hook.name
+                );
+            }
+            if canary.percentage > 100 {
+                anyhow::bail!("Hook {} canary percentage must be between 0 and 100", hook.name);
+            }
+            let unsupported = |transport: &HookTransportConfig| {
+                matches!(
+                    transport,
+                    HookTransportConfig::Local { .. }
+                        | HookTransportConfig::Nats { .. }
+                        | HookTransportConfig::Kafka { .. }
+                        | HookTransportConfig::Federated { .. }
+                )
+            };
+            if unsupported(&hook.transport) || unsupported(&canary.transport) {
+                anyhow::bail!("Hook {} canary only supports grpc/webhook transport", hook.name);
+            }
+        }
+
         Ok(())
     }
 }
@@ -194,15 +387,18 @@ impl DatabaseConfigLoader {
 
 impl ConfigLoader for DatabaseConfigLoader {
     async fn load(&self) -> Result<HookConfig> {
-        let config = self
-            .repository
-            .load_all(self.tenant_id.as_deref())
-            .await
-            .context("Failed to load hook config from database")?;
+        // 指定租户时只加载该租户的Hook链（单租户部署）；
+        // 否则加载全局Hook链和所有租户的专属配置，执行时按请求租户选择
+        let config = match self.tenant_id.as_deref() {
+            Some(tenant_id) => self.repository.load_all(Some(tenant_id)).await,
+            None => self.repository.load_all_tenants().await,
+        }
+        .context("Failed to load hook config from database")?;
 
         info!(
             tenant_id = ?self.tenant_id,
             hooks_count = config.pre_send.len() + config.post_send.len() + config.delivery.len() + config.recall.len(),
+            tenants = config.tenants.len(),
             "Loaded hook config from database"
         );
 
@@ -292,6 +488,83 @@ impl ConfigCenterLoader {
     }
 }
 
+impl ConfigCenterLoader {
+    /// 启动etcd Watch，配置键变更时通过 `notify` 触发重新加载
+    ///
+    /// Watch 断开后按指数退避自动重连；Consul 暂不支持推送，依赖定时刷新
+    pub async fn watch(&self, notify: Arc<Notify>) -> Result<()> {
+        if !self.endpoint.starts_with("etcd://") {
+            debug!(
+                endpoint = %self.endpoint,
+                "Push watch not supported for config center, using periodic refresh"
+            );
+            return Ok(());
+        }
+
+        let (host, port) = self.parse_endpoint()?;
+        let endpoints = vec![format!("http://{}:{}", host, port)];
+        let config_key = self.config_key.clone();
+
+        tokio::spawn(async move {
+            let mut backoff = Duration::from_secs(1);
+            loop {
+                match Self::watch_etcd(&endpoints, &config_key, &notify).await {
+                    Ok(()) => {
+                        warn!(config_key = %config_key, "etcd watch stream closed, reconnecting");
+                        backoff = Duration::from_secs(1);
+                    }
+                    Err(e) => {
+                        warn!(
+                            config_key = %config_key,
+                            error = %e,
+                            retry_in_secs = backoff.as_secs(),
+                            "etcd watch failed, retrying"
+                        );
+                    }
+                }
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(Duration::from_secs(30));
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn watch_etcd(endpoints: &[String], config_key: &str, notify: &Notify) -> Result<()> {
+        use etcd_client::Client;
+
+        let mut client = Client::connect(endpoints, None)
+            .await
+            .context("Failed to connect to etcd")?;
+        // watcher 需要保持存活，drop 后 Watch 会被取消
+        let (_watcher, mut stream) = client
+            .watch(config_key, None)
+            .await
+            .context("Failed to watch config in etcd")?;
+
+        info!(config_key = %config_key, "Watching hook config in etcd");
+
+        // 建立（或重建）Watch 期间可能错过变更，主动触发一次重新加载
+        notify.notify_one();
+
+        while let Some(resp) = stream.message().await.context("etcd watch stream error")? {
+            if resp.canceled() {
+                anyhow::bail!("etcd watch canceled by server");
+            }
+            if !resp.events().is_empty() {
+                debug!(
+                    config_key = %config_key,
+                    events = resp.events().len(),
+                    "Hook config changed in etcd"
+                );
+                notify.notify_one();
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl std::fmt::Debug for ConfigCenterLoader {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ConfigCenterLoader")
@@ -1,13 +1,17 @@
 //! # Hook配置监听器
 //!
 //! 监听配置变更并自动重新加载配置
+//!
+//! 支持两种触发方式：
+//! - **定时刷新**：按 `refresh_interval` 轮询所有加载器（兜底）
+//! - **推送触发**：配置中心（etcd）Watch 到变更后立即重新加载
 
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
-use tokio::sync::RwLock;
-use tracing::{error, info, warn};
+use tokio::sync::{Notify, RwLock, watch};
+use tracing::{debug, error, info, warn};
 
 use crate::domain::model::HookConfig;
 use crate::infrastructure::config::loader::{ConfigLoaderItem, ConfigMerger, ConfigValidator};
@@ -19,14 +23,21 @@ pub struct ConfigWatcher {
     loaders: Vec<Arc<ConfigLoaderItem>>,
     current_config: Arc<RwLock<HookConfig>>,
     refresh_interval: Duration,
+    /// 推送触发的重新加载信号
+    reload_notify: Arc<Notify>,
+    /// 配置版本（每次配置发生变化时递增，用于通知订阅者）
+    version_tx: Arc<watch::Sender<u64>>,
 }
 
 impl ConfigWatcher {
     pub fn new(loaders: Vec<Arc<ConfigLoaderItem>>, refresh_interval: Duration) -> Self {
+        let (version_tx, _) = watch::channel(0);
         Self {
             loaders,
             current_config: Arc::new(RwLock::new(HookConfig::default())),
             refresh_interval,
+            reload_notify: Arc::new(Notify::new()),
+            version_tx: Arc::new(version_tx),
         }
     }
 
@@ -35,20 +46,49 @@ impl ConfigWatcher {
         self.current_config.read().await.clone()
     }
 
+    /// 当前配置版本号
+    pub fn version(&self) -> u64 {
+        *self.version_tx.borrow()
+    }
+
+    /// 订阅配置变更（接收到的值为配置版本号）
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.version_tx.subscribe()
+    }
+
+    /// 触发一次立即重新加载（不等待刷新间隔）
+    pub fn trigger_reload(&self) {
+        self.reload_notify.notify_one();
+    }
+
     /// 启动配置监听
     pub async fn start(&self) -> Result<()> {
         // 初始加载
         self.reload().await?;
 
+        // 启动配置中心推送监听
+        for loader in &self.loaders {
+            if let Err(e) = loader.watch(Arc::clone(&self.reload_notify)).await {
+                warn!(error = %e, "Failed to start config push watch, falling back to polling");
+            }
+        }
+
         // 启动定时刷新任务
         let config = Arc::clone(&self.current_config);
         let loaders = self.loaders.clone();
         let interval = self.refresh_interval;
+        let reload_notify = Arc::clone(&self.reload_notify);
+        let version_tx = Arc::clone(&self.version_tx);
 
         tokio::spawn(async move {
             let mut interval_timer = tokio::time::interval(interval);
             loop {
-                interval_timer.tick().await;
+                tokio::select! {
+                    _ = interval_timer.tick() => {}
+                    _ = reload_notify.notified() => {
+                        debug!("Hook config reload triggered by push notification");
+                    }
+                }
 
                 match Self::load_all(&loaders).await {
                     Ok(new_config) => {
@@ -59,8 +99,9 @@ impl ConfigWatcher {
                         }
 
                         // 更新配置
-                        *config.write().await = new_config;
-                        info!("Hook config reloaded successfully");
+                        if Self::commit(&config, &version_tx, new_config).await {
+                            info!("Hook config reloaded successfully");
+                        }
                     }
                     Err(e) => {
                         error!(error = %e, "Failed to reload hook config");
@@ -76,10 +117,39 @@ impl ConfigWatcher {
     pub async fn reload(&self) -> Result<()> {
         let new_config = Self::load_all(&self.loaders).await?;
         ConfigValidator::validate(&new_config)?;
-        *self.current_config.write().await = new_config;
+        Self::commit(&self.current_config, &self.version_tx, new_config).await;
         Ok(())
     }
 
+    /// 回滚到指定配置（订阅者应用新配置失败时使用，不通知订阅者）
+    pub async fn restore(&self, config: HookConfig) {
+        *self.current_config.write().await = config;
+    }
+
+    /// 提交新配置，配置未变化时返回 false
+    async fn commit(
+        current: &RwLock<HookConfig>,
+        version_tx: &watch::Sender<u64>,
+        new_config: HookConfig,
+    ) -> bool {
+        let mut guard = current.write().await;
+        if Self::is_same(&guard, &new_config) {
+            return false;
+        }
+        *guard = new_config;
+        drop(guard);
+
+        version_tx.send_modify(|version| *version += 1);
+        true
+    }
+
+    fn is_same(a: &HookConfig, b: &HookConfig) -> bool {
+        match (serde_json::to_value(a), serde_json::to_value(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+
     async fn load_all(loaders: &[Arc<ConfigLoaderItem>]) -> Result<HookConfig> {
         let mut configs = Vec::new();
 
@@ -95,3 +165,56 @@ impl ConfigWatcher {
         Ok(ConfigMerger::merge(configs))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reload_skips_unchanged_config() {
+        let watcher = ConfigWatcher::new(Vec::new(), Duration::from_secs(60));
+        let mut changes = watcher.subscribe();
+
+        watcher.reload().await.unwrap();
+        assert_eq!(watcher.version(), 0);
+        assert!(!changes.has_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_commit_bumps_version_on_change() {
+        let watcher = ConfigWatcher::new(Vec::new(), Duration::from_secs(60));
+        let mut changes = watcher.subscribe();
+
+        let mut config = HookConfig::default();
+        config.pre_send.push(
+            serde_json::from_value(serde_json::json!({
+                "name": "hook-a",
+                "enabled": true,
+                "priority": 10,
+                "timeout_ms": 100,
+                "selector": {},
+                "transport": { "type": "local", "target": "noop" }
+            }))
+            .unwrap(),
+        );
+
+        assert!(
+            ConfigWatcher::commit(&watcher.current_config, &watcher.version_tx, config.clone())
+                .await
+        );
+        assert_eq!(watcher.version(), 1);
+        assert!(changes.has_changed().unwrap());
+
+        // 相同配置不会再次通知
+        changes.borrow_and_update();
+        assert!(
+            !ConfigWatcher::commit(&watcher.current_config, &watcher.version_tx, config).await
+        );
+        assert!(!changes.has_changed().unwrap());
+
+        // 回滚不通知订阅者
+        watcher.restore(HookConfig::default()).await;
+        assert!(watcher.get_config().await.pre_send.is_empty());
+        assert!(!changes.has_changed().unwrap());
+    }
+}
@@ -0,0 +1,155 @@
+//! # Hook死信队列
+//!
+//! 将重试耗尽的PostSend/Delivery执行发布到Kafka，消息中保留原始上下文和Hook输入，便于后续重放。
+
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use anyhow::{Context as _, Result, anyhow};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde_json::{Value, json};
+
+use crate::domain::model::{HookDeadLetter, HookDeadLetterPayload};
+use crate::domain::repository::HookDeadLetterPublisher;
+
+/// 默认死信Topic
+pub const DEFAULT_DEAD_LETTER_TOPIC: &str = "flare.im.hook.dlq";
+
+/// 死信队列配置
+#[derive(Debug, Clone)]
+pub struct DeadLetterConfig {
+    /// Kafka地址
+    pub kafka_bootstrap: String,
+    /// 死信Topic
+    pub topic: String,
+}
+
+/// Kafka死信发布器
+pub struct KafkaHookDeadLetterPublisher {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl KafkaHookDeadLetterPublisher {
+    pub fn new(config: &DeadLetterConfig) -> Result<Arc<Self>> {
+        struct SimpleProducerConfig {
+            bootstrap: String,
+        }
+
+        impl flare_server_core::kafka::KafkaProducerConfig for SimpleProducerConfig {
+            fn kafka_bootstrap(&self) -> &str {
+                &self.bootstrap
+            }
+
+            fn message_timeout_ms(&self) -> u64 {
+                5000
+            }
+        }
+
+        let producer_config = SimpleProducerConfig {
+            bootstrap: config.kafka_bootstrap.clone(),
+        };
+        let producer = flare_server_core::kafka::build_kafka_producer(
+            &producer_config as &dyn flare_server_core::kafka::KafkaProducerConfig,
+        )
+        .map_err(|e| anyhow!("Failed to create Kafka producer: {}", e))?;
+
+        Ok(Arc::new(Self {
+            producer,
+            topic: config.topic.clone(),
+        }))
+    }
+}
+
+/// 构建死信消息（JSON）
+pub fn dead_letter_message(letter: &HookDeadLetter) -> Value {
+    let (message_id, input) = match &letter.payload {
+        HookDeadLetterPayload::PostSend { record, draft } => (
+            record.message_id.as_str(),
+            json!({ "record": record, "draft": draft }),
+        ),
+        HookDeadLetterPayload::Delivery { event } => {
+            (event.message_id.as_str(), json!({ "event": event }))
+        }
+    };
+
+    json!({
+        "hook_name": letter.hook_name,
+        "hook_type": letter.payload.hook_type(),
+        "message_id": message_id,
+        "context": {
+            "request_id": letter.ctx.request_id(),
+            "trace_id": letter.ctx.trace_id(),
+            "tenant_id": letter.ctx.tenant_id(),
+            "session_id": letter.ctx.session_id(),
+        },
+        "input": input,
+        "attempts": letter.attempts,
+        "error": letter.last_error,
+        "failed_at": letter
+            .failed_at
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+    })
+}
+
+#[async_trait::async_trait]
+impl HookDeadLetterPublisher for KafkaHookDeadLetterPublisher {
+    async fn publish(&self, letter: &HookDeadLetter) -> Result<()> {
+        let message = dead_letter_message(letter);
+        let payload = serde_json::to_vec(&message).context("Failed to serialize hook dead letter")?;
+        let key = message["message_id"].as_str().unwrap_or_default();
+
+        let record = FutureRecord::to(&self.topic).key(key).payload(&payload);
+        self.producer
+            .send(record, Duration::from_secs(0))
+            .await
+            .map_err(|(e, _)| anyhow!("Failed to publish hook dead letter: {}", e))?;
+
+        tracing::info!(
+            hook = %letter.hook_name,
+            message_id = key,
+            attempts = letter.attempts,
+            error = %letter.last_error,
+            "Hook execution sent to DLQ"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flare_im_core::DeliveryEvent;
+    use flare_server_core::context::Context;
+    use std::collections::HashMap;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_dead_letter_message_keeps_original_input() {
+        let letter = HookDeadLetter {
+            hook_name: "delivery-audit".to_string(),
+            ctx: Context::with_request_id("req-1".to_string()),
+            payload: HookDeadLetterPayload::Delivery {
+                event: DeliveryEvent {
+                    message_id: "msg-1".to_string(),
+                    user_id: "user-1".to_string(),
+                    channel: "websocket".to_string(),
+                    delivered_at: SystemTime::now(),
+                    metadata: HashMap::new(),
+                },
+            },
+            attempts: 4,
+            last_error: "timeout".to_string(),
+            failed_at: SystemTime::now(),
+        };
+
+        let message = dead_letter_message(&letter);
+        assert_eq!(message["hook_type"], "delivery");
+        assert_eq!(message["message_id"], "msg-1");
+        assert_eq!(message["context"]["request_id"], "req-1");
+        assert_eq!(message["input"]["event"]["user_id"], "user-1");
+        assert_eq!(message["attempts"], 4);
+    }
+}
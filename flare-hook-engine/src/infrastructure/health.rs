@@ -0,0 +1,306 @@
+//! # Hook端点健康检查
+//!
+//! 后台定期探测 gRPC/WebHook Hook 端点，连续失败的端点标记为降级（degraded）：
+//! - 降级期间非必需Hook（`require_success = false`）直接跳过，必需Hook照常调用
+//! - 连续探测成功后恢复健康
+//! - 健康状态按Hook统计键（`hook_type:name[@tenant_id]`）保存，配置刷新后保留，
+//!   并同步到 `HookStatistics` 和 Prometheus 指标 `hook_endpoint_healthy`
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use flare_im_core::metrics::HookHealthMetrics;
+
+use crate::domain::model::HookHealthStatus;
+
+static METRICS: Lazy<HookHealthMetrics> = Lazy::new(HookHealthMetrics::new);
+
+/// 健康检查配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookHealthConfig {
+    /// 是否启用健康检查
+    pub enabled: bool,
+    /// 探测间隔（毫秒）
+    pub interval_ms: u64,
+    /// 单次探测超时（毫秒）
+    pub timeout_ms: u64,
+    /// 连续失败多少次标记为降级
+    pub unhealthy_threshold: u32,
+    /// 降级后连续成功多少次恢复健康
+    pub healthy_threshold: u32,
+}
+
+impl Default for HookHealthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: 10_000,
+            timeout_ms: 2_000,
+            unhealthy_threshold: 3,
+            healthy_threshold: 2,
+        }
+    }
+}
+
+/// 健康状态快照（用于统计展示）
+#[derive(Debug, Clone, Default)]
+pub struct HookHealthSnapshot {
+    pub status: HookHealthStatus,
+    /// 当前连续失败次数
+    pub consecutive_failures: u32,
+    /// 累计降级次数
+    pub degraded_count: u64,
+    /// 因降级被跳过的调用次数
+    pub skipped_count: u64,
+    /// 最近一次探测失败的原因
+    pub last_error: Option<String>,
+}
+
+#[derive(Default)]
+struct HealthInner {
+    status: HookHealthStatus,
+    consecutive_failures: u32,
+    consecutive_successes: u32,
+    degraded_count: u64,
+    skipped_count: u64,
+    last_error: Option<String>,
+}
+
+/// 单个Hook端点的健康状态
+pub struct HookHealth {
+    name: String,
+    config: HookHealthConfig,
+    inner: Mutex<HealthInner>,
+}
+
+impl HookHealth {
+    pub fn new(name: impl Into<String>, config: HookHealthConfig) -> Self {
+        let name = name.into();
+        METRICS.endpoint_healthy.with_label_values(&[&name]).set(1);
+        Self {
+            name,
+            config,
+            inner: Mutex::new(HealthInner::default()),
+        }
+    }
+
+    /// 健康状态键（`hook_type:name[@tenant_id]`）
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 记录一次探测结果
+    pub fn record_probe(&self, result: Result<(), String>) {
+        let mut inner = self.inner.lock().unwrap();
+        match result {
+            Ok(()) => {
+                inner.consecutive_failures = 0;
+                inner.consecutive_successes += 1;
+                if inner.status == HookHealthStatus::Degraded
+                    && inner.consecutive_successes >= self.config.healthy_threshold.max(1)
+                {
+                    info!(hook = %self.name, "Hook endpoint recovered");
+                    inner.status = HookHealthStatus::Healthy;
+                    inner.last_error = None;
+                    METRICS
+                        .endpoint_healthy
+                        .with_label_values(&[&self.name])
+                        .set(1);
+                }
+            }
+            Err(error) => {
+                METRICS
+                    .probe_failures_total
+                    .with_label_values(&[&self.name])
+                    .inc();
+                inner.consecutive_successes = 0;
+                inner.consecutive_failures += 1;
+                if inner.status == HookHealthStatus::Healthy
+                    && inner.consecutive_failures >= self.config.unhealthy_threshold.max(1)
+                {
+                    warn!(
+                        hook = %self.name,
+                        failures = inner.consecutive_failures,
+                        error = %error,
+                        "Hook endpoint marked degraded"
+                    );
+                    inner.status = HookHealthStatus::Degraded;
+                    inner.degraded_count += 1;
+                    METRICS
+                        .endpoint_healthy
+                        .with_label_values(&[&self.name])
+                        .set(0);
+                }
+                inner.last_error = Some(error);
+            }
+        }
+    }
+
+    /// 是否跳过本次调用（端点降级且Hook非必需时跳过，并计入跳过次数）
+    pub fn should_skip(&self, require_success: bool) -> bool {
+        if !self.config.enabled || require_success {
+            return false;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.status != HookHealthStatus::Degraded {
+            return false;
+        }
+        inner.skipped_count += 1;
+        METRICS
+            .degraded_skipped_total
+            .with_label_values(&[&self.name])
+            .inc();
+        true
+    }
+
+    /// 当前状态
+    pub fn status(&self) -> HookHealthStatus {
+        self.inner.lock().unwrap().status
+    }
+
+    /// 状态快照
+    pub fn snapshot(&self) -> HookHealthSnapshot {
+        let inner = self.inner.lock().unwrap();
+        HookHealthSnapshot {
+            status: inner.status,
+            consecutive_failures: inner.consecutive_failures,
+            degraded_count: inner.degraded_count,
+            skipped_count: inner.skipped_count,
+            last_error: inner.last_error.clone(),
+        }
+    }
+}
+
+/// 健康状态注册表
+///
+/// 与熔断器注册表一样按Hook统计键保存，执行计划重建时复用已有状态
+pub struct HookHealthRegistry {
+    config: HookHealthConfig,
+    entries: RwLock<HashMap<String, Arc<HookHealth>>>,
+}
+
+impl HookHealthRegistry {
+    pub fn new(config: HookHealthConfig) -> Self {
+        Self {
+            config,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &HookHealthConfig {
+        &self.config
+    }
+
+    /// 获取或创建健康状态
+    pub fn get_or_create(&self, key: &str) -> Arc<HookHealth> {
+        if let Some(health) = self.entries.read().unwrap().get(key) {
+            return health.clone();
+        }
+
+        self.entries
+            .write()
+            .unwrap()
+            .entry(key.to_string())
+            .or_insert_with(|| Arc::new(HookHealth::new(key, self.config.clone())))
+            .clone()
+    }
+
+    /// 获取健康状态快照
+    pub fn snapshot(&self, key: &str) -> Option<HookHealthSnapshot> {
+        self.entries
+            .read()
+            .unwrap()
+            .get(key)
+            .map(|health| health.snapshot())
+    }
+
+    /// 获取所有健康状态快照
+    pub fn snapshots(&self) -> HashMap<String, HookHealthSnapshot> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(key, health)| (key.clone(), health.snapshot()))
+            .collect()
+    }
+
+    /// 只保留指定的健康状态（清理已删除Hook的状态和指标）
+    pub fn retain(&self, keys: &HashSet<String>) {
+        self.entries.write().unwrap().retain(|key, _| {
+            let keep = keys.contains(key);
+            if !keep {
+                let _ = METRICS.endpoint_healthy.remove_label_values(&[key]);
+            }
+            keep
+        });
+    }
+}
+
+impl Default for HookHealthRegistry {
+    fn default() -> Self {
+        Self::new(HookHealthConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> HookHealthConfig {
+        HookHealthConfig {
+            unhealthy_threshold: 2,
+            healthy_threshold: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_degrades_after_consecutive_failures_and_recovers() {
+        let health = HookHealth::new("pre_send:health-test", test_config());
+
+        health.record_probe(Err("connection refused".to_string()));
+        health.record_probe(Ok(()));
+        health.record_probe(Err("connection refused".to_string()));
+        assert_eq!(health.status(), HookHealthStatus::Healthy);
+
+        health.record_probe(Err("timeout".to_string()));
+        assert_eq!(health.status(), HookHealthStatus::Degraded);
+
+        // 降级期间只跳过非必需Hook
+        assert!(health.should_skip(false));
+        assert!(!health.should_skip(true));
+
+        health.record_probe(Ok(()));
+        assert_eq!(health.status(), HookHealthStatus::Degraded);
+        health.record_probe(Ok(()));
+        assert_eq!(health.status(), HookHealthStatus::Healthy);
+        assert!(!health.should_skip(false));
+
+        let snapshot = health.snapshot();
+        assert_eq!(snapshot.degraded_count, 1);
+        assert_eq!(snapshot.skipped_count, 1);
+        assert_eq!(snapshot.last_error, None);
+    }
+
+    #[test]
+    fn test_registry_reuses_and_retains_entries() {
+        let registry = HookHealthRegistry::new(test_config());
+        let a = registry.get_or_create("post_send:health-a");
+        assert!(Arc::ptr_eq(
+            &a,
+            &registry.get_or_create("post_send:health-a")
+        ));
+        registry.get_or_create("post_send:health-b");
+
+        let keep: HashSet<String> = ["post_send:health-a".to_string()].into_iter().collect();
+        registry.retain(&keep);
+        assert!(registry.snapshot("post_send:health-a").is_some());
+        assert!(registry.snapshot("post_send:health-b").is_none());
+    }
+}
@@ -3,6 +3,14 @@
 //! 提供Hook配置加载、适配器、持久化等基础设施实现
 
 pub mod adapters;
+pub mod audit;
+pub mod circuit_breaker;
+pub mod concurrency;
 pub mod config;
+pub mod dead_letter;
+pub mod health;
 pub mod monitoring;
 pub mod persistence;
+pub mod result_cache;
+pub mod sampling;
+pub mod usage;
@@ -1,6 +1,9 @@
 //! # Hook监控统计层
 //!
 //! 提供Hook执行指标收集、执行记录和告警触发能力。
+//!
+//! 执行统计按租户隔离：统计键为 `hook_type:name@tenant_id`（与租户Hook的熔断器键格式一致），
+//! 不同租户的同名Hook互不影响。
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -9,41 +12,174 @@ use tokio::sync::RwLock;
 use tracing::warn;
 
 use crate::domain::model::{HookExecutionResult, HookStatistics};
+use crate::infrastructure::circuit_breaker::{CircuitBreakerRegistry, CircuitBreakerSnapshot};
+use crate::infrastructure::health::{HookHealthRegistry, HookHealthSnapshot};
+
+/// 统计键（`hook_name` 为 `hook_type:name`，有租户时追加 `@tenant_id`）
+pub fn statistics_key(tenant_id: Option<&str>, hook_name: &str) -> String {
+    match tenant_id {
+        Some(tenant_id) => format!("{}@{}", hook_name, tenant_id),
+        None => hook_name.to_string(),
+    }
+}
 
 /// 指标收集器
 pub struct MetricsCollector {
     statistics: Arc<RwLock<HashMap<String, HookStatistics>>>,
+    /// 因请求预算耗尽被截断的执行次数（按Hook类型）
+    deadline_truncated_runs: Arc<RwLock<HashMap<String, u64>>>,
+    /// 因链路预算耗尽被跳过的Hook数（按Hook类型）
+    chain_budget_skipped: Arc<RwLock<HashMap<String, u64>>>,
+    /// 熔断器注册表（可选，用于在统计信息中展示熔断状态）
+    circuit_breakers: Option<Arc<CircuitBreakerRegistry>>,
+    /// 端点健康状态注册表（可选，用于在统计信息中展示健康状态）
+    health: Option<Arc<HookHealthRegistry>>,
 }
 
 impl MetricsCollector {
     pub fn new() -> Self {
         Self {
             statistics: Arc::new(RwLock::new(HashMap::new())),
+            deadline_truncated_runs: Arc::new(RwLock::new(HashMap::new())),
+            chain_budget_skipped: Arc::new(RwLock::new(HashMap::new())),
+            circuit_breakers: None,
+            health: None,
         }
     }
 
-    /// 记录Hook执行结果
-    pub async fn record(&self, result: &HookExecutionResult) {
+    /// 设置熔断器注册表
+    pub fn with_circuit_breakers(mut self, circuit_breakers: Arc<CircuitBreakerRegistry>) -> Self {
+        self.circuit_breakers = Some(circuit_breakers);
+        self
+    }
+
+    /// 设置端点健康状态注册表
+    pub fn with_health_registry(mut self, health: Arc<HookHealthRegistry>) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// 记录Hook执行结果（按执行请求的租户统计）
+    pub async fn record(&self, tenant_id: Option<&str>, result: &HookExecutionResult) {
         let mut stats = self.statistics.write().await;
         let hook_stats = stats
-            .entry(result.hook_name.clone())
+            .entry(statistics_key(tenant_id, &result.hook_name))
             .or_insert_with(Default::default);
         hook_stats.update(result);
     }
 
-    /// 获取Hook统计信息
-    pub async fn get_statistics(&self, hook_name: &str) -> Option<HookStatistics> {
-        let stats = self.statistics.read().await;
-        stats.get(hook_name).cloned()
+    /// 记录一次因请求预算耗尽被截断的执行（`skipped_hooks` 为被跳过的Hook统计键）
+    pub async fn record_deadline_truncated(&self, hook_type: &str, skipped_hooks: &[String]) {
+        *self
+            .deadline_truncated_runs
+            .write()
+            .await
+            .entry(hook_type.to_string())
+            .or_default() += 1;
+
+        let mut stats = self.statistics.write().await;
+        for hook_key in skipped_hooks {
+            stats.entry(hook_key.clone()).or_default().deadline_skipped_count += 1;
+        }
+    }
+
+    /// 各Hook类型因请求预算耗尽被截断的执行次数
+    pub async fn deadline_truncated_runs(&self) -> HashMap<String, u64> {
+        self.deadline_truncated_runs.read().await.clone()
+    }
+
+    /// 记录因链路预算耗尽被跳过的Hook（`skipped_hooks` 为被跳过的Hook统计键）
+    pub async fn record_chain_budget_skipped(&self, hook_type: &str, skipped_hooks: &[String]) {
+        *self
+            .chain_budget_skipped
+            .write()
+            .await
+            .entry(hook_type.to_string())
+            .or_default() += skipped_hooks.len() as u64;
+
+        let mut stats = self.statistics.write().await;
+        for hook_key in skipped_hooks {
+            stats
+                .entry(hook_key.clone())
+                .or_default()
+                .chain_budget_skipped_count += 1;
+        }
+    }
+
+    /// 各Hook类型因链路预算耗尽被跳过的Hook数
+    pub async fn chain_budget_skipped(&self) -> HashMap<String, u64> {
+        self.chain_budget_skipped.read().await.clone()
+    }
+
+    /// 获取租户的Hook统计信息（`hook_name` 格式为 hook_type:name）
+    ///
+    /// 熔断和健康状态优先取租户专属Hook的状态，不存在时取全局Hook的状态
+    pub async fn get_statistics(
+        &self,
+        tenant_id: Option<&str>,
+        hook_name: &str,
+    ) -> Option<HookStatistics> {
+        let key = statistics_key(tenant_id, hook_name);
+        let stats = self.statistics.read().await.get(&key).cloned();
+        let snapshot = self.circuit_breakers.as_ref().and_then(|breakers| {
+            breakers
+                .snapshot(&key)
+                .or_else(|| breakers.snapshot(hook_name))
+        });
+        let health = self
+            .health
+            .as_ref()
+            .and_then(|health| health.snapshot(&key).or_else(|| health.snapshot(hook_name)));
+
+        match (stats, snapshot, health) {
+            (None, None, None) => None,
+            (stats, snapshot, health) => {
+                let mut stats = stats.unwrap_or_default();
+                if let Some(snapshot) = snapshot {
+                    apply_circuit_snapshot(&mut stats, &snapshot);
+                }
+                if let Some(health) = health {
+                    apply_health_snapshot(&mut stats, &health);
+                }
+                Some(stats)
+            }
+        }
     }
 
-    /// 获取所有Hook统计信息
+    /// 获取所有Hook统计信息（按统计键）
     pub async fn get_all_statistics(&self) -> HashMap<String, HookStatistics> {
-        let stats = self.statistics.read().await;
-        stats.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+        let mut all: HashMap<String, HookStatistics> = {
+            let stats = self.statistics.read().await;
+            stats.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+        };
+
+        if let Some(ref breakers) = self.circuit_breakers {
+            for (hook_name, snapshot) in breakers.snapshots() {
+                apply_circuit_snapshot(all.entry(hook_name).or_default(), &snapshot);
+            }
+        }
+        if let Some(ref health) = self.health {
+            for (hook_name, snapshot) in health.snapshots() {
+                apply_health_snapshot(all.entry(hook_name).or_default(), &snapshot);
+            }
+        }
+
+        all
     }
 }
 
+fn apply_circuit_snapshot(stats: &mut HookStatistics, snapshot: &CircuitBreakerSnapshot) {
+    stats.circuit_state = snapshot.state;
+    stats.circuit_open_count = snapshot.open_count;
+    stats.circuit_rejected_count = snapshot.rejected_count;
+}
+
+fn apply_health_snapshot(stats: &mut HookStatistics, snapshot: &HookHealthSnapshot) {
+    stats.health_status = snapshot.status;
+    stats.health_skipped_count = snapshot.skipped_count;
+    stats.last_health_error = snapshot.last_error.clone();
+}
+
 impl Default for MetricsCollector {
     fn default() -> Self {
         Self::new()
@@ -172,9 +308,9 @@ mod tests {
 
         // 记录成功结果
         collector
-            .record(&create_test_result("test-hook", true, 100))
+            .record(None, &create_test_result("test-hook", true, 100))
             .await;
-        let stats = collector.get_statistics("test-hook").await.unwrap();
+        let stats = collector.get_statistics(None, "test-hook").await.unwrap();
         assert_eq!(stats.total_count, 1);
         assert_eq!(stats.success_count, 1);
         assert_eq!(stats.failure_count, 0);
@@ -183,9 +319,9 @@ mod tests {
 
         // 记录失败结果
         collector
-            .record(&create_test_result("test-hook", false, 200))
+            .record(None, &create_test_result("test-hook", false, 200))
             .await;
-        let stats = collector.get_statistics("test-hook").await.unwrap();
+        let stats = collector.get_statistics(None, "test-hook").await.unwrap();
         assert_eq!(stats.total_count, 2);
         assert_eq!(stats.success_count, 1);
         assert_eq!(stats.failure_count, 1);
@@ -200,13 +336,13 @@ mod tests {
         let collector = MetricsCollector::new();
 
         collector
-            .record(&create_test_result("hook-1", true, 100))
+            .record(None, &create_test_result("hook-1", true, 100))
             .await;
         collector
-            .record(&create_test_result("hook-2", true, 200))
+            .record(None, &create_test_result("hook-2", true, 200))
             .await;
         collector
-            .record(&create_test_result("hook-1", false, 150))
+            .record(None, &create_test_result("hook-1", false, 150))
             .await;
 
         let all_stats = collector.get_all_statistics().await;
@@ -272,19 +408,19 @@ mod tests {
         // 创建高失败率的情况
         for _ in 0..10 {
             collector
-                .record(&create_test_result("test-hook", true, 100))
+                .record(None, &create_test_result("test-hook", true, 100))
                 .await;
         }
         for _ in 0..10 {
             collector
-                .record(&create_test_result("test-hook", false, 200))
+                .record(None, &create_test_result("test-hook", false, 200))
                 .await;
         }
 
         // 创建低失败率的情况
         for _ in 0..10 {
             collector
-                .record(&create_test_result("good-hook", true, 50))
+                .record(None, &create_test_result("good-hook", true, 50))
                 .await;
         }
 
@@ -293,10 +429,75 @@ mod tests {
         trigger.check(&collector).await; // 应该警告 test-hook
     }
 
+    #[tokio::test]
+    async fn test_metrics_collector_circuit_state() {
+        use crate::domain::model::CircuitState;
+        use crate::infrastructure::circuit_breaker::CircuitBreakerConfig;
+
+        let breakers = Arc::new(CircuitBreakerRegistry::new(CircuitBreakerConfig {
+            min_requests: 1,
+            ..Default::default()
+        }));
+        let collector = MetricsCollector::new().with_circuit_breakers(breakers.clone());
+
+        breakers.get_or_create("pre_send:test-hook").record_failure();
+
+        let stats = collector
+            .get_statistics(None, "pre_send:test-hook")
+            .await
+            .unwrap();
+        assert_eq!(stats.circuit_state, CircuitState::Open);
+        assert_eq!(stats.circuit_open_count, 1);
+        assert_eq!(stats.total_count, 0);
+
+        let all_stats = collector.get_all_statistics().await;
+        assert_eq!(
+            all_stats.get("pre_send:test-hook").unwrap().circuit_state,
+            CircuitState::Open
+        );
+    }
+
     #[tokio::test]
     async fn test_metrics_collector_not_found() {
         let collector = MetricsCollector::new();
-        let stats = collector.get_statistics("non-existent").await;
+        let stats = collector.get_statistics(None, "non-existent").await;
         assert!(stats.is_none());
     }
+
+    #[tokio::test]
+    async fn test_metrics_collector_isolates_tenants() {
+        let collector = MetricsCollector::new();
+        let results = [
+            ("tenant-a", true, 100),
+            ("tenant-b", false, 200),
+            ("tenant-b", false, 300),
+        ];
+        for (tenant_id, success, latency_ms) in results {
+            let result = create_test_result("pre_send:audit", success, latency_ms);
+            collector.record(Some(tenant_id), &result).await;
+        }
+
+        let stats_a = collector
+            .get_statistics(Some("tenant-a"), "pre_send:audit")
+            .await
+            .unwrap();
+        assert_eq!(stats_a.total_count, 1);
+        assert_eq!(stats_a.failure_count, 0);
+
+        let stats_b = collector
+            .get_statistics(Some("tenant-b"), "pre_send:audit")
+            .await
+            .unwrap();
+        assert_eq!(stats_b.total_count, 2);
+        assert_eq!(stats_b.failure_count, 2);
+
+        assert!(
+            collector
+                .get_statistics(Some("tenant-c"), "pre_send:audit")
+                .await
+                .is_none()
+        );
+        let global = collector.get_statistics(None, "pre_send:audit").await;
+        assert!(global.is_none());
+    }
 }
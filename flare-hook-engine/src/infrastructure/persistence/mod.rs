@@ -1,7 +1,11 @@
 //! # Hook配置持久化
 //!
-//! 提供Hook配置的持久化能力
+//! 提供Hook配置、Hook执行审计日志和Hook资源用量的持久化能力
 
+pub mod postgres_audit;
 pub mod postgres_config;
+pub mod postgres_usage;
 
+pub use postgres_audit::PostgresHookAuditRepository;
 pub use postgres_config::PostgresHookConfigRepository;
+pub use postgres_usage::PostgresHookUsageRepository;
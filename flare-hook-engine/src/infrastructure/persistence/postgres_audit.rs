@@ -0,0 +1,196 @@
+//! # Hook执行审计PostgreSQL持久化
+//!
+//! 审计表按天分区（`hook_audit_logs_YYYYMMDD`），后台任务提前创建分区并删除超出保留期的分区。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+
+use crate::domain::model::{HookAuditDecision, HookAuditEntry, HookAuditQuery};
+use crate::domain::repository::HookAuditRepository;
+
+/// 审计写入为批量操作，少量连接即可
+const DEFAULT_MAX_CONNECTIONS: u32 = 4;
+/// 提前创建的分区天数
+const PARTITION_DAYS_AHEAD: i32 = 3;
+/// 分区维护间隔
+const PARTITION_MAINTENANCE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// 审计记录数据库行
+#[derive(Debug, Clone, FromRow)]
+struct HookAuditRow {
+    id: i64,
+    executed_at: DateTime<Utc>,
+    tenant_id: Option<String>,
+    hook_type: String,
+    hook_name: String,
+    message_id: Option<String>,
+    request_id: String,
+    decision: String,
+    reason: Option<String>,
+    latency_ms: i64,
+    config_revision: Option<i64>,
+}
+
+impl TryFrom<HookAuditRow> for HookAuditEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(row: HookAuditRow) -> Result<Self, Self::Error> {
+        let decision = HookAuditDecision::parse(&row.decision)
+            .with_context(|| format!("unknown hook audit decision: {}", row.decision))?;
+        Ok(Self {
+            id: row.id,
+            hook_type: row.hook_type,
+            hook_name: row.hook_name,
+            tenant_id: row.tenant_id,
+            message_id: row.message_id,
+            request_id: row.request_id,
+            decision,
+            reason: row.reason,
+            latency_ms: row.latency_ms.max(0) as u64,
+            executed_at: row.executed_at.into(),
+            config_revision: row.config_revision.map(|revision| revision.max(0) as u64),
+        })
+    }
+}
+
+/// Hook执行审计数据库仓储
+#[derive(Debug)]
+pub struct PostgresHookAuditRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresHookAuditRepository {
+    /// 创建数据库连接池
+    pub async fn new(database_url: &str) -> Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(DEFAULT_MAX_CONNECTIONS)
+            .connect(database_url)
+            .await
+            .context("failed to create database connection pool")?;
+
+        Ok(Self {
+            pool: Arc::new(pool),
+        })
+    }
+
+    /// 创建未来几天的分区，并删除超出保留期的分区
+    pub async fn maintain_partitions(&self, retention_days: u32) -> Result<()> {
+        sqlx::query(
+            "SELECT create_hook_audit_partition(CURRENT_DATE + day) FROM generate_series(0, $1) AS day",
+        )
+        .bind(PARTITION_DAYS_AHEAD)
+        .execute(&*self.pool)
+        .await
+        .context("failed to create hook audit partitions")?;
+
+        let dropped: i32 = sqlx::query_scalar("SELECT drop_hook_audit_partitions(CURRENT_DATE - $1)")
+            .bind(retention_days as i32)
+            .fetch_one(&*self.pool)
+            .await
+            .context("failed to drop expired hook audit partitions")?;
+        if dropped > 0 {
+            tracing::info!(dropped, retention_days, "Dropped expired hook audit partitions");
+        }
+        Ok(())
+    }
+
+    /// 启动后台分区维护任务（立即执行一次，之后每小时执行）
+    pub fn spawn_partition_maintenance(self: &Arc<Self>, retention_days: u32) {
+        let repository = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(PARTITION_MAINTENANCE_INTERVAL);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = repository.maintain_partitions(retention_days).await {
+                    tracing::warn!(error = %e, "Failed to maintain hook audit partitions");
+                }
+            }
+        });
+    }
+}
+
+#[async_trait::async_trait]
+impl HookAuditRepository for PostgresHookAuditRepository {
+    async fn insert_batch(&self, entries: &[HookAuditEntry]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut query = QueryBuilder::<Postgres>::new(
+            "INSERT INTO hook_audit_logs \
+             (executed_at, tenant_id, hook_type, hook_name, message_id, request_id, decision, reason, latency_ms, \
+             config_revision) ",
+        );
+        query.push_values(entries, |mut b, entry| {
+            b.push_bind(DateTime::<Utc>::from(entry.executed_at))
+                .push_bind(&entry.tenant_id)
+                .push_bind(&entry.hook_type)
+                .push_bind(&entry.hook_name)
+                .push_bind(&entry.message_id)
+                .push_bind(&entry.request_id)
+                .push_bind(entry.decision.as_str())
+                .push_bind(&entry.reason)
+                .push_bind(entry.latency_ms as i64)
+                .push_bind(entry.config_revision.map(|revision| revision as i64));
+        });
+
+        query
+            .build()
+            .execute(&*self.pool)
+            .await
+            .context("failed to insert hook audit entries")?;
+        Ok(())
+    }
+
+    async fn query(&self, query: &HookAuditQuery) -> Result<Vec<HookAuditEntry>> {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "SELECT id, executed_at, tenant_id, hook_type, hook_name, message_id, request_id, \
+             decision, reason, latency_ms, config_revision FROM hook_audit_logs WHERE 1=1",
+        );
+
+        if let Some(ref tenant_id) = query.tenant_id {
+            builder.push(" AND tenant_id = ").push_bind(tenant_id);
+        }
+        if let Some(ref message_id) = query.message_id {
+            builder.push(" AND message_id = ").push_bind(message_id);
+        }
+        if let Some(ref hook_type) = query.hook_type {
+            builder.push(" AND hook_type = ").push_bind(hook_type);
+        }
+        if let Some(ref hook_name) = query.hook_name {
+            builder.push(" AND hook_name = ").push_bind(hook_name);
+        }
+        if let Some(decision) = query.decision {
+            builder.push(" AND decision = ").push_bind(decision.as_str());
+        }
+        // 时间条件用于分区裁剪
+        if let Some(start_time) = query.start_time {
+            builder
+                .push(" AND executed_at >= ")
+                .push_bind(DateTime::<Utc>::from(start_time));
+        }
+        if let Some(end_time) = query.end_time {
+            builder
+                .push(" AND executed_at <= ")
+                .push_bind(DateTime::<Utc>::from(end_time));
+        }
+
+        builder
+            .push(" ORDER BY executed_at DESC, id DESC LIMIT ")
+            .push_bind(query.limit.max(1) as i64);
+
+        let rows = builder
+            .build_query_as::<HookAuditRow>()
+            .fetch_all(&*self.pool)
+            .await
+            .context("failed to query hook audit entries")?;
+
+        rows.into_iter().map(HookAuditEntry::try_from).collect()
+    }
+}
+
@@ -9,12 +9,42 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 use sqlx::postgres::PgPoolOptions;
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgConnection, PgPool};
 
-use crate::domain::model::{HookConfig, HookConfigItem, HookSelectorConfig, HookTransportConfig};
+use crate::domain::model::{
+    HookCacheConfig, HookCanaryConfig, HookConfig, HookConfigChangeType, HookConfigItem,
+    HookConfigVersion, HookRetryConfig, HookSamplingConfig, HookSelectorConfig,
+    HookTransportConfig,
+};
 
 const DEFAULT_MAX_CONNECTIONS: u32 = 10;
 
+/// 分页查询的过滤条件（公共部分，参数 $1-$6 与 `HookConfigFilter` 字段一一对应）
+const LIST_FILTER_CLAUSE: &str = r#"
+    WHERE (tenant_id IS NULL OR tenant_id = $1)
+      AND ($2::TEXT IS NULL OR hook_type = $2)
+      AND ($3::BOOLEAN IS NULL OR enabled = $3)
+      AND ($4::TEXT IS NULL OR name ILIKE $4 ESCAPE '\')
+      AND ($5::TEXT IS NULL OR group_name = $5)
+      AND ($6::TEXT IS NULL OR transport_config->>'type' = $6)
+"#;
+
+/// Hook配置分页查询条件
+#[derive(Debug, Clone, Default)]
+pub struct HookConfigFilter {
+    /// 租户ID（结果包含全局配置；None 时只查询全局配置）
+    pub tenant_id: Option<String>,
+    pub hook_type: Option<String>,
+    /// 启用状态（None 表示不过滤）
+    pub enabled: Option<bool>,
+    /// 名称关键字（模糊匹配，不区分大小写）
+    pub name_keyword: Option<String>,
+    /// 执行分组
+    pub group: Option<String>,
+    /// 传输类型（grpc / webhook / local / nats / kafka）
+    pub transport_type: Option<String>,
+}
+
 /// Hook配置数据库行
 #[derive(Debug, Clone, FromRow)]
 pub struct HookConfigRow {
@@ -34,6 +64,12 @@ pub struct HookConfigRow {
     pub selector_config: Value,
     pub transport_config: Value,
     pub metadata: Option<Value>,
+    pub cache_config: Option<Value>,
+    pub sampling_config: Option<Value>,
+    pub retry_config: Option<Value>,
+    pub canary_config: Option<Value>,
+    pub max_concurrency: Option<i32>,
+    pub config_revision: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub created_by: Option<String>,
@@ -58,7 +94,36 @@ impl TryFrom<HookConfigRow> for HookConfigItem {
             None => HashMap::new(),
         };
 
+        // 解析结果缓存配置
+        let cache = row
+            .cache_config
+            .map(serde_json::from_value::<HookCacheConfig>)
+            .transpose()
+            .context("failed to deserialize cache config")?;
+
+        // 解析采样配置
+        let sampling = row
+            .sampling_config
+            .map(serde_json::from_value::<HookSamplingConfig>)
+            .transpose()
+            .context("failed to deserialize sampling config")?;
+
+        // 解析重试配置
+        let retry = row
+            .retry_config
+            .map(serde_json::from_value::<HookRetryConfig>)
+            .transpose()
+            .context("failed to deserialize retry config")?;
+
+        // 解析金丝雀发布配置
+        let canary = row
+            .canary_config
+            .map(serde_json::from_value::<HookCanaryConfig>)
+            .transpose()
+            .context("failed to deserialize canary config")?;
+
         Ok(HookConfigItem {
+            config_revision: Some(row.config_revision.max(0) as u64),
             name: row.name,
             version: row.version,
             description: row.description,
@@ -72,10 +137,119 @@ impl TryFrom<HookConfigRow> for HookConfigItem {
             selector,
             transport,
             metadata,
+            cache,
+            sampling,
+            retry,
+            canary,
+            max_concurrency: row.max_concurrency.map(|max| max.max(0) as u32),
         })
     }
 }
 
+/// Hook配置版本数据库行
+#[derive(Debug, Clone, FromRow)]
+struct HookConfigVersionRow {
+    hook_config_id: i64,
+    revision: i64,
+    tenant_id: Option<String>,
+    hook_type: String,
+    config: Value,
+    change_type: String,
+    created_by: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<HookConfigVersionRow> for HookConfigVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(row: HookConfigVersionRow) -> Result<Self, Self::Error> {
+        let mut config: HookConfigItem = serde_json::from_value(row.config)
+            .context("failed to deserialize hook config snapshot")?;
+        let revision = row.revision.max(0) as u64;
+        config.config_revision = Some(revision);
+        let change_type = HookConfigChangeType::parse(&row.change_type)
+            .with_context(|| format!("unknown hook config change type: {}", row.change_type))?;
+
+        Ok(HookConfigVersion {
+            hook_config_id: row.hook_config_id,
+            revision,
+            tenant_id: row.tenant_id,
+            hook_type: row.hook_type,
+            config,
+            change_type,
+            created_by: row.created_by,
+            created_at: row.created_at.into(),
+        })
+    }
+}
+
+/// 保存Hook配置当前内容为新版本快照（在变更所在事务内调用）
+async fn record_version(
+    conn: &mut PgConnection,
+    hook_id: i64,
+    change_type: HookConfigChangeType,
+    created_by: Option<&str>,
+) -> Result<u64> {
+    let row = sqlx::query_as::<_, HookConfigRow>("SELECT * FROM hook_configs WHERE id = $1")
+        .bind(hook_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to fetch hook config for versioning: {}", e))?;
+
+    let revision = row.config_revision;
+    let tenant_id = row.tenant_id.clone();
+    let hook_type = row.hook_type.clone();
+    let name = row.name.clone();
+    let hook_item: HookConfigItem = row.try_into()?;
+    let snapshot =
+        serde_json::to_value(&hook_item).context("failed to serialize hook config snapshot")?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO hook_config_versions (
+            hook_config_id, revision, tenant_id, hook_type, name, config, change_type, created_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(hook_id)
+    .bind(revision)
+    .bind(tenant_id)
+    .bind(hook_type)
+    .bind(name)
+    .bind(snapshot)
+    .bind(change_type.as_str())
+    .bind(created_by)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| anyhow::anyhow!("failed to save hook config version: {}", e))?;
+
+    Ok(revision.max(0) as u64)
+}
+
+/// 按Hook类型将配置项放入对应列表
+fn push_hook(config: &mut HookConfig, hook_type: &str, hook_item: HookConfigItem) {
+    match hook_type {
+        "pre_send" => config.pre_send.push(hook_item),
+        "post_send" => config.post_send.push(hook_item),
+        "delivery" => config.delivery.push(hook_item),
+        "recall" => config.recall.push(hook_item),
+        "session_create" => config.session_create.push(hook_item),
+        "session_update" => config.session_update.push(hook_item),
+        "session_delete" => config.session_delete.push(hook_item),
+        "user_login" => config.user_login.push(hook_item),
+        "user_logout" => config.user_logout.push(hook_item),
+        "user_online" => config.user_online.push(hook_item),
+        "user_offline" => config.user_offline.push(hook_item),
+        "push_pre_send" => config.push_pre_send.push(hook_item),
+        "push_post_send" => config.push_post_send.push(hook_item),
+        "push_delivery" => config.push_delivery.push(hook_item),
+        _ => {
+            tracing::warn!(hook_type = %hook_type, "Unknown hook type");
+        }
+    }
+}
+
 /// Hook配置数据库仓储
 #[derive(Debug)]
 pub struct PostgresHookConfigRepository {
@@ -130,24 +304,42 @@ impl PostgresHookConfigRepository {
         for row in query {
             let hook_type = row.hook_type.clone();
             let hook_item: HookConfigItem = row.try_into()?;
-            match hook_type.as_str() {
-                "pre_send" => config.pre_send.push(hook_item),
-                "post_send" => config.post_send.push(hook_item),
-                "delivery" => config.delivery.push(hook_item),
-                "recall" => config.recall.push(hook_item),
-                "session_create" => config.session_create.push(hook_item),
-                "session_update" => config.session_update.push(hook_item),
-                "session_delete" => config.session_delete.push(hook_item),
-                "user_login" => config.user_login.push(hook_item),
-                "user_logout" => config.user_logout.push(hook_item),
-                "user_online" => config.user_online.push(hook_item),
-                "user_offline" => config.user_offline.push(hook_item),
-                "push_pre_send" => config.push_pre_send.push(hook_item),
-                "push_post_send" => config.push_post_send.push(hook_item),
-                "push_delivery" => config.push_delivery.push(hook_item),
-                _ => {
-                    tracing::warn!(hook_type = %hook_type, "Unknown hook type");
-                }
+            push_hook(&mut config, &hook_type, hook_item);
+        }
+
+        Ok(config)
+    }
+
+    /// 加载全局配置及所有租户的专属配置
+    ///
+    /// 全局配置（tenant_id 为空）只加载已启用的Hook；租户配置包含禁用的Hook，
+    /// 用于为该租户关闭同名全局Hook
+    pub async fn load_all_tenants(&self) -> Result<HookConfig> {
+        let rows = sqlx::query_as::<_, HookConfigRow>(
+            r#"
+            SELECT * FROM hook_configs
+            WHERE (tenant_id IS NULL AND enabled = true)
+               OR tenant_id IS NOT NULL
+            ORDER BY hook_type, priority ASC
+            "#,
+        )
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to fetch hook configs: {}", e))?;
+
+        let mut config = HookConfig::default();
+
+        for row in rows {
+            let hook_type = row.hook_type.clone();
+            let tenant_id = row.tenant_id.clone();
+            let hook_item: HookConfigItem = row.try_into()?;
+            match tenant_id {
+                Some(tenant_id) => push_hook(
+                    config.tenants.entry(tenant_id).or_default(),
+                    &hook_type,
+                    hook_item,
+                ),
+                None => push_hook(&mut config, &hook_type, hook_item),
             }
         }
 
@@ -174,15 +366,50 @@ impl PostgresHookConfigRepository {
                     .context("failed to serialize metadata")?,
             )
         };
+        let cache_json = hook_item
+            .cache
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .context("failed to serialize cache config")?;
+        let sampling_json = hook_item
+            .sampling
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .context("failed to serialize sampling config")?;
+        let retry_json = hook_item
+            .retry
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .context("failed to serialize retry config")?;
+        let canary_json = hook_item
+            .canary
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .context("failed to serialize canary config")?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to begin transaction")?;
 
-        let row = sqlx::query_as::<_, (i64,)>(
+        // xmax = 0 表示本次为插入（新建），否则为更新
+        let (hook_id, inserted) = sqlx::query_as::<_, (i64, bool)>(
             r#"
             INSERT INTO hook_configs (
                 tenant_id, hook_type, name, version, description, enabled,
                 priority, group_name, timeout_ms, max_retries, error_policy,
-                require_success, selector_config, transport_config, metadata, created_by
+                require_success, selector_config, transport_config, metadata, cache_config,
+                sampling_config, retry_config, canary_config, max_concurrency, created_by
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+                $19, $20, $21
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
             ON CONFLICT (tenant_id, hook_type, name)
             DO UPDATE SET
                 version = EXCLUDED.version,
@@ -197,8 +424,14 @@ impl PostgresHookConfigRepository {
                 selector_config = EXCLUDED.selector_config,
                 transport_config = EXCLUDED.transport_config,
                 metadata = EXCLUDED.metadata,
+                cache_config = EXCLUDED.cache_config,
+                sampling_config = EXCLUDED.sampling_config,
+                retry_config = EXCLUDED.retry_config,
+                canary_config = EXCLUDED.canary_config,
+                max_concurrency = EXCLUDED.max_concurrency,
+                config_revision = hook_configs.config_revision + 1,
                 updated_at = CURRENT_TIMESTAMP
-            RETURNING id
+            RETURNING id, (xmax = 0) AS inserted
             "#,
         )
         .bind(tenant_id)
@@ -216,12 +449,25 @@ impl PostgresHookConfigRepository {
         .bind(selector_json)
         .bind(transport_json)
         .bind(metadata_json)
+        .bind(cache_json)
+        .bind(sampling_json)
+        .bind(retry_json)
+        .bind(canary_json)
+        .bind(hook_item.max_concurrency.map(|max| max as i32))
         .bind(created_by)
-        .fetch_one(&*self.pool)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| anyhow::anyhow!("failed to save hook config: {}", e))?;
 
-        Ok(row.0)
+        let change_type = if inserted {
+            HookConfigChangeType::Create
+        } else {
+            HookConfigChangeType::Update
+        };
+        record_version(&mut tx, hook_id, change_type, created_by).await?;
+        tx.commit().await.context("failed to commit hook config")?;
+
+        Ok(hook_id)
     }
 
     /// 根据ID查询Hook配置
@@ -279,6 +525,27 @@ impl PostgresHookConfigRepository {
 
     /// 更新Hook配置
     pub async fn update(&self, hook_id: i64, hook_item: &HookConfigItem) -> Result<bool> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to begin transaction")?;
+
+        if !Self::update_row(&mut tx, hook_id, hook_item).await? {
+            return Ok(false);
+        }
+        record_version(&mut tx, hook_id, HookConfigChangeType::Update, None).await?;
+        tx.commit().await.context("failed to commit hook config")?;
+
+        Ok(true)
+    }
+
+    /// 更新Hook配置行并递增版本号
+    async fn update_row(
+        conn: &mut PgConnection,
+        hook_id: i64,
+        hook_item: &HookConfigItem,
+    ) -> Result<bool> {
         let selector_json = serde_json::to_value(&hook_item.selector)
             .context("failed to serialize selector config")?;
         let transport_json = serde_json::to_value(&hook_item.transport)
@@ -291,6 +558,30 @@ impl PostgresHookConfigRepository {
                     .context("failed to serialize metadata")?,
             )
         };
+        let cache_json = hook_item
+            .cache
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .context("failed to serialize cache config")?;
+        let sampling_json = hook_item
+            .sampling
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .context("failed to serialize sampling config")?;
+        let retry_json = hook_item
+            .retry
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .context("failed to serialize retry config")?;
+        let canary_json = hook_item
+            .canary
+            .as_ref()
+            .map(serde_json::to_value)
+            .transpose()
+            .context("failed to serialize canary config")?;
 
         let result = sqlx::query(
             r#"
@@ -307,8 +598,14 @@ impl PostgresHookConfigRepository {
                 selector_config = $10,
                 transport_config = $11,
                 metadata = $12,
+                cache_config = $13,
+                sampling_config = $14,
+                retry_config = $15,
+                canary_config = $16,
+                max_concurrency = $17,
+                config_revision = config_revision + 1,
                 updated_at = CURRENT_TIMESTAMP
-            WHERE id = $13
+            WHERE id = $18
             "#,
         )
         .bind(&hook_item.version)
@@ -323,8 +620,13 @@ impl PostgresHookConfigRepository {
         .bind(selector_json)
         .bind(transport_json)
         .bind(metadata_json)
+        .bind(cache_json)
+        .bind(sampling_json)
+        .bind(retry_json)
+        .bind(canary_json)
+        .bind(hook_item.max_concurrency.map(|max| max as i32))
         .bind(hook_id)
-        .execute(&*self.pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| anyhow::anyhow!("failed to update hook config: {}", e))?;
 
@@ -333,21 +635,105 @@ impl PostgresHookConfigRepository {
 
     /// 更新Hook状态（启用/禁用）
     pub async fn update_enabled(&self, hook_id: i64, enabled: bool) -> Result<bool> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to begin transaction")?;
+
         let result = sqlx::query(
             r#"
             UPDATE hook_configs
             SET enabled = $1,
+                config_revision = config_revision + 1,
                 updated_at = CURRENT_TIMESTAMP
             WHERE id = $2
             "#,
         )
         .bind(enabled)
         .bind(hook_id)
-        .execute(&*self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| anyhow::anyhow!("failed to update hook enabled status: {}", e))?;
 
-        Ok(result.rows_affected() > 0)
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        record_version(&mut tx, hook_id, HookConfigChangeType::Status, None).await?;
+        tx.commit().await.context("failed to commit hook status")?;
+
+        Ok(true)
+    }
+
+    /// 查询Hook配置的版本历史（按版本号倒序）
+    pub async fn list_versions(&self, hook_id: i64, limit: usize) -> Result<Vec<HookConfigVersion>> {
+        let rows = sqlx::query_as::<_, HookConfigVersionRow>(
+            r#"
+            SELECT hook_config_id, revision, tenant_id, hook_type, config, change_type,
+                   created_by, created_at
+            FROM hook_config_versions
+            WHERE hook_config_id = $1
+            ORDER BY revision DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(hook_id)
+        .bind(limit.max(1) as i64)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to fetch hook config versions: {}", e))?;
+
+        rows.into_iter().map(HookConfigVersion::try_from).collect()
+    }
+
+    /// 将Hook配置回滚到指定版本
+    ///
+    /// 回滚本身生成一个新版本（内容与目标版本相同），返回新版本号；目标版本不存在时返回 `None`
+    pub async fn rollback(
+        &self,
+        hook_id: i64,
+        revision: u64,
+        operator: Option<&str>,
+    ) -> Result<Option<u64>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("failed to begin transaction")?;
+
+        // 锁定配置行，避免并发变更交错
+        let locked = sqlx::query_scalar::<_, i64>("SELECT id FROM hook_configs WHERE id = $1 FOR UPDATE")
+            .bind(hook_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to lock hook config: {}", e))?;
+        if locked.is_none() {
+            return Ok(None);
+        }
+
+        let snapshot = sqlx::query_scalar::<_, Value>(
+            r#"
+            SELECT config FROM hook_config_versions
+            WHERE hook_config_id = $1 AND revision = $2
+            "#,
+        )
+        .bind(hook_id)
+        .bind(revision as i64)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to fetch hook config version: {}", e))?;
+        let Some(snapshot) = snapshot else {
+            return Ok(None);
+        };
+        let hook_item: HookConfigItem = serde_json::from_value(snapshot)
+            .context("failed to deserialize hook config snapshot")?;
+
+        Self::update_row(&mut tx, hook_id, &hook_item).await?;
+        let new_revision =
+            record_version(&mut tx, hook_id, HookConfigChangeType::Rollback, operator).await?;
+        tx.commit().await.context("failed to commit hook config rollback")?;
+
+        Ok(Some(new_revision))
     }
 
     /// 删除Hook配置
@@ -375,6 +761,51 @@ impl PostgresHookConfigRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    /// 分页查询Hook配置，返回 `(当前页, 总数)`
+    pub async fn list_page(
+        &self,
+        filter: &HookConfigFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<(Vec<HookConfigRow>, i64)> {
+        let name_pattern = filter
+            .name_keyword
+            .as_deref()
+            .map(|keyword| format!("%{}%", escape_like(keyword)));
+
+        let total: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM hook_configs {}",
+            LIST_FILTER_CLAUSE
+        ))
+        .bind(filter.tenant_id.as_deref())
+        .bind(filter.hook_type.as_deref())
+        .bind(filter.enabled)
+        .bind(name_pattern.as_deref())
+        .bind(filter.group.as_deref())
+        .bind(filter.transport_type.as_deref())
+        .fetch_one(&*self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to count hook configs: {}", e))?;
+
+        let rows = sqlx::query_as::<_, HookConfigRow>(&format!(
+            "SELECT * FROM hook_configs {} ORDER BY hook_type, priority ASC, id ASC LIMIT $7 OFFSET $8",
+            LIST_FILTER_CLAUSE
+        ))
+        .bind(filter.tenant_id.as_deref())
+        .bind(filter.hook_type.as_deref())
+        .bind(filter.enabled)
+        .bind(name_pattern.as_deref())
+        .bind(filter.group.as_deref())
+        .bind(filter.transport_type.as_deref())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to query hook configs: {}", e))?;
+
+        Ok((rows, total))
+    }
+
     /// 查询Hook配置（支持过滤）
     pub async fn query(
         &self,
@@ -447,3 +878,199 @@ impl PostgresHookConfigRepository {
         Ok(rows)
     }
 }
+
+/// 转义 LIKE 模式中的通配符
+fn escape_like(keyword: &str) -> String {
+    keyword
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook_item(timeout_ms: u64) -> HookConfigItem {
+        serde_json::from_value(serde_json::json!({
+            "name": "rollback-hook",
+            "enabled": true,
+            "priority": 10,
+            "timeout_ms": timeout_ms,
+            "selector": {},
+            "transport": { "type": "local", "target": "noop" }
+        }))
+        .unwrap()
+    }
+
+    /// 需要已执行 deploy/migrations 的 PostgreSQL：
+    /// `HOOK_ENGINE_TEST_POSTGRES_URL=postgres://... cargo test -p flare-hook-engine -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL (HOOK_ENGINE_TEST_POSTGRES_URL)"]
+    async fn test_rollback_restores_previous_revision_as_new_version() {
+        let url = std::env::var("HOOK_ENGINE_TEST_POSTGRES_URL")
+            .expect("HOOK_ENGINE_TEST_POSTGRES_URL is not set");
+        let repo = PostgresHookConfigRepository::new(&url).await.unwrap();
+        let tenant_id = format!("test-{}", uuid::Uuid::new_v4());
+
+        let hook_id = repo
+            .save(Some(&tenant_id), "pre_send", &hook_item(100), Some("alice"))
+            .await
+            .unwrap();
+        assert!(repo.update(hook_id, &hook_item(500)).await.unwrap());
+        let versions = repo.list_versions(hook_id, 10).await.unwrap();
+        assert_eq!(versions.len(), 2);
+        let (updated, created) = (&versions[0], &versions[1]);
+        assert_eq!(updated.change_type, HookConfigChangeType::Update);
+        assert_eq!(created.change_type, HookConfigChangeType::Create);
+        assert!(updated.revision > created.revision);
+
+        // 回滚到创建时的版本：内容恢复，并生成新的回滚版本
+        let new_revision = repo
+            .rollback(hook_id, created.revision, Some("bob"))
+            .await
+            .unwrap()
+            .expect("revision exists");
+        assert!(new_revision > updated.revision);
+        let (row, current) = repo.get_by_id(hook_id).await.unwrap().unwrap();
+        assert_eq!(current.timeout_ms, 100);
+        assert_eq!(row.config_revision.max(0) as u64, new_revision);
+
+        let versions = repo.list_versions(hook_id, 10).await.unwrap();
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[0].revision, new_revision);
+        assert_eq!(versions[0].change_type, HookConfigChangeType::Rollback);
+        assert_eq!(versions[0].created_by.as_deref(), Some("bob"));
+        assert_eq!(versions[0].config.timeout_ms, 100);
+
+        repo.delete(Some(&tenant_id), "pre_send", "rollback-hook")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL (HOOK_ENGINE_TEST_POSTGRES_URL)"]
+    async fn test_rollback_to_missing_revision_changes_nothing() {
+        let url = std::env::var("HOOK_ENGINE_TEST_POSTGRES_URL")
+            .expect("HOOK_ENGINE_TEST_POSTGRES_URL is not set");
+        let repo = PostgresHookConfigRepository::new(&url).await.unwrap();
+        let tenant_id = format!("test-{}", uuid::Uuid::new_v4());
+
+        let hook_id = repo
+            .save(Some(&tenant_id), "pre_send", &hook_item(100), None)
+            .await
+            .unwrap();
+        let before = repo.list_versions(hook_id, 10).await.unwrap();
+
+        assert_eq!(repo.rollback(hook_id, 9_999, None).await.unwrap(), None);
+        // 不存在的Hook同样返回 None
+        assert_eq!(repo.rollback(i64::MAX, 1, None).await.unwrap(), None);
+
+        let after = repo.list_versions(hook_id, 10).await.unwrap();
+        assert_eq!(after.len(), before.len());
+        let (row, current) = repo.get_by_id(hook_id).await.unwrap().unwrap();
+        assert_eq!(current.timeout_ms, 100);
+        assert_eq!(row.config_revision.max(0) as u64, before[0].revision);
+
+        repo.delete(Some(&tenant_id), "pre_send", "rollback-hook")
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_escape_like_matches_keyword_literally() {
+        assert_eq!(escape_like("content-filter"), "content-filter");
+        assert_eq!(escape_like("100%_off\\"), "100\\%\\_off\\\\");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL (HOOK_ENGINE_TEST_POSTGRES_URL)"]
+    async fn test_list_page_filters_and_paginates() {
+        let url = std::env::var("HOOK_ENGINE_TEST_POSTGRES_URL")
+            .expect("HOOK_ENGINE_TEST_POSTGRES_URL is not set");
+        let repo = PostgresHookConfigRepository::new(&url).await.unwrap();
+        let tenant_id = format!("test-{}", uuid::Uuid::new_v4());
+        let other_tenant = format!("test-{}", uuid::Uuid::new_v4());
+
+        let named = |name: &str, enabled: bool| {
+            let mut item = hook_item(100);
+            item.name = name.to_string();
+            item.enabled = enabled;
+            item
+        };
+        for (name, enabled) in [("filter_a", true), ("filter%b", true), ("audit", false)] {
+            repo.save(Some(&tenant_id), "pre_send", &named(name, enabled), None)
+                .await
+                .unwrap();
+        }
+        repo.save(
+            Some(&other_tenant),
+            "pre_send",
+            &named("filter_a", true),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let filter = |enabled: Option<bool>, name_keyword: Option<&str>| HookConfigFilter {
+            tenant_id: Some(tenant_id.clone()),
+            hook_type: Some("pre_send".to_string()),
+            enabled,
+            name_keyword: name_keyword.map(str::to_string),
+            ..Default::default()
+        };
+        let names = |rows: &[HookConfigRow]| {
+            let mut names: Vec<String> = rows
+                .iter()
+                .filter(|row| row.tenant_id.is_some())
+                .map(|row| row.name.clone())
+                .collect();
+            names.sort();
+            names
+        };
+
+        // 其他租户的Hook不可见；全局Hook（tenant_id 为空）可见但不在断言范围内
+        let (rows, _) = repo.list_page(&filter(None, None), 0, 100).await.unwrap();
+        assert_eq!(names(&rows), ["audit", "filter%b", "filter_a"]);
+        assert!(rows.iter().all(|row| {
+            row.tenant_id.is_none() || row.tenant_id.as_deref() == Some(tenant_id.as_str())
+        }));
+
+        let (rows, _) = repo
+            .list_page(&filter(Some(false), None), 0, 100)
+            .await
+            .unwrap();
+        assert_eq!(names(&rows), ["audit"]);
+
+        // 关键字中的 % 和 _ 按字面匹配
+        let (rows, total) = repo
+            .list_page(&filter(None, Some("filter%")), 0, 100)
+            .await
+            .unwrap();
+        assert_eq!(names(&rows), ["filter%b"]);
+        assert_eq!(total, 1);
+
+        // 分页：total 为过滤后的总数，与 offset/limit 无关
+        let (first, total) = repo
+            .list_page(&filter(None, Some("filter")), 0, 1)
+            .await
+            .unwrap();
+        let (second, _) = repo
+            .list_page(&filter(None, Some("filter")), 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 1);
+        assert_ne!(first[0].id, second[0].id);
+
+        for name in ["filter_a", "filter%b", "audit"] {
+            repo.delete(Some(&tenant_id), "pre_send", name)
+                .await
+                .unwrap();
+        }
+        repo.delete(Some(&other_tenant), "pre_send", "filter_a")
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,140 @@
+//! # Hook资源用量PostgreSQL持久化
+//!
+//! 用量按 (日期, 租户, Hook类型, Hook名称) 每天一行，写入时将增量累加到已有统计行。
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+
+use crate::domain::model::{HookUsageQuery, HookUsageRecord};
+use crate::domain::repository::HookUsageRepository;
+
+/// 用量定期批量写入，少量连接即可
+const DEFAULT_MAX_CONNECTIONS: u32 = 2;
+
+/// 用量统计数据库行
+#[derive(Debug, Clone, FromRow)]
+struct HookUsageRow {
+    usage_date: NaiveDate,
+    tenant_id: String,
+    hook_type: String,
+    hook_name: String,
+    invocations: i64,
+    failures: i64,
+    total_latency_ms: i64,
+    egress_bytes: i64,
+}
+
+impl From<HookUsageRow> for HookUsageRecord {
+    fn from(row: HookUsageRow) -> Self {
+        Self {
+            date: row.usage_date,
+            tenant_id: row.tenant_id,
+            hook_type: row.hook_type,
+            hook_name: row.hook_name,
+            invocations: row.invocations.max(0) as u64,
+            failures: row.failures.max(0) as u64,
+            total_latency_ms: row.total_latency_ms.max(0) as u64,
+            egress_bytes: row.egress_bytes.max(0) as u64,
+        }
+    }
+}
+
+/// Hook资源用量数据库仓储
+#[derive(Debug)]
+pub struct PostgresHookUsageRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresHookUsageRepository {
+    /// 创建数据库连接池
+    pub async fn new(database_url: &str) -> Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(DEFAULT_MAX_CONNECTIONS)
+            .connect(database_url)
+            .await
+            .context("failed to create database connection pool")?;
+
+        Ok(Self {
+            pool: Arc::new(pool),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl HookUsageRepository for PostgresHookUsageRepository {
+    async fn upsert_batch(&self, records: &[HookUsageRecord]) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let mut query = QueryBuilder::<Postgres>::new(
+            "INSERT INTO hook_usage_daily \
+             (usage_date, tenant_id, hook_type, hook_name, invocations, failures, total_latency_ms, \
+             egress_bytes) ",
+        );
+        query.push_values(records, |mut b, record| {
+            b.push_bind(record.date)
+                .push_bind(&record.tenant_id)
+                .push_bind(&record.hook_type)
+                .push_bind(&record.hook_name)
+                .push_bind(record.invocations as i64)
+                .push_bind(record.failures as i64)
+                .push_bind(record.total_latency_ms as i64)
+                .push_bind(record.egress_bytes as i64);
+        });
+        query.push(
+            " ON CONFLICT (usage_date, tenant_id, hook_type, hook_name) DO UPDATE SET \
+             invocations = hook_usage_daily.invocations + EXCLUDED.invocations, \
+             failures = hook_usage_daily.failures + EXCLUDED.failures, \
+             total_latency_ms = hook_usage_daily.total_latency_ms + EXCLUDED.total_latency_ms, \
+             egress_bytes = hook_usage_daily.egress_bytes + EXCLUDED.egress_bytes, \
+             updated_at = NOW()",
+        );
+
+        query
+            .build()
+            .execute(&*self.pool)
+            .await
+            .context("failed to upsert hook usage")?;
+        Ok(())
+    }
+
+    async fn query(&self, query: &HookUsageQuery) -> Result<Vec<HookUsageRecord>> {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "SELECT usage_date, tenant_id, hook_type, hook_name, invocations, failures, \
+             total_latency_ms, egress_bytes FROM hook_usage_daily WHERE 1=1",
+        );
+
+        if let Some(ref tenant_id) = query.tenant_id {
+            builder.push(" AND tenant_id = ").push_bind(tenant_id);
+        }
+        if let Some(ref hook_type) = query.hook_type {
+            builder.push(" AND hook_type = ").push_bind(hook_type);
+        }
+        if let Some(ref hook_name) = query.hook_name {
+            builder.push(" AND hook_name = ").push_bind(hook_name);
+        }
+        if let Some(start_date) = query.start_date {
+            builder.push(" AND usage_date >= ").push_bind(start_date);
+        }
+        if let Some(end_date) = query.end_date {
+            builder.push(" AND usage_date <= ").push_bind(end_date);
+        }
+
+        builder
+            .push(" ORDER BY usage_date DESC, tenant_id, hook_type, hook_name LIMIT ")
+            .push_bind(query.limit.max(1) as i64);
+
+        let rows = builder
+            .build_query_as::<HookUsageRow>()
+            .fetch_all(&*self.pool)
+            .await
+            .context("failed to query hook usage")?;
+
+        Ok(rows.into_iter().map(HookUsageRecord::from).collect())
+    }
+}
@@ -0,0 +1,317 @@
+//! # Hook结果缓存
+//!
+//! 对幂等的PreSend校验类Hook（如敏感词检测）缓存执行结果，
+//! 相同租户、相同消息内容的请求直接复用上一次的决策，不再调用下游。
+//!
+//! 缓存键为 `tenant_id + sha256(payload)`；缓存值包含决策和Hook处理后的payload，
+//! 因此对改写内容的Hook（如敏感词替换）同样适用。依赖payload以外字段的Hook不应启用缓存。
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use sha2::{Digest, Sha256};
+
+use crate::domain::model::HookCacheConfig;
+use flare_im_core::error::{ErrorBuilder, ErrorCode, FlareError};
+use flare_im_core::{MessageDraft, PreSendDecision};
+
+/// 缓存的决策
+#[derive(Debug, Clone)]
+enum CachedDecision {
+    Continue,
+    /// 拒绝（保留原始错误码和详情，命中时原样重建错误）
+    Reject(CachedError),
+}
+
+/// 拒绝错误的可缓存形式
+#[derive(Debug, Clone)]
+enum CachedError {
+    Localized {
+        code: ErrorCode,
+        reason: String,
+        details: Option<String>,
+    },
+    System(String),
+    Io(String),
+}
+
+impl CachedError {
+    fn from_error(error: &FlareError) -> Self {
+        match error {
+            FlareError::Localized {
+                code,
+                reason,
+                details,
+                ..
+            } => CachedError::Localized {
+                code: *code,
+                reason: reason.clone(),
+                details: details.clone(),
+            },
+            FlareError::System(message) => CachedError::System(message.clone()),
+            FlareError::Io(message) => CachedError::Io(message.clone()),
+        }
+    }
+
+    fn to_error(&self) -> FlareError {
+        match self {
+            CachedError::Localized {
+                code,
+                reason,
+                details,
+            } => {
+                let mut builder = ErrorBuilder::new(*code, reason);
+                if let Some(details) = details {
+                    builder = builder.details(details.clone());
+                }
+                builder.build_error()
+            }
+            CachedError::System(message) => FlareError::System(message.clone()),
+            CachedError::Io(message) => FlareError::Io(message.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    decision: CachedDecision,
+    /// Hook处理后的payload（未改写时为 None）
+    payload: Option<Vec<u8>>,
+    expires_at: Instant,
+    /// 写入代次（与淘汰队列中的记录比对，避免旧记录淘汰重新写入的条目）
+    generation: u64,
+}
+
+/// Hook结果缓存
+pub struct HookResultCache {
+    ttl: Duration,
+    max_entries: usize,
+    inner: Mutex<CacheInner>,
+}
+
+#[derive(Default)]
+struct CacheInner {
+    entries: HashMap<String, CacheEntry>,
+    /// 写入顺序（键和写入代次，用于超过容量时淘汰最早的条目）
+    order: VecDeque<(String, u64)>,
+    next_generation: u64,
+}
+
+impl CacheInner {
+    /// 淘汰队列中的记录是否仍对应当前条目
+    fn is_current(&self, key: &str, generation: u64) -> bool {
+        self.entries
+            .get(key)
+            .is_some_and(|entry| entry.generation == generation)
+    }
+}
+
+impl HookResultCache {
+    pub fn new(config: &HookCacheConfig) -> Self {
+        Self {
+            ttl: Duration::from_millis(config.ttl_ms),
+            max_entries: config.max_entries.max(1),
+            inner: Mutex::new(CacheInner::default()),
+        }
+    }
+
+    /// 计算缓存键
+    pub fn key(tenant_id: &str, payload: &[u8]) -> String {
+        let digest = Sha256::digest(payload);
+        format!("{}:{}", tenant_id, hex::encode(digest))
+    }
+
+    /// 查询缓存，命中时将缓存的payload写回draft并返回决策
+    pub fn get(&self, key: &str, draft: &mut MessageDraft) -> Option<PreSendDecision> {
+        let entry = {
+            let mut inner = self.inner.lock().unwrap();
+            match inner.entries.get(key) {
+                Some(entry) if entry.expires_at > Instant::now() => entry.clone(),
+                Some(_) => {
+                    inner.entries.remove(key);
+                    return None;
+                }
+                None => return None,
+            }
+        };
+
+        if let Some(payload) = entry.payload {
+            draft.payload = payload;
+        }
+
+        Some(match entry.decision {
+            CachedDecision::Continue => PreSendDecision::Continue,
+            CachedDecision::Reject(error) => PreSendDecision::Reject {
+                error: error.to_error(),
+            },
+        })
+    }
+
+    /// 写入缓存
+    ///
+    /// `original_payload` 为Hook执行前的payload，用于判断Hook是否改写了内容
+    pub fn put(
+        &self,
+        key: String,
+        decision: &PreSendDecision,
+        original_payload: &[u8],
+        draft: &MessageDraft,
+    ) {
+        let decision = match decision {
+            PreSendDecision::Continue => CachedDecision::Continue,
+            PreSendDecision::Reject { error } => {
+                CachedDecision::Reject(CachedError::from_error(error))
+            }
+        };
+        let payload = (draft.payload.as_slice() != original_payload).then(|| draft.payload.clone());
+
+        let mut inner = self.inner.lock().unwrap();
+        let generation = inner.next_generation;
+        inner.next_generation += 1;
+        let entry = CacheEntry {
+            decision,
+            payload,
+            expires_at: Instant::now() + self.ttl,
+            generation,
+        };
+        inner.entries.insert(key.clone(), entry);
+        inner.order.push_back((key, generation));
+
+        // 超过容量时淘汰最早写入的条目（已删除或已被重新写入的旧记录直接跳过）
+        while inner.entries.len() > self.max_entries {
+            match inner.order.pop_front() {
+                Some((oldest, generation)) => {
+                    if inner.is_current(&oldest, generation) {
+                        inner.entries.remove(&oldest);
+                    }
+                }
+                None => break,
+            }
+        }
+        if inner.order.len() > self.max_entries * 2 {
+            let order = std::mem::take(&mut inner.order);
+            inner.order = order
+                .into_iter()
+                .filter(|(key, generation)| inner.is_current(key, *generation))
+                .collect();
+        }
+    }
+
+    /// 当前缓存条目数
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(ttl_ms: u64, max_entries: usize) -> HookResultCache {
+        HookResultCache::new(&HookCacheConfig {
+            ttl_ms,
+            max_entries,
+        })
+    }
+
+    #[test]
+    fn test_key_scoped_by_tenant() {
+        let a = HookResultCache::key("tenant-a", b"hello");
+        let b = HookResultCache::key("tenant-b", b"hello");
+        assert_ne!(a, b);
+        assert_eq!(a, HookResultCache::key("tenant-a", b"hello"));
+    }
+
+    #[test]
+    fn test_cache_hit_restores_rewritten_payload() {
+        let cache = cache(60_000, 10);
+        let key = HookResultCache::key("t", b"bad word");
+
+        let mut draft = MessageDraft::new(b"*** word".to_vec());
+        cache.put(key.clone(), &PreSendDecision::Continue, b"bad word", &draft);
+
+        draft.payload = b"bad word".to_vec();
+        let decision = cache.get(&key, &mut draft).unwrap();
+        assert!(decision.is_continue());
+        assert_eq!(draft.payload, b"*** word");
+    }
+
+    #[test]
+    fn test_cache_expired_entry_misses() {
+        let cache = cache(0, 10);
+        let key = HookResultCache::key("t", b"hello");
+        let mut draft = MessageDraft::new(b"hello".to_vec());
+        cache.put(key.clone(), &PreSendDecision::Continue, b"hello", &draft);
+
+        assert!(cache.get(&key, &mut draft).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest_when_full() {
+        let cache = cache(60_000, 2);
+        let draft = MessageDraft::new(Vec::new());
+        for payload in [b"a", b"b", b"c"] {
+            let key = HookResultCache::key("t", payload);
+            cache.put(key, &PreSendDecision::Continue, b"", &draft);
+        }
+
+        let mut draft = MessageDraft::new(Vec::new());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&HookResultCache::key("t", b"a"), &mut draft).is_none());
+        assert!(cache.get(&HookResultCache::key("t", b"c"), &mut draft).is_some());
+    }
+
+    #[test]
+    fn test_cached_reject_keeps_error_code_and_details() {
+        let cache = cache(60_000, 10);
+        let key = HookResultCache::key("t", b"spam");
+        let draft = MessageDraft::new(b"spam".to_vec());
+        let error = ErrorBuilder::new(ErrorCode::InvalidParameter, "content blocked")
+            .details("hook=sensitive-words".to_string())
+            .build_error();
+        let reject = PreSendDecision::Reject { error };
+        cache.put(key.clone(), &reject, b"spam", &draft);
+
+        let mut draft = MessageDraft::new(b"spam".to_vec());
+        let Some(PreSendDecision::Reject { error }) = cache.get(&key, &mut draft) else {
+            panic!("expected cached reject");
+        };
+        let FlareError::Localized {
+            code,
+            reason,
+            details,
+            ..
+        } = error
+        else {
+            panic!("expected localized error, got {error:?}");
+        };
+        assert_eq!(code, ErrorCode::InvalidParameter);
+        assert_eq!(reason, "content blocked");
+        assert_eq!(details.as_deref(), Some("hook=sensitive-words"));
+    }
+
+    #[test]
+    fn test_rewritten_entry_not_evicted_by_stale_order_record() {
+        let cache = cache(60_000, 2);
+        let draft = MessageDraft::new(Vec::new());
+        let key = |payload: &[u8]| HookResultCache::key("t", payload);
+
+        // a 写入两次：第一次的淘汰记录不应淘汰重新写入的 a
+        cache.put(key(b"a"), &PreSendDecision::Continue, b"", &draft);
+        cache.put(key(b"b"), &PreSendDecision::Continue, b"", &draft);
+        cache.put(key(b"a"), &PreSendDecision::Continue, b"", &draft);
+        cache.put(key(b"c"), &PreSendDecision::Continue, b"", &draft);
+
+        let mut draft = MessageDraft::new(Vec::new());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&key(b"b"), &mut draft).is_none());
+        assert!(cache.get(&key(b"a"), &mut draft).is_some());
+        assert!(cache.get(&key(b"c"), &mut draft).is_some());
+    }
+}
@@ -0,0 +1,306 @@
+//! # Hook调用采样
+//!
+//! 按Hook、按租户限速采集gRPC/WebHook调用的请求与响应（脱敏后），
+//! 写入有界的内存存储，供管理接口查询，方便接入方排查Hook响应被拒绝、解析失败等问题。
+//!
+//! 脱敏规则：
+//! - 消息内容（`payload`）替换为长度和 sha256 摘要
+//! - 键名包含 token/secret/password/authorization/cookie/signature 的字段替换为 `***`
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::domain::model::HookSamplingConfig;
+
+/// 采样存储默认容量
+pub const DEFAULT_SAMPLE_CAPACITY: usize = 1000;
+
+/// 需要脱敏的字段名关键字（小写匹配）
+const SENSITIVE_KEYS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "authorization",
+    "cookie",
+    "signature",
+];
+
+/// 单次调用采样记录
+#[derive(Debug, Clone)]
+pub struct HookSample {
+    pub id: u64,
+    pub hook_type: String,
+    pub hook_name: String,
+    pub tenant_id: String,
+    /// 调用的接口（pre_send/post_send/delivery/recall）
+    pub operation: &'static str,
+    pub captured_at: SystemTime,
+    pub latency_ms: u64,
+    pub success: bool,
+    /// 脱敏后的请求
+    pub request: Value,
+    /// 脱敏后的响应（调用出错时为 Null）
+    pub response: Value,
+    pub error_message: Option<String>,
+}
+
+impl HookSample {
+    /// Hook标识（`hook_type:name`，与统计信息一致）
+    pub fn hook_id(&self) -> String {
+        format!("{}:{}", self.hook_type, self.hook_name)
+    }
+}
+
+/// 有界采样存储（超过容量时丢弃最早的记录）
+pub struct HookSampleStore {
+    capacity: usize,
+    next_id: AtomicU64,
+    samples: Mutex<VecDeque<HookSample>>,
+}
+
+impl HookSampleStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            next_id: AtomicU64::new(1),
+            samples: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// 写入采样记录（自动分配ID）
+    pub fn push(&self, mut sample: HookSample) {
+        sample.id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut samples = self.samples.lock().unwrap();
+        samples.push_back(sample);
+        while samples.len() > self.capacity {
+            samples.pop_front();
+        }
+    }
+
+    /// 查询指定租户的采样记录（按时间倒序）
+    pub fn query(&self, tenant_id: &str, hook_id: Option<&str>, limit: usize) -> Vec<HookSample> {
+        self.samples
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|s| s.tenant_id == tenant_id)
+            .filter(|s| hook_id.is_none_or(|id| s.hook_id() == id))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for HookSampleStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAMPLE_CAPACITY)
+    }
+}
+
+/// 单个Hook的采样器（按租户、按分钟限速）
+pub struct HookSampler {
+    hook_type: String,
+    hook_name: String,
+    config: HookSamplingConfig,
+    /// 租户 -> (分钟序号, 本分钟已采样次数)
+    windows: Mutex<HashMap<String, (u64, u32)>>,
+}
+
+impl HookSampler {
+    pub fn new(hook_type: &str, hook_name: &str, config: HookSamplingConfig) -> Self {
+        Self {
+            hook_type: hook_type.to_string(),
+            hook_name: hook_name.to_string(),
+            config,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 判断本次调用是否采样（命中时占用当前分钟的名额）
+    pub fn try_sample(&self, tenant_id: &str) -> bool {
+        self.try_sample_at(tenant_id, current_minute())
+    }
+
+    fn try_sample_at(&self, tenant_id: &str, minute: u64) -> bool {
+        if self.config.per_minute == 0
+            || (!self.config.tenants.is_empty()
+                && !self.config.tenants.iter().any(|t| t == tenant_id))
+        {
+            return false;
+        }
+
+        let mut windows = self.windows.lock().unwrap();
+        // 清理过期窗口，避免租户数量多时无限增长
+        if windows.len() > 1024 {
+            windows.retain(|_, (window, _)| *window == minute);
+        }
+
+        let (window, count) = windows.entry(tenant_id.to_string()).or_insert((minute, 0));
+        if *window != minute {
+            *window = minute;
+            *count = 0;
+        }
+        if *count >= self.config.per_minute {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+
+    /// 构建采样记录（请求和响应在此统一脱敏）
+    pub fn sample(
+        &self,
+        tenant_id: &str,
+        operation: &'static str,
+        latency_ms: u64,
+        request: Value,
+        outcome: Result<Value, String>,
+    ) -> HookSample {
+        let (success, response, error_message) = match outcome {
+            Ok(response) => (true, redact(response), None),
+            Err(error) => (false, Value::Null, Some(error)),
+        };
+        HookSample {
+            id: 0,
+            hook_type: self.hook_type.clone(),
+            hook_name: self.hook_name.clone(),
+            tenant_id: tenant_id.to_string(),
+            operation,
+            captured_at: SystemTime::now(),
+            latency_ms,
+            success,
+            request: redact(request),
+            response,
+            error_message,
+        }
+    }
+}
+
+fn current_minute() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 60)
+        .unwrap_or(0)
+}
+
+/// 脱敏JSON值
+pub fn redact(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let lower = key.to_lowercase();
+                    let value = if lower == "payload" {
+                        redact_payload(&value)
+                    } else if SENSITIVE_KEYS.iter().any(|k| lower.contains(k)) {
+                        Value::String("***".to_string())
+                    } else {
+                        redact(value)
+                    };
+                    (key, value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact).collect()),
+        other => other,
+    }
+}
+
+/// 消息内容只保留长度和摘要
+fn redact_payload(value: &Value) -> Value {
+    let bytes: Vec<u8> = match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_u64().map(|b| b as u8))
+            .collect(),
+        Value::String(s) => s.as_bytes().to_vec(),
+        Value::Null => return Value::Null,
+        _ => return Value::String("***".to_string()),
+    };
+    serde_json::json!({
+        "size": bytes.len(),
+        "sha256": hex::encode(Sha256::digest(&bytes)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sampler(per_minute: u32, tenants: &[&str]) -> HookSampler {
+        HookSampler::new(
+            "pre_send",
+            "sensitive-word",
+            HookSamplingConfig {
+                per_minute,
+                tenants: tenants.iter().map(|t| t.to_string()).collect(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_sampler_limits_per_tenant_per_minute() {
+        let sampler = sampler(2, &[]);
+        assert!(sampler.try_sample_at("t1", 100));
+        assert!(sampler.try_sample_at("t1", 100));
+        assert!(!sampler.try_sample_at("t1", 100));
+        // 其他租户独立计数
+        assert!(sampler.try_sample_at("t2", 100));
+        // 下一分钟重新计数
+        assert!(sampler.try_sample_at("t1", 101));
+    }
+
+    #[test]
+    fn test_sampler_respects_tenant_scope() {
+        let sampler = sampler(10, &["t1"]);
+        assert!(sampler.try_sample_at("t1", 1));
+        assert!(!sampler.try_sample_at("t2", 1));
+    }
+
+    #[test]
+    fn test_redact_payload_and_secrets() {
+        let value = redact(json!({
+            "draft": {
+                "payload": [104, 105],
+                "headers": {"Authorization": "Bearer abc", "x-trace": "1"},
+            },
+            "api_token": "xyz",
+        }));
+
+        assert_eq!(value["draft"]["payload"]["size"], 2);
+        assert!(value["draft"]["payload"]["sha256"].is_string());
+        assert_eq!(value["draft"]["headers"]["Authorization"], "***");
+        assert_eq!(value["draft"]["headers"]["x-trace"], "1");
+        assert_eq!(value["api_token"], "***");
+    }
+
+    #[test]
+    fn test_store_is_bounded_and_tenant_scoped() {
+        let store = HookSampleStore::new(2);
+        let sampler = sampler(10, &[]);
+        for tenant in ["t1", "t2", "t1"] {
+            store.push(sampler.sample(tenant, "pre_send", 1, json!({}), Ok(json!({}))));
+        }
+
+        assert_eq!(store.len(), 2);
+        let samples = store.query("t1", Some("pre_send:sensitive-word"), 10);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].id, 3);
+        assert!(store.query("t1", Some("pre_send:other"), 10).is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+//! # Hook资源用量统计
+//!
+//! 按天、租户、Hook聚合调用次数、出错次数、累计耗时和发往WebHook/gRPC目标的请求字节数，
+//! 用于向租户分摊（chargeback）租户专属Hook的处理成本。
+//!
+//! - 用量先在内存中按 (日期, 租户, Hook) 聚合，由后台任务定期将增量累加到仓储，不阻塞Hook执行路径；
+//!   落库失败时增量保留到下次刷新
+//! - 出站字节数由 [`meter_egress`] 在单次Hook执行范围内计数，传输适配器发送请求时调用 [`record_egress`]
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::NaiveDate;
+
+use crate::domain::model::HookUsageRecord;
+use crate::domain::repository::{HookUsageRecorder, HookUsageRepository};
+
+tokio::task_local! {
+    /// 当前Hook执行已发送的请求字节数
+    static EGRESS_BYTES: Cell<u64>;
+}
+
+/// 执行 `future` 并统计其间通过 [`record_egress`] 记录的出站字节数
+pub async fn meter_egress<F: Future>(future: F) -> (F::Output, u64) {
+    EGRESS_BYTES
+        .scope(Cell::new(0), async move {
+            let output = future.await;
+            (output, EGRESS_BYTES.with(Cell::get))
+        })
+        .await
+}
+
+/// 记录发往Hook目标的请求字节数（不在 [`meter_egress`] 范围内时忽略）
+pub fn record_egress(bytes: usize) {
+    let _ = EGRESS_BYTES.try_with(|total| total.set(total.get() + bytes as u64));
+}
+
+/// 用量统计配置
+#[derive(Debug, Clone)]
+pub struct HookUsageConfig {
+    /// 聚合结果的落库间隔
+    pub flush_interval: Duration,
+}
+
+impl Default for HookUsageConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// (日期, 租户, Hook类型, Hook名称)
+type UsageKey = (NaiveDate, String, String, String);
+
+/// 内存聚合、定期落库的用量记录器
+pub struct AggregatingHookUsageRecorder {
+    pending: Arc<Mutex<HashMap<UsageKey, HookUsageRecord>>>,
+}
+
+impl AggregatingHookUsageRecorder {
+    /// 创建记录器并启动后台落库任务
+    pub fn start(repository: Arc<dyn HookUsageRepository>, config: &HookUsageConfig) -> Arc<Self> {
+        let pending = Arc::new(Mutex::new(HashMap::new()));
+        tokio::spawn(run_flush_loop(
+            pending.clone(),
+            repository,
+            config.flush_interval,
+        ));
+        Arc::new(Self { pending })
+    }
+}
+
+impl HookUsageRecorder for AggregatingHookUsageRecorder {
+    fn record(&self, usage: HookUsageRecord) {
+        merge_pending(&mut self.pending.lock().unwrap(), usage);
+    }
+}
+
+fn merge_pending(pending: &mut HashMap<UsageKey, HookUsageRecord>, usage: HookUsageRecord) {
+    let key = (
+        usage.date,
+        usage.tenant_id.clone(),
+        usage.hook_type.clone(),
+        usage.hook_name.clone(),
+    );
+    match pending.get_mut(&key) {
+        Some(total) => total.merge(&usage),
+        None => {
+            pending.insert(key, usage);
+        }
+    }
+}
+
+async fn run_flush_loop(
+    pending: Arc<Mutex<HashMap<UsageKey, HookUsageRecord>>>,
+    repository: Arc<dyn HookUsageRepository>,
+    flush_interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(flush_interval.max(Duration::from_millis(1)));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // 第一次 tick 立即完成，跳过
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+        // 记录器已释放时写完剩余用量后退出
+        let released = Arc::strong_count(&pending) == 1;
+        let records: Vec<HookUsageRecord> = std::mem::take(&mut *pending.lock().unwrap())
+            .into_values()
+            .collect();
+        if !records.is_empty() {
+            flush(repository.as_ref(), &pending, records).await;
+        }
+        if released {
+            break;
+        }
+    }
+}
+
+async fn flush(
+    repository: &dyn HookUsageRepository,
+    pending: &Mutex<HashMap<UsageKey, HookUsageRecord>>,
+    records: Vec<HookUsageRecord>,
+) {
+    if let Err(e) = repository.upsert_batch(&records).await {
+        tracing::warn!(
+            count = records.len(),
+            error = %e,
+            "Failed to persist hook usage, retrying on next flush"
+        );
+        let mut pending = pending.lock().unwrap();
+        for record in records {
+            merge_pending(&mut pending, record);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::HookUsageQuery;
+
+    #[derive(Default)]
+    struct MemoryUsageRepository {
+        records: Mutex<Vec<HookUsageRecord>>,
+        fail_once: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl HookUsageRepository for MemoryUsageRepository {
+        async fn upsert_batch(&self, records: &[HookUsageRecord]) -> anyhow::Result<()> {
+            if self
+                .fail_once
+                .swap(false, std::sync::atomic::Ordering::SeqCst)
+            {
+                anyhow::bail!("database unavailable");
+            }
+            let mut stored = self.records.lock().unwrap();
+            for record in records {
+                match stored.iter_mut().find(|r| {
+                    r.date == record.date
+                        && r.tenant_id == record.tenant_id
+                        && r.hook_id() == record.hook_id()
+                }) {
+                    Some(total) => total.merge(record),
+                    None => stored.push(record.clone()),
+                }
+            }
+            Ok(())
+        }
+
+        async fn query(&self, _query: &HookUsageQuery) -> anyhow::Result<Vec<HookUsageRecord>> {
+            Ok(vec![])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_meter_egress_counts_within_scope() {
+        record_egress(100);
+        let (output, bytes) = meter_egress(async {
+            record_egress(10);
+            record_egress(32);
+            "done"
+        })
+        .await;
+        assert_eq!(output, "done");
+        assert_eq!(bytes, 42);
+    }
+
+    #[tokio::test]
+    async fn test_aggregating_recorder_flushes_daily_totals() {
+        let repository = Arc::new(MemoryUsageRepository::default());
+        repository
+            .fail_once
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        let recorder = AggregatingHookUsageRecorder::start(
+            repository.clone(),
+            &HookUsageConfig {
+                flush_interval: Duration::from_millis(20),
+            },
+        );
+
+        recorder.record(HookUsageRecord::invocation(
+            Some("tenant-a"),
+            "pre_send",
+            "audit",
+            false,
+            10,
+            200,
+        ));
+        recorder.record(HookUsageRecord::invocation(
+            Some("tenant-a"),
+            "pre_send",
+            "audit",
+            true,
+            30,
+            200,
+        ));
+        recorder.record(HookUsageRecord::invocation(
+            None, "pre_send", "audit", false, 5, 0,
+        ));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // 第一次落库失败的增量在下次刷新时写入
+        let mut records = repository.records.lock().unwrap().clone();
+        records.sort_by(|a, b| a.tenant_id.cmp(&b.tenant_id));
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].tenant_id, "0");
+        assert_eq!(records[1].tenant_id, "tenant-a");
+        assert_eq!(records[1].invocations, 2);
+        assert_eq!(records[1].failures, 1);
+        assert_eq!(records[1].avg_latency_ms(), 20);
+        assert_eq!(records[1].egress_bytes, 400);
+    }
+}
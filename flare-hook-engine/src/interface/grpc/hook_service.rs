@@ -9,22 +9,56 @@ use flare_proto::hooks::{
     CreateHookConfigRequest, CreateHookConfigResponse, DeleteHookConfigRequest,
     DeleteHookConfigResponse, GetHookConfigRequest, GetHookConfigResponse,
     GetHookStatisticsRequest, GetHookStatisticsResponse, HookConfig, HookExecution,
-    HookRetryPolicy, HookSelector, HookStatistics, HookTransport, ListHookConfigsRequest,
-    ListHookConfigsResponse, QueryHookExecutionsRequest, QueryHookExecutionsResponse,
-    SetHookStatusRequest, SetHookStatusResponse, UpdateHookConfigRequest, UpdateHookConfigResponse,
+    HookRetryPolicy, HookSelector, HookSimulationTrace, HookStatistics, HookTransport,
+    ListHookConfigVersionsRequest, ListHookConfigVersionsResponse, ListHookConfigsRequest,
+    ListHookConfigsResponse, ListHookStatisticsRequest, ListHookStatisticsResponse,
+    HookUsage, QueryHookExecutionsRequest, QueryHookExecutionsResponse, QueryHookSamplesRequest,
+    QueryHookSamplesResponse, QueryHookUsageRequest, QueryHookUsageResponse,
+    RollbackHookConfigRequest, RollbackHookConfigResponse,
+    SetHookStatusRequest, SetHookStatusResponse, SimulatePreSendRequest,
+    SimulatePreSendResponse, UpdateHookConfigRequest, UpdateHookConfigResponse,
 };
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 use flare_server_core::context::Context;
+use flare_im_core::pagination::{CursorCodec, PageInfo, PageLimit};
 use flare_im_core::utils::context::require_context;
 
+use crate::application::handlers::HookCommandHandler;
 use crate::domain::model::{
-    HookConfigItem, HookSelectorConfig, HookTransportConfig,
+    BackoffStrategy, FederationFallback, HookAuditDecision, HookAuditEntry, HookAuditQuery,
+    HookCacheConfig, HookCanaryConfig, HookConfigItem, HookConfigVersion, HookRetryConfig,
+    HookSamplingConfig, HookSelectorConfig, HookTrace, HookTransportConfig, HookUsageQuery,
+    HookUsageRecord, RateLimitHookConfig,
+};
+use crate::domain::repository::{HookAuditRepository, HookUsageRepository};
+use crate::infrastructure::adapters::conversion::{
+    message_draft_to_proto, proto_to_context, proto_to_message_draft,
 };
 use std::str::FromStr;
-use crate::infrastructure::persistence::postgres_config::PostgresHookConfigRepository;
+use crate::infrastructure::persistence::postgres_config::{
+    HookConfigFilter, HookConfigRow, PostgresHookConfigRepository,
+};
+use crate::infrastructure::sampling::HookSample;
 use crate::service::registry::CoreHookRegistry;
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
+
+/// Local 传输的内嵌脚本在 proto `HookTransport.metadata` 中的键
+const LOCAL_SCRIPT_METADATA_KEY: &str = "script";
+/// Local 传输的内置限流配置（JSON）在 proto `HookTransport.metadata` 中的键
+const LOCAL_RATE_LIMIT_METADATA_KEY: &str = "rate_limit";
+/// WebHook 传输轮换中的旧密钥在 proto `HookTransport.metadata` 中的键
+const WEBHOOK_PREVIOUS_SECRET_METADATA_KEY: &str = "previous_secret";
+/// 联邦传输的降级策略（JSON）在 proto `HookTransport.metadata` 中的键
+const FEDERATION_FALLBACK_METADATA_KEY: &str = "fallback";
+/// 联邦传输的决策缓存配置（JSON）在 proto `HookTransport.metadata` 中的键
+const FEDERATION_DECISION_CACHE_METADATA_KEY: &str = "decision_cache";
+/// Hook 配置列表的分页游标作用域
+const HOOK_CONFIGS_CURSOR_SCOPE: &str = "hook.configs";
+/// 配置、统计、执行记录与采样查询的条数限制
+const HOOK_PAGE_LIMIT: PageLimit = PageLimit::new(100, 1000);
+/// 配置版本历史的条数限制
+const HOOK_VERSION_PAGE_LIMIT: PageLimit = PageLimit::new(50, 500);
 
 /// 从gRPC请求中提取租户ID（向后兼容函数）
 ///
@@ -43,6 +77,12 @@ pub struct HookServiceServer {
     registry: Arc<CoreHookRegistry>,
     metrics_collector: Option<Arc<crate::infrastructure::monitoring::MetricsCollector>>,
     execution_recorder: Option<Arc<crate::infrastructure::monitoring::ExecutionRecorder>>,
+    /// 用于Hook链预演（未设置时预演接口不可用）
+    command_handler: Option<Arc<HookCommandHandler>>,
+    /// 执行审计仓储（设置后执行记录从审计日志查询）
+    audit_repository: Option<Arc<dyn HookAuditRepository>>,
+    /// 资源用量仓储（未设置时用量查询接口不可用）
+    usage_repository: Option<Arc<dyn HookUsageRepository>>,
 }
 
 impl HookServiceServer {
@@ -55,6 +95,9 @@ impl HookServiceServer {
             registry,
             metrics_collector: None,
             execution_recorder: None,
+            command_handler: None,
+            audit_repository: None,
+            usage_repository: None,
         }
     }
 
@@ -67,6 +110,116 @@ impl HookServiceServer {
         self.execution_recorder = Some(execution_recorder);
         self
     }
+
+    pub fn with_command_handler(mut self, command_handler: Arc<HookCommandHandler>) -> Self {
+        self.command_handler = Some(command_handler);
+        self
+    }
+
+    pub fn with_audit_repository(mut self, audit_repository: Arc<dyn HookAuditRepository>) -> Self {
+        self.audit_repository = Some(audit_repository);
+        self
+    }
+
+    pub fn with_usage_repository(mut self, usage_repository: Arc<dyn HookUsageRepository>) -> Self {
+        self.usage_repository = Some(usage_repository);
+        self
+    }
+
+    /// 从监控系统读取租户的Hook统计数据（`hook_name` 格式为 hook_type:name），无数据时返回空统计
+    async fn collect_statistics(
+        &self,
+        hook_id: i64,
+        tenant_id: Option<&str>,
+        hook_name: &str,
+    ) -> HookStatistics {
+        let stats = match self.metrics_collector {
+            Some(ref metrics_collector) => {
+                metrics_collector.get_statistics(tenant_id, hook_name).await
+            }
+            None => None,
+        };
+        domain_to_protobuf_statistics(hook_id.to_string(), &stats.unwrap_or_default())
+    }
+
+    /// 解析hook_id（格式：hook_type:name 或 id）并查询配置行，按数字ID查询时校验租户归属
+    async fn resolve_hook_row(
+        &self,
+        tenant_id: Option<&str>,
+        hook_id: &str,
+    ) -> Result<(HookConfigRow, HookConfigItem), Status> {
+        let found = if let Ok(id) = hook_id.parse::<i64>() {
+            self.repository
+                .get_by_id(id)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to get hook config: {}", e)))?
+                .filter(|(row, _)| tenant_id.is_none() || row.tenant_id.as_deref() == tenant_id)
+        } else {
+            let parts: Vec<&str> = hook_id.splitn(2, ':').collect();
+            if parts.len() != 2 {
+                return Err(Status::invalid_argument(
+                    "Invalid hook_id format, expected numeric id or 'hook_type:name'",
+                ));
+            }
+            self.repository
+                .get_by_name(tenant_id, parts[0], parts[1])
+                .await
+                .map_err(|e| Status::internal(format!("Failed to get hook config: {}", e)))?
+        };
+        found.ok_or_else(|| Status::not_found("Hook config not found"))
+    }
+
+    /// 从审计日志查询执行记录（按租户隔离，支持按消息ID排查拒绝原因）
+    async fn query_audit_executions(
+        &self,
+        audit_repository: &dyn HookAuditRepository,
+        tenant_id: Option<String>,
+        req: &QueryHookExecutionsRequest,
+    ) -> Result<Vec<HookExecution>, Status> {
+        // 解析hook_id（格式：hook_type:name 或 id）
+        let hook_id = if req.hook_id.is_empty() {
+            None
+        } else if let Ok(id) = req.hook_id.parse::<i64>() {
+            let (row, _) = self
+                .repository
+                .get_by_id(id)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to get hook config: {}", e)))?
+                .ok_or_else(|| Status::not_found("Hook config not found"))?;
+            Some((row.hook_type, row.name))
+        } else {
+            let (hook_type, name) = req
+                .hook_id
+                .split_once(':')
+                .ok_or_else(|| Status::invalid_argument("hook_id must be an id or hook_type:name"))?;
+            Some((hook_type.to_string(), name.to_string()))
+        };
+        let (hook_type, hook_name) = hook_id.unzip();
+
+        let time_range = req.time_range.as_ref();
+        let query = HookAuditQuery {
+            tenant_id,
+            message_id: (!req.message_id.is_empty()).then(|| req.message_id.clone()),
+            hook_type,
+            hook_name,
+            decision: req.success_only.then_some(HookAuditDecision::Continue),
+            start_time: time_range
+                .and_then(|r| r.start_time.as_ref())
+                .map(timestamp_to_system_time),
+            end_time: time_range
+                .and_then(|r| r.end_time.as_ref())
+                .map(timestamp_to_system_time),
+            limit: HOOK_PAGE_LIMIT.clamp(
+                req.pagination.as_ref().map_or(0, |p| p.limit as i64),
+            ),
+        };
+
+        let entries = audit_repository
+            .query(&query)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to query hook audit log: {}", e)))?;
+        Ok(entries.iter().map(audit_entry_to_protobuf).collect())
+    }
 }
 
 #[tonic::async_trait]
@@ -126,6 +279,12 @@ impl HookService for HookServiceServer {
         // 转换protobuf类型到内部类型
         let hook_item = protobuf_to_hook_config_item(&req, None)
             .map_err(|e| Status::invalid_argument(format!("Invalid hook config: {}", e)))?;
+        crate::infrastructure::config::ConfigValidator::validate_transport(&req.hook_type, &hook_item)
+            .map_err(|e| Status::invalid_argument(format!("Invalid hook config: {}", e)))?;
+        if hook_item.canary.is_some() {
+            crate::infrastructure::config::ConfigValidator::validate_hook(&hook_item)
+                .map_err(|e| Status::invalid_argument(format!("Invalid canary config: {}", e)))?;
+        }
 
         // 保存到数据库（优先从 Context 提取，其次从请求参数）
         let created_by = ctx
@@ -297,6 +456,26 @@ impl HookService for HookServiceServer {
         if req.priority != 0 {
             hook_item.priority = req.priority;
         }
+        if !req.group.is_empty() {
+            hook_item.group = Some(parse_hook_group(&req.group).map_err(|e| {
+                Status::invalid_argument(e.to_string())
+            })?);
+        }
+        if req.cache_ttl_ms != 0 {
+            hook_item.cache = hook_cache_config(req.cache_ttl_ms, req.cache_max_entries);
+        }
+        if req.sample_per_minute != 0 {
+            // 保留已配置的采样租户范围
+            let tenants = hook_item
+                .sampling
+                .take()
+                .map(|s| s.tenants)
+                .unwrap_or_default();
+            hook_item.sampling = Some(HookSamplingConfig {
+                per_minute: req.sample_per_minute,
+                tenants,
+            });
+        }
         if let Some(ref transport) = req.transport {
             hook_item.transport = match transport.r#type.as_str() {
                 "grpc" => {
@@ -334,6 +513,7 @@ impl HookService for HookServiceServer {
                         },
                         load_balance,
                         metadata: transport.metadata.clone(),
+                        tls: None,
                     }
                 }
                 "webhook" => HookTransportConfig::Webhook {
@@ -343,11 +523,39 @@ impl HookService for HookServiceServer {
                     } else {
                         Some(transport.secret.clone())
                     },
+                    previous_secret: transport
+                        .metadata
+                        .get(WEBHOOK_PREVIOUS_SECRET_METADATA_KEY)
+                        .cloned(),
                     headers: transport.headers.clone(),
+                    timeout_ms: (transport.timeout_ms > 0).then_some(transport.timeout_ms as u64),
+                    max_retries: None,
                 },
                 "local" => HookTransportConfig::Local {
                     target: transport.target.clone(),
+                    script: transport.metadata.get(LOCAL_SCRIPT_METADATA_KEY).cloned(),
+                    rate_limit: transport
+                        .metadata
+                        .get(LOCAL_RATE_LIMIT_METADATA_KEY)
+                        .map(|raw| serde_json::from_str::<RateLimitHookConfig>(raw))
+                        .transpose()
+                        .map_err(|e| {
+                            Status::invalid_argument(format!("Invalid rate_limit config: {}", e))
+                        })?,
                 },
+                "nats" => HookTransportConfig::Nats {
+                    url: transport.endpoint.clone(),
+                    subject: transport.target.clone(),
+                    timeout_ms: (transport.timeout_ms > 0).then_some(transport.timeout_ms as u64),
+                    metadata: transport.metadata.clone(),
+                },
+                "kafka" => HookTransportConfig::Kafka {
+                    profile: transport.service_name.clone(),
+                    topic: transport.target.clone(),
+                    metadata: transport.metadata.clone(),
+                },
+                "federated" => federated_transport(transport)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?,
                 _ => {
                     return Err(Status::invalid_argument(format!(
                         "Unsupported transport type: {}",
@@ -356,15 +564,36 @@ impl HookService for HookServiceServer {
                 }
             };
             hook_item.timeout_ms = transport.timeout_ms as u64;
+            // 切换主版本地址即全量切换，未同时指定金丝雀版本时结束灰度
+            if req.canary_version.is_empty() {
+                hook_item.canary = None;
+            }
+        }
+        if !req.canary_version.is_empty() {
+            hook_item.canary = hook_canary_config(
+                &req.canary_version,
+                &req.canary_endpoint,
+                req.canary_percentage,
+                &req.canary_tenants,
+                &hook_item.transport,
+            );
+            crate::infrastructure::config::ConfigValidator::validate_hook(&hook_item)
+                .map_err(|e| Status::invalid_argument(format!("Invalid canary config: {}", e)))?;
         }
         if let Some(ref selector) = req.selector {
             hook_item.selector = HookSelectorConfig {
                 tenants: selector.tenants.clone(),
                 conversation_types: selector.conversation_types.clone(),
                 message_types: selector.message_types.clone(),
+                business_types: selector.business_types.clone(),
+                tag_expressions: selector.tag_expressions.clone(),
                 user_ids: vec![],
                 tags: std::collections::HashMap::new(),
             };
+            hook_item
+                .selector
+                .compile()
+                .map_err(|e| Status::invalid_argument(format!("Invalid selector: {}", e)))?;
         }
         if let Some(ref retry_policy) = req.retry_policy {
             hook_item.max_retries = retry_policy.max_retries as u32;
@@ -375,6 +604,7 @@ impl HookService for HookServiceServer {
             } else {
                 hook_item.error_policy = "fail_fast".to_string();
             }
+            hook_item.retry = hook_retry_config(retry_policy);
         }
 
         // 更新数据库
@@ -436,59 +666,51 @@ impl HookService for HookServiceServer {
             None
         };
 
-        // 查询Hook配置（支持enabled_only过滤和租户过滤）
-        let hook_type_filter = if req.hook_type.is_empty() {
-            None
-        } else {
-            Some(req.hook_type.as_str())
-        };
+        let filter = list_filter(&req, tenant_id)?;
 
-        let rows = self
+        // 分页：cursor 为上一页响应返回的不透明游标（编码下一页的起始偏移量）
+        let mut pagination = req.pagination.unwrap_or_default();
+        let codec = CursorCodec::global();
+        let offset = codec
+            .decode::<u64>(HOOK_CONFIGS_CURSOR_SCOPE, &pagination.cursor)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?
+            .unwrap_or(0);
+        let limit = HOOK_PAGE_LIMIT.clamp(pagination.limit as i64);
+
+        let (rows, total_count) = self
             .repository
-            .query(tenant_id.as_deref(), hook_type_filter, req.enabled_only)
+            .list_page(&filter, offset as i64, limit as i64)
             .await
             .map_err(|e| Status::internal(format!("Failed to query hook configs: {}", e)))?;
 
         // 转换为protobuf类型
-        let mut configs = Vec::new();
-
+        let mut configs = Vec::with_capacity(rows.len());
         for row in rows {
             let hook_item: crate::domain::model::HookConfigItem = row
                 .clone()
                 .try_into()
                 .map_err(|e| Status::internal(format!("Failed to convert hook config: {}", e)))?;
-
-            // 应用enabled_only过滤（如果查询时没有过滤）
-            if !req.enabled_only || hook_item.enabled {
-                configs.push(
-                    hook_config_item_to_protobuf(
-                        &row.id.to_string(),
-                        row.tenant_id.as_deref().unwrap_or(""),
-                        &row.hook_type,
-                        &hook_item,
-                    )
-                    .map_err(|e| {
-                        Status::internal(format!("Failed to convert hook config: {}", e))
-                    })?,
-                );
-            }
+            configs.push(
+                hook_config_item_to_protobuf(
+                    &row.id.to_string(),
+                    row.tenant_id.as_deref().unwrap_or(""),
+                    &row.hook_type,
+                    &hook_item,
+                )
+                .map_err(|e| Status::internal(format!("Failed to convert hook config: {}", e)))?,
+            );
         }
 
-        // 应用分页限制
-        let limit = req
-            .pagination
-            .as_ref()
-            .map(|p| p.limit as usize)
-            .unwrap_or(100)
-            .min(1000); // 最多1000条
-
-        let total_count = configs.len();
-        let configs: Vec<_> = configs.into_iter().take(limit).collect();
-
         // 更新分页信息
-        let mut pagination = req.pagination.unwrap_or_default();
-        pagination.has_more = total_count > configs.len();
-        pagination.total_size = total_count as i64;
+        let request_cursor = std::mem::take(&mut pagination.cursor);
+        PageInfo::from_offset(
+            codec,
+            HOOK_CONFIGS_CURSOR_SCOPE,
+            offset,
+            configs.len(),
+            total_count.max(0) as u64,
+        )
+        .apply_to(&mut pagination, request_cursor, limit);
 
         Ok(Response::new(ListHookConfigsResponse {
             configs,
@@ -520,37 +742,15 @@ impl HookService for HookServiceServer {
             return Err(Status::invalid_argument("hook_id is required"));
         }
 
-        // 解析hook_id（格式：hook_type:name 或 id）
-        let hook_id_parsed = req.hook_id.parse::<i64>();
-
-        let deleted = if let Ok(id) = hook_id_parsed {
-            // 作为数字ID查询并删除
-            if let Ok(Some((row, _))) = self.repository.get_by_id(id).await {
-                self.repository
-                    .delete(row.tenant_id.as_deref(), &row.hook_type, &row.name)
-                    .await
-                    .map_err(|e| Status::internal(format!("Failed to delete hook config: {}", e)))?
-            } else {
-                false
-            }
-        } else {
-            // 作为hook_type:name格式解析
-            let parts: Vec<&str> = req.hook_id.splitn(2, ':').collect();
-            if parts.len() != 2 {
-                return Err(Status::invalid_argument(
-                    "Invalid hook_id format, expected numeric id or 'hook_type:name'",
-                ));
-            }
-
-            let hook_type = parts[0];
-            let name = parts[1];
-
-            // 删除Hook配置
-            self.repository
-                .delete(tenant_id.as_deref(), hook_type, name)
-                .await
-                .map_err(|e| Status::internal(format!("Failed to delete hook config: {}", e)))?
-        };
+        // 解析hook_id（格式：hook_type:name 或 id），按数字ID删除时校验租户归属
+        let (row, _) = self
+            .resolve_hook_row(tenant_id.as_deref(), &req.hook_id)
+            .await?;
+        let deleted = self
+            .repository
+            .delete(row.tenant_id.as_deref(), &row.hook_type, &row.name)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to delete hook config: {}", e)))?;
 
         if !deleted {
             return Err(Status::not_found("Hook config not found"));
@@ -591,32 +791,11 @@ impl HookService for HookServiceServer {
             return Err(Status::invalid_argument("hook_id is required"));
         }
 
-        // 解析hook_id（格式：hook_type:name 或 id）
-        let hook_id_parsed = req.hook_id.parse::<i64>();
-
-        let hook_id = if let Ok(id) = hook_id_parsed {
-            id
-        } else {
-            // 作为hook_type:name格式解析，需要先查询获取ID
-            let parts: Vec<&str> = req.hook_id.splitn(2, ':').collect();
-            if parts.len() != 2 {
-                return Err(Status::invalid_argument(
-                    "Invalid hook_id format, expected numeric id or 'hook_type:name'",
-                ));
-            }
-
-            let hook_type = parts[0];
-            let name = parts[1];
-
-            let (row, _) = self
-                .repository
-                .get_by_name(tenant_id.as_deref(), hook_type, name)
-                .await
-                .map_err(|e| Status::internal(format!("Failed to get hook config: {}", e)))?
-                .ok_or_else(|| Status::not_found("Hook config not found"))?;
-
-            row.id
-        };
+        // 解析hook_id（格式：hook_type:name 或 id），按数字ID更新时校验租户归属
+        let (row, _) = self
+            .resolve_hook_row(tenant_id.as_deref(), &req.hook_id)
+            .await?;
+        let hook_id = row.id;
 
         // 更新数据库中的enabled字段
         let updated = self
@@ -664,85 +843,101 @@ impl HookService for HookServiceServer {
             return Err(Status::invalid_argument("hook_id is required"));
         }
 
-        // 解析hook_id（格式：hook_type:name 或 id）
-        let hook_id_parsed = req.hook_id.parse::<i64>();
+        // 解析hook_id（格式：hook_type:name[#version] 或 id）
+        let (row, hook_name) = if let Ok(id) = req.hook_id.parse::<i64>() {
+            let (row, _) = self
+                .repository
+                .get_by_id(id)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to get hook config: {}", e)))?
+                .ok_or_else(|| Status::not_found("Hook config not found"))?;
 
-        let hook_id = if let Ok(id) = hook_id_parsed {
-            // 作为数字ID使用
-            id
+            // 按数字ID查询时校验Hook归属，避免跨租户读取统计数据
+            if tenant_id.is_some() && row.tenant_id != tenant_id {
+                return Err(Status::not_found("Hook config not found"));
+            }
+            let hook_name = format!("{}:{}", row.hook_type, row.name);
+            (row, hook_name)
         } else {
-            // 作为hook_type:name格式解析，需要先查询获取ID
+            // 作为hook_type:name格式解析（按租户查询），金丝雀版本统计带 #version 后缀
             let parts: Vec<&str> = req.hook_id.splitn(2, ':').collect();
             if parts.len() != 2 {
                 return Err(Status::invalid_argument(
                     "Invalid hook_id format, expected numeric id or 'hook_type:name'",
                 ));
             }
-
-            let hook_type = parts[0];
-            let name = parts[1];
+            let name = parts[1].split('#').next().unwrap_or(parts[1]);
 
             let (row, _) = self
                 .repository
-                .get_by_name(tenant_id.as_deref(), hook_type, name)
+                .get_by_name(tenant_id.as_deref(), parts[0], name)
                 .await
                 .map_err(|e| Status::internal(format!("Failed to get hook config: {}", e)))?
                 .ok_or_else(|| Status::not_found("Hook config not found"))?;
-
-            row.id
+            (row, req.hook_id.clone())
         };
 
-        // 从监控系统查询统计数据
-        let statistics = if let Some(ref metrics_collector) = self.metrics_collector {
-            // 根据hook_id查询统计信息
-            // hook_id可能是数字ID或hook_type:name格式，需要转换为hook名称
-            let hook_name = if hook_id_parsed.is_ok() {
-                // 如果是数字ID，需要先查询获取hook名称
-                if let Ok(Some((row, _))) = self.repository.get_by_id(hook_id).await {
-                    format!("{}:{}", row.hook_type, row.name)
-                } else {
-                    hook_id.to_string()
-                }
-            } else {
-                // 如果是hook_type:name格式，直接使用
-                req.hook_id.clone()
-            };
+        // 未携带租户的管理请求查看Hook所属租户的统计
+        let stats_tenant = tenant_id.as_deref().or(row.tenant_id.as_deref());
+        let statistics = self
+            .collect_statistics(row.id, stats_tenant, &hook_name)
+            .await;
 
-            // 从MetricsCollector查询统计数据
-            if let Some(stats) = metrics_collector.get_statistics(&hook_name).await {
-                // 将domain model转换为protobuf类型
-                domain_to_protobuf_statistics(hook_id.to_string(), &stats)
-            } else {
-                // 没有统计数据，返回空统计数据
-                HookStatistics {
-                    hook_id: hook_id.to_string(),
-                    total_executions: 0,
-                    success_count: 0,
-                    failure_count: 0,
-                    avg_latency_ms: 0.0,
-                    p99_latency_ms: 0.0,
-                    rate_limit_count: 0,
-                    circuit_break_count: 0,
-                    error_count_by_code: std::collections::HashMap::new(),
-                }
-            }
+        Ok(Response::new(GetHookStatisticsResponse {
+            statistics: Some(statistics),
+            status: Some(RpcStatus {
+                code: ErrorCode::Ok as i32,
+                message: "OK".to_string(),
+                details: vec![],
+                context: Some(ErrorContext {
+                    service: "hook-engine".to_string(),
+                    instance: "default".to_string(),
+                    region: String::new(),
+                    zone: String::new(),
+                    attributes: std::collections::HashMap::new(),
+                }),
+            }),
+        }))
+    }
+
+    async fn list_hook_statistics(
+        &self,
+        request: Request<ListHookStatisticsRequest>,
+    ) -> Result<Response<ListHookStatisticsResponse>, Status> {
+        let tenant_id = extract_tenant_id(&request);
+        let req = request.into_inner();
+
+        // 统计数据按租户隔离，只返回本租户配置的Hook
+        let tenant_id = if !req.tenant_id.is_empty() {
+            req.tenant_id.clone()
         } else {
-            // 没有监控系统，返回空统计数据
-            HookStatistics {
-                hook_id: hook_id.to_string(),
-                total_executions: 0,
-                success_count: 0,
-                failure_count: 0,
-                avg_latency_ms: 0.0,
-                p99_latency_ms: 0.0,
-                rate_limit_count: 0,
-                circuit_break_count: 0,
-                error_count_by_code: std::collections::HashMap::new(),
-            }
+            tenant_id.ok_or_else(|| Status::invalid_argument("tenant_id is required"))?
         };
 
-        Ok(Response::new(GetHookStatisticsResponse {
-            statistics: Some(statistics),
+        let hook_type_filter = if req.hook_type.is_empty() {
+            None
+        } else {
+            Some(req.hook_type.as_str())
+        };
+
+        let rows = self
+            .repository
+            .query(Some(&tenant_id), hook_type_filter, req.enabled_only)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to query hook configs: {}", e)))?;
+
+        let limit = HOOK_PAGE_LIMIT.clamp(req.limit as i64);
+        let mut statistics = Vec::with_capacity(rows.len().min(limit));
+        for row in rows.iter().take(limit) {
+            let hook_name = format!("{}:{}", row.hook_type, row.name);
+            statistics.push(
+                self.collect_statistics(row.id, Some(&tenant_id), &hook_name)
+                    .await,
+            );
+        }
+
+        Ok(Response::new(ListHookStatisticsResponse {
+            statistics,
             status: Some(RpcStatus {
                 code: ErrorCode::Ok as i32,
                 message: "OK".to_string(),
@@ -762,10 +957,14 @@ impl HookService for HookServiceServer {
         &self,
         request: Request<QueryHookExecutionsRequest>,
     ) -> Result<Response<QueryHookExecutionsResponse>, Status> {
+        let tenant_id = extract_tenant_id(&request);
         let req = request.into_inner();
 
-        // 从执行记录器查询历史记录
-        let executions = if let Some(ref execution_recorder) = self.execution_recorder {
+        // 优先从审计日志查询，未启用审计日志时从执行记录器查询历史记录
+        let executions = if let Some(ref audit_repository) = self.audit_repository {
+            self.query_audit_executions(audit_repository.as_ref(), tenant_id, &req)
+                .await?
+        } else if let Some(ref execution_recorder) = self.execution_recorder {
             // 解析hook_id（格式：hook_type:name 或 id）
             let hook_name = if !req.hook_id.is_empty() {
                 let hook_id_parsed = req.hook_id.parse::<i64>();
@@ -785,12 +984,8 @@ impl HookService for HookServiceServer {
             };
 
             // 确定查询限制
-            let limit = req
-                .pagination
-                .as_ref()
-                .map(|p| p.limit as usize)
-                .unwrap_or(100)
-                .min(1000); // 最多1000条
+            let limit =
+                HOOK_PAGE_LIMIT.clamp(req.pagination.as_ref().map_or(0, |p| p.limit as i64));
 
             // 从ExecutionRecorder查询执行记录
             let records = execution_recorder.query(hook_name.as_deref(), limit).await;
@@ -859,9 +1054,106 @@ impl HookService for HookServiceServer {
             vec![]
         };
 
+        // 执行记录只返回最近一页，不支持继续翻页
+        let mut pagination = req.pagination.unwrap_or_default();
+        let limit = HOOK_PAGE_LIMIT.clamp(pagination.limit as i64);
+        let request_cursor = std::mem::take(&mut pagination.cursor);
+        PageInfo::default().apply_to(&mut pagination, request_cursor, limit);
+
         Ok(Response::new(QueryHookExecutionsResponse {
             executions,
-            pagination: req.pagination,
+            pagination: Some(pagination),
+            status: Some(RpcStatus {
+                code: ErrorCode::Ok as i32,
+                message: "OK".to_string(),
+                details: vec![],
+                context: Some(ErrorContext {
+                    service: "hook-engine".to_string(),
+                    instance: "default".to_string(),
+                    region: String::new(),
+                    zone: String::new(),
+                    attributes: std::collections::HashMap::new(),
+                }),
+            }),
+        }))
+    }
+
+    async fn simulate_pre_send(
+        &self,
+        request: Request<SimulatePreSendRequest>,
+    ) -> Result<Response<SimulatePreSendResponse>, Status> {
+        let req = request.into_inner();
+        let command_handler = self
+            .command_handler
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("Hook simulation is not enabled"))?;
+
+        let context = req
+            .context
+            .as_ref()
+            .ok_or_else(|| Status::invalid_argument("context is required"))?;
+        let draft = req
+            .draft
+            .as_ref()
+            .ok_or_else(|| Status::invalid_argument("draft is required"))?;
+
+        // 仅支持PreSend类Hook链（默认 pre_send）
+        let hook_type = if req.hook_type.is_empty() {
+            "pre_send"
+        } else {
+            req.hook_type.as_str()
+        };
+        if !matches!(hook_type, "pre_send" | "push_pre_send") {
+            return Err(Status::invalid_argument(format!(
+                "Unsupported hook_type for simulation: {}",
+                hook_type
+            )));
+        }
+
+        // 候选配置（尚未保存或未上线的Hook）按名称覆盖当前生效的Hook
+        let candidates = req
+            .candidate_hooks
+            .iter()
+            .map(|candidate| {
+                let item = protobuf_to_hook_config_item(candidate, None)?;
+                crate::infrastructure::config::ConfigValidator::validate_hook(&item)?;
+                crate::infrastructure::config::ConfigValidator::validate_transport(hook_type, &item)?;
+                Ok(item)
+            })
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| Status::invalid_argument(format!("Invalid candidate hook: {}", e)))?;
+
+        let ctx = proto_to_context(context);
+        let plans = self
+            .registry
+            .build_shadow_plans(ctx.tenant_id(), hook_type, candidates)
+            .await
+            .map_err(|e| Status::failed_precondition(e.to_string()))?
+            .into_iter()
+            .filter(|plan| plan.matches(&ctx))
+            .collect();
+
+        let simulation = command_handler
+            .handle_simulate_pre_send(&ctx, &proto_to_message_draft(draft), plans)
+            .await;
+
+        tracing::info!(
+            hook_type,
+            hooks = simulation.traces.len(),
+            outcome = simulation.outcome.as_str(),
+            "Simulated PreSend hook chain"
+        );
+
+        Ok(Response::new(SimulatePreSendResponse {
+            allow: !matches!(
+                simulation.outcome,
+                crate::domain::model::HookTraceOutcome::Reject { .. }
+                    | crate::domain::model::HookTraceOutcome::Error { .. }
+            ),
+            outcome: simulation.outcome.as_str().to_string(),
+            message: simulation.outcome.message().unwrap_or_default().to_string(),
+            traces: simulation.traces.iter().map(hook_trace_to_protobuf).collect(),
+            draft: Some(message_draft_to_proto(&simulation.draft)),
             status: Some(RpcStatus {
                 code: ErrorCode::Ok as i32,
                 message: "OK".to_string(),
@@ -876,6 +1168,313 @@ impl HookService for HookServiceServer {
             }),
         }))
     }
+
+    async fn query_hook_samples(
+        &self,
+        request: Request<QueryHookSamplesRequest>,
+    ) -> Result<Response<QueryHookSamplesResponse>, Status> {
+        let tenant_id = extract_tenant_id(&request);
+        let req = request.into_inner();
+
+        // 采样数据按租户隔离，只能查询本租户的采样
+        let tenant_id = if !req.tenant_id.is_empty() {
+            req.tenant_id.clone()
+        } else {
+            tenant_id.ok_or_else(|| Status::invalid_argument("tenant_id is required"))?
+        };
+
+        // 解析hook_id（格式：hook_type:name 或 id）
+        let hook_id = if req.hook_id.is_empty() {
+            None
+        } else if let Ok(id) = req.hook_id.parse::<i64>() {
+            let (row, _) = self
+                .repository
+                .get_by_id(id)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to get hook config: {}", e)))?
+                .ok_or_else(|| Status::not_found("Hook config not found"))?;
+            Some(format!("{}:{}", row.hook_type, row.name))
+        } else {
+            Some(req.hook_id.clone())
+        };
+
+        let limit = HOOK_PAGE_LIMIT.clamp(req.limit as i64);
+        let samples = self
+            .registry
+            .sample_store()
+            .query(&tenant_id, hook_id.as_deref(), limit)
+            .iter()
+            .map(hook_sample_to_protobuf)
+            .collect();
+
+        Ok(Response::new(QueryHookSamplesResponse {
+            samples,
+            status: Some(RpcStatus {
+                code: ErrorCode::Ok as i32,
+                message: "OK".to_string(),
+                details: vec![],
+                context: Some(ErrorContext {
+                    service: "hook-engine".to_string(),
+                    instance: "default".to_string(),
+                    region: String::new(),
+                    zone: String::new(),
+                    attributes: std::collections::HashMap::new(),
+                }),
+            }),
+        }))
+    }
+
+    async fn list_hook_config_versions(
+        &self,
+        request: Request<ListHookConfigVersionsRequest>,
+    ) -> Result<Response<ListHookConfigVersionsResponse>, Status> {
+        let tenant_id = extract_tenant_id(&request);
+        let req = request.into_inner();
+
+        if req.hook_id.is_empty() {
+            return Err(Status::invalid_argument("hook_id is required"));
+        }
+
+        let (row, _) = self
+            .resolve_hook_row(tenant_id.as_deref(), &req.hook_id)
+            .await?;
+
+        let limit = HOOK_VERSION_PAGE_LIMIT.clamp(req.limit as i64);
+        let versions = self
+            .repository
+            .list_versions(row.id, limit)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to list hook config versions: {}", e)))?
+            .iter()
+            .map(hook_config_version_to_protobuf)
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| Status::internal(format!("Failed to convert hook config: {}", e)))?;
+
+        Ok(Response::new(ListHookConfigVersionsResponse {
+            versions,
+            status: Some(RpcStatus {
+                code: ErrorCode::Ok as i32,
+                message: "OK".to_string(),
+                details: vec![],
+                context: Some(ErrorContext {
+                    service: "hook-engine".to_string(),
+                    instance: "default".to_string(),
+                    region: String::new(),
+                    zone: String::new(),
+                    attributes: std::collections::HashMap::new(),
+                }),
+            }),
+        }))
+    }
+
+    async fn rollback_hook_config(
+        &self,
+        request: Request<RollbackHookConfigRequest>,
+    ) -> Result<Response<RollbackHookConfigResponse>, Status> {
+        let ctx = require_context(&request).map_err(|_| Status::internal("Context not found"))?;
+        let tenant_id = ctx.tenant_id().map(str::to_string);
+        let operator = ctx.user_id().map(|s| s.to_string());
+        let req = request.into_inner();
+
+        if req.hook_id.is_empty() {
+            return Err(Status::invalid_argument("hook_id is required"));
+        }
+        if req.revision == 0 {
+            return Err(Status::invalid_argument("revision is required"));
+        }
+
+        let (row, _) = self
+            .resolve_hook_row(tenant_id.as_deref(), &req.hook_id)
+            .await?;
+
+        let new_revision = self
+            .repository
+            .rollback(row.id, req.revision, operator.as_deref())
+            .await
+            .map_err(|e| Status::internal(format!("Failed to rollback hook config: {}", e)))?
+            .ok_or_else(|| {
+                Status::not_found(format!("Hook config revision {} not found", req.revision))
+            })?;
+
+        tracing::info!(
+            hook_id = row.id,
+            hook_type = %row.hook_type,
+            name = %row.name,
+            target_revision = req.revision,
+            new_revision,
+            "Hook config rolled back"
+        );
+
+        // 通知配置监听器重新加载配置
+        self.registry
+            .reload_config()
+            .await
+            .map_err(|e| Status::internal(format!("Failed to reload config: {}", e)))?;
+
+        let (row, hook_item) = self
+            .repository
+            .get_by_id(row.id)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get hook config: {}", e)))?
+            .ok_or_else(|| Status::not_found("Hook config not found"))?;
+        let hook_config = hook_config_item_to_protobuf(
+            &row.id.to_string(),
+            row.tenant_id.as_deref().unwrap_or_default(),
+            &row.hook_type,
+            &hook_item,
+        )
+        .map_err(|e| Status::internal(format!("Failed to convert hook config: {}", e)))?;
+
+        Ok(Response::new(RollbackHookConfigResponse {
+            config: Some(hook_config),
+            revision: new_revision,
+            status: Some(RpcStatus {
+                code: ErrorCode::Ok as i32,
+                message: "OK".to_string(),
+                details: vec![],
+                context: Some(ErrorContext {
+                    service: "hook-engine".to_string(),
+                    instance: "default".to_string(),
+                    region: String::new(),
+                    zone: String::new(),
+                    attributes: std::collections::HashMap::new(),
+                }),
+            }),
+        }))
+    }
+
+    async fn query_hook_usage(
+        &self,
+        request: Request<QueryHookUsageRequest>,
+    ) -> Result<Response<QueryHookUsageResponse>, Status> {
+        let tenant_id = extract_tenant_id(&request);
+        let req = request.into_inner();
+
+        let usage_repository = self
+            .usage_repository
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("Hook usage accounting is not enabled"))?;
+
+        // 请求未指定租户时按调用方租户过滤，平台运维（无租户上下文）可查询所有租户
+        let tenant_id = if !req.tenant_id.is_empty() {
+            Some(req.tenant_id.clone())
+        } else {
+            tenant_id
+        };
+
+        // 解析hook_id（格式：hook_type:name 或 id）
+        let hook_id = if req.hook_id.is_empty() {
+            None
+        } else if let Ok(id) = req.hook_id.parse::<i64>() {
+            let (row, _) = self
+                .repository
+                .get_by_id(id)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to get hook config: {}", e)))?
+                .ok_or_else(|| Status::not_found("Hook config not found"))?;
+            Some((row.hook_type, row.name))
+        } else {
+            let (hook_type, name) = req
+                .hook_id
+                .split_once(':')
+                .ok_or_else(|| Status::invalid_argument("hook_id must be an id or hook_type:name"))?;
+            Some((hook_type.to_string(), name.to_string()))
+        };
+        let (hook_type, hook_name) = hook_id.unzip();
+
+        let query = HookUsageQuery {
+            tenant_id,
+            hook_type,
+            hook_name,
+            start_date: parse_usage_date(&req.start_date)?,
+            end_date: parse_usage_date(&req.end_date)?,
+            limit: HOOK_PAGE_LIMIT.clamp(req.limit as i64),
+        };
+        let usage = usage_repository
+            .query(&query)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to query hook usage: {}", e)))?
+            .iter()
+            .map(hook_usage_to_protobuf)
+            .collect();
+
+        Ok(Response::new(QueryHookUsageResponse {
+            usage,
+            status: Some(RpcStatus {
+                code: ErrorCode::Ok as i32,
+                message: "OK".to_string(),
+                details: vec![],
+                context: Some(ErrorContext {
+                    service: "hook-engine".to_string(),
+                    instance: "default".to_string(),
+                    region: String::new(),
+                    zone: String::new(),
+                    attributes: std::collections::HashMap::new(),
+                }),
+            }),
+        }))
+    }
+}
+
+/// 将配置版本转换为protobuf类型
+fn hook_config_version_to_protobuf(
+    version: &HookConfigVersion,
+) -> Result<flare_proto::hooks::HookConfigVersion> {
+    let config = hook_config_item_to_protobuf(
+        &version.hook_config_id.to_string(),
+        version.tenant_id.as_deref().unwrap_or_default(),
+        &version.hook_type,
+        &version.config,
+    )?;
+    Ok(flare_proto::hooks::HookConfigVersion {
+        revision: version.revision,
+        config: Some(config),
+        change_type: version.change_type.as_str().to_string(),
+        created_by: version.created_by.clone().unwrap_or_default(),
+        created_at: Some(
+            crate::infrastructure::adapters::conversion::system_time_to_timestamp(
+                version.created_at,
+            ),
+        ),
+    })
+}
+
+/// 将采样记录转换为protobuf类型
+fn hook_sample_to_protobuf(sample: &HookSample) -> flare_proto::hooks::HookSample {
+    flare_proto::hooks::HookSample {
+        sample_id: sample.id.to_string(),
+        hook_id: sample.hook_id(),
+        tenant_id: sample.tenant_id.clone(),
+        operation: sample.operation.to_string(),
+        captured_at: Some(
+            crate::infrastructure::adapters::conversion::system_time_to_timestamp(
+                sample.captured_at,
+            ),
+        ),
+        latency_ms: sample.latency_ms as i64,
+        success: sample.success,
+        request_json: sample.request.to_string(),
+        response_json: if sample.response.is_null() {
+            String::new()
+        } else {
+            sample.response.to_string()
+        },
+        error_message: sample.error_message.clone().unwrap_or_default(),
+    }
+}
+
+/// 将预演记录转换为protobuf类型
+fn hook_trace_to_protobuf(trace: &HookTrace) -> HookSimulationTrace {
+    HookSimulationTrace {
+        hook_name: trace.hook_name.clone(),
+        group: trace.group.as_str().to_string(),
+        priority: trace.priority,
+        outcome: trace.outcome.as_str().to_string(),
+        message: trace.outcome.message().unwrap_or_default().to_string(),
+        latency_ms: trace.latency_ms as i64,
+        draft_modified: trace.draft_modified,
+        circuit_open: trace.circuit_open,
+    }
 }
 
 /// 将统计数据转换为protobuf类型
@@ -889,13 +1488,73 @@ fn domain_to_protobuf_statistics(
         success_count: stats.success_count as i64,
         failure_count: stats.failure_count as i64,
         avg_latency_ms: stats.avg_latency_ms,
-        p99_latency_ms: 0.0,    // 暂时不计算P99延迟
-        rate_limit_count: 0,    // 暂时不统计限流次数
-        circuit_break_count: 0, // 暂时不统计熔断次数
+        p50_latency_ms: stats.p50_latency_ms(),
+        p99_latency_ms: stats.p99_latency_ms(),
+        rate_limit_count: 0, // 暂时不统计限流次数
+        circuit_break_count: stats.circuit_rejected_count as i64,
+        circuit_state: stats.circuit_state.as_str().to_string(),
+        deadline_skipped_count: stats.deadline_skipped_count as i64,
+        chain_budget_skipped_count: stats.chain_budget_skipped_count as i64,
+        health_status: stats.health_status.as_str().to_string(),
+        health_skipped_count: stats.health_skipped_count as i64,
         error_count_by_code: std::collections::HashMap::new(), // 暂时不统计错误码
     }
 }
 
+/// 将资源用量转换为protobuf类型
+fn hook_usage_to_protobuf(record: &HookUsageRecord) -> HookUsage {
+    HookUsage {
+        date: record.date.to_string(),
+        tenant_id: record.tenant_id.clone(),
+        hook_id: record.hook_id(),
+        hook_type: record.hook_type.clone(),
+        hook_name: record.hook_name.clone(),
+        invocation_count: record.invocations as i64,
+        failure_count: record.failures as i64,
+        total_latency_ms: record.total_latency_ms as i64,
+        avg_latency_ms: record.avg_latency_ms() as i64,
+        egress_bytes: record.egress_bytes as i64,
+    }
+}
+
+/// 解析用量查询日期（`YYYY-MM-DD`，空字符串表示不限制）
+fn parse_usage_date(value: &str) -> Result<Option<NaiveDate>, Status> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|_| {
+            Status::invalid_argument(format!("Invalid date {}, expected YYYY-MM-DD", value))
+        })
+}
+
+/// 构建Hook配置列表的过滤条件：类型、启用状态、名称关键字、分组、传输类型（空字符串表示不过滤）
+fn list_filter(
+    req: &ListHookConfigsRequest,
+    tenant_id: Option<String>,
+) -> Result<HookConfigFilter, Status> {
+    let non_empty = |value: &str| (!value.is_empty()).then(|| value.to_string());
+    let enabled = match (req.enabled_only, req.disabled_only) {
+        (true, true) => {
+            return Err(Status::invalid_argument(
+                "enabled_only and disabled_only are mutually exclusive",
+            ));
+        }
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        (false, false) => None,
+    };
+    Ok(HookConfigFilter {
+        tenant_id,
+        hook_type: non_empty(&req.hook_type),
+        enabled,
+        name_keyword: non_empty(&req.name_keyword),
+        group: non_empty(&req.group),
+        transport_type: non_empty(&req.transport_type),
+    })
+}
+
 /// 将protobuf类型转换为内部HookConfigItem类型
 fn protobuf_to_hook_config_item(
     req: &CreateHookConfigRequest,
@@ -913,6 +1572,8 @@ fn protobuf_to_hook_config_item(
             tenants: s.tenants.clone(),
             conversation_types: s.conversation_types.clone(),
             message_types: s.message_types.clone(),
+            business_types: s.business_types.clone(),
+            tag_expressions: s.tag_expressions.clone(),
             user_ids: vec![],
             tags: std::collections::HashMap::new(),
         })
@@ -955,6 +1616,7 @@ fn protobuf_to_hook_config_item(
                 },
                 load_balance,
                 metadata: transport.metadata.clone(),
+                tls: None,
             }
         }
         "webhook" => HookTransportConfig::Webhook {
@@ -964,11 +1626,40 @@ fn protobuf_to_hook_config_item(
             } else {
                 Some(transport.secret.clone())
             },
+            previous_secret: transport
+                .metadata
+                .get(WEBHOOK_PREVIOUS_SECRET_METADATA_KEY)
+                .cloned(),
             headers: transport.headers.clone(),
+            timeout_ms: (transport.timeout_ms > 0).then_some(transport.timeout_ms as u64),
+            max_retries: None,
         },
+        // 本地传输：target 为插件名称，内嵌脚本与限流配置放在 metadata 中
         "local" => HookTransportConfig::Local {
             target: transport.target.clone(),
+            script: transport.metadata.get(LOCAL_SCRIPT_METADATA_KEY).cloned(),
+            rate_limit: transport
+                .metadata
+                .get(LOCAL_RATE_LIMIT_METADATA_KEY)
+                .map(|raw| serde_json::from_str::<RateLimitHookConfig>(raw))
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid rate_limit config: {}", e))?,
+        },
+        // NATS传输：endpoint 为服务器地址，target 为请求主题
+        "nats" => HookTransportConfig::Nats {
+            url: transport.endpoint.clone(),
+            subject: transport.target.clone(),
+            timeout_ms: (transport.timeout_ms > 0).then_some(transport.timeout_ms as u64),
+            metadata: transport.metadata.clone(),
         },
+        // Kafka传输：service_name 为Kafka配置档名称，target 为Topic
+        "kafka" => HookTransportConfig::Kafka {
+            profile: transport.service_name.clone(),
+            topic: transport.target.clone(),
+            metadata: transport.metadata.clone(),
+        },
+        // 联邦传输：endpoint 为中心Hook引擎地址，降级策略与决策缓存以JSON放在 metadata 中
+        "federated" => federated_transport(transport)?,
         _ => {
             return Err(anyhow::anyhow!(
                 "Unsupported transport type: {}",
@@ -988,13 +1679,25 @@ fn protobuf_to_hook_config_item(
         "fail_fast".to_string()
     };
 
+    let canary = hook_canary_config(
+        &req.canary_version,
+        &req.canary_endpoint,
+        req.canary_percentage,
+        &req.canary_tenants,
+        &transport_config,
+    );
+
     Ok(HookConfigItem {
         name: req.name.clone(),
         version: None,
         description: None,
         enabled: true,
         priority: req.priority,
-        group: None,
+        group: if req.group.is_empty() {
+            None
+        } else {
+            Some(parse_hook_group(&req.group)?)
+        },
         timeout_ms: transport.timeout_ms as u64,
         max_retries,
         error_policy,
@@ -1002,9 +1705,165 @@ fn protobuf_to_hook_config_item(
         selector,
         transport: transport_config,
         metadata: std::collections::HashMap::new(),
+        cache: hook_cache_config(req.cache_ttl_ms, req.cache_max_entries),
+        sampling: (req.sample_per_minute > 0).then(|| HookSamplingConfig {
+            per_minute: req.sample_per_minute,
+            tenants: vec![],
+        }),
+        retry: retry_policy.and_then(hook_retry_config),
+        canary,
+        max_concurrency: None,
+        config_revision: None,
     })
 }
 
+/// 构建金丝雀发布配置（版本号为空表示不灰度）
+///
+/// 金丝雀版本沿用主版本的传输类型和参数，仅替换调用地址
+fn hook_canary_config(
+    version: &str,
+    endpoint: &str,
+    percentage: u32,
+    tenants: &[String],
+    transport: &HookTransportConfig,
+) -> Option<HookCanaryConfig> {
+    if version.is_empty() {
+        return None;
+    }
+    let transport = match transport.clone() {
+        HookTransportConfig::Grpc {
+            registry_type,
+            namespace,
+            load_balance,
+            metadata,
+            tls,
+            ..
+        } => HookTransportConfig::Grpc {
+            endpoint: Some(endpoint.to_string()),
+            service_name: None,
+            registry_type,
+            namespace,
+            load_balance,
+            metadata,
+            tls,
+        },
+        HookTransportConfig::Webhook {
+            secret,
+            previous_secret,
+            headers,
+            timeout_ms,
+            max_retries,
+            ..
+        } => HookTransportConfig::Webhook {
+            endpoint: endpoint.to_string(),
+            secret,
+            previous_secret,
+            headers,
+            timeout_ms,
+            max_retries,
+        },
+        // Local Plugin / NATS / Kafka / 联邦传输不支持灰度，由配置校验拒绝
+        other @ (HookTransportConfig::Local { .. }
+        | HookTransportConfig::Nats { .. }
+        | HookTransportConfig::Kafka { .. }
+        | HookTransportConfig::Federated { .. }) => other,
+    };
+    Some(HookCanaryConfig {
+        version: version.to_string(),
+        transport,
+        percentage,
+        tenants: tenants.to_vec(),
+    })
+}
+
+/// 金丝雀版本的调用地址
+fn canary_endpoint(canary: &HookCanaryConfig) -> String {
+    match &canary.transport {
+        HookTransportConfig::Grpc {
+            endpoint,
+            service_name,
+            ..
+        } => endpoint
+            .clone()
+            .or_else(|| service_name.clone())
+            .unwrap_or_default(),
+        HookTransportConfig::Webhook { endpoint, .. } => endpoint.clone(),
+        HookTransportConfig::Local { target, .. } => target.clone(),
+        HookTransportConfig::Nats { subject, .. } => subject.clone(),
+        HookTransportConfig::Kafka { topic, .. } => topic.clone(),
+        HookTransportConfig::Federated { endpoint, .. } => endpoint.clone(),
+    }
+}
+
+/// 从 proto 传输配置构建联邦传输（metadata 中的降级策略与决策缓存键不作为请求元数据转发）
+fn federated_transport(transport: &HookTransport) -> Result<HookTransportConfig> {
+    let mut metadata = transport.metadata.clone();
+    let fallback = metadata
+        .remove(FEDERATION_FALLBACK_METADATA_KEY)
+        .map(|raw| serde_json::from_str::<FederationFallback>(&raw))
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("Invalid federation fallback config: {}", e))?
+        .unwrap_or_default();
+    let decision_cache = metadata
+        .remove(FEDERATION_DECISION_CACHE_METADATA_KEY)
+        .map(|raw| serde_json::from_str::<HookCacheConfig>(&raw))
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("Invalid federation decision_cache config: {}", e))?;
+    Ok(HookTransportConfig::Federated {
+        endpoint: transport.endpoint.clone(),
+        tls: None,
+        timeout_ms: (transport.timeout_ms > 0).then_some(transport.timeout_ms as u64),
+        metadata,
+        decision_cache,
+        fallback,
+    })
+}
+
+/// 构建失败重试配置（max_retries为0表示不重试，未指定的字段使用默认值）
+fn hook_retry_config(policy: &HookRetryPolicy) -> Option<HookRetryConfig> {
+    if policy.max_retries <= 0 {
+        return None;
+    }
+    let defaults = HookRetryConfig::default();
+    Some(HookRetryConfig {
+        max_retries: policy.max_retries as u32,
+        retry_interval_ms: if policy.retry_interval_ms > 0 {
+            policy.retry_interval_ms as u64
+        } else {
+            defaults.retry_interval_ms
+        },
+        backoff_strategy: BackoffStrategy::parse(&policy.backoff_strategy).unwrap_or_default(),
+        ..defaults
+    })
+}
+
+/// 构建结果缓存配置（ttl为0表示不缓存，max_entries为0时使用默认容量）
+fn hook_cache_config(ttl_ms: u64, max_entries: u32) -> Option<HookCacheConfig> {
+    if ttl_ms == 0 {
+        return None;
+    }
+    let mut cache = HookCacheConfig {
+        ttl_ms,
+        ..Default::default()
+    };
+    if max_entries > 0 {
+        cache.max_entries = max_entries as usize;
+    }
+    Some(cache)
+}
+
+/// 校验并规范化Hook分组（validation/critical/business）
+fn parse_hook_group(group: &str) -> Result<String> {
+    flare_im_core::HookGroup::parse(group)
+        .map(|g| g.as_str().to_string())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid hook group: {}, expected validation/critical/business",
+                group
+            )
+        })
+}
+
 /// 将内部HookConfigItem类型转换为protobuf类型
 fn hook_config_item_to_protobuf(
     hook_id: &str,
@@ -1020,7 +1879,24 @@ fn hook_config_item_to_protobuf(
         hook_type: hook_type.to_string(),
         tenant_id: tenant_id.to_string(),
         priority: item.priority,
+        group: item.group.clone().unwrap_or_default(),
+        cache_ttl_ms: item.cache.as_ref().map(|c| c.ttl_ms).unwrap_or(0),
+        cache_max_entries: item.cache.as_ref().map(|c| c.max_entries as u32).unwrap_or(0),
+        sample_per_minute: item.sampling.as_ref().map(|s| s.per_minute).unwrap_or(0),
+        canary_version: item
+            .canary
+            .as_ref()
+            .map(|c| c.version.clone())
+            .unwrap_or_default(),
+        canary_endpoint: item.canary.as_ref().map(canary_endpoint).unwrap_or_default(),
+        canary_percentage: item.canary.as_ref().map(|c| c.percentage).unwrap_or(0),
+        canary_tenants: item
+            .canary
+            .as_ref()
+            .map(|c| c.tenants.clone())
+            .unwrap_or_default(),
         enabled: item.enabled,
+        config_revision: item.config_revision.unwrap_or(0),
         transport: Some(match &item.transport {
             HookTransportConfig::Grpc {
                 endpoint,
@@ -1029,6 +1905,7 @@ fn hook_config_item_to_protobuf(
                 namespace,
                 load_balance,
                 metadata,
+                ..
             } => HookTransport {
                 r#type: "grpc".to_string(),
                 service_name: service_name.clone().unwrap_or_default(),
@@ -1048,7 +1925,10 @@ fn hook_config_item_to_protobuf(
             HookTransportConfig::Webhook {
                 endpoint,
                 secret,
+                previous_secret,
                 headers,
+                timeout_ms,
+                ..
             } => HookTransport {
                 r#type: "webhook".to_string(),
                 service_name: String::new(),
@@ -1059,10 +1939,22 @@ fn hook_config_item_to_protobuf(
                 secret: secret.clone().unwrap_or_default(),
                 headers: headers.clone(),
                 target: String::new(),
-                timeout_ms: item.timeout_ms as i32,
-                metadata: std::collections::HashMap::new(),
+                timeout_ms: timeout_ms.unwrap_or(item.timeout_ms) as i32,
+                metadata: previous_secret
+                    .iter()
+                    .map(|secret| {
+                        (
+                            WEBHOOK_PREVIOUS_SECRET_METADATA_KEY.to_string(),
+                            secret.clone(),
+                        )
+                    })
+                    .collect(),
             },
-            HookTransportConfig::Local { target } => HookTransport {
+            HookTransportConfig::Local {
+                target,
+                script,
+                rate_limit,
+            } => HookTransport {
                 r#type: "local".to_string(),
                 service_name: String::new(),
                 endpoint: String::new(),
@@ -1073,19 +1965,107 @@ fn hook_config_item_to_protobuf(
                 headers: std::collections::HashMap::new(),
                 target: target.clone(),
                 timeout_ms: item.timeout_ms as i32,
-                metadata: std::collections::HashMap::new(),
+                metadata: script
+                    .iter()
+                    .map(|script| (LOCAL_SCRIPT_METADATA_KEY.to_string(), script.clone()))
+                    .chain(
+                        rate_limit
+                            .iter()
+                            .filter_map(|config| serde_json::to_string(config).ok())
+                            .map(|config| (LOCAL_RATE_LIMIT_METADATA_KEY.to_string(), config)),
+                    )
+                    .collect(),
+            },
+            HookTransportConfig::Nats {
+                url,
+                subject,
+                timeout_ms,
+                metadata,
+            } => HookTransport {
+                r#type: "nats".to_string(),
+                service_name: String::new(),
+                endpoint: url.clone(),
+                registry_type: String::new(),
+                namespace: String::new(),
+                load_balance: String::new(),
+                secret: String::new(),
+                headers: std::collections::HashMap::new(),
+                target: subject.clone(),
+                timeout_ms: timeout_ms.unwrap_or(item.timeout_ms) as i32,
+                metadata: metadata.clone(),
+            },
+            HookTransportConfig::Kafka {
+                profile,
+                topic,
+                metadata,
+            } => HookTransport {
+                r#type: "kafka".to_string(),
+                service_name: profile.clone(),
+                endpoint: String::new(),
+                registry_type: String::new(),
+                namespace: String::new(),
+                load_balance: String::new(),
+                secret: String::new(),
+                headers: std::collections::HashMap::new(),
+                target: topic.clone(),
+                timeout_ms: item.timeout_ms as i32,
+                metadata: metadata.clone(),
+            },
+            HookTransportConfig::Federated {
+                endpoint,
+                timeout_ms,
+                metadata,
+                decision_cache,
+                fallback,
+                ..
+            } => HookTransport {
+                r#type: "federated".to_string(),
+                service_name: String::new(),
+                endpoint: endpoint.clone(),
+                registry_type: String::new(),
+                namespace: String::new(),
+                load_balance: String::new(),
+                secret: String::new(),
+                headers: std::collections::HashMap::new(),
+                target: String::new(),
+                timeout_ms: timeout_ms.unwrap_or(item.timeout_ms) as i32,
+                metadata: metadata
+                    .clone()
+                    .into_iter()
+                    .chain(
+                        serde_json::to_string(fallback)
+                            .ok()
+                            .map(|config| (FEDERATION_FALLBACK_METADATA_KEY.to_string(), config)),
+                    )
+                    .chain(
+                        decision_cache
+                            .iter()
+                            .filter_map(|config| serde_json::to_string(config).ok())
+                            .map(|config| {
+                                (FEDERATION_DECISION_CACHE_METADATA_KEY.to_string(), config)
+                            }),
+                    )
+                    .collect(),
             },
         }),
         selector: Some(HookSelector {
             tenants: item.selector.tenants.clone(),
             conversation_types: item.selector.conversation_types.clone(),
             message_types: item.selector.message_types.clone(),
-            business_types: vec![], // 暂时不支持business_types
+            business_types: item.selector.business_types.clone(),
+            tag_expressions: item.selector.tag_expressions.clone(),
         }),
-        retry_policy: Some(HookRetryPolicy {
-            max_retries: item.max_retries as i32,
-            retry_interval_ms: 1000,                     // 默认值
-            backoff_strategy: "exponential".to_string(), // 默认值
+        retry_policy: Some(match item.retry.as_ref() {
+            Some(retry) => HookRetryPolicy {
+                max_retries: retry.max_retries as i32,
+                retry_interval_ms: retry.retry_interval_ms as _,
+                backoff_strategy: retry.backoff_strategy.as_str().to_string(),
+            },
+            None => HookRetryPolicy {
+                max_retries: item.max_retries as i32,
+                retry_interval_ms: 1000,                     // 默认值
+                backoff_strategy: "exponential".to_string(), // 默认值
+            },
         }),
         created_at: Some(prost_types::Timestamp {
             seconds: now.timestamp(),
@@ -1099,6 +2079,39 @@ fn hook_config_item_to_protobuf(
 }
 
 /// 将执行结果转换为protobuf类型
+fn timestamp_to_system_time(timestamp: &prost_types::Timestamp) -> std::time::SystemTime {
+    std::time::SystemTime::UNIX_EPOCH
+        + std::time::Duration::new(timestamp.seconds.max(0) as u64, timestamp.nanos.max(0) as u32)
+}
+
+/// 审计记录转换为执行记录（拒绝和出错的执行记为失败，error_code区分两者）
+fn audit_entry_to_protobuf(entry: &HookAuditEntry) -> HookExecution {
+    let executed_at = entry
+        .executed_at
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .map(|d| prost_types::Timestamp {
+            seconds: d.as_secs() as i64,
+            nanos: d.subsec_nanos() as i32,
+        })
+        .unwrap_or_default();
+
+    HookExecution {
+        execution_id: entry.id.to_string(),
+        hook_id: entry.hook_id(),
+        message_id: entry.message_id.clone().unwrap_or_default(),
+        success: entry.decision == HookAuditDecision::Continue,
+        latency_ms: entry.latency_ms as i32,
+        error_code: match entry.decision {
+            HookAuditDecision::Continue => String::new(),
+            HookAuditDecision::Reject => "HOOK_REJECTED".to_string(),
+            HookAuditDecision::Error => "HOOK_ERROR".to_string(),
+        },
+        error_message: entry.reason.clone().unwrap_or_default(),
+        executed_at: Some(executed_at),
+        config_revision: entry.config_revision.unwrap_or(0),
+    }
+}
+
 fn domain_to_protobuf_execution(
     execution_id: String,
     hook_id: String,
@@ -1142,5 +2155,109 @@ fn domain_to_protobuf_execution(
         error_code: String::new(), // 暂时不填充错误码
         error_message: result.error_message.clone().unwrap_or_default(),
         executed_at: Some(executed_at),
+        config_revision: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_request(transport: HookTransport) -> CreateHookConfigRequest {
+        CreateHookConfigRequest {
+            name: "content-filter".to_string(),
+            priority: 100,
+            transport: Some(transport),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_create_local_hook_from_proto() {
+        let plugin = create_request(HookTransport {
+            r#type: "local".to_string(),
+            target: "sensitive_word_filter".to_string(),
+            ..Default::default()
+        });
+        let item = protobuf_to_hook_config_item(&plugin, None).unwrap();
+        assert!(matches!(
+            item.transport,
+            HookTransportConfig::Local { ref target, script: None, rate_limit: None }
+                if target == "sensitive_word_filter"
+        ));
+
+        let script = create_request(HookTransport {
+            r#type: "local".to_string(),
+            target: "deny_links".to_string(),
+            metadata: [(
+                LOCAL_SCRIPT_METADATA_KEY.to_string(),
+                "if draft.payload.contains(\"http\") { reject(\"links\") }".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        });
+        let item = protobuf_to_hook_config_item(&script, None).unwrap();
+        assert!(matches!(
+            item.transport,
+            HookTransportConfig::Local { script: Some(_), .. }
+        ));
+
+        let invalid = create_request(HookTransport {
+            r#type: "local".to_string(),
+            target: "limiter".to_string(),
+            metadata: [(
+                LOCAL_RATE_LIMIT_METADATA_KEY.to_string(),
+                "not json".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        });
+        assert!(protobuf_to_hook_config_item(&invalid, None).is_err());
+    }
+
+    #[test]
+    fn test_list_filter_from_request() {
+        let filter = list_filter(&ListHookConfigsRequest::default(), None).unwrap();
+        assert!(filter.tenant_id.is_none());
+        assert!(filter.hook_type.is_none());
+        assert!(filter.enabled.is_none());
+        assert!(filter.name_keyword.is_none());
+        assert!(filter.group.is_none());
+        assert!(filter.transport_type.is_none());
+
+        let request = ListHookConfigsRequest {
+            hook_type: "pre_send".to_string(),
+            name_keyword: "filter".to_string(),
+            group: "validation".to_string(),
+            transport_type: "grpc".to_string(),
+            disabled_only: true,
+            ..Default::default()
+        };
+        let filter = list_filter(&request, Some("tenant-a".to_string())).unwrap();
+        assert_eq!(filter.tenant_id.as_deref(), Some("tenant-a"));
+        assert_eq!(filter.hook_type.as_deref(), Some("pre_send"));
+        assert_eq!(filter.enabled, Some(false));
+        assert_eq!(filter.name_keyword.as_deref(), Some("filter"));
+        assert_eq!(filter.group.as_deref(), Some("validation"));
+        assert_eq!(filter.transport_type.as_deref(), Some("grpc"));
+
+        let enabled_only = ListHookConfigsRequest {
+            enabled_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            list_filter(&enabled_only, None).unwrap().enabled,
+            Some(true)
+        );
+
+        let conflicting = ListHookConfigsRequest {
+            enabled_only: true,
+            disabled_only: true,
+            ..Default::default()
+        };
+        let err = list_filter(&conflicting, None).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
     }
 }
@@ -10,8 +10,6 @@ use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
 use crate::application::handlers::HookCommandHandler;
-use crate::domain::model::HookExecutionPlan;
-use crate::infrastructure::adapters::HookAdapterFactory;
 use crate::infrastructure::adapters::conversion::{
     context_to_proto, delivery_event_to_proto, message_draft_to_proto,
     message_record_to_proto, proto_to_message_draft, proto_to_pre_send_decision,
@@ -27,19 +25,16 @@ use flare_server_core::context::Context;
 pub struct HookExtensionServer {
     command_handler: Arc<HookCommandHandler>,
     registry: Arc<CoreHookRegistry>,
-    adapter_factory: Arc<HookAdapterFactory>,
 }
 
 impl HookExtensionServer {
     pub fn new(
         command_handler: Arc<HookCommandHandler>,
         registry: Arc<CoreHookRegistry>,
-        adapter_factory: Arc<HookAdapterFactory>,
     ) -> Self {
         Self {
             command_handler,
             registry,
-            adapter_factory,
         }
     }
 
@@ -108,39 +103,11 @@ impl HookExtensionServer {
             message_id: proto.message_id.clone(),
             operator_id: proto.operator_id.clone(),
             recalled_at,
+            sent_at: RecallEvent::sent_at_from_metadata(&proto.metadata),
             metadata: proto.metadata.clone(),
         })
     }
 
-    /// 从 HookConfigItem 创建 HookExecutionPlan（包含适配器）
-    ///
-    /// # 参数
-    /// * `config` - Hook配置项
-    /// * `hook_type` - Hook类型（pre_send, post_send, delivery, recall等）
-    async fn create_execution_plan(
-        &self,
-        config: crate::domain::model::HookConfigItem,
-        hook_type: &str,
-    ) -> Result<HookExecutionPlan> {
-        let mut plan = HookExecutionPlan::from_hook_config(config.clone(), hook_type);
-
-        // 如果配置已启用且不是 Local Plugin，创建适配器
-        if config.enabled {
-            if !matches!(
-                config.transport,
-                crate::domain::model::HookTransportConfig::Local { .. }
-            ) {
-                let adapter = self
-                    .adapter_factory
-                    .create_adapter(&config.transport)
-                    .await?;
-                plan = plan.with_adapter(adapter);
-            }
-        }
-
-        Ok(plan)
-    }
-
     /// 构建 RpcStatus
     fn build_rpc_status(code: i32, message: &str) -> RpcStatus {
         RpcStatus {
@@ -188,26 +155,11 @@ impl HookExtension for HookExtensionServer {
         let ctx = Self::proto_to_context(&context);
         let mut message_draft = proto_to_message_draft(&draft);
 
-        // 获取PreSend Hook列表
-        let hooks = self
+        // 获取PreSend Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_pre_send_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self.create_execution_plan(hook_config, "pre_send").await {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "pre_send")
+            .await;
 
         // 执行Hook
         let decision = self
@@ -260,26 +212,11 @@ impl HookExtension for HookExtensionServer {
             .map_err(|e| Status::invalid_argument(format!("Invalid record: {}", e)))?;
         let message_draft = proto_to_message_draft(&draft);
 
-        // 获取PostSend Hook列表
-        let hooks = self
+        // 获取PostSend Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_post_send_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self.create_execution_plan(hook_config, "post_send").await {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "post_send")
+            .await;
 
         // 执行Hook
         self.command_handler
@@ -310,26 +247,11 @@ impl HookExtension for HookExtensionServer {
         let delivery_event = Self::proto_to_delivery_event(&event)
             .map_err(|e| Status::invalid_argument(format!("Invalid event: {}", e)))?;
 
-        // 获取Delivery Hook列表
-        let hooks = self
+        // 获取Delivery Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_delivery_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self.create_execution_plan(hook_config, "delivery").await {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "delivery")
+            .await;
 
         // 执行Hook
         self.command_handler
@@ -360,26 +282,11 @@ impl HookExtension for HookExtensionServer {
         let recall_event = Self::proto_to_recall_event(&event)
             .map_err(|e| Status::invalid_argument(format!("Invalid event: {}", e)))?;
 
-        // 获取Recall Hook列表
-        let hooks = self
+        // 获取Recall Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_recall_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self.create_execution_plan(hook_config, "recall").await {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "recall")
+            .await;
 
         // 执行Hook
         let decision = self
@@ -421,29 +328,11 @@ impl HookExtension for HookExtensionServer {
         // 转换为内部类型
         let ctx = Self::proto_to_context(&context);
 
-        // 获取ConversationLifecycle Hook列表
-        let hooks = self
+        // 获取ConversationLifecycle Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_conversation_lifecycle_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self
-                    .create_execution_plan(hook_config, "conversation_lifecycle")
-                    .await
-                {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "conversation_lifecycle")
+            .await;
 
         // 执行Hook（目前只记录日志，后续可以根据Hook类型实现具体逻辑）
         use crate::infrastructure::adapters::hook_context_data::get_hook_context_data;
@@ -534,31 +423,13 @@ impl HookExtension for HookExtensionServer {
             .ok_or_else(|| Status::invalid_argument("draft is required"))?;
 
         // 转换为内部类型
-        let _ctx = Self::proto_to_context(&context);
+        let ctx = Self::proto_to_context(&context);
 
-        // 获取PushPreSend Hook列表
-        let hooks = self
+        // 获取PushPreSend Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_push_pre_send_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self
-                    .create_execution_plan(hook_config, "push_pre_send")
-                    .await
-                {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "push_pre_send")
+            .await;
 
         // 执行Hook（目前只记录日志，后续可以实现类似 PreSend 的逻辑）
         for plan in execution_plans {
@@ -594,31 +465,13 @@ impl HookExtension for HookExtensionServer {
             .ok_or_else(|| Status::invalid_argument("draft is required"))?;
 
         // 转换为内部类型
-        let _ctx = Self::proto_to_context(&_context);
+        let ctx = Self::proto_to_context(&_context);
 
-        // 获取PushPostSend Hook列表
-        let hooks = self
+        // 获取PushPostSend Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_push_post_send_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self
-                    .create_execution_plan(hook_config, "push_post_send")
-                    .await
-                {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "push_post_send")
+            .await;
 
         // 执行Hook（目前只记录日志，后续可以实现类似 PostSend 的逻辑）
         for plan in execution_plans {
@@ -647,31 +500,13 @@ impl HookExtension for HookExtensionServer {
             .ok_or_else(|| Status::invalid_argument("event is required"))?;
 
         // 转换为内部类型
-        let _ctx = Self::proto_to_context(&_context);
+        let ctx = Self::proto_to_context(&_context);
 
-        // 获取PushDelivery Hook列表
-        let hooks = self
+        // 获取PushDelivery Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_push_delivery_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self
-                    .create_execution_plan(hook_config, "push_delivery")
-                    .await
-                {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "push_delivery")
+            .await;
 
         // 执行Hook（目前只记录日志，后续可以实现类似 Delivery 的逻辑）
         for plan in execution_plans {
@@ -703,28 +538,13 @@ impl HookExtension for HookExtensionServer {
             .ok_or_else(|| Status::invalid_argument("event is required"))?;
 
         // 转换为内部类型
-        let _ctx = Self::proto_to_context(&_context);
+        let ctx = Self::proto_to_context(&_context);
 
-        // 获取UserLogin Hook列表
-        let hooks = self
+        // 获取UserLogin Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_user_login_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self.create_execution_plan(hook_config, "user_login").await {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "user_login")
+            .await;
 
         // 执行Hook（目前只记录日志，后续可以实现类似 PreSend 的逻辑，可以拒绝登录）
         for plan in execution_plans {
@@ -756,28 +576,13 @@ impl HookExtension for HookExtensionServer {
             .ok_or_else(|| Status::invalid_argument("event is required"))?;
 
         // 转换为内部类型
-        let _ctx = Self::proto_to_context(&_context);
+        let ctx = Self::proto_to_context(&_context);
 
-        // 获取UserLogout Hook列表
-        let hooks = self
+        // 获取UserLogout Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_user_logout_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self.create_execution_plan(hook_config, "user_logout").await {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "user_logout")
+            .await;
 
         // 执行Hook（目前只记录日志，后续可以实现类似 PostSend 的逻辑）
         for plan in execution_plans {
@@ -808,28 +613,13 @@ impl HookExtension for HookExtensionServer {
             .ok_or_else(|| Status::invalid_argument("event is required"))?;
 
         // 转换为内部类型
-        let _ctx = Self::proto_to_context(&_context);
+        let ctx = Self::proto_to_context(&_context);
 
-        // 获取UserOnline Hook列表
-        let hooks = self
+        // 获取UserOnline Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_user_online_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self.create_execution_plan(hook_config, "user_online").await {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "user_online")
+            .await;
 
         // 执行Hook（目前只记录日志，后续可以实现类似 PostSend 的逻辑）
         for plan in execution_plans {
@@ -860,31 +650,13 @@ impl HookExtension for HookExtensionServer {
             .ok_or_else(|| Status::invalid_argument("event is required"))?;
 
         // 转换为内部类型
-        let _ctx = Self::proto_to_context(&_context);
+        let ctx = Self::proto_to_context(&_context);
 
-        // 获取UserOffline Hook列表
-        let hooks = self
+        // 获取UserOffline Hook执行计划（配置变更时由注册表整体重建）
+        let execution_plans = self
             .registry
-            .get_user_offline_hooks()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to get hooks: {}", e)))?;
-
-        // 创建HookExecutionPlan（包含适配器）
-        let mut execution_plans = Vec::new();
-        for hook_config in hooks {
-            if hook_config.enabled {
-                match self
-                    .create_execution_plan(hook_config, "user_offline")
-                    .await
-                {
-                    Ok(plan) => execution_plans.push(plan),
-                    Err(e) => {
-                        tracing::warn!(error = %e, "Failed to create execution plan, skipping hook");
-                        continue;
-                    }
-                }
-            }
-        }
+            .select_execution_plans(&ctx, "user_offline")
+            .await;
 
         // 执行Hook（目前只记录日志，后续可以实现类似 PostSend 的逻辑）
         for plan in execution_plans {
@@ -903,3 +675,45 @@ impl HookExtension for HookExtensionServer {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::RecallDefaultPolicy;
+    use crate::infrastructure::adapters::HookAdapter;
+    use crate::infrastructure::adapters::default_policy::DefaultPolicyHookAdapter;
+    use std::collections::HashMap;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    #[tokio::test]
+    async fn test_recall_sent_time_survives_grpc_conversion() {
+        let recalled_at = UNIX_EPOCH + Duration::from_secs(1_700_000_600);
+        let event = RecallEvent {
+            message_id: "msg-1".to_string(),
+            operator_id: "user-1".to_string(),
+            recalled_at,
+            sent_at: None,
+            metadata: HashMap::new(),
+        }
+        .with_sent_at(recalled_at - Duration::from_secs(300));
+
+        let proto = recall_event_to_proto(&event);
+        let converted = HookExtensionServer::proto_to_recall_event(&proto).unwrap();
+        assert_eq!(converted.sent_at, event.sent_at);
+        assert_eq!(converted.recalled_at, recalled_at);
+
+        // 默认撤回策略基于转换后的发送时间判断时长
+        let adapter = DefaultPolicyHookAdapter::Recall(RecallDefaultPolicy {
+            deny_older_than_secs: Some(120),
+        });
+        let ctx = Context::with_request_id("req-1".to_string());
+        let decision = adapter.recall(&ctx, &converted).await.unwrap();
+        assert!(!decision.is_continue());
+
+        // 调用方未携带发送时间时为 None
+        let mut proto = proto;
+        proto.metadata.clear();
+        let converted = HookExtensionServer::proto_to_recall_event(&proto).unwrap();
+        assert_eq!(converted.sent_at, None::<SystemTime>);
+    }
+}
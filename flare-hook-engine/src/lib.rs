@@ -26,7 +26,11 @@ pub mod service;
 
 // Re-export commonly used types
 pub use domain::model::{
-    ExecutionMode, HookConfig, HookExecutionPlan, HookExecutionResult, HookStatistics,
+    CircuitState, ExecutionMode, HookConfig, HookExecutionPlan, HookExecutionResult,
+    HookStatistics,
 };
+pub use infrastructure::circuit_breaker::{CircuitBreakerConfig, CircuitBreakerRegistry};
+pub use infrastructure::concurrency::{ConcurrencyConfig, HookConcurrencyLimiter};
+pub use infrastructure::health::{HookHealthConfig, HookHealthRegistry};
 pub use infrastructure::config::{ConfigLoader, ConfigWatcher};
 pub use service::ApplicationBootstrap;
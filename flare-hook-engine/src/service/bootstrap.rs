@@ -22,10 +22,40 @@ pub struct HookEngineConfig {
     pub config_center_endpoint: Option<String>,
     /// 租户ID（可选，用于多租户场景）
     pub tenant_id: Option<String>,
-    /// 执行模式（串行/并发）
+    /// PreSend business组执行模式（串行/并发）
     pub execution_mode: crate::domain::model::ExecutionMode,
+    /// 并发执行时的草稿合并策略
+    pub draft_merge: crate::domain::model::DraftMergeStrategy,
     /// 配置刷新间隔（秒）
     pub refresh_interval_secs: u64,
+    /// Hook熔断配置
+    pub circuit_breaker: crate::infrastructure::circuit_breaker::CircuitBreakerConfig,
+    /// gRPC/WebHook端点健康检查配置
+    pub health_check: crate::infrastructure::health::HookHealthConfig,
+    /// gRPC Hook的Channel配置（连接超时、keep-alive）
+    pub grpc_channel: crate::infrastructure::adapters::grpc_pool::GrpcChannelConfig,
+    /// Hook执行并发限制（租户、单Hook、全局在途执行数上限）
+    pub concurrency: crate::infrastructure::concurrency::ConcurrencyConfig,
+    /// PostSend/Delivery重试耗尽后的死信队列（可选）
+    pub dead_letter: Option<crate::infrastructure::dead_letter::DeadLetterConfig>,
+    /// Hook执行审计日志（可选，需配置数据库）
+    pub audit: Option<crate::infrastructure::audit::HookAuditConfig>,
+    /// Hook资源用量统计（可选，需配置数据库，按天、租户、Hook聚合用于成本分摊）
+    pub usage: Option<crate::infrastructure::usage::HookUsageConfig>,
+    /// 动态库插件目录（可选，启动时加载其中的 `.so` / `.dylib` 插件）
+    pub plugin_dir: Option<std::path::PathBuf>,
+    /// Redis地址（可选，内置限流Hook的计数存储）
+    pub redis_url: Option<String>,
+    /// 请求预算保留量：调用方剩余预算不足该值时跳过剩余的business组Hook
+    pub deadline_reserve: std::time::Duration,
+    /// Hook链路总预算（按Hook类型），耗尽后跳过剩余的非必需Hook
+    pub chain_budgets: std::collections::HashMap<String, std::time::Duration>,
+    /// 为Hook执行指标记录 trace_id exemplar（启用OTLP追踪时开启）
+    pub trace_exemplars: bool,
+    /// 慢Hook告警阈值（None 表示不告警）
+    pub slow_hook_threshold: Option<std::time::Duration>,
+    /// PostSend/Delivery后台重试在途上限（超过后直接写入死信）
+    pub max_pending_retries: usize,
 }
 
 impl Default for HookEngineConfig {
@@ -36,7 +66,22 @@ impl Default for HookEngineConfig {
             config_center_endpoint: None,
             tenant_id: None,
             execution_mode: crate::domain::model::ExecutionMode::Sequential,
+            draft_merge: Default::default(),
             refresh_interval_secs: 60,
+            circuit_breaker: Default::default(),
+            health_check: Default::default(),
+            grpc_channel: Default::default(),
+            concurrency: Default::default(),
+            dead_letter: None,
+            audit: None,
+            usage: None,
+            plugin_dir: None,
+            redis_url: None,
+            deadline_reserve: crate::domain::service::DEFAULT_DEADLINE_RESERVE,
+            chain_budgets: Default::default(),
+            trace_exemplars: false,
+            slow_hook_threshold: Some(crate::domain::service::DEFAULT_SLOW_HOOK_THRESHOLD),
+            max_pending_retries: crate::domain::service::DEFAULT_MAX_PENDING_RETRIES,
         }
     }
 }
@@ -47,10 +92,10 @@ pub struct ApplicationBootstrap;
 impl ApplicationBootstrap {
     /// 运行应用的主入口点
     pub async fn run(config: HookEngineConfig) -> Result<()> {
-        use flare_im_core::load_config;
+        use flare_im_core::{load_config_with_validation, strict_mode};
 
         // 加载应用配置
-        let app_config = load_config(Some("config"));
+        let app_config = load_config_with_validation(Some("config"), strict_mode())?;
 
         // 解析服务器地址
         let address: SocketAddr = format!(
@@ -114,11 +159,13 @@ impl ApplicationBootstrap {
                             );
                         
                         Server::builder()
+                            .add_service(flare_im_core::ServerInfoServer::from_global())
                             .add_service(hook_extension_service)
                             .add_service(hook_service_wrapped)
                     }
                     None => {
                         Server::builder()
+                            .add_service(flare_im_core::ServerInfoServer::from_global())
                             .add_service(hook_extension_service)
                     }
                 };
@@ -1,67 +1,721 @@
 //! # Hook服务注册
 //!
 //! 提供Hook服务的注册和管理
+//!
+//! 注册表持有由当前配置构建好的 `HookExecutionPlan` 集合（含适配器），
+//! 配置变更时重建并原子替换；与上一版本配置完全相同的Hook直接复用已构建的执行计划
+//! （保留结果缓存、适配器连接和已编译的选择器），只重建变更的Hook，已删除Hook的状态随旧集合释放；
+//! 新配置无法构建适配器时保留旧集合（回滚），
+//! 并记录被拒绝配置的指纹，配置源仍是该配置时不再重复构建，直到配置源发生变化。
+//!
+//! 配置了租户专属Hook的租户会单独生成一份与全局Hook链合并后的执行计划，
+//! 执行时按请求上下文中的租户选择，未配置的租户使用全局Hook链。
 
-use crate::domain::model::HookConfigItem;
-use crate::infrastructure::config::ConfigWatcher;
-use anyhow::Result;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, error, info, warn};
+
+use crate::domain::model::{
+    HookConfig, HookConfigItem, HookDefaultPolicies, HookExecutionPlan, HookTransportConfig,
+};
+use crate::infrastructure::adapters::canary::CanaryHookAdapter;
+use crate::infrastructure::adapters::default_policy::{
+    DEFAULT_POLICY_HOOK_PREFIX, DefaultPolicyHookAdapter,
+};
+use crate::infrastructure::adapters::sampled::SampledHookAdapter;
+use crate::infrastructure::adapters::{HookAdapter, HookAdapterFactory};
+use crate::infrastructure::circuit_breaker::CircuitBreakerRegistry;
+use crate::infrastructure::config::ConfigWatcher;
+use crate::infrastructure::health::{HookHealth, HookHealthRegistry};
+use crate::infrastructure::monitoring::MetricsCollector;
+use crate::infrastructure::sampling::{HookSampleStore, HookSampler};
+
+/// 构建执行计划所需的共享组件
+struct PlanComponents<'a> {
+    adapter_factory: &'a HookAdapterFactory,
+    circuit_breakers: &'a CircuitBreakerRegistry,
+    health: &'a HookHealthRegistry,
+    sample_store: &'a Arc<HookSampleStore>,
+    /// 金丝雀Hook的版本维度统计
+    metrics: &'a Arc<MetricsCollector>,
+}
+
+/// 已构建的Hook执行计划集合
+///
+/// 配置与执行计划总是一起替换，保证读取到的配置与实际执行的计划一致
+pub struct HookPlanSet {
+    /// 对应的配置版本号
+    version: u64,
+    config: HookConfig,
+    /// 全局Hook链
+    plans: HashMap<&'static str, Vec<HookExecutionPlan>>,
+    /// 租户Hook链（已与全局Hook链合并）
+    tenant_plans: HashMap<String, HashMap<&'static str, Vec<HookExecutionPlan>>>,
+    /// 所有Hook的熔断器键
+    breaker_keys: HashSet<String>,
+    /// 按熔断器键索引的已构建执行计划及其配置指纹（下次重建时复用未变更的Hook）
+    built: HashMap<String, (String, HookExecutionPlan)>,
+}
+
+impl HookPlanSet {
+    fn empty() -> Self {
+        Self {
+            version: 0,
+            config: HookConfig::default(),
+            plans: HashMap::new(),
+            tenant_plans: HashMap::new(),
+            breaker_keys: HashSet::new(),
+            built: HashMap::new(),
+        }
+    }
+
+    /// 根据配置构建执行计划集合
+    ///
+    /// 配置与 `previous` 中同一Hook完全相同时复用其执行计划；
+    /// `strict` 为 true 时任一Hook适配器构建失败即返回错误；
+    /// 否则跳过失败的Hook（用于启动时，避免单个下游不可用导致服务无法启动）
+    async fn build(
+        version: u64,
+        config: HookConfig,
+        previous: &HookPlanSet,
+        components: &PlanComponents<'_>,
+        strict: bool,
+    ) -> Result<Self> {
+        let mut breaker_keys = HashSet::new();
+        let mut built = HashMap::new();
+        let mut plans = Self::build_plans(
+            &config,
+            None,
+            previous,
+            components,
+            strict,
+            &mut breaker_keys,
+            &mut built,
+        )
+        .await?;
+
+        // 租户只构建专属Hook，其余Hook复用全局执行计划
+        let mut tenant_plans = HashMap::new();
+        for (tenant_id, tenant_config) in &config.tenants {
+            let overrides = Self::build_plans(
+                tenant_config,
+                Some(tenant_id),
+                previous,
+                components,
+                strict,
+                &mut breaker_keys,
+                &mut built,
+            )
+            .await
+            .with_context(|| format!("Failed to build hooks for tenant {}", tenant_id))?;
+            let mut merged = Self::overlay(&plans, tenant_config, overrides);
+            Self::inject_default_policies(
+                &mut merged,
+                &config.defaults.overlay(&tenant_config.defaults),
+            );
+            tenant_plans.insert(tenant_id.clone(), merged);
+        }
+
+        // 默认策略在租户Hook链合并之后注入，避免全局默认策略混入配置了专属Hook的租户
+        Self::inject_default_policies(&mut plans, &config.defaults);
+
+        Ok(Self {
+            version,
+            config,
+            plans,
+            tenant_plans,
+            breaker_keys,
+            built,
+        })
+    }
+
+    /// 构建配置中所有已启用Hook的执行计划（配置未变更的Hook复用 `previous` 中的执行计划）
+    async fn build_plans(
+        config: &HookConfig,
+        tenant_id: Option<&str>,
+        previous: &HookPlanSet,
+        components: &PlanComponents<'_>,
+        strict: bool,
+        breaker_keys: &mut HashSet<String>,
+        built: &mut HashMap<String, (String, HookExecutionPlan)>,
+    ) -> Result<HashMap<&'static str, Vec<HookExecutionPlan>>> {
+        let mut plans = HashMap::new();
+        for (hook_type, hooks) in Self::hooks_by_type(config) {
+            let mut execution_plans = Vec::new();
+            for hook in hooks.into_iter().filter(|h| h.enabled) {
+                let name = hook.name.clone();
+                let breaker_key = circuit_breaker_key(hook_type, &name, tenant_id);
+                let fingerprint = config_fingerprint(&hook);
+                let reused = previous
+                    .built
+                    .get(&breaker_key)
+                    .filter(|(hash, _)| fingerprint.as_ref() == Some(hash))
+                    .map(|(_, plan)| plan.clone());
+                let plan = match reused {
+                    Some(plan) => Ok(plan),
+                    None => Self::build_plan(hook, hook_type, &breaker_key, components).await,
+                };
+                match plan {
+                    Ok(plan) => {
+                        if let Some(fingerprint) = fingerprint {
+                            built.insert(breaker_key.clone(), (fingerprint, plan.clone()));
+                        }
+                        breaker_keys.insert(breaker_key);
+                        execution_plans.push(plan);
+                    }
+                    Err(e) if strict => {
+                        return Err(e).with_context(|| {
+                            format!("Failed to build adapter for hook {} ({})", name, hook_type)
+                        });
+                    }
+                    Err(e) => {
+                        warn!(hook = %name, hook_type, tenant_id, error = %e, "Failed to create execution plan, skipping hook");
+                    }
+                }
+            }
+            plans.insert(hook_type, execution_plans);
+        }
+        Ok(plans)
+    }
+
+    /// 将租户专属Hook叠加到全局Hook链上（同名Hook以租户配置为准，禁用的租户Hook只移除同名全局Hook）
+    fn overlay(
+        global: &HashMap<&'static str, Vec<HookExecutionPlan>>,
+        tenant_config: &HookConfig,
+        mut overrides: HashMap<&'static str, Vec<HookExecutionPlan>>,
+    ) -> HashMap<&'static str, Vec<HookExecutionPlan>> {
+        let mut merged = global.clone();
+        for (hook_type, hooks) in Self::hooks_by_type(tenant_config) {
+            if hooks.is_empty() {
+                continue;
+            }
+            let plans = merged.entry(hook_type).or_default();
+            plans.retain(|plan| !hooks.iter().any(|h| h.name == plan.name()));
+            plans.extend(overrides.remove(hook_type).unwrap_or_default());
+        }
+        merged
+    }
+
+    /// 为未配置任何Hook的类型注入内置默认策略Hook
+    fn inject_default_policies(
+        plans: &mut HashMap<&'static str, Vec<HookExecutionPlan>>,
+        defaults: &HookDefaultPolicies,
+    ) {
+        let adapters: [(&'static str, Option<DefaultPolicyHookAdapter>); 2] = [
+            (
+                "pre_send",
+                defaults.pre_send.clone().map(DefaultPolicyHookAdapter::PreSend),
+            ),
+            (
+                "recall",
+                defaults.recall.clone().map(DefaultPolicyHookAdapter::Recall),
+            ),
+        ];
+        for (hook_type, adapter) in adapters {
+            let Some(adapter) = adapter else {
+                continue;
+            };
+            let chain = plans.entry(hook_type).or_default();
+            if chain.is_empty() {
+                chain.push(default_policy_plan(hook_type, adapter));
+            }
+        }
+    }
+
+    /// 获取租户的执行计划（未配置租户专属Hook时使用全局Hook链）
+    fn plans_for(&self, tenant_id: Option<&str>, hook_type: &str) -> &[HookExecutionPlan] {
+        tenant_id
+            .and_then(|tenant_id| self.tenant_plans.get(tenant_id))
+            .unwrap_or(&self.plans)
+            .get(hook_type)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// 从 HookConfigItem 创建 HookExecutionPlan（包含适配器、熔断器、金丝雀分流和采样）
+    async fn build_plan(
+        config: HookConfigItem,
+        hook_type: &str,
+        breaker_key: &str,
+        components: &PlanComponents<'_>,
+    ) -> Result<HookExecutionPlan> {
+        let transport = config.transport.clone();
+        let version = config.version.clone();
+        let canary = config.canary.clone();
+        let sampler = config
+            .sampling
+            .clone()
+            .map(|sampling| HookSampler::new(hook_type, &config.name, sampling));
+        let selector = config
+            .selector
+            .compile()
+            .with_context(|| format!("Invalid selector for hook {}", config.name))?;
+        let mut plan =
+            HookExecutionPlan::from_hook_config(config, hook_type).with_selector(selector);
+
+        // Local Plugin 由执行计划自身处理，不需要创建适配器（内嵌脚本、内置限流除外）
+        if !matches!(transport, HookTransportConfig::Local { script: None, rate_limit: None, .. }) {
+            let mut adapter = components.adapter_factory.create_adapter(&transport).await?;
+            if let Some(canary) = canary {
+                let canary_adapter = components
+                    .adapter_factory
+                    .create_adapter(&canary.transport)
+                    .await
+                    .with_context(|| format!("Failed to build canary adapter ({})", canary.version))?;
+                adapter = Arc::new(CanaryHookAdapter::new(
+                    breaker_key,
+                    version.as_deref(),
+                    adapter,
+                    &canary,
+                    canary_adapter,
+                    components.metrics.clone(),
+                ));
+            }
+            if let Some(sampler) = sampler {
+                adapter = Arc::new(SampledHookAdapter::new(
+                    adapter,
+                    sampler,
+                    components.sample_store.clone(),
+                ));
+            }
+            plan = plan
+                .with_adapter(adapter)
+                .with_circuit_breaker(components.circuit_breakers.get_or_create(breaker_key));
+            // 只有远程端点需要健康检查
+            if components.health.config().enabled
+                && matches!(
+                    transport,
+                    HookTransportConfig::Grpc { .. } | HookTransportConfig::Webhook { .. }
+                )
+            {
+                plan = plan.with_health(components.health.get_or_create(breaker_key));
+            }
+        }
+
+        Ok(plan)
+    }
+
+    fn hooks_by_type(config: &HookConfig) -> Vec<(&'static str, Vec<HookConfigItem>)> {
+        let mut lifecycle = Vec::new();
+        lifecycle.extend(config.session_create.iter().cloned());
+        lifecycle.extend(config.session_update.iter().cloned());
+        lifecycle.extend(config.session_delete.iter().cloned());
+
+        vec![
+            ("pre_send", config.pre_send.clone()),
+            ("post_send", config.post_send.clone()),
+            ("delivery", config.delivery.clone()),
+            ("recall", config.recall.clone()),
+            ("conversation_lifecycle", lifecycle),
+            ("user_login", config.user_login.clone()),
+            ("user_logout", config.user_logout.clone()),
+            ("user_online", config.user_online.clone()),
+            ("user_offline", config.user_offline.clone()),
+            ("push_pre_send", config.push_pre_send.clone()),
+            ("push_post_send", config.push_post_send.clone()),
+            ("push_delivery", config.push_delivery.clone()),
+        ]
+    }
+
+    fn plan_count(&self) -> usize {
+        self.plans.values().map(Vec::len).sum()
+    }
+
+    /// 当前集合中所有Hook的熔断器键
+    fn circuit_breaker_keys(&self) -> &HashSet<String> {
+        &self.breaker_keys
+    }
+
+    /// 需要健康检查的端点（租户Hook链复用的全局Hook按健康状态去重）
+    fn health_targets(&self) -> Vec<(Arc<HookHealth>, Arc<dyn HookAdapter>)> {
+        let mut seen = HashSet::new();
+        self.plans
+            .values()
+            .chain(self.tenant_plans.values().flat_map(HashMap::values))
+            .flatten()
+            .filter_map(|plan| Some((plan.health()?.clone(), plan.adapter()?.clone())))
+            .filter(|(health, _)| seen.insert(health.name().to_string()))
+            .collect()
+    }
+}
+
+/// 构建内置默认策略Hook的执行计划（进程内执行，不挂载熔断器）
+fn default_policy_plan(hook_type: &str, adapter: DefaultPolicyHookAdapter) -> HookExecutionPlan {
+    let name = format!("{}:{}", DEFAULT_POLICY_HOOK_PREFIX, hook_type);
+    let config = HookConfigItem {
+        name: name.clone(),
+        version: None,
+        description: Some("Built-in default policy".to_string()),
+        enabled: true,
+        priority: 1000,
+        group: None,
+        timeout_ms: 100,
+        max_retries: 0,
+        error_policy: "fail_fast".to_string(),
+        require_success: true,
+        selector: Default::default(),
+        transport: HookTransportConfig::Local {
+            target: name,
+            script: None,
+            rate_limit: None,
+        },
+        metadata: HashMap::new(),
+        cache: None,
+        sampling: None,
+        retry: None,
+        canary: None,
+        max_concurrency: None,
+        config_revision: None,
+    };
+    HookExecutionPlan::from_hook_config(config, hook_type).with_adapter(Arc::new(adapter))
+}
+
+/// 配置指纹（经 `serde_json::Value` 规范化，HashMap 字段顺序不影响结果）
+///
+/// 用于整体配置（识别被拒绝的配置）和单个Hook配置（判断重建时能否复用执行计划）
+fn config_fingerprint<T: serde::Serialize>(config: &T) -> Option<String> {
+    let value = serde_json::to_value(config).ok()?;
+    let bytes = serde_json::to_vec(&value).ok()?;
+    Some(hex::encode(Sha256::digest(bytes)))
+}
+
+/// 熔断器键（与统计信息的 `hook_type:name` 格式一致，租户专属Hook追加 `@tenant_id`）
+fn circuit_breaker_key(hook_type: &str, name: &str, tenant_id: Option<&str>) -> String {
+    match tenant_id {
+        Some(tenant_id) => format!("{}:{}@{}", hook_type, name, tenant_id),
+        None => format!("{}:{}", hook_type, name),
+    }
+}
 
 /// Hook服务注册表
 pub struct CoreHookRegistry {
     config_watcher: Arc<ConfigWatcher>,
+    adapter_factory: Arc<HookAdapterFactory>,
+    /// 熔断器注册表（跨配置版本保留熔断状态）
+    circuit_breakers: Arc<CircuitBreakerRegistry>,
+    /// 端点健康状态注册表（跨配置版本保留健康状态）
+    health: Arc<HookHealthRegistry>,
+    /// Hook调用采样存储（跨配置版本保留）
+    sample_store: Arc<HookSampleStore>,
+    /// 指标收集器（记录金丝雀Hook各版本的调用统计）
+    metrics: Arc<MetricsCollector>,
+    /// 当前生效的执行计划集合（整体原子替换）
+    plan_set: RwLock<Arc<HookPlanSet>>,
+    /// 串行化重建过程，避免并发重建相互覆盖；同时保存最近一次被拒绝（已回滚）配置的指纹
+    apply_lock: Mutex<Option<String>>,
 }
 
 impl CoreHookRegistry {
-    pub fn new(config_watcher: Arc<ConfigWatcher>) -> Self {
-        Self { config_watcher }
+    pub fn new(
+        config_watcher: Arc<ConfigWatcher>,
+        adapter_factory: Arc<HookAdapterFactory>,
+        circuit_breakers: Arc<CircuitBreakerRegistry>,
+    ) -> Self {
+        Self {
+            config_watcher,
+            adapter_factory,
+            circuit_breakers,
+            health: Arc::new(HookHealthRegistry::default()),
+            sample_store: Arc::new(HookSampleStore::default()),
+            metrics: Arc::new(MetricsCollector::new()),
+            plan_set: RwLock::new(Arc::new(HookPlanSet::empty())),
+            apply_lock: Mutex::new(None),
+        }
+    }
+
+    /// 设置指标收集器（与统计查询共享，使金丝雀版本的统计可查询）
+    pub fn with_metrics_collector(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// 设置端点健康状态注册表（与统计查询共享，使健康状态可查询）
+    pub fn with_health_registry(mut self, health: Arc<HookHealthRegistry>) -> Self {
+        self.health = health;
+        self
+    }
+
+    fn components(&self) -> PlanComponents<'_> {
+        PlanComponents {
+            adapter_factory: &self.adapter_factory,
+            circuit_breakers: &self.circuit_breakers,
+            health: &self.health,
+            sample_store: &self.sample_store,
+            metrics: &self.metrics,
+        }
+    }
+
+    /// 构建初始执行计划并订阅配置变更
+    pub async fn start(self: &Arc<Self>) -> Result<()> {
+        {
+            let _guard = self.apply_lock.lock().await;
+            let version = self.config_watcher.version();
+            let config = self.config_watcher.get_config().await;
+            let plan_set = HookPlanSet::build(
+                version,
+                config,
+                &HookPlanSet::empty(),
+                &self.components(),
+                false,
+            )
+            .await?;
+            info!(
+                version,
+                plans = plan_set.plan_count(),
+                tenants = plan_set.tenant_plans.len(),
+                "Hook execution plans initialized"
+            );
+            *self.plan_set.write().await = Arc::new(plan_set);
+        }
+
+        let mut changes = self.config_watcher.subscribe();
+        let registry = Arc::clone(self);
+        tokio::spawn(async move {
+            while changes.changed().await.is_ok() {
+                // 错误已在 apply_latest 中记录，旧的执行计划继续生效
+                let _ = registry.apply_latest(false).await;
+            }
+        });
+
+        if self.health.config().enabled {
+            let registry = Arc::clone(self);
+            tokio::spawn(async move { registry.run_health_checks().await });
+        }
+
+        Ok(())
+    }
+
+    /// 定期探测当前执行计划中的gRPC/WebHook端点
+    async fn run_health_checks(&self) {
+        let config = self.health.config().clone();
+        let timeout = Duration::from_millis(config.timeout_ms);
+        let mut ticker = tokio::time::interval(Duration::from_millis(config.interval_ms.max(100)));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        info!(
+            interval_ms = config.interval_ms,
+            timeout_ms = config.timeout_ms,
+            "Hook endpoint health checks started"
+        );
+
+        loop {
+            ticker.tick().await;
+            let targets = self.plan_set.read().await.health_targets();
+            let probes = targets.into_iter().map(|(health, adapter)| async move {
+                let result = match tokio::time::timeout(timeout, adapter.health_check()).await {
+                    Ok(Ok(())) => Ok(()),
+                    Ok(Err(e)) => Err(format!("{:#}", e)),
+                    Err(_) => Err(format!(
+                        "health check timed out after {}ms",
+                        timeout.as_millis()
+                    )),
+                };
+                health.record_probe(result);
+            });
+            futures_util::future::join_all(probes).await;
+        }
+    }
+
+    /// 使用最新配置重建执行计划，失败时回滚到上一个可用配置
+    ///
+    /// 回滚后配置源仍会在下次刷新时提交同一份配置，指纹与被拒绝配置一致时直接回滚，
+    /// 不再重复构建适配器和记录错误；`force` 为 true 时（手动重新加载）仍重新构建，
+    /// 用于下游恢复后重试同一份配置
+    async fn apply_latest(&self, force: bool) -> Result<()> {
+        let mut rejected = self.apply_lock.lock().await;
+
+        let version = self.config_watcher.version();
+        let previous = self.plan_set.read().await.clone();
+        if previous.version >= version {
+            return Ok(());
+        }
+
+        let config = self.config_watcher.get_config().await;
+        let fingerprint = config_fingerprint(&config);
+        if !force && fingerprint.is_some() && *rejected == fingerprint {
+            debug!(
+                version,
+                active_version = previous.version,
+                "Hook config unchanged since last rejection, skipped"
+            );
+            self.config_watcher.restore(previous.config.clone()).await;
+            anyhow::bail!("Hook config was rejected before and has not changed");
+        }
+
+        match HookPlanSet::build(version, config, &previous, &self.components(), true).await {
+            Ok(plan_set) => {
+                *rejected = None;
+                info!(
+                    previous_version = previous.version,
+                    version,
+                    plans = plan_set.plan_count(),
+                    tenants = plan_set.tenant_plans.len(),
+                    "Hook execution plans swapped"
+                );
+                // 清理已删除Hook的熔断器和健康状态
+                self.circuit_breakers
+                    .retain(plan_set.circuit_breaker_keys());
+                self.health.retain(plan_set.circuit_breaker_keys());
+                *self.plan_set.write().await = Arc::new(plan_set);
+                Ok(())
+            }
+            Err(e) => {
+                error!(
+                    version,
+                    active_version = previous.version,
+                    error = %e,
+                    "Failed to apply hook config, rolled back to previous config"
+                );
+                self.config_watcher.restore(previous.config.clone()).await;
+                *rejected = fingerprint;
+                Err(e)
+            }
+        }
+    }
+
+    /// Hook调用采样存储
+    pub fn sample_store(&self) -> Arc<HookSampleStore> {
+        self.sample_store.clone()
+    }
+
+    /// 获取指定类型的全局执行计划（仅包含已启用的Hook）
+    pub async fn get_execution_plans(&self, hook_type: &str) -> Vec<HookExecutionPlan> {
+        self.get_execution_plans_for_tenant(None, hook_type).await
+    }
+
+    /// 获取租户的执行计划（仅包含已启用的Hook）
+    ///
+    /// 租户配置了专属Hook时返回与全局Hook链合并后的计划，否则返回全局Hook链
+    pub async fn get_execution_plans_for_tenant(
+        &self,
+        tenant_id: Option<&str>,
+        hook_type: &str,
+    ) -> Vec<HookExecutionPlan> {
+        self.plan_set
+            .read()
+            .await
+            .plans_for(tenant_id, hook_type)
+            .to_vec()
+    }
+
+    /// 获取命中当前请求的执行计划
+    ///
+    /// 在租户执行计划的基础上按Hook选择器（租户、会话类型、消息类型、业务类型、发送者、标签）过滤
+    pub async fn select_execution_plans(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        hook_type: &str,
+    ) -> Vec<HookExecutionPlan> {
+        let plan_set = self.plan_set.read().await;
+        let plans = plan_set.plans_for(ctx.tenant_id(), hook_type);
+        let selected: Vec<HookExecutionPlan> = plans
+            .iter()
+            .filter(|plan| plan.matches(ctx))
+            .cloned()
+            .collect();
+        if selected.len() < plans.len() {
+            debug!(
+                hook_type,
+                skipped = plans.len() - selected.len(),
+                "Hooks skipped by selector"
+            );
+        }
+        selected
+    }
+
+    /// 构建用于预演的执行计划
+    ///
+    /// 以请求租户当前生效的执行计划为基础，`candidates` 中的Hook按名称覆盖或追加（未启用的候选Hook只移除同名Hook）。
+    /// 候选Hook不挂载熔断器，预演不会影响线上熔断状态。
+    pub async fn build_shadow_plans(
+        &self,
+        tenant_id: Option<&str>,
+        hook_type: &str,
+        candidates: Vec<HookConfigItem>,
+    ) -> Result<Vec<HookExecutionPlan>> {
+        let mut plans: Vec<HookExecutionPlan> = self
+            .get_execution_plans_for_tenant(tenant_id, hook_type)
+            .await
+            .into_iter()
+            .filter(|plan| !candidates.iter().any(|c| c.name == plan.name()))
+            .collect();
+
+        for candidate in candidates.into_iter().filter(|c| c.enabled) {
+            let name = candidate.name.clone();
+            let transport = candidate.transport.clone();
+            let selector = candidate
+                .selector
+                .compile()
+                .with_context(|| format!("Invalid selector for candidate hook {}", name))?;
+            let mut plan =
+                HookExecutionPlan::from_hook_config(candidate, hook_type).with_selector(selector);
+            if !matches!(transport, HookTransportConfig::Local { script: None, rate_limit: None, .. }) {
+                let adapter = self
+                    .adapter_factory
+                    .create_adapter(&transport)
+                    .await
+                    .with_context(|| format!("Failed to build adapter for candidate hook {}", name))?;
+                plan = plan.with_adapter(adapter);
+            }
+            plans.push(plan);
+        }
+
+        Ok(plans)
+    }
+
+    async fn current_config(&self) -> HookConfig {
+        self.plan_set.read().await.config.clone()
     }
 
     /// 获取PreSend Hook列表
     pub async fn get_pre_send_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.pre_send)
     }
 
     /// 获取PostSend Hook列表
     pub async fn get_post_send_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.post_send)
     }
 
     /// 获取Delivery Hook列表
     pub async fn get_delivery_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.delivery)
     }
 
     /// 获取Recall Hook列表
     pub async fn get_recall_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.recall)
     }
 
     /// 获取SessionCreate Hook列表
     pub async fn get_session_create_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.session_create)
     }
 
     /// 获取SessionUpdate Hook列表
     pub async fn get_session_update_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.session_update)
     }
 
     /// 获取SessionDelete Hook列表
     pub async fn get_session_delete_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.session_delete)
     }
 
     /// 获取所有ConversationLifecycle Hook列表（合并create/update/delete）
     pub async fn get_conversation_lifecycle_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         let mut hooks = Vec::new();
         hooks.extend(config.session_create);
         hooks.extend(config.session_update);
@@ -71,54 +725,308 @@ impl CoreHookRegistry {
 
     /// 获取UserLogin Hook列表
     pub async fn get_user_login_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.user_login)
     }
 
     /// 获取UserLogout Hook列表
     pub async fn get_user_logout_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.user_logout)
     }
 
     /// 获取UserOnline Hook列表
     pub async fn get_user_online_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.user_online)
     }
 
     /// 获取UserOffline Hook列表
     pub async fn get_user_offline_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.user_offline)
     }
 
     /// 获取PushPreSend Hook列表
     pub async fn get_push_pre_send_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.push_pre_send)
     }
 
     /// 获取PushPostSend Hook列表
     pub async fn get_push_post_send_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.push_post_send)
     }
 
     /// 获取PushDelivery Hook列表
     pub async fn get_push_delivery_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.push_delivery)
     }
 
     /// 获取GetConversationParticipants Hook列表
     pub async fn get_conversation_participants_hooks(&self) -> Result<Vec<HookConfigItem>> {
-        let config = self.config_watcher.get_config().await;
+        let config = self.current_config().await;
         Ok(config.get_conversation_participants)
     }
 
-    /// 重新加载配置
+    /// 重新加载配置并重建执行计划
+    ///
+    /// 新配置无法构建适配器时返回错误，当前执行计划保持不变
     pub async fn reload_config(&self) -> Result<()> {
-        self.config_watcher.reload().await
+        self.config_watcher.reload().await?;
+        self.apply_latest(true).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_hook(name: &str, enabled: bool) -> HookConfigItem {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "enabled": enabled,
+            "priority": 10,
+            "timeout_ms": 100,
+            "selector": {},
+            "transport": { "type": "local", "target": "noop" }
+        }))
+        .unwrap()
+    }
+
+    fn plan_names(plan_set: &HookPlanSet, tenant_id: Option<&str>) -> Vec<String> {
+        let mut names: Vec<String> = plan_set
+            .plans_for(tenant_id, "pre_send")
+            .iter()
+            .map(|plan| plan.name().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn test_tenant_plans_override_global_chain() {
+        let mut tenant = HookConfig::default();
+        tenant.pre_send.push(local_hook("hook-b", false));
+        tenant.pre_send.push(local_hook("hook-c", true));
+
+        let mut config = HookConfig::default();
+        config.pre_send.push(local_hook("hook-a", true));
+        config.pre_send.push(local_hook("hook-b", true));
+        config.tenants.insert("tenant-1".to_string(), tenant);
+
+        let components = PlanComponents {
+            adapter_factory: &HookAdapterFactory::new(),
+            circuit_breakers: &CircuitBreakerRegistry::default(),
+            health: &HookHealthRegistry::default(),
+            sample_store: &Arc::new(HookSampleStore::default()),
+            metrics: &Arc::new(MetricsCollector::new()),
+        };
+        let plan_set = HookPlanSet::build(1, config, &HookPlanSet::empty(), &components, true)
+            .await
+            .unwrap();
+
+        // 租户禁用 hook-b 并追加 hook-c，其他租户使用全局Hook链
+        assert_eq!(plan_names(&plan_set, Some("tenant-1")), ["hook-a", "hook-c"]);
+        assert_eq!(plan_names(&plan_set, Some("tenant-2")), ["hook-a", "hook-b"]);
+        assert_eq!(plan_names(&plan_set, None), ["hook-a", "hook-b"]);
+
+        // 租户专属Hook使用独立的熔断器
+        assert!(plan_set.circuit_breaker_keys().contains("pre_send:hook-c@tenant-1"));
+        assert!(plan_set.circuit_breaker_keys().contains("pre_send:hook-a"));
+    }
+
+    #[tokio::test]
+    async fn test_default_policies_only_fill_empty_chains() {
+        use crate::domain::model::{DefaultDecision, RecallDefaultPolicy};
+
+        let mut tenant = HookConfig::default();
+        tenant.defaults.pre_send = Some(DefaultDecision::Deny { reason: None });
+        let mut hooked_tenant = HookConfig::default();
+        hooked_tenant.recall.push(local_hook("recall-hook", true));
+
+        let mut config = HookConfig::default();
+        config.defaults.pre_send = Some(DefaultDecision::Allow);
+        config.defaults.recall = Some(RecallDefaultPolicy {
+            deny_older_than_secs: Some(120),
+        });
+        config.tenants.insert("tenant-1".to_string(), tenant);
+        config.tenants.insert("tenant-2".to_string(), hooked_tenant);
+
+        let components = PlanComponents {
+            adapter_factory: &HookAdapterFactory::new(),
+            circuit_breakers: &CircuitBreakerRegistry::default(),
+            health: &HookHealthRegistry::default(),
+            sample_store: &Arc::new(HookSampleStore::default()),
+            metrics: &Arc::new(MetricsCollector::new()),
+        };
+        let plan_set = HookPlanSet::build(1, config, &HookPlanSet::empty(), &components, true)
+            .await
+            .unwrap();
+
+        let names = |tenant_id: Option<&str>, hook_type: &str| -> Vec<String> {
+            plan_set
+                .plans_for(tenant_id, hook_type)
+                .iter()
+                .map(|plan| plan.name().to_string())
+                .collect()
+        };
+        assert_eq!(names(None, "pre_send"), ["builtin:default:pre_send"]);
+        assert_eq!(names(None, "recall"), ["builtin:default:recall"]);
+        assert_eq!(names(Some("tenant-1"), "pre_send"), ["builtin:default:pre_send"]);
+        // 配置了Recall Hook的租户不注入默认策略
+        assert_eq!(names(Some("tenant-2"), "recall"), ["recall-hook"]);
+        assert!(names(None, "post_send").is_empty());
+
+        // 租户覆盖的默认策略生效
+        let ctx = flare_server_core::context::Context::with_request_id("req-1".to_string());
+        let mut draft = flare_im_core::MessageDraft::new(Vec::new());
+        let decision = plan_set.plans_for(Some("tenant-1"), "pre_send")[0]
+            .execute(&ctx, &mut draft)
+            .await
+            .unwrap();
+        assert!(!decision.is_continue());
+    }
+
+    fn cached_hook(name: &str, timeout_ms: u64) -> HookConfigItem {
+        HookConfigItem {
+            timeout_ms,
+            cache: Some(Default::default()),
+            ..local_hook(name, true)
+        }
+    }
+
+    fn result_cache<'a>(
+        plan_set: &'a HookPlanSet,
+        tenant_id: Option<&str>,
+        name: &str,
+    ) -> &'a Arc<crate::infrastructure::result_cache::HookResultCache> {
+        plan_set
+            .plans_for(tenant_id, "pre_send")
+            .iter()
+            .find(|plan| plan.name() == name)
+            .and_then(|plan| plan.result_cache())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_reuses_unchanged_hooks() {
+        let components = PlanComponents {
+            adapter_factory: &HookAdapterFactory::new(),
+            circuit_breakers: &CircuitBreakerRegistry::default(),
+            health: &HookHealthRegistry::default(),
+            sample_store: &Arc::new(HookSampleStore::default()),
+            metrics: &Arc::new(MetricsCollector::new()),
+        };
+        let config = |hook_b_timeout_ms: u64, with_hook_c: bool| {
+            let mut tenant = HookConfig::default();
+            tenant.pre_send.push(cached_hook("hook-t", 100));
+            let mut config = HookConfig::default();
+            config.pre_send.push(cached_hook("hook-a", 100));
+            config
+                .pre_send
+                .push(cached_hook("hook-b", hook_b_timeout_ms));
+            if with_hook_c {
+                config.pre_send.push(cached_hook("hook-c", 100));
+            }
+            config.tenants.insert("tenant-1".to_string(), tenant);
+            config
+        };
+
+        let first = HookPlanSet::build(
+            1,
+            config(100, true),
+            &HookPlanSet::empty(),
+            &components,
+            true,
+        )
+        .await
+        .unwrap();
+        let second = HookPlanSet::build(2, config(200, false), &first, &components, true)
+            .await
+            .unwrap();
+
+        // 未变更的Hook（含租户专属Hook）沿用原执行计划，结果缓存不被清空
+        for (tenant_id, name) in [
+            (None, "hook-a"),
+            (Some("tenant-1"), "hook-a"),
+            (Some("tenant-1"), "hook-t"),
+        ] {
+            assert!(Arc::ptr_eq(
+                result_cache(&first, tenant_id, name),
+                result_cache(&second, tenant_id, name)
+            ));
+        }
+        // 变更的Hook重建，旧缓存随旧执行计划释放
+        assert!(!Arc::ptr_eq(
+            result_cache(&first, None, "hook-b"),
+            result_cache(&second, None, "hook-b")
+        ));
+        assert_eq!(plan_names(&second, None), ["hook-a", "hook-b"]);
+        assert!(!second.built.contains_key("pre_send:hook-c"));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_config_skipped_until_source_changes() {
+        use crate::infrastructure::config::FileConfigLoader;
+        use crate::infrastructure::config::loader::ConfigLoaderItem;
+
+        const HOOK: &str = r#"
+            [[pre_send]]
+            name = "hook-a"
+            enabled = true
+            priority = 10
+            timeout_ms = 100
+            [pre_send.selector]
+        "#;
+        let path = std::env::temp_dir().join(format!(
+            "flare-hook-registry-rejected-{}.toml",
+            std::process::id()
+        ));
+        let loader = ConfigLoaderItem::File(FileConfigLoader::new(&path));
+        let watcher = Arc::new(ConfigWatcher::new(
+            vec![Arc::new(loader)],
+            Duration::from_secs(60),
+        ));
+        let registry = CoreHookRegistry::new(
+            watcher.clone(),
+            Arc::new(HookAdapterFactory::new()),
+            Arc::new(CircuitBreakerRegistry::default()),
+        );
+
+        // gRPC Hook 既没有 endpoint 也没有 service_name，适配器构建失败并回滚
+        std::fs::write(
+            &path,
+            format!("{HOOK}[pre_send.transport]\ntype = \"grpc\"\n"),
+        )
+        .unwrap();
+        watcher.reload().await.unwrap();
+        assert!(registry.apply_latest(false).await.is_err());
+        assert!(watcher.get_config().await.pre_send.is_empty());
+        assert!(registry.apply_lock.lock().await.is_some());
+
+        // 配置源未变化：再次提交同一份配置时跳过构建，仍保持回滚后的配置
+        watcher.reload().await.unwrap();
+        let err = registry.apply_latest(false).await.unwrap_err();
+        assert!(err.to_string().contains("rejected before"));
+        assert!(watcher.get_config().await.pre_send.is_empty());
+
+        // 配置源变化后重新构建
+        std::fs::write(
+            &path,
+            format!("{HOOK}[pre_send.transport]\ntype = \"local\"\ntarget = \"noop\"\n"),
+        )
+        .unwrap();
+        watcher.reload().await.unwrap();
+        registry.apply_latest(false).await.unwrap();
+        assert_eq!(
+            plan_names(&registry.plan_set.read().await, None),
+            ["hook-a"]
+        );
+        assert!(registry.apply_lock.lock().await.is_none());
+
+        std::fs::remove_file(&path).ok();
     }
 }
@@ -8,12 +8,21 @@ use anyhow::{Context, Result};
 
 use crate::application::handlers::{HookCommandHandler, HookQueryHandler};
 use crate::domain::service::HookOrchestrationService;
+use crate::domain::repository::{HookAuditRepository, HookUsageRepository};
 use crate::infrastructure::adapters::HookAdapterFactory;
+use crate::infrastructure::adapters::plugin::PluginRegistry;
+use crate::infrastructure::audit::BatchingHookAuditRecorder;
+use crate::infrastructure::circuit_breaker::CircuitBreakerRegistry;
+use crate::infrastructure::concurrency::HookConcurrencyLimiter;
 use crate::infrastructure::config::ConfigWatcher;
+use crate::infrastructure::dead_letter::KafkaHookDeadLetterPublisher;
+use crate::infrastructure::health::HookHealthRegistry;
 use crate::infrastructure::config::loader::{
     ConfigCenterLoader, ConfigLoaderItem, DatabaseConfigLoader, FileConfigLoader,
 };
 use crate::infrastructure::monitoring::{ExecutionRecorder, MetricsCollector};
+use crate::infrastructure::persistence::{PostgresHookAuditRepository, PostgresHookUsageRepository};
+use crate::infrastructure::usage::AggregatingHookUsageRecorder;
 use crate::interface::grpc::{HookExtensionServer, HookServiceServer};
 use crate::service::bootstrap::HookEngineConfig;
 use crate::service::registry::CoreHookRegistry;
@@ -139,33 +148,143 @@ pub async fn initialize(config: HookEngineConfig) -> Result<ApplicationContext>
         .await
         .context("Failed to start config watcher")?;
 
-    // 3. 创建监控组件
-    let metrics_collector = Arc::new(MetricsCollector::new());
+    // 3. 创建熔断器、端点健康状态注册表和监控组件
+    let circuit_breakers = Arc::new(CircuitBreakerRegistry::new(config.circuit_breaker.clone()));
+    let health_registry = Arc::new(HookHealthRegistry::new(config.health_check.clone()));
+    let metrics_collector = Arc::new(
+        MetricsCollector::new()
+            .with_circuit_breakers(circuit_breakers.clone())
+            .with_health_registry(health_registry.clone()),
+    );
     let execution_recorder = Arc::new(ExecutionRecorder::new());
 
-    // 4. 创建适配器工厂
-    let adapter_factory = Arc::new(HookAdapterFactory::new());
+    // 4. 创建适配器工厂（Kafka传输复用全局Kafka配置档，Local传输可引用插件目录中的动态库插件，内置限流使用Redis计数）
+    let app_config = flare_im_core::load_config(Some("config"));
+    let plugins = match &config.plugin_dir {
+        Some(dir) => {
+            let plugins = PluginRegistry::load_dir(dir).context("Failed to load hook plugins")?;
+            tracing::info!(dir = %dir.display(), count = plugins.len(), "Hook plugins loaded");
+            plugins
+        }
+        None => PluginRegistry::default(),
+    };
+    let mut adapter_factory = HookAdapterFactory::new()
+        .with_kafka_profiles(app_config.kafka.clone())
+        .with_plugins(Arc::new(plugins))
+        .with_grpc_channel_config(config.grpc_channel.clone());
+    if let Some(redis_url) = &config.redis_url {
+        let client = redis::Client::open(redis_url.as_str())
+            .context("Failed to create Redis client for rate limit hooks")?;
+        adapter_factory = adapter_factory.with_redis_client(client);
+    }
+    let adapter_factory = Arc::new(adapter_factory);
 
-    // 5. 创建编排服务
-    let orchestration_service = Arc::new(HookOrchestrationService);
+    // 5. 创建编排服务（配置了死信队列时，重试耗尽的执行写入Kafka；配置了审计日志时记录每次执行）
+    let mut orchestration_service = HookOrchestrationService::new()
+        .with_deadline_reserve(config.deadline_reserve)
+        .with_execution_mode(config.execution_mode, config.draft_merge)
+        .with_trace_exemplars(config.trace_exemplars)
+        .with_slow_hook_threshold(config.slow_hook_threshold)
+        .with_max_pending_retries(config.max_pending_retries)
+        .with_metrics(metrics_collector.clone());
+    if config.concurrency.enabled {
+        let limiter = Arc::new(HookConcurrencyLimiter::new(config.concurrency.clone()));
+        orchestration_service = orchestration_service.with_concurrency_limiter(limiter);
+        tracing::info!(
+            global_max_in_flight = config.concurrency.global_max_in_flight,
+            hook_max_in_flight = config.concurrency.hook_max_in_flight,
+            tenant_max_in_flight = config.concurrency.tenant_max_in_flight,
+            "Hook concurrency limits enabled"
+        );
+    }
+    for (hook_type, budget) in &config.chain_budgets {
+        orchestration_service = orchestration_service.with_chain_budget(hook_type.clone(), *budget);
+    }
+    if let Some(ref dead_letter) = config.dead_letter {
+        let publisher = KafkaHookDeadLetterPublisher::new(dead_letter)
+            .context("Failed to create hook dead letter publisher")?;
+        orchestration_service = orchestration_service.with_dead_letter_publisher(publisher);
+        tracing::info!(topic = %dead_letter.topic, "Hook dead letter queue enabled");
+    }
+    // 配置了审计日志时，每次Hook执行异步批量写入数据库
+    let audit_repository = match (&config.audit, &config.database_url) {
+        (Some(audit), Some(database_url)) => {
+            let repository = Arc::new(
+                PostgresHookAuditRepository::new(database_url)
+                    .await
+                    .context("Failed to create hook audit repository")?,
+            );
+            repository.spawn_partition_maintenance(audit.retention_days);
+            let repository: Arc<dyn HookAuditRepository> = repository;
+            orchestration_service = orchestration_service
+                .with_audit_recorder(BatchingHookAuditRecorder::start(repository.clone(), audit));
+            tracing::info!(retention_days = audit.retention_days, "Hook audit log enabled");
+            Some(repository)
+        }
+        (Some(_), None) => {
+            tracing::warn!("Hook audit log requires a database, audit log disabled");
+            None
+        }
+        _ => None,
+    };
+    // 配置了用量统计时，按天、租户、Hook聚合用量并定期累加到数据库
+    let usage_repository = match (&config.usage, &config.database_url) {
+        (Some(usage), Some(database_url)) => {
+            let repository: Arc<dyn HookUsageRepository> = Arc::new(
+                PostgresHookUsageRepository::new(database_url)
+                    .await
+                    .context("Failed to create hook usage repository")?,
+            );
+            let recorder = AggregatingHookUsageRecorder::start(repository.clone(), usage);
+            orchestration_service = orchestration_service.with_usage_recorder(recorder);
+            tracing::info!(
+                flush_interval_ms = usage.flush_interval.as_millis() as u64,
+                "Hook usage accounting enabled"
+            );
+            Some(repository)
+        }
+        (Some(_), None) => {
+            tracing::warn!("Hook usage accounting requires a database, usage accounting disabled");
+            None
+        }
+        _ => None,
+    };
+    let orchestration_service = Arc::new(orchestration_service);
 
     // 6. 创建命令和查询处理器
     let command_handler = Arc::new(HookCommandHandler::new(orchestration_service.clone()));
     let query_handler = Arc::new(HookQueryHandler::new(metrics_collector.clone()));
 
-    // 7. 创建Hook注册表
-    let registry = Arc::new(CoreHookRegistry::new(config_watcher.clone()));
+    // 7. 创建Hook注册表（构建执行计划并订阅配置变更，变更时原子替换）
+    let registry = Arc::new(
+        CoreHookRegistry::new(
+            config_watcher.clone(),
+            adapter_factory.clone(),
+            circuit_breakers.clone(),
+        )
+        .with_metrics_collector(metrics_collector.clone())
+        .with_health_registry(health_registry.clone()),
+    );
+    registry
+        .start()
+        .await
+        .context("Failed to start hook registry")?;
 
     // 8. 构建 HookExtension 服务
-    let hook_extension_service =
-        HookExtensionServer::new(command_handler, registry.clone(), adapter_factory);
+    let hook_extension_service = HookExtensionServer::new(command_handler.clone(), registry.clone());
 
     // 9. 构建 HookService 服务（如果配置了数据库）
     let hook_service = if let Some(ref repository) = config_repository {
-        Some(
-            HookServiceServer::new(repository.clone(), registry.clone())
-                .with_monitoring(metrics_collector.clone(), execution_recorder.clone()),
-        )
+        let mut hook_service = HookServiceServer::new(repository.clone(), registry.clone())
+            .with_monitoring(metrics_collector.clone(), execution_recorder.clone())
+            .with_command_handler(command_handler);
+        if let Some(audit_repository) = audit_repository {
+            hook_service = hook_service.with_audit_repository(audit_repository);
+        }
+        if let Some(usage_repository) = usage_repository {
+            hook_service = hook_service.with_usage_repository(usage_repository);
+        }
+        Some(hook_service)
     } else {
         tracing::warn!("Database repository not available, HookService will not be available");
         None
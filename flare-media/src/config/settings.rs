@@ -16,6 +16,10 @@ pub struct MediaConfig {
     pub chunk_upload_dir: String,
     pub chunk_ttl_seconds: i64,
     pub max_chunk_size_bytes: i64,
+    /// Hook 配置（未配置时使用默认的 config/hooks.toml、config/hooks.d）
+    pub hook_config: Option<String>,
+    /// Hook 配置目录
+    pub hook_config_dir: Option<String>,
 }
 
 impl MediaConfig {
@@ -108,6 +112,8 @@ impl MediaConfig {
             chunk_upload_dir,
             chunk_ttl_seconds,
             max_chunk_size_bytes,
+            hook_config: service.hook_config,
+            hook_config_dir: service.hook_config_dir,
         }
     }
 
@@ -4,10 +4,13 @@ use md5::compute as md5_compute;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
 use tokio::fs;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tracing::instrument;
 use uuid::Uuid;
+use flare_im_core::hooks::{HookDispatcher, MediaUploadedEvent};
 use flare_server_core::context::{Context, ContextExt};
 
 use crate::domain::model::{
@@ -30,6 +33,8 @@ pub struct MediaService {
     upload_conversation_store: Option<UploadSessionStoreRef>,
     local_store: Option<LocalStoreRef>,
     config: MediaDomainConfig,
+    /// 生命周期 Hook（可选，上传完成后执行 MediaUploaded Hook）
+    hooks: Option<Arc<HookDispatcher>>,
 }
 
 impl MediaService {
@@ -58,9 +63,50 @@ impl MediaService {
             upload_conversation_store,
             local_store,
             config,
+            hooks: None,
         }
     }
 
+    /// 设置 Hook 调度器
+    pub fn with_hooks(mut self, hooks: Arc<HookDispatcher>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// 后台执行 MediaUploaded Hook，失败只记录日志，不影响上传结果
+    fn notify_uploaded(
+        &self,
+        ctx: &Context,
+        uploader_id: &str,
+        metadata: &MediaFileMetadata,
+        deduplicated: bool,
+    ) {
+        let Some(hooks) = self.hooks.clone() else {
+            return;
+        };
+        let ctx = ctx.clone();
+        let event = MediaUploadedEvent {
+            file_id: metadata.file_id.clone(),
+            uploader_id: uploader_id.to_string(),
+            file_name: metadata.file_name.clone(),
+            mime_type: metadata.mime_type.clone(),
+            file_size: metadata.file_size,
+            url: metadata.url.clone(),
+            deduplicated,
+            uploaded_at: SystemTime::now(),
+            metadata: metadata.metadata.clone(),
+        };
+        tokio::spawn(async move {
+            if let Err(err) = hooks.media_uploaded(&ctx, &event).await {
+                tracing::warn!(
+                    file_id = %event.file_id,
+                    error = %err,
+                    "MediaUploaded hook failed"
+                );
+            }
+        });
+    }
+
     #[instrument(skip(self, ctx, init), fields(
         request_id = %ctx.request_id(),
         trace_id = %ctx.trace_id(),
@@ -358,6 +404,7 @@ impl MediaService {
                     existing_file_id = existing.file_id,
                     "返回已存在的文件元数据"
                 );
+                self.notify_uploaded(ctx, context.user_id, &existing, true);
                 return Ok(existing);
             } else {
                 tracing::debug!(
@@ -511,6 +558,7 @@ impl MediaService {
         }
 
         tracing::debug!(file_id = context.file_id, "文件存储完成");
+        self.notify_uploaded(ctx, context.user_id, &metadata, false);
         Ok(metadata)
     }
 
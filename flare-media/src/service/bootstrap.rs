@@ -15,10 +15,10 @@ pub struct ApplicationBootstrap;
 impl ApplicationBootstrap {
     /// 运行应用的主入口点
     pub async fn run() -> Result<()> {
-        use flare_im_core::{ServiceHelper, load_config};
+        use flare_im_core::{ServiceHelper, load_config_with_validation, strict_mode};
 
         // 加载应用配置
-        let app_config = load_config(Some("config"));
+        let app_config = load_config_with_validation(Some("config"), strict_mode())?;
         let service_config = app_config.media_service();
 
         info!("Parsing server address...");
@@ -64,6 +64,7 @@ impl ApplicationBootstrap {
                     .layer(MediaServiceServer::new(handler));
                 
                 Server::builder()
+                    .add_service(flare_im_core::ServerInfoServer::from_global())
                     .add_service(media_service)
                     .serve_with_shutdown(address_clone, async move {
                         info!(
@@ -5,6 +5,7 @@
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
+use flare_im_core::hooks::{HookConfigLoader, HookDispatcher};
 
 use crate::application::handlers::{MediaCommandHandler, MediaQueryHandler};
 use crate::config::MediaConfig;
@@ -116,13 +117,27 @@ async fn build_media_service(
         config.max_chunk_size_bytes,
     );
 
-    Ok(Arc::new(MediaService::new(
-        object_repo,
-        metadata_store,
-        reference_store,
-        metadata_cache,
-        upload_conversation_store,
-        local_store,
-        domain_config,
-    )))
+    let mut hook_loader = HookConfigLoader::new();
+    if let Some(path) = &config.hook_config {
+        hook_loader = hook_loader.add_candidate(path.clone());
+    }
+    if let Some(dir) = &config.hook_config_dir {
+        hook_loader = hook_loader.add_candidate(dir.clone());
+    }
+    let hooks = HookDispatcher::load(&hook_loader)
+        .await
+        .map_err(|err| anyhow::anyhow!("Failed to load hooks: {}", err))?;
+
+    Ok(Arc::new(
+        MediaService::new(
+            object_repo,
+            metadata_store,
+            reference_store,
+            metadata_cache,
+            upload_conversation_store,
+            local_store,
+            domain_config,
+        )
+        .with_hooks(Arc::new(hooks)),
+    ))
 }
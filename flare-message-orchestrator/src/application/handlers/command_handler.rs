@@ -17,6 +17,7 @@ use crate::application::commands::{
     ReadMessageCommand, RecallMessageCommand, RemoveReactionCommand, SendMessageCommand,
     StoreMessageCommand, UnmarkMessageCommand, UnpinMessageCommand,
 };
+use crate::domain::model::StoredMessage;
 use crate::domain::service::MessageDomainService;
 use crate::domain::service::message_operation_service::MessageOperationService;
 use crate::domain::service::message_temporary_service::MessageTemporaryService;
@@ -50,7 +51,7 @@ impl MessageCommandHandler {
         trace_id = %ctx.trace_id(),
         tenant_id = %ctx.tenant_id().unwrap_or("0"),
     ))]
-    pub async fn handle_store_message(&self, ctx: &Context, command: StoreMessageCommand) -> Result<(String, u64)> {
+    pub async fn handle_store_message(&self, ctx: &Context, command: StoreMessageCommand) -> Result<StoredMessage> {
         ctx.ensure_not_cancelled()?;
         let start = Instant::now();
 
@@ -95,7 +96,7 @@ impl MessageCommandHandler {
     pub async fn handle_store_message_without_pre_hook(
         &self,
         command: StoreMessageCommand,
-    ) -> Result<(String, u64)> {
+    ) -> Result<StoredMessage> {
         let start = Instant::now();
 
         // 提取租户ID和消息类型用于指标标签（在移动之前）
@@ -165,7 +166,7 @@ impl MessageCommandHandler {
                 .orchestrate_message_storage(&request_ctx, request, true)
                 .await
             {
-                Ok((message_id, _seq)) => message_ids.push(message_id),
+                Ok(stored) => message_ids.push(stored.message_id),
                 Err(e) => {
                     tracing::warn!(error = %e, "Failed to store message in batch");
                     // 继续处理其他消息
@@ -270,7 +271,7 @@ impl MessageCommandHandler {
         &self,
         ctx: &Context,
         mut cmd: SendMessageCommand,
-    ) -> Result<(String, u64)> {
+    ) -> Result<StoredMessage> {
         ctx.ensure_not_cancelled()?;
         
         // 如果 cmd.tenant 为空，从 ctx 中提取 tenant_id 并设置
@@ -316,7 +317,11 @@ impl MessageCommandHandler {
                 self.handle_temporary_message(ctx, temp_cmd).await?;
 
                 // 临时消息返回消息ID和seq=0
-                Ok((message.server_id, 0))
+                Ok(StoredMessage {
+                    message_id: message.server_id,
+                    seq: 0,
+                    persisted_ts: None,
+                })
             }
             crate::domain::model::message_kind::MessageCategory::Operation => {
                 // 操作消息：直接提取 MessageOperation 并执行操作
@@ -328,7 +333,11 @@ impl MessageCommandHandler {
 
                     // 操作消息返回目标消息ID和seq=0（操作不产生新消息）
                     // 但操作结果会通过推送消息通知用户
-                    Ok((operation.target_message_id.clone(), 0))
+                    Ok(StoredMessage {
+                        message_id: operation.target_message_id.clone(),
+                        seq: 0,
+                        persisted_ts: None,
+                    })
                 } else {
                     // 无法提取操作，降级为普通消息
                     tracing::warn!(
@@ -346,7 +355,7 @@ impl MessageCommandHandler {
     }
 
     /// 处理普通消息（内部方法）
-    async fn handle_normal_message(&self, ctx: &Context, cmd: SendMessageCommand) -> Result<(String, u64)> {
+    async fn handle_normal_message(&self, ctx: &Context, cmd: SendMessageCommand) -> Result<StoredMessage> {
         ctx.ensure_not_cancelled()?;
         // 验证单聊消息必须包含 receiver_id
         if cmd.message.conversation_type == flare_proto::common::ConversationType::Single as i32 {
@@ -437,8 +446,8 @@ impl MessageCommandHandler {
             };
 
             match self.handle_send_message(ctx, send_cmd).await {
-                Ok((message_id, seq)) => {
-                    successes.push((message_id, seq));
+                Ok(stored) => {
+                    successes.push((stored.message_id, stored.seq));
                 }
                 Err(e) => {
                     tracing::warn!(error = %e, "Failed to send message in batch");
@@ -31,6 +31,12 @@ pub struct MessageOrchestratorConfig {
     /// 业务系统标识符（SVID），用于服务发现时的过滤
     /// 例如："svid.im"、"svid.customer" 等
     pub svid: Option<String>,
+    /// 租户自定义消息 Schema 文件（JSON：租户ID -> 自定义类型 -> Schema）
+    pub custom_content_schema_file: Option<String>,
+    /// 同步写入（sync = true）等待持久化回执的超时时间（毫秒）
+    pub sync_reply_timeout_ms: u64,
+    /// 同步写入回执频道前缀（实际频道为 `<前缀>:<实例ID>`）
+    pub sync_reply_channel_prefix: String,
 }
 
 fn env_or_fallback(primary: &str, fallback: &str) -> Option<String> {
@@ -208,6 +214,16 @@ impl MessageOrchestratorConfig {
             "SVID",
         ).or_else(|| Some("svid.im".to_string())); // 默认为 svid.im
 
+        let custom_content_schema_file =
+            env::var("MESSAGE_ORCHESTRATOR_CUSTOM_CONTENT_SCHEMA_FILE").ok();
+
+        let sync_reply_timeout_ms = env::var("MESSAGE_ORCHESTRATOR_SYNC_REPLY_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(3000);
+        let sync_reply_channel_prefix = env::var("MESSAGE_ORCHESTRATOR_SYNC_REPLY_CHANNEL")
+            .unwrap_or_else(|_| "storage:sync_reply".to_string());
+
         Self {
             kafka_bootstrap,
             kafka_storage_topic,
@@ -229,6 +245,9 @@ impl MessageOrchestratorConfig {
             conversation_service_type,
             server_id,
             svid,
+            custom_content_schema_file,
+            sync_reply_timeout_ms,
+            sync_reply_channel_prefix,
         }
     }
 
@@ -1,8 +1,8 @@
 use anyhow::{Result, anyhow};
 use chrono::Utc;
 use flare_im_core::utils::{
-    TimelineMetadata, current_millis, datetime_to_timestamp, embed_timeline_in_extra,
-    timestamp_to_millis,
+    TimelineMetadata, current_millis, datetime_to_timestamp, embed_origin_device_id,
+    embed_timeline_in_extra, timestamp_to_millis,
 };
 use flare_proto::storage::StoreMessageRequest;
 use uuid::Uuid;
@@ -17,6 +17,23 @@ pub struct MessageDefaults {
     pub default_tenant_id: Option<String>,
 }
 
+/// 消息存储结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredMessage {
+    pub message_id: String,
+    pub seq: u64,
+    /// 持久化时间（毫秒，仅同步写入收到 Storage Writer 回执时存在）
+    pub persisted_ts: Option<i64>,
+}
+
+/// 同步写入超时：消息已进入存储队列，但未在限定时间内收到持久化回执
+#[derive(Debug, thiserror::Error)]
+#[error("message {message_id} accepted but persistence not confirmed within {timeout_ms}ms")]
+pub struct SyncPersistenceTimeout {
+    pub message_id: String,
+    pub timeout_ms: u64,
+}
+
 #[derive(Clone)]
 pub struct MessageSubmission {
     pub kafka_payload: StoreMessageRequest,
@@ -144,6 +161,11 @@ impl MessageSubmission {
                 .or_insert(tenant.clone());
         }
 
+        // 记录发送设备，推送时同步到发送者的其他设备并抑制对发送设备的回显
+        if let Some(device) = request.context.as_ref().and_then(|ctx| ctx.device.as_ref()) {
+            embed_origin_device_id(&mut message, &device.device_id);
+        }
+
         let timeline = TimelineMetadata {
             emit_ts,
             ingestion_ts,
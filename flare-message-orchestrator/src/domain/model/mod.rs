@@ -3,5 +3,7 @@ pub mod message_submission;
 pub mod message_fsm;
 
 pub use message_kind::MessageProfile;
-pub use message_submission::{MessageDefaults, MessageSubmission};
+pub use message_submission::{
+    MessageDefaults, MessageSubmission, StoredMessage, SyncPersistenceTimeout,
+};
 pub use message_fsm::{Message, MessageFsmState, EditHistoryEntry};
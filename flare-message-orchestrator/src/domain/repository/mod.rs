@@ -1,9 +1,11 @@
 use anyhow::Result;
+use flare_im_core::utils::PersistenceReply;
 use flare_proto::push::PushMessageRequest as PushPushMessageRequest;
 use flare_proto::storage::StoreMessageRequest as StorageStoreMessageRequest;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use tokio::sync::oneshot;
 
 use crate::domain::model::MessageSubmission;
 
@@ -159,6 +161,13 @@ pub trait ConversationRepository: Send + Sync {
         business_type: &'a str,
         participants: Vec<String>,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+    /// 查询会话绑定的表情包集合ID（会话不存在时返回 None）
+    fn bound_sticker_sets<'a>(
+        &'a self,
+        ctx: &'a flare_server_core::context::Context,
+        conversation_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Vec<String>>>> + Send + 'a>>;
 }
 
 /// ConversationRepository 的枚举封装，用于在 Rust 2024 下避免 `dyn` + async trait 带来的
@@ -187,4 +196,26 @@ impl ConversationRepository for ConversationRepositoryItem {
             ),
         }
     }
+
+    fn bound_sticker_sets<'a>(
+        &'a self,
+        ctx: &'a flare_server_core::context::Context,
+        conversation_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Vec<String>>>> + Send + 'a>> {
+        match self {
+            ConversationRepositoryItem::Grpc(repo) => repo.bound_sticker_sets(ctx, conversation_id),
+        }
+    }
+}
+
+/// 同步写入回执等待（`sync = true` 的消息等待 Storage Writer 的持久化回执）
+pub trait PersistenceReplyWaiter: Send + Sync {
+    /// 本实例订阅的回执频道（写入 `StoreMessageRequest.tags`）
+    fn reply_channel(&self) -> &str;
+
+    /// 注册等待（须在发布存储消息之前调用，避免回执先于注册到达）
+    fn register(&self, message_id: &str) -> oneshot::Receiver<PersistenceReply>;
+
+    /// 取消等待（发布失败或等待超时时调用）
+    fn cancel(&self, message_id: &str);
 }
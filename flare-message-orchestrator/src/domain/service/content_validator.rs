@@ -0,0 +1,402 @@
+//! 内容校验 - 按内容类型校验结构化消息内容
+//!
+//! 在 PreSend Hook 之前执行，拒绝结构不完整的消息，避免畸形内容流入存储：
+//! - 图片：必须携带 file_id，宽高在合理范围内
+//! - 位置：经纬度有效
+//! - 名片：必填字段完整
+//! - 自定义消息：按租户配置的 JSON Schema（子集）校验 payload
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use flare_proto::common::Message;
+use flare_proto::common::message_content::Content;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 图片最大边长（像素）
+const MAX_IMAGE_DIMENSION: i64 = 20_000;
+
+/// 字段级校验错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    /// 字段路径（如 `image.width`、`custom.payload.order_id`）
+    pub field: String,
+    /// 错误原因
+    pub reason: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// 内容校验失败
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContentValidationError {
+    pub content_type: String,
+    pub errors: Vec<FieldError>,
+}
+
+impl fmt::Display for ContentValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields: Vec<String> = self
+            .errors
+            .iter()
+            .map(|err| format!("{}: {}", err.field, err.reason))
+            .collect();
+        write!(
+            f,
+            "invalid {} content: {}",
+            self.content_type,
+            fields.join("; ")
+        )
+    }
+}
+
+impl std::error::Error for ContentValidationError {}
+
+/// 内容校验器
+pub trait ContentValidator: Send + Sync {
+    /// 校验内容，返回全部字段错误（为空表示通过）
+    fn validate(&self, tenant_id: &str, content: &Content) -> Vec<FieldError>;
+}
+
+/// 内容校验器注册表（按内容类型分发）
+#[derive(Default)]
+pub struct ValidatorRegistry {
+    validators: HashMap<&'static str, Vec<Arc<dyn ContentValidator>>>,
+}
+
+impl ValidatorRegistry {
+    /// 空注册表（不做任何校验）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册内置校验器（图片、位置、名片、自定义消息）
+    pub fn with_builtin(custom_schemas: CustomContentSchemas) -> Self {
+        let mut registry = Self::new();
+        registry.register("image", Arc::new(ImageValidator));
+        registry.register("location", Arc::new(LocationValidator));
+        registry.register("card", Arc::new(CardValidator));
+        registry.register(
+            "custom",
+            Arc::new(CustomContentValidator::new(custom_schemas)),
+        );
+        registry
+    }
+
+    /// 注册校验器（同一内容类型可注册多个，按注册顺序执行）
+    pub fn register(&mut self, content_type: &'static str, validator: Arc<dyn ContentValidator>) {
+        self.validators
+            .entry(content_type)
+            .or_default()
+            .push(validator);
+    }
+
+    /// 校验消息内容
+    pub fn validate(
+        &self,
+        tenant_id: &str,
+        message: &Message,
+    ) -> Result<(), ContentValidationError> {
+        let Some(content) = message.content.as_ref().and_then(|c| c.content.as_ref()) else {
+            return Ok(());
+        };
+        let content_type = content_type(content);
+        let Some(validators) = self.validators.get(content_type) else {
+            return Ok(());
+        };
+
+        let errors: Vec<FieldError> = validators
+            .iter()
+            .flat_map(|validator| validator.validate(tenant_id, content))
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ContentValidationError {
+                content_type: content_type.to_string(),
+                errors,
+            })
+        }
+    }
+}
+
+fn content_type(content: &Content) -> &'static str {
+    match content {
+        Content::Image(_) => "image",
+        Content::Location(_) => "location",
+        Content::Card(_) => "card",
+        Content::Custom(_) => "custom",
+        _ => "other",
+    }
+}
+
+/// 图片校验：file_id 必填，宽高为正且不超过上限
+pub struct ImageValidator;
+
+impl ContentValidator for ImageValidator {
+    fn validate(&self, _tenant_id: &str, content: &Content) -> Vec<FieldError> {
+        let Content::Image(image) = content else {
+            return Vec::new();
+        };
+        let mut errors = Vec::new();
+        if image.file_id.trim().is_empty() {
+            errors.push(FieldError::new("image.file_id", "is required"));
+        }
+        for (field, value) in [
+            ("image.width", i64::from(image.width)),
+            ("image.height", i64::from(image.height)),
+        ] {
+            if value <= 0 || value > MAX_IMAGE_DIMENSION {
+                errors.push(FieldError::new(
+                    field,
+                    format!("must be between 1 and {MAX_IMAGE_DIMENSION}"),
+                ));
+            }
+        }
+        errors
+    }
+}
+
+/// 位置校验：纬度 [-90, 90]，经度 [-180, 180]
+pub struct LocationValidator;
+
+impl ContentValidator for LocationValidator {
+    fn validate(&self, _tenant_id: &str, content: &Content) -> Vec<FieldError> {
+        let Content::Location(location) = content else {
+            return Vec::new();
+        };
+        let mut errors = Vec::new();
+        let latitude = f64::from(location.latitude);
+        if !(-90.0..=90.0).contains(&latitude) {
+            errors.push(FieldError::new(
+                "location.latitude",
+                "must be between -90 and 90",
+            ));
+        }
+        let longitude = f64::from(location.longitude);
+        if !(-180.0..=180.0).contains(&longitude) {
+            errors.push(FieldError::new(
+                "location.longitude",
+                "must be between -180 and 180",
+            ));
+        }
+        errors
+    }
+}
+
+/// 名片校验：user_id、nickname 必填
+pub struct CardValidator;
+
+impl ContentValidator for CardValidator {
+    fn validate(&self, _tenant_id: &str, content: &Content) -> Vec<FieldError> {
+        let Content::Card(card) = content else {
+            return Vec::new();
+        };
+        [
+            ("card.user_id", &card.user_id),
+            ("card.nickname", &card.nickname),
+        ]
+        .into_iter()
+        .filter(|(_, value)| value.trim().is_empty())
+        .map(|(field, _)| FieldError::new(field, "is required"))
+        .collect()
+    }
+}
+
+/// JSON 值类型（JSON Schema `type` 关键字）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Object,
+    Array,
+    Null,
+}
+
+impl JsonType {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            JsonType::String => value.is_string(),
+            JsonType::Number => value.is_number(),
+            JsonType::Integer => value.is_i64() || value.is_u64(),
+            JsonType::Boolean => value.is_boolean(),
+            JsonType::Object => value.is_object(),
+            JsonType::Array => value.is_array(),
+            JsonType::Null => value.is_null(),
+        }
+    }
+}
+
+/// 属性定义
+#[derive(Debug, Clone, Deserialize)]
+pub struct PropertySchema {
+    #[serde(rename = "type")]
+    pub json_type: JsonType,
+}
+
+/// 自定义消息 payload 的 JSON Schema（支持 `required` 与顶层 `properties.*.type`）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CustomContentSchema {
+    #[serde(default)]
+    pub required: Vec<String>,
+    #[serde(default)]
+    pub properties: HashMap<String, PropertySchema>,
+}
+
+/// 租户自定义消息 Schema：租户ID -> 自定义类型 -> Schema，租户 `*` 对所有租户生效
+pub type CustomContentSchemas = HashMap<String, HashMap<String, CustomContentSchema>>;
+
+/// 自定义消息校验：按租户与自定义类型查找 Schema，未配置 Schema 的类型不校验
+pub struct CustomContentValidator {
+    schemas: CustomContentSchemas,
+}
+
+impl CustomContentValidator {
+    pub fn new(schemas: CustomContentSchemas) -> Self {
+        Self { schemas }
+    }
+
+    fn schema_for(&self, tenant_id: &str, custom_type: &str) -> Option<&CustomContentSchema> {
+        [tenant_id, "*"]
+            .into_iter()
+            .find_map(|tenant| self.schemas.get(tenant)?.get(custom_type))
+    }
+}
+
+impl ContentValidator for CustomContentValidator {
+    fn validate(&self, tenant_id: &str, content: &Content) -> Vec<FieldError> {
+        let Content::Custom(custom) = content else {
+            return Vec::new();
+        };
+        let Some(schema) = self.schema_for(tenant_id, &custom.r#type) else {
+            return Vec::new();
+        };
+
+        let payload = match serde_json::from_slice::<Value>(&custom.payload) {
+            Ok(Value::Object(payload)) => payload,
+            Ok(_) => return vec![FieldError::new("custom.payload", "must be a JSON object")],
+            Err(err) => {
+                return vec![FieldError::new(
+                    "custom.payload",
+                    format!("invalid JSON: {err}"),
+                )];
+            }
+        };
+
+        let mut errors: Vec<FieldError> = schema
+            .required
+            .iter()
+            .filter(|field| !payload.contains_key(field.as_str()))
+            .map(|field| FieldError::new(format!("custom.payload.{field}"), "is required"))
+            .collect();
+        for (field, property) in &schema.properties {
+            if let Some(value) = payload.get(field) {
+                if !property.json_type.matches(value) {
+                    errors.push(FieldError::new(
+                        format!("custom.payload.{field}"),
+                        format!("must be of type {:?}", property.json_type).to_lowercase(),
+                    ));
+                }
+            }
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flare_proto::common::{CustomContent, ImageContent, LocationContent, MessageContent};
+
+    fn message(content: Content) -> Message {
+        Message {
+            content: Some(MessageContent {
+                content: Some(content),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_builtin_validators_report_field_errors() {
+        let registry = ValidatorRegistry::with_builtin(CustomContentSchemas::new());
+
+        let image = message(Content::Image(ImageContent {
+            width: 0,
+            height: 600,
+            ..Default::default()
+        }));
+        let err = registry.validate("t1", &image).unwrap_err();
+        assert_eq!(err.content_type, "image");
+        let fields: Vec<&str> = err.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["image.file_id", "image.width"]);
+
+        let location = message(Content::Location(LocationContent {
+            latitude: 91.0,
+            longitude: 120.0,
+            ..Default::default()
+        }));
+        let err = registry.validate("t1", &location).unwrap_err();
+        assert_eq!(
+            err.errors,
+            vec![FieldError::new(
+                "location.latitude",
+                "must be between -90 and 90"
+            )]
+        );
+
+        let valid = message(Content::Location(LocationContent {
+            latitude: 31.2,
+            longitude: 121.5,
+            ..Default::default()
+        }));
+        assert!(registry.validate("t1", &valid).is_ok());
+    }
+
+    #[test]
+    fn test_custom_content_schema_per_tenant() {
+        let schemas: CustomContentSchemas = serde_json::from_str(
+            r#"{"t1": {"order": {"required": ["order_id"], "properties": {"amount": {"type": "integer"}}}}}"#,
+        )
+        .unwrap();
+        let registry = ValidatorRegistry::with_builtin(schemas);
+        let order = |payload: &str| {
+            message(Content::Custom(CustomContent {
+                r#type: "order".to_string(),
+                payload: payload.as_bytes().to_vec(),
+                ..Default::default()
+            }))
+        };
+
+        let err = registry
+            .validate("t1", &order(r#"{"amount": "12"}"#))
+            .unwrap_err();
+        assert_eq!(
+            err.errors,
+            vec![
+                FieldError::new("custom.payload.order_id", "is required"),
+                FieldError::new("custom.payload.amount", "must be of type integer"),
+            ]
+        );
+        assert!(
+            registry
+                .validate("t1", &order(r#"{"order_id": "o1", "amount": 12}"#))
+                .is_ok()
+        );
+        // 其他租户未配置 Schema，不校验
+        assert!(registry.validate("t2", &order("not json")).is_ok());
+    }
+}
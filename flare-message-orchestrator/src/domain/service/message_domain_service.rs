@@ -1,29 +1,35 @@
 //! 消息领域服务 - 包含所有业务逻辑实现
 
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context as AnyhowContext, Result};
 use flare_server_core::context::Context;
 use flare_im_core::hooks::HookDispatcher;
 use flare_im_core::tracing::create_span;
+use flare_im_core::utils::SYNC_REPLY_CHANNEL_TAG;
 use flare_proto::push::{PushMessageRequest, PushOptions};
 use flare_proto::storage::StoreMessageRequest;
 use prost::Message;
 use tracing::{Span, instrument};
 
 use crate::domain::model::MessageProfile;
-use crate::domain::model::{MessageDefaults, MessageSubmission};
+use crate::domain::model::message_kind::MessageProcessingType;
+use crate::domain::model::{
+    MessageDefaults, MessageSubmission, StoredMessage, SyncPersistenceTimeout,
+};
 use crate::domain::repository::{
     MessageEventPublisher, MessageEventPublisherItem, ConversationRepository, ConversationRepositoryItem,
-    WalRepository, WalRepositoryItem,
+    PersistenceReplyWaiter, WalRepository, WalRepositoryItem,
 };
+use crate::domain::service::content_validator::{CustomContentSchemas, ValidatorRegistry};
 use crate::domain::service::hook_builder::{
     build_hook_context_from_ctx,
     apply_draft_to_request, build_draft_from_request, build_hook_context, build_message_record,
     draft_from_submission, merge_context,
 };
 use crate::domain::service::sequence_allocator::SequenceAllocator;
+use crate::domain::service::sticker_validator::StickerReferenceValidator;
 
 /// 消息领域服务 - 包含所有业务逻辑
 pub struct MessageDomainService {
@@ -34,6 +40,14 @@ pub struct MessageDomainService {
     sequence_allocator: Arc<SequenceAllocator>,
     defaults: MessageDefaults,
     hooks: Arc<HookDispatcher>,
+    /// 内容校验器（PreSend Hook 之前执行）
+    validators: Arc<ValidatorRegistry>,
+    /// 贴纸引用校验器（未配置时不校验贴纸引用的表情包集合）
+    sticker_validator: Option<Arc<StickerReferenceValidator>>,
+    /// 同步写入回执等待（未配置时 `sync = true` 与异步写入行为一致）
+    persistence_waiter: Option<Arc<dyn PersistenceReplyWaiter>>,
+    /// 同步写入等待持久化回执的超时时间
+    sync_timeout: Duration,
 }
 
 impl MessageDomainService {
@@ -52,9 +66,36 @@ impl MessageDomainService {
             sequence_allocator,
             defaults,
             hooks,
+            validators: Arc::new(ValidatorRegistry::with_builtin(CustomContentSchemas::new())),
+            sticker_validator: None,
+            persistence_waiter: None,
+            sync_timeout: Duration::ZERO,
         }
     }
 
+    /// 设置内容校验器（默认仅注册内置校验器，不含租户自定义消息 Schema）
+    pub fn with_validators(mut self, validators: Arc<ValidatorRegistry>) -> Self {
+        self.validators = validators;
+        self
+    }
+
+    /// 设置贴纸引用校验器（校验贴纸消息引用的表情包集合已绑定到会话）
+    pub fn with_sticker_validator(mut self, validator: Arc<StickerReferenceValidator>) -> Self {
+        self.sticker_validator = Some(validator);
+        self
+    }
+
+    /// 设置同步写入回执等待（`sync = true` 的普通消息在超时时间内等待持久化回执）
+    pub fn with_persistence_waiter(
+        mut self,
+        waiter: Arc<dyn PersistenceReplyWaiter>,
+        timeout: Duration,
+    ) -> Self {
+        self.persistence_waiter = Some(waiter);
+        self.sync_timeout = timeout;
+        self
+    }
+
     /// 编排消息存储流程（业务逻辑）
     /// 按照"PreSend Hook → WAL → Kafka → PostSend Hook"的顺序编排消息写入流程
     #[instrument(skip(self), fields(tenant_id, message_id, message_type))]
@@ -63,7 +104,7 @@ impl MessageDomainService {
         ctx: &Context,
         mut request: StoreMessageRequest,
         execute_pre_send: bool,
-    ) -> Result<(String, u64)> {
+    ) -> Result<StoredMessage> {
         let _start = Instant::now();
         let _span = Span::current();
 
@@ -82,6 +123,14 @@ impl MessageDomainService {
             }
         }
 
+        // 按内容类型校验结构化内容，畸形内容在 Hook 之前直接拒绝
+        if let Some(message) = &request.message {
+            self.validators.validate(&tenant_id, message)?;
+            if let Some(sticker_validator) = &self.sticker_validator {
+                sticker_validator.validate(ctx, &tenant_id, message).await?;
+            }
+        }
+
         // 从Context构建hook_context（确保tenant_id从Context获取）
         let original_context = build_hook_context_from_ctx(ctx, &request);
         let mut draft =
@@ -142,6 +191,9 @@ impl MessageDomainService {
         // 临时方案：将 seq 存储在 extra 字段中
         let mut submission = submission;
         submission.message.seq = session_seq;
+        if let Some(message) = submission.kafka_payload.message.as_mut() {
+            message.seq = session_seq;
+        }
 
         // 获取消息类型信息（用于判断是否需要持久化）
         // 注意：MessageProfile::ensure 会修改 message，所以需要 clone
@@ -270,16 +322,36 @@ impl MessageDomainService {
         // 构建推送任务
         let push_request = self.build_push_request(&submission, &profile)?;
 
+        // 同步写入：发布前注册回执等待，Storage Writer 持久化后回复本实例的回执频道
+        let pending_reply = match &self.persistence_waiter {
+            Some(waiter)
+                if submission.kafka_payload.sync
+                    && processing_type == MessageProcessingType::Normal =>
+            {
+                submission.kafka_payload.tags.insert(
+                    SYNC_REPLY_CHANNEL_TAG.to_string(),
+                    waiter.reply_channel().to_string(),
+                );
+                Some((waiter.clone(), waiter.register(&submission.message_id)))
+            }
+            _ => None,
+        };
+
         // 根据消息类型决定发布策略
         let _kafka_span = create_span("message-orchestrator", "kafka_produce");
 
         match processing_type {
             crate::domain::model::message_kind::MessageProcessingType::Normal => {
                 // 普通消息：并行发布到存储队列和推送队列
-                self.publisher
+                let published = self
+                    .publisher
                     .publish_both(submission.kafka_payload.clone(), push_request)
                     .await
-                    .context("Failed to publish message event")?;
+                    .context("Failed to publish message event");
+                if let (Err(_), Some((waiter, _))) = (&published, &pending_reply) {
+                    waiter.cancel(&submission.message_id);
+                }
+                published?;
             }
             crate::domain::model::message_kind::MessageProcessingType::Notification => {
                 // 通知消息：仅发布到推送队列
@@ -293,16 +365,44 @@ impl MessageDomainService {
         // 让 _kafka_span 离开作用域以结束 span
 
         let record = build_message_record(&submission, &submission.kafka_payload);
-        let post_draft =
-            draft_from_submission(&submission).context("Failed to build draft from submission")?;
+        let post_send = async {
+            let post_draft = draft_from_submission(&submission)
+                .context("Failed to build draft from submission")?;
 
-        // 执行 PostSend Hook（使用hook_context，确保tenant_id正确）
-        self.hooks
-            .post_send(&hook_context, &record, &post_draft)
-            .await
-            .context("PostSend hook failed")?;
+            // 执行 PostSend Hook（使用hook_context，确保tenant_id正确）
+            self.hooks
+                .post_send(&hook_context, &record, &post_draft)
+                .await
+                .context("PostSend hook failed")
+        };
+        if let Err(err) = post_send.await {
+            if let Some((waiter, _)) = &pending_reply {
+                waiter.cancel(&submission.message_id);
+            }
+            return Err(err);
+        }
+
+        let mut stored = StoredMessage {
+            message_id: submission.message_id.clone(),
+            seq: submission.message.seq,
+            persisted_ts: None,
+        };
+        // 同步写入：等待持久化回执（超时返回错误，消息仍会被异步持久化）
+        if let Some((waiter, reply)) = pending_reply {
+            match tokio::time::timeout(self.sync_timeout, reply).await {
+                Ok(Ok(reply)) => stored.persisted_ts = Some(reply.persisted_ts),
+                _ => {
+                    waiter.cancel(&submission.message_id);
+                    return Err(SyncPersistenceTimeout {
+                        message_id: submission.message_id.clone(),
+                        timeout_ms: self.sync_timeout.as_millis() as u64,
+                    }
+                    .into());
+                }
+            }
+        }
 
-        Ok((submission.message_id, submission.message.seq))
+        Ok(stored)
     }
 
     /// 构建推送请求
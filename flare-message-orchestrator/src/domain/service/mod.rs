@@ -1,3 +1,4 @@
+pub mod content_validator;
 pub mod hook_builder;
 pub mod message_domain_service;
 pub mod message_operation_builder;
@@ -6,9 +7,12 @@ pub mod message_read_service;
 pub mod message_temporary_service;
 pub mod operation_classifier;
 pub mod sequence_allocator;
+pub mod sticker_validator;
 
+pub use content_validator::{ContentValidationError, ValidatorRegistry};
 pub use hook_builder::*;
 pub use message_domain_service::MessageDomainService;
 pub use message_read_service::MessageReadService;
 pub use message_temporary_service::MessageTemporaryService;
 pub use sequence_allocator::SequenceAllocator;
+pub use sticker_validator::StickerReferenceValidator;
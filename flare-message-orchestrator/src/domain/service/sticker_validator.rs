@@ -0,0 +1,222 @@
+//! 贴纸引用校验 - 校验贴纸消息引用的表情包集合已绑定到会话
+//!
+//! 贴纸消息为 `custom` 内容，`type` 为 `sticker`，payload 为 JSON：
+//! `{"set_id": "<表情包集合ID>", "sticker_id": "<flare-media 文件ID>"}`。
+//! 会话通过属性 `sticker_sets` 绑定租户上传的表情包集合（由会话服务维护）：
+//! - 引用未绑定集合的贴纸消息在 PreSend Hook 之前直接拒绝
+//! - 会话的绑定结果按会话短时缓存，避免每条贴纸消息都查询会话服务
+//! - 会话服务不可用时降级放行（与会话自动创建的降级策略一致）
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use flare_proto::common::Message;
+use flare_proto::common::message_content::Content;
+use flare_server_core::context::Context;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+use crate::domain::repository::{ConversationRepository, ConversationRepositoryItem};
+use crate::domain::service::content_validator::{ContentValidationError, FieldError};
+
+/// 贴纸消息的自定义类型
+pub const STICKER_CUSTOM_TYPE: &str = "sticker";
+
+/// 会话绑定缓存的默认有效期
+const DEFAULT_BINDING_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// 最多缓存的会话数（超出时清空重建）
+const MAX_CACHED_CONVERSATIONS: usize = 10_000;
+
+/// 贴纸消息 payload
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct StickerReference {
+    #[serde(default)]
+    pub set_id: String,
+    #[serde(default)]
+    pub sticker_id: String,
+}
+
+impl StickerReference {
+    /// 从消息内容中提取贴纸引用（非贴纸消息返回 None）
+    pub fn from_content(content: &Content) -> Option<Result<Self, Vec<FieldError>>> {
+        let Content::Custom(custom) = content else {
+            return None;
+        };
+        if custom.r#type != STICKER_CUSTOM_TYPE {
+            return None;
+        }
+
+        let reference = match serde_json::from_slice::<Self>(&custom.payload) {
+            Ok(reference) => reference,
+            Err(err) => {
+                return Some(Err(vec![FieldError::new(
+                    "custom.payload",
+                    format!("invalid sticker payload: {err}"),
+                )]));
+            }
+        };
+        let errors: Vec<FieldError> = [
+            ("custom.payload.set_id", &reference.set_id),
+            ("custom.payload.sticker_id", &reference.sticker_id),
+        ]
+        .into_iter()
+        .filter(|(_, value)| value.trim().is_empty())
+        .map(|(field, _)| FieldError::new(field, "is required"))
+        .collect();
+        if errors.is_empty() {
+            Some(Ok(reference))
+        } else {
+            Some(Err(errors))
+        }
+    }
+}
+
+/// 贴纸引用校验器
+pub struct StickerReferenceValidator {
+    conversations: Arc<ConversationRepositoryItem>,
+    /// 租户:会话 -> (查询时间, 绑定的集合ID)
+    cache: RwLock<HashMap<String, (Instant, Arc<Vec<String>>)>>,
+    cache_ttl: Duration,
+}
+
+impl StickerReferenceValidator {
+    pub fn new(conversations: Arc<ConversationRepositoryItem>) -> Self {
+        Self {
+            conversations,
+            cache: RwLock::new(HashMap::new()),
+            cache_ttl: DEFAULT_BINDING_CACHE_TTL,
+        }
+    }
+
+    /// 设置会话绑定缓存有效期（绑定变更最多延迟该时间生效）
+    pub fn with_cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = cache_ttl;
+        self
+    }
+
+    /// 校验贴纸消息引用的集合已绑定到消息所属会话（非贴纸消息直接通过）
+    pub async fn validate(
+        &self,
+        ctx: &Context,
+        tenant_id: &str,
+        message: &Message,
+    ) -> Result<(), ContentValidationError> {
+        let Some(content) = message.content.as_ref().and_then(|c| c.content.as_ref()) else {
+            return Ok(());
+        };
+        let Some(reference) = StickerReference::from_content(content) else {
+            return Ok(());
+        };
+        let reference = reference.map_err(|errors| ContentValidationError {
+            content_type: STICKER_CUSTOM_TYPE.to_string(),
+            errors,
+        })?;
+        if message.conversation_id.is_empty() {
+            return Ok(());
+        }
+
+        let Some(bound) = self
+            .bound_sets(ctx, tenant_id, &message.conversation_id)
+            .await
+        else {
+            return Ok(());
+        };
+        if bound.iter().any(|set_id| *set_id == reference.set_id) {
+            Ok(())
+        } else {
+            Err(ContentValidationError {
+                content_type: STICKER_CUSTOM_TYPE.to_string(),
+                errors: vec![FieldError::new(
+                    "custom.payload.set_id",
+                    format!(
+                        "sticker set {} is not bound to conversation",
+                        reference.set_id
+                    ),
+                )],
+            })
+        }
+    }
+
+    /// 查询会话绑定的集合（会话不存在视为未绑定；查询失败时返回 None 表示降级放行）
+    async fn bound_sets(
+        &self,
+        ctx: &Context,
+        tenant_id: &str,
+        conversation_id: &str,
+    ) -> Option<Arc<Vec<String>>> {
+        let key = format!("{}:{}", tenant_id, conversation_id);
+        if let Some((fetched_at, bound)) = self.cache.read().await.get(&key) {
+            if fetched_at.elapsed() < self.cache_ttl {
+                return Some(bound.clone());
+            }
+        }
+
+        match self
+            .conversations
+            .bound_sticker_sets(ctx, conversation_id)
+            .await
+        {
+            Ok(bound) => {
+                let bound = Arc::new(bound.unwrap_or_default());
+                let mut cache = self.cache.write().await;
+                if cache.len() >= MAX_CACHED_CONVERSATIONS {
+                    cache.clear();
+                }
+                cache.insert(key, (Instant::now(), bound.clone()));
+                Some(bound)
+            }
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    conversation_id = %conversation_id,
+                    "Failed to load conversation sticker sets, skipping sticker validation"
+                );
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flare_proto::common::CustomContent;
+
+    fn sticker(payload: &str) -> Content {
+        Content::Custom(CustomContent {
+            r#type: STICKER_CUSTOM_TYPE.to_string(),
+            payload: payload.as_bytes().to_vec(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_sticker_reference_from_content() {
+        assert_eq!(
+            StickerReference::from_content(&sticker(r#"{"set_id": "brand", "sticker_id": "f1"}"#)),
+            Some(Ok(StickerReference {
+                set_id: "brand".to_string(),
+                sticker_id: "f1".to_string(),
+            }))
+        );
+        assert_eq!(
+            StickerReference::from_content(&sticker(r#"{"sticker_id": "f1"}"#)),
+            Some(Err(vec![FieldError::new(
+                "custom.payload.set_id",
+                "is required"
+            )]))
+        );
+        assert!(matches!(
+            StickerReference::from_content(&sticker("not json")),
+            Some(Err(_))
+        ));
+
+        let order = Content::Custom(CustomContent {
+            r#type: "order".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(StickerReference::from_content(&order), None);
+    }
+}
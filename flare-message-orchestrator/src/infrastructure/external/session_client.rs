@@ -3,15 +3,22 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use anyhow::Result;
+use flare_proto::common::{FilterExpression, FilterOperator, Pagination};
 use flare_proto::conversation::conversation_service_client::ConversationServiceClient;
-use flare_proto::conversation::{CreateConversationRequest, ConversationParticipant};
+use flare_proto::conversation::{
+    CreateConversationRequest, ConversationParticipant, SearchConversationsRequest,
+};
 use flare_server_core::context::{Context, ContextExt};
+use flare_im_core::fault_injection;
 use flare_server_core::client::set_context_metadata;
 use tonic::transport::Channel;
 use tracing::{debug, warn, instrument};
 
 use crate::domain::repository::ConversationRepository;
 
+/// 会话属性中绑定表情包集合的键（与会话服务一致，值为逗号分隔的集合ID）
+const STICKER_SETS_METADATA_KEY: &str = "sticker_sets";
+
 /// gRPC Conversation 客户端（外部依赖）
 #[derive(Debug)]
 pub struct GrpcConversationClient {
@@ -91,8 +98,12 @@ impl ConversationRepository for GrpcConversationClient {
                 "Context metadata before gRPC call"
             );
             
-            let mut client = client.lock().await;
-            match client.create_conversation(grpc_request).await {
+            let injected = fault_injection::inject_grpc("conversation.create_conversation").await;
+            let result = match injected {
+                Ok(()) => client.lock().await.create_conversation(grpc_request).await,
+                Err(status) => Err(status),
+            };
+            match result {
                 Ok(response) => {
                     let inner = response.into_inner();
                     if let Some(conv) = inner.conversation {
@@ -128,4 +139,64 @@ impl ConversationRepository for GrpcConversationClient {
             }
         })
     }
+
+    #[instrument(skip(self, ctx), fields(
+        request_id = %ctx.request_id(),
+        conversation_id = %conversation_id,
+    ))]
+    fn bound_sticker_sets<'a>(
+        &'a self,
+        ctx: &'a Context,
+        conversation_id: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Vec<String>>>> + Send + 'a>> {
+        // 会话服务没有单个会话查询接口，按 conversation_id 过滤搜索（会话 metadata 即会话属性）
+        let request = SearchConversationsRequest {
+            filters: vec![FilterExpression {
+                field: "conversation_id".to_string(),
+                op: FilterOperator::Eq as i32,
+                values: vec![conversation_id.to_string()],
+            }],
+            pagination: Some(Pagination {
+                limit: 1,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let client = Arc::clone(&self.client);
+        Box::pin(async move {
+            let mut grpc_request = tonic::Request::new(request);
+            set_context_metadata(&mut grpc_request, ctx);
+
+            fault_injection::inject_grpc("conversation.search_conversations")
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to query conversation sticker sets: {}", e))?;
+            let response = client
+                .lock()
+                .await
+                .search_conversations(grpc_request)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to query conversation sticker sets: {}", e))?
+                .into_inner();
+
+            Ok(response
+                .conversations
+                .into_iter()
+                .find(|conversation| conversation.conversation_id == conversation_id)
+                .map(|conversation| {
+                    conversation
+                        .metadata
+                        .get(STICKER_SETS_METADATA_KEY)
+                        .map(|value| {
+                            value
+                                .split(',')
+                                .map(str::trim)
+                                .filter(|set_id| !set_id.is_empty())
+                                .map(str::to_string)
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                }))
+        })
+    }
 }
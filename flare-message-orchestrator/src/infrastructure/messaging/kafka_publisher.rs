@@ -3,6 +3,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{Result, anyhow};
+use flare_im_core::fault_injection::{self, FaultAction, FaultSeam};
 use flare_proto::push::PushMessageRequest as PushPushMessageRequest;
 use flare_proto::storage::StoreMessageRequest as StorageStoreMessageRequest;
 use futures::FutureExt;
@@ -122,6 +123,18 @@ impl KafkaMessagePublisher {
         }
     }
 
+    /// 发布前的故障注入（丢弃时返回 false，批次视为已发送但未写入 Kafka）
+    async fn inject_publish_fault(topic: &str, batch_size: usize) -> Result<bool> {
+        match fault_injection::inject(FaultSeam::KafkaPublish, topic).await {
+            FaultAction::Proceed => Ok(true),
+            FaultAction::Drop => {
+                tracing::warn!(topic = %topic, batch_size, "Kafka batch dropped by fault injection");
+                Ok(false)
+            }
+            FaultAction::Fail(message) => Err(anyhow!("Kafka send error: {}", message)),
+        }
+    }
+
     /// 批量发布存储消息
     async fn publish_storage_batch(&self, payloads: Vec<StorageStoreMessageRequest>) -> Result<()> {
         if payloads.is_empty() {
@@ -157,6 +170,10 @@ impl KafkaMessagePublisher {
             return Ok(());
         }
 
+        if !Self::inject_publish_fault(&self.config.kafka_storage_topic, encoded_payloads.len()).await? {
+            return Ok(());
+        }
+
         // 构建记录（借用 encoded_payloads）
         let records: Vec<_> = valid_indices
             .iter()
@@ -222,6 +239,10 @@ impl KafkaMessagePublisher {
             return Ok(());
         }
 
+        if !Self::inject_publish_fault(&self.config.kafka_operation_topic, encoded_payloads.len()).await? {
+            return Ok(());
+        }
+
         let records: Vec<_> = valid_indices
             .iter()
             .enumerate()
@@ -285,6 +306,10 @@ impl KafkaMessagePublisher {
             return Ok(());
         }
 
+        if !Self::inject_publish_fault(&self.config.kafka_push_topic, encoded_payloads.len()).await? {
+            return Ok(());
+        }
+
         // 构建记录（借用 encoded_payloads）
         let records: Vec<_> = valid_indices
             .iter()
@@ -1,4 +1,5 @@
 pub mod kafka_publisher;
+pub mod sync_reply;
 
 #[cfg(test)]
 mod kafka_publisher_test;
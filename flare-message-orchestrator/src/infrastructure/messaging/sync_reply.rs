@@ -0,0 +1,150 @@
+//! 同步写入回执监听
+//!
+//! 每个编排服务实例订阅独立的 Redis 频道，Storage Writer 持久化 `sync = true` 的消息后向该频道发布回执，
+//! 按 message_id 唤醒等待中的请求。订阅断开后自动重新订阅，断开期间的回执丢失，对应请求等待超时。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use anyhow::Result;
+use flare_im_core::utils::PersistenceReply;
+use futures::StreamExt;
+use tokio::sync::oneshot;
+use tracing::{debug, info, warn};
+
+use crate::domain::repository::PersistenceReplyWaiter;
+
+/// 订阅断开后重新订阅的间隔
+const RESUBSCRIBE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 基于 Redis Pub/Sub 的同步写入回执监听器
+pub struct RedisPersistenceReplyListener {
+    channel: String,
+    pending: Mutex<HashMap<String, oneshot::Sender<PersistenceReply>>>,
+}
+
+impl RedisPersistenceReplyListener {
+    /// 订阅回执频道并在后台分发回执
+    pub async fn start(client: redis::Client, channel: String) -> Result<Arc<Self>> {
+        let pubsub = subscribe(&client, &channel).await?;
+        let listener = Arc::new(Self {
+            channel,
+            pending: Mutex::new(HashMap::new()),
+        });
+        tokio::spawn(Self::run(Arc::downgrade(&listener), client, pubsub));
+        info!(channel = %listener.channel, "Sync persistence reply listener started");
+        Ok(listener)
+    }
+
+    async fn run(listener: Weak<Self>, client: redis::Client, mut pubsub: redis::aio::PubSub) {
+        loop {
+            {
+                let mut messages = pubsub.on_message();
+                while let Some(msg) = messages.next().await {
+                    let Some(listener) = listener.upgrade() else {
+                        return;
+                    };
+                    let reply = msg
+                        .get_payload::<String>()
+                        .map_err(anyhow::Error::from)
+                        .and_then(|payload| {
+                            Ok(serde_json::from_str::<PersistenceReply>(&payload)?)
+                        });
+                    match reply {
+                        Ok(reply) => listener.dispatch(reply),
+                        Err(e) => warn!(error = %e, "Invalid sync persistence reply"),
+                    }
+                }
+            }
+
+            let Some(channel) = listener.upgrade().map(|listener| listener.channel.clone()) else {
+                return;
+            };
+            warn!(channel = %channel, "Sync persistence reply subscription lost, resubscribing");
+            pubsub = loop {
+                tokio::time::sleep(RESUBSCRIBE_INTERVAL).await;
+                if listener.strong_count() == 0 {
+                    return;
+                }
+                match subscribe(&client, &channel).await {
+                    Ok(pubsub) => break pubsub,
+                    Err(e) => warn!(error = %e, "Failed to resubscribe sync persistence replies"),
+                }
+            };
+        }
+    }
+
+    fn pending(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<String, oneshot::Sender<PersistenceReply>>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn dispatch(&self, reply: PersistenceReply) {
+        match self.pending().remove(&reply.message_id) {
+            Some(sender) => {
+                let _ = sender.send(reply);
+            }
+            None => debug!(
+                message_id = %reply.message_id,
+                "No pending sync request for persistence reply"
+            ),
+        }
+    }
+}
+
+impl PersistenceReplyWaiter for RedisPersistenceReplyListener {
+    fn reply_channel(&self) -> &str {
+        &self.channel
+    }
+
+    fn register(&self, message_id: &str) -> oneshot::Receiver<PersistenceReply> {
+        let (sender, receiver) = oneshot::channel();
+        self.pending().insert(message_id.to_string(), sender);
+        receiver
+    }
+
+    fn cancel(&self, message_id: &str) {
+        self.pending().remove(message_id);
+    }
+}
+
+async fn subscribe(client: &redis::Client, channel: &str) -> Result<redis::aio::PubSub> {
+    let mut pubsub = client.get_async_pubsub().await?;
+    pubsub.subscribe(channel).await?;
+    Ok(pubsub)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reply(message_id: &str) -> PersistenceReply {
+        PersistenceReply {
+            message_id: message_id.to_string(),
+            conversation_id: "conv-1".to_string(),
+            seq: 7,
+            persisted_ts: 1_700_000_000_000,
+            deduplicated: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_wakes_pending_request() {
+        let listener = RedisPersistenceReplyListener {
+            channel: "storage:sync_reply:test".to_string(),
+            pending: Mutex::new(HashMap::new()),
+        };
+
+        let receiver = listener.register("msg-1");
+        listener.dispatch(reply("msg-2"));
+        listener.dispatch(reply("msg-1"));
+        assert_eq!(receiver.await.unwrap(), reply("msg-1"));
+
+        let receiver = listener.register("msg-3");
+        listener.cancel("msg-3");
+        assert!(receiver.await.is_err());
+        assert!(listener.pending().is_empty());
+    }
+}
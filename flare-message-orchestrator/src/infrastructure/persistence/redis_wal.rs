@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use flare_im_core::fault_injection::{self, FaultAction, FaultSeam};
 use prost::Message;
 use redis::AsyncCommands;
 use redis::aio::ConnectionManager;
@@ -60,6 +61,18 @@ impl WalRepository for RedisWalRepository {
                 }
             };
 
+            match fault_injection::inject(FaultSeam::Redis, "wal.append").await {
+                FaultAction::Proceed => {}
+                FaultAction::Drop => {
+                    tracing::warn!(
+                        message_id = %_submission.message_id,
+                        "WAL write dropped by fault injection"
+                    );
+                    return Ok(());
+                }
+                FaultAction::Fail(message) => return Err(anyhow::anyhow!(message)),
+            }
+
             let mut conn = _self.connection().await?;
 
             // 使用 message.server_id 作为 WAL key（确保与查询时一致）
@@ -119,6 +132,12 @@ impl WalRepository for RedisWalRepository {
                 "🔍 Querying WAL for message"
             );
 
+            match fault_injection::inject(FaultSeam::Redis, "wal.get").await {
+                FaultAction::Proceed => {}
+                FaultAction::Drop => return Ok(None),
+                FaultAction::Fail(message) => return Err(anyhow::anyhow!(message)),
+            }
+
             let mut conn = _self.connection().await?;
 
             // 从 Redis Hash 中查询
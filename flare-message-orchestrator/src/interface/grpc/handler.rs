@@ -45,6 +45,8 @@ use crate::application::commands::StoreMessageCommand;
 use crate::application::handlers::{MessageCommandHandler, MessageQueryHandler};
 use crate::application::utils::OperationMessageBuilder;
 use crate::application::queries::QueryMessageQuery;
+use crate::domain::model::{StoredMessage, SyncPersistenceTimeout};
+use crate::domain::service::ContentValidationError;
 use flare_proto::message::message_service_server::MessageService;
 use flare_im_core::utils::context::require_context;
 use flare_server_core::context::Context;
@@ -79,6 +81,12 @@ impl MessageGrpcHandler {
     }
 }
 
+/// 内容校验失败映射为 InvalidArgument，details 携带 JSON 格式的字段错误列表
+fn content_validation_status(err: &ContentValidationError) -> Status {
+    let details = serde_json::to_vec(&err.errors).unwrap_or_default();
+    Status::with_details(tonic::Code::InvalidArgument, err.to_string(), details.into())
+}
+
     #[tonic::async_trait]
     impl MessageService for MessageGrpcHandler {
     #[instrument(skip(self, request))]
@@ -106,22 +114,25 @@ impl MessageGrpcHandler {
 
             // 调用应用层处理器处理发送消息逻辑
             match self.command_handler.handle_send_message(&ctx, cmd).await {
-            Ok((message_id, seq)) => {
+            Ok(stored) => {
                 let now = chrono::Utc::now();
                 let timeline = Some(flare_proto::common::MessageTimeline {
                     created_at: Some(prost_types::Timestamp {
                         seconds: now.timestamp(),
                         nanos: now.timestamp_subsec_nanos() as i32,
                     }),
-                    persisted_at: None,
+                    // 仅同步写入（sync = true）时返回持久化时间
+                    persisted_at: stored
+                        .persisted_ts
+                        .and_then(flare_im_core::utils::millis_to_timestamp),
                     delivered_at: None,
                     read_at: None,
                 });
 
                 Ok(Response::new(SendMessageResponse {
                     success: true,
-                    server_msg_id: message_id,
-                        seq,
+                    server_msg_id: stored.message_id,
+                        seq: stored.seq,
                     sent_at: Some(prost_types::Timestamp {
                         seconds: now.timestamp(),
                         nanos: now.timestamp_subsec_nanos() as i32,
@@ -131,6 +142,13 @@ impl MessageGrpcHandler {
                 }))
             }
             Err(err) => {
+                if let Some(invalid) = err.downcast_ref::<ContentValidationError>() {
+                    return Err(content_validation_status(invalid));
+                }
+                if let Some(timeout) = err.downcast_ref::<SyncPersistenceTimeout>() {
+                    warn!(message_id = %timeout.message_id, "Sync send not confirmed by storage writer");
+                    return Err(Status::deadline_exceeded(timeout.to_string()));
+                }
                     error!(error = %err, "Failed to send message");
                 Err(Status::internal(err.to_string()))
             }
@@ -242,7 +260,7 @@ impl MessageGrpcHandler {
             })
             .await
             {
-            Ok((message_id, _seq)) => {
+            Ok(StoredMessage { message_id, .. }) => {
                 info!(
                     message_id = %message_id,
                     conversation_id = %req.conversation_id,
@@ -15,7 +15,7 @@ pub struct ApplicationBootstrap;
 impl ApplicationBootstrap {
     /// 运行应用的主入口点
     pub async fn run() -> Result<()> {
-        use flare_im_core::{ServiceHelper, load_config};
+        use flare_im_core::{ServiceHelper, load_config_with_validation, strict_mode};
 
         // 初始化 OpenTelemetry 追踪
         #[cfg(feature = "tracing")]
@@ -31,8 +31,12 @@ impl ApplicationBootstrap {
             }
         }
 
+        // 故障注入（仅非生产环境，设置 FLARE_FAULT_SCENARIO 时启用）
+        flare_im_core::fault_injection::init_from_env()
+            .context("failed to initialize fault injection")?;
+
         // 加载应用配置
-        let app_config = load_config(Some("./config"));
+        let app_config = load_config_with_validation(Some("./config"), strict_mode())?;
         let service_config = app_config.message_orchestrator_service();
 
         info!("Parsing server address...");
@@ -81,6 +85,7 @@ impl ApplicationBootstrap {
                     .layer(MessageServiceServer::new(handler));
                 
                 Server::builder()
+                    .add_service(flare_im_core::ServerInfoServer::from_global())
                     .add_service(message_service)
                     .serve_with_shutdown(address_clone, async move {
                         info!(
@@ -3,6 +3,7 @@
 //! 类似 Go 的 Wire 框架，提供简单的依赖构建方法
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use flare_proto::storage::storage_reader_service_client::StorageReaderServiceClient;
@@ -13,9 +14,14 @@ use crate::config::MessageOrchestratorConfig;
 use crate::domain::repository::{
     MessageEventPublisherItem, ConversationRepositoryItem, WalRepositoryItem,
 };
-use crate::domain::service::{MessageDomainService, MessageTemporaryService, SequenceAllocator};
+use crate::domain::service::content_validator::CustomContentSchemas;
+use crate::domain::service::{
+    MessageDomainService, MessageTemporaryService, SequenceAllocator, StickerReferenceValidator,
+    ValidatorRegistry,
+};
 use crate::infrastructure::external::session_client::GrpcConversationClient;
 use crate::infrastructure::messaging::kafka_publisher::KafkaMessagePublisher;
+use crate::infrastructure::messaging::sync_reply::RedisPersistenceReplyListener;
 use crate::infrastructure::persistence::noop_wal::NoopWalRepository;
 use crate::infrastructure::persistence::redis_wal::RedisWalRepository;
 use crate::interface::grpc::handler::MessageGrpcHandler;
@@ -74,15 +80,31 @@ pub async fn initialize(
     // 8. 构建 Session 服务客户端（可选）
     let conversation_repository = build_conversation_client(&config).await;
 
-    // 9. 构建领域服务
-    let domain_service = Arc::new(MessageDomainService::new(
+    // 9. 构建领域服务（内容校验器与贴纸引用校验在 PreSend Hook 之前执行）
+    let validators = build_validator_registry(&config).context("Failed to create validators")?;
+    let sticker_validator = conversation_repository
+        .clone()
+        .map(|repo| Arc::new(StickerReferenceValidator::new(repo)));
+    let mut domain_service = MessageDomainService::new(
         Arc::clone(&publisher), // 使用 Arc::clone 避免移动
         wal_repository.clone(), // 先 clone，后续还需要使用
         conversation_repository,
         sequence_allocator,
         config.defaults(),
         hooks,
-    ));
+    )
+    .with_validators(validators);
+    if let Some(sticker_validator) = sticker_validator {
+        domain_service = domain_service.with_sticker_validator(sticker_validator);
+    }
+    // 同步写入（sync = true）等待 Storage Writer 的持久化回执
+    if let Some(listener) = build_persistence_reply_listener(&config).await {
+        domain_service = domain_service.with_persistence_waiter(
+            listener,
+            Duration::from_millis(config.sync_reply_timeout_ms),
+        );
+    }
+    let domain_service = Arc::new(domain_service);
 
     // 10. 构建 Storage Reader 客户端（如果配置了 reader_endpoint）
     let reader_client = build_storage_reader_client(&config).await;
@@ -223,6 +245,37 @@ async fn build_sequence_allocator(
 }
 
 /// 构建 Hook Dispatcher
+/// 构建同步写入回执监听器（未配置 Redis 或订阅失败时返回 None，sync = true 按异步写入处理）
+async fn build_persistence_reply_listener(
+    config: &Arc<MessageOrchestratorConfig>,
+) -> Option<Arc<RedisPersistenceReplyListener>> {
+    let url = config.redis_url.as_ref()?;
+    // 每个实例使用独立的回执频道
+    let instance_id = config
+        .server_id
+        .clone()
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let channel = format!("{}:{}", config.sync_reply_channel_prefix, instance_id);
+
+    let client = match redis::Client::open(url.as_str()) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to create Redis client for sync persistence replies");
+            return None;
+        }
+    };
+    match RedisPersistenceReplyListener::start(client, channel).await {
+        Ok(listener) => Some(listener),
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                "Failed to subscribe sync persistence replies, sync sends will not wait for persistence"
+            );
+            None
+        }
+    }
+}
+
 async fn build_hook_dispatcher(
     config: &Arc<MessageOrchestratorConfig>,
 ) -> Result<Arc<HookDispatcher>> {
@@ -246,6 +299,23 @@ async fn build_hook_dispatcher(
     Ok(Arc::new(HookDispatcher::new(registry)))
 }
 
+/// 构建内容校验器（内置校验器 + 租户自定义消息 Schema）
+fn build_validator_registry(
+    config: &Arc<MessageOrchestratorConfig>,
+) -> Result<Arc<ValidatorRegistry>> {
+    let schemas: CustomContentSchemas = match &config.custom_content_schema_file {
+        Some(path) => {
+            let raw = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read custom content schema file {path}"))?;
+            serde_json::from_str(&raw)
+                .with_context(|| format!("Invalid custom content schema file {path}"))?
+        }
+        None => CustomContentSchemas::new(),
+    };
+    tracing::info!(tenants = schemas.len(), "Content validators initialized");
+    Ok(Arc::new(ValidatorRegistry::with_builtin(schemas)))
+}
+
 /// 构建 Session 服务客户端
 async fn build_conversation_client(
     config: &Arc<MessageOrchestratorConfig>,
@@ -0,0 +1,21 @@
+//! Flare Push Common
+//!
+//! 推送服务共享代码库,包含:
+//! - 共享数据模型 (models)
+//! - 推送路由规则 (routing)
+//! - 在线状态查询及其依赖的 Signaling / Conversation 客户端 (online, signaling, session_client)
+//! - 投递模拟 (simulator)
+//!
+//! 被 proxy、server 两个子模块共同使用，Push Proxy 无需依赖整个 Push Server
+
+pub mod models;
+pub mod online;
+pub mod routing;
+pub mod session_client;
+pub mod signaling;
+pub mod simulator;
+
+// 导出常用类型
+pub use models::*;
+pub use online::{OnlineStatusRepository, OnlineStatusRepositoryImpl};
+pub use simulator::DeliverySimulator;
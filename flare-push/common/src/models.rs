@@ -0,0 +1,86 @@
+//! 推送共享数据模型
+
+use std::collections::BTreeMap;
+
+/// 用户在线状态信息
+#[derive(Debug, Clone)]
+pub struct OnlineStatus {
+    pub user_id: String,
+    pub online: bool,
+    pub gateway_id: Option<String>,
+    pub server_id: Option<String>,
+}
+
+/// 推送路由
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeliveryRoute {
+    /// 在线推送（经由指定网关）
+    Online { gateway_id: String },
+    /// 离线推送
+    Offline,
+}
+
+/// 收件人被过滤的原因
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterReason {
+    /// 通知类消息（或不持久化的消息），用户离线时直接舍弃
+    NotificationOffline,
+    /// 未配置可用的离线推送渠道，离线推送不会真正发出
+    NoOfflineProvider,
+}
+
+impl FilterReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterReason::NotificationOffline => "notification_offline",
+            FilterReason::NoOfflineProvider => "no_offline_provider",
+        }
+    }
+}
+
+/// 被过滤的收件人
+#[derive(Clone, Debug)]
+pub struct FilteredRecipient {
+    pub user_id: String,
+    pub reason: FilterReason,
+}
+
+/// 投递模拟不执行的过滤（在投递链路的其他服务中执行，模拟结果不反映其影响）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnsimulatedFilter {
+    /// 受众选择器（标签、人群包、会话角色）：模拟请求不携带选择器，只按显式 user_ids 计算
+    AudienceSelector,
+    /// 专注模式：接入网关按连接维护，下发时才过滤，模拟仍按在线网关报告
+    FocusMode,
+    /// 回执策略：接入网关记录已读 ACK 时执行，只影响回执推送，不影响消息分发
+    ReceiptsPolicy,
+}
+
+impl UnsimulatedFilter {
+    pub const ALL: [UnsimulatedFilter; 3] = [
+        UnsimulatedFilter::AudienceSelector,
+        UnsimulatedFilter::FocusMode,
+        UnsimulatedFilter::ReceiptsPolicy,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnsimulatedFilter::AudienceSelector => "audience_selector",
+            UnsimulatedFilter::FocusMode => "focus_mode",
+            UnsimulatedFilter::ReceiptsPolicy => "receipts_policy",
+        }
+    }
+}
+
+/// 投递模拟结果（只计算投递路径，不实际发送）
+#[derive(Clone, Debug, Default)]
+pub struct DeliverySimulation {
+    /// gateway_id -> 在线投递的用户
+    pub online: BTreeMap<String, Vec<String>>,
+    /// 离线推送渠道 -> 离线推送的用户
+    pub offline: BTreeMap<String, Vec<String>>,
+    /// 被过滤的用户
+    pub filtered: Vec<FilteredRecipient>,
+    /// 未执行的过滤，实际投递可能比模拟结果少
+    pub unsimulated: Vec<UnsimulatedFilter>,
+}
@@ -1,4 +1,4 @@
-//! 在线状态仓储实现 - 直接使用 Signaling Online 服务
+//! 在线状态仓储 - 直接使用 Signaling Online 服务
 //!
 //! 设计原则：
 //! - 直接调用 signaling-online 服务，不通过 Redis 缓存
@@ -12,9 +12,35 @@ use async_trait::async_trait;
 use flare_server_core::error::Result;
 use tracing::{info, warn};
 
-use crate::domain::repository::{OnlineStatus, OnlineStatusRepository};
-use crate::infrastructure::session_client::ConversationServiceClient;
-use crate::infrastructure::signaling::SignalingOnlineClient;
+use crate::models::OnlineStatus;
+use crate::session_client::ConversationServiceClient;
+use crate::signaling::SignalingOnlineClient;
+
+/// 在线状态查询（Push Server 分发与 Push Proxy 投递模拟共用）
+#[async_trait]
+pub trait OnlineStatusRepository: Send + Sync {
+    async fn is_online(&self, ctx: &flare_server_core::context::Context) -> Result<bool>;
+
+    /// 批量查询用户在线状态（返回用户ID到在线状态的映射）
+    async fn batch_get_online_status(
+        &self,
+        user_ids: &[String],
+    ) -> Result<HashMap<String, OnlineStatus>>;
+
+    /// 查询某个会话（聊天室/群）的所有在线用户ID列表
+    ///
+    /// # 参数
+    /// * `conversation_id` - 会话ID（聊天室ID或群ID）
+    ///
+    /// # 返回
+    /// * `Ok(Vec<String>)` - 在线用户ID列表
+    /// * `Err(Error)` - 查询失败
+    ///
+    /// # 注意
+    /// 此方法用于聊天室消息推送场景，当业务系统未提供 receiver_ids 时，
+    /// 自动查询该聊天室的所有在线用户进行推送。
+    async fn get_all_online_users_for_session(&self, conversation_id: &str) -> Result<Vec<String>>;
+}
 
 /// 在线状态仓储 - 直接使用 Signaling Online 服务
 pub struct OnlineStatusRepositoryImpl {
@@ -0,0 +1,234 @@
+//! 推送路由规则
+//!
+//! Push Server 的实际分发与 Push Proxy 的投递模拟共用同一套校验、消息类型判断和路由规则
+
+use flare_proto::common::Message;
+use flare_proto::push::PushMessageRequest;
+use flare_server_core::error::Result;
+use tracing::{error, warn};
+
+use crate::models::{DeliveryRoute, OnlineStatus};
+
+/// 校验推送消息请求的完整性
+///
+/// 单聊必须提供 receiver_id 和 user_ids，群聊/频道必须提供 channel_id
+pub fn validate_message_request(request: &PushMessageRequest) -> Result<()> {
+    // 验证消息完整性：receiver_id 和 channel_id 不能同时为空
+    if let Some(ref message) = request.message {
+        // 单聊消息：必须提供 receiver_id
+        if message.conversation_type == 1 {
+            if message.receiver_id.is_empty() {
+                error!(
+                    message_id = %message.server_id,
+                    conversation_id = %message.conversation_id,
+                    sender_id = %message.sender_id,
+                    user_ids = ?request.user_ids,
+                    "Single chat message missing receiver_id in push service"
+                );
+                return Err(flare_server_core::error::ErrorBuilder::new(
+                    flare_server_core::error::ErrorCode::InvalidParameter,
+                    format!("Single chat message must provide receiver_id. message_id={}, conversation_id={}, sender_id={}", 
+                        message.server_id, message.conversation_id, message.sender_id)
+                ).build_error());
+            }
+
+            // 如果 user_ids 为空，说明消息编排服务没有正确设置，这是错误
+            if request.user_ids.is_empty() {
+                error!(
+                    message_id = %message.server_id,
+                    receiver_id = %message.receiver_id,
+                    "user_ids is empty in PushMessageRequest, message orchestrator should set it"
+                );
+                return Err(flare_server_core::error::ErrorBuilder::new(
+                    flare_server_core::error::ErrorCode::InvalidParameter,
+                    format!("user_ids cannot be empty for single chat message. message_id={}, receiver_id={}", 
+                        message.server_id, message.receiver_id)
+                ).build_error());
+            }
+        }
+        // 群聊/频道消息：必须提供 channel_id
+        else if message.conversation_type == 2 || message.conversation_type == 3 {
+            if message.channel_id.is_empty() {
+                return Err(flare_server_core::error::ErrorBuilder::new(
+                    flare_server_core::error::ErrorCode::InvalidParameter,
+                    "Group/channel message must provide channel_id",
+                )
+                .build_error());
+            }
+        }
+
+        // 注意：已移除消息去重逻辑
+        // ACK 机制已经保证消息可靠性：
+        // 1. 客户端收到消息后发送 ACK
+        // 2. Gateway 通过 Push Proxy → Kafka → Push Server 上报 ACK
+        // 3. Push Server 确认 ACK 后停止重试
+        // 4. 如果 ACK 超时，Push Server 会重试推送（最多重试 N 次）
+        // 因此不需要额外的去重逻辑，ACK 机制已经保证了消息的可靠性和幂等性
+    }
+
+    // 验证 user_ids 不为空
+    if request.user_ids.is_empty() {
+        return Err(flare_server_core::error::ErrorBuilder::new(
+            flare_server_core::error::ErrorCode::InvalidParameter,
+            "user_ids cannot be empty after deduplication. All recipients were filtered out as duplicates"
+        ).build_error());
+    }
+
+    Ok(())
+}
+
+/// 判断推送消息的分发类型
+///
+/// 返回 (message_type, is_notification)：
+/// - `Normal`：用户离线时生成离线推送任务
+/// - `Notification`：用户离线时直接舍弃
+pub fn classify_message_request(request: &PushMessageRequest) -> (&'static str, bool) {
+    if let Some(ref message) = request.message {
+        // 快速判断：从 message.message_type 枚举值判断
+        use flare_proto::common::MessageType;
+        let msg_type =
+            MessageType::try_from(message.message_type).unwrap_or(MessageType::Unspecified);
+
+        let is_notification =
+            matches!(msg_type, MessageType::Notification | MessageType::Typing);
+
+        let persist_if_offline = request
+            .options
+            .as_ref()
+            .map(|o| o.persist_if_offline)
+            .unwrap_or(!is_notification);
+
+        let msg_type_str = if is_notification {
+            "Notification"
+        } else if persist_if_offline {
+            "Normal"
+        } else {
+            "Notification"
+        };
+
+        (msg_type_str, is_notification)
+    } else {
+        // 如果没有 message，根据 options 判断
+        let persist_if_offline = request
+            .options
+            .as_ref()
+            .map(|o| o.persist_if_offline)
+            .unwrap_or(true);
+        (
+            if persist_if_offline {
+                "Normal"
+            } else {
+                "Notification"
+            },
+            !persist_if_offline,
+        )
+    }
+}
+
+/// 多端同步的接收者
+///
+/// 消息带有来源设备和 server_id、且发送者不在接收者列表中时，返回发送者 ID
+/// （缺少 server_id 时无法生成稳定的同步任务 ID，不做同步）
+pub fn self_sync_recipient(request: &PushMessageRequest) -> Option<&str> {
+    let message = request.message.as_ref()?;
+    flare_im_core::utils::extract_origin_device_id(message)?;
+    if message.server_id.is_empty() {
+        return None;
+    }
+    let sender_id = message.sender_id.as_str();
+    if sender_id.is_empty() || request.user_ids.iter().any(|id| id == sender_id) {
+        return None;
+    }
+    Some(sender_id)
+}
+
+/// 多端同步推送任务的 message_id
+///
+/// 由源消息的 server_id 派生：同一条消息被重复消费（重试、重平衡）时得到相同的任务 ID，
+/// 下游按 message_id 追踪状态和 ACK 时不会产生重复的同步推送
+pub fn self_sync_message_id(message: &Message) -> String {
+    format!("{}:self_sync", message.server_id)
+}
+
+/// 根据在线状态决定推送路由
+///
+/// 在线且有 gateway_id 的用户走在线推送，其余（离线、无网关、未查询到状态）走离线推送
+pub fn resolve_route(user_id: &str, status: Option<&OnlineStatus>) -> DeliveryRoute {
+    match status {
+        Some(status) if status.online => match &status.gateway_id {
+            Some(gateway_id) => DeliveryRoute::Online {
+                gateway_id: gateway_id.clone(),
+            },
+            None => {
+                warn!(
+                    user_id = %user_id,
+                    "Online user has no gateway_id, treating as offline"
+                );
+                DeliveryRoute::Offline
+            }
+        },
+        Some(_) => DeliveryRoute::Offline,
+        None => {
+            warn!(user_id = %user_id, "User status not found, treating as offline");
+            DeliveryRoute::Offline
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flare_im_core::utils::embed_origin_device_id;
+
+    use super::*;
+
+    fn sent_from_device(server_id: &str, device_id: &str, user_ids: &[&str]) -> PushMessageRequest {
+        let mut message = Message {
+            server_id: server_id.to_string(),
+            sender_id: "alice".to_string(),
+            ..Default::default()
+        };
+        embed_origin_device_id(&mut message, device_id);
+        PushMessageRequest {
+            user_ids: user_ids.iter().map(|id| id.to_string()).collect(),
+            message: Some(message),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_self_sync_recipient_is_sender_of_device_originated_message() {
+        assert_eq!(
+            self_sync_recipient(&sent_from_device("msg-1", "device-a", &["bob"])),
+            Some("alice")
+        );
+
+        // 发送者已在接收者列表中、缺少来源设备或 server_id 时不额外同步
+        assert_eq!(
+            self_sync_recipient(&sent_from_device("msg-1", "device-a", &["bob", "alice"])),
+            None
+        );
+        assert_eq!(
+            self_sync_recipient(&sent_from_device("msg-1", "", &["bob"])),
+            None
+        );
+        assert_eq!(
+            self_sync_recipient(&sent_from_device("", "device-a", &["bob"])),
+            None
+        );
+        assert_eq!(self_sync_recipient(&PushMessageRequest::default()), None);
+    }
+
+    #[test]
+    fn test_self_sync_message_id_is_derived_from_source_message() {
+        let first = sent_from_device("msg-1", "device-a", &["bob"]);
+        let retried = sent_from_device("msg-1", "device-a", &["bob"]);
+        let other = sent_from_device("msg-2", "device-a", &["bob"]);
+
+        let id = self_sync_message_id(first.message.as_ref().unwrap());
+        assert_eq!(id, "msg-1:self_sync");
+        // 同一条消息重复消费时 ID 不变，不同消息互不冲突，也不与源消息 ID 相同
+        assert_eq!(id, self_sync_message_id(retried.message.as_ref().unwrap()));
+        assert_ne!(id, self_sync_message_id(other.message.as_ref().unwrap()));
+        assert_ne!(id, "msg-1");
+    }
+}
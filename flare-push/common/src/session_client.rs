@@ -2,7 +2,7 @@
 
 use std::sync::Arc;
 
-use flare_proto::common::{ActorContext, RequestContext, TenantContext};
+use flare_proto::common::{ActorContext, ConversationParticipant, RequestContext, TenantContext};
 use flare_proto::conversation::conversation_service_client::ConversationServiceClient as ConversationServiceClientProto;
 use flare_proto::conversation::{UpdateConversationRequest, UpdateConversationResponse};
 use flare_server_core::context::{Context, ContextExt};
@@ -111,6 +111,40 @@ impl ConversationServiceClient {
         ctx: &Context,
         conversation_id: &str,
     ) -> Result<Vec<String>> {
+        Ok(self
+            .load_participants(ctx, conversation_id)
+            .await?
+            .into_iter()
+            .map(|p| p.user_id)
+            .collect())
+    }
+
+    /// 获取会话中拥有指定角色之一的参与者（roles 为空时返回全部参与者）
+    #[tracing::instrument(skip(self, ctx), fields(
+        request_id = %ctx.request_id(),
+        trace_id = %ctx.trace_id(),
+        conversation_id = %conversation_id,
+    ))]
+    pub async fn get_participants_by_roles(
+        &self,
+        ctx: &Context,
+        conversation_id: &str,
+        roles: &[String],
+    ) -> Result<Vec<String>> {
+        Ok(self
+            .load_participants(ctx, conversation_id)
+            .await?
+            .into_iter()
+            .filter(|p| roles.is_empty() || p.roles.iter().any(|role| roles.contains(role)))
+            .map(|p| p.user_id)
+            .collect())
+    }
+
+    async fn load_participants(
+        &self,
+        ctx: &Context,
+        conversation_id: &str,
+    ) -> Result<Vec<ConversationParticipant>> {
         ctx.ensure_not_cancelled().map_err(|e| {
             ErrorBuilder::new(
                 ErrorCode::InternalError,
@@ -169,13 +203,8 @@ impl ConversationServiceClient {
             })?
             .into_inner();
 
-        // 从 Conversation 中提取参与者 user_id 列表
         if let Some(conversation) = response.conversation {
-            Ok(conversation
-                .participants
-                .into_iter()
-                .map(|p| p.user_id)
-                .collect())
+            Ok(conversation.participants)
         } else {
             Err(
                 ErrorBuilder::new(ErrorCode::InvalidParameter, "conversation not found")
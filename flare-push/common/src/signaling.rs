@@ -11,7 +11,7 @@ use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
 use tokio::sync::Mutex;
 use tonic::transport::Channel;
 
-use crate::domain::repository::OnlineStatus;
+use crate::models::OnlineStatus;
 
 /// Signaling Online客户端
 pub struct SignalingOnlineClient {
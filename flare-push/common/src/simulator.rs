@@ -0,0 +1,282 @@
+//! 投递模拟 - 计算消息的投递路径但不实际发送
+//!
+//! 复用推送链路的校验、消息类型判断和路由规则，报告：
+//! - 哪些用户会在线投递（按网关分组）
+//! - 哪些用户会离线推送（按推送渠道分组）
+//! - 哪些用户会被过滤（及原因）
+//!
+//! 用于客服排查和接入调试，不写入消息状态、不注册 ACK、不发布任何任务。
+//!
+//! 只覆盖 Push Server 内的过滤（通知离线舍弃、离线推送渠道），以下过滤不模拟，
+//! 在结果的 `unsimulated` 中列出（见 [`UnsimulatedFilter`]）：
+//! - 受众选择器：由 Push Proxy 入队前展开，模拟只接受显式 user_ids
+//! - 专注模式：接入网关按连接维护的内存状态，模拟仍报告到网关
+//! - 回执策略：接入网关记录已读 ACK 时执行，不影响消息分发
+
+use std::sync::Arc;
+
+use flare_proto::push::PushMessageRequest;
+use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
+use tracing::{info, instrument};
+
+use crate::models::{
+    DeliveryRoute, DeliverySimulation, FilterReason, FilteredRecipient, UnsimulatedFilter,
+};
+use crate::online::OnlineStatusRepository;
+use crate::routing::{classify_message_request, resolve_route, validate_message_request};
+
+/// 实际发送离线推送的渠道（其余渠道由 Worker 使用 noop 发送器处理，不会真正推送）
+const OFFLINE_PROVIDERS: &[&str] = &["fcm", "apns", "webpush"];
+
+/// 投递模拟器
+pub struct DeliverySimulator {
+    online_repo: Arc<dyn OnlineStatusRepository>,
+    /// 离线推送渠道（与 Push Worker 的 push_provider 保持一致）
+    offline_provider: String,
+}
+
+impl DeliverySimulator {
+    pub fn new(online_repo: Arc<dyn OnlineStatusRepository>, offline_provider: String) -> Self {
+        Self {
+            online_repo,
+            offline_provider,
+        }
+    }
+
+    /// 模拟投递
+    ///
+    /// 未提供 user_ids 时，按消息的 conversation_id 查询会话在线用户（与聊天室推送一致）
+    #[instrument(skip(self, request), fields(user_count = request.user_ids.len()))]
+    pub async fn simulate(&self, request: &PushMessageRequest) -> Result<DeliverySimulation> {
+        let mut request = request.clone();
+        if request.user_ids.is_empty() {
+            if let Some(conversation_id) = request
+                .message
+                .as_ref()
+                .map(|m| m.conversation_id.clone())
+                .filter(|id| !id.is_empty())
+            {
+                request.user_ids = self
+                    .online_repo
+                    .get_all_online_users_for_session(&conversation_id)
+                    .await?;
+            }
+        }
+
+        validate_message_request(&request)?;
+
+        let (message_type, _) = classify_message_request(&request);
+        let statuses = self
+            .online_repo
+            .batch_get_online_status(&request.user_ids)
+            .await
+            .map_err(|e| {
+                ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    "Failed to batch query online status",
+                )
+                .details(e.to_string())
+                .build_error()
+            })?;
+
+        let provider_available = OFFLINE_PROVIDERS.contains(&self.offline_provider.as_str());
+        let mut simulation = DeliverySimulation {
+            unsimulated: UnsimulatedFilter::ALL.to_vec(),
+            ..Default::default()
+        };
+        for user_id in &request.user_ids {
+            match resolve_route(user_id, statuses.get(user_id)) {
+                DeliveryRoute::Online { gateway_id } => {
+                    simulation
+                        .online
+                        .entry(gateway_id)
+                        .or_default()
+                        .push(user_id.clone());
+                }
+                DeliveryRoute::Offline if message_type == "Notification" => {
+                    simulation.filtered.push(FilteredRecipient {
+                        user_id: user_id.clone(),
+                        reason: FilterReason::NotificationOffline,
+                    });
+                }
+                DeliveryRoute::Offline if !provider_available => {
+                    simulation.filtered.push(FilteredRecipient {
+                        user_id: user_id.clone(),
+                        reason: FilterReason::NoOfflineProvider,
+                    });
+                }
+                DeliveryRoute::Offline => {
+                    simulation
+                        .offline
+                        .entry(self.offline_provider.clone())
+                        .or_default()
+                        .push(user_id.clone());
+                }
+            }
+        }
+
+        info!(
+            gateway_count = simulation.online.len(),
+            offline_count = simulation.offline.values().map(Vec::len).sum::<usize>(),
+            filtered_count = simulation.filtered.len(),
+            "Simulated push delivery"
+        );
+
+        Ok(simulation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use async_trait::async_trait;
+    use flare_proto::common::Message;
+    use flare_proto::push::PushOptions;
+
+    use super::*;
+    use crate::models::OnlineStatus;
+
+    struct FakeOnlineRepo {
+        statuses: HashMap<String, OnlineStatus>,
+    }
+
+    #[async_trait]
+    impl OnlineStatusRepository for FakeOnlineRepo {
+        async fn is_online(&self, _ctx: &flare_server_core::context::Context) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn batch_get_online_status(
+            &self,
+            user_ids: &[String],
+        ) -> Result<HashMap<String, OnlineStatus>> {
+            Ok(user_ids
+                .iter()
+                .filter_map(|id| self.statuses.get(id).map(|s| (id.clone(), s.clone())))
+                .collect())
+        }
+
+        async fn get_all_online_users_for_session(
+            &self,
+            _conversation_id: &str,
+        ) -> Result<Vec<String>> {
+            Ok(self
+                .statuses
+                .values()
+                .filter(|s| s.online)
+                .map(|s| s.user_id.clone())
+                .collect())
+        }
+    }
+
+    fn simulator(provider: &str) -> DeliverySimulator {
+        let status = |user_id: &str, gateway_id: Option<&str>| OnlineStatus {
+            user_id: user_id.to_string(),
+            online: gateway_id.is_some(),
+            gateway_id: gateway_id.map(str::to_string),
+            server_id: None,
+        };
+        let statuses = [
+            status("alice", Some("gw-1")),
+            status("bob", Some("gw-2")),
+            status("carol", None),
+        ]
+        .into_iter()
+        .map(|s| (s.user_id.clone(), s))
+        .collect();
+        DeliverySimulator::new(Arc::new(FakeOnlineRepo { statuses }), provider.to_string())
+    }
+
+    fn group_request(user_ids: &[&str], persist_if_offline: bool) -> PushMessageRequest {
+        PushMessageRequest {
+            user_ids: user_ids.iter().map(|id| id.to_string()).collect(),
+            message: Some(Message {
+                conversation_id: "group-1".to_string(),
+                conversation_type: 2,
+                channel_id: "group-1".to_string(),
+                ..Default::default()
+            }),
+            options: Some(PushOptions {
+                persist_if_offline,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simulate_groups_online_and_offline() {
+        let simulation = simulator("fcm")
+            .simulate(&group_request(&["alice", "bob", "carol", "dave"], true))
+            .await
+            .unwrap();
+
+        assert_eq!(simulation.online["gw-1"], vec!["alice"]);
+        assert_eq!(simulation.online["gw-2"], vec!["bob"]);
+        // carol 离线、dave 未查询到状态，均走离线推送
+        assert_eq!(simulation.offline["fcm"], vec!["carol", "dave"]);
+        assert!(simulation.filtered.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_simulate_reports_unsimulated_filters() {
+        let simulation = simulator("fcm")
+            .simulate(&group_request(&["alice"], true))
+            .await
+            .unwrap();
+
+        // 专注模式在网关下发时才过滤，模拟结果仍按在线网关报告
+        assert_eq!(simulation.online["gw-1"], vec!["alice"]);
+        assert_eq!(
+            simulation.unsimulated,
+            vec![
+                UnsimulatedFilter::AudienceSelector,
+                UnsimulatedFilter::FocusMode,
+                UnsimulatedFilter::ReceiptsPolicy,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_simulate_filters_offline_notifications() {
+        let simulation = simulator("fcm")
+            .simulate(&group_request(&["alice", "carol"], false))
+            .await
+            .unwrap();
+
+        assert_eq!(simulation.online["gw-1"], vec!["alice"]);
+        assert!(simulation.offline.is_empty());
+        assert_eq!(simulation.filtered.len(), 1);
+        assert_eq!(simulation.filtered[0].user_id, "carol");
+        assert_eq!(
+            simulation.filtered[0].reason,
+            FilterReason::NotificationOffline
+        );
+    }
+
+    #[tokio::test]
+    async fn test_simulate_noop_provider_filters_offline() {
+        let simulation = simulator("noop")
+            .simulate(&group_request(&["carol"], true))
+            .await
+            .unwrap();
+
+        assert!(simulation.offline.is_empty());
+        assert_eq!(
+            simulation.filtered[0].reason,
+            FilterReason::NoOfflineProvider
+        );
+    }
+
+    #[tokio::test]
+    async fn test_simulate_resolves_session_when_no_user_ids() {
+        let simulation = simulator("fcm")
+            .simulate(&group_request(&[], true))
+            .await
+            .unwrap();
+
+        let online: usize = simulation.online.values().map(Vec::len).sum();
+        assert_eq!(online, 2);
+    }
+}
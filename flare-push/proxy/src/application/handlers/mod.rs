@@ -1,5 +1,7 @@
 //! CQRS Handler（编排层）
 
 pub mod command_handler;
+pub mod query_handler;
 
 pub use command_handler::PushCommandHandler;
+pub use query_handler::PushQueryHandler;
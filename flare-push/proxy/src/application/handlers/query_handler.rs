@@ -0,0 +1,115 @@
+//! 查询处理器（查询侧）- 只读操作，不发布任何推送事件
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use flare_proto::push::{
+    FilteredRecipient, GatewayDelivery, OfflineDelivery, PushMessageRequest,
+    SimulateDeliveryResponse,
+};
+use flare_push_common::{DeliverySimulation, DeliverySimulator, UnsimulatedFilter};
+use flare_server_core::context::{Context, ContextExt};
+use tracing::instrument;
+
+use crate::application::queries::SimulateDeliveryQuery;
+use crate::domain::service::AudienceResolver;
+
+/// 推送查询处理器
+pub struct PushQueryHandler {
+    /// 投递模拟器（未配置 Signaling 服务发现时为 None）
+    simulator: Option<Arc<DeliverySimulator>>,
+    /// 受众解析器（与入队共用，模拟时按相同规则去重和限制受众规模）
+    audience_resolver: Arc<AudienceResolver>,
+}
+
+impl PushQueryHandler {
+    pub fn new(
+        simulator: Option<Arc<DeliverySimulator>>,
+        audience_resolver: Arc<AudienceResolver>,
+    ) -> Self {
+        Self {
+            simulator,
+            audience_resolver,
+        }
+    }
+
+    /// 模拟投递：报告在线投递（按网关）、离线推送（按渠道）和被过滤的用户，不实际发送
+    #[instrument(skip(self, ctx, query), fields(
+        request_id = %ctx.request_id(),
+        trace_id = %ctx.trace_id(),
+        user_count = query.request.user_ids.len(),
+    ))]
+    pub async fn handle_simulate_delivery(
+        &self,
+        ctx: &Context,
+        query: SimulateDeliveryQuery,
+    ) -> Result<SimulateDeliveryResponse> {
+        ctx.ensure_not_cancelled()?;
+        let simulator = self.simulator.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("Delivery simulation requires signaling service discovery")
+        })?;
+
+        let req = query.request;
+        // 显式 user_ids 与入队时一样去重并校验受众上限（模拟请求不携带受众选择器）
+        let user_ids = self
+            .audience_resolver
+            .resolve(ctx, &req.user_ids, None)
+            .await?;
+        let push_request = PushMessageRequest {
+            user_ids,
+            message: req.message,
+            options: req.options,
+            context: req.context,
+            tenant: req.tenant,
+            template_id: String::new(),
+            template_data: std::collections::HashMap::new(),
+        };
+
+        let simulation = simulator
+            .simulate(&push_request)
+            .await
+            .map_err(|e| anyhow::anyhow!("Delivery simulation failed: {}", e))?;
+        Ok(simulation_to_proto(simulation))
+    }
+}
+
+fn simulation_to_proto(simulation: DeliverySimulation) -> SimulateDeliveryResponse {
+    SimulateDeliveryResponse {
+        online_deliveries: simulation
+            .online
+            .into_iter()
+            .map(|(gateway_id, user_ids)| GatewayDelivery {
+                gateway_id,
+                user_ids,
+            })
+            .collect(),
+        offline_deliveries: simulation
+            .offline
+            .into_iter()
+            .map(|(provider, user_ids)| OfflineDelivery { provider, user_ids })
+            .collect(),
+        filtered_recipients: simulation
+            .filtered
+            .into_iter()
+            .map(|recipient| FilteredRecipient {
+                user_id: recipient.user_id,
+                reason: recipient.reason.as_str().to_string(),
+            })
+            .collect(),
+        // 未模拟的过滤在状态消息中列出，提示实际投递可能少于模拟结果
+        status: Some(flare_proto::common::RpcStatus {
+            code: flare_proto::common::ErrorCode::Ok as i32,
+            message: unsimulated_message(&simulation.unsimulated),
+            details: Default::default(),
+            context: None,
+        }),
+    }
+}
+
+fn unsimulated_message(filters: &[UnsimulatedFilter]) -> String {
+    if filters.is_empty() {
+        return String::new();
+    }
+    let names: Vec<&str> = filters.iter().map(UnsimulatedFilter::as_str).collect();
+    format!("not simulated: {}", names.join(","))
+}
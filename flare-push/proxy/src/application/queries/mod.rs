@@ -1,3 +1,10 @@
 //! 查询结构体定义（Query DTO）
 
-// Push Proxy 目前没有查询需求，保留此文件以备将来扩展
+use flare_proto::push::SimulateDeliveryRequest;
+
+/// 模拟投递查询（只计算投递路径，不实际发送）
+#[derive(Debug, Clone)]
+pub struct SimulateDeliveryQuery {
+    /// 原始请求
+    pub request: SimulateDeliveryRequest,
+}
@@ -2,6 +2,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use flare_proto::flare::push::v1::PushAckRequest;
 use flare_proto::push::{PushMessageRequest, PushNotificationRequest};
+use flare_server_core::context::Context;
 
 /// 推送事件发布器（需要作为 trait 对象使用，保留 async-trait）
 #[async_trait]
@@ -10,3 +11,29 @@ pub trait PushEventPublisher: Send + Sync {
     async fn publish_notification(&self, request: &PushNotificationRequest) -> Result<()>;
     async fn publish_ack(&self, request: &PushAckRequest) -> Result<()>;
 }
+
+/// 受众目录：按租户读取标签/人群包成员（需要作为 trait 对象使用，保留 async-trait）
+#[async_trait]
+pub trait AudienceDirectory: Send + Sync {
+    /// 分页读取成员
+    ///
+    /// 返回 `(成员用户ID, 下一页游标)`；下一页游标为 `None` 表示已读完
+    async fn members(
+        &self,
+        tenant_id: &str,
+        name: &str,
+        cursor: Option<String>,
+        page_size: usize,
+    ) -> Result<(Vec<String>, Option<String>)>;
+}
+
+/// 会话角色目录：查询会话中拥有指定角色的成员
+#[async_trait]
+pub trait ConversationRoleDirectory: Send + Sync {
+    async fn members_with_roles(
+        &self,
+        ctx: &Context,
+        conversation_id: &str,
+        roles: &[String],
+    ) -> Result<Vec<String>>;
+}
@@ -0,0 +1,434 @@
+//! 受众解析领域服务
+//!
+//! 在入队前将推送请求中的受众选择器（标签、人群包、会话角色）展开为具体用户列表，
+//! 与显式指定的 user_ids 合并去重，并按上限截断校验
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow, bail};
+use flare_proto::push::AudienceSelector;
+use flare_server_core::context::{Context, ContextExt};
+use tracing::debug;
+
+use crate::domain::repositories::{AudienceDirectory, ConversationRoleDirectory};
+
+/// 目录分页大小
+const DIRECTORY_PAGE_SIZE: usize = 1_000;
+
+/// 受众解析器
+pub struct AudienceResolver {
+    tags: Option<Arc<dyn AudienceDirectory>>,
+    segments: Option<Arc<dyn AudienceDirectory>>,
+    roles: Option<Arc<dyn ConversationRoleDirectory>>,
+    max_audience_size: usize,
+}
+
+/// 去重累加器（保持首次出现顺序，超过上限立即失败）
+struct AudienceCollector {
+    users: Vec<String>,
+    seen: HashSet<String>,
+    limit: usize,
+}
+
+impl AudienceCollector {
+    fn new(limit: usize) -> Self {
+        Self {
+            users: Vec::new(),
+            seen: HashSet::new(),
+            limit,
+        }
+    }
+
+    fn extend(&mut self, user_ids: impl IntoIterator<Item = String>) -> Result<()> {
+        for user_id in user_ids {
+            if user_id.is_empty() || !self.seen.insert(user_id.clone()) {
+                continue;
+            }
+            if self.users.len() >= self.limit {
+                bail!("audience size exceeds maximum limit of {}", self.limit);
+            }
+            self.users.push(user_id);
+        }
+        Ok(())
+    }
+}
+
+impl AudienceResolver {
+    pub fn new(max_audience_size: usize) -> Self {
+        Self {
+            tags: None,
+            segments: None,
+            roles: None,
+            max_audience_size: max_audience_size.max(1),
+        }
+    }
+
+    /// 设置标签目录
+    pub fn with_tag_directory(mut self, directory: Arc<dyn AudienceDirectory>) -> Self {
+        self.tags = Some(directory);
+        self
+    }
+
+    /// 设置人群包目录
+    pub fn with_segment_directory(mut self, directory: Arc<dyn AudienceDirectory>) -> Self {
+        self.segments = Some(directory);
+        self
+    }
+
+    /// 设置会话角色目录
+    pub fn with_role_directory(mut self, directory: Arc<dyn ConversationRoleDirectory>) -> Self {
+        self.roles = Some(directory);
+        self
+    }
+
+    /// 解析最终受众
+    ///
+    /// - 显式 user_ids 在前，选择器展开的用户按标签、人群包、会话角色的顺序追加
+    /// - 上限取配置值与请求 `max_users` 中较小者，超限时整个请求失败（不做静默截断）
+    pub async fn resolve(
+        &self,
+        ctx: &Context,
+        user_ids: &[String],
+        selector: Option<&AudienceSelector>,
+    ) -> Result<Vec<String>> {
+        let limit = match selector.map(|s| s.max_users as usize) {
+            Some(max_users) if max_users > 0 => max_users.min(self.max_audience_size),
+            _ => self.max_audience_size,
+        };
+        let mut collector = AudienceCollector::new(limit);
+        collector.extend(user_ids.iter().cloned())?;
+
+        let Some(selector) = selector else {
+            return Ok(collector.users);
+        };
+        let tenant_id = ctx.tenant_id().unwrap_or("0").to_string();
+
+        for tag in &selector.tags {
+            let directory = self.tags.as_ref().ok_or_else(|| {
+                anyhow!("tag audience is not supported: tag directory not configured")
+            })?;
+            Self::collect_directory(directory.as_ref(), &tenant_id, tag, &mut collector).await?;
+        }
+
+        for segment in &selector.segments {
+            let directory = self.segments.as_ref().ok_or_else(|| {
+                anyhow!("segment audience is not supported: segment directory not configured")
+            })?;
+            Self::collect_directory(directory.as_ref(), &tenant_id, segment, &mut collector)
+                .await?;
+        }
+
+        for role_selector in &selector.conversation_roles {
+            let directory = self.roles.as_ref().ok_or_else(|| {
+                anyhow!("role audience is not supported: conversation service not configured")
+            })?;
+            if role_selector.conversation_id.is_empty() {
+                bail!("conversation_id is required for role audience");
+            }
+            let members = directory
+                .members_with_roles(ctx, &role_selector.conversation_id, &role_selector.roles)
+                .await?;
+            collector.extend(members)?;
+        }
+
+        debug!(
+            tenant_id = %tenant_id,
+            explicit = user_ids.len(),
+            resolved = collector.users.len(),
+            "Resolved push audience"
+        );
+        Ok(collector.users)
+    }
+
+    async fn collect_directory(
+        directory: &dyn AudienceDirectory,
+        tenant_id: &str,
+        name: &str,
+        collector: &mut AudienceCollector,
+    ) -> Result<()> {
+        let mut cursor = None;
+        loop {
+            let (members, next) = directory
+                .members(tenant_id, name, cursor, DIRECTORY_PAGE_SIZE)
+                .await?;
+            collector.extend(members)?;
+            match next {
+                Some(next) => cursor = Some(next),
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    /// 内存目录：(租户, 名称) -> 成员，游标为下一页起始下标
+    #[derive(Default)]
+    struct MemoryDirectory {
+        members: HashMap<(String, String), Vec<String>>,
+        pages: Mutex<usize>,
+    }
+
+    impl MemoryDirectory {
+        fn with(mut self, tenant_id: &str, name: &str, members: &[&str]) -> Self {
+            self.members.insert(
+                (tenant_id.to_string(), name.to_string()),
+                members.iter().map(|m| m.to_string()).collect(),
+            );
+            self
+        }
+    }
+
+    #[async_trait]
+    impl AudienceDirectory for MemoryDirectory {
+        async fn members(
+            &self,
+            tenant_id: &str,
+            name: &str,
+            cursor: Option<String>,
+            page_size: usize,
+        ) -> Result<(Vec<String>, Option<String>)> {
+            *self.pages.lock().unwrap() += 1;
+            let all = self
+                .members
+                .get(&(tenant_id.to_string(), name.to_string()))
+                .cloned()
+                .unwrap_or_default();
+            let start: usize = cursor.map(|c| c.parse().unwrap()).unwrap_or(0);
+            let end = (start + page_size).min(all.len());
+            let next = (end < all.len()).then(|| end.to_string());
+            Ok((all[start..end].to_vec(), next))
+        }
+    }
+
+    /// 内存会话角色目录：会话 -> (成员, 角色)
+    #[derive(Default)]
+    struct MemoryRoles {
+        members: HashMap<String, Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl ConversationRoleDirectory for MemoryRoles {
+        async fn members_with_roles(
+            &self,
+            _ctx: &Context,
+            conversation_id: &str,
+            roles: &[String],
+        ) -> Result<Vec<String>> {
+            Ok(self
+                .members
+                .get(conversation_id)
+                .into_iter()
+                .flatten()
+                .filter(|(_, role)| roles.contains(role))
+                .map(|(user_id, _)| user_id.clone())
+                .collect())
+        }
+    }
+
+    fn ctx() -> Context {
+        Context::root().with_tenant_id("tenant-a")
+    }
+
+    fn users(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn add_role_selector(selector: &mut AudienceSelector, conversation_id: &str, roles: &[&str]) {
+        selector.conversation_roles.push(Default::default());
+        let role_selector = selector.conversation_roles.last_mut().unwrap();
+        role_selector.conversation_id = conversation_id.to_string();
+        role_selector.roles = users(roles);
+    }
+
+    fn full_resolver(max_audience_size: usize) -> AudienceResolver {
+        let tags = MemoryDirectory::default()
+            .with("tenant-a", "vip", &["u2", "u3"])
+            .with("tenant-b", "vip", &["other-tenant"]);
+        let segments = MemoryDirectory::default().with("tenant-a", "churned", &["u3", "u4"]);
+        let mut roles = MemoryRoles::default();
+        roles.members.insert(
+            "conv-1".to_string(),
+            vec![
+                ("u1".to_string(), "admin".to_string()),
+                ("u5".to_string(), "admin".to_string()),
+                ("u6".to_string(), "member".to_string()),
+            ],
+        );
+        AudienceResolver::new(max_audience_size)
+            .with_tag_directory(Arc::new(tags))
+            .with_segment_directory(Arc::new(segments))
+            .with_role_directory(Arc::new(roles))
+    }
+
+    #[tokio::test]
+    async fn test_explicit_users_and_selectors_are_merged_in_order_without_duplicates() {
+        let resolver = full_resolver(100);
+        let mut selector = AudienceSelector {
+            tags: users(&["vip"]),
+            segments: users(&["churned"]),
+            ..Default::default()
+        };
+        add_role_selector(&mut selector, "conv-1", &["admin"]);
+
+        let resolved = resolver
+            .resolve(&ctx(), &users(&["u1", "", "u2", "u1"]), Some(&selector))
+            .await
+            .unwrap();
+
+        // 显式用户在前，随后依次为标签、人群包、会话角色；空ID与重复用户被忽略，其他租户的标签成员不会混入
+        assert_eq!(resolved, users(&["u1", "u2", "u3", "u4", "u5"]));
+    }
+
+    #[tokio::test]
+    async fn test_selector_kinds_resolve_independently() {
+        let resolver = full_resolver(100);
+        let tags_only = AudienceSelector {
+            tags: users(&["vip"]),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolver
+                .resolve(&ctx(), &[], Some(&tags_only))
+                .await
+                .unwrap(),
+            users(&["u2", "u3"])
+        );
+
+        let mut roles_only = AudienceSelector::default();
+        add_role_selector(&mut roles_only, "conv-1", &["admin", "member"]);
+        assert_eq!(
+            resolver
+                .resolve(&ctx(), &[], Some(&roles_only))
+                .await
+                .unwrap(),
+            users(&["u1", "u5", "u6"])
+        );
+
+        // 未知标签解析为空，不影响显式用户
+        let unknown = AudienceSelector {
+            tags: users(&["missing"]),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolver
+                .resolve(&ctx(), &users(&["u9"]), Some(&unknown))
+                .await
+                .unwrap(),
+            users(&["u9"])
+        );
+        assert_eq!(
+            resolver
+                .resolve(&ctx(), &users(&["u9", "u9"]), None)
+                .await
+                .unwrap(),
+            users(&["u9"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_directory_members_are_read_page_by_page() {
+        let members: Vec<String> = (0..DIRECTORY_PAGE_SIZE * 2 + 1)
+            .map(|i| format!("user-{}", i))
+            .collect();
+        let member_refs: Vec<&str> = members.iter().map(String::as_str).collect();
+        let directory = Arc::new(MemoryDirectory::default().with("tenant-a", "all", &member_refs));
+        let resolver = AudienceResolver::new(10_000).with_tag_directory(directory.clone());
+        let selector = AudienceSelector {
+            tags: users(&["all"]),
+            ..Default::default()
+        };
+
+        let resolved = resolver
+            .resolve(&ctx(), &[], Some(&selector))
+            .await
+            .unwrap();
+
+        assert_eq!(resolved, members);
+        assert_eq!(*directory.pages.lock().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_audience_limit_uses_smaller_of_config_and_request() {
+        let resolver = full_resolver(4);
+        let selector = AudienceSelector {
+            tags: users(&["vip"]),
+            segments: users(&["churned"]),
+            ..Default::default()
+        };
+
+        // u1 + u2/u3 + u3(重复)/u4 = 4 个不同用户，重复成员不计入上限
+        assert_eq!(
+            resolver
+                .resolve(&ctx(), &users(&["u1"]), Some(&selector))
+                .await
+                .unwrap(),
+            users(&["u1", "u2", "u3", "u4"])
+        );
+        // 超过配置上限时整个请求失败，不做静默截断
+        let err = resolver
+            .resolve(&ctx(), &users(&["u0", "u1"]), Some(&selector))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("maximum limit of 4"));
+
+        // 请求的 max_users 更小时以请求为准，大于配置值时仍以配置为准
+        let narrow = AudienceSelector {
+            max_users: 2,
+            ..selector.clone()
+        };
+        let err = resolver
+            .resolve(&ctx(), &users(&["u1"]), Some(&narrow))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("maximum limit of 2"));
+        let wide = AudienceSelector {
+            max_users: 100,
+            ..selector
+        };
+        assert!(
+            resolver
+                .resolve(&ctx(), &users(&["u0", "u1"]), Some(&wide))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_selectors_are_rejected() {
+        let resolver = AudienceResolver::new(100);
+        let tags = AudienceSelector {
+            tags: users(&["vip"]),
+            ..Default::default()
+        };
+        assert!(resolver.resolve(&ctx(), &[], Some(&tags)).await.is_err());
+        let segments = AudienceSelector {
+            segments: users(&["churned"]),
+            ..Default::default()
+        };
+        assert!(
+            resolver
+                .resolve(&ctx(), &[], Some(&segments))
+                .await
+                .is_err()
+        );
+
+        // 会话角色选择器必须指定会话
+        let resolver = full_resolver(100);
+        let mut roles = AudienceSelector::default();
+        add_role_selector(&mut roles, "", &["admin"]);
+        let err = resolver
+            .resolve(&ctx(), &[], Some(&roles))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("conversation_id is required"));
+    }
+}
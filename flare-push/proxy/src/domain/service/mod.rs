@@ -1,5 +1,7 @@
 //! 领域服务（Domain Service）
 
+pub mod audience_resolver;
 pub mod push_domain_service;
 
+pub use audience_resolver::AudienceResolver;
 pub use push_domain_service::PushDomainService;
@@ -15,6 +15,7 @@ use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
 use crate::domain::repositories::PushEventPublisher;
+use crate::domain::service::AudienceResolver;
 use crate::infrastructure::validator::RequestValidator;
 use flare_im_core::hooks::HookDispatcher;
 
@@ -23,6 +24,9 @@ pub struct PushDomainService {
     publisher: Arc<dyn PushEventPublisher>,
     validator: Arc<dyn RequestValidator>,
     hook_dispatcher: HookDispatcher,
+    audience_resolver: Arc<AudienceResolver>,
+    /// 扇出分批大小（每个 Kafka 消息包含的最大用户数）
+    fanout_batch_size: usize,
 }
 
 impl PushDomainService {
@@ -30,14 +34,52 @@ impl PushDomainService {
         publisher: Arc<dyn PushEventPublisher>,
         validator: Arc<dyn RequestValidator>,
         hook_dispatcher: HookDispatcher,
+        audience_resolver: Arc<AudienceResolver>,
+        fanout_batch_size: usize,
     ) -> Self {
         Self {
             publisher,
             validator,
             hook_dispatcher,
+            audience_resolver,
+            fanout_batch_size: fanout_batch_size.max(1),
         }
     }
 
+    /// 分批发布推送消息，返回发布失败的用户
+    ///
+    /// 每批复制原请求并替换为该批用户，受众选择器已在入队前展开，不再下发
+    async fn publish_message_batches(
+        &self,
+        request: &PushMessageRequest,
+        user_ids: &[String],
+        task_id: &str,
+    ) -> Vec<PushFailure> {
+        let mut failures = Vec::new();
+        for (batch_index, batch) in user_ids.chunks(self.fanout_batch_size).enumerate() {
+            let mut batch_request = request.clone();
+            batch_request.user_ids = batch.to_vec();
+            batch_request.audience = None;
+
+            if let Err(err) = self.publisher.publish_message(&batch_request).await {
+                warn!(
+                    error = %err,
+                    task_id = %task_id,
+                    batch_index,
+                    batch_size = batch.len(),
+                    "Failed to publish message to Kafka"
+                );
+                failures.extend(batch.iter().map(|user_id| PushFailure {
+                    user_id: user_id.clone(),
+                    code: error_code_internal(),
+                    error_message: err.to_string(),
+                    metadata: std::collections::HashMap::new(),
+                }));
+            }
+        }
+        failures
+    }
+
     /// 入队推送消息（业务逻辑）
     #[instrument(skip(self, ctx), fields(
         request_id = %ctx.request_id(),
@@ -56,58 +98,151 @@ impl PushDomainService {
             .validate_message_request(&request)
             .with_context(|| "Request validation failed")?;
 
-        let user_ids = request.user_ids.clone();
+        // 2. 受众解析：展开标签/人群包/会话角色选择器
+        let user_ids = self
+            .audience_resolver
+            .resolve(ctx, &request.user_ids, request.audience.as_ref())
+            .await
+            .with_context(|| "Audience resolution failed")?;
+        if user_ids.is_empty() {
+            return Err(anyhow::anyhow!("resolved audience is empty"));
+        }
         let task_id = Uuid::new_v4().to_string();
 
-        // 2. 发布到 Kafka（幂等性由 Kafka 保证）
-        match self.publisher.publish_message(&request).await {
-            Ok(_) => {
-                // 3. PostSend Hook（异步，不阻塞响应）
-                // 注意：PostSend Hook 在 proxy 中只做审计日志，不修改消息状态
-                // 实际的消息状态由 server/worker 处理
-                tokio::spawn({
-                    let hook_dispatcher = self.hook_dispatcher.clone();
-                    let request = request.clone();
-                    let task_id = task_id.clone();
-                    let user_count = user_ids.len();
-                    let ctx = ctx.clone();
-                    async move {
-                        // 调用 PostSend Hook（审计日志）
-                        tracing::info!(
-                            task_id = %task_id,
-                            user_count = user_count,
-                            "Push message enqueued successfully"
-                        );
+        // 3. 分批发布到 Kafka（幂等性由 Kafka 保证）
+        let failures = self
+            .publish_message_batches(&request, &user_ids, &task_id)
+            .await;
+        if failures.len() == user_ids.len() {
+            return Ok(PushMessageResponse {
+                success_count: 0,
+                fail_count: user_ids.len() as i32,
+                failed_user_ids: user_ids.clone(),
+                failures,
+                task_id: String::new(),
+                status: Some(rpc_status_internal("failed to enqueue push message")),
+            });
+        }
 
-                        let tenant_id = ctx.tenant_id().unwrap_or("0").to_string();
-                        
-                        // 创建新的Context用于hook（保留原始Context的tenant_id）
-                        let mut hook_ctx = ctx.clone();
-                        if hook_ctx.request_id() != task_id {
-                            hook_ctx = Context::with_request_id(task_id.clone());
-                            if let Some(tenant_id) = ctx.tenant_id() {
-                                hook_ctx = hook_ctx.with_tenant_id(tenant_id.to_string());
-                            }
-                            if let Some(user_id) = ctx.user_id() {
-                                hook_ctx = hook_ctx.with_user_id(user_id.to_string());
-                            }
-                        }
-                        if hook_ctx.trace_id() != task_id {
-                            hook_ctx = hook_ctx.with_trace_id(task_id.clone());
-                        }
-                        
-                        // 创建 HookContextData
-                        let conversation_id = request.message.as_ref().map(|m| m.conversation_id.clone());
-                        let conversation_type = request.message.as_ref().map(|m| {
-                            match m.conversation_type {
+        // 4. PostSend Hook（异步，不阻塞响应）
+        // 注意：PostSend Hook 在 proxy 中只做审计日志，不修改消息状态
+        // 实际的消息状态由 server/worker 处理
+        tokio::spawn({
+            let hook_dispatcher = self.hook_dispatcher.clone();
+            let request = request.clone();
+            let task_id = task_id.clone();
+            let user_count = user_ids.len();
+            let ctx = ctx.clone();
+            async move {
+                // 调用 PostSend Hook（审计日志）
+                tracing::info!(
+                    task_id = %task_id,
+                    user_count = user_count,
+                    "Push message enqueued successfully"
+                );
+
+                let tenant_id = ctx.tenant_id().unwrap_or("0").to_string();
+                
+                // 创建新的Context用于hook（保留原始Context的tenant_id）
+                let mut hook_ctx = ctx.clone();
+                if hook_ctx.request_id() != task_id {
+                    hook_ctx = Context::with_request_id(task_id.clone());
+                    if let Some(tenant_id) = ctx.tenant_id() {
+                        hook_ctx = hook_ctx.with_tenant_id(tenant_id.to_string());
+                    }
+                    if let Some(user_id) = ctx.user_id() {
+                        hook_ctx = hook_ctx.with_user_id(user_id.to_string());
+                    }
+                }
+                if hook_ctx.trace_id() != task_id {
+                    hook_ctx = hook_ctx.with_trace_id(task_id.clone());
+                }
+                
+                // 创建 HookContextData
+                let conversation_id = request.message.as_ref().map(|m| m.conversation_id.clone());
+                let conversation_type = request.message.as_ref().map(|m| {
+                    match m.conversation_type {
+                        1 => "single".to_string(),
+                        2 => "group".to_string(),
+                        3 => "broadcast".to_string(),
+                        _ => "unknown".to_string(),
+                    }
+                });
+                let message_type = request.message.as_ref().map(|m| {
+                    match m.message_type {
+                        1 => "text".to_string(),
+                        2 => "image".to_string(),
+                        3 => "audio".to_string(),
+                        4 => "video".to_string(),
+                        5 => "file".to_string(),
+                        6 => "location".to_string(),
+                        7 => "contact".to_string(),
+                        8 => "system".to_string(),
+                        9 => "custom".to_string(),
+                        _ => "unknown".to_string(),
+                    }
+                });
+                let sender_id = request.message.as_ref().map(|m| m.sender_id.clone());
+                
+                if let Some(conv_id) = &conversation_id {
+                    hook_ctx = hook_ctx.with_session_id(conv_id.clone());
+                }
+                
+                let hook_data = HookContextData::new()
+                    .with_conversation_id(conversation_id.unwrap_or_default())
+                    .with_conversation_type(conversation_type.unwrap_or_default())
+                    .with_message_type(message_type.unwrap_or_default())
+                    .with_sender_id(sender_id.unwrap_or_default())
+                    .occurred_now();
+                
+                hook_ctx = set_hook_context_data(hook_ctx, hook_data);
+
+                let payload = Vec::new();
+                let mut draft = MessageDraft::new(payload);
+
+                // 从请求选项中获取元数据
+                if let Some(options) = &request.options {
+                    draft.metadata = options.metadata.clone();
+                }
+
+                let record = MessageRecord {
+                    message_id: request
+                        .message
+                        .as_ref()
+                        .map(|m| m.server_id.clone())
+                        .unwrap_or_default(),
+                    client_message_id: request
+                        .message
+                        .as_ref()
+                        .map(|m| m.client_msg_id.clone()),
+                    conversation_id: request
+                        .message
+                        .as_ref()
+                        .map(|m| m.conversation_id.clone())
+                        .unwrap_or_default(),
+                    sender_id: request
+                        .message
+                        .as_ref()
+                        .map(|m| m.sender_id.clone())
+                        .unwrap_or_default(),
+                    conversation_type: request
+                        .message
+                        .as_ref()
+                        .map(|m| {
+                            let conversation_type_str = match m.conversation_type {
                                 1 => "single".to_string(),
                                 2 => "group".to_string(),
                                 3 => "broadcast".to_string(),
                                 _ => "unknown".to_string(),
-                            }
-                        });
-                        let message_type = request.message.as_ref().map(|m| {
-                            match m.message_type {
+                            };
+                            Some(conversation_type_str)
+                        })
+                        .flatten(),
+                    message_type: request
+                        .message
+                        .as_ref()
+                        .map(|m| {
+                            let message_type_str = match m.message_type {
                                 1 => "text".to_string(),
                                 2 => "image".to_string(),
                                 3 => "audio".to_string(),
@@ -118,143 +253,42 @@ impl PushDomainService {
                                 8 => "system".to_string(),
                                 9 => "custom".to_string(),
                                 _ => "unknown".to_string(),
-                            }
-                        });
-                        let sender_id = request.message.as_ref().map(|m| m.sender_id.clone());
-                        
-                        if let Some(conv_id) = &conversation_id {
-                            hook_ctx = hook_ctx.with_session_id(conv_id.clone());
-                        }
-                        
-                        let hook_data = HookContextData::new()
-                            .with_conversation_id(conversation_id.unwrap_or_default())
-                            .with_conversation_type(conversation_type.unwrap_or_default())
-                            .with_message_type(message_type.unwrap_or_default())
-                            .with_sender_id(sender_id.unwrap_or_default())
-                            .occurred_now();
-                        
-                        hook_ctx = set_hook_context_data(hook_ctx, hook_data);
-
-                        let payload = Vec::new();
-                        let mut draft = MessageDraft::new(payload);
-
-                        // 从请求选项中获取元数据
-                        if let Some(options) = &request.options {
-                            draft.metadata = options.metadata.clone();
-                        }
-
-                        let record = MessageRecord {
-                            message_id: request
-                                .message
-                                .as_ref()
-                                .map(|m| m.server_id.clone())
-                                .unwrap_or_default(),
-                            client_message_id: request
-                                .message
-                                .as_ref()
-                                .map(|m| m.client_msg_id.clone()),
-                            conversation_id: request
-                                .message
-                                .as_ref()
-                                .map(|m| m.conversation_id.clone())
-                                .unwrap_or_default(),
-                            sender_id: request
-                                .message
-                                .as_ref()
-                                .map(|m| m.sender_id.clone())
-                                .unwrap_or_default(),
-                            conversation_type: request
-                                .message
-                                .as_ref()
-                                .map(|m| {
-                                    let conversation_type_str = match m.conversation_type {
-                                        1 => "single".to_string(),
-                                        2 => "group".to_string(),
-                                        3 => "broadcast".to_string(),
-                                        _ => "unknown".to_string(),
-                                    };
-                                    Some(conversation_type_str)
-                                })
-                                .flatten(),
-                            message_type: request
-                                .message
-                                .as_ref()
-                                .map(|m| {
-                                    let message_type_str = match m.message_type {
-                                        1 => "text".to_string(),
-                                        2 => "image".to_string(),
-                                        3 => "audio".to_string(),
-                                        4 => "video".to_string(),
-                                        5 => "file".to_string(),
-                                        6 => "location".to_string(),
-                                        7 => "contact".to_string(),
-                                        8 => "system".to_string(),
-                                        9 => "custom".to_string(),
-                                        _ => "unknown".to_string(),
-                                    };
-                                    Some(message_type_str)
-                                })
-                                .flatten(),
-                            persisted_at: std::time::SystemTime::now(),
-                            metadata: if let Some(options) = &request.options {
-                                options.metadata.clone()
-                            } else {
-                                std::collections::HashMap::new()
-                            },
-                        };
-
-                        // 执行 PostSend Hook
-                        if let Err(e) = hook_dispatcher.post_send(&hook_ctx, &record, &draft).await {
-                            tracing::warn!(
-                                task_id = %task_id,
-                                error = %e,
-                                "Failed to execute PostSend hook"
-                            );
-                        } else {
-                            tracing::debug!(
-                                task_id = %task_id,
-                                "Successfully executed PostSend hook"
-                            );
-                        }
-                    }
-                });
-
-                Ok(PushMessageResponse {
-                    success_count: user_ids.len() as i32,
-                    fail_count: 0,
-                    failed_user_ids: Vec::new(),
-                    failures: Vec::new(),
-                    task_id,
-                    status: Some(rpc_status_success()),
-                })
+                            };
+                            Some(message_type_str)
+                        })
+                        .flatten(),
+                    persisted_at: std::time::SystemTime::now(),
+                    metadata: if let Some(options) = &request.options {
+                        options.metadata.clone()
+                    } else {
+                        std::collections::HashMap::new()
+                    },
+                };
+
+                // 执行 PostSend Hook
+                if let Err(e) = hook_dispatcher.post_send(&hook_ctx, &record, &draft).await {
+                    tracing::warn!(
+                        task_id = %task_id,
+                        error = %e,
+                        "Failed to execute PostSend hook"
+                    );
+                } else {
+                    tracing::debug!(
+                        task_id = %task_id,
+                        "Successfully executed PostSend hook"
+                    );
+                }
             }
-            Err(err) => {
-                warn!(
-                    error = %err,
-                    task_id = %task_id,
-                    "Failed to publish message to Kafka"
-                );
-
-                let failures: Vec<PushFailure> = user_ids
-                    .iter()
-                    .map(|user_id| PushFailure {
-                        user_id: user_id.clone(),
-                        code: error_code_internal(),
-                        error_message: err.to_string(),
-                        metadata: std::collections::HashMap::new(),
-                    })
-                    .collect();
-
-                Ok(PushMessageResponse {
-                    success_count: 0,
-                    fail_count: user_ids.len() as i32,
-                    failed_user_ids: user_ids.clone(),
-                    failures,
-                    task_id: String::new(),
-                    status: Some(rpc_status_internal("failed to enqueue push message")),
-                })
-            }
-        }
+        });
+
+        Ok(PushMessageResponse {
+            success_count: (user_ids.len() - failures.len()) as i32,
+            fail_count: failures.len() as i32,
+            failed_user_ids: failures.iter().map(|f| f.user_id.clone()).collect(),
+            failures,
+            task_id,
+            status: Some(rpc_status_success()),
+        })
     }
 
     /// 入队推送通知（业务逻辑）
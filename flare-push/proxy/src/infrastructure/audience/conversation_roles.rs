@@ -0,0 +1,35 @@
+//! 基于会话服务的角色目录
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use flare_push_common::session_client::ConversationServiceClient;
+use flare_server_core::context::Context;
+
+use crate::domain::repositories::ConversationRoleDirectory;
+
+pub struct ConversationRoleDirectoryImpl {
+    client: Arc<ConversationServiceClient>,
+}
+
+impl ConversationRoleDirectoryImpl {
+    pub fn new(client: Arc<ConversationServiceClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ConversationRoleDirectory for ConversationRoleDirectoryImpl {
+    async fn members_with_roles(
+        &self,
+        ctx: &Context,
+        conversation_id: &str,
+        roles: &[String],
+    ) -> Result<Vec<String>> {
+        self.client
+            .get_participants_by_roles(ctx, conversation_id, roles)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to query conversation roles: {}", e))
+    }
+}
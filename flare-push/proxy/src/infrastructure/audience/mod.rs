@@ -0,0 +1,9 @@
+//! 受众目录基础设施层
+
+pub mod conversation_roles;
+pub mod postgres_segments;
+pub mod redis_tags;
+
+pub use conversation_roles::ConversationRoleDirectoryImpl;
+pub use postgres_segments::PostgresSegmentDirectory;
+pub use redis_tags::RedisTagDirectory;
@@ -0,0 +1,63 @@
+//! 基于 PostgreSQL 的受众人群包目录
+//!
+//! 成员存储于 `push_audience_segments` 表，按 user_id 做键集分页
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::PgPool;
+use sqlx::postgres::PgPoolOptions;
+
+use crate::domain::repositories::AudienceDirectory;
+
+const DEFAULT_MAX_CONNECTIONS: u32 = 5;
+
+pub struct PostgresSegmentDirectory {
+    pool: PgPool,
+}
+
+impl PostgresSegmentDirectory {
+    pub async fn new(database_url: &str) -> Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(DEFAULT_MAX_CONNECTIONS)
+            .connect(database_url)
+            .await
+            .context("failed to connect to postgres")?;
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl AudienceDirectory for PostgresSegmentDirectory {
+    async fn members(
+        &self,
+        tenant_id: &str,
+        name: &str,
+        cursor: Option<String>,
+        page_size: usize,
+    ) -> Result<(Vec<String>, Option<String>)> {
+        let members: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT user_id
+            FROM push_audience_segments
+            WHERE tenant_id = $1 AND segment = $2 AND user_id > $3
+            ORDER BY user_id
+            LIMIT $4
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(name)
+        .bind(cursor.unwrap_or_default())
+        .bind(page_size as i64)
+        .fetch_all(&self.pool)
+        .await
+        .with_context(|| format!("Failed to load audience segment {}", name))?;
+
+        // 不足一页说明已读完，否则以最后一个 user_id 作为下一页游标
+        let next = if members.len() < page_size {
+            None
+        } else {
+            members.last().cloned()
+        };
+        Ok((members, next))
+    }
+}
@@ -0,0 +1,58 @@
+//! 基于 Redis Set 的受众标签目录
+//!
+//! 每个标签对应一个 Set：`{prefix}:{tenant_id}:tag:{tag}`，成员为用户ID，
+//! 由租户业务系统维护；读取时使用 SSCAN 分页，避免大标签阻塞 Redis
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+
+use crate::domain::repositories::AudienceDirectory;
+
+pub struct RedisTagDirectory {
+    conn: ConnectionManager,
+    key_prefix: String,
+}
+
+impl RedisTagDirectory {
+    pub async fn new(redis_url: &str, key_prefix: String) -> Result<Self> {
+        let client =
+            redis::Client::open(redis_url).context("Failed to create audience redis client")?;
+        let conn = ConnectionManager::new(client)
+            .await
+            .context("Failed to connect audience redis")?;
+        Ok(Self { conn, key_prefix })
+    }
+
+    fn tag_key(&self, tenant_id: &str, tag: &str) -> String {
+        format!("{}:{}:tag:{}", self.key_prefix, tenant_id, tag)
+    }
+}
+
+#[async_trait]
+impl AudienceDirectory for RedisTagDirectory {
+    async fn members(
+        &self,
+        tenant_id: &str,
+        name: &str,
+        cursor: Option<String>,
+        page_size: usize,
+    ) -> Result<(Vec<String>, Option<String>)> {
+        let mut conn = self.conn.clone();
+        let cursor: u64 = match cursor {
+            Some(cursor) => cursor.parse().context("invalid tag cursor")?,
+            None => 0,
+        };
+        let (next, members): (u64, Vec<String>) = redis::cmd("SSCAN")
+            .arg(self.tag_key(tenant_id, name))
+            .arg(cursor)
+            .arg("COUNT")
+            .arg(page_size)
+            .query_async(&mut conn)
+            .await
+            .with_context(|| format!("Failed to scan audience tag {}", name))?;
+
+        // SSCAN 游标回到 0 表示遍历结束
+        Ok((members, (next != 0).then(|| next.to_string())))
+    }
+}
@@ -8,6 +8,20 @@ pub struct PushProxyConfig {
     pub notification_topic: String,
     pub ack_topic: String, // ACK Topic（从 Gateway 接收客户端 ACK）
     pub kafka_timeout_ms: u64,
+    /// 离线推送渠道（与 Push Worker 的 push_provider 一致，用于投递模拟）
+    pub offline_provider: String,
+    /// 默认租户ID（查询在线状态时使用）
+    pub default_tenant_id: String,
+    /// 受众标签存储 Redis 地址（未配置时标签选择器不可用）
+    pub audience_redis_url: Option<String>,
+    /// 受众标签 Redis 键前缀
+    pub audience_key_prefix: String,
+    /// 受众人群包存储 PostgreSQL 地址（未配置时人群包选择器不可用）
+    pub audience_database_url: Option<String>,
+    /// 受众解析后的最大用户数
+    pub max_audience_size: usize,
+    /// 扇出分批大小（每个 Kafka 消息包含的最大用户数）
+    pub fanout_batch_size: usize,
 }
 
 impl PushProxyConfig {
@@ -40,6 +54,42 @@ impl PushProxyConfig {
                 .timeout_ms
                 .or_else(|| kafka_profile.and_then(|cfg| cfg.timeout_ms))
                 .unwrap_or(5_000),
+            offline_provider: std::env::var("PUSH_PROXY_OFFLINE_PROVIDER")
+                .or_else(|_| std::env::var("PUSH_WORKER_PUSH_PROVIDER"))
+                .unwrap_or_else(|_| "noop".to_string()),
+            default_tenant_id: std::env::var("PUSH_SERVER_DEFAULT_TENANT_ID")
+                .ok()
+                .or_else(|| app.push_server_service().default_tenant_id)
+                .unwrap_or_else(|| "default".to_string()),
+            audience_redis_url: std::env::var("PUSH_PROXY_AUDIENCE_REDIS_URL").ok().or_else(|| {
+                service
+                    .audience_redis
+                    .as_deref()
+                    .and_then(|name| app.redis_profile(name))
+                    .map(|cfg| cfg.url.clone())
+            }),
+            audience_key_prefix: std::env::var("PUSH_PROXY_AUDIENCE_KEY_PREFIX")
+                .unwrap_or_else(|_| "push:audience".to_string()),
+            audience_database_url: std::env::var("PUSH_PROXY_AUDIENCE_DATABASE_URL")
+                .ok()
+                .or_else(|| {
+                    service
+                        .audience_postgres
+                        .as_deref()
+                        .and_then(|name| app.postgres_profile(name))
+                        .map(|cfg| cfg.url.clone())
+                }),
+            max_audience_size: std::env::var("PUSH_PROXY_MAX_AUDIENCE_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .or(service.max_audience_size)
+                .unwrap_or(100_000),
+            fanout_batch_size: std::env::var("PUSH_PROXY_FANOUT_BATCH_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .or(service.fanout_batch_size)
+                .filter(|size| *size > 0)
+                .unwrap_or(500),
         }
     }
 }
@@ -1,3 +1,4 @@
+pub mod audience;
 pub mod config;
 pub mod messaging;
 pub mod validator;
@@ -20,8 +20,16 @@ impl Default for RequestValidatorImpl {
 
 impl crate::infrastructure::validator::RequestValidator for RequestValidatorImpl {
     fn validate_message_request(&self, request: &PushMessageRequest) -> Result<()> {
-        // 校验用户ID列表不为空
-        ensure!(!request.user_ids.is_empty(), "user_ids cannot be empty");
+        // 校验用户ID列表或受众选择器至少提供一个
+        let has_audience = request.audience.as_ref().is_some_and(|audience| {
+            !audience.tags.is_empty()
+                || !audience.segments.is_empty()
+                || !audience.conversation_roles.is_empty()
+        });
+        ensure!(
+            !request.user_ids.is_empty() || has_audience,
+            "user_ids or audience is required"
+        );
 
         // 校验用户ID数量限制（防止批量过大）
         ensure!(
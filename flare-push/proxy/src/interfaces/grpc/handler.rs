@@ -7,7 +7,8 @@ use flare_proto::push::{
     CreateTemplateResponse, DeleteTemplateRequest, DeleteTemplateResponse, ListTemplatesRequest,
     ListTemplatesResponse, PushMessageRequest, PushMessageResponse, PushNotificationRequest,
     PushNotificationResponse, QueryPushStatusRequest, QueryPushStatusResponse, SchedulePushRequest,
-    SchedulePushResponse, UpdateTemplateRequest, UpdateTemplateResponse,
+    SchedulePushResponse, SimulateDeliveryRequest, SimulateDeliveryResponse, UpdateTemplateRequest,
+    UpdateTemplateResponse,
 };
 use tonic::{Request, Response, Status};
 use tracing::{error, info};
@@ -15,17 +16,23 @@ use flare_im_core::utils::context::require_context;
 use flare_server_core::context::Context;
 
 use crate::application::commands::{EnqueueMessageCommand, EnqueueNotificationCommand};
-use crate::application::handlers::PushCommandHandler;
+use crate::application::handlers::{PushCommandHandler, PushQueryHandler};
+use crate::application::queries::SimulateDeliveryQuery;
 
 #[derive(Clone)]
 pub struct PushGrpcHandler {
     command_handler: Arc<PushCommandHandler>,
+    query_handler: Arc<PushQueryHandler>,
 }
 
 impl PushGrpcHandler {
-    pub fn new(command_handler: Arc<PushCommandHandler>) -> Self {
+    pub fn new(
+        command_handler: Arc<PushCommandHandler>,
+        query_handler: Arc<PushQueryHandler>,
+    ) -> Self {
         Self {
             command_handler,
+            query_handler,
         }
     }
 
@@ -80,6 +87,22 @@ impl PushGrpcHandler {
             }
         }
     }
+
+    pub async fn simulate_delivery(
+        &self,
+        request: Request<SimulateDeliveryRequest>,
+    ) -> Result<Response<SimulateDeliveryResponse>, Status> {
+        let ctx = require_context(&request)?;
+        let req = request.into_inner();
+        let query = SimulateDeliveryQuery { request: req };
+        match self.query_handler.handle_simulate_delivery(&ctx, query).await {
+            Ok(resp) => Ok(Response::new(resp)),
+            Err(err) => {
+                error!(?err, "failed to simulate push delivery");
+                Err(Status::failed_precondition(err.to_string()))
+            }
+        }
+    }
 }
 
 #[tonic::async_trait]
@@ -177,4 +200,15 @@ impl PushService for PushGrpcHandler {
         );
         self.push_ack(request).await
     }
+
+    async fn simulate_delivery(
+        &self,
+        request: Request<SimulateDeliveryRequest>,
+    ) -> Result<Response<SimulateDeliveryResponse>, Status> {
+        info!(
+            "Simulate delivery request: {} users",
+            request.get_ref().user_ids.len()
+        );
+        self.simulate_delivery(request).await
+    }
 }
@@ -6,14 +6,22 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 
-use crate::application::handlers::PushCommandHandler;
+use crate::application::handlers::{PushCommandHandler, PushQueryHandler};
 use crate::domain::repositories::PushEventPublisher;
-use crate::domain::service::PushDomainService;
+use crate::domain::service::{AudienceResolver, PushDomainService};
+use crate::infrastructure::audience::{
+    ConversationRoleDirectoryImpl, PostgresSegmentDirectory, RedisTagDirectory,
+};
 use crate::infrastructure::config::PushProxyConfig;
 use crate::infrastructure::messaging::kafka_publisher::KafkaPushEventPublisher;
 use crate::interfaces::grpc::handler::PushGrpcHandler;
 
 use flare_im_core::hooks::HookDispatcher;
+use flare_im_core::service_names::{CONVERSATION, SIGNALING_ONLINE, get_service_name};
+use flare_push_common::session_client::ConversationServiceClient;
+use flare_push_common::signaling::SignalingOnlineClient;
+use flare_push_common::{DeliverySimulator, OnlineStatusRepositoryImpl};
+use flare_server_core::discovery::ServiceClient;
 
 /// 应用上下文 - 包含所有已初始化的服务
 pub struct ApplicationContext {
@@ -48,21 +56,118 @@ pub async fn initialize(
     // 4. 初始化 Hook 调度器
     let hook_dispatcher = HookDispatcher::new(flare_im_core::hooks::GlobalHookRegistry::get());
 
-    // 5. 构建领域服务
+    // 5. 构建受众解析器
+    let audience_resolver = Arc::new(build_audience_resolver(&proxy_config).await?);
+
+    // 6. 构建领域服务
     let domain_service = Arc::new(PushDomainService::new(
         publisher,
         validator,
         hook_dispatcher.clone(),
+        audience_resolver.clone(),
+        proxy_config.fanout_batch_size,
     ));
 
-    // 6. 构建命令处理器
+    // 7. 构建命令处理器
     let command_handler = Arc::new(PushCommandHandler::new(domain_service));
 
-    // 7. 构建 gRPC 处理器
-    let handler = PushGrpcHandler::new(command_handler);
+    // 8. 构建查询处理器（投递模拟）
+    let simulator = build_delivery_simulator(&proxy_config).await?;
+    let query_handler = Arc::new(PushQueryHandler::new(simulator, audience_resolver));
+
+    // 9. 构建 gRPC 处理器
+    let handler = PushGrpcHandler::new(command_handler, query_handler);
 
     Ok(ApplicationContext {
         handler,
         hook_dispatcher,
     })
 }
+
+/// 构建受众解析器
+///
+/// 标签目录（Redis）、人群包目录（PostgreSQL）与会话角色目录（Conversation 服务）均为可选，
+/// 未配置时请求中对应的选择器会被拒绝
+async fn build_audience_resolver(proxy_config: &PushProxyConfig) -> Result<AudienceResolver> {
+    let mut resolver = AudienceResolver::new(proxy_config.max_audience_size);
+
+    if let Some(redis_url) = &proxy_config.audience_redis_url {
+        let directory =
+            RedisTagDirectory::new(redis_url, proxy_config.audience_key_prefix.clone()).await?;
+        resolver = resolver.with_tag_directory(Arc::new(directory));
+    }
+
+    if let Some(database_url) = &proxy_config.audience_database_url {
+        let directory = PostgresSegmentDirectory::new(database_url).await?;
+        resolver = resolver.with_segment_directory(Arc::new(directory));
+    }
+
+    let conversation_service = get_service_name(CONVERSATION);
+    match flare_im_core::discovery::create_discover(&conversation_service)
+        .await
+        .ok()
+        .flatten()
+    {
+        Some(discover) => {
+            let client =
+                ConversationServiceClient::with_service_client(ServiceClient::new(discover));
+            resolver = resolver
+                .with_role_directory(Arc::new(ConversationRoleDirectoryImpl::new(client)));
+        }
+        None => {
+            tracing::warn!(
+                "Conversation service discovery not configured, role audience is disabled"
+            );
+        }
+    }
+
+    Ok(resolver)
+}
+
+/// 构建投递模拟器
+///
+/// 使用与 Push Server 相同的在线状态查询和路由规则（flare-push-common）；未配置 Signaling 服务发现时返回 None（SimulateDelivery 不可用）
+async fn build_delivery_simulator(
+    proxy_config: &PushProxyConfig,
+) -> Result<Option<Arc<DeliverySimulator>>> {
+    let signaling_service = get_service_name(SIGNALING_ONLINE);
+    let Some(signaling_discover) = flare_im_core::discovery::create_discover(&signaling_service)
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to create signaling service discover for {}: {}",
+                signaling_service,
+                e
+            )
+        })?
+    else {
+        tracing::warn!("Signaling service discovery not configured, SimulateDelivery is disabled");
+        return Ok(None);
+    };
+    let signaling_client = SignalingOnlineClient::with_service_client(ServiceClient::new(
+        signaling_discover,
+    ));
+
+    // 会话服务可选：用于未指定 user_ids 时查询会话在线用户
+    let conversation_service = get_service_name(CONVERSATION);
+    let conversation_discover = flare_im_core::discovery::create_discover(&conversation_service)
+        .await
+        .ok()
+        .flatten();
+    let online_repo = match conversation_discover {
+        Some(discover) => OnlineStatusRepositoryImpl::with_conversation_client(
+            signaling_client,
+            ConversationServiceClient::with_service_client(ServiceClient::new(discover)),
+            proxy_config.default_tenant_id.clone(),
+        ),
+        None => OnlineStatusRepositoryImpl::new(
+            signaling_client,
+            proxy_config.default_tenant_id.clone(),
+        ),
+    };
+
+    Ok(Some(Arc::new(DeliverySimulator::new(
+        Arc::new(online_repo),
+        proxy_config.offline_provider.clone(),
+    ))))
+}
@@ -1,8 +1,32 @@
 //! 推送服务配置模块
 
-use flare_im_core::config::{FlareAppConfig, RedisPoolConfig};
+use flare_im_core::config::{FlareAppConfig, RedisPoolConfig, TenantIsolationConfigSection};
 use flare_server_core::kafka::{KafkaConsumerConfig, KafkaProducerConfig};
 use std::env;
+use std::time::Duration;
+
+use crate::domain::model::{AutoscaleMode, AutoscalePolicy, TenantIsolationPolicy, TenantTier};
+
+/// 网关重投递缓冲配置
+#[derive(Debug, Clone)]
+pub struct GatewayRedeliveryConfig {
+    /// 任务最长缓冲时间，超时后转入离线推送
+    pub ttl: Duration,
+    /// 每个网关最多缓冲的任务数，超出部分直接转入离线推送
+    pub max_per_gateway: usize,
+    /// 网关健康探测间隔
+    pub probe_interval: Duration,
+}
+
+impl Default for GatewayRedeliveryConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(30),
+            max_per_gateway: 10_000,
+            probe_interval: Duration::from_secs(1),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct PushServerConfig {
@@ -37,6 +61,8 @@ pub struct PushServerConfig {
     pub push_retry_initial_delay_ms: u64,
     pub push_retry_max_delay_ms: u64,
     pub push_retry_backoff_multiplier: f64,
+    // 网关不可达时的重投递缓冲（None 表示关闭，直接走离线推送）
+    pub gateway_redelivery: Option<GatewayRedeliveryConfig>,
     // ACK超时配置
     pub ack_timeout_seconds: u64,
     pub ack_monitor_interval_seconds: u64,
@@ -51,6 +77,7 @@ pub struct PushServerConfig {
     pub ack_cache_capacity: usize,  // 内存缓存容量
     pub ack_batch_interval_ms: u64, // 批量处理间隔（毫秒）
     pub ack_batch_size: usize,      // 批量处理大小
+    pub ack_archive_database_url: Option<String>, // 终态ACK归档数据库（可选，Postgres 按天分区）
     // ACK 超时重试配置（区别于推送重试，避免 Kafka 阻塞）
     pub ack_retry_initial_delay_ms: u64, // ACK 超时重试初始延迟（毫秒，较短）
     pub ack_retry_max_delay_ms: u64,     // ACK 超时重试最大延迟（毫秒，较短）
@@ -59,6 +86,13 @@ pub struct PushServerConfig {
     pub dlq_topic: String,
     // ACK Topic（从 Access Gateway 接收客户端 ACK）
     pub ack_topic: String,
+    // 多端同步：将发送者的消息推送到其其他在线设备
+    pub self_sync_enabled: bool,
+    // 基于消费积压的自动扩缩容（None 表示关闭）
+    pub autoscale: Option<AutoscalePolicy>,
+    pub autoscale_webhook_url: Option<String>,
+    // 租户隔离工作池（None 表示所有租户共享 worker）
+    pub tenant_isolation: Option<TenantIsolationPolicy>,
 }
 
 impl PushServerConfig {
@@ -198,6 +232,11 @@ impl PushServerConfig {
             .and_then(|v| v.parse::<f64>().ok())
             .unwrap_or(2.0);
 
+        // 网关重投递缓冲（默认开启）
+        let gateway_redelivery = env_parse("PUSH_SERVER_GATEWAY_REDELIVERY_ENABLED")
+            .unwrap_or(true)
+            .then(gateway_redelivery_from_env);
+
         // ACK超时配置
         let ack_timeout_seconds = env::var("PUSH_SERVER_ACK_TIMEOUT_SECONDS")
             .ok()
@@ -224,6 +263,12 @@ impl PushServerConfig {
         let ack_topic =
             env::var("PUSH_SERVER_ACK_TOPIC").unwrap_or_else(|_| "flare.im.push.acks".to_string());
 
+        // 多端同步（默认开启）
+        let self_sync_enabled = env::var("PUSH_SERVER_SELF_SYNC")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(true);
+
         // ACK 监控性能优化配置
         let ack_scan_batch_size = env::var("PUSH_SERVER_ACK_SCAN_BATCH_SIZE")
             .ok()
@@ -270,6 +315,11 @@ impl PushServerConfig {
             .map(|ack| ack.batch_size)
             .unwrap_or(100);
 
+        // 终态ACK归档到 Postgres（可选，用于合规查询）
+        let ack_archive_database_url = env::var("PUSH_SERVER_ACK_ARCHIVE_DATABASE_URL")
+            .ok()
+            .filter(|url| !url.is_empty());
+
         // ACK 超时重试配置（区别于推送重试，避免 Kafka 阻塞）
         // ACK 超时重试应该更快，避免阻塞 Kafka 消费
         let ack_retry_initial_delay_ms = env::var("PUSH_SERVER_ACK_RETRY_INITIAL_DELAY_MS")
@@ -282,6 +332,20 @@ impl PushServerConfig {
             .and_then(|v| v.parse().ok())
             .unwrap_or(1000); // 1秒，比推送重试更短，避免阻塞 Kafka
 
+        // 自动扩缩容（默认关闭）
+        let autoscale = env::var("PUSH_SERVER_AUTOSCALE_ENABLED")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok())
+            .unwrap_or(false)
+            .then(autoscale_policy_from_env);
+
+        let autoscale_webhook_url = env::var("PUSH_SERVER_AUTOSCALE_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.is_empty());
+
+        // 租户隔离（默认关闭，环境变量优先于服务配置）
+        let tenant_isolation = tenant_isolation_policy(service.tenant_isolation.as_ref());
+
         Self {
             kafka_bootstrap,
             consumer_group,
@@ -308,6 +372,7 @@ impl PushServerConfig {
             push_retry_initial_delay_ms,
             push_retry_max_delay_ms,
             push_retry_backoff_multiplier,
+            gateway_redelivery,
             ack_timeout_seconds,
             ack_monitor_interval_seconds,
             ack_timeout_max_retries,
@@ -319,13 +384,137 @@ impl PushServerConfig {
             ack_cache_capacity,
             ack_batch_interval_ms,
             ack_batch_size,
+            ack_archive_database_url,
             ack_retry_initial_delay_ms,
             ack_retry_max_delay_ms,
             offline_topic,
             dlq_topic,
             ack_topic,
+            self_sync_enabled,
+            autoscale,
+            autoscale_webhook_url,
+            tenant_isolation,
+        }
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+    env::var(key).ok().and_then(|v| v.parse::<T>().ok())
+}
+
+/// 从环境变量读取网关重投递配置，未设置的项使用默认值
+fn gateway_redelivery_from_env() -> GatewayRedeliveryConfig {
+    let default = GatewayRedeliveryConfig::default();
+    GatewayRedeliveryConfig {
+        ttl: env_parse("PUSH_SERVER_GATEWAY_REDELIVERY_TTL_MS")
+            .map(Duration::from_millis)
+            .unwrap_or(default.ttl),
+        max_per_gateway: env_parse("PUSH_SERVER_GATEWAY_REDELIVERY_MAX_PER_GATEWAY")
+            .unwrap_or(default.max_per_gateway)
+            .max(1),
+        probe_interval: env_parse("PUSH_SERVER_GATEWAY_PROBE_INTERVAL_MS")
+            .map(Duration::from_millis)
+            .unwrap_or(default.probe_interval),
+    }
+}
+
+/// 从环境变量读取自动扩缩容策略，未设置的项使用默认值
+fn autoscale_policy_from_env() -> AutoscalePolicy {
+    let default = AutoscalePolicy::default();
+    let min_capacity = env_parse("PUSH_SERVER_AUTOSCALE_MIN")
+        .unwrap_or(default.min_capacity)
+        .max(1);
+    let max_capacity = env_parse("PUSH_SERVER_AUTOSCALE_MAX")
+        .unwrap_or(default.max_capacity)
+        .max(min_capacity);
+
+    AutoscalePolicy {
+        mode: env::var("PUSH_SERVER_AUTOSCALE_MODE")
+            .ok()
+            .and_then(|v| AutoscaleMode::parse(&v))
+            .unwrap_or(default.mode),
+        interval: env_parse("PUSH_SERVER_AUTOSCALE_INTERVAL_MS")
+            .map(Duration::from_millis)
+            .unwrap_or(default.interval),
+        scale_up_lag: env_parse("PUSH_SERVER_AUTOSCALE_SCALE_UP_LAG")
+            .unwrap_or(default.scale_up_lag),
+        scale_down_lag: env_parse("PUSH_SERVER_AUTOSCALE_SCALE_DOWN_LAG")
+            .unwrap_or(default.scale_down_lag),
+        scale_up_age_ms: env_parse("PUSH_SERVER_AUTOSCALE_SCALE_UP_AGE_MS")
+            .unwrap_or(default.scale_up_age_ms),
+        scale_down_age_ms: env_parse("PUSH_SERVER_AUTOSCALE_SCALE_DOWN_AGE_MS")
+            .unwrap_or(default.scale_down_age_ms),
+        min_capacity,
+        max_capacity,
+        step: env_parse("PUSH_SERVER_AUTOSCALE_STEP")
+            .unwrap_or(default.step)
+            .max(1),
+        stable_samples: env_parse("PUSH_SERVER_AUTOSCALE_STABLE_SAMPLES")
+            .unwrap_or(default.stable_samples)
+            .max(1),
+        cooldown: env_parse("PUSH_SERVER_AUTOSCALE_COOLDOWN_MS")
+            .map(Duration::from_millis)
+            .unwrap_or(default.cooldown),
+    }
+}
+
+/// 读取租户隔离策略：服务配置中的等级定义，叠加环境变量覆盖
+///
+/// `PUSH_SERVER_TENANT_TIERS` 格式为 `tenant=tier,tenant=tier`
+fn tenant_isolation_policy(
+    section: Option<&TenantIsolationConfigSection>,
+) -> Option<TenantIsolationPolicy> {
+    let enabled = env_parse("PUSH_SERVER_TENANT_ISOLATION_ENABLED")
+        .unwrap_or_else(|| section.is_some_and(|section| section.enabled));
+    if !enabled {
+        return None;
+    }
+
+    let mut policy = TenantIsolationPolicy::default();
+    if let Some(section) = section {
+        if let Some(total_workers) = section.total_workers {
+            policy.total_workers = total_workers;
+        }
+        if let Some(default_tier) = &section.default_tier {
+            policy.default_tier = default_tier.clone();
+        }
+        if let Some(max_spilled_tasks) = section.max_spilled_tasks {
+            policy.max_spilled_tasks = max_spilled_tasks;
+        }
+        policy.tiers = section
+            .tiers
+            .iter()
+            .map(|(name, tier)| {
+                (
+                    name.clone(),
+                    TenantTier {
+                        workers: tier.workers,
+                        queue_capacity: tier.queue_capacity,
+                        weight: tier.weight,
+                    },
+                )
+            })
+            .collect();
+        policy.tenant_tiers = section.tenants.clone();
+    }
+
+    if let Some(total_workers) = env_parse("PUSH_SERVER_TENANT_ISOLATION_WORKERS") {
+        policy.total_workers = total_workers;
+    }
+    if let Some(max_spilled_tasks) = env_parse("PUSH_SERVER_TENANT_SPILL_CAPACITY") {
+        policy.max_spilled_tasks = max_spilled_tasks;
+    }
+    if let Ok(mapping) = env::var("PUSH_SERVER_TENANT_TIERS") {
+        for entry in mapping.split(',') {
+            if let Some((tenant_id, tier)) = entry.split_once('=') {
+                policy
+                    .tenant_tiers
+                    .insert(tenant_id.trim().to_string(), tier.trim().to_string());
+            }
         }
     }
+    policy.total_workers = policy.total_workers.max(1);
+    Some(policy)
 }
 
 // 实现 KafkaConsumerConfig trait，使 PushServerConfig 可以使用通用的 Kafka 消费者构建器
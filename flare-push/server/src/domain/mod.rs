@@ -2,6 +2,13 @@ pub mod model;
 pub mod repository;
 pub mod service;
 
-pub use model::{DispatchNotification, PushDispatchTask, RequestMetadata};
-pub use repository::{OnlineStatus, OnlineStatusRepository, PushTaskPublisher};
-pub use service::PushDomainService;
+pub use model::{
+    AutoscaleMode, AutoscalePolicy, ConsumerLag, DeliveryRoute, DeliverySimulation,
+    DispatchNotification, FilterReason, FilteredRecipient, PushDispatchTask, RequestMetadata,
+    ScaleDirection, ScaleEvent,
+};
+pub use repository::{
+    ConcurrencyController, ConsumerLagMonitor, OnlineStatus, OnlineStatusRepository,
+    PushTaskPublisher, ScaleEventSink,
+};
+pub use service::{AutoscaleController, PushDomainService};
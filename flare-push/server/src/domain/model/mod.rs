@@ -1,3 +1,6 @@
+pub use flare_push_common::models::{
+    DeliveryRoute, DeliverySimulation, FilterReason, FilteredRecipient,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -38,3 +41,168 @@ pub struct RequestMetadata {
     pub client_ip: Option<String>,
     pub user_agent: Option<String>,
 }
+
+/// 消费积压快照
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConsumerLag {
+    /// 各分区积压消息数之和（高水位 - 已提交 offset）
+    pub total_lag: i64,
+    /// 最近消费消息的积压时长（毫秒，消息写入到被消费的间隔；无积压时为 0）
+    pub message_age_ms: u64,
+}
+
+/// 扩缩容方向
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScaleDirection {
+    Up,
+    Down,
+}
+
+impl ScaleDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScaleDirection::Up => "up",
+            ScaleDirection::Down => "down",
+        }
+    }
+}
+
+/// 扩缩容事件
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ScaleEvent {
+    pub direction: ScaleDirection,
+    /// 调整前的容量（进程内并发度，或外部模式下的实例数）
+    pub current: usize,
+    /// 期望容量
+    pub desired: usize,
+    pub lag: i64,
+    pub message_age_ms: u64,
+}
+
+/// 自动扩缩容方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoscaleMode {
+    /// 调整进程内消费并发度
+    Concurrency,
+    /// 只发出扩缩容事件与外部指标（由 Webhook / K8s HPA 调整实例数）
+    External,
+}
+
+impl AutoscaleMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "concurrency" => Some(Self::Concurrency),
+            "external" => Some(Self::External),
+            _ => None,
+        }
+    }
+}
+
+/// 基于消费积压的自动扩缩容策略
+///
+/// 积压数或积压时长达到扩容阈值时扩容，两者同时低于缩容阈值时缩容，
+/// 介于两组阈值之间不调整（滞回区间），并要求连续多次采样满足条件、两次调整间隔不小于冷却时间。
+#[derive(Clone, Debug)]
+pub struct AutoscalePolicy {
+    pub mode: AutoscaleMode,
+    /// 采样间隔
+    pub interval: std::time::Duration,
+    pub scale_up_lag: i64,
+    pub scale_down_lag: i64,
+    pub scale_up_age_ms: u64,
+    pub scale_down_age_ms: u64,
+    pub min_capacity: usize,
+    pub max_capacity: usize,
+    /// 每次调整的步长
+    pub step: usize,
+    /// 触发调整所需的连续采样次数
+    pub stable_samples: u32,
+    /// 两次调整的最小间隔
+    pub cooldown: std::time::Duration,
+}
+
+impl Default for AutoscalePolicy {
+    fn default() -> Self {
+        Self {
+            mode: AutoscaleMode::Concurrency,
+            interval: std::time::Duration::from_secs(5),
+            scale_up_lag: 10_000,
+            scale_down_lag: 1_000,
+            scale_up_age_ms: 30_000,
+            scale_down_age_ms: 5_000,
+            min_capacity: 1,
+            max_capacity: 32,
+            step: 2,
+            stable_samples: 3,
+            cooldown: std::time::Duration::from_secs(30),
+        }
+    }
+}
+
+/// 租户等级（每个租户一个独立的工作池，规格由等级决定）
+#[derive(Clone, Debug)]
+pub struct TenantTier {
+    /// 单个租户最多同时占用的 worker 数
+    pub workers: usize,
+    /// 单个租户的待处理队列上限（达到后转入暂存区）
+    pub queue_capacity: usize,
+    /// 加权轮询权重（每轮可连续派发的任务数）
+    pub weight: u32,
+}
+
+impl Default for TenantTier {
+    fn default() -> Self {
+        Self {
+            workers: 4,
+            queue_capacity: 1_000,
+            weight: 1,
+        }
+    }
+}
+
+/// 租户隔离策略
+///
+/// 所有租户共享 `total_workers` 个 worker，按等级权重轮询派发；
+/// 单个租户受其等级的 worker 数与队列上限约束，突发流量不会占满全部 worker。
+#[derive(Clone, Debug)]
+pub struct TenantIsolationPolicy {
+    pub total_workers: usize,
+    pub tiers: HashMap<String, TenantTier>,
+    /// 租户 -> 等级
+    pub tenant_tiers: HashMap<String, String>,
+    /// 未配置等级的租户使用的等级
+    pub default_tier: String,
+    /// 租户队列已满时暂存任务的总数上限（所有租户共享，达到后消费端等待）
+    pub max_spilled_tasks: usize,
+}
+
+impl Default for TenantIsolationPolicy {
+    fn default() -> Self {
+        Self {
+            total_workers: 32,
+            tiers: HashMap::new(),
+            tenant_tiers: HashMap::new(),
+            default_tier: "standard".to_string(),
+            max_spilled_tasks: 10_000,
+        }
+    }
+}
+
+impl TenantIsolationPolicy {
+    /// 租户所属等级
+    pub fn tier_name(&self, tenant_id: &str) -> &str {
+        self.tenant_tiers
+            .get(tenant_id)
+            .filter(|tier| self.tiers.contains_key(tier.as_str()))
+            .unwrap_or(&self.default_tier)
+    }
+
+    /// 租户所属等级的规格（等级未定义时使用默认规格）
+    pub fn tier(&self, tenant_id: &str) -> TenantTier {
+        self.tiers
+            .get(self.tier_name(tenant_id))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
@@ -1,42 +1,10 @@
 use async_trait::async_trait;
 use flare_server_core::error::Result;
-use std::collections::HashMap;
 
-use crate::domain::model::PushDispatchTask;
+use crate::domain::model::{ConsumerLag, PushDispatchTask, ScaleEvent};
 
-/// 用户在线状态信息
-#[derive(Debug, Clone)]
-pub struct OnlineStatus {
-    pub user_id: String,
-    pub online: bool,
-    pub gateway_id: Option<String>,
-    pub server_id: Option<String>,
-}
-
-#[async_trait]
-pub trait OnlineStatusRepository: Send + Sync {
-    async fn is_online(&self, ctx: &flare_server_core::context::Context) -> Result<bool>;
-
-    /// 批量查询用户在线状态（返回用户ID到在线状态的映射）
-    async fn batch_get_online_status(
-        &self,
-        user_ids: &[String],
-    ) -> Result<HashMap<String, OnlineStatus>>;
-
-    /// 查询某个会话（聊天室/群）的所有在线用户ID列表
-    ///
-    /// # 参数
-    /// * `conversation_id` - 会话ID（聊天室ID或群ID）
-    ///
-    /// # 返回
-    /// * `Ok(Vec<String>)` - 在线用户ID列表
-    /// * `Err(Error)` - 查询失败
-    ///
-    /// # 注意
-    /// 此方法用于聊天室消息推送场景，当业务系统未提供 receiver_ids 时，
-    /// 自动查询该聊天室的所有在线用户进行推送。
-    async fn get_all_online_users_for_session(&self, conversation_id: &str) -> Result<Vec<String>>;
-}
+pub use flare_push_common::models::OnlineStatus;
+pub use flare_push_common::online::OnlineStatusRepository;
 
 #[async_trait]
 pub trait PushTaskPublisher: Send + Sync {
@@ -53,3 +21,22 @@ pub trait PushTaskPublisher: Send + Sync {
         retry_count: u32,
     ) -> Result<()>;
 }
+
+/// 消费积压监控
+#[async_trait]
+pub trait ConsumerLagMonitor: Send + Sync {
+    async fn current_lag(&self) -> Result<ConsumerLag>;
+}
+
+/// 进程内消费并发度控制
+pub trait ConcurrencyController: Send + Sync {
+    fn current(&self) -> usize;
+
+    fn set(&self, concurrency: usize);
+}
+
+/// 扩缩容事件发布（Webhook 等外部扩缩容系统）
+#[async_trait]
+pub trait ScaleEventSink: Send + Sync {
+    async fn emit(&self, event: &ScaleEvent) -> Result<()>;
+}
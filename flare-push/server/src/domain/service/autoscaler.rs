@@ -0,0 +1,302 @@
+//! 基于消费积压的自动扩缩容
+//!
+//! 定期采样推送任务 Topic 的消费积压（积压数 + 积压时长），按策略决定扩容或缩容：
+//! - `Concurrency` 模式：直接调整进程内消费并发度
+//! - `External` 模式：只更新外部指标并发出扩缩容事件，由 Webhook / K8s HPA 调整实例数
+//!
+//! 决策带滞回：扩容阈值与缩容阈值之间不调整，且需连续多次采样满足条件、两次调整之间有冷却时间，
+//! 避免积压在阈值附近抖动时反复扩缩。
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use flare_im_core::metrics::PushServerMetrics;
+use tracing::{info, warn};
+
+use crate::domain::model::{AutoscaleMode, AutoscalePolicy, ConsumerLag, ScaleDirection, ScaleEvent};
+use crate::domain::repository::{ConcurrencyController, ConsumerLagMonitor, ScaleEventSink};
+
+/// 扩缩容决策器（纯状态机，不做 IO）
+pub struct AutoscaleDecider {
+    policy: AutoscalePolicy,
+    /// 连续满足扩容条件的采样次数
+    above: u32,
+    /// 连续满足缩容条件的采样次数
+    below: u32,
+    last_scaled: Option<Instant>,
+}
+
+impl AutoscaleDecider {
+    pub fn new(policy: AutoscalePolicy) -> Self {
+        Self {
+            policy,
+            above: 0,
+            below: 0,
+            last_scaled: None,
+        }
+    }
+
+    pub fn policy(&self) -> &AutoscalePolicy {
+        &self.policy
+    }
+
+    /// 根据一次采样结果决定是否调整容量
+    pub fn evaluate(
+        &mut self,
+        lag: &ConsumerLag,
+        current: usize,
+        now: Instant,
+    ) -> Option<ScaleEvent> {
+        let policy = &self.policy;
+        let high = lag.total_lag >= policy.scale_up_lag
+            || lag.message_age_ms >= policy.scale_up_age_ms;
+        let low = lag.total_lag <= policy.scale_down_lag
+            && lag.message_age_ms <= policy.scale_down_age_ms;
+
+        if high {
+            self.above += 1;
+            self.below = 0;
+        } else if low {
+            self.below += 1;
+            self.above = 0;
+        } else {
+            // 滞回区间：保持现状
+            self.above = 0;
+            self.below = 0;
+            return None;
+        }
+
+        if let Some(last) = self.last_scaled {
+            if now.saturating_duration_since(last) < policy.cooldown {
+                return None;
+            }
+        }
+
+        let (direction, desired) = if self.above >= policy.stable_samples {
+            (
+                ScaleDirection::Up,
+                current
+                    .saturating_add(policy.step)
+                    .clamp(policy.min_capacity, policy.max_capacity),
+            )
+        } else if self.below >= policy.stable_samples {
+            (
+                ScaleDirection::Down,
+                current
+                    .saturating_sub(policy.step)
+                    .clamp(policy.min_capacity, policy.max_capacity),
+            )
+        } else {
+            return None;
+        };
+
+        if desired == current {
+            return None;
+        }
+
+        self.above = 0;
+        self.below = 0;
+        self.last_scaled = Some(now);
+
+        Some(ScaleEvent {
+            direction,
+            current,
+            desired,
+            lag: lag.total_lag,
+            message_age_ms: lag.message_age_ms,
+        })
+    }
+}
+
+/// 自动扩缩容控制器
+pub struct AutoscaleController {
+    decider: AutoscaleDecider,
+    monitor: Arc<dyn ConsumerLagMonitor>,
+    /// 进程内并发度控制（`Concurrency` 模式必需）
+    concurrency: Option<Arc<dyn ConcurrencyController>>,
+    sinks: Vec<Arc<dyn ScaleEventSink>>,
+    metrics: Arc<PushServerMetrics>,
+    /// `External` 模式下记录的期望实例数
+    external_capacity: usize,
+}
+
+impl AutoscaleController {
+    pub fn new(
+        policy: AutoscalePolicy,
+        monitor: Arc<dyn ConsumerLagMonitor>,
+        metrics: Arc<PushServerMetrics>,
+    ) -> Self {
+        let external_capacity = policy.min_capacity;
+        Self {
+            decider: AutoscaleDecider::new(policy),
+            monitor,
+            concurrency: None,
+            sinks: Vec::new(),
+            metrics,
+            external_capacity,
+        }
+    }
+
+    pub fn with_concurrency(mut self, concurrency: Arc<dyn ConcurrencyController>) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    pub fn with_sink(mut self, sink: Arc<dyn ScaleEventSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    fn current_capacity(&self) -> usize {
+        match (self.decider.policy().mode, &self.concurrency) {
+            (AutoscaleMode::Concurrency, Some(concurrency)) => concurrency.current(),
+            _ => self.external_capacity,
+        }
+    }
+
+    /// 采样并调整一次
+    pub async fn tick(&mut self) {
+        let lag = match self.monitor.current_lag().await {
+            Ok(lag) => lag,
+            Err(err) => {
+                warn!(error = %err, "Failed to sample push consumer lag");
+                return;
+            }
+        };
+
+        self.metrics.consumer_lag.set(lag.total_lag);
+        self.metrics
+            .consumer_message_age_ms
+            .set(lag.message_age_ms as i64);
+
+        let current = self.current_capacity();
+        self.metrics.consumer_capacity.set(current as i64);
+
+        let Some(event) = self.decider.evaluate(&lag, current, Instant::now()) else {
+            return;
+        };
+
+        info!(
+            direction = event.direction.as_str(),
+            current = event.current,
+            desired = event.desired,
+            lag = event.lag,
+            message_age_ms = event.message_age_ms,
+            "Scaling push consumer capacity"
+        );
+
+        match (self.decider.policy().mode, &self.concurrency) {
+            (AutoscaleMode::Concurrency, Some(concurrency)) => concurrency.set(event.desired),
+            _ => self.external_capacity = event.desired,
+        }
+        self.metrics.consumer_capacity.set(event.desired as i64);
+
+        for sink in &self.sinks {
+            if let Err(err) = sink.emit(&event).await {
+                warn!(error = %err, "Failed to emit push consumer scale event");
+            }
+        }
+    }
+
+    /// 按采样间隔持续运行
+    pub async fn run(mut self) {
+        let sample_interval = self.decider.policy().interval.max(Duration::from_millis(1));
+        let mut interval = tokio::time::interval(sample_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.tick().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn policy() -> AutoscalePolicy {
+        AutoscalePolicy {
+            scale_up_lag: 100,
+            scale_down_lag: 10,
+            scale_up_age_ms: 10_000,
+            scale_down_age_ms: 1_000,
+            min_capacity: 1,
+            max_capacity: 8,
+            step: 2,
+            stable_samples: 2,
+            cooldown: Duration::from_secs(10),
+            ..AutoscalePolicy::default()
+        }
+    }
+
+    fn lag(total_lag: i64, message_age_ms: u64) -> ConsumerLag {
+        ConsumerLag {
+            total_lag,
+            message_age_ms,
+        }
+    }
+
+    #[test]
+    fn test_scale_up_after_stable_samples() {
+        let mut decider = AutoscaleDecider::new(policy());
+        let now = Instant::now();
+
+        assert!(decider.evaluate(&lag(500, 0), 4, now).is_none());
+        let event = decider.evaluate(&lag(500, 0), 4, now).unwrap();
+        assert_eq!(event.direction, ScaleDirection::Up);
+        assert_eq!(event.desired, 6);
+    }
+
+    #[test]
+    fn test_message_age_alone_triggers_scale_up() {
+        let mut decider = AutoscaleDecider::new(policy());
+        let now = Instant::now();
+
+        decider.evaluate(&lag(0, 20_000), 1, now);
+        let event = decider.evaluate(&lag(0, 20_000), 1, now).unwrap();
+        assert_eq!(event.direction, ScaleDirection::Up);
+        assert_eq!(event.desired, 3);
+    }
+
+    #[test]
+    fn test_hysteresis_band_resets_counters() {
+        let mut decider = AutoscaleDecider::new(policy());
+        let now = Instant::now();
+
+        assert!(decider.evaluate(&lag(500, 0), 4, now).is_none());
+        // 介于两组阈值之间
+        assert!(decider.evaluate(&lag(50, 0), 4, now).is_none());
+        assert!(decider.evaluate(&lag(500, 0), 4, now).is_none());
+    }
+
+    #[test]
+    fn test_cooldown_and_bounds() {
+        let mut decider = AutoscaleDecider::new(policy());
+        let now = Instant::now();
+
+        decider.evaluate(&lag(500, 0), 7, now);
+        let event = decider.evaluate(&lag(500, 0), 7, now).unwrap();
+        assert_eq!(event.desired, 8);
+
+        // 冷却时间内不调整
+        decider.evaluate(&lag(0, 0), 8, now + Duration::from_secs(1));
+        assert!(
+            decider
+                .evaluate(&lag(0, 0), 8, now + Duration::from_secs(2))
+                .is_none()
+        );
+
+        let event = decider
+            .evaluate(&lag(0, 0), 8, now + Duration::from_secs(11))
+            .unwrap();
+        assert_eq!(event.direction, ScaleDirection::Down);
+        assert_eq!(event.desired, 6);
+
+        // 已在下限时不再缩容
+        let later = now + Duration::from_secs(30);
+        decider.evaluate(&lag(0, 0), 1, later);
+        assert!(decider.evaluate(&lag(0, 0), 1, later).is_none());
+    }
+}
@@ -1,5 +1,9 @@
 //! 领域服务（Domain Service）
 
+pub mod autoscaler;
 pub mod push_domain_service;
+pub mod tenant_isolation;
 
+pub use autoscaler::{AutoscaleController, AutoscaleDecider};
 pub use push_domain_service::PushDomainService;
+pub use tenant_isolation::TenantWorkerScheduler;
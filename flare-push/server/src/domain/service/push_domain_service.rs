@@ -2,29 +2,47 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use flare_im_core::gateway::GatewayRouterTrait;
 use flare_im_core::hooks::HookDispatcher;
 use flare_im_core::metrics::PushServerMetrics;
 use flare_proto::common::Message;
 use flare_proto::push::{PushMessageRequest, PushNotificationRequest};
+use flare_push_common::routing::{
+    classify_message_request, resolve_route, self_sync_message_id, self_sync_recipient,
+    validate_message_request,
+};
 use flare_server_core::error::Result;
 use futures::future;
 use prost::Message as ProstMessage;
 use tokio::sync::RwLock;
-use tracing::{error, info, instrument, warn};
+use tracing::{debug, error, info, instrument, warn};
 
 use crate::config::PushServerConfig;
-use crate::domain::model::PushDispatchTask;
-use crate::domain::repository::{OnlineStatusRepository, PushTaskPublisher};
+use crate::domain::model::{DeliveryRoute, PushDispatchTask};
+use crate::domain::repository::{OnlineStatus, OnlineStatusRepository, PushTaskPublisher};
 use crate::infrastructure::ack_tracker::AckTracker;
+use crate::infrastructure::gateway_redelivery::{GatewayRedeliveryBuffer, is_gateway_unreachable};
 use crate::infrastructure::message_state::{MessageStateTracker, MessageStatus};
 use crate::infrastructure::retry::RetryPolicy;
 
 /// 消息去重缓存（基于 message_id + user_id）
 type MessageDedupCache = Arc<RwLock<HashMap<String, Instant>>>;
 
+/// 网关不可达、等待重新解析路由的推送批次
+struct UnreachableGatewayBatch {
+    gateway_id: String,
+    error: String,
+    tasks: Vec<PushDispatchTask>,
+}
+
+/// 按当前网关分组的在线任务与已离线的任务
+type ReroutedTasks = (
+    HashMap<String, Vec<(String, PushDispatchTask)>>,
+    Vec<PushDispatchTask>,
+);
+
 /// 推送领域服务 - 包含所有业务逻辑
 pub struct PushDomainService {
     config: Arc<PushServerConfig>,
@@ -38,6 +56,8 @@ pub struct PushDomainService {
     metrics: Arc<PushServerMetrics>,
     /// 消息去重缓存（防止重复推送）
     dedup_cache: MessageDedupCache,
+    /// 网关不可达时的重投递缓冲（None 表示直接走离线推送）
+    redelivery: Option<Arc<GatewayRedeliveryBuffer>>,
 }
 
 impl PushDomainService {
@@ -69,77 +89,93 @@ impl PushDomainService {
             retry_policy,
             metrics,
             dedup_cache: Arc::new(RwLock::new(HashMap::new())),
+            redelivery: None,
         }
     }
 
-    /// 分发推送消息（业务逻辑）- 从 Kafka 消费
-    #[instrument(skip(self), fields(
-        user_count = request.user_ids.len(),
-        message_id = %request.message.as_ref().map(|m| m.server_id.as_str()).unwrap_or(""),
-        sender_id = %request.message.as_ref().map(|m| m.sender_id.as_str()).unwrap_or(""),
-        receiver_id = %request.message.as_ref().map(|m| m.receiver_id.as_str()).unwrap_or(""),
-    ))]
-    pub async fn dispatch_push_message(&self, request: PushMessageRequest) -> Result<()> {
-        // 验证消息完整性：receiver_id 和 channel_id 不能同时为空
-        if let Some(ref message) = request.message {
-            // 单聊消息：必须提供 receiver_id
-            if message.conversation_type == 1 {
-                if message.receiver_id.is_empty() {
-                    error!(
-                        message_id = %message.server_id,
-                        conversation_id = %message.conversation_id,
-                        sender_id = %message.sender_id,
-                        user_ids = ?request.user_ids,
-                        "Single chat message missing receiver_id in push service"
+    /// 启用网关重投递缓冲
+    pub fn with_gateway_redelivery(mut self, buffer: Arc<GatewayRedeliveryBuffer>) -> Self {
+        self.redelivery = Some(buffer);
+        self
+    }
+
+    /// 网关重投递循环：定期探测有缓冲任务的网关，恢复后重新投递
+    ///
+    /// 重投递会重新查询在线状态，用户已切换网关或已离线时按新路由处理；
+    /// 网关持续不可达时，超过保留时长的任务转入离线推送。
+    pub async fn run_gateway_redelivery(self: Arc<Self>, probe_interval: Duration) {
+        let Some(buffer) = self.redelivery.clone() else {
+            return;
+        };
+        // 探测间隔为 0 时 interval 会 panic
+        let mut ticker = tokio::time::interval(probe_interval.max(Duration::from_millis(1)));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+            for gateway_id in buffer.gateway_ids().await {
+                let reachable = match self.gateway_router.probe_gateway(&gateway_id).await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        debug!(gateway_id = %gateway_id, error = %e, "Gateway still unreachable");
+                        false
+                    }
+                };
+                let (live, expired) = buffer.flush(&gateway_id, reachable).await;
+
+                if !live.is_empty() {
+                    info!(
+                        gateway_id = %gateway_id,
+                        task_count = live.len(),
+                        "Gateway recovered, redelivering buffered push tasks"
                     );
-                    return Err(flare_server_core::error::ErrorBuilder::new(
-                        flare_server_core::error::ErrorCode::InvalidParameter,
-                        format!("Single chat message must provide receiver_id. message_id={}, conversation_id={}, sender_id={}", 
-                            message.server_id, message.conversation_id, message.sender_id)
-                    ).build_error());
+                    self.metrics
+                        .gateway_redelivery_total
+                        .with_label_values(&["redelivered"])
+                        .inc_by(live.len() as u64);
+                    if let Err(e) = self.process_tasks(live).await {
+                        warn!(
+                            gateway_id = %gateway_id,
+                            error = %e,
+                            "Failed to redeliver buffered push tasks"
+                        );
+                    }
                 }
 
-                // 如果 user_ids 为空，说明消息编排服务没有正确设置，这是错误
-                if request.user_ids.is_empty() {
-                    error!(
-                        message_id = %message.server_id,
-                        receiver_id = %message.receiver_id,
-                        "user_ids is empty in PushMessageRequest, message orchestrator should set it"
+                if !expired.is_empty() {
+                    warn!(
+                        gateway_id = %gateway_id,
+                        task_count = expired.len(),
+                        "Buffered push tasks expired before gateway recovered, falling back to offline push"
                     );
-                    return Err(flare_server_core::error::ErrorBuilder::new(
-                        flare_server_core::error::ErrorCode::InvalidParameter,
-                        format!("user_ids cannot be empty for single chat message. message_id={}, receiver_id={}", 
-                            message.server_id, message.receiver_id)
-                    ).build_error());
-                }
-            }
-            // 群聊/频道消息：必须提供 channel_id
-            else if message.conversation_type == 2 || message.conversation_type == 3 {
-                if message.channel_id.is_empty() {
-                    return Err(flare_server_core::error::ErrorBuilder::new(
-                        flare_server_core::error::ErrorCode::InvalidParameter,
-                        "Group/channel message must provide channel_id",
-                    )
-                    .build_error());
+                    self.metrics
+                        .gateway_redelivery_total
+                        .with_label_values(&["expired"])
+                        .inc_by(expired.len() as u64);
+                    if let Err(e) = self.handle_offline_tasks(expired).await {
+                        warn!(
+                            gateway_id = %gateway_id,
+                            error = %e,
+                            "Failed to hand expired push tasks to offline push"
+                        );
+                    }
                 }
             }
-
-            // 注意：已移除消息去重逻辑
-            // ACK 机制已经保证消息可靠性：
-            // 1. 客户端收到消息后发送 ACK
-            // 2. Gateway 通过 Push Proxy → Kafka → Push Server 上报 ACK
-            // 3. Push Server 确认 ACK 后停止重试
-            // 4. 如果 ACK 超时，Push Server 会重试推送（最多重试 N 次）
-            // 因此不需要额外的去重逻辑，ACK 机制已经保证了消息的可靠性和幂等性
+            self.metrics
+                .gateway_redelivery_buffered
+                .set(buffer.len().await as i64);
         }
+    }
 
-        // 验证 user_ids 不为空
-        if request.user_ids.is_empty() {
-            return Err(flare_server_core::error::ErrorBuilder::new(
-                flare_server_core::error::ErrorCode::InvalidParameter,
-                "user_ids cannot be empty after deduplication. All recipients were filtered out as duplicates"
-            ).build_error());
-        }
+    /// 分发推送消息（业务逻辑）- 从 Kafka 消费
+    #[instrument(skip(self), fields(
+        user_count = request.user_ids.len(),
+        message_id = %request.message.as_ref().map(|m| m.server_id.as_str()).unwrap_or(""),
+        sender_id = %request.message.as_ref().map(|m| m.sender_id.as_str()).unwrap_or(""),
+        receiver_id = %request.message.as_ref().map(|m| m.receiver_id.as_str()).unwrap_or(""),
+    ))]
+    pub async fn dispatch_push_message(&self, request: PushMessageRequest) -> Result<()> {
+        validate_message_request(&request)?;
 
         // 将 PushMessageRequest 转换为 PushDispatchTask 并批量处理
         let tasks = self.convert_message_request_to_tasks(&request)?;
@@ -276,32 +312,14 @@ impl PushDomainService {
         let mut offline_tasks: Vec<PushDispatchTask> = Vec::new();
 
         for task in tasks {
-            let user_id = &task.user_id;
-            let status = online_status_map.get(user_id);
-
-            if let Some(status) = status {
-                if status.online {
-                    // 在线用户：按 gateway_id 分组
-                    if let Some(gateway_id) = &status.gateway_id {
-                        gateway_groups
-                            .entry(gateway_id.clone())
-                            .or_insert_with(Vec::new)
-                            .push((user_id.clone(), task));
-                    } else {
-                        warn!(
-                            user_id = %user_id,
-                            "Online user has no gateway_id, treating as offline"
-                        );
-                        offline_tasks.push(task);
-                    }
-                } else {
-                    // 离线用户
-                    offline_tasks.push(task);
+            match resolve_route(&task.user_id, online_status_map.get(&task.user_id)) {
+                DeliveryRoute::Online { gateway_id } => {
+                    gateway_groups
+                        .entry(gateway_id)
+                        .or_insert_with(Vec::new)
+                        .push((task.user_id.clone(), task));
                 }
-            } else {
-                // 未查询到状态，视为离线
-                warn!(user_id = %user_id, "User status not found, treating as offline");
-                offline_tasks.push(task);
+                DeliveryRoute::Offline => offline_tasks.push(task),
             }
         }
 
@@ -320,6 +338,7 @@ impl PushDomainService {
             let ack_tracker = Arc::clone(&self.ack_tracker);
             let metrics = Arc::clone(&self.metrics);
             let task_publisher = Arc::clone(&self.task_publisher);
+            let redelivery = self.redelivery.clone();
             let gateway_id_clone = gateway_id.clone();
 
             let retry_policy_clone = self.retry_policy.clone();
@@ -333,6 +352,8 @@ impl PushDomainService {
                     metrics,
                     task_publisher,
                     retry_policy_clone,
+                    redelivery,
+                    true,
                 )
                 .await
             }));
@@ -340,12 +361,20 @@ impl PushDomainService {
 
         // 等待所有推送完成
         let push_results = future::join_all(push_tasks).await;
+        let mut unreachable_batches = Vec::new();
         for result in push_results {
-            if let Err(e) = result {
-                error!(error = %e, "Gateway push task panicked");
+            match result {
+                Ok(Ok(Some(batch))) => unreachable_batches.push(batch),
+                Ok(_) => {}
+                Err(e) => error!(error = %e, "Gateway push task panicked"),
             }
         }
 
+        // 网关在推送过程中不可达（可能已被替换）：重新解析用户当前网关，透明重试一次
+        for batch in unreachable_batches {
+            self.reroute_unreachable_batch(batch).await;
+        }
+
         // 5. 处理离线用户（根据消息类型）
         if !offline_tasks.is_empty() {
             self.handle_offline_tasks(offline_tasks).await?;
@@ -355,7 +384,9 @@ impl PushDomainService {
     }
 
     /// 批量推送到网关（按 gateway_id 分组）
-    #[instrument(skip(router, state_tracker, ack_tracker, metrics, task_publisher, retry_policy), fields(gateway_id = %gateway_id, user_count = user_tasks.len()))]
+    ///
+    /// `reroute` 为 true 时，网关不可达的批次不立即缓冲或转离线，而是返回给调用方重新解析路由
+    #[instrument(skip(router, state_tracker, ack_tracker, metrics, task_publisher, retry_policy, redelivery), fields(gateway_id = %gateway_id, user_count = user_tasks.len()))]
     async fn push_to_gateway_batch(
         router: Arc<dyn GatewayRouterTrait>,
         gateway_id: &str,
@@ -365,7 +396,9 @@ impl PushDomainService {
         metrics: Arc<PushServerMetrics>,
         task_publisher: Arc<dyn PushTaskPublisher>,
         retry_policy: RetryPolicy,
-    ) -> Result<()> {
+        redelivery: Option<Arc<GatewayRedeliveryBuffer>>,
+        reroute: bool,
+    ) -> Result<Option<UnreachableGatewayBatch>> {
         // 按用户分组任务（一个用户可能有多个任务）
         // 保留 user_groups 用于后续查找 task 的 message_type
         let mut user_groups: HashMap<String, Vec<PushDispatchTask>> = HashMap::new();
@@ -414,7 +447,7 @@ impl PushDomainService {
         }
 
         if user_message_map.is_empty() {
-            return Ok(());
+            return Ok(None);
         }
 
         // 为每个用户构建推送请求（支持一个用户多条消息）
@@ -638,52 +671,255 @@ impl PushDomainService {
                     "Failed to push to gateway"
                 );
 
-                for (user_id, messages) in &user_message_map {
-                    for (message_id, _) in messages {
-                        state_tracker
-                            .update_status(
-                                message_id,
-                                user_id,
-                                MessageStatus::Failed,
-                                Some(e.to_string()),
-                            )
-                            .await;
+                // 仅处理已成功解码、参与本次推送的任务
+                let failed_tasks: Vec<PushDispatchTask> = user_groups
+                    .into_values()
+                    .flatten()
+                    .filter(|task| {
+                        user_message_map.get(&task.user_id).is_some_and(|messages| {
+                            messages.iter().any(|(id, _)| id == &task.message_id)
+                        })
+                    })
+                    .collect();
+
+                // 网关不可达：交给调用方重新解析路由（网关实例可能已被替换）
+                if reroute && is_gateway_unreachable(&e) {
+                    return Ok(Some(UnreachableGatewayBatch {
+                        gateway_id: gateway_id.to_string(),
+                        error: e,
+                        tasks: failed_tasks,
+                    }));
+                }
 
-                        // 检查消息类型，决定是否创建离线任务
-                        if let Some(tasks) = user_groups.get(user_id) {
-                            if let Some(task) = tasks.iter().find(|t| &t.message_id == message_id) {
-                                if task.message_type == "Normal" {
-                                    // 普通消息：创建离线推送任务
-                                    if let Err(e) = task_publisher.publish(task).await {
-                                        warn!(
-                                            user_id = %user_id,
-                                            message_id = %message_id,
-                                            error = %e,
-                                            "Failed to create offline task"
-                                        );
-                                    }
-                                } else {
-                                    // 通知消息：直接舍弃
-                                    state_tracker
-                                        .update_status(
-                                            message_id,
-                                            user_id,
-                                            MessageStatus::Expired,
-                                            Some(
-                                                "Notification discarded due to push failure"
-                                                    .to_string(),
-                                            ),
-                                        )
-                                        .await;
-                                }
-                            }
-                        }
-                    }
+                Self::handle_gateway_failure(
+                    gateway_id,
+                    &e,
+                    failed_tasks,
+                    &state_tracker,
+                    &metrics,
+                    &task_publisher,
+                    redelivery.as_ref(),
+                )
+                .await;
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// 网关推送失败的兜底处理：网关不可达时缓冲等待重投递，其余任务标记失败并转离线推送
+    async fn handle_gateway_failure(
+        gateway_id: &str,
+        error: &str,
+        mut failed_tasks: Vec<PushDispatchTask>,
+        state_tracker: &MessageStateTracker,
+        metrics: &PushServerMetrics,
+        task_publisher: &Arc<dyn PushTaskPublisher>,
+        redelivery: Option<&Arc<GatewayRedeliveryBuffer>>,
+    ) {
+        // 网关不可达（而非用户离线）：缓冲等待网关恢复，避免在线用户收到离线推送
+        if let Some(buffer) = redelivery.filter(|_| is_gateway_unreachable(error)) {
+            // 超出缓冲容量的任务会在下方被标记为失败并走离线推送
+            for task in &failed_tasks {
+                state_tracker
+                    .update_status(
+                        &task.message_id,
+                        &task.user_id,
+                        MessageStatus::Pending,
+                        Some("Gateway unreachable, buffered for redelivery".to_string()),
+                    )
+                    .await;
+            }
+            let total = failed_tasks.len();
+            failed_tasks = buffer.buffer(gateway_id, failed_tasks).await;
+            let buffered = total - failed_tasks.len();
+
+            metrics
+                .gateway_redelivery_total
+                .with_label_values(&["buffered"])
+                .inc_by(buffered as u64);
+            if !failed_tasks.is_empty() {
+                metrics
+                    .gateway_redelivery_total
+                    .with_label_values(&["overflow"])
+                    .inc_by(failed_tasks.len() as u64);
+            }
+            warn!(
+                gateway_id = %gateway_id,
+                buffered,
+                overflow = failed_tasks.len(),
+                "Gateway unreachable, buffered push tasks for redelivery"
+            );
+        }
+
+        for task in &failed_tasks {
+            let user_id = &task.user_id;
+            let message_id = &task.message_id;
+            state_tracker
+                .update_status(
+                    message_id,
+                    user_id,
+                    MessageStatus::Failed,
+                    Some(error.to_string()),
+                )
+                .await;
+
+            // 检查消息类型，决定是否创建离线任务
+            if task.message_type == "Normal" {
+                // 普通消息：创建离线推送任务
+                if let Err(e) = task_publisher.publish(task).await {
+                    warn!(
+                        user_id = %user_id,
+                        message_id = %message_id,
+                        error = %e,
+                        "Failed to create offline task"
+                    );
                 }
+            } else {
+                // 通知消息：直接舍弃
+                state_tracker
+                    .update_status(
+                        message_id,
+                        user_id,
+                        MessageStatus::Expired,
+                        Some("Notification discarded due to push failure".to_string()),
+                    )
+                    .await;
             }
         }
+    }
 
-        Ok(())
+    /// 网关不可达的批次：重新解析用户当前所在网关后透明重试一次
+    ///
+    /// - 网关实例已被替换或用户已切换网关：按新路由重试（重试失败后按原有流程缓冲或转离线推送）
+    /// - 用户已离线：直接走离线推送
+    /// - 网关未被替换且用户仍在原网关：网关确实不可达，按原有流程缓冲或转离线推送
+    #[instrument(skip(self, batch), fields(gateway_id = %batch.gateway_id, task_count = batch.tasks.len()))]
+    async fn reroute_unreachable_batch(&self, batch: UnreachableGatewayBatch) {
+        let UnreachableGatewayBatch {
+            gateway_id,
+            error,
+            tasks,
+        } = batch;
+
+        let Some((gateway_groups, offline_tasks)) =
+            self.reresolve_gateway_route(&gateway_id, &tasks).await
+        else {
+            self.metrics
+                .gateway_reroute_total
+                .with_label_values(&["unchanged"])
+                .inc_by(tasks.len() as u64);
+            Self::handle_gateway_failure(
+                &gateway_id,
+                &error,
+                tasks,
+                &self.state_tracker,
+                &self.metrics,
+                &self.task_publisher,
+                self.redelivery.as_ref(),
+            )
+            .await;
+            return;
+        };
+
+        let rerouted: usize = gateway_groups.values().map(Vec::len).sum();
+        self.metrics
+            .gateway_reroute_total
+            .with_label_values(&["rerouted"])
+            .inc_by(rerouted as u64);
+        self.metrics
+            .gateway_reroute_total
+            .with_label_values(&["offline"])
+            .inc_by(offline_tasks.len() as u64);
+        info!(
+            gateway_id = %gateway_id,
+            rerouted,
+            offline = offline_tasks.len(),
+            new_gateway_ids = ?gateway_groups.keys().collect::<Vec<_>>(),
+            "Gateway replaced during fan-out, retrying with re-resolved routes"
+        );
+
+        // 重试只进行一次，失败后不再重新解析
+        let retries = gateway_groups
+            .into_iter()
+            .map(|(current_gateway_id, user_tasks)| {
+                let router = Arc::clone(&self.gateway_router);
+                let state_tracker = Arc::clone(&self.state_tracker);
+                let ack_tracker = Arc::clone(&self.ack_tracker);
+                let metrics = Arc::clone(&self.metrics);
+                let task_publisher = Arc::clone(&self.task_publisher);
+                let redelivery = self.redelivery.clone();
+                let retry_policy = self.retry_policy.clone();
+                tokio::spawn(async move {
+                    Self::push_to_gateway_batch(
+                        router,
+                        &current_gateway_id,
+                        user_tasks,
+                        state_tracker,
+                        ack_tracker,
+                        metrics,
+                        task_publisher,
+                        retry_policy,
+                        redelivery,
+                        false,
+                    )
+                    .await
+                })
+            });
+        for result in future::join_all(retries).await {
+            match result {
+                Ok(Err(e)) => warn!(error = %e, "Rerouted gateway push failed"),
+                Err(e) => error!(error = %e, "Rerouted gateway push task panicked"),
+                Ok(Ok(_)) => {}
+            }
+        }
+
+        if !offline_tasks.is_empty() {
+            if let Err(e) = self.handle_offline_tasks(offline_tasks).await {
+                warn!(
+                    gateway_id = %gateway_id,
+                    error = %e,
+                    "Failed to hand rerouted offline tasks to offline push"
+                );
+            }
+        }
+    }
+
+    /// 重新解析网关不可达批次中用户的当前路由
+    ///
+    /// 返回（按当前网关分组的在线任务，已离线的任务）；网关未被替换且所有用户仍在原网关时返回 None
+    async fn reresolve_gateway_route(
+        &self,
+        gateway_id: &str,
+        tasks: &[PushDispatchTask],
+    ) -> Option<ReroutedTasks> {
+        let replaced = match self.gateway_router.refresh_gateway(gateway_id).await {
+            Ok(replaced) => replaced,
+            Err(e) => {
+                warn!(gateway_id = %gateway_id, error = %e, "Failed to refresh gateway instance");
+                false
+            }
+        };
+
+        let user_ids: Vec<String> = tasks
+            .iter()
+            .map(|task| task.user_id.clone())
+            .collect::<std::collections::HashSet<_>>()
+            .into_iter()
+            .collect();
+        let online_status_map = match self.online_repo.batch_get_online_status(&user_ids).await {
+            Ok(map) => map,
+            Err(e) => {
+                warn!(
+                    gateway_id = %gateway_id,
+                    error = %e,
+                    "Failed to re-query online status after gateway failure"
+                );
+                return None;
+            }
+        };
+
+        regroup_unreachable_tasks(gateway_id, tasks, &online_status_map, replaced)
     }
 
     /// 处理离线任务（根据消息类型）
@@ -781,47 +1017,7 @@ impl PushDomainService {
         request: &PushMessageRequest,
     ) -> Result<Vec<PushDispatchTask>> {
         // P2优化：消息类型提前判断
-        // 从 request.message 中快速提取 message_type，避免完整反序列化
-        let (message_type, is_notification) = if let Some(ref message) = request.message {
-            // 快速判断：从 message.message_type 枚举值判断
-            use flare_proto::common::MessageType;
-            let msg_type =
-                MessageType::try_from(message.message_type).unwrap_or(MessageType::Unspecified);
-
-            let is_notification =
-                matches!(msg_type, MessageType::Notification | MessageType::Typing);
-
-            let persist_if_offline = request
-                .options
-                .as_ref()
-                .map(|o| o.persist_if_offline)
-                .unwrap_or(!is_notification);
-
-            let msg_type_str = if is_notification {
-                "Notification"
-            } else if persist_if_offline {
-                "Normal"
-            } else {
-                "Notification"
-            };
-
-            (msg_type_str, is_notification)
-        } else {
-            // 如果没有 message，根据 options 判断
-            let persist_if_offline = request
-                .options
-                .as_ref()
-                .map(|o| o.persist_if_offline)
-                .unwrap_or(true);
-            (
-                if persist_if_offline {
-                    "Normal"
-                } else {
-                    "Notification"
-                },
-                !persist_if_offline,
-            )
-        };
+        let (message_type, is_notification) = classify_message_request(request);
 
         // P2优化：零拷贝序列化（延迟序列化，只在需要时序列化）
         // 注意：这里 message 字段存储的是 PushMessageRequest 的序列化 bytes
@@ -866,6 +1062,32 @@ impl PushDomainService {
             });
         }
 
+        // 多端同步：发送者的其他在线设备也需要收到这条消息，原始设备由网关过滤
+        if self.config.self_sync_enabled {
+            if let (Some(sender_id), Some(message)) =
+                (self_sync_recipient(request), request.message.as_ref())
+            {
+                let mut metadata = HashMap::new();
+                metadata.insert("self_sync".to_string(), "true".to_string());
+                tasks.push(PushDispatchTask {
+                    user_id: sender_id.to_string(),
+                    message_id: self_sync_message_id(message),
+                    // 发送者离线时无需离线推送，上线后通过同步拉取
+                    message_type: "Notification".to_string(),
+                    message: message_bytes,
+                    notification: None,
+                    headers: HashMap::new(),
+                    metadata,
+                    online: false,
+                    tenant_id: request.tenant.as_ref().map(|t| t.tenant_id.clone()),
+                    require_online: true,
+                    persist_if_offline: false,
+                    priority: request.options.as_ref().map(|o| o.priority).unwrap_or(5),
+                    context: None,
+                });
+            }
+        }
+
         Ok(tasks)
     }
 
@@ -933,3 +1155,134 @@ impl PushDomainService {
     // 所有降级处理和提取函数已移除
     // 消息进入时必须验证完整性：单聊提供 receiver_id，群聊/频道提供 channel_id
 }
+
+/// 按重新查询的在线状态为网关不可达批次重新分组
+///
+/// 网关实例未被替换（`replaced` 为 false）且所有用户仍在原网关时返回 None，由调用方按网关不可达处理
+fn regroup_unreachable_tasks(
+    gateway_id: &str,
+    tasks: &[PushDispatchTask],
+    online_status_map: &HashMap<String, OnlineStatus>,
+    replaced: bool,
+) -> Option<ReroutedTasks> {
+    let mut moved = false;
+    let mut gateway_groups: HashMap<String, Vec<(String, PushDispatchTask)>> = HashMap::new();
+    let mut offline_tasks = Vec::new();
+    for task in tasks {
+        match resolve_route(&task.user_id, online_status_map.get(&task.user_id)) {
+            DeliveryRoute::Online {
+                gateway_id: current_gateway_id,
+            } => {
+                moved |= current_gateway_id != gateway_id;
+                gateway_groups
+                    .entry(current_gateway_id)
+                    .or_default()
+                    .push((task.user_id.clone(), task.clone()));
+            }
+            DeliveryRoute::Offline => {
+                moved = true;
+                offline_tasks.push(task.clone());
+            }
+        }
+    }
+
+    (replaced || moved).then_some((gateway_groups, offline_tasks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(user_id: &str) -> PushDispatchTask {
+        PushDispatchTask {
+            user_id: user_id.to_string(),
+            message_id: format!("msg-{}", user_id),
+            message_type: "Normal".to_string(),
+            message: Vec::new(),
+            notification: None,
+            headers: HashMap::new(),
+            metadata: HashMap::new(),
+            online: true,
+            tenant_id: Some("tenant-a".to_string()),
+            require_online: false,
+            persist_if_offline: true,
+            priority: 0,
+            context: None,
+        }
+    }
+
+    /// 用户当前所在网关（None 表示已离线）
+    fn statuses(routes: &[(&str, Option<&str>)]) -> HashMap<String, OnlineStatus> {
+        routes
+            .iter()
+            .map(|(user_id, gateway_id)| {
+                let status = OnlineStatus {
+                    user_id: user_id.to_string(),
+                    online: gateway_id.is_some(),
+                    gateway_id: gateway_id.map(str::to_string),
+                    server_id: None,
+                };
+                (user_id.to_string(), status)
+            })
+            .collect()
+    }
+
+    fn grouped_users(
+        groups: &HashMap<String, Vec<(String, PushDispatchTask)>>,
+        gateway_id: &str,
+    ) -> Vec<&str> {
+        groups[gateway_id]
+            .iter()
+            .map(|(user_id, _)| user_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_regroup_returns_none_when_gateway_unchanged() {
+        let tasks = vec![task("u1"), task("u2")];
+        let online = statuses(&[("u1", Some("gw-1")), ("u2", Some("gw-1"))]);
+
+        assert!(regroup_unreachable_tasks("gw-1", &tasks, &online, false).is_none());
+    }
+
+    #[test]
+    fn test_regroup_retries_same_gateway_after_instance_replaced() {
+        let tasks = vec![task("u1"), task("u2")];
+        let online = statuses(&[("u1", Some("gw-1")), ("u2", Some("gw-1"))]);
+
+        let (groups, offline) = regroup_unreachable_tasks("gw-1", &tasks, &online, true).unwrap();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(grouped_users(&groups, "gw-1"), vec!["u1", "u2"]);
+        assert!(offline.is_empty());
+    }
+
+    #[test]
+    fn test_regroup_follows_users_to_new_gateway() {
+        let tasks = vec![task("u1"), task("u2"), task("u3")];
+        let online = statuses(&[
+            ("u1", Some("gw-2")),
+            ("u2", Some("gw-1")),
+            ("u3", Some("gw-2")),
+        ]);
+
+        let (groups, offline) = regroup_unreachable_tasks("gw-1", &tasks, &online, false).unwrap();
+
+        assert_eq!(grouped_users(&groups, "gw-2"), vec!["u1", "u3"]);
+        assert_eq!(grouped_users(&groups, "gw-1"), vec!["u2"]);
+        assert!(offline.is_empty());
+    }
+
+    #[test]
+    fn test_regroup_sends_disconnected_users_offline() {
+        let tasks = vec![task("u1"), task("u2"), task("u3")];
+        // u3 未查询到在线状态，同样按离线处理
+        let online = statuses(&[("u1", Some("gw-1")), ("u2", None)]);
+
+        let (groups, offline) = regroup_unreachable_tasks("gw-1", &tasks, &online, false).unwrap();
+
+        assert_eq!(grouped_users(&groups, "gw-1"), vec!["u1"]);
+        let offline_users: Vec<&str> = offline.iter().map(|task| task.user_id.as_str()).collect();
+        assert_eq!(offline_users, vec!["u2", "u3"]);
+    }
+}
@@ -0,0 +1,304 @@
+//! 租户隔离调度领域服务
+//!
+//! 扇出任务按租户进入各自的有界队列，避免单个租户的突发流量拖慢其他租户：
+//! - 每个租户一个独立的工作池：最多同时占用其等级配置的 worker 数，队列满时转入该租户的暂存区，
+//!   调用方不因单个租户积压而阻塞；暂存区为所有租户共享的有界缓冲，满时才由调用方等待
+//! - 租户之间按等级权重做差额轮询（DRR），权重为 w 的租户每轮可连续派发 w 个任务
+//! - 全局 worker 数为所有租户共享的上限
+//!
+//! 调度器只负责排队与派发顺序，不涉及 Kafka 与推送执行
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::domain::model::TenantIsolationPolicy;
+
+struct TenantPool<T> {
+    tier: String,
+    items: VecDeque<T>,
+    /// 正在处理的任务数
+    in_flight: usize,
+    workers: usize,
+    capacity: usize,
+    weight: usize,
+    /// 差额计数（本轮尚可派发的任务数）
+    deficit: usize,
+}
+
+/// 租户隔离调度器
+pub struct TenantWorkerScheduler<T> {
+    policy: TenantIsolationPolicy,
+    pools: HashMap<String, TenantPool<T>>,
+    /// 有待处理任务的租户（轮询顺序）
+    active: VecDeque<String>,
+    in_flight: usize,
+    /// 租户队列已满时暂存的任务（保持租户内顺序），租户队列派发出空位后补入
+    spilled: HashMap<String, VecDeque<T>>,
+    spilled_total: usize,
+}
+
+impl<T> TenantWorkerScheduler<T> {
+    pub fn new(policy: TenantIsolationPolicy) -> Self {
+        Self {
+            policy,
+            pools: HashMap::new(),
+            active: VecDeque::new(),
+            in_flight: 0,
+            spilled: HashMap::new(),
+            spilled_total: 0,
+        }
+    }
+
+    pub fn policy(&self) -> &TenantIsolationPolicy {
+        &self.policy
+    }
+
+    /// 加入租户队列，队列已满时原样返回任务
+    pub fn push(&mut self, tenant_id: &str, item: T) -> Result<(), T> {
+        let policy = &self.policy;
+        let pool = self.pools.entry(tenant_id.to_string()).or_insert_with(|| {
+            let tier = policy.tier(tenant_id);
+            TenantPool {
+                tier: policy.tier_name(tenant_id).to_string(),
+                items: VecDeque::new(),
+                in_flight: 0,
+                workers: tier.workers.max(1),
+                capacity: tier.queue_capacity.max(1),
+                weight: tier.weight.max(1) as usize,
+                deficit: 0,
+            }
+        });
+        if pool.items.len() >= pool.capacity {
+            return Err(item);
+        }
+        if pool.items.is_empty() {
+            self.active.push_back(tenant_id.to_string());
+        }
+        pool.items.push_back(item);
+        Ok(())
+    }
+
+    /// 加入租户队列，队列已满（或该租户已有暂存任务）时转入暂存区
+    ///
+    /// 返回是否进入暂存区；暂存区已满时原样返回任务
+    pub fn push_or_spill(&mut self, tenant_id: &str, item: T) -> Result<bool, T> {
+        let item = if self.spilled.contains_key(tenant_id) {
+            item
+        } else {
+            match self.push(tenant_id, item) {
+                Ok(()) => return Ok(false),
+                Err(rejected) => rejected,
+            }
+        };
+        if self.spilled_total >= self.policy.max_spilled_tasks {
+            return Err(item);
+        }
+        self.spilled
+            .entry(tenant_id.to_string())
+            .or_default()
+            .push_back(item);
+        self.spilled_total += 1;
+        Ok(true)
+    }
+
+    /// 暂存区任务总数
+    pub fn spilled(&self) -> usize {
+        self.spilled_total
+    }
+
+    /// 派发下一个任务：全局 worker 已满或所有有积压的租户都已占满自己的工作池时返回 None
+    pub fn dispatch(&mut self) -> Option<(String, T)> {
+        if self.in_flight >= self.policy.total_workers.max(1) {
+            return None;
+        }
+        for _ in 0..self.active.len() {
+            let tenant_id = self.active.pop_front()?;
+            let Some(pool) = self.pools.get_mut(&tenant_id) else {
+                continue;
+            };
+            if pool.in_flight >= pool.workers {
+                // 工作池已满，本轮未用完的差额作废，避免恢复后超出权重份额
+                pool.deficit = 0;
+                self.active.push_back(tenant_id);
+                continue;
+            }
+            let Some(item) = pool.items.pop_front() else {
+                pool.deficit = 0;
+                continue;
+            };
+            // 队列腾出空位，补入该租户最早的暂存任务
+            if let Some(spilled) = self.spilled.get_mut(&tenant_id) {
+                if let Some(next) = spilled.pop_front() {
+                    pool.items.push_back(next);
+                    self.spilled_total -= 1;
+                }
+                if spilled.is_empty() {
+                    self.spilled.remove(&tenant_id);
+                }
+            }
+
+            if pool.deficit == 0 {
+                pool.deficit = pool.weight;
+            }
+            pool.deficit -= 1;
+            pool.in_flight += 1;
+            self.in_flight += 1;
+
+            if pool.items.is_empty() {
+                pool.deficit = 0;
+            } else if pool.deficit > 0 {
+                // 差额未用完时继续优先派发该租户
+                self.active.push_front(tenant_id.clone());
+            } else {
+                self.active.push_back(tenant_id.clone());
+            }
+            return Some((tenant_id, item));
+        }
+        None
+    }
+
+    /// 任务处理完成，释放租户与全局的 worker 占用
+    pub fn complete(&mut self, tenant_id: &str) {
+        if let Some(pool) = self.pools.get_mut(tenant_id) {
+            pool.in_flight = pool.in_flight.saturating_sub(1);
+            if pool.in_flight == 0 && pool.items.is_empty() {
+                self.pools.remove(tenant_id);
+            }
+        }
+        self.in_flight = self.in_flight.saturating_sub(1);
+    }
+
+    /// 租户所属等级
+    pub fn tier_of(&self, tenant_id: &str) -> &str {
+        self.pools
+            .get(tenant_id)
+            .map(|pool| pool.tier.as_str())
+            .unwrap_or_else(|| self.policy.tier_name(tenant_id))
+    }
+
+    /// 租户的待处理任务数（含暂存区）
+    pub fn backlog(&self, tenant_id: &str) -> usize {
+        self.pools
+            .get(tenant_id)
+            .map(|pool| pool.items.len())
+            .unwrap_or(0)
+            + self.spilled.get(tenant_id).map(VecDeque::len).unwrap_or(0)
+    }
+
+    /// 租户正在处理的任务数
+    pub fn in_flight(&self, tenant_id: &str) -> usize {
+        self.pools
+            .get(tenant_id)
+            .map(|pool| pool.in_flight)
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::TenantTier;
+
+    fn policy() -> TenantIsolationPolicy {
+        let mut policy = TenantIsolationPolicy {
+            total_workers: 4,
+            ..Default::default()
+        };
+        policy.tiers.insert(
+            "standard".to_string(),
+            TenantTier {
+                workers: 2,
+                queue_capacity: 3,
+                weight: 1,
+            },
+        );
+        policy.tiers.insert(
+            "premium".to_string(),
+            TenantTier {
+                workers: 4,
+                queue_capacity: 10,
+                weight: 2,
+            },
+        );
+        policy
+            .tenant_tiers
+            .insert("vip".to_string(), "premium".to_string());
+        policy
+    }
+
+    #[test]
+    fn test_bursting_tenant_is_bounded() {
+        let mut scheduler = TenantWorkerScheduler::new(policy());
+        for i in 0..3 {
+            assert!(scheduler.push("noisy", i).is_ok());
+        }
+        // 队列已满
+        assert_eq!(scheduler.push("noisy", 3), Err(3));
+        scheduler.push("quiet", 0).unwrap();
+
+        // 突发租户最多占用自己工作池的 2 个 worker，其他租户仍可派发
+        let dispatched: Vec<String> = std::iter::from_fn(|| scheduler.dispatch())
+            .map(|(tenant_id, _)| tenant_id)
+            .collect();
+        assert_eq!(dispatched, vec!["noisy", "quiet", "noisy"]);
+        assert_eq!(scheduler.in_flight("noisy"), 2);
+        assert_eq!(scheduler.backlog("noisy"), 1);
+
+        scheduler.complete("noisy");
+        assert_eq!(scheduler.dispatch(), Some(("noisy".to_string(), 2)));
+        assert!(scheduler.dispatch().is_none());
+    }
+
+    #[test]
+    fn test_full_tenant_queue_spills_without_blocking_others() {
+        let mut policy = policy();
+        policy.max_spilled_tasks = 2;
+        let mut scheduler = TenantWorkerScheduler::new(policy);
+        for i in 0..3 {
+            assert_eq!(scheduler.push_or_spill("noisy", i), Ok(false));
+        }
+        // 队列已满，转入暂存区
+        assert_eq!(scheduler.push_or_spill("noisy", 3), Ok(true));
+        assert_eq!(scheduler.push_or_spill("noisy", 4), Ok(true));
+        assert_eq!(scheduler.backlog("noisy"), 5);
+        // 暂存区已满
+        assert_eq!(scheduler.push_or_spill("noisy", 5), Err(5));
+        assert_eq!(scheduler.push_or_spill("quiet", 0), Ok(false));
+
+        // 派发后暂存任务按顺序补入租户队列
+        let mut noisy = Vec::new();
+        while let Some((tenant_id, item)) = scheduler.dispatch() {
+            if tenant_id == "noisy" {
+                noisy.push(item);
+            }
+            scheduler.complete(&tenant_id);
+        }
+        assert_eq!(noisy, vec![0, 1, 2, 3, 4]);
+        assert_eq!(scheduler.spilled(), 0);
+        assert_eq!(scheduler.backlog("noisy"), 0);
+    }
+
+    #[test]
+    fn test_weighted_dispatch_by_tier() {
+        let mut policy = policy();
+        policy.total_workers = 100;
+        policy.tiers.get_mut("standard").unwrap().workers = 100;
+        policy.tiers.get_mut("premium").unwrap().workers = 100;
+        let mut scheduler = TenantWorkerScheduler::new(policy);
+        for i in 0..3 {
+            scheduler.push("basic", i).unwrap();
+        }
+        for i in 0..4 {
+            scheduler.push("vip", i).unwrap();
+        }
+        assert_eq!(scheduler.tier_of("vip"), "premium");
+        assert_eq!(scheduler.tier_of("basic"), "standard");
+
+        let dispatched: Vec<String> = std::iter::from_fn(|| scheduler.dispatch())
+            .map(|(tenant_id, _)| tenant_id)
+            .collect();
+        assert_eq!(
+            dispatched,
+            vec!["basic", "vip", "vip", "basic", "vip", "vip", "basic"]
+        );
+    }
+}
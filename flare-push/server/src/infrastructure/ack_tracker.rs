@@ -10,7 +10,7 @@ use tracing::{debug, error, info, instrument, warn};
 
 use crate::config::PushServerConfig;
 use crate::domain::repository::PushTaskPublisher;
-use flare_im_core::ack::{AckModule, AckStatus, AckType, ImportanceLevel};
+use flare_im_core::ack::{AckModule, AckStatus, AckStore, AckType, ImportanceLevel};
 use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
 
 /// ACK跟踪器（使用统一的 AckManager）
@@ -64,7 +64,7 @@ impl AckTracker {
         };
 
         self.ack_manager
-            .record_ack_status(ack_info)
+            .record_ack_status_in_tenant(ctx.tenant_id(), ack_info)
             .await
             .map_err(|e| {
                 ErrorBuilder::new(
@@ -121,7 +121,7 @@ impl AckTracker {
                 };
 
                 self.ack_manager
-                    .record_ack_status(updated_ack_info)
+                    .record_ack_status_in_tenant(ctx.tenant_id(), updated_ack_info)
                     .await
                     .map_err(|e| {
                         ErrorBuilder::new(
@@ -232,50 +232,19 @@ impl AckTracker {
             "Starting ACK timeout check (optimized)"
         );
 
-        // 1. 从 Redis 扫描所有 ACK keys（分批扫描，限制数量）
-        let redis_manager = &ack_manager.service.redis_manager;
-        let ack_keys = match redis_manager
-            .scan_all_ack_keys(
-                Some(scan_batch_size * 10), // 最多扫描 10 个批次
-                scan_batch_size,
+        // 1. 从ACK存储扫描所有 ACK 状态（分批扫描，限制数量）
+        let ack_infos = match ack_manager
+            .store
+            .scan_ack_statuses(
+                scan_batch_size * 10, // 最多扫描 10 个批次
+                pipeline_batch_size,
             )
             .await
-        {
-            Ok(keys) => {
-                debug!(
-                    key_count = keys.len(),
-                    "Scanned {} ACK keys from Redis (limited)",
-                    keys.len()
-                );
-                keys
-            }
-            Err(e) => {
-                error!(
-                    error = %e,
-                    "Failed to scan ACK keys from Redis"
-                );
-                return Err(ErrorBuilder::new(
-                    ErrorCode::InternalError,
-                    format!("Failed to scan ACK keys: {}", e),
-                )
-                .build_error());
-            }
-        };
-
-        if ack_keys.is_empty() {
-            debug!("No ACK keys found in Redis");
-            return Ok(());
-        }
-
-        // 2. 批量获取 ACK 状态信息（分批 Pipeline）
-        let ack_infos = match redis_manager
-            .batch_get_ack_status_from_keys(&ack_keys, pipeline_batch_size)
-            .await
         {
             Ok(infos) => {
                 debug!(
                     ack_count = infos.len(),
-                    "Retrieved {} ACK statuses from Redis (batched pipeline)",
+                    "Scanned {} ACK statuses from store (limited)",
                     infos.len()
                 );
                 infos
@@ -283,17 +252,22 @@ impl AckTracker {
             Err(e) => {
                 error!(
                     error = %e,
-                    "Failed to batch get ACK statuses from Redis"
+                    "Failed to scan ACK statuses from store"
                 );
                 return Err(ErrorBuilder::new(
                     ErrorCode::InternalError,
-                    format!("Failed to batch get ACK statuses: {}", e),
+                    format!("Failed to scan ACK statuses: {}", e),
                 )
                 .build_error());
             }
         };
 
-        // 3. 过滤出 Pending 状态且超时的 ACK
+        if ack_infos.is_empty() {
+            debug!("No ACK statuses found in store");
+            return Ok(());
+        }
+
+        // 2. 过滤出 Pending 状态且超时的 ACK
         let mut timeout_events = Vec::new();
         let mut processed_count = 0;
 
@@ -326,7 +300,7 @@ impl AckTracker {
             processed_count += 1;
         }
 
-        // 4. 批量处理超时事件（限制并发数量）
+        // 3. 批量处理超时事件（限制并发数量）
         let timeout_count = timeout_events.len();
         if timeout_count > 0 {
             info!(
@@ -0,0 +1,76 @@
+//! 可动态调整的并发度限制器
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::domain::repository::ConcurrencyController;
+
+/// 基于信号量的并发度限制器
+///
+/// 扩容时直接补充许可；缩容时在后台占用多余的许可并丢弃，
+/// 正在处理中的任务不受影响，完成后总许可数自然收敛到目标值。
+pub struct DynamicConcurrencyLimiter {
+    semaphore: Arc<Semaphore>,
+    limit: AtomicUsize,
+}
+
+impl DynamicConcurrencyLimiter {
+    pub fn new(initial: usize) -> Arc<Self> {
+        let initial = initial.max(1);
+        Arc::new(Self {
+            semaphore: Arc::new(Semaphore::new(initial)),
+            limit: AtomicUsize::new(initial),
+        })
+    }
+
+    /// 获取一个处理许可（许可释放前占用一个并发槽位）
+    pub async fn acquire(&self) -> OwnedSemaphorePermit {
+        self.semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("concurrency semaphore is never closed")
+    }
+}
+
+impl ConcurrencyController for DynamicConcurrencyLimiter {
+    fn current(&self) -> usize {
+        self.limit.load(Ordering::Acquire)
+    }
+
+    fn set(&self, concurrency: usize) {
+        let target = concurrency.max(1);
+        let previous = self.limit.swap(target, Ordering::AcqRel);
+        if target > previous {
+            self.semaphore.add_permits(target - previous);
+        } else if target < previous {
+            let semaphore = self.semaphore.clone();
+            let diff = (previous - target) as u32;
+            tokio::spawn(async move {
+                if let Ok(permits) = semaphore.acquire_many_owned(diff).await {
+                    permits.forget();
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_set_adjusts_available_permits() {
+        let limiter = DynamicConcurrencyLimiter::new(2);
+        limiter.set(5);
+        assert_eq!(limiter.current(), 5);
+        assert_eq!(limiter.semaphore.available_permits(), 5);
+
+        limiter.set(3);
+        assert_eq!(limiter.current(), 3);
+        tokio::task::yield_now().await;
+        assert_eq!(limiter.semaphore.available_permits(), 3);
+    }
+}
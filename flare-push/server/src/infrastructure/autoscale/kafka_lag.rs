@@ -0,0 +1,147 @@
+//! Kafka 消费积压采样
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::{ClientConfig, Offset, TopicPartitionList};
+
+use crate::config::PushServerConfig;
+use crate::domain::model::ConsumerLag;
+use crate::domain::repository::ConsumerLagMonitor;
+
+const KAFKA_QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// 消费进度（由消费者记录最近一条消息的写入时间，用于计算积压时长）
+#[derive(Default)]
+pub struct ConsumerProgress {
+    last_message_ts_ms: AtomicI64,
+}
+
+impl ConsumerProgress {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// 记录消息写入 Kafka 的时间（毫秒）
+    pub fn record(&self, message_ts_ms: i64) {
+        self.last_message_ts_ms.store(message_ts_ms, Ordering::Release);
+    }
+
+    /// 最近消费消息的积压时长（毫秒）
+    pub fn age_ms(&self) -> u64 {
+        let ts = self.last_message_ts_ms.load(Ordering::Acquire);
+        if ts <= 0 {
+            return 0;
+        }
+        (now_millis() - ts).max(0) as u64
+    }
+}
+
+/// 基于已提交 offset 与高水位计算消费组积压
+pub struct KafkaConsumerLagMonitor {
+    config: Arc<PushServerConfig>,
+    consumer: Arc<BaseConsumer>,
+    progress: Arc<ConsumerProgress>,
+}
+
+impl KafkaConsumerLagMonitor {
+    pub fn new(config: Arc<PushServerConfig>, progress: Arc<ConsumerProgress>) -> Result<Self> {
+        // 使用与推送消费者相同的 group.id 查询已提交 offset，但不订阅 Topic，不参与分区分配
+        let consumer: BaseConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &config.kafka_bootstrap)
+            .set("group.id", &config.consumer_group)
+            .set("enable.auto.commit", "false")
+            .create()
+            .map_err(|err| {
+                ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    "failed to create kafka lag monitor",
+                )
+                .details(err.to_string())
+                .build_error()
+            })?;
+
+        Ok(Self {
+            config,
+            consumer: Arc::new(consumer),
+            progress,
+        })
+    }
+
+    fn query_lag(consumer: &BaseConsumer, topic: &str) -> std::result::Result<i64, String> {
+        let metadata = consumer
+            .fetch_metadata(Some(topic), KAFKA_QUERY_TIMEOUT)
+            .map_err(|err| err.to_string())?;
+        let Some(topic_metadata) = metadata.topics().iter().find(|t| t.name() == topic) else {
+            return Ok(0);
+        };
+
+        let mut partitions = TopicPartitionList::new();
+        for partition in topic_metadata.partitions() {
+            partitions.add_partition(topic, partition.id());
+        }
+        let committed = consumer
+            .committed_offsets(partitions, KAFKA_QUERY_TIMEOUT)
+            .map_err(|err| err.to_string())?;
+
+        let mut total_lag = 0i64;
+        for element in committed.elements() {
+            let (low, high) = consumer
+                .fetch_watermarks(topic, element.partition(), KAFKA_QUERY_TIMEOUT)
+                .map_err(|err| err.to_string())?;
+            let position = match element.offset() {
+                Offset::Offset(offset) => offset,
+                // 尚未提交过 offset 时按 earliest 策略从低水位开始计算
+                _ => low,
+            };
+            total_lag += (high - position).max(0);
+        }
+        Ok(total_lag)
+    }
+}
+
+#[async_trait]
+impl ConsumerLagMonitor for KafkaConsumerLagMonitor {
+    async fn current_lag(&self) -> Result<ConsumerLag> {
+        let consumer = self.consumer.clone();
+        let topic = self.config.task_topic.clone();
+        let total_lag = tokio::task::spawn_blocking(move || Self::query_lag(&consumer, &topic))
+            .await
+            .map_err(|err| {
+                ErrorBuilder::new(ErrorCode::InternalError, "kafka lag query panicked")
+                    .details(err.to_string())
+                    .build_error()
+            })?
+            .map_err(|err| {
+                ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    "failed to query kafka consumer lag",
+                )
+                .details(err)
+                .build_error()
+            })?;
+
+        // 没有积压时最近一条消息的时间不再代表排队时长
+        let message_age_ms = if total_lag > 0 {
+            self.progress.age_ms()
+        } else {
+            0
+        };
+
+        Ok(ConsumerLag {
+            total_lag,
+            message_age_ms,
+        })
+    }
+}
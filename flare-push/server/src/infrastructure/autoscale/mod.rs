@@ -0,0 +1,9 @@
+//! 自动扩缩容基础设施：消费积压采样、进程内并发度控制、扩缩容事件 Webhook
+
+pub mod concurrency;
+pub mod kafka_lag;
+pub mod webhook;
+
+pub use concurrency::DynamicConcurrencyLimiter;
+pub use kafka_lag::{ConsumerProgress, KafkaConsumerLagMonitor};
+pub use webhook::WebhookScaleEventSink;
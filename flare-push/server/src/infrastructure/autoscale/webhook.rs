@@ -0,0 +1,70 @@
+//! 扩缩容事件 Webhook
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
+use reqwest::Client;
+use serde_json::json;
+
+use crate::domain::model::ScaleEvent;
+use crate::domain::repository::ScaleEventSink;
+
+/// 将扩缩容事件以 JSON POST 到外部扩缩容系统
+pub struct WebhookScaleEventSink {
+    client: Client,
+    url: String,
+    /// 事件来源（消费组），便于外部系统区分多个 Push Server 部署
+    source: String,
+}
+
+impl WebhookScaleEventSink {
+    pub fn new(url: String, source: String) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .map_err(|err| {
+                ErrorBuilder::new(ErrorCode::InternalError, "failed to build webhook client")
+                    .details(err.to_string())
+                    .build_error()
+            })?;
+
+        Ok(Self {
+            client,
+            url,
+            source,
+        })
+    }
+}
+
+#[async_trait]
+impl ScaleEventSink for WebhookScaleEventSink {
+    async fn emit(&self, event: &ScaleEvent) -> Result<()> {
+        let body = json!({
+            "source": self.source,
+            "event": event,
+        });
+
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|err| {
+                ErrorBuilder::new(ErrorCode::ServiceUnavailable, "scale webhook request failed")
+                    .details(err.to_string())
+                    .build_error()
+            })?;
+
+        if !response.status().is_success() {
+            return Err(ErrorBuilder::new(
+                ErrorCode::ServiceUnavailable,
+                "scale webhook returned error status",
+            )
+            .details(response.status().to_string())
+            .build_error());
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,2 @@
 pub mod multi_level_cache;
 pub mod online_status_cache;
-pub mod redis_online;
@@ -0,0 +1,254 @@
+//! 网关重投递缓冲区
+//!
+//! 网关实例短暂不可达（重启、滚动发布）时，在线用户的推送任务先按 gateway_id 缓冲，
+//! 由后台探测循环在网关恢复后重新投递，避免产生多余的离线推送。
+//! 超过保留时长仍未恢复的任务交回离线推送流程。
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+use crate::domain::model::PushDispatchTask;
+
+/// 判断网关推送错误是否属于“网关不可达”（而非用户离线等业务错误）
+pub fn is_gateway_unreachable(error: &str) -> bool {
+    let error = error.to_lowercase();
+    if error.contains("user offline") || error.contains("users offline") {
+        return false;
+    }
+    [
+        "timeout",
+        "connect",
+        "network",
+        "transport",
+        "unavailable",
+        "gateway instance not found",
+    ]
+    .iter()
+    .any(|keyword| error.contains(keyword))
+}
+
+struct BufferedTask {
+    task: PushDispatchTask,
+    buffered_at: Instant,
+}
+
+/// 按网关缓冲的待重投递任务
+pub struct GatewayRedeliveryBuffer {
+    ttl: Duration,
+    max_per_gateway: usize,
+    queues: Mutex<HashMap<String, VecDeque<BufferedTask>>>,
+}
+
+impl GatewayRedeliveryBuffer {
+    pub fn new(ttl: Duration, max_per_gateway: usize) -> Self {
+        Self {
+            ttl,
+            max_per_gateway,
+            queues: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 缓冲任务，返回因容量不足未能缓冲的任务
+    pub async fn buffer(
+        &self,
+        gateway_id: &str,
+        tasks: Vec<PushDispatchTask>,
+    ) -> Vec<PushDispatchTask> {
+        let mut queues = self.queues.lock().await;
+        let queue = queues.entry(gateway_id.to_string()).or_default();
+        let now = Instant::now();
+
+        let mut rejected = Vec::new();
+        for task in tasks {
+            if queue.len() >= self.max_per_gateway {
+                rejected.push(task);
+            } else {
+                queue.push_back(BufferedTask {
+                    task,
+                    buffered_at: now,
+                });
+            }
+        }
+        rejected
+    }
+
+    /// 当前有缓冲任务的网关
+    pub async fn gateway_ids(&self) -> Vec<String> {
+        self.queues.lock().await.keys().cloned().collect()
+    }
+
+    /// 缓冲任务总数
+    pub async fn len(&self) -> usize {
+        self.queues.lock().await.values().map(VecDeque::len).sum()
+    }
+
+    /// 按探测结果取出网关的缓冲任务，返回（待重投递，已过期）
+    ///
+    /// 网关恢复时取出全部任务；仍不可达时只取出已过期任务，其余继续缓冲
+    pub async fn flush(
+        &self,
+        gateway_id: &str,
+        reachable: bool,
+    ) -> (Vec<PushDispatchTask>, Vec<PushDispatchTask>) {
+        if reachable {
+            self.drain(gateway_id).await
+        } else {
+            (Vec::new(), self.drain_expired(gateway_id).await)
+        }
+    }
+
+    /// 取出网关的全部缓冲任务，返回（未过期，已过期）
+    pub async fn drain(&self, gateway_id: &str) -> (Vec<PushDispatchTask>, Vec<PushDispatchTask>) {
+        let queue = self.queues.lock().await.remove(gateway_id);
+        let mut live = Vec::new();
+        let mut expired = Vec::new();
+        for buffered in queue.into_iter().flatten() {
+            if buffered.buffered_at.elapsed() >= self.ttl {
+                expired.push(buffered.task);
+            } else {
+                live.push(buffered.task);
+            }
+        }
+        (live, expired)
+    }
+
+    /// 取出网关已过期的缓冲任务（网关仍不可达时使用）
+    pub async fn drain_expired(&self, gateway_id: &str) -> Vec<PushDispatchTask> {
+        let mut queues = self.queues.lock().await;
+        let Some(queue) = queues.get_mut(gateway_id) else {
+            return Vec::new();
+        };
+
+        // 队列按缓冲时间有序，从队头取出过期任务即可
+        let mut expired = Vec::new();
+        while queue
+            .front()
+            .is_some_and(|buffered| buffered.buffered_at.elapsed() >= self.ttl)
+        {
+            if let Some(buffered) = queue.pop_front() {
+                expired.push(buffered.task);
+            }
+        }
+        if queue.is_empty() {
+            queues.remove(gateway_id);
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(user_id: &str) -> PushDispatchTask {
+        PushDispatchTask {
+            user_id: user_id.to_string(),
+            message_id: format!("msg-{}", user_id),
+            message_type: String::new(),
+            message: Vec::new(),
+            notification: None,
+            headers: HashMap::new(),
+            metadata: HashMap::new(),
+            online: true,
+            tenant_id: Some("tenant-a".to_string()),
+            require_online: false,
+            persist_if_offline: true,
+            priority: 0,
+            context: None,
+        }
+    }
+
+    fn user_ids(tasks: &[PushDispatchTask]) -> Vec<&str> {
+        tasks.iter().map(|task| task.user_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_gateway_unreachable_classification() {
+        assert!(is_gateway_unreachable("request Timeout after 3s"));
+        assert!(is_gateway_unreachable(
+            "transport error: connection refused"
+        ));
+        assert!(is_gateway_unreachable("Gateway instance not found: gw-1"));
+        // 用户离线是业务结果，不应缓冲
+        assert!(!is_gateway_unreachable("user offline"));
+        assert!(!is_gateway_unreachable("3 users offline (connect later)"));
+        assert!(!is_gateway_unreachable("permission denied"));
+    }
+
+    #[tokio::test]
+    async fn test_buffer_is_bounded_per_gateway() {
+        let buffer = GatewayRedeliveryBuffer::new(Duration::from_secs(60), 2);
+
+        let rejected = buffer
+            .buffer("gw-1", vec![task("u1"), task("u2"), task("u3")])
+            .await;
+        assert_eq!(user_ids(&rejected), ["u3"]);
+        // 容量按网关独立计算
+        assert!(buffer.buffer("gw-2", vec![task("u4")]).await.is_empty());
+        let rejected = buffer.buffer("gw-1", vec![task("u5")]).await;
+        assert_eq!(user_ids(&rejected), ["u5"]);
+
+        assert_eq!(buffer.len().await, 3);
+        let mut gateway_ids = buffer.gateway_ids().await;
+        gateway_ids.sort();
+        assert_eq!(gateway_ids, ["gw-1", "gw-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_flush_keeps_tasks_until_gateway_is_reachable() {
+        let buffer = GatewayRedeliveryBuffer::new(Duration::from_secs(60), 10);
+        buffer.buffer("gw-1", vec![task("u1"), task("u2")]).await;
+        buffer.buffer("gw-2", vec![task("u3")]).await;
+
+        // 仍不可达且未过期：不重投递，也不转离线
+        let (live, expired) = buffer.flush("gw-1", false).await;
+        assert!(live.is_empty() && expired.is_empty());
+        assert_eq!(buffer.len().await, 3);
+
+        // 恢复后按缓冲顺序全部取出，且只影响该网关
+        let (live, expired) = buffer.flush("gw-1", true).await;
+        assert_eq!(user_ids(&live), ["u1", "u2"]);
+        assert!(expired.is_empty());
+        assert_eq!(buffer.gateway_ids().await, ["gw-2"]);
+
+        // 已取出的网关再次刷新为空
+        let (live, expired) = buffer.flush("gw-1", true).await;
+        assert!(live.is_empty() && expired.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_expired_tasks_are_dropped_oldest_first() {
+        let buffer = GatewayRedeliveryBuffer::new(Duration::from_millis(50), 10);
+        buffer.buffer("gw-1", vec![task("u1"), task("u2")]).await;
+        std::thread::sleep(Duration::from_millis(60));
+        buffer.buffer("gw-1", vec![task("u3")]).await;
+
+        // 网关仍不可达：只从队头取出已过期任务，新任务继续等待
+        let (live, expired) = buffer.flush("gw-1", false).await;
+        assert!(live.is_empty());
+        assert_eq!(user_ids(&expired), ["u1", "u2"]);
+        assert_eq!(buffer.len().await, 1);
+
+        // 过期后恢复：剩余任务转为离线推送，队列被清空
+        std::thread::sleep(Duration::from_millis(60));
+        let (live, expired) = buffer.flush("gw-1", true).await;
+        assert!(live.is_empty());
+        assert_eq!(user_ids(&expired), ["u3"]);
+        assert!(buffer.gateway_ids().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_drain_splits_live_and_expired_tasks() {
+        let buffer = GatewayRedeliveryBuffer::new(Duration::from_millis(50), 10);
+        buffer.buffer("gw-1", vec![task("u1")]).await;
+        std::thread::sleep(Duration::from_millis(60));
+        buffer.buffer("gw-1", vec![task("u2")]).await;
+
+        let (live, expired) = buffer.flush("gw-1", true).await;
+        assert_eq!(user_ids(&live), ["u2"]);
+        assert_eq!(user_ids(&expired), ["u1"]);
+        assert_eq!(buffer.len().await, 0);
+    }
+}
@@ -1,9 +1,9 @@
 pub mod ack_tracker;
+pub mod autoscale;
 pub mod cache;
+pub mod gateway_redelivery;
 pub mod hook;
 pub mod message_state;
 pub mod mq;
 pub mod persistence;
 pub mod retry;
-pub mod session_client;
-pub mod signaling;
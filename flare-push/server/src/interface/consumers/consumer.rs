@@ -6,21 +6,40 @@ use flare_proto::push::PushMessageRequest;
 use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
 use prost::Message;
 use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
-use rdkafka::message::{BorrowedMessage, Message as _};
+use rdkafka::message::Message as _;
+use rdkafka::{Offset, TopicPartitionList};
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
 use crate::application::commands::PushMessageCommand;
 use crate::application::handlers::PushCommandHandler;
 use crate::config::PushServerConfig;
+use crate::infrastructure::autoscale::{ConsumerProgress, DynamicConcurrencyLimiter};
+use crate::interface::consumers::offsets::{OffsetCompletion, OffsetTracker};
+use crate::interface::consumers::tenant_pools::TenantWorkerPools;
 use flare_server_core::kafka::{
     KafkaConsumerConfig, build_kafka_consumer, subscribe_and_wait_for_assignment,
 };
 
+/// 定期提交已完成 offset 的间隔（后台处理完成后没有新消息到达时也能推进提交位置）
+const COMMIT_INTERVAL: Duration = Duration::from_secs(1);
+/// 停机时等待在途消息处理完成的上限（略大于单条消息的处理超时）
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(35);
+
 pub struct PushKafkaConsumer {
     config: Arc<PushServerConfig>,
     consumer: StreamConsumer,
     command_handler: Arc<PushCommandHandler>,
     metrics: Arc<PushServerMetrics>,
+    /// 自动扩缩容：进程内并发度限制（None 表示逐条顺序处理）
+    limiter: Option<Arc<DynamicConcurrencyLimiter>>,
+    /// 自动扩缩容：消费进度（用于计算积压时长）
+    progress: Option<Arc<ConsumerProgress>>,
+    /// 租户隔离：按租户分队列、加权派发的工作池（优先于并发模式）
+    tenant_pools: Option<Arc<TenantWorkerPools>>,
+    /// 各分区在途消息，只提交连续处理完成的 offset
+    offsets: Arc<OffsetTracker>,
 }
 
 impl PushKafkaConsumer {
@@ -74,9 +93,30 @@ impl PushKafkaConsumer {
             consumer,
             command_handler,
             metrics,
+            limiter: None,
+            progress: None,
+            tenant_pools: None,
+            offsets: Arc::new(OffsetTracker::default()),
         })
     }
 
+    /// 启用自动扩缩容：按限制器的并发度并行处理消息，并记录消费进度
+    pub fn with_autoscale(
+        mut self,
+        limiter: Option<Arc<DynamicConcurrencyLimiter>>,
+        progress: Arc<ConsumerProgress>,
+    ) -> Self {
+        self.limiter = limiter;
+        self.progress = Some(progress);
+        self
+    }
+
+    /// 启用租户隔离：消息按租户进入各自的工作池处理
+    pub fn with_tenant_pools(mut self, tenant_pools: Option<Arc<TenantWorkerPools>>) -> Self {
+        self.tenant_pools = tenant_pools;
+        self
+    }
+
     pub async fn run(&self) -> Result<()> {
         let mut consecutive_errors = 0;
         let mut last_error_time = None;
@@ -89,6 +129,11 @@ impl PushKafkaConsumer {
             "Push Server Consumer started, waiting for messages..."
         );
 
+        let shutdown = tokio::signal::ctrl_c();
+        tokio::pin!(shutdown);
+        let mut commit_tick = tokio::time::interval(COMMIT_INTERVAL);
+        commit_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
         // 注意：partition assignment 已在 new() 方法中完成，这里直接开始消费
         loop {
             // 定期输出心跳日志（每 10 秒）
@@ -102,7 +147,18 @@ impl PushKafkaConsumer {
             }
 
             // 消费单条消息（StreamConsumer 每次返回一条消息）
-            match self.consumer.recv().await {
+            let received = tokio::select! {
+                _ = &mut shutdown => {
+                    self.drain().await;
+                    return Ok(());
+                }
+                _ = commit_tick.tick() => {
+                    self.commit_offsets(CommitMode::Async);
+                    continue;
+                }
+                received = self.consumer.recv() => received,
+            };
+            match received {
                 Ok(record) => {
                     // 成功收到消息，重置错误计数
                     consecutive_errors = 0;
@@ -118,76 +174,68 @@ impl PushKafkaConsumer {
                         message_count
                     );
 
-                    if let Some(payload) = record.payload() {
-                        info!(
-                            payload_len = payload.len(),
-                            "Decoding PushMessageRequest, payload size: {} bytes",
-                            payload.len()
-                        );
+                    if let Some(progress) = &self.progress {
+                        if let Some(ts) = record.timestamp().to_millis() {
+                            progress.record(ts);
+                        }
+                    }
 
-                        // 解析 PushMessageRequest
-                        match PushMessageRequest::decode(payload) {
-                            Ok(request) => {
-                                info!(
-                                    user_ids = ?request.user_ids,
-                                    user_ids_count = request.user_ids.len(),
-                                    "Received push message from Kafka"
-                                );
+                    // 处理失败、超时或解码失败时也视为完成并提交 offset，避免无限重试导致 consumer 卡住
+                    // 注意：这会导致消息丢失，可以考虑将来发送到死信队列
+                    let completion = self.offsets.track(record.partition(), record.offset());
+                    if let Some(tenant_pools) = &self.tenant_pools {
+                        // 租户隔离模式：放入租户队列，worker 处理完成后才推进提交位置
+                        // （租户队列满时转入暂存区，只有暂存区也满时才在此等待）
+                        match decode_request(record.payload(), record.partition(), record.offset())
+                        {
+                            Some(request) => {
+                                let tenant_id = request
+                                    .tenant
+                                    .as_ref()
+                                    .map(|tenant| tenant.tenant_id.as_str())
+                                    .filter(|tenant_id| !tenant_id.is_empty())
+                                    .unwrap_or(&self.config.default_tenant_id)
+                                    .to_string();
+                                tenant_pools.submit(&tenant_id, request, completion).await;
+                            }
+                            None => completion.complete(),
+                        }
+                    } else {
+                        match &self.limiter {
+                            Some(limiter) => {
+                                // 并发模式：占用一个并发槽位后在后台处理，处理完成后才推进提交位置
+                                let permit = limiter.acquire().await;
+                                let payload = record.payload().map(|p| p.to_vec());
+                                let (partition, offset) = (record.partition(), record.offset());
 
-                                // 处理单条消息（添加超时保护，避免阻塞 consumer）
-                                let command = PushMessageCommand { request };
                                 let handler = self.command_handler.clone();
-                                let timeout_duration = std::time::Duration::from_secs(30); // 30秒超时
-
-                                match tokio::time::timeout(
-                                    timeout_duration,
-                                    handler.handle_push_message(command),
-                                )
-                                .await
-                                {
-                                    Ok(Ok(_)) => {
-                                        info!("Successfully processed push message");
-                                        // 处理成功，提交 offset
-                                        self.commit_message(&record);
-                                    }
-                                    Ok(Err(err)) => {
-                                        error!(?err, "failed to process push message");
-                                        // 处理失败时也提交 offset，避免无限重试导致 consumer 卡住
-                                        // 注意：这会导致消息丢失，但可以避免整个 consumer 停止工作
-                                        // 可以考虑将来发送到死信队列
-                                        warn!(
-                                            "Processing failed, committing offset to avoid blocking consumer"
-                                        );
-                                        self.commit_message(&record);
-                                    }
-                                    Err(_) => {
-                                        error!(
-                                            timeout_secs = timeout_duration.as_secs(),
-                                            "push message processing timed out, skipping message"
-                                        );
-                                        // 超时时提交 offset，避免 consumer 卡住
-                                        self.commit_message(&record);
-                                    }
-                                }
-                            }
-                            Err(err) => {
-                                error!(
-                                    error = ?err,
-                                    offset = record.offset(),
-                                    partition = record.partition(),
-                                    "failed to decode PushMessageRequest, skipping message"
+                                spawn_tracked(
+                                    permit,
+                                    async move {
+                                        process_payload(
+                                            handler,
+                                            payload.as_deref(),
+                                            partition,
+                                            offset,
+                                        )
+                                        .await
+                                    },
+                                    completion,
                                 );
-                                // 解码失败时，提交 offset 并跳过消息，避免 consumer 卡住
-                                // 注意：这种情况下消息会丢失，但可以避免整个 consumer 停止工作
-                                // 可以考虑将来发送到死信队列
-                                self.commit_message(&record);
+                            }
+                            None => {
+                                process_payload(
+                                    self.command_handler.clone(),
+                                    record.payload(),
+                                    record.partition(),
+                                    record.offset(),
+                                )
+                                .await;
+                                completion.complete();
                             }
                         }
-                    } else {
-                        warn!("Received message with empty payload");
-                        // 空 payload 的消息也需要提交 offset，避免卡住
-                        self.commit_message(&record);
                     }
+                    self.commit_offsets(CommitMode::Async);
                 }
                 Err(err) => {
                     consecutive_errors += 1;
@@ -227,25 +275,198 @@ impl PushKafkaConsumer {
         &self.config
     }
 
-    /// 提交 Kafka message offset
-    /// 只有在手动提交模式下才需要调用此方法
-    fn commit_message(&self, message: &BorrowedMessage<'_>) {
-        // 只有在手动提交模式下才提交
-        if !self.config.enable_auto_commit() {
-            if let Err(err) = self.consumer.commit_message(message, CommitMode::Async) {
-                warn!(
-                    error = ?err,
-                    offset = message.offset(),
-                    partition = message.partition(),
-                    "Failed to commit Kafka message offset"
-                );
-            } else {
-                debug!(
-                    offset = message.offset(),
-                    partition = message.partition(),
-                    "Committed Kafka message offset"
-                );
+    /// 停机：等待在途消息（租户队列与并发模式的后台任务）处理完成后同步提交 offset
+    async fn drain(&self) {
+        info!(
+            in_flight = self.offsets.in_flight(),
+            "Shutdown signal received, draining in-flight push messages"
+        );
+        if tokio::time::timeout(DRAIN_TIMEOUT, self.offsets.wait_idle())
+            .await
+            .is_err()
+        {
+            warn!(
+                in_flight = self.offsets.in_flight(),
+                "Timed out draining push messages, unfinished messages will be redelivered"
+            );
+        }
+        self.commit_offsets(CommitMode::Sync);
+    }
+
+    /// 提交各分区连续处理完成的 offset
+    /// 只有在手动提交模式下才需要提交
+    fn commit_offsets(&self, mode: CommitMode) {
+        if self.config.enable_auto_commit() {
+            return;
+        }
+
+        let mut tpl = TopicPartitionList::new();
+        for (partition, offset) in self.offsets.commit_points() {
+            if let Err(err) =
+                tpl.add_partition_offset(&self.config.task_topic, partition, Offset::Offset(offset))
+            {
+                warn!(error = ?err, partition, "Invalid Kafka commit offset");
             }
         }
+        if tpl.count() == 0 {
+            return;
+        }
+        if let Err(err) = self.consumer.commit(&tpl, mode) {
+            warn!(error = ?err, "Failed to commit Kafka offsets");
+        } else {
+            debug!(partitions = tpl.count(), "Committed Kafka offsets");
+        }
+    }
+}
+
+/// 解码并处理单条推送消息
+/// 在后台处理一条消息：处理结束后才释放并发槽位并标记 offset 完成
+///
+/// 处理中的消息不会被提交，进程在处理完成前退出时会重新消费
+fn spawn_tracked<F>(
+    permit: OwnedSemaphorePermit,
+    work: F,
+    completion: OffsetCompletion,
+) -> JoinHandle<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        work.await;
+        drop(permit);
+        completion.complete();
+    })
+}
+
+async fn process_payload(
+    handler: Arc<PushCommandHandler>,
+    payload: Option<&[u8]>,
+    partition: i32,
+    offset: i64,
+) {
+    if let Some(request) = decode_request(payload, partition, offset) {
+        process_request(handler, request).await;
+    }
+}
+
+/// 解码推送消息，空消息或解码失败时返回 None（调用方跳过该消息）
+fn decode_request(
+    payload: Option<&[u8]>,
+    partition: i32,
+    offset: i64,
+) -> Option<PushMessageRequest> {
+    let Some(payload) = payload else {
+        warn!("Received message with empty payload");
+        return None;
+    };
+
+    info!(
+        payload_len = payload.len(),
+        "Decoding PushMessageRequest, payload size: {} bytes",
+        payload.len()
+    );
+
+    match PushMessageRequest::decode(payload) {
+        Ok(request) => Some(request),
+        Err(err) => {
+            error!(
+                error = ?err,
+                offset,
+                partition,
+                "failed to decode PushMessageRequest, skipping message"
+            );
+            None
+        }
+    }
+}
+
+/// 处理单条推送消息（带超时保护，避免阻塞 consumer）
+pub(super) async fn process_request(handler: Arc<PushCommandHandler>, request: PushMessageRequest) {
+    info!(
+        user_ids = ?request.user_ids,
+        user_ids_count = request.user_ids.len(),
+        "Received push message from Kafka"
+    );
+
+    let command = PushMessageCommand { request };
+    let timeout_duration = Duration::from_secs(30); // 30秒超时
+
+    match tokio::time::timeout(timeout_duration, handler.handle_push_message(command)).await {
+        Ok(Ok(_)) => {
+            info!("Successfully processed push message");
+        }
+        Ok(Err(err)) => {
+            error!(?err, "failed to process push message");
+            warn!("Processing failed, committing offset to avoid blocking consumer");
+        }
+        Err(_) => {
+            error!(
+                timeout_secs = timeout_duration.as_secs(),
+                "push message processing timed out, skipping message"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_offset_completes_only_after_background_processing() {
+        let limiter = DynamicConcurrencyLimiter::new(1);
+        let offsets = Arc::new(OffsetTracker::default());
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+
+        let handle = spawn_tracked(
+            limiter.acquire().await,
+            async move {
+                let _ = released.await;
+            },
+            offsets.track(0, 10),
+        );
+        tokio::task::yield_now().await;
+
+        // 处理中：offset 不可提交，并发槽位仍被占用
+        assert!(offsets.commit_points().is_empty());
+        assert_eq!(offsets.in_flight(), 1);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), limiter.acquire())
+                .await
+                .is_err()
+        );
+
+        release.send(()).unwrap();
+        handle.await.unwrap();
+
+        assert_eq!(offsets.commit_points(), [(0, 11)]);
+        assert_eq!(offsets.in_flight(), 0);
+        tokio::time::timeout(Duration::from_secs(1), limiter.acquire())
+            .await
+            .expect("permit released after processing");
+    }
+
+    #[tokio::test]
+    async fn test_out_of_order_completion_commits_contiguous_offsets() {
+        let limiter = DynamicConcurrencyLimiter::new(2);
+        let offsets = Arc::new(OffsetTracker::default());
+        let (release_first, first_released) = tokio::sync::oneshot::channel::<()>();
+
+        let first = spawn_tracked(
+            limiter.acquire().await,
+            async move {
+                let _ = first_released.await;
+            },
+            offsets.track(0, 10),
+        );
+        let second = spawn_tracked(limiter.acquire().await, async {}, offsets.track(0, 11));
+
+        // 后到的消息先处理完成，但前一条仍在处理中，不能越过它提交
+        second.await.unwrap();
+        assert!(offsets.commit_points().is_empty());
+
+        release_first.send(()).unwrap();
+        first.await.unwrap();
+        assert_eq!(offsets.commit_points(), [(0, 12)]);
     }
 }
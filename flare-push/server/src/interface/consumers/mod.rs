@@ -1,5 +1,8 @@
 pub mod ack_consumer;
 pub mod consumer;
+pub mod offsets;
+pub mod tenant_pools;
 
 pub use ack_consumer::AckKafkaConsumer;
 pub use consumer::PushKafkaConsumer;
+pub use tenant_pools::TenantWorkerPools;
@@ -0,0 +1,173 @@
+//! 分区 offset 跟踪
+//!
+//! 并发模式与租户隔离模式下消息在后台乱序处理完成，消费端只提交各分区连续完成的 offset。
+//! 进程崩溃或分区再均衡时，已接收但尚未处理完成的消息会被重新消费，不会丢失。
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Notify;
+
+/// 各分区的在途 offset
+#[derive(Default)]
+pub struct OffsetTracker {
+    partitions: Mutex<HashMap<i32, PartitionOffsets>>,
+    /// 所有在途消息处理完成
+    idle: Notify,
+}
+
+impl OffsetTracker {
+    /// 登记已接收的消息，处理结束后调用返回值的 [`OffsetCompletion::complete`]
+    pub fn track(self: &Arc<Self>, partition: i32, offset: i64) -> OffsetCompletion {
+        self.partitions
+            .lock()
+            .unwrap()
+            .entry(partition)
+            .or_default()
+            .track(offset);
+        OffsetCompletion {
+            tracker: self.clone(),
+            partition,
+            offset,
+        }
+    }
+
+    fn complete(&self, partition: i32, offset: i64) {
+        let mut partitions = self.partitions.lock().unwrap();
+        partitions.entry(partition).or_default().complete(offset);
+        if partitions.values().all(|p| p.in_flight.is_empty()) {
+            self.idle.notify_waiters();
+        }
+    }
+
+    /// 各分区可提交的 offset（较上次提交前进的分区）
+    pub fn commit_points(&self) -> Vec<(i32, i64)> {
+        self.partitions
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter_map(|(partition, offsets)| Some((*partition, offsets.commit_point()?)))
+            .collect()
+    }
+
+    /// 在途消息数
+    pub fn in_flight(&self) -> usize {
+        self.partitions
+            .lock()
+            .unwrap()
+            .values()
+            .map(|p| p.in_flight.len())
+            .sum()
+    }
+
+    /// 等待所有在途消息处理完成
+    pub async fn wait_idle(&self) {
+        loop {
+            let idle = self.idle.notified();
+            tokio::pin!(idle);
+            idle.as_mut().enable();
+            if self.in_flight() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+/// 单条消息的处理完成回执
+pub struct OffsetCompletion {
+    tracker: Arc<OffsetTracker>,
+    partition: i32,
+    offset: i64,
+}
+
+impl OffsetCompletion {
+    /// 标记消息处理完成（成功、失败或跳过均视为完成，与顺序模式一致）
+    pub fn complete(self) {
+        self.tracker.complete(self.partition, self.offset);
+    }
+}
+
+/// 分区内的在途 offset（乱序完成时只提交连续完成的部分）
+#[derive(Default)]
+struct PartitionOffsets {
+    in_flight: BTreeSet<i64>,
+    /// 已完成的最大 offset
+    max_done: Option<i64>,
+    /// 已提交的 offset（下一条待消费的位置）
+    committed: Option<i64>,
+}
+
+impl PartitionOffsets {
+    fn track(&mut self, offset: i64) {
+        self.in_flight.insert(offset);
+    }
+
+    fn complete(&mut self, offset: i64) {
+        self.in_flight.remove(&offset);
+        self.max_done = Some(self.max_done.map_or(offset, |done| done.max(offset)));
+    }
+
+    /// 可提交的 offset（较上次提交前进时返回）
+    fn commit_point(&mut self) -> Option<i64> {
+        let next = match self.in_flight.first() {
+            Some(offset) => *offset,
+            None => self.max_done? + 1,
+        };
+        if self.committed.is_some_and(|committed| committed >= next) {
+            return None;
+        }
+        self.committed = Some(next);
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_commits_only_contiguous_completed_offsets() {
+        let tracker = Arc::new(OffsetTracker::default());
+        let first = tracker.track(0, 10);
+        let second = tracker.track(0, 11);
+        let other = tracker.track(1, 5);
+
+        // offset 11 先完成，10 仍在处理中，分区 0 只能提交到 10（下一条待消费的位置）
+        second.complete();
+        let mut points = tracker.commit_points();
+        points.sort();
+        assert_eq!(points, [(0, 10), (1, 5)]);
+
+        first.complete();
+        other.complete();
+        let mut points = tracker.commit_points();
+        points.sort();
+        assert_eq!(points, [(0, 12), (1, 6)]);
+        // 未前进时不重复提交
+        assert!(tracker.commit_points().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle_returns_after_in_flight_completes() {
+        let tracker = Arc::new(OffsetTracker::default());
+        tracker.wait_idle().await;
+
+        let completion = tracker.track(0, 1);
+        assert_eq!(tracker.in_flight(), 1);
+        let waiter = tokio::spawn({
+            let tracker = tracker.clone();
+            async move { tracker.wait_idle().await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        completion.complete();
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tracker.commit_points(), [(0, 2)]);
+    }
+}
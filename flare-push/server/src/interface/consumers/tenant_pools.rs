@@ -0,0 +1,165 @@
+//! 租户隔离工作池
+//!
+//! 消费端解码出推送请求后按租户放入 [`TenantWorkerScheduler`]，由固定数量的 worker
+//! 按等级权重取出处理。单个租户的突发流量只会占满自己的队列与 worker，其队列满时任务
+//! 转入暂存区而不阻塞消费端，其他租户的扇出延迟不受影响；只有所有租户共享的暂存区也满时
+//! 消费端才等待。
+//!
+//! 任务携带消息的 [`OffsetCompletion`]，worker 处理完成后才推进该分区的提交位置。
+
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use flare_im_core::metrics::PushServerMetrics;
+use flare_proto::push::PushMessageRequest;
+use tokio::sync::Notify;
+use tracing::{debug, info, warn};
+
+use crate::application::handlers::PushCommandHandler;
+use crate::domain::model::TenantIsolationPolicy;
+use crate::domain::service::TenantWorkerScheduler;
+use crate::infrastructure::autoscale::DynamicConcurrencyLimiter;
+
+use super::consumer::process_request;
+use super::offsets::OffsetCompletion;
+
+struct PendingTask {
+    request: PushMessageRequest,
+    enqueued_at: Instant,
+    completion: OffsetCompletion,
+}
+
+pub struct TenantWorkerPools {
+    scheduler: Mutex<TenantWorkerScheduler<PendingTask>>,
+    /// 有新任务可派发（入队或 worker 释放）
+    work_ready: Notify,
+    /// 暂存区有空位
+    space_ready: Notify,
+    handler: Arc<PushCommandHandler>,
+    metrics: Arc<PushServerMetrics>,
+    /// 自动扩缩容：进程内并发度限制（与租户隔离叠加生效）
+    limiter: Option<Arc<DynamicConcurrencyLimiter>>,
+}
+
+impl TenantWorkerPools {
+    /// 创建工作池并启动 `total_workers` 个 worker
+    pub fn start(
+        policy: TenantIsolationPolicy,
+        handler: Arc<PushCommandHandler>,
+        metrics: Arc<PushServerMetrics>,
+        limiter: Option<Arc<DynamicConcurrencyLimiter>>,
+    ) -> Arc<Self> {
+        let total_workers = policy.total_workers.max(1);
+        info!(
+            total_workers,
+            tiers = policy.tiers.len(),
+            tenants = policy.tenant_tiers.len(),
+            default_tier = %policy.default_tier,
+            "Starting tenant isolated push worker pools"
+        );
+
+        let pools = Arc::new(Self {
+            scheduler: Mutex::new(TenantWorkerScheduler::new(policy)),
+            work_ready: Notify::new(),
+            space_ready: Notify::new(),
+            handler,
+            metrics,
+            limiter,
+        });
+        for _ in 0..total_workers {
+            let pools = pools.clone();
+            tokio::spawn(async move { pools.run_worker().await });
+        }
+        pools
+    }
+
+    /// 放入租户队列；队列已满时转入暂存区，暂存区也满时等待任务被取走
+    pub async fn submit(
+        &self,
+        tenant_id: &str,
+        request: PushMessageRequest,
+        completion: OffsetCompletion,
+    ) {
+        let mut task = PendingTask {
+            request,
+            enqueued_at: Instant::now(),
+            completion,
+        };
+        loop {
+            let space_ready = self.space_ready.notified();
+            tokio::pin!(space_ready);
+            space_ready.as_mut().enable();
+            {
+                let mut scheduler = self.scheduler.lock().unwrap();
+                match scheduler.push_or_spill(tenant_id, task) {
+                    Ok(spilled) => {
+                        self.record_depth(&scheduler, tenant_id);
+                        if spilled {
+                            debug!(tenant_id, "Tenant push queue is full, task spilled");
+                            self.metrics
+                                .tenant_queue_full_total
+                                .with_label_values(&[tenant_id])
+                                .inc();
+                        }
+                        break;
+                    }
+                    Err(rejected) => task = rejected,
+                }
+            }
+            warn!(
+                tenant_id,
+                "Tenant push spill buffer is full, waiting for capacity"
+            );
+            space_ready.await;
+        }
+        self.work_ready.notify_one();
+    }
+
+    async fn run_worker(&self) {
+        loop {
+            let work_ready = self.work_ready.notified();
+            tokio::pin!(work_ready);
+            work_ready.as_mut().enable();
+
+            let next = {
+                let mut scheduler = self.scheduler.lock().unwrap();
+                scheduler.dispatch().map(|(tenant_id, task)| {
+                    self.record_depth(&scheduler, &tenant_id);
+                    let tier = scheduler.tier_of(&tenant_id).to_string();
+                    (tenant_id, tier, task)
+                })
+            };
+            let Some((tenant_id, tier, task)) = next else {
+                work_ready.await;
+                continue;
+            };
+            self.space_ready.notify_one();
+            // 可能还有其他任务可派发，唤醒下一个空闲 worker
+            self.work_ready.notify_one();
+
+            let permit = match &self.limiter {
+                Some(limiter) => Some(limiter.acquire().await),
+                None => None,
+            };
+            process_request(self.handler.clone(), task.request).await;
+            drop(permit);
+            task.completion.complete();
+
+            self.metrics
+                .tenant_fanout_latency_seconds
+                .with_label_values(&[tenant_id.as_str(), tier.as_str()])
+                .observe(task.enqueued_at.elapsed().as_secs_f64());
+
+            self.scheduler.lock().unwrap().complete(&tenant_id);
+            // 租户释放 worker 后其积压任务可以继续派发
+            self.work_ready.notify_one();
+        }
+    }
+
+    fn record_depth(&self, scheduler: &TenantWorkerScheduler<PendingTask>, tenant_id: &str) {
+        self.metrics
+            .tenant_queue_depth
+            .with_label_values(&[tenant_id])
+            .set(scheduler.backlog(tenant_id) as i64);
+    }
+}
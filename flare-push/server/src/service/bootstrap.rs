@@ -4,7 +4,7 @@
 //! ACK 通过 Push Proxy → Kafka → Push Server 的方式传递
 
 use anyhow::Result;
-use tracing::info;
+use tracing::{info, warn};
 
 use flare_server_core::runtime::ServiceRuntime;
 
@@ -21,7 +21,7 @@ impl ApplicationBootstrap {
     /// 注意：Push Server 是纯消费者，不提供 gRPC 服务
     /// ACK 通过 Push Proxy → Kafka → Push Server 的方式传递
     pub async fn run() -> Result<()> {
-        use flare_im_core::load_config;
+        use flare_im_core::{load_config_with_validation, strict_mode};
 
         // 初始化 OpenTelemetry 追踪
         #[cfg(feature = "tracing")]
@@ -37,17 +37,61 @@ impl ApplicationBootstrap {
         }
 
         // 加载应用配置
-        let app_config = load_config(Some("./config"));
+        let app_config = load_config_with_validation(Some("./config"), strict_mode())?;
 
         // 使用 Wire 风格的依赖注入构建应用上下文
         let context = wire::initialize(app_config).await?;
 
+        // 监听配置变更
+        Self::spawn_config_watch();
+
         info!("ApplicationBootstrap created successfully");
 
         // 运行服务（纯消费者，只启动 Kafka 消费者）
         Self::run_with_context(context).await
     }
 
+    /// 监听配置变更，push_server 相关配置变化时重新读取推送配置
+    ///
+    /// 已建立的 Kafka 消费者与 Redis 连接不会重建，变更的连接参数需重启生效
+    fn spawn_config_watch() {
+        use flare_im_core::{ConfigManager, ConfigSection};
+
+        let watcher = match ConfigManager::watch(Some("./config")) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                warn!(error = %e, "Config hot-reload disabled");
+                return;
+            }
+        };
+        let mut changes = watcher.subscribe();
+        tokio::spawn(async move {
+            loop {
+                let event = match changes.recv().await {
+                    Ok(event) => event,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                if !event.affects_service("push_server")
+                    && !event.affects(&ConfigSection::Kafka)
+                    && !event.affects(&ConfigSection::Redis)
+                {
+                    continue;
+                }
+
+                let config = crate::config::PushServerConfig::from_app_config(&event.config);
+                info!(
+                    version = event.version,
+                    sections = ?event.sections,
+                    kafka_bootstrap = %config.kafka_bootstrap,
+                    push_retry_max_attempts = config.push_retry_max_attempts,
+                    ack_timeout_seconds = config.ack_timeout_seconds,
+                    "Push server configuration changed, connection settings apply after restart"
+                );
+            }
+        });
+    }
+
     /// 运行服务（带应用上下文）
     ///
     /// 注意：Push Server 是纯消费者，不提供 gRPC 服务
@@ -8,21 +8,26 @@ use anyhow::{Context as AnyhowContext, Result};
 
 use crate::application::handlers::PushCommandHandler;
 use crate::config::PushServerConfig;
-use crate::domain::service::PushDomainService;
+use crate::domain::model::AutoscaleMode;
+use crate::domain::service::{AutoscaleController, PushDomainService};
 use crate::infrastructure::ack_tracker::AckTracker;
+use crate::infrastructure::autoscale::{
+    ConsumerProgress, DynamicConcurrencyLimiter, KafkaConsumerLagMonitor, WebhookScaleEventSink,
+};
 use crate::infrastructure::cache::online_status_cache::CachedOnlineStatusRepository;
-use crate::infrastructure::cache::redis_online::OnlineStatusRepositoryImpl;
+use crate::infrastructure::gateway_redelivery::GatewayRedeliveryBuffer;
 use crate::infrastructure::message_state::MessageStateTracker;
 use crate::infrastructure::mq::kafka_task_publisher::KafkaPushTaskPublisher;
-use crate::infrastructure::session_client::ConversationServiceClient;
-use crate::infrastructure::signaling::SignalingOnlineClient;
-use crate::interface::consumers::{AckKafkaConsumer, PushKafkaConsumer};
+use crate::interface::consumers::{AckKafkaConsumer, PushKafkaConsumer, TenantWorkerPools};
 use deadpool_redis;
-use flare_im_core::ack::{AckModule, AckServiceConfig};
+use flare_im_core::ack::{AckArchiveConfig, AckModule, AckServiceConfig};
 use flare_im_core::gateway::{GatewayRouter, GatewayRouterConfig, GatewayRouterTrait};
 use flare_im_core::hooks::{HookDispatcher, HookRegistry};
 use flare_im_core::metrics::PushServerMetrics;
 use flare_im_core::service_names::{ACCESS_GATEWAY, CONVERSATION, SIGNALING_ONLINE, get_service_name};
+use flare_push_common::online::OnlineStatusRepositoryImpl;
+use flare_push_common::session_client::ConversationServiceClient;
+use flare_push_common::signaling::SignalingOnlineClient;
 
 /// 应用上下文 - 包含所有已初始化的服务
 ///
@@ -207,6 +212,10 @@ pub async fn initialize(
         cache_capacity: server_config.ack_cache_capacity,
         batch_interval_ms: server_config.ack_batch_interval_ms,
         batch_size: server_config.ack_batch_size,
+        archive: AckArchiveConfig {
+            database_url: server_config.ack_archive_database_url.clone(),
+            ..AckArchiveConfig::default()
+        },
         // 使用默认的业务场景配置（可以根据需要从配置文件读取）
         ..AckServiceConfig::default()
     };
@@ -235,7 +244,7 @@ pub async fn initialize(
     let metrics = Arc::new(PushServerMetrics::new());
 
     // 14. 构建领域服务
-    let domain_service = Arc::new(PushDomainService::new(
+    let mut domain_service = PushDomainService::new(
         server_config.clone(),
         online_repo.clone(),
         task_publisher.clone(),
@@ -244,21 +253,88 @@ pub async fn initialize(
         state_tracker.clone(),
         ack_tracker,
         metrics.clone(),
-    ));
+    );
+    if let Some(redelivery) = server_config.gateway_redelivery.as_ref() {
+        domain_service = domain_service.with_gateway_redelivery(Arc::new(
+            GatewayRedeliveryBuffer::new(redelivery.ttl, redelivery.max_per_gateway),
+        ));
+    }
+    let domain_service = Arc::new(domain_service);
+
+    // 14.1 网关重投递循环（网关恢复后重新投递缓冲的任务）
+    if let Some(redelivery) = server_config.gateway_redelivery.as_ref() {
+        tokio::spawn(
+            domain_service
+                .clone()
+                .run_gateway_redelivery(redelivery.probe_interval),
+        );
+        tracing::info!(
+            ttl_ms = redelivery.ttl.as_millis() as u64,
+            max_per_gateway = redelivery.max_per_gateway,
+            probe_interval_ms = redelivery.probe_interval.as_millis() as u64,
+            "Gateway redelivery buffer enabled"
+        );
+    }
 
     // 15. 构建命令处理器
     let command_handler = Arc::new(PushCommandHandler::new(domain_service.clone()));
 
     // 16. 构建推送消息消费者
-    let consumer = Arc::new(
-        PushKafkaConsumer::new(
-            server_config.clone(),
+    let mut consumer = PushKafkaConsumer::new(
+        server_config.clone(),
+        command_handler.clone(),
+        metrics.clone(),
+    )
+    .await
+    .with_context(|| "Failed to create Push Kafka consumer")?;
+
+    // 16.1 基于消费积压的自动扩缩容（可选）
+    let mut concurrency_limiter = None;
+    if let Some(policy) = server_config.autoscale.clone() {
+        let progress = ConsumerProgress::new();
+        let monitor = Arc::new(
+            KafkaConsumerLagMonitor::new(server_config.clone(), progress.clone())
+                .with_context(|| "Failed to create Kafka consumer lag monitor")?,
+        );
+        let mut controller = AutoscaleController::new(policy.clone(), monitor, metrics.clone());
+
+        let limiter = if policy.mode == AutoscaleMode::Concurrency {
+            let limiter = DynamicConcurrencyLimiter::new(policy.min_capacity);
+            controller = controller.with_concurrency(limiter.clone());
+            Some(limiter)
+        } else {
+            None
+        };
+
+        if let Some(url) = server_config.autoscale_webhook_url.clone() {
+            let sink = WebhookScaleEventSink::new(url, server_config.consumer_group.clone())
+                .with_context(|| "Failed to create scale event webhook")?;
+            controller = controller.with_sink(Arc::new(sink));
+        }
+
+        consumer = consumer.with_autoscale(limiter.clone(), progress);
+        concurrency_limiter = limiter;
+        tokio::spawn(controller.run());
+
+        tracing::info!(
+            mode = ?policy.mode,
+            min = policy.min_capacity,
+            max = policy.max_capacity,
+            "Push consumer autoscaling enabled"
+        );
+    }
+
+    // 16.2 租户隔离工作池（可选，并发模式的扩缩容限制器同时生效）
+    if let Some(policy) = server_config.tenant_isolation.clone() {
+        let tenant_pools = TenantWorkerPools::start(
+            policy,
             command_handler.clone(),
             metrics.clone(),
-        )
-        .await
-        .with_context(|| "Failed to create Push Kafka consumer")?,
-    );
+            concurrency_limiter,
+        );
+        consumer = consumer.with_tenant_pools(Some(tenant_pools));
+    }
+    let consumer = Arc::new(consumer);
 
     // 17. 构建 ACK 消费者
     let ack_consumer = Arc::new(
@@ -27,6 +27,8 @@ pub struct PushWorkerConfig {
     pub ack_timeout_seconds: u64,
     // 死信队列配置
     pub dlq_topic: String, // 死信队列topic: flare.im.push.dlq
+    // 失效 Token 清理配置
+    pub invalid_token_topic: Option<String>, // 失效设备Token事件topic（可选，未配置时只记录日志）
     // 推送渠道配置
     pub push_provider: String, // "fcm" | "apns" | "webpush" | "noop"
     // Gateway Router 配置
@@ -104,6 +106,9 @@ impl PushWorkerConfig {
             .ok()
             .unwrap_or_else(|| "flare.im.push.dlq".to_string());
 
+        // 失效 Token 清理配置
+        let invalid_token_topic = env::var("PUSH_WORKER_INVALID_TOKEN_TOPIC").ok();
+
         // 推送渠道配置
         let push_provider = env::var("PUSH_WORKER_PUSH_PROVIDER")
             .ok()
@@ -143,6 +148,7 @@ impl PushWorkerConfig {
             ack_topic,
             ack_timeout_seconds,
             dlq_topic,
+            invalid_token_topic,
             push_provider,
             access_gateway_service,
             hook_engine_endpoint,
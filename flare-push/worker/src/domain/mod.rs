@@ -4,8 +4,12 @@ pub mod model;
 pub mod repository;
 pub mod service;
 
-pub use model::{DispatchNotification, PushDispatchTask, RequestMetadata};
+pub use model::{
+    DispatchNotification, InvalidPushTokenEvent, ProviderError, ProviderOutcome, PushDispatchTask,
+    RequestMetadata,
+};
 pub use repository::{
-    AckPublisher, DlqPublisher, OfflinePushSender, OnlinePushSender, PushAckEvent,
+    AckPublisher, DlqPublisher, InvalidTokenPublisher, OfflinePushSender, OnlinePushSender,
+    PushAckEvent,
 };
 pub use service::PushDomainService;
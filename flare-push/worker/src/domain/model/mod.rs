@@ -41,3 +41,104 @@ pub struct PushDispatchTask {
     pub priority: i32,
     pub context: Option<RequestMetadata>,
 }
+
+/// 推送渠道响应的归一化结果
+///
+/// 各渠道（FCM/APNs/WebPush/厂商通道）的失败响应统一映射为该枚举，
+/// 由它决定是否重试、是否清理失效 Token，并作为指标的 `failure_reason` 标签
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProviderOutcome {
+    /// 临时故障（网络错误、超时、渠道 5xx），可重试
+    Retryable,
+    /// 设备 Token 失效（未注册、已过期、与发送者不匹配），需要清理 Token
+    TokenInvalid,
+    /// 推送内容超过渠道限制，重试无意义
+    PayloadTooLarge,
+    /// 被渠道限流，按渠道建议的间隔重试
+    Throttled,
+    /// 渠道鉴权失败（凭据缺失、过期或无效），需要修复配置
+    AuthFailure,
+    /// 其他不可重试的失败（请求参数错误、缺少 Token 等）
+    Rejected,
+}
+
+impl ProviderOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderOutcome::Retryable => "retryable",
+            ProviderOutcome::TokenInvalid => "token_invalid",
+            ProviderOutcome::PayloadTooLarge => "payload_too_large",
+            ProviderOutcome::Throttled => "throttled",
+            ProviderOutcome::AuthFailure => "auth_failure",
+            ProviderOutcome::Rejected => "rejected",
+        }
+    }
+
+    /// 是否可以重试
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ProviderOutcome::Retryable | ProviderOutcome::Throttled
+        )
+    }
+
+    /// 按 HTTP 状态码归一化（未提供错误码的渠道和厂商通道使用）
+    pub fn from_http_status(status: u16) -> Self {
+        match status {
+            401 | 403 => ProviderOutcome::AuthFailure,
+            404 | 410 => ProviderOutcome::TokenInvalid,
+            413 => ProviderOutcome::PayloadTooLarge,
+            429 => ProviderOutcome::Throttled,
+            408 | 500..=599 => ProviderOutcome::Retryable,
+            _ => ProviderOutcome::Rejected,
+        }
+    }
+}
+
+/// 推送渠道失败（携带归一化结果）
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{provider} push failed ({}): {detail}", outcome.as_str())]
+pub struct ProviderError {
+    /// 渠道名称（fcm, apns, webpush, online 等）
+    pub provider: &'static str,
+    pub outcome: ProviderOutcome,
+    /// 渠道建议的重试间隔（限流响应的 Retry-After）
+    pub retry_after: Option<std::time::Duration>,
+    /// 原始错误信息
+    pub detail: String,
+}
+
+impl ProviderError {
+    pub fn new(
+        provider: &'static str,
+        outcome: ProviderOutcome,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            provider,
+            outcome,
+            retry_after: None,
+            detail: detail.into(),
+        }
+    }
+
+    pub fn with_retry_after(mut self, retry_after: Option<std::time::Duration>) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+}
+
+/// 失效设备 Token 事件（发布后由 Token 所属服务删除该 Token）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvalidPushTokenEvent {
+    pub user_id: String,
+    pub tenant_id: Option<String>,
+    /// 渠道名称（fcm, apns, webpush）
+    pub provider: String,
+    /// 失效的 Token（WebPush 为订阅 JSON）
+    pub token: String,
+    pub platform: Option<String>,
+    /// 渠道返回的原始错误
+    pub reason: String,
+    pub timestamp: i64,
+}
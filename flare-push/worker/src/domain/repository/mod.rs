@@ -3,7 +3,7 @@
 use async_trait::async_trait;
 use flare_server_core::error::Result;
 
-use crate::domain::model::PushDispatchTask;
+use crate::domain::model::{InvalidPushTokenEvent, ProviderError, PushDispatchTask};
 
 /// 在线推送发送器（Repository）
 ///
@@ -17,10 +17,20 @@ pub trait OnlinePushSender: Send + Sync {
 
 /// 离线推送发送器（Repository）
 ///
+/// 失败时返回归一化的 [`ProviderError`]，由领域服务据此决定重试和 Token 清理
+///
 /// 注意：由于需要作为 trait 对象使用，保留 async-trait 宏
 #[async_trait]
 pub trait OfflinePushSender: Send + Sync {
-    async fn send(&self, task: &PushDispatchTask) -> Result<()>;
+    /// 渠道名称（fcm, apns, webpush, noop）
+    fn provider(&self) -> &'static str;
+
+    /// 任务元数据中存放该渠道设备 Token 的键
+    fn token_key(&self) -> Option<&'static str> {
+        None
+    }
+
+    async fn send(&self, task: &PushDispatchTask) -> std::result::Result<(), ProviderError>;
 }
 
 /// ACK 事件
@@ -48,3 +58,13 @@ pub trait AckPublisher: Send + Sync {
 pub trait DlqPublisher: Send + Sync {
     async fn publish_to_dlq(&self, task: &PushDispatchTask, error: &str) -> Result<()>;
 }
+
+/// 失效 Token 发布器（Repository）
+///
+/// 渠道返回 Token 失效时发布事件，由持有设备 Token 的服务删除，避免后续推送继续失败
+///
+/// 注意：由于需要作为 trait 对象使用，保留 async-trait 宏
+#[async_trait]
+pub trait InvalidTokenPublisher: Send + Sync {
+    async fn publish_invalid_token(&self, event: &InvalidPushTokenEvent) -> Result<()>;
+}
@@ -6,13 +6,16 @@ use std::sync::Arc;
 use flare_im_core::gateway::GatewayRouterTrait;
 use flare_im_core::hooks::HookDispatcher;
 use flare_im_core::metrics::PushWorkerMetrics;
-use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
+use flare_server_core::error::Result;
 use tracing::{error, info, instrument, warn};
 
 use crate::config::PushWorkerConfig;
-use crate::domain::model::PushDispatchTask;
+use crate::domain::model::{
+    InvalidPushTokenEvent, ProviderError, ProviderOutcome, PushDispatchTask,
+};
 use crate::domain::repository::{
-    AckPublisher, DlqPublisher, OfflinePushSender, OnlinePushSender, PushAckEvent,
+    AckPublisher, DlqPublisher, InvalidTokenPublisher, OfflinePushSender, OnlinePushSender,
+    PushAckEvent,
 };
 use crate::infrastructure::hook::{HookExecutor, build_delivery_context, build_delivery_event};
 use crate::infrastructure::retry::{RetryPolicy, RetryableError};
@@ -24,6 +27,7 @@ pub struct PushDomainService {
     offline_sender: Arc<dyn OfflinePushSender>,
     ack_publisher: Arc<dyn AckPublisher>,
     dlq_publisher: Arc<dyn DlqPublisher>,
+    token_publisher: Arc<dyn InvalidTokenPublisher>,
     gateway_router: Option<Arc<dyn GatewayRouterTrait>>,
     hooks: Arc<HookDispatcher>,
     hook_executor: Arc<HookExecutor>,
@@ -38,6 +42,7 @@ impl PushDomainService {
         offline_sender: Arc<dyn OfflinePushSender>,
         ack_publisher: Arc<dyn AckPublisher>,
         dlq_publisher: Arc<dyn DlqPublisher>,
+        token_publisher: Arc<dyn InvalidTokenPublisher>,
         gateway_router: Option<Arc<dyn GatewayRouterTrait>>,
         hooks: Arc<HookDispatcher>,
         hook_executor: Arc<HookExecutor>,
@@ -56,6 +61,7 @@ impl PushDomainService {
             offline_sender,
             ack_publisher,
             dlq_publisher,
+            token_publisher,
             gateway_router,
             hooks,
             hook_executor,
@@ -127,35 +133,42 @@ impl PushDomainService {
                 Ok(())
             }
             Err(e) => {
-                // 推送失败，上报ACK并发送到死信队列
+                // 推送失败，上报ACK并按归一化结果处理（清理失效Token或发送到死信队列）
                 let error_str = e.to_string();
+                let reason = e.outcome.as_str();
                 error!(
                     message_id = %task.message_id,
                     user_id = %task.user_id,
+                    provider = e.provider,
+                    outcome = reason,
                     error = %error_str,
                     "Push failed after retries"
                 );
 
-                // 记录离线推送失败（仅离线推送）
+                // 记录离线推送失败（仅离线推送，failure_reason 为归一化结果）
                 if !task.online {
-                    // 注意：metrics 的 label_values 需要 &str，error_str 已经是 String
-                    let error_label = error_str.as_str();
                     self.metrics
                         .offline_push_failure_total
-                        .with_label_values(&[platform, error_label, tenant_id])
+                        .with_label_values(&[platform, reason, tenant_id])
                         .inc();
                 }
 
                 // 上报失败ACK
                 let _ = self.publish_ack(&task, false, Some(&error_str)).await;
 
+                // Token 失效：通知清理，重放也不会成功，不进入死信队列
+                if e.outcome == ProviderOutcome::TokenInvalid {
+                    self.publish_invalid_token(&task, &e).await;
+                    return Ok(());
+                }
+
                 // 发送到死信队列
                 self.dlq_publisher.publish_to_dlq(&task, &error_str).await?;
 
                 // 记录死信队列消息数
                 self.metrics
                     .dlq_messages_total
-                    .with_label_values(&[error_str.as_str(), tenant_id])
+                    .with_label_values(&[reason, tenant_id])
                     .inc();
 
                 Ok(()) // 返回Ok，避免重复处理
@@ -203,32 +216,37 @@ impl PushDomainService {
 
     /// 执行在线推送（通过 Gateway Router）
     #[instrument(skip(self))]
-    async fn execute_online_push(&self, task: &PushDispatchTask) -> Result<()> {
+    async fn execute_online_push(
+        &self,
+        task: &PushDispatchTask,
+    ) -> std::result::Result<(), ProviderError> {
         // 如果有 Gateway Router，使用它路由推送
         if let Some(router) = &self.gateway_router {
             // 需要查询用户的 gateway_id（从 Signaling Online 服务）
             // 简化处理：假设 task.metadata 中包含 gateway_id
             let gateway_id = task.metadata.get("gateway_id").ok_or_else(|| {
-                ErrorBuilder::new(
-                    ErrorCode::InvalidParameter,
+                ProviderError::new(
+                    "online",
+                    ProviderOutcome::Rejected,
                     "gateway_id not found in task metadata",
                 )
-                .build_error()
             })?;
 
             // 构建 PushMessageRequest
-            let push_request = self.build_push_message_request(task)?;
+            let push_request = self.build_push_message_request(task).map_err(|e| {
+                ProviderError::new("online", ProviderOutcome::Rejected, e.to_string())
+            })?;
 
             // 通过 Gateway Router 路由推送
             match router.route_push_message(gateway_id, push_request).await {
                 Ok(response) => {
                     // 检查推送结果
                     if response.results.is_empty() {
-                        return Err(ErrorBuilder::new(
-                            ErrorCode::ServiceUnavailable,
+                        return Err(ProviderError::new(
+                            "online",
+                            ProviderOutcome::Retryable,
                             "No push results returned",
-                        )
-                        .build_error());
+                        ));
                     }
 
                     // 检查是否有失败的用户
@@ -265,59 +283,107 @@ impl PushDomainService {
             }
         } else {
             // 没有 Gateway Router，使用 OnlinePushSender（可能是 Noop）
-            self.execute_with_retry(|| self.online_sender.send(task))
-                .await
-                .map_err(|e| {
-                    ErrorBuilder::new(ErrorCode::ServiceUnavailable, "Online push failed")
-                        .details(e)
-                        .build_error()
+            self.execute_with_retry(task, || async {
+                self.online_sender.send(task).await.map_err(|e| {
+                    // 在线通道没有渠道错误码，沿用错误信息判断是否可重试
+                    let detail = e.to_string();
+                    let outcome = if anyhow::Error::from(e).is_retryable() {
+                        ProviderOutcome::Retryable
+                    } else {
+                        ProviderOutcome::Rejected
+                    };
+                    ProviderError::new("online", outcome, detail)
                 })
+            })
+            .await
         }
     }
 
     /// 执行离线推送（通过外部渠道）
     #[instrument(skip(self))]
-    async fn execute_offline_push(&self, task: &PushDispatchTask) -> Result<()> {
-        self.execute_with_retry(|| self.offline_sender.send(task))
+    async fn execute_offline_push(
+        &self,
+        task: &PushDispatchTask,
+    ) -> std::result::Result<(), ProviderError> {
+        self.execute_with_retry(task, || self.offline_sender.send(task))
             .await
-            .map_err(|e| {
-                ErrorBuilder::new(ErrorCode::ServiceUnavailable, "Offline push failed")
-                    .details(e)
-                    .build_error()
-            })
     }
 
     /// 带重试的执行推送
-    async fn execute_with_retry<F, Fut>(&self, mut f: F) -> std::result::Result<(), String>
+    ///
+    /// 只重试归一化结果为可重试（临时故障、限流）的失败；限流时优先使用渠道返回的
+    /// `Retry-After`（不超过最大延迟），其余失败立即返回
+    async fn execute_with_retry<F, Fut>(
+        &self,
+        task: &PushDispatchTask,
+        mut f: F,
+    ) -> std::result::Result<(), ProviderError>
     where
         F: FnMut() -> Fut,
-        Fut: std::future::Future<Output = Result<()>>,
+        Fut: std::future::Future<Output = std::result::Result<(), ProviderError>>,
     {
+        let tenant_id = task.tenant_id.as_deref().unwrap_or("unknown");
+        let platform = task
+            .metadata
+            .get("platform")
+            .map(|s| s.as_str())
+            .unwrap_or("unknown");
         let mut attempt = 0;
-        let mut last_error = None;
 
-        while attempt < self.retry_policy.max_attempts {
+        loop {
             match f().await {
                 Ok(_) => return Ok(()),
                 Err(e) => {
-                    let error_str = e.to_string();
-                    let err = anyhow::Error::from(e);
-                    if err.is_retryable() && attempt < self.retry_policy.max_attempts - 1 {
-                        // 可重试的错误，等待后重试
-                        let delay = self.retry_policy.calculate_delay(attempt);
-                        tokio::time::sleep(delay).await;
-                        last_error = Some(error_str);
-                        attempt += 1;
-                        continue;
-                    } else {
+                    if !e.outcome.is_retryable() || attempt + 1 >= self.retry_policy.max_attempts {
                         // 永久失败或达到最大重试次数
-                        return Err(error_str);
+                        return Err(e);
                     }
+
+                    // 可重试的错误，等待后重试
+                    let delay = match e.retry_after {
+                        Some(retry_after) => retry_after.min(std::time::Duration::from_millis(
+                            self.retry_policy.max_delay_ms,
+                        )),
+                        None => self.retry_policy.calculate_delay(attempt),
+                    };
+                    self.metrics
+                        .push_retry_total
+                        .with_label_values(&[platform, e.outcome.as_str(), tenant_id])
+                        .inc();
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
                 }
             }
         }
+    }
+
+    /// 发布失效 Token 事件（失败只记录日志，不影响推送结果处理）
+    async fn publish_invalid_token(&self, task: &PushDispatchTask, error: &ProviderError) {
+        let Some(token) = self
+            .offline_sender
+            .token_key()
+            .and_then(|key| task.metadata.get(key))
+        else {
+            return;
+        };
 
-        Err(last_error.unwrap_or_else(|| "Max retries exceeded".to_string()))
+        let event = InvalidPushTokenEvent {
+            user_id: task.user_id.clone(),
+            tenant_id: task.tenant_id.clone(),
+            provider: error.provider.to_string(),
+            token: token.clone(),
+            platform: task.metadata.get("platform").cloned(),
+            reason: error.detail.clone(),
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+        if let Err(e) = self.token_publisher.publish_invalid_token(&event).await {
+            warn!(
+                user_id = %task.user_id,
+                provider = error.provider,
+                error = %e,
+                "Failed to publish invalid push token event"
+            );
+        }
     }
 
     /// 构建 PushMessageRequest（用于 Gateway Router）
@@ -420,6 +486,7 @@ impl PushDomainService {
             offline_sender: Arc::clone(&self.offline_sender),
             ack_publisher: Arc::clone(&self.ack_publisher),
             dlq_publisher: Arc::clone(&self.dlq_publisher),
+            token_publisher: Arc::clone(&self.token_publisher),
             gateway_router: self.gateway_router.as_ref().map(|r| Arc::clone(r)),
             hooks: Arc::clone(&self.hooks),
             hook_executor: Arc::clone(&self.hook_executor),
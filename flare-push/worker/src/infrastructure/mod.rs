@@ -6,9 +6,11 @@ pub mod hook;
 pub mod offline;
 pub mod online;
 pub mod retry;
+pub mod token_publisher;
 
 pub use ack_publisher::{KafkaAckPublisher, NoopAckPublisher};
 pub use dlq_publisher::KafkaDlqPublisher;
 pub use offline::{NoopOfflinePushSender, OfflinePushSenderRef, build_offline_sender};
 pub use online::{NoopOnlinePushSender, OnlinePushSenderRef, build_online_sender};
 pub use retry::{RetryPolicy, RetryableError, execute_with_retry};
+pub use token_publisher::{KafkaInvalidTokenPublisher, NoopInvalidTokenPublisher};
@@ -1,4 +1,5 @@
 pub mod noop;
+pub mod response;
 
 use async_trait::async_trait;
 use reqwest::Client;
@@ -6,9 +7,11 @@ use serde_json::Value;
 use std::sync::Arc;
 
 use crate::config::PushWorkerConfig;
-use crate::domain::model::PushDispatchTask;
+use crate::domain::model::{ProviderError, ProviderOutcome, PushDispatchTask};
 use crate::domain::repository::OfflinePushSender;
-use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
+use response::{
+    classify_apns, classify_fcm, classify_webpush, failure_from_response, failure_from_transport,
+};
 
 pub type OfflinePushSenderRef = Arc<dyn OfflinePushSender>;
 
@@ -38,14 +41,22 @@ impl FcmOfflinePushSender {
 
 #[async_trait]
 impl OfflinePushSender for FcmOfflinePushSender {
-    async fn send(&self, task: &PushDispatchTask) -> Result<()> {
+    fn provider(&self) -> &'static str {
+        "fcm"
+    }
+
+    fn token_key(&self) -> Option<&'static str> {
+        Some("fcm_token")
+    }
+
+    async fn send(&self, task: &PushDispatchTask) -> Result<(), ProviderError> {
         // 获取FCM配置信息（从task.metadata中获取）
         let fcm_token = task.metadata.get("fcm_token").ok_or_else(|| {
-            ErrorBuilder::new(
-                ErrorCode::InvalidParameter,
+            ProviderError::new(
+                "fcm",
+                ProviderOutcome::Rejected,
                 "FCM token not found in task metadata",
             )
-            .build_error()
         })?;
 
         // 构建FCM推送消息
@@ -67,11 +78,11 @@ impl OfflinePushSender for FcmOfflinePushSender {
         // 实际调用FCM API发送推送
         // 这里应该使用HTTP客户端发送POST请求到FCM服务器
         let fcm_api_key = std::env::var("FCM_API_KEY").map_err(|_| {
-            ErrorBuilder::new(
-                ErrorCode::ConfigurationError,
+            ProviderError::new(
+                "fcm",
+                ProviderOutcome::AuthFailure,
                 "FCM_API_KEY environment variable not set",
             )
-            .build_error()
         })?;
 
         let response = self
@@ -81,14 +92,7 @@ impl OfflinePushSender for FcmOfflinePushSender {
             .json(&message)
             .send()
             .await
-            .map_err(|e| {
-                ErrorBuilder::new(
-                    ErrorCode::ServiceUnavailable,
-                    "Failed to send FCM push notification",
-                )
-                .details(e.to_string())
-                .build_error()
-            })?;
+            .map_err(|e| failure_from_transport("fcm", e))?;
 
         if response.status().is_success() {
             tracing::info!(
@@ -97,22 +101,15 @@ impl OfflinePushSender for FcmOfflinePushSender {
                 "FCM offline push sent successfully"
             );
         } else {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
+            let error = failure_from_response("fcm", response, classify_fcm).await;
             tracing::error!(
                 user_id = %task.user_id,
                 message_id = %task.message_id,
-                error = %error_text,
+                outcome = error.outcome.as_str(),
+                error = %error.detail,
                 "Failed to send FCM offline push"
             );
-            return Err(ErrorBuilder::new(
-                ErrorCode::ServiceUnavailable,
-                "FCM push notification failed",
-            )
-            .details(error_text)
-            .build_error());
+            return Err(error);
         }
 
         Ok(())
@@ -134,14 +131,22 @@ impl ApnsOfflinePushSender {
 
 #[async_trait]
 impl OfflinePushSender for ApnsOfflinePushSender {
-    async fn send(&self, task: &PushDispatchTask) -> Result<()> {
+    fn provider(&self) -> &'static str {
+        "apns"
+    }
+
+    fn token_key(&self) -> Option<&'static str> {
+        Some("apns_token")
+    }
+
+    async fn send(&self, task: &PushDispatchTask) -> Result<(), ProviderError> {
         // 获取APNs配置信息（从task.metadata中获取）
         let apns_token = task.metadata.get("apns_token").ok_or_else(|| {
-            ErrorBuilder::new(
-                ErrorCode::InvalidParameter,
+            ProviderError::new(
+                "apns",
+                ProviderOutcome::Rejected,
                 "APNs token not found in task metadata",
             )
-            .build_error()
         })?;
 
         // 构建APNs推送消息
@@ -162,11 +167,11 @@ impl OfflinePushSender for ApnsOfflinePushSender {
         // 实际调用APNs API发送推送
         // 这里应该使用HTTP/2客户端发送POST请求到APNs服务器
         let apns_auth_key = std::env::var("APNS_AUTH_KEY").map_err(|_| {
-            ErrorBuilder::new(
-                ErrorCode::ConfigurationError,
+            ProviderError::new(
+                "apns",
+                ProviderOutcome::AuthFailure,
                 "APNS_AUTH_KEY environment variable not set",
             )
-            .build_error()
         })?;
 
         let response = self
@@ -176,14 +181,7 @@ impl OfflinePushSender for ApnsOfflinePushSender {
             .json(&message)
             .send()
             .await
-            .map_err(|e| {
-                ErrorBuilder::new(
-                    ErrorCode::ServiceUnavailable,
-                    "Failed to send APNs push notification",
-                )
-                .details(e.to_string())
-                .build_error()
-            })?;
+            .map_err(|e| failure_from_transport("apns", e))?;
 
         if response.status().is_success() {
             tracing::info!(
@@ -192,22 +190,15 @@ impl OfflinePushSender for ApnsOfflinePushSender {
                 "APNs offline push sent successfully"
             );
         } else {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
+            let error = failure_from_response("apns", response, classify_apns).await;
             tracing::error!(
                 user_id = %task.user_id,
                 message_id = %task.message_id,
-                error = %error_text,
+                outcome = error.outcome.as_str(),
+                error = %error.detail,
                 "Failed to send APNs offline push"
             );
-            return Err(ErrorBuilder::new(
-                ErrorCode::ServiceUnavailable,
-                "APNs push notification failed",
-            )
-            .details(error_text)
-            .build_error());
+            return Err(error);
         }
 
         Ok(())
@@ -229,35 +220,42 @@ impl WebPushOfflinePushSender {
 
 #[async_trait]
 impl OfflinePushSender for WebPushOfflinePushSender {
-    async fn send(&self, task: &PushDispatchTask) -> Result<()> {
+    fn provider(&self) -> &'static str {
+        "webpush"
+    }
+
+    fn token_key(&self) -> Option<&'static str> {
+        Some("webpush_subscription")
+    }
+
+    async fn send(&self, task: &PushDispatchTask) -> Result<(), ProviderError> {
         // 获取WebPush配置信息（从task.metadata中获取）
         let subscription = task.metadata.get("webpush_subscription").ok_or_else(|| {
-            ErrorBuilder::new(
-                ErrorCode::InvalidParameter,
+            ProviderError::new(
+                "webpush",
+                ProviderOutcome::Rejected,
                 "WebPush subscription not found in task metadata",
             )
-            .build_error()
         })?;
 
-        // 解析订阅信息
+        // 解析订阅信息（订阅格式错误视为失效，交由 Token 清理）
         let subscription_value: Value = serde_json::from_str(subscription).map_err(|e| {
-            ErrorBuilder::new(
-                ErrorCode::InvalidParameter,
-                "Invalid WebPush subscription JSON",
+            ProviderError::new(
+                "webpush",
+                ProviderOutcome::TokenInvalid,
+                format!("Invalid WebPush subscription JSON: {}", e),
             )
-            .details(e.to_string())
-            .build_error()
         })?;
 
         let endpoint = subscription_value
             .get("endpoint")
             .and_then(|v| v.as_str())
             .ok_or_else(|| {
-                ErrorBuilder::new(
-                    ErrorCode::InvalidParameter,
+                ProviderError::new(
+                    "webpush",
+                    ProviderOutcome::TokenInvalid,
                     "WebPush subscription missing endpoint",
                 )
-                .build_error()
             })?;
 
         // 构建WebPush推送消息
@@ -281,14 +279,7 @@ impl OfflinePushSender for WebPushOfflinePushSender {
             .json(&message)
             .send()
             .await
-            .map_err(|e| {
-                ErrorBuilder::new(
-                    ErrorCode::ServiceUnavailable,
-                    "Failed to send WebPush notification",
-                )
-                .details(e.to_string())
-                .build_error()
-            })?;
+            .map_err(|e| failure_from_transport("webpush", e))?;
 
         if response.status().is_success() {
             tracing::info!(
@@ -297,22 +288,15 @@ impl OfflinePushSender for WebPushOfflinePushSender {
                 "WebPush offline push sent successfully"
             );
         } else {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
+            let error = failure_from_response("webpush", response, classify_webpush).await;
             tracing::error!(
                 user_id = %task.user_id,
                 message_id = %task.message_id,
-                error = %error_text,
+                outcome = error.outcome.as_str(),
+                error = %error.detail,
                 "Failed to send WebPush offline push"
             );
-            return Err(ErrorBuilder::new(
-                ErrorCode::ServiceUnavailable,
-                "WebPush notification failed",
-            )
-            .details(error_text)
-            .build_error());
+            return Err(error);
         }
 
         Ok(())
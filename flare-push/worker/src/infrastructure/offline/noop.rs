@@ -1,16 +1,19 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use flare_server_core::error::Result;
 use tracing::info;
 
-use crate::domain::model::PushDispatchTask;
+use crate::domain::model::{ProviderError, PushDispatchTask};
 
 pub struct NoopOfflinePushSender;
 
 #[async_trait]
 impl crate::domain::repository::OfflinePushSender for NoopOfflinePushSender {
-    async fn send(&self, task: &PushDispatchTask) -> Result<()> {
+    fn provider(&self) -> &'static str {
+        "noop"
+    }
+
+    async fn send(&self, task: &PushDispatchTask) -> Result<(), ProviderError> {
         info!(user_id = %task.user_id, "noop offline push sender invoked");
         Ok(())
     }
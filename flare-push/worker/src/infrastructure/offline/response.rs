@@ -0,0 +1,286 @@
+//! 推送渠道响应归一化
+//!
+//! 将各渠道的 HTTP 状态码和错误码映射为 [`ProviderOutcome`]：
+//! - FCM：解析 `error.details[].errorCode`（UNREGISTERED、QUOTA_EXCEEDED 等）
+//! - APNs：解析响应体中的 `reason`（BadDeviceToken、TooManyRequests 等）
+//! - WebPush 和厂商通道：按 HTTP 状态码映射
+
+use std::time::Duration;
+
+use reqwest::Response;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde_json::Value;
+
+use crate::domain::model::{ProviderError, ProviderOutcome};
+
+/// 将失败的 HTTP 响应转换为 [`ProviderError`]
+pub async fn failure_from_response(
+    provider: &'static str,
+    response: Response,
+    classify: fn(u16, &str) -> ProviderOutcome,
+) -> ProviderError {
+    let status = response.status().as_u16();
+    let retry_after = parse_retry_after(response.headers());
+    let body = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_string());
+    let outcome = classify(status, &body);
+    ProviderError::new(provider, outcome, format!("HTTP {}: {}", status, body))
+        .with_retry_after(retry_after)
+}
+
+/// 将请求发送失败（连接、超时等）转换为 [`ProviderError`]
+pub fn failure_from_transport(provider: &'static str, err: reqwest::Error) -> ProviderError {
+    let outcome = if err.is_builder() {
+        // 请求构建失败（如 WebPush endpoint 非法）重试也不会成功
+        ProviderOutcome::Rejected
+    } else {
+        ProviderOutcome::Retryable
+    };
+    ProviderError::new(provider, outcome, err.to_string())
+}
+
+/// FCM HTTP v1 响应归一化
+pub fn classify_fcm(status: u16, body: &str) -> ProviderOutcome {
+    let error = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|v| v.get("error").cloned());
+    let error_code = error
+        .as_ref()
+        .and_then(|e| e.get("details"))
+        .and_then(|d| d.as_array())
+        .and_then(|details| {
+            details
+                .iter()
+                .find_map(|d| d.get("errorCode").and_then(|c| c.as_str()))
+        })
+        .map(str::to_string);
+    let message = error
+        .as_ref()
+        .and_then(|e| e.get("message"))
+        .and_then(|m| m.as_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match error_code.as_deref() {
+        Some("UNREGISTERED") | Some("SENDER_ID_MISMATCH") => ProviderOutcome::TokenInvalid,
+        Some("QUOTA_EXCEEDED") => ProviderOutcome::Throttled,
+        Some("THIRD_PARTY_AUTH_ERROR") => ProviderOutcome::AuthFailure,
+        Some("UNAVAILABLE") | Some("INTERNAL") => ProviderOutcome::Retryable,
+        Some("INVALID_ARGUMENT") => {
+            if message.contains("too big") || message.contains("too large") {
+                ProviderOutcome::PayloadTooLarge
+            } else if message.contains("registration token") {
+                ProviderOutcome::TokenInvalid
+            } else {
+                ProviderOutcome::Rejected
+            }
+        }
+        _ => ProviderOutcome::from_http_status(status),
+    }
+}
+
+/// APNs 响应归一化
+pub fn classify_apns(status: u16, body: &str) -> ProviderOutcome {
+    let reason = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|v| v.get("reason").and_then(|r| r.as_str()).map(str::to_string));
+
+    match reason.as_deref() {
+        Some("BadDeviceToken")
+        | Some("DeviceTokenNotForTopic")
+        | Some("Unregistered")
+        | Some("ExpiredToken") => ProviderOutcome::TokenInvalid,
+        Some("PayloadTooLarge") => ProviderOutcome::PayloadTooLarge,
+        Some("TooManyRequests") | Some("TooManyProviderTokenUpdates") => ProviderOutcome::Throttled,
+        Some("InvalidProviderToken")
+        | Some("MissingProviderToken")
+        | Some("ExpiredProviderToken")
+        | Some("BadCertificate")
+        | Some("BadCertificateEnvironment")
+        | Some("Forbidden") => ProviderOutcome::AuthFailure,
+        Some("InternalServerError") | Some("ServiceUnavailable") | Some("Shutdown") => {
+            ProviderOutcome::Retryable
+        }
+        _ => ProviderOutcome::from_http_status(status),
+    }
+}
+
+/// WebPush（RFC 8030）响应归一化：404/410 表示订阅失效
+pub fn classify_webpush(status: u16, _body: &str) -> ProviderOutcome {
+    ProviderOutcome::from_http_status(status)
+}
+
+/// 厂商通道响应归一化（未单独适配错误码的渠道按 HTTP 状态码映射）
+pub fn classify_vendor(status: u16, _body: &str) -> ProviderOutcome {
+    ProviderOutcome::from_http_status(status)
+}
+
+/// 解析 `Retry-After`（仅支持秒数形式）
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn fcm_error(code: &str, message: &str) -> String {
+        serde_json::json!({
+            "error": {
+                "message": message,
+                "details": [{ "errorCode": code }]
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_classify_fcm() {
+        let cases = [
+            (
+                404,
+                fcm_error("UNREGISTERED", ""),
+                ProviderOutcome::TokenInvalid,
+            ),
+            (
+                403,
+                fcm_error("SENDER_ID_MISMATCH", ""),
+                ProviderOutcome::TokenInvalid,
+            ),
+            (
+                429,
+                fcm_error("QUOTA_EXCEEDED", ""),
+                ProviderOutcome::Throttled,
+            ),
+            (
+                401,
+                fcm_error("THIRD_PARTY_AUTH_ERROR", ""),
+                ProviderOutcome::AuthFailure,
+            ),
+            (
+                503,
+                fcm_error("UNAVAILABLE", ""),
+                ProviderOutcome::Retryable,
+            ),
+            (500, fcm_error("INTERNAL", ""), ProviderOutcome::Retryable),
+            (
+                400,
+                fcm_error("INVALID_ARGUMENT", "Message is too big"),
+                ProviderOutcome::PayloadTooLarge,
+            ),
+            (
+                400,
+                fcm_error(
+                    "INVALID_ARGUMENT",
+                    "The registration token is not a valid FCM registration token",
+                ),
+                ProviderOutcome::TokenInvalid,
+            ),
+            (
+                400,
+                fcm_error("INVALID_ARGUMENT", "Invalid JSON payload"),
+                ProviderOutcome::Rejected,
+            ),
+            // 无法解析错误码时按状态码归一化
+            (
+                502,
+                "<html>Bad Gateway</html>".to_string(),
+                ProviderOutcome::Retryable,
+            ),
+            (401, String::new(), ProviderOutcome::AuthFailure),
+        ];
+        for (status, body, expected) in cases {
+            assert_eq!(classify_fcm(status, &body), expected, "{} {}", status, body);
+        }
+    }
+
+    #[test]
+    fn test_classify_apns() {
+        let cases = [
+            (400, "BadDeviceToken", ProviderOutcome::TokenInvalid),
+            (400, "DeviceTokenNotForTopic", ProviderOutcome::TokenInvalid),
+            (410, "Unregistered", ProviderOutcome::TokenInvalid),
+            (410, "ExpiredToken", ProviderOutcome::TokenInvalid),
+            (413, "PayloadTooLarge", ProviderOutcome::PayloadTooLarge),
+            (429, "TooManyRequests", ProviderOutcome::Throttled),
+            (
+                429,
+                "TooManyProviderTokenUpdates",
+                ProviderOutcome::Throttled,
+            ),
+            (403, "InvalidProviderToken", ProviderOutcome::AuthFailure),
+            (403, "ExpiredProviderToken", ProviderOutcome::AuthFailure),
+            (
+                403,
+                "BadCertificateEnvironment",
+                ProviderOutcome::AuthFailure,
+            ),
+            (500, "InternalServerError", ProviderOutcome::Retryable),
+            (503, "ServiceUnavailable", ProviderOutcome::Retryable),
+            (503, "Shutdown", ProviderOutcome::Retryable),
+            (400, "BadTopic", ProviderOutcome::Rejected),
+        ];
+        for (status, reason, expected) in cases {
+            let body = serde_json::json!({ "reason": reason }).to_string();
+            assert_eq!(classify_apns(status, &body), expected, "{}", reason);
+        }
+        assert_eq!(classify_apns(503, ""), ProviderOutcome::Retryable);
+    }
+
+    #[test]
+    fn test_classify_by_http_status() {
+        let cases = [
+            (401, ProviderOutcome::AuthFailure),
+            (403, ProviderOutcome::AuthFailure),
+            (404, ProviderOutcome::TokenInvalid),
+            (410, ProviderOutcome::TokenInvalid),
+            (413, ProviderOutcome::PayloadTooLarge),
+            (429, ProviderOutcome::Throttled),
+            (408, ProviderOutcome::Retryable),
+            (500, ProviderOutcome::Retryable),
+            (599, ProviderOutcome::Retryable),
+            (400, ProviderOutcome::Rejected),
+            (422, ProviderOutcome::Rejected),
+        ];
+        for (status, expected) in cases {
+            assert_eq!(classify_webpush(status, ""), expected, "{}", status);
+            assert_eq!(classify_vendor(status, "ignored"), expected, "{}", status);
+        }
+    }
+
+    #[test]
+    fn test_only_transient_outcomes_are_retried() {
+        assert!(ProviderOutcome::Retryable.is_retryable());
+        assert!(ProviderOutcome::Throttled.is_retryable());
+        for outcome in [
+            ProviderOutcome::TokenInvalid,
+            ProviderOutcome::PayloadTooLarge,
+            ProviderOutcome::AuthFailure,
+            ProviderOutcome::Rejected,
+        ] {
+            assert!(!outcome.is_retryable(), "{}", outcome.as_str());
+        }
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static(" 30 "));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(30)));
+        // HTTP 日期形式不支持
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+}
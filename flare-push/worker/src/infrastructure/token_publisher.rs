@@ -0,0 +1,112 @@
+//! 失效 Token 发布器（基础设施层实现）
+
+use async_trait::async_trait;
+use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
+use flare_server_core::kafka::build_kafka_producer;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::domain::model::InvalidPushTokenEvent;
+
+/// Kafka 失效 Token 发布器（以 user_id 作为 key，保证同一用户的事件有序）
+pub struct KafkaInvalidTokenPublisher {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl KafkaInvalidTokenPublisher {
+    pub fn new(bootstrap_servers: &str, topic: String) -> Result<Arc<Self>> {
+        struct SimpleProducerConfig {
+            bootstrap: String,
+        }
+
+        impl flare_server_core::kafka::KafkaProducerConfig for SimpleProducerConfig {
+            fn kafka_bootstrap(&self) -> &str {
+                &self.bootstrap
+            }
+
+            fn message_timeout_ms(&self) -> u64 {
+                5000 // 默认 5 秒
+            }
+        }
+
+        let config = SimpleProducerConfig {
+            bootstrap: bootstrap_servers.to_string(),
+        };
+
+        let producer =
+            build_kafka_producer(&config as &dyn flare_server_core::kafka::KafkaProducerConfig)
+                .map_err(|e| {
+                    ErrorBuilder::new(
+                        ErrorCode::ServiceUnavailable,
+                        "Failed to create Kafka producer",
+                    )
+                    .details(e.to_string())
+                    .build_error()
+                })?;
+
+        Ok(Arc::new(Self { producer, topic }))
+    }
+}
+
+#[async_trait]
+impl crate::domain::repository::InvalidTokenPublisher for KafkaInvalidTokenPublisher {
+    async fn publish_invalid_token(&self, event: &InvalidPushTokenEvent) -> Result<()> {
+        let payload = serde_json::to_vec(event).map_err(|e| {
+            ErrorBuilder::new(
+                ErrorCode::InternalError,
+                "Failed to serialize invalid token event",
+            )
+            .details(e.to_string())
+            .build_error()
+        })?;
+
+        let record = FutureRecord::to(&self.topic)
+            .key(&event.user_id)
+            .payload(&payload);
+
+        match self
+            .producer
+            .send(record, std::time::Duration::from_secs(0))
+            .await
+        {
+            Ok(_) => {
+                info!(
+                    user_id = %event.user_id,
+                    provider = %event.provider,
+                    "Invalid push token event published"
+                );
+                Ok(())
+            }
+            Err((e, _)) => {
+                error!(
+                    user_id = %event.user_id,
+                    ?e,
+                    "Failed to publish invalid push token event"
+                );
+                Err(ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    "Failed to publish invalid push token event",
+                )
+                .details(e.to_string())
+                .build_error())
+            }
+        }
+    }
+}
+
+/// Noop 失效 Token 发布器（未配置 Topic 时只记录日志）
+pub struct NoopInvalidTokenPublisher;
+
+#[async_trait]
+impl crate::domain::repository::InvalidTokenPublisher for NoopInvalidTokenPublisher {
+    async fn publish_invalid_token(&self, event: &InvalidPushTokenEvent) -> Result<()> {
+        info!(
+            user_id = %event.user_id,
+            provider = %event.provider,
+            "Invalid push token detected but no cleanup topic configured"
+        );
+        Ok(())
+    }
+}
@@ -15,7 +15,7 @@ pub struct ApplicationBootstrap;
 impl ApplicationBootstrap {
     /// 运行应用的主入口点
     pub async fn run() -> Result<()> {
-        use flare_im_core::load_config;
+        use flare_im_core::{load_config_with_validation, strict_mode};
 
         // 初始化 OpenTelemetry 追踪
         #[cfg(feature = "tracing")]
@@ -31,7 +31,7 @@ impl ApplicationBootstrap {
         }
 
         // 加载应用配置
-        let app_config = load_config(Some("config"));
+        let app_config = load_config_with_validation(Some("config"), strict_mode())?;
 
         // 使用 Wire 风格的依赖注入构建应用上下文
         let context = wire::initialize(app_config).await?;
@@ -8,13 +8,18 @@ use anyhow::{Context as AnyhowContext, Result};
 
 use crate::application::handlers::PushCommandHandler;
 use crate::config::PushWorkerConfig;
-use crate::domain::repository::{AckPublisher, DlqPublisher, OfflinePushSender, OnlinePushSender};
+use crate::domain::repository::{
+    AckPublisher, DlqPublisher, InvalidTokenPublisher, OfflinePushSender, OnlinePushSender,
+};
 use crate::domain::service::PushDomainService;
 use crate::infrastructure::ack_publisher::{KafkaAckPublisher, NoopAckPublisher};
 use crate::infrastructure::dlq_publisher::KafkaDlqPublisher;
 use crate::infrastructure::hook::HookExecutor;
 use crate::infrastructure::offline::{NoopOfflinePushSender, build_offline_sender};
 use crate::infrastructure::online::{NoopOnlinePushSender, build_online_sender};
+use crate::infrastructure::token_publisher::{
+    KafkaInvalidTokenPublisher, NoopInvalidTokenPublisher,
+};
 use crate::interface::consumers::PushWorkerConsumer;
 use flare_im_core::gateway::{GatewayRouter, GatewayRouterConfig};
 use flare_im_core::hooks::{HookDispatcher, HookRegistry};
@@ -84,6 +89,17 @@ pub async fn initialize(
     )
     .map_err(|e| anyhow::anyhow!("Failed to create Kafka DLQ publisher: {}", e))?;
 
+    // 5.1 构建失效 Token 发布器（未配置 Topic 时只记录日志）
+    let token_publisher: Arc<dyn InvalidTokenPublisher> =
+        if let Some(ref topic) = worker_config.invalid_token_topic {
+            KafkaInvalidTokenPublisher::new(&worker_config.kafka_bootstrap, topic.clone())
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to create Kafka invalid token publisher: {}", e)
+                })?
+        } else {
+            Arc::new(NoopInvalidTokenPublisher)
+        };
+
     // 6. 构建 Gateway Router（如果配置了 access_gateway_service）
     let gateway_router: Option<Arc<dyn flare_im_core::gateway::GatewayRouterTrait>> =
         if let Some(ref service_name) = worker_config.access_gateway_service {
@@ -115,6 +131,7 @@ pub async fn initialize(
         offline_sender.clone(),
         ack_publisher.clone(),
         dlq_publisher.clone(),
+        token_publisher,
         gateway_router,
         hooks,
         hook_executor,
@@ -0,0 +1,230 @@
+//! 协议帧回放工具
+//!
+//! 将管理接口导出的帧录制（`GET /admin/connections/{connection_id}/recording`）
+//! 中的上行帧按原始时间间隔回放到测试网关，并对比网关下行帧与录制中的下行帧，
+//! 用于在测试环境复现客户端协议问题。
+//!
+//! 用法：`frame-replay <recording.json>`
+//!
+//! 环境变量：
+//! - `REPLAY_WS_URL`：测试网关地址（默认 `ws://localhost:60051`）
+//! - `USER_ID`：回放使用的用户（默认取录制中的用户）
+//! - `TOKEN`：认证令牌（未设置时用 `TOKEN_SECRET` 签发，默认 `insecure-secret`）
+//! - `REPLAY_SPEED`：回放倍速（默认 1.0，0 表示不等待）
+//! - `REPLAY_SETTLE_MS`：最后一帧发送后等待下行帧的时间（默认 2000）
+//!
+//! 握手由客户端重新完成，不回放；录制时负载已脱敏的帧以空负载回放，
+//! 需要复现与负载相关的问题时应在受控环境关闭 `GATEWAY_FRAME_RECORDER_REDACT_PAYLOADS`
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use flare_core::client::{ClientEventHandler, ObserverClientBuilder};
+use flare_core::common::MessageParser;
+use flare_core::common::compression::CompressionAlgorithm;
+use flare_core::common::config_types::TransportProtocol;
+use flare_core::common::device::{DeviceInfo, DevicePlatform};
+use flare_core::common::protocol::Frame;
+use flare_core::common::protocol::flare::core::commands::message_command::Type as MsgType;
+use flare_core::common::protocol::flare::core::commands::notification_command::Type as NotifType;
+use flare_core::common::protocol::flare::core::commands::system_command::Type as SysType;
+use flare_core::transport::events::{ConnectionEvent, ConnectionObserver};
+use flare_signaling_gateway::domain::service::frame_recorder_service::frame_kind;
+use flare_signaling_gateway::domain::service::{FrameDirection, FrameRecording};
+use tracing::{info, warn};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt().with_target(false).init();
+
+    let path = std::env::args()
+        .nth(1)
+        .context("usage: frame-replay <recording.json>")?;
+    let recording: FrameRecording = serde_json::from_slice(
+        &std::fs::read(&path).with_context(|| format!("Failed to read {}", path))?,
+    )
+    .with_context(|| format!("Invalid frame recording: {}", path))?;
+
+    let ws_url =
+        std::env::var("REPLAY_WS_URL").unwrap_or_else(|_| "ws://localhost:60051".to_string());
+    let user_id = std::env::var("USER_ID")
+        .ok()
+        .or_else(|| recording.user_id.clone())
+        .context("USER_ID is required when the recording has no user_id")?;
+    let speed = std::env::var("REPLAY_SPEED")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|speed| *speed >= 0.0)
+        .unwrap_or(1.0);
+    let settle = Duration::from_millis(
+        std::env::var("REPLAY_SETTLE_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(2000),
+    );
+    let token = std::env::var("TOKEN").unwrap_or_else(|_| {
+        use flare_server_core::TokenService;
+        TokenService::new(
+            std::env::var("TOKEN_SECRET").unwrap_or_else(|_| "insecure-secret".to_string()),
+            "flare-im-core".to_string(),
+            3600,
+        )
+        .generate_token(&user_id, None, None)
+        .unwrap_or_default()
+    });
+
+    info!(
+        connection_id = %recording.connection_id,
+        frames = recording.frames.len(),
+        dropped = recording.dropped,
+        payloads_redacted = recording.payloads_redacted,
+        %user_id,
+        %ws_url,
+        "Replaying frame recording"
+    );
+    if recording.dropped > 0 {
+        warn!(
+            dropped = recording.dropped,
+            "Recording ring buffer overflowed, earliest frames are missing"
+        );
+    }
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let platform = DevicePlatform::PC;
+    let device_info = DeviceInfo::new(
+        format!("frame-replay-{}", std::process::id()),
+        platform.clone(),
+    )
+    .with_model(platform.as_str().to_string());
+    let mut builder = ObserverClientBuilder::new(&ws_url)
+        .with_observer(Arc::new(ReplayObserver {
+            received: received.clone(),
+        }) as Arc<dyn ConnectionObserver>)
+        .with_event_handler(Arc::new(NoopEvents) as Arc<dyn ClientEventHandler>)
+        .with_protocol_race(vec![TransportProtocol::WebSocket])
+        .with_protocol_url(TransportProtocol::WebSocket, ws_url.clone())
+        .with_format(flare_core::common::protocol::SerializationFormat::Protobuf)
+        .with_compression(CompressionAlgorithm::None)
+        .with_device_info(device_info)
+        .with_user_id(user_id.clone())
+        .with_connect_timeout(Duration::from_secs(10))
+        .with_max_reconnect_attempts(Some(0));
+    if !token.is_empty() {
+        builder = builder.with_token(token);
+    }
+    let mut client = builder.build_with_race().await?;
+
+    let mut previous_at = None;
+    let mut sent = 0usize;
+    for recorded in recording
+        .frames
+        .iter()
+        .filter(|f| f.direction == FrameDirection::Inbound)
+    {
+        if let Some(previous_at) = previous_at {
+            let gap = (recorded.recorded_at - previous_at)
+                .to_std()
+                .unwrap_or_default();
+            if speed > 0.0 {
+                tokio::time::sleep(gap.div_f64(speed)).await;
+            }
+        }
+        previous_at = Some(recorded.recorded_at);
+
+        let frame = recorded
+            .decode_frame()
+            .with_context(|| format!("Failed to decode frame seq={}", recorded.seq))?;
+        client
+            .send_frame(&frame)
+            .await
+            .with_context(|| format!("Failed to send frame seq={}", recorded.seq))?;
+        sent += 1;
+        info!(seq = recorded.seq, kind = %recorded.kind, "Replayed inbound frame");
+    }
+
+    tokio::time::sleep(settle).await;
+    client.disconnect().await?;
+
+    // 对比下行帧类型序列（消息内容与 ID 在回放时必然不同，只比较类型）
+    let expected: Vec<&str> = recording
+        .frames
+        .iter()
+        .filter(|f| f.direction == FrameDirection::Outbound)
+        .map(|f| f.kind.as_str())
+        .collect();
+    let received = received.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    println!("replayed inbound frames: {}", sent);
+    println!("recorded outbound: {:?}", expected);
+    println!("received outbound: {:?}", received);
+    if expected == received {
+        println!("outbound frame kinds match the recording");
+    } else {
+        println!("outbound frame kinds differ from the recording");
+    }
+    Ok(())
+}
+
+/// 收集网关下行帧的类型
+struct ReplayObserver {
+    received: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl ConnectionObserver for ReplayObserver {
+    fn on_event(&self, event: &ConnectionEvent) {
+        match event {
+            ConnectionEvent::Message(data) => match MessageParser::protobuf().parse(data) {
+                Ok(frame) => {
+                    let kind = frame_kind(&frame);
+                    info!(%kind, "Received outbound frame");
+                    self.received
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .push(kind);
+                }
+                Err(err) => warn!(?err, "Failed to parse outbound frame"),
+            },
+            ConnectionEvent::Disconnected(reason) => warn!(%reason, "Disconnected"),
+            ConnectionEvent::Error(err) => warn!(?err, "Connection error"),
+            ConnectionEvent::Connected => info!("Connected"),
+        }
+    }
+}
+
+struct NoopEvents;
+
+#[async_trait]
+impl ClientEventHandler for NoopEvents {
+    async fn handle_system_command(
+        &self,
+        _t: SysType,
+        _f: &Frame,
+    ) -> flare_core::common::error::Result<Option<Frame>> {
+        Ok(None)
+    }
+
+    async fn handle_message_command(
+        &self,
+        _t: MsgType,
+        _f: &Frame,
+    ) -> flare_core::common::error::Result<Option<Frame>> {
+        Ok(None)
+    }
+
+    async fn handle_notification_command(
+        &self,
+        _t: NotifType,
+        _f: &Frame,
+    ) -> flare_core::common::error::Result<Option<Frame>> {
+        Ok(None)
+    }
+
+    async fn handle_connection_event(
+        &self,
+        _e: &ConnectionEvent,
+    ) -> flare_core::common::error::Result<()> {
+        Ok(())
+    }
+}
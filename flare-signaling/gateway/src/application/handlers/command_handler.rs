@@ -32,6 +32,7 @@ mod push_commands {
 
     use crate::domain::service::{DomainPushResult, PushDomainService};
     use crate::infrastructure::AckPublisher;
+    use crate::infrastructure::messaging::resume_handoff::RESUME_HANDOFF_DEVICE_KEY;
 
     /// 推送消息命令
     #[derive(Debug)]
@@ -352,6 +353,64 @@ mod push_commands {
     }
 
     impl PushMessageService {
+        /// 目标设备处于续连宽限期时暂存消息（续连后补发），返回暂存的设备数
+        fn buffer_for_resume(
+            &self,
+            user_id: &str,
+            message: &flare_proto::common::Message,
+            message_bytes: &[u8],
+            options: &PushOptions,
+            offline_handled: bool,
+        ) -> usize {
+            let buffered = self.domain_service.buffer_for_resume(
+                user_id,
+                message,
+                message_bytes,
+                options,
+                offline_handled,
+            );
+            if buffered > 0 {
+                tracing::debug!(
+                    user_id = %user_id,
+                    message_id = %message.server_id,
+                    devices = buffered,
+                    offline_handled,
+                    "Message buffered for resuming devices"
+                );
+            }
+            buffered
+        }
+
+        /// 没有可投递连接时的结果
+        ///
+        /// 消息暂存给续连宽限期内的设备时仍返回 `UserOffline`，Push Server 照常走离线流程
+        fn offline_result(
+            &self,
+            user_id: String,
+            message: &flare_proto::common::Message,
+            message_bytes: &[u8],
+            options: &PushOptions,
+            reason: &str,
+        ) -> PushResult {
+            let buffered = self.buffer_for_resume(&user_id, message, message_bytes, options, true);
+            let error_message = if buffered > 0 {
+                format!("{}, buffered for {} resuming device(s)", reason, buffered)
+            } else {
+                reason.to_string()
+            };
+            PushResult {
+                user_id,
+                status: PushStatus::UserOffline as i32,
+                success_count: 0,
+                failure_count: 0,
+                error_message,
+                pushed_at: Some(prost_types::Timestamp {
+                    seconds: Utc::now().timestamp(),
+                    nanos: 0,
+                }),
+            }
+        }
+
         async fn process_single_user(
             &self,
             user_id: String,
@@ -363,6 +422,19 @@ mod push_commands {
 
             max_seq: u64,
         ) -> PushResult {
+            // 宽限期到期转交的消息只投递给指定设备，不重复推送给其他在线设备
+            let handoff_options;
+            let options = match message.extra.get(RESUME_HANDOFF_DEVICE_KEY) {
+                Some(device_id) => {
+                    handoff_options = PushOptions {
+                        device_ids: vec![device_id.clone()],
+                        ..options.clone()
+                    };
+                    &handoff_options
+                }
+                None => options,
+            };
+
             // 检查用户是否在线
             let is_online = match self.domain_service.check_user_online(&user_id).await {
                 Ok(online) => online,
@@ -373,21 +445,17 @@ mod push_commands {
             };
 
             if !is_online {
-                return PushResult {
+                return self.offline_result(
                     user_id,
-                    status: PushStatus::UserOffline as i32,
-                    success_count: 0,
-                    failure_count: 0,
-                    error_message: "User is offline".to_string(),
-                    pushed_at: Some(prost_types::Timestamp {
-                        seconds: Utc::now().timestamp(),
-                        nanos: 0,
-                    }),
-                };
+                    message,
+                    message_bytes,
+                    options,
+                    "User is offline",
+                );
             }
 
             // 获取过滤后的连接
-            let filtered_connections = match self
+            let mut filtered_connections = match self
                 .domain_service
                 .get_filtered_connections(&user_id, options)
                 .await
@@ -409,25 +477,49 @@ mod push_commands {
                 }
             };
 
+            // 回声抑制：发送者的消息不再推回发送设备，只同步到其他设备
+            let connection_count = filtered_connections.len();
+            filtered_connections.retain(|conn| {
+                !flare_im_core::utils::is_origin_device(message, &user_id, &conn.device_id)
+            });
+            if filtered_connections.len() < connection_count {
+                tracing::debug!(
+                    user_id = %user_id,
+                    message_id = %message.server_id,
+                    "Suppressed echo to origin device"
+                );
+                if filtered_connections.is_empty() {
+                    return self.offline_result(
+                        user_id,
+                        message,
+                        message_bytes,
+                        options,
+                        "Only origin device online, echo suppressed",
+                    );
+                }
+            }
+
             if filtered_connections.is_empty() {
-                return PushResult {
+                return self.offline_result(
                     user_id,
-                    status: PushStatus::UserOffline as i32,
-                    success_count: 0,
-                    failure_count: 0,
-                    error_message: "No matching connections".to_string(),
-                    pushed_at: Some(prost_types::Timestamp {
-                        seconds: Utc::now().timestamp(),
-                        nanos: 0,
-                    }),
-                };
+                    message,
+                    message_bytes,
+                    options,
+                    "No matching connections",
+                );
             }
 
             // 推送消息
             let push_start = Instant::now();
             let domain_result = match self
                 .domain_service
-                .push_to_connections(&user_id, &filtered_connections, message_bytes)
+                .push_to_connections(
+                    &user_id,
+                    &filtered_connections,
+                    message_bytes,
+                    &message.conversation_id,
+                    max_seq,
+                )
                 .await
             {
                 Ok((user_success, user_failure)) => DomainPushResult {
@@ -512,6 +604,10 @@ mod push_commands {
                 }
             }
 
+            // 续连宽限期内的其他设备同样暂存：在线设备已收到，Push Server 不会走离线流程，
+            // 宽限期到期仍未续连时由维护任务转交离线推送
+            self.buffer_for_resume(&user_id, message, message_bytes, options, false);
+
             // 构建结果
             let push_status = if domain_result.success_count > 0 && domain_result.failure_count == 0
             {
@@ -0,0 +1,317 @@
+//! # 连接管理处理器（编排层）
+//!
+//! 供运维/客服排查用户长连接：
+//! - 查询用户的在线连接：合并会话存储（Online 服务，覆盖所有网关实例）与本网关 ConnectionManager 的实时数据
+//! - 强制断开本网关上的指定连接，断开前向客户端推送关闭原因，并作废该用户的续连票据
+//! - 开启/导出/停止本网关上指定连接的协议帧录制（启用帧录制时）
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use flare_server_core::context::Context;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::domain::service::{
+    ConnectionInspectionService, FrameRecorderError, FrameRecorderService, FrameRecording,
+    OnlineServiceClient, ResumeTicketService,
+};
+use crate::interface::handler::LongConnectionHandler;
+
+/// 用户的一条在线连接
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveConnection {
+    /// 本网关的连接ID（连接位于其他网关时为空）
+    pub connection_id: Option<String>,
+    /// 会话存储中的会话ID（会话存储不可用时为空）
+    pub session_id: Option<String>,
+    pub user_id: String,
+    pub device_id: String,
+    pub platform: String,
+    /// 连接所在的网关实例
+    pub gateway_id: String,
+    pub server_id: Option<String>,
+    /// 连接是否位于本网关（只有本网关的连接有实时数据，可被强制断开）
+    pub local: bool,
+    pub protocol: Option<String>,
+    pub connected_at: Option<DateTime<Utc>>,
+    pub last_heartbeat_at: Option<DateTime<Utc>>,
+    /// 发送中的下行帧数量
+    pub queue_depth: Option<usize>,
+    /// 握手协商的能力
+    pub capabilities: BTreeMap<String, String>,
+}
+
+/// 强制断开结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisconnectOutcome {
+    Disconnected,
+    /// 连接不在本网关（可能已断开，或需在所属网关上操作）
+    NotFound,
+}
+
+/// 开启帧录制结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordingOutcome {
+    Started,
+    /// 连接不在本网关
+    NotFound,
+    /// 本网关未启用帧录制
+    Disabled,
+    /// 同时存在的录制数已达上限
+    Rejected(FrameRecorderError),
+}
+
+/// 连接管理服务
+pub struct ConnectionAdminService {
+    connection_handler: Arc<LongConnectionHandler>,
+    connection_inspection: Arc<ConnectionInspectionService>,
+    online_service_client: Option<Arc<OnlineServiceClient>>,
+    /// 协议帧录制服务（未设置时帧录制接口不可用）
+    frame_recorder: Option<Arc<FrameRecorderService>>,
+    /// 续连票据服务（强制断开时作废用户票据，防止客户端凭票据续连）
+    resume_tickets: Option<Arc<ResumeTicketService>>,
+    gateway_id: String,
+}
+
+impl ConnectionAdminService {
+    pub fn new(
+        connection_handler: Arc<LongConnectionHandler>,
+        connection_inspection: Arc<ConnectionInspectionService>,
+        online_service_client: Option<Arc<OnlineServiceClient>>,
+        gateway_id: String,
+    ) -> Self {
+        Self {
+            connection_handler,
+            connection_inspection,
+            online_service_client,
+            frame_recorder: None,
+            resume_tickets: None,
+            gateway_id,
+        }
+    }
+
+    /// 设置协议帧录制服务（需与连接处理器使用同一实例）
+    pub fn with_frame_recorder(mut self, frame_recorder: Arc<FrameRecorderService>) -> Self {
+        self.frame_recorder = Some(frame_recorder);
+        self
+    }
+
+    /// 设置续连票据服务（需与认证器使用同一实例）
+    pub fn with_resume_tickets(mut self, resume_tickets: Arc<ResumeTicketService>) -> Self {
+        self.resume_tickets = Some(resume_tickets);
+        self
+    }
+
+    pub fn gateway_id(&self) -> &str {
+        &self.gateway_id
+    }
+
+    /// 查询用户的所有在线连接
+    ///
+    /// 会话存储中的每个设备会话输出一条记录，位于本网关的会话补充 ConnectionManager 的实时数据；
+    /// 本网关存在但会话存储中没有的连接（注册未完成或会话存储不可用）也会输出
+    pub async fn list_user_connections(
+        &self,
+        ctx: &Context,
+        user_id: &str,
+    ) -> Result<Vec<LiveConnection>> {
+        let mut local = self.local_connections(user_id).await;
+        let mut connections = Vec::new();
+
+        if let Some(ref online) = self.online_service_client {
+            match online.list_user_devices(ctx, user_id).await {
+                Ok(response) => {
+                    for device in response.devices {
+                        let last_heartbeat_at = device.last_active_time.as_ref().and_then(|ts| {
+                            DateTime::from_timestamp(ts.seconds, ts.nanos.max(0) as u32)
+                        });
+                        let mut connection = LiveConnection {
+                            connection_id: None,
+                            session_id: Some(device.conversation_id).filter(|id| !id.is_empty()),
+                            user_id: user_id.to_string(),
+                            device_id: device.device_id,
+                            platform: device.platform,
+                            gateway_id: device.gateway_id,
+                            server_id: Some(device.server_id).filter(|id| !id.is_empty()),
+                            local: false,
+                            protocol: None,
+                            connected_at: None,
+                            last_heartbeat_at,
+                            queue_depth: None,
+                            capabilities: BTreeMap::new(),
+                        };
+                        // 同一设备在本网关的连接：补充实时数据
+                        if connection.gateway_id == self.gateway_id {
+                            if let Some(pos) = local
+                                .iter()
+                                .position(|c| c.device_id == connection.device_id)
+                            {
+                                let live = local.swap_remove(pos);
+                                connection.connection_id = live.connection_id;
+                                connection.local = true;
+                                connection.protocol = live.protocol;
+                                connection.connected_at = live.connected_at;
+                                connection.last_heartbeat_at =
+                                    connection.last_heartbeat_at.or(live.last_heartbeat_at);
+                                connection.queue_depth = live.queue_depth;
+                                connection.capabilities = live.capabilities;
+                            }
+                        }
+                        connections.push(connection);
+                    }
+                }
+                Err(e) => {
+                    warn!(error = %e, user_id = %user_id, "Failed to query session store, returning local connections only");
+                }
+            }
+        }
+
+        connections.extend(local);
+        Ok(connections)
+    }
+
+    /// 强制断开本网关上的指定连接
+    ///
+    /// 先推送 `ConnectionClosed` 通知（推送失败不影响断开），作废该用户的续连票据，再关闭连接
+    pub async fn force_disconnect(
+        &self,
+        connection_id: &str,
+        reason_code: &str,
+        reason: &str,
+        operator: &str,
+    ) -> Result<DisconnectOutcome> {
+        let Some(manager) = self.connection_handler.connection_manager() else {
+            return Ok(DisconnectOutcome::NotFound);
+        };
+        if manager.get_connection(connection_id).await.is_none() {
+            return Ok(DisconnectOutcome::NotFound);
+        }
+
+        if let Err(e) = self
+            .connection_handler
+            .push_connection_closed(connection_id, reason_code, reason)
+            .await
+        {
+            warn!(error = %e, connection_id = %connection_id, "Failed to push connection closed notice");
+        }
+        if let Some(ref resume_tickets) = self.resume_tickets
+            && let Some(user_id) = self
+                .connection_handler
+                .user_id_for_connection(connection_id)
+                .await
+        {
+            resume_tickets.revoke_user(&user_id);
+        }
+        self.connection_handler
+            .disconnect_connection(connection_id)
+            .await;
+
+        info!(
+            connection_id = %connection_id,
+            reason_code = %reason_code,
+            reason = %reason,
+            operator = %operator,
+            "Connection force-disconnected by admin"
+        );
+        Ok(DisconnectOutcome::Disconnected)
+    }
+
+    /// 开启本网关上指定连接的协议帧录制
+    pub async fn start_frame_recording(&self, connection_id: &str) -> RecordingOutcome {
+        let Some(ref recorder) = self.frame_recorder else {
+            return RecordingOutcome::Disabled;
+        };
+        let Some(manager) = self.connection_handler.connection_manager() else {
+            return RecordingOutcome::NotFound;
+        };
+        if manager.get_connection(connection_id).await.is_none() {
+            return RecordingOutcome::NotFound;
+        }
+
+        let user_id = self
+            .connection_handler
+            .user_id_for_connection(connection_id)
+            .await;
+        match recorder.start(connection_id, user_id.as_deref()) {
+            Ok(()) => {
+                info!(connection_id = %connection_id, "Frame recording started by admin");
+                RecordingOutcome::Started
+            }
+            Err(e) => RecordingOutcome::Rejected(e),
+        }
+    }
+
+    /// 导出指定连接的录制结果（未启用帧录制或未录制时返回 None）
+    pub fn frame_recording(&self, connection_id: &str) -> Option<FrameRecording> {
+        self.frame_recorder.as_ref()?.dump(connection_id)
+    }
+
+    /// 停止指定连接的录制并返回录制结果
+    pub fn stop_frame_recording(&self, connection_id: &str) -> Option<FrameRecording> {
+        let recording = self.frame_recorder.as_ref()?.stop(connection_id)?;
+        info!(
+            connection_id = %connection_id,
+            frames = recording.frames.len(),
+            "Frame recording stopped by admin"
+        );
+        Some(recording)
+    }
+
+    /// 本网关上用户的连接实时数据
+    async fn local_connections(&self, user_id: &str) -> Vec<LiveConnection> {
+        let Some(manager) = self.connection_handler.connection_manager() else {
+            return Vec::new();
+        };
+
+        let mut seen = HashSet::new();
+        let mut connections = Vec::new();
+        for connection_id in manager.get_user_connections(user_id).await {
+            if !seen.insert(connection_id.clone()) {
+                continue;
+            }
+            let Some((_, conn_info)) = manager.get_connection(&connection_id).await else {
+                continue;
+            };
+            let timestamp = |secs: u64| {
+                (secs > 0)
+                    .then(|| DateTime::from_timestamp(secs as i64, 0))
+                    .flatten()
+            };
+            connections.push(LiveConnection {
+                queue_depth: Some(self.connection_inspection.queue_depth(&connection_id)),
+                connection_id: Some(connection_id),
+                session_id: None,
+                user_id: user_id.to_string(),
+                device_id: conn_info
+                    .device_info
+                    .as_ref()
+                    .map(|d| d.device_id.clone())
+                    .unwrap_or_else(|| "unknown".to_string()),
+                platform: conn_info
+                    .device_info
+                    .as_ref()
+                    .map(|d| format!("{:?}", d.platform))
+                    .unwrap_or_else(|| "unknown".to_string()),
+                gateway_id: self.gateway_id.clone(),
+                server_id: None,
+                local: true,
+                protocol: Some(
+                    conn_info
+                        .metadata
+                        .get("protocol")
+                        .cloned()
+                        .unwrap_or_else(|| "websocket".to_string()),
+                ),
+                connected_at: timestamp(conn_info.created_at),
+                last_heartbeat_at: timestamp(conn_info.last_active),
+                capabilities: ConnectionInspectionService::negotiated_capabilities(
+                    &conn_info.metadata,
+                ),
+            });
+        }
+        connections
+    }
+}
@@ -38,7 +38,7 @@ impl ConnectionHandler {
     ///
     /// 流程：
     /// 1. 记录指标
-    /// 2. 注册会话到 Signaling Online
+    /// 2. 注册会话到 Signaling Online（续连票据认证的连接携带原会话ID）
     /// 3. 记录日志
     #[instrument(skip(self), fields(connection_id, user_id, device_id))]
     pub async fn handle_connect(
@@ -48,6 +48,7 @@ impl ConnectionHandler {
         device_id: &str,
         active_connections: usize,
         connection_metadata: Option<&std::collections::HashMap<String, String>>,
+        resume_conversation_id: Option<&str>,
     ) -> Result<String> {
         // 更新活跃连接数
         self.metrics
@@ -65,7 +66,13 @@ impl ConnectionHandler {
         // 注册会话到 Signaling Online（传递连接 metadata）
         match self
             .session_domain_service
-            .register_session(user_id, device_id, Some(connection_id), connection_metadata)
+            .register_session(
+                user_id,
+                device_id,
+                Some(connection_id),
+                connection_metadata,
+                resume_conversation_id,
+            )
             .await
         {
             Ok(conversation_id) => {
@@ -143,6 +150,62 @@ impl ConnectionHandler {
         Ok(())
     }
 
+    /// 处理宽限期内的快速续连
+    ///
+    /// 会话仍注册在本网关，无需再次注册到 Signaling Online，直接沿用原会话
+    #[instrument(skip(self), fields(connection_id, user_id, device_id))]
+    pub fn handle_resume(
+        &self,
+        connection_id: &str,
+        user_id: &str,
+        device_id: &str,
+        active_connections: usize,
+        conversation_id: &str,
+    ) -> String {
+        self.metrics
+            .connections_active
+            .set(active_connections as i64);
+
+        info!(
+            user_id = %user_id,
+            device_id = %device_id,
+            connection_id = %connection_id,
+            conversation_id = %conversation_id,
+            "Connection resumed"
+        );
+        conversation_id.to_string()
+    }
+
+    /// 处理续连宽限期结束仍未续连的会话
+    ///
+    /// 用户在本网关已没有其他连接时注销会话
+    #[instrument(skip(self), fields(user_id, device_id))]
+    pub async fn handle_detached_expired(
+        &self,
+        user_id: &str,
+        device_id: &str,
+        conversation_id: &str,
+    ) -> Result<()> {
+        let connections = self
+            .connection_query
+            .query_user_connections(user_id)
+            .await
+            .unwrap_or_default();
+        if !connections.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            user_id = %user_id,
+            device_id = %device_id,
+            conversation_id = %conversation_id,
+            "Resume grace period expired, unregistering session"
+        );
+        self.session_domain_service
+            .unregister_session(user_id, Some(conversation_id))
+            .await
+    }
+
     /// 刷新会话心跳
     #[instrument(skip(self), fields(connection_id, user_id))]
     pub async fn refresh_session(
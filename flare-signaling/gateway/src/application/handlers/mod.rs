@@ -5,6 +5,7 @@
 pub mod command_handler;
 pub mod query_handler;
 pub mod connection_handler;
+pub mod connection_admin_handler;
 pub mod message_handler;
 
 pub use command_handler::{
@@ -14,4 +15,7 @@ pub use query_handler::{
     ConnectionQueryService, QueryUserConnectionsQuery,
 };
 pub use connection_handler::ConnectionHandler;
+pub use connection_admin_handler::{
+    ConnectionAdminService, DisconnectOutcome, LiveConnection, RecordingOutcome,
+};
 pub use message_handler::MessageHandler;
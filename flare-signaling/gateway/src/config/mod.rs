@@ -3,4 +3,7 @@
 //! 提供Access Gateway的配置加载和解析
 
 pub mod settings;
-pub use settings::AccessGatewayConfig;
+pub use settings::{
+    AccessGatewayConfig, AdminApiConfig, ClientAckRecordingConfig, ReadReceiptConfig,
+    SecurityWebhookConfig, SessionReceiptsConfig,
+};
@@ -1,3 +1,7 @@
+use std::collections::HashSet;
+
+use flare_conversation::domain::model::ReceiptsPolicy;
+use flare_im_core::ack::{AckType, ImportanceLevel};
 use flare_im_core::config::{FlareAppConfig, RedisPoolConfig};
 
 #[derive(Debug, Clone)]
@@ -13,6 +17,8 @@ pub struct AccessGatewayConfig {
     pub token_issuer: String,
     pub token_ttl_seconds: u64,
     pub token_store_redis_url: Option<String>,
+    /// 令牌签名密钥文件（多密钥轮换，配置后优先于 token_secret）
+    pub token_keys_file: Option<String>,
     // ACK上报配置（使用 gRPC，无需 Kafka）
     pub use_ack_report: bool,
     // 跨地区网关路由配置
@@ -22,6 +28,118 @@ pub struct AccessGatewayConfig {
     pub compression_algorithm: Option<String>,
     pub enable_encryption: bool,
     pub encryption_key: Option<String>,
+    // 长连接监听分片数（1 表示单 acceptor）
+    pub listener_shards: usize,
+    // 延迟探测配置（配置 region 后启用）
+    pub latency_probe: crate::domain::service::LatencyProbeConfig,
+    // 会话聚焦配置（聚焦会话优先全量推送，其他会话降级为通知）
+    pub conversation_focus: crate::domain::service::ConversationFocusConfig,
+    /// 登录安全策略文件（配置后启用登录异常检测）
+    pub login_security_config: Option<String>,
+    /// 安全事件 Webhook（可选）
+    pub security_webhook: Option<SecurityWebhookConfig>,
+    /// 连接管理 HTTP 接口（可选）
+    pub admin_api: Option<AdminApiConfig>,
+    /// 客户端 ACK 写入 ACK 模块（可选）
+    pub client_ack_recording: Option<ClientAckRecordingConfig>,
+    /// 续连票据（配置签名密钥后启用）
+    pub resume_ticket: Option<crate::domain::service::ResumeTicketConfig>,
+    /// 协议帧录制（需同时启用连接管理接口）
+    pub frame_recorder: Option<crate::domain::service::FrameRecorderConfig>,
+}
+
+/// 客户端 ACK 写入 ACK 模块的配置
+#[derive(Debug, Clone)]
+pub struct ClientAckRecordingConfig {
+    /// ACK 模块使用的 Redis
+    pub redis_url: String,
+    /// ACK 帧未携带 `ack_type` 时的类型
+    pub default_ack_type: AckType,
+    /// ACK 帧未携带 `importance` 时的重要性
+    pub importance: ImportanceLevel,
+    /// 已读回执推送（可选）
+    pub read_receipts: Option<ReadReceiptConfig>,
+    /// 按会话回执策略过滤送达/已读 ACK（可选）
+    pub session_receipts: Option<SessionReceiptsConfig>,
+}
+
+impl Default for ClientAckRecordingConfig {
+    fn default() -> Self {
+        Self {
+            redis_url: "redis://127.0.0.1:6379".to_string(),
+            default_ack_type: AckType::DeliveryAck,
+            importance: ImportanceLevel::Medium,
+            read_receipts: None,
+            session_receipts: None,
+        }
+    }
+}
+
+/// 已读回执推送配置（按会话类型启用）
+#[derive(Debug, Clone)]
+pub struct ReadReceiptConfig {
+    /// 推送已读回执的会话类型（`single` / `group` / `channel`）
+    pub conversation_types: HashSet<String>,
+}
+
+impl ReadReceiptConfig {
+    /// 从逗号分隔的会话类型列表解析（为空时返回 None）
+    pub fn from_list(list: &str) -> Option<Self> {
+        let conversation_types: HashSet<String> = list
+            .split(',')
+            .map(|t| t.trim().to_ascii_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+        (!conversation_types.is_empty()).then_some(Self { conversation_types })
+    }
+
+    /// 会话类型是否推送已读回执（ACK 帧未携带会话类型时按单聊处理）
+    pub fn allows(&self, conversation_type: &str) -> bool {
+        let conversation_type = if conversation_type.is_empty() {
+            "single"
+        } else {
+            conversation_type
+        };
+        self.conversation_types.contains(conversation_type)
+    }
+}
+
+/// 会话回执策略配置（查询会话属性 `receipts_policy`）
+#[derive(Debug, Clone)]
+pub struct SessionReceiptsConfig {
+    /// 会话未配置回执策略时使用的策略（应与会话服务默认策略一致）
+    pub default_policy: ReceiptsPolicy,
+    /// 会话回执策略缓存时间（秒）
+    pub cache_ttl_secs: u64,
+}
+
+impl Default for SessionReceiptsConfig {
+    fn default() -> Self {
+        Self {
+            default_policy: ReceiptsPolicy::Full,
+            cache_ttl_secs: 60,
+        }
+    }
+}
+
+/// 连接管理 HTTP 接口配置
+#[derive(Debug, Clone)]
+pub struct AdminApiConfig {
+    /// 监听地址（如 `0.0.0.0:60052`）
+    pub address: String,
+    /// 访问令牌（`Authorization: Bearer <token>`）
+    pub token: String,
+}
+
+/// 安全事件 Webhook 配置
+#[derive(Debug, Clone)]
+pub struct SecurityWebhookConfig {
+    pub url: String,
+    /// 签名密钥（HMAC-SHA256 签名请求体，见 `flare_im_core::hooks::signature`）
+    pub secret: Option<String>,
+    /// 轮换中的旧签名密钥（轮换期间同时携带新旧两个签名）
+    pub previous_secret: Option<String>,
+    pub timeout_ms: u64,
 }
 
 impl AccessGatewayConfig {
@@ -103,6 +221,10 @@ impl AccessGatewayConfig {
 
         let token_ttl_seconds = service.token_ttl_seconds.unwrap_or(3600);
 
+        let token_keys_file = std::env::var("ACCESS_GATEWAY_TOKEN_KEYS_FILE")
+            .ok()
+            .or_else(|| service.token_keys_file.clone());
+
         // ACK上报配置（使用 gRPC，默认开启）
         let use_ack_report = std::env::var("ACCESS_GATEWAY_USE_ACK_REPORT")
             .ok()
@@ -134,6 +256,199 @@ impl AccessGatewayConfig {
             .ok()
             .or_else(|| service.encryption_key.clone());
 
+        // 监听分片配置（支持环境变量覆盖，0 表示按CPU核数）
+        let listener_shards = crate::infrastructure::listener::resolve_listener_shards(
+            std::env::var("GATEWAY_LISTENER_SHARDS")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .or(service.listener_shards),
+        );
+
+        // 延迟探测配置（支持环境变量覆盖）
+        let mut latency_probe = crate::domain::service::LatencyProbeConfig::default();
+        if let Some(high_rtt_ms) = std::env::var("GATEWAY_PROBE_HIGH_RTT_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            latency_probe.high_rtt_ms = high_rtt_ms;
+        }
+        if let Some(streak) = std::env::var("GATEWAY_PROBE_HIGH_RTT_STREAK")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+        {
+            latency_probe.high_rtt_streak = streak.max(1);
+        }
+        if let Some(secs) = std::env::var("GATEWAY_PROBE_REPORT_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            latency_probe.report_interval = std::time::Duration::from_secs(secs.max(1));
+        }
+
+        // 会话聚焦配置（支持环境变量覆盖）
+        let mut conversation_focus = crate::domain::service::ConversationFocusConfig::default();
+        if let Some(enabled) = std::env::var("GATEWAY_FOCUS_MODE_ENABLED")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok())
+        {
+            conversation_focus.enabled = enabled;
+        }
+        if let Some(secs) = std::env::var("GATEWAY_FOCUS_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            conversation_focus.ttl = std::time::Duration::from_secs(secs.max(1));
+        }
+
+        // 登录安全策略（支持环境变量覆盖）
+        let login_security_config = std::env::var("GATEWAY_LOGIN_SECURITY_CONFIG")
+            .ok()
+            .or_else(|| service.login_security_config.clone());
+
+        // 安全事件 Webhook（未配置 URL 时不启用）
+        let security_webhook = std::env::var("GATEWAY_SECURITY_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .map(|url| SecurityWebhookConfig {
+                url,
+                secret: std::env::var("GATEWAY_SECURITY_WEBHOOK_SECRET")
+                    .ok()
+                    .filter(|s| !s.is_empty()),
+                previous_secret: std::env::var("GATEWAY_SECURITY_WEBHOOK_PREVIOUS_SECRET")
+                    .ok()
+                    .filter(|s| !s.is_empty()),
+                timeout_ms: std::env::var("GATEWAY_SECURITY_WEBHOOK_TIMEOUT_MS")
+                    .ok()
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .unwrap_or(5000),
+            });
+
+        // 连接管理接口（地址与令牌都配置后启用）
+        let admin_api = std::env::var("GATEWAY_ADMIN_ADDR")
+            .ok()
+            .filter(|addr| !addr.is_empty())
+            .and_then(|address| {
+                let token = std::env::var("GATEWAY_ADMIN_TOKEN")
+                    .ok()
+                    .filter(|t| !t.is_empty());
+                if token.is_none() {
+                    tracing::warn!(
+                        "GATEWAY_ADMIN_ADDR is set without GATEWAY_ADMIN_TOKEN, admin API disabled"
+                    );
+                }
+                token.map(|token| AdminApiConfig { address, token })
+            });
+
+        // 客户端 ACK 写入 ACK 模块（配置 Redis 后启用）
+        let client_ack_recording = std::env::var("GATEWAY_ACK_RECORD_REDIS_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .map(|redis_url| {
+                let defaults = ClientAckRecordingConfig::default();
+                ClientAckRecordingConfig {
+                    redis_url,
+                    default_ack_type: std::env::var("GATEWAY_ACK_RECORD_DEFAULT_TYPE")
+                        .ok()
+                        .and_then(|v| {
+                            crate::infrastructure::messaging::ack_recorder::parse_ack_type(&v)
+                        })
+                        .unwrap_or(defaults.default_ack_type),
+                    importance: std::env::var("GATEWAY_ACK_RECORD_IMPORTANCE")
+                        .ok()
+                        .and_then(|v| {
+                            crate::infrastructure::messaging::ack_recorder::parse_importance(&v)
+                        })
+                        .unwrap_or(defaults.importance),
+                    // 已读回执推送（如 `single,group`，未设置时不推送）
+                    read_receipts: std::env::var("GATEWAY_READ_RECEIPTS")
+                        .ok()
+                        .and_then(|v| ReadReceiptConfig::from_list(&v)),
+                    // 按会话回执策略过滤（`GATEWAY_SESSION_RECEIPTS=true` 启用）
+                    session_receipts: std::env::var("GATEWAY_SESSION_RECEIPTS")
+                        .ok()
+                        .filter(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                        .map(|_| {
+                            let defaults = SessionReceiptsConfig::default();
+                            SessionReceiptsConfig {
+                                default_policy: std::env::var("GATEWAY_SESSION_RECEIPTS_DEFAULT")
+                                    .ok()
+                                    .and_then(|v| ReceiptsPolicy::from_str(&v))
+                                    .unwrap_or(defaults.default_policy),
+                                cache_ttl_secs: std::env::var(
+                                    "GATEWAY_SESSION_RECEIPTS_CACHE_SECS",
+                                )
+                                .ok()
+                                .and_then(|v| v.parse().ok())
+                                .unwrap_or(defaults.cache_ttl_secs),
+                            }
+                        }),
+                }
+            });
+
+        // 续连票据（配置签名密钥后启用，集群默认取网关地区）
+        let resume_ticket = std::env::var("GATEWAY_RESUME_TICKET_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .map(|secret| {
+                let defaults = crate::domain::service::ResumeTicketConfig::default();
+                crate::domain::service::ResumeTicketConfig {
+                    secret,
+                    cluster: std::env::var("GATEWAY_CLUSTER")
+                        .ok()
+                        .filter(|cluster| !cluster.is_empty())
+                        .or_else(|| region.clone())
+                        .unwrap_or(defaults.cluster),
+                    ttl: std::env::var("GATEWAY_RESUME_TICKET_TTL_SECS")
+                        .ok()
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .map(|secs| std::time::Duration::from_secs(secs.max(1)))
+                        .unwrap_or(defaults.ttl),
+                    grace: std::env::var("GATEWAY_RESUME_GRACE_SECS")
+                        .ok()
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .map(std::time::Duration::from_secs)
+                        .unwrap_or(defaults.grace),
+                    max_pending: std::env::var("GATEWAY_RESUME_MAX_PENDING")
+                        .ok()
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .unwrap_or(defaults.max_pending),
+                }
+            });
+
+        // 协议帧录制（`GATEWAY_FRAME_RECORDER_ENABLED=true` 启用）
+        let frame_recorder = std::env::var("GATEWAY_FRAME_RECORDER_ENABLED")
+            .ok()
+            .filter(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .map(|_| {
+                let defaults = crate::domain::service::FrameRecorderConfig::default();
+                crate::domain::service::FrameRecorderConfig {
+                    capacity: std::env::var("GATEWAY_FRAME_RECORDER_CAPACITY")
+                        .ok()
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .map(|capacity| capacity.max(1))
+                        .unwrap_or(defaults.capacity),
+                    max_recordings: std::env::var("GATEWAY_FRAME_RECORDER_MAX_RECORDINGS")
+                        .ok()
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .unwrap_or(defaults.max_recordings),
+                    // 仅在受控环境中关闭负载脱敏
+                    redact_payloads: std::env::var("GATEWAY_FRAME_RECORDER_REDACT_PAYLOADS")
+                        .ok()
+                        .map(|v| !(v.eq_ignore_ascii_case("false") || v == "0"))
+                        .unwrap_or(defaults.redact_payloads),
+                    // 建连时自动录制的用户（逗号分隔）
+                    auto_record_users: std::env::var("GATEWAY_FRAME_RECORDER_USERS")
+                        .ok()
+                        .map(|v| {
+                            v.split(',')
+                                .map(|u| u.trim().to_string())
+                                .filter(|u| !u.is_empty())
+                                .collect()
+                        })
+                        .unwrap_or(defaults.auto_record_users),
+                }
+            });
+
         Self {
             signaling_service,
             route_service,
@@ -146,12 +461,22 @@ impl AccessGatewayConfig {
             token_issuer,
             token_ttl_seconds,
             token_store_redis_url: token_profile.as_ref().map(|p| p.url.clone()),
+            token_keys_file,
             use_ack_report,
             gateway_id,
             region,
             compression_algorithm,
             enable_encryption,
             encryption_key,
+            listener_shards,
+            latency_probe,
+            conversation_focus,
+            login_security_config,
+            security_webhook,
+            admin_api,
+            client_ack_recording,
+            resume_ticket,
+            frame_recorder,
         }
     }
 }
@@ -6,6 +6,7 @@ use flare_proto::signaling::{
 use flare_server_core::error::Result;
 
 use super::model::ConnectionInfo;
+use super::service::login_security_service::SecurityEvent;
 
 /// Signaling Gateway 接口
 ///
@@ -29,3 +30,11 @@ pub trait ConnectionQuery: Send + Sync {
     /// 查询用户的所有连接
     async fn query_user_connections(&self, user_id: &str) -> Result<Vec<ConnectionInfo>>;
 }
+
+/// 安全事件发布接口
+///
+/// 登录异常检测产生的安全事件通过此接口上报（如租户 Webhook）
+#[async_trait]
+pub trait SecurityEventPublisher: Send + Sync {
+    async fn publish(&self, event: &SecurityEvent) -> anyhow::Result<()>;
+}
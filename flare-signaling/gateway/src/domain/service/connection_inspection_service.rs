@@ -0,0 +1,127 @@
+//! 连接巡检领域服务
+//!
+//! 职责：
+//! - 统计每个连接正在发送中的下行帧数量（发送队列深度），供管理接口排查慢连接
+//! - 从连接 metadata 中提取握手协商的能力（压缩、加密、协议版本等）
+//!
+//! 发送队列深度通过 [`OutboundGuard`] 计数：发送前登记，发送结束（无论成功与否）时释放。
+//! 每次下行发送都会登记，计数表按连接分片加锁，避免多个监听分片的发送在同一把锁上排队
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dashmap::DashMap;
+
+/// 客户端声明的能力列表（逗号分隔）
+pub const CAPABILITIES_METADATA_KEY: &str = "capabilities";
+
+/// 握手协商结果在连接 metadata 中的键
+const NEGOTIATED_METADATA_KEYS: &[&str] =
+    &["protocol_version", "format", "compression", "encryption"];
+
+/// 连接巡检服务
+#[derive(Default)]
+pub struct ConnectionInspectionService {
+    /// connection_id -> 发送中的帧数量
+    outbound: DashMap<String, Arc<AtomicUsize>>,
+}
+
+/// 下行发送登记，Drop 时释放计数
+pub struct OutboundGuard {
+    counter: Arc<AtomicUsize>,
+}
+
+impl Drop for OutboundGuard {
+    fn drop(&mut self) {
+        self.counter.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl ConnectionInspectionService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记一次下行发送
+    pub fn begin_send(&self, connection_id: &str) -> OutboundGuard {
+        let counter = self
+            .outbound
+            .entry(connection_id.to_string())
+            .or_default()
+            .clone();
+        counter.fetch_add(1, Ordering::Relaxed);
+        OutboundGuard { counter }
+    }
+
+    /// 连接当前的发送队列深度
+    pub fn queue_depth(&self, connection_id: &str) -> usize {
+        self.outbound
+            .get(connection_id)
+            .map(|counter| counter.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// 连接断开时清理记录
+    pub fn remove_connection(&self, connection_id: &str) {
+        self.outbound.remove(connection_id);
+    }
+
+    /// 从连接 metadata 中提取协商能力
+    ///
+    /// `capabilities` 中的每一项记为 `true`，其余协商结果按原值返回
+    pub fn negotiated_capabilities(metadata: &HashMap<String, String>) -> BTreeMap<String, String> {
+        let mut capabilities: BTreeMap<String, String> = metadata
+            .get(CAPABILITIES_METADATA_KEY)
+            .map(|list| {
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| (item.to_string(), "true".to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for key in NEGOTIATED_METADATA_KEYS {
+            if let Some(value) = metadata.get(*key) {
+                capabilities.insert((*key).to_string(), value.clone());
+            }
+        }
+        capabilities
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_depth_tracks_in_flight_sends() {
+        let service = ConnectionInspectionService::new();
+        let first = service.begin_send("conn-1");
+        let second = service.begin_send("conn-1");
+        assert_eq!(service.queue_depth("conn-1"), 2);
+        assert_eq!(service.queue_depth("conn-2"), 0);
+
+        drop(first);
+        assert_eq!(service.queue_depth("conn-1"), 1);
+
+        // 连接移除后仍在发送的登记释放不会出错
+        service.remove_connection("conn-1");
+        drop(second);
+        assert_eq!(service.queue_depth("conn-1"), 0);
+    }
+
+    #[test]
+    fn test_negotiated_capabilities() {
+        let metadata = HashMap::from([
+            ("capabilities".to_string(), "ack, resume,".to_string()),
+            ("compression".to_string(), "zstd".to_string()),
+            ("tenant_id".to_string(), "t1".to_string()),
+        ]);
+        let capabilities = ConnectionInspectionService::negotiated_capabilities(&metadata);
+        assert_eq!(capabilities.len(), 3);
+        assert_eq!(capabilities["ack"], "true");
+        assert_eq!(capabilities["resume"], "true");
+        assert_eq!(capabilities["compression"], "zstd");
+    }
+}
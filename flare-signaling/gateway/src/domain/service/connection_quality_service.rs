@@ -5,11 +5,12 @@
 //! - 监控丢包率
 //! - 评估网络质量
 //! - 提供质量报告供路由决策使用
+//!
+//! 质量记录按连接分片加锁，心跳上报只锁住所在分片
 
-use std::collections::HashMap;
-use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+
+use dashmap::DashMap;
 use tracing::{debug, warn};
 
 /// 链接质量记录
@@ -67,7 +68,7 @@ impl QualityLevel {
 /// 链接质量监控服务
 pub struct ConnectionQualityService {
     // connection_id -> ConnectionQualityMetrics
-    metrics: Arc<RwLock<HashMap<String, ConnectionQualityMetrics>>>,
+    metrics: DashMap<String, ConnectionQualityMetrics>,
 
     // 质量数据过期时间（默认5分钟）
     expiration: Duration,
@@ -76,7 +77,7 @@ pub struct ConnectionQualityService {
 impl ConnectionQualityService {
     pub fn new() -> Self {
         Self {
-            metrics: Arc::new(RwLock::new(HashMap::new())),
+            metrics: DashMap::new(),
             expiration: Duration::from_secs(300),
         }
     }
@@ -89,9 +90,8 @@ impl ConnectionQualityService {
         device_id: &str,
         rtt_ms: i64,
     ) {
-        let mut metrics_map = self.metrics.write().await;
-
-        let metrics = metrics_map
+        let mut metrics = self
+            .metrics
             .entry(connection_id.to_string())
             .or_insert_with(|| ConnectionQualityMetrics {
                 connection_id: connection_id.to_string(),
@@ -136,9 +136,7 @@ impl ConnectionQualityService {
         packets_sent: u64,
         packets_lost: u64,
     ) {
-        let mut metrics_map = self.metrics.write().await;
-
-        if let Some(metrics) = metrics_map.get_mut(connection_id) {
+        if let Some(mut metrics) = self.metrics.get_mut(connection_id) {
             metrics.packets_sent = packets_sent;
             metrics.packets_lost = packets_lost;
 
@@ -165,9 +163,7 @@ impl ConnectionQualityService {
 
     /// 更新网络类型
     pub async fn update_network_type(&self, connection_id: &str, network_type: String) {
-        let mut metrics_map = self.metrics.write().await;
-
-        if let Some(metrics) = metrics_map.get_mut(connection_id) {
+        if let Some(mut metrics) = self.metrics.get_mut(connection_id) {
             metrics.network_type = network_type;
             metrics.last_update = Instant::now();
         }
@@ -175,17 +171,17 @@ impl ConnectionQualityService {
 
     /// 获取连接质量
     pub async fn get_quality(&self, connection_id: &str) -> Option<ConnectionQualityMetrics> {
-        let metrics_map = self.metrics.read().await;
-        metrics_map.get(connection_id).cloned()
+        self.metrics
+            .get(connection_id)
+            .map(|metrics| metrics.clone())
     }
 
     /// 获取用户所有设备的质量信息
     pub async fn get_user_devices_quality(&self, user_id: &str) -> Vec<ConnectionQualityMetrics> {
-        let metrics_map = self.metrics.read().await;
-        metrics_map
-            .values()
+        self.metrics
+            .iter()
             .filter(|m| m.user_id == user_id)
-            .cloned()
+            .map(|m| m.clone())
             .collect()
     }
 
@@ -208,16 +204,15 @@ impl ConnectionQualityService {
 
     /// 移除连接质量记录
     pub async fn remove_connection(&self, connection_id: &str) {
-        let mut metrics_map = self.metrics.write().await;
-        metrics_map.remove(connection_id);
+        self.metrics.remove(connection_id);
     }
 
     /// 清理过期数据
     pub async fn cleanup_expired(&self) {
-        let mut metrics_map = self.metrics.write().await;
         let now = Instant::now();
 
-        metrics_map.retain(|_, metrics| now.duration_since(metrics.last_update) < self.expiration);
+        self.metrics
+            .retain(|_, metrics| now.duration_since(metrics.last_update) < self.expiration);
     }
 }
 
@@ -42,7 +42,8 @@ impl ConversationDomainService {
 
     /// 注册会话（在线状态）
     ///
-    /// 将用户的连接信息注册到 Signaling Online 服务
+    /// 将用户的连接信息注册到 Signaling Online 服务；
+    /// 续连时携带 `resume_conversation_id`，由 Signaling Online 恢复原会话
     #[instrument(skip(self), fields(user_id, device_id, gateway_id = %self.gateway_id))]
     pub async fn register_session(
         &self,
@@ -50,6 +51,7 @@ impl ConversationDomainService {
         device_id: &str,
         connection_id: Option<&str>,
         connection_metadata: Option<&HashMap<String, String>>,
+        resume_conversation_id: Option<&str>,
     ) -> Result<String> {
         use uuid::Uuid;
 
@@ -83,7 +85,7 @@ impl ConversationDomainService {
             device_priority: 2, // Normal 优先级
             token_version: 0,
             initial_quality: None,
-            resume_conversation_id: String::new(),
+            resume_conversation_id: resume_conversation_id.unwrap_or_default().to_string(),
         };
 
         // 调用 Signaling Online 服务，添加超时保护
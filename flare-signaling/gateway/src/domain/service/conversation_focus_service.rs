@@ -0,0 +1,220 @@
+//! 会话聚焦领域服务
+//!
+//! 职责：
+//! - 记录每个连接当前打开（聚焦）的会话，由客户端通过 FocusConversation 帧上报
+//! - 按聚焦状态决定投递方式：聚焦会话全量推送并优先投递，其他会话降级为通知（客户端按需拉取）
+//! - 用户任一设备正在查看某会话时，该会话的新消息不再提示声音与角标
+//!
+//! 未上报聚焦状态的连接保持全量推送；聚焦状态超过有效期未刷新视为失效（客户端切到后台未及时清除时兜底）
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::domain::model::ConnectionInfo;
+
+/// 会话聚焦配置
+#[derive(Debug, Clone)]
+pub struct ConversationFocusConfig {
+    /// 是否启用聚焦模式（关闭时忽略 FocusConversation 帧）
+    pub enabled: bool,
+    /// 聚焦状态有效期（客户端需在有效期内重新上报）
+    pub ttl: Duration,
+}
+
+impl Default for ConversationFocusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl: Duration::from_secs(600),
+        }
+    }
+}
+
+/// 单个连接的投递方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusDelivery {
+    /// 全量推送消息
+    Full { silent: bool },
+    /// 仅推送会话更新通知，由客户端拉取消息
+    Notify { silent: bool },
+}
+
+impl FocusDelivery {
+    /// 是否不提示声音与角标
+    pub fn is_silent(&self) -> bool {
+        match self {
+            FocusDelivery::Full { silent } | FocusDelivery::Notify { silent } => *silent,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FocusEntry {
+    conversation_id: String,
+    updated_at: Instant,
+}
+
+/// 会话聚焦服务
+pub struct ConversationFocusService {
+    config: ConversationFocusConfig,
+    /// connection_id -> 聚焦的会话
+    focus: Mutex<HashMap<String, FocusEntry>>,
+}
+
+impl ConversationFocusService {
+    pub fn new(config: ConversationFocusConfig) -> Self {
+        Self {
+            config,
+            focus: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &ConversationFocusConfig {
+        &self.config
+    }
+
+    /// 设置连接聚焦的会话（会话ID为空时清除）
+    pub fn set_focus(&self, connection_id: &str, conversation_id: &str) {
+        let mut focus = self.focus.lock().unwrap_or_else(|e| e.into_inner());
+        if conversation_id.is_empty() {
+            focus.remove(connection_id);
+            return;
+        }
+        focus.insert(
+            connection_id.to_string(),
+            FocusEntry {
+                conversation_id: conversation_id.to_string(),
+                updated_at: Instant::now(),
+            },
+        );
+    }
+
+    /// 连接当前聚焦的会话（已过期的视为未聚焦）
+    pub fn focused_conversation(&self, connection_id: &str) -> Option<String> {
+        let focus = self.focus.lock().unwrap_or_else(|e| e.into_inner());
+        focus
+            .get(connection_id)
+            .filter(|entry| entry.updated_at.elapsed() < self.config.ttl)
+            .map(|entry| entry.conversation_id.clone())
+    }
+
+    /// 连接断开时清理聚焦状态
+    pub fn remove_connection(&self, connection_id: &str) {
+        let mut focus = self.focus.lock().unwrap_or_else(|e| e.into_inner());
+        focus.remove(connection_id);
+    }
+
+    /// 为用户的连接规划一条会话消息的投递方式，聚焦该会话的连接排在前面优先投递
+    pub fn plan_delivery(
+        &self,
+        conversation_id: &str,
+        connections: &[ConnectionInfo],
+    ) -> Vec<(ConnectionInfo, FocusDelivery)> {
+        let focused: Vec<Option<String>> = connections
+            .iter()
+            .map(|conn| {
+                if conversation_id.is_empty() {
+                    None
+                } else {
+                    self.focused_conversation(&conn.connection_id)
+                }
+            })
+            .collect();
+        // 用户任一设备正在查看该会话时，所有设备均不提示
+        let silent = focused
+            .iter()
+            .any(|focus| focus.as_deref() == Some(conversation_id));
+
+        let mut plan: Vec<(bool, ConnectionInfo, FocusDelivery)> = connections
+            .iter()
+            .zip(focused)
+            .map(|(conn, focus)| match focus.as_deref() {
+                Some(focused) if focused == conversation_id => {
+                    (true, conn.clone(), FocusDelivery::Full { silent })
+                }
+                Some(_) => (false, conn.clone(), FocusDelivery::Notify { silent }),
+                None => (false, conn.clone(), FocusDelivery::Full { silent }),
+            })
+            .collect();
+        plan.sort_by_key(|(focused_here, _, _)| !focused_here);
+        plan.into_iter()
+            .map(|(_, conn, delivery)| (conn, delivery))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection(connection_id: &str) -> ConnectionInfo {
+        ConnectionInfo {
+            connection_id: connection_id.to_string(),
+            protocol: "websocket".to_string(),
+            device_id: format!("device-{}", connection_id),
+            platform: "ios".to_string(),
+            connected_at: None,
+            last_active_at: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_delivery_by_focus() {
+        let service = ConversationFocusService::new(ConversationFocusConfig::default());
+        let connections = vec![
+            connection("conn-1"),
+            connection("conn-2"),
+            connection("conn-3"),
+        ];
+        service.set_focus("conn-2", "conv-a");
+        service.set_focus("conn-3", "conv-b");
+
+        // 聚焦 conv-a 的连接优先并全量推送；聚焦其他会话的连接降级为通知；任一设备聚焦时全部静默
+        let plan = service.plan_delivery("conv-a", &connections);
+        let plan: Vec<(&str, FocusDelivery)> = plan
+            .iter()
+            .map(|(conn, delivery)| (conn.connection_id.as_str(), *delivery))
+            .collect();
+        assert_eq!(
+            plan,
+            vec![
+                ("conn-2", FocusDelivery::Full { silent: true }),
+                ("conn-1", FocusDelivery::Full { silent: true }),
+                ("conn-3", FocusDelivery::Notify { silent: true }),
+            ]
+        );
+
+        // 无人聚焦的会话正常提示
+        let plan = service.plan_delivery("conv-c", &connections);
+        assert!(plan.iter().all(|(_, delivery)| !delivery.is_silent()));
+        assert_eq!(
+            plan.iter()
+                .filter(|(_, delivery)| matches!(delivery, FocusDelivery::Notify { .. }))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_focus_cleared_and_expired() {
+        let service = ConversationFocusService::new(ConversationFocusConfig {
+            enabled: true,
+            ttl: Duration::from_millis(0),
+        });
+        service.set_focus("conn-1", "conv-a");
+        assert_eq!(service.focused_conversation("conn-1"), None);
+
+        let service = ConversationFocusService::new(ConversationFocusConfig::default());
+        service.set_focus("conn-1", "conv-a");
+        assert_eq!(
+            service.focused_conversation("conn-1").as_deref(),
+            Some("conv-a")
+        );
+        service.set_focus("conn-1", "");
+        assert_eq!(service.focused_conversation("conn-1"), None);
+        service.set_focus("conn-1", "conv-a");
+        service.remove_connection("conn-1");
+        assert_eq!(service.focused_conversation("conn-1"), None);
+    }
+}
@@ -0,0 +1,380 @@
+//! 协议帧录制领域服务
+//!
+//! 职责：
+//! - 为运维指定的连接录制上下行协议帧（按连接的环形缓冲区，超出容量时丢弃最早的帧）
+//! - 录制前脱敏：元数据中的令牌、密钥、签名等字段替换为 `[REDACTED]`，
+//!   默认清空消息负载（只保留原始长度）
+//! - 导出录制结果，供 `frame-replay` 工具回放到测试网关复现客户端协议问题
+//!
+//! 录制默认关闭，需通过管理接口按连接开启，或配置用户列表在其连接建立时自动开启；
+//! 连接断开后录制结果保留到被停止为止，便于事后导出
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use flare_core::common::protocol::Frame;
+use flare_core::common::protocol::flare::core::commands::command::Type as CommandType;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+/// 脱敏后的替换值
+const REDACTED: &[u8] = b"[REDACTED]";
+
+/// 元数据键包含以下片段（不区分大小写）时脱敏
+const SENSITIVE_KEY_FRAGMENTS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "authorization",
+    "signature",
+    "ticket",
+    "credential",
+];
+
+/// 帧录制配置
+#[derive(Debug, Clone)]
+pub struct FrameRecorderConfig {
+    /// 每个连接保留的最大帧数
+    pub capacity: usize,
+    /// 同时存在的最大录制数（含已断开但未停止的连接）
+    pub max_recordings: usize,
+    /// 是否清空消息负载（关闭后回放可复现与负载内容相关的问题，仅用于受控环境）
+    pub redact_payloads: bool,
+    /// 连接建立时自动开启录制的用户
+    pub auto_record_users: HashSet<String>,
+}
+
+impl Default for FrameRecorderConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 512,
+            max_recordings: 64,
+            redact_payloads: true,
+            auto_record_users: HashSet::new(),
+        }
+    }
+}
+
+/// 帧方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameDirection {
+    /// 客户端 → 网关
+    Inbound,
+    /// 网关 → 客户端
+    Outbound,
+}
+
+/// 录制的一帧
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// 连接内的帧序号（从 1 开始，丢弃的帧仍占用序号）
+    pub seq: u64,
+    pub direction: FrameDirection,
+    pub recorded_at: DateTime<Utc>,
+    /// 帧类型摘要（如 `message:0`、`custom:SyncMessages`）
+    pub kind: String,
+    /// 脱敏前的负载长度
+    pub payload_len: usize,
+    /// 脱敏后的帧（protobuf 编码，base64）
+    pub frame: String,
+}
+
+impl RecordedFrame {
+    /// 解码录制的帧
+    pub fn decode_frame(&self) -> anyhow::Result<Frame> {
+        let bytes = base64::engine::general_purpose::STANDARD.decode(&self.frame)?;
+        Ok(Frame::decode(bytes.as_slice())?)
+    }
+}
+
+/// 连接的录制结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameRecording {
+    pub connection_id: String,
+    pub user_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// 连接断开时间（连接仍在线时为空）
+    pub closed_at: Option<DateTime<Utc>>,
+    /// 负载是否已清空
+    pub payloads_redacted: bool,
+    /// 因超出容量被丢弃的帧数
+    pub dropped: u64,
+    pub frames: Vec<RecordedFrame>,
+}
+
+/// 开启录制失败
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FrameRecorderError {
+    #[error("too many active frame recordings (max {0})")]
+    TooManyRecordings(usize),
+}
+
+#[derive(Debug)]
+struct Recording {
+    user_id: Option<String>,
+    started_at: DateTime<Utc>,
+    closed_at: Option<DateTime<Utc>>,
+    next_seq: u64,
+    dropped: u64,
+    frames: VecDeque<RecordedFrame>,
+}
+
+/// 协议帧录制服务
+pub struct FrameRecorderService {
+    config: FrameRecorderConfig,
+    /// connection_id -> 录制
+    recordings: Mutex<HashMap<String, Recording>>,
+}
+
+impl FrameRecorderService {
+    pub fn new(config: FrameRecorderConfig) -> Self {
+        Self {
+            config,
+            recordings: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 开启连接的录制（已在录制时保留已录制的帧）
+    pub fn start(
+        &self,
+        connection_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<(), FrameRecorderError> {
+        let mut recordings = self.recordings.lock().unwrap_or_else(|e| e.into_inner());
+        if recordings.contains_key(connection_id) {
+            return Ok(());
+        }
+        if recordings.len() >= self.config.max_recordings {
+            return Err(FrameRecorderError::TooManyRecordings(
+                self.config.max_recordings,
+            ));
+        }
+        recordings.insert(
+            connection_id.to_string(),
+            Recording {
+                user_id: user_id.map(str::to_string),
+                started_at: Utc::now(),
+                closed_at: None,
+                next_seq: 1,
+                dropped: 0,
+                frames: VecDeque::new(),
+            },
+        );
+        Ok(())
+    }
+
+    /// 用户的连接是否需要在建立时自动录制
+    pub fn should_auto_record(&self, user_id: &str) -> bool {
+        self.config.auto_record_users.contains(user_id)
+    }
+
+    /// 连接是否正在录制（已断开的连接不再录制）
+    pub fn is_recording(&self, connection_id: &str) -> bool {
+        self.recordings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(connection_id)
+            .is_some_and(|recording| recording.closed_at.is_none())
+    }
+
+    /// 录制一帧（连接未在录制时忽略）
+    pub fn record(&self, connection_id: &str, direction: FrameDirection, frame: &Frame) {
+        if !self.is_recording(connection_id) {
+            return;
+        }
+        // 脱敏与编码在锁外完成
+        let (redacted, payload_len) = redact_frame(frame, self.config.redact_payloads);
+        let kind = frame_kind(&redacted);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(redacted.encode_to_vec());
+
+        let mut recordings = self.recordings.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(recording) = recordings
+            .get_mut(connection_id)
+            .filter(|recording| recording.closed_at.is_none())
+        else {
+            return;
+        };
+        if recording.frames.len() >= self.config.capacity.max(1) {
+            recording.frames.pop_front();
+            recording.dropped += 1;
+        }
+        recording.frames.push_back(RecordedFrame {
+            seq: recording.next_seq,
+            direction,
+            recorded_at: Utc::now(),
+            kind,
+            payload_len,
+            frame: encoded,
+        });
+        recording.next_seq += 1;
+    }
+
+    /// 导出录制结果
+    pub fn dump(&self, connection_id: &str) -> Option<FrameRecording> {
+        self.recordings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(connection_id)
+            .map(|recording| self.snapshot(connection_id, recording))
+    }
+
+    /// 停止录制并返回录制结果
+    pub fn stop(&self, connection_id: &str) -> Option<FrameRecording> {
+        self.recordings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(connection_id)
+            .map(|recording| self.snapshot(connection_id, &recording))
+    }
+
+    /// 连接断开时结束录制（录制结果保留到被停止）
+    pub fn close_connection(&self, connection_id: &str) {
+        if let Some(recording) = self
+            .recordings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(connection_id)
+        {
+            recording.closed_at.get_or_insert_with(Utc::now);
+        }
+    }
+
+    fn snapshot(&self, connection_id: &str, recording: &Recording) -> FrameRecording {
+        FrameRecording {
+            connection_id: connection_id.to_string(),
+            user_id: recording.user_id.clone(),
+            started_at: recording.started_at,
+            closed_at: recording.closed_at,
+            payloads_redacted: self.config.redact_payloads,
+            dropped: recording.dropped,
+            frames: recording.frames.iter().cloned().collect(),
+        }
+    }
+}
+
+/// 帧类型摘要
+pub fn frame_kind(frame: &Frame) -> String {
+    match frame.command.as_ref().and_then(|cmd| cmd.r#type.as_ref()) {
+        Some(CommandType::Message(cmd)) => format!("message:{}", cmd.r#type),
+        Some(CommandType::Custom(cmd)) => format!("custom:{}", cmd.name),
+        Some(_) => "other".to_string(),
+        None => "empty".to_string(),
+    }
+}
+
+/// 脱敏帧，返回脱敏后的帧与原始负载长度
+fn redact_frame(frame: &Frame, redact_payloads: bool) -> (Frame, usize) {
+    let mut frame = frame.clone();
+    redact_metadata(&mut frame.metadata);
+
+    let mut payload_len = 0;
+    match frame.command.as_mut().and_then(|cmd| cmd.r#type.as_mut()) {
+        Some(CommandType::Message(cmd)) => {
+            redact_metadata(&mut cmd.metadata);
+            payload_len = cmd.payload.len();
+            if redact_payloads {
+                cmd.payload.clear();
+            }
+        }
+        Some(CommandType::Custom(cmd)) => {
+            redact_metadata(&mut cmd.metadata);
+            payload_len = cmd.data.len();
+            if redact_payloads {
+                cmd.data.clear();
+            }
+        }
+        _ => {}
+    }
+    (frame, payload_len)
+}
+
+fn redact_metadata(metadata: &mut HashMap<String, Vec<u8>>) {
+    for (key, value) in metadata.iter_mut() {
+        let key = key.to_ascii_lowercase();
+        if SENSITIVE_KEY_FRAGMENTS
+            .iter()
+            .any(|fragment| key.contains(fragment))
+        {
+            *value = REDACTED.to_vec();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flare_core::common::protocol::{MessageCommand, Reliability, frame_with_message_command};
+
+    fn message_frame(payload: &[u8]) -> Frame {
+        let mut metadata = HashMap::new();
+        metadata.insert("conversation_id".to_string(), b"conv-1".to_vec());
+        metadata.insert("auth_token".to_string(), b"secret-token".to_vec());
+        frame_with_message_command(
+            MessageCommand {
+                r#type: 0,
+                message_id: "m-1".to_string(),
+                payload: payload.to_vec(),
+                metadata,
+                seq: 0,
+            },
+            Reliability::AtLeastOnce,
+        )
+    }
+
+    #[test]
+    fn test_record_redacts_and_evicts_oldest() {
+        let service = FrameRecorderService::new(FrameRecorderConfig {
+            capacity: 2,
+            ..Default::default()
+        });
+        // 未开启录制的连接不记录
+        service.record("conn-1", FrameDirection::Inbound, &message_frame(b"hello"));
+        assert!(service.dump("conn-1").is_none());
+
+        service.start("conn-1", Some("u1")).unwrap();
+        for _ in 0..3 {
+            service.record("conn-1", FrameDirection::Inbound, &message_frame(b"hello"));
+        }
+
+        let recording = service.dump("conn-1").unwrap();
+        assert_eq!(recording.dropped, 1);
+        assert_eq!(
+            recording.frames.iter().map(|f| f.seq).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        let recorded = &recording.frames[0];
+        assert_eq!(recorded.kind, "message:0");
+        assert_eq!(recorded.payload_len, 5);
+
+        let frame = recorded.decode_frame().unwrap();
+        let Some(CommandType::Message(cmd)) = frame.command.and_then(|c| c.r#type) else {
+            panic!("expected message command");
+        };
+        assert!(cmd.payload.is_empty());
+        assert_eq!(cmd.metadata["auth_token"], REDACTED);
+        assert_eq!(cmd.metadata["conversation_id"], b"conv-1");
+    }
+
+    #[test]
+    fn test_closed_recording_kept_until_stopped() {
+        let service = FrameRecorderService::new(FrameRecorderConfig {
+            max_recordings: 1,
+            ..Default::default()
+        });
+        service.start("conn-1", None).unwrap();
+        assert_eq!(
+            service.start("conn-2", None),
+            Err(FrameRecorderError::TooManyRecordings(1))
+        );
+
+        service.close_connection("conn-1");
+        service.record("conn-1", FrameDirection::Outbound, &message_frame(b"late"));
+        let recording = service.stop("conn-1").unwrap();
+        assert!(recording.closed_at.is_some());
+        assert!(recording.frames.is_empty());
+        assert!(service.dump("conn-1").is_none());
+    }
+}
@@ -0,0 +1,220 @@
+//! 延迟探测领域服务
+//!
+//! 职责：
+//! - 记录客户端通过 LatencyProbe 帧上报的到当前网关的 RTT
+//! - 按客户端地理位置（client_geo）汇总本网关所在地区的延迟，定期上报 Route 服务
+//! - 识别持续高延迟的连接，触发接入地区推荐
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 延迟探测配置
+#[derive(Debug, Clone)]
+pub struct LatencyProbeConfig {
+    /// 高延迟阈值（毫秒）
+    pub high_rtt_ms: u64,
+    /// 连续多少次高延迟视为持续高延迟
+    pub high_rtt_streak: u32,
+    /// 同一连接两次地区推荐的最小间隔
+    pub recommend_cooldown: Duration,
+    /// 汇总数据上报间隔
+    pub report_interval: Duration,
+}
+
+impl Default for LatencyProbeConfig {
+    fn default() -> Self {
+        Self {
+            high_rtt_ms: 250,
+            high_rtt_streak: 5,
+            recommend_cooldown: Duration::from_secs(600),
+            report_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// 某个客户端地理位置在本网关的延迟汇总
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoLatencySample {
+    pub client_geo: String,
+    pub sample_count: u64,
+    pub avg_rtt_ms: f64,
+}
+
+#[derive(Debug, Default)]
+struct GeoAccumulator {
+    sample_count: u64,
+    rtt_sum_ms: u64,
+}
+
+#[derive(Debug, Default)]
+struct ConnectionProbeState {
+    high_rtt_streak: u32,
+    last_recommended_at: Option<Instant>,
+    /// 待随下一次探测回显返回的推荐地区
+    recommended_region: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct ProbeState {
+    pending: HashMap<String, GeoAccumulator>,
+    connections: HashMap<String, ConnectionProbeState>,
+}
+
+/// 延迟探测服务
+pub struct LatencyProbeService {
+    region: String,
+    config: LatencyProbeConfig,
+    state: Mutex<ProbeState>,
+}
+
+impl LatencyProbeService {
+    pub fn new(region: String, config: LatencyProbeConfig) -> Self {
+        Self {
+            region,
+            config,
+            state: Mutex::new(ProbeState::default()),
+        }
+    }
+
+    /// 本网关所在地区
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    pub fn config(&self) -> &LatencyProbeConfig {
+        &self.config
+    }
+
+    /// 记录一次探测结果，返回是否需要为该连接推荐其他地区
+    pub fn record(&self, connection_id: &str, client_geo: &str, rtt_ms: u64) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let accumulator = state.pending.entry(client_geo.to_string()).or_default();
+        accumulator.sample_count += 1;
+        accumulator.rtt_sum_ms += rtt_ms;
+
+        let connection = state.connections.entry(connection_id.to_string()).or_default();
+        if rtt_ms < self.config.high_rtt_ms {
+            connection.high_rtt_streak = 0;
+            return false;
+        }
+        connection.high_rtt_streak += 1;
+        if connection.high_rtt_streak < self.config.high_rtt_streak {
+            return false;
+        }
+
+        let cooling_down = connection
+            .last_recommended_at
+            .is_some_and(|at| at.elapsed() < self.config.recommend_cooldown);
+        if cooling_down {
+            return false;
+        }
+        connection.last_recommended_at = Some(Instant::now());
+        true
+    }
+
+    /// 取出上次上报以来的汇总数据
+    pub fn drain_samples(&self) -> Vec<GeoLatencySample> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .pending
+            .drain()
+            .filter(|(_, acc)| acc.sample_count > 0)
+            .map(|(client_geo, acc)| GeoLatencySample {
+                client_geo,
+                sample_count: acc.sample_count,
+                avg_rtt_ms: acc.rtt_sum_ms as f64 / acc.sample_count as f64,
+            })
+            .collect()
+    }
+
+    /// 保存连接的推荐地区（连接已断开时忽略）
+    pub fn set_recommendation(&self, connection_id: &str, region: String) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(connection) = state.connections.get_mut(connection_id) {
+            connection.recommended_region = Some(region);
+        }
+    }
+
+    /// 取出连接待返回的推荐地区
+    pub fn take_recommendation(&self, connection_id: &str) -> Option<String> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .connections
+            .get_mut(connection_id)
+            .and_then(|connection| connection.recommended_region.take())
+    }
+
+    /// 连接断开时清理探测状态
+    pub fn remove_connection(&self, connection_id: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.connections.remove(connection_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> LatencyProbeService {
+        LatencyProbeService::new(
+            "shanghai".to_string(),
+            LatencyProbeConfig {
+                high_rtt_ms: 200,
+                high_rtt_streak: 3,
+                ..LatencyProbeConfig::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_recommend_after_persistent_high_latency() {
+        let service = service();
+        assert!(!service.record("conn-1", "US", 300));
+        assert!(!service.record("conn-1", "US", 300));
+        // 中途恢复正常则重新计数
+        assert!(!service.record("conn-1", "US", 50));
+        assert!(!service.record("conn-1", "US", 300));
+        assert!(!service.record("conn-1", "US", 300));
+        assert!(service.record("conn-1", "US", 300));
+        // 冷却期内不重复推荐
+        assert!(!service.record("conn-1", "US", 300));
+
+        service.set_recommendation("conn-1", "us-west".to_string());
+        assert_eq!(service.take_recommendation("conn-1").as_deref(), Some("us-west"));
+        assert_eq!(service.take_recommendation("conn-1"), None);
+
+        // 已断开的连接不保存推荐
+        service.remove_connection("conn-1");
+        service.set_recommendation("conn-1", "us-west".to_string());
+        assert_eq!(service.take_recommendation("conn-1"), None);
+    }
+
+    #[test]
+    fn test_drain_samples_aggregates_by_geo() {
+        let service = service();
+        service.record("conn-1", "US", 300);
+        service.record("conn-2", "US", 100);
+        service.record("conn-3", "CN-East", 20);
+
+        let mut samples = service.drain_samples();
+        samples.sort_by(|a, b| a.client_geo.cmp(&b.client_geo));
+        assert_eq!(
+            samples,
+            vec![
+                GeoLatencySample {
+                    client_geo: "CN-East".to_string(),
+                    sample_count: 1,
+                    avg_rtt_ms: 20.0,
+                },
+                GeoLatencySample {
+                    client_geo: "US".to_string(),
+                    sample_count: 2,
+                    avg_rtt_ms: 200.0,
+                },
+            ]
+        );
+        assert!(service.drain_samples().is_empty());
+    }
+}
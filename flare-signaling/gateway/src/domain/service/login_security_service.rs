@@ -0,0 +1,593 @@
+//! 登录安全领域服务
+//!
+//! 职责：
+//! - 按租户/用户记录登录特征（设备、IP、地理位置、时间），识别异常登录：
+//!   - 异地登录：两次登录的地理距离超出合理移动速度（含同时在相距很远的地区登录）
+//!   - 登录频繁：同一用户在窗口期内登录次数超过阈值
+//!   - 撞库：同一 IP 在窗口期内认证失败次数超过阈值
+//! - 通过 `SecurityEventPublisher` 上报安全事件（后台发送，失败只记录日志）
+//! - 租户开启 step-up 时要求重新认证：断开异常连接，撞库 IP 在封禁期内直接拒绝认证
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::domain::repository::SecurityEventPublisher;
+
+/// 连接 metadata 中的客户端 IP
+pub const METADATA_KEY_CLIENT_IP: &str = "client_ip";
+/// 连接 metadata 中的客户端地区（与延迟探测的 client_geo 一致）
+pub const METADATA_KEY_CLIENT_GEO: &str = "client_geo";
+/// 连接 metadata 中的客户端纬度
+pub const METADATA_KEY_GEO_LAT: &str = "geo_lat";
+/// 连接 metadata 中的客户端经度
+pub const METADATA_KEY_GEO_LON: &str = "geo_lon";
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// 登录安全策略（按租户配置，未配置的字段使用默认值）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LoginSecurityPolicy {
+    /// 是否启用异常检测
+    pub enabled: bool,
+    /// 登录记录与认证失败的统计窗口（秒）
+    pub window_secs: u64,
+    /// 窗口期内同一用户允许的最大登录次数
+    pub max_logins_per_window: usize,
+    /// 窗口期内同一 IP 允许的最大认证失败次数
+    pub max_failures_per_ip: usize,
+    /// 两次登录距离超过该值（公里）才检查移动速度
+    pub min_travel_distance_km: f64,
+    /// 合理的最大移动速度（公里/小时），超过视为异地登录
+    pub max_travel_speed_kmh: f64,
+    /// 检测到异常时是否要求重新认证
+    pub step_up: bool,
+    /// 撞库 IP 的封禁时长（秒，仅 step_up 开启时生效）
+    pub block_secs: u64,
+}
+
+impl Default for LoginSecurityPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_secs: 600,
+            max_logins_per_window: 20,
+            max_failures_per_ip: 10,
+            min_travel_distance_km: 500.0,
+            max_travel_speed_kmh: 900.0,
+            step_up: false,
+            block_secs: 300,
+        }
+    }
+}
+
+impl LoginSecurityPolicy {
+    fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs.max(1))
+    }
+}
+
+/// 登录安全配置
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LoginSecurityConfig {
+    /// 默认策略（认证失败时无法确定租户，使用默认策略）
+    #[serde(rename = "default")]
+    pub default_policy: LoginSecurityPolicy,
+    /// 租户策略：租户ID -> 策略
+    pub tenants: HashMap<String, LoginSecurityPolicy>,
+}
+
+impl LoginSecurityConfig {
+    /// 从 TOML 文件加载
+    pub fn from_file(path: &str) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read login security config {}: {}", path, e))?;
+        toml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse login security config {}: {}", path, e))
+    }
+
+    pub fn policy_for(&self, tenant_id: &str) -> &LoginSecurityPolicy {
+        self.tenants.get(tenant_id).unwrap_or(&self.default_policy)
+    }
+}
+
+/// 地理坐标
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl GeoPoint {
+    /// 两点间的球面距离（公里）
+    pub fn distance_km(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.lon - self.lon).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
+
+/// 一次成功登录
+#[derive(Debug, Clone)]
+pub struct LoginAttempt {
+    pub tenant_id: String,
+    pub user_id: String,
+    pub device_id: String,
+    pub connection_id: String,
+    pub ip: Option<String>,
+    pub region: Option<String>,
+    pub geo: Option<GeoPoint>,
+}
+
+impl LoginAttempt {
+    /// 从连接 metadata 中提取 IP、地区与坐标
+    pub fn from_metadata(
+        tenant_id: &str,
+        user_id: &str,
+        device_id: &str,
+        connection_id: &str,
+        metadata: Option<&HashMap<String, String>>,
+    ) -> Self {
+        let get = |key: &str| {
+            metadata
+                .and_then(|m| m.get(key))
+                .filter(|v| !v.is_empty())
+                .cloned()
+        };
+        let coordinate = |key: &str| get(key).and_then(|v| v.parse::<f64>().ok());
+        let geo = match (
+            coordinate(METADATA_KEY_GEO_LAT),
+            coordinate(METADATA_KEY_GEO_LON),
+        ) {
+            (Some(lat), Some(lon))
+                if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) =>
+            {
+                Some(GeoPoint { lat, lon })
+            }
+            _ => None,
+        };
+
+        Self {
+            tenant_id: tenant_id.to_string(),
+            user_id: user_id.to_string(),
+            device_id: device_id.to_string(),
+            connection_id: connection_id.to_string(),
+            ip: get(METADATA_KEY_CLIENT_IP),
+            region: get(METADATA_KEY_CLIENT_GEO),
+            geo,
+        }
+    }
+}
+
+/// 异常登录类型
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LoginAnomaly {
+    /// 异地登录（移动速度超出合理范围）
+    ImpossibleTravel {
+        previous_device_id: String,
+        previous_ip: Option<String>,
+        previous_region: Option<String>,
+        distance_km: f64,
+        speed_kmh: f64,
+    },
+    /// 同一用户登录过于频繁
+    LoginBurst { logins: usize, window_secs: u64 },
+    /// 同一 IP 认证失败过多（疑似撞库）
+    CredentialStuffing { failures: usize, window_secs: u64 },
+}
+
+impl LoginAnomaly {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginAnomaly::ImpossibleTravel { .. } => "impossible_travel",
+            LoginAnomaly::LoginBurst { .. } => "login_burst",
+            LoginAnomaly::CredentialStuffing { .. } => "credential_stuffing",
+        }
+    }
+}
+
+/// 安全事件
+#[derive(Debug, Clone, Serialize)]
+pub struct SecurityEvent {
+    pub event_id: String,
+    pub tenant_id: String,
+    pub user_id: Option<String>,
+    pub device_id: Option<String>,
+    pub connection_id: Option<String>,
+    pub ip: Option<String>,
+    pub region: Option<String>,
+    pub gateway_id: String,
+    pub anomaly: LoginAnomaly,
+    /// 是否已要求重新认证
+    pub step_up: bool,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// 登录检测结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoginVerdict {
+    pub anomalies: Vec<LoginAnomaly>,
+    /// 是否需要断开连接并要求重新认证
+    pub step_up: bool,
+}
+
+#[derive(Debug, Clone)]
+struct LoginRecord {
+    device_id: String,
+    ip: Option<String>,
+    region: Option<String>,
+    geo: Option<GeoPoint>,
+    at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct SecurityState {
+    /// (租户ID, 用户ID) -> 窗口期内的登录记录
+    logins: HashMap<(String, String), VecDeque<LoginRecord>>,
+    /// IP -> 窗口期内的认证失败时间
+    failures: HashMap<String, VecDeque<Instant>>,
+    /// IP -> 封禁截止时间
+    blocked: HashMap<String, Instant>,
+}
+
+/// 登录安全服务
+pub struct LoginSecurityService {
+    gateway_id: String,
+    config: LoginSecurityConfig,
+    publisher: Option<Arc<dyn SecurityEventPublisher>>,
+    state: Mutex<SecurityState>,
+}
+
+impl LoginSecurityService {
+    pub fn new(gateway_id: String, config: LoginSecurityConfig) -> Self {
+        Self {
+            gateway_id,
+            config,
+            publisher: None,
+            state: Mutex::new(SecurityState::default()),
+        }
+    }
+
+    /// 设置安全事件发布者
+    pub fn with_publisher(mut self, publisher: Arc<dyn SecurityEventPublisher>) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// 记录一次成功登录，返回检测结果并上报安全事件
+    pub fn on_login(&self, attempt: &LoginAttempt) -> LoginVerdict {
+        let verdict = self.check_login(attempt, Instant::now());
+        if !verdict.anomalies.is_empty() {
+            self.emit(
+                &attempt.tenant_id,
+                Some(attempt),
+                attempt.ip.clone(),
+                &verdict,
+            );
+        }
+        verdict
+    }
+
+    /// 记录一次认证失败，返回检测结果并上报安全事件
+    ///
+    /// 认证失败时无法从令牌中确定租户，由调用方传入默认租户，检测使用默认策略
+    pub fn on_auth_failure(&self, tenant_id: &str, ip: &str) -> LoginVerdict {
+        let verdict = self.check_failure(ip, Instant::now());
+        if !verdict.anomalies.is_empty() {
+            self.emit(tenant_id, None, Some(ip.to_string()), &verdict);
+        }
+        verdict
+    }
+
+    /// IP 是否处于封禁期（撞库后要求重新认证）
+    pub fn is_blocked(&self, ip: &str) -> bool {
+        self.is_blocked_at(ip, Instant::now())
+    }
+
+    fn is_blocked_at(&self, ip: &str, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.blocked.get(ip) {
+            Some(until) if *until > now => true,
+            Some(_) => {
+                state.blocked.remove(ip);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// 清理窗口期外的登录记录、认证失败记录和已过期的封禁
+    pub fn prune(&self) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.logins.retain(|(tenant_id, _), records| {
+            let window = self.config.policy_for(tenant_id).window();
+            records
+                .back()
+                .is_some_and(|record| now.duration_since(record.at) < window)
+        });
+        let window = self.config.default_policy.window();
+        state.failures.retain(|_, failures| {
+            failures
+                .back()
+                .is_some_and(|at| now.duration_since(*at) < window)
+        });
+        state.blocked.retain(|_, until| *until > now);
+    }
+
+    fn check_login(&self, attempt: &LoginAttempt, now: Instant) -> LoginVerdict {
+        let policy = self.config.policy_for(&attempt.tenant_id);
+        if !policy.enabled {
+            return LoginVerdict::default();
+        }
+        let window = policy.window();
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let records = state
+            .logins
+            .entry((attempt.tenant_id.clone(), attempt.user_id.clone()))
+            .or_default();
+        while records
+            .front()
+            .is_some_and(|record| now.duration_since(record.at) >= window)
+        {
+            records.pop_front();
+        }
+
+        let mut anomalies = Vec::new();
+        if let Some(geo) = attempt.geo {
+            // 取速度最大的一次作为异地登录证据
+            let travel = records
+                .iter()
+                .filter_map(|record| {
+                    let distance_km = record.geo?.distance_km(&geo);
+                    if distance_km < policy.min_travel_distance_km {
+                        return None;
+                    }
+                    // 不足一分钟按一分钟计算，避免除零
+                    let hours = now.duration_since(record.at).as_secs_f64().max(60.0) / 3600.0;
+                    let speed_kmh = distance_km / hours;
+                    (speed_kmh > policy.max_travel_speed_kmh).then_some((
+                        record,
+                        distance_km,
+                        speed_kmh,
+                    ))
+                })
+                .max_by(|a, b| a.2.total_cmp(&b.2));
+            if let Some((record, distance_km, speed_kmh)) = travel {
+                anomalies.push(LoginAnomaly::ImpossibleTravel {
+                    previous_device_id: record.device_id.clone(),
+                    previous_ip: record.ip.clone(),
+                    previous_region: record.region.clone(),
+                    distance_km: distance_km.round(),
+                    speed_kmh: speed_kmh.round(),
+                });
+            }
+        }
+
+        records.push_back(LoginRecord {
+            device_id: attempt.device_id.clone(),
+            ip: attempt.ip.clone(),
+            region: attempt.region.clone(),
+            geo: attempt.geo,
+            at: now,
+        });
+        if records.len() > policy.max_logins_per_window {
+            anomalies.push(LoginAnomaly::LoginBurst {
+                logins: records.len(),
+                window_secs: policy.window_secs,
+            });
+        }
+
+        LoginVerdict {
+            step_up: policy.step_up && !anomalies.is_empty(),
+            anomalies,
+        }
+    }
+
+    fn check_failure(&self, ip: &str, now: Instant) -> LoginVerdict {
+        let policy = &self.config.default_policy;
+        if !policy.enabled {
+            return LoginVerdict::default();
+        }
+        let window = policy.window();
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let failures = state.failures.entry(ip.to_string()).or_default();
+        while failures
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= window)
+        {
+            failures.pop_front();
+        }
+        failures.push_back(now);
+        let count = failures.len();
+        if count <= policy.max_failures_per_ip {
+            return LoginVerdict::default();
+        }
+
+        // 超过阈值后重新计数，避免每次失败都上报
+        failures.clear();
+        if policy.step_up {
+            state
+                .blocked
+                .insert(ip.to_string(), now + Duration::from_secs(policy.block_secs));
+        }
+        LoginVerdict {
+            anomalies: vec![LoginAnomaly::CredentialStuffing {
+                failures: count,
+                window_secs: policy.window_secs,
+            }],
+            step_up: policy.step_up,
+        }
+    }
+
+    fn emit(
+        &self,
+        tenant_id: &str,
+        attempt: Option<&LoginAttempt>,
+        ip: Option<String>,
+        verdict: &LoginVerdict,
+    ) {
+        for anomaly in &verdict.anomalies {
+            warn!(
+                tenant_id = %tenant_id,
+                user_id = ?attempt.map(|a| a.user_id.as_str()),
+                ip = ?ip,
+                anomaly = %anomaly.as_str(),
+                step_up = verdict.step_up,
+                "Login anomaly detected"
+            );
+        }
+
+        let Some(publisher) = self.publisher.clone() else {
+            return;
+        };
+        let events: Vec<SecurityEvent> = verdict
+            .anomalies
+            .iter()
+            .map(|anomaly| SecurityEvent {
+                event_id: Uuid::new_v4().to_string(),
+                tenant_id: tenant_id.to_string(),
+                user_id: attempt.map(|a| a.user_id.clone()),
+                device_id: attempt.map(|a| a.device_id.clone()),
+                connection_id: attempt.map(|a| a.connection_id.clone()),
+                ip: ip.clone(),
+                region: attempt.and_then(|a| a.region.clone()),
+                gateway_id: self.gateway_id.clone(),
+                anomaly: anomaly.clone(),
+                step_up: verdict.step_up,
+                occurred_at: Utc::now(),
+            })
+            .collect();
+        tokio::spawn(async move {
+            for event in events {
+                if let Err(err) = publisher.publish(&event).await {
+                    warn!(
+                        error = %err,
+                        event_id = %event.event_id,
+                        anomaly = %event.anomaly.as_str(),
+                        "Failed to publish security event"
+                    );
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHANGHAI: GeoPoint = GeoPoint {
+        lat: 31.23,
+        lon: 121.47,
+    };
+    const NEW_YORK: GeoPoint = GeoPoint {
+        lat: 40.71,
+        lon: -74.01,
+    };
+
+    fn attempt(tenant_id: &str, device_id: &str, geo: Option<GeoPoint>) -> LoginAttempt {
+        LoginAttempt {
+            tenant_id: tenant_id.to_string(),
+            user_id: "u1".to_string(),
+            device_id: device_id.to_string(),
+            connection_id: format!("conn-{device_id}"),
+            ip: None,
+            region: None,
+            geo,
+        }
+    }
+
+    #[test]
+    fn test_impossible_travel_and_login_burst() {
+        let mut config = LoginSecurityConfig::default();
+        config.tenants.insert(
+            "t1".to_string(),
+            LoginSecurityPolicy {
+                max_logins_per_window: 2,
+                step_up: true,
+                ..LoginSecurityPolicy::default()
+            },
+        );
+        let service = LoginSecurityService::new("gw-1".to_string(), config);
+        let now = Instant::now();
+
+        let verdict = service.check_login(&attempt("t1", "phone", Some(SHANGHAI)), now);
+        assert_eq!(verdict, LoginVerdict::default());
+
+        // 一分钟后在纽约登录，远超合理移动速度
+        let verdict = service.check_login(
+            &attempt("t1", "laptop", Some(NEW_YORK)),
+            now + Duration::from_secs(60),
+        );
+        assert!(verdict.step_up);
+        assert!(matches!(
+            &verdict.anomalies[..],
+            [LoginAnomaly::ImpossibleTravel { previous_device_id, distance_km, .. }]
+                if previous_device_id == "phone" && *distance_km > 10_000.0
+        ));
+
+        // 第三次登录超过租户的登录次数上限
+        let verdict =
+            service.check_login(&attempt("t1", "pad", None), now + Duration::from_secs(120));
+        assert_eq!(
+            verdict.anomalies,
+            vec![LoginAnomaly::LoginBurst {
+                logins: 3,
+                window_secs: 600
+            }]
+        );
+
+        // 默认策略不开启 step-up，且窗口期外的记录不参与检测
+        service.check_login(&attempt("t2", "phone", Some(SHANGHAI)), now);
+        let verdict = service.check_login(
+            &attempt("t2", "laptop", Some(NEW_YORK)),
+            now + Duration::from_secs(600),
+        );
+        assert_eq!(verdict, LoginVerdict::default());
+    }
+
+    #[test]
+    fn test_credential_stuffing_blocks_ip_when_step_up() {
+        let config = LoginSecurityConfig {
+            default_policy: LoginSecurityPolicy {
+                max_failures_per_ip: 2,
+                step_up: true,
+                block_secs: 60,
+                ..LoginSecurityPolicy::default()
+            },
+            ..LoginSecurityConfig::default()
+        };
+        let service = LoginSecurityService::new("gw-1".to_string(), config);
+        let now = Instant::now();
+
+        assert!(service.check_failure("10.0.0.1", now).anomalies.is_empty());
+        assert!(service.check_failure("10.0.0.1", now).anomalies.is_empty());
+        let verdict = service.check_failure("10.0.0.1", now);
+        assert!(verdict.step_up);
+        assert_eq!(
+            verdict.anomalies,
+            vec![LoginAnomaly::CredentialStuffing {
+                failures: 3,
+                window_secs: 600
+            }]
+        );
+
+        assert!(service.is_blocked_at("10.0.0.1", now + Duration::from_secs(30)));
+        assert!(!service.is_blocked_at("10.0.0.2", now));
+        assert!(!service.is_blocked_at("10.0.0.1", now + Duration::from_secs(60)));
+    }
+}
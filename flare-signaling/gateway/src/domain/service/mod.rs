@@ -1,7 +1,13 @@
 pub mod connection_domain_service;
+pub mod connection_inspection_service;
 pub mod connection_quality_service;
+pub mod conversation_focus_service;
+pub mod frame_recorder_service;
+pub mod latency_probe_service;
+pub mod login_security_service;
 pub mod multi_device_push_service;
 pub mod push_domain_service;
+pub mod resume_ticket_service;
 pub mod conversation_domain_service;
 pub mod subscription_service;
 pub mod message_domain_service;
@@ -11,11 +17,28 @@ mod online_client;
 pub use online_client::OnlineServiceClient;
 
 pub use connection_domain_service::{ConnectionDomainService, ConnectionDomainServiceConfig};
+pub use connection_inspection_service::{ConnectionInspectionService, OutboundGuard};
 pub use connection_quality_service::{
     ConnectionQualityMetrics, ConnectionQualityService, QualityLevel,
 };
+pub use conversation_focus_service::{
+    ConversationFocusConfig, ConversationFocusService, FocusDelivery,
+};
+pub use frame_recorder_service::{
+    FrameDirection, FrameRecorderConfig, FrameRecorderError, FrameRecorderService, FrameRecording,
+    RecordedFrame,
+};
+pub use latency_probe_service::{GeoLatencySample, LatencyProbeConfig, LatencyProbeService};
+pub use login_security_service::{
+    LoginAnomaly, LoginAttempt, LoginSecurityConfig, LoginSecurityPolicy, LoginSecurityService,
+    LoginVerdict, SecurityEvent,
+};
 pub use multi_device_push_service::MultiDevicePushService;
 pub use push_domain_service::{DomainPushResult, PushDomainService};
+pub use resume_ticket_service::{
+    DetachedSession, IssuedTicket, PendingDelivery, ResumeClaims, ResumeTicketConfig,
+    ResumeTicketError, ResumeTicketService, ResumedTicket,
+};
 pub use conversation_domain_service::ConversationDomainService;
 pub use subscription_service::SubscriptionService;
 pub use message_domain_service::MessageDomainService;
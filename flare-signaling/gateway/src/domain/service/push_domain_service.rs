@@ -10,6 +10,9 @@ use tracing::instrument;
 
 use crate::domain::model::ConnectionInfo;
 use crate::domain::repository::ConnectionQuery;
+use crate::domain::service::{
+    ConversationFocusService, FocusDelivery, PendingDelivery, ResumeTicketService,
+};
 use crate::interface::handler::LongConnectionHandler;
 
 /// 推送结果（领域层）
@@ -25,6 +28,10 @@ pub struct DomainPushResult {
 pub struct PushDomainService {
     connection_handler: Arc<LongConnectionHandler>,
     connection_query: Arc<dyn ConnectionQuery>,
+    /// 会话聚焦服务（未设置时所有连接全量推送）
+    conversation_focus: Option<Arc<ConversationFocusService>>,
+    /// 续连票据服务（未设置时离线设备的消息不暂存）
+    resume_tickets: Option<Arc<ResumeTicketService>>,
 }
 
 impl PushDomainService {
@@ -35,9 +42,59 @@ impl PushDomainService {
         Self {
             connection_handler,
             connection_query,
+            conversation_focus: None,
+            resume_tickets: None,
         }
     }
 
+    /// 设置会话聚焦服务
+    pub fn with_conversation_focus(
+        mut self,
+        conversation_focus: Arc<ConversationFocusService>,
+    ) -> Self {
+        self.conversation_focus = Some(conversation_focus);
+        self
+    }
+
+    /// 设置续连票据服务
+    pub fn with_resume_tickets(mut self, resume_tickets: Arc<ResumeTicketService>) -> Self {
+        self.resume_tickets = Some(resume_tickets);
+        self
+    }
+
+    /// 暂存推送给续连宽限期内设备的消息，返回暂存的设备数
+    ///
+    /// 按设备过滤并做回声抑制；指定平台过滤时无法判断断开设备的平台，不暂存。
+    /// `offline_handled` 表示本次推送向 Push Server 返回离线（离线流程会执行）
+    pub fn buffer_for_resume(
+        &self,
+        user_id: &str,
+        message: &flare_proto::common::Message,
+        message_bytes: &[u8],
+        options: &PushOptions,
+        offline_handled: bool,
+    ) -> usize {
+        let Some(resume_tickets) = &self.resume_tickets else {
+            return 0;
+        };
+        if !options.platforms.is_empty() {
+            return 0;
+        }
+        let delivery = PendingDelivery {
+            conversation_id: message.conversation_id.clone(),
+            payload: message_bytes.to_vec(),
+            offline_handled,
+        };
+        resume_tickets.buffer(
+            user_id,
+            |device_id| {
+                (options.device_ids.is_empty() || options.device_ids.iter().any(|d| d == device_id))
+                    && !flare_im_core::utils::is_origin_device(message, user_id, device_id)
+            },
+            &delivery,
+        )
+    }
+
     /// 检查用户是否在线
     ///
     /// Gateway 直接查询本地连接状态，不维护缓存
@@ -85,12 +142,17 @@ impl PushDomainService {
     /// 推送消息到连接（直接单条推送，保持 Gateway 轻量）
     ///
     /// 优化：去重连接，避免重复推送
+    ///
+    /// 启用会话聚焦时，聚焦该会话的连接优先投递，聚焦其他会话的连接只收到会话更新通知；
+    /// 用户任一设备正在查看该会话时，投递帧携带 `silent` 标记，客户端不再响铃与计角标
     #[instrument(skip(self, message_bytes), fields(user_id = %user_id, connection_count = connections.len()))]
     pub async fn push_to_connections(
         &self,
         user_id: &str,
         connections: &[ConnectionInfo],
         message_bytes: &[u8],
+        conversation_id: &str,
+        max_seq: u64,
     ) -> Result<(i32, i32)> {
         let start_time = std::time::Instant::now();
 
@@ -101,7 +163,7 @@ impl PushDomainService {
 
         for conn in connections {
             if seen_connection_ids.insert(conn.connection_id.clone()) {
-                unique_connections.push(conn);
+                unique_connections.push(conn.clone());
             } else {
                 tracing::warn!(
                     user_id = %user_id,
@@ -121,16 +183,45 @@ impl PushDomainService {
             "Connection deduplication completed"
         );
 
+        let plan = match &self.conversation_focus {
+            Some(focus) => focus.plan_delivery(conversation_id, &unique_connections),
+            None => unique_connections
+                .into_iter()
+                .map(|conn| (conn, FocusDelivery::Full { silent: false }))
+                .collect(),
+        };
+
         let mut success_count = 0;
         let mut failure_count = 0;
         let push_start = std::time::Instant::now();
-        for conn in &unique_connections {
+        for (conn, delivery) in &plan {
             let conn_start = std::time::Instant::now();
-            match self
-                .connection_handler
-                .push_message_to_connection(&conn.connection_id, message_bytes.to_vec())
-                .await
-            {
+            let result = match delivery {
+                FocusDelivery::Full { silent } => {
+                    let mut metadata = std::collections::HashMap::new();
+                    if *silent {
+                        metadata.insert("silent".to_string(), b"1".to_vec());
+                    }
+                    self.connection_handler
+                        .push_message_to_connection_with_metadata(
+                            &conn.connection_id,
+                            message_bytes.to_vec(),
+                            metadata,
+                        )
+                        .await
+                }
+                FocusDelivery::Notify { silent } => {
+                    self.connection_handler
+                        .push_conversation_notify(
+                            &conn.connection_id,
+                            conversation_id,
+                            max_seq,
+                            *silent,
+                        )
+                        .await
+                }
+            };
+            match result {
                 Ok(_) => {
                     success_count += 1;
                     tracing::debug!(
@@ -0,0 +1,632 @@
+//! 续连票据领域服务
+//!
+//! 职责：
+//! - 连接建立时签发短期续连票据，绑定（租户、用户、设备、网关集群）与连接认证时的 token，
+//!   定期刷新；token 过期或被吊销后不再刷新
+//! - 校验客户端重连时出示的票据，跳过会话注册；票据只能使用一次，续连时重新校验绑定的 token
+//! - 用户被踢下线或 token 被吊销时作废该用户的全部票据与保留会话
+//! - 携带票据的连接断开后保留会话一段宽限期，期间到达的推送暂存为待投递消息，
+//!   续连后补发；宽限期结束仍未续连时由调用方注销会话，并将未走过离线流程的消息转交离线推送
+//!
+//! 票据格式：`rt1.` + `base64url(JSON声明).base64url(签名)`，签名范围包含网关集群，
+//! 其他集群签发的票据无法使用。声明中的随机 nonce 登记在签发网关的内存中，
+//! 校验时消费，重放或在其他网关上出示的票据均被拒绝（客户端回退到 token 认证）
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use flare_im_core::pagination::{CursorCodec, PaginationError};
+use serde::{Deserialize, Serialize};
+
+/// 续连票据前缀（用于区分票据与 JWT）
+pub const RESUME_TICKET_PREFIX: &str = "rt1.";
+/// 连接 metadata：通过续连票据认证
+pub const METADATA_KEY_RESUMED: &str = "resumed";
+/// 连接 metadata：票据绑定的会话ID
+pub const METADATA_KEY_RESUME_CONVERSATION_ID: &str = "resume_conversation_id";
+
+/// 续连票据配置
+#[derive(Debug, Clone)]
+pub struct ResumeTicketConfig {
+    /// 签名密钥（同一集群内的网关需一致）
+    pub secret: String,
+    /// 网关集群（票据只能在签发集群内使用）
+    pub cluster: String,
+    /// 票据有效期
+    pub ttl: Duration,
+    /// 断开后保留会话的宽限期
+    pub grace: Duration,
+    /// 宽限期内每个设备最多暂存的待投递消息数（超出时丢弃最早的消息）
+    pub max_pending: usize,
+}
+
+impl Default for ResumeTicketConfig {
+    fn default() -> Self {
+        Self {
+            secret: String::new(),
+            cluster: "default".to_string(),
+            ttl: Duration::from_secs(120),
+            grace: Duration::from_secs(30),
+            max_pending: 200,
+        }
+    }
+}
+
+/// 续连票据校验错误
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ResumeTicketError {
+    #[error("malformed resume ticket")]
+    Malformed,
+    #[error("invalid resume ticket signature")]
+    InvalidSignature,
+    #[error("resume ticket expired")]
+    Expired,
+    #[error("resume ticket is bound to another device")]
+    DeviceMismatch,
+    #[error("resume ticket already used or revoked")]
+    Consumed,
+}
+
+/// 票据声明
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResumeClaims {
+    #[serde(rename = "t")]
+    pub tenant_id: String,
+    #[serde(rename = "u")]
+    pub user_id: String,
+    #[serde(rename = "d")]
+    pub device_id: String,
+    #[serde(rename = "c")]
+    pub conversation_id: String,
+    /// 过期时间（毫秒时间戳）
+    #[serde(rename = "e")]
+    pub expires_at: i64,
+    /// 一次性随机数（校验时消费）
+    #[serde(rename = "n")]
+    pub nonce: String,
+}
+
+/// 校验通过的票据
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumedTicket {
+    pub claims: ResumeClaims,
+    /// 签发票据的连接认证时使用的 token（续连时需重新校验）
+    pub auth_token: String,
+}
+
+/// 签发的票据
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssuedTicket {
+    pub connection_id: String,
+    pub ticket: String,
+    pub expires_at: i64,
+}
+
+/// 宽限期内暂存的待投递消息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingDelivery {
+    pub conversation_id: String,
+    pub payload: Vec<u8>,
+    /// 推送时已向 Push Server 返回离线（离线流程已执行），宽限期到期后无需转交
+    pub offline_handled: bool,
+}
+
+/// 断开后保留的会话
+#[derive(Debug)]
+pub struct DetachedSession {
+    pub tenant_id: String,
+    pub user_id: String,
+    pub device_id: String,
+    pub conversation_id: String,
+    pub pending: VecDeque<PendingDelivery>,
+    /// 超出上限被丢弃的消息数
+    pub dropped: usize,
+    nonce: String,
+    detached_at: Instant,
+    /// 用户被踢下线或 token 被吊销，下次维护时按过期处理
+    revoked: bool,
+}
+
+#[derive(Debug)]
+struct ActiveSession {
+    claims: ResumeClaims,
+    auth_token: String,
+    issued_at: Instant,
+}
+
+/// 已签发且未消费的票据
+#[derive(Debug)]
+struct TicketGrant {
+    connection_id: String,
+    user_id: String,
+    auth_token: String,
+    expires_at: i64,
+}
+
+#[derive(Debug, Default)]
+struct ResumeState {
+    /// connection_id -> 认证通过、尚未签发票据的 token
+    bound_tokens: HashMap<String, (String, Instant)>,
+    /// connection_id -> 已签发票据的连接
+    active: HashMap<String, ActiveSession>,
+    /// (user_id, device_id) -> 断开后保留的会话
+    detached: HashMap<(String, String), DetachedSession>,
+    /// nonce -> 可使用的票据（每个连接只保留最新签发的票据）
+    grants: HashMap<String, TicketGrant>,
+}
+
+/// 续连票据服务
+pub struct ResumeTicketService {
+    config: ResumeTicketConfig,
+    codec: CursorCodec,
+    scope: String,
+    state: Mutex<ResumeState>,
+}
+
+impl ResumeTicketService {
+    pub fn new(config: ResumeTicketConfig) -> Self {
+        Self {
+            codec: CursorCodec::new(config.secret.clone()),
+            scope: format!("resume_ticket:{}", config.cluster),
+            config,
+            state: Mutex::new(ResumeState::default()),
+        }
+    }
+
+    pub fn config(&self) -> &ResumeTicketConfig {
+        &self.config
+    }
+
+    /// 认证令牌是否为续连票据
+    pub fn is_ticket(token: &str) -> bool {
+        token.starts_with(RESUME_TICKET_PREFIX)
+    }
+
+    /// 记录连接认证时使用的 token（签发票据时绑定，续连与刷新时重新校验）
+    pub fn bind_token(&self, connection_id: &str, auth_token: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.bound_tokens.insert(
+            connection_id.to_string(),
+            (auth_token.to_string(), Instant::now()),
+        );
+    }
+
+    /// 为连接签发票据（同一连接重复签发时替换旧票据），连接未绑定 token 时不签发
+    pub fn issue(
+        &self,
+        connection_id: &str,
+        tenant_id: &str,
+        user_id: &str,
+        device_id: &str,
+        conversation_id: &str,
+    ) -> Option<IssuedTicket> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let auth_token = match state.bound_tokens.remove(connection_id) {
+            Some((auth_token, _)) => auth_token,
+            None => state.active.get(connection_id)?.auth_token.clone(),
+        };
+        let claims = ResumeClaims {
+            tenant_id: tenant_id.to_string(),
+            user_id: user_id.to_string(),
+            device_id: device_id.to_string(),
+            conversation_id: conversation_id.to_string(),
+            expires_at: Utc::now().timestamp_millis() + self.config.ttl.as_millis() as i64,
+            nonce: String::new(),
+        };
+        let issued = self.grant(&mut state, connection_id, claims, auth_token);
+        Some(issued)
+    }
+
+    /// 校验并消费票据，`device_id` 为重连时客户端上报的设备
+    ///
+    /// 同一票据只能使用一次；仍在线的签发连接不再刷新票据，断开时也不再保留会话
+    pub fn verify(
+        &self,
+        ticket: &str,
+        device_id: Option<&str>,
+    ) -> Result<ResumedTicket, ResumeTicketError> {
+        self.verify_at(ticket, device_id, Utc::now().timestamp_millis())
+    }
+
+    fn verify_at(
+        &self,
+        ticket: &str,
+        device_id: Option<&str>,
+        now_ms: i64,
+    ) -> Result<ResumedTicket, ResumeTicketError> {
+        let encoded = ticket
+            .strip_prefix(RESUME_TICKET_PREFIX)
+            .ok_or(ResumeTicketError::Malformed)?;
+        let claims: ResumeClaims = self
+            .codec
+            .decode(&self.scope, encoded)
+            .map_err(|err| match err {
+                PaginationError::MalformedCursor => ResumeTicketError::Malformed,
+                PaginationError::InvalidSignature => ResumeTicketError::InvalidSignature,
+            })?
+            .ok_or(ResumeTicketError::Malformed)?;
+        if claims.expires_at <= now_ms {
+            return Err(ResumeTicketError::Expired);
+        }
+        if device_id != Some(claims.device_id.as_str()) {
+            return Err(ResumeTicketError::DeviceMismatch);
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let grant = state
+            .grants
+            .remove(&claims.nonce)
+            .ok_or(ResumeTicketError::Consumed)?;
+        state.active.remove(&grant.connection_id);
+        Ok(ResumedTicket {
+            claims,
+            auth_token: grant.auth_token,
+        })
+    }
+
+    /// 作废用户的全部票据：在线连接不再刷新，保留会话在下次维护时按过期处理
+    ///
+    /// 用户被踢下线或 token 被吊销时调用，返回作废的票据数
+    pub fn revoke_user(&self, user_id: &str) -> usize {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let state = &mut *state;
+        let before = state.grants.len();
+        state.grants.retain(|_, grant| grant.user_id != user_id);
+        state
+            .active
+            .retain(|_, active| active.claims.user_id != user_id);
+        for session in state.detached.values_mut() {
+            if session.user_id == user_id {
+                session.revoked = true;
+            }
+        }
+        before - state.grants.len()
+    }
+
+    /// 连接断开：已签发票据的连接转为保留会话，返回是否保留
+    pub fn detach(&self, connection_id: &str) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(active) = state.active.remove(connection_id) else {
+            return false;
+        };
+        if self.config.grace.is_zero() {
+            state.grants.remove(&active.claims.nonce);
+            return false;
+        }
+        let claims = active.claims;
+        let previous = state.detached.insert(
+            (claims.user_id.clone(), claims.device_id.clone()),
+            DetachedSession {
+                tenant_id: claims.tenant_id,
+                user_id: claims.user_id,
+                device_id: claims.device_id,
+                conversation_id: claims.conversation_id,
+                pending: VecDeque::new(),
+                dropped: 0,
+                nonce: claims.nonce,
+                detached_at: Instant::now(),
+                revoked: false,
+            },
+        );
+        if let Some(previous) = previous {
+            state.grants.remove(&previous.nonce);
+        }
+        true
+    }
+
+    /// 设备重新连接：取出保留的会话（含待投递消息），会话的票据随之作废
+    pub fn reattach(&self, user_id: &str, device_id: &str) -> Option<DetachedSession> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let session = state
+            .detached
+            .remove(&(user_id.to_string(), device_id.to_string()))?;
+        state.grants.remove(&session.nonce);
+        Some(session)
+    }
+
+    /// 暂存推送给保留会话的消息，`accepts` 按设备过滤，返回暂存的设备数
+    pub fn buffer(
+        &self,
+        user_id: &str,
+        accepts: impl Fn(&str) -> bool,
+        delivery: &PendingDelivery,
+    ) -> usize {
+        if self.config.max_pending == 0 {
+            return 0;
+        }
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut buffered = 0;
+        for session in state.detached.values_mut() {
+            if session.user_id != user_id || !accepts(&session.device_id) {
+                continue;
+            }
+            if session.pending.len() >= self.config.max_pending {
+                session.pending.pop_front();
+                session.dropped += 1;
+            }
+            session.pending.push_back(delivery.clone());
+            buffered += 1;
+        }
+        buffered
+    }
+
+    /// 取出超过宽限期或已作废的保留会话，并清理过期票据
+    pub fn take_expired(&self) -> Vec<DetachedSession> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now_ms = Utc::now().timestamp_millis();
+        let ttl = self.config.ttl;
+        state.grants.retain(|_, grant| grant.expires_at > now_ms);
+        state
+            .bound_tokens
+            .retain(|_, (_, bound_at)| bound_at.elapsed() < ttl);
+
+        let expired: Vec<(String, String)> = state
+            .detached
+            .iter()
+            .filter(|(_, session)| {
+                session.revoked || session.detached_at.elapsed() >= self.config.grace
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        let expired: Vec<DetachedSession> = expired
+            .into_iter()
+            .filter_map(|key| state.detached.remove(&key))
+            .collect();
+        for session in &expired {
+            state.grants.remove(&session.nonce);
+        }
+        expired
+    }
+
+    /// 为签发超过有效期一半的连接重新签发票据（旧票据随之作废）
+    ///
+    /// `token_valid` 校验连接绑定的 token（含过期与吊销检查），校验失败时作废该用户的全部票据
+    pub fn refresh_due(&self, token_valid: impl Fn(&str) -> bool) -> Vec<IssuedTicket> {
+        let due: Vec<(String, String)> = {
+            let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state
+                .active
+                .iter()
+                .filter(|(_, active)| active.issued_at.elapsed() >= self.config.ttl / 2)
+                .map(|(connection_id, active)| (connection_id.clone(), active.auth_token.clone()))
+                .collect()
+        };
+
+        let mut refreshed = Vec::new();
+        for (connection_id, auth_token) in due {
+            // token 校验可能访问令牌存储，不持有锁
+            let valid = token_valid(&auth_token);
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(active) = state.active.remove(&connection_id) else {
+                continue;
+            };
+            if !valid {
+                let user_id = active.claims.user_id.clone();
+                state.grants.remove(&active.claims.nonce);
+                drop(state);
+                self.revoke_user(&user_id);
+                continue;
+            }
+            let mut claims = active.claims;
+            state.grants.remove(&claims.nonce);
+            claims.expires_at = Utc::now().timestamp_millis() + self.config.ttl.as_millis() as i64;
+            refreshed.push(self.grant(&mut state, &connection_id, claims, active.auth_token));
+        }
+        refreshed
+    }
+
+    /// 连接断开且不保留会话时清理票据状态
+    pub fn remove_connection(&self, connection_id: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.bound_tokens.remove(connection_id);
+        if let Some(active) = state.active.remove(connection_id) {
+            state.grants.remove(&active.claims.nonce);
+        }
+    }
+
+    /// 生成新的 nonce 并登记票据，替换连接之前签发的票据
+    fn grant(
+        &self,
+        state: &mut ResumeState,
+        connection_id: &str,
+        mut claims: ResumeClaims,
+        auth_token: String,
+    ) -> IssuedTicket {
+        if let Some(previous) = state.active.remove(connection_id) {
+            state.grants.remove(&previous.claims.nonce);
+        }
+        claims.nonce = uuid::Uuid::new_v4().simple().to_string();
+        let issued = self.encode(connection_id, &claims);
+        state.grants.insert(
+            claims.nonce.clone(),
+            TicketGrant {
+                connection_id: connection_id.to_string(),
+                user_id: claims.user_id.clone(),
+                auth_token: auth_token.clone(),
+                expires_at: claims.expires_at,
+            },
+        );
+        state.active.insert(
+            connection_id.to_string(),
+            ActiveSession {
+                claims,
+                auth_token,
+                issued_at: Instant::now(),
+            },
+        );
+        issued
+    }
+
+    fn encode(&self, connection_id: &str, claims: &ResumeClaims) -> IssuedTicket {
+        IssuedTicket {
+            connection_id: connection_id.to_string(),
+            ticket: format!(
+                "{}{}",
+                RESUME_TICKET_PREFIX,
+                self.codec.encode(&self.scope, claims)
+            ),
+            expires_at: claims.expires_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(cluster: &str) -> ResumeTicketService {
+        ResumeTicketService::new(ResumeTicketConfig {
+            secret: "test-secret".to_string(),
+            cluster: cluster.to_string(),
+            max_pending: 2,
+            ..ResumeTicketConfig::default()
+        })
+    }
+
+    fn issue(service: &ResumeTicketService, connection_id: &str, user_id: &str) -> IssuedTicket {
+        service.bind_token(connection_id, "jwt-1");
+        service
+            .issue(connection_id, "t1", user_id, "d1", "conv-1")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_ticket_bound_to_device_and_cluster() {
+        let service = service("cluster-a");
+        // 未绑定 token 的连接不签发票据
+        assert!(
+            service
+                .issue("conn-0", "t1", "u1", "d1", "conv-1")
+                .is_none()
+        );
+
+        let issued = issue(&service, "conn-1", "u1");
+        assert!(ResumeTicketService::is_ticket(&issued.ticket));
+
+        assert_eq!(
+            service.verify(&issued.ticket, Some("d2")),
+            Err(ResumeTicketError::DeviceMismatch)
+        );
+        assert_eq!(
+            service.verify_at(&issued.ticket, Some("d1"), issued.expires_at),
+            Err(ResumeTicketError::Expired)
+        );
+        assert_eq!(
+            self::service("cluster-b").verify(&issued.ticket, Some("d1")),
+            Err(ResumeTicketError::InvalidSignature)
+        );
+        assert_eq!(
+            service.verify("eyJhbGciOiJIUzI1NiJ9.e30.sig", Some("d1")),
+            Err(ResumeTicketError::Malformed)
+        );
+
+        let resumed = service.verify(&issued.ticket, Some("d1")).unwrap();
+        assert_eq!(resumed.claims.user_id, "u1");
+        assert_eq!(resumed.claims.conversation_id, "conv-1");
+        assert_eq!(resumed.auth_token, "jwt-1");
+    }
+
+    #[test]
+    fn test_ticket_is_single_use() {
+        let service = service("cluster-a");
+        let issued = issue(&service, "conn-1", "u1");
+        assert!(service.verify(&issued.ticket, Some("d1")).is_ok());
+        assert_eq!(
+            service.verify(&issued.ticket, Some("d1")),
+            Err(ResumeTicketError::Consumed)
+        );
+        // 已被续连的连接断开时不再保留会话
+        assert!(!service.detach("conn-1"));
+
+        // 重新签发后旧票据作废
+        let first = issue(&service, "conn-2", "u1");
+        let second = service.issue("conn-2", "t1", "u1", "d1", "conv-1").unwrap();
+        assert_eq!(
+            service.verify(&first.ticket, Some("d1")),
+            Err(ResumeTicketError::Consumed)
+        );
+        assert!(service.verify(&second.ticket, Some("d1")).is_ok());
+    }
+
+    #[test]
+    fn test_revoked_token_stops_refresh_and_resume() {
+        let service = ResumeTicketService::new(ResumeTicketConfig {
+            secret: "test-secret".to_string(),
+            ttl: Duration::from_millis(0),
+            ..ResumeTicketConfig::default()
+        });
+        issue(&service, "conn-1", "u1");
+        assert_eq!(service.refresh_due(|_| true).len(), 1);
+        assert!(service.refresh_due(|_| false).is_empty());
+        // token 失效后连接不再刷新票据，断开时也不保留会话
+        assert!(service.refresh_due(|_| true).is_empty());
+        assert!(!service.detach("conn-1"));
+
+        let service = self::service("cluster-a");
+        let issued = issue(&service, "conn-1", "u1");
+        issue(&service, "conn-2", "u2");
+        assert!(service.detach("conn-1"));
+        assert_eq!(service.revoke_user("u1"), 1);
+        assert_eq!(
+            service.verify(&issued.ticket, Some("d1")),
+            Err(ResumeTicketError::Consumed)
+        );
+        let expired = service.take_expired();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].user_id, "u1");
+        assert!(service.detach("conn-2"));
+    }
+
+    #[test]
+    fn test_detached_session_buffers_until_reattach() {
+        let service = service("cluster-a");
+        issue(&service, "conn-1", "u1");
+
+        // 未断开或未签发票据的连接不保留会话
+        assert!(!service.detach("conn-unknown"));
+        assert!(service.detach("conn-1"));
+
+        let delivery = |id: &str| PendingDelivery {
+            conversation_id: "conv-9".to_string(),
+            payload: id.as_bytes().to_vec(),
+            offline_handled: false,
+        };
+        assert_eq!(service.buffer("u1", |_| true, &delivery("m1")), 1);
+        assert_eq!(
+            service.buffer("u1", |device| device != "d1", &delivery("mx")),
+            0
+        );
+        service.buffer("u1", |_| true, &delivery("m2"));
+        service.buffer("u1", |_| true, &delivery("m3"));
+
+        let session = service.reattach("u1", "d1").unwrap();
+        assert_eq!(session.conversation_id, "conv-1");
+        assert_eq!(session.dropped, 1);
+        assert_eq!(
+            session.pending.into_iter().collect::<Vec<_>>(),
+            vec![delivery("m2"), delivery("m3")]
+        );
+        assert!(service.reattach("u1", "d1").is_none());
+    }
+
+    #[test]
+    fn test_expired_detached_sessions() {
+        let service = ResumeTicketService::new(ResumeTicketConfig {
+            secret: "test-secret".to_string(),
+            grace: Duration::from_millis(0),
+            ..ResumeTicketConfig::default()
+        });
+        issue(&service, "conn-1", "u1");
+        // 宽限期为 0 时不保留会话
+        assert!(!service.detach("conn-1"));
+        assert!(service.take_expired().is_empty());
+
+        let service = self::service("cluster-a");
+        issue(&service, "conn-1", "u1");
+        assert!(service.detach("conn-1"));
+        assert!(service.take_expired().is_empty());
+        assert!(service.refresh_due(|_| true).is_empty());
+    }
+}
@@ -3,40 +3,228 @@
 //! 提供 token 认证功能
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use flare_core::common::device::DeviceInfo;
 use flare_core::common::error::Result;
 use flare_core::server::auth::{AuthResult, Authenticator};
+use flare_im_core::auth::TokenKeyRing;
 use flare_server_core::TokenService;
 use tracing::{debug, instrument, warn};
 
+use crate::domain::service::login_security_service::{
+    METADATA_KEY_CLIENT_GEO, METADATA_KEY_CLIENT_IP, METADATA_KEY_GEO_LAT, METADATA_KEY_GEO_LON,
+};
+use crate::domain::service::resume_ticket_service::{
+    METADATA_KEY_RESUME_CONVERSATION_ID, METADATA_KEY_RESUMED,
+};
+use crate::domain::service::{
+    LoginSecurityService, ResumeTicketError, ResumeTicketService, ResumedTicket,
+};
+
+/// 透传到连接 metadata 的客户端认证元数据（用于登录异常检测）
+const FORWARDED_METADATA_KEYS: [&str; 4] = [
+    METADATA_KEY_CLIENT_IP,
+    METADATA_KEY_CLIENT_GEO,
+    METADATA_KEY_GEO_LAT,
+    METADATA_KEY_GEO_LON,
+];
+
 /// Token 认证器
 ///
 /// 验证客户端提供的 token，提取用户ID
 pub struct TokenAuthenticator {
     token_service: Arc<TokenService>,
+    /// 多密钥验证（签名密钥轮换）
+    key_ring: Option<KeyRingVerifier>,
+    /// 登录安全检测（撞库识别与 IP 封禁）
+    login_security: Option<Arc<LoginSecurityService>>,
+    /// 续连票据校验（快速续连）
+    resume_tickets: Option<Arc<ResumeTicketService>>,
+}
+
+/// 按 kid 选择密钥的验证器
+///
+/// 每个密钥对应一个 TokenService（共享令牌存储，保留吊销检查），密钥内容变化时重建
+struct KeyRingVerifier {
+    key_ring: Arc<TokenKeyRing>,
+    build_service: Box<dyn Fn(String) -> TokenService + Send + Sync>,
+    /// key_id -> (secret, TokenService)
+    services: Mutex<HashMap<String, (String, Arc<TokenService>)>>,
+}
+
+impl KeyRingVerifier {
+    fn service_for(&self, key_id: &str, secret: &str) -> Arc<TokenService> {
+        let mut services = self
+            .services
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match services.get(key_id) {
+            Some((cached_secret, service)) if cached_secret == secret => service.clone(),
+            _ => {
+                let service = Arc::new((self.build_service)(secret.to_string()));
+                services.insert(key_id.to_string(), (secret.to_string(), service.clone()));
+                service
+            }
+        }
+    }
 }
 
 impl TokenAuthenticator {
     pub fn new(token_service: Arc<TokenService>) -> Self {
-        Self { token_service }
+        Self {
+            token_service,
+            key_ring: None,
+            login_security: None,
+            resume_tickets: None,
+        }
+    }
+
+    /// 启用登录安全检测
+    pub fn with_login_security(mut self, login_security: Arc<LoginSecurityService>) -> Self {
+        self.login_security = Some(login_security);
+        self
+    }
+
+    /// 启用续连票据认证
+    pub fn with_resume_tickets(mut self, resume_tickets: Arc<ResumeTicketService>) -> Self {
+        self.resume_tickets = Some(resume_tickets);
+        self
+    }
+
+    /// 启用多密钥验证
+    ///
+    /// `build_service` 根据密钥内容构建 TokenService（issuer、TTL、令牌存储与单密钥模式一致）
+    pub fn with_key_ring(
+        mut self,
+        key_ring: Arc<TokenKeyRing>,
+        build_service: impl Fn(String) -> TokenService + Send + Sync + 'static,
+    ) -> Self {
+        self.key_ring = Some(KeyRingVerifier {
+            key_ring,
+            build_service: Box::new(build_service),
+            services: Mutex::new(HashMap::new()),
+        });
+        self
+    }
+
+    /// 签发 token
+    ///
+    /// 启用多密钥时使用当前生效密钥签发并在头部写入 `kid`，`token_secret` 只用于验证历史令牌
+    pub fn issue_token(
+        &self,
+        user_id: &str,
+        device_id: Option<&str>,
+        tenant_id: Option<&str>,
+    ) -> anyhow::Result<String> {
+        let Some(verifier) = &self.key_ring else {
+            return self
+                .token_service
+                .generate_token(user_id, device_id, tenant_id)
+                .map_err(|err| anyhow::anyhow!("Failed to issue token: {:?}", err));
+        };
+
+        // Claims 由生效密钥对应的 TokenService 生成（issuer、TTL、令牌存储与验证一致），再按 kid 签名
+        let keys = verifier.key_ring.snapshot();
+        let service = verifier.service_for(keys.active_key_id(), keys.active_secret());
+        let token = service
+            .generate_token(user_id, device_id, tenant_id)
+            .map_err(|err| anyhow::anyhow!("Failed to issue token: {:?}", err))?;
+        let claims = service
+            .validate_token(&token)
+            .map_err(|err| anyhow::anyhow!("Failed to issue token: {:?}", err))?;
+        verifier.key_ring.sign(&claims)
     }
 
     /// 验证 token（调用核心 TokenService）
     ///
     /// 返回完整的 TokenClaims，如果验证失败则返回 None
     fn verify_token(&self, token: &str) -> Option<flare_server_core::TokenClaims> {
-        match self.token_service.validate_token(token) {
-            Ok(claims) => Some(claims),
+        let Some(verifier) = &self.key_ring else {
+            return match self.token_service.validate_token(token) {
+                Ok(claims) => Some(claims),
+                Err(err) => {
+                    warn!(?err, "Token validation failed");
+                    None
+                }
+            };
+        };
+
+        let candidates = match verifier.key_ring.verification_keys(token) {
+            Ok(candidates) => candidates,
+            Err(err) => {
+                warn!(%err, "Token validation failed");
+                return None;
+            }
+        };
+        for (key_id, secret) in candidates {
+            match verifier.service_for(&key_id, &secret).validate_token(token) {
+                Ok(claims) => return Some(claims),
+                Err(err) => debug!(?err, key_id = %key_id, "Token validation failed with key"),
+            }
+        }
+        warn!("Token validation failed with all signing keys");
+        None
+    }
+
+    /// token 是否仍然有效（含过期与吊销检查），用于刷新续连票据前的复查
+    pub fn is_token_valid(&self, token: &str) -> bool {
+        self.verify_token(token).is_some()
+    }
+
+    /// 校验并消费续连票据，并重新校验票据绑定的 token（吊销后作废该用户的全部票据）
+    fn authenticate_resume(
+        &self,
+        resume_tickets: &ResumeTicketService,
+        ticket: &str,
+        connection_id: &str,
+        device_info: Option<&DeviceInfo>,
+        forwarded: HashMap<String, String>,
+    ) -> AuthResult {
+        let device_id = device_info.map(|device| device.device_id.as_str());
+        let resumed = resume_tickets.verify(ticket, device_id).and_then(|resumed| {
+            if self.verify_token(&resumed.auth_token).is_some() {
+                return Ok(resumed);
+            }
+            resume_tickets.revoke_user(&resumed.claims.user_id);
+            Err(ResumeTicketError::Consumed)
+        });
+        match resumed {
+            Ok(ResumedTicket { claims, auth_token }) => {
+                resume_tickets.bind_token(connection_id, &auth_token);
+                let mut user_metadata = HashMap::new();
+                user_metadata.insert("user_id".to_string(), claims.user_id.clone());
+                user_metadata.insert("tenant_id".to_string(), claims.tenant_id);
+                user_metadata.insert("device_id".to_string(), claims.device_id);
+                user_metadata.insert(METADATA_KEY_RESUMED.to_string(), "true".to_string());
+                user_metadata.insert(
+                    METADATA_KEY_RESUME_CONVERSATION_ID.to_string(),
+                    claims.conversation_id,
+                );
+                user_metadata.extend(forwarded);
+
+                debug!(
+                    connection_id = %connection_id,
+                    user_id = %claims.user_id,
+                    "✅ 续连票据验证成功"
+                );
+                AuthResult::success_with_metadata(Some(claims.user_id), user_metadata)
+            }
             Err(err) => {
-                warn!(?err, "Token validation failed");
-                None
+                warn!(connection_id = %connection_id, %err, "❌ 续连票据验证失败");
+                AuthResult::failure("续连票据无效或已过期，请使用 token 重新认证".to_string())
             }
         }
     }
 
+    /// 默认租户ID（token 中未携带租户时使用）
+    fn default_tenant_id() -> String {
+        std::env::var("ACCESS_GATEWAY_DEFAULT_TENANT_ID")
+            .ok()
+            .unwrap_or_else(|| "0".to_string())
+    }
+
     /// 获取 token 预览（用于日志记录）
     fn token_preview(&self, token: &str) -> String {
         if token.len() > 12 {
@@ -55,7 +243,7 @@ impl Authenticator for TokenAuthenticator {
         token: &str,
         connection_id: &str,
         device_info: Option<&DeviceInfo>,
-        _metadata: Option<&HashMap<String, Vec<u8>>>,
+        metadata: Option<&HashMap<String, Vec<u8>>>,
     ) -> Result<AuthResult> {
         // 记录设备信息
         if let Some(device) = device_info {
@@ -70,6 +258,40 @@ impl Authenticator for TokenAuthenticator {
             "验证 token"
         );
 
+        let forwarded: HashMap<String, String> = metadata
+            .into_iter()
+            .flat_map(|metadata| {
+                FORWARDED_METADATA_KEYS.iter().filter_map(move |key| {
+                    let value = String::from_utf8(metadata.get(*key)?.clone()).ok()?;
+                    (!value.is_empty()).then(|| (key.to_string(), value))
+                })
+            })
+            .collect();
+        let client_ip = forwarded.get(METADATA_KEY_CLIENT_IP);
+
+        // 撞库封禁期内的 IP 直接拒绝，要求稍后重新认证
+        if let (Some(login_security), Some(ip)) = (&self.login_security, client_ip) {
+            if login_security.is_blocked(ip) {
+                warn!(connection_id = %connection_id, ip = %ip, "❌ 客户端 IP 已被临时封禁");
+                return Ok(AuthResult::failure(
+                    "认证失败次数过多，请稍后重新认证".to_string(),
+                ));
+            }
+        }
+
+        // 续连票据走快速路径，客户端在票据失效时回退到 token 认证
+        if let Some(resume_tickets) = &self.resume_tickets {
+            if ResumeTicketService::is_ticket(token) {
+                return Ok(self.authenticate_resume(
+                    resume_tickets,
+                    token,
+                    connection_id,
+                    device_info,
+                    forwarded,
+                ));
+            }
+        }
+
         match self.verify_token(token) {
             Some(claims) => {
                 let user_id = claims.sub.clone();
@@ -80,17 +302,16 @@ impl Authenticator for TokenAuthenticator {
                 user_metadata.insert("user_id".to_string(), user_id.clone());
                 
                 // 从 token claims 提取 tenant_id，如果没有则使用默认值 "0"
-                let tenant_id = claims.tenant_id.unwrap_or_else(|| {
-                    // 从环境变量或配置中获取默认值，如果没有则使用 "0"
-                    std::env::var("ACCESS_GATEWAY_DEFAULT_TENANT_ID")
-                        .ok()
-                        .unwrap_or_else(|| "0".to_string())
-                });
+                let tenant_id = claims.tenant_id.unwrap_or_else(Self::default_tenant_id);
                 user_metadata.insert("tenant_id".to_string(), tenant_id.clone());
                 
                 if let Some(device_id) = claims.device_id {
                     user_metadata.insert("device_id".to_string(), device_id);
                 }
+                user_metadata.extend(forwarded);
+                if let Some(resume_tickets) = &self.resume_tickets {
+                    resume_tickets.bind_token(connection_id, token);
+                }
                 
                 debug!(
                     connection_id = %connection_id,
@@ -109,6 +330,9 @@ impl Authenticator for TokenAuthenticator {
                     token_preview = %self.token_preview(token),
                     "❌ Token 验证失败"
                 );
+                if let (Some(login_security), Some(ip)) = (&self.login_security, client_ip) {
+                    login_security.on_auth_failure(&Self::default_tenant_id(), ip);
+                }
                 Ok(AuthResult::failure("Token 无效或已过期".to_string()))
             }
         }
@@ -0,0 +1,106 @@
+//! 长连接监听分片
+//!
+//! 单个 acceptor 在多核机器上会成为建连吞吐瓶颈。开启分片后，每个分片是一个独立的
+//! FlareServer，通过 SO_REUSEPORT 绑定同一地址，由内核在分片之间分发新连接；
+//! 所有分片共享同一个 ConnectionManager / DeviceManager / 事件处理器，
+//! 因此推送、连接查询等逻辑与单 acceptor 模式完全一致。网关自身的连接级状态
+//! （发送队列深度、连接质量）按连接分片加锁，ServerHandle 等启动后只读的组件不加锁。
+
+use anyhow::Result;
+use flare_core::server::builder::flare::FlareServer;
+use tracing::{info, warn};
+
+/// 解析监听分片数
+///
+/// - 未配置或配置为 1：单 acceptor（默认）
+/// - 配置为 0：按 CPU 核数分片
+/// - 其他值：按配置分片
+pub fn resolve_listener_shards(configured: Option<usize>) -> usize {
+    match configured {
+        None => 1,
+        Some(0) => std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        Some(shards) => shards,
+    }
+}
+
+/// 分片长连接服务器（一个或多个共享连接管理器的 FlareServer）
+pub struct ShardedLongConnectionServer {
+    shards: Vec<FlareServer>,
+}
+
+impl ShardedLongConnectionServer {
+    pub fn new(shards: Vec<FlareServer>) -> Self {
+        Self { shards }
+    }
+
+    /// 分片数量
+    pub fn len(&self) -> usize {
+        self.shards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.is_empty()
+    }
+
+    /// 启动所有分片
+    ///
+    /// 任一分片启动失败时停止已启动的分片并返回错误，避免部分分片继续占用端口接收连接
+    pub async fn start(&mut self) -> Result<()> {
+        for shard in 0..self.shards.len() {
+            if let Err(e) = self.shards[shard].start().await {
+                for (started, server) in self.shards.drain(..shard).enumerate() {
+                    if let Err(stop_err) = server.stop().await {
+                        warn!(
+                            shard = started,
+                            error = %stop_err,
+                            "Failed to stop listener shard after start failure"
+                        );
+                    }
+                }
+                return Err(anyhow::anyhow!(
+                    "Failed to start listener shard {}: {}",
+                    shard,
+                    e
+                ));
+            }
+        }
+        info!(
+            shards = self.shards.len(),
+            "Long connection listener shards started"
+        );
+        Ok(())
+    }
+
+    /// 停止所有分片（单个分片停止失败不影响其他分片）
+    pub async fn stop(self) -> Result<()> {
+        let mut failed = 0;
+        for (shard, server) in self.shards.into_iter().enumerate() {
+            if let Err(e) = server.stop().await {
+                warn!(shard, error = %e, "Failed to stop listener shard");
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            return Err(anyhow::anyhow!(
+                "{} listener shard(s) failed to stop",
+                failed
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_listener_shards() {
+        assert_eq!(resolve_listener_shards(None), 1);
+        assert_eq!(resolve_listener_shards(Some(1)), 1);
+        assert_eq!(resolve_listener_shards(Some(4)), 4);
+        assert!(resolve_listener_shards(Some(0)) >= 1);
+    }
+}
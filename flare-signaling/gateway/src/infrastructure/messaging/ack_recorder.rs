@@ -0,0 +1,478 @@
+//! 客户端 ACK 记录器
+//!
+//! 将客户端上报的 ACK 帧直接写入 ACK 模块（`AckManager::record_ack`），
+//! 无需业务方再把网关的 ACK 指标与 AckManager 手动对接。
+//!
+//! ACK 帧 metadata 约定（均为可选）：
+//! - `ack_type`：`delivery` / `read` / `transport` / `storage` / `server`，缺省使用配置的默认类型
+//! - `ack_status`：`failed` 表示客户端处理失败，其余按已接收记录
+//! - `importance`：`low` / `medium` / `high`，缺省使用配置的重要性
+//!
+//! 启用已读回执时，已读 ACK 记录成功后异步推送回执给原发送者（见 [`super::read_receipt`]）
+//!
+//! 启用会话回执策略时，按会话的 `receipts_policy` 丢弃不允许的送达/已读 ACK（见 [`super::receipts_policy`]），
+//! 被丢弃的已读 ACK 也不会推送回执；ACK 帧需携带 `conversation_id`
+
+use std::sync::Arc;
+
+use flare_conversation::domain::model::ReceiptsPolicy;
+use flare_core::common::protocol::MessageCommand;
+use flare_im_core::ack::{
+    ACK_TENANT_METADATA_KEY, AckEvent, AckManager, AckStatus, AckType, ImportanceLevel,
+};
+use tracing::{debug, warn};
+
+use super::read_receipt::{ReadReceipt, ReadReceiptPublisher};
+use super::receipts_policy::ReceiptsPolicyResolver;
+use crate::config::ClientAckRecordingConfig;
+
+/// 客户端 ACK 记录器
+pub struct ClientAckRecorder {
+    ack_manager: Arc<dyn AckManager>,
+    config: ClientAckRecordingConfig,
+    gateway_id: String,
+    read_receipt_publisher: Option<Arc<dyn ReadReceiptPublisher>>,
+    receipts_policy_resolver: Option<Arc<dyn ReceiptsPolicyResolver>>,
+}
+
+/// ACK 所属连接的信息（写入 `AckEvent.metadata`）
+pub struct AckConnectionMeta<'a> {
+    pub connection_id: &'a str,
+    pub user_id: &'a str,
+    pub device_id: Option<&'a str>,
+    pub tenant_id: &'a str,
+}
+
+impl ClientAckRecorder {
+    pub fn new(
+        ack_manager: Arc<dyn AckManager>,
+        config: ClientAckRecordingConfig,
+        gateway_id: String,
+    ) -> Self {
+        Self {
+            ack_manager,
+            config,
+            gateway_id,
+            read_receipt_publisher: None,
+            receipts_policy_resolver: None,
+        }
+    }
+
+    /// 设置已读回执发布器（配置了 `read_receipts` 时生效）
+    pub fn with_read_receipt_publisher(mut self, publisher: Arc<dyn ReadReceiptPublisher>) -> Self {
+        self.read_receipt_publisher = Some(publisher);
+        self
+    }
+
+    /// 设置会话回执策略查询（配置了 `session_receipts` 时生效）
+    pub fn with_receipts_policy_resolver(
+        mut self,
+        resolver: Arc<dyn ReceiptsPolicyResolver>,
+    ) -> Self {
+        self.receipts_policy_resolver = Some(resolver);
+        self
+    }
+
+    /// 记录客户端 ACK（写入失败只记录日志，不影响 ACK 主流程）
+    pub async fn record(&self, msg_cmd: &MessageCommand, conn: &AckConnectionMeta<'_>) {
+        let Some(event) = self.event_from_command(msg_cmd, conn) else {
+            return;
+        };
+        let message_id = event.message_id.clone();
+        let ack_type = event.ack_type.as_str();
+        if let Some(receipts) = self.session_receipts_policy(msg_cmd, conn).await {
+            if !receipts_policy_allows(receipts, event.ack_type) {
+                debug!(
+                    message_id = %message_id,
+                    user_id = %conn.user_id,
+                    ack_type,
+                    receipts_policy = receipts.as_str(),
+                    "Client ACK dropped by conversation receipts policy"
+                );
+                return;
+            }
+        }
+        let receipt = self.read_receipt_for(&event, msg_cmd, conn);
+        match self.ack_manager.record_ack(event).await {
+            Ok(()) => {
+                debug!(
+                    message_id = %message_id,
+                    user_id = %conn.user_id,
+                    ack_type,
+                    "Client ACK recorded"
+                );
+                if let (Some(receipt), Some(publisher)) =
+                    (receipt, self.read_receipt_publisher.clone())
+                {
+                    // 回执推送不阻塞 ACK 处理
+                    tokio::spawn(async move {
+                        if let Err(e) = publisher.publish_receipt(&receipt).await {
+                            warn!(
+                                error = %e,
+                                message_id = %receipt.message_id,
+                                sender_id = %receipt.sender_id,
+                                "Failed to publish read receipt"
+                            );
+                        }
+                    });
+                }
+            }
+            Err(e) => warn!(
+                error = %e,
+                message_id = %message_id,
+                user_id = %conn.user_id,
+                "Failed to record client ACK"
+            ),
+        }
+    }
+
+    /// ACK 所属会话的回执策略（未启用或 ACK 帧未携带 `conversation_id` 时为 None）
+    async fn session_receipts_policy(
+        &self,
+        msg_cmd: &MessageCommand,
+        conn: &AckConnectionMeta<'_>,
+    ) -> Option<ReceiptsPolicy> {
+        let resolver = self.receipts_policy_resolver.as_ref()?;
+        let conversation_id = msg_cmd
+            .metadata
+            .get("conversation_id")
+            .and_then(|v| std::str::from_utf8(v).ok())
+            .filter(|id| !id.is_empty())?;
+        Some(resolver.resolve(conn.tenant_id, conversation_id).await)
+    }
+
+    /// 将 ACK 帧转换为 ACK 事件（message_id 为空时忽略）
+    pub(crate) fn event_from_command(
+        &self,
+        msg_cmd: &MessageCommand,
+        conn: &AckConnectionMeta<'_>,
+    ) -> Option<AckEvent> {
+        if msg_cmd.message_id.is_empty() || conn.user_id.is_empty() {
+            return None;
+        }
+        let metadata_str = |key: &str| {
+            msg_cmd
+                .metadata
+                .get(key)
+                .and_then(|v| std::str::from_utf8(v).ok())
+        };
+
+        let ack_type = metadata_str("ack_type")
+            .and_then(parse_ack_type)
+            .unwrap_or(self.config.default_ack_type);
+        let status = match metadata_str("ack_status") {
+            Some("failed") => AckStatus::Failed,
+            _ => AckStatus::Received,
+        };
+        let importance = metadata_str("importance")
+            .and_then(parse_importance)
+            .unwrap_or_else(|| self.config.importance.clone());
+
+        let mut metadata = serde_json::json!({
+            "connection_id": conn.connection_id,
+            "gateway_id": self.gateway_id,
+        });
+        metadata[ACK_TENANT_METADATA_KEY] = serde_json::Value::from(conn.tenant_id);
+        if let Some(device_id) = conn.device_id {
+            metadata["device_id"] = serde_json::Value::from(device_id);
+        }
+
+        Some(AckEvent {
+            message_id: msg_cmd.message_id.clone(),
+            user_id: conn.user_id.to_string(),
+            ack_type,
+            status,
+            timestamp: chrono::Utc::now().timestamp(),
+            importance,
+            metadata: Some(metadata),
+        })
+    }
+
+    /// 已读 ACK 对应的回执（未启用、非已读 ACK、缺少发送者、自己阅读或会话类型未启用时为 None）
+    pub(crate) fn read_receipt_for(
+        &self,
+        event: &AckEvent,
+        msg_cmd: &MessageCommand,
+        conn: &AckConnectionMeta<'_>,
+    ) -> Option<ReadReceipt> {
+        self.read_receipt_publisher.as_ref()?;
+        let policy = self.config.read_receipts.as_ref()?;
+        if event.ack_type != AckType::ReadAck || event.status != AckStatus::Received {
+            return None;
+        }
+        let metadata_string = |key: &str| {
+            msg_cmd
+                .metadata
+                .get(key)
+                .and_then(|v| std::str::from_utf8(v).ok())
+                .map(str::to_string)
+                .unwrap_or_default()
+        };
+        let sender_id = metadata_string("sender_id");
+        if sender_id.is_empty() || sender_id == conn.user_id {
+            return None;
+        }
+        let conversation_type = metadata_string("conversation_type");
+        if !policy.allows(&conversation_type) {
+            return None;
+        }
+
+        Some(ReadReceipt {
+            message_id: event.message_id.clone(),
+            conversation_id: metadata_string("conversation_id"),
+            conversation_type,
+            sender_id,
+            reader_id: conn.user_id.to_string(),
+            tenant_id: conn.tenant_id.to_string(),
+            read_at: event.timestamp,
+        })
+    }
+}
+
+/// 回执策略是否允许记录该类型的 ACK（只限制送达/已读 ACK）
+pub fn receipts_policy_allows(policy: ReceiptsPolicy, ack_type: AckType) -> bool {
+    match ack_type {
+        AckType::DeliveryAck => policy.allows_delivery(),
+        AckType::ReadAck => policy.allows_read(),
+        _ => true,
+    }
+}
+
+/// 解析 ACK 类型
+pub fn parse_ack_type(value: &str) -> Option<AckType> {
+    match value {
+        "transport" => Some(AckType::TransportAck),
+        "server" => Some(AckType::ServerAck),
+        "delivery" => Some(AckType::DeliveryAck),
+        "storage" => Some(AckType::StorageAck),
+        "read" => Some(AckType::ReadAck),
+        _ => None,
+    }
+}
+
+/// 解析重要性级别
+pub fn parse_importance(value: &str) -> Option<ImportanceLevel> {
+    match value {
+        "low" => Some(ImportanceLevel::Low),
+        "medium" => Some(ImportanceLevel::Medium),
+        "high" => Some(ImportanceLevel::High),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use flare_im_core::ack::AckStatusInfo;
+
+    struct NoopAckManager;
+
+    #[async_trait::async_trait]
+    impl AckManager for NoopAckManager {
+        async fn record_ack(&self, _event: AckEvent) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+
+        async fn get_ack_status(
+            &self,
+            _message_id: &str,
+            _user_id: &str,
+        ) -> Result<Option<AckStatusInfo>, Box<dyn std::error::Error>> {
+            Ok(None)
+        }
+
+        async fn batch_get_ack_status(
+            &self,
+            _acks: Vec<(String, String)>,
+        ) -> Result<Vec<AckStatusInfo>, Box<dyn std::error::Error>> {
+            Ok(Vec::new())
+        }
+
+        async fn exists_ack(
+            &self,
+            _message_id: &str,
+            _user_id: &str,
+        ) -> Result<bool, Box<dyn std::error::Error>> {
+            Ok(false)
+        }
+
+        async fn delete_ack(
+            &self,
+            _message_id: &str,
+            _user_id: &str,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    struct NoopReceiptPublisher;
+
+    #[async_trait::async_trait]
+    impl ReadReceiptPublisher for NoopReceiptPublisher {
+        async fn publish_receipt(
+            &self,
+            _receipt: &ReadReceipt,
+        ) -> flare_server_core::error::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn recorder() -> ClientAckRecorder {
+        ClientAckRecorder::new(
+            Arc::new(NoopAckManager),
+            ClientAckRecordingConfig::default(),
+            "gateway-1".to_string(),
+        )
+    }
+
+    fn command(message_id: &str, metadata: &[(&str, &str)]) -> MessageCommand {
+        MessageCommand {
+            r#type: 0,
+            message_id: message_id.to_string(),
+            payload: Vec::new(),
+            metadata: metadata
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.as_bytes().to_vec()))
+                .collect::<HashMap<_, _>>(),
+            seq: 0,
+        }
+    }
+
+    fn conn() -> AckConnectionMeta<'static> {
+        AckConnectionMeta {
+            connection_id: "conn-1",
+            user_id: "user-1",
+            device_id: Some("device-1"),
+            tenant_id: "tenant-1",
+        }
+    }
+
+    #[test]
+    fn test_event_from_command_defaults() {
+        let event = recorder()
+            .event_from_command(&command("msg-1", &[]), &conn())
+            .unwrap();
+        assert_eq!(event.message_id, "msg-1");
+        assert_eq!(event.ack_type, AckType::DeliveryAck);
+        assert_eq!(event.status, AckStatus::Received);
+        assert_eq!(event.importance, ImportanceLevel::Medium);
+
+        let metadata = event.metadata.unwrap();
+        assert_eq!(metadata[ACK_TENANT_METADATA_KEY], "tenant-1");
+        assert_eq!(metadata["device_id"], "device-1");
+        assert_eq!(metadata["connection_id"], "conn-1");
+        assert_eq!(metadata["gateway_id"], "gateway-1");
+    }
+
+    #[test]
+    fn test_event_from_command_metadata_overrides() {
+        let event = recorder()
+            .event_from_command(
+                &command(
+                    "msg-1",
+                    &[
+                        ("ack_type", "read"),
+                        ("ack_status", "failed"),
+                        ("importance", "high"),
+                    ],
+                ),
+                &conn(),
+            )
+            .unwrap();
+        assert_eq!(event.ack_type, AckType::ReadAck);
+        assert_eq!(event.status, AckStatus::Failed);
+        assert_eq!(event.importance, ImportanceLevel::High);
+
+        assert!(
+            recorder()
+                .event_from_command(&command("", &[]), &conn())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_read_receipt_policy() {
+        let config = ClientAckRecordingConfig {
+            read_receipts: crate::config::ReadReceiptConfig::from_list("single"),
+            ..ClientAckRecordingConfig::default()
+        };
+        let receipts =
+            ClientAckRecorder::new(Arc::new(NoopAckManager), config, "gateway-1".to_string())
+                .with_read_receipt_publisher(Arc::new(NoopReceiptPublisher));
+        let receipt_for = |recorder: &ClientAckRecorder, metadata: &[(&str, &str)]| {
+            let cmd = command("msg-1", metadata);
+            let event = recorder.event_from_command(&cmd, &conn()).unwrap();
+            recorder.read_receipt_for(&event, &cmd, &conn())
+        };
+
+        let receipt = receipt_for(
+            &receipts,
+            &[
+                ("ack_type", "read"),
+                ("sender_id", "user-2"),
+                ("conversation_id", "conv-1"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(receipt.sender_id, "user-2");
+        assert_eq!(receipt.reader_id, "user-1");
+        assert_eq!(receipt.conversation_id, "conv-1");
+        assert_eq!(receipt.tenant_id, "tenant-1");
+
+        // 群聊未启用、非已读 ACK、缺少发送者、自己阅读、未启用回执时不推送
+        let read_from = |sender: &'static str| [("ack_type", "read"), ("sender_id", sender)];
+        assert!(
+            receipt_for(
+                &receipts,
+                &[
+                    ("ack_type", "read"),
+                    ("sender_id", "user-2"),
+                    ("conversation_type", "group"),
+                ],
+            )
+            .is_none()
+        );
+        assert!(
+            receipt_for(
+                &receipts,
+                &[("ack_type", "delivery"), ("sender_id", "user-2")]
+            )
+            .is_none()
+        );
+        assert!(receipt_for(&receipts, &[("ack_type", "read")]).is_none());
+        assert!(receipt_for(&receipts, &read_from("user-1")).is_none());
+        assert!(receipt_for(&recorder(), &read_from("user-2")).is_none());
+    }
+
+    #[test]
+    fn test_receipts_policy_allows() {
+        assert!(receipts_policy_allows(
+            ReceiptsPolicy::Full,
+            AckType::ReadAck
+        ));
+        assert!(receipts_policy_allows(
+            ReceiptsPolicy::DeliveryOnly,
+            AckType::DeliveryAck
+        ));
+        assert!(!receipts_policy_allows(
+            ReceiptsPolicy::DeliveryOnly,
+            AckType::ReadAck
+        ));
+        assert!(!receipts_policy_allows(
+            ReceiptsPolicy::None,
+            AckType::DeliveryAck
+        ));
+        assert!(!receipts_policy_allows(
+            ReceiptsPolicy::None,
+            AckType::ReadAck
+        ));
+        // 传输/服务端/存储 ACK 不受回执策略限制
+        assert!(receipts_policy_allows(
+            ReceiptsPolicy::None,
+            AckType::TransportAck
+        ));
+    }
+}
@@ -13,20 +13,19 @@ use flare_core::common::protocol::{
 };
 use flare_core::server::handle::ServerHandle;
 use prost::Message as ProstMessage;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::sync::{Arc, OnceLock};
 use tracing::{info, warn};
 
 /// ACK 发送器
 ///
 /// 提供向客户端发送 ACK 和错误通知的功能
 pub struct AckSender {
-    server_handle: Arc<Mutex<Option<Arc<dyn ServerHandle>>>>,
+    server_handle: Arc<OnceLock<Arc<dyn ServerHandle>>>,
 }
 
 impl AckSender {
     /// 创建新的 ACK 发送器
-    pub fn new(server_handle: Arc<Mutex<Option<Arc<dyn ServerHandle>>>>) -> Self {
+    pub fn new(server_handle: Arc<OnceLock<Arc<dyn ServerHandle>>>) -> Self {
         Self { server_handle }
     }
 
@@ -236,8 +235,7 @@ impl AckSender {
 
     /// 发送 Frame 到指定连接（内部辅助方法）
     async fn send_frame(&self, connection_id: &str, frame: &Frame) -> Result<()> {
-        let handle_guard = self.server_handle.lock().await;
-        let handle = match handle_guard.as_ref() {
+        let handle = match self.server_handle.get() {
             Some(handle) => handle,
             None => {
                 return Err(FlareError::system(
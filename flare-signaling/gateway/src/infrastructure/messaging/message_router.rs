@@ -353,6 +353,68 @@ impl MessageRouter {
         Ok(send_response)
     }
 
+    /// 上报本网关按客户端地理位置汇总的延迟数据
+    pub async fn report_region_latency(
+        &self,
+        gateway_id: &str,
+        region: &str,
+        samples: Vec<crate::domain::service::GeoLatencySample>,
+    ) -> Result<()> {
+        use flare_proto::signaling::router::{RegionLatencySample, ReportRegionLatencyRequest};
+
+        let mut client_guard = self.ensure_client().await?;
+        let client = client_guard.as_mut().ok_or_else(|| {
+            anyhow::anyhow!("Route Service client not available after initialization")
+        })?;
+
+        let request = ReportRegionLatencyRequest {
+            gateway_id: gateway_id.to_string(),
+            region: region.to_string(),
+            samples: samples
+                .into_iter()
+                .map(|sample| RegionLatencySample {
+                    client_geo: sample.client_geo,
+                    sample_count: sample.sample_count,
+                    avg_rtt_ms: sample.avg_rtt_ms,
+                })
+                .collect(),
+        };
+        client
+            .report_region_latency(tonic::Request::new(request))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to report region latency: {}", e))?;
+        Ok(())
+    }
+
+    /// 查询持续高延迟客户端的推荐接入地区（无更优地区时返回 None）
+    pub async fn recommend_region(
+        &self,
+        user_id: &str,
+        client_geo: &str,
+        current_region: &str,
+        rtt_ms: u64,
+    ) -> Result<Option<String>> {
+        use flare_proto::signaling::router::RecommendRegionRequest;
+
+        let mut client_guard = self.ensure_client().await?;
+        let client = client_guard.as_mut().ok_or_else(|| {
+            anyhow::anyhow!("Route Service client not available after initialization")
+        })?;
+
+        let response = client
+            .recommend_region(tonic::Request::new(RecommendRegionRequest {
+                user_id: user_id.to_string(),
+                client_geo: client_geo.to_string(),
+                current_region: current_region.to_string(),
+                rtt_ms,
+            }))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to recommend region: {}", e))?
+            .into_inner();
+
+        Ok((!response.recommended_region.is_empty()).then_some(response.recommended_region))
+    }
+
     /// 检查客户端是否已连接
     pub async fn is_connected(&self) -> bool {
         self.router_client.lock().await.is_some()
@@ -1,6 +1,11 @@
 pub mod ack_publisher;
+pub mod ack_recorder;
 pub mod ack_sender;
 pub mod message_router;
+pub mod read_receipt;
+pub mod receipts_policy;
+pub mod resume_handoff;
+pub mod security_webhook;
 
 #[cfg(test)]
 mod message_router_test;
@@ -0,0 +1,182 @@
+//! 已读回执推送
+//!
+//! 客户端上报已读 ACK 后，生成轻量的已读回执（Read 操作消息），经 Push Proxy 实时推送给原发送者的在线设备：
+//! - 只推送在线设备，离线不持久化（离线设备上线后通过会话已读游标同步）
+//! - 按会话类型启用（见 `ReadReceiptConfig`），群聊可关闭
+//!
+//! 已读 ACK 帧 metadata 约定：`sender_id`（必填，原消息发送者）、`conversation_id`、`conversation_type`
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use flare_proto::common::message_content::Content;
+use flare_proto::common::message_operation::OperationData;
+use flare_proto::common::{
+    Message, MessageContent, MessageOperation, OperationType, ReadOperationData, TenantContext,
+};
+use flare_proto::push::push_service_client::PushServiceClient;
+use flare_proto::push::{PushMessageRequest, PushOptions};
+use flare_server_core::client::set_context_metadata;
+use flare_server_core::context::Context;
+use flare_server_core::discovery::ServiceClient;
+use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
+use tokio::sync::RwLock;
+use tonic::transport::Channel;
+
+/// 已读回执
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadReceipt {
+    /// 被阅读的消息ID
+    pub message_id: String,
+    pub conversation_id: String,
+    /// 会话类型（`single` / `group` / `channel`，未知时为空）
+    pub conversation_type: String,
+    /// 原消息发送者（回执接收方）
+    pub sender_id: String,
+    /// 阅读者
+    pub reader_id: String,
+    pub tenant_id: String,
+    /// 阅读时间（Unix 秒）
+    pub read_at: i64,
+}
+
+impl ReadReceipt {
+    /// 构建推送请求（Read 操作消息，仅推送在线设备）
+    pub fn to_push_request(&self) -> PushMessageRequest {
+        let timestamp = prost_types::Timestamp {
+            seconds: self.read_at,
+            nanos: 0,
+        };
+        let operation = MessageOperation {
+            operation_type: OperationType::Read as i32,
+            target_message_id: self.message_id.clone(),
+            operator_id: self.reader_id.clone(),
+            timestamp: Some(timestamp.clone()),
+            show_notice: false,
+            notice_text: String::new(),
+            target_user_id: self.sender_id.clone(),
+            operation_data: Some(OperationData::Read(ReadOperationData {
+                message_ids: vec![self.message_id.clone()],
+                read_at: Some(timestamp.clone()),
+                burn_after_read: false,
+            })),
+            metadata: HashMap::new(),
+        };
+
+        let message = Message {
+            server_id: format!("receipt_{}", uuid::Uuid::new_v4()),
+            conversation_id: self.conversation_id.clone(),
+            sender_id: self.reader_id.clone(),
+            message_type: flare_proto::MessageType::Operation as i32,
+            timestamp: Some(timestamp),
+            content: Some(MessageContent {
+                content: Some(Content::Operation(operation)),
+                extensions: Vec::new(),
+            }),
+            extra: HashMap::from([
+                ("message_type".to_string(), "operation".to_string()),
+                ("operation_type".to_string(), "read".to_string()),
+                ("read_receipt".to_string(), "true".to_string()),
+            ]),
+            ..Default::default()
+        };
+
+        PushMessageRequest {
+            user_ids: vec![self.sender_id.clone()],
+            message: Some(message),
+            options: Some(PushOptions {
+                require_online: true,
+                persist_if_offline: false,
+                priority: 3,
+                metadata: HashMap::new(),
+                channel: String::new(),
+                mute_when_quiet: false,
+            }),
+            context: None,
+            tenant: Some(TenantContext {
+                tenant_id: self.tenant_id.clone(),
+                ..Default::default()
+            }),
+            template_id: String::new(),
+            template_data: HashMap::new(),
+        }
+    }
+}
+
+/// 已读回执发布器
+#[async_trait]
+pub trait ReadReceiptPublisher: Send + Sync {
+    async fn publish_receipt(&self, receipt: &ReadReceipt) -> Result<()>;
+}
+
+/// 经 Push Proxy 推送已读回执
+pub struct GrpcReadReceiptPublisher {
+    service_type: String,
+    /// 服务发现客户端与 Push Proxy 客户端（懒加载）
+    client: RwLock<Option<(ServiceClient, PushServiceClient<Channel>)>>,
+}
+
+impl GrpcReadReceiptPublisher {
+    pub fn new(service_type: String) -> Self {
+        Self {
+            service_type,
+            client: RwLock::new(None),
+        }
+    }
+
+    async fn client(&self) -> Result<PushServiceClient<Channel>> {
+        if let Some((_, client)) = self.client.read().await.as_ref() {
+            return Ok(client.clone());
+        }
+
+        let service_discover = flare_im_core::discovery::create_discover(&self.service_type)
+            .await
+            .map_err(|e| {
+                ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    format!("Failed to create service discover: {}", e),
+                )
+                .build_error()
+            })?
+            .ok_or_else(|| {
+                ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    "Service discovery not configured".to_string(),
+                )
+                .build_error()
+            })?;
+        let mut service_client = ServiceClient::new(service_discover);
+        let channel = service_client.get_channel().await.map_err(|e| {
+            ErrorBuilder::new(
+                ErrorCode::ServiceUnavailable,
+                format!("Failed to get channel from service client: {}", e),
+            )
+            .build_error()
+        })?;
+        let client = PushServiceClient::new(channel);
+        *self.client.write().await = Some((service_client, client.clone()));
+        Ok(client)
+    }
+}
+
+#[async_trait]
+impl ReadReceiptPublisher for GrpcReadReceiptPublisher {
+    async fn publish_receipt(&self, receipt: &ReadReceipt) -> Result<()> {
+        let mut client = self.client().await?;
+
+        let ctx = Context::with_request_id(uuid::Uuid::new_v4().to_string())
+            .with_tenant_id(receipt.tenant_id.clone())
+            .with_user_id(receipt.reader_id.clone());
+        let mut request = tonic::Request::new(receipt.to_push_request());
+        set_context_metadata(&mut request, &ctx);
+
+        client.push_message(request).await.map_err(|e| {
+            ErrorBuilder::new(
+                ErrorCode::ServiceUnavailable,
+                format!("Failed to push read receipt: {}", e),
+            )
+            .build_error()
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+//! 会话回执策略查询
+//!
+//! 按会话查询回执策略（会话属性 `receipts_policy`），用于过滤送达/已读 ACK 与已读回执推送：
+//! - 查询结果按 `(tenant_id, conversation_id)` 缓存，过期后重新查询
+//! - 会话服务不可用或会话不存在时使用默认策略，不阻塞 ACK 处理
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use flare_conversation::domain::model::ReceiptsPolicy;
+use flare_proto::common::{FilterExpression, FilterOperator, Pagination};
+use flare_proto::conversation::SearchConversationsRequest;
+use flare_proto::conversation::conversation_service_client::ConversationServiceClient;
+use flare_server_core::client::set_context_metadata;
+use flare_server_core::context::Context;
+use flare_server_core::discovery::ServiceClient;
+use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
+use tokio::sync::{Mutex, RwLock};
+use tonic::transport::Channel;
+use tracing::warn;
+
+/// 缓存条目上限（超过时先清理过期条目，仍超过则清空）
+const MAX_CACHED_POLICIES: usize = 10_000;
+
+/// 会话回执策略查询
+#[async_trait]
+pub trait ReceiptsPolicyResolver: Send + Sync {
+    async fn resolve(&self, tenant_id: &str, conversation_id: &str) -> ReceiptsPolicy;
+}
+
+/// 通过会话服务查询回执策略（带本地缓存）
+pub struct ConversationReceiptsPolicyResolver {
+    service_type: String,
+    default_policy: ReceiptsPolicy,
+    cache_ttl: Duration,
+    /// 服务发现客户端与会话服务客户端（懒加载）
+    client: RwLock<Option<(ServiceClient, ConversationServiceClient<Channel>)>>,
+    cache: Mutex<HashMap<(String, String), (ReceiptsPolicy, Instant)>>,
+}
+
+impl ConversationReceiptsPolicyResolver {
+    pub fn new(service_type: String, default_policy: ReceiptsPolicy, cache_ttl: Duration) -> Self {
+        Self {
+            service_type,
+            default_policy,
+            cache_ttl,
+            client: RwLock::new(None),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn client(&self) -> Result<ConversationServiceClient<Channel>> {
+        if let Some((_, client)) = self.client.read().await.as_ref() {
+            return Ok(client.clone());
+        }
+
+        let service_discover = flare_im_core::discovery::create_discover(&self.service_type)
+            .await
+            .map_err(|e| {
+                ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    format!("Failed to create service discover: {}", e),
+                )
+                .build_error()
+            })?
+            .ok_or_else(|| {
+                ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    "Service discovery not configured".to_string(),
+                )
+                .build_error()
+            })?;
+        let mut service_client = ServiceClient::new(service_discover);
+        let channel = service_client.get_channel().await.map_err(|e| {
+            ErrorBuilder::new(
+                ErrorCode::ServiceUnavailable,
+                format!("Failed to get channel from service client: {}", e),
+            )
+            .build_error()
+        })?;
+        let client = ConversationServiceClient::new(channel);
+        *self.client.write().await = Some((service_client, client.clone()));
+        Ok(client)
+    }
+
+    /// 查询会话属性中的回执策略（会话不存在时为 None）
+    async fn query(
+        &self,
+        tenant_id: &str,
+        conversation_id: &str,
+    ) -> Result<Option<ReceiptsPolicy>> {
+        let mut client = self.client().await?;
+
+        // 会话服务没有单个会话查询接口，按 conversation_id 过滤搜索（会话 metadata 即会话属性）
+        let ctx = Context::with_request_id(uuid::Uuid::new_v4().to_string())
+            .with_tenant_id(tenant_id.to_string());
+        let mut request = tonic::Request::new(SearchConversationsRequest {
+            filters: vec![FilterExpression {
+                field: "conversation_id".to_string(),
+                op: FilterOperator::Eq as i32,
+                values: vec![conversation_id.to_string()],
+            }],
+            pagination: Some(Pagination {
+                limit: 1,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        set_context_metadata(&mut request, &ctx);
+
+        let response = client
+            .search_conversations(request)
+            .await
+            .map_err(|e| {
+                ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    format!("Failed to query conversation receipts policy: {}", e),
+                )
+                .build_error()
+            })?
+            .into_inner();
+
+        Ok(response
+            .conversations
+            .into_iter()
+            .find(|conversation| conversation.conversation_id == conversation_id)
+            .map(|conversation| {
+                ReceiptsPolicy::from_attributes(&conversation.metadata, self.default_policy)
+            }))
+    }
+}
+
+#[async_trait]
+impl ReceiptsPolicyResolver for ConversationReceiptsPolicyResolver {
+    async fn resolve(&self, tenant_id: &str, conversation_id: &str) -> ReceiptsPolicy {
+        let key = (tenant_id.to_string(), conversation_id.to_string());
+        let cached = self.cache.lock().await.get(&key).copied();
+        if let Some((policy, _)) = cached.filter(|(_, expires_at)| *expires_at > Instant::now()) {
+            return policy;
+        }
+
+        let policy = match self.query(tenant_id, conversation_id).await {
+            Ok(policy) => policy.unwrap_or(self.default_policy),
+            Err(e) => {
+                // 查询失败不缓存，下次 ACK 重新查询
+                warn!(
+                    error = %e,
+                    conversation_id = %conversation_id,
+                    "Failed to resolve receipts policy, using default"
+                );
+                return self.default_policy;
+            }
+        };
+
+        let now = Instant::now();
+        let mut cache = self.cache.lock().await;
+        if cache.len() >= MAX_CACHED_POLICIES {
+            cache.retain(|_, (_, expires_at)| *expires_at > now);
+            if cache.len() >= MAX_CACHED_POLICIES {
+                cache.clear();
+            }
+        }
+        cache.insert(key, (policy, now + self.cache_ttl));
+        policy
+    }
+}
@@ -0,0 +1,148 @@
+//! 续连宽限期到期消息转交
+//!
+//! 续连宽限期内暂存的消息，如果推送时用户还有其他在线设备（推送结果为成功，Push Server 不会走离线流程），
+//! 宽限期结束仍未续连时经 Push Proxy 重新提交：
+//! - 消息 `extra` 携带 `resume_handoff_device`，网关只投递给该设备，不会重复推送给其他在线设备
+//! - 该设备仍不在线时网关返回 `UserOffline`，由 Push Server 走离线推送流程
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use flare_proto::common::{Message, MessageEnvelope, TenantContext};
+use flare_proto::push::push_service_client::PushServiceClient;
+use flare_proto::push::{PushMessageRequest, PushOptions};
+use flare_server_core::client::set_context_metadata;
+use flare_server_core::context::Context;
+use flare_server_core::discovery::ServiceClient;
+use flare_server_core::error::{ErrorBuilder, ErrorCode, Result};
+use prost::Message as ProstMessage;
+use tokio::sync::RwLock;
+use tonic::transport::Channel;
+
+use crate::domain::service::DetachedSession;
+
+/// 消息 extra：只投递给指定设备（宽限期到期转交的消息）
+pub const RESUME_HANDOFF_DEVICE_KEY: &str = "resume_handoff_device";
+
+/// 构建转交推送请求（只包含未走过离线流程的暂存消息）
+pub fn handoff_requests(session: &DetachedSession) -> Vec<PushMessageRequest> {
+    session
+        .pending
+        .iter()
+        .filter(|delivery| !delivery.offline_handled)
+        .filter_map(|delivery| MessageEnvelope::decode(delivery.payload.as_slice()).ok())
+        .flat_map(|envelope| envelope.messages)
+        .map(|message| handoff_request(session, message))
+        .collect()
+}
+
+fn handoff_request(session: &DetachedSession, mut message: Message) -> PushMessageRequest {
+    message.extra.insert(
+        RESUME_HANDOFF_DEVICE_KEY.to_string(),
+        session.device_id.clone(),
+    );
+    PushMessageRequest {
+        user_ids: vec![session.user_id.clone()],
+        message: Some(message),
+        options: Some(PushOptions {
+            require_online: false,
+            persist_if_offline: true,
+            priority: 0, // 默认优先级
+            metadata: HashMap::new(),
+            channel: String::new(),
+            mute_when_quiet: false,
+        }),
+        context: None,
+        tenant: Some(TenantContext {
+            tenant_id: session.tenant_id.clone(),
+            ..Default::default()
+        }),
+        template_id: String::new(),
+        template_data: HashMap::new(),
+    }
+}
+
+/// 宽限期到期消息转交发布器
+#[async_trait]
+pub trait ResumeHandoffPublisher: Send + Sync {
+    /// 转交保留会话中未投递的消息，返回转交的消息数
+    async fn publish_undelivered(&self, session: &DetachedSession) -> Result<usize>;
+}
+
+/// 经 Push Proxy 转交未投递消息
+pub struct GrpcResumeHandoffPublisher {
+    service_type: String,
+    /// 服务发现客户端与 Push Proxy 客户端（懒加载）
+    client: RwLock<Option<(ServiceClient, PushServiceClient<Channel>)>>,
+}
+
+impl GrpcResumeHandoffPublisher {
+    pub fn new(service_type: String) -> Self {
+        Self {
+            service_type,
+            client: RwLock::new(None),
+        }
+    }
+
+    async fn client(&self) -> Result<PushServiceClient<Channel>> {
+        if let Some((_, client)) = self.client.read().await.as_ref() {
+            return Ok(client.clone());
+        }
+
+        let service_discover = flare_im_core::discovery::create_discover(&self.service_type)
+            .await
+            .map_err(|e| {
+                ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    format!("Failed to create service discover: {}", e),
+                )
+                .build_error()
+            })?
+            .ok_or_else(|| {
+                ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    "Service discovery not configured".to_string(),
+                )
+                .build_error()
+            })?;
+        let mut service_client = ServiceClient::new(service_discover);
+        let channel = service_client.get_channel().await.map_err(|e| {
+            ErrorBuilder::new(
+                ErrorCode::ServiceUnavailable,
+                format!("Failed to get channel from service client: {}", e),
+            )
+            .build_error()
+        })?;
+        let client = PushServiceClient::new(channel);
+        *self.client.write().await = Some((service_client, client.clone()));
+        Ok(client)
+    }
+}
+
+#[async_trait]
+impl ResumeHandoffPublisher for GrpcResumeHandoffPublisher {
+    async fn publish_undelivered(&self, session: &DetachedSession) -> Result<usize> {
+        let requests = handoff_requests(session);
+        if requests.is_empty() {
+            return Ok(0);
+        }
+        let mut client = self.client().await?;
+
+        let ctx = Context::with_request_id(uuid::Uuid::new_v4().to_string())
+            .with_tenant_id(session.tenant_id.clone())
+            .with_user_id(session.user_id.clone());
+        let count = requests.len();
+        for request in requests {
+            let mut request = tonic::Request::new(request);
+            set_context_metadata(&mut request, &ctx);
+            client.push_message(request).await.map_err(|e| {
+                ErrorBuilder::new(
+                    ErrorCode::ServiceUnavailable,
+                    format!("Failed to hand off pending delivery: {}", e),
+                )
+                .build_error()
+            })?;
+        }
+        Ok(count)
+    }
+}
@@ -0,0 +1,67 @@
+//! 安全事件 Webhook
+//!
+//! 将登录异常检测产生的安全事件以 JSON POST 到配置的 Webhook，供风控、审计系统消费。
+//! 配置密钥时按 WebHook 签名规则签名请求体（`X-Hook-Timestamp` + `X-Hook-Signature`）。
+
+use std::time::Duration;
+
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use flare_im_core::hooks::WebhookSigner;
+use reqwest::Client;
+
+use crate::config::SecurityWebhookConfig;
+use crate::domain::repository::SecurityEventPublisher;
+use crate::domain::service::SecurityEvent;
+
+/// Webhook 安全事件发布者
+pub struct WebhookSecurityEventPublisher {
+    client: Client,
+    config: SecurityWebhookConfig,
+    signer: Option<WebhookSigner>,
+}
+
+impl WebhookSecurityEventPublisher {
+    pub fn new(config: SecurityWebhookConfig) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .context("Failed to build security webhook client")?;
+        let signer =
+            WebhookSigner::from_secrets(config.secret.clone(), config.previous_secret.clone());
+        Ok(Self {
+            client,
+            config,
+            signer,
+        })
+    }
+}
+
+#[async_trait]
+impl SecurityEventPublisher for WebhookSecurityEventPublisher {
+    async fn publish(&self, event: &SecurityEvent) -> Result<()> {
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .json(event)
+            .build()
+            .context("Failed to build security webhook request")?;
+        if let Some(signer) = &self.signer {
+            signer.sign_request(&mut request);
+        }
+        let response = self
+            .client
+            .execute(request)
+            .await
+            .map_err(|e| anyhow!("Security webhook request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Security webhook returned {} for event {}",
+                response.status(),
+                event.event_id
+            ));
+        }
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@ pub mod connection_context;
 pub mod connection_query;
 pub mod conversation_client;
 pub mod error;
+pub mod listener;
 pub mod messaging;
 
 pub use messaging::ack_publisher::{
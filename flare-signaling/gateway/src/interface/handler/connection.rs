@@ -2,7 +2,7 @@
 //!
 //! 提供连接处理器的结构定义和连接管理相关方法
 
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use flare_core::server::handle::ServerHandle;
 use flare_core::server::ConnectionManagerTrait;
 use flare_server_core::discovery::ServiceClient;
@@ -11,7 +11,12 @@ use tracing::warn;
 
 use crate::application::handlers::{ConnectionHandler, MessageHandler};
 use crate::domain::repository::SignalingGateway;
+use crate::domain::service::{
+    ConnectionInspectionService, ConversationFocusService, FrameDirection, FrameRecorderService,
+    LatencyProbeService, LoginSecurityService, OutboundGuard, ResumeTicketService,
+};
 use crate::infrastructure::AckPublisher;
+use crate::infrastructure::messaging::ack_recorder::ClientAckRecorder;
 use crate::infrastructure::messaging::ack_sender::AckSender;
 use crate::infrastructure::messaging::message_router::MessageRouter;
 
@@ -28,8 +33,9 @@ pub struct LongConnectionHandler {
     pub(crate) signaling_gateway: Arc<dyn SignalingGateway>,
     pub(crate) gateway_id: String,
     pub(crate) default_tenant_id: String, // 默认租户ID（确保总是存在）
-    pub(crate) server_handle: Arc<Mutex<Option<Arc<dyn ServerHandle>>>>,
-    pub(crate) manager_trait: Arc<Mutex<Option<Arc<dyn ConnectionManagerTrait>>>>,
+    /// 服务器启动前设置一次，之后只读（所有监听分片的连接事件都会读取，不能加锁）
+    pub(crate) server_handle: Arc<OnceLock<Arc<dyn ServerHandle>>>,
+    pub(crate) manager_trait: Arc<OnceLock<Arc<dyn ConnectionManagerTrait>>>,
     pub(crate) ack_publisher: Option<Arc<dyn AckPublisher>>,
     pub(crate) message_router: Option<Arc<MessageRouter>>,
     pub(crate) ack_sender: Arc<AckSender>,
@@ -44,6 +50,20 @@ pub struct LongConnectionHandler {
         >,
     >,
     pub(crate) conversation_service_discover: Arc<Mutex<Option<ServiceClient>>>,
+    /// 延迟探测服务（未设置时忽略 LatencyProbe 帧）
+    pub(crate) latency_probe: Option<Arc<LatencyProbeService>>,
+    /// 会话聚焦服务（未设置时忽略 FocusConversation 帧）
+    pub(crate) conversation_focus: Option<Arc<ConversationFocusService>>,
+    /// 登录安全检测服务（未设置时不检测登录异常）
+    pub(crate) login_security: Option<Arc<LoginSecurityService>>,
+    /// 连接巡检服务（未设置时不统计发送队列深度）
+    pub(crate) connection_inspection: Option<Arc<ConnectionInspectionService>>,
+    /// 客户端 ACK 记录器（未设置时 ACK 只上报 Push Server，不写入 ACK 模块）
+    pub(crate) client_ack_recorder: Option<Arc<ClientAckRecorder>>,
+    /// 续连票据服务（未设置时不签发票据，断开即注销会话）
+    pub(crate) resume_tickets: Option<Arc<ResumeTicketService>>,
+    /// 协议帧录制服务（未设置时不录制）
+    pub(crate) frame_recorder: Option<Arc<FrameRecorderService>>,
     // 应用层处理器
     pub connection_handler: Arc<ConnectionHandler>,
     pub message_handler: Arc<MessageHandler>,
@@ -60,7 +80,7 @@ impl LongConnectionHandler {
         connection_handler: Arc<ConnectionHandler>,
         message_handler: Arc<MessageHandler>,
     ) -> Self {
-        let server_handle = Arc::new(Mutex::new(None));
+        let server_handle = Arc::new(OnceLock::new());
         let ack_sender = Arc::new(AckSender::new(server_handle.clone()));
 
         Self {
@@ -68,13 +88,20 @@ impl LongConnectionHandler {
             gateway_id,
             default_tenant_id,
             server_handle,
-            manager_trait: Arc::new(Mutex::new(None)),
+            manager_trait: Arc::new(OnceLock::new()),
             ack_publisher,
             message_router,
             ack_sender,
             metrics,
             conversation_service_client: Arc::new(Mutex::new(None)),
             conversation_service_discover: Arc::new(Mutex::new(None)),
+            latency_probe: None,
+            conversation_focus: None,
+            login_security: None,
+            connection_inspection: None,
+            client_ack_recorder: None,
+            resume_tickets: None,
+            frame_recorder: None,
             connection_handler,
             message_handler,
         }
@@ -89,7 +116,7 @@ impl LongConnectionHandler {
         message_router: Option<Arc<MessageRouter>>,
         metrics: Arc<flare_im_core::metrics::AccessGatewayMetrics>,
     ) -> Self {
-        let server_handle = Arc::new(Mutex::new(None));
+        let server_handle = Arc::new(OnceLock::new());
         let ack_sender = Arc::new(AckSender::new(server_handle.clone()));
 
         // 创建临时的应用服务实例来打破循环依赖
@@ -122,31 +149,126 @@ impl LongConnectionHandler {
             gateway_id,
             default_tenant_id,
             server_handle,
-            manager_trait: Arc::new(Mutex::new(None)),
+            manager_trait: Arc::new(OnceLock::new()),
             ack_publisher,
             message_router,
             ack_sender,
             metrics,
             conversation_service_client: Arc::new(Mutex::new(None)),
             conversation_service_discover: Arc::new(Mutex::new(None)),
+            latency_probe: None,
+            conversation_focus: None,
+            login_security: None,
+            connection_inspection: None,
+            client_ack_recorder: None,
+            resume_tickets: None,
+            frame_recorder: None,
             connection_handler,
             message_handler,
         }
     }
 
-    /// 设置 ServerHandle
-    pub async fn set_server_handle(&self, handle: Arc<dyn ServerHandle>) {
-        *self.server_handle.lock().await = Some(handle);
+    /// 设置延迟探测服务
+    pub fn with_latency_probe(mut self, latency_probe: Arc<LatencyProbeService>) -> Self {
+        self.latency_probe = Some(latency_probe);
+        self
     }
 
-    /// 设置 ConnectionManagerTrait
-    pub async fn set_connection_manager(&self, manager: Arc<dyn ConnectionManagerTrait>) {
-        *self.manager_trait.lock().await = Some(manager);
+    /// 设置会话聚焦服务
+    pub fn with_conversation_focus(
+        mut self,
+        conversation_focus: Arc<ConversationFocusService>,
+    ) -> Self {
+        self.conversation_focus = Some(conversation_focus);
+        self
+    }
+
+    /// 设置登录安全检测服务
+    pub fn with_login_security(mut self, login_security: Arc<LoginSecurityService>) -> Self {
+        self.login_security = Some(login_security);
+        self
+    }
+
+    /// 设置连接巡检服务
+    pub fn with_connection_inspection(
+        mut self,
+        connection_inspection: Arc<ConnectionInspectionService>,
+    ) -> Self {
+        self.connection_inspection = Some(connection_inspection);
+        self
+    }
+
+    /// 设置客户端 ACK 记录器
+    pub fn with_client_ack_recorder(mut self, client_ack_recorder: Arc<ClientAckRecorder>) -> Self {
+        self.client_ack_recorder = Some(client_ack_recorder);
+        self
+    }
+
+    /// 设置续连票据服务
+    pub fn with_resume_tickets(mut self, resume_tickets: Arc<ResumeTicketService>) -> Self {
+        self.resume_tickets = Some(resume_tickets);
+        self
+    }
+
+    /// 设置协议帧录制服务
+    pub fn with_frame_recorder(mut self, frame_recorder: Arc<FrameRecorderService>) -> Self {
+        self.frame_recorder = Some(frame_recorder);
+        self
+    }
+
+    /// 指定连接是否正在录制协议帧（用于跳过仅为录制而构造帧的开销）
+    pub(crate) fn is_recording_frames(&self, connection_id: &str) -> bool {
+        self.frame_recorder
+            .as_ref()
+            .is_some_and(|recorder| recorder.is_recording(connection_id))
+    }
+
+    /// 录制指定连接的一帧（连接未在录制时忽略）
+    pub(crate) fn record_frame(
+        &self,
+        connection_id: &str,
+        direction: FrameDirection,
+        frame: &flare_core::common::protocol::Frame,
+    ) {
+        if let Some(recorder) = &self.frame_recorder {
+            recorder.record(connection_id, direction, frame);
+        }
+    }
+
+    /// 登记一次到指定连接的下行发送（未启用连接巡检时返回 None）
+    pub(crate) fn track_outbound(&self, connection_id: &str) -> Option<OutboundGuard> {
+        self.connection_inspection
+            .as_ref()
+            .map(|inspection| inspection.begin_send(connection_id))
+    }
+
+    /// 设置 ServerHandle（只能设置一次，重复设置时保留第一次的值）
+    pub fn set_server_handle(&self, handle: Arc<dyn ServerHandle>) {
+        if self.server_handle.set(handle).is_err() {
+            warn!("server handle already set, ignoring");
+        }
+    }
+
+    /// 设置 ConnectionManagerTrait（只能设置一次，重复设置时保留第一次的值）
+    pub fn set_connection_manager(&self, manager: Arc<dyn ConnectionManagerTrait>) {
+        if self.manager_trait.set(manager).is_err() {
+            warn!("connection manager already set, ignoring");
+        }
+    }
+
+    /// 获取 ServerHandle（服务器启动前为 None）
+    pub(crate) fn server_handle(&self) -> Option<Arc<dyn ServerHandle>> {
+        self.server_handle.get().cloned()
+    }
+
+    /// 获取 ConnectionManager（服务器启动前为 None）
+    pub(crate) fn connection_manager(&self) -> Option<Arc<dyn ConnectionManagerTrait>> {
+        self.manager_trait.get().cloned()
     }
 
     /// 获取用户ID（从连接信息中提取）
     pub async fn user_id_for_connection(&self, connection_id: &str) -> Option<String> {
-        if let Some(ref manager) = *self.manager_trait.lock().await {
+        if let Some(manager) = self.connection_manager() {
             if let Some((_, conn_info)) = manager.get_connection(connection_id).await {
                 return conn_info.user_id.clone();
             }
@@ -156,7 +278,7 @@ impl LongConnectionHandler {
 
     /// 获取连接信息（包括设备ID等）
     pub(crate) async fn get_connection_info(&self, connection_id: &str) -> Option<(String, String)> {
-        if let Some(ref manager) = *self.manager_trait.lock().await {
+        if let Some(manager) = self.connection_manager() {
             if let Some((_, conn_info)) = manager.get_connection(connection_id).await {
                 // 如果 user_id 为 None，记录警告但不返回 None，而是尝试从其他途径获取
                 // 这样可以在连接建立时即使暂时没有 user_id 也能继续处理
@@ -191,7 +313,7 @@ impl LongConnectionHandler {
     /// 这里返回 None，conversation_id 应该从消息 payload 中提取
     pub(crate) async fn get_conversation_id_for_connection(&self, connection_id: &str) -> Option<String> {
         // 从连接管理器中尝试获取会话ID
-        if let Some(ref manager) = *self.manager_trait.lock().await {
+        if let Some(manager) = self.connection_manager() {
             if let Some((_, conn_info)) = manager.get_connection(connection_id).await {
                 // 尝试从连接信息的元数据中获取会话ID
                 let metadata = &conn_info.metadata;
@@ -224,7 +346,7 @@ impl LongConnectionHandler {
         &self,
         connection_id: &str,
     ) -> Option<std::collections::HashMap<String, String>> {
-        if let Some(ref manager) = *self.manager_trait.lock().await {
+        if let Some(manager) = self.connection_manager() {
             if let Some((_, conn_info)) = manager.get_connection(connection_id).await {
                 return Some(conn_info.metadata.clone());
             }
@@ -234,7 +356,7 @@ impl LongConnectionHandler {
 
     /// 主动断开指定连接
     pub async fn disconnect_connection(&self, connection_id: &str) {
-        if let Some(handle) = self.server_handle.lock().await.clone() {
+        if let Some(handle) = self.server_handle() {
             if let Err(err) = handle.disconnect(connection_id).await {
                 warn!(?err, %connection_id, "failed to disconnect connection");
             }
@@ -40,6 +40,16 @@ impl LongConnectionHandler {
                     "ListSessions" => {
                         return self.handle_list_sessions(custom_cmd, request_id).await;
                     }
+                    "LatencyProbe" => {
+                        return self
+                            .handle_latency_probe(custom_cmd, request_id, connection_id)
+                            .await;
+                    }
+                    "FocusConversation" => {
+                        return self
+                            .handle_focus_conversation(custom_cmd, request_id, connection_id)
+                            .await;
+                    }
                     _ => {
                         debug!(
                             connection_id = %connection_id,
@@ -209,4 +219,150 @@ impl LongConnectionHandler {
                 .build();
         Ok(Some(response_frame))
     }
+
+    /// 处理 LatencyProbe 自定义命令
+    ///
+    /// 立即回显探测帧供客户端计算 RTT；客户端在下一次探测中携带上一次测得的 RTT。
+    /// 持续高延迟的连接在后台向 Route 服务查询推荐接入地区，结果随下一次回显返回
+    async fn handle_latency_probe(
+        &self,
+        custom_cmd: &flare_core::common::protocol::CustomCommand,
+        request_id: String,
+        connection_id: &str,
+    ) -> CoreResult<Option<Frame>> {
+        use flare_proto::access_gateway::{LatencyProbeRequest, LatencyProbeResponse};
+
+        let Some(latency_probe) = self.latency_probe.clone() else {
+            return Ok(None);
+        };
+        let received_at_ms = chrono::Utc::now().timestamp_millis();
+        let req = LatencyProbeRequest::decode(&custom_cmd.data[..]).map_err(|e| {
+            CoreFlareError::deserialization_error(format!("decode LatencyProbeRequest: {}", e))
+        })?;
+
+        if req.last_rtt_ms > 0
+            && latency_probe.record(connection_id, &req.client_geo, req.last_rtt_ms)
+        {
+            if let (Some(router), Some(user_id)) = (
+                self.message_router.clone(),
+                self.user_id_for_connection(connection_id).await,
+            ) {
+                let latency_probe = latency_probe.clone();
+                let connection_id = connection_id.to_string();
+                let client_geo = req.client_geo.clone();
+                let rtt_ms = req.last_rtt_ms;
+                tokio::spawn(async move {
+                    match router
+                        .recommend_region(&user_id, &client_geo, latency_probe.region(), rtt_ms)
+                        .await
+                    {
+                        Ok(Some(region)) => {
+                            debug!(
+                                connection_id = %connection_id,
+                                rtt_ms,
+                                region = %region,
+                                "Recommending another access region for high-latency client"
+                            );
+                            latency_probe.set_recommendation(&connection_id, region);
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            debug!(connection_id = %connection_id, error = %e, "Region recommendation unavailable");
+                        }
+                    }
+                });
+            }
+        }
+
+        let resp = LatencyProbeResponse {
+            probe_id: req.probe_id,
+            client_sent_at_ms: req.client_sent_at_ms,
+            server_received_at_ms: received_at_ms,
+            gateway_id: self.gateway_id.clone(),
+            region: latency_probe.region().to_string(),
+            recommended_region: latency_probe
+                .take_recommendation(connection_id)
+                .unwrap_or_default(),
+        };
+        let mut buf = Vec::new();
+        LatencyProbeResponse::encode(&resp, &mut buf).map_err(|e| {
+            CoreFlareError::serialization_error(format!("encode LatencyProbeResponse: {}", e))
+        })?;
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("request_id".to_string(), request_id.as_bytes().to_vec());
+        let response_frame =
+            flare_core::common::protocol::builder::FrameBuilder::new()
+                .with_command(
+                    flare_core::common::protocol::flare::core::commands::Command {
+                        r#type: Some(CommandType::Custom(
+                            flare_core::common::protocol::CustomCommand {
+                                name: "LatencyProbe".to_string(),
+                                data: buf,
+                                metadata,
+                            },
+                        )),
+                    },
+                )
+                .with_message_id(request_id)
+                .with_reliability(Reliability::AtLeastOnce)
+                .build();
+        Ok(Some(response_frame))
+    }
+
+    /// 处理 FocusConversation 自定义命令
+    ///
+    /// 载荷为 JSON：`{"conversation_id": "..."}`，会话ID为空表示退出聚焦。
+    /// 客户端需在聚焦有效期内重复上报，处理后原样回显载荷作为确认
+    async fn handle_focus_conversation(
+        &self,
+        custom_cmd: &flare_core::common::protocol::CustomCommand,
+        request_id: String,
+        connection_id: &str,
+    ) -> CoreResult<Option<Frame>> {
+        #[derive(serde::Deserialize)]
+        struct FocusConversationRequest {
+            #[serde(default)]
+            conversation_id: String,
+        }
+
+        let Some(conversation_focus) = self.conversation_focus.clone() else {
+            return Ok(None);
+        };
+        if !conversation_focus.config().enabled {
+            return Ok(None);
+        }
+        let req: FocusConversationRequest =
+            serde_json::from_slice(&custom_cmd.data).map_err(|e| {
+                CoreFlareError::deserialization_error(format!(
+                    "decode FocusConversation: {}",
+                    e
+                ))
+            })?;
+        conversation_focus.set_focus(connection_id, &req.conversation_id);
+        debug!(
+            connection_id = %connection_id,
+            conversation_id = %req.conversation_id,
+            "Conversation focus updated"
+        );
+
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("request_id".to_string(), request_id.as_bytes().to_vec());
+        let response_frame =
+            flare_core::common::protocol::builder::FrameBuilder::new()
+                .with_command(
+                    flare_core::common::protocol::flare::core::commands::Command {
+                        r#type: Some(CommandType::Custom(
+                            flare_core::common::protocol::CustomCommand {
+                                name: "FocusConversation".to_string(),
+                                data: custom_cmd.data.clone(),
+                                metadata,
+                            },
+                        )),
+                    },
+                )
+                .with_message_id(request_id)
+                .with_reliability(Reliability::AtLeastOnce)
+                .build();
+        Ok(Some(response_frame))
+    }
 }
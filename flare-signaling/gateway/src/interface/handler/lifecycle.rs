@@ -3,39 +3,148 @@
 //! 处理连接建立和断开事件
 
 use flare_core::common::error::Result as CoreResult;
-use tracing::warn;
+use tracing::{info, warn};
 use tracing::instrument;
 
 use super::connection::LongConnectionHandler;
+use crate::domain::service::resume_ticket_service::{
+    METADATA_KEY_RESUME_CONVERSATION_ID, METADATA_KEY_RESUMED,
+};
+use crate::domain::service::{DetachedSession, LoginAttempt};
 
 impl LongConnectionHandler {
     /// 连接建立时的内部实现（协议适配层）
     #[instrument(skip(self), fields(connection_id))]
     pub(crate) async fn on_connect_impl(&self, connection_id: &str) -> CoreResult<()> {
+        self.metrics.connections_accepted_total.inc();
+
         // 获取当前活跃连接数
-        let active_count = self.server_handle
-            .lock()
-            .await
-            .as_ref()
+        let active_count = self
+            .server_handle()
             .map(|h| h.connection_count())
             .unwrap_or(0);
 
         // 获取连接信息并处理
         if let Some((user_id, device_id)) = self.get_connection_info(connection_id).await {
+            // 配置了自动录制的用户从建连开始录制协议帧
+            if let Some(recorder) = self
+                .frame_recorder
+                .as_ref()
+                .filter(|recorder| recorder.should_auto_record(&user_id))
+            {
+                if let Err(e) = recorder.start(connection_id, Some(&user_id)) {
+                    warn!(
+                        connection_id = %connection_id,
+                        error = %e,
+                        "Failed to start frame recording"
+                    );
+                }
+            }
+
             // 获取连接 metadata（包含 tenant_id 等信息）
             let connection_metadata = self.get_connection_metadata(connection_id).await;
-            
-            if let Err(err) = self
-                .connection_handler
-                .handle_connect(connection_id, &user_id, &device_id, active_count, connection_metadata.as_ref())
-                .await
+
+            // 通过续连票据认证的连接携带原会话ID
+            let resume_conversation_id = connection_metadata
+                .as_ref()
+                .filter(|metadata| {
+                    metadata.get(METADATA_KEY_RESUMED).map(String::as_str) == Some("true")
+                })
+                .and_then(|metadata| metadata.get(METADATA_KEY_RESUME_CONVERSATION_ID))
+                .cloned();
+
+            // 登录异常检测：租户开启 step-up 时断开连接，要求客户端重新认证
+            // （续连票据只签发给已通过检测的设备，续连时不再检测）
+            if let (Some(login_security), None) = (&self.login_security, &resume_conversation_id) {
+                let tenant_id = self.get_tenant_id_for_connection(connection_id).await;
+                let attempt = LoginAttempt::from_metadata(
+                    &tenant_id,
+                    &user_id,
+                    &device_id,
+                    connection_id,
+                    connection_metadata.as_ref(),
+                );
+                if login_security.on_login(&attempt).step_up {
+                    warn!(
+                        user_id = %user_id,
+                        connection_id = %connection_id,
+                        "Login anomaly requires re-authentication, disconnecting"
+                    );
+                    self.disconnect_connection(connection_id).await;
+                    return Ok(());
+                }
+            }
+
+            let detached = self
+                .resume_tickets
+                .as_ref()
+                .and_then(|resume_tickets| resume_tickets.reattach(&user_id, &device_id));
+            let conversation_id = match detached {
+                // 宽限期内续连：会话仍注册在本网关，直接恢复并补发暂存消息
+                Some(detached)
+                    if resume_conversation_id.as_deref()
+                        == Some(detached.conversation_id.as_str()) =>
+                {
+                    let conversation_id = self.connection_handler.handle_resume(
+                        connection_id,
+                        &user_id,
+                        &device_id,
+                        active_count,
+                        &detached.conversation_id,
+                    );
+                    self.replay_pending(connection_id, detached).await;
+                    Some(conversation_id)
+                }
+                detached => {
+                    let registered = self
+                        .connection_handler
+                        .handle_connect(
+                            connection_id,
+                            &user_id,
+                            &device_id,
+                            active_count,
+                            connection_metadata.as_ref(),
+                            resume_conversation_id.as_deref(),
+                        )
+                        .await;
+                    if let Some(detached) = detached {
+                        self.replay_pending(connection_id, detached).await;
+                    }
+                    match registered {
+                        Ok(conversation_id) => Some(conversation_id),
+                        Err(err) => {
+                            warn!(
+                                ?err,
+                                user_id = %user_id,
+                                connection_id = %connection_id,
+                                "Failed to handle connection"
+                            );
+                            None
+                        }
+                    }
+                }
+            };
+
+            // 签发新的续连票据
+            if let (Some(resume_tickets), Some(conversation_id)) =
+                (&self.resume_tickets, conversation_id)
             {
-                warn!(
-                    ?err,
-                    user_id = %user_id,
-                    connection_id = %connection_id,
-                    "Failed to handle connection"
+                let tenant_id = self.get_tenant_id_for_connection(connection_id).await;
+                let issued = resume_tickets.issue(
+                    connection_id,
+                    &tenant_id,
+                    &user_id,
+                    &device_id,
+                    &conversation_id,
                 );
+                // 未经 token 认证的连接（无绑定 token）不签发票据
+                if let Some(issued) = issued
+                    && let Err(err) = self
+                        .push_resume_ticket(connection_id, &issued.ticket, issued.expires_at)
+                        .await
+                {
+                    warn!(?err, connection_id = %connection_id, "Failed to push resume ticket");
+                }
             }
         } else {
             warn!(
@@ -50,18 +159,37 @@ impl LongConnectionHandler {
     /// 连接断开时的内部实现（协议适配层）
     #[instrument(skip(self), fields(connection_id))]
     pub(crate) async fn on_disconnect_impl(&self, connection_id: &str) -> CoreResult<()> {
+        if let Some(ref latency_probe) = self.latency_probe {
+            latency_probe.remove_connection(connection_id);
+        }
+        if let Some(ref conversation_focus) = self.conversation_focus {
+            conversation_focus.remove_connection(connection_id);
+        }
+        if let Some(ref connection_inspection) = self.connection_inspection {
+            connection_inspection.remove_connection(connection_id);
+        }
+        if let Some(ref frame_recorder) = self.frame_recorder {
+            frame_recorder.close_connection(connection_id);
+        }
+        // 已签发续连票据的连接保留会话到宽限期结束
+        let detached = self.resume_tickets.as_ref().is_some_and(|resume_tickets| {
+            let detached = resume_tickets.detach(connection_id);
+            if !detached {
+                resume_tickets.remove_connection(connection_id);
+            }
+            detached
+        });
+
         // 获取当前活跃连接数
-        let active_count = self.server_handle
-            .lock()
-            .await
-            .as_ref()
+        let active_count = self
+            .server_handle()
             .map(|h| h.connection_count())
             .unwrap_or(0);
 
         // 获取 user_id 并处理断开
         if let Some(user_id) = self.user_id_for_connection(connection_id).await {
             // 检查是否还有其他连接（在断开前，连接数 > 1 表示还有其他连接）
-            let has_other_connections = if let Some(ref manager) = *self.manager_trait.lock().await {
+            let has_other_connections = if let Some(manager) = self.connection_manager() {
                 let count = manager.connection_count().await;
                 count > 1 // 当前连接还未移除，所以 > 1 表示还有其他连接
             } else {
@@ -71,7 +199,12 @@ impl LongConnectionHandler {
             // 委托给应用层服务处理
             if let Err(err) = self
                 .connection_handler
-                .handle_disconnect(connection_id, &user_id, active_count, has_other_connections)
+                .handle_disconnect(
+                    connection_id,
+                    &user_id,
+                    active_count,
+                    has_other_connections || detached,
+                )
                 .await
             {
                 warn!(
@@ -85,4 +218,41 @@ impl LongConnectionHandler {
 
         Ok(())
     }
+
+    /// 补发宽限期内暂存的消息
+    async fn replay_pending(&self, connection_id: &str, detached: DetachedSession) {
+        if detached.dropped > 0 {
+            warn!(
+                user_id = %detached.user_id,
+                connection_id = %connection_id,
+                dropped = detached.dropped,
+                "Pending deliveries exceeded limit during resume grace period"
+            );
+        }
+        let total = detached.pending.len();
+        let mut replayed = 0;
+        for delivery in detached.pending {
+            match self
+                .push_message_to_connection(connection_id, delivery.payload)
+                .await
+            {
+                Ok(()) => replayed += 1,
+                Err(err) => warn!(
+                    ?err,
+                    connection_id = %connection_id,
+                    conversation_id = %delivery.conversation_id,
+                    "Failed to replay pending delivery"
+                ),
+            }
+        }
+        if total > 0 {
+            info!(
+                user_id = %detached.user_id,
+                connection_id = %connection_id,
+                replayed,
+                total,
+                "Replayed pending deliveries after resume"
+            );
+        }
+    }
 }
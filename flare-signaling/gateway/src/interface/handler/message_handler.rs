@@ -13,6 +13,8 @@ use prost::Message;
 use tracing::{debug, error, instrument, warn};
 
 use super::connection::LongConnectionHandler;
+use crate::domain::service::FrameDirection;
+use crate::infrastructure::messaging::ack_recorder::AckConnectionMeta;
 
 /// 实现 ServerEventHandler trait（Flare 模式核心接口）
 ///
@@ -27,6 +29,7 @@ impl ServerEventHandler for LongConnectionHandler {
         connection_id: &str,
     ) -> CoreResult<Option<Frame>> {
         let client_message_id = command.message_id.clone();
+        self.record_inbound_message(command, connection_id);
 
         // 刷新会话心跳（忽略错误，不影响主流程）
         if let Err(err) = self.refresh_session(connection_id).await {
@@ -87,6 +90,7 @@ impl ServerEventHandler for LongConnectionHandler {
         };
         
         let frame = frame_with_message_command(ack_cmd, Reliability::AtLeastOnce);
+        self.record_frame(connection_id, FrameDirection::Outbound, &frame);
         Ok(Some(frame))
     }
 
@@ -97,6 +101,7 @@ impl ServerEventHandler for LongConnectionHandler {
         command: &MessageCommand,
         connection_id: &str,
     ) -> CoreResult<Option<Frame>> {
+        self.record_inbound_message(command, connection_id);
         self.handle_client_ack(command, connection_id).await?;
         Ok(None)
     }
@@ -158,8 +163,13 @@ impl ServerEventHandler for LongConnectionHandler {
             .with_reliability(Reliability::AtLeastOnce)
             .with_timestamp(current_timestamp())
             .build();
-        
-        self.handle_frame_impl(&frame, connection_id).await
+        self.record_frame(connection_id, FrameDirection::Inbound, &frame);
+
+        let response = self.handle_frame_impl(&frame, connection_id).await?;
+        if let Some(response) = &response {
+            self.record_frame(connection_id, FrameDirection::Outbound, response);
+        }
+        Ok(response)
     }
 
     /// 处理连接建立完成事件
@@ -182,6 +192,14 @@ impl ServerEventHandler for LongConnectionHandler {
 // ============================================================================
 
 impl LongConnectionHandler {
+    /// 录制上行消息命令（框架已拆出命令，仅在录制时重新组帧）
+    fn record_inbound_message(&self, command: &MessageCommand, connection_id: &str) {
+        if self.is_recording_frames(connection_id) {
+            let frame = frame_with_message_command(command.clone(), Reliability::AtLeastOnce);
+            self.record_frame(connection_id, FrameDirection::Inbound, &frame);
+        }
+    }
+
     /// 处理消息发送（协议适配层）
     ///
     /// 从连接信息获取 user_id，委托给应用层服务处理
@@ -231,6 +249,30 @@ impl LongConnectionHandler {
             .handle_client_ack(connection_id, &user_id, msg_cmd)
             .await?;
 
+        // 写入 ACK 模块（启用时；无法识别用户的连接不记录）
+        if let Some(recorder) = self
+            .client_ack_recorder
+            .as_ref()
+            .filter(|_| user_id != "unknown")
+        {
+            let tenant_id = self.get_tenant_id_for_connection(connection_id).await;
+            let device_id = self
+                .get_connection_info(connection_id)
+                .await
+                .map(|(_, device_id)| device_id);
+            recorder
+                .record(
+                    msg_cmd,
+                    &AckConnectionMeta {
+                        connection_id,
+                        user_id: &user_id,
+                        device_id: device_id.as_deref(),
+                        tenant_id: &tenant_id,
+                    },
+                )
+                .await;
+        }
+
         // 推送窗口 ACK 更新会话游标（如果提供）
         if let (Some(conversation_id_bytes), Some(ack_seq_bytes)) = (
             msg_cmd.metadata.get("conversation_id"),
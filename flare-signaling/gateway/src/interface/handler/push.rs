@@ -2,17 +2,20 @@
 //!
 //! 提供向客户端推送消息的功能
 
+use std::collections::HashMap;
+
 use flare_core::common::error::{FlareError as CoreFlareError, Result as CoreResult};
+use flare_core::common::protocol::flare::core::commands::command::Type as CommandType;
 use flare_core::common::protocol::{MessageCommand, Reliability, frame_with_message_command, generate_message_id};
 use tracing::{debug, info};
 
 use super::connection::LongConnectionHandler;
+use crate::domain::service::FrameDirection;
 
 impl LongConnectionHandler {
     /// 推送消息到客户端
     pub async fn push_message_to_user(&self, user_id: &str, message: Vec<u8>) -> CoreResult<()> {
-        let handle_guard = self.server_handle.lock().await;
-        let handle = match handle_guard.as_ref() {
+        let handle = match self.server_handle() {
             Some(handle) => handle,
             None => {
                 return Err(CoreFlareError::system(
@@ -49,8 +52,18 @@ impl LongConnectionHandler {
         connection_id: &str,
         message: Vec<u8>,
     ) -> CoreResult<()> {
-        let handle_guard = self.server_handle.lock().await;
-        let handle = match handle_guard.as_ref() {
+        self.push_message_to_connection_with_metadata(connection_id, message, Default::default())
+            .await
+    }
+
+    /// 推送消息到指定连接（携带帧元数据，如 `silent` 提示客户端不响铃、不计角标）
+    pub async fn push_message_to_connection_with_metadata(
+        &self,
+        connection_id: &str,
+        message: Vec<u8>,
+        metadata: HashMap<String, Vec<u8>>,
+    ) -> CoreResult<()> {
+        let handle = match self.server_handle() {
             Some(handle) => handle,
             None => {
                 return Err(CoreFlareError::system(
@@ -63,12 +76,14 @@ impl LongConnectionHandler {
             r#type: 0,
             message_id: generate_message_id(),
             payload: message,
-            metadata: Default::default(),
+            metadata,
             seq: 0,
         };
 
         let frame = frame_with_message_command(cmd, Reliability::AtLeastOnce);
 
+        self.record_frame(connection_id, FrameDirection::Outbound, &frame);
+        let _outbound = self.track_outbound(connection_id);
         handle
             .send_to(connection_id, &frame)
             .await
@@ -81,14 +96,177 @@ impl LongConnectionHandler {
         Ok(())
     }
 
+    /// 推送会话更新通知到指定连接（聚焦模式下非聚焦会话的降级投递）
+    ///
+    /// 通知只携带会话ID与最新 seq，客户端按需调用 SyncMessages 拉取消息
+    pub async fn push_conversation_notify(
+        &self,
+        connection_id: &str,
+        conversation_id: &str,
+        max_seq: u64,
+        silent: bool,
+    ) -> CoreResult<()> {
+        let handle = match self.server_handle() {
+            Some(handle) => handle,
+            None => {
+                return Err(CoreFlareError::system(
+                    "ServerHandle not initialized".to_string(),
+                ));
+            }
+        };
+
+        let data = serde_json::to_vec(&serde_json::json!({
+            "conversation_id": conversation_id,
+            "max_seq": max_seq,
+            "silent": silent,
+        }))
+        .map_err(|e| {
+            CoreFlareError::serialization_error(format!("encode ConversationNotify: {}", e))
+        })?;
+        let frame = flare_core::common::protocol::builder::FrameBuilder::new()
+            .with_command(flare_core::common::protocol::flare::core::commands::Command {
+                r#type: Some(CommandType::Custom(
+                    flare_core::common::protocol::CustomCommand {
+                        name: "ConversationNotify".to_string(),
+                        data,
+                        metadata: HashMap::new(),
+                    },
+                )),
+            })
+            .with_message_id(generate_message_id())
+            .with_reliability(Reliability::AtLeastOnce)
+            .build();
+
+        self.record_frame(connection_id, FrameDirection::Outbound, &frame);
+        let _outbound = self.track_outbound(connection_id);
+        handle
+            .send_to(connection_id, &frame)
+            .await
+            .map_err(|e| CoreFlareError::system(format!("Failed to send notify: {}", e)))?;
+
+        debug!(
+            connection_id = %connection_id,
+            conversation_id = %conversation_id,
+            max_seq,
+            "Conversation notify pushed to connection"
+        );
+        Ok(())
+    }
+
+    /// 推送连接关闭通知（管理端强制断开前告知客户端原因）
+    ///
+    /// 客户端收到 `ConnectionClosed` 后按 reason_code 决定是否自动重连
+    pub async fn push_connection_closed(
+        &self,
+        connection_id: &str,
+        reason_code: &str,
+        reason: &str,
+    ) -> CoreResult<()> {
+        let handle = match self.server_handle() {
+            Some(handle) => handle,
+            None => {
+                return Err(CoreFlareError::system(
+                    "ServerHandle not initialized".to_string(),
+                ));
+            }
+        };
+
+        let data = serde_json::to_vec(&serde_json::json!({
+            "reason_code": reason_code,
+            "reason": reason,
+        }))
+        .map_err(|e| {
+            CoreFlareError::serialization_error(format!("encode ConnectionClosed: {}", e))
+        })?;
+        let frame = flare_core::common::protocol::builder::FrameBuilder::new()
+            .with_command(flare_core::common::protocol::flare::core::commands::Command {
+                r#type: Some(CommandType::Custom(
+                    flare_core::common::protocol::CustomCommand {
+                        name: "ConnectionClosed".to_string(),
+                        data,
+                        metadata: HashMap::new(),
+                    },
+                )),
+            })
+            .with_message_id(generate_message_id())
+            .with_reliability(Reliability::AtLeastOnce)
+            .build();
+
+        self.record_frame(connection_id, FrameDirection::Outbound, &frame);
+        handle
+            .send_to(connection_id, &frame)
+            .await
+            .map_err(|e| CoreFlareError::system(format!("Failed to send close notice: {}", e)))?;
+
+        debug!(
+            connection_id = %connection_id,
+            reason_code = %reason_code,
+            "Connection closed notice pushed"
+        );
+        Ok(())
+    }
+
+    /// 推送续连票据（连接建立、续连成功及票据刷新时下发）
+    ///
+    /// 客户端保存最新的 `ResumeTicket`，断线重连时以票据代替 token 认证
+    pub async fn push_resume_ticket(
+        &self,
+        connection_id: &str,
+        ticket: &str,
+        expires_at: i64,
+    ) -> CoreResult<()> {
+        let handle = match self.server_handle() {
+            Some(handle) => handle,
+            None => {
+                return Err(CoreFlareError::system(
+                    "ServerHandle not initialized".to_string(),
+                ));
+            }
+        };
+
+        let data = serde_json::to_vec(&serde_json::json!({
+            "ticket": ticket,
+            "expires_at": expires_at,
+        }))
+        .map_err(|e| {
+            CoreFlareError::serialization_error(format!("encode ResumeTicket: {}", e))
+        })?;
+        let frame = flare_core::common::protocol::builder::FrameBuilder::new()
+            .with_command(flare_core::common::protocol::flare::core::commands::Command {
+                r#type: Some(CommandType::Custom(
+                    flare_core::common::protocol::CustomCommand {
+                        name: "ResumeTicket".to_string(),
+                        data,
+                        metadata: HashMap::new(),
+                    },
+                )),
+            })
+            .with_message_id(generate_message_id())
+            .with_reliability(Reliability::AtLeastOnce)
+            .build();
+
+        self.record_frame(connection_id, FrameDirection::Outbound, &frame);
+        let _outbound = self.track_outbound(connection_id);
+        handle
+            .send_to(connection_id, &frame)
+            .await
+            .map_err(|e| CoreFlareError::system(format!("Failed to send resume ticket: {}", e)))?;
+
+        debug!(
+            connection_id = %connection_id,
+            expires_at,
+            "Resume ticket pushed to connection"
+        );
+        Ok(())
+    }
+
     /// 推送数据包到指定连接
     pub async fn push_packet_to_connection(
         &self,
         connection_id: &str,
         packet: &flare_proto::common::ServerPacket,
     ) -> CoreResult<()> {
-        let handle_guard = self.server_handle.lock().await;
-        let handle = match handle_guard.as_ref() {
+        let handle = match self.server_handle() {
             Some(handle) => handle,
             None => {
                 return Err(CoreFlareError::system(
@@ -116,6 +294,8 @@ impl LongConnectionHandler {
         let message_id = cmd.message_id.clone();
         let frame = frame_with_message_command(cmd, Reliability::AtLeastOnce);
 
+        self.record_frame(connection_id, FrameDirection::Outbound, &frame);
+        let _outbound = self.track_outbound(connection_id);
         handle
             .send_to(connection_id, &frame)
             .await
@@ -135,8 +315,7 @@ impl LongConnectionHandler {
         user_id: &str,
         packet: &flare_proto::common::ServerPacket,
     ) -> CoreResult<()> {
-        let handle_guard = self.server_handle.lock().await;
-        let handle = match handle_guard.as_ref() {
+        let handle = match self.server_handle() {
             Some(handle) => handle,
             None => {
                 return Err(CoreFlareError::system(
@@ -0,0 +1,217 @@
+//! # 连接管理 HTTP 接口
+//!
+//! - `GET /admin/users/{user_id}/connections?tenant_id=`：查询用户的在线连接
+//!   （所在网关、协议、建连时间、最近心跳、发送队列深度、协商能力）
+//! - `POST /admin/connections/{connection_id}/disconnect`：强制断开本网关上的连接，
+//!   请求体 `{"reason_code": "...", "reason": "...", "operator": "..."}`
+//! - `POST /admin/connections/{connection_id}/recording`：开启本网关上连接的协议帧录制
+//! - `GET /admin/connections/{connection_id}/recording`：导出录制结果（脱敏后的上下行帧），
+//!   可直接作为 `frame-replay` 工具的输入
+//! - `DELETE /admin/connections/{connection_id}/recording`：停止录制并返回最终录制结果
+//!
+//! 帧录制接口需启用 `GATEWAY_FRAME_RECORDER_ENABLED`，未启用时返回 404
+//!
+//! 鉴权：`Authorization: Bearer <admin_token>`
+
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use flare_server_core::context::Context;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::application::handlers::{ConnectionAdminService, DisconnectOutcome, RecordingOutcome};
+use crate::domain::service::FrameRecording;
+
+/// reason_code 最大长度
+const MAX_REASON_CODE_LEN: usize = 64;
+
+/// 管理接口共享状态
+#[derive(Clone)]
+pub struct AdminState {
+    pub service: Arc<ConnectionAdminService>,
+    pub token: Arc<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConnectionsQuery {
+    #[serde(default)]
+    tenant_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DisconnectRequest {
+    reason_code: String,
+    #[serde(default)]
+    reason: String,
+    #[serde(default)]
+    operator: Option<String>,
+}
+
+/// 构建管理接口路由
+pub fn router(state: AdminState) -> Router {
+    Router::new()
+        .route("/admin/users/:user_id/connections", get(list_connections))
+        .route(
+            "/admin/connections/:connection_id/disconnect",
+            post(disconnect_connection),
+        )
+        .route(
+            "/admin/connections/:connection_id/recording",
+            post(start_recording)
+                .get(dump_recording)
+                .delete(stop_recording),
+        )
+        .with_state(state)
+}
+
+async fn list_connections(
+    State(state): State<AdminState>,
+    Path(user_id): Path<String>,
+    Query(query): Query<ConnectionsQuery>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let mut ctx = Context::with_request_id(uuid::Uuid::new_v4().to_string());
+    if let Some(tenant_id) = query.tenant_id.filter(|t| !t.is_empty()) {
+        ctx = ctx.with_tenant_id(tenant_id);
+    }
+
+    match state.service.list_user_connections(&ctx, &user_id).await {
+        Ok(connections) => Json(serde_json::json!({
+            "user_id": user_id,
+            "queried_gateway_id": state.service.gateway_id(),
+            "connections": connections,
+        }))
+        .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+async fn disconnect_connection(
+    State(state): State<AdminState>,
+    Path(connection_id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<DisconnectRequest>,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    if request.reason_code.is_empty() || request.reason_code.len() > MAX_REASON_CODE_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("reason_code must be 1-{} bytes", MAX_REASON_CODE_LEN),
+        )
+            .into_response();
+    }
+
+    let operator = request.operator.as_deref().unwrap_or("admin");
+    match state
+        .service
+        .force_disconnect(&connection_id, &request.reason_code, &request.reason, operator)
+        .await
+    {
+        Ok(DisconnectOutcome::Disconnected) => Json(serde_json::json!({
+            "connection_id": connection_id,
+            "gateway_id": state.service.gateway_id(),
+            "disconnected": true,
+        }))
+        .into_response(),
+        Ok(DisconnectOutcome::NotFound) => (
+            StatusCode::NOT_FOUND,
+            format!(
+                "Connection {} not found on gateway {}; use the gateway_id from the connection lookup",
+                connection_id,
+                state.service.gateway_id()
+            ),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+async fn start_recording(
+    State(state): State<AdminState>,
+    Path(connection_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    match state.service.start_frame_recording(&connection_id).await {
+        RecordingOutcome::Started => Json(serde_json::json!({
+            "connection_id": connection_id,
+            "gateway_id": state.service.gateway_id(),
+            "recording": true,
+        }))
+        .into_response(),
+        RecordingOutcome::NotFound => (
+            StatusCode::NOT_FOUND,
+            format!(
+                "Connection {} not found on gateway {}",
+                connection_id,
+                state.service.gateway_id()
+            ),
+        )
+            .into_response(),
+        RecordingOutcome::Disabled => (
+            StatusCode::NOT_FOUND,
+            "Frame recorder is not enabled on this gateway",
+        )
+            .into_response(),
+        RecordingOutcome::Rejected(e) => (StatusCode::CONFLICT, e.to_string()).into_response(),
+    }
+}
+
+async fn dump_recording(
+    State(state): State<AdminState>,
+    Path(connection_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    recording_response(state.service.frame_recording(&connection_id), &connection_id)
+}
+
+async fn stop_recording(
+    State(state): State<AdminState>,
+    Path(connection_id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    if !is_authorized(&state, &headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    recording_response(state.service.stop_frame_recording(&connection_id), &connection_id)
+}
+
+fn recording_response(recording: Option<FrameRecording>, connection_id: &str) -> Response {
+    match recording {
+        Some(recording) => Json(recording).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            format!("No frame recording for connection {}", connection_id),
+        )
+            .into_response(),
+    }
+}
+
+fn is_authorized(state: &AdminState, headers: &HeaderMap) -> bool {
+    let authorized = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .is_some_and(|token| token == state.token.as_str());
+    if !authorized {
+        warn!("Rejected admin request with invalid token");
+    }
+    authorized
+}
@@ -0,0 +1,3 @@
+//! HTTP 接口（运维管理）
+
+pub mod admin;
@@ -14,7 +14,7 @@ pub struct ApplicationBootstrap;
 impl ApplicationBootstrap {
     /// 运行应用的主入口点
     pub async fn run() -> Result<()> {
-        use flare_im_core::load_config;
+        use flare_im_core::{load_config_with_validation, strict_mode};
         use std::path::Path;
 
         // 加载应用配置（尝试多个候选路径）
@@ -34,7 +34,7 @@ impl ApplicationBootstrap {
             .unwrap_or_else(|| "config".to_string()); // 默认使用 "config"
         
         info!(config_path = %config_path, "Loading configuration");
-        let app_config = load_config(Some(&config_path));
+        let app_config = load_config_with_validation(Some(&config_path), strict_mode())?;
         // 初始化 OpenTelemetry 追踪
         #[cfg(feature = "tracing")]
         {
@@ -8,4 +8,4 @@ mod wire;
 pub use bootstrap::ApplicationBootstrap;
 pub use service_manager::{PortConfig, ServiceManager};
 pub use startup::{GrpcServiceInfo, StartupInfo};
-pub use wire::{ApplicationContext, GrpcServices};
+pub use wire::{AdminApiContext, ApplicationContext, GrpcServices};
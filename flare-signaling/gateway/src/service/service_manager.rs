@@ -179,6 +179,7 @@ impl ServiceManager {
                 );
             
             let server_result = Server::builder()
+                .add_service(flare_im_core::ServerInfoServer::from_global())
                 .add_service(access_gateway_service)
                 .serve_with_shutdown(grpc_addr, async {
                     shutdown_rx.await.ok();
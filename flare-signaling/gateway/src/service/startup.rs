@@ -185,8 +185,10 @@ pub async fn start_services(
     // 获取长连接服务器（用于优雅停机）
     let long_connection_server = context.long_connection_server.clone();
 
+    let admin_api = context.admin_api;
+
     // 使用 ServiceRuntime 统一管理服务生命周期
-    let runtime = ServiceRuntime::new("access-gateway", grpc_addr)
+    let mut runtime = ServiceRuntime::new("access-gateway", grpc_addr)
         // 添加 gRPC 服务任务
         .add_spawn_with_shutdown("grpc-server", move |shutdown_rx| async move {
             info!("正在启动 gRPC 服务器: {}", grpc_addr);
@@ -205,6 +207,7 @@ pub async fn start_services(
                 );
             
             let server_result = Server::builder()
+                .add_service(flare_im_core::ServerInfoServer::from_global())
                 .add_service(access_gateway_service)
                 .serve_with_shutdown(grpc_addr, async move {
                     info!(
@@ -237,30 +240,63 @@ pub async fn start_services(
             }
         });
 
+    // 连接管理 HTTP 接口（可选）
+    if let Some(admin_api) = admin_api {
+        let admin_addr: SocketAddr = admin_api
+            .address
+            .parse()
+            .map_err(|err| anyhow::anyhow!("Invalid admin API address: {}", err))?;
+        let router = crate::interface::http::admin::router(admin_api.state);
+
+        runtime = runtime.add_spawn_with_shutdown("admin-http", move |shutdown_rx| async move {
+            let listener = tokio::net::TcpListener::bind(admin_addr)
+                .await
+                .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
+                    format!("Failed to bind admin API address: {}", e).into()
+                })?;
+            info!(address = %admin_addr, "✅ Access Gateway admin API is listening");
+
+            axum::serve(listener, router)
+                .with_graceful_shutdown(async move {
+                    let _ = shutdown_rx.await;
+                })
+                .await
+                .map_err(|e| format!("admin API server error: {}", e).into())
+        });
+    }
+
     // 运行服务（带服务注册）
     let gateway_id_for_reg = gateway_id.clone();
     let region_for_reg = region.clone();
+    // 实例代际：每次进程启动唯一，同一 gateway_id 重启后随之变化
+    let generation_for_reg = uuid::Uuid::new_v4().to_string();
     let long_connection_server_for_cleanup = long_connection_server.clone();
 
     runtime
         .run_with_registration(move |addr| {
             let gateway_id_clone = gateway_id_for_reg.clone();
             let region_clone = region_for_reg.clone();
+            let instance_generation = generation_for_reg.clone();
 
             Box::pin(async move {
-                // 注册服务（使用常量）
+                // 注册服务（使用常量），上报实例代际供推送侧识别实例替换
                 use flare_im_core::service_names::ACCESS_GATEWAY;
-                match flare_im_core::discovery::register_service_only(
+                let metadata = std::collections::HashMap::from([(
+                    flare_im_core::gateway::GATEWAY_GENERATION_METADATA.to_string(),
+                    instance_generation.clone(),
+                )]);
+                match flare_im_core::discovery::register_service_only_with_metadata(
                     ACCESS_GATEWAY,
                     addr,
                     Some(gateway_id_clone.clone()),
+                    Some(metadata),
                 )
                 .await
                 {
                     Ok(Some(registry)) => {
                         info!(
-                            "✅ Service registered: {} (instance_id={}, region={:?})",
-                            ACCESS_GATEWAY, gateway_id_clone, region_clone
+                            "✅ Service registered: {} (instance_id={}, region={:?}, generation={})",
+                            ACCESS_GATEWAY, gateway_id_clone, region_clone, instance_generation
                         );
                         Ok(Some(registry))
                     }
@@ -9,18 +9,27 @@ use anyhow::{Context as AnyhowContext, Result};
 use uuid::Uuid;
 
 use crate::application::handlers::{
-    ConnectionQueryService, PushMessageService,
+    ConnectionAdminService, ConnectionQueryService, PushMessageService,
 };
 use crate::application::handlers::{ConnectionHandler, MessageHandler};
 use crate::config::AccessGatewayConfig;
 use crate::domain::repository::{ConnectionQuery, SignalingGateway};
-use crate::domain::service::{GatewayService, PushDomainService, ConversationDomainService, MessageDomainService};
+use crate::domain::service::{GatewayService, LatencyProbeService, PushDomainService, ConversationDomainService, MessageDomainService};
+use crate::domain::service::{ConversationFocusService, LoginSecurityConfig, LoginSecurityService};
+use crate::domain::service::{
+    ConnectionInspectionService, FrameRecorderService, ResumeTicketService,
+};
 use crate::infrastructure::auth::TokenAuthenticator;
 use crate::infrastructure::connection_query::ManagerConnectionQuery;
+use crate::infrastructure::listener::ShardedLongConnectionServer;
+use crate::infrastructure::messaging::ack_recorder::ClientAckRecorder;
+use crate::infrastructure::messaging::resume_handoff::ResumeHandoffPublisher;
+use crate::infrastructure::messaging::security_webhook::WebhookSecurityEventPublisher;
 use crate::infrastructure::signaling::grpc::GrpcSignalingGateway;
 use crate::infrastructure::{AckPublisher, GrpcAckPublisher};
 use crate::interface::handler::LongConnectionHandler;
 use crate::interface::grpc::handler::AccessGatewayHandler;
+use crate::interface::http::admin::AdminState;
 use crate::service::service_manager::PortConfig;
 
 // 注意：最新的 Flare 模式不再需要在 FlareServerBuilder 中配置中间件
@@ -31,6 +40,16 @@ use flare_core::server::handle::{DefaultServerHandle, ServerHandle};
 use flare_im_core::metrics::AccessGatewayMetrics;
 use flare_server_core::Config;
 use flare_server_core::auth::{RedisTokenStore, TokenService};
+use flare_im_core::auth::{SigningKeySet, TokenKeyRing, spawn_key_file_reload};
+
+/// 令牌签名密钥文件重新加载间隔
+const TOKEN_KEYS_RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 登录安全检测记录清理间隔
+const LOGIN_SECURITY_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 续连票据维护间隔（刷新票据、清理超过宽限期的会话）
+const RESUME_TICKET_MAINTENANCE_INTERVAL: Duration = Duration::from_secs(1);
 
 /// gRPC 服务集合
 ///
@@ -41,7 +60,7 @@ pub struct GrpcServices {
 
 /// 应用上下文 - 包含所有已初始化的服务
 pub struct ApplicationContext {
-    pub long_connection_server: Arc<tokio::sync::Mutex<Option<FlareServer>>>,
+    pub long_connection_server: Arc<tokio::sync::Mutex<Option<ShardedLongConnectionServer>>>,
     pub grpc_services: GrpcServices,
     /// 推送领域服务（用于批量消息刷新）
     pub push_domain_service: Arc<crate::domain::service::push_domain_service::PushDomainService>,
@@ -49,6 +68,14 @@ pub struct ApplicationContext {
     pub gateway_id: String,
     /// 地区
     pub region: Option<String>,
+    /// 连接管理 HTTP 接口（未配置时为 None）
+    pub admin_api: Option<AdminApiContext>,
+}
+
+/// 连接管理 HTTP 接口上下文
+pub struct AdminApiContext {
+    pub address: String,
+    pub state: AdminState,
 }
 
 /// 构建应用上下文
@@ -261,7 +288,7 @@ pub async fn initialize(
     ));
 
     // 16. 更新连接处理器中的应用处理器引用
-    let connection_handler = Arc::new(LongConnectionHandler::new(
+    let mut long_connection_handler = LongConnectionHandler::new(
         signaling_gateway.clone(),
         gateway_id.clone(),
         access_config.default_tenant_id.clone(),
@@ -270,13 +297,91 @@ pub async fn initialize(
         metrics.clone(),
         connection_handler_app.clone(),
         message_handler_app.clone(),
-    ));
+    );
+    if let Some(ref region) = region {
+        let latency_probe = Arc::new(LatencyProbeService::new(
+            region.clone(),
+            access_config.latency_probe.clone(),
+        ));
+        spawn_latency_reporter(
+            latency_probe.clone(),
+            message_router_arc.clone(),
+            gateway_id.clone(),
+        );
+        long_connection_handler = long_connection_handler.with_latency_probe(latency_probe);
+    }
+    let login_security = build_login_security(&access_config, &gateway_id)?;
+    if let Some(ref login_security) = login_security {
+        long_connection_handler =
+            long_connection_handler.with_login_security(login_security.clone());
+    }
+    let conversation_focus = access_config
+        .conversation_focus
+        .enabled
+        .then(|| Arc::new(ConversationFocusService::new(access_config.conversation_focus.clone())));
+    if let Some(ref conversation_focus) = conversation_focus {
+        long_connection_handler =
+            long_connection_handler.with_conversation_focus(conversation_focus.clone());
+    }
+    // 连接巡检仅在启用管理接口时统计
+    let connection_inspection = access_config
+        .admin_api
+        .as_ref()
+        .map(|_| Arc::new(ConnectionInspectionService::new()));
+    if let Some(ref connection_inspection) = connection_inspection {
+        long_connection_handler =
+            long_connection_handler.with_connection_inspection(connection_inspection.clone());
+    }
+    // 协议帧录制仅在启用管理接口时可用（录制结果只能通过管理接口导出）
+    let frame_recorder = access_config
+        .frame_recorder
+        .as_ref()
+        .filter(|_| access_config.admin_api.is_some())
+        .map(|config| {
+            tracing::info!(
+                capacity = config.capacity,
+                max_recordings = config.max_recordings,
+                redact_payloads = config.redact_payloads,
+                auto_record_users = config.auto_record_users.len(),
+                "Frame recorder enabled"
+            );
+            Arc::new(FrameRecorderService::new(config.clone()))
+        });
+    if let Some(ref frame_recorder) = frame_recorder {
+        long_connection_handler =
+            long_connection_handler.with_frame_recorder(frame_recorder.clone());
+    }
+    if let Some(ref recording) = access_config.client_ack_recording {
+        long_connection_handler = long_connection_handler
+            .with_client_ack_recorder(build_client_ack_recorder(recording, &gateway_id).await?);
+    }
+    let resume_tickets = access_config.resume_ticket.as_ref().map(|config| {
+        tracing::info!(
+            cluster = %config.cluster,
+            ttl_secs = config.ttl.as_secs(),
+            grace_secs = config.grace.as_secs(),
+            "Resume tickets enabled"
+        );
+        Arc::new(ResumeTicketService::new(config.clone()))
+    });
+    if let Some(ref resume_tickets) = resume_tickets {
+        long_connection_handler =
+            long_connection_handler.with_resume_tickets(resume_tickets.clone());
+    }
+    let connection_handler = Arc::new(long_connection_handler);
 
     // 17. 构建推送领域服务
-    let push_domain_service = Arc::new(PushDomainService::new(
+    let mut push_domain_service = PushDomainService::new(
         connection_handler.clone(),
         connection_query.clone(),
-    ));
+    );
+    if let Some(conversation_focus) = conversation_focus {
+        push_domain_service = push_domain_service.with_conversation_focus(conversation_focus);
+    }
+    if let Some(ref resume_tickets) = resume_tickets {
+        push_domain_service = push_domain_service.with_resume_tickets(resume_tickets.clone());
+    }
+    let push_domain_service = Arc::new(push_domain_service);
 
     // 18. 构建推送服务（应用层）
     let push_service = Arc::new(PushMessageService::new(
@@ -288,7 +393,20 @@ pub async fn initialize(
     let connection_query_service = Arc::new(ConnectionQueryService::new(connection_query.clone()));
 
     // 19. 构建认证器
-    let authenticator = build_authenticator(&access_config).await;
+    let authenticator =
+        build_authenticator(&access_config, login_security, resume_tickets.clone()).await?;
+    if let Some(ref resume_tickets) = resume_tickets {
+        use crate::infrastructure::messaging::resume_handoff::GrpcResumeHandoffPublisher;
+        use flare_im_core::service_names::{PUSH_PROXY, get_service_name};
+
+        spawn_resume_ticket_maintenance(
+            resume_tickets.clone(),
+            authenticator.clone(),
+            Arc::new(GrpcResumeHandoffPublisher::new(get_service_name(PUSH_PROXY))),
+            connection_handler.clone(),
+            connection_handler_app.clone(),
+        );
+    }
 
     // 20. 构建长连接服务器
     debug!(ws_port = %port_config.ws_port, quic_port = %port_config.quic_port, "Building long connection server");
@@ -300,6 +418,7 @@ pub async fn initialize(
         authenticator,
         connection_handler.clone(),
         access_config.clone(),
+        metrics.clone(),
     )
     .await
     .with_context(|| "Failed to build long connection server")?;
@@ -318,6 +437,32 @@ pub async fn initialize(
     ));
     debug!("gRPC handlers built successfully");
 
+    // 连接管理 HTTP 接口（查询在线连接、强制断开、帧录制）
+    let admin_api = match (access_config.admin_api.as_ref(), connection_inspection) {
+        (Some(admin_config), Some(connection_inspection)) => {
+            let mut admin_service = ConnectionAdminService::new(
+                connection_handler.clone(),
+                connection_inspection,
+                gateway_service.online_service_client.clone(),
+                gateway_id.clone(),
+            );
+            if let Some(frame_recorder) = frame_recorder {
+                admin_service = admin_service.with_frame_recorder(frame_recorder);
+            }
+            if let Some(ref resume_tickets) = resume_tickets {
+                admin_service = admin_service.with_resume_tickets(resume_tickets.clone());
+            }
+            Some(AdminApiContext {
+                address: admin_config.address.clone(),
+                state: AdminState {
+                    service: Arc::new(admin_service),
+                    token: Arc::new(admin_config.token.clone()),
+                },
+            })
+        }
+        _ => None,
+    };
+
     // 22. gRPC 地址
     let grpc_addr = format!(
         "{}:{}",
@@ -336,7 +481,182 @@ pub async fn initialize(
         push_domain_service: push_domain_service.clone(),
         gateway_id,
         region,
+        admin_api,
+    })
+}
+
+/// 构建客户端 ACK 记录器（客户端 ACK 直接写入 ACK 模块）
+async fn build_client_ack_recorder(
+    recording: &crate::config::ClientAckRecordingConfig,
+    gateway_id: &str,
+) -> Result<Arc<ClientAckRecorder>> {
+    use flare_im_core::ack::{AckModule, AckServiceConfig};
+
+    let ack_module = AckModule::new(AckServiceConfig {
+        redis_url: recording.redis_url.clone(),
+        ..AckServiceConfig::default()
     })
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to initialize ACK module: {}", e))?;
+    tracing::info!(
+        default_ack_type = recording.default_ack_type.as_str(),
+        importance = recording.importance.as_str(),
+        "Client ACK recording enabled"
+    );
+    let mut recorder = ClientAckRecorder::new(
+        Arc::new(ack_module),
+        recording.clone(),
+        gateway_id.to_string(),
+    );
+    if let Some(read_receipts) = &recording.read_receipts {
+        use crate::infrastructure::messaging::read_receipt::GrpcReadReceiptPublisher;
+        use flare_im_core::service_names::{PUSH_PROXY, get_service_name};
+
+        recorder = recorder.with_read_receipt_publisher(Arc::new(GrpcReadReceiptPublisher::new(
+            get_service_name(PUSH_PROXY),
+        )));
+        tracing::info!(
+            conversation_types = ?read_receipts.conversation_types,
+            "Read receipt fan-out enabled"
+        );
+    }
+    if let Some(session_receipts) = &recording.session_receipts {
+        use crate::infrastructure::messaging::receipts_policy::ConversationReceiptsPolicyResolver;
+        use flare_im_core::service_names::{CONVERSATION, get_service_name};
+
+        recorder = recorder.with_receipts_policy_resolver(Arc::new(
+            ConversationReceiptsPolicyResolver::new(
+                get_service_name(CONVERSATION),
+                session_receipts.default_policy,
+                std::time::Duration::from_secs(session_receipts.cache_ttl_secs),
+            ),
+        ));
+        tracing::info!(
+            default_policy = session_receipts.default_policy.as_str(),
+            cache_ttl_secs = session_receipts.cache_ttl_secs,
+            "Conversation receipts policy enabled"
+        );
+    }
+    Ok(Arc::new(recorder))
+}
+
+/// 定期将延迟探测汇总数据上报 Route 服务
+fn spawn_latency_reporter(
+    latency_probe: Arc<LatencyProbeService>,
+    message_router: Arc<crate::infrastructure::messaging::message_router::MessageRouter>,
+    gateway_id: String,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(latency_probe.config().report_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let samples = latency_probe.drain_samples();
+            if samples.is_empty() {
+                continue;
+            }
+            if let Err(e) = message_router
+                .report_region_latency(&gateway_id, latency_probe.region(), samples)
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to report region latency");
+            }
+        }
+    });
+}
+
+/// 定期刷新活跃连接的续连票据（绑定的 token 失效时停止刷新），并注销超过宽限期仍未续连的会话
+///
+/// 到期会话中未走过离线流程的暂存消息转交离线推送
+fn spawn_resume_ticket_maintenance(
+    resume_tickets: Arc<ResumeTicketService>,
+    authenticator: Arc<TokenAuthenticator>,
+    handoff_publisher: Arc<dyn ResumeHandoffPublisher>,
+    long_connection_handler: Arc<LongConnectionHandler>,
+    connection_handler: Arc<ConnectionHandler>,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RESUME_TICKET_MAINTENANCE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            for issued in resume_tickets.refresh_due(|token| authenticator.is_token_valid(token)) {
+                if let Err(e) = long_connection_handler
+                    .push_resume_ticket(&issued.connection_id, &issued.ticket, issued.expires_at)
+                    .await
+                {
+                    tracing::debug!(
+                        ?e,
+                        connection_id = %issued.connection_id,
+                        "Failed to push refreshed resume ticket"
+                    );
+                }
+            }
+            for session in resume_tickets.take_expired() {
+                if let Err(e) = connection_handler
+                    .handle_detached_expired(
+                        &session.user_id,
+                        &session.device_id,
+                        &session.conversation_id,
+                    )
+                    .await
+                {
+                    tracing::warn!(
+                        ?e,
+                        user_id = %session.user_id,
+                        "Failed to unregister expired resume session"
+                    );
+                }
+                match handoff_publisher.publish_undelivered(&session).await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::debug!(
+                        user_id = %session.user_id,
+                        device_id = %session.device_id,
+                        count,
+                        "Handed off undelivered resume messages to offline push"
+                    ),
+                    Err(e) => tracing::warn!(
+                        ?e,
+                        user_id = %session.user_id,
+                        device_id = %session.device_id,
+                        "Failed to hand off undelivered resume messages"
+                    ),
+                }
+            }
+        }
+    });
+}
+
+/// 构建登录安全检测服务（未配置策略文件时不启用）
+fn build_login_security(
+    config: &AccessGatewayConfig,
+    gateway_id: &str,
+) -> Result<Option<Arc<LoginSecurityService>>> {
+    let Some(path) = &config.login_security_config else {
+        return Ok(None);
+    };
+    let security_config = LoginSecurityConfig::from_file(path)?;
+    let mut service = LoginSecurityService::new(gateway_id.to_string(), security_config);
+    if let Some(webhook) = &config.security_webhook {
+        service = service.with_publisher(Arc::new(WebhookSecurityEventPublisher::new(
+            webhook.clone(),
+        )?));
+        tracing::info!(url = %webhook.url, "Security event webhook enabled");
+    }
+    let service = Arc::new(service);
+
+    let pruner = service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(LOGIN_SECURITY_PRUNE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            pruner.prune();
+        }
+    });
+
+    tracing::info!(path = %path, "Login anomaly detection enabled");
+    Ok(Some(service))
 }
 
 /// 构建连接查询
@@ -349,30 +669,60 @@ async fn build_connection_query(
 /// 构建认证器
 async fn build_authenticator(
     config: &AccessGatewayConfig,
-) -> Arc<dyn flare_core::server::auth::Authenticator + Send + Sync> {
+    login_security: Option<Arc<LoginSecurityService>>,
+    resume_tickets: Option<Arc<ResumeTicketService>>,
+) -> Result<Arc<TokenAuthenticator>> {
     use tracing::warn;
 
-    let mut token_service = TokenService::new(
-        config.token_secret.clone(),
-        config.token_issuer.clone(),
-        config.token_ttl_seconds,
-    );
-
-    if let Some(store_url) = &config.token_store_redis_url {
-        match RedisTokenStore::new(store_url) {
-            Ok(store) => {
-                token_service = token_service.with_store(Arc::new(store));
-            }
+    let token_store = match &config.token_store_redis_url {
+        Some(store_url) => match RedisTokenStore::new(store_url) {
+            Ok(store) => Some(Arc::new(store)),
             Err(err) => {
                 warn!(
                     ?err,
                     "Failed to initialize token store, proceeding without revocation support"
                 );
+                None
             }
+        },
+        None => None,
+    };
+
+    let issuer = config.token_issuer.clone();
+    let ttl_seconds = config.token_ttl_seconds;
+    let build_service = move |secret: String| {
+        let token_service = TokenService::new(secret, issuer.clone(), ttl_seconds);
+        match &token_store {
+            Some(store) => token_service.with_store(store.clone()),
+            None => token_service,
         }
-    }
+    };
+
+    let authenticator =
+        TokenAuthenticator::new(Arc::new(build_service(config.token_secret.clone())));
+
+    // 多密钥签发与验证（签名密钥轮换）：密钥文件加载失败时拒绝启动，不回退到单密钥；
+    // token_secret 保留为仅验证的历史密钥
+    let authenticator = match &config.token_keys_file {
+        Some(path) => {
+            let keys = SigningKeySet::from_file(path)
+                .with_context(|| format!("Failed to load token signing keys from {}", path))?;
+            let key_ring = TokenKeyRing::with_legacy_secret(keys, config.token_secret.clone());
+            spawn_key_file_reload(key_ring.clone(), path, TOKEN_KEYS_RELOAD_INTERVAL);
+            authenticator.with_key_ring(key_ring, build_service)
+        }
+        None => authenticator,
+    };
+    let authenticator = match login_security {
+        Some(login_security) => authenticator.with_login_security(login_security),
+        None => authenticator,
+    };
+    let authenticator = match resume_tickets {
+        Some(resume_tickets) => authenticator.with_resume_tickets(resume_tickets),
+        None => authenticator,
+    };
 
-    Arc::new(TokenAuthenticator::new(Arc::new(token_service)))
+    Ok(Arc::new(authenticator))
 }
 
 /// 使用 Flare 模式构建服务器
@@ -390,6 +740,7 @@ fn build_flare_server(
     authenticator: Arc<dyn flare_core::server::auth::Authenticator + Send + Sync>,
     compression_algorithm: flare_core::common::compression::CompressionAlgorithm,
     encryption_enabled: bool,
+    reuse_port: bool,
 ) -> Result<FlareServer> {
     use flare_core::common::config_types::{HeartbeatConfig, TransportProtocol};
     use flare_core::common::protocol::SerializationFormat;
@@ -418,6 +769,11 @@ fn build_flare_server(
         .with_default_format(SerializationFormat::Protobuf)
         .with_default_compression(compression_algorithm);
     
+    // 多分片监听时通过 SO_REUSEPORT 绑定同一地址
+    if reuse_port {
+        builder = builder.with_reuse_port(true);
+    }
+
     // 可选：启用加密
     if encryption_enabled {
         builder = builder.with_default_encryption(
@@ -449,7 +805,8 @@ async fn build_long_connection_server(
     authenticator: Arc<dyn flare_core::server::auth::Authenticator + Send + Sync>,
     connection_handler: Arc<LongConnectionHandler>,
     access_config: Arc<AccessGatewayConfig>,
-) -> Result<Arc<tokio::sync::Mutex<Option<FlareServer>>>> {
+    metrics: Arc<AccessGatewayMetrics>,
+) -> Result<Arc<tokio::sync::Mutex<Option<ShardedLongConnectionServer>>>> {
     use tracing::{error, info, warn};
 
     // 创建设备管理器（平台互斥策略：同一用户同一平台只能有一个设备在线）
@@ -487,8 +844,12 @@ async fn build_long_connection_server(
         "Configuration parsed, building FlareServer"
     );
 
+    let shard_count = access_config.listener_shards.max(1);
+    let reuse_port = shard_count > 1;
+
     // 尝试构建服务器（优先使用 QUIC + WebSocket）
-    let server = match build_flare_server(
+    let mut quic_enabled = true;
+    let first_shard = match build_flare_server(
         ws_addr.clone(),
         Some(quic_addr.clone()),
         connection_handler.clone(),
@@ -497,6 +858,7 @@ async fn build_long_connection_server(
         authenticator.clone(),
         compression_algorithm.clone(),
         encryption_config.enabled,
+        reuse_port,
     ) {
         Ok(server) => server,
         Err(e) => {
@@ -505,6 +867,7 @@ async fn build_long_connection_server(
             if error_msg.contains("Address already in use") 
                 || error_msg.contains("创建 QUIC 端点失败") {
                 warn!(quic_addr = %quic_addr, "QUIC port unavailable, falling back to WebSocket-only mode");
+                quic_enabled = false;
                 build_flare_server(
                     ws_addr.clone(),
                     None, // 仅 WebSocket
@@ -512,8 +875,9 @@ async fn build_long_connection_server(
                     connection_manager.clone(),
                     device_manager.clone(),
                     authenticator.clone(),
-                    compression_algorithm,
+                    compression_algorithm.clone(),
                     encryption_config.enabled,
+                    reuse_port,
                 )?
             } else {
                 error!(error = %e, "Failed to build FlareServer");
@@ -522,8 +886,26 @@ async fn build_long_connection_server(
         }
     };
 
+    // 其余分片与第一个分片使用相同的协议和共享组件
+    let mut shards = vec![first_shard];
+    for _ in 1..shard_count {
+        shards.push(build_flare_server(
+            ws_addr.clone(),
+            quic_enabled.then(|| quic_addr.clone()),
+            connection_handler.clone(),
+            connection_manager.clone(),
+            device_manager.clone(),
+            authenticator.clone(),
+            compression_algorithm.clone(),
+            encryption_config.enabled,
+            reuse_port,
+        )?);
+    }
+    let mut server = ShardedLongConnectionServer::new(shards);
+    metrics.listener_shards.set(server.len() as i64);
+
     // 设置 server handle 和 connection manager（用于消息发送和连接管理）
-    setup_server_components(&connection_handler, &connection_manager).await;
+    setup_server_components(&connection_handler, &connection_manager);
     
     // 启动服务器
     server.start().await.map_err(|e| {
@@ -531,7 +913,12 @@ async fn build_long_connection_server(
         anyhow::anyhow!("Failed to start server: {}", e)
     })?;
 
-    info!(ws_addr = %ws_addr, quic_addr = %quic_addr, "✅ Long connection server started");
+    info!(
+        ws_addr = %ws_addr,
+        quic_addr = %quic_addr,
+        listener_shards = server.len(),
+        "✅ Long connection server started"
+    );
 
     Ok(Arc::new(tokio::sync::Mutex::new(Some(server))))
 }
@@ -606,7 +993,7 @@ async fn setup_encryption_config(
 }
 
 /// 设置服务器组件（ServerHandle 和 ConnectionManager）
-async fn setup_server_components(
+fn setup_server_components(
     connection_handler: &Arc<LongConnectionHandler>,
     connection_manager: &Arc<ConnectionManager>,
 ) {
@@ -617,8 +1004,8 @@ async fn setup_server_components(
     let server_handle: Arc<dyn ServerHandle> =
         Arc::new(DefaultServerHandle::new(manager_trait.clone()));
 
-    connection_handler.set_server_handle(server_handle).await;
-    connection_handler.set_connection_manager(manager_trait).await;
+    connection_handler.set_server_handle(server_handle);
+    connection_handler.set_connection_manager(manager_trait);
     
     info!("✅ Server handle and connection manager configured");
 }
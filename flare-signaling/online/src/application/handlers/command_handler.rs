@@ -31,15 +31,25 @@ impl OnlineCommandHandler {
     }
 
     /// 处理登录命令
-    #[instrument(skip(self), fields(user_id = %command.request.user_id, device_id = %command.request.device_id))]
-    pub async fn handle_login(&self, command: LoginCommand) -> Result<LoginResponse> {
-        self.online_domain_service.login(command.request).await
+    #[instrument(skip(self, ctx), fields(user_id = %command.request.user_id, device_id = %command.request.device_id))]
+    pub async fn handle_login(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        command: LoginCommand,
+    ) -> Result<LoginResponse> {
+        self.online_domain_service.login(ctx, command.request).await
     }
 
     /// 处理登出命令
-    #[instrument(skip(self), fields(user_id = %command.request.user_id, conversation_id = %command.request.conversation_id))]
-    pub async fn handle_logout(&self, command: LogoutCommand) -> Result<LogoutResponse> {
-        self.online_domain_service.logout(command.request).await
+    #[instrument(skip(self, ctx), fields(user_id = %command.request.user_id, conversation_id = %command.request.conversation_id))]
+    pub async fn handle_logout(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        command: LogoutCommand,
+    ) -> Result<LogoutResponse> {
+        self.online_domain_service
+            .logout(ctx, command.request)
+            .await
     }
 
     /// 处理心跳命令
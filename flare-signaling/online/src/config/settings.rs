@@ -7,6 +7,10 @@ pub struct OnlineConfig {
     pub redis_url: String,
     pub redis_ttl_seconds: u64,
     pub presence_prefix: String,
+    /// Hook 配置（未配置时使用默认的 config/hooks.toml、config/hooks.d）
+    pub hook_config: Option<String>,
+    /// Hook 配置目录
+    pub hook_config_dir: Option<String>,
 }
 
 impl OnlineConfig {
@@ -38,10 +42,20 @@ impl OnlineConfig {
             .or_else(|| service_config.presence_prefix.clone())
             .unwrap_or_else(|| "presence:user".to_string());
 
+        let hook_config = env::var("SIGNALING_ONLINE_HOOKS_CONFIG")
+            .ok()
+            .or_else(|| service_config.hook_config.clone());
+
+        let hook_config_dir = env::var("SIGNALING_ONLINE_HOOKS_CONFIG_DIR")
+            .ok()
+            .or_else(|| service_config.hook_config_dir.clone());
+
         Ok(Self {
             redis_url,
             redis_ttl_seconds,
             presence_prefix,
+            hook_config,
+            hook_config_dir,
         })
     }
 }
@@ -2,12 +2,15 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use anyhow::Result;
+use flare_im_core::hooks::{HookDispatcher, PresenceChangedEvent};
 use flare_proto::signaling::online::{
     DeviceConflictStrategy, GetOnlineStatusResponse, HeartbeatResponse, LoginRequest,
     LoginResponse, LogoutRequest, LogoutResponse, OnlineStatus,
 };
+use flare_server_core::context::Context;
 use prost_types::Timestamp;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
@@ -32,6 +35,8 @@ pub struct OnlineStatusService {
     repository: Arc<dyn ConversationRepository + Send + Sync>,
     sessions: Arc<RwLock<HashMap<String, InMemoryConnection>>>,
     gateway_id: String,
+    /// 生命周期 Hook（可选，上线/下线时执行 PresenceChanged Hook）
+    hooks: Option<Arc<HookDispatcher>>,
 }
 
 impl OnlineStatusService {
@@ -40,10 +45,48 @@ impl OnlineStatusService {
             repository,
             sessions: Arc::new(RwLock::new(HashMap::new())),
             gateway_id,
+            hooks: None,
         }
     }
 
-    pub async fn login(&self, request: LoginRequest) -> Result<LoginResponse> {
+    /// 设置 Hook 调度器
+    pub fn with_hooks(mut self, hooks: Arc<HookDispatcher>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// 后台执行 PresenceChanged Hook，失败只记录日志，不影响登录/登出
+    fn notify_presence_changed(&self, ctx: &Context, session: &Connection, online: bool) {
+        let Some(hooks) = self.hooks.clone() else {
+            return;
+        };
+        let ctx = ctx.clone();
+        let event = PresenceChangedEvent {
+            user_id: session.user_id().as_str().to_string(),
+            device_id: session.device_id().as_str().to_string(),
+            device_platform: session.device_platform().to_string(),
+            gateway_id: Some(session.gateway_id().to_string()).filter(|id| !id.is_empty()),
+            online,
+            changed_at: SystemTime::now(),
+            metadata: HashMap::from([(
+                "connection_id".to_string(),
+                session.id().as_str().to_string(),
+            )]),
+        };
+        tokio::spawn(async move {
+            if let Err(err) = hooks.presence_changed(&ctx, &event).await {
+                warn!(
+                    user_id = %event.user_id,
+                    device_id = %event.device_id,
+                    online = event.online,
+                    error = %err,
+                    "PresenceChanged hook failed"
+                );
+            }
+        });
+    }
+
+    pub async fn login(&self, ctx: &Context, request: LoginRequest) -> Result<LoginResponse> {
         let user_id = &request.user_id;
         let device_id = &request.device_id;
         let device_platform = request.device_platform.as_str();
@@ -153,6 +196,7 @@ impl OnlineStatusService {
         }
 
         self.repository.save_connection(&session).await?;
+        self.notify_presence_changed(ctx, &session, true);
 
         info!(
             user_id = %user_id,
@@ -172,22 +216,38 @@ impl OnlineStatusService {
         })
     }
 
-    pub async fn logout(&self, request: LogoutRequest) -> Result<LogoutResponse> {
+    pub async fn logout(&self, ctx: &Context, request: LogoutRequest) -> Result<LogoutResponse> {
         let user_id = &request.user_id;
         let conversation_id = &request.conversation_id;
 
         // 从内存中移除会话
-        {
+        let removed = {
             let mut map = self.sessions.write().await;
-            map.remove(conversation_id);
-        }
+            map.remove(conversation_id)
+        };
 
         // 从Redis中移除会话
         let user_vo = UserId::new(user_id.clone()).unwrap();
         let session_vo = ConnectionId::from_string(conversation_id.clone()).unwrap();
+
+        // 会话可能由其他实例登录，配置了 Hook 时从仓储中查找设备信息
+        let session = match removed {
+            Some(entry) => Some(entry.session),
+            None if self.hooks.is_some() => self
+                .repository
+                .get_user_connections(&user_vo)
+                .await?
+                .into_iter()
+                .find(|session| session.id().as_str() == conversation_id.as_str()),
+            None => None,
+        };
+
         self.repository
             .remove_connection(&session_vo, &user_vo)
             .await?;
+        if let Some(session) = &session {
+            self.notify_presence_changed(ctx, session, false);
+        }
 
         info!(
             user_id = %user_id,
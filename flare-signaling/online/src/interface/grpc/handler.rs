@@ -47,10 +47,13 @@ impl OnlineHandler {
         &self,
         request: Request<LoginRequest>,
     ) -> std::result::Result<Response<LoginResponse>, Status> {
+        // 网关未透传上下文时使用空上下文（仅影响 Hook 的租户匹配）
+        let ctx = flare_im_core::utils::context::extract_context_opt(&request)
+            .unwrap_or_else(flare_server_core::context::Context::root);
         let command = LoginCommand {
             request: request.into_inner(),
         };
-        match self.command_handler.handle_login(command).await {
+        match self.command_handler.handle_login(&ctx, command).await {
             Ok(response) => Ok(Response::new(response)),
             Err(err) => {
                 error!(?err, "login failed");
@@ -63,10 +66,13 @@ impl OnlineHandler {
         &self,
         request: Request<LogoutRequest>,
     ) -> std::result::Result<Response<LogoutResponse>, Status> {
+        // 网关未透传上下文时使用空上下文（仅影响 Hook 的租户匹配）
+        let ctx = flare_im_core::utils::context::extract_context_opt(&request)
+            .unwrap_or_else(flare_server_core::context::Context::root);
         let command = LogoutCommand {
             request: request.into_inner(),
         };
-        match self.command_handler.handle_logout(command).await {
+        match self.command_handler.handle_logout(&ctx, command).await {
             Ok(response) => Ok(Response::new(response)),
             Err(err) => {
                 error!(?err, "logout failed");
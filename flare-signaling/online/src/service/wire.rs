@@ -5,6 +5,7 @@
 use std::sync::Arc;
 
 use anyhow::{Context as AnyhowContext, Result};
+use flare_im_core::hooks::{HookConfigLoader, HookDispatcher};
 use redis::Client;
 
 use crate::application::handlers::{OnlineCommandHandler, OnlineQueryHandler};
@@ -73,10 +74,20 @@ pub async fn initialize(
         "gateway-{}",
         uuid::Uuid::new_v4().to_string()[..8].to_string()
     );
-    let online_domain_service = Arc::new(OnlineStatusDomainService::new(
-        conversation_repository.clone(),
-        gateway_id,
-    ));
+    let mut hook_loader = HookConfigLoader::new();
+    if let Some(path) = &online_config.hook_config {
+        hook_loader = hook_loader.add_candidate(path.clone());
+    }
+    if let Some(dir) = &online_config.hook_config_dir {
+        hook_loader = hook_loader.add_candidate(dir.clone());
+    }
+    let hooks = HookDispatcher::load(&hook_loader)
+        .await
+        .map_err(|err| anyhow::anyhow!("Failed to load hooks: {}", err))?;
+    let online_domain_service = Arc::new(
+        OnlineStatusDomainService::new(conversation_repository.clone(), gateway_id)
+            .with_hooks(Arc::new(hooks)),
+    );
 
     let subscription_domain_service = Arc::new(SubscriptionDomainService::new(
         subscription_repository,
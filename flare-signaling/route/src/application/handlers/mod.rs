@@ -4,9 +4,11 @@ pub mod command_handler;
 pub mod query_handler;
 pub mod device_route_handler;
 pub mod message_routing_handler;
+pub mod region_latency_handler;
 
 pub use command_handler::RouteCommandHandler;
 pub use query_handler::RouteQueryHandler;
 pub use device_route_handler::DeviceRouteHandler;
 pub use message_routing_handler::MessageRoutingHandler;
+pub use region_latency_handler::RegionLatencyHandler;
 
@@ -0,0 +1,67 @@
+//! 地区延迟处理器
+//!
+//! 负责接入网关延迟上报与接入地区推荐的业务流程编排
+
+use std::sync::Arc;
+use flare_proto::signaling::router::RegionLatencySample;
+use tracing::{debug, info};
+
+use crate::domain::value_objects::RegionLatencyTable;
+
+/// 地区延迟处理器
+///
+/// 职责：
+/// - 汇总网关上报的按 client_geo 聚合的延迟数据
+/// - 为持续高延迟的客户端推荐更优接入地区
+pub struct RegionLatencyHandler {
+    latency_table: Arc<RegionLatencyTable>,
+}
+
+impl RegionLatencyHandler {
+    pub fn new(latency_table: Arc<RegionLatencyTable>) -> Self {
+        Self { latency_table }
+    }
+
+    /// 记录网关上报的延迟汇总
+    pub fn report(&self, gateway_id: &str, region: &str, samples: Vec<RegionLatencySample>) {
+        debug!(
+            gateway_id = %gateway_id,
+            region = %region,
+            sample_groups = samples.len(),
+            "Received region latency report"
+        );
+        for sample in samples {
+            self.latency_table.record(
+                region,
+                &sample.client_geo,
+                sample.sample_count,
+                sample.avg_rtt_ms,
+            );
+        }
+    }
+
+    /// 推荐接入地区，返回 `(region, expected_rtt_ms)`
+    pub fn recommend(
+        &self,
+        user_id: &str,
+        client_geo: &str,
+        current_region: &str,
+        rtt_ms: u64,
+    ) -> Option<(String, f64)> {
+        let recommendation = self
+            .latency_table
+            .recommend(current_region, client_geo, rtt_ms);
+        if let Some((region, expected_rtt_ms)) = &recommendation {
+            info!(
+                user_id = %user_id,
+                client_geo = %client_geo,
+                current_region = %current_region,
+                rtt_ms,
+                recommended_region = %region,
+                expected_rtt_ms = *expected_rtt_ms,
+                "Recommending access region"
+            );
+        }
+        recommendation
+    }
+}
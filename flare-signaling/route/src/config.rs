@@ -17,6 +17,10 @@ pub struct RouteConfig {
     pub group_fanout_max: u64,
     /// 是否开启流控（默认关闭）
     pub flow_control_enabled: bool,
+    /// 地区推荐所需的最小样本数（默认 50）
+    pub region_min_samples: u64,
+    /// 推荐地区延迟需低于当前 RTT 的比例（默认 0.7）
+    pub region_improvement_ratio: f64,
 }
 
 impl RouteConfig {
@@ -68,6 +72,14 @@ impl RouteConfig {
                 .ok()
                 .map(|v| v.to_lowercase() == "true")
                 .unwrap_or(false),
+            region_min_samples: env::var("ROUTER_REGION_MIN_SAMPLES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(50),
+            region_improvement_ratio: env::var("ROUTER_REGION_IMPROVEMENT_RATIO")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.7),
         })
     }
 }
@@ -1,16 +1,18 @@
 //! 值对象模块
 //!
-//! 包含路由相关的值对象：分片管理器、负载均衡器、流控器、跨机房选择器、Trace注入器
+//! 包含路由相关的值对象：分片管理器、负载均衡器、流控器、跨机房选择器、Trace注入器、地区延迟表
 
 pub mod shard_manager;
 pub mod load_balancer;
 pub mod flow_controller;
 pub mod az_selector;
 pub mod trace_injector;
+pub mod region_latency;
 
 pub use shard_manager::ShardManager;
 pub use load_balancer::{ServiceLoadBalancer, LoadBalancingStrategy};
 pub use flow_controller::{FlowController, MonitoringClient};
 pub use az_selector::{AzSelector, ConfigClient};
 pub use trace_injector::TraceInjector;
+pub use region_latency::RegionLatencyTable;
 
@@ -0,0 +1,104 @@
+//! 地区延迟统计值对象
+//!
+//! 汇总各接入网关上报的客户端延迟探测数据（按 client_geo × region），
+//! 为持续高延迟的客户端推荐更优的接入地区
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// 单个 (client_geo, region) 的延迟统计
+#[derive(Debug, Clone)]
+struct RegionLatencyStat {
+    sample_count: u64,
+    avg_rtt_ms: f64,
+    updated_at: Instant,
+}
+
+/// 地区延迟表
+///
+/// - 统计采用样本数加权的滑动平均，历史样本数封顶，保证能跟上网络变化
+/// - 超过 `stale_after` 未更新的统计不参与推荐
+pub struct RegionLatencyTable {
+    /// 参与推荐的最小样本数
+    min_samples: u64,
+    /// 推荐地区的延迟需低于当前 RTT 的比例（例如 0.7 表示至少改善 30%）
+    improvement_ratio: f64,
+    /// 统计过期时间
+    stale_after: Duration,
+    /// 历史样本权重上限
+    max_weight: u64,
+    /// client_geo -> region -> 统计
+    stats: RwLock<HashMap<String, HashMap<String, RegionLatencyStat>>>,
+}
+
+impl RegionLatencyTable {
+    pub fn new(min_samples: u64, improvement_ratio: f64) -> Self {
+        Self {
+            min_samples: min_samples.max(1),
+            improvement_ratio: improvement_ratio.clamp(0.0, 1.0),
+            stale_after: Duration::from_secs(600),
+            max_weight: 10_000,
+            stats: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 记录网关上报的延迟汇总
+    pub fn record(&self, region: &str, client_geo: &str, sample_count: u64, avg_rtt_ms: f64) {
+        if sample_count == 0 || !avg_rtt_ms.is_finite() || avg_rtt_ms < 0.0 {
+            return;
+        }
+
+        let mut stats = self.stats.write().unwrap_or_else(|e| e.into_inner());
+        let entry = stats
+            .entry(client_geo.to_string())
+            .or_default()
+            .entry(region.to_string())
+            .or_insert_with(|| RegionLatencyStat {
+                sample_count: 0,
+                avg_rtt_ms: 0.0,
+                updated_at: Instant::now(),
+            });
+
+        // 过期统计直接重置，避免旧数据拖累
+        let history = if entry.updated_at.elapsed() > self.stale_after {
+            0
+        } else {
+            entry.sample_count.min(self.max_weight)
+        };
+        let total = history + sample_count;
+        entry.avg_rtt_ms =
+            (entry.avg_rtt_ms * history as f64 + avg_rtt_ms * sample_count as f64) / total as f64;
+        entry.sample_count = total;
+        entry.updated_at = Instant::now();
+    }
+
+    /// 为客户端推荐接入地区
+    ///
+    /// 返回 `(region, expected_rtt_ms)`；没有明显更优的地区时返回 `None`
+    pub fn recommend(
+        &self,
+        current_region: &str,
+        client_geo: &str,
+        rtt_ms: u64,
+    ) -> Option<(String, f64)> {
+        let stats = self.stats.read().unwrap_or_else(|e| e.into_inner());
+        let regions = stats.get(client_geo)?;
+        let threshold = rtt_ms as f64 * self.improvement_ratio;
+
+        regions
+            .iter()
+            .filter(|(region, stat)| {
+                region.as_str() != current_region
+                    && stat.sample_count >= self.min_samples
+                    && stat.updated_at.elapsed() <= self.stale_after
+                    && stat.avg_rtt_ms < threshold
+            })
+            .min_by(|(_, a), (_, b)| {
+                a.avg_rtt_ms
+                    .partial_cmp(&b.avg_rtt_ms)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(region, stat)| (region.clone(), stat.avg_rtt_ms))
+    }
+}
@@ -8,7 +8,7 @@ use tonic::{Request, Response, Status};
 use tracing::debug;
 
 use crate::application::handlers::{
-    DeviceRouteHandler, MessageRoutingHandler,
+    DeviceRouteHandler, MessageRoutingHandler, RegionLatencyHandler,
 };
 use crate::util;
 
@@ -18,6 +18,7 @@ use crate::util;
 /// - 根据推送策略选择最优设备
 /// - 提供设备路由查询能力
 /// - 路由消息到业务系统（根据 SVID）
+/// - 汇总网关延迟上报，推荐接入地区
 /// - 无状态服务，所有数据实时从 Online 服务查询
 ///
 /// # DDD + CQRS 架构
@@ -26,16 +27,19 @@ use crate::util;
 pub struct RouteHandler {
     device_route_handler: Arc<DeviceRouteHandler>,
     message_routing_handler: Arc<MessageRoutingHandler>,
+    region_latency_handler: Arc<RegionLatencyHandler>,
 }
 
 impl RouteHandler {
     pub fn new(
         device_route_handler: Arc<DeviceRouteHandler>,
         message_routing_handler: Arc<MessageRoutingHandler>,
+        region_latency_handler: Arc<RegionLatencyHandler>,
     ) -> Self {
         Self {
             device_route_handler,
             message_routing_handler,
+            region_latency_handler,
         }
     }
 
@@ -194,4 +198,42 @@ impl RouterService for RouteHandler {
             },
         }))
     }
+
+    async fn report_region_latency(
+        &self,
+        request: Request<ReportRegionLatencyRequest>,
+    ) -> std::result::Result<Response<ReportRegionLatencyResponse>, Status> {
+        let req = request.into_inner();
+        if req.region.is_empty() {
+            return Ok(Response::new(ReportRegionLatencyResponse {
+                status: util::rpc_status_error(ErrorCode::InvalidParameter, "region is required"),
+            }));
+        }
+
+        self.region_latency_handler
+            .report(&req.gateway_id, &req.region, req.samples);
+
+        Ok(Response::new(ReportRegionLatencyResponse {
+            status: util::rpc_status_ok(),
+        }))
+    }
+
+    async fn recommend_region(
+        &self,
+        request: Request<RecommendRegionRequest>,
+    ) -> std::result::Result<Response<RecommendRegionResponse>, Status> {
+        let req = request.into_inner();
+
+        let (recommended_region, expected_rtt_ms) = self
+            .region_latency_handler
+            .recommend(&req.user_id, &req.client_geo, &req.current_region, req.rtt_ms)
+            .map(|(region, rtt)| (region, rtt.round() as u64))
+            .unwrap_or_default();
+
+        Ok(Response::new(RecommendRegionResponse {
+            recommended_region,
+            expected_rtt_ms,
+            status: util::rpc_status_ok(),
+        }))
+    }
 }
@@ -9,8 +9,9 @@ use anyhow::{Context as AnyhowContext, Result};
 use crate::config::RouteConfig;
 use crate::infrastructure::{OnlineServiceClient, forwarder::MessageForwarder};
 use crate::application::handlers::{
-    DeviceRouteHandler, MessageRoutingHandler,
+    DeviceRouteHandler, MessageRoutingHandler, RegionLatencyHandler,
 };
+use crate::domain::value_objects::RegionLatencyTable;
 use crate::interface::grpc::handler::RouteHandler;
 
 /// 应用上下文 - 包含所有已初始化的服务
@@ -68,9 +69,19 @@ pub async fn initialize(
     let message_routing_handler = Arc::new(
         MessageRoutingHandler::new(message_forwarder)
     );
+    let region_latency_handler = Arc::new(RegionLatencyHandler::new(Arc::new(
+        RegionLatencyTable::new(
+            route_config.region_min_samples,
+            route_config.region_improvement_ratio,
+        ),
+    )));
 
     // 7. 构建 gRPC Handler（通过 Application 层）
-    let handler = RouteHandler::new(device_route_handler, message_routing_handler);
+    let handler = RouteHandler::new(
+        device_route_handler,
+        message_routing_handler,
+        region_latency_handler,
+    );
 
     Ok(ApplicationContext { handler })
 }
@@ -55,3 +55,18 @@ pub struct ExportMessagesCommand {
     pub end_time: Option<i64>,
     pub limit: Option<i32>,
 }
+
+/// 用户数据删除命令（被遗忘权，任务由后台执行）
+#[derive(Debug, Clone)]
+pub struct PurgeUserDataCommand {
+    pub user_id: String,
+    pub mode: crate::domain::model::UserPurgeMode,
+    /// 发起人（管理员ID或工单号，写入审计日志）
+    pub requested_by: Option<String>,
+}
+
+/// 重试失败的用户数据删除任务命令
+#[derive(Debug, Clone)]
+pub struct RetryUserPurgeJobCommand {
+    pub job_id: String,
+}
@@ -7,18 +7,32 @@ use tracing::instrument;
 
 use crate::application::commands::{
     ClearConversationCommand, DeleteMessageCommand, DeleteMessageForUserCommand, ExportMessagesCommand,
-    MarkReadCommand, RecallMessageCommand, SetMessageAttributesCommand,
+    MarkReadCommand, PurgeUserDataCommand, RecallMessageCommand, RetryUserPurgeJobCommand,
+    SetMessageAttributesCommand,
+};
+use crate::domain::model::UserPurgeJob;
+use crate::domain::service::{
+    MessageStorageDomainService, PiiPseudonymizer, UserPurgeDomainService,
 };
-use crate::domain::service::MessageStorageDomainService;
 
 /// 消息存储命令处理器（编排层）
 pub struct MessageStorageCommandHandler {
     domain_service: Arc<MessageStorageDomainService>,
+    pii_pseudonymizer: Option<Arc<PiiPseudonymizer>>,
 }
 
 impl MessageStorageCommandHandler {
     pub fn new(domain_service: Arc<MessageStorageDomainService>) -> Self {
-        Self { domain_service }
+        Self {
+            domain_service,
+            pii_pseudonymizer: None,
+        }
+    }
+
+    /// 导出前按租户策略假名化 PII 字段
+    pub fn with_pii_pseudonymizer(mut self, pseudonymizer: Option<Arc<PiiPseudonymizer>>) -> Self {
+        self.pii_pseudonymizer = pseudonymizer;
+        self
     }
 
     /// 删除消息
@@ -135,6 +149,7 @@ impl MessageStorageCommandHandler {
 
         // 克隆必要的依赖项
         let domain_service = self.domain_service.clone();
+        let pii_pseudonymizer = self.pii_pseudonymizer.clone();
         let command_clone = command.clone();
         let export_task_id_clone = export_task_id.clone();
 
@@ -142,8 +157,13 @@ impl MessageStorageCommandHandler {
         tokio::spawn(async move {
             // 执行导出逻辑
             if let Err(e) =
-                Self::execute_export_task(domain_service, command_clone, &export_task_id_clone)
-                    .await
+                Self::execute_export_task(
+                    domain_service,
+                    pii_pseudonymizer,
+                    command_clone,
+                    &export_task_id_clone,
+                )
+                .await
             {
                 tracing::error!(
                     task_id = %export_task_id_clone,
@@ -164,6 +184,7 @@ impl MessageStorageCommandHandler {
     /// 执行导出任务的具体逻辑
     async fn execute_export_task(
         domain_service: Arc<MessageStorageDomainService>,
+        pii_pseudonymizer: Option<Arc<PiiPseudonymizer>>,
         command: ExportMessagesCommand,
         task_id: &str,
     ) -> Result<()> {
@@ -174,9 +195,10 @@ impl MessageStorageCommandHandler {
         );
 
         // 查询消息
-        let messages = domain_service
+        let mut messages = domain_service
             .query_messages(
                 &command.conversation_id,
+                None,
                 command.start_time.unwrap_or(0),
                 command.end_time.unwrap_or(0),
                 command.limit.unwrap_or(100),
@@ -184,6 +206,14 @@ impl MessageStorageCommandHandler {
             )
             .await?;
 
+        // 按租户策略假名化 PII 字段（失败时中止导出，避免输出明文）
+        let mut pseudonymized_fields = 0;
+        if let Some(pseudonymizer) = &pii_pseudonymizer {
+            for message in &mut messages.messages {
+                pseudonymized_fields += pseudonymizer.pseudonymize_message(message).await?;
+            }
+        }
+
         // 这里应该实现实际的导出逻辑，比如：
         // 1. 将消息序列化为某种格式（CSV、JSON等）
         // 2. 上传到对象存储（S3、MinIO等）
@@ -194,6 +224,7 @@ impl MessageStorageCommandHandler {
             task_id = %task_id,
             conversation_id = %command.conversation_id,
             message_count = messages.messages.len(),
+            pseudonymized_fields,
             "Exported messages"
         );
 
@@ -203,3 +234,53 @@ impl MessageStorageCommandHandler {
         Ok(())
     }
 }
+
+/// 用户数据删除命令处理器（管理操作，删除任务由后台执行）
+pub struct UserPurgeCommandHandler {
+    domain_service: Arc<UserPurgeDomainService>,
+}
+
+impl UserPurgeCommandHandler {
+    pub fn new(domain_service: Arc<UserPurgeDomainService>) -> Self {
+        Self { domain_service }
+    }
+
+    /// 处理用户数据删除命令：创建删除任务，由后台任务执行
+    #[instrument(skip(self, ctx), fields(user_id = %command.user_id, mode = command.mode.as_str()))]
+    pub async fn handle_purge_user_data(
+        &self,
+        ctx: &flare_server_core::context::Context,
+        command: PurgeUserDataCommand,
+    ) -> Result<UserPurgeJob> {
+        let tenant_id = ctx.tenant_id().unwrap_or("0");
+        self.domain_service
+            .submit(tenant_id, &command.user_id, command.mode, command.requested_by)
+            .await
+    }
+
+    /// 处理重试用户数据删除任务命令
+    #[instrument(skip(self), fields(job_id = %command.job_id))]
+    pub async fn handle_retry_user_purge_job(&self, command: RetryUserPurgeJobCommand) -> Result<()> {
+        self.domain_service.retry(&command.job_id).await?;
+
+        tracing::info!(job_id = %command.job_id, "User purge job scheduled for retry");
+        Ok(())
+    }
+
+    /// 查询删除任务的进度与完成报告
+    pub async fn get_job(&self, job_id: &str) -> Result<Option<UserPurgeJob>> {
+        self.domain_service.get_job(job_id).await
+    }
+
+    /// 后台轮询执行待执行或中断的删除任务
+    pub async fn run_pending_jobs(self: Arc<Self>, poll_interval: std::time::Duration) {
+        let mut interval = tokio::time::interval(poll_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.domain_service.resume_pending().await {
+                tracing::warn!(error = %err, "Failed to resume user purge jobs");
+            }
+        }
+    }
+}
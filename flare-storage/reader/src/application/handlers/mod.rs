@@ -3,5 +3,5 @@
 pub mod command_handler;
 pub mod query_handler;
 
-pub use command_handler::MessageStorageCommandHandler;
-pub use query_handler::MessageStorageQueryHandler;
+pub use command_handler::{MessageStorageCommandHandler, UserPurgeCommandHandler};
+pub use query_handler::{MessageCountQueryHandler, MessageStorageQueryHandler};
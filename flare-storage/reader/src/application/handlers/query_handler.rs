@@ -3,19 +3,23 @@
 //! 在 CQRS 架构中，查询侧通常直接调用基础设施层（仓储实现），
 //! 因为查询是只读操作，不涉及业务逻辑，不需要经过领域层。
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
 use flare_im_core::utils::extract_seq_from_message;
 use flare_proto::common::Message;
+use std::collections::HashSet;
 use std::sync::Arc;
 use tracing::instrument;
 
 use crate::application::queries::{
-    GetMessageQuery, ListMessageTagsQuery, QueryMessagesBySeqQuery, QueryMessagesQuery,
-    SearchMessagesQuery,
+    GetConversationMessageStatsQuery, GetLastMessagesQuery, GetMessageQuery, ListMessageTagsQuery,
+    QueryMessagesBySeqQuery, QueryMessagesQuery, SearchMessagesQuery,
 };
+use crate::domain::model::{ConversationMessageStats, HistoryCursor, HistoryQueryMode};
 use crate::domain::repository::MessageStorage;
-use crate::domain::service::{MessageStorageDomainService, QueryMessagesResult};
+use crate::domain::service::{
+    MessageCountDomainService, MessageStorageDomainService, QueryMessagesResult,
+};
 
 /// 消息存储查询处理器（查询侧）
 ///
@@ -75,6 +79,36 @@ impl MessageStorageQueryHandler {
         &self,
         query: QueryMessagesQuery,
     ) -> Result<QueryMessagesResult> {
+        // 历史游标（最新 N 条 / 跳转到日期的后续翻页）与对应模式由领域服务按 seq 处理
+        let history_cursor = query.cursor.as_deref().and_then(HistoryCursor::decode);
+        if history_cursor.is_some() || query.mode != HistoryQueryMode::Range {
+            let domain_service = self
+                .domain_service
+                .as_ref()
+                .ok_or_else(|| anyhow!("history query mode requires domain service"))?;
+            if history_cursor.is_none() && query.mode == HistoryQueryMode::JumpToDate {
+                let at = DateTime::from_timestamp(query.start_time, 0)
+                    .filter(|_| query.start_time > 0)
+                    .ok_or_else(|| anyhow!("start_time is required for jump_to_date"))?;
+                return domain_service
+                    .query_messages_around(
+                        &query.conversation_id,
+                        query.viewer.as_ref(),
+                        at,
+                        query.limit,
+                    )
+                    .await;
+            }
+            return domain_service
+                .query_latest_messages(
+                    &query.conversation_id,
+                    query.viewer.as_ref(),
+                    query.limit,
+                    history_cursor,
+                )
+                .await;
+        }
+
         // 如果有领域服务，使用领域服务处理分页逻辑
         if let Some(domain_service) = &self.domain_service {
             let start_time = if query.start_time == 0 {
@@ -93,6 +127,7 @@ impl MessageStorageQueryHandler {
             domain_service
                 .query_messages(
                     &query.conversation_id,
+                    query.viewer.as_ref(),
                     start_time,
                     end_time,
                     query.limit,
@@ -140,8 +175,10 @@ impl MessageStorageQueryHandler {
             Ok(QueryMessagesResult {
                 messages,
                 next_cursor,
+                prev_cursor: String::new(),
                 has_more,
                 total_size: message_count as i64,
+                total_size_approximate: false,
             })
         }
     }
@@ -152,6 +189,22 @@ impl MessageStorageQueryHandler {
         self.storage.get_message(&query.message_id).await
     }
 
+    /// 批量获取会话最后一条消息
+    ///
+    /// 按请求顺序返回（去重），没有消息的会话不出现在结果中
+    #[instrument(skip(self), fields(count = query.conversation_ids.len()))]
+    pub async fn handle_get_last_messages(
+        &self,
+        query: GetLastMessagesQuery,
+    ) -> Result<Vec<Message>> {
+        let conversation_ids = distinct_conversation_ids(query.conversation_ids);
+        let mut last_messages = self.storage.get_last_messages(&conversation_ids).await?;
+        Ok(conversation_ids
+            .iter()
+            .filter_map(|id| last_messages.remove(id))
+            .collect())
+    }
+
     /// 获取消息的时间戳
     #[instrument(skip(self), fields(message_id = %message_id))]
     pub async fn handle_get_message_timestamp(
@@ -201,7 +254,7 @@ impl MessageStorageQueryHandler {
             domain_service
                 .query_messages_by_seq(
                     &query.conversation_id,
-                    query.user_id.as_deref(),
+                    query.viewer.as_ref(),
                     query.after_seq,
                     query.before_seq,
                     query.limit,
@@ -213,7 +266,7 @@ impl MessageStorageQueryHandler {
                 .storage
                 .query_messages_by_seq(
                     &query.conversation_id,
-                    query.user_id.as_deref(),
+                    query.viewer.as_ref().map(|viewer| viewer.user_id.as_str()),
                     query.after_seq,
                     query.before_seq,
                     query.limit,
@@ -224,8 +277,10 @@ impl MessageStorageQueryHandler {
             QueryMessagesResult {
                 messages,
                 next_cursor: String::new(),
+                prev_cursor: String::new(),
                 has_more: false,
                 total_size: 0,
+                total_size_approximate: false,
             }
         };
 
@@ -238,3 +293,66 @@ impl MessageStorageQueryHandler {
         Ok((messages.messages, last_seq))
     }
 }
+
+/// 会话消息计数查询处理器（超大会话的近似计数，需要 PostgreSQL）
+pub struct MessageCountQueryHandler {
+    domain_service: Arc<MessageCountDomainService>,
+}
+
+impl MessageCountQueryHandler {
+    pub fn new(domain_service: Arc<MessageCountDomainService>) -> Self {
+        Self { domain_service }
+    }
+
+    /// 查询会话消息统计（消息总数、未读数与未读角标）
+    #[instrument(skip(self), fields(conversation_id = %query.conversation_id))]
+    pub async fn handle_get_conversation_message_stats(
+        &self,
+        query: GetConversationMessageStatsQuery,
+    ) -> Result<ConversationMessageStats> {
+        if query.conversation_id.is_empty() {
+            return Err(anyhow!("conversation_id is required"));
+        }
+        self.domain_service
+            .conversation_stats(&query.conversation_id, query.last_read_seq.max(0))
+            .await
+    }
+
+    /// 后台定期校准超大会话的计数快照
+    pub async fn run_reconciliation(self: Arc<Self>, poll_interval: std::time::Duration) {
+        let mut interval = tokio::time::interval(poll_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.domain_service.reconcile_stale().await {
+                tracing::warn!(error = %err, "Failed to reconcile message counts");
+            }
+        }
+    }
+}
+
+/// 去掉空值与重复的会话 ID，保留首次出现的顺序
+fn distinct_conversation_ids(conversation_ids: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    conversation_ids
+        .into_iter()
+        .filter(|id| !id.is_empty() && seen.insert(id.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distinct_conversation_ids_keeps_request_order() {
+        let ids = ["conv-2", "", "conv-1", "conv-2", "conv-3", "conv-1"]
+            .map(String::from)
+            .to_vec();
+
+        assert_eq!(
+            distinct_conversation_ids(ids),
+            vec!["conv-2", "conv-1", "conv-3"]
+        );
+    }
+}
@@ -19,6 +19,8 @@ mod tests {
             end_time: 0,
             limit: 10,
             cursor: None,
+            viewer: None,
+            mode: Default::default(),
         };
         
         // 执行查询
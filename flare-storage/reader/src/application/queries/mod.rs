@@ -1,5 +1,10 @@
 //! 查询结构体定义（Query DTO）
 
+use crate::domain::model::{HistoryQueryMode, HistoryViewer};
+
+/// 请求上下文中指定历史查询模式的属性键（`range` / `latest` / `jump_to_date`，缺省为 `range`）
+pub const HISTORY_MODE_ATTRIBUTE: &str = "history_mode";
+
 /// 查询消息列表
 #[derive(Debug, Clone)]
 pub struct QueryMessagesQuery {
@@ -8,6 +13,10 @@ pub struct QueryMessagesQuery {
     pub end_time: i64,
     pub limit: i32,
     pub cursor: Option<String>,
+    /// 查询用户（可选，用于裁剪新成员不可见的历史消息）
+    pub viewer: Option<HistoryViewer>,
+    /// 查询模式（`JumpToDate` 以 `start_time` 为跳转时间；携带历史游标时按游标方向翻页）
+    pub mode: HistoryQueryMode,
 }
 
 /// 获取单条消息
@@ -16,6 +25,15 @@ pub struct GetMessageQuery {
     pub message_id: String,
 }
 
+/// 单次批量获取最后一条消息的会话数上限
+pub const MAX_LAST_MESSAGES_BATCH: usize = 500;
+
+/// 批量获取会话最后一条消息
+#[derive(Debug, Clone)]
+pub struct GetLastMessagesQuery {
+    pub conversation_ids: Vec<String>,
+}
+
 /// 搜索消息
 #[derive(Debug, Clone)]
 pub struct SearchMessagesQuery {
@@ -38,5 +56,14 @@ pub struct QueryMessagesBySeqQuery {
     pub after_seq: i64,
     pub before_seq: Option<i64>,
     pub limit: i32,
-    pub user_id: Option<String>,
+    /// 查询用户（可选，用于过滤已删除消息及历史可见边界之前的消息）
+    pub viewer: Option<HistoryViewer>,
+}
+
+/// 查询会话消息统计（消息总数与未读数，超大会话为估算值）
+#[derive(Debug, Clone)]
+pub struct GetConversationMessageStatsQuery {
+    pub conversation_id: String,
+    /// 用户最后已读消息的 seq（未读数为 seq 大于该值的消息数）
+    pub last_read_seq: i64,
 }
@@ -18,6 +18,26 @@ pub struct StorageReaderConfig {
     pub redis_cache_ttl_seconds: u64,
     pub redis_message_cache_ttl_seconds: u64,
     pub redis_session_cache_ttl_seconds: u64,
+    /// 会话最后一条消息视图回填 TTL（与 Writer 热缓存 TTL 保持一致）
+    pub redis_last_message_ttl_seconds: u64,
+    /// 消息内容加密密钥文件（可选，需与 Writer 使用相同的密钥文件）
+    pub encryption_key_file: Option<String>,
+    /// 分析导出的 PII 假名化策略文件（可选，按租户配置字段与算法）
+    pub pii_policy_file: Option<String>,
+    /// PII 假名化密钥文件（与策略文件同时配置，格式同加密密钥文件）
+    pub pii_key_file: Option<String>,
+    /// 用户数据删除任务每批处理的记录数
+    pub user_purge_batch_size: i64,
+    /// 用户数据删除任务轮询间隔（毫秒）
+    pub user_purge_poll_interval_ms: u64,
+    /// 精确计数阈值（会话消息数超过后返回估算值）
+    pub message_count_exact_threshold: i64,
+    /// 未读角标上限（超过后显示为 `999+` 并返回估算值）
+    pub unread_badge_cap: i64,
+    /// 计数快照校准间隔（秒）
+    pub message_count_reconcile_interval_seconds: u64,
+    /// 每轮校准的快照数
+    pub message_count_reconcile_batch_size: i64,
 }
 
 impl StorageReaderConfig {
@@ -102,6 +122,49 @@ impl StorageReaderConfig {
             .and_then(|v| v.parse::<u64>().ok())
             .unwrap_or(1800); // 30 minutes
 
+        let redis_last_message_ttl_seconds = env::var("STORAGE_REDIS_LAST_MESSAGE_TTL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(7 * 24 * 3600); // 7 days
+
+        let encryption_key_file = env::var("STORAGE_ENCRYPTION_KEY_FILE").ok();
+        let pii_policy_file = env::var("STORAGE_PII_POLICY_FILE").ok();
+        let pii_key_file = env::var("STORAGE_PII_KEY_FILE").ok();
+
+        // 用户数据删除任务配置
+        let user_purge_batch_size = env::var("STORAGE_USER_PURGE_BATCH_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(500);
+
+        let user_purge_poll_interval_ms = env::var("STORAGE_USER_PURGE_POLL_INTERVAL_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(10_000);
+
+        // 超大会话近似计数配置
+        let message_count_exact_threshold = env::var("STORAGE_MESSAGE_COUNT_EXACT_THRESHOLD")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(10_000);
+
+        let unread_badge_cap = env::var("STORAGE_UNREAD_BADGE_CAP")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(999);
+
+        let message_count_reconcile_interval_seconds =
+            env::var("STORAGE_MESSAGE_COUNT_RECONCILE_INTERVAL_SECONDS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(3600);
+
+        let message_count_reconcile_batch_size =
+            env::var("STORAGE_MESSAGE_COUNT_RECONCILE_BATCH_SIZE")
+                .ok()
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(100);
+
         Ok(Self {
             redis_url,
             postgres_url,
@@ -115,6 +178,16 @@ impl StorageReaderConfig {
             redis_cache_ttl_seconds,
             redis_message_cache_ttl_seconds,
             redis_session_cache_ttl_seconds,
+            redis_last_message_ttl_seconds,
+            encryption_key_file,
+            pii_policy_file,
+            pii_key_file,
+            user_purge_batch_size,
+            user_purge_poll_interval_ms,
+            message_count_exact_threshold,
+            unread_badge_cap,
+            message_count_reconcile_interval_seconds,
+            message_count_reconcile_batch_size,
         })
     }
 
@@ -145,6 +218,16 @@ impl StorageReaderConfig {
             redis_cache_ttl_seconds: 300,
             redis_message_cache_ttl_seconds: 3600,
             redis_session_cache_ttl_seconds: 1800,
+            redis_last_message_ttl_seconds: 7 * 24 * 3600,
+            encryption_key_file: env::var("STORAGE_ENCRYPTION_KEY_FILE").ok(),
+            pii_policy_file: env::var("STORAGE_PII_POLICY_FILE").ok(),
+            pii_key_file: env::var("STORAGE_PII_KEY_FILE").ok(),
+            user_purge_batch_size: 500,
+            user_purge_poll_interval_ms: 10_000,
+            message_count_exact_threshold: 10_000,
+            unread_badge_cap: 999,
+            message_count_reconcile_interval_seconds: 3600,
+            message_count_reconcile_batch_size: 100,
         }
     }
 }
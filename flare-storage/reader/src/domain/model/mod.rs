@@ -1,7 +1,11 @@
 //! 领域模型定义
 
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL};
+use chrono::{DateTime, Utc};
+use flare_im_core::pagination::CursorCodec;
 use flare_proto::common::{MessageOperation, MessageReadRecord, Reaction, VisibilityStatus};
 use prost_types::Timestamp;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// 消息更新结构
@@ -18,3 +22,362 @@ pub struct MessageUpdate {
     /// 消息状态（可选，用于更新消息状态）
     pub status: Option<i32>, // MessageStatus 枚举值
 }
+
+/// 历史消息查询模式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HistoryQueryMode {
+    /// 按时间范围查询（默认，向后兼容）
+    #[default]
+    Range,
+    /// 最新 N 条，按 seq 降序返回（打开会话时的首屏）
+    Latest,
+    /// 跳转到指定时间：定位不早于该时间的第一条消息，连同前后上下文按 seq 升序返回
+    JumpToDate,
+}
+
+impl HistoryQueryMode {
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "range" => Some(Self::Range),
+            "latest" => Some(Self::Latest),
+            "jump_to_date" => Some(Self::JumpToDate),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryQueryMode::Range => "range",
+            HistoryQueryMode::Latest => "latest",
+            HistoryQueryMode::JumpToDate => "jump_to_date",
+        }
+    }
+}
+
+/// 查询历史消息的用户（用于裁剪新成员不可见的历史消息）
+///
+/// 成员的可见边界按 `(tenant_id, conversation_id, user_id)` 查询，不同租户的同名会话互不影响
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryViewer {
+    pub tenant_id: String,
+    pub user_id: String,
+}
+
+/// 历史消息翻页方向
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryDirection {
+    /// 向更早的消息翻页（seq < 游标，降序）
+    Older,
+    /// 向更新的消息翻页（seq > 游标，升序）
+    Newer,
+}
+
+/// 历史消息游标（对客户端不透明）
+///
+/// 使用 `flare_im_core::pagination` 的签名游标编码，客户端只需原样回传；
+/// 上一版本签发的 `h1:<older|newer>:<seq>` 游标（URL 安全 base64，无签名）在本版本内仍可解码，
+/// 下一版本移除；其余无法解码的游标按时间范围查询的旧格式（`<ts>:<message_id>`）处理
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryCursor {
+    pub direction: HistoryDirection,
+    pub seq: i64,
+}
+
+impl HistoryCursor {
+    /// 游标作用域（与其他接口的游标互不通用）
+    const SCOPE: &'static str = "storage.history";
+    /// 上一版本的游标版本前缀（兼容一个版本）
+    const LEGACY_VERSION: &'static str = "h1";
+
+    pub fn older(seq: i64) -> Self {
+        Self {
+            direction: HistoryDirection::Older,
+            seq,
+        }
+    }
+
+    pub fn newer(seq: i64) -> Self {
+        Self {
+            direction: HistoryDirection::Newer,
+            seq,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        CursorCodec::global().encode(Self::SCOPE, self)
+    }
+
+    pub fn decode(raw: &str) -> Option<Self> {
+        CursorCodec::global()
+            .decode(Self::SCOPE, raw)
+            .ok()
+            .flatten()
+            .or_else(|| Self::decode_legacy(raw))
+    }
+
+    /// 解码上一版本的 `h1:` 游标（升级期间客户端持有的游标仍可继续翻页）
+    fn decode_legacy(raw: &str) -> Option<Self> {
+        let bytes = BASE64_URL.decode(raw).ok()?;
+        let raw = String::from_utf8(bytes).ok()?;
+        let mut parts = raw.splitn(3, ':');
+        if parts.next()? != Self::LEGACY_VERSION {
+            return None;
+        }
+        let direction = match parts.next()? {
+            "older" => HistoryDirection::Older,
+            "newer" => HistoryDirection::Newer,
+            _ => return None,
+        };
+        let seq = parts.next()?.parse::<i64>().ok()?;
+        Some(Self { direction, seq })
+    }
+}
+
+/// 匿名化后替代用户ID的占位值（发送者、接收者、编辑者）
+pub const ANONYMIZED_USER_ID: &str = "deleted_user";
+
+/// 用户数据删除时消息的处理方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserPurgeMode {
+    /// 完全删除用户发送的消息（连同其他用户对这些消息的已读、反应等状态）
+    Delete,
+    /// 保留消息内容，将发送者替换为匿名ID（会话上下文对其他成员保持完整）
+    Anonymize,
+}
+
+impl UserPurgeMode {
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "delete" => Some(Self::Delete),
+            "anonymize" => Some(Self::Anonymize),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserPurgeMode::Delete => "delete",
+            UserPurgeMode::Anonymize => "anonymize",
+        }
+    }
+}
+
+/// 用户数据删除任务状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserPurgeJobStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl UserPurgeJobStatus {
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "running" => Some(Self::Running),
+            "completed" => Some(Self::Completed),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserPurgeJobStatus::Pending => "pending",
+            UserPurgeJobStatus::Running => "running",
+            UserPurgeJobStatus::Completed => "completed",
+            UserPurgeJobStatus::Failed => "failed",
+        }
+    }
+}
+
+/// 用户数据删除步骤（按声明顺序执行）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UserPurgeStep {
+    /// 用户发送的消息（含消息缓存）
+    Messages,
+    /// 已读记录与用户私有消息状态（已读、可见性、标记、反应）
+    ReadRecords,
+    /// 同步游标
+    Cursors,
+    /// ACK 历史（含归档）
+    AckRecords,
+    /// 用户拥有的媒体引用（无引用的媒体文件由媒体服务按宽限期清理）
+    MediaReferences,
+    /// 全部步骤已完成
+    Done,
+}
+
+impl UserPurgeStep {
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "messages" => Some(Self::Messages),
+            "read_records" => Some(Self::ReadRecords),
+            "cursors" => Some(Self::Cursors),
+            "ack_records" => Some(Self::AckRecords),
+            "media_references" => Some(Self::MediaReferences),
+            "done" => Some(Self::Done),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserPurgeStep::Messages => "messages",
+            UserPurgeStep::ReadRecords => "read_records",
+            UserPurgeStep::Cursors => "cursors",
+            UserPurgeStep::AckRecords => "ack_records",
+            UserPurgeStep::MediaReferences => "media_references",
+            UserPurgeStep::Done => "done",
+        }
+    }
+
+    /// 下一个步骤
+    pub fn next(&self) -> Self {
+        match self {
+            UserPurgeStep::Messages => UserPurgeStep::ReadRecords,
+            UserPurgeStep::ReadRecords => UserPurgeStep::Cursors,
+            UserPurgeStep::Cursors => UserPurgeStep::AckRecords,
+            UserPurgeStep::AckRecords => UserPurgeStep::MediaReferences,
+            UserPurgeStep::MediaReferences | UserPurgeStep::Done => UserPurgeStep::Done,
+        }
+    }
+}
+
+/// 用户数据删除任务（被遗忘权）
+#[derive(Clone, Debug)]
+pub struct UserPurgeJob {
+    pub job_id: String,
+    pub tenant_id: String,
+    pub user_id: String,
+    pub mode: UserPurgeMode,
+    pub status: UserPurgeJobStatus,
+    /// 当前步骤，任务中断后从该步骤续跑（每步分批执行，已处理的数据不会再被匹配）
+    pub current_step: UserPurgeStep,
+    /// 各步骤已处理的记录数（步骤名 -> 记录数），任务完成后即为完成报告
+    pub report: HashMap<String, i64>,
+    pub requested_by: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl UserPurgeJob {
+    /// 累加步骤处理的记录数
+    pub fn record(&mut self, step: UserPurgeStep, affected: u64) {
+        *self.report.entry(step.as_str().to_string()).or_insert(0) += affected as i64;
+    }
+}
+
+/// 用户数据删除审计动作
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserPurgeAuditAction {
+    Submitted,
+    StepCompleted,
+    Completed,
+    Failed,
+    Retried,
+}
+
+impl UserPurgeAuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserPurgeAuditAction::Submitted => "submitted",
+            UserPurgeAuditAction::StepCompleted => "step_completed",
+            UserPurgeAuditAction::Completed => "completed",
+            UserPurgeAuditAction::Failed => "failed",
+            UserPurgeAuditAction::Retried => "retried",
+        }
+    }
+}
+
+/// 用户数据删除审计记录
+#[derive(Clone, Debug)]
+pub struct UserPurgeAuditEntry {
+    pub job_id: String,
+    pub tenant_id: String,
+    pub user_id: String,
+    pub action: UserPurgeAuditAction,
+    pub step: Option<UserPurgeStep>,
+    pub affected_count: i64,
+    pub detail: Option<String>,
+}
+
+/// 消息计数（超过精确计数阈值时为估算值）
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MessageCount {
+    pub value: i64,
+    /// 是否为估算值
+    pub approximate: bool,
+}
+
+impl MessageCount {
+    pub fn exact(value: i64) -> Self {
+        Self {
+            value: value.max(0),
+            approximate: false,
+        }
+    }
+
+    pub fn estimated(value: i64) -> Self {
+        Self {
+            value: value.max(0),
+            approximate: true,
+        }
+    }
+
+    /// 角标文案：超过上限时显示 `{cap}+`（如 `999+`）
+    pub fn badge(&self, cap: i64) -> String {
+        if self.value > cap {
+            format!("{}+", cap)
+        } else {
+            self.value.to_string()
+        }
+    }
+}
+
+/// 会话消息计数快照（超大会话增量维护，后台定期校准）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageCountSnapshot {
+    pub conversation_id: String,
+    pub message_count: i64,
+    /// 已计入 `message_count` 的最大 seq
+    pub max_seq: i64,
+    /// 最近一次精确校准时间（首次越过阈值时为 Unix 纪元，等待后台校准）
+    pub reconciled_at: DateTime<Utc>,
+}
+
+/// 会话消息统计
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConversationMessageStats {
+    pub message_count: MessageCount,
+    pub unread_count: MessageCount,
+    /// 未读角标文案（超过上限时为 `999+`）
+    pub unread_badge: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_cursor_roundtrip() {
+        let cursor = HistoryCursor::older(42);
+        assert_eq!(HistoryCursor::decode(&cursor.encode()), Some(cursor));
+    }
+
+    #[test]
+    fn test_history_cursor_decodes_legacy_h1() {
+        let raw = BASE64_URL.encode("h1:newer:7");
+        assert_eq!(HistoryCursor::decode(&raw), Some(HistoryCursor::newer(7)));
+
+        assert_eq!(
+            HistoryCursor::decode(&BASE64_URL.encode("h2:older:7")),
+            None
+        );
+        assert_eq!(HistoryCursor::decode("1700000000:msg-1"), None);
+    }
+}
@@ -1,6 +1,9 @@
 //! 仓储接口定义（Port）
 
-use crate::domain::model::MessageUpdate;
+use crate::domain::model::{
+    MessageCountSnapshot, MessageUpdate, UserPurgeAuditEntry, UserPurgeJob, UserPurgeMode,
+    UserPurgeStep,
+};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use flare_proto::common::{Message, VisibilityStatus};
@@ -41,6 +44,26 @@ pub trait MessageStorage: Send + Sync {
         limit: i32,
     ) -> Result<Vec<Message>>;
 
+    /// 基于 seq 倒序查询消息（最新 N 条及向更早翻页）
+    ///
+    /// # 参数
+    /// * `conversation_id` - 会话ID
+    /// * `user_id` - 用户ID（可选，用于过滤已删除消息）
+    /// * `before_seq` - 查询 seq < before_seq 的消息（None 表示从最新一条开始）
+    /// * `after_seq` - 查询 seq > after_seq 的消息（下界，0 表示不限制）
+    /// * `limit` - 返回消息数量限制
+    ///
+    /// # 返回
+    /// * `Ok(Vec<Message>)` - 消息列表（按 seq 降序排序）
+    async fn query_messages_by_seq_desc(
+        &self,
+        conversation_id: &str,
+        user_id: Option<&str>,
+        before_seq: Option<i64>,
+        after_seq: i64,
+        limit: i32,
+    ) -> Result<Vec<Message>>;
+
     async fn count_messages(
         &self,
         conversation_id: &str,
@@ -51,6 +74,15 @@ pub trait MessageStorage: Send + Sync {
 
     async fn get_message(&self, message_id: &str) -> Result<Option<Message>>;
 
+    /// 批量获取会话的最后一条消息
+    ///
+    /// # 返回
+    /// * `Ok(HashMap<conversation_id, Message>)` - 没有消息的会话不在结果中
+    async fn get_last_messages(
+        &self,
+        conversation_ids: &[String],
+    ) -> Result<HashMap<String, Message>>;
+
     /// 获取消息的时间戳
     ///
     /// 用于清除会话时根据消息ID确定清除时间点
@@ -87,6 +119,32 @@ pub trait MessageStorage: Send + Sync {
     ) -> Result<()>;
 
     async fn list_all_tags(&self) -> Result<Vec<String>>;
+
+    /// 获取用户在会话中的历史消息可见起始时间
+    ///
+    /// 新成员加入时按会话的历史可见性配置计算该边界，早于边界的消息对其不可见
+    ///
+    /// # 返回
+    /// * `Ok(None)` - 全部历史可见（默认实现）
+    async fn history_visible_from(
+        &self,
+        _tenant_id: &str,
+        _conversation_id: &str,
+        _user_id: &str,
+    ) -> Result<Option<DateTime<Utc>>> {
+        Ok(None)
+    }
+
+    /// 获取会话中不早于指定时间的第一条消息的 seq
+    ///
+    /// 用于将历史可见边界换算为 seq 边界，以及定位跳转到指定时间的锚点消息
+    async fn first_seq_since(
+        &self,
+        _conversation_id: &str,
+        _since: DateTime<Utc>,
+    ) -> Result<Option<i64>> {
+        Ok(None)
+    }
 }
 
 #[async_trait::async_trait]
@@ -139,3 +197,90 @@ pub trait MessageStateRepository: Send + Sync {
         message_ids: &[String],
     ) -> anyhow::Result<()>;
 }
+
+/// 用户数据删除仓储接口（删除任务、审计日志与各存储的分批清除）
+#[async_trait::async_trait]
+pub trait UserPurgeRepository: Send + Sync {
+    /// 创建删除任务
+    async fn create_job(&self, job: &UserPurgeJob) -> Result<()>;
+
+    async fn get_job(&self, job_id: &str) -> Result<Option<UserPurgeJob>>;
+
+    /// 列出待执行或执行中断的任务（按创建时间升序）
+    async fn list_resumable_jobs(&self, limit: i64) -> Result<Vec<UserPurgeJob>>;
+
+    /// 持久化任务状态、当前步骤与报告
+    async fn update_job(&self, job: &UserPurgeJob) -> Result<()>;
+
+    /// 追加审计记录
+    async fn append_audit(&self, entry: &UserPurgeAuditEntry) -> Result<()>;
+
+    /// 清除一批属于该用户的数据，返回本批处理的记录数（返回 0 表示该步骤已完成）
+    ///
+    /// 已处理的数据不会再被匹配，因此重复执行是幂等的
+    async fn purge_batch(
+        &self,
+        tenant_id: &str,
+        user_id: &str,
+        step: UserPurgeStep,
+        mode: UserPurgeMode,
+        limit: i64,
+    ) -> Result<u64>;
+}
+
+/// 会话消息计数仓储接口（超大会话的近似计数与定期校准）
+#[async_trait::async_trait]
+pub trait MessageCountRepository: Send + Sync {
+    async fn get_snapshot(&self, conversation_id: &str) -> Result<Option<MessageCountSnapshot>>;
+
+    /// 写入快照（覆盖已有快照）
+    async fn save_snapshot(&self, snapshot: &MessageCountSnapshot) -> Result<()>;
+
+    /// 增量推进快照：仅当快照的 `max_seq` 仍为 `from_seq` 时累加 `delta` 并推进到 `to_seq`
+    ///
+    /// # 返回
+    /// * `Ok(false)` - 快照已被其他实例推进或校准，本次增量被丢弃
+    async fn advance_snapshot(
+        &self,
+        conversation_id: &str,
+        from_seq: i64,
+        to_seq: i64,
+        delta: i64,
+    ) -> Result<bool>;
+
+    async fn delete_snapshot(&self, conversation_id: &str) -> Result<()>;
+
+    /// 列出校准时间早于 `before` 的快照所属会话（按校准时间升序）
+    async fn list_stale_snapshots(&self, before: DateTime<Utc>, limit: i64) -> Result<Vec<String>>;
+
+    /// 统计 seq > `after_seq` 的消息数，最多统计 `cap` 条
+    ///
+    /// # 返回
+    /// * `Ok((count, max_seq))` - 统计的条数及其中的最大 seq（没有消息时为 None）
+    async fn count_after_seq_capped(
+        &self,
+        conversation_id: &str,
+        after_seq: i64,
+        cap: i64,
+    ) -> Result<(i64, Option<i64>)>;
+
+    /// 统计时间范围内的消息数，最多统计 `cap` 条
+    async fn count_range_capped(
+        &self,
+        conversation_id: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        cap: i64,
+    ) -> Result<i64>;
+
+    /// 时间范围内消息的最小与最大 seq（不指定范围时为整个会话）
+    async fn seq_bounds(
+        &self,
+        conversation_id: &str,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> Result<Option<(i64, i64)>>;
+
+    /// 精确统计会话的消息数与最大 seq（后台校准使用，超大会话较慢）
+    async fn count_exact(&self, conversation_id: &str) -> Result<(i64, i64)>;
+}
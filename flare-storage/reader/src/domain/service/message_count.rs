@@ -0,0 +1,434 @@
+//! 消息计数领域服务 - 超大会话的近似消息数与未读数估算
+//!
+//! 消息数不超过精确计数阈值的会话始终精确计数（带上限的索引扫描，代价有界）。
+//! 会话首次越过阈值时以最大 seq 估算并创建计数快照，此后每次查询只统计快照之后的新消息并增量推进快照；
+//! 后台按校准间隔精确重算快照，消除删除、撤回等造成的偏差。
+//! 未读数超过角标上限时按 seq 差值估算，角标显示为 `999+`。估算结果均带 `approximate` 标记。
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use tracing::{debug, info, instrument, warn};
+
+use crate::domain::model::{ConversationMessageStats, MessageCount, MessageCountSnapshot};
+use crate::domain::repository::MessageCountRepository;
+
+/// 精确计数阈值（默认值）
+const DEFAULT_EXACT_THRESHOLD: i64 = 10_000;
+/// 未读角标上限（默认值）
+const DEFAULT_BADGE_CAP: i64 = 999;
+/// 快照校准间隔（默认值，秒）
+const DEFAULT_RECONCILE_INTERVAL_SECS: i64 = 3600;
+/// 每轮校准的快照数（默认值）
+const DEFAULT_RECONCILE_BATCH_SIZE: i64 = 100;
+
+/// 消息计数领域服务
+pub struct MessageCountDomainService {
+    repo: Arc<dyn MessageCountRepository>,
+    exact_threshold: i64,
+    badge_cap: i64,
+    reconcile_interval: Duration,
+    reconcile_batch_size: i64,
+}
+
+impl MessageCountDomainService {
+    pub fn new(repo: Arc<dyn MessageCountRepository>) -> Self {
+        Self {
+            repo,
+            exact_threshold: DEFAULT_EXACT_THRESHOLD,
+            badge_cap: DEFAULT_BADGE_CAP,
+            reconcile_interval: Duration::seconds(DEFAULT_RECONCILE_INTERVAL_SECS),
+            reconcile_batch_size: DEFAULT_RECONCILE_BATCH_SIZE,
+        }
+    }
+
+    pub fn with_exact_threshold(mut self, exact_threshold: i64) -> Self {
+        self.exact_threshold = exact_threshold.max(1);
+        self
+    }
+
+    pub fn with_badge_cap(mut self, badge_cap: i64) -> Self {
+        self.badge_cap = badge_cap.max(1);
+        self
+    }
+
+    pub fn with_reconcile_interval(mut self, reconcile_interval: std::time::Duration) -> Self {
+        self.reconcile_interval =
+            Duration::from_std(reconcile_interval).unwrap_or(self.reconcile_interval);
+        self
+    }
+
+    pub fn with_reconcile_batch_size(mut self, reconcile_batch_size: i64) -> Self {
+        self.reconcile_batch_size = reconcile_batch_size.max(1);
+        self
+    }
+
+    /// 会话消息总数（超过精确计数阈值时为估算值）
+    #[instrument(skip(self))]
+    pub async fn conversation_count(&self, conversation_id: &str) -> Result<MessageCount> {
+        if let Some(snapshot) = self.repo.get_snapshot(conversation_id).await?
+            && snapshot.message_count > self.exact_threshold
+        {
+            return self.advance(snapshot).await;
+        }
+
+        let (count, _) = self
+            .repo
+            .count_after_seq_capped(conversation_id, 0, self.exact_threshold + 1)
+            .await?;
+        if count <= self.exact_threshold {
+            return Ok(MessageCount::exact(count));
+        }
+
+        // 首次越过阈值：以最大 seq 估算并创建快照，等待后台精确校准
+        let max_seq = self.max_seq(conversation_id).await?;
+        let snapshot = MessageCountSnapshot {
+            conversation_id: conversation_id.to_string(),
+            message_count: max_seq.max(count),
+            max_seq,
+            reconciled_at: DateTime::UNIX_EPOCH,
+        };
+        self.repo.save_snapshot(&snapshot).await?;
+        info!(
+            conversation_id = %conversation_id,
+            estimated = snapshot.message_count,
+            "Conversation exceeded exact message count threshold"
+        );
+        Ok(MessageCount::estimated(snapshot.message_count))
+    }
+
+    /// 用户在会话中的未读数（seq > `last_read_seq` 的消息，超过角标上限时按 seq 差值估算）
+    #[instrument(skip(self))]
+    pub async fn unread_count(
+        &self,
+        conversation_id: &str,
+        last_read_seq: i64,
+    ) -> Result<MessageCount> {
+        let (count, _) = self
+            .repo
+            .count_after_seq_capped(conversation_id, last_read_seq, self.badge_cap + 1)
+            .await?;
+        if count <= self.badge_cap {
+            return Ok(MessageCount::exact(count));
+        }
+
+        let max_seq = self.max_seq(conversation_id).await?;
+        Ok(MessageCount::estimated(
+            (max_seq - last_read_seq).max(count),
+        ))
+    }
+
+    /// 时间范围内的消息数（超过精确计数阈值时按范围内的 seq 跨度估算）
+    pub async fn range_count(
+        &self,
+        conversation_id: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<MessageCount> {
+        let count = self
+            .repo
+            .count_range_capped(
+                conversation_id,
+                start_time,
+                end_time,
+                self.exact_threshold + 1,
+            )
+            .await?;
+        if count <= self.exact_threshold {
+            return Ok(MessageCount::exact(count));
+        }
+
+        let span = self
+            .repo
+            .seq_bounds(conversation_id, Some(start_time), Some(end_time))
+            .await?
+            .map(|(min_seq, max_seq)| max_seq - min_seq + 1)
+            .unwrap_or(count);
+        Ok(MessageCount::estimated(span.max(count)))
+    }
+
+    /// 会话消息统计（总数、未读数与未读角标）
+    pub async fn conversation_stats(
+        &self,
+        conversation_id: &str,
+        last_read_seq: i64,
+    ) -> Result<ConversationMessageStats> {
+        let message_count = self.conversation_count(conversation_id).await?;
+        let unread_count = self.unread_count(conversation_id, last_read_seq).await?;
+        Ok(ConversationMessageStats {
+            message_count,
+            unread_badge: unread_count.badge(self.badge_cap),
+            unread_count,
+        })
+    }
+
+    /// 精确校准超过校准间隔的快照，返回本轮校准的会话数
+    ///
+    /// 消息数回落到阈值以内的会话删除快照，恢复精确计数
+    pub async fn reconcile_stale(&self) -> Result<usize> {
+        let before = Utc::now() - self.reconcile_interval;
+        let conversation_ids = self
+            .repo
+            .list_stale_snapshots(before, self.reconcile_batch_size)
+            .await?;
+
+        let mut reconciled = 0;
+        for conversation_id in &conversation_ids {
+            match self.reconcile(conversation_id).await {
+                Ok(()) => reconciled += 1,
+                Err(err) => warn!(
+                    conversation_id = %conversation_id,
+                    error = %err,
+                    "Failed to reconcile message count"
+                ),
+            }
+        }
+        Ok(reconciled)
+    }
+
+    async fn reconcile(&self, conversation_id: &str) -> Result<()> {
+        let (count, max_seq) = self.repo.count_exact(conversation_id).await?;
+        if count <= self.exact_threshold {
+            return self.repo.delete_snapshot(conversation_id).await;
+        }
+
+        self.repo
+            .save_snapshot(&MessageCountSnapshot {
+                conversation_id: conversation_id.to_string(),
+                message_count: count,
+                max_seq,
+                reconciled_at: Utc::now(),
+            })
+            .await?;
+        debug!(conversation_id = %conversation_id, count, max_seq, "Message count reconciled");
+        Ok(())
+    }
+
+    /// 统计快照之后的新消息并增量推进快照
+    async fn advance(&self, snapshot: MessageCountSnapshot) -> Result<MessageCount> {
+        let conversation_id = snapshot.conversation_id.as_str();
+        let (mut delta, mut to_seq) = self
+            .repo
+            .count_after_seq_capped(conversation_id, snapshot.max_seq, self.exact_threshold + 1)
+            .await?;
+        if delta > self.exact_threshold {
+            // 新消息过多：按 seq 差值估算
+            let max_seq = self.max_seq(conversation_id).await?;
+            delta = (max_seq - snapshot.max_seq).max(delta);
+            to_seq = Some(max_seq);
+        }
+
+        let Some(to_seq) = to_seq.filter(|_| delta > 0) else {
+            return Ok(MessageCount::estimated(snapshot.message_count));
+        };
+        if !self
+            .repo
+            .advance_snapshot(conversation_id, snapshot.max_seq, to_seq, delta)
+            .await?
+        {
+            debug!(conversation_id = %conversation_id, "Message count snapshot advanced concurrently");
+        }
+        Ok(MessageCount::estimated(snapshot.message_count + delta))
+    }
+
+    /// 会话当前最大 seq（没有消息时为 0）
+    async fn max_seq(&self, conversation_id: &str) -> Result<i64> {
+        Ok(self
+            .repo
+            .seq_bounds(conversation_id, None, None)
+            .await?
+            .map(|(_, max_seq)| max_seq)
+            .unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// 内存仓储：每条消息为 (seq, 时间戳秒)
+    #[derive(Default)]
+    struct MemoryCountRepository {
+        messages: Mutex<Vec<(i64, i64)>>,
+        snapshot: Mutex<Option<MessageCountSnapshot>>,
+    }
+
+    impl MemoryCountRepository {
+        fn with_messages(count: i64) -> Self {
+            let repo = Self::default();
+            repo.push(1, count);
+            repo
+        }
+
+        fn push(&self, from_seq: i64, count: i64) {
+            self.messages
+                .lock()
+                .unwrap()
+                .extend((from_seq..from_seq + count).map(|seq| (seq, seq)));
+        }
+
+        fn remove(&self, seq: i64) {
+            self.messages.lock().unwrap().retain(|(s, _)| *s != seq);
+        }
+
+        fn snapshot(&self) -> Option<MessageCountSnapshot> {
+            self.snapshot.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MessageCountRepository for MemoryCountRepository {
+        async fn get_snapshot(
+            &self,
+            _conversation_id: &str,
+        ) -> Result<Option<MessageCountSnapshot>> {
+            Ok(self.snapshot())
+        }
+
+        async fn save_snapshot(&self, snapshot: &MessageCountSnapshot) -> Result<()> {
+            *self.snapshot.lock().unwrap() = Some(snapshot.clone());
+            Ok(())
+        }
+
+        async fn advance_snapshot(
+            &self,
+            _conversation_id: &str,
+            from_seq: i64,
+            to_seq: i64,
+            delta: i64,
+        ) -> Result<bool> {
+            let mut snapshot = self.snapshot.lock().unwrap();
+            match snapshot.as_mut() {
+                Some(snapshot) if snapshot.max_seq == from_seq => {
+                    snapshot.message_count += delta;
+                    snapshot.max_seq = to_seq;
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        }
+
+        async fn delete_snapshot(&self, _conversation_id: &str) -> Result<()> {
+            *self.snapshot.lock().unwrap() = None;
+            Ok(())
+        }
+
+        async fn list_stale_snapshots(
+            &self,
+            before: DateTime<Utc>,
+            _limit: i64,
+        ) -> Result<Vec<String>> {
+            Ok(self
+                .snapshot()
+                .filter(|snapshot| snapshot.reconciled_at < before)
+                .map(|snapshot| snapshot.conversation_id)
+                .into_iter()
+                .collect())
+        }
+
+        async fn count_after_seq_capped(
+            &self,
+            _conversation_id: &str,
+            after_seq: i64,
+            cap: i64,
+        ) -> Result<(i64, Option<i64>)> {
+            let messages = self.messages.lock().unwrap();
+            let seqs: Vec<i64> = messages
+                .iter()
+                .map(|(seq, _)| *seq)
+                .filter(|seq| *seq > after_seq)
+                .take(cap as usize)
+                .collect();
+            Ok((seqs.len() as i64, seqs.iter().max().copied()))
+        }
+
+        async fn count_range_capped(
+            &self,
+            _conversation_id: &str,
+            start_time: DateTime<Utc>,
+            end_time: DateTime<Utc>,
+            cap: i64,
+        ) -> Result<i64> {
+            let messages = self.messages.lock().unwrap();
+            Ok(messages
+                .iter()
+                .filter(|(_, ts)| (start_time.timestamp()..=end_time.timestamp()).contains(ts))
+                .take(cap as usize)
+                .count() as i64)
+        }
+
+        async fn seq_bounds(
+            &self,
+            _conversation_id: &str,
+            start_time: Option<DateTime<Utc>>,
+            end_time: Option<DateTime<Utc>>,
+        ) -> Result<Option<(i64, i64)>> {
+            let messages = self.messages.lock().unwrap();
+            let seqs = messages.iter().filter(|(_, ts)| {
+                start_time.is_none_or(|start| *ts >= start.timestamp())
+                    && end_time.is_none_or(|end| *ts <= end.timestamp())
+            });
+            let min = seqs.clone().map(|(seq, _)| *seq).min();
+            let max = seqs.map(|(seq, _)| *seq).max();
+            Ok(min.zip(max))
+        }
+
+        async fn count_exact(&self, _conversation_id: &str) -> Result<(i64, i64)> {
+            let messages = self.messages.lock().unwrap();
+            let max_seq = messages.iter().map(|(seq, _)| *seq).max().unwrap_or(0);
+            Ok((messages.len() as i64, max_seq))
+        }
+    }
+
+    fn service(repo: &Arc<MemoryCountRepository>) -> MessageCountDomainService {
+        MessageCountDomainService::new(repo.clone())
+            .with_exact_threshold(100)
+            .with_badge_cap(99)
+    }
+
+    #[tokio::test]
+    async fn test_small_conversation_counts_exactly() {
+        let repo = Arc::new(MemoryCountRepository::with_messages(80));
+        let stats = service(&repo).conversation_stats("c1", 30).await.unwrap();
+
+        assert_eq!(stats.message_count, MessageCount::exact(80));
+        assert_eq!(stats.unread_count, MessageCount::exact(50));
+        assert_eq!(stats.unread_badge, "50");
+        assert!(repo.snapshot().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_huge_conversation_is_estimated_and_reconciled() {
+        let repo = Arc::new(MemoryCountRepository::with_messages(150));
+        repo.remove(10);
+        let service = service(&repo);
+
+        // 首次越过阈值：按最大 seq 估算并创建快照
+        let count = service.conversation_count("c1").await.unwrap();
+        assert_eq!(count, MessageCount::estimated(150));
+
+        // 新消息增量推进快照
+        repo.push(151, 5);
+        let count = service.conversation_count("c1").await.unwrap();
+        assert_eq!(count, MessageCount::estimated(155));
+        assert_eq!(repo.snapshot().unwrap().max_seq, 155);
+
+        // 校准消除删除造成的偏差
+        assert_eq!(service.reconcile_stale().await.unwrap(), 1);
+        let count = service.conversation_count("c1").await.unwrap();
+        assert_eq!(count, MessageCount::estimated(154));
+        assert_eq!(service.reconcile_stale().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unread_above_badge_cap_is_estimated() {
+        let repo = Arc::new(MemoryCountRepository::with_messages(5000));
+        let stats = service(&repo).conversation_stats("c1", 1000).await.unwrap();
+
+        assert_eq!(stats.unread_count, MessageCount::estimated(4000));
+        assert_eq!(stats.unread_badge, "99+");
+    }
+}
@@ -2,6 +2,7 @@
 
 use anyhow::{Result, anyhow};
 use chrono::{DateTime, Duration, TimeZone, Utc};
+use flare_im_core::pagination::{PageLimit, UNKNOWN_TOTAL};
 use flare_im_core::utils::{
     TimelineMetadata, extract_seq_from_message, extract_timeline_from_extra, timestamp_to_datetime,
 };
@@ -11,8 +12,14 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tracing::instrument;
 
-use crate::domain::model::MessageUpdate;
+use crate::domain::model::{
+    HistoryCursor, HistoryDirection, HistoryViewer, MessageCount, MessageUpdate,
+};
 use crate::domain::repository::{MessageStorage, VisibilityStorage};
+use crate::domain::service::MessageCountDomainService;
+
+/// 未指定条数时的默认单页条数
+const DEFAULT_PAGE_SIZE: usize = 50;
 
 /// 领域服务配置（值对象，不依赖基础设施层）
 #[derive(Debug, Clone)]
@@ -56,8 +63,33 @@ impl RetrievedMessage {
 pub struct QueryMessagesResult {
     pub messages: Vec<Message>,
     pub next_cursor: String,
+    /// 反方向翻页游标（仅历史游标查询返回，其余为空）
+    pub prev_cursor: String,
     pub has_more: bool,
     pub total_size: i64,
+    /// `total_size` 是否为估算值（超大会话）
+    pub total_size_approximate: bool,
+}
+
+impl QueryMessagesResult {
+    fn empty() -> Self {
+        Self {
+            messages: Vec::new(),
+            next_cursor: String::new(),
+            prev_cursor: String::new(),
+            has_more: false,
+            total_size: 0,
+            total_size_approximate: false,
+        }
+    }
+}
+
+/// 生成指向消息 seq 的历史游标（消息缺少 seq 时为空）
+fn history_cursor(message: Option<&Message>, direction: HistoryDirection) -> String {
+    message
+        .and_then(extract_seq_from_message)
+        .map(|seq| HistoryCursor { direction, seq }.encode())
+        .unwrap_or_default()
 }
 
 /// 消息存储领域服务 - 包含所有业务逻辑
@@ -67,6 +99,8 @@ pub struct MessageStorageDomainService {
     message_state_repo:
         Option<Arc<dyn crate::domain::repository::MessageStateRepository + Send + Sync>>,
     config: MessageStorageDomainConfig,
+    /// 消息计数服务（可选，配置后超大会话的 `total_size` 为估算值）
+    message_counter: Option<Arc<MessageCountDomainService>>,
 }
 
 impl MessageStorageDomainService {
@@ -83,14 +117,26 @@ impl MessageStorageDomainService {
             visibility_storage,
             message_state_repo,
             config,
+            message_counter: None,
         }
     }
 
+    pub fn with_message_counter(
+        mut self,
+        message_counter: Option<Arc<MessageCountDomainService>>,
+    ) -> Self {
+        self.message_counter = message_counter;
+        self
+    }
+
     /// 查询消息列表（基于时间戳，向后兼容）
-    #[instrument(skip(self), fields(conversation_id = %conversation_id))]
+    ///
+    /// 提供 `viewer` 时，起始时间不早于该用户的历史消息可见边界
+    #[instrument(skip(self), fields(conversation_id = %conversation_id, viewer = ?viewer))]
     pub async fn query_messages(
         &self,
         conversation_id: &str,
+        viewer: Option<&HistoryViewer>,
         start_time: i64,
         end_time: i64,
         limit: i32,
@@ -100,7 +146,7 @@ impl MessageStorageDomainService {
             return Err(anyhow!("conversation_id is required"));
         }
 
-        let limit = limit.clamp(1, self.config.max_page_size) as usize;
+        let limit = self.page_limit().clamp(limit as i64);
         let cursor = QueryCursor::from_raw(cursor);
 
         let end_ts = if end_time == 0 {
@@ -108,14 +154,30 @@ impl MessageStorageDomainService {
         } else {
             end_time
         };
-        let start_ts = if start_time == 0 {
+        let mut start_ts = if start_time == 0 {
             end_ts - self.config.default_range_seconds
         } else {
             start_time
         };
 
         let end_ts_ms = end_ts * 1_000;
-        let start_ts_ms = start_ts * 1_000;
+        let mut start_ts_ms = start_ts * 1_000;
+
+        // 新成员只能看到可见边界之后的历史消息
+        if let Some(visible_from) = self.history_visible_from(conversation_id, viewer).await? {
+            start_ts = start_ts.max(visible_from.timestamp());
+            start_ts_ms = start_ts_ms.max(visible_from.timestamp_millis());
+            if start_ts_ms > end_ts_ms {
+                return Ok(QueryMessagesResult {
+                    messages: Vec::new(),
+                    next_cursor: String::new(),
+                    prev_cursor: String::new(),
+                    has_more: false,
+                    total_size: 0,
+                    total_size_approximate: false,
+                });
+            }
+        }
 
         // 计算总记录数
         let start_dt_for_count = Utc
@@ -127,16 +189,23 @@ impl MessageStorageDomainService {
             .single()
             .unwrap_or_else(Utc::now);
 
-        let total_size = self
-            .storage
-            .count_messages(
-                conversation_id,
-                None,
-                Some(start_dt_for_count),
-                Some(end_dt_for_count),
-            )
-            .await
-            .map_err(|e| anyhow!("Failed to count messages: {}", e))?;
+        let total_size = match &self.message_counter {
+            Some(counter) => counter
+                .range_count(conversation_id, start_dt_for_count, end_dt_for_count)
+                .await
+                .map_err(|e| anyhow!("Failed to count messages: {}", e))?,
+            None => self
+                .storage
+                .count_messages(
+                    conversation_id,
+                    None,
+                    Some(start_dt_for_count),
+                    Some(end_dt_for_count),
+                )
+                .await
+                .map(MessageCount::exact)
+                .map_err(|e| anyhow!("Failed to count messages: {}", e))?,
+        };
 
         let mut seen = HashSet::new();
         if let Some(cursor) = &cursor {
@@ -170,8 +239,10 @@ impl MessageStorageDomainService {
         Ok(QueryMessagesResult {
             messages,
             next_cursor: next_cursor.clone(),
+            prev_cursor: String::new(),
             has_more: !next_cursor.is_empty(),
-            total_size,
+            total_size: total_size.value,
+            total_size_approximate: total_size.approximate,
         })
     }
 
@@ -179,7 +250,7 @@ impl MessageStorageDomainService {
     ///
     /// # 参数
     /// * `conversation_id` - 会话ID
-    /// * `user_id` - 用户ID（可选，用于过滤已删除消息）
+    /// * `viewer` - 查询用户（可选，用于过滤已删除消息及历史可见边界之前的消息）
     /// * `after_seq` - 查询 seq > after_seq 的消息（用于增量同步）
     /// * `before_seq` - 查询 seq < before_seq 的消息（可选，用于分页）
     /// * `limit` - 返回消息数量限制
@@ -190,7 +261,7 @@ impl MessageStorageDomainService {
     pub async fn query_messages_by_seq(
         &self,
         conversation_id: &str,
-        user_id: Option<&str>,
+        viewer: Option<&HistoryViewer>,
         after_seq: i64,
         before_seq: Option<i64>,
         limit: i32,
@@ -198,8 +269,15 @@ impl MessageStorageDomainService {
         if conversation_id.is_empty() {
             return Err(anyhow!("conversation_id is required"));
         }
+        let user_id = viewer.map(|viewer| viewer.user_id.as_str());
 
-        let limit = limit.clamp(1, self.config.max_page_size) as usize;
+        let limit = self.page_limit().clamp(limit as i64);
+
+        // 新成员只能看到可见边界之后的历史消息
+        let Some(visible_after_seq) = self.visible_after_seq(conversation_id, viewer).await? else {
+            return Ok(QueryMessagesResult::empty());
+        };
+        let after_seq = after_seq.max(visible_after_seq);
 
         // 使用基于 seq 的查询
         let messages = self
@@ -227,11 +305,232 @@ impl MessageStorageDomainService {
         Ok(QueryMessagesResult {
             messages,
             next_cursor: next_cursor.clone(),
+            prev_cursor: String::new(),
             has_more: !next_cursor.is_empty(),
             total_size,
+            total_size_approximate: false,
+        })
+    }
+
+    /// 查询最新消息，或沿历史游标继续翻页
+    ///
+    /// - 无游标或向更早翻页：按 seq 降序返回（`seq < 游标`），`next_cursor` 继续向更早翻页
+    /// - 向更新翻页：按 seq 升序返回（`seq > 游标`），`next_cursor` 继续向更新翻页
+    ///
+    /// 返回的 `prev_cursor` 用于从本页反方向翻页
+    #[instrument(skip(self), fields(conversation_id = %conversation_id, viewer = ?viewer))]
+    pub async fn query_latest_messages(
+        &self,
+        conversation_id: &str,
+        viewer: Option<&HistoryViewer>,
+        limit: i32,
+        cursor: Option<HistoryCursor>,
+    ) -> Result<QueryMessagesResult> {
+        if conversation_id.is_empty() {
+            return Err(anyhow!("conversation_id is required"));
+        }
+        let user_id = viewer.map(|viewer| viewer.user_id.as_str());
+
+        let limit = self.page_limit().clamp(limit as i64);
+        let Some(visible_after_seq) = self.visible_after_seq(conversation_id, viewer).await? else {
+            return Ok(QueryMessagesResult::empty());
+        };
+
+        let direction = cursor
+            .map(|cursor| cursor.direction)
+            .unwrap_or(HistoryDirection::Older);
+        // 多取一条用于判断是否还有下一页
+        let mut messages = match direction {
+            HistoryDirection::Newer => {
+                let after_seq = cursor.map(|cursor| cursor.seq).unwrap_or(0);
+                self.storage
+                    .query_messages_by_seq(
+                        conversation_id,
+                        user_id,
+                        after_seq.max(visible_after_seq),
+                        None,
+                        limit as i32 + 1,
+                    )
+                    .await
+            }
+            HistoryDirection::Older => {
+                self.storage
+                    .query_messages_by_seq_desc(
+                        conversation_id,
+                        user_id,
+                        cursor.map(|cursor| cursor.seq),
+                        visible_after_seq,
+                        limit as i32 + 1,
+                    )
+                    .await
+            }
+        }
+        .map_err(|e| anyhow!("Failed to query latest messages: {}", e))?;
+
+        let has_more = messages.len() > limit;
+        messages.truncate(limit);
+
+        let next_cursor = if has_more {
+            history_cursor(messages.last(), direction)
+        } else {
+            String::new()
+        };
+        // 首屏之前没有更新的消息，只有沿游标翻页时才提供反方向游标
+        let prev_cursor = if cursor.is_some() {
+            let reverse = match direction {
+                HistoryDirection::Older => HistoryDirection::Newer,
+                HistoryDirection::Newer => HistoryDirection::Older,
+            };
+            history_cursor(messages.first(), reverse)
+        } else {
+            String::new()
+        };
+
+        Ok(QueryMessagesResult {
+            messages,
+            next_cursor: next_cursor.clone(),
+            prev_cursor,
+            has_more: !next_cursor.is_empty(),
+            total_size: UNKNOWN_TOTAL,
+            total_size_approximate: false,
         })
     }
 
+    /// 跳转到指定时间
+    ///
+    /// 以不早于 `at` 的第一条消息为锚点，返回锚点之前约一半、锚点及之后其余的消息（按 seq 升序）；
+    /// `prev_cursor` 向更早翻页，`next_cursor` 向更新翻页。`at` 之后没有消息时返回最后一页
+    #[instrument(skip(self), fields(conversation_id = %conversation_id, viewer = ?viewer, at = %at))]
+    pub async fn query_messages_around(
+        &self,
+        conversation_id: &str,
+        viewer: Option<&HistoryViewer>,
+        at: DateTime<Utc>,
+        limit: i32,
+    ) -> Result<QueryMessagesResult> {
+        if conversation_id.is_empty() {
+            return Err(anyhow!("conversation_id is required"));
+        }
+        let user_id = viewer.map(|viewer| viewer.user_id.as_str());
+
+        let limit = self.page_limit().clamp(limit as i64);
+        let Some(visible_after_seq) = self.visible_after_seq(conversation_id, viewer).await? else {
+            return Ok(QueryMessagesResult::empty());
+        };
+
+        let anchor_seq = self
+            .storage
+            .first_seq_since(conversation_id, at)
+            .await
+            .map_err(|e| anyhow!("Failed to resolve jump anchor seq: {}", e))?
+            .map(|seq| seq.max(visible_after_seq + 1));
+
+        // 锚点之前的上下文（倒序取出后翻转为升序）；
+        // 不需要上下文时仍多取一条，用于判断是否提供向更早翻页的游标
+        let before_count = if anchor_seq.is_some() {
+            limit / 2
+        } else {
+            limit
+        };
+        let mut older = self
+            .storage
+            .query_messages_by_seq_desc(
+                conversation_id,
+                user_id,
+                anchor_seq,
+                visible_after_seq,
+                before_count as i32 + 1,
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to query messages before anchor: {}", e))?;
+        let has_older = older.len() > before_count;
+        older.truncate(before_count);
+        older.reverse();
+
+        // 锚点及之后的消息
+        let after_count = limit - before_count;
+        let mut newer = match anchor_seq {
+            Some(anchor_seq) => self
+                .storage
+                .query_messages_by_seq(
+                    conversation_id,
+                    user_id,
+                    anchor_seq - 1,
+                    None,
+                    after_count as i32 + 1,
+                )
+                .await
+                .map_err(|e| anyhow!("Failed to query messages after anchor: {}", e))?,
+            None => Vec::new(),
+        };
+        let has_newer = newer.len() > after_count;
+        newer.truncate(after_count);
+
+        let mut messages = older;
+        messages.extend(newer);
+
+        let prev_cursor = if has_older {
+            history_cursor(messages.first(), HistoryDirection::Older)
+        } else {
+            String::new()
+        };
+        let next_cursor = if has_newer {
+            history_cursor(messages.last(), HistoryDirection::Newer)
+        } else {
+            String::new()
+        };
+
+        Ok(QueryMessagesResult {
+            messages,
+            has_more: !next_cursor.is_empty() || !prev_cursor.is_empty(),
+            next_cursor,
+            prev_cursor,
+            total_size: UNKNOWN_TOTAL,
+            total_size_approximate: false,
+        })
+    }
+
+    /// 单页条数限制（未指定时使用默认值，超过配置上限时截断）
+    fn page_limit(&self) -> PageLimit {
+        PageLimit::new(DEFAULT_PAGE_SIZE, self.config.max_page_size.max(1) as usize)
+    }
+
+    /// 将用户的历史消息可见边界换算为 seq 下界（`seq > 下界` 的消息可见）
+    ///
+    /// # 返回
+    /// * `Ok(Some(0))` - 不限制
+    /// * `Ok(None)` - 可见边界之后没有消息
+    async fn visible_after_seq(
+        &self,
+        conversation_id: &str,
+        viewer: Option<&HistoryViewer>,
+    ) -> Result<Option<i64>> {
+        let Some(visible_from) = self.history_visible_from(conversation_id, viewer).await? else {
+            return Ok(Some(0));
+        };
+        let first_seq = self
+            .storage
+            .first_seq_since(conversation_id, visible_from)
+            .await
+            .map_err(|e| anyhow!("Failed to resolve history visibility seq: {}", e))?;
+        Ok(first_seq.map(|seq| seq - 1))
+    }
+
+    /// 查询用户的历史消息可见边界（未提供用户时不限制）
+    async fn history_visible_from(
+        &self,
+        conversation_id: &str,
+        viewer: Option<&HistoryViewer>,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let Some(viewer) = viewer.filter(|viewer| !viewer.user_id.is_empty()) else {
+            return Ok(None);
+        };
+        self.storage
+            .history_visible_from(&viewer.tenant_id, conversation_id, &viewer.user_id)
+            .await
+            .map_err(|e| anyhow!("Failed to query history visibility: {}", e))
+    }
+
     async fn query_from_storage(
         &self,
         conversation_id: &str,
@@ -311,7 +610,7 @@ impl MessageStorageDomainService {
         end_time: Option<DateTime<Utc>>,
         limit: i32,
     ) -> Result<Vec<Message>> {
-        let limit = limit.clamp(1, self.config.max_page_size);
+        let limit = self.page_limit().clamp(limit as i64) as i32;
         self.storage
             .search_messages(filters, start_time, end_time, limit)
             .await
@@ -742,3 +1041,533 @@ impl MessageStorageDomainService {
         Ok(cleared_count)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONVERSATION_ID: &str = "conv-1";
+
+    fn base_time() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    /// seq 为 `seq` 的消息，发送时间为基准时间之后 `seq` 分钟
+    fn message_at(seq: i64) -> Message {
+        let ts = base_time() + Duration::minutes(seq);
+        Message {
+            server_id: format!("msg-{}", seq),
+            conversation_id: CONVERSATION_ID.to_string(),
+            timestamp: Some(Timestamp {
+                seconds: ts.timestamp(),
+                nanos: 0,
+            }),
+            extra: HashMap::from([("seq".to_string(), seq.to_string())]),
+            ..Default::default()
+        }
+    }
+
+    fn seq_of(message: &Message) -> i64 {
+        extract_seq_from_message(message).unwrap_or_default()
+    }
+
+    fn time_of(message: &Message) -> DateTime<Utc> {
+        message
+            .timestamp
+            .as_ref()
+            .and_then(timestamp_to_datetime)
+            .unwrap()
+    }
+
+    fn seqs(messages: &[Message]) -> Vec<i64> {
+        messages.iter().map(seq_of).collect()
+    }
+
+    /// 内存消息存储（单个会话，seq 从 1 开始连续）
+    struct MemoryStorage {
+        messages: Vec<Message>,
+        /// (tenant_id, conversation_id, user_id) -> 历史可见起始时间
+        visible_from: HashMap<(String, String, String), DateTime<Utc>>,
+    }
+
+    impl MemoryStorage {
+        fn new(count: i64) -> Self {
+            Self {
+                messages: (1..=count).map(message_at).collect(),
+                visible_from: HashMap::new(),
+            }
+        }
+
+        fn with_visible_from(mut self, tenant_id: &str, user_id: &str, at: DateTime<Utc>) -> Self {
+            self.visible_from.insert(
+                (
+                    tenant_id.to_string(),
+                    CONVERSATION_ID.to_string(),
+                    user_id.to_string(),
+                ),
+                at,
+            );
+            self
+        }
+
+        fn in_range(
+            &self,
+            start_time: Option<DateTime<Utc>>,
+            end_time: Option<DateTime<Utc>>,
+        ) -> impl Iterator<Item = &Message> {
+            self.messages.iter().filter(move |message| {
+                let ts = time_of(message);
+                start_time.is_none_or(|start| ts >= start) && end_time.is_none_or(|end| ts <= end)
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MessageStorage for MemoryStorage {
+        async fn store_message(&self, _message: &Message, _conversation_id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn query_messages(
+            &self,
+            _conversation_id: &str,
+            _user_id: Option<&str>,
+            start_time: Option<DateTime<Utc>>,
+            end_time: Option<DateTime<Utc>>,
+            limit: i32,
+        ) -> Result<Vec<Message>> {
+            let mut messages: Vec<Message> = self.in_range(start_time, end_time).cloned().collect();
+            messages.reverse();
+            messages.truncate(limit as usize);
+            Ok(messages)
+        }
+
+        async fn query_messages_by_seq(
+            &self,
+            _conversation_id: &str,
+            _user_id: Option<&str>,
+            after_seq: i64,
+            before_seq: Option<i64>,
+            limit: i32,
+        ) -> Result<Vec<Message>> {
+            Ok(self
+                .messages
+                .iter()
+                .filter(|m| seq_of(m) > after_seq && before_seq.is_none_or(|b| seq_of(m) < b))
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn query_messages_by_seq_desc(
+            &self,
+            _conversation_id: &str,
+            _user_id: Option<&str>,
+            before_seq: Option<i64>,
+            after_seq: i64,
+            limit: i32,
+        ) -> Result<Vec<Message>> {
+            Ok(self
+                .messages
+                .iter()
+                .rev()
+                .filter(|m| seq_of(m) > after_seq && before_seq.is_none_or(|b| seq_of(m) < b))
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn count_messages(
+            &self,
+            _conversation_id: &str,
+            _user_id: Option<&str>,
+            start_time: Option<DateTime<Utc>>,
+            end_time: Option<DateTime<Utc>>,
+        ) -> Result<i64> {
+            Ok(self.in_range(start_time, end_time).count() as i64)
+        }
+
+        async fn get_message(&self, message_id: &str) -> Result<Option<Message>> {
+            Ok(self
+                .messages
+                .iter()
+                .find(|m| m.server_id == message_id)
+                .cloned())
+        }
+
+        async fn get_last_messages(
+            &self,
+            _conversation_ids: &[String],
+        ) -> Result<HashMap<String, Message>> {
+            Ok(HashMap::new())
+        }
+
+        async fn get_message_timestamp(&self, _message_id: &str) -> Result<Option<DateTime<Utc>>> {
+            Ok(None)
+        }
+
+        async fn update_message(&self, _message_id: &str, _updates: MessageUpdate) -> Result<()> {
+            Ok(())
+        }
+
+        async fn batch_update_visibility(
+            &self,
+            _message_ids: &[String],
+            _user_id: &str,
+            _visibility: VisibilityStatus,
+        ) -> Result<usize> {
+            Ok(0)
+        }
+
+        async fn search_messages(
+            &self,
+            _filters: &[flare_proto::common::FilterExpression],
+            _start_time: Option<DateTime<Utc>>,
+            _end_time: Option<DateTime<Utc>>,
+            _limit: i32,
+        ) -> Result<Vec<Message>> {
+            Ok(Vec::new())
+        }
+
+        async fn update_message_attributes(
+            &self,
+            _message_id: &str,
+            _attributes: HashMap<String, String>,
+            _tags: Vec<String>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn list_all_tags(&self) -> Result<Vec<String>> {
+            Ok(Vec::new())
+        }
+
+        async fn history_visible_from(
+            &self,
+            tenant_id: &str,
+            conversation_id: &str,
+            user_id: &str,
+        ) -> Result<Option<DateTime<Utc>>> {
+            Ok(self
+                .visible_from
+                .get(&(
+                    tenant_id.to_string(),
+                    conversation_id.to_string(),
+                    user_id.to_string(),
+                ))
+                .copied())
+        }
+
+        async fn first_seq_since(
+            &self,
+            _conversation_id: &str,
+            since: DateTime<Utc>,
+        ) -> Result<Option<i64>> {
+            Ok(self.in_range(Some(since), None).next().map(seq_of))
+        }
+    }
+
+    fn service(storage: MemoryStorage) -> MessageStorageDomainService {
+        MessageStorageDomainService::new(
+            Arc::new(storage),
+            None,
+            None,
+            MessageStorageDomainConfig {
+                max_page_size: 100,
+                default_range_seconds: 86_400,
+            },
+        )
+    }
+
+    fn viewer(tenant_id: &str) -> HistoryViewer {
+        HistoryViewer {
+            tenant_id: tenant_id.to_string(),
+            user_id: "user-1".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_visibility_clamps_queries_for_new_member() {
+        // user-1 在 tenant-a 中的可见边界为第 6 条消息的发送时间
+        let service = service(MemoryStorage::new(10).with_visible_from(
+            "tenant-a",
+            "user-1",
+            base_time() + Duration::minutes(6),
+        ));
+        let member = viewer("tenant-a");
+
+        let by_seq = service
+            .query_messages_by_seq(CONVERSATION_ID, Some(&member), 0, None, 50)
+            .await
+            .unwrap();
+        assert_eq!(seqs(&by_seq.messages), vec![6, 7, 8, 9, 10]);
+
+        let latest = service
+            .query_latest_messages(CONVERSATION_ID, Some(&member), 50, None)
+            .await
+            .unwrap();
+        assert_eq!(seqs(&latest.messages), vec![10, 9, 8, 7, 6]);
+
+        let range = service
+            .query_messages(
+                CONVERSATION_ID,
+                Some(&member),
+                base_time().timestamp(),
+                (base_time() + Duration::minutes(20)).timestamp(),
+                50,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(range.messages.len(), 5);
+        assert!(range.messages.iter().all(|m| seq_of(m) >= 6));
+
+        // 跳转到边界之前的时间时，锚点不早于边界，且没有更早的可见消息
+        let around = service
+            .query_messages_around(CONVERSATION_ID, Some(&member), base_time(), 4)
+            .await
+            .unwrap();
+        assert_eq!(seqs(&around.messages), vec![6, 7]);
+        assert!(around.prev_cursor.is_empty());
+        assert!(!around.next_cursor.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_history_visibility_is_scoped_by_tenant_and_user() {
+        let service = service(MemoryStorage::new(10).with_visible_from(
+            "tenant-a",
+            "user-1",
+            base_time() + Duration::minutes(6),
+        ));
+
+        // 其他租户的同名用户、未提供用户时不裁剪
+        for member in [Some(viewer("tenant-b")), None] {
+            let result = service
+                .query_messages_by_seq(CONVERSATION_ID, member.as_ref(), 0, None, 50)
+                .await
+                .unwrap();
+            assert_eq!(result.messages.len(), 10);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_visibility_after_last_message_hides_everything() {
+        let service = service(MemoryStorage::new(10).with_visible_from(
+            "tenant-a",
+            "user-1",
+            base_time() + Duration::minutes(30),
+        ));
+        let member = viewer("tenant-a");
+
+        let by_seq = service
+            .query_messages_by_seq(CONVERSATION_ID, Some(&member), 0, None, 50)
+            .await
+            .unwrap();
+        assert!(by_seq.messages.is_empty());
+
+        let latest = service
+            .query_latest_messages(CONVERSATION_ID, Some(&member), 50, None)
+            .await
+            .unwrap();
+        assert!(latest.messages.is_empty());
+        assert!(!latest.has_more);
+
+        // 查询范围整体早于可见边界
+        let range = service
+            .query_messages(
+                CONVERSATION_ID,
+                Some(&member),
+                base_time().timestamp(),
+                (base_time() + Duration::minutes(20)).timestamp(),
+                50,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(range.messages.is_empty());
+        assert_eq!(range.total_size, 0);
+    }
+
+    fn cursor(raw: &str) -> Option<HistoryCursor> {
+        Some(HistoryCursor::decode(raw).expect("valid history cursor"))
+    }
+
+    #[tokio::test]
+    async fn test_latest_messages_page_backwards_and_forwards() {
+        let service = service(MemoryStorage::new(10));
+
+        // 首屏：最新 N 条降序，只有向更早翻页的游标
+        let first = service
+            .query_latest_messages(CONVERSATION_ID, None, 3, None)
+            .await
+            .unwrap();
+        assert_eq!(seqs(&first.messages), vec![10, 9, 8]);
+        assert!(first.has_more);
+        assert!(first.prev_cursor.is_empty());
+        assert_eq!(cursor(&first.next_cursor), Some(HistoryCursor::older(8)));
+
+        let second = service
+            .query_latest_messages(CONVERSATION_ID, None, 3, cursor(&first.next_cursor))
+            .await
+            .unwrap();
+        assert_eq!(seqs(&second.messages), vec![7, 6, 5]);
+        assert_eq!(cursor(&second.next_cursor), Some(HistoryCursor::older(5)));
+        assert_eq!(cursor(&second.prev_cursor), Some(HistoryCursor::newer(7)));
+
+        // 反方向翻页回到更新的消息（升序），已到最新时没有下一页
+        let back = service
+            .query_latest_messages(CONVERSATION_ID, None, 3, cursor(&second.prev_cursor))
+            .await
+            .unwrap();
+        assert_eq!(seqs(&back.messages), vec![8, 9, 10]);
+        assert!(!back.has_more);
+        assert!(back.next_cursor.is_empty());
+        assert_eq!(cursor(&back.prev_cursor), Some(HistoryCursor::older(8)));
+
+        // 剩余条数恰好等于 limit 时不再返回下一页
+        let last = service
+            .query_latest_messages(CONVERSATION_ID, None, 3, Some(HistoryCursor::older(4)))
+            .await
+            .unwrap();
+        assert_eq!(seqs(&last.messages), vec![3, 2, 1]);
+        assert!(!last.has_more);
+        assert!(last.next_cursor.is_empty());
+
+        let beyond = service
+            .query_latest_messages(CONVERSATION_ID, None, 3, Some(HistoryCursor::older(1)))
+            .await
+            .unwrap();
+        assert!(beyond.messages.is_empty());
+        assert!(beyond.next_cursor.is_empty() && beyond.prev_cursor.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_latest_messages_limit_edges() {
+        let service = service(MemoryStorage::new(120));
+
+        let one = service
+            .query_latest_messages(CONVERSATION_ID, None, 1, None)
+            .await
+            .unwrap();
+        assert_eq!(seqs(&one.messages), vec![120]);
+        assert_eq!(cursor(&one.next_cursor), Some(HistoryCursor::older(120)));
+
+        // 非正数使用默认页大小，超过上限截断为 max_page_size
+        for (limit, expected) in [
+            (0, DEFAULT_PAGE_SIZE),
+            (-5, DEFAULT_PAGE_SIZE),
+            (1_000, 100),
+        ] {
+            let result = service
+                .query_latest_messages(CONVERSATION_ID, None, limit, None)
+                .await
+                .unwrap();
+            assert_eq!(result.messages.len(), expected, "limit {}", limit);
+            assert!(result.has_more);
+        }
+
+        assert!(
+            service
+                .query_latest_messages("", None, 10, None)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_messages_around_anchor_with_cursors() {
+        let service = service(MemoryStorage::new(10));
+
+        // 锚点为不早于 at 的第一条消息，前后各取约一半
+        let around = service
+            .query_messages_around(CONVERSATION_ID, None, base_time() + Duration::minutes(5), 4)
+            .await
+            .unwrap();
+        assert_eq!(seqs(&around.messages), vec![3, 4, 5, 6]);
+        assert!(around.has_more);
+        assert_eq!(cursor(&around.prev_cursor), Some(HistoryCursor::older(3)));
+        assert_eq!(cursor(&around.next_cursor), Some(HistoryCursor::newer(6)));
+
+        // 游标可继续用于最新消息查询翻页
+        let older = service
+            .query_latest_messages(CONVERSATION_ID, None, 10, cursor(&around.prev_cursor))
+            .await
+            .unwrap();
+        assert_eq!(seqs(&older.messages), vec![2, 1]);
+        let newer = service
+            .query_latest_messages(CONVERSATION_ID, None, 10, cursor(&around.next_cursor))
+            .await
+            .unwrap();
+        assert_eq!(seqs(&newer.messages), vec![7, 8, 9, 10]);
+
+        // at 落在两条消息之间时锚定到之后的那条
+        let between = service
+            .query_messages_around(
+                CONVERSATION_ID,
+                None,
+                base_time() + Duration::minutes(5) + Duration::seconds(30),
+                2,
+            )
+            .await
+            .unwrap();
+        assert_eq!(seqs(&between.messages), vec![5, 6]);
+    }
+
+    #[tokio::test]
+    async fn test_messages_around_edges() {
+        let service = service(MemoryStorage::new(10));
+
+        // at 早于第一条消息：没有更早的上下文
+        let start = service
+            .query_messages_around(CONVERSATION_ID, None, base_time(), 4)
+            .await
+            .unwrap();
+        assert_eq!(seqs(&start.messages), vec![1, 2]);
+        assert!(start.prev_cursor.is_empty());
+        assert_eq!(cursor(&start.next_cursor), Some(HistoryCursor::newer(2)));
+
+        // at 之后没有消息：返回最后一页
+        let end = service
+            .query_messages_around(
+                CONVERSATION_ID,
+                None,
+                base_time() + Duration::minutes(30),
+                4,
+            )
+            .await
+            .unwrap();
+        assert_eq!(seqs(&end.messages), vec![7, 8, 9, 10]);
+        assert!(end.next_cursor.is_empty());
+        assert_eq!(cursor(&end.prev_cursor), Some(HistoryCursor::older(7)));
+
+        // limit 为 1 时只返回锚点，两个方向的游标仍然可用
+        let single = service
+            .query_messages_around(CONVERSATION_ID, None, base_time() + Duration::minutes(5), 1)
+            .await
+            .unwrap();
+        assert_eq!(seqs(&single.messages), vec![5]);
+        assert_eq!(cursor(&single.prev_cursor), Some(HistoryCursor::older(5)));
+        assert_eq!(cursor(&single.next_cursor), Some(HistoryCursor::newer(5)));
+
+        // 整个会话不足一页
+        let all = service
+            .query_messages_around(
+                CONVERSATION_ID,
+                None,
+                base_time() + Duration::minutes(5),
+                50,
+            )
+            .await
+            .unwrap();
+        assert_eq!(seqs(&all.messages), (1..=10).collect::<Vec<_>>());
+        assert!(!all.has_more);
+        assert!(all.prev_cursor.is_empty() && all.next_cursor.is_empty());
+
+        assert!(
+            service
+                .query_messages_around("", None, base_time(), 4)
+                .await
+                .is_err()
+        );
+    }
+}
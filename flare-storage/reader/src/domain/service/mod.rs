@@ -1,4 +1,10 @@
+pub mod message_count;
 pub mod message_storage;
+pub mod pii_hashing;
+pub mod user_purge;
+pub use message_count::MessageCountDomainService;
 pub use message_storage::{
     MessageStorageDomainConfig, MessageStorageDomainService, QueryMessagesResult,
 };
+pub use pii_hashing::{PiiField, PiiHashAlgorithm, PiiHashingPolicy, PiiPseudonymizer};
+pub use user_purge::UserPurgeDomainService;
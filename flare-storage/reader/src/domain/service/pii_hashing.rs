@@ -0,0 +1,392 @@
+//! 分析导出的 PII 字段假名化
+//!
+//! 导出（以及基于导出数据的汇总统计）前，按租户策略将发送者/接收者ID、IP 等字段替换为假名：
+//! - 字段：`sender_id`、`receiver_id` 或 `extra.<key>`（如 `extra.client_ip`）
+//! - 算法：`hmac-sha256`（默认）或 `sha256`（盐值拼接后哈希）
+//! - 盐值：由 [`KmsProvider`] 按租户提供的数据密钥，假名中携带密钥ID，密钥轮换后历史导出仍可追溯
+//!
+//! 假名格式为 `pii:{key_id}:{hex}`，同一租户、同一密钥下相同的原值得到相同的假名，可用于关联分析。
+//! 法律要求追溯身份时，持有密钥访问权限的调用方通过 [`PiiPseudonymizer::reidentify`]
+//! 校验候选ID与假名是否匹配，每次追溯都会写入审计日志。
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow};
+use flare_im_core::encryption::{DataKey, KmsProvider};
+use flare_proto::common::Message;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 假名前缀
+const PSEUDONYM_PREFIX: &str = "pii:";
+/// 假名摘要长度（字节）
+const PSEUDONYM_DIGEST_LEN: usize = 16;
+/// `extra` 字段前缀
+const EXTRA_FIELD_PREFIX: &str = "extra.";
+/// 消息未携带租户时使用的租户ID
+const DEFAULT_TENANT_ID: &str = "0";
+
+/// 需要假名化的字段
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PiiField {
+    SenderId,
+    ReceiverId,
+    /// `extra` 中的键（如 `client_ip`、`device_id`）
+    Extra(String),
+}
+
+impl PiiField {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "sender_id" => Ok(Self::SenderId),
+            "receiver_id" => Ok(Self::ReceiverId),
+            other => match other.strip_prefix(EXTRA_FIELD_PREFIX) {
+                Some(key) if !key.is_empty() => Ok(Self::Extra(key.to_string())),
+                _ => Err(anyhow!("unsupported PII field: {other}")),
+            },
+        }
+    }
+}
+
+/// 假名化算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PiiHashAlgorithm {
+    /// HMAC-SHA256（以数据密钥为 HMAC 密钥）
+    #[default]
+    HmacSha256,
+    /// SHA256(盐值 || 原值)
+    Sha256,
+}
+
+impl PiiHashAlgorithm {
+    fn digest(self, key: &[u8], value: &str) -> Vec<u8> {
+        match self {
+            Self::HmacSha256 => {
+                let mut mac =
+                    HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+                mac.update(value.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }
+            Self::Sha256 => {
+                let mut hasher = Sha256::new();
+                hasher.update(key);
+                hasher.update(value.as_bytes());
+                hasher.finalize().to_vec()
+            }
+        }
+    }
+}
+
+/// 租户的假名化策略
+#[derive(Debug, Clone, Default)]
+pub struct PiiHashingPolicy {
+    pub fields: Vec<PiiField>,
+    pub algorithm: PiiHashAlgorithm,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolicyEntry {
+    #[serde(default)]
+    fields: Vec<String>,
+    #[serde(default)]
+    algorithm: PiiHashAlgorithm,
+}
+
+impl TryFrom<PolicyEntry> for PiiHashingPolicy {
+    type Error = anyhow::Error;
+
+    fn try_from(entry: PolicyEntry) -> Result<Self> {
+        Ok(Self {
+            fields: entry
+                .fields
+                .iter()
+                .map(|field| PiiField::parse(field))
+                .collect::<Result<_>>()?,
+            algorithm: entry.algorithm,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PolicyFile {
+    #[serde(default)]
+    default: Option<PolicyEntry>,
+    #[serde(default)]
+    tenants: HashMap<String, PolicyEntry>,
+}
+
+/// PII 假名化服务
+pub struct PiiPseudonymizer {
+    kms: Arc<dyn KmsProvider>,
+    default_policy: Option<PiiHashingPolicy>,
+    tenant_policies: HashMap<String, PiiHashingPolicy>,
+}
+
+impl PiiPseudonymizer {
+    pub fn new(kms: Arc<dyn KmsProvider>) -> Self {
+        Self {
+            kms,
+            default_policy: None,
+            tenant_policies: HashMap::new(),
+        }
+    }
+
+    /// 从策略文件加载
+    ///
+    /// ```toml
+    /// [default]
+    /// fields = ["sender_id", "receiver_id", "extra.client_ip"]
+    ///
+    /// [tenants.tenant-a]
+    /// fields = ["sender_id", "receiver_id", "extra.client_ip", "extra.device_id"]
+    /// algorithm = "sha256"
+    /// ```
+    pub fn from_policy_file(kms: Arc<dyn KmsProvider>, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read PII policy file: {}", path.display()))?;
+        Self::from_toml(kms, &content)
+    }
+
+    /// 从 TOML 内容加载
+    pub fn from_toml(kms: Arc<dyn KmsProvider>, content: &str) -> Result<Self> {
+        let file: PolicyFile =
+            toml::from_str(content).context("Failed to parse PII policy file")?;
+        let mut pseudonymizer = Self::new(kms);
+        if let Some(default) = file.default {
+            pseudonymizer = pseudonymizer.with_default_policy(default.try_into()?);
+        }
+        for (tenant_id, entry) in file.tenants {
+            let policy = entry
+                .try_into()
+                .with_context(|| format!("Invalid PII policy for tenant {tenant_id}"))?;
+            pseudonymizer = pseudonymizer.with_tenant_policy(tenant_id, policy);
+        }
+        Ok(pseudonymizer)
+    }
+
+    /// 未单独配置策略的租户使用的策略
+    pub fn with_default_policy(mut self, policy: PiiHashingPolicy) -> Self {
+        self.default_policy = Some(policy);
+        self
+    }
+
+    pub fn with_tenant_policy(
+        mut self,
+        tenant_id: impl Into<String>,
+        policy: PiiHashingPolicy,
+    ) -> Self {
+        self.tenant_policies.insert(tenant_id.into(), policy);
+        self
+    }
+
+    /// 租户生效的策略（未配置时不做假名化）
+    pub fn policy(&self, tenant_id: &str) -> Option<&PiiHashingPolicy> {
+        self.tenant_policies
+            .get(tenant_id)
+            .or(self.default_policy.as_ref())
+    }
+
+    /// 按消息所属租户的策略假名化消息字段，返回替换的字段数
+    pub async fn pseudonymize_message(&self, message: &mut Message) -> Result<usize> {
+        let tenant_id = message
+            .tenant
+            .as_ref()
+            .map(|tenant| tenant.tenant_id.as_str())
+            .filter(|tenant_id| !tenant_id.is_empty())
+            .unwrap_or(DEFAULT_TENANT_ID)
+            .to_string();
+        let Some(policy) = self.policy(&tenant_id) else {
+            return Ok(0);
+        };
+        let key = self.kms.active_key(&tenant_id).await?;
+
+        let mut replaced = 0;
+        for field in &policy.fields {
+            let value = match field {
+                PiiField::SenderId => Some(&mut message.sender_id),
+                PiiField::ReceiverId => Some(&mut message.receiver_id),
+                PiiField::Extra(extra_key) => message.extra.get_mut(extra_key),
+            };
+            if let Some(value) = value.filter(|value| !value.is_empty()) {
+                *value = pseudonym(policy.algorithm, &key, value);
+                replaced += 1;
+            }
+        }
+        Ok(replaced)
+    }
+
+    /// 计算租户当前密钥下的假名
+    pub async fn pseudonymize(&self, tenant_id: &str, value: &str) -> Result<String> {
+        let algorithm = self
+            .policy(tenant_id)
+            .map(|policy| policy.algorithm)
+            .unwrap_or_default();
+        let key = self.kms.active_key(tenant_id).await?;
+        Ok(pseudonym(algorithm, &key, value))
+    }
+
+    /// 身份追溯：返回与假名匹配的候选原值（使用假名中记录的密钥，须属于该租户）
+    ///
+    /// 调用方负责校验追溯请求的授权，`requested_by`（审批人或工单号）写入审计日志
+    pub async fn reidentify(
+        &self,
+        tenant_id: &str,
+        pseudonym_value: &str,
+        candidates: &[&str],
+        requested_by: &str,
+    ) -> Result<Option<String>> {
+        let (key_id, _) = pseudonym_value
+            .strip_prefix(PSEUDONYM_PREFIX)
+            .and_then(|rest| rest.rsplit_once(':'))
+            .ok_or_else(|| anyhow!("malformed pseudonym"))?;
+        let key = self.kms.key_by_id(key_id).await?;
+        if key.tenant_id != tenant_id {
+            warn!(
+                tenant_id = %tenant_id,
+                key_id = %key_id,
+                requested_by = %requested_by,
+                "Rejected PII re-identification with another tenant's key"
+            );
+            return Err(anyhow!("pseudonym does not belong to tenant {tenant_id}"));
+        }
+        let algorithm = self
+            .policy(tenant_id)
+            .map(|policy| policy.algorithm)
+            .unwrap_or_default();
+
+        let matched = candidates
+            .iter()
+            .find(|candidate| pseudonym(algorithm, &key, candidate) == pseudonym_value)
+            .map(|candidate| candidate.to_string());
+        info!(
+            tenant_id = %tenant_id,
+            key_id = %key_id,
+            requested_by = %requested_by,
+            candidates = candidates.len(),
+            matched = matched.is_some(),
+            "PII re-identification performed"
+        );
+        Ok(matched)
+    }
+}
+
+fn pseudonym(algorithm: PiiHashAlgorithm, key: &DataKey, value: &str) -> String {
+    let digest = algorithm.digest(&key.key, value);
+    format!(
+        "{PSEUDONYM_PREFIX}{}:{}",
+        key.key_id,
+        hex::encode(&digest[..PSEUDONYM_DIGEST_LEN])
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use flare_im_core::encryption::LocalKeyFileKms;
+
+    use super::*;
+
+    const KEY_FILE: &str = r#"
+        default_tenant = "0"
+
+        [[keys]]
+        key_id = "t1-v1"
+        tenant_id = "t1"
+        key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8="
+
+        [[keys]]
+        key_id = "t2-v1"
+        tenant_id = "t2"
+        key = "HxwdHhscGhkYFxYVFBMSERAPDg0MCwoJCAcGBQQDAgE="
+
+        [[keys]]
+        key_id = "default-v1"
+        tenant_id = "0"
+        key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8="
+    "#;
+
+    const POLICY_FILE: &str = r#"
+        [default]
+        fields = ["sender_id"]
+
+        [tenants.t1]
+        fields = ["sender_id", "receiver_id", "extra.client_ip"]
+    "#;
+
+    fn pseudonymizer() -> PiiPseudonymizer {
+        let kms = Arc::new(LocalKeyFileKms::from_toml(KEY_FILE).unwrap());
+        PiiPseudonymizer::from_toml(kms, POLICY_FILE).unwrap()
+    }
+
+    fn message(tenant_id: &str) -> Message {
+        Message {
+            sender_id: "alice".to_string(),
+            receiver_id: "bob".to_string(),
+            extra: HashMap::from([("client_ip".to_string(), "10.0.0.1".to_string())]),
+            tenant: Some(flare_proto::common::TenantContext {
+                tenant_id: tenant_id.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pseudonymize_message_by_tenant_policy() {
+        let pseudonymizer = pseudonymizer();
+
+        let mut t1 = message("t1");
+        assert_eq!(
+            pseudonymizer.pseudonymize_message(&mut t1).await.unwrap(),
+            3
+        );
+        assert!(t1.sender_id.starts_with("pii:t1-v1:"));
+        assert_ne!(t1.sender_id, t1.receiver_id);
+        assert!(t1.extra["client_ip"].starts_with("pii:t1-v1:"));
+        assert_eq!(
+            t1.sender_id,
+            pseudonymizer.pseudonymize("t1", "alice").await.unwrap()
+        );
+
+        // 未单独配置的租户使用默认策略，不同租户的假名互不相同
+        let mut t2 = message("t2");
+        assert_eq!(
+            pseudonymizer.pseudonymize_message(&mut t2).await.unwrap(),
+            1
+        );
+        assert!(t2.sender_id.starts_with("pii:t2-v1:"));
+        assert_eq!(t2.receiver_id, "bob");
+        assert_eq!(t2.extra["client_ip"], "10.0.0.1");
+    }
+
+    #[tokio::test]
+    async fn test_reidentify_requires_matching_tenant() {
+        let pseudonymizer = pseudonymizer();
+        let value = pseudonymizer.pseudonymize("t1", "alice").await.unwrap();
+
+        let matched = pseudonymizer
+            .reidentify("t1", &value, &["bob", "alice"], "ticket-1")
+            .await
+            .unwrap();
+        assert_eq!(matched.as_deref(), Some("alice"));
+        assert!(
+            pseudonymizer
+                .reidentify("t2", &value, &["alice"], "ticket-1")
+                .await
+                .is_err()
+        );
+        assert!(
+            pseudonymizer
+                .reidentify("t1", "alice", &["alice"], "ticket-1")
+                .await
+                .is_err()
+        );
+    }
+}
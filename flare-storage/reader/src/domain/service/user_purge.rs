@@ -0,0 +1,447 @@
+//! 用户数据删除领域服务 - 被遗忘权（Right to Erasure）
+//!
+//! 任务按 [`UserPurgeStep`] 的顺序逐个存储清除用户数据，每个步骤分批执行，每批完成后持久化进度与计数；
+//! 已清除的数据不会再被匹配，因此任务中断后从当前步骤重新执行是幂等的。
+//! 任务提交、每个步骤完成以及任务结束时都会写入审计日志，审计写入失败时任务失败，重试后从未审计的步骤续跑。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use tracing::{info, instrument, warn};
+
+use crate::domain::model::{
+    UserPurgeAuditAction, UserPurgeAuditEntry, UserPurgeJob, UserPurgeJobStatus, UserPurgeMode,
+    UserPurgeStep,
+};
+use crate::domain::repository::UserPurgeRepository;
+
+/// 每批处理的记录数（默认值）
+const DEFAULT_BATCH_SIZE: i64 = 500;
+
+/// 用户数据删除领域服务
+pub struct UserPurgeDomainService {
+    repo: Arc<dyn UserPurgeRepository>,
+    batch_size: i64,
+}
+
+impl UserPurgeDomainService {
+    pub fn new(repo: Arc<dyn UserPurgeRepository>) -> Self {
+        Self {
+            repo,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// 提交删除任务（由后台执行）
+    #[instrument(skip(self))]
+    pub async fn submit(
+        &self,
+        tenant_id: &str,
+        user_id: &str,
+        mode: UserPurgeMode,
+        requested_by: Option<String>,
+    ) -> Result<UserPurgeJob> {
+        if user_id.is_empty() {
+            return Err(anyhow!("user_id is required"));
+        }
+
+        let now = Utc::now();
+        let job = UserPurgeJob {
+            job_id: format!("purge-{}", uuid::Uuid::new_v4()),
+            tenant_id: tenant_id.to_string(),
+            user_id: user_id.to_string(),
+            mode,
+            status: UserPurgeJobStatus::Pending,
+            current_step: UserPurgeStep::Messages,
+            report: HashMap::new(),
+            requested_by,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+        };
+        self.repo.create_job(&job).await?;
+        self.audit(
+            &job,
+            UserPurgeAuditAction::Submitted,
+            None,
+            0,
+            Some(format!(
+                "mode={}, requested_by={}",
+                job.mode.as_str(),
+                job.requested_by.as_deref().unwrap_or("")
+            )),
+        )
+        .await?;
+
+        info!(job_id = %job.job_id, mode = job.mode.as_str(), "User purge job submitted");
+        Ok(job)
+    }
+
+    /// 查询任务（进度与完成报告）
+    pub async fn get_job(&self, job_id: &str) -> Result<Option<UserPurgeJob>> {
+        self.repo.get_job(job_id).await
+    }
+
+    /// 将失败的任务重新置为待执行（保留进度，从中断的步骤续跑）
+    pub async fn retry(&self, job_id: &str) -> Result<()> {
+        let mut job = self
+            .repo
+            .get_job(job_id)
+            .await?
+            .ok_or_else(|| anyhow!("user purge job {} not found", job_id))?;
+        if job.status != UserPurgeJobStatus::Failed {
+            return Err(anyhow!(
+                "user purge job {} is {}, only failed jobs can be retried",
+                job_id,
+                job.status.as_str()
+            ));
+        }
+
+        job.status = UserPurgeJobStatus::Pending;
+        job.last_error = None;
+        self.repo.update_job(&job).await?;
+        self.audit(&job, UserPurgeAuditAction::Retried, None, 0, None)
+            .await
+    }
+
+    /// 执行所有待执行或中断的任务
+    pub async fn resume_pending(&self) -> Result<usize> {
+        let jobs = self.repo.list_resumable_jobs(10).await?;
+        let count = jobs.len();
+        for job in jobs {
+            let job_id = job.job_id.clone();
+            if let Err(err) = self.run_job(job).await {
+                warn!(job_id = %job_id, error = %err, "User purge job failed");
+            }
+        }
+        Ok(count)
+    }
+
+    /// 执行单个删除任务，失败时记录错误并标记为失败
+    #[instrument(skip(self, job), fields(job_id = %job.job_id))]
+    pub async fn run_job(&self, mut job: UserPurgeJob) -> Result<UserPurgeJob> {
+        let result = self.process(&mut job).await;
+
+        match result {
+            Ok(()) => {
+                job.status = UserPurgeJobStatus::Completed;
+                job.last_error = None;
+                job.completed_at = Some(Utc::now());
+            }
+            Err(ref err) => {
+                job.status = UserPurgeJobStatus::Failed;
+                job.last_error = Some(err.to_string());
+            }
+        }
+        self.repo.update_job(&job).await?;
+
+        match result {
+            Ok(()) => {
+                let total = job.report.values().sum();
+                self.audit(&job, UserPurgeAuditAction::Completed, None, total, None)
+                    .await?;
+                info!(
+                    job_id = %job.job_id,
+                    report = ?job.report,
+                    "User purge job completed"
+                );
+                Ok(job)
+            }
+            Err(err) => {
+                // 失败审计写入失败时只记录日志，保留原始错误
+                if let Err(audit_err) = self
+                    .audit(
+                        &job,
+                        UserPurgeAuditAction::Failed,
+                        Some(job.current_step),
+                        0,
+                        Some(err.to_string()),
+                    )
+                    .await
+                {
+                    warn!(
+                        job_id = %job.job_id,
+                        error = %audit_err,
+                        "Failed to audit user purge failure"
+                    );
+                }
+                Err(err)
+            }
+        }
+    }
+
+    async fn process(&self, job: &mut UserPurgeJob) -> Result<()> {
+        job.status = UserPurgeJobStatus::Running;
+        self.repo.update_job(job).await?;
+
+        while job.current_step != UserPurgeStep::Done {
+            let step = job.current_step;
+            let mut step_total = 0i64;
+            loop {
+                let affected = self
+                    .repo
+                    .purge_batch(
+                        &job.tenant_id,
+                        &job.user_id,
+                        step,
+                        job.mode,
+                        self.batch_size,
+                    )
+                    .await?;
+                if affected == 0 {
+                    break;
+                }
+                job.record(step, affected);
+                step_total += affected as i64;
+
+                // 每批持久化计数，便于查询进度
+                self.repo.update_job(job).await?;
+            }
+
+            // 先写审计再推进步骤，避免步骤完成但审计缺失
+            self.audit(
+                job,
+                UserPurgeAuditAction::StepCompleted,
+                Some(step),
+                step_total,
+                None,
+            )
+            .await?;
+            job.current_step = step.next();
+            self.repo.update_job(job).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 写入审计记录
+    async fn audit(
+        &self,
+        job: &UserPurgeJob,
+        action: UserPurgeAuditAction,
+        step: Option<UserPurgeStep>,
+        affected_count: i64,
+        detail: Option<String>,
+    ) -> Result<()> {
+        let entry = UserPurgeAuditEntry {
+            job_id: job.job_id.clone(),
+            tenant_id: job.tenant_id.clone(),
+            user_id: job.user_id.clone(),
+            action,
+            step,
+            affected_count,
+            detail,
+        };
+        self.repo.append_audit(&entry).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    type RecordKey = (String, String, UserPurgeStep);
+
+    /// 内存仓储：按 (租户, 用户, 步骤) 记录待清除的数据条数
+    #[derive(Default)]
+    struct MemoryPurgeRepository {
+        jobs: Mutex<HashMap<String, UserPurgeJob>>,
+        audits: Mutex<Vec<UserPurgeAuditEntry>>,
+        records: Mutex<HashMap<RecordKey, u64>>,
+        /// 下一次执行该步骤时失败（模拟存储故障）
+        fail_once: Mutex<Option<UserPurgeStep>>,
+    }
+
+    impl MemoryPurgeRepository {
+        fn seed(&self, tenant_id: &str, user_id: &str, step: UserPurgeStep, count: u64) {
+            self.records
+                .lock()
+                .unwrap()
+                .insert((tenant_id.to_string(), user_id.to_string(), step), count);
+        }
+
+        fn remaining(&self, tenant_id: &str, user_id: &str, step: UserPurgeStep) -> u64 {
+            self.records
+                .lock()
+                .unwrap()
+                .get(&(tenant_id.to_string(), user_id.to_string(), step))
+                .copied()
+                .unwrap_or(0)
+        }
+
+        fn audit_actions(&self) -> Vec<UserPurgeAuditAction> {
+            self.audits
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|entry| entry.action)
+                .collect()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl UserPurgeRepository for MemoryPurgeRepository {
+        async fn create_job(&self, job: &UserPurgeJob) -> Result<()> {
+            self.jobs
+                .lock()
+                .unwrap()
+                .insert(job.job_id.clone(), job.clone());
+            Ok(())
+        }
+
+        async fn get_job(&self, job_id: &str) -> Result<Option<UserPurgeJob>> {
+            Ok(self.jobs.lock().unwrap().get(job_id).cloned())
+        }
+
+        async fn list_resumable_jobs(&self, limit: i64) -> Result<Vec<UserPurgeJob>> {
+            let mut jobs: Vec<_> = self
+                .jobs
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|job| {
+                    matches!(
+                        job.status,
+                        UserPurgeJobStatus::Pending | UserPurgeJobStatus::Running
+                    )
+                })
+                .cloned()
+                .collect();
+            jobs.sort_by_key(|job| job.created_at);
+            jobs.truncate(limit as usize);
+            Ok(jobs)
+        }
+
+        async fn update_job(&self, job: &UserPurgeJob) -> Result<()> {
+            self.create_job(job).await
+        }
+
+        async fn append_audit(&self, entry: &UserPurgeAuditEntry) -> Result<()> {
+            self.audits.lock().unwrap().push(entry.clone());
+            Ok(())
+        }
+
+        async fn purge_batch(
+            &self,
+            tenant_id: &str,
+            user_id: &str,
+            step: UserPurgeStep,
+            _mode: UserPurgeMode,
+            limit: i64,
+        ) -> Result<u64> {
+            let mut fail_once = self.fail_once.lock().unwrap();
+            if *fail_once == Some(step) {
+                *fail_once = None;
+                return Err(anyhow!("store unavailable"));
+            }
+            let mut records = self.records.lock().unwrap();
+            let Some(remaining) =
+                records.get_mut(&(tenant_id.to_string(), user_id.to_string(), step))
+            else {
+                return Ok(0);
+            };
+            let affected = (*remaining).min(limit as u64);
+            *remaining -= affected;
+            Ok(affected)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_purge_is_scoped_to_tenant_and_user() {
+        let repo = Arc::new(MemoryPurgeRepository::default());
+        repo.seed("tenant-a", "user-1", UserPurgeStep::Messages, 5);
+        repo.seed("tenant-a", "user-1", UserPurgeStep::ReadRecords, 3);
+        repo.seed("tenant-b", "user-1", UserPurgeStep::Messages, 4);
+        repo.seed("tenant-a", "user-2", UserPurgeStep::Messages, 2);
+        let service = UserPurgeDomainService::new(repo.clone()).with_batch_size(2);
+
+        let job = service
+            .submit("tenant-a", "user-1", UserPurgeMode::Delete, None)
+            .await
+            .unwrap();
+        let job = service.run_job(job).await.unwrap();
+
+        assert_eq!(job.status, UserPurgeJobStatus::Completed);
+        assert_eq!(job.current_step, UserPurgeStep::Done);
+        assert_eq!(job.report.get("messages"), Some(&5));
+        assert_eq!(job.report.get("read_records"), Some(&3));
+        assert_eq!(
+            repo.remaining("tenant-a", "user-1", UserPurgeStep::Messages),
+            0
+        );
+        // 同一用户在其他租户的数据、同租户其他用户的数据不受影响
+        assert_eq!(
+            repo.remaining("tenant-b", "user-1", UserPurgeStep::Messages),
+            4
+        );
+        assert_eq!(
+            repo.remaining("tenant-a", "user-2", UserPurgeStep::Messages),
+            2
+        );
+
+        // 提交、每个步骤完成、任务完成各一条审计
+        let actions = repo.audit_actions();
+        assert_eq!(actions.first(), Some(&UserPurgeAuditAction::Submitted));
+        assert_eq!(actions.last(), Some(&UserPurgeAuditAction::Completed));
+        assert_eq!(
+            actions
+                .iter()
+                .filter(|action| **action == UserPurgeAuditAction::StepCompleted)
+                .count(),
+            5
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_job_resumes_from_current_step_without_double_counting() {
+        let repo = Arc::new(MemoryPurgeRepository::default());
+        repo.seed("tenant-a", "user-1", UserPurgeStep::Messages, 3);
+        repo.seed("tenant-a", "user-1", UserPurgeStep::Cursors, 2);
+        *repo.fail_once.lock().unwrap() = Some(UserPurgeStep::Cursors);
+        let service = UserPurgeDomainService::new(repo.clone()).with_batch_size(2);
+
+        let job = service
+            .submit("tenant-a", "user-1", UserPurgeMode::Anonymize, None)
+            .await
+            .unwrap();
+        let job_id = job.job_id.clone();
+        assert!(service.run_job(job).await.is_err());
+
+        let failed = service.get_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(failed.status, UserPurgeJobStatus::Failed);
+        assert_eq!(failed.current_step, UserPurgeStep::Cursors);
+        assert_eq!(failed.report.get("messages"), Some(&3));
+        // 失败的任务不会被后台循环自动续跑
+        assert_eq!(service.resume_pending().await.unwrap(), 0);
+
+        service.retry(&job_id).await.unwrap();
+        assert_eq!(service.resume_pending().await.unwrap(), 1);
+        let completed = service.get_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(completed.status, UserPurgeJobStatus::Completed);
+        assert_eq!(completed.report.get("messages"), Some(&3));
+        assert_eq!(completed.report.get("cursors"), Some(&2));
+
+        // 后台循环再次执行时不会重复处理已完成的任务
+        assert_eq!(service.resume_pending().await.unwrap(), 0);
+        let actions = repo.audit_actions();
+        assert!(actions.contains(&UserPurgeAuditAction::Failed));
+        assert!(actions.contains(&UserPurgeAuditAction::Retried));
+        assert_eq!(
+            actions
+                .iter()
+                .filter(|action| **action == UserPurgeAuditAction::Completed)
+                .count(),
+            1
+        );
+    }
+}
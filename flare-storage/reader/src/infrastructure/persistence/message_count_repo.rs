@@ -0,0 +1,219 @@
+//! 会话消息计数仓储实现
+//!
+//! 计数快照保存在 `conversation_message_counts` 表；带上限的计数使用子查询 `LIMIT`，
+//! 扫描行数不超过上限，超大会话的查询代价有界。
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, Row};
+
+use crate::domain::model::MessageCountSnapshot;
+use crate::domain::repository::MessageCountRepository;
+
+pub struct PostgresMessageCountRepository {
+    pool: Arc<Pool<Postgres>>,
+}
+
+impl PostgresMessageCountRepository {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl MessageCountRepository for PostgresMessageCountRepository {
+    async fn get_snapshot(&self, conversation_id: &str) -> Result<Option<MessageCountSnapshot>> {
+        let row = sqlx::query(
+            r#"
+            SELECT conversation_id, message_count, max_seq, reconciled_at
+            FROM conversation_message_counts
+            WHERE conversation_id = $1
+            "#,
+        )
+        .bind(conversation_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .context("Failed to get message count snapshot")?;
+
+        Ok(row.map(|row| MessageCountSnapshot {
+            conversation_id: row.get("conversation_id"),
+            message_count: row.get("message_count"),
+            max_seq: row.get("max_seq"),
+            reconciled_at: row.get("reconciled_at"),
+        }))
+    }
+
+    async fn save_snapshot(&self, snapshot: &MessageCountSnapshot) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO conversation_message_counts
+                (conversation_id, message_count, max_seq, reconciled_at, updated_at)
+            VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP)
+            ON CONFLICT (conversation_id) DO UPDATE
+            SET message_count = EXCLUDED.message_count,
+                max_seq = EXCLUDED.max_seq,
+                reconciled_at = EXCLUDED.reconciled_at,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(&snapshot.conversation_id)
+        .bind(snapshot.message_count)
+        .bind(snapshot.max_seq)
+        .bind(snapshot.reconciled_at)
+        .execute(self.pool.as_ref())
+        .await
+        .context("Failed to save message count snapshot")?;
+
+        Ok(())
+    }
+
+    async fn advance_snapshot(
+        &self,
+        conversation_id: &str,
+        from_seq: i64,
+        to_seq: i64,
+        delta: i64,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE conversation_message_counts
+            SET message_count = message_count + $4, max_seq = $3, updated_at = CURRENT_TIMESTAMP
+            WHERE conversation_id = $1 AND max_seq = $2
+            "#,
+        )
+        .bind(conversation_id)
+        .bind(from_seq)
+        .bind(to_seq)
+        .bind(delta)
+        .execute(self.pool.as_ref())
+        .await
+        .context("Failed to advance message count snapshot")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_snapshot(&self, conversation_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM conversation_message_counts WHERE conversation_id = $1")
+            .bind(conversation_id)
+            .execute(self.pool.as_ref())
+            .await
+            .context("Failed to delete message count snapshot")?;
+
+        Ok(())
+    }
+
+    async fn list_stale_snapshots(&self, before: DateTime<Utc>, limit: i64) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT conversation_id FROM conversation_message_counts
+            WHERE reconciled_at < $1
+            ORDER BY reconciled_at ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(before)
+        .bind(limit)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .context("Failed to list stale message count snapshots")?;
+
+        Ok(rows.iter().map(|row| row.get("conversation_id")).collect())
+    }
+
+    async fn count_after_seq_capped(
+        &self,
+        conversation_id: &str,
+        after_seq: i64,
+        cap: i64,
+    ) -> Result<(i64, Option<i64>)> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS count, MAX(seq) AS max_seq FROM (
+                SELECT seq FROM messages
+                WHERE conversation_id = $1 AND seq > $2
+                ORDER BY seq ASC
+                LIMIT $3
+            ) capped
+            "#,
+        )
+        .bind(conversation_id)
+        .bind(after_seq)
+        .bind(cap)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .context("Failed to count messages after seq")?;
+
+        Ok((row.get("count"), row.get("max_seq")))
+    }
+
+    async fn count_range_capped(
+        &self,
+        conversation_id: &str,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        cap: i64,
+    ) -> Result<i64> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS count FROM (
+                SELECT 1 FROM messages
+                WHERE conversation_id = $1 AND timestamp >= $2 AND timestamp <= $3
+                LIMIT $4
+            ) capped
+            "#,
+        )
+        .bind(conversation_id)
+        .bind(start_time)
+        .bind(end_time)
+        .bind(cap)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .context("Failed to count messages in range")?;
+
+        Ok(row.get("count"))
+    }
+
+    async fn seq_bounds(
+        &self,
+        conversation_id: &str,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> Result<Option<(i64, i64)>> {
+        let row = sqlx::query(
+            r#"
+            SELECT MIN(seq) AS min_seq, MAX(seq) AS max_seq FROM messages
+            WHERE conversation_id = $1 AND seq IS NOT NULL
+              AND ($2::timestamptz IS NULL OR timestamp >= $2)
+              AND ($3::timestamptz IS NULL OR timestamp <= $3)
+            "#,
+        )
+        .bind(conversation_id)
+        .bind(start_time)
+        .bind(end_time)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .context("Failed to get message seq bounds")?;
+
+        let min_seq: Option<i64> = row.get("min_seq");
+        let max_seq: Option<i64> = row.get("max_seq");
+        Ok(min_seq.zip(max_seq))
+    }
+
+    async fn count_exact(&self, conversation_id: &str) -> Result<(i64, i64)> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS count, COALESCE(MAX(seq), 0) AS max_seq FROM messages
+            WHERE conversation_id = $1 AND seq IS NOT NULL
+            "#,
+        )
+        .bind(conversation_id)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .context("Failed to count conversation messages")?;
+
+        Ok((row.get("count"), row.get("max_seq")))
+    }
+}
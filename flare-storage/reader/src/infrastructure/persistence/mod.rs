@@ -1,4 +1,7 @@
+pub mod message_count_repo;
 pub mod message_state_repo;
 pub mod postgres_store;
 pub mod helpers;
 pub mod redis_cache;
+pub mod reencrypt_queue;
+pub mod user_purge_repo;
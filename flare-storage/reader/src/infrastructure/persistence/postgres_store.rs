@@ -8,7 +8,10 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use chrono::{DateTime, Utc};
+use flare_im_core::encryption::FieldEncryptor;
+use flare_im_core::utils::translation::TRANSLATION_ATTRIBUTE_PREFIX;
 use flare_im_core::utils::{datetime_to_timestamp, timestamp_to_datetime};
 use flare_proto::common::{Message, MessageStatus, VisibilityStatus};
 use prost::Message as ProstMessage;
@@ -20,11 +23,27 @@ use crate::domain::model::MessageUpdate;
 use crate::domain::repository::{MessageStorage, VisibilityStorage};
 use crate::infrastructure::persistence::redis_cache::RedisMessageCache;
 use crate::infrastructure::persistence::helpers::*;
+use crate::infrastructure::persistence::reencrypt_queue::{
+    DEFAULT_REENCRYPT_QUEUE_CAPACITY, ReencryptQueue, ReencryptTask,
+};
+
+/// 写入时未能确定租户的消息使用的租户ID（与 storage-writer 的 `extract_tenant_id` 一致）
+pub(crate) const DEFAULT_TENANT_ID: &str = "default";
+
+/// 加密存储的译文属性值前缀（`enc:` + base64 编码的加密信封）
+const SEALED_ATTRIBUTE_PREFIX: &str = "enc:";
+
+/// 内容解密失败时写入占位消息 extra 的标记键（值为 `decrypt_failed`）
+pub const CONTENT_UNAVAILABLE_EXTRA_KEY: &str = "content_unavailable";
 
 /// PostgreSQL 消息存储实现（带 Redis 缓存）
 pub struct PostgresMessageStorage {
     pool: Pool<Postgres>,
     cache: Option<Arc<RedisMessageCache>>,
+    /// 消息内容加密器（配置了密钥文件时启用）
+    encryptor: Option<Arc<FieldEncryptor>>,
+    /// 惰性重加密队列（启用加密时存在）
+    reencrypt_queue: Option<ReencryptQueue>,
 }
 
 impl PostgresMessageStorage {
@@ -53,16 +72,39 @@ impl PostgresMessageStorage {
             .await
             .context("Failed to connect to PostgreSQL")?;
 
-        // 初始化 Redis 缓存（可选）
+        let encryptor = match &config.encryption_key_file {
+            Some(path) => Some(Arc::new(
+                FieldEncryptor::from_key_file(path)
+                    .context("Failed to load message encryption key file")?,
+            )),
+            None => None,
+        };
+
+        // 初始化 Redis 缓存（可选，启用加密时缓存值同样加密）
         let cache = if let Some(redis_url) = &config.redis_url {
             let client =
                 redis::Client::open(redis_url.as_str()).context("Failed to create Redis client")?;
-            Some(Arc::new(RedisMessageCache::new(Arc::new(client), config)))
+            Some(Arc::new(
+                RedisMessageCache::new(Arc::new(client), config).with_encryptor(encryptor.clone()),
+            ))
         } else {
             None
         };
 
-        let storage = Self { pool, cache };
+        let reencrypt_queue = encryptor.as_ref().map(|encryptor| {
+            ReencryptQueue::spawn(
+                DEFAULT_REENCRYPT_QUEUE_CAPACITY,
+                encryptor.clone(),
+                pool.clone(),
+            )
+        });
+
+        let storage = Self {
+            pool,
+            cache,
+            encryptor,
+            reencrypt_queue,
+        };
 
         // 验证表结构（不创建，由 Writer 或 init.sql 创建）
         storage
@@ -209,8 +251,118 @@ impl PostgresMessageStorage {
         Ok(())
     }
 
+    /// 解密消息内容（校验密文属于该租户与消息 server_id）
+    ///
+    /// 内容需要重新加密（密钥已轮换或旧版本信封）时提交到有界重加密队列，由后台任务
+    /// 用当前密钥重新加密并写回（惰性重加密），写回以原密文作为条件，避免覆盖并发编辑
+    async fn open_content(
+        &self,
+        tenant_id: &str,
+        server_id: &str,
+        content: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        let encryptor = match self.encryptor {
+            Some(ref encryptor) if FieldEncryptor::is_encrypted(&content) => encryptor,
+            _ => return Ok(content),
+        };
+
+        let decrypted = encryptor
+            .decrypt(tenant_id, server_id, &content)
+            .await
+            .with_context(|| format!("Failed to decrypt content of message {}", server_id))?;
+
+        if decrypted.needs_reencrypt
+            && let Some(ref queue) = self.reencrypt_queue
+        {
+            queue.enqueue(ReencryptTask {
+                server_id: server_id.to_string(),
+                content,
+                field: decrypted.clone(),
+            });
+        }
+
+        Ok(decrypted.plaintext)
+    }
+
+    /// 启用加密时加密译文属性（译文与消息内容同等敏感），密文绑定到租户、消息与属性键
+    async fn seal_translation_attributes(
+        &self,
+        message_id: &str,
+        attributes: &mut HashMap<String, String>,
+    ) -> Result<()> {
+        let Some(ref encryptor) = self.encryptor else {
+            return Ok(());
+        };
+        if !attributes
+            .keys()
+            .any(|key| key.starts_with(TRANSLATION_ATTRIBUTE_PREFIX))
+        {
+            return Ok(());
+        }
+
+        let tenant_id: Option<String> =
+            sqlx::query_scalar("SELECT tenant_id FROM messages WHERE server_id = $1")
+                .bind(message_id)
+                .fetch_optional(&self.pool)
+                .await
+                .context("Failed to load message tenant for attribute encryption")?
+                .flatten();
+        let tenant_id = tenant_id.as_deref().unwrap_or(DEFAULT_TENANT_ID);
+
+        for (key, value) in attributes.iter_mut() {
+            if !key.starts_with(TRANSLATION_ATTRIBUTE_PREFIX) {
+                continue;
+            }
+            *value = seal_attribute(encryptor, tenant_id, message_id, key, value)
+                .await
+                .with_context(|| format!("Failed to encrypt attribute {}", key))?;
+        }
+        Ok(())
+    }
+
+    /// 解密译文属性，解密失败的译文直接移除（客户端回退显示原文）
+    async fn open_translation_attributes(
+        &self,
+        tenant_id: &str,
+        server_id: &str,
+        attributes: &mut HashMap<String, String>,
+    ) {
+        let sealed: Vec<String> = attributes
+            .iter()
+            .filter(|(key, value)| {
+                key.starts_with(TRANSLATION_ATTRIBUTE_PREFIX)
+                    && value.starts_with(SEALED_ATTRIBUTE_PREFIX)
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in sealed {
+            let Some(value) = attributes.remove(&key) else {
+                continue;
+            };
+            let opened = match self.encryptor {
+                Some(ref encryptor) => {
+                    open_attribute(encryptor, tenant_id, server_id, &key, &value).await
+                }
+                None => Err(anyhow::anyhow!("message encryption is not configured")),
+            };
+            match opened {
+                Ok(plaintext) => {
+                    attributes.insert(key, plaintext);
+                }
+                Err(e) => tracing::warn!(
+                    message_id = %server_id,
+                    tenant_id,
+                    attribute = %key,
+                    error = %format!("{:#}", e),
+                    "Message translation unavailable, dropping attribute"
+                ),
+            }
+        }
+    }
+
     /// 从数据库行转换为 Message protobuf
-    fn row_to_message(&self, row: &sqlx::postgres::PgRow) -> Result<Message> {
+    async fn row_to_message(&self, row: &sqlx::postgres::PgRow) -> Result<Message> {
         let server_id: String = row.get("server_id");
         let conversation_id: String = row.get("conversation_id");
         let client_msg_id: Option<String> = row.get("client_msg_id");
@@ -231,8 +383,29 @@ impl PostgresMessageStorage {
         let _updated_at: Option<DateTime<Utc>> = row.get("updated_at");
         let visibility: Option<Value> = row.get("visibility");
         let read_by: Option<Value> = row.get("read_by");
-
-        // 解析 content (MessageContent protobuf)
+        let tenant_id: Option<String> = row.get("tenant_id");
+
+        // 解析 content (MessageContent protobuf，启用加密时先解密)
+        // 单条消息解密失败时返回不含内容的占位消息（失败次数计入 field_decrypt_failures_total），
+        // 不影响同一页的其他消息
+        let tenant_id = tenant_id.as_deref().unwrap_or(DEFAULT_TENANT_ID);
+        let mut content_unavailable = false;
+        let content = match content {
+            Some(bytes) => match self.open_content(tenant_id, &server_id, bytes).await {
+                Ok(bytes) => Some(bytes),
+                Err(e) => {
+                    tracing::warn!(
+                        message_id = %server_id,
+                        tenant_id,
+                        error = %format!("{:#}", e),
+                        "Message content unavailable, returning placeholder"
+                    );
+                    content_unavailable = true;
+                    None
+                }
+            },
+            None => None,
+        };
         let content_proto = content.and_then(|bytes| ProstMessage::decode(&bytes[..]).ok());
 
         // 解析 extra JSONB
@@ -245,11 +418,20 @@ impl PostgresMessageStorage {
             }
         }
 
+        if content_unavailable {
+            extra_map.insert(
+                CONTENT_UNAVAILABLE_EXTRA_KEY.to_string(),
+                "decrypt_failed".to_string(),
+            );
+        }
+
         // 使用 helpers 模块中的函数解析 extra 字段
         let tenant = parse_tenant_from_extra(&extra_map);
         let source = parse_message_source_from_extra(&extra_map);
         let tags = parse_tags_from_extra(&extra_map);
-        let attributes = parse_attributes_from_extra(&extra_map);
+        let mut attributes = parse_attributes_from_extra(&extra_map);
+        self.open_translation_attributes(tenant_id, &server_id, &mut attributes)
+            .await;
 
         // 解析 visibility
         let mut visibility_map = HashMap::new();
@@ -307,6 +489,48 @@ impl PostgresMessageStorage {
     }
 }
 
+/// 属性密文绑定的记录ID（消息 server_id + 属性键，防止密文在消息或属性间挪用）
+fn attribute_record_id(server_id: &str, key: &str) -> String {
+    format!("{}#{}", server_id, key)
+}
+
+async fn seal_attribute(
+    encryptor: &FieldEncryptor,
+    tenant_id: &str,
+    server_id: &str,
+    key: &str,
+    value: &str,
+) -> Result<String> {
+    let sealed = encryptor
+        .encrypt(
+            tenant_id,
+            &attribute_record_id(server_id, key),
+            value.as_bytes(),
+        )
+        .await?;
+    Ok(format!(
+        "{}{}",
+        SEALED_ATTRIBUTE_PREFIX,
+        BASE64.encode(sealed)
+    ))
+}
+
+async fn open_attribute(
+    encryptor: &FieldEncryptor,
+    tenant_id: &str,
+    server_id: &str,
+    key: &str,
+    value: &str,
+) -> Result<String> {
+    let sealed = BASE64
+        .decode(&value[SEALED_ATTRIBUTE_PREFIX.len()..])
+        .context("Invalid sealed attribute encoding")?;
+    let opened = encryptor
+        .decrypt(tenant_id, &attribute_record_id(server_id, key), &sealed)
+        .await?;
+    String::from_utf8(opened.plaintext).context("Sealed attribute is not valid UTF-8")
+}
+
 #[async_trait]
 impl MessageStorage for PostgresMessageStorage {
     async fn store_message(&self, _message: &Message, _conversation_id: &str) -> Result<()> {
@@ -355,7 +579,7 @@ impl MessageStorage for PostgresMessageStorage {
                 server_id, conversation_id, client_msg_id, sender_id, content, timestamp,
                 extra, created_at, message_type, content_type, business_type,
                 status, is_recalled, recalled_at, is_burn_after_read, burn_after_seconds,
-                seq, updated_at, visibility, read_by, operations
+                seq, updated_at, visibility, read_by, operations, tenant_id
             FROM messages
             WHERE conversation_id = 
             "#,
@@ -386,7 +610,7 @@ impl MessageStorage for PostgresMessageStorage {
 
         let mut messages = Vec::with_capacity(rows.len());
         for row in rows {
-            messages.push(self.row_to_message(&row)?);
+            messages.push(self.row_to_message(&row).await?);
         }
 
         // 反转顺序，使最旧的消息在前（符合历史消息查询习惯）
@@ -430,7 +654,7 @@ impl MessageStorage for PostgresMessageStorage {
                 server_id, conversation_id, client_msg_id, sender_id, content, timestamp,
                 extra, created_at, message_type, content_type, business_type,
                 status, is_recalled, recalled_at, is_burn_after_read, burn_after_seconds,
-                seq, updated_at, visibility, read_by, operations
+                seq, updated_at, visibility, read_by, operations, tenant_id
             FROM messages
             WHERE conversation_id = 
             "#,
@@ -462,7 +686,63 @@ impl MessageStorage for PostgresMessageStorage {
 
         let mut messages = Vec::with_capacity(rows.len());
         for row in rows {
-            messages.push(self.row_to_message(&row)?);
+            messages.push(self.row_to_message(&row).await?);
+        }
+
+        Ok(messages)
+    }
+
+    async fn query_messages_by_seq_desc(
+        &self,
+        conversation_id: &str,
+        user_id: Option<&str>,
+        before_seq: Option<i64>,
+        after_seq: i64,
+        limit: i32,
+    ) -> Result<Vec<Message>> {
+        let limit = limit.min(1000).max(1);
+
+        // 倒序扫描 (conversation_id, seq) 索引，只读取需要的 N 条
+        let mut query = sqlx::QueryBuilder::new(
+            r#"
+            SELECT 
+                server_id, conversation_id, client_msg_id, sender_id, content, timestamp,
+                extra, created_at, message_type, content_type, business_type,
+                status, is_recalled, recalled_at, is_burn_after_read, burn_after_seconds,
+                seq, updated_at, visibility, read_by, operations, tenant_id
+            FROM messages
+            WHERE conversation_id = 
+            "#,
+        );
+        query.push_bind(conversation_id);
+        query.push(" AND seq > ");
+        query.push_bind(after_seq);
+
+        if let Some(before) = before_seq {
+            query.push(" AND seq < ");
+            query.push_bind(before);
+        }
+
+        // 如果提供了 user_id，过滤已删除的消息
+        if let Some(uid) = user_id {
+            query.push(" AND COALESCE((visibility->>");
+            query.push_bind(uid);
+            query.push(")::int, 0) != 2");
+        }
+
+        query.push(" ORDER BY seq DESC");
+        query.push(" LIMIT ");
+        query.push_bind(limit);
+
+        let rows = query
+            .build()
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to query messages by seq (descending)")?;
+
+        let mut messages = Vec::with_capacity(rows.len());
+        for row in rows {
+            messages.push(self.row_to_message(&row).await?);
         }
 
         Ok(messages)
@@ -480,7 +760,7 @@ impl MessageStorage for PostgresMessageStorage {
                 server_id, conversation_id, client_msg_id, sender_id, content, timestamp,
                 extra, created_at, message_type, content_type, business_type,
                 status, is_recalled, recalled_at, is_burn_after_read, burn_after_seconds,
-                seq, updated_at, visibility, read_by, operations
+                seq, updated_at, visibility, read_by, operations, tenant_id
             FROM messages
             WHERE server_id = $1
             LIMIT 1
@@ -493,7 +773,7 @@ impl MessageStorage for PostgresMessageStorage {
 
         match row {
             Some(row) => {
-                let message = self.row_to_message(&row)?;
+                let message = self.row_to_message(&row).await?;
 
                 // 回填缓存（异步，不阻塞）
                 if let Some(cache) = &self.cache {
@@ -515,6 +795,90 @@ impl MessageStorage for PostgresMessageStorage {
         }
     }
 
+    async fn get_last_messages(
+        &self,
+        conversation_ids: &[String],
+    ) -> Result<HashMap<String, Message>> {
+        if conversation_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        // 先读 Redis 中的最后一条消息视图（由 Writer 维护）
+        let mut result = HashMap::with_capacity(conversation_ids.len());
+        if let Some(cache) = &self.cache {
+            match cache.get_last_messages(conversation_ids).await {
+                Ok(cached) => result = cached,
+                Err(e) => {
+                    tracing::warn!(
+                        error = %e,
+                        "Failed to read last message view from Redis, falling back to database"
+                    );
+                }
+            }
+        }
+
+        let missing: Vec<&str> = conversation_ids
+            .iter()
+            .filter(|id| !result.contains_key(id.as_str()))
+            .map(|id| id.as_str())
+            .collect();
+        if missing.is_empty() {
+            return Ok(result);
+        }
+
+        // 未命中的会话一次性回源：DISTINCT ON 取每个会话 seq 最大的消息
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT ON (conversation_id)
+                server_id, conversation_id, client_msg_id, sender_id, content, timestamp,
+                extra, created_at, message_type, content_type, business_type,
+                status, is_recalled, recalled_at, is_burn_after_read, burn_after_seconds,
+                seq, updated_at, visibility, read_by, operations, tenant_id
+            FROM messages
+            WHERE conversation_id = ANY($1)
+            ORDER BY conversation_id, seq DESC NULLS LAST, timestamp DESC
+            "#,
+        )
+        .bind(&missing)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to query last messages")?;
+
+        let mut loaded = Vec::with_capacity(rows.len());
+        for row in rows {
+            loaded.push(self.row_to_message(&row).await?);
+        }
+
+        tracing::debug!(
+            requested = conversation_ids.len(),
+            cache_hits = result.len(),
+            loaded = loaded.len(),
+            "Loaded last messages"
+        );
+
+        // 回填视图（异步，不阻塞）
+        if let Some(cache) = &self.cache {
+            let cache_clone = Arc::clone(cache);
+            let messages_clone = loaded.clone();
+            tokio::spawn(async move {
+                for message in &messages_clone {
+                    if let Err(e) = cache_clone.cache_last_message(message).await {
+                        tracing::warn!(
+                            error = %e,
+                            conversation_id = %message.conversation_id,
+                            "Failed to cache last message to Redis (non-blocking)"
+                        );
+                    }
+                }
+            });
+        }
+
+        for message in loaded {
+            result.insert(message.conversation_id.clone(), message);
+        }
+        Ok(result)
+    }
+
     async fn get_message_timestamp(&self, message_id: &str) -> Result<Option<DateTime<Utc>>> {
         // 直接查询消息的时间戳，避免加载完整的消息内容
         let row = sqlx::query(
@@ -631,25 +995,22 @@ impl MessageStorage for PostgresMessageStorage {
             separated.push("::jsonb");
             has_updates = true;
         }
-        if let Some(attributes) = updates.attributes {
-            // 更新 extra 中的 attributes（需要合并到 extra JSONB）
-            separated.push(r#"extra = COALESCE(extra, '{}'::jsonb) || "#);
-            let attrs_json: HashMap<String, Value> = attributes
+        if updates.attributes.is_some() || updates.tags.is_some() {
+            // attributes 与 tags 都存放在 extra 中，合并为一次赋值（同一列不能在 SET 中出现两次）
+            let mut extra_json: HashMap<String, Value> = updates
+                .attributes
+                .unwrap_or_default()
                 .into_iter()
                 .map(|(k, v)| (k, Value::String(v)))
                 .collect();
-            separated.push_bind(serde_json::to_value(&attrs_json)?);
+            if let Some(tags) = updates.tags {
+                extra_json.insert("tags".to_string(), serde_json::to_value(&tags)?);
+            }
+            separated.push(r#"extra = COALESCE(extra, '{}'::jsonb) || "#);
+            separated.push_bind(serde_json::to_value(&extra_json)?);
             separated.push("::jsonb");
             has_updates = true;
         }
-        if let Some(tags) = updates.tags {
-            // 更新 extra 中的 tags
-            separated
-                .push(r#"extra = COALESCE(extra, '{}'::jsonb) || jsonb_build_object('tags', "#);
-            separated.push_bind(serde_json::to_value(&tags)?);
-            separated.push(")");
-            has_updates = true;
-        }
         if let Some(status) = updates.status {
             separated.push("status = ");
             // status 在数据库中存储为枚举字符串
@@ -707,21 +1068,27 @@ impl MessageStorage for PostgresMessageStorage {
         query.push(" WHERE server_id = ");
         query.push_bind(message_id);
 
-        query
-            .build()
-            .execute(&self.pool)
+        query.push(" RETURNING conversation_id");
+
+        let conversation_id: Option<String> = query
+            .build_query_scalar()
+            .fetch_optional(&self.pool)
             .await
             .context("Failed to update message")?;
 
-        // 更新后清除缓存
-        // 注意：需要 conversation_id 才能清除缓存，但这里只有 message_id
-        // 实际生产环境可以维护 message_id -> conversation_id 的映射，或通过查询获取
-        // 这里暂时不实现缓存失效，因为需要额外的查询开销
-        if self.cache.is_some() {
-            tracing::debug!(
-                message_id = %message_id,
-                "Message updated, cache invalidation skipped (requires conversation_id query)"
-            );
+        // 更新后失效会话最后一条消息视图（撤回、编辑后视图需要回源重建）
+        // 注意：单条消息缓存与会话查询缓存依赖 TTL 过期
+        if let (Some(cache), Some(conversation_id)) = (&self.cache, conversation_id) {
+            if let Err(e) = cache
+                .invalidate_last_message(&conversation_id, message_id)
+                .await
+            {
+                tracing::warn!(
+                    error = %e,
+                    message_id = %message_id,
+                    "Failed to invalidate last message view"
+                );
+            }
         }
 
         Ok(())
@@ -809,7 +1176,7 @@ impl MessageStorage for PostgresMessageStorage {
                 server_id, conversation_id, client_msg_id, sender_id, content, timestamp,
                 extra, created_at, message_type, content_type, business_type,
                 status, is_recalled, recalled_at, is_burn_after_read, burn_after_seconds,
-                seq, updated_at, visibility, read_by, operations
+                seq, updated_at, visibility, read_by, operations, tenant_id
             FROM messages
             WHERE timestamp >= 
             "#,
@@ -863,7 +1230,7 @@ impl MessageStorage for PostgresMessageStorage {
 
         let mut messages = Vec::with_capacity(rows.len());
         for row in rows {
-            messages.push(self.row_to_message(&row)?);
+            messages.push(self.row_to_message(&row).await?);
         }
 
         Ok(messages)
@@ -872,9 +1239,12 @@ impl MessageStorage for PostgresMessageStorage {
     async fn update_message_attributes(
         &self,
         message_id: &str,
-        attributes: HashMap<String, String>,
+        mut attributes: HashMap<String, String>,
         tags: Vec<String>,
     ) -> Result<()> {
+        self.seal_translation_attributes(message_id, &mut attributes)
+            .await?;
+
         // 更新 extra JSONB 中的 attributes 和 tags
         let mut extra_updates = serde_json::Map::new();
 
@@ -935,6 +1305,53 @@ impl MessageStorage for PostgresMessageStorage {
 
         Ok(tags)
     }
+
+    async fn history_visible_from(
+        &self,
+        tenant_id: &str,
+        conversation_id: &str,
+        user_id: &str,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let visible_from: Option<Option<DateTime<Utc>>> = sqlx::query_scalar(
+            r#"
+            SELECT history_visible_from
+            FROM conversation_participants
+            WHERE tenant_id = $1 AND conversation_id = $2 AND user_id = $3
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(conversation_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to query history visibility boundary")?;
+
+        Ok(visible_from.flatten())
+    }
+
+    async fn first_seq_since(
+        &self,
+        conversation_id: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Option<i64>> {
+        // 走 (conversation_id, timestamp) 索引取第一条，避免聚合扫描边界之后的全部消息
+        let seq: Option<i64> = sqlx::query_scalar(
+            r#"
+            SELECT seq
+            FROM messages
+            WHERE conversation_id = $1 AND timestamp >= $2 AND seq IS NOT NULL
+            ORDER BY timestamp ASC, seq ASC
+            LIMIT 1
+            "#,
+        )
+        .bind(conversation_id)
+        .bind(since)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to query first seq since boundary")?;
+
+        Ok(seq)
+    }
 }
 
 #[async_trait]
@@ -1042,3 +1459,50 @@ impl VisibilityStorage for PostgresMessageStorage {
         Ok(message_ids)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flare_im_core::encryption::LocalKeyFileKms;
+
+    fn encryptor() -> FieldEncryptor {
+        let kms = LocalKeyFileKms::from_toml(
+            r#"
+            [[keys]]
+            key_id = "tenant-a-v1"
+            tenant_id = "tenant-a"
+            key = "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE="
+            "#,
+        )
+        .unwrap();
+        FieldEncryptor::new(Arc::new(kms))
+    }
+
+    #[tokio::test]
+    async fn test_sealed_translation_roundtrip_is_bound_to_message_and_key() {
+        let encryptor = encryptor();
+        let value = r#"{"text":"Hello","translated_at":1}"#;
+        let sealed = seal_attribute(&encryptor, "tenant-a", "msg-1", "translation:en", value)
+            .await
+            .unwrap();
+        assert!(sealed.starts_with(SEALED_ATTRIBUTE_PREFIX));
+        assert!(!sealed.contains("Hello"));
+
+        let opened = open_attribute(&encryptor, "tenant-a", "msg-1", "translation:en", &sealed)
+            .await
+            .unwrap();
+        assert_eq!(opened, value);
+
+        // 密文挪到其他消息或其他语言的属性下无法解密
+        assert!(
+            open_attribute(&encryptor, "tenant-a", "msg-2", "translation:en", &sealed)
+                .await
+                .is_err()
+        );
+        assert!(
+            open_attribute(&encryptor, "tenant-a", "msg-1", "translation:fr", &sealed)
+                .await
+                .is_err()
+        );
+    }
+}
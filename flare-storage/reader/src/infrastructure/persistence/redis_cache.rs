@@ -2,23 +2,53 @@
 //!
 //! 提供消息查询缓存、会话状态缓存等功能
 //! 实现 L2 缓存策略：Redis -> TimescaleDB
+//!
+//! 启用字段加密时缓存值整体加密（与 Writer 的热缓存格式一致），不在 Redis 中保留明文副本
 
 use anyhow::{Context, Result};
-use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use chrono::{DateTime, Utc};
+use flare_im_core::encryption::{FieldEncryptor, open_cache_value, seal_cache_value};
 use prost::Message as ProstMessage;
 use redis::{AsyncCommands, aio::ConnectionManager};
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::config::StorageReaderConfig;
+use crate::infrastructure::persistence::postgres_store::DEFAULT_TENANT_ID;
 use flare_proto::common::Message;
 
+/// 回填会话最后一条消息视图（与 Writer 使用相同脚本：seq 不回退）
+///
+/// KEYS[1] = `cache:session:{conversation_id}:last`
+/// ARGV = [seq, server_id, encoded_message, ttl_seconds]
+const UPDATE_LAST_MESSAGE_SCRIPT: &str = r#"
+local current = redis.call('HGET', KEYS[1], 'seq')
+if current and tonumber(current) > tonumber(ARGV[1]) then
+    return 0
+end
+redis.call('HSET', KEYS[1], 'seq', ARGV[1], 'server_id', ARGV[2], 'message', ARGV[3])
+if tonumber(ARGV[4]) > 0 then
+    redis.call('EXPIRE', KEYS[1], ARGV[4])
+end
+return 1
+"#;
+
+/// 视图仍指向该消息时删除
+const INVALIDATE_LAST_MESSAGE_SCRIPT: &str = r#"
+if redis.call('HGET', KEYS[1], 'server_id') == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
 /// Redis 消息缓存仓储
 pub struct RedisMessageCache {
     client: Arc<redis::Client>,
     message_ttl_seconds: u64,
     session_ttl_seconds: u64,
+    last_message_ttl_seconds: u64,
+    /// 字段加密器（启用时缓存值加密存储）
+    encryptor: Option<Arc<FieldEncryptor>>,
 }
 
 impl RedisMessageCache {
@@ -27,9 +57,36 @@ impl RedisMessageCache {
             client,
             message_ttl_seconds: config.redis_message_cache_ttl_seconds,
             session_ttl_seconds: config.redis_session_cache_ttl_seconds,
+            last_message_ttl_seconds: config.redis_last_message_ttl_seconds,
+            encryptor: None,
         }
     }
 
+    /// 启用缓存值加密
+    pub fn with_encryptor(mut self, encryptor: Option<Arc<FieldEncryptor>>) -> Self {
+        self.encryptor = encryptor;
+        self
+    }
+
+    /// 编码并加密（启用时）缓存值，密文绑定到缓存键
+    async fn seal(&self, cache_key: &str, message: &Message) -> Result<String> {
+        let mut buf = Vec::new();
+        message.encode(&mut buf)?;
+        seal_cache_value(
+            self.encryptor.as_deref(),
+            cache_tenant_id(message),
+            cache_key,
+            &buf,
+        )
+        .await
+    }
+
+    /// 解密（启用时）并解码缓存值
+    async fn open(&self, cache_key: &str, encoded: &str) -> Result<Message> {
+        let bytes = open_cache_value(self.encryptor.as_deref(), cache_key, encoded).await?;
+        Message::decode(&bytes[..]).context("Failed to decode protobuf message")
+    }
+
     /// 获取 Redis 连接
     async fn get_connection(&self) -> Result<ConnectionManager> {
         Ok(ConnectionManager::new(self.client.as_ref().clone()).await?)
@@ -41,10 +98,8 @@ impl RedisMessageCache {
 
         let message_key = format!("cache:msg:{}:{}", message.conversation_id, message.server_id);
 
-        // 编码消息为 protobuf bytes，然后 base64 编码
-        let mut buf = Vec::new();
-        message.encode(&mut buf)?;
-        let encoded = BASE64.encode(&buf);
+        // 编码消息为 protobuf bytes，然后 base64 编码（启用加密时整体加密）
+        let encoded = self.seal(&message_key, message).await?;
 
         let _: () = conn.set(&message_key, encoded).await?;
 
@@ -76,10 +131,7 @@ impl RedisMessageCache {
 
         for message in messages {
             let message_key = format!("cache:msg:{}:{}", message.conversation_id, message.server_id);
-
-            let mut buf = Vec::new();
-            message.encode(&mut buf)?;
-            let encoded = BASE64.encode(&buf);
+            let encoded = self.seal(&message_key, message).await?;
 
             pipe.cmd("SET").arg(&message_key).arg(&encoded);
             if ttl > 0 {
@@ -107,15 +159,7 @@ impl RedisMessageCache {
         let encoded: Option<String> = conn.get(&message_key).await?;
 
         match encoded {
-            Some(encoded) => {
-                // 解码 base64，然后反序列化为 Message
-                let bytes = BASE64
-                    .decode(&encoded)
-                    .context("Failed to decode base64 message")?;
-                let message =
-                    Message::decode(&bytes[..]).context("Failed to decode protobuf message")?;
-                Ok(Some(message))
-            }
+            Some(encoded) => Ok(Some(self.open(&message_key, &encoded).await?)),
             None => Ok(None),
         }
     }
@@ -139,15 +183,13 @@ impl RedisMessageCache {
             .collect();
 
         // 使用 MGET 批量获取
-        let encoded_list: Vec<Option<String>> = conn.get(keys).await?;
+        let encoded_list: Vec<Option<String>> = conn.get(&keys).await?;
 
         let mut result = HashMap::new();
         for (i, encoded_opt) in encoded_list.into_iter().enumerate() {
             if let Some(encoded) = encoded_opt {
-                if let Ok(bytes) = BASE64.decode(&encoded) {
-                    if let Ok(message) = Message::decode(&bytes[..]) {
-                        result.insert(message_ids[i].clone(), message);
-                    }
+                if let Ok(message) = self.open(&keys[i], &encoded).await {
+                    result.insert(message_ids[i].clone(), message);
                 }
             }
         }
@@ -282,5 +324,97 @@ impl RedisMessageCache {
 
         Ok(())
     }
+
+    /// 批量读取会话最后一条消息视图
+    ///
+    /// 返回 conversation_id -> Message，未命中或无法解码的会话不在结果中
+    pub async fn get_last_messages(
+        &self,
+        conversation_ids: &[String],
+    ) -> Result<HashMap<String, Message>> {
+        if conversation_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut conn = self.get_connection().await?;
+
+        let last_keys: Vec<String> = conversation_ids
+            .iter()
+            .map(|conversation_id| format!("cache:session:{}:last", conversation_id))
+            .collect();
+        let mut pipe = redis::pipe();
+        for last_key in &last_keys {
+            pipe.cmd("HGET").arg(last_key).arg("message");
+        }
+        let encoded_list: Vec<Option<String>> = pipe.query_async(&mut conn).await?;
+
+        let mut result = HashMap::new();
+        for ((conversation_id, last_key), encoded_opt) in
+            conversation_ids.iter().zip(&last_keys).zip(encoded_list)
+        {
+            let Some(encoded) = encoded_opt else {
+                continue;
+            };
+            match self.open(last_key, &encoded).await {
+                Ok(message) => {
+                    result.insert(conversation_id.clone(), message);
+                }
+                Err(_) => {
+                    tracing::warn!(
+                        conversation_id = %conversation_id,
+                        "Failed to decode last message view, falling back to database"
+                    );
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// 回填会话最后一条消息视图
+    pub async fn cache_last_message(&self, message: &Message) -> Result<()> {
+        let mut conn = self.get_connection().await?;
+
+        let last_key = format!("cache:session:{}:last", message.conversation_id);
+        let encoded = self.seal(&last_key, message).await?;
+        let _: i64 = redis::Script::new(UPDATE_LAST_MESSAGE_SCRIPT)
+            .key(&last_key)
+            .arg(message.seq)
+            .arg(&message.server_id)
+            .arg(encoded)
+            .arg(self.last_message_ttl_seconds)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 失效会话最后一条消息视图（仅当视图指向该消息时）
+    pub async fn invalidate_last_message(
+        &self,
+        conversation_id: &str,
+        message_id: &str,
+    ) -> Result<()> {
+        let mut conn = self.get_connection().await?;
+
+        let last_key = format!("cache:session:{}:last", conversation_id);
+        let _: i64 = redis::Script::new(INVALIDATE_LAST_MESSAGE_SCRIPT)
+            .key(&last_key)
+            .arg(message_id)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(())
+    }
 }
 
+/// 缓存值加密使用的租户（与落库时一致：优先 message.tenant，其次 extra 中的 tenant_id）
+fn cache_tenant_id(message: &Message) -> &str {
+    message
+        .tenant
+        .as_ref()
+        .map(|tenant| tenant.tenant_id.as_str())
+        .or_else(|| message.extra.get("tenant_id").map(String::as_str))
+        .filter(|tenant_id| !tenant_id.is_empty())
+        .unwrap_or(DEFAULT_TENANT_ID)
+}
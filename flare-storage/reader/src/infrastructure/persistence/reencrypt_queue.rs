@@ -0,0 +1,145 @@
+//! 惰性重加密队列
+//!
+//! 读取到需要重新加密的消息（密钥已轮换或旧版本信封）时入队，由单个后台任务用当前密钥
+//! 重新加密并写回。队列有界，同一消息在处理完成前只入队一次；队列满时直接丢弃，
+//! 下次读取该消息时会再次入队。
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use flare_im_core::encryption::{DecryptedField, FieldEncryptor};
+use sqlx::{Pool, Postgres};
+use tokio::sync::mpsc;
+
+/// 默认队列容量
+pub const DEFAULT_REENCRYPT_QUEUE_CAPACITY: usize = 1024;
+
+/// 重加密任务
+pub struct ReencryptTask {
+    pub server_id: String,
+    /// 读取到的原密文（写回条件，避免覆盖并发编辑）
+    pub content: Vec<u8>,
+    pub field: DecryptedField,
+}
+
+/// 有界、按 server_id 去重的重加密队列
+#[derive(Clone)]
+pub struct ReencryptQueue {
+    sender: mpsc::Sender<ReencryptTask>,
+    /// 已入队或处理中的 server_id
+    pending: Arc<Mutex<HashSet<String>>>,
+}
+
+impl ReencryptQueue {
+    pub fn new(capacity: usize) -> (Self, mpsc::Receiver<ReencryptTask>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let queue = Self {
+            sender,
+            pending: Arc::new(Mutex::new(HashSet::new())),
+        };
+        (queue, receiver)
+    }
+
+    /// 创建队列并启动后台写回任务
+    pub fn spawn(capacity: usize, encryptor: Arc<FieldEncryptor>, pool: Pool<Postgres>) -> Self {
+        let (queue, receiver) = Self::new(capacity);
+        tokio::spawn(queue.clone().run(receiver, encryptor, pool));
+        queue
+    }
+
+    /// 入队（已在队列中或队列已满时返回 false）
+    pub fn enqueue(&self, task: ReencryptTask) -> bool {
+        let server_id = task.server_id.clone();
+        if !self.pending.lock().unwrap().insert(server_id.clone()) {
+            return false;
+        }
+
+        match self.sender.try_send(task) {
+            Ok(()) => true,
+            Err(e) => {
+                self.pending.lock().unwrap().remove(&server_id);
+                tracing::debug!(
+                    message_id = %server_id,
+                    error = %e,
+                    "Re-encrypt queue unavailable, skipping until next read"
+                );
+                false
+            }
+        }
+    }
+
+    async fn run(
+        self,
+        mut receiver: mpsc::Receiver<ReencryptTask>,
+        encryptor: Arc<FieldEncryptor>,
+        pool: Pool<Postgres>,
+    ) {
+        while let Some(task) = receiver.recv().await {
+            if let Err(e) = reencrypt(&encryptor, &pool, &task).await {
+                tracing::warn!(
+                    message_id = %task.server_id,
+                    error = %e,
+                    "Failed to re-encrypt message content"
+                );
+            }
+            self.pending.lock().unwrap().remove(&task.server_id);
+        }
+    }
+}
+
+async fn reencrypt(
+    encryptor: &FieldEncryptor,
+    pool: &Pool<Postgres>,
+    task: &ReencryptTask,
+) -> anyhow::Result<()> {
+    if let Some(resealed) = encryptor.reencrypt(&task.server_id, &task.field).await? {
+        sqlx::query("UPDATE messages SET content = $1 WHERE server_id = $2 AND content = $3")
+            .bind(&resealed)
+            .bind(&task.server_id)
+            .bind(&task.content)
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(server_id: &str) -> ReencryptTask {
+        ReencryptTask {
+            server_id: server_id.to_string(),
+            content: Vec::new(),
+            field: DecryptedField {
+                plaintext: Vec::new(),
+                key_id: None,
+                tenant_id: None,
+                needs_reencrypt: true,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_deduplicates_pending_message() {
+        let (queue, mut receiver) = ReencryptQueue::new(4);
+        assert!(queue.enqueue(task("msg-1")));
+        assert!(!queue.enqueue(task("msg-1")));
+        assert!(queue.enqueue(task("msg-2")));
+
+        assert_eq!(receiver.recv().await.unwrap().server_id, "msg-1");
+        assert_eq!(receiver.recv().await.unwrap().server_id, "msg-2");
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_drops_when_full() {
+        let (queue, mut receiver) = ReencryptQueue::new(1);
+        assert!(queue.enqueue(task("msg-1")));
+        assert!(!queue.enqueue(task("msg-2")));
+
+        // 丢弃的消息不占用去重集合，腾出空间后可再次入队
+        receiver.recv().await.unwrap();
+        assert!(queue.enqueue(task("msg-2")));
+    }
+}
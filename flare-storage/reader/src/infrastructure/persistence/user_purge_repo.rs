@@ -0,0 +1,485 @@
+//! 用户数据删除仓储实现
+//!
+//! 负责删除任务与审计日志的持久化，以及按步骤分批清除用户在各表中的数据。
+//! 用户发送的消息被删除或匿名化后，同步失效 Redis 中对应会话的消息缓存与最后一条消息视图。
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres, Row};
+use tracing::{instrument, warn};
+
+use crate::domain::model::{
+    ANONYMIZED_USER_ID, UserPurgeAuditEntry, UserPurgeJob, UserPurgeJobStatus, UserPurgeMode,
+    UserPurgeStep,
+};
+use crate::domain::repository::UserPurgeRepository;
+use crate::infrastructure::persistence::redis_cache::RedisMessageCache;
+
+/// 按用户逐行清除的表
+struct UserRowTable {
+    table: &'static str,
+    user_column: &'static str,
+    /// 表中是否有 tenant_id 列；没有时通过 messages 表关联租户
+    tenant_scoped: bool,
+}
+
+const READ_RECORD_TABLES: &[UserRowTable] = &[
+    UserRowTable {
+        table: "message_read_records",
+        user_column: "user_id",
+        tenant_scoped: true,
+    },
+    UserRowTable {
+        table: "message_visibility",
+        user_column: "user_id",
+        tenant_scoped: true,
+    },
+    UserRowTable {
+        table: "marked_messages",
+        user_column: "user_id",
+        tenant_scoped: true,
+    },
+    UserRowTable {
+        table: "message_state",
+        user_column: "user_id",
+        tenant_scoped: false,
+    },
+];
+
+const CURSOR_TABLES: &[UserRowTable] = &[UserRowTable {
+    table: "user_sync_cursor",
+    user_column: "user_id",
+    tenant_scoped: true,
+}];
+
+const ACK_TABLES: &[UserRowTable] = &[
+    UserRowTable {
+        table: "message_ack_records",
+        user_column: "user_id",
+        tenant_scoped: true,
+    },
+    UserRowTable {
+        table: "ack_archive_records",
+        user_column: "user_id",
+        tenant_scoped: false,
+    },
+];
+
+const MEDIA_REFERENCE_TABLES: &[UserRowTable] = &[UserRowTable {
+    table: "media_references",
+    user_column: "owner_id",
+    tenant_scoped: true,
+}];
+
+/// 完全删除消息时一并删除的消息关联表（其他用户对这些消息的状态，均有 tenant_id 列）
+const MESSAGE_DEPENDENT_TABLES: &[&str] = &[
+    "message_read_records",
+    "message_visibility",
+    "marked_messages",
+    "message_reactions",
+    "pinned_messages",
+    "message_edit_history",
+    "message_operation_history",
+    "message_ack_records",
+];
+
+/// PostgreSQL 用户数据删除仓储实现
+pub struct PostgresUserPurgeRepository {
+    pool: Arc<Pool<Postgres>>,
+    /// 消息缓存（可选），消息被删除或匿名化后失效对应会话的缓存
+    cache: Option<Arc<RedisMessageCache>>,
+}
+
+impl PostgresUserPurgeRepository {
+    pub fn new(pool: Arc<Pool<Postgres>>, cache: Option<Arc<RedisMessageCache>>) -> Self {
+        Self { pool, cache }
+    }
+
+    fn row_to_job(row: &sqlx::postgres::PgRow) -> Result<UserPurgeJob> {
+        let mode: String = row.get("mode");
+        let status: String = row.get("status");
+        let current_step: String = row.get("current_step");
+        let report: serde_json::Value = row.get("report");
+        Ok(UserPurgeJob {
+            job_id: row.get("job_id"),
+            tenant_id: row.get("tenant_id"),
+            user_id: row.get("user_id"),
+            mode: UserPurgeMode::from_str(&mode)
+                .with_context(|| format!("Unknown user purge mode: {}", mode))?,
+            status: UserPurgeJobStatus::from_str(&status)
+                .with_context(|| format!("Unknown user purge job status: {}", status))?,
+            current_step: UserPurgeStep::from_str(&current_step)
+                .with_context(|| format!("Unknown user purge step: {}", current_step))?,
+            report: serde_json::from_value::<HashMap<String, i64>>(report)
+                .context("Failed to parse user purge report")?,
+            requested_by: row.get("requested_by"),
+            last_error: row.get("last_error"),
+            created_at: row.get::<DateTime<Utc>, _>("created_at"),
+            updated_at: row.get::<DateTime<Utc>, _>("updated_at"),
+            completed_at: row.get::<Option<DateTime<Utc>>, _>("completed_at"),
+        })
+    }
+
+    /// 依次清除各表中属于该用户的一批记录，返回处理的总行数
+    async fn purge_user_rows(
+        &self,
+        tables: &[UserRowTable],
+        tenant_id: &str,
+        user_id: &str,
+        limit: i64,
+    ) -> Result<u64> {
+        let mut affected = 0;
+        for table in tables {
+            let select = if table.tenant_scoped {
+                format!(
+                    "SELECT ctid FROM {table} WHERE tenant_id = $1 AND {user} = $2 LIMIT $3",
+                    table = table.table,
+                    user = table.user_column,
+                )
+            } else {
+                format!(
+                    "SELECT t.ctid FROM {table} t JOIN messages m ON m.server_id = t.message_id \
+                     WHERE m.tenant_id = $1 AND t.{user} = $2 LIMIT $3",
+                    table = table.table,
+                    user = table.user_column,
+                )
+            };
+            let sql = format!(
+                "DELETE FROM {table} WHERE ctid = ANY(ARRAY({select}))",
+                table = table.table,
+            );
+            let result = sqlx::query(&sql)
+                .bind(tenant_id)
+                .bind(user_id)
+                .bind(limit)
+                .execute(self.pool.as_ref())
+                .await
+                .with_context(|| format!("Failed to purge user rows from {}", table.table))?;
+            affected += result.rows_affected();
+        }
+        Ok(affected)
+    }
+
+    /// 从消息反应的用户列表中移除该用户，移除后没有用户的反应一并删除
+    async fn purge_reactions(&self, tenant_id: &str, user_id: &str, limit: i64) -> Result<u64> {
+        let updated: Vec<(i64, i32)> = sqlx::query_as(
+            r#"
+            UPDATE message_reactions
+            SET user_ids = array_remove(user_ids, $2),
+                count = cardinality(array_remove(user_ids, $2)),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE id = ANY(ARRAY(
+                SELECT id FROM message_reactions
+                WHERE tenant_id = $1 AND $2 = ANY(user_ids)
+                LIMIT $3
+            ))
+            RETURNING id, count
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .context("Failed to remove user from message reactions")?;
+
+        let empty_ids: Vec<i64> = updated
+            .iter()
+            .filter(|(_, count)| *count == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        if !empty_ids.is_empty() {
+            sqlx::query("DELETE FROM message_reactions WHERE id = ANY($1)")
+                .bind(&empty_ids)
+                .execute(self.pool.as_ref())
+                .await
+                .context("Failed to delete empty message reactions")?;
+        }
+        Ok(updated.len() as u64)
+    }
+
+    /// 处理一批用户发送的消息：完全删除（连同关联状态）或匿名化发送者
+    ///
+    /// 发送的消息处理完后，再匿名化以该用户为接收者的单聊消息
+    async fn purge_messages(
+        &self,
+        tenant_id: &str,
+        user_id: &str,
+        mode: UserPurgeMode,
+        limit: i64,
+    ) -> Result<u64> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+
+        let sent = match mode {
+            UserPurgeMode::Delete => sqlx::query(
+                r#"
+                DELETE FROM messages
+                WHERE (timestamp, server_id) IN (
+                    SELECT timestamp, server_id FROM messages
+                    WHERE tenant_id = $1 AND sender_id = $2
+                    LIMIT $3
+                )
+                RETURNING conversation_id, server_id
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(user_id)
+            .bind(limit),
+            UserPurgeMode::Anonymize => sqlx::query(
+                r#"
+                UPDATE messages
+                SET sender_id = $4, client_msg_id = NULL, updated_at = CURRENT_TIMESTAMP
+                WHERE (timestamp, server_id) IN (
+                    SELECT timestamp, server_id FROM messages
+                    WHERE tenant_id = $1 AND sender_id = $2
+                    LIMIT $3
+                )
+                RETURNING conversation_id, server_id
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(user_id)
+            .bind(limit)
+            .bind(ANONYMIZED_USER_ID),
+        }
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to purge sent messages")?;
+
+        let message_ids: Vec<String> = sent.iter().map(|row| row.get("server_id")).collect();
+        let rows = match mode {
+            _ if sent.is_empty() => sqlx::query(
+                r#"
+                UPDATE messages
+                SET receiver_id = $4, updated_at = CURRENT_TIMESTAMP
+                WHERE (timestamp, server_id) IN (
+                    SELECT timestamp, server_id FROM messages
+                    WHERE tenant_id = $1 AND receiver_id = $2
+                    LIMIT $3
+                )
+                RETURNING conversation_id, server_id
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(user_id)
+            .bind(limit)
+            .bind(ANONYMIZED_USER_ID)
+            .fetch_all(&mut *tx)
+            .await
+            .context("Failed to anonymize received messages")?,
+            UserPurgeMode::Delete => {
+                for table in MESSAGE_DEPENDENT_TABLES {
+                    let sql = format!(
+                        "DELETE FROM {} WHERE tenant_id = $1 AND message_id = ANY($2)",
+                        table
+                    );
+                    sqlx::query(&sql)
+                        .bind(tenant_id)
+                        .bind(&message_ids)
+                        .execute(&mut *tx)
+                        .await
+                        .with_context(|| format!("Failed to delete message rows from {}", table))?;
+                }
+                for table in ["message_state", "ack_archive_records"] {
+                    let sql = format!("DELETE FROM {} WHERE message_id = ANY($1)", table);
+                    sqlx::query(&sql)
+                        .bind(&message_ids)
+                        .execute(&mut *tx)
+                        .await
+                        .with_context(|| format!("Failed to delete message rows from {}", table))?;
+                }
+                sent
+            }
+            UserPurgeMode::Anonymize => {
+                sqlx::query(
+                    r#"
+                    UPDATE message_edit_history SET editor_id = $3
+                    WHERE tenant_id = $1 AND message_id = ANY($2) AND editor_id <> $3
+                    "#,
+                )
+                .bind(tenant_id)
+                .bind(&message_ids)
+                .bind(ANONYMIZED_USER_ID)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to anonymize message edit history")?;
+                sent
+            }
+        };
+
+        tx.commit()
+            .await
+            .context("Failed to commit message purge")?;
+
+        let conversation_ids: HashSet<String> = rows
+            .iter()
+            .map(|row| row.get::<String, _>("conversation_id"))
+            .collect();
+        self.invalidate_caches(&conversation_ids).await;
+
+        Ok(rows.len() as u64)
+    }
+
+    /// 失效会话的消息缓存（含最后一条消息视图）
+    ///
+    /// 数据库修改已提交，重试不会再次匹配这些消息，因此失效失败只记录日志，残留缓存随 TTL 过期
+    async fn invalidate_caches(&self, conversation_ids: &HashSet<String>) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        for conversation_id in conversation_ids {
+            if let Err(err) = cache.invalidate_session(conversation_id).await {
+                warn!(
+                    conversation_id = %conversation_id,
+                    error = %err,
+                    "Failed to invalidate message cache after user purge"
+                );
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl UserPurgeRepository for PostgresUserPurgeRepository {
+    #[instrument(skip(self, job), fields(job_id = %job.job_id))]
+    async fn create_job(&self, job: &UserPurgeJob) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_purge_jobs (
+                job_id, tenant_id, user_id, mode, status, current_step, report,
+                requested_by, last_error, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#,
+        )
+        .bind(&job.job_id)
+        .bind(&job.tenant_id)
+        .bind(&job.user_id)
+        .bind(job.mode.as_str())
+        .bind(job.status.as_str())
+        .bind(job.current_step.as_str())
+        .bind(serde_json::to_value(&job.report)?)
+        .bind(&job.requested_by)
+        .bind(&job.last_error)
+        .bind(job.created_at)
+        .bind(job.updated_at)
+        .execute(self.pool.as_ref())
+        .await
+        .context("Failed to create user purge job")?;
+
+        Ok(())
+    }
+
+    async fn get_job(&self, job_id: &str) -> Result<Option<UserPurgeJob>> {
+        let row = sqlx::query("SELECT * FROM user_purge_jobs WHERE job_id = $1")
+            .bind(job_id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .context("Failed to get user purge job")?;
+
+        row.as_ref().map(Self::row_to_job).transpose()
+    }
+
+    async fn list_resumable_jobs(&self, limit: i64) -> Result<Vec<UserPurgeJob>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM user_purge_jobs
+            WHERE status IN ('pending', 'running')
+            ORDER BY created_at ASC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .context("Failed to list resumable user purge jobs")?;
+
+        rows.iter().map(Self::row_to_job).collect()
+    }
+
+    async fn update_job(&self, job: &UserPurgeJob) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE user_purge_jobs
+            SET status = $2, current_step = $3, report = $4, last_error = $5,
+                completed_at = $6, updated_at = CURRENT_TIMESTAMP
+            WHERE job_id = $1
+            "#,
+        )
+        .bind(&job.job_id)
+        .bind(job.status.as_str())
+        .bind(job.current_step.as_str())
+        .bind(serde_json::to_value(&job.report)?)
+        .bind(&job.last_error)
+        .bind(job.completed_at)
+        .execute(self.pool.as_ref())
+        .await
+        .context("Failed to update user purge job")?;
+
+        Ok(())
+    }
+
+    async fn append_audit(&self, entry: &UserPurgeAuditEntry) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_purge_audit_logs (
+                job_id, tenant_id, user_id, action, step, affected_count, detail, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, CURRENT_TIMESTAMP)
+            "#,
+        )
+        .bind(&entry.job_id)
+        .bind(&entry.tenant_id)
+        .bind(&entry.user_id)
+        .bind(entry.action.as_str())
+        .bind(entry.step.map(|step| step.as_str()))
+        .bind(entry.affected_count)
+        .bind(&entry.detail)
+        .execute(self.pool.as_ref())
+        .await
+        .context("Failed to append user purge audit log")?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(step = step.as_str()))]
+    async fn purge_batch(
+        &self,
+        tenant_id: &str,
+        user_id: &str,
+        step: UserPurgeStep,
+        mode: UserPurgeMode,
+        limit: i64,
+    ) -> Result<u64> {
+        match step {
+            UserPurgeStep::Messages => self.purge_messages(tenant_id, user_id, mode, limit).await,
+            UserPurgeStep::ReadRecords => {
+                let rows = self
+                    .purge_user_rows(READ_RECORD_TABLES, tenant_id, user_id, limit)
+                    .await?;
+                Ok(rows + self.purge_reactions(tenant_id, user_id, limit).await?)
+            }
+            UserPurgeStep::Cursors => {
+                self.purge_user_rows(CURSOR_TABLES, tenant_id, user_id, limit)
+                    .await
+            }
+            UserPurgeStep::AckRecords => {
+                self.purge_user_rows(ACK_TABLES, tenant_id, user_id, limit)
+                    .await
+            }
+            UserPurgeStep::MediaReferences => {
+                self.purge_user_rows(MEDIA_REFERENCE_TABLES, tenant_id, user_id, limit)
+                    .await
+            }
+            UserPurgeStep::Done => Ok(0),
+        }
+    }
+}
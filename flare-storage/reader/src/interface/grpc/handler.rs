@@ -1,26 +1,46 @@
 use std::sync::Arc;
 
 use chrono::{TimeZone, Utc};
-use flare_proto::common::OperationType;
+use flare_im_core::utils::translation::{
+    TRANSLATION_LOCALE_EXTRA, requested_locale, retain_translation,
+};
+use flare_proto::common::{Message, OperationType};
 use flare_proto::storage::storage_reader_service_server::StorageReaderService;
 use flare_proto::storage::*;
+use tonic::metadata::MetadataValue;
 use tonic::{Request, Response, Status};
 use tracing::error;
 
 use crate::application::commands::{
     ClearConversationCommand, DeleteMessageCommand, DeleteMessageForUserCommand, ExportMessagesCommand,
-    MarkReadCommand, RecallMessageCommand, SetMessageAttributesCommand,
+    MarkReadCommand, PurgeUserDataCommand, RecallMessageCommand, RetryUserPurgeJobCommand,
+    SetMessageAttributesCommand,
+};
+use crate::application::handlers::{
+    MessageCountQueryHandler, MessageStorageCommandHandler, MessageStorageQueryHandler,
+    UserPurgeCommandHandler,
 };
-use crate::application::handlers::{MessageStorageCommandHandler, MessageStorageQueryHandler};
 use crate::application::queries::{
-    GetMessageQuery, ListMessageTagsQuery, QueryMessagesBySeqQuery, QueryMessagesQuery,
-    SearchMessagesQuery,
+    GetConversationMessageStatsQuery, GetLastMessagesQuery, GetMessageQuery,
+    HISTORY_MODE_ATTRIBUTE, ListMessageTagsQuery, MAX_LAST_MESSAGES_BATCH, QueryMessagesBySeqQuery,
+    QueryMessagesQuery, SearchMessagesQuery,
 };
+use crate::domain::model::{HistoryQueryMode, HistoryViewer, UserPurgeMode};
+
+/// 请求未携带租户时使用的租户ID（与会话服务写入成员记录时的缺省值一致）
+const DEFAULT_PARTICIPANT_TENANT_ID: &str = "0";
+
+/// `QueryMessages` 响应元数据：`pagination.total_size` 为估算值（超大会话）时为 `true`
+const TOTAL_SIZE_APPROXIMATE_METADATA_KEY: &str = "x-total-size-approximate";
 
 #[derive(Clone)]
 pub struct StorageReaderGrpcHandler {
     command_handler: Arc<MessageStorageCommandHandler>,
     query_handler: Arc<MessageStorageQueryHandler>,
+    /// 会话消息计数查询处理器（需要 PostgreSQL）
+    message_count_handler: Option<Arc<MessageCountQueryHandler>>,
+    /// 用户数据删除命令处理器（需要 PostgreSQL）
+    user_purge_handler: Option<Arc<UserPurgeCommandHandler>>,
 }
 
 impl StorageReaderGrpcHandler {
@@ -31,8 +51,63 @@ impl StorageReaderGrpcHandler {
         Ok(Self {
             command_handler,
             query_handler,
+            message_count_handler: None,
+            user_purge_handler: None,
         })
     }
+
+    pub fn with_message_count_handler(
+        mut self,
+        message_count_handler: Option<Arc<MessageCountQueryHandler>>,
+    ) -> Self {
+        self.message_count_handler = message_count_handler;
+        self
+    }
+
+    pub fn with_user_purge_handler(
+        mut self,
+        user_purge_handler: Option<Arc<UserPurgeCommandHandler>>,
+    ) -> Self {
+        self.user_purge_handler = user_purge_handler;
+        self
+    }
+
+    fn message_count_handler(&self) -> Result<&Arc<MessageCountQueryHandler>, Status> {
+        self.message_count_handler
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("message counting is not configured"))
+    }
+
+    fn user_purge_handler(&self) -> Result<&Arc<UserPurgeCommandHandler>, Status> {
+        self.user_purge_handler
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("user data purge is not configured"))
+    }
+}
+
+/// 只保留与请求语言匹配的译文，并在 `extra` 中标明匹配的语言
+fn localize_messages(messages: &mut [Message], locale: &str) {
+    for message in messages {
+        if let Some(matched) = retain_translation(&mut message.attributes, locale) {
+            message
+                .extra
+                .insert(TRANSLATION_LOCALE_EXTRA.to_string(), matched);
+        }
+    }
+}
+
+/// 历史消息查询用户（未提供用户时不裁剪，租户取自请求 Context）
+fn history_viewer(
+    ctx: Option<&flare_server_core::context::Context>,
+    user_id: Option<String>,
+) -> Option<HistoryViewer> {
+    let user_id = user_id.filter(|id| !id.is_empty())?;
+    let tenant_id = ctx
+        .and_then(|ctx| ctx.tenant_id())
+        .filter(|tenant_id| !tenant_id.is_empty())
+        .unwrap_or(DEFAULT_PARTICIPANT_TENANT_ID)
+        .to_string();
+    Some(HistoryViewer { tenant_id, user_id })
 }
 
 #[tonic::async_trait]
@@ -41,7 +116,32 @@ impl StorageReaderService for StorageReaderGrpcHandler {
         &self,
         request: Request<QueryMessagesRequest>,
     ) -> Result<Response<QueryMessagesResponse>, Status> {
+        // 请求用户来自 Context，用于裁剪新成员不可见的历史消息
+        let ctx = flare_im_core::utils::context::extract_context_opt(&request);
+        let viewer = history_viewer(
+            ctx.as_ref(),
+            ctx.as_ref()
+                .and_then(|ctx| ctx.user_id().map(|id| id.to_string())),
+        );
         let req = request.into_inner();
+        let mode = match req
+            .context
+            .as_ref()
+            .and_then(|ctx| ctx.attributes.get(HISTORY_MODE_ATTRIBUTE))
+            .filter(|mode| !mode.is_empty())
+        {
+            Some(mode) => HistoryQueryMode::from_str(mode).ok_or_else(|| {
+                Status::invalid_argument(format!("unsupported history_mode: {}", mode))
+            })?,
+            None => HistoryQueryMode::Range,
+        };
+        if mode == HistoryQueryMode::JumpToDate && req.cursor.is_empty() && req.start_time <= 0 {
+            return Err(Status::invalid_argument(
+                "start_time is required for jump_to_date",
+            ));
+        }
+        // 多语言会话只返回请求语言的译文（上下文属性 `locale`，缺省为设备语言）
+        let locale = req.context.as_ref().and_then(requested_locale);
         let cursor_clone = req.cursor.clone();
         let query = QueryMessagesQuery {
             conversation_id: req.conversation_id,
@@ -53,6 +153,8 @@ impl StorageReaderService for StorageReaderGrpcHandler {
             } else {
                 Some(req.cursor)
             },
+            viewer,
+            mode,
         };
 
         match self
@@ -60,8 +162,11 @@ impl StorageReaderService for StorageReaderGrpcHandler {
             .handle_query_messages_with_pagination(query)
             .await
         {
-            Ok(result) => {
-                Ok(Response::new(QueryMessagesResponse {
+            Ok(mut result) => {
+                if let Some(locale) = &locale {
+                    localize_messages(&mut result.messages, locale);
+                }
+                let mut response = Response::new(QueryMessagesResponse {
                     messages: result.messages,
                     next_cursor: result.next_cursor.clone(),
                     has_more: result.has_more,
@@ -69,11 +174,18 @@ impl StorageReaderService for StorageReaderGrpcHandler {
                         cursor: cursor_clone,
                         limit: req.limit,
                         has_more: result.has_more,
-                        previous_cursor: String::new(),
+                        previous_cursor: result.prev_cursor,
                         total_size: result.total_size,
                     }),
                     status: Some(flare_server_core::error::ok_status()),
-                }))
+                });
+                if result.total_size_approximate {
+                    response.metadata_mut().insert(
+                        TOTAL_SIZE_APPROXIMATE_METADATA_KEY,
+                        MetadataValue::from_static("true"),
+                    );
+                }
+                Ok(response)
             }
             Err(err) => {
                 error!(error = ?err, "Failed to query messages");
@@ -86,6 +198,7 @@ impl StorageReaderService for StorageReaderGrpcHandler {
         &self,
         request: Request<flare_proto::storage::QueryMessagesBySeqRequest>,
     ) -> Result<Response<flare_proto::storage::QueryMessagesBySeqResponse>, Status> {
+        let ctx = flare_im_core::utils::context::extract_context_opt(&request);
         let req = request.into_inner();
         let query = QueryMessagesBySeqQuery {
             conversation_id: req.conversation_id,
@@ -96,11 +209,7 @@ impl StorageReaderService for StorageReaderGrpcHandler {
                 Some(req.before_seq)
             },
             limit: req.limit,
-            user_id: if req.user_id.is_empty() {
-                None
-            } else {
-                Some(req.user_id)
-            },
+            viewer: history_viewer(ctx.as_ref(), Some(req.user_id)),
         };
 
         match self.query_handler.handle_query_messages_by_seq(query).await {
@@ -155,6 +264,34 @@ impl StorageReaderService for StorageReaderGrpcHandler {
         }
     }
 
+    async fn get_last_messages(
+        &self,
+        request: Request<GetLastMessagesRequest>,
+    ) -> Result<Response<GetLastMessagesResponse>, Status> {
+        let req = request.into_inner();
+        if req.conversation_ids.len() > MAX_LAST_MESSAGES_BATCH {
+            return Err(Status::invalid_argument(format!(
+                "too many conversation_ids: {} (max {})",
+                req.conversation_ids.len(),
+                MAX_LAST_MESSAGES_BATCH
+            )));
+        }
+        let query = GetLastMessagesQuery {
+            conversation_ids: req.conversation_ids,
+        };
+
+        match self.query_handler.handle_get_last_messages(query).await {
+            Ok(messages) => Ok(Response::new(GetLastMessagesResponse {
+                messages,
+                status: Some(flare_server_core::error::ok_status()),
+            })),
+            Err(err) => {
+                error!(error = ?err, "Failed to get last messages");
+                Err(Status::internal(err.to_string()))
+            }
+        }
+    }
+
     async fn delete_message(
         &self,
         request: Request<DeleteMessageRequest>,
@@ -552,4 +689,118 @@ impl StorageReaderService for StorageReaderGrpcHandler {
             status: Some(flare_server_core::error::ok_status()),
         }))
     }
+
+    async fn get_conversation_message_stats(
+        &self,
+        request: Request<GetConversationMessageStatsRequest>,
+    ) -> Result<Response<GetConversationMessageStatsResponse>, Status> {
+        let handler = self.message_count_handler()?;
+        let req = request.into_inner();
+        if req.conversation_id.is_empty() {
+            return Err(Status::invalid_argument("conversation_id is required"));
+        }
+        let query = GetConversationMessageStatsQuery {
+            conversation_id: req.conversation_id,
+            last_read_seq: req.last_read_seq,
+        };
+
+        match handler.handle_get_conversation_message_stats(query).await {
+            Ok(stats) => Ok(Response::new(GetConversationMessageStatsResponse {
+                message_count: stats.message_count.value,
+                message_count_approximate: stats.message_count.approximate,
+                unread_count: stats.unread_count.value,
+                unread_count_approximate: stats.unread_count.approximate,
+                unread_badge: stats.unread_badge,
+                status: Some(flare_server_core::error::ok_status()),
+            })),
+            Err(err) => {
+                error!(error = ?err, "Failed to get conversation message stats");
+                Err(Status::internal(err.to_string()))
+            }
+        }
+    }
+
+    async fn purge_user_data(
+        &self,
+        request: Request<PurgeUserDataRequest>,
+    ) -> Result<Response<PurgeUserDataResponse>, Status> {
+        let handler = self.user_purge_handler()?;
+        let ctx = flare_im_core::utils::context::require_context(&request)?;
+        let req = request.into_inner();
+        if req.user_id.is_empty() {
+            return Err(Status::invalid_argument("user_id is required"));
+        }
+        let command = PurgeUserDataCommand {
+            user_id: req.user_id,
+            mode: if req.anonymize_messages {
+                UserPurgeMode::Anonymize
+            } else {
+                UserPurgeMode::Delete
+            },
+            requested_by: (!req.requested_by.is_empty()).then_some(req.requested_by),
+        };
+
+        match handler.handle_purge_user_data(&ctx, command).await {
+            Ok(job) => Ok(Response::new(PurgeUserDataResponse {
+                job_id: job.job_id,
+                status: Some(flare_server_core::error::ok_status()),
+            })),
+            Err(err) => {
+                error!(error = ?err, "Failed to submit user purge job");
+                Err(Status::internal(err.to_string()))
+            }
+        }
+    }
+
+    async fn get_purge_user_data_job(
+        &self,
+        request: Request<GetPurgeUserDataJobRequest>,
+    ) -> Result<Response<GetPurgeUserDataJobResponse>, Status> {
+        let handler = self.user_purge_handler()?;
+        let req = request.into_inner();
+
+        match handler.get_job(&req.job_id).await {
+            Ok(Some(job)) => Ok(Response::new(GetPurgeUserDataJobResponse {
+                job_id: job.job_id,
+                user_id: job.user_id,
+                mode: job.mode.as_str().to_string(),
+                job_status: job.status.as_str().to_string(),
+                current_step: job.current_step.as_str().to_string(),
+                report: job.report,
+                last_error: job.last_error.unwrap_or_default(),
+                created_at: Some(flare_im_core::utils::datetime_to_timestamp(job.created_at)),
+                completed_at: job
+                    .completed_at
+                    .map(flare_im_core::utils::datetime_to_timestamp),
+                status: Some(flare_server_core::error::ok_status()),
+            })),
+            Ok(None) => Err(Status::not_found(format!(
+                "user purge job {} not found",
+                req.job_id
+            ))),
+            Err(err) => {
+                error!(error = ?err, "Failed to get user purge job");
+                Err(Status::internal(err.to_string()))
+            }
+        }
+    }
+
+    async fn retry_purge_user_data_job(
+        &self,
+        request: Request<RetryPurgeUserDataJobRequest>,
+    ) -> Result<Response<RetryPurgeUserDataJobResponse>, Status> {
+        let handler = self.user_purge_handler()?;
+        let req = request.into_inner();
+        let command = RetryUserPurgeJobCommand { job_id: req.job_id };
+
+        match handler.handle_retry_user_purge_job(command).await {
+            Ok(()) => Ok(Response::new(RetryPurgeUserDataJobResponse {
+                status: Some(flare_server_core::error::ok_status()),
+            })),
+            Err(err) => {
+                error!(error = ?err, "Failed to retry user purge job");
+                Err(Status::failed_precondition(err.to_string()))
+            }
+        }
+    }
 }
@@ -6,12 +6,21 @@ use std::sync::Arc;
 
 use anyhow::{Context as AnyhowContext, Result};
 
-use crate::application::handlers::{MessageStorageCommandHandler, MessageStorageQueryHandler};
+use crate::application::handlers::{
+    MessageCountQueryHandler, MessageStorageCommandHandler, MessageStorageQueryHandler,
+    UserPurgeCommandHandler,
+};
 use crate::config::StorageReaderConfig;
 use crate::domain::repository::{MessageStateRepository, MessageStorage, VisibilityStorage};
-use crate::domain::service::{MessageStorageDomainConfig, MessageStorageDomainService};
+use crate::domain::service::{
+    MessageCountDomainService, MessageStorageDomainConfig, MessageStorageDomainService,
+    PiiPseudonymizer, UserPurgeDomainService,
+};
+use crate::infrastructure::persistence::message_count_repo::PostgresMessageCountRepository;
 use crate::infrastructure::persistence::message_state_repo::PostgresMessageStateRepository;
 use crate::infrastructure::persistence::postgres_store::PostgresMessageStorage;
+use crate::infrastructure::persistence::redis_cache::RedisMessageCache;
+use crate::infrastructure::persistence::user_purge_repo::PostgresUserPurgeRepository;
 use crate::interface::grpc::handler::StorageReaderGrpcHandler;
 
 /// 应用上下文 - 包含所有已初始化的服务
@@ -56,11 +65,10 @@ pub async fn initialize(
     // 3. 创建可见性存储（可选，暂时为 None）
     let visibility_storage: Option<Arc<dyn VisibilityStorage + Send + Sync>> = None;
 
-    // 4. 创建消息状态仓储（使用相同的 PostgreSQL 连接池）
-    let message_state_repo: Option<Arc<dyn MessageStateRepository + Send + Sync>> = {
-        if let Some(url) = &config.postgres_url {
-            // 创建新的连接池用于 message_state_repo
-            // 注意：这里可以优化为共享连接池，但为了简化，先创建新池
+    // 4. 创建消息状态仓储与用户数据删除仓储（共享同一个 PostgreSQL 连接池）
+    let pool: Option<Arc<sqlx::PgPool>> = match &config.postgres_url {
+        Some(url) => {
+            // 注意：这里可以优化为与消息存储共享连接池，但为了简化，先创建新池
             use sqlx::postgres::PgPoolOptions;
             let pool = PgPoolOptions::new()
                 .max_connections(config.postgres_max_connections)
@@ -77,14 +85,17 @@ pub async fn initialize(
                 .test_before_acquire(true)
                 .connect(url)
                 .await
-                .with_context(|| "Failed to create pool for message_state_repo")?;
-            Some(Arc::new(PostgresMessageStateRepository::new(Arc::new(
-                pool,
-            ))))
-        } else {
-            None
+                .with_context(|| "Failed to create PostgreSQL pool")?;
+            Some(Arc::new(pool))
         }
+        None => None,
     };
+    let message_state_repo: Option<Arc<dyn MessageStateRepository + Send + Sync>> = pool
+        .as_ref()
+        .map(|pool| {
+            Arc::new(PostgresMessageStateRepository::new(pool.clone()))
+                as Arc<dyn MessageStateRepository + Send + Sync>
+        });
 
     // 5. 构建领域配置
     let domain_config = MessageStorageDomainConfig {
@@ -92,16 +103,25 @@ pub async fn initialize(
         default_range_seconds: config.default_range_seconds,
     };
 
-    // 6. 构建领域服务
-    let domain_service = Arc::new(MessageStorageDomainService::new(
-        storage.clone(),
-        visibility_storage,
-        message_state_repo,
-        domain_config,
-    ));
+    // 6. 构建领域服务（配置了 PostgreSQL 时超大会话的消息数为估算值）
+    let message_counter = pool
+        .as_ref()
+        .map(|pool| build_message_counter(pool.clone(), &config));
+    let domain_service = Arc::new(
+        MessageStorageDomainService::new(
+            storage.clone(),
+            visibility_storage,
+            message_state_repo,
+            domain_config,
+        )
+        .with_message_counter(message_counter.clone()),
+    );
 
     // 6. 构建命令处理器
-    let command_handler = Arc::new(MessageStorageCommandHandler::new(domain_service.clone()));
+    let command_handler = Arc::new(
+        MessageStorageCommandHandler::new(domain_service.clone())
+            .with_pii_pseudonymizer(build_pii_pseudonymizer(&config)?),
+    );
 
     // 7. 构建查询处理器（对于基于 seq 的查询，需要使用领域服务）
     let query_handler = Arc::new(MessageStorageQueryHandler::with_domain_service(
@@ -109,10 +129,89 @@ pub async fn initialize(
         domain_service.clone(),
     ));
 
-    // 8. 构建 gRPC 处理器
-    let grpc_handler = StorageReaderGrpcHandler::new(command_handler, query_handler).await?;
+    // 8. 构建会话消息计数查询，并在后台定期校准超大会话的计数快照
+    let message_count_handler =
+        message_counter.map(|counter| Arc::new(MessageCountQueryHandler::new(counter)));
+    if let Some(handler) = &message_count_handler {
+        let poll_interval = std::time::Duration::from_secs(
+            (config.message_count_reconcile_interval_seconds / 10).clamp(1, 60),
+        );
+        tokio::spawn(handler.clone().run_reconciliation(poll_interval));
+    }
+
+    // 9. 构建用户数据删除任务（需要 PostgreSQL），并在后台续跑未完成的任务
+    let user_purge_handler = pool
+        .map(|pool| build_user_purge_handler(pool, &config))
+        .transpose()?;
+    if let Some(handler) = &user_purge_handler {
+        let poll_interval =
+            std::time::Duration::from_millis(config.user_purge_poll_interval_ms.max(1));
+        tokio::spawn(handler.clone().run_pending_jobs(poll_interval));
+    }
+
+    // 10. 构建 gRPC 处理器
+    let grpc_handler = StorageReaderGrpcHandler::new(command_handler, query_handler)
+        .await?
+        .with_message_count_handler(message_count_handler)
+        .with_user_purge_handler(user_purge_handler);
 
     Ok(ApplicationContext {
         handler: grpc_handler,
     })
 }
+
+/// 构建分析导出的 PII 假名化服务（需同时配置策略文件与密钥文件）
+fn build_pii_pseudonymizer(config: &StorageReaderConfig) -> Result<Option<Arc<PiiPseudonymizer>>> {
+    match (&config.pii_policy_file, &config.pii_key_file) {
+        (Some(policy_file), Some(key_file)) => {
+            let kms = flare_im_core::encryption::LocalKeyFileKms::from_file(key_file)
+                .with_context(|| "Failed to load PII key file")?;
+            let pseudonymizer = PiiPseudonymizer::from_policy_file(Arc::new(kms), policy_file)?;
+            tracing::info!(policy_file = %policy_file, "PII pseudonymization enabled for exports");
+            Ok(Some(Arc::new(pseudonymizer)))
+        }
+        (None, None) => Ok(None),
+        _ => Err(anyhow::anyhow!(
+            "STORAGE_PII_POLICY_FILE and STORAGE_PII_KEY_FILE must be configured together"
+        )),
+    }
+}
+
+/// 构建超大会话近似计数服务
+fn build_message_counter(
+    pool: Arc<sqlx::PgPool>,
+    config: &StorageReaderConfig,
+) -> Arc<MessageCountDomainService> {
+    let repo = Arc::new(PostgresMessageCountRepository::new(pool));
+    Arc::new(
+        MessageCountDomainService::new(repo)
+            .with_exact_threshold(config.message_count_exact_threshold)
+            .with_badge_cap(config.unread_badge_cap)
+            .with_reconcile_interval(std::time::Duration::from_secs(
+                config.message_count_reconcile_interval_seconds,
+            ))
+            .with_reconcile_batch_size(config.message_count_reconcile_batch_size),
+    )
+}
+
+/// 构建用户数据删除命令处理器（配置了 Redis 时同步失效消息缓存）
+fn build_user_purge_handler(
+    pool: Arc<sqlx::PgPool>,
+    config: &StorageReaderConfig,
+) -> Result<Arc<UserPurgeCommandHandler>> {
+    let cache = config
+        .redis_url
+        .as_ref()
+        .map(|redis_url| {
+            redis::Client::open(redis_url.as_str())
+                .map(|client| Arc::new(RedisMessageCache::new(Arc::new(client), config)))
+                .with_context(|| "Failed to create Redis client for user purge")
+        })
+        .transpose()?;
+
+    let repo = Arc::new(PostgresUserPurgeRepository::new(pool, cache));
+    let domain_service = Arc::new(
+        UserPurgeDomainService::new(repo).with_batch_size(config.user_purge_batch_size),
+    );
+    Ok(Arc::new(UserPurgeCommandHandler::new(domain_service)))
+}
@@ -123,6 +123,10 @@ impl MessagePersistenceCommandHandler {
         let message_id = prepared.message_id.clone();
         let conversation_id = prepared.conversation_id.clone();
         let timeline = prepared.timeline.clone();
+        let sync_reply = prepared
+            .reply_channel
+            .clone()
+            .map(|channel| (channel, prepared.message.seq as i64));
 
         // 从 request 构建 Context（在移动 request 之前先保存 tenant 信息）
         use flare_server_core::context::Context;
@@ -224,6 +228,13 @@ impl MessagePersistenceCommandHandler {
             tracing::warn!(error = %e, message_id = %result.message_id, "Failed to publish ACK, but message is already persisted");
         }
 
+        // 同步写入回执（编排服务等待该回执后响应 sync = true 的请求）
+        if let Some((channel, seq)) = &sync_reply {
+            self.domain_service
+                .publish_sync_reply(channel, *seq, &result)
+                .await;
+        }
+
         Ok(result)
     }
 
@@ -358,6 +369,13 @@ impl MessagePersistenceCommandHandler {
                 tracing::warn!(error = %e, message_id = %result.message_id, "Failed to publish ACK");
             }
 
+            // 同步写入回执
+            if let Some(channel) = &prepared.reply_channel {
+                self.domain_service
+                    .publish_sync_reply(channel, prepared.message.seq as i64, &result)
+                    .await;
+            }
+
             results.push(result);
         }
 
@@ -0,0 +1,88 @@
+//! 存储一致性校验处理器（编排层）- 定时触发校验并记录指标
+
+use flare_im_core::metrics::StorageWriterMetrics;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::domain::model::ConsistencyReport;
+use crate::domain::service::StoreConsistencyVerifier;
+
+/// 存储一致性校验处理器
+pub struct ConsistencyVerificationHandler {
+    verifier: Arc<StoreConsistencyVerifier>,
+    metrics: Arc<StorageWriterMetrics>,
+    interval: Duration,
+    sample_size: usize,
+}
+
+impl ConsistencyVerificationHandler {
+    pub fn new(
+        verifier: Arc<StoreConsistencyVerifier>,
+        metrics: Arc<StorageWriterMetrics>,
+        interval: Duration,
+        sample_size: usize,
+    ) -> Self {
+        Self {
+            verifier,
+            metrics,
+            interval,
+            sample_size,
+        }
+    }
+
+    /// 执行一轮校验
+    pub async fn tick(&self) {
+        match self.verifier.verify_once(self.sample_size).await {
+            Ok(report) => self.record(&report),
+            Err(err) => warn!(error = %err, "Store consistency verification failed"),
+        }
+    }
+
+    /// 按校验间隔持续运行
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval.max(Duration::from_millis(1)));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.tick().await;
+        }
+    }
+
+    fn record(&self, report: &ConsistencyReport) {
+        if report.sampled == 0 {
+            return;
+        }
+
+        self.metrics
+            .consistency_checked_total
+            .inc_by(report.sampled as u64);
+        self.metrics
+            .consistency_repaired_total
+            .inc_by(report.repaired as u64);
+
+        for divergence in &report.divergences {
+            self.metrics
+                .consistency_divergence_total
+                .with_label_values(&[divergence.store.as_str(), divergence.kind.as_str()])
+                .inc();
+            warn!(
+                message_id = %divergence.message.message_id,
+                conversation_id = %divergence.message.conversation_id,
+                store = divergence.store.as_str(),
+                kind = divergence.kind.as_str(),
+                "Message store divergence detected"
+            );
+        }
+
+        if !report.is_consistent() {
+            info!(
+                sampled = report.sampled,
+                skipped = report.skipped,
+                divergences = report.divergences.len(),
+                repaired = report.repaired,
+                "Store consistency verification finished with divergences"
+            );
+        }
+    }
+}
@@ -1,5 +1,9 @@
 //! CQRS Handler（编排层）
 
 pub mod command_handler;
+pub mod consistency_handler;
+pub mod write_event_handler;
 
 pub use command_handler::MessagePersistenceCommandHandler;
+pub use consistency_handler::ConsistencyVerificationHandler;
+pub use write_event_handler::LastMessageCacheInvalidator;
@@ -0,0 +1,38 @@
+//! 写入事件订阅者（编排层）- 订阅写入事件总线，异步失效缓存视图
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use flare_im_core::EventHandler;
+
+use crate::domain::events::StorageWriteEvent;
+use crate::domain::repository::HotCacheRepository;
+
+/// 会话最后一条消息视图失效器（撤回、编辑、删除后）
+pub struct LastMessageCacheInvalidator {
+    hot_cache_repo: Arc<dyn HotCacheRepository + Send + Sync>,
+}
+
+impl LastMessageCacheInvalidator {
+    pub fn new(hot_cache_repo: Arc<dyn HotCacheRepository + Send + Sync>) -> Self {
+        Self { hot_cache_repo }
+    }
+}
+
+#[async_trait]
+impl EventHandler<StorageWriteEvent> for LastMessageCacheInvalidator {
+    async fn handle(&self, event: &StorageWriteEvent) -> Result<()> {
+        let StorageWriteEvent::MessageModified {
+            conversation_id,
+            message_id,
+        } = event
+        else {
+            return Ok(());
+        };
+        self.hot_cache_repo
+            .invalidate_last_message(conversation_id, message_id)
+            .await
+            .with_context(|| format!("invalidate last message view of {conversation_id}"))
+    }
+}
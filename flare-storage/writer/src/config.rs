@@ -1,7 +1,9 @@
 use anyhow::Result;
 use flare_im_core::config::FlareAppConfig;
 use flare_server_core::kafka::{KafkaConsumerConfig, KafkaProducerConfig};
+use std::collections::HashMap;
 use std::env;
+use std::str::FromStr;
 
 #[derive(Clone, Debug)]
 pub struct StorageWriterConfig {
@@ -27,6 +29,36 @@ pub struct StorageWriterConfig {
     pub postgres_idle_timeout_seconds: u64,
     pub postgres_max_lifetime_seconds: u64,
     pub media_service_endpoint: Option<String>,
+    /// 消息内容加密密钥文件（可选，配置后启用字段级加密）
+    pub encryption_key_file: Option<String>,
+    /// 是否启用存储一致性校验（热缓存 / 实时存储 / 归档存储）
+    pub verify_enabled: bool,
+    /// 一致性校验间隔（毫秒）
+    pub verify_interval_ms: u64,
+    /// 每轮校验的抽样消息数
+    pub verify_sample_size: usize,
+    /// 最近写入消息的采样窗口大小
+    pub verify_window_size: usize,
+    /// 发现热缓存缺失或不一致时是否回填
+    pub verify_repair: bool,
+    /// 写入事件总线每个订阅者的队列容量
+    pub event_bus_capacity: usize,
+    /// 是否启用租户公平调度（按租户限速与加权出队）
+    pub fairness_enabled: bool,
+    /// 默认每租户写入速率（条/秒，0 表示不限速）
+    pub fairness_tenant_rate: f64,
+    /// 每租户令牌桶容量（允许的突发条数）
+    pub fairness_tenant_burst: f64,
+    /// 按租户覆盖的写入速率（条/秒）
+    pub fairness_tenant_rates: HashMap<String, f64>,
+    /// 租户出队权重（默认 1）
+    pub fairness_tenant_weights: HashMap<String, u32>,
+    /// 所有租户的积压上限（达到后暂停拉取）
+    pub fairness_max_backlog: usize,
+    /// 单租户积压达到该值并持续一段时间后暂停其所在分区
+    pub fairness_pause_backlog: usize,
+    /// 租户积压持续超限多久后暂停分区（毫秒）
+    pub fairness_pause_after_ms: u64,
 }
 
 impl StorageWriterConfig {
@@ -153,6 +185,17 @@ impl StorageWriterConfig {
 
         let media_service_endpoint = env::var("MEDIA_SERVICE_ENDPOINT").ok();
 
+        let encryption_key_file = env::var("STORAGE_ENCRYPTION_KEY_FILE").ok();
+
+        let verify = VerifySettings::from_env();
+
+        let event_bus_capacity = env::var("STORAGE_EVENT_BUS_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(1024);
+
+        let fairness = FairnessSettings::from_env();
+
         Ok(Self {
             kafka_bootstrap,
             kafka_topic,
@@ -174,6 +217,21 @@ impl StorageWriterConfig {
             postgres_idle_timeout_seconds,
             postgres_max_lifetime_seconds,
             media_service_endpoint,
+            encryption_key_file,
+            verify_enabled: verify.enabled,
+            verify_interval_ms: verify.interval_ms,
+            verify_sample_size: verify.sample_size,
+            verify_window_size: verify.window_size,
+            verify_repair: verify.repair,
+            event_bus_capacity,
+            fairness_enabled: fairness.enabled,
+            fairness_tenant_rate: fairness.tenant_rate,
+            fairness_tenant_burst: fairness.tenant_burst,
+            fairness_tenant_rates: fairness.tenant_rates,
+            fairness_tenant_weights: fairness.tenant_weights,
+            fairness_max_backlog: fairness.max_backlog,
+            fairness_pause_backlog: fairness.pause_backlog,
+            fairness_pause_after_ms: fairness.pause_after_ms,
         })
     }
 
@@ -248,6 +306,15 @@ impl StorageWriterConfig {
             .unwrap_or(3600);
 
         let media_service_endpoint = env::var("MEDIA_SERVICE_ENDPOINT").ok();
+        let encryption_key_file = env::var("STORAGE_ENCRYPTION_KEY_FILE").ok();
+        let verify = VerifySettings::from_env();
+
+        let event_bus_capacity = env::var("STORAGE_EVENT_BUS_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(1024);
+
+        let fairness = FairnessSettings::from_env();
 
         Self {
             kafka_bootstrap,
@@ -270,10 +337,153 @@ impl StorageWriterConfig {
             postgres_idle_timeout_seconds,
             postgres_max_lifetime_seconds,
             media_service_endpoint,
+            encryption_key_file,
+            verify_enabled: verify.enabled,
+            verify_interval_ms: verify.interval_ms,
+            verify_sample_size: verify.sample_size,
+            verify_window_size: verify.window_size,
+            verify_repair: verify.repair,
+            event_bus_capacity,
+            fairness_enabled: fairness.enabled,
+            fairness_tenant_rate: fairness.tenant_rate,
+            fairness_tenant_burst: fairness.tenant_burst,
+            fairness_tenant_rates: fairness.tenant_rates,
+            fairness_tenant_weights: fairness.tenant_weights,
+            fairness_max_backlog: fairness.max_backlog,
+            fairness_pause_backlog: fairness.pause_backlog,
+            fairness_pause_after_ms: fairness.pause_after_ms,
         }
     }
 }
 
+/// 一致性校验配置（仅从环境变量读取）
+struct VerifySettings {
+    enabled: bool,
+    interval_ms: u64,
+    sample_size: usize,
+    window_size: usize,
+    repair: bool,
+}
+
+impl VerifySettings {
+    fn from_env() -> Self {
+        let enabled = env::var("STORAGE_VERIFY_ENABLED")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok())
+            .unwrap_or(false);
+
+        let interval_ms = env::var("STORAGE_VERIFY_INTERVAL_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(30_000);
+
+        let sample_size = env::var("STORAGE_VERIFY_SAMPLE_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(50);
+
+        let window_size = env::var("STORAGE_VERIFY_WINDOW_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(1000);
+
+        let repair = env::var("STORAGE_VERIFY_REPAIR")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok())
+            .unwrap_or(false);
+
+        Self {
+            enabled,
+            interval_ms,
+            sample_size,
+            window_size,
+            repair,
+        }
+    }
+}
+
+/// 租户公平调度配置（仅从环境变量读取）
+struct FairnessSettings {
+    enabled: bool,
+    tenant_rate: f64,
+    tenant_burst: f64,
+    tenant_rates: HashMap<String, f64>,
+    tenant_weights: HashMap<String, u32>,
+    max_backlog: usize,
+    pause_backlog: usize,
+    pause_after_ms: u64,
+}
+
+impl FairnessSettings {
+    fn from_env() -> Self {
+        let enabled = env::var("STORAGE_FAIRNESS_ENABLED")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok())
+            .unwrap_or(false);
+
+        let tenant_rate = env::var("STORAGE_TENANT_RATE_LIMIT")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.0);
+
+        let tenant_burst = env::var("STORAGE_TENANT_BURST")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(1000.0);
+
+        // 格式：tenant_a=500,tenant_b=100
+        let tenant_rates = env::var("STORAGE_TENANT_RATE_LIMITS")
+            .map(|v| parse_tenant_map(&v))
+            .unwrap_or_default();
+
+        // 格式：tenant_a=4,tenant_b=1
+        let tenant_weights = env::var("STORAGE_TENANT_WEIGHTS")
+            .map(|v| parse_tenant_map(&v))
+            .unwrap_or_default();
+
+        let max_backlog = env::var("STORAGE_FAIRNESS_MAX_BACKLOG")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(10_000);
+
+        let pause_backlog = env::var("STORAGE_TENANT_PAUSE_BACKLOG")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(2_000);
+
+        let pause_after_ms = env::var("STORAGE_TENANT_PAUSE_AFTER_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(5_000);
+
+        Self {
+            enabled,
+            tenant_rate,
+            tenant_burst,
+            tenant_rates,
+            tenant_weights,
+            max_backlog,
+            pause_backlog,
+            pause_after_ms,
+        }
+    }
+}
+
+/// 解析 `tenant=value` 逗号分隔列表（忽略无法解析的项）
+fn parse_tenant_map<T: FromStr>(value: &str) -> HashMap<String, T> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (tenant_id, value) = entry.split_once('=')?;
+            let tenant_id = tenant_id.trim();
+            if tenant_id.is_empty() {
+                return None;
+            }
+            Some((tenant_id.to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
 // 实现 KafkaConsumerConfig trait，使 StorageWriterConfig 可以使用通用的 Kafka 消费者构建器
 impl KafkaConsumerConfig for StorageWriterConfig {
     fn kafka_bootstrap(&self) -> &str {
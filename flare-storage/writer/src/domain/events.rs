@@ -26,3 +26,15 @@ impl AckStatus {
         }
     }
 }
+
+/// 存储写入事件（经进程内事件总线通知缓存失效等可重建的下游视图）
+///
+/// 会话最后消息与未读数不走总线：它们在写入路径上同步更新，失败时整条消息重新消费
+#[derive(Debug, Clone)]
+pub enum StorageWriteEvent {
+    /// 消息被撤回、编辑或删除
+    MessageModified {
+        conversation_id: String,
+        message_id: String,
+    },
+}
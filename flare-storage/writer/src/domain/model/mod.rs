@@ -20,6 +20,8 @@ pub struct PreparedMessage {
     pub message: flare_proto::common::Message,
     pub timeline: TimelineMetadata,
     pub sync: bool,
+    /// 同步写入回执频道（`sync = true` 且请求携带回执频道时存在）
+    pub reply_channel: Option<String>,
 }
 
 #[derive(Debug)]
@@ -40,3 +42,72 @@ impl PersistenceResult {
         }
     }
 }
+
+/// 最近写入的消息标识（一致性校验采样用）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrittenMessageRef {
+    pub conversation_id: String,
+    pub message_id: String,
+}
+
+/// 参与一致性校验的存储
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreKind {
+    HotCache,
+    Realtime,
+    Archive,
+}
+
+impl StoreKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StoreKind::HotCache => "hot_cache",
+            StoreKind::Realtime => "realtime",
+            StoreKind::Archive => "archive",
+        }
+    }
+}
+
+/// 不一致类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DivergenceKind {
+    /// 存储中缺失该消息
+    Missing,
+    /// 与基准存储中的消息内容不一致
+    Mismatch,
+}
+
+impl DivergenceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DivergenceKind::Missing => "missing",
+            DivergenceKind::Mismatch => "mismatch",
+        }
+    }
+}
+
+/// 单条消息的不一致记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub message: WrittenMessageRef,
+    pub store: StoreKind,
+    pub kind: DivergenceKind,
+}
+
+/// 一轮一致性校验的结果
+#[derive(Debug, Clone, Default)]
+pub struct ConsistencyReport {
+    /// 本轮采样的消息数
+    pub sampled: usize,
+    /// 所有存储中都不存在（可能已过期或尚未落库），不计入不一致
+    pub skipped: usize,
+    pub divergences: Vec<Divergence>,
+    /// 已从基准存储回填热缓存的消息数
+    pub repaired: usize,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
@@ -2,6 +2,7 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
+use flare_im_core::utils::PersistenceReply;
 use flare_proto::common::Message;
 
 use crate::domain::events::AckEvent;
@@ -44,6 +45,15 @@ pub trait HotCacheRepository: Send + Sync {
         }
         Ok(())
     }
+
+    /// 读取热缓存中的消息（用于一致性校验）
+    async fn get_hot(&self, conversation_id: &str, message_id: &str) -> Result<Option<Message>>;
+
+    /// 失效会话最后一条消息视图（仅当视图指向该消息时生效，用于撤回、编辑、删除）
+    async fn invalidate_last_message(&self, conversation_id: &str, message_id: &str) -> Result<()> {
+        let _ = (conversation_id, message_id);
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -58,6 +68,9 @@ pub trait RealtimeStoreRepository: Send + Sync {
         }
         Ok(())
     }
+
+    /// 读取实时存储中的消息（用于一致性校验）
+    async fn get_realtime(&self, message_id: &str) -> Result<Option<Message>>;
 }
 
 #[async_trait]
@@ -209,6 +222,12 @@ pub trait AckPublisher: Send + Sync {
     async fn publish(&self, event: AckEvent<'_>) -> Result<()>;
 }
 
+/// 同步写入回执发布（`sync = true` 的消息持久化后回复请求指定的频道）
+#[async_trait]
+pub trait PersistenceReplyPublisher: Send + Sync {
+    async fn publish(&self, channel: &str, reply: &PersistenceReply) -> Result<()>;
+}
+
 #[async_trait]
 pub trait MediaAttachmentVerifier: Send + Sync {
     async fn fetch_metadata(&self, ctx: &flare_server_core::context::Context, file_ids: &[String]) -> Result<Vec<MediaAttachmentMetadata>>;
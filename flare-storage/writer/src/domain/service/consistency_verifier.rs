@@ -0,0 +1,246 @@
+//! 存储一致性校验领域服务
+//!
+//! 对最近写入的消息抽样，检查其在热缓存（Redis）、实时存储与归档存储中是否都存在且内容一致。
+//! 以实时存储为基准（未配置时使用归档存储），可选地用基准存储中的消息回填热缓存。
+//!
+//! 注意：归档存储回读的消息只保留核心字段，因此只比较消息ID、会话、发送者、seq、类型与内容。
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use flare_proto::common::Message;
+use prost::Message as _;
+use tracing::warn;
+
+use crate::domain::model::{
+    ConsistencyReport, Divergence, DivergenceKind, StoreKind, WrittenMessageRef,
+};
+use crate::domain::repository::{
+    ArchiveStoreRepository, HotCacheRepository, RealtimeStoreRepository,
+};
+
+/// 最近写入消息的采样窗口（有界，满后丢弃最早的记录）
+pub struct RecentWriteSampler {
+    capacity: usize,
+    entries: Mutex<VecDeque<WrittenMessageRef>>,
+}
+
+impl RecentWriteSampler {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// 记录一条已持久化的消息
+    pub fn record(&self, conversation_id: &str, message_id: &str) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(WrittenMessageRef {
+            conversation_id: conversation_id.to_string(),
+            message_id: message_id.to_string(),
+        });
+    }
+
+    /// 取出最多 `limit` 条待校验的消息（按写入顺序）
+    pub fn take(&self, limit: usize) -> Vec<WrittenMessageRef> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let count = limit.min(entries.len());
+        entries.drain(..count).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 存储一致性校验器
+pub struct StoreConsistencyVerifier {
+    sampler: Arc<RecentWriteSampler>,
+    hot_cache_repo: Option<Arc<dyn HotCacheRepository + Send + Sync>>,
+    realtime_repo: Option<Arc<dyn RealtimeStoreRepository + Send + Sync>>,
+    archive_repo: Option<Arc<dyn ArchiveStoreRepository + Send + Sync>>,
+    /// 发现热缓存缺失或不一致时是否用基准存储回填
+    repair: bool,
+}
+
+impl StoreConsistencyVerifier {
+    pub fn new(
+        sampler: Arc<RecentWriteSampler>,
+        hot_cache_repo: Option<Arc<dyn HotCacheRepository + Send + Sync>>,
+        realtime_repo: Option<Arc<dyn RealtimeStoreRepository + Send + Sync>>,
+        archive_repo: Option<Arc<dyn ArchiveStoreRepository + Send + Sync>>,
+        repair: bool,
+    ) -> Self {
+        Self {
+            sampler,
+            hot_cache_repo,
+            realtime_repo,
+            archive_repo,
+            repair,
+        }
+    }
+
+    /// 至少配置两个存储时校验才有意义
+    pub fn is_applicable(&self) -> bool {
+        [
+            self.hot_cache_repo.is_some(),
+            self.realtime_repo.is_some(),
+            self.archive_repo.is_some(),
+        ]
+        .iter()
+        .filter(|enabled| **enabled)
+        .count()
+            >= 2
+    }
+
+    /// 抽样校验一轮
+    pub async fn verify_once(&self, sample_size: usize) -> Result<ConsistencyReport> {
+        let samples = self.sampler.take(sample_size);
+        let mut report = ConsistencyReport {
+            sampled: samples.len(),
+            ..ConsistencyReport::default()
+        };
+
+        for sample in samples {
+            self.verify_message(&sample, &mut report).await?;
+        }
+
+        Ok(report)
+    }
+
+    async fn verify_message(
+        &self,
+        sample: &WrittenMessageRef,
+        report: &mut ConsistencyReport,
+    ) -> Result<()> {
+        let mut copies: Vec<(StoreKind, Option<Message>)> = Vec::with_capacity(3);
+        if let Some(repo) = &self.realtime_repo {
+            copies.push((StoreKind::Realtime, repo.get_realtime(&sample.message_id).await?));
+        }
+        if let Some(repo) = &self.archive_repo {
+            copies.push((StoreKind::Archive, repo.get_message(&sample.message_id).await?));
+        }
+        if let Some(repo) = &self.hot_cache_repo {
+            copies.push((
+                StoreKind::HotCache,
+                repo.get_hot(&sample.conversation_id, &sample.message_id)
+                    .await?,
+            ));
+        }
+
+        if copies.iter().all(|(_, copy)| copy.is_none()) {
+            report.skipped += 1;
+            return Ok(());
+        }
+
+        // 第一个存储（实时存储优先，其次归档存储）作为基准
+        let (baseline_store, baseline) = &copies[0];
+        let mut cache_stale = false;
+        for (store, copy) in &copies {
+            let kind = match (copy, baseline) {
+                (None, _) => Some(DivergenceKind::Missing),
+                (Some(copy), Some(baseline)) if !same_message(copy, baseline) => {
+                    Some(DivergenceKind::Mismatch)
+                }
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                if *store == StoreKind::HotCache {
+                    cache_stale = true;
+                }
+                report.divergences.push(Divergence {
+                    message: sample.clone(),
+                    store: *store,
+                    kind,
+                });
+            }
+        }
+
+        if cache_stale && self.repair && *baseline_store != StoreKind::HotCache {
+            if let (Some(repo), Some(baseline)) = (&self.hot_cache_repo, baseline) {
+                match repo.store_hot(baseline).await {
+                    Ok(()) => report.repaired += 1,
+                    Err(err) => warn!(
+                        error = %err,
+                        message_id = %sample.message_id,
+                        "Failed to repair hot cache entry"
+                    ),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 比较两份消息的核心字段是否一致
+pub fn same_message(left: &Message, right: &Message) -> bool {
+    left.server_id == right.server_id
+        && left.conversation_id == right.conversation_id
+        && left.sender_id == right.sender_id
+        && left.seq == right.seq
+        && left.message_type == right.message_type
+        && left.content.as_ref().map(|c| c.encode_to_vec())
+            == right.content.as_ref().map(|c| c.encode_to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(server_id: &str, seq: u64) -> Message {
+        Message {
+            server_id: server_id.to_string(),
+            conversation_id: "conv-1".to_string(),
+            sender_id: "user-1".to_string(),
+            seq,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sampler_drops_oldest_when_full() {
+        let sampler = RecentWriteSampler::new(2);
+        sampler.record("conv-1", "msg-1");
+        sampler.record("conv-1", "msg-2");
+        sampler.record("conv-1", "msg-3");
+
+        let taken = sampler.take(10);
+        let ids: Vec<_> = taken.iter().map(|r| r.message_id.as_str()).collect();
+        assert_eq!(ids, vec!["msg-2", "msg-3"]);
+        assert!(sampler.is_empty());
+    }
+
+    #[test]
+    fn test_sampler_take_respects_limit() {
+        let sampler = RecentWriteSampler::new(10);
+        for i in 0..5 {
+            sampler.record("conv-1", &format!("msg-{}", i));
+        }
+
+        assert_eq!(sampler.take(3).len(), 3);
+        assert_eq!(sampler.len(), 2);
+    }
+
+    #[test]
+    fn test_same_message_ignores_non_core_fields() {
+        let left = message("msg-1", 7);
+        let mut right = message("msg-1", 7);
+        right
+            .extra
+            .insert("persisted_ts".to_string(), "1".to_string());
+        assert!(same_message(&left, &right));
+
+        let right = message("msg-1", 8);
+        assert!(!same_message(&left, &right));
+    }
+}
@@ -6,6 +6,7 @@
 //! - 根据操作类型更新数据库
 
 use anyhow::{Context, Result, anyhow};
+use flare_im_core::EventBus;
 use flare_proto::common::{
     Message, MessageOperation, OperationType,
     message_operation::OperationData,
@@ -14,16 +15,38 @@ use prost::Message as ProstMessage;
 use std::sync::Arc;
 use tracing::{instrument, warn};
 
-use crate::domain::repository::ArchiveStoreRepository;
+use crate::domain::events::StorageWriteEvent;
+use crate::domain::repository::{ArchiveStoreRepository, HotCacheRepository};
 
 /// 消息操作领域服务
 pub struct MessageOperationDomainService {
     archive_repo: Option<Arc<dyn ArchiveStoreRepository + Send + Sync>>,
+    hot_cache_repo: Option<Arc<dyn HotCacheRepository + Send + Sync>>,
+    event_bus: Option<Arc<EventBus<StorageWriteEvent>>>,
 }
 
 impl MessageOperationDomainService {
     pub fn new(archive_repo: Option<Arc<dyn ArchiveStoreRepository + Send + Sync>>) -> Self {
-        Self { archive_repo }
+        Self {
+            archive_repo,
+            hot_cache_repo: None,
+            event_bus: None,
+        }
+    }
+
+    /// 设置热缓存仓储（撤回、编辑、删除后失效会话最后一条消息视图）
+    pub fn with_hot_cache(
+        mut self,
+        hot_cache_repo: Option<Arc<dyn HotCacheRepository + Send + Sync>>,
+    ) -> Self {
+        self.hot_cache_repo = hot_cache_repo;
+        self
+    }
+
+    /// 设置写入事件总线（撤回、编辑、删除后发布事件，由订阅者失效缓存视图）
+    pub fn with_event_bus(mut self, event_bus: Arc<EventBus<StorageWriteEvent>>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
     }
 
     /// 检查消息是否为操作消息
@@ -94,11 +117,21 @@ impl MessageOperationDomainService {
 
         match OperationType::try_from(operation.operation_type) {
             Ok(OperationType::Recall) => {
-                self.handle_recall_operation(&operation, archive_repo).await
+                self.handle_recall_operation(&operation, archive_repo)
+                    .await?;
+                self.invalidate_last_message(message, &operation).await;
+                Ok(())
+            }
+            Ok(OperationType::Edit) => {
+                self.handle_edit_operation(&operation, archive_repo).await?;
+                self.invalidate_last_message(message, &operation).await;
+                Ok(())
             }
-            Ok(OperationType::Edit) => self.handle_edit_operation(&operation, archive_repo).await,
             Ok(OperationType::Delete) => {
-                self.handle_delete_operation(&operation, archive_repo).await
+                self.handle_delete_operation(&operation, archive_repo)
+                    .await?;
+                self.invalidate_last_message(message, &operation).await;
+                Ok(())
             }
             Ok(OperationType::Read) => {
                 self.handle_read_operation(&operation, archive_repo).await
@@ -122,6 +155,35 @@ impl MessageOperationDomainService {
         }
     }
 
+    /// 失效会话最后一条消息视图（失败不影响操作落库，读侧回源后会重建视图）
+    async fn invalidate_last_message(&self, message: &Message, operation: &MessageOperation) {
+        if let Some(bus) = &self.event_bus {
+            let event = StorageWriteEvent::MessageModified {
+                conversation_id: message.conversation_id.clone(),
+                message_id: operation.target_message_id.clone(),
+            };
+            // 停机期间总线已关闭时回退到同步失效，避免事件被静默丢弃
+            if bus.publish_wait(event).await.is_ok() {
+                return;
+            }
+        }
+
+        let Some(repo) = &self.hot_cache_repo else {
+            return;
+        };
+        if let Err(err) = repo
+            .invalidate_last_message(&message.conversation_id, &operation.target_message_id)
+            .await
+        {
+            warn!(
+                error = %err,
+                conversation_id = %message.conversation_id,
+                message_id = %operation.target_message_id,
+                "Failed to invalidate last message view"
+            );
+        }
+    }
+
     /// 处理撤回操作
     #[instrument(skip(self, archive_repo), fields(message_id = %operation.target_message_id))]
     async fn handle_recall_operation(
@@ -364,3 +426,240 @@ impl MessageOperationDomainService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use flare_proto::common::{DeleteOperationData, DeleteType};
+
+    use crate::application::handlers::write_event_handler::LastMessageCacheInvalidator;
+
+    #[derive(Default)]
+    struct MemoryArchive {
+        fail_fsm_update: bool,
+        fsm_updates: Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl ArchiveStoreRepository for MemoryArchive {
+        async fn store_archive(&self, _message: &Message) -> Result<()> {
+            Ok(())
+        }
+
+        async fn update_message_fsm_state(
+            &self,
+            message_id: &str,
+            fsm_state: &str,
+            _recall_reason: Option<&str>,
+        ) -> Result<()> {
+            if self.fail_fsm_update {
+                return Err(anyhow!("archive unavailable"));
+            }
+            self.fsm_updates
+                .lock()
+                .unwrap()
+                .push((message_id.to_string(), fsm_state.to_string()));
+            Ok(())
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryHotCache {
+        fail_invalidate: bool,
+        invalidated: Mutex<Vec<(String, String)>>,
+    }
+
+    impl MemoryHotCache {
+        fn invalidated(&self) -> Vec<(String, String)> {
+            self.invalidated.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl HotCacheRepository for MemoryHotCache {
+        async fn store_hot(&self, _message: &Message) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get_hot(
+            &self,
+            _conversation_id: &str,
+            _message_id: &str,
+        ) -> Result<Option<Message>> {
+            Ok(None)
+        }
+
+        async fn invalidate_last_message(
+            &self,
+            conversation_id: &str,
+            message_id: &str,
+        ) -> Result<()> {
+            if self.fail_invalidate {
+                return Err(anyhow!("redis unavailable"));
+            }
+            self.invalidated
+                .lock()
+                .unwrap()
+                .push((conversation_id.to_string(), message_id.to_string()));
+            Ok(())
+        }
+    }
+
+    fn service(
+        archive: Arc<MemoryArchive>,
+        hot: Arc<MemoryHotCache>,
+    ) -> MessageOperationDomainService {
+        MessageOperationDomainService::new(Some(archive)).with_hot_cache(Some(hot))
+    }
+
+    fn operation(operation_type: OperationType) -> MessageOperation {
+        MessageOperation {
+            operation_type: operation_type as i32,
+            target_message_id: "msg-1".to_string(),
+            operator_id: "user-1".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn hard_delete() -> MessageOperation {
+        MessageOperation {
+            operation_data: Some(OperationData::Delete(DeleteOperationData {
+                delete_type: DeleteType::Hard as i32,
+                ..Default::default()
+            })),
+            ..operation(OperationType::Delete)
+        }
+    }
+
+    fn message() -> Message {
+        Message {
+            conversation_id: "conv-1".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recall_and_hard_delete_invalidate_last_message() {
+        let archive = Arc::new(MemoryArchive::default());
+        let hot = Arc::new(MemoryHotCache::default());
+        let service = service(archive.clone(), hot.clone());
+
+        service
+            .process_operation(operation(OperationType::Recall), &message())
+            .await
+            .unwrap();
+        service
+            .process_operation(hard_delete(), &message())
+            .await
+            .unwrap();
+
+        let expected = ("conv-1".to_string(), "msg-1".to_string());
+        assert_eq!(hot.invalidated(), vec![expected.clone(), expected]);
+        assert_eq!(
+            *archive.fsm_updates.lock().unwrap(),
+            vec![
+                ("msg-1".to_string(), "RECALLED".to_string()),
+                ("msg-1".to_string(), "DELETED_HARD".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_operation_keeps_last_message() {
+        let hot = Arc::new(MemoryHotCache::default());
+        let service = service(Arc::new(MemoryArchive::default()), hot.clone());
+
+        service
+            .process_operation(operation(OperationType::Read), &message())
+            .await
+            .unwrap();
+
+        assert!(hot.invalidated().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_archive_failure_skips_invalidation() {
+        let archive = Arc::new(MemoryArchive {
+            fail_fsm_update: true,
+            ..Default::default()
+        });
+        let hot = Arc::new(MemoryHotCache::default());
+        let service = service(archive, hot.clone());
+
+        let result = service
+            .process_operation(operation(OperationType::Recall), &message())
+            .await;
+
+        assert!(result.is_err());
+        assert!(hot.invalidated().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalidation_failure_does_not_fail_operation() {
+        let archive = Arc::new(MemoryArchive::default());
+        let hot = Arc::new(MemoryHotCache {
+            fail_invalidate: true,
+            ..Default::default()
+        });
+        let service = service(archive.clone(), hot);
+
+        service
+            .process_operation(operation(OperationType::Recall), &message())
+            .await
+            .unwrap();
+
+        assert_eq!(archive.fsm_updates.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalidation_goes_through_event_bus() {
+        let hot = Arc::new(MemoryHotCache::default());
+        let subscriber_cache = Arc::new(MemoryHotCache::default());
+        let bus = Arc::new(EventBus::<StorageWriteEvent>::new("storage-writer-test"));
+        bus.subscribe(
+            "last-message-invalidator",
+            16,
+            Arc::new(LastMessageCacheInvalidator::new(subscriber_cache.clone())),
+        );
+        let service =
+            service(Arc::new(MemoryArchive::default()), hot.clone()).with_event_bus(bus.clone());
+
+        service
+            .process_operation(operation(OperationType::Recall), &message())
+            .await
+            .unwrap();
+        // 停机会等待订阅者处理完已入队的事件
+        bus.shutdown().await;
+
+        assert!(hot.invalidated().is_empty());
+        assert_eq!(
+            subscriber_cache.invalidated(),
+            vec![("conv-1".to_string(), "msg-1".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_closed_event_bus_falls_back_to_direct_invalidation() {
+        let hot = Arc::new(MemoryHotCache::default());
+        let bus = Arc::new(EventBus::<StorageWriteEvent>::new("storage-writer-test"));
+        bus.shutdown().await;
+        let service = service(Arc::new(MemoryArchive::default()), hot.clone()).with_event_bus(bus);
+
+        service
+            .process_operation(operation(OperationType::Recall), &message())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            hot.invalidated(),
+            vec![("conv-1".to_string(), "msg-1".to_string())]
+        );
+    }
+}
@@ -3,7 +3,9 @@
 use std::sync::Arc;
 
 use anyhow::{Result, anyhow};
-use flare_im_core::utils::{current_millis, extract_timeline_from_extra};
+use flare_im_core::utils::{
+    PersistenceReply, SYNC_REPLY_CHANNEL_TAG, current_millis, extract_timeline_from_extra,
+};
 use flare_proto::common::Message;
 use flare_proto::storage::StoreMessageRequest;
 use serde_json;
@@ -13,9 +15,10 @@ use crate::domain::events::{AckEvent, AckStatus};
 use crate::domain::model::{PersistenceResult, PreparedMessage};
 use crate::domain::repository::{
     AckPublisher, ArchiveStoreRepository, HotCacheRepository, MediaAttachmentVerifier,
-    MessageIdempotencyRepository, RealtimeStoreRepository, ConversationStateRepository,
+    MessageIdempotencyRepository, PersistenceReplyPublisher, RealtimeStoreRepository, ConversationStateRepository,
     ConversationUpdateRepository, UserSyncCursorRepository, WalCleanupRepository,
 };
+use crate::domain::service::consistency_verifier::RecentWriteSampler;
 use crate::domain::service::conversation_domain_service::ConversationDomainService; // 添加ConversationDomainService导入
 use flare_server_core::ServiceClient; // 添加ServiceClient导入
 use tokio::sync::Mutex; // 添加Mutex导入
@@ -35,6 +38,10 @@ pub struct MessagePersistenceDomainService {
     user_cursor_repo: Option<Arc<dyn UserSyncCursorRepository + Send + Sync>>,
     session_update_repo: Option<Arc<dyn ConversationUpdateRepository + Send + Sync>>,
     conversation_domain_service: Arc<ConversationDomainService>, // 使用ConversationDomainService替代原来的conversation_client
+    /// 一致性校验采样窗口（启用校验时记录成功写入的消息）
+    write_sampler: Option<Arc<RecentWriteSampler>>,
+    /// 同步写入回执发布器（未配置时 `sync = true` 的请求由编排服务等待超时）
+    reply_publisher: Option<Arc<dyn PersistenceReplyPublisher + Send + Sync>>,
 }
 
 impl MessagePersistenceDomainService {
@@ -67,14 +74,37 @@ impl MessagePersistenceDomainService {
             user_cursor_repo,
             session_update_repo,
             conversation_domain_service, // 使用ConversationDomainService
+            write_sampler: None,
+            reply_publisher: None,
         }
     }
 
+    /// 启用一致性校验采样
+    pub fn with_write_sampler(mut self, sampler: Arc<RecentWriteSampler>) -> Self {
+        self.write_sampler = Some(sampler);
+        self
+    }
+
+    /// 设置同步写入回执发布器
+    pub fn with_reply_publisher(
+        mut self,
+        publisher: Arc<dyn PersistenceReplyPublisher + Send + Sync>,
+    ) -> Self {
+        self.reply_publisher = Some(publisher);
+        self
+    }
+
     /// 准备消息（从请求中提取并准备消息）
     ///
     /// 注意：消息从 Kafka 队列中读取出来时，说明已经成功发送并被接收，
     /// 因此应该将状态从 `Created` 更新为 `Sent`
     pub fn prepare_message(&self, request: StoreMessageRequest) -> Result<PreparedMessage> {
+        let reply_channel = request
+            .sync
+            .then(|| request.tags.get(SYNC_REPLY_CHANNEL_TAG).cloned())
+            .flatten()
+            .filter(|channel| !channel.is_empty());
+
         let conversation_id = if request.conversation_id.is_empty() {
             request
                 .message
@@ -118,6 +148,7 @@ impl MessagePersistenceDomainService {
             message,
             timeline,
             sync: request.sync,
+            reply_channel,
         })
     }
 
@@ -241,6 +272,9 @@ impl MessagePersistenceDomainService {
         if let Some(repo) = &self.archive_repo {
             repo.store_archive(&prepared.message).await?;
         }
+        if let Some(sampler) = &self.write_sampler {
+            sampler.record(&conversation_id, &message_id);
+        }
 
         // Redis 更新
         if let Some(repo) = &self.conversation_state_repo {
@@ -267,7 +301,6 @@ impl MessagePersistenceDomainService {
             repo.batch_update_unread_count(&conversation_id, s, Some(&sender_id))
                 .await?;
         }
-
         // 批量持久化完成
         Ok(())
     }
@@ -309,6 +342,11 @@ impl MessagePersistenceDomainService {
         if let Some(repo) = &self.archive_repo {
             repo.store_archive_batch(&messages).await?;
         }
+        if let Some(sampler) = &self.write_sampler {
+            for p in &prepared {
+                sampler.record(&p.conversation_id, &p.message_id);
+            }
+        }
 
         // 3. 批量更新 Redis（按会话分组）
         let mut conversation_groups: std::collections::HashMap<String, Vec<(&PreparedMessage, i64)>> =
@@ -411,6 +449,28 @@ impl MessagePersistenceDomainService {
         Ok(())
     }
 
+    /// 发布同步写入回执（回复失败只记录警告，编排服务等待超时后返回未确认）
+    pub async fn publish_sync_reply(&self, channel: &str, seq: i64, result: &PersistenceResult) {
+        let Some(publisher) = &self.reply_publisher else {
+            return;
+        };
+        let reply = PersistenceReply {
+            message_id: result.message_id.clone(),
+            conversation_id: result.conversation_id.clone(),
+            seq,
+            persisted_ts: result.timeline.persisted_ts.unwrap_or_else(current_millis),
+            deduplicated: result.deduplicated,
+        };
+        if let Err(err) = publisher.publish(channel, &reply).await {
+            warn!(
+                error = ?err,
+                message_id = %result.message_id,
+                channel = %channel,
+                "Failed to publish sync persistence reply"
+            );
+        }
+    }
+
     /// 获取会话参与者列表
     ///
     /// 通过gRPC调用Conversation服务获取会话的所有参与者，用于更新未读数
@@ -565,3 +625,96 @@ impl MessagePersistenceDomainService {
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex as StdMutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use async_trait::async_trait;
+    use flare_im_core::utils::TimelineMetadata;
+    use flare_server_core::context::Context;
+
+    use super::*;
+
+    /// 记录未读数更新；`fail` 为真时模拟仓储故障
+    #[derive(Default)]
+    struct RecordingUpdateRepo {
+        fail: AtomicBool,
+        unread_updates: StdMutex<Vec<(String, i64)>>,
+    }
+
+    #[async_trait]
+    impl ConversationUpdateRepository for RecordingUpdateRepo {
+        async fn update_last_message(&self, _: &str, _: &str, _: i64) -> Result<()> {
+            if self.fail.load(Ordering::SeqCst) {
+                return Err(anyhow!("redis unavailable"));
+            }
+            Ok(())
+        }
+
+        async fn batch_update_unread_count(
+            &self,
+            conversation_id: &str,
+            last_message_seq: i64,
+            _: Option<&str>,
+        ) -> Result<()> {
+            self.unread_updates
+                .lock()
+                .unwrap()
+                .push((conversation_id.to_string(), last_message_seq));
+            Ok(())
+        }
+    }
+
+    fn prepared(seq: u64) -> PreparedMessage {
+        PreparedMessage {
+            conversation_id: "conv-1".to_string(),
+            message_id: format!("msg-{seq}"),
+            message: Message {
+                seq,
+                sender_id: "alice".to_string(),
+                ..Default::default()
+            },
+            timeline: TimelineMetadata::default(),
+            sync: false,
+            reply_channel: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_counter_failure_fails_write_and_redelivery_counts_once() {
+        let repo = Arc::new(RecordingUpdateRepo::default());
+        let service = MessagePersistenceDomainService::new(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(repo.clone()),
+            None,
+        );
+        let ctx = Context::root();
+
+        // 计数更新失败必须传回写入路径，消息不会被确认
+        repo.fail.store(true, Ordering::SeqCst);
+        assert!(service.persist_message(&ctx, prepared(7)).await.is_err());
+        assert!(repo.unread_updates.lock().unwrap().is_empty());
+
+        // 重新消费后计数增加恰好一次
+        repo.fail.store(false, Ordering::SeqCst);
+        service.persist_message(&ctx, prepared(7)).await.unwrap();
+        service
+            .persist_batch(&ctx, vec![prepared(8), prepared(9)])
+            .await
+            .unwrap();
+        assert_eq!(
+            *repo.unread_updates.lock().unwrap(),
+            vec![("conv-1".to_string(), 7), ("conv-1".to_string(), 9)]
+        );
+    }
+}
@@ -6,3 +6,9 @@ pub use message_operation::MessageOperationDomainService;
 
 pub mod conversation_domain_service;
 pub use conversation_domain_service::ConversationDomainService;
+
+pub mod consistency_verifier;
+pub use consistency_verifier::{RecentWriteSampler, StoreConsistencyVerifier};
+
+pub mod tenant_fairness;
+pub use tenant_fairness::{FairBatch, TenantFairScheduler, TenantFairnessPolicy};
@@ -0,0 +1,403 @@
+//! 租户公平调度领域服务
+//!
+//! 避免单个租户（例如批量导入历史消息）占满写入能力、饿死其他租户的实时写入：
+//! - 每个租户一个令牌桶，限制其写入速率（未配置速率时不限速）
+//! - 各租户积压队列之间按权重做差额轮询（DRR），每轮按权重分配可出队的条数
+//! - 租户积压持续超过阈值时判定为过载，由调用方暂停对应的 Kafka 分区
+//!
+//! 调度器只负责排队与出队顺序，不涉及 Kafka 与持久化
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// 租户公平调度策略
+#[derive(Debug, Clone)]
+pub struct TenantFairnessPolicy {
+    /// 默认每租户写入速率（条/秒，0 表示不限速）
+    pub default_rate: f64,
+    /// 令牌桶容量（允许的突发条数）
+    pub burst: f64,
+    /// 按租户覆盖的写入速率（条/秒，0 表示不限速）
+    pub tenant_rates: HashMap<String, f64>,
+    /// 租户权重（默认 1）
+    pub tenant_weights: HashMap<String, u32>,
+    /// 所有租户的积压上限（达到后停止拉取）
+    pub max_backlog: usize,
+    /// 单租户积压达到该值并持续 `pause_after` 后判定为过载
+    pub pause_backlog: usize,
+    /// 过载判定的持续时间
+    pub pause_after: Duration,
+}
+
+impl TenantFairnessPolicy {
+    fn rate_for(&self, tenant_id: &str) -> f64 {
+        self.tenant_rates
+            .get(tenant_id)
+            .copied()
+            .unwrap_or(self.default_rate)
+    }
+
+    fn weight_for(&self, tenant_id: &str) -> usize {
+        self.tenant_weights
+            .get(tenant_id)
+            .copied()
+            .unwrap_or(1)
+            .max(1) as usize
+    }
+
+    /// 过载租户的积压降到该值以下后恢复
+    pub fn resume_backlog(&self) -> usize {
+        self.pause_backlog / 2
+    }
+}
+
+/// 令牌桶
+struct TokenBucket {
+    /// 每秒补充的令牌数（0 表示不限速）
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, capacity: f64, now: Instant) -> Self {
+        let capacity = capacity.max(1.0);
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            refilled_at: now,
+        }
+    }
+
+    fn unlimited(&self) -> bool {
+        self.rate <= 0.0
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.refilled_at = now;
+    }
+
+    /// 当前可用的整数令牌数
+    fn available(&self) -> usize {
+        if self.unlimited() {
+            usize::MAX
+        } else {
+            self.tokens.floor() as usize
+        }
+    }
+
+    fn consume(&mut self, count: usize) {
+        if !self.unlimited() {
+            self.tokens -= count as f64;
+        }
+    }
+
+    /// 距下一个令牌可用的时间（按 `now` 推算，不修改桶状态）
+    fn next_token_in(&self, now: Instant) -> Duration {
+        if self.unlimited() {
+            return Duration::ZERO;
+        }
+        let elapsed = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        let tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        if tokens >= 1.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64((1.0 - tokens) / self.rate)
+    }
+}
+
+struct TenantQueue<T> {
+    items: VecDeque<T>,
+    bucket: TokenBucket,
+    weight: usize,
+    /// 差额计数（本轮尚可出队的条数）
+    deficit: usize,
+    /// 积压持续超过阈值的起始时间
+    overloaded_since: Option<Instant>,
+}
+
+/// 一次出队的结果
+pub struct FairBatch<T> {
+    /// (tenant_id, 条目)，同一租户内保持入队顺序
+    pub items: Vec<(String, T)>,
+    /// 因令牌不足本次未能出队的租户
+    pub throttled: Vec<String>,
+}
+
+/// 租户公平调度器
+pub struct TenantFairScheduler<T> {
+    policy: TenantFairnessPolicy,
+    tenants: HashMap<String, TenantQueue<T>>,
+    /// 有积压的租户（轮询顺序）
+    active: VecDeque<String>,
+    total: usize,
+}
+
+impl<T> TenantFairScheduler<T> {
+    pub fn new(policy: TenantFairnessPolicy) -> Self {
+        Self {
+            policy,
+            tenants: HashMap::new(),
+            active: VecDeque::new(),
+            total: 0,
+        }
+    }
+
+    pub fn policy(&self) -> &TenantFairnessPolicy {
+        &self.policy
+    }
+
+    /// 加入租户积压队列
+    pub fn push(&mut self, tenant_id: &str, item: T, now: Instant) {
+        let policy = &self.policy;
+        let queue = self
+            .tenants
+            .entry(tenant_id.to_string())
+            .or_insert_with(|| TenantQueue {
+                items: VecDeque::new(),
+                bucket: TokenBucket::new(policy.rate_for(tenant_id), policy.burst, now),
+                weight: policy.weight_for(tenant_id),
+                deficit: 0,
+                overloaded_since: None,
+            });
+        if queue.items.is_empty() {
+            self.active.push_back(tenant_id.to_string());
+        }
+        queue.items.push_back(item);
+        if queue.items.len() >= policy.pause_backlog && queue.overloaded_since.is_none() {
+            queue.overloaded_since = Some(now);
+        }
+        self.total += 1;
+    }
+
+    /// 按权重与令牌桶出队最多 `max` 条
+    pub fn next_batch(&mut self, max: usize, now: Instant) -> FairBatch<T> {
+        let mut batch = FairBatch {
+            items: Vec::new(),
+            throttled: Vec::new(),
+        };
+        for queue in self.tenants.values_mut() {
+            queue.bucket.refill(now);
+        }
+
+        while batch.items.len() < max && !self.active.is_empty() {
+            let mut progressed = false;
+            for _ in 0..self.active.len() {
+                if batch.items.len() >= max {
+                    break;
+                }
+                let Some(tenant_id) = self.active.pop_front() else {
+                    break;
+                };
+                let Some(queue) = self.tenants.get_mut(&tenant_id) else {
+                    continue;
+                };
+
+                let available = queue.bucket.available();
+                if available == 0 {
+                    if !batch.throttled.contains(&tenant_id) {
+                        batch.throttled.push(tenant_id.clone());
+                    }
+                    self.active.push_back(tenant_id);
+                    continue;
+                }
+
+                queue.deficit += queue.weight;
+                let count = queue
+                    .deficit
+                    .min(available)
+                    .min(queue.items.len())
+                    .min(max - batch.items.len());
+                // 令牌不足时未用完的差额不累积，避免令牌恢复后超出权重份额
+                queue.deficit = (queue.deficit - count).min(queue.weight);
+                queue.bucket.consume(count);
+                for item in queue.items.drain(..count) {
+                    batch.items.push((tenant_id.clone(), item));
+                }
+                self.total -= count;
+                progressed |= count > 0;
+
+                if queue.items.len() < self.policy.pause_backlog {
+                    queue.overloaded_since = None;
+                }
+                if queue.items.is_empty() {
+                    queue.deficit = 0;
+                } else {
+                    self.active.push_back(tenant_id);
+                }
+            }
+            if !progressed {
+                break;
+            }
+        }
+        batch
+    }
+
+    /// 所有租户的积压总数
+    pub fn len(&self) -> usize {
+        self.total
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// 积压是否达到上限
+    pub fn is_full(&self) -> bool {
+        self.total >= self.policy.max_backlog.max(1)
+    }
+
+    /// 租户的积压数量
+    pub fn backlog(&self, tenant_id: &str) -> usize {
+        self.tenants
+            .get(tenant_id)
+            .map(|queue| queue.items.len())
+            .unwrap_or(0)
+    }
+
+    /// 各租户的积压数量（包括已清空的租户，便于指标归零）
+    pub fn backlogs(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.tenants
+            .iter()
+            .map(|(tenant_id, queue)| (tenant_id.as_str(), queue.items.len()))
+    }
+
+    /// 租户积压中的条目
+    pub fn queued(&self, tenant_id: &str) -> impl Iterator<Item = &T> {
+        self.tenants
+            .get(tenant_id)
+            .into_iter()
+            .flat_map(|queue| queue.items.iter())
+    }
+
+    /// 积压持续超过阈值达到 `pause_after` 的租户
+    pub fn overloaded_tenants(&self, now: Instant) -> Vec<String> {
+        self.tenants
+            .iter()
+            .filter(|(_, queue)| {
+                queue.overloaded_since.is_some_and(|since| {
+                    now.saturating_duration_since(since) >= self.policy.pause_after
+                })
+            })
+            .map(|(tenant_id, _)| tenant_id.clone())
+            .collect()
+    }
+
+    /// 有积压但全部被限速时，距最早可出队的时间
+    pub fn next_ready_in(&self, now: Instant) -> Option<Duration> {
+        self.active
+            .iter()
+            .filter_map(|tenant_id| self.tenants.get(tenant_id))
+            .map(|queue| queue.bucket.next_token_in(now))
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> TenantFairnessPolicy {
+        TenantFairnessPolicy {
+            default_rate: 0.0,
+            burst: 100.0,
+            tenant_rates: HashMap::new(),
+            tenant_weights: HashMap::new(),
+            max_backlog: 1000,
+            pause_backlog: 100,
+            pause_after: Duration::from_secs(5),
+        }
+    }
+
+    fn tenants_of(batch: &FairBatch<u32>) -> Vec<&str> {
+        batch.items.iter().map(|(t, _)| t.as_str()).collect()
+    }
+
+    #[test]
+    fn test_weighted_round_robin() {
+        let mut policy = policy();
+        policy.tenant_weights.insert("bulk".to_string(), 1);
+        policy.tenant_weights.insert("realtime".to_string(), 2);
+        let mut scheduler = TenantFairScheduler::new(policy);
+        let now = Instant::now();
+        for i in 0..50 {
+            scheduler.push("bulk", i, now);
+        }
+        for i in 0..3 {
+            scheduler.push("realtime", i, now);
+        }
+
+        // 批量导入的租户排在前面，实时租户仍按权重获得出队机会
+        let batch = scheduler.next_batch(6, now);
+        assert_eq!(
+            tenants_of(&batch),
+            vec!["bulk", "realtime", "realtime", "bulk", "realtime", "bulk"]
+        );
+        // 同一租户内保持入队顺序
+        let bulk: Vec<u32> = batch
+            .items
+            .iter()
+            .filter(|(t, _)| t == "bulk")
+            .map(|(_, i)| *i)
+            .collect();
+        assert_eq!(bulk, vec![0, 1, 2]);
+        assert_eq!(scheduler.len(), 47);
+        assert_eq!(scheduler.backlog("realtime"), 0);
+    }
+
+    #[test]
+    fn test_token_bucket_throttles_tenant() {
+        let mut policy = policy();
+        policy.burst = 2.0;
+        policy.tenant_rates.insert("bulk".to_string(), 10.0);
+        let mut scheduler = TenantFairScheduler::new(policy);
+        let now = Instant::now();
+        for i in 0..10 {
+            scheduler.push("bulk", i, now);
+        }
+        scheduler.push("realtime", 0, now);
+
+        let batch = scheduler.next_batch(10, now);
+        assert_eq!(tenants_of(&batch), vec!["bulk", "realtime", "bulk"]);
+        assert_eq!(batch.throttled, vec!["bulk".to_string()]);
+
+        // 令牌耗尽后需等待补充
+        assert!(scheduler.next_batch(10, now).items.is_empty());
+        let wait = scheduler.next_ready_in(now).unwrap();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_millis(100));
+
+        let later = now + Duration::from_millis(100);
+        assert_eq!(scheduler.next_batch(10, later).items.len(), 1);
+    }
+
+    #[test]
+    fn test_overloaded_tenants() {
+        let mut policy = policy();
+        policy.pause_backlog = 3;
+        let mut scheduler = TenantFairScheduler::new(policy);
+        let now = Instant::now();
+        for i in 0..3 {
+            scheduler.push("bulk", i, now);
+        }
+        scheduler.push("realtime", 0, now);
+
+        assert!(scheduler.overloaded_tenants(now).is_empty());
+        let later = now + Duration::from_secs(5);
+        assert_eq!(
+            scheduler.overloaded_tenants(later),
+            vec!["bulk".to_string()]
+        );
+
+        // 积压回落到阈值以下后不再过载
+        scheduler.next_batch(1, later);
+        assert!(scheduler.overloaded_tenants(later).is_empty());
+    }
+}
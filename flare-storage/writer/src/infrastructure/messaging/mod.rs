@@ -1 +1,2 @@
 pub mod ack_publisher;
+pub mod reply_publisher;
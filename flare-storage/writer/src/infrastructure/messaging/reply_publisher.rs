@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use anyhow::Result;
+use flare_im_core::utils::PersistenceReply;
+use redis::{AsyncCommands, aio::ConnectionManager};
+use tokio::sync::OnceCell;
+
+use crate::domain::repository::PersistenceReplyPublisher;
+
+/// 通过 Redis Pub/Sub 发布同步写入回执
+pub struct RedisPersistenceReplyPublisher {
+    client: Arc<redis::Client>,
+    conn: OnceCell<ConnectionManager>,
+}
+
+impl RedisPersistenceReplyPublisher {
+    pub fn new(client: Arc<redis::Client>) -> Self {
+        Self {
+            client,
+            conn: OnceCell::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl PersistenceReplyPublisher for RedisPersistenceReplyPublisher {
+    async fn publish(&self, channel: &str, reply: &PersistenceReply) -> Result<()> {
+        let conn = self
+            .conn
+            .get_or_try_init(|| ConnectionManager::new(self.client.as_ref().clone()))
+            .await?;
+        let payload = serde_json::to_string(reply)?;
+        let _: i64 = conn.clone().publish(channel, payload).await?;
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use flare_im_core::encryption::FieldEncryptor;
 use flare_im_core::utils::timestamp_to_datetime;
 use flare_proto::common::{ContentType, Message, MessageSource, MessageStatus, MessageType};
 use prost::Message as _;
@@ -71,6 +72,52 @@ pub fn encode_message_content(message: &Message) -> Vec<u8> {
         .unwrap_or_default()
 }
 
+/// 加密消息内容，密文绑定到租户与消息 server_id（未启用加密或内容为空时原样返回）
+pub async fn seal_content(
+    encryptor: Option<&FieldEncryptor>,
+    tenant_id: &str,
+    server_id: &str,
+    content: Vec<u8>,
+) -> Result<Vec<u8>> {
+    match encryptor {
+        Some(encryptor) if !content.is_empty() => {
+            encryptor.encrypt(tenant_id, server_id, &content).await
+        }
+        _ => Ok(content),
+    }
+}
+
+/// 解密消息内容，校验密文属于该租户与消息 server_id（历史明文原样返回）
+pub async fn open_content(
+    encryptor: Option<&FieldEncryptor>,
+    tenant_id: &str,
+    server_id: &str,
+    content: Vec<u8>,
+) -> Result<Vec<u8>> {
+    match encryptor {
+        Some(encryptor) if FieldEncryptor::is_encrypted(&content) => {
+            let opened = encryptor.decrypt(tenant_id, server_id, &content).await?;
+            Ok(opened.plaintext)
+        }
+        _ => Ok(content),
+    }
+}
+
+/// 提取消息租户ID（优先 message.tenant，其次 extra 中的 tenant_id）
+pub fn extract_tenant_id(message: &Message, extra_value: &Map<String, Value>) -> String {
+    message
+        .tenant
+        .as_ref()
+        .map(|t| t.tenant_id.clone())
+        .or_else(|| {
+            extra_value
+                .get("tenant_id")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        })
+        .unwrap_or_else(|| "default".to_string())
+}
+
 pub fn build_extra_value(message: &Message) -> Result<Map<String, Value>> {
     let mut extra_value = Map::new();
 
@@ -7,14 +7,20 @@ use prost::Message;
 use serde_json::{json, Value};
 use sqlx::{Pool, Postgres, QueryBuilder, Row};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use flare_im_core::encryption::FieldEncryptor;
+use std::sync::Arc;
+
+use crate::infrastructure::persistence::helpers::seal_content;
 
 pub struct OperationStore {
     pool: Pool<Postgres>,
+    /// 消息内容加密器（可选）
+    encryptor: Option<Arc<FieldEncryptor>>,
 }
 
 impl OperationStore {
-    pub fn new(pool: Pool<Postgres>) -> Self {
-        Self { pool }
+    pub fn new(pool: Pool<Postgres>, encryptor: Option<Arc<FieldEncryptor>>) -> Self {
+        Self { pool, encryptor }
     }
 
     pub async fn update_message_fsm_state(
@@ -87,6 +93,13 @@ impl OperationStore {
 
         let mut new_content_bytes = Vec::new();
         new_content.encode(&mut new_content_bytes)?;
+        let new_content_bytes = seal_content(
+            self.encryptor.as_deref(),
+            tenant_id,
+            message_id,
+            new_content_bytes,
+        )
+        .await?;
 
         // UPDATE 时不需要 tenant_id 作为条件（唯一索引已保证）
         sqlx::query(
@@ -1,11 +1,13 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::Utc;
+use flare_im_core::encryption::FieldEncryptor;
 use flare_im_core::utils::timestamp_to_datetime;
 use flare_proto::common::Message;
 use prost::Message as _;
 use serde_json::to_value;
 use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
+use std::sync::Arc;
 
 use crate::infrastructure::persistence::operation_store;
 
@@ -15,6 +17,8 @@ use crate::domain::repository::ArchiveStoreRepository;
 pub struct PostgresMessageStore {
     pool: Pool<Postgres>,
     operation_store: operation_store::OperationStore,
+    /// 消息内容加密器（配置了密钥文件时启用）
+    encryptor: Option<Arc<FieldEncryptor>>,
 }
 
 impl PostgresMessageStore {
@@ -41,11 +45,21 @@ impl PostgresMessageStore {
             .connect(url)
             .await?;
 
-        let operation_store = operation_store::OperationStore::new(pool.clone());
+        let encryptor = match &config.encryption_key_file {
+            Some(path) => {
+                tracing::info!(key_file = %path, "Message content encryption enabled");
+                Some(Arc::new(FieldEncryptor::from_key_file(path)?))
+            }
+            None => None,
+        };
+
+        let operation_store =
+            operation_store::OperationStore::new(pool.clone(), encryptor.clone());
 
         let store = Self {
             pool,
             operation_store,
+            encryptor,
         };
         Ok(Some(store))
     }
@@ -111,8 +125,15 @@ impl PostgresMessageStore {
             .cloned()
             .unwrap_or_else(|| serde_json::Map::new());
 
-        // 解析 content 为 MessageContent
+        // 解析 content 为 MessageContent（启用加密时先解密）
         let content = if let Some(content_bytes) = row.content {
+            let content_bytes = crate::infrastructure::persistence::helpers::open_content(
+                self.encryptor.as_deref(),
+                &row.tenant_id,
+                &row.server_id,
+                content_bytes,
+            )
+            .await?;
             flare_proto::common::MessageContent::decode(content_bytes.as_slice())
                 .ok()
                 .map(|c| Some(c))
@@ -247,21 +268,20 @@ impl ArchiveStoreRepository for PostgresMessageStore {
 
         let timestamp = get_message_timestamp(message);
         let content_type = infer_content_type(message);
-        let content_bytes = encode_message_content(message);
         let extra_value = build_extra_value(message)?;
         let message_type_str = message_type_to_string(message.message_type);
         let seq = extract_seq_from_extra(&extra_value);
 
         // 提取 tenant_id（从 message.tenant 或 extra 中提取）
         use serde_json::Value as JsonValue;
-        let tenant_id = message.tenant.as_ref()
-            .map(|t| t.tenant_id.clone())
-            .or_else(|| {
-                extra_value.get("tenant_id")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-            })
-            .unwrap_or_else(|| "default".to_string());
+        let tenant_id = extract_tenant_id(message, &extra_value);
+        let content_bytes = seal_content(
+            self.encryptor.as_deref(),
+            &tenant_id,
+            &message.server_id,
+            encode_message_content(message),
+        )
+        .await?;
 
         // 提取 conversation_type（从 message 或 extra 中）
         let conversation_type_str = if message.conversation_type != 0 {
@@ -609,10 +629,29 @@ impl PostgresMessageStore {
         use sqlx::QueryBuilder;
         use std::time::Duration;
 
+        // 预先加密消息内容（加密为异步操作，无法在 map 中完成）
+        let mut sealed_contents = Vec::with_capacity(messages.len());
+        for message in messages {
+            use crate::infrastructure::persistence::helpers::*;
+
+            let extra_value = build_extra_value(message).unwrap_or_default();
+            let tenant_id = extract_tenant_id(message, &extra_value);
+            sealed_contents.push(
+                seal_content(
+                    self.encryptor.as_deref(),
+                    &tenant_id,
+                    &message.server_id,
+                    encode_message_content(message),
+                )
+                .await?,
+            );
+        }
+
         // 预先处理所有消息，提取需要的数据（在重试循环外，避免重复计算）
         let prepared_data: Vec<_> = messages
             .iter()
-            .map(|message| {
+            .zip(sealed_contents)
+            .map(|(message, content_bytes)| {
                 let timestamp = message
                     .timestamp
                     .as_ref()
@@ -623,7 +662,6 @@ impl PostgresMessageStore {
 
                 let extra_value = build_extra_value(message).unwrap_or_default();
                 let content_type = infer_content_type(message);
-                let content_bytes = encode_message_content(message);
                 let message_type_str = message_type_to_string(message.message_type);
                 let seq = extract_seq_from_extra(&extra_value);
                 let status_str = message_status_to_string(message.status);
@@ -797,7 +835,14 @@ impl PostgresMessageStore {
 
             let extra_value = build_extra_value(message).unwrap_or_default();
             let content_type = infer_content_type(message);
-            let content_bytes = encode_message_content(message);
+            let tenant_id = extract_tenant_id(message, &extra_value);
+            let content_bytes = seal_content(
+                self.encryptor.as_deref(),
+                &tenant_id,
+                &message.server_id,
+                encode_message_content(message),
+            )
+            .await?;
             let message_type_str = message_type_to_string(message.message_type);
             let seq = extract_seq_from_extra(&extra_value);
             let status_str = message_status_to_string(message.status);
@@ -2,17 +2,44 @@ use async_trait::async_trait;
 use std::sync::Arc;
 
 use anyhow::Result;
-use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use flare_im_core::encryption::{FieldEncryptor, open_cache_value, seal_cache_value};
 use prost::Message as _;
 use redis::{AsyncCommands, aio::ConnectionManager};
 use std::convert::TryInto;
 
 use crate::config::StorageWriterConfig;
 use crate::domain::repository::HotCacheRepository;
+use crate::infrastructure::persistence::helpers;
+
+/// 会话最后一条消息视图：仅当新消息 seq 不小于当前值时覆盖，避免乱序消费回退
+///
+/// KEYS[1] = `cache:session:{conversation_id}:last`
+/// ARGV = [seq, server_id, encoded_message, ttl_seconds]
+const UPDATE_LAST_MESSAGE_SCRIPT: &str = r#"
+local current = redis.call('HGET', KEYS[1], 'seq')
+if current and tonumber(current) > tonumber(ARGV[1]) then
+    return 0
+end
+redis.call('HSET', KEYS[1], 'seq', ARGV[1], 'server_id', ARGV[2], 'message', ARGV[3])
+if tonumber(ARGV[4]) > 0 then
+    redis.call('EXPIRE', KEYS[1], ARGV[4])
+end
+return 1
+"#;
+
+/// 最后一条消息被撤回/编辑/删除时失效视图（仅当视图仍指向该消息）
+const INVALIDATE_LAST_MESSAGE_SCRIPT: &str = r#"
+if redis.call('HGET', KEYS[1], 'server_id') == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
 
 pub struct RedisHotCacheRepository {
     client: Arc<redis::Client>,
     ttl_seconds: u64,
+    /// 字段加密器（启用时缓存值加密存储，避免 Redis 中保留明文副本）
+    encryptor: Option<Arc<FieldEncryptor>>,
     // 注意：redis-rs 的 ConnectionManager 内部已实现连接池，无需手动管理
 }
 
@@ -21,15 +48,48 @@ impl RedisHotCacheRepository {
         Self {
             client,
             ttl_seconds: config.redis_hot_ttl_seconds,
+            encryptor: None,
         }
     }
 
+    /// 启用缓存值加密
+    pub fn with_encryptor(mut self, encryptor: Option<Arc<FieldEncryptor>>) -> Self {
+        self.encryptor = encryptor;
+        self
+    }
+
+    /// 编码并加密（启用时）缓存值，密文绑定到缓存键
+    async fn seal(&self, cache_key: &str, tenant_id: &str, buf: &[u8]) -> Result<String> {
+        seal_cache_value(self.encryptor.as_deref(), tenant_id, cache_key, buf).await
+    }
+
     /// 获取连接（redis-rs 内部已实现连接池，自动复用）
     async fn get_connection(&self) -> Result<ConnectionManager> {
         // redis-rs 的 ConnectionManager 内部已经实现了连接池
         // 直接创建即可，底层会自动复用连接
         Ok(ConnectionManager::new(self.client.as_ref().clone()).await?)
     }
+
+    /// 更新会话最后一条消息视图
+    async fn update_last_message(
+        &self,
+        conn: &mut ConnectionManager,
+        message: &flare_proto::common::Message,
+        tenant_id: &str,
+        buf: &[u8],
+    ) -> Result<()> {
+        let last_key = format!("cache:session:{}:last", message.conversation_id);
+        let encoded = self.seal(&last_key, tenant_id, buf).await?;
+        let _: i64 = redis::Script::new(UPDATE_LAST_MESSAGE_SCRIPT)
+            .key(&last_key)
+            .arg(message.seq)
+            .arg(&message.server_id)
+            .arg(&encoded)
+            .arg(self.ttl_seconds)
+            .invoke_async(conn)
+            .await?;
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -40,15 +100,18 @@ impl HotCacheRepository for RedisHotCacheRepository {
         let message_key = format!("cache:msg:{}:{}", message.conversation_id, message.server_id);
         let index_key = format!("cache:session:{}:index", message.conversation_id);
 
-        // 将 Message 编码为 protobuf bytes，然后 base64 编码存储
+        // 将 Message 编码为 protobuf bytes，然后 base64 编码存储（启用加密时整体加密）
+        let tenant_id = cache_tenant_id(message);
         let mut buf = Vec::new();
         message.encode(&mut buf)?;
-        let encoded = BASE64.encode(&buf);
-        let _: () = conn.set(&message_key, encoded).await?;
+        let encoded = self.seal(&message_key, &tenant_id, &buf).await?;
+        let _: () = conn.set(&message_key, &encoded).await?;
         if self.ttl_seconds > 0 {
             let ttl: i64 = self.ttl_seconds.try_into()?;
             let _: () = conn.expire(&message_key, ttl).await?;
         }
+        self.update_last_message(&mut conn, message, &tenant_id, &buf)
+            .await?;
 
         // 从 extra 中提取 ingestion_ts，如果没有则使用当前时间
         let ingestion_ts = flare_im_core::utils::extract_timeline_from_extra(
@@ -83,6 +146,11 @@ impl HotCacheRepository for RedisHotCacheRepository {
         // 按会话分组，优化索引更新
         let mut session_indices: std::collections::HashMap<String, Vec<(String, f64)>> =
             std::collections::HashMap::new();
+        // 每个会话 seq 最大的消息（用于更新最后一条消息视图）
+        let mut session_last: std::collections::HashMap<
+            &str,
+            (&flare_proto::common::Message, String, Vec<u8>),
+        > = std::collections::HashMap::new();
 
         // 构建 Pipeline
         let mut pipe = redis::pipe();
@@ -98,13 +166,20 @@ impl HotCacheRepository for RedisHotCacheRepository {
         for message in messages {
             let message_key = format!("cache:msg:{}:{}", message.conversation_id, message.server_id);
 
-            // 编码消息
+            // 编码消息（启用加密时整体加密）
+            let tenant_id = cache_tenant_id(message);
             let mut buf = Vec::new();
             message.encode(&mut buf)?;
-            let encoded = BASE64.encode(&buf);
+            let encoded = self.seal(&message_key, &tenant_id, &buf).await?;
 
             // 添加到 Pipeline：SET 命令
             pipe.cmd("SET").arg(&message_key).arg(&encoded);
+            match session_last.get(message.conversation_id.as_str()) {
+                Some((last, _, _)) if last.seq > message.seq => {}
+                _ => {
+                    session_last.insert(&message.conversation_id, (message, tenant_id, buf));
+                }
+            }
 
             // 添加到 Pipeline：EXPIRE 命令（如果有 TTL）
             if ttl > 0 {
@@ -153,6 +228,12 @@ impl HotCacheRepository for RedisHotCacheRepository {
             let _: Vec<redis::Value> = zadd_pipe.query_async(&mut conn).await?;
         }
 
+        // 更新最后一条消息视图（每个会话只写一次）
+        for (message, tenant_id, buf) in session_last.into_values() {
+            self.update_last_message(&mut conn, message, &tenant_id, &buf)
+                .await?;
+        }
+
         tracing::debug!(
             batch_size = messages.len(),
             "Successfully batch cached {} messages to Redis using Pipeline",
@@ -161,4 +242,119 @@ impl HotCacheRepository for RedisHotCacheRepository {
 
         Ok(())
     }
+
+    async fn get_hot(
+        &self,
+        conversation_id: &str,
+        message_id: &str,
+    ) -> Result<Option<flare_proto::common::Message>> {
+        let mut conn = self.get_connection().await?;
+
+        let message_key = format!("cache:msg:{}:{}", conversation_id, message_id);
+        let encoded: Option<String> = conn.get(&message_key).await?;
+        let Some(encoded) = encoded else {
+            return Ok(None);
+        };
+
+        let buf = open_cache_value(self.encryptor.as_deref(), &message_key, &encoded).await?;
+        Ok(Some(flare_proto::common::Message::decode(buf.as_slice())?))
+    }
+
+    async fn invalidate_last_message(&self, conversation_id: &str, message_id: &str) -> Result<()> {
+        let mut conn = self.get_connection().await?;
+
+        let last_key = format!("cache:session:{}:last", conversation_id);
+        let _: i64 = redis::Script::new(INVALIDATE_LAST_MESSAGE_SCRIPT)
+            .key(&last_key)
+            .arg(message_id)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+}
+
+/// 缓存值加密使用的租户（与落库时的租户解析一致）
+fn cache_tenant_id(message: &flare_proto::common::Message) -> String {
+    helpers::extract_tenant_id(
+        message,
+        &helpers::build_extra_value(message).unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn invoke_update(
+        conn: &mut ConnectionManager,
+        key: &str,
+        seq: i64,
+        server_id: &str,
+    ) -> i64 {
+        redis::Script::new(UPDATE_LAST_MESSAGE_SCRIPT)
+            .key(key)
+            .arg(seq)
+            .arg(server_id)
+            .arg(format!("encoded-{server_id}"))
+            .arg(60)
+            .invoke_async(conn)
+            .await
+            .unwrap()
+    }
+
+    async fn invoke_invalidate(conn: &mut ConnectionManager, key: &str, server_id: &str) -> i64 {
+        redis::Script::new(INVALIDATE_LAST_MESSAGE_SCRIPT)
+            .key(key)
+            .arg(server_id)
+            .invoke_async(conn)
+            .await
+            .unwrap()
+    }
+
+    async fn connect() -> ConnectionManager {
+        let url =
+            std::env::var("STORAGE_TEST_REDIS_URL").expect("STORAGE_TEST_REDIS_URL is not set");
+        let client = redis::Client::open(url).unwrap();
+        ConnectionManager::new(client).await.unwrap()
+    }
+
+    /// `STORAGE_TEST_REDIS_URL=redis://... cargo test -p flare-storage-writer -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires Redis (STORAGE_TEST_REDIS_URL)"]
+    async fn test_update_last_message_script_ignores_older_seq() {
+        let mut conn = connect().await;
+        let key = format!("test:cache:session:{}:last", uuid::Uuid::new_v4());
+
+        assert_eq!(invoke_update(&mut conn, &key, 10, "msg-10").await, 1);
+        // 乱序到达的旧消息不覆盖视图
+        assert_eq!(invoke_update(&mut conn, &key, 9, "msg-9").await, 0);
+        let server_id: String = conn.hget(&key, "server_id").await.unwrap();
+        assert_eq!(server_id, "msg-10");
+        // 相同 seq（重复消费）与更新的 seq 均覆盖
+        assert_eq!(invoke_update(&mut conn, &key, 10, "msg-10").await, 1);
+        assert_eq!(invoke_update(&mut conn, &key, 11, "msg-11").await, 1);
+        let (seq, message): (i64, String) = conn.hget(&key, &["seq", "message"]).await.unwrap();
+        assert_eq!((seq, message.as_str()), (11, "encoded-msg-11"));
+        let ttl: i64 = conn.ttl(&key).await.unwrap();
+        assert!(ttl > 0 && ttl <= 60);
+
+        let _: () = conn.del(&key).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Redis (STORAGE_TEST_REDIS_URL)"]
+    async fn test_invalidate_last_message_script_only_matches_current_message() {
+        let mut conn = connect().await;
+        let key = format!("test:cache:session:{}:last", uuid::Uuid::new_v4());
+        invoke_update(&mut conn, &key, 5, "msg-5").await;
+
+        // 被修改的不是当前最后一条消息时保留视图
+        assert_eq!(invoke_invalidate(&mut conn, &key, "msg-4").await, 0);
+        let exists: bool = conn.exists(&key).await.unwrap();
+        assert!(exists);
+
+        assert_eq!(invoke_invalidate(&mut conn, &key, "msg-5").await, 1);
+        let exists: bool = conn.exists(&key).await.unwrap();
+        assert!(!exists);
+    }
 }
@@ -1,5 +1,6 @@
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use flare_im_core::metrics::StorageWriterMetrics;
@@ -7,14 +8,18 @@ use flare_proto::storage::StoreMessageRequest;
 use flare_server_core::error::{ErrorBuilder, ErrorCode};
 use flare_server_core::kafka::{build_kafka_consumer, subscribe_and_wait_for_assignment};
 use prost::Message as _;
-use rdkafka::Message;
 use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
 use rdkafka::message::BorrowedMessage;
+use rdkafka::{Message, Offset, TopicPartitionList};
 use tracing::{Span, debug, error, info, instrument, warn};
 
 use crate::application::commands::ProcessStoreMessageCommand;
 use crate::application::handlers::MessagePersistenceCommandHandler;
 use crate::config::StorageWriterConfig;
+use crate::domain::service::{TenantFairScheduler, TenantFairnessPolicy};
+
+/// 公平调度积压中的一条消息
+type ScheduledMessage<'a> = (StoreMessageRequest, BorrowedMessage<'a>);
 
 pub struct NormalMessageConsumer {
     config: Arc<StorageWriterConfig>,
@@ -81,6 +86,10 @@ impl NormalMessageConsumer {
             "Starting normal message consumer loop"
         );
 
+        if self.config.fairness_enabled {
+            return self.consume_messages_fair().await;
+        }
+
         loop {
             let mut batch = Vec::new();
             let max_records = 100;
@@ -152,17 +161,59 @@ impl NormalMessageConsumer {
         let mut valid_messages = Vec::new();
 
         for message in messages {
-            let payload = match message.payload() {
-                Some(payload) => payload,
-                None => {
-                    warn!("Kafka message without payload encountered");
-                    continue;
-                }
-            };
+            if let Some(request) = self.decode_message(&message) {
+                requests.push(request);
+                valid_messages.push(message);
+            }
+        }
+
+        if !self.persist_requests(requests, batch_start).await {
+            return Ok(());
+        }
+
+        for message in &valid_messages {
+            self.commit_message(message);
+        }
+
+        info!(
+            batch_size = valid_messages.len(),
+            "Batch normal messages persisted successfully"
+        );
 
-            match StoreMessageRequest::decode(payload) {
-                Ok(mut request) => {
-                    if let Some(ref mut msg) = request.message {
+        Ok(())
+    }
+
+    /// 解码存储请求（兼容 PushMessageRequest），无法解码时返回 None
+    fn decode_message(&self, message: &BorrowedMessage<'_>) -> Option<StoreMessageRequest> {
+        let payload = match message.payload() {
+            Some(payload) => payload,
+            None => {
+                warn!("Kafka message without payload encountered");
+                return None;
+            }
+        };
+
+        match StoreMessageRequest::decode(payload) {
+            Ok(mut request) => {
+                if let Some(ref mut msg) = request.message {
+                    msg.client_msg_id =
+                        String::from_utf8_lossy(msg.client_msg_id.as_bytes()).to_string();
+                    if let Some(ref mut content) = msg.content {
+                        if let Some(flare_proto::common::message_content::Content::Text(
+                            ref mut text_content,
+                        )) = content.content
+                        {
+                            text_content.text =
+                                String::from_utf8_lossy(text_content.text.as_bytes()).to_string();
+                        }
+                    }
+                }
+                Some(request)
+            }
+            Err(err) => {
+                tracing::warn!(error = ?err, "Failed to decode StoreMessageRequest, trying PushMessageRequest fallback");
+                if let Ok(mut push_req) = flare_proto::push::PushMessageRequest::decode(payload) {
+                    if let Some(ref mut msg) = push_req.message {
                         msg.client_msg_id =
                             String::from_utf8_lossy(msg.client_msg_id.as_bytes()).to_string();
                         if let Some(ref mut content) = msg.content {
@@ -175,52 +226,37 @@ impl NormalMessageConsumer {
                                         .to_string();
                             }
                         }
-                    }
-                    requests.push(request);
-                    valid_messages.push(message);
-                }
-                Err(err) => {
-                    tracing::warn!(error = ?err, "Failed to decode StoreMessageRequest, trying PushMessageRequest fallback");
-                    if let Ok(mut push_req) = flare_proto::push::PushMessageRequest::decode(payload)
-                    {
-                        if let Some(ref mut msg) = push_req.message {
-                            msg.client_msg_id =
-                                String::from_utf8_lossy(msg.client_msg_id.as_bytes()).to_string();
-                            if let Some(ref mut content) = msg.content {
-                                if let Some(flare_proto::common::message_content::Content::Text(
-                                    ref mut text_content,
-                                )) = content.content
-                                {
-                                    text_content.text =
-                                        String::from_utf8_lossy(text_content.text.as_bytes())
-                                            .to_string();
-                                }
-                            }
-                            let store = flare_proto::storage::StoreMessageRequest {
-                                conversation_id: msg.conversation_id.clone(),
-                                message: Some(msg.clone()),
-                                sync: false,
-                                context: Default::default(),
-                                tenant: Default::default(),
-                                tags: std::collections::HashMap::new(),
-                            };
-                            requests.push(store);
-                            valid_messages.push(message);
-                        } else {
-                            error!("PushMessageRequest without message payload");
-                        }
+                        Some(flare_proto::storage::StoreMessageRequest {
+                            conversation_id: msg.conversation_id.clone(),
+                            message: Some(msg.clone()),
+                            sync: false,
+                            context: Default::default(),
+                            tenant: Default::default(),
+                            tags: std::collections::HashMap::new(),
+                        })
                     } else {
-                        tracing::warn!(
-                            error = ?err,
-                            offset = message.offset(),
-                            partition = message.partition(),
-                            "Failed to decode message, skipping (development mode)"
-                        );
+                        error!("PushMessageRequest without message payload");
+                        None
                     }
+                } else {
+                    tracing::warn!(
+                        error = ?err,
+                        offset = message.offset(),
+                        partition = message.partition(),
+                        "Failed to decode message, skipping (development mode)"
+                    );
+                    None
                 }
             }
         }
+    }
 
+    /// 持久化一批存储请求，失败时返回 false（不提交 offset）
+    async fn persist_requests(
+        &self,
+        requests: Vec<StoreMessageRequest>,
+        batch_start: Instant,
+    ) -> bool {
         let commands: Vec<_> = requests
             .into_iter()
             .map(|req| ProcessStoreMessageCommand { request: req })
@@ -228,24 +264,255 @@ impl NormalMessageConsumer {
 
         if let Err(e) = self.command_handler.handle_batch(commands).await {
             error!(error = %e, "Failed to process batch");
-            return Ok(());
+            return false;
         }
 
         let batch_duration = batch_start.elapsed();
         self.metrics
             .messages_persisted_duration_seconds
             .observe(batch_duration.as_secs_f64());
+        true
+    }
 
-        for message in &valid_messages {
-            self.commit_message(message);
-        }
-
+    /// 租户公平调度模式的消费循环
+    ///
+    /// 拉取的消息先按租户进入积压队列，再按权重与令牌桶出队写入，避免单个租户饿死其他租户。
+    /// 出队顺序与分区内顺序不一致，offset 按分区只提交连续完成的部分；
+    /// 租户积压持续超限时暂停其消息所在的分区，积压达到总上限时暂停全部分区（仍持续 poll 以保持组成员身份）
+    async fn consume_messages_fair(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let policy = TenantFairnessPolicy {
+            default_rate: self.config.fairness_tenant_rate,
+            burst: self.config.fairness_tenant_burst,
+            tenant_rates: self.config.fairness_tenant_rates.clone(),
+            tenant_weights: self.config.fairness_tenant_weights.clone(),
+            max_backlog: self.config.fairness_max_backlog,
+            pause_backlog: self.config.fairness_pause_backlog,
+            pause_after: Duration::from_millis(self.config.fairness_pause_after_ms),
+        };
         info!(
-            batch_size = valid_messages.len(),
-            "Batch normal messages persisted successfully"
+            max_backlog = policy.max_backlog,
+            pause_backlog = policy.pause_backlog,
+            tenant_rate = policy.default_rate,
+            "Tenant fairness scheduling enabled"
         );
 
-        Ok(())
+        let mut scheduler: TenantFairScheduler<ScheduledMessage<'_>> =
+            TenantFairScheduler::new(policy);
+        let mut offsets: HashMap<i32, PartitionOffsets> = HashMap::new();
+        // 分区 -> 触发暂停的租户
+        let mut paused: HashMap<i32, String> = HashMap::new();
+        let mut backlog_full = false;
+        let max_records = self.config.max_poll_records.max(1);
+        let fetch_wait = Duration::from_millis(self.config.fetch_max_wait_ms);
+
+        loop {
+            // 积压全部被限速时，最多等待到下一个令牌可用
+            let wait = if scheduler.is_empty() {
+                fetch_wait
+            } else {
+                scheduler
+                    .next_ready_in(Instant::now())
+                    .unwrap_or(fetch_wait)
+                    .min(fetch_wait)
+            };
+
+            for _ in 0..max_records {
+                match tokio::time::timeout(wait, self.kafka_consumer.recv()).await {
+                    Ok(Ok(message)) => {
+                        offsets
+                            .entry(message.partition())
+                            .or_default()
+                            .track(message.offset());
+                        match self.decode_message(&message) {
+                            Some(request) => {
+                                let tenant_id = request_tenant_id(&request);
+                                scheduler.push(&tenant_id, (request, message), Instant::now());
+                            }
+                            None => {
+                                // 无法解码的消息直接视为已完成
+                                offsets
+                                    .entry(message.partition())
+                                    .or_default()
+                                    .complete(message.offset());
+                            }
+                        }
+                        if scheduler.is_full() {
+                            break;
+                        }
+                    }
+                    Ok(Err(e)) => {
+                        error!(error = ?e, "Error receiving normal message from Kafka");
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        break;
+                    }
+                    Err(_) => break,
+                }
+            }
+
+            self.apply_backpressure(&scheduler, &mut paused, &mut backlog_full);
+
+            let batch = scheduler.next_batch(max_records, Instant::now());
+            for tenant_id in &batch.throttled {
+                self.metrics
+                    .tenant_throttled_total
+                    .with_label_values(&[tenant_id.as_str()])
+                    .inc();
+            }
+            if !batch.items.is_empty() {
+                let batch_start = Instant::now();
+                self.metrics.batch_size.observe(batch.items.len() as f64);
+                let (requests, messages): (Vec<_>, Vec<_>) =
+                    batch.items.into_iter().map(|(_, item)| item).unzip();
+
+                // 与非公平模式一致：写入失败只记录日志，不阻塞后续 offset 提交
+                if self.persist_requests(requests, batch_start).await {
+                    debug!(
+                        batch_size = messages.len(),
+                        backlog = scheduler.len(),
+                        "Fair-scheduled batch persisted"
+                    );
+                }
+                for message in &messages {
+                    offsets
+                        .entry(message.partition())
+                        .or_default()
+                        .complete(message.offset());
+                }
+            }
+            self.commit_offsets(&mut offsets);
+
+            for (tenant_id, backlog) in scheduler.backlogs() {
+                self.metrics
+                    .tenant_backlog
+                    .with_label_values(&[tenant_id])
+                    .set(backlog as i64);
+            }
+        }
+    }
+
+    /// 过载控制：暂停/恢复 Kafka 分区
+    fn apply_backpressure(
+        &self,
+        scheduler: &TenantFairScheduler<ScheduledMessage<'_>>,
+        paused: &mut HashMap<i32, String>,
+        backlog_full: &mut bool,
+    ) {
+        let policy = scheduler.policy();
+
+        // 积压达到总上限：暂停全部分区，回落到一半以下后恢复（租户过载暂停的分区除外）
+        if scheduler.is_full() && !*backlog_full {
+            warn!(
+                backlog = scheduler.len(),
+                "Fairness backlog full, pausing all assigned partitions"
+            );
+            self.set_paused(self.assigned_partitions(), true);
+            *backlog_full = true;
+        } else if *backlog_full && scheduler.len() <= policy.max_backlog / 2 {
+            let partitions = self
+                .assigned_partitions()
+                .into_iter()
+                .filter(|partition| !paused.contains_key(partition))
+                .collect();
+            self.set_paused(partitions, false);
+            *backlog_full = false;
+        }
+
+        // 租户积压持续超限：暂停其消息所在的分区
+        for tenant_id in scheduler.overloaded_tenants(Instant::now()) {
+            let partitions: BTreeSet<i32> = scheduler
+                .queued(&tenant_id)
+                .map(|(_, message)| message.partition())
+                .filter(|partition| !paused.contains_key(partition))
+                .collect();
+            if partitions.is_empty() {
+                continue;
+            }
+            warn!(
+                tenant_id = %tenant_id,
+                backlog = scheduler.backlog(&tenant_id),
+                partitions = ?partitions,
+                "Tenant write backlog overloaded, pausing partitions"
+            );
+            self.set_paused(partitions.iter().copied().collect(), true);
+            for partition in partitions {
+                paused.insert(partition, tenant_id.clone());
+            }
+        }
+
+        let resumable: Vec<i32> = paused
+            .iter()
+            .filter(|(_, tenant_id)| scheduler.backlog(tenant_id) <= policy.resume_backlog())
+            .map(|(partition, _)| *partition)
+            .collect();
+        for partition in &resumable {
+            if let Some(tenant_id) = paused.remove(partition) {
+                info!(
+                    tenant_id = %tenant_id,
+                    partition,
+                    "Tenant backlog drained, resuming partition"
+                );
+            }
+        }
+        if !resumable.is_empty() && !*backlog_full {
+            self.set_paused(resumable, false);
+        }
+
+        self.metrics.paused_partitions.set(paused.len() as i64);
+    }
+
+    /// 当前分配到的分区
+    fn assigned_partitions(&self) -> Vec<i32> {
+        match self.kafka_consumer.assignment() {
+            Ok(assignment) => assignment
+                .elements()
+                .iter()
+                .map(|element| element.partition())
+                .collect(),
+            Err(err) => {
+                warn!(error = ?err, "Failed to read Kafka partition assignment");
+                Vec::new()
+            }
+        }
+    }
+
+    fn set_paused(&self, partitions: Vec<i32>, pause: bool) {
+        if partitions.is_empty() {
+            return;
+        }
+        let mut tpl = TopicPartitionList::new();
+        for partition in partitions {
+            tpl.add_partition(&self.config.kafka_topic, partition);
+        }
+        let result = if pause {
+            self.kafka_consumer.pause(&tpl)
+        } else {
+            self.kafka_consumer.resume(&tpl)
+        };
+        if let Err(err) = result {
+            warn!(error = ?err, pause, "Failed to change Kafka partition pause state");
+        }
+    }
+
+    /// 提交各分区连续完成的 offset
+    fn commit_offsets(&self, offsets: &mut HashMap<i32, PartitionOffsets>) {
+        let mut tpl = TopicPartitionList::new();
+        for (partition, tracker) in offsets.iter_mut() {
+            if let Some(offset) = tracker.commit_point() {
+                if let Err(err) = tpl.add_partition_offset(
+                    &self.config.kafka_topic,
+                    *partition,
+                    Offset::Offset(offset),
+                ) {
+                    warn!(error = ?err, partition, "Invalid Kafka commit offset");
+                }
+            }
+        }
+        if tpl.count() == 0 {
+            return;
+        }
+        if let Err(err) = self.kafka_consumer.commit(&tpl, CommitMode::Async) {
+            warn!(error = ?err, "Failed to commit Kafka offsets");
+        }
     }
 
     fn commit_message(&self, message: &BorrowedMessage<'_>) {
@@ -258,3 +525,55 @@ impl NormalMessageConsumer {
     }
 }
 
+/// 消息的租户ID（请求上下文优先，其次消息本身）
+fn request_tenant_id(request: &StoreMessageRequest) -> String {
+    request
+        .tenant
+        .as_ref()
+        .map(|tenant| tenant.tenant_id.as_str())
+        .filter(|tenant_id| !tenant_id.is_empty())
+        .or_else(|| {
+            request
+                .message
+                .as_ref()
+                .and_then(|message| message.tenant.as_ref())
+                .map(|tenant| tenant.tenant_id.as_str())
+                .filter(|tenant_id| !tenant_id.is_empty())
+        })
+        .unwrap_or("default")
+        .to_string()
+}
+
+/// 分区内的在途 offset（乱序完成时只提交连续完成的部分）
+#[derive(Default)]
+struct PartitionOffsets {
+    in_flight: BTreeSet<i64>,
+    /// 已完成的最大 offset
+    max_done: Option<i64>,
+    /// 已提交的 offset（下一条待消费的位置）
+    committed: Option<i64>,
+}
+
+impl PartitionOffsets {
+    fn track(&mut self, offset: i64) {
+        self.in_flight.insert(offset);
+    }
+
+    fn complete(&mut self, offset: i64) {
+        self.in_flight.remove(&offset);
+        self.max_done = Some(self.max_done.map_or(offset, |done| done.max(offset)));
+    }
+
+    /// 可提交的 offset（较上次提交前进时返回）
+    fn commit_point(&mut self) -> Option<i64> {
+        let next = match self.in_flight.first() {
+            Some(offset) => *offset,
+            None => self.max_done? + 1,
+        };
+        if self.committed.is_some_and(|committed| committed >= next) {
+            return None;
+        }
+        self.committed = Some(next);
+        Some(next)
+    }
+}
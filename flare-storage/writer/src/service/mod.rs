@@ -15,10 +15,10 @@ pub struct ApplicationBootstrap;
 impl ApplicationBootstrap {
     /// 运行应用的主入口点
     pub async fn run() -> Result<()> {
-        use flare_im_core::load_config;
+        use flare_im_core::{load_config_with_validation, strict_mode};
 
         // 加载应用配置
-        let app_config = load_config(Some("./config"));
+        let app_config = load_config_with_validation(Some("./config"), strict_mode())?;
 
         // 使用 Wire 风格的依赖注入构建应用上下文
         let context = self::wire::initialize(app_config).await?;
@@ -39,7 +39,8 @@ impl ApplicationBootstrap {
         // 使用 ServiceRuntime 管理两个独立的消费者
         let normal_consumer = context.normal_consumer;
         let operation_consumer = context.operation_consumer;
-        
+        let event_bus = context.event_bus;
+
         let runtime = ServiceRuntime::new_consumer_only("storage-writer")
             .add_consumer(
                 "normal-message-consumer",
@@ -58,7 +59,12 @@ impl ApplicationBootstrap {
                         .await
                         .map_err(|e| format!("Operation message consumer error: {}", e).into())
                 },
-            );
+            )
+            .add_spawn_with_shutdown("storage-write-event-bus", move |shutdown_rx| {
+                event_bus.serve_until(async move {
+                    let _ = shutdown_rx.await;
+                })
+            });
 
         // 运行服务（不带服务注册，因为这是消费者服务）
         runtime.run().await
@@ -7,17 +7,25 @@ use std::sync::Arc;
 use anyhow::{Context as AnyhowContext, Result};
 use tracing::warn;
 
-use crate::application::handlers::MessagePersistenceCommandHandler;
+use crate::application::handlers::{
+    ConsistencyVerificationHandler, LastMessageCacheInvalidator,
+    MessagePersistenceCommandHandler,
+};
 use crate::config::StorageWriterConfig;
+use crate::domain::events::StorageWriteEvent;
 use crate::domain::repository::{
     AckPublisher, ArchiveStoreRepository, HotCacheRepository, MediaAttachmentVerifier,
     MessageIdempotencyRepository, ConversationStateRepository, UserSyncCursorRepository,
     WalCleanupRepository,
 };
 use crate::domain::repository::ConversationUpdateRepository;
-use crate::domain::service::{MessageOperationDomainService, MessagePersistenceDomainService};
+use crate::domain::service::{
+    MessageOperationDomainService, MessagePersistenceDomainService, RecentWriteSampler,
+    StoreConsistencyVerifier,
+};
 use crate::infrastructure::external::media::MediaAttachmentClient;
 use crate::infrastructure::messaging::ack_publisher::KafkaAckPublisher;
+use crate::infrastructure::messaging::reply_publisher::RedisPersistenceReplyPublisher;
 use crate::infrastructure::persistence::postgres_store::PostgresMessageStore;
 use crate::infrastructure::persistence::redis_cache::RedisHotCacheRepository;
 use crate::infrastructure::persistence::redis_idempotency::RedisIdempotencyRepository;
@@ -27,6 +35,8 @@ use crate::infrastructure::persistence::conversation_state::RedisConversationSta
 use crate::infrastructure::persistence::user_cursor::RedisUserCursorRepository;
 use crate::interface::messaging::normal_consumer::NormalMessageConsumer;
 use crate::interface::messaging::operation_consumer::OperationMessageConsumer;
+use flare_im_core::EventBus;
+use flare_im_core::encryption::FieldEncryptor;
 use flare_im_core::metrics::StorageWriterMetrics;
 use flare_server_core::ServiceClient;
 use flare_server_core::kafka::build_kafka_producer; // 添加ServiceClient导入
@@ -35,6 +45,8 @@ use flare_server_core::kafka::build_kafka_producer; // 添加ServiceClient导入
 pub struct ApplicationContext {
     pub normal_consumer: NormalMessageConsumer,
     pub operation_consumer: OperationMessageConsumer,
+    /// 写入事件总线（停机时等待订阅者处理完剩余事件）
+    pub event_bus: Arc<EventBus<StorageWriteEvent>>,
 }
 
 /// 构建应用上下文
@@ -73,10 +85,19 @@ pub async fn initialize(
             as Arc<dyn MessageIdempotencyRepository + Send + Sync>
     });
 
-    // 7. 创建热缓存仓储（可选）
+    // 7. 创建热缓存仓储（可选，启用字段加密时缓存值同样加密）
+    let cache_encryptor = match (&redis_client, &config.encryption_key_file) {
+        (Some(_), Some(path)) => Some(Arc::new(
+            FieldEncryptor::from_key_file(path)
+                .context("Failed to load message encryption key file for hot cache")?,
+        )),
+        _ => None,
+    };
     let hot_cache_repo = redis_client.as_ref().map(|client| {
-        Arc::new(RedisHotCacheRepository::new(client.clone(), &config))
-            as Arc<dyn HotCacheRepository + Send + Sync>
+        Arc::new(
+            RedisHotCacheRepository::new(client.clone(), &config)
+                .with_encryptor(cache_encryptor.clone()),
+        ) as Arc<dyn HotCacheRepository + Send + Sync>
     });
 
     // 8. 创建 WAL 清理仓储（可选）
@@ -163,11 +184,29 @@ pub async fn initialize(
         }
     };
 
-    // 17. 创建领域服务（不包含指标，符合 DDD 原则）
+    // 17. 创建一致性校验器（可选，需至少两个存储）
+    let write_sampler = build_consistency_verifier(
+        &config,
+        hot_cache_repo.clone(),
+        archive_repo.clone(),
+        metrics.clone(),
+    );
+
+    // 18. 创建写入事件总线：缓存视图失效由订阅者异步完成（会话计数在写入路径上同步更新）
+    let event_bus = Arc::new(EventBus::<StorageWriteEvent>::new("storage-writer"));
+    if let Some(repo) = &hot_cache_repo {
+        event_bus.subscribe(
+            "last-message-invalidator",
+            config.event_bus_capacity,
+            Arc::new(LastMessageCacheInvalidator::new(repo.clone())),
+        );
+    }
+
+    // 19. 创建领域服务（不包含指标，符合 DDD 原则）
     // 注意：根据设计文档，只使用 PostgreSQL 作为归档存储，Redis 作为缓存
-    let domain_service = Arc::new(MessagePersistenceDomainService::new(
+    let mut domain_service = MessagePersistenceDomainService::new(
         idempotency_repo,
-        hot_cache_repo,
+        hot_cache_repo.clone(),
         None, // realtime_repo: 已移除 MongoDB 支持
         archive_repo.clone(),
         wal_cleanup_repo,
@@ -177,7 +216,16 @@ pub async fn initialize(
         user_cursor_repo,
         session_update_repo,
         conversation_client, // 添加conversation_client参数
-    ));
+    );
+    if let Some(sampler) = write_sampler {
+        domain_service = domain_service.with_write_sampler(sampler);
+    }
+    // 同步写入回执（编排服务通过 Redis Pub/Sub 等待 sync = true 的消息持久化）
+    if let Some(client) = &redis_client {
+        domain_service = domain_service
+            .with_reply_publisher(Arc::new(RedisPersistenceReplyPublisher::new(client.clone())));
+    }
+    let domain_service = Arc::new(domain_service);
 
     // 更新conversation_state_repo，注入domain_service
     if let Some(repo) = &mut conversation_state_repo {
@@ -191,7 +239,11 @@ pub async fn initialize(
     }
 
     // 17. 创建操作消息领域服务
-    let operation_service = Arc::new(MessageOperationDomainService::new(archive_repo));
+    let operation_service = Arc::new(
+        MessageOperationDomainService::new(archive_repo)
+            .with_hot_cache(hot_cache_repo.clone())
+            .with_event_bus(event_bus.clone()),
+    );
 
     // 18. 创建命令处理器（应用层负责指标记录）
     let command_handler = Arc::new(MessagePersistenceCommandHandler::new(
@@ -220,6 +272,7 @@ pub async fn initialize(
     Ok(ApplicationContext {
         normal_consumer,
         operation_consumer,
+        event_bus,
     })
 }
 
@@ -246,6 +299,41 @@ fn build_ack_publisher(
     }
 }
 
+/// 构建一致性校验器并在后台运行，返回供写入路径记录的采样窗口
+fn build_consistency_verifier(
+    config: &Arc<StorageWriterConfig>,
+    hot_cache_repo: Option<Arc<dyn HotCacheRepository + Send + Sync>>,
+    archive_repo: Option<Arc<dyn ArchiveStoreRepository + Send + Sync>>,
+    metrics: Arc<StorageWriterMetrics>,
+) -> Option<Arc<RecentWriteSampler>> {
+    if !config.verify_enabled {
+        return None;
+    }
+
+    let sampler = Arc::new(RecentWriteSampler::new(config.verify_window_size));
+    let verifier = StoreConsistencyVerifier::new(
+        sampler.clone(),
+        hot_cache_repo.clone(),
+        None, // realtime_repo: 已移除 MongoDB 支持
+        archive_repo,
+        config.verify_repair,
+    );
+    if !verifier.is_applicable() {
+        warn!("Store consistency verification requires at least two stores; disabled");
+        return None;
+    }
+
+    let handler = ConsistencyVerificationHandler::new(
+        Arc::new(verifier),
+        metrics,
+        std::time::Duration::from_millis(config.verify_interval_ms.max(1)),
+        config.verify_sample_size,
+    );
+    tokio::spawn(handler.run());
+
+    Some(sampler)
+}
+
 /// 构建 Redis 客户端
 fn build_redis_client(config: &Arc<StorageWriterConfig>) -> Option<Arc<redis::Client>> {
     config.redis_url.as_ref().and_then(|url| {
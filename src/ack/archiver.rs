@@ -1,174 +1,292 @@
-//! ACK异步归档机制
-//! 用于审计和分析的ACK日志异步归档
-
+//! ACK异步归档
+//! 终态ACK经有界队列异步批量写入归档存储，默认实现为 Postgres 按天分区表，用于合规查询
+
+use crate::ack::config::AckArchiveConfig;
+use crate::ack::metrics::AckMetrics;
+use crate::ack::redis_manager::AckStatusInfo;
+use crate::ack::traits::AckArchiveSink;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool, Row};
+use std::collections::HashSet;
 use std::sync::Arc;
-// use std::time::{/*Duration,*/ SystemTime, UNIX_EPOCH};
-use tokio::time::Duration as TokioDuration;
+use std::time::Duration;
 use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::time::interval;
 
 /// ACK归档记录
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
 pub struct AckArchiveRecord {
     /// 消息ID
     pub message_id: String,
     /// 用户ID
     pub user_id: String,
     /// ACK类型
-    pub ack_type: String,
+    pub ack_type: Option<String>,
     /// ACK状态
     pub ack_status: String,
-    /// 时间戳
-    pub timestamp: i64,
     /// 重要性等级
-    pub importance_level: i16,
-    /// 元数据
-    pub metadata: Option<serde_json::Value>,
-    /// 归档时间
-    pub archived_at: i64,
+    pub importance: String,
+    /// 确认时间（Unix 秒）
+    pub acked_at: i64,
+}
+
+impl From<&AckStatusInfo> for AckArchiveRecord {
+    fn from(info: &AckStatusInfo) -> Self {
+        Self {
+            message_id: info.message_id.clone(),
+            user_id: info.user_id.clone(),
+            ack_type: info.ack_type.map(|ack_type| ack_type.as_str().to_string()),
+            ack_status: info.status.as_str().to_string(),
+            importance: info.importance.as_str().to_string(),
+            acked_at: info.timestamp as i64,
+        }
+    }
 }
 
 /// ACK归档器
+///
+/// 热路径只做非阻塞入队：队列满时丢弃记录并计数，归档延迟不会反压到ACK处理；
+/// 后台任务攒批（达到 `batch_size` 或 `flush_interval_ms` 到期）后写入归档存储
 pub struct AckArchiver {
-    /// 数据库连接池
-    db_pool: Arc<PgPool>,
-    /// 归档通道发送端
+    /// 归档队列发送端
     tx: mpsc::Sender<AckArchiveRecord>,
+    /// 监控指标
+    metrics: Arc<AckMetrics>,
 }
 
 impl AckArchiver {
-    /// 创建新的ACK归档器
-    pub fn new(db_pool: PgPool, buffer_size: usize) -> Self {
-        let (tx, mut rx) = mpsc::channel::<AckArchiveRecord>(buffer_size);
-        let db_pool = Arc::new(db_pool);
-        let db_pool_clone = db_pool.clone();
-
-        // 启动后台归档任务
-        tokio::spawn(async move {
-            let mut batch = Vec::new();
-            let mut interval = interval(TokioDuration::from_secs(60)); // 每分钟归档一次
-
-            loop {
-                tokio::select! {
-                    // 接收归档记录
-                    Some(record) = rx.recv() => {
+    /// 创建归档器并启动后台写入任务
+    pub fn start(
+        sink: Arc<dyn AckArchiveSink>,
+        config: AckArchiveConfig,
+        metrics: Arc<AckMetrics>,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel(config.queue_capacity.max(1));
+        tokio::spawn(Self::run(rx, sink, config, metrics.clone()));
+        Self { tx, metrics }
+    }
+
+    /// 提交归档记录（非阻塞），队列已满时丢弃并返回 false
+    pub fn submit(&self, record: AckArchiveRecord) -> bool {
+        match self.tx.try_send(record) {
+            Ok(()) => {
+                let queued = self.tx.max_capacity() - self.tx.capacity();
+                self.metrics.update_archive_queue_size(queued as i64);
+                true
+            }
+            Err(TrySendError::Full(record)) => {
+                self.metrics.record_archive_dropped("queue_full", 1);
+                tracing::warn!(
+                    message_id = %record.message_id,
+                    user_id = %record.user_id,
+                    "ACK archive queue full, dropping record"
+                );
+                false
+            }
+            Err(TrySendError::Closed(_)) => {
+                self.metrics.record_archive_dropped("closed", 1);
+                false
+            }
+        }
+    }
+
+    /// 后台写入任务：攒批后写入，所有发送端释放后写完剩余记录退出
+    async fn run(
+        mut rx: mpsc::Receiver<AckArchiveRecord>,
+        sink: Arc<dyn AckArchiveSink>,
+        config: AckArchiveConfig,
+        metrics: Arc<AckMetrics>,
+    ) {
+        let batch_size = config.batch_size.max(1);
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut ticker = interval(Duration::from_millis(config.flush_interval_ms.max(1)));
+
+        loop {
+            tokio::select! {
+                received = rx.recv() => match received {
+                    Some(record) => {
                         batch.push(record);
-                        
-                        // 达到批次大小立即处理
-                        if batch.len() >= 100 {
-                            if let Err(e) = Self::batch_archive(&db_pool_clone, &mut batch).await {
-                                eprintln!("Failed to batch archive ACKs: {}", e);
-                            }
+                        if batch.len() < batch_size {
+                            continue;
                         }
                     }
-                    // 定时处理
-                    _ = interval.tick() => {
+                    None => {
                         if !batch.is_empty() {
-                            if let Err(e) = Self::batch_archive(&db_pool_clone, &mut batch).await {
-                                eprintln!("Failed to batch archive ACKs: {}", e);
-                            }
+                            Self::flush(sink.as_ref(), &mut batch, &config, &metrics).await;
                         }
+                        break;
+                    }
+                },
+                _ = ticker.tick() => {
+                    if batch.is_empty() {
+                        continue;
                     }
                 }
             }
-        });
+            Self::flush(sink.as_ref(), &mut batch, &config, &metrics).await;
+        }
+    }
 
-        Self { db_pool, tx }
+    /// 写入一批记录，失败时按线性退避重试，重试耗尽后丢弃
+    async fn flush(
+        sink: &dyn AckArchiveSink,
+        batch: &mut Vec<AckArchiveRecord>,
+        config: &AckArchiveConfig,
+        metrics: &AckMetrics,
+    ) {
+        let mut attempt = 0;
+        loop {
+            match sink.write_batch(batch).await {
+                Ok(()) => {
+                    metrics.record_acks_archived(batch.len() as u64);
+                    break;
+                }
+                Err(e) if attempt < config.max_retries => {
+                    attempt += 1;
+                    tracing::warn!(
+                        count = batch.len(),
+                        attempt,
+                        error = %e,
+                        "Failed to archive ACKs, retrying"
+                    );
+                    tokio::time::sleep(Duration::from_millis(
+                        config.retry_backoff_ms * attempt as u64,
+                    ))
+                    .await;
+                }
+                Err(e) => {
+                    metrics.record_archive_dropped("write_failed", batch.len() as u64);
+                    tracing::error!(
+                        count = batch.len(),
+                        error = %e,
+                        "Failed to archive ACKs, dropping batch"
+                    );
+                    break;
+                }
+            }
+        }
+        batch.clear();
     }
+}
 
-    /// 归档ACK记录
-    pub async fn archive_ack(&self, record: AckArchiveRecord) -> Result<(), Box<dyn std::error::Error>> {
-        self.tx.send(record).await?;
-        Ok(())
+/// 归档查询条件（时间范围为 Unix 秒，指定时间范围可只扫描对应的日分区）
+#[derive(Debug, Clone, Default)]
+pub struct AckArchiveQuery {
+    pub message_id: Option<String>,
+    pub user_id: Option<String>,
+    pub start_time: Option<i64>,
+    pub end_time: Option<i64>,
+    pub limit: Option<u32>,
+}
+
+/// Postgres 归档存储
+///
+/// 写入按 `acked_at` 按天分区的 `ack_archive_records` 表，写入前按需创建当天分区；
+/// 以 (message_id, user_id, ack_status, acked_at) 去重，重试写入幂等
+pub struct PostgresAckArchiveSink {
+    /// 数据库连接池
+    pool: PgPool,
+    /// 已确认存在的日分区
+    partitions: std::sync::Mutex<HashSet<NaiveDate>>,
+}
+
+impl PostgresAckArchiveSink {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            partitions: std::sync::Mutex::new(HashSet::new()),
+        }
     }
 
-    /// 批量归档ACK记录
-    async fn batch_archive(db_pool: &PgPool, batch: &mut Vec<AckArchiveRecord>) -> Result<(), Box<dyn std::error::Error>> {
-        if batch.is_empty() {
+    /// 确保日分区存在（多实例并发创建时忽略已存在错误）
+    async fn ensure_partition(&self, day: NaiveDate) -> Result<(), sqlx::Error> {
+        let known = self
+            .partitions
+            .lock()
+            .map(|partitions| partitions.contains(&day))
+            .unwrap_or(false);
+        if known {
             return Ok(());
         }
 
-        // 构建批量插入语句
-        let mut query_builder = sqlx::QueryBuilder::new(
-            "INSERT INTO ack_archive_records 
-             (message_id, user_id, ack_type, ack_status, timestamp, importance_level, metadata, archived_at)"
+        let next_day = day.succ_opt().unwrap_or(day);
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS ack_archive_records_{} PARTITION OF ack_archive_records \
+             FOR VALUES FROM ('{} 00:00:00+00') TO ('{} 00:00:00+00')",
+            day.format("%Y%m%d"),
+            day,
+            next_day
         );
+        if let Err(e) = sqlx::query(&sql).execute(&self.pool).await {
+            let already_exists = e
+                .as_database_error()
+                .and_then(|db| db.code())
+                .is_some_and(|code| code == "42P07");
+            if !already_exists {
+                return Err(e);
+            }
+        }
 
-        query_builder.push_values(batch, |mut b, record| {
-            b.push_bind(&record.message_id)
-                .push_bind(&record.user_id)
-                .push_bind(&record.ack_type)
-                .push_bind(&record.ack_status)
-                .push_bind(record.timestamp)
-                .push_bind(record.importance_level)
-                .push_bind(&record.metadata)
-                .push_bind(record.archived_at);
-        });
-
-        query_builder.push(" ON CONFLICT DO NOTHING");
-
-        let query = query_builder.build();
-        query.execute(db_pool).await?;
-
+        if let Ok(mut partitions) = self.partitions.lock() {
+            partitions.insert(day);
+        }
         Ok(())
     }
 
-    /// 查询归档的ACK记录
-    pub async fn query_archived_acks(
+    /// 查询归档记录（按确认时间倒序）
+    pub async fn query_records(
         &self,
-        message_id: Option<&str>,
-        user_id: Option<&str>,
-        start_time: Option<i64>,
-        end_time: Option<i64>,
-        limit: Option<u32>,
-    ) -> Result<Vec<AckArchiveRecord>, Box<dyn std::error::Error>> {
-        let mut query_builder = sqlx::QueryBuilder::new(
-            "SELECT message_id, user_id, ack_type, ack_status, timestamp, importance_level, metadata, archived_at 
-             FROM ack_archive_records WHERE 1=1"
+        query: &AckArchiveQuery,
+    ) -> Result<Vec<AckArchiveRecord>, sqlx::Error> {
+        let mut builder = sqlx::QueryBuilder::new(
+            "SELECT message_id, user_id, ack_type, ack_status, importance, \
+             EXTRACT(EPOCH FROM acked_at)::BIGINT AS acked_at \
+             FROM ack_archive_records WHERE 1=1",
         );
-
-        if let Some(msg_id) = message_id {
-            query_builder.push(" AND message_id = ").push_bind(msg_id);
+        if let Some(message_id) = &query.message_id {
+            builder.push(" AND message_id = ").push_bind(message_id);
         }
-
-        if let Some(uid) = user_id {
-            query_builder.push(" AND user_id = ").push_bind(uid);
+        if let Some(user_id) = &query.user_id {
+            builder.push(" AND user_id = ").push_bind(user_id);
         }
-
-        if let Some(start) = start_time {
-            query_builder.push(" AND timestamp >= ").push_bind(start);
+        if let Some(start) = query
+            .start_time
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        {
+            builder.push(" AND acked_at >= ").push_bind(start);
         }
-
-        if let Some(end) = end_time {
-            query_builder.push(" AND timestamp <= ").push_bind(end);
+        if let Some(end) = query
+            .end_time
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        {
+            builder.push(" AND acked_at <= ").push_bind(end);
         }
-
-        query_builder.push(" ORDER BY timestamp DESC");
-
-        if let Some(lim) = limit {
-            query_builder.push(" LIMIT ").push_bind(lim as i64);
+        builder.push(" ORDER BY acked_at DESC");
+        if let Some(limit) = query.limit {
+            builder.push(" LIMIT ").push_bind(limit as i64);
         }
 
-        let query = query_builder.build_query_as::<AckArchiveRecord>();
-        let records = query.fetch_all(&*self.db_pool).await?;
-
-        Ok(records)
+        builder
+            .build_query_as::<AckArchiveRecord>()
+            .fetch_all(&self.pool)
+            .await
     }
 
-    /// 获取归档统计信息
-    pub async fn get_archive_stats(&self) -> Result<ArchiveStats, Box<dyn std::error::Error>> {
+    /// 获取归档统计信息（扫描全部日分区）
+    pub async fn get_archive_stats(&self) -> Result<ArchiveStats, sqlx::Error> {
         let row = sqlx::query(
-            "SELECT 
-                COUNT(*) as total_records,
-                COUNT(CASE WHEN importance_level = 3 THEN 1 END) as high_importance,
-                COUNT(CASE WHEN importance_level = 2 THEN 1 END) as medium_importance,
-                COUNT(CASE WHEN importance_level = 1 THEN 1 END) as low_importance,
-                MAX(timestamp) as latest_timestamp
-             FROM ack_archive_records"
+            "SELECT \
+                COUNT(*) AS total_records, \
+                COUNT(*) FILTER (WHERE importance = 'high') AS high_importance, \
+                COUNT(*) FILTER (WHERE importance = 'medium') AS medium_importance, \
+                COUNT(*) FILTER (WHERE importance = 'low') AS low_importance, \
+                EXTRACT(EPOCH FROM MAX(acked_at))::BIGINT AS latest_timestamp \
+             FROM ack_archive_records",
         )
-        .fetch_one(&*self.db_pool)
+        .fetch_one(&self.pool)
         .await?;
 
         Ok(ArchiveStats {
@@ -176,7 +294,9 @@ impl AckArchiver {
             high_importance: row.try_get("high_importance")?,
             medium_importance: row.try_get("medium_importance")?,
             low_importance: row.try_get("low_importance")?,
-            latest_timestamp: row.try_get("latest_timestamp").unwrap_or(0),
+            latest_timestamp: row
+                .try_get::<Option<i64>, _>("latest_timestamp")?
+                .unwrap_or(0),
         })
     }
 }
@@ -192,62 +312,153 @@ pub struct ArchiveStats {
     pub medium_importance: i64,
     /// 低重要性记录数
     pub low_importance: i64,
-    /// 最新时间戳
+    /// 最新确认时间（Unix 秒，无记录时为 0）
     pub latest_timestamp: i64,
 }
 
+/// 确认时间（超出范围时取当前时间）
+fn acked_at(record: &AckArchiveRecord) -> DateTime<Utc> {
+    DateTime::from_timestamp(record.acked_at, 0).unwrap_or_else(Utc::now)
+}
+
+#[async_trait]
+impl AckArchiveSink for PostgresAckArchiveSink {
+    async fn write_batch(
+        &self,
+        records: &[AckArchiveRecord],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let days: HashSet<NaiveDate> = records
+            .iter()
+            .map(|record| acked_at(record).date_naive())
+            .collect();
+        for day in days {
+            self.ensure_partition(day).await?;
+        }
+
+        let mut builder = sqlx::QueryBuilder::new(
+            "INSERT INTO ack_archive_records \
+             (message_id, user_id, ack_type, ack_status, importance, acked_at) ",
+        );
+        builder.push_values(records, |mut row, record| {
+            row.push_bind(&record.message_id)
+                .push_bind(&record.user_id)
+                .push_bind(&record.ack_type)
+                .push_bind(&record.ack_status)
+                .push_bind(&record.importance)
+                .push_bind(acked_at(record));
+        });
+        builder.push(" ON CONFLICT DO NOTHING");
+        builder.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use sqlx::{PgPool, postgres::PgPoolOptions};
-    use tokio;
-
-    #[tokio::test]
-    async fn test_ack_archiver() -> Result<(), Box<dyn std::error::Error>> {
-        // 注意：这需要一个运行中的PostgreSQL实例
-        let db_pool = PgPoolOptions::new()
-            .max_connections(5)
-            .connect("postgresql://localhost/test_db")
-            .await?;
-
+    use crate::ack::redis_manager::{AckStatus, AckType, ImportanceLevel};
+    use std::sync::Mutex;
+
+    /// 记录写入批次的内存存储，前 `failures` 次写入返回错误
+    #[derive(Default)]
+    struct MemorySink {
+        batches: Mutex<Vec<Vec<AckArchiveRecord>>>,
+        failures: Mutex<u32>,
+    }
 
-        let archiver = AckArchiver::new(db_pool, 100);
+    #[async_trait]
+    impl AckArchiveSink for MemorySink {
+        async fn write_batch(
+            &self,
+            records: &[AckArchiveRecord],
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err("unavailable".into());
+            }
+            self.batches.lock().unwrap().push(records.to_vec());
+            Ok(())
+        }
+    }
 
-        let record = AckArchiveRecord {
-            message_id: "test_msg_1".to_string(),
+    fn record(message_id: &str) -> AckArchiveRecord {
+        AckArchiveRecord::from(&AckStatusInfo {
+            message_id: message_id.to_string(),
             user_id: "user_1".to_string(),
-            ack_type: "client".to_string(),
-            ack_status: "received".to_string(),
+            ack_type: Some(AckType::DeliveryAck),
+            status: AckStatus::Received,
             timestamp: 1234567890,
-            importance_level: 3,
-            metadata: Some(serde_json::json!({"test": "data"})),
-            archived_at: 1234567890,
-        };
-
-        // 归档记录
-        archiver.archive_ack(record).await?;
-
-        // 等待归档完成
-        tokio::time::sleep(TokioDuration::from_millis(100)).await;
+            importance: ImportanceLevel::High,
+        })
+    }
 
-        // 查询归档记录
-        let records = archiver.query_archived_acks(
-            Some("test_msg_1"),
-            Some("user_1"),
-            None,
-            None,
-            Some(10),
-        ).await?;
+    fn metrics() -> Arc<AckMetrics> {
+        Arc::new(AckMetrics::new(&prometheus::Registry::new()).unwrap())
+    }
 
-        assert!(!records.is_empty());
-        assert_eq!(records[0].message_id, "test_msg_1");
-        assert_eq!(records[0].user_id, "user_1");
+    #[test]
+    fn test_archive_record_from_status() {
+        let record = record("msg_1");
+        assert_eq!(record.ack_type.as_deref(), Some("delivery"));
+        assert_eq!(record.ack_status, "received");
+        assert_eq!(record.importance, "high");
+        assert_eq!(record.acked_at, 1234567890);
+    }
 
-        // 获取统计信息
-        let stats = archiver.get_archive_stats().await?;
-        assert!(stats.total_records >= 1);
+    #[tokio::test]
+    async fn test_archiver_batches_and_retries() {
+        let sink = Arc::new(MemorySink {
+            failures: Mutex::new(1),
+            ..Default::default()
+        });
+        let metrics = metrics();
+        let config = AckArchiveConfig {
+            batch_size: 2,
+            flush_interval_ms: 10,
+            retry_backoff_ms: 1,
+            ..Default::default()
+        };
+        let archiver = AckArchiver::start(sink.clone(), config, metrics.clone());
+
+        assert!(archiver.submit(record("msg_1")));
+        assert!(archiver.submit(record("msg_2")));
+        assert!(archiver.submit(record("msg_3")));
+        drop(archiver);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let batches = sink.batches.lock().unwrap();
+        let archived: usize = batches.iter().map(Vec::len).sum();
+        assert_eq!(archived, 3);
+        assert!(batches.iter().all(|batch| batch.len() <= 2));
+        assert_eq!(metrics.acks_archived.get(), 3);
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn test_archiver_drops_when_queue_full() {
+        let metrics = metrics();
+        let config = AckArchiveConfig {
+            queue_capacity: 1,
+            batch_size: 10,
+            flush_interval_ms: 60_000,
+            ..Default::default()
+        };
+        let archiver = AckArchiver::start(Arc::new(MemorySink::default()), config, metrics.clone());
+
+        // 后台任务尚未调度，第二条记录因队列已满被丢弃
+        assert!(archiver.submit(record("msg_1")));
+        assert!(!archiver.submit(record("msg_2")));
+        assert_eq!(
+            metrics
+                .archive_dropped
+                .with_label_values(&["queue_full"])
+                .get(),
+            1
+        );
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,188 @@
+//! ACK后台清理
+//! 定期扫描逐用户ACK记录，将超过保留时间的终态记录按消息合并到摘要后删除，防止高流量租户下 Redis 无限增长
+
+use crate::ack::config::AckCleanupConfig;
+use crate::ack::metrics::AckMetrics;
+use crate::ack::store::{AckStore, AckStoreResult};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::interval;
+
+/// 一轮清理的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AckCleanupReport {
+    /// 扫描的逐用户ACK记录数
+    pub scanned: usize,
+    /// 合并到摘要的消息数
+    pub merged_messages: usize,
+    /// 删除的逐用户ACK键数
+    pub reclaimed_keys: usize,
+}
+
+/// ACK清理任务
+pub struct AckCleanupJob {
+    store: Arc<dyn AckStore>,
+    metrics: Arc<AckMetrics>,
+    config: AckCleanupConfig,
+}
+
+impl AckCleanupJob {
+    pub fn new(
+        store: Arc<dyn AckStore>,
+        metrics: Arc<AckMetrics>,
+        config: AckCleanupConfig,
+    ) -> Self {
+        Self {
+            store,
+            metrics,
+            config,
+        }
+    }
+
+    /// 执行一轮清理
+    ///
+    /// 单条消息合并失败只记录日志，继续处理其余消息
+    pub async fn run_once(&self) -> AckStoreResult<AckCleanupReport> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let statuses = self
+            .store
+            .scan_ack_statuses(self.config.max_records, self.config.scan_batch_size.max(1))
+            .await?;
+
+        let mut report = AckCleanupReport {
+            scanned: statuses.len(),
+            ..Default::default()
+        };
+        let mut by_message: HashMap<String, Vec<String>> = HashMap::new();
+        for ack_info in statuses {
+            if ack_info.status.is_final()
+                && now.saturating_sub(ack_info.timestamp) >= self.config.retention_secs
+            {
+                by_message
+                    .entry(ack_info.message_id)
+                    .or_default()
+                    .push(ack_info.user_id);
+            }
+        }
+
+        for (message_id, user_ids) in by_message {
+            match self
+                .store
+                .merge_final_acks(&message_id, &user_ids, now)
+                .await
+            {
+                Ok(0) => {}
+                Ok(merged) => {
+                    report.merged_messages += 1;
+                    report.reclaimed_keys += merged;
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, message_id = %message_id, "Failed to merge final ACKs");
+                }
+            }
+        }
+
+        self.metrics
+            .record_ack_cleanup(report.merged_messages as u64, report.reclaimed_keys as u64);
+        Ok(report)
+    }
+
+    /// 启动周期清理任务
+    pub fn start(self: Arc<Self>) {
+        let interval_duration = Duration::from_secs(self.config.interval_secs.max(1));
+
+        tokio::spawn(async move {
+            let mut interval = interval(interval_duration);
+            // 启动时不立即扫描
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                match self.run_once().await {
+                    Ok(report) => tracing::info!(
+                        scanned = report.scanned,
+                        merged_messages = report.merged_messages,
+                        reclaimed_keys = report.reclaimed_keys,
+                        "ACK cleanup finished"
+                    ),
+                    Err(e) => tracing::warn!(error = %e, "ACK cleanup failed"),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ack::memory_store::MemoryAckStore;
+    use crate::ack::redis_manager::{
+        AckStatus, AckStatusInfo, AckTtlPolicy, AckType, ImportanceLevel,
+    };
+    use prometheus::Registry;
+
+    fn ack(message_id: &str, user_id: &str, status: AckStatus, timestamp: u64) -> AckStatusInfo {
+        AckStatusInfo {
+            message_id: message_id.to_string(),
+            user_id: user_id.to_string(),
+            ack_type: Some(AckType::DeliveryAck),
+            status,
+            timestamp,
+            importance: ImportanceLevel::Medium,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_once_merges_aged_final_acks() -> AckStoreResult<()> {
+        let store: Arc<dyn AckStore> =
+            Arc::new(MemoryAckStore::new(AckTtlPolicy::from_default_ttl(3600)));
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let aged = now - 7200;
+        store
+            .store_ack_status(&ack("msg_1", "user_1", AckStatus::Pending, aged))
+            .await?;
+        store
+            .store_ack_status(&ack("msg_1", "user_2", AckStatus::Pending, aged))
+            .await?;
+        store
+            .store_ack_status(&ack("msg_1", "user_1", AckStatus::Received, aged))
+            .await?;
+        store
+            .store_ack_status(&ack("msg_2", "user_1", AckStatus::Processed, now))
+            .await?;
+
+        let job = AckCleanupJob::new(
+            store.clone(),
+            Arc::new(AckMetrics::new(&Registry::new()).unwrap()),
+            AckCleanupConfig::default(),
+        );
+        let report = job.run_once().await?;
+        assert_eq!(report.scanned, 3);
+        assert_eq!(report.merged_messages, 1);
+        assert_eq!(report.reclaimed_keys, 1);
+
+        // 已合并的记录从摘要读取，未到保留时间与仍在等待的记录保持不变
+        let summary = store.get_ack_summary("msg_1").await?.unwrap();
+        assert_eq!(summary.total, 1);
+        let status = store.get_ack_status("msg_1", "user_2").await?.unwrap();
+        assert_eq!(status.status, AckStatus::Pending);
+        assert!(store.get_ack_summary("msg_2").await?.is_none());
+
+        assert_eq!(
+            job.run_once().await?,
+            AckCleanupReport {
+                scanned: 2,
+                ..Default::default()
+            }
+        );
+        Ok(())
+    }
+}
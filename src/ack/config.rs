@@ -1,6 +1,7 @@
 //! ACK配置管理
 //! 支持根据不同业务场景动态调整ACK重要性级别配置
 
+use crate::ack::redis_manager::{AckDeadlinePolicy, AckTtlPolicy, ImportanceLevel};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -18,16 +19,24 @@ pub struct AckImportanceConfig {
 /// 重要性级别配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportanceLevelConfig {
-    /// Redis过期时间（秒）
+    /// Redis过期时间（秒，0 表示使用全局 `redis_ttl`）
+    #[serde(default)]
     pub redis_ttl: u64,
     /// 是否立即持久化
     pub immediate_persistence: bool,
+    /// 终态ACK是否归档（需配置归档存储，默认归档）
+    #[serde(default = "default_archive")]
+    pub archive: bool,
     /// 超时时间（秒）
     pub timeout_seconds: u64,
     /// 最大重试次数
     pub max_retries: u32,
 }
 
+fn default_archive() -> bool {
+    true
+}
+
 /// 业务场景配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BusinessScenarioConfig {
@@ -42,9 +51,12 @@ pub struct BusinessScenarioConfig {
 /// ACK服务配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AckServiceConfig {
-    /// Redis URL
+    /// ACK状态存储配置
+    #[serde(default)]
+    pub store: AckStoreConfig,
+    /// Redis URL（`redis` 存储使用；`redis_cluster` 存储未配置节点时作为唯一节点）
     pub redis_url: String,
-    /// Redis默认过期时间（秒）
+    /// Redis默认过期时间（秒，重要性级别未配置过期时间时使用）
     pub redis_ttl: u64,
     /// 内存缓存容量
     pub cache_capacity: usize,
@@ -56,11 +68,271 @@ pub struct AckServiceConfig {
     pub importance_config: AckImportanceConfig,
     /// 业务场景配置
     pub business_scenarios: HashMap<String, BusinessScenarioConfig>,
+    /// 消息级ACK压缩配置
+    #[serde(default)]
+    pub compaction: AckCompactionConfig,
+    /// ACK超时扫描配置
+    #[serde(default)]
+    pub timeout_scan: AckTimeoutScanConfig,
+    /// ACK归档配置
+    #[serde(default)]
+    pub archive: AckArchiveConfig,
+    /// 群聊消息ACK聚合配置
+    #[serde(default)]
+    pub group_aggregation: AckGroupAggregationConfig,
+    /// ACK状态订阅配置
+    #[serde(default)]
+    pub watch: AckWatchConfig,
+    /// ACK阶段耗时跟踪配置
+    #[serde(default)]
+    pub timeline: AckTimelineConfig,
+    /// ACK写入管道配置（`redis` 存储使用）
+    #[serde(default)]
+    pub write_pipeline: AckWritePipelineConfig,
+    /// 后台ACK清理配置
+    #[serde(default)]
+    pub cleanup: AckCleanupConfig,
+}
+
+/// 后台ACK清理配置
+///
+/// 定期扫描逐用户ACK记录，将超过保留时间的终态记录合并到消息摘要后删除，
+/// 回收未能在写入时完成压缩的记录（例如部分接收者的记录已过期）；
+/// 全量扫描开销较大，多实例部署时只需在一个实例上启用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AckCleanupConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 清理间隔（秒）
+    pub interval_secs: u64,
+    /// 终态记录的保留时间（秒，按ACK时间戳计算）
+    pub retention_secs: u64,
+    /// 单次 SCAN 的键数量
+    pub scan_batch_size: usize,
+    /// 单轮最多扫描的记录数（0 表示不限制）
+    pub max_records: usize,
+}
+
+impl Default for AckCleanupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 3600,
+            retention_secs: 3600,
+            scan_batch_size: 1000,
+            max_records: 0,
+        }
+    }
+}
+
+/// ACK写入管道配置
+///
+/// 启用后单机 Redis 存储的逐条写入先进入有界缓冲，后台任务攒批后通过 Redis 管道一次写入，
+/// 调用方仍等待各自的写入结果；缓冲已满时直接写入，不排队等待
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AckWritePipelineConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 缓冲容量
+    pub buffer_capacity: usize,
+    /// 单个管道最多写入的ACK数
+    pub max_batch: usize,
+    /// 未攒满一批时的最长等待时间（毫秒）
+    pub flush_interval_ms: u64,
+}
+
+impl Default for AckWritePipelineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            buffer_capacity: 16384,
+            max_batch: 256,
+            flush_interval_ms: 2,
+        }
+    }
+}
+
+/// ACK阶段耗时跟踪配置
+///
+/// 在内存中按消息+用户关联发送、传输ACK、服务器ACK、送达ACK与已读ACK，
+/// 记录相邻阶段及发送到已读的耗时；同一消息+用户的各阶段ACK需由同一实例处理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AckTimelineConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 未完成（未收到已读ACK）的时间线保留时间（秒）
+    pub ttl: u64,
+    /// 最多同时跟踪的时间线数量（超过后不再跟踪新的消息+用户）
+    pub max_entries: usize,
+}
+
+impl Default for AckTimelineConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl: 86400, // 1天
+            max_entries: 100_000,
+        }
+    }
+}
+
+/// ACK状态订阅配置
+///
+/// 状态变更通过有界广播队列分发给订阅者（`AckModule::watch_ack_status`），
+/// 队列满时消费过慢的订阅者跳过最早的事件，不阻塞ACK处理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AckWatchConfig {
+    /// 是否启用订阅
+    pub enabled: bool,
+    /// 广播队列容量
+    pub capacity: usize,
+}
+
+impl Default for AckWatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            capacity: 4096,
+        }
+    }
+}
+
+/// 群聊消息ACK聚合配置
+///
+/// 群聊消息登记接收者人数后按用户去重累计送达数与已读数，全员已读时向已注册的处理器发送事件；
+/// 送达ACK（`DeliveryAck` 成功终态）自动计入送达数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AckGroupAggregationConfig {
+    /// 是否启用聚合
+    pub enabled: bool,
+    /// 聚合记录过期时间（秒）
+    pub ttl: u64,
+}
+
+impl Default for AckGroupAggregationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl: 7 * 86400, // 7天
+        }
+    }
+}
+
+/// ACK状态存储后端
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AckStoreBackend {
+    /// 进程内存储（测试与单机开发，不支持多实例共享）
+    Memory,
+    /// 单机 Redis
+    #[default]
+    Redis,
+    /// Redis 集群（按消息ID分片）
+    RedisCluster,
+}
+
+/// ACK状态存储配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AckStoreConfig {
+    /// 存储后端
+    pub backend: AckStoreBackend,
+    /// Redis 集群主节点地址（`redis_cluster` 存储使用，需列出全部主节点以支持扫描）
+    pub cluster_nodes: Vec<String>,
+}
+
+/// ACK归档配置
+///
+/// 配置 `database_url` 后终态ACK异步批量写入 Postgres 按天分区的归档表，用于合规查询；
+/// 也可通过 `AckModule::set_archive_sink` 接入其他存储。队列满时丢弃新记录，不阻塞ACK处理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AckArchiveConfig {
+    /// 归档数据库地址（为空时不启用 Postgres 归档）
+    pub database_url: Option<String>,
+    /// 归档数据库最大连接数
+    pub max_connections: u32,
+    /// 归档队列容量
+    pub queue_capacity: usize,
+    /// 单批写入的最大记录数
+    pub batch_size: usize,
+    /// 未攒满一批时的刷新间隔（毫秒）
+    pub flush_interval_ms: u64,
+    /// 写入失败的最大重试次数
+    pub max_retries: u32,
+    /// 重试退避基数（毫秒，按重试次数线性增长）
+    pub retry_backoff_ms: u64,
+}
+
+impl Default for AckArchiveConfig {
+    fn default() -> Self {
+        Self {
+            database_url: None,
+            max_connections: 5,
+            queue_capacity: 10000,
+            batch_size: 500,
+            flush_interval_ms: 1000,
+            max_retries: 3,
+            retry_backoff_ms: 200,
+        }
+    }
+}
+
+/// ACK超时扫描配置
+///
+/// 启用后 Pending 状态的ACK按重要性级别的 `timeout_seconds` 写入截止时间队列，
+/// 扫描任务领取到期项并向已注册的超时处理器发送 `AckTimeoutEvent`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AckTimeoutScanConfig {
+    /// 是否启用超时扫描
+    pub enabled: bool,
+    /// 扫描间隔（毫秒）
+    pub scan_interval_ms: u64,
+    /// 单次扫描最多领取的ACK数
+    pub batch_size: usize,
+}
+
+impl Default for AckTimeoutScanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            scan_interval_ms: 1000,
+            batch_size: 500,
+        }
+    }
+}
+
+/// 消息级ACK压缩配置
+///
+/// 消息的所有接收者都进入终态后，逐用户的ACK记录合并为一条摘要，摘要保留更长时间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AckCompactionConfig {
+    /// 是否启用压缩
+    pub enabled: bool,
+    /// 摘要过期时间（秒）
+    pub summary_ttl: u64,
+}
+
+impl Default for AckCompactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            summary_ttl: 86400, // 1天
+        }
+    }
 }
 
 impl Default for AckServiceConfig {
     fn default() -> Self {
         Self {
+            store: AckStoreConfig::default(),
             redis_url: "redis://127.0.0.1/".to_string(),
             redis_ttl: 3600, // 1小时
             cache_capacity: 10000,
@@ -70,18 +342,21 @@ impl Default for AckServiceConfig {
                 high: ImportanceLevelConfig {
                     redis_ttl: 7200, // 2小时
                     immediate_persistence: true,
+                    archive: true,
                     timeout_seconds: 30, // 30秒超时
                     max_retries: 3,
                 },
                 medium: ImportanceLevelConfig {
                     redis_ttl: 3600, // 1小时
                     immediate_persistence: false,
+                    archive: true,
                     timeout_seconds: 60, // 60秒超时
                     max_retries: 2,
                 },
                 low: ImportanceLevelConfig {
-                    redis_ttl: 1800, // 30分钟
+                    redis_ttl: 300, // 5分钟，低重要性ACK（输入状态、在线状态等）尽快过期
                     immediate_persistence: false,
+                    archive: false,       // 低重要性ACK无合规要求，不归档
                     timeout_seconds: 120, // 120秒超时
                     max_retries: 1,
                 },
@@ -137,11 +412,56 @@ impl Default for AckServiceConfig {
 
                 scenarios
             },
+            compaction: AckCompactionConfig::default(),
+            timeout_scan: AckTimeoutScanConfig::default(),
+            archive: AckArchiveConfig::default(),
+            group_aggregation: AckGroupAggregationConfig::default(),
+            watch: AckWatchConfig::default(),
+            timeline: AckTimelineConfig::default(),
+            write_pipeline: AckWritePipelineConfig::default(),
+            cleanup: AckCleanupConfig::default(),
         }
     }
 }
 
 impl AckServiceConfig {
+    /// 按重要性分级的过期时间（级别未配置时使用 `redis_ttl`）
+    pub fn ttl_policy(&self) -> AckTtlPolicy {
+        let ttl = |level: &ImportanceLevelConfig| match level.redis_ttl {
+            0 => self.redis_ttl,
+            ttl => ttl,
+        };
+        AckTtlPolicy {
+            high: ttl(&self.importance_config.high),
+            medium: ttl(&self.importance_config.medium),
+            low: ttl(&self.importance_config.low),
+            summary: self.compaction.summary_ttl,
+        }
+    }
+
+    /// 获取重要性级别对应的配置
+    pub fn importance_level_config(&self, importance: &ImportanceLevel) -> &ImportanceLevelConfig {
+        match importance {
+            ImportanceLevel::High => &self.importance_config.high,
+            ImportanceLevel::Medium => &self.importance_config.medium,
+            ImportanceLevel::Low => &self.importance_config.low,
+        }
+    }
+
+    /// 该重要性级别的终态ACK是否归档
+    pub fn archives(&self, importance: &ImportanceLevel) -> bool {
+        self.importance_level_config(importance).archive
+    }
+
+    /// 按重要性分级的确认超时时间
+    pub fn deadline_policy(&self) -> AckDeadlinePolicy {
+        AckDeadlinePolicy {
+            high: self.importance_config.high.timeout_seconds,
+            medium: self.importance_config.medium.timeout_seconds,
+            low: self.importance_config.low.timeout_seconds,
+        }
+    }
+
     /// 根据业务场景和消息类型获取重要性级别配置
     pub fn get_importance_config_for_message(
         &self,
@@ -178,3 +498,25 @@ impl AckServiceConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_importance_level_ttl_and_archive() {
+        let mut config = AckServiceConfig::default();
+        config.importance_config.high.redis_ttl = 30 * 86400;
+        config.importance_config.medium.redis_ttl = 0;
+        config.importance_config.low.redis_ttl = 86400;
+
+        let policy = config.ttl_policy();
+        // 级别过期时间不受全局 redis_ttl 限制，未配置时回退到全局值
+        assert_eq!(policy.high, 30 * 86400);
+        assert_eq!(policy.medium, config.redis_ttl);
+        assert_eq!(policy.low, 86400);
+
+        assert!(config.archives(&ImportanceLevel::High));
+        assert!(!config.archives(&ImportanceLevel::Low));
+    }
+}
@@ -0,0 +1,256 @@
+//! 群聊消息ACK聚合
+//!
+//! 群聊消息发送时登记接收者人数，之后按用户去重累计送达数与已读数：
+//! - 每条消息一个计数哈希（`ack_group:{message_id}`），记录接收者总数、送达数、已读数
+//! - 送达/已读用户分别记录在集合中去重（用户ID为字符串，无法使用位图；HLL 为近似计数，无法判定全员已读）
+//! - 已读隐含送达；已读数达到接收者总数时记录全员已读时间并删除成员集合，之后的重复ACK不再计数
+
+use std::collections::HashMap;
+
+use redis::aio::ConnectionLike;
+use redis::{AsyncCommands, RedisResult, Script};
+
+/// 累计一次群聊消息ACK（返回 `[是否新增送达, 是否新增已读, 是否本次达成全员已读]`）
+///
+/// KEYS: 计数哈希、送达用户集合、已读用户集合
+/// ARGV: 用户ID、ACK类型（delivered / read）、当前时间
+const RECORD_GROUP_ACK_SCRIPT: &str = r#"
+if redis.call('EXISTS', KEYS[1]) == 0 or redis.call('HEXISTS', KEYS[1], 'all_read_at') == 1 then
+    return {0, 0, 0}
+end
+local ttl = redis.call('TTL', KEYS[1])
+local delivered_new = redis.call('SADD', KEYS[2], ARGV[1])
+if delivered_new == 1 then
+    redis.call('HINCRBY', KEYS[1], 'delivered', 1)
+    if ttl > 0 then
+        redis.call('EXPIRE', KEYS[2], ttl)
+    end
+end
+if ARGV[2] ~= 'read' then
+    return {delivered_new, 0, 0}
+end
+local read_new = redis.call('SADD', KEYS[3], ARGV[1])
+if read_new == 0 then
+    return {delivered_new, 0, 0}
+end
+if ttl > 0 then
+    redis.call('EXPIRE', KEYS[3], ttl)
+end
+local read = redis.call('HINCRBY', KEYS[1], 'read', 1)
+if read < tonumber(redis.call('HGET', KEYS[1], 'total')) then
+    return {delivered_new, 1, 0}
+end
+redis.call('HSET', KEYS[1], 'all_read_at', ARGV[3])
+redis.call('DEL', KEYS[2], KEYS[3])
+return {delivered_new, 1, 1}
+"#;
+
+/// 群聊消息ACK类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupAckKind {
+    /// 已送达
+    Delivered,
+    /// 已读（同时计为已送达）
+    Read,
+}
+
+impl GroupAckKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupAckKind::Delivered => "delivered",
+            GroupAckKind::Read => "read",
+        }
+    }
+}
+
+/// 一次群聊消息ACK的累计结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GroupAckOutcome {
+    /// 该用户首次计入送达
+    pub delivered_counted: bool,
+    /// 该用户首次计入已读
+    pub read_counted: bool,
+    /// 本次ACK使消息达成全员已读
+    pub fully_read: bool,
+}
+
+/// 群聊消息ACK聚合摘要
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageAckSummary {
+    /// 消息ID
+    pub message_id: String,
+    /// 会话ID
+    pub conversation_id: String,
+    /// 接收者总数（不含发送者）
+    pub total_recipients: u64,
+    /// 已送达人数
+    pub delivered_count: u64,
+    /// 已读人数
+    pub read_count: u64,
+    /// 全员已读时间（秒）
+    pub all_read_at: Option<u64>,
+}
+
+impl MessageAckSummary {
+    /// 是否全员已读
+    pub fn is_fully_read(&self) -> bool {
+        self.all_read_at.is_some()
+    }
+
+    fn from_fields(message_id: &str, fields: HashMap<String, String>) -> Option<Self> {
+        let parse = |field: &str| {
+            fields
+                .get(field)
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+        Some(Self {
+            message_id: message_id.to_string(),
+            conversation_id: fields.get("conversation_id").cloned().unwrap_or_default(),
+            total_recipients: parse("total")?,
+            delivered_count: parse("delivered").unwrap_or(0),
+            read_count: parse("read").unwrap_or(0),
+            all_read_at: parse("all_read_at"),
+        })
+    }
+}
+
+/// 群聊消息ACK聚合（Redis 实现，单机与集群存储共用）
+pub(crate) struct GroupAckAggregator {
+    /// 聚合记录过期时间（秒）
+    ttl: u64,
+    /// 键是否使用哈希标签（集群模式下同一消息的键需落在同一槽位）
+    hash_tag: bool,
+    /// 累计脚本
+    record_script: Script,
+}
+
+impl GroupAckAggregator {
+    pub(crate) fn new(ttl: u64) -> Self {
+        Self {
+            ttl: ttl.max(1),
+            hash_tag: false,
+            record_script: Script::new(RECORD_GROUP_ACK_SCRIPT),
+        }
+    }
+
+    /// 使用哈希标签生成键（集群模式）
+    pub(crate) fn with_hash_tag(mut self) -> Self {
+        self.hash_tag = true;
+        self
+    }
+
+    /// 登记群聊消息的接收者人数（重复登记只更新人数，不清空已有计数）
+    pub(crate) async fn register_message<C: ConnectionLike + Send>(
+        &self,
+        conn: &mut C,
+        message_id: &str,
+        conversation_id: &str,
+        recipient_count: u64,
+    ) -> RedisResult<()> {
+        let key = self.summary_key(message_id);
+        let _: () = redis::pipe()
+            .atomic()
+            .hset_multiple(
+                &key,
+                &[
+                    ("conversation_id", conversation_id.to_string()),
+                    ("total", recipient_count.to_string()),
+                ],
+            )
+            .ignore()
+            .expire(&key, self.ttl as i64)
+            .ignore()
+            .query_async(conn)
+            .await?;
+        Ok(())
+    }
+
+    /// 累计一次送达/已读ACK（消息未登记或已全员已读时不计数）
+    pub(crate) async fn record<C: ConnectionLike + Send>(
+        &self,
+        conn: &mut C,
+        message_id: &str,
+        user_id: &str,
+        kind: GroupAckKind,
+        now: u64,
+    ) -> RedisResult<GroupAckOutcome> {
+        let tag = self.tag(message_id);
+        let result: Vec<i64> = self
+            .record_script
+            .key(format!("ack_group:{}", tag))
+            .key(format!("ack_group_delivered:{}", tag))
+            .key(format!("ack_group_read:{}", tag))
+            .arg(user_id)
+            .arg(kind.as_str())
+            .arg(now)
+            .invoke_async(conn)
+            .await?;
+        let flag = |index: usize| result.get(index).copied().unwrap_or(0) == 1;
+        Ok(GroupAckOutcome {
+            delivered_counted: flag(0),
+            read_counted: flag(1),
+            fully_read: flag(2),
+        })
+    }
+
+    /// 获取群聊消息的ACK聚合摘要（未登记或已过期时返回 None）
+    pub(crate) async fn get_summary<C: ConnectionLike + Send>(
+        &self,
+        conn: &mut C,
+        message_id: &str,
+    ) -> RedisResult<Option<MessageAckSummary>> {
+        let fields: HashMap<String, String> = conn.hgetall(self.summary_key(message_id)).await?;
+        Ok(MessageAckSummary::from_fields(message_id, fields))
+    }
+
+    /// 计数哈希键（不匹配 `ack:*:*`，不会被超时扫描命中）
+    fn summary_key(&self, message_id: &str) -> String {
+        format!("ack_group:{}", self.tag(message_id))
+    }
+
+    fn tag(&self, message_id: &str) -> String {
+        if self.hash_tag {
+            format!("{{{}}}", message_id)
+        } else {
+            message_id.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_from_fields() {
+        let fields: HashMap<String, String> = [
+            ("conversation_id", "group_1"),
+            ("total", "3"),
+            ("delivered", "3"),
+            ("read", "3"),
+            ("all_read_at", "1234567890"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let summary = MessageAckSummary::from_fields("msg_1", fields).unwrap();
+        assert_eq!(summary.conversation_id, "group_1");
+        assert_eq!(summary.total_recipients, 3);
+        assert_eq!(summary.delivered_count, 3);
+        assert_eq!(summary.read_count, 3);
+        assert!(summary.is_fully_read());
+
+        // 尚无ACK的已登记消息
+        let fields: HashMap<String, String> = [("conversation_id", "group_1"), ("total", "5")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let summary = MessageAckSummary::from_fields("msg_2", fields).unwrap();
+        assert_eq!(summary.delivered_count, 0);
+        assert!(!summary.is_fully_read());
+
+        // 未登记的消息
+        assert!(MessageAckSummary::from_fields("msg_3", HashMap::new()).is_none());
+    }
+}
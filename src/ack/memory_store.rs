@@ -0,0 +1,774 @@
+//! ACK状态内存存储
+//!
+//! 进程内实现，语义与 Redis 实现一致（分级过期、消息级压缩、截止时间队列、群聊聚合），
+//! 用于测试与单机开发，不支持多实例共享。过期记录在访问时视为不存在，并在写入时定期清理
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+
+use crate::ack::group_aggregator::{GroupAckKind, GroupAckOutcome, MessageAckSummary};
+use crate::ack::redis_manager::{
+    AckDeadlinePolicy, AckStatus, AckStatusInfo, AckSummary, AckTtlPolicy, DEFAULT_GROUP_TTL,
+    ExpiredAckDeadline, RedisStats,
+};
+use crate::ack::store::{AckStore, AckStoreResult};
+
+/// 每写入多少次清理一次过期记录
+const PURGE_EVERY_WRITES: u64 = 1024;
+
+/// 内存ACK存储
+pub struct MemoryAckStore {
+    /// 按重要性分级的过期时间
+    ttl_policy: AckTtlPolicy,
+    /// 是否启用消息级压缩
+    compaction_enabled: bool,
+    /// 确认超时时间（未设置时不跟踪截止时间）
+    deadline_policy: Option<AckDeadlinePolicy>,
+    /// 群聊消息ACK聚合记录过期时间（秒）
+    group_ttl: u64,
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    /// (message_id, user_id) -> ACK状态
+    acks: HashMap<(String, String), Expiring<AckStatusInfo>>,
+    /// message_id -> 接收者登记表（user_id -> 状态）
+    recipients: HashMap<String, Expiring<HashMap<String, AckStatus>>>,
+    /// message_id -> 压缩摘要
+    summaries: HashMap<String, Expiring<AckSummary>>,
+    /// 截止时间队列（截止时间, message_id, user_id）
+    deadlines: BTreeSet<(u64, String, String)>,
+    /// (message_id, user_id) -> 截止时间
+    deadline_index: HashMap<(String, String), u64>,
+    /// user_id -> 待确认索引（时间戳, message_id）
+    pending: HashMap<String, BTreeSet<(u64, String)>>,
+    /// message_id -> 群聊聚合
+    groups: HashMap<String, GroupState>,
+    /// 写入次数（用于定期清理）
+    writes: u64,
+}
+
+struct Expiring<T> {
+    value: T,
+    expires_at: u64,
+}
+
+impl<T> Expiring<T> {
+    fn live(&self, now: u64) -> Option<&T> {
+        (self.expires_at > now).then_some(&self.value)
+    }
+}
+
+struct GroupState {
+    conversation_id: String,
+    total: u64,
+    delivered_count: u64,
+    read_count: u64,
+    delivered: HashSet<String>,
+    read: HashSet<String>,
+    all_read_at: Option<u64>,
+    expires_at: u64,
+}
+
+impl MemoryState {
+    fn remove_deadline(&mut self, message_id: &str, user_id: &str) {
+        let key = (message_id.to_string(), user_id.to_string());
+        if let Some(deadline) = self.deadline_index.remove(&key) {
+            self.deadlines.remove(&(deadline, key.0, key.1));
+        }
+    }
+
+    fn set_deadline(&mut self, message_id: &str, user_id: &str, deadline: u64) {
+        self.remove_deadline(message_id, user_id);
+        self.deadline_index
+            .insert((message_id.to_string(), user_id.to_string()), deadline);
+        self.deadlines
+            .insert((deadline, message_id.to_string(), user_id.to_string()));
+    }
+
+    /// 移出用户的待确认索引（需在删除或覆盖逐用户记录之前调用）
+    fn remove_pending(&mut self, message_id: &str, user_id: &str) {
+        let key = (message_id.to_string(), user_id.to_string());
+        let Some(entry) = self.acks.get(&key) else {
+            return;
+        };
+        let timestamp = entry.value.timestamp;
+        if let Some(index) = self.pending.get_mut(user_id) {
+            index.remove(&(timestamp, key.0));
+            if index.is_empty() {
+                self.pending.remove(user_id);
+            }
+        }
+    }
+
+    fn purge_expired(&mut self, now: u64) {
+        self.acks.retain(|_, entry| entry.expires_at > now);
+        let acks = &self.acks;
+        self.pending.retain(|user_id, index| {
+            index.retain(|(_, message_id)| {
+                acks.contains_key(&(message_id.clone(), user_id.clone()))
+            });
+            !index.is_empty()
+        });
+        self.recipients.retain(|_, entry| entry.expires_at > now);
+        self.summaries.retain(|_, entry| entry.expires_at > now);
+        self.groups.retain(|_, group| group.expires_at > now);
+    }
+}
+
+impl MemoryAckStore {
+    pub fn new(ttl_policy: AckTtlPolicy) -> Self {
+        Self {
+            ttl_policy,
+            compaction_enabled: true,
+            deadline_policy: None,
+            group_ttl: DEFAULT_GROUP_TTL,
+            state: Mutex::new(MemoryState::default()),
+        }
+    }
+
+    /// 设置确认超时时间，Pending 状态的ACK会写入截止时间队列
+    pub fn with_deadline_policy(mut self, deadline_policy: AckDeadlinePolicy) -> Self {
+        self.deadline_policy = Some(deadline_policy);
+        self
+    }
+
+    /// 设置是否启用消息级压缩
+    pub fn with_compaction(mut self, enabled: bool) -> Self {
+        self.compaction_enabled = enabled;
+        self
+    }
+
+    /// 设置群聊消息ACK聚合记录的过期时间（秒）
+    pub fn with_group_ttl(mut self, ttl: u64) -> Self {
+        self.group_ttl = ttl.max(1);
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn store(&self, state: &mut MemoryState, ack_info: &AckStatusInfo, now: u64) -> bool {
+        let ttl = self.ttl_policy.ttl_for(&ack_info.importance);
+        let message_id = &ack_info.message_id;
+        let user_id = &ack_info.user_id;
+        state.remove_pending(message_id, user_id);
+        if !ack_info.status.is_final() {
+            state
+                .pending
+                .entry(user_id.clone())
+                .or_default()
+                .insert((ack_info.timestamp, message_id.clone()));
+        }
+        state.acks.insert(
+            (message_id.clone(), user_id.clone()),
+            Expiring {
+                value: ack_info.clone(),
+                expires_at: now + ttl,
+            },
+        );
+
+        if ack_info.status.is_final() {
+            state.remove_deadline(message_id, user_id);
+        } else if let Some(policy) = &self.deadline_policy {
+            let deadline = policy.deadline_for(ack_info, now);
+            if deadline > 0 {
+                state.set_deadline(message_id, user_id, deadline);
+            }
+        }
+
+        if !self.compaction_enabled {
+            return false;
+        }
+
+        // 非终态：登记为接收者
+        if !ack_info.status.is_final() {
+            let entry = state
+                .recipients
+                .entry(message_id.clone())
+                .or_insert_with(|| Expiring {
+                    value: HashMap::new(),
+                    expires_at: 0,
+                });
+            if entry.expires_at <= now {
+                entry.value.clear();
+            }
+            entry
+                .value
+                .entry(user_id.clone())
+                .or_insert(AckStatus::Pending);
+            entry.expires_at = entry.expires_at.max(now + ttl);
+            return false;
+        }
+
+        // 终态：更新登记表，所有接收者进入终态后压缩
+        let Some(entry) = state.recipients.get_mut(message_id) else {
+            return false;
+        };
+        if entry.expires_at <= now || entry.value.get(user_id) != Some(&AckStatus::Pending) {
+            return false;
+        }
+        entry.value.insert(user_id.clone(), ack_info.status.clone());
+        if entry
+            .value
+            .values()
+            .any(|status| *status == AckStatus::Pending)
+        {
+            return false;
+        }
+
+        let Some(entry) = state.recipients.remove(message_id) else {
+            return false;
+        };
+        // 清理任务可能已将部分终态记录合并进摘要，此处累加
+        let mut summary = Self::summary(&state, message_id, now).unwrap_or_else(|| AckSummary {
+            message_id: message_id.clone(),
+            total: 0,
+            status_counts: HashMap::new(),
+            failed_users: Vec::new(),
+            completed_at: now,
+            importance: ack_info.importance.clone(),
+        });
+        summary.completed_at = now;
+        summary.importance = ack_info.importance.clone();
+        for (user, status) in entry.value {
+            state.acks.remove(&(message_id.clone(), user.clone()));
+            summary.total += 1;
+            *summary
+                .status_counts
+                .entry(status.as_str().to_string())
+                .or_insert(0) += 1;
+            if status == AckStatus::Failed {
+                summary.failed_users.push(user);
+            }
+        }
+        summary.failed_users.sort();
+        state.summaries.insert(
+            message_id.clone(),
+            Expiring {
+                value: summary,
+                expires_at: now + self.ttl_policy.summary.max(1),
+            },
+        );
+        true
+    }
+
+    fn summary(state: &MemoryState, message_id: &str, now: u64) -> Option<AckSummary> {
+        state
+            .summaries
+            .get(message_id)
+            .and_then(|entry| entry.live(now))
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl AckStore for MemoryAckStore {
+    fn ttl_policy(&self) -> &AckTtlPolicy {
+        &self.ttl_policy
+    }
+
+    async fn store_ack_status(&self, ack_info: &AckStatusInfo) -> AckStoreResult<bool> {
+        self.batch_store_ack_status(std::slice::from_ref(ack_info))
+            .await
+            .map(|compacted| compacted > 0)
+    }
+
+    async fn batch_store_ack_status(&self, ack_infos: &[AckStatusInfo]) -> AckStoreResult<usize> {
+        let now = now_secs();
+        let mut state = self.lock();
+        let mut compacted = 0;
+        for ack_info in ack_infos {
+            if self.store(&mut state, ack_info, now) {
+                compacted += 1;
+            }
+        }
+        state.writes += ack_infos.len() as u64;
+        if state.writes >= PURGE_EVERY_WRITES {
+            state.writes = 0;
+            state.purge_expired(now);
+        }
+        Ok(compacted)
+    }
+
+    async fn get_ack_status(
+        &self,
+        message_id: &str,
+        user_id: &str,
+    ) -> AckStoreResult<Option<AckStatusInfo>> {
+        let now = now_secs();
+        let state = self.lock();
+        let key = (message_id.to_string(), user_id.to_string());
+        if let Some(ack_info) = state.acks.get(&key).and_then(|entry| entry.live(now)) {
+            return Ok(Some(ack_info.clone()));
+        }
+        Ok(Self::summary(&state, message_id, now).map(|summary| summary.status_info_for(user_id)))
+    }
+
+    async fn get_ack_summary(&self, message_id: &str) -> AckStoreResult<Option<AckSummary>> {
+        Ok(Self::summary(&self.lock(), message_id, now_secs()))
+    }
+
+    async fn delete_ack_status(&self, message_id: &str, user_id: &str) -> AckStoreResult<()> {
+        let mut state = self.lock();
+        state.remove_pending(message_id, user_id);
+        state
+            .acks
+            .remove(&(message_id.to_string(), user_id.to_string()));
+        state.remove_deadline(message_id, user_id);
+        Ok(())
+    }
+
+    async fn exists_ack(&self, message_id: &str, user_id: &str) -> AckStoreResult<bool> {
+        let now = now_secs();
+        let state = self.lock();
+        let key = (message_id.to_string(), user_id.to_string());
+        Ok(state
+            .acks
+            .get(&key)
+            .and_then(|entry| entry.live(now))
+            .is_some()
+            || Self::summary(&state, message_id, now).is_some())
+    }
+
+    async fn claim_expired_deadlines(
+        &self,
+        now: u64,
+        limit: usize,
+    ) -> AckStoreResult<Vec<ExpiredAckDeadline>> {
+        let mut state = self.lock();
+        let claimed: Vec<(u64, String, String)> = state
+            .deadlines
+            .iter()
+            .take_while(|(deadline, _, _)| *deadline <= now)
+            .take(limit)
+            .cloned()
+            .collect();
+        Ok(claimed
+            .into_iter()
+            .map(|(deadline, message_id, user_id)| {
+                state.remove_deadline(&message_id, &user_id);
+                ExpiredAckDeadline {
+                    message_id,
+                    user_id,
+                    deadline,
+                }
+            })
+            .collect())
+    }
+
+    async fn scan_ack_statuses(
+        &self,
+        max_records: usize,
+        _batch_size: usize,
+    ) -> AckStoreResult<Vec<AckStatusInfo>> {
+        let now = now_secs();
+        let state = self.lock();
+        let live = state
+            .acks
+            .values()
+            .filter_map(|entry| entry.live(now))
+            .cloned();
+        Ok(if max_records > 0 {
+            live.take(max_records).collect()
+        } else {
+            live.collect()
+        })
+    }
+
+    async fn list_pending_acks(
+        &self,
+        user_id: &str,
+        limit: usize,
+        since: u64,
+    ) -> AckStoreResult<Vec<AckStatusInfo>> {
+        let now = now_secs();
+        let state = self.lock();
+        let Some(index) = state.pending.get(user_id) else {
+            return Ok(Vec::new());
+        };
+        let pending = index
+            .range((since, String::new())..)
+            .filter_map(|(_, message_id)| {
+                state
+                    .acks
+                    .get(&(message_id.clone(), user_id.to_string()))
+                    .and_then(|entry| entry.live(now))
+            })
+            .filter(|ack_info| ack_info.status == AckStatus::Pending)
+            .cloned();
+        Ok(if limit > 0 {
+            pending.take(limit).collect()
+        } else {
+            pending.collect()
+        })
+    }
+
+    async fn merge_final_acks(
+        &self,
+        message_id: &str,
+        user_ids: &[String],
+        now: u64,
+    ) -> AckStoreResult<usize> {
+        let mut state = self.lock();
+        let mut merged = Vec::new();
+        for user_id in user_ids {
+            let key = (message_id.to_string(), user_id.clone());
+            let Some(ack_info) = state.acks.get(&key).and_then(|entry| entry.live(now)) else {
+                continue;
+            };
+            if !ack_info.status.is_final() {
+                continue;
+            }
+            let ack_info = ack_info.clone();
+            state.acks.remove(&key);
+            merged.push(ack_info);
+        }
+        let Some(first) = merged.first() else {
+            return Ok(0);
+        };
+
+        let summary_expires_at = now + self.ttl_policy.summary.max(1);
+        let importance = first.importance.clone();
+        if Self::summary(&state, message_id, now).is_none() {
+            state.summaries.remove(message_id);
+        }
+        let entry = state
+            .summaries
+            .entry(message_id.to_string())
+            .or_insert_with(|| Expiring {
+                value: AckSummary {
+                    message_id: message_id.to_string(),
+                    total: 0,
+                    status_counts: HashMap::new(),
+                    failed_users: Vec::new(),
+                    completed_at: now,
+                    importance,
+                },
+                expires_at: 0,
+            });
+        entry.expires_at = entry.expires_at.max(summary_expires_at);
+        for ack_info in &merged {
+            let summary = &mut entry.value;
+            summary.total += 1;
+            *summary
+                .status_counts
+                .entry(ack_info.status.as_str().to_string())
+                .or_insert(0) += 1;
+            if ack_info.status == AckStatus::Failed {
+                summary.failed_users.push(ack_info.user_id.clone());
+            }
+        }
+        entry.value.failed_users.sort();
+
+        if let Some(registry) = state.recipients.get_mut(message_id) {
+            for ack_info in &merged {
+                registry.value.remove(&ack_info.user_id);
+            }
+            if registry.value.is_empty() {
+                state.recipients.remove(message_id);
+            }
+        }
+        Ok(merged.len())
+    }
+
+    async fn register_group_message(
+        &self,
+        message_id: &str,
+        conversation_id: &str,
+        recipient_count: u64,
+    ) -> AckStoreResult<()> {
+        let expires_at = now_secs() + self.group_ttl;
+        let mut state = self.lock();
+        let group = state
+            .groups
+            .entry(message_id.to_string())
+            .or_insert_with(|| GroupState {
+                conversation_id: String::new(),
+                total: 0,
+                delivered_count: 0,
+                read_count: 0,
+                delivered: HashSet::new(),
+                read: HashSet::new(),
+                all_read_at: None,
+                expires_at,
+            });
+        group.conversation_id = conversation_id.to_string();
+        group.total = recipient_count;
+        group.expires_at = expires_at;
+        Ok(())
+    }
+
+    async fn record_group_ack(
+        &self,
+        message_id: &str,
+        user_id: &str,
+        kind: GroupAckKind,
+        now: u64,
+    ) -> AckStoreResult<GroupAckOutcome> {
+        let mut state = self.lock();
+        let mut outcome = GroupAckOutcome::default();
+        let Some(group) = state.groups.get_mut(message_id) else {
+            return Ok(outcome);
+        };
+        if group.expires_at <= now || group.all_read_at.is_some() {
+            return Ok(outcome);
+        }
+        if group.delivered.insert(user_id.to_string()) {
+            group.delivered_count += 1;
+            outcome.delivered_counted = true;
+        }
+        if kind == GroupAckKind::Read && group.read.insert(user_id.to_string()) {
+            group.read_count += 1;
+            outcome.read_counted = true;
+            if group.read_count >= group.total {
+                group.all_read_at = Some(now);
+                group.delivered.clear();
+                group.read.clear();
+                outcome.fully_read = true;
+            }
+        }
+        Ok(outcome)
+    }
+
+    async fn get_group_summary(
+        &self,
+        message_id: &str,
+    ) -> AckStoreResult<Option<MessageAckSummary>> {
+        let now = now_secs();
+        let state = self.lock();
+        Ok(state
+            .groups
+            .get(message_id)
+            .filter(|group| group.expires_at > now)
+            .map(|group| MessageAckSummary {
+                message_id: message_id.to_string(),
+                conversation_id: group.conversation_id.clone(),
+                total_recipients: group.total,
+                delivered_count: group.delivered_count,
+                read_count: group.read_count,
+                all_read_at: group.all_read_at,
+            }))
+    }
+
+    async fn get_stats(&self) -> AckStoreResult<RedisStats> {
+        Ok(RedisStats {
+            used_memory: 0,
+            used_memory_peak: 0,
+        })
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ack::redis_manager::{AckType, ImportanceLevel};
+
+    fn ack(message_id: &str, user_id: &str, status: AckStatus) -> AckStatusInfo {
+        AckStatusInfo {
+            message_id: message_id.to_string(),
+            user_id: user_id.to_string(),
+            ack_type: Some(AckType::DeliveryAck),
+            status,
+            timestamp: 1234567890,
+            importance: ImportanceLevel::High,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compaction_and_summary_fallback() -> AckStoreResult<()> {
+        let store = MemoryAckStore::new(AckTtlPolicy::from_default_ttl(3600));
+        store
+            .store_ack_status(&ack("msg_1", "user_1", AckStatus::Pending))
+            .await?;
+        store
+            .store_ack_status(&ack("msg_1", "user_2", AckStatus::Pending))
+            .await?;
+
+        assert!(
+            !store
+                .store_ack_status(&ack("msg_1", "user_1", AckStatus::Received))
+                .await?
+        );
+        assert!(
+            store
+                .store_ack_status(&ack("msg_1", "user_2", AckStatus::Failed))
+                .await?
+        );
+
+        let summary = store.get_ack_summary("msg_1").await?.unwrap();
+        assert_eq!(summary.total, 2);
+        assert_eq!(summary.failed_users, vec!["user_2".to_string()]);
+
+        // 逐用户记录已压缩，查询回落到摘要
+        let status = store.get_ack_status("msg_1", "user_1").await?.unwrap();
+        assert_eq!(status.status, AckStatus::Received);
+        assert_eq!(status.ack_type, None);
+        assert!(store.exists_ack("msg_1", "user_2").await?);
+        assert!(store.scan_ack_statuses(0, 100).await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_claim_expired_deadlines() -> AckStoreResult<()> {
+        let store = MemoryAckStore::new(AckTtlPolicy::from_default_ttl(3600)).with_deadline_policy(
+            AckDeadlinePolicy {
+                high: 30,
+                medium: 60,
+                low: 0,
+            },
+        );
+        store
+            .store_ack_status(&ack("msg_1", "user_1", AckStatus::Pending))
+            .await?;
+        store
+            .store_ack_status(&ack("msg_1", "user_2", AckStatus::Pending))
+            .await?;
+        store
+            .store_ack_status(&ack("msg_1", "user_2", AckStatus::Received))
+            .await?;
+
+        let now = now_secs();
+        assert!(store.claim_expired_deadlines(now, 10).await?.is_empty());
+
+        // 终态的ACK已移出队列，领取后不再重复返回
+        let expired = store.claim_expired_deadlines(now + 60, 10).await?;
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].user_id, "user_1");
+        assert!(
+            store
+                .claim_expired_deadlines(now + 60, 10)
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_pending_acks() -> AckStoreResult<()> {
+        let store = MemoryAckStore::new(AckTtlPolicy::from_default_ttl(3600));
+        for (message_id, timestamp) in [("msg_1", 100), ("msg_2", 200), ("msg_3", 300)] {
+            let mut ack_info = ack(message_id, "user_1", AckStatus::Pending);
+            ack_info.timestamp = timestamp;
+            store.store_ack_status(&ack_info).await?;
+        }
+        store
+            .store_ack_status(&ack("msg_1", "user_2", AckStatus::Pending))
+            .await?;
+        store
+            .store_ack_status(&ack("msg_2", "user_1", AckStatus::Received))
+            .await?;
+
+        // 已确认的消息移出索引，结果按时间戳升序
+        let pending = store.list_pending_acks("user_1", 0, 0).await?;
+        let ids: Vec<&str> = pending.iter().map(|a| a.message_id.as_str()).collect();
+        assert_eq!(ids, vec!["msg_1", "msg_3"]);
+
+        let pending = store.list_pending_acks("user_1", 1, 150).await?;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].message_id, "msg_3");
+
+        store.delete_ack_status("msg_3", "user_1").await?;
+        assert_eq!(store.list_pending_acks("user_1", 0, 150).await?.len(), 0);
+        assert!(store.list_pending_acks("user_3", 0, 0).await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_final_acks() -> AckStoreResult<()> {
+        let store = MemoryAckStore::new(AckTtlPolicy::from_default_ttl(3600));
+        for user_id in ["user_1", "user_2", "user_3"] {
+            store
+                .store_ack_status(&ack("msg_1", user_id, AckStatus::Pending))
+                .await?;
+        }
+        store
+            .store_ack_status(&ack("msg_1", "user_1", AckStatus::Received))
+            .await?;
+        store
+            .store_ack_status(&ack("msg_1", "user_2", AckStatus::Failed))
+            .await?;
+
+        // 仍为 Pending 的记录不合并
+        let users: Vec<String> = ["user_1", "user_2", "user_3"]
+            .iter()
+            .map(|u| u.to_string())
+            .collect();
+        assert_eq!(
+            store.merge_final_acks("msg_1", &users, now_secs()).await?,
+            2
+        );
+
+        let summary = store.get_ack_summary("msg_1").await?.unwrap();
+        assert_eq!(summary.total, 2);
+        assert_eq!(summary.failed_users, vec!["user_2".to_string()]);
+        let status = store.get_ack_status("msg_1", "user_2").await?.unwrap();
+        assert_eq!(status.status, AckStatus::Failed);
+        let status = store.get_ack_status("msg_1", "user_3").await?.unwrap();
+        assert_eq!(status.status, AckStatus::Pending);
+
+        // 剩余接收者进入终态后写入时压缩，计数累加到已有摘要
+        assert!(
+            store
+                .store_ack_status(&ack("msg_1", "user_3", AckStatus::Received))
+                .await?
+        );
+        let summary = store.get_ack_summary("msg_1").await?.unwrap();
+        assert_eq!(summary.total, 3);
+        assert_eq!(
+            store.merge_final_acks("msg_1", &users, now_secs()).await?,
+            0
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_group_read_complete_once() -> AckStoreResult<()> {
+        let store = MemoryAckStore::new(AckTtlPolicy::from_default_ttl(3600));
+        let now = now_secs();
+
+        // 未登记的消息不计数
+        let outcome = store
+            .record_group_ack("msg_1", "user_1", GroupAckKind::Read, now)
+            .await?;
+        assert_eq!(outcome, GroupAckOutcome::default());
+
+        store.register_group_message("msg_1", "group_1", 2).await?;
+        let outcome = store
+            .record_group_ack("msg_1", "user_1", GroupAckKind::Delivered, now)
+            .await?;
+        assert!(outcome.delivered_counted && !outcome.read_counted);
+        let outcome = store
+            .record_group_ack("msg_1", "user_1", GroupAckKind::Read, now)
+            .await?;
+        assert!(!outcome.delivered_counted && outcome.read_counted && !outcome.fully_read);
+        let outcome = store
+            .record_group_ack("msg_1", "user_2", GroupAckKind::Read, now)
+            .await?;
+        assert!(outcome.delivered_counted && outcome.fully_read);
+
+        // 全员已读后重复ACK不再计数
+        let outcome = store
+            .record_group_ack("msg_1", "user_2", GroupAckKind::Read, now)
+            .await?;
+        assert_eq!(outcome, GroupAckOutcome::default());
+
+        let summary = store.get_group_summary("msg_1").await?.unwrap();
+        assert_eq!(summary.conversation_id, "group_1");
+        assert_eq!((summary.delivered_count, summary.read_count), (2, 2));
+        assert!(summary.is_fully_read());
+        Ok(())
+    }
+}
@@ -45,6 +45,28 @@ pub struct AckMetrics {
     pub memory_usage_bytes: IntGauge,
     /// 不同重要性级别的处理延迟
     pub ack_processing_latency_by_importance: HistogramVec,
+    /// 压缩为摘要的消息数
+    pub acks_compacted: IntCounter,
+    /// 内存缓存中过期淘汰的ACK数
+    pub cache_evicted: IntCounter,
+    /// 写入归档存储的ACK数
+    pub acks_archived: IntCounter,
+    /// 未能归档而丢弃的ACK数（按原因分类）
+    pub archive_dropped: IntCounterVec,
+    /// 归档队列中等待写入的ACK数
+    pub archive_queue_size: IntGauge,
+    /// 群聊消息送达/已读计数（按类型分类，仅统计首次计入的用户）
+    pub group_acks_counted: IntCounterVec,
+    /// 全员已读的群聊消息数
+    pub group_messages_fully_read: IntCounter,
+    /// 订阅者消费过慢而跳过的ACK状态变更数
+    pub ack_watch_lagged: IntCounter,
+    /// ACK各阶段之间的耗时（按租户与阶段区间分类，含发送到已读的端到端耗时）
+    pub ack_stage_latency: HistogramVec,
+    /// 清理任务删除的逐用户ACK键数
+    pub ack_cleanup_reclaimed_keys: IntCounter,
+    /// 清理任务合并到摘要的消息数
+    pub ack_cleanup_merged_messages: IntCounter,
 }
 
 impl AckMetrics {
@@ -142,7 +164,86 @@ impl AckMetrics {
         registry.register(Box::new(ack_retries.clone()))?;
         registry.register(Box::new(ack_processing_latency.clone()))?;
         registry.register(Box::new(memory_usage_bytes.clone()))?;
+        let acks_compacted = IntCounter::new(
+            "ack_compacted_messages_total",
+            "Total number of messages whose per-user ACKs were compacted into a summary",
+        )?;
+
+        let cache_evicted = IntCounter::new(
+            "ack_cache_evicted_total",
+            "Total number of expired ACKs evicted from the memory cache",
+        )?;
+
+        let acks_archived = IntCounter::new(
+            "ack_archived_total",
+            "Total number of final ACKs written to the archive sink",
+        )?;
+
+        let archive_dropped = IntCounterVec::new(
+            Opts::new(
+                "ack_archive_dropped_total",
+                "Total number of ACKs dropped before reaching the archive sink",
+            ),
+            &["reason"],
+        )?;
+
+        let archive_queue_size = IntGauge::new(
+            "ack_archive_queue_size",
+            "Current number of ACKs waiting to be archived",
+        )?;
+
+        let group_acks_counted = IntCounterVec::new(
+            Opts::new(
+                "ack_group_counted_total",
+                "Total number of distinct group message recipients counted as delivered or read",
+            ),
+            &["kind"],
+        )?;
+
+        let group_messages_fully_read = IntCounter::new(
+            "ack_group_messages_fully_read_total",
+            "Total number of group messages read by all recipients",
+        )?;
+
+        let ack_watch_lagged = IntCounter::new(
+            "ack_watch_lagged_total",
+            "Total number of ACK status changes skipped by lagging watchers",
+        )?;
+
+        let ack_stage_latency = HistogramVec::new(
+            HistogramOpts::new(
+                "ack_stage_latency_seconds",
+                "Latency between ACK stages of a message for one recipient in seconds",
+            )
+            .buckets(vec![
+                0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0, 1800.0, 3600.0,
+                86400.0,
+            ]),
+            &["tenant", "span"],
+        )?;
+
+        let ack_cleanup_reclaimed_keys = IntCounter::new(
+            "ack_cleanup_reclaimed_keys_total",
+            "Total number of per-user ACK keys reclaimed by the cleanup job",
+        )?;
+
+        let ack_cleanup_merged_messages = IntCounter::new(
+            "ack_cleanup_merged_messages_total",
+            "Total number of messages whose final ACKs were merged into summaries by the cleanup job",
+        )?;
+
         registry.register(Box::new(ack_processing_latency_by_importance.clone()))?;
+        registry.register(Box::new(acks_compacted.clone()))?;
+        registry.register(Box::new(cache_evicted.clone()))?;
+        registry.register(Box::new(acks_archived.clone()))?;
+        registry.register(Box::new(archive_dropped.clone()))?;
+        registry.register(Box::new(archive_queue_size.clone()))?;
+        registry.register(Box::new(group_acks_counted.clone()))?;
+        registry.register(Box::new(group_messages_fully_read.clone()))?;
+        registry.register(Box::new(ack_watch_lagged.clone()))?;
+        registry.register(Box::new(ack_stage_latency.clone()))?;
+        registry.register(Box::new(ack_cleanup_reclaimed_keys.clone()))?;
+        registry.register(Box::new(ack_cleanup_merged_messages.clone()))?;
 
         Ok(Self {
             total_acks_processed,
@@ -162,6 +263,17 @@ impl AckMetrics {
             ack_processing_latency,
             memory_usage_bytes,
             ack_processing_latency_by_importance,
+            acks_compacted,
+            cache_evicted,
+            acks_archived,
+            archive_dropped,
+            archive_queue_size,
+            group_acks_counted,
+            group_messages_fully_read,
+            ack_watch_lagged,
+            ack_stage_latency,
+            ack_cleanup_reclaimed_keys,
+            ack_cleanup_merged_messages,
         })
     }
 
@@ -189,6 +301,63 @@ impl AckMetrics {
         self.acks_by_type.with_label_values(&[ack_type]).inc();
     }
 
+    /// 记录压缩为摘要的消息数
+    pub fn record_acks_compacted(&self, count: u64) {
+        if count > 0 {
+            self.acks_compacted.inc_by(count);
+        }
+    }
+
+    /// 记录一轮清理任务的结果
+    pub fn record_ack_cleanup(&self, merged_messages: u64, reclaimed_keys: u64) {
+        self.ack_cleanup_merged_messages.inc_by(merged_messages);
+        self.ack_cleanup_reclaimed_keys.inc_by(reclaimed_keys);
+    }
+
+    /// 记录内存缓存淘汰的ACK数
+    pub fn record_cache_evicted(&self, count: u64) {
+        self.cache_evicted.inc_by(count);
+    }
+
+    /// 记录写入归档存储的ACK数
+    pub fn record_acks_archived(&self, count: u64) {
+        self.acks_archived.inc_by(count);
+    }
+
+    /// 记录未能归档而丢弃的ACK数（reason: queue_full / write_failed / closed）
+    pub fn record_archive_dropped(&self, reason: &str, count: u64) {
+        self.archive_dropped
+            .with_label_values(&[reason])
+            .inc_by(count);
+    }
+
+    /// 更新归档队列大小
+    pub fn update_archive_queue_size(&self, size: i64) {
+        self.archive_queue_size.set(size);
+    }
+
+    /// 记录群聊消息首次计入的送达/已读（kind: delivered / read）
+    pub fn record_group_ack_counted(&self, kind: &str) {
+        self.group_acks_counted.with_label_values(&[kind]).inc();
+    }
+
+    /// 记录全员已读的群聊消息
+    pub fn record_group_message_fully_read(&self) {
+        self.group_messages_fully_read.inc();
+    }
+
+    /// 记录订阅者跳过的ACK状态变更数
+    pub fn record_ack_watch_lagged(&self, count: u64) {
+        self.ack_watch_lagged.inc_by(count);
+    }
+
+    /// 记录ACK阶段耗时（span: `<起始阶段>_to_<结束阶段>`）
+    pub fn record_ack_stage_latency(&self, tenant: &str, span: &str, seconds: f64) {
+        self.ack_stage_latency
+            .with_label_values(&[tenant, span])
+            .observe(seconds);
+    }
+
     /// 记录ACK处理延迟
     pub fn record_ack_processing_latency(&self, ack_type: &str, duration: f64) {
         self.ack_processing_latency
@@ -1,14 +1,26 @@
 //! ACK处理模块
-//! 整合ACK状态管理、Redis缓存、批量处理和异步归档功能
+//! 整合ACK状态管理、可插拔状态存储（内存 / Redis / Redis 集群）、批量处理和异步归档功能
 
+pub mod archiver;
+pub mod cleanup;
 pub mod config;
+pub mod group_aggregator;
+pub mod memory_store;
 pub mod metrics;
+pub mod redis_cluster_store;
 pub mod redis_manager;
 pub mod service;
+pub mod store;
+pub mod timeline;
 pub mod traits;
+pub mod watch;
+pub mod write_buffer;
 
+use crate::ack::archiver::{
+    AckArchiveQuery, AckArchiveRecord, ArchiveStats, PostgresAckArchiveSink,
+};
+use crate::ack::cleanup::AckCleanupJob;
 use crate::ack::metrics::AckMetrics;
-use crate::ack::redis_manager::RedisAckManager;
 use crate::ack::service::AckService;
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -16,27 +28,54 @@ use std::sync::Arc;
 /// ACK处理模块（精简版）
 ///
 /// 核心功能：
-/// - ACK 状态管理（内存 + Redis）
+/// - ACK 状态管理（内存缓存 + 可插拔状态存储）
+/// - 按重要性分级过期与消息级压缩
 /// - 批量处理
+/// - 终态ACK异步归档（可选）
+/// - 超过保留时间的终态ACK后台清理（可选）
+/// - 群聊消息送达/已读聚合
+/// - ACK状态变更订阅
+/// - ACK阶段时间线与按租户的"发送→已读"耗时
 /// - 监控指标
 pub struct AckModule {
     /// ACK服务（实现 AckManager trait）
     pub service: Arc<AckService>,
-    /// Redis管理器
-    pub redis_manager: Arc<RedisAckManager>,
+    /// ACK状态存储（按 `AckServiceConfig::store` 选择实现）
+    pub store: Arc<dyn AckStore>,
     /// 监控指标（暴露给外部使用）
     pub metrics: Arc<AckMetrics>,
+    /// Postgres 归档存储（配置了 `archive.database_url` 时启用）
+    pub archive_store: Option<Arc<PostgresAckArchiveSink>>,
+    /// 终态ACK清理任务
+    cleanup: Arc<AckCleanupJob>,
 }
 
 // 重新导出类型，方便外部使用
-pub use config::AckServiceConfig;
-pub use redis_manager::{AckStatus, AckStatusInfo, AckType, ImportanceLevel};
-pub use traits::{AckEvent, AckManager, AckTimeoutEvent};
+pub use archiver::AckArchiver;
+pub use cleanup::AckCleanupReport;
+pub use config::{
+    AckArchiveConfig, AckCleanupConfig, AckCompactionConfig, AckGroupAggregationConfig,
+    AckServiceConfig, AckStoreBackend, AckStoreConfig, AckTimelineConfig, AckTimeoutScanConfig,
+    AckWatchConfig, AckWritePipelineConfig,
+};
+pub use group_aggregator::{GroupAckKind, GroupAckOutcome, MessageAckSummary};
+pub use memory_store::MemoryAckStore;
+pub use redis_cluster_store::RedisClusterAckStore;
+pub use redis_manager::{
+    AckDeadlinePolicy, AckStatus, AckStatusInfo, AckSummary, AckTtlPolicy, AckType, ImportanceLevel,
+};
+pub use store::{AckStore, AckStoreResult};
+pub use timeline::{AckStage, AckTimeline};
+pub use traits::{
+    ACK_TENANT_METADATA_KEY, AckArchiveSink, AckEvent, AckManager, AckTimeoutEvent,
+    AckTimeoutHandler, GroupReadCompleteEvent, GroupReadCompleteHandler,
+};
+pub use watch::{AckStatusChange, AckWatchFilter, AckWatcher};
 
 impl AckModule {
     /// 创建新的ACK处理模块（精简版）
     ///
-    /// 不需要数据库连接，只使用 Redis 进行状态管理
+    /// 状态存储按 `store.backend` 选择（默认单机 Redis）；配置了 `archive.database_url` 时额外连接 Postgres 归档终态ACK
     pub async fn new(
         ack_config: crate::ack::config::AckServiceConfig,
     ) -> Result<Self, Box<dyn std::error::Error>> {
@@ -45,19 +84,49 @@ impl AckModule {
         let metrics = Arc::new(AckMetrics::new(&REGISTRY)?);
 
         // 创建ACK服务
-        let service = Arc::new(AckService::new(ack_config, metrics.clone()).await?);
+        let service = Arc::new(AckService::new(ack_config.clone(), metrics.clone()).await?);
 
-        // 获取Redis管理器引用
-        let redis_manager = service.redis_manager.clone();
+        // 获取状态存储引用
+        let store = service.store.clone();
+
+        // 可选的 Postgres 归档
+        let archive_store = match &ack_config.archive.database_url {
+            Some(database_url) => {
+                let pool = sqlx::postgres::PgPoolOptions::new()
+                    .max_connections(ack_config.archive.max_connections)
+                    .connect_lazy(database_url)?;
+                let store = Arc::new(PostgresAckArchiveSink::new(pool));
+                service.set_archive_sink(store.clone())?;
+                Some(store)
+            }
+            None => None,
+        };
+
+        // 终态ACK清理（未启用时仍可通过 run_ack_cleanup 手动触发）
+        let cleanup = Arc::new(AckCleanupJob::new(
+            store.clone(),
+            metrics.clone(),
+            ack_config.cleanup.clone(),
+        ));
+        if ack_config.cleanup.enabled {
+            cleanup.clone().start();
+        }
 
         Ok(Self {
             service,
-            redis_manager,
+            store,
             metrics, // 暴露 metrics 供外部使用
+            archive_store,
+            cleanup,
         })
     }
 
-    /// 记录ACK状态（简化版，不归档）
+    /// 立即执行一轮终态ACK清理，返回回收的键数等结果
+    pub async fn run_ack_cleanup(&self) -> AckStoreResult<AckCleanupReport> {
+        self.cleanup.run_once().await
+    }
+
+    /// 记录ACK状态（启用归档时终态ACK异步写入归档存储）
     pub async fn record_ack_status(
         &self,
         ack_info: AckStatusInfo,
@@ -65,6 +134,15 @@ impl AckModule {
         self.service.record_ack_internal(ack_info).await
     }
 
+    /// 记录ACK状态，并按租户记录ACK阶段耗时（未知租户时为 None）
+    pub async fn record_ack_status_in_tenant(
+        &self,
+        tenant_id: Option<&str>,
+        ack_info: AckStatusInfo,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.service.record_ack_in_tenant(tenant_id, ack_info).await
+    }
+
     /// 获取ACK状态
     pub async fn get_ack_status(
         &self,
@@ -74,6 +152,88 @@ impl AckModule {
         self.service.get_ack_status(message_id, user_id).await
     }
 
+    /// 注册ACK超时处理器（Pending 状态超过重要性级别的超时时间后回调）
+    pub async fn register_timeout_handler(&self, handler: Arc<dyn AckTimeoutHandler>) {
+        self.service.register_timeout_handler(handler).await;
+    }
+
+    /// 设置自定义ACK归档存储（未配置 Postgres 归档时使用，只能设置一次）
+    pub fn set_archive_sink(
+        &self,
+        sink: Arc<dyn AckArchiveSink>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.service.set_archive_sink(sink)
+    }
+
+    /// 查询 Postgres 中的归档ACK记录（未启用 Postgres 归档时返回错误）
+    pub async fn query_archived_acks(
+        &self,
+        query: &AckArchiveQuery,
+    ) -> Result<Vec<AckArchiveRecord>, Box<dyn std::error::Error>> {
+        let store = self
+            .archive_store
+            .as_ref()
+            .ok_or("ACK Postgres archive is not configured")?;
+        Ok(store.query_records(query).await?)
+    }
+
+    /// 获取 Postgres 归档统计信息（未启用 Postgres 归档时返回错误）
+    pub async fn get_archive_stats(&self) -> Result<ArchiveStats, Box<dyn std::error::Error>> {
+        let store = self
+            .archive_store
+            .as_ref()
+            .ok_or("ACK Postgres archive is not configured")?;
+        Ok(store.get_archive_stats().await?)
+    }
+
+    /// 获取消息级压缩摘要（消息的所有接收者进入终态后生成）
+    pub async fn get_ack_summary(
+        &self,
+        message_id: &str,
+    ) -> Result<Option<AckSummary>, Box<dyn std::error::Error>> {
+        self.store
+            .get_ack_summary(message_id)
+            .await
+            .map_err(|e| -> Box<dyn std::error::Error> { e })
+    }
+
+    /// 登记群聊消息的接收者人数（接收者不含发送者），之后的送达/已读ACK按用户去重累计
+    pub async fn register_group_message(
+        &self,
+        message_id: &str,
+        conversation_id: &str,
+        recipient_count: u64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.service
+            .register_group_message(message_id, conversation_id, recipient_count)
+            .await
+    }
+
+    /// 记录群聊消息的送达/已读（送达ACK经 `record_ack` 记录时会自动计入送达数）
+    pub async fn record_group_ack(
+        &self,
+        message_id: &str,
+        user_id: &str,
+        kind: GroupAckKind,
+    ) -> Result<GroupAckOutcome, Box<dyn std::error::Error>> {
+        self.service
+            .record_group_ack(message_id, user_id, kind)
+            .await
+    }
+
+    /// 获取群聊消息的送达/已读聚合摘要
+    pub async fn get_message_ack_summary(
+        &self,
+        message_id: &str,
+    ) -> Result<Option<MessageAckSummary>, Box<dyn std::error::Error>> {
+        self.service.get_message_ack_summary(message_id).await
+    }
+
+    /// 注册群聊消息全员已读处理器（每条消息只触发一次）
+    pub async fn register_group_read_handler(&self, handler: Arc<dyn GroupReadCompleteHandler>) {
+        self.service.register_group_read_handler(handler).await;
+    }
+
     /// 检查ACK是否存在
     pub async fn exists_ack(
         &self,
@@ -92,6 +252,29 @@ impl AckModule {
         self.service.delete_ack(message_id, user_id).await
     }
 
+    /// 订阅ACK状态变更（只接收订阅之后的变更；未启用订阅时返回错误）
+    pub fn watch_ack_status(
+        &self,
+        filter: AckWatchFilter,
+    ) -> Result<AckWatcher, Box<dyn std::error::Error>> {
+        self.service.watch_ack_status(filter)
+    }
+
+    /// 查询用户仍未确认的ACK（按时间戳升序，最多 `limit` 条，0 表示不限制）
+    pub async fn list_pending_acks(
+        &self,
+        user_id: &str,
+        limit: usize,
+        since: u64,
+    ) -> Result<Vec<crate::ack::redis_manager::AckStatusInfo>, Box<dyn std::error::Error>> {
+        self.service.list_pending_acks(user_id, limit, since).await
+    }
+
+    /// 获取消息+用户的ACK阶段时间线
+    pub fn get_ack_timeline(&self, message_id: &str, user_id: &str) -> Option<AckTimeline> {
+        self.service.get_ack_timeline(message_id, user_id)
+    }
+
     /// 获取模块统计信息
     pub async fn get_stats(&self) -> Result<AckModuleStats, Box<dyn std::error::Error>> {
         let service_stats = self.service.get_stats().await?;
@@ -137,6 +320,22 @@ impl AckManager for AckModule {
     ) -> Result<(), Box<dyn std::error::Error>> {
         self.service.delete_ack(message_id, user_id).await
     }
+
+    fn watch_ack_status(
+        &self,
+        filter: AckWatchFilter,
+    ) -> Result<AckWatcher, Box<dyn std::error::Error>> {
+        self.service.watch_ack_status(filter)
+    }
+
+    async fn list_pending_acks(
+        &self,
+        user_id: &str,
+        limit: usize,
+        since: u64,
+    ) -> Result<Vec<crate::ack::redis_manager::AckStatusInfo>, Box<dyn std::error::Error>> {
+        self.service.list_pending_acks(user_id, limit, since).await
+    }
 }
 
 /// ACK模块统计信息
@@ -153,8 +352,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_ack_module() -> Result<(), Box<dyn std::error::Error>> {
-        // 注意：这需要一个运行中的Redis实例
-        let ack_config = AckServiceConfig::default();
+        // 使用内存存储，无需 Redis
+        let mut ack_config = AckServiceConfig::default();
+        ack_config.store.backend = AckStoreBackend::Memory;
 
         let module = AckModule::new(ack_config).await?;
 
@@ -0,0 +1,414 @@
+//! ACK状态 Redis 集群存储
+//!
+//! 与单机实现使用相同的写入与压缩脚本，区别在于：
+//! - 同一消息的键使用哈希标签（`ack:{message_id}:user_id`），保证脚本访问的键落在同一槽位
+//! - 确认截止时间队列与用户待确认索引不在消息键的槽位，在脚本之后单独维护
+//! - 扫描与统计逐个主节点执行，`cluster_nodes` 需列出全部主节点
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use redis::cluster::ClusterClient;
+use redis::cluster_async::ClusterConnection;
+use redis::{AsyncCommands, Client, RedisError, RedisResult, Script};
+use tokio::sync::OnceCell;
+
+use crate::ack::group_aggregator::{
+    GroupAckAggregator, GroupAckKind, GroupAckOutcome, MessageAckSummary,
+};
+use crate::ack::redis_manager::{
+    AckDeadlinePolicy, AckStatusInfo, AckSummary, AckTtlPolicy, CLAIM_EXPIRED_DEADLINES_SCRIPT,
+    DEADLINES_KEY, DEFAULT_GROUP_TTL, ExpiredAckDeadline, MERGE_FINAL_ACKS_SCRIPT, RedisStats,
+    STORE_ACK_SCRIPT, deadline_member, parse_expired_deadlines, parse_memory_info,
+    parse_pending_acks, pending_index_key,
+};
+use crate::ack::store::{AckStore, AckStoreResult};
+
+/// Redis 集群ACK存储
+pub struct RedisClusterAckStore {
+    /// 集群客户端
+    client: ClusterClient,
+    /// 集群连接（首次使用时建立，之后复用）
+    connection: OnceCell<ClusterConnection>,
+    /// 各主节点的单机客户端（用于扫描与统计）
+    nodes: Vec<Client>,
+    /// 按重要性分级的过期时间
+    ttl_policy: AckTtlPolicy,
+    /// 是否启用消息级压缩
+    compaction_enabled: bool,
+    /// 确认超时时间（未设置时不跟踪截止时间）
+    deadline_policy: Option<AckDeadlinePolicy>,
+    /// 写入与压缩脚本
+    store_script: Script,
+    /// 群聊消息ACK聚合
+    group: GroupAckAggregator,
+}
+
+impl RedisClusterAckStore {
+    /// 创建集群存储（`nodes` 为全部主节点地址）
+    pub fn new(nodes: &[String], ttl_policy: AckTtlPolicy) -> RedisResult<Self> {
+        let client = ClusterClient::new(nodes.to_vec())?;
+        let nodes = nodes
+            .iter()
+            .map(|node| Client::open(node.as_str()))
+            .collect::<RedisResult<Vec<_>>>()?;
+        Ok(Self {
+            client,
+            connection: OnceCell::new(),
+            nodes,
+            ttl_policy,
+            compaction_enabled: true,
+            deadline_policy: None,
+            store_script: Script::new(STORE_ACK_SCRIPT),
+            group: GroupAckAggregator::new(DEFAULT_GROUP_TTL).with_hash_tag(),
+        })
+    }
+
+    /// 设置确认超时时间，Pending 状态的ACK会写入截止时间队列
+    pub fn with_deadline_policy(mut self, deadline_policy: AckDeadlinePolicy) -> Self {
+        self.deadline_policy = Some(deadline_policy);
+        self
+    }
+
+    /// 设置是否启用消息级压缩
+    pub fn with_compaction(mut self, enabled: bool) -> Self {
+        self.compaction_enabled = enabled;
+        self
+    }
+
+    /// 设置群聊消息ACK聚合记录的过期时间（秒）
+    pub fn with_group_ttl(mut self, ttl: u64) -> Self {
+        self.group = GroupAckAggregator::new(ttl).with_hash_tag();
+        self
+    }
+
+    async fn connection(&self) -> RedisResult<ClusterConnection> {
+        self.connection
+            .get_or_try_init(|| self.client.get_async_connection())
+            .await
+            .cloned()
+    }
+
+    async fn store_with_connection(
+        &self,
+        conn: &mut ClusterConnection,
+        ack_info: &AckStatusInfo,
+    ) -> RedisResult<bool> {
+        let value = serde_json::to_string(ack_info).map_err(|e| {
+            RedisError::from((
+                redis::ErrorKind::TypeError,
+                "JSON serialization error",
+                e.to_string(),
+            ))
+        })?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let compacted: i64 = self
+            .store_script
+            .key(ack_key(&ack_info.message_id, &ack_info.user_id))
+            .key(format!("ack_recipients:{{{}}}", ack_info.message_id))
+            .key(summary_key(&ack_info.message_id))
+            .arg(&ack_info.user_id)
+            .arg(value)
+            .arg(self.ttl_policy.ttl_for(&ack_info.importance))
+            .arg(ack_info.status.as_str())
+            .arg(ack_info.status.is_final() as u8)
+            .arg(self.compaction_enabled as u8)
+            .arg(self.ttl_policy.summary.max(1))
+            .arg(now)
+            .arg(format!("ack:{{{}}}:", ack_info.message_id))
+            .arg(ack_info.importance.as_str())
+            .arg(0)
+            .arg("")
+            .invoke_async(conn)
+            .await?;
+
+        // 截止时间队列单独维护（与消息键不在同一槽位）
+        let member = deadline_member(&ack_info.message_id, &ack_info.user_id);
+        if ack_info.status.is_final() {
+            if self.deadline_policy.is_some() {
+                let _: () = conn.zrem(DEADLINES_KEY, member).await?;
+            }
+        } else {
+            let deadline = self
+                .deadline_policy
+                .as_ref()
+                .map(|policy| policy.deadline_for(ack_info, now))
+                .unwrap_or(0);
+            if deadline > 0 {
+                let _: () = conn.zadd(DEADLINES_KEY, member, deadline).await?;
+            }
+        }
+
+        // 用户待确认索引单独维护（与消息键不在同一槽位）
+        let index_key = pending_index_key(&ack_info.user_id);
+        if ack_info.status.is_final() {
+            let _: () = conn.zrem(&index_key, &ack_info.message_id).await?;
+        } else {
+            let _: () = conn
+                .zadd(&index_key, &ack_info.message_id, ack_info.timestamp)
+                .await?;
+            let ttl = self.ttl_policy.ttl_for(&ack_info.importance);
+            let remaining: i64 = conn.ttl(&index_key).await?;
+            if remaining < ttl as i64 {
+                let _: () = conn.expire(&index_key, ttl as i64).await?;
+            }
+        }
+        Ok(compacted == 1)
+    }
+}
+
+#[async_trait]
+impl AckStore for RedisClusterAckStore {
+    fn ttl_policy(&self) -> &AckTtlPolicy {
+        &self.ttl_policy
+    }
+
+    async fn store_ack_status(&self, ack_info: &AckStatusInfo) -> AckStoreResult<bool> {
+        let mut conn = self.connection().await?;
+        Ok(self.store_with_connection(&mut conn, ack_info).await?)
+    }
+
+    async fn batch_store_ack_status(&self, ack_infos: &[AckStatusInfo]) -> AckStoreResult<usize> {
+        let mut conn = self.connection().await?;
+        let mut compacted = 0;
+        for ack_info in ack_infos {
+            if self.store_with_connection(&mut conn, ack_info).await? {
+                compacted += 1;
+            }
+        }
+        Ok(compacted)
+    }
+
+    async fn get_ack_status(
+        &self,
+        message_id: &str,
+        user_id: &str,
+    ) -> AckStoreResult<Option<AckStatusInfo>> {
+        let mut conn = self.connection().await?;
+        let value: Option<String> = conn.get(ack_key(message_id, user_id)).await?;
+        match value {
+            Some(data) => Ok(Some(serde_json::from_str(&data)?)),
+            None => Ok(self
+                .get_ack_summary(message_id)
+                .await?
+                .map(|summary| summary.status_info_for(user_id))),
+        }
+    }
+
+    async fn get_ack_summary(&self, message_id: &str) -> AckStoreResult<Option<AckSummary>> {
+        let mut conn = self.connection().await?;
+        let fields: HashMap<String, String> = conn.hgetall(summary_key(message_id)).await?;
+        Ok(AckSummary::from_fields(message_id, fields))
+    }
+
+    async fn delete_ack_status(&self, message_id: &str, user_id: &str) -> AckStoreResult<()> {
+        let mut conn = self.connection().await?;
+        let _: () = conn.del(ack_key(message_id, user_id)).await?;
+        let _: () = conn
+            .zrem(DEADLINES_KEY, deadline_member(message_id, user_id))
+            .await?;
+        let _: () = conn.zrem(pending_index_key(user_id), message_id).await?;
+        Ok(())
+    }
+
+    async fn exists_ack(&self, message_id: &str, user_id: &str) -> AckStoreResult<bool> {
+        let mut conn = self.connection().await?;
+        let exists: bool = conn.exists(ack_key(message_id, user_id)).await?;
+        if exists {
+            return Ok(true);
+        }
+        Ok(conn.exists(summary_key(message_id)).await?)
+    }
+
+    async fn claim_expired_deadlines(
+        &self,
+        now: u64,
+        limit: usize,
+    ) -> AckStoreResult<Vec<ExpiredAckDeadline>> {
+        let mut conn = self.connection().await?;
+        let entries: Vec<(String, f64)> = Script::new(CLAIM_EXPIRED_DEADLINES_SCRIPT)
+            .key(DEADLINES_KEY)
+            .arg(now)
+            .arg(limit)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(parse_expired_deadlines(entries))
+    }
+
+    async fn scan_ack_statuses(
+        &self,
+        max_records: usize,
+        batch_size: usize,
+    ) -> AckStoreResult<Vec<AckStatusInfo>> {
+        let batch_size = batch_size.max(1);
+        let mut ack_infos = Vec::new();
+        // 扫描到的键位于该主节点，直接在同一节点上批量读取
+        for node in &self.nodes {
+            let mut conn = node.get_multiplexed_async_connection().await?;
+            let mut cursor: u64 = 0;
+            loop {
+                let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                    .arg(cursor)
+                    .arg("MATCH")
+                    .arg("ack:*:*")
+                    .arg("COUNT")
+                    .arg(batch_size)
+                    .query_async(&mut conn)
+                    .await?;
+                cursor = next;
+
+                if !keys.is_empty() {
+                    let mut pipe = redis::pipe();
+                    for key in &keys {
+                        pipe.cmd("GET").arg(key);
+                    }
+                    let values: Vec<Option<String>> = pipe.query_async(&mut conn).await?;
+                    for data in values.into_iter().flatten() {
+                        match serde_json::from_str::<AckStatusInfo>(&data) {
+                            Ok(ack_info) => ack_infos.push(ack_info),
+                            Err(e) => {
+                                tracing::warn!(error = %e, "Failed to deserialize ACK status from Redis");
+                            }
+                        }
+                    }
+                }
+
+                if max_records > 0 && ack_infos.len() >= max_records {
+                    ack_infos.truncate(max_records);
+                    return Ok(ack_infos);
+                }
+                if cursor == 0 {
+                    break;
+                }
+            }
+        }
+        Ok(ack_infos)
+    }
+
+    async fn list_pending_acks(
+        &self,
+        user_id: &str,
+        limit: usize,
+        since: u64,
+    ) -> AckStoreResult<Vec<AckStatusInfo>> {
+        let mut conn = self.connection().await?;
+        let index_key = pending_index_key(user_id);
+        let message_ids: Vec<String> = redis::cmd("ZRANGEBYSCORE")
+            .arg(&index_key)
+            .arg(since)
+            .arg("+inf")
+            .arg("LIMIT")
+            .arg(0)
+            .arg(if limit > 0 { limit as i64 } else { -1 })
+            .query_async(&mut conn)
+            .await?;
+        if message_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // ACK键分布在不同槽位，非原子管道按键路由
+        let mut pipe = redis::pipe();
+        for message_id in &message_ids {
+            pipe.cmd("GET").arg(ack_key(message_id, user_id));
+        }
+        let values: Vec<Option<String>> = pipe.query_async(&mut conn).await?;
+
+        let (pending, stale) = parse_pending_acks(message_ids, values);
+        if !stale.is_empty() {
+            let _: () = conn.zrem(&index_key, stale).await?;
+        }
+        Ok(pending)
+    }
+
+    async fn merge_final_acks(
+        &self,
+        message_id: &str,
+        user_ids: &[String],
+        now: u64,
+    ) -> AckStoreResult<usize> {
+        if user_ids.is_empty() {
+            return Ok(0);
+        }
+        let mut conn = self.connection().await?;
+        let script = Script::new(MERGE_FINAL_ACKS_SCRIPT);
+        let mut invocation = script.prepare_invoke();
+        invocation
+            .key(summary_key(message_id))
+            .key(format!("ack_recipients:{{{}}}", message_id))
+            .arg(format!("ack:{{{}}}:", message_id))
+            .arg(self.ttl_policy.summary.max(1))
+            .arg(now);
+        for user_id in user_ids {
+            invocation.arg(user_id);
+        }
+        let merged: usize = invocation.invoke_async(&mut conn).await?;
+        Ok(merged)
+    }
+
+    async fn register_group_message(
+        &self,
+        message_id: &str,
+        conversation_id: &str,
+        recipient_count: u64,
+    ) -> AckStoreResult<()> {
+        let mut conn = self.connection().await?;
+        Ok(self
+            .group
+            .register_message(&mut conn, message_id, conversation_id, recipient_count)
+            .await?)
+    }
+
+    async fn record_group_ack(
+        &self,
+        message_id: &str,
+        user_id: &str,
+        kind: GroupAckKind,
+        now: u64,
+    ) -> AckStoreResult<GroupAckOutcome> {
+        let mut conn = self.connection().await?;
+        Ok(self
+            .group
+            .record(&mut conn, message_id, user_id, kind, now)
+            .await?)
+    }
+
+    async fn get_group_summary(
+        &self,
+        message_id: &str,
+    ) -> AckStoreResult<Option<MessageAckSummary>> {
+        let mut conn = self.connection().await?;
+        Ok(self.group.get_summary(&mut conn, message_id).await?)
+    }
+
+    async fn get_stats(&self) -> AckStoreResult<RedisStats> {
+        let mut stats = RedisStats {
+            used_memory: 0,
+            used_memory_peak: 0,
+        };
+        for node in &self.nodes {
+            let mut conn = node.get_multiplexed_async_connection().await?;
+            let info: String = redis::cmd("INFO")
+                .arg("memory")
+                .query_async(&mut conn)
+                .await?;
+            let node_stats = parse_memory_info(&info);
+            stats.used_memory += node_stats.used_memory;
+            stats.used_memory_peak += node_stats.used_memory_peak;
+        }
+        Ok(stats)
+    }
+}
+
+/// 逐用户ACK键（哈希标签为消息ID）
+fn ack_key(message_id: &str, user_id: &str) -> String {
+    format!("ack:{{{}}}:{}", message_id, user_id)
+}
+
+/// 消息级压缩摘要键（哈希标签为消息ID）
+fn summary_key(message_id: &str) -> String {
+    format!("ack_summary:{{{}}}", message_id)
+}
@@ -1,9 +1,153 @@
 //! ACK状态Redis管理器
-//! 实现基于Redis的ACK状态暂存机制，用于支持ACK重传判断和状态查询
+//! 实现基于Redis的ACK状态暂存机制（`AckStore` 的单机 Redis 实现），用于支持ACK重传判断和状态查询
+//!
+//! 消息级压缩：记录为 Pending 的用户会登记为该消息的接收者，
+//! 所有接收者都进入终态后，逐用户的ACK键合并为一条摘要记录（仅保留各状态计数与失败用户），
+//! 摘要使用更长的过期时间，之后的状态查询回落到摘要。
+//!
+//! 写入通过 Redis 管道批量执行：批量存储一次往返写入整批ACK，
+//! 启用写入管道（`AckWritePipelineConfig`）时并发的逐条写入也经写入缓冲攒批后通过管道写入。
 
-use redis::{AsyncCommands, Client, RedisError, RedisResult};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use redis::{AsyncCommands, Client, Cmd, RedisError, RedisResult, Script};
 use serde::{Deserialize, Serialize};
 
+use crate::ack::config::AckWritePipelineConfig;
+use crate::ack::group_aggregator::{
+    GroupAckAggregator, GroupAckKind, GroupAckOutcome, MessageAckSummary,
+};
+use crate::ack::store::{AckStore, AckStoreResult};
+use crate::ack::write_buffer::AckWriteBuffer;
+
+/// 群聊消息ACK聚合记录的默认过期时间（秒）
+pub(crate) const DEFAULT_GROUP_TTL: u64 = 7 * 86400;
+
+/// 写入ACK状态并维护接收者登记表，所有接收者进入终态时压缩为摘要（返回1表示已压缩）
+///
+/// 同时维护确认截止时间队列与用户待确认索引：Pending 写入，终态移出
+/// （集群模式下两者与消息键不在同一槽位，不传第4、5个键，由调用方单独维护）
+///
+/// KEYS: ACK键、接收者登记表、摘要键、截止时间队列（可选）、用户待确认索引（可选）
+/// ARGV: 用户ID、ACK内容、TTL、状态、是否终态、是否启用压缩、摘要TTL、当前时间、ACK键前缀、重要性、
+///       截止时间（0表示不跟踪）、截止时间队列成员、消息ID、ACK时间戳
+pub(crate) const STORE_ACK_SCRIPT: &str = r#"
+redis.call('SET', KEYS[1], ARGV[2], 'EX', ARGV[3])
+if KEYS[4] then
+    if ARGV[5] == '1' then
+        redis.call('ZREM', KEYS[4], ARGV[12])
+    elseif tonumber(ARGV[11]) > 0 then
+        redis.call('ZADD', KEYS[4], ARGV[11], ARGV[12])
+    end
+end
+if KEYS[5] then
+    if ARGV[5] == '1' then
+        redis.call('ZREM', KEYS[5], ARGV[13])
+    else
+        redis.call('ZADD', KEYS[5], ARGV[14], ARGV[13])
+        if redis.call('TTL', KEYS[5]) < tonumber(ARGV[3]) then
+            redis.call('EXPIRE', KEYS[5], ARGV[3])
+        end
+    end
+end
+if ARGV[6] ~= '1' then
+    return 0
+end
+if ARGV[5] ~= '1' then
+    if redis.call('HSETNX', KEYS[2], ARGV[1], 'pending') == 1 then
+        redis.call('HINCRBY', KEYS[2], '__pending', 1)
+    end
+    if redis.call('TTL', KEYS[2]) < tonumber(ARGV[3]) then
+        redis.call('EXPIRE', KEYS[2], ARGV[3])
+    end
+    return 0
+end
+if redis.call('HGET', KEYS[2], ARGV[1]) ~= 'pending' then
+    return 0
+end
+redis.call('HSET', KEYS[2], ARGV[1], ARGV[4])
+if redis.call('HINCRBY', KEYS[2], '__pending', -1) > 0 then
+    return 0
+end
+local entries = redis.call('HGETALL', KEYS[2])
+for i = 1, #entries, 2 do
+    local user, status = entries[i], entries[i + 1]
+    if user ~= '__pending' then
+        redis.call('DEL', ARGV[9] .. user)
+        redis.call('HINCRBY', KEYS[3], 'total', 1)
+        redis.call('HINCRBY', KEYS[3], status, 1)
+        if status == 'failed' then
+            redis.call('HSET', KEYS[3], 'failed:' .. user, 1)
+        end
+    end
+end
+redis.call('HSET', KEYS[3], 'completed_at', ARGV[8], 'importance', ARGV[10])
+redis.call('EXPIRE', KEYS[3], ARGV[7])
+redis.call('DEL', KEYS[2])
+return 1
+"#;
+
+/// 将逐用户终态ACK合并到消息摘要并删除（返回删除的记录数）
+///
+/// 只合并仍为终态的记录；摘要已存在时累加计数，合并后登记表中只剩计数字段时删除登记表
+///
+/// KEYS: 摘要键、接收者登记表
+/// ARGV: ACK键前缀、摘要TTL、当前时间、用户ID...
+pub(crate) const MERGE_FINAL_ACKS_SCRIPT: &str = r#"
+local merged = 0
+local importance = nil
+for i = 4, #ARGV do
+    local user = ARGV[i]
+    local value = redis.call('GET', ARGV[1] .. user)
+    if value then
+        local ack = cjson.decode(value)
+        if ack['status'] ~= 'Pending' then
+            local status = string.lower(ack['status'])
+            importance = importance or string.lower(ack['importance'])
+            redis.call('DEL', ARGV[1] .. user)
+            redis.call('HINCRBY', KEYS[1], 'total', 1)
+            redis.call('HINCRBY', KEYS[1], status, 1)
+            if status == 'failed' then
+                redis.call('HSET', KEYS[1], 'failed:' .. user, 1)
+            end
+            if redis.call('HGET', KEYS[2], user) == 'pending' then
+                redis.call('HINCRBY', KEYS[2], '__pending', -1)
+            end
+            redis.call('HDEL', KEYS[2], user)
+            merged = merged + 1
+        end
+    end
+end
+if merged == 0 then
+    return 0
+end
+if redis.call('HEXISTS', KEYS[1], 'completed_at') == 0 then
+    redis.call('HSET', KEYS[1], 'completed_at', ARGV[3], 'importance', importance)
+end
+if redis.call('TTL', KEYS[1]) < tonumber(ARGV[2]) then
+    redis.call('EXPIRE', KEYS[1], ARGV[2])
+end
+if redis.call('HLEN', KEYS[2]) <= 1 then
+    redis.call('DEL', KEYS[2])
+end
+return merged
+"#;
+
+/// 原子地领取已过截止时间的ACK（领取后移出队列，多实例扫描不会重复）
+pub(crate) const CLAIM_EXPIRED_DEADLINES_SCRIPT: &str = r#"
+local entries = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[1], 'WITHSCORES', 'LIMIT', 0, ARGV[2])
+for i = 1, #entries, 2 do
+    redis.call('ZREM', KEYS[1], entries[i])
+end
+return entries
+"#;
+
+/// 确认截止时间队列（ZSET，score 为截止时间秒，成员为 `[message_id, user_id]`）
+pub(crate) const DEADLINES_KEY: &str = "ack_deadlines";
+
 /// ACK状态信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AckStatusInfo {
@@ -32,6 +176,20 @@ pub enum AckType {
     DeliveryAck,
     /// 存储 ACK
     StorageAck,
+    /// 已读 ACK
+    ReadAck,
+}
+
+impl AckType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AckType::TransportAck => "transport",
+            AckType::ServerAck => "server",
+            AckType::DeliveryAck => "delivery",
+            AckType::StorageAck => "storage",
+            AckType::ReadAck => "read",
+        }
+    }
 }
 
 /// ACK状态枚举
@@ -47,6 +205,22 @@ pub enum AckStatus {
     Failed,
 }
 
+impl AckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AckStatus::Pending => "pending",
+            AckStatus::Received => "received",
+            AckStatus::Processed => "processed",
+            AckStatus::Failed => "failed",
+        }
+    }
+
+    /// 是否为终态（除 Pending 外均不再需要重传判断）
+    pub fn is_final(&self) -> bool {
+        !matches!(self, AckStatus::Pending)
+    }
+}
+
 /// 重要性等级
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ImportanceLevel {
@@ -58,12 +232,274 @@ pub enum ImportanceLevel {
     High = 3,
 }
 
+impl ImportanceLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportanceLevel::Low => "low",
+            ImportanceLevel::Medium => "medium",
+            ImportanceLevel::High => "high",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "low" => Some(ImportanceLevel::Low),
+            "medium" => Some(ImportanceLevel::Medium),
+            "high" => Some(ImportanceLevel::High),
+            _ => None,
+        }
+    }
+}
+
+/// 按重要性分级的过期时间（秒）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AckTtlPolicy {
+    /// 高重要性ACK过期时间
+    pub high: u64,
+    /// 中等重要性ACK过期时间
+    pub medium: u64,
+    /// 低重要性ACK过期时间
+    pub low: u64,
+    /// 压缩摘要过期时间
+    pub summary: u64,
+}
+
+impl AckTtlPolicy {
+    /// 由默认过期时间推导（中等为1/2，低为1/4，摘要为4倍）
+    pub fn from_default_ttl(default_ttl: u64) -> Self {
+        Self {
+            high: default_ttl,
+            medium: default_ttl / 2,
+            low: default_ttl / 4,
+            summary: default_ttl.saturating_mul(4),
+        }
+    }
+
+    pub fn ttl_for(&self, importance: &ImportanceLevel) -> u64 {
+        match importance {
+            ImportanceLevel::Low => self.low,
+            ImportanceLevel::Medium => self.medium,
+            ImportanceLevel::High => self.high,
+        }
+        .max(1)
+    }
+}
+
+/// 按重要性分级的确认超时时间（秒，0表示不跟踪）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AckDeadlinePolicy {
+    /// 高重要性ACK超时时间
+    pub high: u64,
+    /// 中等重要性ACK超时时间
+    pub medium: u64,
+    /// 低重要性ACK超时时间
+    pub low: u64,
+}
+
+impl AckDeadlinePolicy {
+    pub fn timeout_for(&self, importance: &ImportanceLevel) -> u64 {
+        match importance {
+            ImportanceLevel::Low => self.low,
+            ImportanceLevel::Medium => self.medium,
+            ImportanceLevel::High => self.high,
+        }
+    }
+
+    /// Pending 状态ACK的截止时间（0表示不跟踪）
+    pub fn deadline_for(&self, ack_info: &AckStatusInfo, now: u64) -> u64 {
+        if ack_info.status.is_final() {
+            return 0;
+        }
+        match self.timeout_for(&ack_info.importance) {
+            0 => 0,
+            timeout => now + timeout,
+        }
+    }
+}
+
+/// 已过截止时间的待确认ACK
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpiredAckDeadline {
+    pub message_id: String,
+    pub user_id: String,
+    /// 截止时间（秒）
+    pub deadline: u64,
+}
+
+/// 消息级ACK压缩摘要
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AckSummary {
+    /// 消息ID
+    pub message_id: String,
+    /// 接收者总数
+    pub total: u64,
+    /// 各终态的用户数
+    pub status_counts: HashMap<String, u64>,
+    /// 失败的用户
+    pub failed_users: Vec<String>,
+    /// 压缩时间（秒）
+    pub completed_at: u64,
+    /// 重要性等级
+    pub importance: ImportanceLevel,
+}
+
+impl AckSummary {
+    /// 摘要中某个用户的状态（失败用户单独记录，其余用户按成功终态返回）
+    pub fn status_for(&self, user_id: &str) -> AckStatus {
+        if self.failed_users.iter().any(|failed| failed == user_id) {
+            AckStatus::Failed
+        } else if self.status_counts.get("received").copied().unwrap_or(0) > 0 {
+            AckStatus::Received
+        } else {
+            AckStatus::Processed
+        }
+    }
+
+    /// 由摘要推导某个用户的ACK状态（逐用户记录已压缩时的查询回落）
+    pub(crate) fn status_info_for(&self, user_id: &str) -> AckStatusInfo {
+        AckStatusInfo {
+            message_id: self.message_id.clone(),
+            user_id: user_id.to_string(),
+            ack_type: None,
+            status: self.status_for(user_id),
+            timestamp: self.completed_at,
+            importance: self.importance.clone(),
+        }
+    }
+
+    pub(crate) fn from_fields(message_id: &str, fields: HashMap<String, String>) -> Option<Self> {
+        let completed_at = fields.get("completed_at")?.parse().ok()?;
+        let mut summary = Self {
+            message_id: message_id.to_string(),
+            total: 0,
+            status_counts: HashMap::new(),
+            failed_users: Vec::new(),
+            completed_at,
+            importance: fields
+                .get("importance")
+                .and_then(|value| ImportanceLevel::parse(value))
+                .unwrap_or(ImportanceLevel::Medium),
+        };
+        for (field, value) in fields {
+            if let Some(user_id) = field.strip_prefix("failed:") {
+                summary.failed_users.push(user_id.to_string());
+            } else if field == "total" {
+                summary.total = value.parse().unwrap_or(0);
+            } else if field != "completed_at" && field != "importance" {
+                summary
+                    .status_counts
+                    .insert(field, value.parse().unwrap_or(0));
+            }
+        }
+        summary.failed_users.sort();
+        Some(summary)
+    }
+}
+
+/// Redis ACK写入器（单条写入、批量写入与写入缓冲共用）
+///
+/// 写入脚本通过 `EVALSHA` 加入管道，Redis 未缓存脚本时加载后重试一次
+#[derive(Clone)]
+pub(crate) struct RedisAckWriter {
+    /// 按重要性分级的过期时间
+    ttl_policy: AckTtlPolicy,
+    /// 是否启用消息级压缩
+    compaction_enabled: bool,
+    /// 确认超时时间（未设置时不跟踪截止时间）
+    deadline_policy: Option<AckDeadlinePolicy>,
+    /// 写入与压缩脚本
+    store_script: Script,
+}
+
+impl RedisAckWriter {
+    /// 通过一个管道写入一批ACK状态（返回各条写入是否完成压缩）
+    pub(crate) async fn store_batch(
+        &self,
+        conn: &mut MultiplexedConnection,
+        ack_infos: &[AckStatusInfo],
+    ) -> RedisResult<Vec<bool>> {
+        if ack_infos.is_empty() {
+            return Ok(Vec::new());
+        }
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut pipe = redis::pipe();
+        for ack_info in ack_infos {
+            pipe.add_command(self.store_command(ack_info, now)?);
+        }
+        let compacted: Vec<i64> = match pipe.query_async(conn).await {
+            Err(e) if e.kind() == redis::ErrorKind::NoScriptError => {
+                let _: String = redis::cmd("SCRIPT")
+                    .arg("LOAD")
+                    .arg(STORE_ACK_SCRIPT)
+                    .query_async(conn)
+                    .await?;
+                pipe.query_async(conn).await?
+            }
+            result => result?,
+        };
+        Ok(compacted.into_iter().map(|value| value == 1).collect())
+    }
+
+    /// 单条ACK的写入命令
+    fn store_command(&self, ack_info: &AckStatusInfo, now: u64) -> RedisResult<Cmd> {
+        let value = serde_json::to_string(ack_info).map_err(|e| {
+            RedisError::from((
+                redis::ErrorKind::TypeError,
+                "JSON serialization error",
+                e.to_string(),
+            ))
+        })?;
+
+        let mut cmd = redis::cmd("EVALSHA");
+        cmd.arg(self.store_script.get_hash())
+            .arg(5)
+            .arg(format_key(&ack_info.message_id, &ack_info.user_id))
+            .arg(recipients_key(&ack_info.message_id))
+            .arg(summary_key(&ack_info.message_id))
+            .arg(DEADLINES_KEY)
+            .arg(pending_index_key(&ack_info.user_id))
+            .arg(&ack_info.user_id)
+            .arg(value)
+            .arg(self.ttl_policy.ttl_for(&ack_info.importance))
+            .arg(ack_info.status.as_str())
+            .arg(ack_info.status.is_final() as u8)
+            .arg(self.compaction_enabled as u8)
+            .arg(self.ttl_policy.summary.max(1))
+            .arg(now)
+            .arg(format!("ack:{}:", ack_info.message_id))
+            .arg(ack_info.importance.as_str())
+            .arg(self.deadline_for(ack_info, now))
+            .arg(deadline_member(&ack_info.message_id, &ack_info.user_id))
+            .arg(&ack_info.message_id)
+            .arg(ack_info.timestamp);
+        Ok(cmd)
+    }
+
+    /// Pending 状态ACK的截止时间（0表示不跟踪）
+    fn deadline_for(&self, ack_info: &AckStatusInfo, now: u64) -> u64 {
+        self.deadline_policy
+            .as_ref()
+            .map(|policy| policy.deadline_for(ack_info, now))
+            .unwrap_or(0)
+    }
+}
+
 /// Redis ACK管理器
 pub struct RedisAckManager {
     /// Redis客户端
     pub client: Client,
-    /// 默认过期时间（秒）
-    default_ttl: u64,
+    /// ACK写入器
+    writer: RedisAckWriter,
+    /// 写入管道配置（未设置时逐条直接写入）
+    write_pipeline: Option<AckWritePipelineConfig>,
+    /// 写入缓冲（首次写入时启动后台管道写入任务）
+    write_buffer: OnceLock<AckWriteBuffer>,
+    /// 群聊消息ACK聚合
+    group: GroupAckAggregator,
 }
 
 impl RedisAckManager {
@@ -72,31 +508,93 @@ impl RedisAckManager {
         let client = Client::open(redis_url)?;
         Ok(Self {
             client,
-            default_ttl,
+            writer: RedisAckWriter {
+                ttl_policy: AckTtlPolicy::from_default_ttl(default_ttl),
+                compaction_enabled: true,
+                deadline_policy: None,
+                store_script: Script::new(STORE_ACK_SCRIPT),
+            },
+            write_pipeline: None,
+            write_buffer: OnceLock::new(),
+            group: GroupAckAggregator::new(DEFAULT_GROUP_TTL),
         })
     }
 
-    /// 存储ACK状态
-    pub async fn store_ack_status(&self, ack_info: &AckStatusInfo) -> RedisResult<()> {
+    /// 设置群聊消息ACK聚合记录的过期时间（秒）
+    pub fn with_group_ttl(mut self, ttl: u64) -> Self {
+        self.group = GroupAckAggregator::new(ttl);
+        self
+    }
+
+    /// 设置确认超时时间，Pending 状态的ACK会写入截止时间队列
+    pub fn with_deadline_policy(mut self, deadline_policy: AckDeadlinePolicy) -> Self {
+        self.writer.deadline_policy = Some(deadline_policy);
+        self
+    }
+
+    /// 设置分级过期时间
+    pub fn with_ttl_policy(mut self, ttl_policy: AckTtlPolicy) -> Self {
+        self.writer.ttl_policy = ttl_policy;
+        self
+    }
+
+    /// 设置是否启用消息级压缩
+    pub fn with_compaction(mut self, enabled: bool) -> Self {
+        self.writer.compaction_enabled = enabled;
+        self
+    }
+
+    /// 启用写入管道：逐条写入先进入缓冲，攒批后通过 Redis 管道一次写入
+    pub fn with_write_pipeline(mut self, config: AckWritePipelineConfig) -> Self {
+        self.write_pipeline = config.enabled.then_some(config);
+        self
+    }
+
+    pub fn ttl_policy(&self) -> &AckTtlPolicy {
+        &self.writer.ttl_policy
+    }
+
+    /// 存储ACK状态（返回该消息是否因本次写入完成压缩）
+    ///
+    /// 启用写入管道时进入写入缓冲等待批量写入的结果，缓冲已满时直接写入
+    pub async fn store_ack_status(&self, ack_info: &AckStatusInfo) -> RedisResult<bool> {
+        if let Some(config) = &self.write_pipeline {
+            let buffer = self.write_buffer.get_or_init(|| {
+                AckWriteBuffer::start(self.client.clone(), self.writer.clone(), config)
+            });
+            if let Some(result) = buffer.submit(ack_info.clone()) {
+                return result.await.unwrap_or_else(|_| {
+                    Err(RedisError::from((
+                        redis::ErrorKind::IoError,
+                        "ACK write buffer closed",
+                    )))
+                });
+            }
+        }
+
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let key = self.format_key(&ack_info.message_id, &ack_info.user_id);
-        let value = serde_json::to_string(ack_info).map_err(|e| {
-            RedisError::from((
-                redis::ErrorKind::TypeError,
-                "JSON serialization error",
-                e.to_string(),
-            ))
-        })?;
+        let compacted = self
+            .writer
+            .store_batch(&mut conn, std::slice::from_ref(ack_info))
+            .await?;
+        Ok(compacted.first().copied().unwrap_or(false))
+    }
 
-        // 根据重要性等级设置不同的过期时间
-        let ttl = match ack_info.importance {
-            ImportanceLevel::Low => self.default_ttl / 4, // 低重要性，较短过期时间
-            ImportanceLevel::Medium => self.default_ttl / 2, // 中等重要性，中等过期时间
-            ImportanceLevel::High => self.default_ttl,    // 高重要性，较长过期时间
-        };
+    /// 领取截止时间不晚于 `now` 的待确认ACK（最多 `limit` 条）
+    pub async fn claim_expired_deadlines(
+        &self,
+        now: u64,
+        limit: usize,
+    ) -> RedisResult<Vec<ExpiredAckDeadline>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let entries: Vec<(String, f64)> = Script::new(CLAIM_EXPIRED_DEADLINES_SCRIPT)
+            .key(DEADLINES_KEY)
+            .arg(now)
+            .arg(limit)
+            .invoke_async(&mut conn)
+            .await?;
 
-        let _: () = conn.set_ex(&key, value, ttl).await?;
-        Ok(())
+        Ok(parse_expired_deadlines(entries))
     }
 
     /// 获取ACK状态
@@ -106,7 +604,7 @@ impl RedisAckManager {
         user_id: &str,
     ) -> RedisResult<Option<AckStatusInfo>> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let key = self.format_key(message_id, user_id);
+        let key = format_key(message_id, user_id);
         let value: Option<String> = conn.get(&key).await?;
 
         match value {
@@ -120,58 +618,86 @@ impl RedisAckManager {
                 })?;
                 Ok(Some(ack_info))
             }
-            None => Ok(None),
+            None => Ok(self
+                .get_ack_summary(message_id)
+                .await?
+                .map(|summary| summary.status_info_for(user_id))),
         }
     }
 
-    /// 批量存储ACK状态
-    pub async fn batch_store_ack_status(&self, ack_infos: &[AckStatusInfo]) -> RedisResult<()> {
+    /// 获取消息级压缩摘要
+    pub async fn get_ack_summary(&self, message_id: &str) -> RedisResult<Option<AckSummary>> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let mut pipe = redis::pipe();
-
-        for ack_info in ack_infos {
-            let key = self.format_key(&ack_info.message_id, &ack_info.user_id);
-            let value = serde_json::to_string(ack_info).map_err(|e| {
-                RedisError::from((
-                    redis::ErrorKind::TypeError,
-                    "JSON serialization error",
-                    e.to_string(),
-                ))
-            })?;
-
-            // 根据重要性等级设置不同的过期时间
-            let ttl = match ack_info.importance {
-                ImportanceLevel::Low => self.default_ttl / 4,
-                ImportanceLevel::Medium => self.default_ttl / 2,
-                ImportanceLevel::High => self.default_ttl,
-            };
-
-            pipe.cmd("SETEX").arg(&key).arg(ttl).arg(value);
-        }
+        let fields: HashMap<String, String> = conn.hgetall(summary_key(message_id)).await?;
+        Ok(AckSummary::from_fields(message_id, fields))
+    }
 
-        let _: () = pipe.query_async(&mut conn).await?;
-        Ok(())
+    /// 批量存储ACK状态（通过一个管道写入，返回完成压缩的消息数）
+    pub async fn batch_store_ack_status(&self, ack_infos: &[AckStatusInfo]) -> RedisResult<usize> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let compacted = self.writer.store_batch(&mut conn, ack_infos).await?;
+        Ok(compacted.into_iter().filter(|compacted| *compacted).count())
     }
 
     /// 删除ACK状态
     pub async fn delete_ack_status(&self, message_id: &str, user_id: &str) -> RedisResult<()> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let key = self.format_key(message_id, user_id);
+        let key = format_key(message_id, user_id);
         let _: () = conn.del(&key).await?;
+        let _: () = conn
+            .zrem(DEADLINES_KEY, deadline_member(message_id, user_id))
+            .await?;
+        let _: () = conn.zrem(pending_index_key(user_id), message_id).await?;
         Ok(())
     }
 
+    /// 查询用户仍处于 Pending 状态的ACK（按时间戳升序，最多 `limit` 条，0 表示不限制）
+    ///
+    /// 索引中已过期、已压缩或已进入终态的成员在查询时惰性清理，因此返回条数可能少于 `limit`
+    pub async fn list_pending_acks(
+        &self,
+        user_id: &str,
+        limit: usize,
+        since: u64,
+    ) -> RedisResult<Vec<AckStatusInfo>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let index_key = pending_index_key(user_id);
+        let message_ids: Vec<String> = redis::cmd("ZRANGEBYSCORE")
+            .arg(&index_key)
+            .arg(since)
+            .arg("+inf")
+            .arg("LIMIT")
+            .arg(0)
+            .arg(if limit > 0 { limit as i64 } else { -1 })
+            .query_async(&mut conn)
+            .await?;
+        if message_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for message_id in &message_ids {
+            pipe.cmd("GET").arg(format_key(message_id, user_id));
+        }
+        let values: Vec<Option<String>> = pipe.query_async(&mut conn).await?;
+
+        let (pending, stale) = parse_pending_acks(message_ids, values);
+        if !stale.is_empty() {
+            let _: () = conn.zrem(&index_key, stale).await?;
+        }
+        Ok(pending)
+    }
+
     /// 检查ACK是否存在
     pub async fn exists_ack(&self, message_id: &str, user_id: &str) -> RedisResult<bool> {
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let key = self.format_key(message_id, user_id);
+        let key = format_key(message_id, user_id);
         let exists: bool = conn.exists(&key).await?;
-        Ok(exists)
-    }
-
-    /// 格式化Redis键
-    fn format_key(&self, message_id: &str, user_id: &str) -> String {
-        format!("ack:{}:{}", message_id, user_id)
+        if exists {
+            return Ok(true);
+        }
+        // 已压缩的消息：摘要存在即视为已确认
+        Ok(conn.exists(summary_key(message_id)).await?)
     }
 
     /// 扫描所有 ACK keys（使用 SCAN 命令，避免阻塞）
@@ -278,30 +804,217 @@ impl RedisAckManager {
             .query_async(&mut conn)
             .await?;
 
-        // 解析Redis内存信息
-        let mut used_memory = 0u64;
-        let mut used_memory_peak = 0u64;
+        Ok(parse_memory_info(&info))
+    }
+}
 
-        for line in info.lines() {
-            if line.starts_with("used_memory:") {
-                if let Some(value) = line.split(':').nth(1) {
-                    used_memory = value.parse().unwrap_or(0);
-                }
-            } else if line.starts_with("used_memory_peak:") {
-                if let Some(value) = line.split(':').nth(1) {
-                    used_memory_peak = value.parse().unwrap_or(0);
-                }
-            }
+#[async_trait]
+impl AckStore for RedisAckManager {
+    fn ttl_policy(&self) -> &AckTtlPolicy {
+        &self.writer.ttl_policy
+    }
+
+    async fn store_ack_status(&self, ack_info: &AckStatusInfo) -> AckStoreResult<bool> {
+        Ok(RedisAckManager::store_ack_status(self, ack_info).await?)
+    }
+
+    async fn batch_store_ack_status(&self, ack_infos: &[AckStatusInfo]) -> AckStoreResult<usize> {
+        Ok(RedisAckManager::batch_store_ack_status(self, ack_infos).await?)
+    }
+
+    async fn get_ack_status(
+        &self,
+        message_id: &str,
+        user_id: &str,
+    ) -> AckStoreResult<Option<AckStatusInfo>> {
+        Ok(RedisAckManager::get_ack_status(self, message_id, user_id).await?)
+    }
+
+    async fn get_ack_summary(&self, message_id: &str) -> AckStoreResult<Option<AckSummary>> {
+        Ok(RedisAckManager::get_ack_summary(self, message_id).await?)
+    }
+
+    async fn delete_ack_status(&self, message_id: &str, user_id: &str) -> AckStoreResult<()> {
+        Ok(RedisAckManager::delete_ack_status(self, message_id, user_id).await?)
+    }
+
+    async fn exists_ack(&self, message_id: &str, user_id: &str) -> AckStoreResult<bool> {
+        Ok(RedisAckManager::exists_ack(self, message_id, user_id).await?)
+    }
+
+    async fn claim_expired_deadlines(
+        &self,
+        now: u64,
+        limit: usize,
+    ) -> AckStoreResult<Vec<ExpiredAckDeadline>> {
+        Ok(RedisAckManager::claim_expired_deadlines(self, now, limit).await?)
+    }
+
+    async fn scan_ack_statuses(
+        &self,
+        max_records: usize,
+        batch_size: usize,
+    ) -> AckStoreResult<Vec<AckStatusInfo>> {
+        let keys = self
+            .scan_all_ack_keys(Some(max_records), batch_size.max(1))
+            .await?;
+        Ok(self
+            .batch_get_ack_status_from_keys(&keys, batch_size.max(1))
+            .await?)
+    }
+
+    async fn list_pending_acks(
+        &self,
+        user_id: &str,
+        limit: usize,
+        since: u64,
+    ) -> AckStoreResult<Vec<AckStatusInfo>> {
+        Ok(RedisAckManager::list_pending_acks(self, user_id, limit, since).await?)
+    }
+
+    async fn merge_final_acks(
+        &self,
+        message_id: &str,
+        user_ids: &[String],
+        now: u64,
+    ) -> AckStoreResult<usize> {
+        if user_ids.is_empty() {
+            return Ok(0);
         }
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let script = Script::new(MERGE_FINAL_ACKS_SCRIPT);
+        let mut invocation = script.prepare_invoke();
+        invocation
+            .key(summary_key(message_id))
+            .key(recipients_key(message_id))
+            .arg(format!("ack:{}:", message_id))
+            .arg(self.writer.ttl_policy.summary.max(1))
+            .arg(now);
+        for user_id in user_ids {
+            invocation.arg(user_id);
+        }
+        let merged: usize = invocation.invoke_async(&mut conn).await?;
+        Ok(merged)
+    }
 
-        Ok(RedisStats {
-            used_memory,
-            used_memory_peak,
-        })
+    async fn register_group_message(
+        &self,
+        message_id: &str,
+        conversation_id: &str,
+        recipient_count: u64,
+    ) -> AckStoreResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        Ok(self
+            .group
+            .register_message(&mut conn, message_id, conversation_id, recipient_count)
+            .await?)
+    }
+
+    async fn record_group_ack(
+        &self,
+        message_id: &str,
+        user_id: &str,
+        kind: GroupAckKind,
+        now: u64,
+    ) -> AckStoreResult<GroupAckOutcome> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        Ok(self
+            .group
+            .record(&mut conn, message_id, user_id, kind, now)
+            .await?)
+    }
+
+    async fn get_group_summary(
+        &self,
+        message_id: &str,
+    ) -> AckStoreResult<Option<MessageAckSummary>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        Ok(self.group.get_summary(&mut conn, message_id).await?)
+    }
+
+    async fn get_stats(&self) -> AckStoreResult<RedisStats> {
+        Ok(RedisAckManager::get_stats(self).await?)
+    }
+}
+
+/// 格式化Redis键
+fn format_key(message_id: &str, user_id: &str) -> String {
+    format!("ack:{}:{}", message_id, user_id)
+}
+
+/// 消息接收者登记表键（不匹配 `ack:*:*`，不会被超时扫描命中）
+fn recipients_key(message_id: &str) -> String {
+    format!("ack_recipients:{}", message_id)
+}
+
+/// 消息级压缩摘要键
+fn summary_key(message_id: &str) -> String {
+    format!("ack_summary:{}", message_id)
+}
+
+/// 截止时间队列成员（JSON 数组，避免ID中的分隔符产生歧义）
+pub(crate) fn deadline_member(message_id: &str, user_id: &str) -> String {
+    serde_json::to_string(&(message_id, user_id)).unwrap_or_default()
+}
+
+/// 用户待确认索引键（ZSET，score 为ACK时间戳，成员为 message_id）
+pub(crate) fn pending_index_key(user_id: &str) -> String {
+    format!("ack_pending:{}", user_id)
+}
+
+/// 按索引顺序拆分待确认ACK与需清理的索引成员（记录不存在、无法解析或已进入终态）
+pub(crate) fn parse_pending_acks(
+    message_ids: Vec<String>,
+    values: Vec<Option<String>>,
+) -> (Vec<AckStatusInfo>, Vec<String>) {
+    let mut pending = Vec::new();
+    let mut stale = Vec::new();
+    for (message_id, value) in message_ids.into_iter().zip(values) {
+        match value.and_then(|data| serde_json::from_str::<AckStatusInfo>(&data).ok()) {
+            Some(ack_info) if ack_info.status == AckStatus::Pending => pending.push(ack_info),
+            _ => stale.push(message_id),
+        }
+    }
+    (pending, stale)
+}
+
+/// 解析领取到的截止时间队列成员
+pub(crate) fn parse_expired_deadlines(entries: Vec<(String, f64)>) -> Vec<ExpiredAckDeadline> {
+    entries
+        .into_iter()
+        .filter_map(
+            |(member, deadline)| match serde_json::from_str::<(String, String)>(&member) {
+                Ok((message_id, user_id)) => Some(ExpiredAckDeadline {
+                    message_id,
+                    user_id,
+                    deadline: deadline as u64,
+                }),
+                Err(e) => {
+                    tracing::warn!(error = %e, member = %member, "Invalid ACK deadline member");
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// 从 `INFO memory` 输出中解析内存使用
+pub(crate) fn parse_memory_info(info: &str) -> RedisStats {
+    let mut stats = RedisStats {
+        used_memory: 0,
+        used_memory_peak: 0,
+    };
+    for line in info.lines() {
+        if let Some(value) = line.strip_prefix("used_memory:") {
+            stats.used_memory = value.trim().parse().unwrap_or(0);
+        } else if let Some(value) = line.strip_prefix("used_memory_peak:") {
+            stats.used_memory_peak = value.trim().parse().unwrap_or(0);
+        }
     }
+    stats
 }
 
-/// Redis统计信息
+/// 存储统计信息（内存存储不统计，均为0）
 #[derive(Debug, Clone)]
 pub struct RedisStats {
     /// 已使用内存（字节）
@@ -315,6 +1028,68 @@ mod tests {
     use super::*;
     use tokio;
 
+    #[test]
+    fn test_ttl_policy_tiers_by_importance() {
+        let policy = AckTtlPolicy::from_default_ttl(3600);
+        assert_eq!(policy.ttl_for(&ImportanceLevel::High), 3600);
+        assert_eq!(policy.ttl_for(&ImportanceLevel::Medium), 1800);
+        assert_eq!(policy.ttl_for(&ImportanceLevel::Low), 900);
+        assert!(policy.summary > policy.high);
+    }
+
+    #[test]
+    fn test_deadline_only_for_pending_acks() {
+        let manager = RedisAckManager::new("redis://127.0.0.1/", 3600)
+            .unwrap()
+            .with_deadline_policy(AckDeadlinePolicy {
+                high: 30,
+                medium: 60,
+                low: 0,
+            });
+        let mut ack_info = AckStatusInfo {
+            message_id: "msg_1".to_string(),
+            user_id: "user_1".to_string(),
+            ack_type: Some(AckType::DeliveryAck),
+            status: AckStatus::Pending,
+            timestamp: 1000,
+            importance: ImportanceLevel::High,
+        };
+        assert_eq!(manager.deadline_for(&ack_info, 1000), 1030);
+
+        ack_info.importance = ImportanceLevel::Low;
+        assert_eq!(manager.deadline_for(&ack_info, 1000), 0);
+
+        ack_info.importance = ImportanceLevel::Medium;
+        ack_info.status = AckStatus::Received;
+        assert_eq!(manager.deadline_for(&ack_info, 1000), 0);
+    }
+
+    #[test]
+    fn test_summary_from_fields() {
+        let fields: HashMap<String, String> = [
+            ("total", "3"),
+            ("received", "2"),
+            ("failed", "1"),
+            ("failed:user_3", "1"),
+            ("completed_at", "1234567890"),
+            ("importance", "high"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let summary = AckSummary::from_fields("msg_1", fields).unwrap();
+        assert_eq!(summary.total, 3);
+        assert_eq!(summary.status_counts.get("received"), Some(&2));
+        assert_eq!(summary.failed_users, vec!["user_3".to_string()]);
+        assert_eq!(summary.importance, ImportanceLevel::High);
+        assert_eq!(summary.status_for("user_1"), AckStatus::Received);
+        assert_eq!(summary.status_for("user_3"), AckStatus::Failed);
+
+        // 没有压缩时间视为摘要不存在
+        assert!(AckSummary::from_fields("msg_1", HashMap::new()).is_none());
+    }
+
     #[tokio::test]
     async fn test_ack_status_management() -> RedisResult<()> {
         // 注意：这需要一个运行中的Redis实例
@@ -353,4 +1128,51 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_pipelined_store_ack_status() -> RedisResult<()> {
+        // 注意：这需要一个运行中的Redis实例
+        let manager = std::sync::Arc::new(
+            RedisAckManager::new("redis://127.0.0.1/", 3600)?.with_write_pipeline(
+                AckWritePipelineConfig {
+                    max_batch: 8,
+                    ..AckWritePipelineConfig::default()
+                },
+            ),
+        );
+
+        let writes: Vec<_> = (0..20)
+            .map(|i| {
+                let manager = manager.clone();
+                tokio::spawn(async move {
+                    manager
+                        .store_ack_status(&AckStatusInfo {
+                            message_id: "test_pipeline_msg".to_string(),
+                            user_id: format!("user_{}", i),
+                            ack_type: Some(AckType::DeliveryAck),
+                            status: AckStatus::Pending,
+                            timestamp: 1234567890,
+                            importance: ImportanceLevel::Medium,
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for write in writes {
+            assert!(!write.await.expect("write task panicked")?);
+        }
+
+        for i in 0..20 {
+            let user_id = format!("user_{}", i);
+            let retrieved = manager
+                .get_ack_status("test_pipeline_msg", &user_id)
+                .await?;
+            assert_eq!(retrieved.map(|ack| ack.status), Some(AckStatus::Pending));
+            manager
+                .delete_ack_status("test_pipeline_msg", &user_id)
+                .await?;
+        }
+
+        Ok(())
+    }
 }
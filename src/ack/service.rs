@@ -1,15 +1,23 @@
 //! ACK处理服务（精简版）
 //! 核心功能：状态管理、批量处理、监控指标
 
+use crate::ack::archiver::{AckArchiveRecord, AckArchiver};
 use crate::ack::config::AckServiceConfig;
+use crate::ack::group_aggregator::{GroupAckKind, GroupAckOutcome, MessageAckSummary};
 use crate::ack::metrics::AckMetrics;
-use crate::ack::redis_manager::{AckStatusInfo, ImportanceLevel, RedisAckManager};
-use crate::ack::traits::{AckEvent, AckManager};
+use crate::ack::redis_manager::{AckStatus, AckStatusInfo, AckType, ImportanceLevel};
+use crate::ack::store::{AckStore, build_ack_store};
+use crate::ack::timeline::{AckTimeline, AckTimelineTracker};
+use crate::ack::traits::{
+    ACK_TENANT_METADATA_KEY, AckArchiveSink, AckEvent, AckManager, AckTimeoutEvent,
+    AckTimeoutHandler, GroupReadCompleteEvent, GroupReadCompleteHandler,
+};
+use crate::ack::watch::{AckStatusBroadcaster, AckStatusChange, AckWatchFilter, AckWatcher};
 use async_trait::async_trait;
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
@@ -17,8 +25,8 @@ use tokio::time::interval;
 
 /// ACK处理服务
 pub struct AckService {
-    /// Redis管理器
-    pub redis_manager: Arc<RedisAckManager>,
+    /// ACK状态存储
+    pub store: Arc<dyn AckStore>,
     /// 内存缓存
     cache: Arc<DashMap<String, CachedAckInfo>>,
     /// 批量处理队列
@@ -27,6 +35,16 @@ pub struct AckService {
     high_priority_queue: Arc<RwLock<VecDeque<AckStatusInfo>>>,
     /// 监控指标
     metrics: Arc<AckMetrics>,
+    /// ACK超时处理器
+    timeout_handlers: Arc<RwLock<Vec<Arc<dyn AckTimeoutHandler>>>>,
+    /// ACK归档器（设置归档存储后启用）
+    archiver: OnceLock<AckArchiver>,
+    /// 群聊消息全员已读处理器
+    group_read_handlers: Arc<RwLock<Vec<Arc<dyn GroupReadCompleteHandler>>>>,
+    /// ACK状态变更广播（未启用订阅时为 None）
+    watch: Option<AckStatusBroadcaster>,
+    /// ACK阶段时间线
+    timeline: Arc<AckTimelineTracker>,
     /// 配置
     config: AckServiceConfig,
 }
@@ -46,17 +64,29 @@ impl AckService {
         config: AckServiceConfig,
         metrics: Arc<AckMetrics>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        let redis_manager = Arc::new(RedisAckManager::new(&config.redis_url, config.redis_ttl)?);
+        let store = build_ack_store(&config)?;
         let cache = Arc::new(DashMap::with_capacity(config.cache_capacity));
         let batch_queue = Arc::new(Mutex::new(VecDeque::new()));
         let high_priority_queue = Arc::new(RwLock::new(VecDeque::new()));
+        let timeline = Arc::new(AckTimelineTracker::new(
+            config.timeline.clone(),
+            metrics.clone(),
+        ));
 
         let service = Self {
-            redis_manager,
+            store,
             cache,
             batch_queue,
             high_priority_queue,
             metrics,
+            timeout_handlers: Arc::new(RwLock::new(Vec::new())),
+            archiver: OnceLock::new(),
+            group_read_handlers: Arc::new(RwLock::new(Vec::new())),
+            watch: config
+                .watch
+                .enabled
+                .then(|| AckStatusBroadcaster::new(config.watch.capacity)),
+            timeline,
             config: config.clone(),
         };
 
@@ -65,6 +95,7 @@ impl AckService {
         service.start_high_priority_processor().await;
         service.start_metrics_evaluation().await;
         service.start_timeout_monitor().await;
+        service.start_cache_eviction().await;
 
         Ok(service)
     }
@@ -72,7 +103,8 @@ impl AckService {
     /// 启动批处理任务
     async fn start_batch_processor(&self) {
         let batch_queue = self.batch_queue.clone();
-        let redis_manager = self.redis_manager.clone();
+        let store = self.store.clone();
+        let metrics = self.metrics.clone();
         let batch_size = self.config.batch_size;
         let interval_duration = Duration::from_millis(self.config.batch_interval_ms);
 
@@ -95,8 +127,9 @@ impl AckService {
 
                 // 只有当有待处理的ACK时才执行批量存储
                 if !acks_to_process.is_empty() {
-                    if let Err(e) = redis_manager.batch_store_ack_status(&acks_to_process).await {
-                        tracing::error!(error = %e, "Failed to batch store ACKs");
+                    match store.batch_store_ack_status(&acks_to_process).await {
+                        Ok(compacted) => metrics.record_acks_compacted(compacted as u64),
+                        Err(e) => tracing::error!(error = %e, "Failed to batch store ACKs"),
                     }
                 }
             }
@@ -106,7 +139,8 @@ impl AckService {
     /// 启动高优先级处理任务
     async fn start_high_priority_processor(&self) {
         let high_priority_queue = self.high_priority_queue.clone();
-        let redis_manager = self.redis_manager.clone();
+        let store = self.store.clone();
+        let metrics = self.metrics.clone();
         let batch_size = self.config.batch_size;
         let interval_duration = Duration::from_millis(10); // 高优先级任务更快的处理间隔
 
@@ -129,8 +163,11 @@ impl AckService {
 
                 // 只有当有待处理的高优先级ACK时才执行批量存储
                 if !acks_to_process.is_empty() {
-                    if let Err(e) = redis_manager.batch_store_ack_status(&acks_to_process).await {
-                        tracing::error!(error = %e, "Failed to batch store high priority ACKs");
+                    match store.batch_store_ack_status(&acks_to_process).await {
+                        Ok(compacted) => metrics.record_acks_compacted(compacted as u64),
+                        Err(e) => {
+                            tracing::error!(error = %e, "Failed to batch store high priority ACKs")
+                        }
                     }
                 }
             }
@@ -142,7 +179,7 @@ impl AckService {
         let metrics = self.metrics.clone();
         let batch_queue = self.batch_queue.clone();
         let high_priority_queue = self.high_priority_queue.clone();
-        let redis_manager = self.redis_manager.clone();
+        let store = self.store.clone();
         let interval_duration = Duration::from_secs(30);
 
         tokio::spawn(async move {
@@ -165,8 +202,8 @@ impl AckService {
                 };
                 metrics.update_high_priority_queue_size(high_priority_queue_size as i64);
 
-                // 获取Redis统计信息并更新相关指标
-                if let Ok(redis_stats) = redis_manager.get_stats().await {
+                // 获取存储统计信息并更新相关指标
+                if let Ok(redis_stats) = store.get_stats().await {
                     metrics.update_redis_connections(redis_stats.used_memory as i64);
                     metrics.update_memory_usage(redis_stats.used_memory as i64);
                 }
@@ -175,7 +212,224 @@ impl AckService {
     }
 
     /// 启动超时监控任务
+    ///
+    /// 定期从截止时间队列领取到期的ACK，仍为 Pending 的向已注册的处理器发送超时事件
     async fn start_timeout_monitor(&self) {
+        if !self.config.timeout_scan.enabled {
+            return;
+        }
+        let store = self.store.clone();
+        let cache = self.cache.clone();
+        let handlers = self.timeout_handlers.clone();
+        let metrics = self.metrics.clone();
+        let batch_size = self.config.timeout_scan.batch_size.max(1);
+        let interval_duration =
+            Duration::from_millis(self.config.timeout_scan.scan_interval_ms.max(1));
+
+        tokio::spawn(async move {
+            let mut interval = interval(interval_duration);
+
+            loop {
+                interval.tick().await;
+
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                let expired = match store.claim_expired_deadlines(now, batch_size).await {
+                    Ok(expired) => expired,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Failed to claim expired ACK deadlines");
+                        continue;
+                    }
+                };
+                if expired.is_empty() {
+                    continue;
+                }
+
+                let handlers = handlers.read().await.clone();
+                for deadline in expired {
+                    // 内存缓存中的状态最新，缓存未命中时回落到存储
+                    let cache_key = format!("{}:{}", deadline.message_id, deadline.user_id);
+                    let cached = cache.get(&cache_key).map(|cached| cached.ack_info.clone());
+                    let ack_info = match cached {
+                        Some(ack_info) => Some(ack_info),
+                        None => store
+                            .get_ack_status(&deadline.message_id, &deadline.user_id)
+                            .await
+                            .unwrap_or_else(|e| {
+                                tracing::warn!(
+                                    message_id = %deadline.message_id,
+                                    user_id = %deadline.user_id,
+                                    error = %e,
+                                    "Failed to load ACK status for timeout check"
+                                );
+                                None
+                            }),
+                    };
+                    let Some(ack_info) = ack_info else {
+                        continue;
+                    };
+                    if ack_info.status.is_final() {
+                        continue;
+                    }
+
+                    metrics.record_ack_timeout();
+                    let event = AckTimeoutEvent {
+                        message_id: deadline.message_id,
+                        user_id: deadline.user_id,
+                        ack_type: ack_info.ack_type.unwrap_or(AckType::DeliveryAck),
+                        timeout_at: deadline.deadline as i64,
+                    };
+                    tracing::debug!(
+                        message_id = %event.message_id,
+                        user_id = %event.user_id,
+                        importance = ack_info.importance.as_str(),
+                        "ACK timed out"
+                    );
+                    for handler in &handlers {
+                        if let Err(e) = handler.on_timeout(event.clone()).await {
+                            tracing::warn!(
+                                message_id = %event.message_id,
+                                user_id = %event.user_id,
+                                error = %e,
+                                "ACK timeout handler failed"
+                            );
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// 注册ACK超时处理器
+    pub async fn register_timeout_handler(&self, handler: Arc<dyn AckTimeoutHandler>) {
+        self.timeout_handlers.write().await.push(handler);
+    }
+
+    /// 注册群聊消息全员已读处理器
+    pub async fn register_group_read_handler(&self, handler: Arc<dyn GroupReadCompleteHandler>) {
+        self.group_read_handlers.write().await.push(handler);
+    }
+
+    /// 登记群聊消息的接收者人数（接收者不含发送者；未启用聚合或人数为0时忽略）
+    pub async fn register_group_message(
+        &self,
+        message_id: &str,
+        conversation_id: &str,
+        recipient_count: u64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.config.group_aggregation.enabled || recipient_count == 0 {
+            return Ok(());
+        }
+        self.store
+            .register_group_message(message_id, conversation_id, recipient_count)
+            .await
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    /// 累计群聊消息的送达/已读ACK，达成全员已读时通知已注册的处理器
+    pub async fn record_group_ack(
+        &self,
+        message_id: &str,
+        user_id: &str,
+        kind: GroupAckKind,
+    ) -> Result<GroupAckOutcome, Box<dyn std::error::Error>> {
+        if !self.config.group_aggregation.enabled {
+            return Ok(GroupAckOutcome::default());
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let outcome = self
+            .store
+            .record_group_ack(message_id, user_id, kind, now)
+            .await
+            .map_err(store_error)?;
+        if outcome.delivered_counted {
+            self.metrics
+                .record_group_ack_counted(GroupAckKind::Delivered.as_str());
+        }
+        if outcome.read_counted {
+            self.metrics
+                .record_group_ack_counted(GroupAckKind::Read.as_str());
+        }
+        if outcome.fully_read {
+            self.metrics.record_group_message_fully_read();
+            self.notify_group_read_complete(message_id, now).await;
+        }
+        Ok(outcome)
+    }
+
+    /// 获取群聊消息的ACK聚合摘要
+    pub async fn get_message_ack_summary(
+        &self,
+        message_id: &str,
+    ) -> Result<Option<MessageAckSummary>, Box<dyn std::error::Error>> {
+        self.store
+            .get_group_summary(message_id)
+            .await
+            .map_err(store_error)
+    }
+
+    async fn notify_group_read_complete(&self, message_id: &str, completed_at: u64) {
+        let handlers = self.group_read_handlers.read().await.clone();
+        if handlers.is_empty() {
+            return;
+        }
+        let summary = match self.store.get_group_summary(message_id).await {
+            Ok(Some(summary)) => summary,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(
+                    message_id = %message_id,
+                    error = %e,
+                    "Failed to load group ACK summary for read-complete event"
+                );
+                return;
+            }
+        };
+        let event = GroupReadCompleteEvent {
+            message_id: summary.message_id,
+            conversation_id: summary.conversation_id,
+            total_recipients: summary.total_recipients,
+            completed_at: completed_at as i64,
+        };
+        for handler in &handlers {
+            if let Err(e) = handler.on_read_complete(event.clone()).await {
+                tracing::warn!(
+                    message_id = %event.message_id,
+                    error = %e,
+                    "Group read-complete handler failed"
+                );
+            }
+        }
+    }
+
+    /// 设置ACK归档存储并启动归档任务（只能设置一次），之后进入终态的ACK异步写入该存储
+    pub fn set_archive_sink(
+        &self,
+        sink: Arc<dyn AckArchiveSink>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut started = false;
+        self.archiver.get_or_init(|| {
+            started = true;
+            AckArchiver::start(sink, self.config.archive.clone(), self.metrics.clone())
+        });
+        if !started {
+            return Err("ACK archive sink is already set".into());
+        }
+        Ok(())
+    }
+
+    /// 启动内存缓存淘汰任务（按重要性分级的过期时间淘汰，低重要性ACK最先过期；同时淘汰过期的ACK时间线）
+    async fn start_cache_eviction(&self) {
+        let cache = self.cache.clone();
+        let timeline = self.timeline.clone();
+        let metrics = self.metrics.clone();
+        let ttl_policy = *self.store.ttl_policy();
         let interval_duration = Duration::from_secs(30);
 
         tokio::spawn(async move {
@@ -184,9 +438,25 @@ impl AckService {
             loop {
                 interval.tick().await;
 
-                // 检查超时的 ACK（简化实现：扫描 Redis 中的 Pending ACK）
-                // 实际应该使用 Redis 的过期键通知或更高效的机制
-                // 当前实现：定期扫描（性能较低，但简单可靠）
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                let before = cache.len();
+                cache.retain(|_, cached| {
+                    now.saturating_sub(cached.cached_at)
+                        < ttl_policy.ttl_for(&cached.ack_info.importance)
+                });
+                let evicted = before.saturating_sub(cache.len());
+                if evicted > 0 {
+                    metrics.record_cache_evicted(evicted as u64);
+                    tracing::debug!(evicted, "Evicted expired ACKs from memory cache");
+                }
+
+                let expired_timelines = timeline.evict_expired();
+                if expired_timelines > 0 {
+                    tracing::debug!(expired_timelines, "Evicted unfinished ACK timelines");
+                }
             }
         });
     }
@@ -196,6 +466,17 @@ impl AckService {
         &self,
         ack_info: AckStatusInfo,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        self.record_ack_in_tenant(None, ack_info).await
+    }
+
+    /// 记录ACK状态，并按租户记录ACK阶段耗时（未知租户时为 None）
+    pub async fn record_ack_in_tenant(
+        &self,
+        tenant_id: Option<&str>,
+        ack_info: AckStatusInfo,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.timeline.observe(&ack_info, tenant_id);
+
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
@@ -218,9 +499,44 @@ impl AckService {
             }
         }
 
+        // 成功的送达/已读ACK计入群聊消息送达数/已读数（消息未登记时不计数）
+        let group_kind = match ack_info.ack_type {
+            Some(AckType::DeliveryAck) => Some(GroupAckKind::Delivered),
+            Some(AckType::ReadAck) => Some(GroupAckKind::Read),
+            _ => None,
+        };
+        if let Some(kind) = group_kind
+            .filter(|_| ack_info.status.is_final() && ack_info.status != AckStatus::Failed)
+        {
+            if let Err(e) = self
+                .record_group_ack(&ack_info.message_id, &ack_info.user_id, kind)
+                .await
+            {
+                tracing::warn!(
+                    message_id = %ack_info.message_id,
+                    user_id = %ack_info.user_id,
+                    kind = kind.as_str(),
+                    error = %e,
+                    "Failed to count group message ACK"
+                );
+            }
+        }
+
+        // 终态ACK按重要性级别的归档策略异步归档（队列满时丢弃，不阻塞）
+        if ack_info.status.is_final() && self.config.archives(&ack_info.importance) {
+            if let Some(archiver) = self.archiver.get() {
+                archiver.submit(AckArchiveRecord::from(&ack_info));
+            }
+        }
+
         // 将ACK信息缓存到内存中
         let cache_key = self.format_cache_key(&ack_info.message_id, &ack_info.user_id);
-        self.cache.insert(
+        let watched = self
+            .watch
+            .as_ref()
+            .filter(|watch| watch.has_subscribers())
+            .map(|watch| (watch, ack_info.clone()));
+        let previous = self.cache.insert(
             cache_key,
             CachedAckInfo {
                 ack_info,
@@ -228,9 +544,35 @@ impl AckService {
             },
         );
 
+        // 状态发生变化时广播给订阅者（重复上报相同状态不广播）
+        if let Some((watch, ack_info)) = watched {
+            let previous_status = previous.map(|cached| cached.ack_info.status);
+            if previous_status.as_ref() != Some(&ack_info.status) {
+                watch.publish(AckStatusChange {
+                    ack: ack_info,
+                    previous_status,
+                    changed_at: now,
+                });
+            }
+        }
+
         Ok(())
     }
 
+    /// 获取消息+用户的ACK阶段时间线（已读后结束的或已过期的时间线返回 None）
+    pub fn get_ack_timeline(&self, message_id: &str, user_id: &str) -> Option<AckTimeline> {
+        self.timeline.get(message_id, user_id)
+    }
+
+    /// 订阅ACK状态变更（只接收订阅之后的变更；未启用订阅时返回错误）
+    pub fn watch_ack_status(
+        &self,
+        filter: AckWatchFilter,
+    ) -> Result<AckWatcher, Box<dyn std::error::Error>> {
+        let watch = self.watch.as_ref().ok_or("ACK status watch is disabled")?;
+        Ok(watch.subscribe(filter, self.metrics.clone()))
+    }
+
     /// 记录ACK状态（公开方法，兼容旧代码）
     pub async fn record_ack(
         &self,
@@ -252,13 +594,14 @@ impl AckService {
             return Ok(Some(cached.ack_info.clone()));
         }
 
-        // 如果内存缓存中没有，从Redis获取
+        // 如果内存缓存中没有，从存储获取
         if let Some(ack_info) = self
-            .redis_manager
+            .store
             .get_ack_status(message_id, user_id)
-            .await?
+            .await
+            .map_err(store_error)?
         {
-            // 将从Redis获取的ACK信息缓存到内存中
+            // 将从存储获取的ACK信息缓存到内存中
             let now = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
@@ -278,6 +621,31 @@ impl AckService {
         Ok(None)
     }
 
+    /// 查询用户仍未确认的ACK（按时间戳升序，最多 `limit` 条，0 表示不限制）
+    ///
+    /// 基于存储的用户待确认索引；内存缓存中已进入终态但尚未落盘的记录会被排除。
+    /// 仅缓存的低重要性ACK与尚在批处理队列中的记录不会出现在结果中
+    pub async fn list_pending_acks(
+        &self,
+        user_id: &str,
+        limit: usize,
+        since: u64,
+    ) -> Result<Vec<AckStatusInfo>, Box<dyn std::error::Error>> {
+        let pending = self
+            .store
+            .list_pending_acks(user_id, limit, since)
+            .await
+            .map_err(store_error)?;
+        Ok(pending
+            .into_iter()
+            .filter(|ack_info| {
+                self.cache
+                    .get(&self.format_cache_key(&ack_info.message_id, user_id))
+                    .is_none_or(|cached| !cached.ack_info.status.is_final())
+            })
+            .collect())
+    }
+
     /// 检查ACK是否存在
     pub async fn exists_ack(
         &self,
@@ -291,8 +659,11 @@ impl AckService {
             return Ok(true);
         }
 
-        // 如果内存缓存中不存在，检查Redis中是否存在
-        Ok(self.redis_manager.exists_ack(message_id, user_id).await?)
+        // 如果内存缓存中不存在，检查存储中是否存在
+        self.store
+            .exists_ack(message_id, user_id)
+            .await
+            .map_err(store_error)
     }
 
     /// 删除ACK状态
@@ -305,10 +676,11 @@ impl AckService {
 
         // 从内存缓存中删除
         self.cache.remove(&cache_key);
-        // 从Redis中删除
-        self.redis_manager
+        // 从存储中删除
+        self.store
             .delete_ack_status(message_id, user_id)
-            .await?;
+            .await
+            .map_err(store_error)?;
 
         Ok(())
     }
@@ -336,7 +708,7 @@ impl AckService {
 
     /// 获取服务统计信息
     pub async fn get_stats(&self) -> Result<AckServiceStats, Box<dyn std::error::Error>> {
-        let redis_stats = self.redis_manager.get_stats().await?;
+        let redis_stats = self.store.get_stats().await.map_err(store_error)?;
         let cache_size = self.cache.len();
 
         let batch_queue_size = {
@@ -361,6 +733,12 @@ impl AckService {
 #[async_trait]
 impl AckManager for AckService {
     async fn record_ack(&self, event: AckEvent) -> Result<(), Box<dyn std::error::Error>> {
+        let tenant_id = event
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(ACK_TENANT_METADATA_KEY))
+            .and_then(|tenant_id| tenant_id.as_str())
+            .map(str::to_string);
         let ack_info = AckStatusInfo {
             message_id: event.message_id,
             user_id: event.user_id,
@@ -370,7 +748,8 @@ impl AckManager for AckService {
             importance: event.importance,
         };
 
-        self.record_ack_internal(ack_info).await
+        self.record_ack_in_tenant(tenant_id.as_deref(), ack_info)
+            .await
     }
 
     async fn get_ack_status(
@@ -403,6 +782,27 @@ impl AckManager for AckService {
     ) -> Result<(), Box<dyn std::error::Error>> {
         self.delete_ack(message_id, user_id).await
     }
+
+    fn watch_ack_status(
+        &self,
+        filter: AckWatchFilter,
+    ) -> Result<AckWatcher, Box<dyn std::error::Error>> {
+        AckService::watch_ack_status(self, filter)
+    }
+
+    async fn list_pending_acks(
+        &self,
+        user_id: &str,
+        limit: usize,
+        since: u64,
+    ) -> Result<Vec<AckStatusInfo>, Box<dyn std::error::Error>> {
+        AckService::list_pending_acks(self, user_id, limit, since).await
+    }
+}
+
+/// 存储错误转换为服务层错误
+fn store_error(e: Box<dyn std::error::Error + Send + Sync>) -> Box<dyn std::error::Error> {
+    e
 }
 
 /// ACK服务统计信息
@@ -425,7 +825,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_ack_service() -> Result<(), Box<dyn std::error::Error>> {
-        let config = AckServiceConfig::default();
+        // 使用内存存储，无需 Redis
+        let mut config = AckServiceConfig::default();
+        config.store.backend = crate::ack::config::AckStoreBackend::Memory;
 
         let registry = prometheus::Registry::new();
         let metrics = Arc::new(AckMetrics::new(&registry)?);
@@ -457,4 +859,48 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_watch_ack_status() -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = AckServiceConfig::default();
+        config.store.backend = crate::ack::config::AckStoreBackend::Memory;
+
+        let registry = prometheus::Registry::new();
+        let metrics = Arc::new(AckMetrics::new(&registry)?);
+        let service = AckService::new(config, metrics).await?;
+
+        let mut watcher = service.watch_ack_status(AckWatchFilter::message("watch_msg"))?;
+        let ack = |message_id: &str, status: AckStatus| AckStatusInfo {
+            message_id: message_id.to_string(),
+            user_id: "user_1".to_string(),
+            ack_type: Some(AckType::DeliveryAck),
+            status,
+            timestamp: 1234567890,
+            importance: ImportanceLevel::Low,
+        };
+
+        service
+            .record_ack(ack("other_msg", AckStatus::Pending))
+            .await?;
+        service
+            .record_ack(ack("watch_msg", AckStatus::Pending))
+            .await?;
+        // 重复上报相同状态不广播
+        service
+            .record_ack(ack("watch_msg", AckStatus::Pending))
+            .await?;
+        service
+            .record_ack(ack("watch_msg", AckStatus::Processed))
+            .await?;
+
+        let change = watcher.recv().await.unwrap();
+        assert_eq!(change.ack.status, AckStatus::Pending);
+        assert!(change.previous_status.is_none());
+
+        let change = watcher.recv().await.unwrap();
+        assert_eq!(change.ack.status, AckStatus::Processed);
+        assert_eq!(change.previous_status, Some(AckStatus::Pending));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,162 @@
+//! ACK状态存储抽象
+//!
+//! `AckService` 通过 `AckStore` 读写ACK状态，按 `AckServiceConfig::store` 选择实现：
+//! - `memory`：进程内存储，用于测试与单机开发，无需 Redis
+//! - `redis`：单机 Redis（`RedisAckManager`）
+//! - `redis_cluster`：Redis 集群（`RedisClusterAckStore`），同一消息的键通过哈希标签落在同一槽位
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::ack::config::{AckServiceConfig, AckStoreBackend};
+use crate::ack::group_aggregator::{GroupAckKind, GroupAckOutcome, MessageAckSummary};
+use crate::ack::memory_store::MemoryAckStore;
+use crate::ack::redis_cluster_store::RedisClusterAckStore;
+use crate::ack::redis_manager::{
+    AckStatusInfo, AckSummary, AckTtlPolicy, ExpiredAckDeadline, RedisAckManager, RedisStats,
+};
+
+/// ACK存储操作结果
+pub type AckStoreResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// ACK状态存储
+///
+/// 实现需保证：
+/// - 记录为 Pending 的用户登记为消息接收者，所有接收者进入终态后压缩为摘要（启用压缩时）
+/// - Pending 状态写入确认截止时间队列（设置了超时时间时），终态移出队列
+/// - 状态查询在逐用户记录不存在时回落到摘要
+#[async_trait]
+pub trait AckStore: Send + Sync {
+    /// 按重要性分级的过期时间
+    fn ttl_policy(&self) -> &AckTtlPolicy;
+
+    /// 存储ACK状态（返回该消息是否因本次写入完成压缩）
+    async fn store_ack_status(&self, ack_info: &AckStatusInfo) -> AckStoreResult<bool>;
+
+    /// 批量存储ACK状态（返回完成压缩的消息数）
+    async fn batch_store_ack_status(&self, ack_infos: &[AckStatusInfo]) -> AckStoreResult<usize>;
+
+    /// 获取ACK状态
+    async fn get_ack_status(
+        &self,
+        message_id: &str,
+        user_id: &str,
+    ) -> AckStoreResult<Option<AckStatusInfo>>;
+
+    /// 获取消息级压缩摘要
+    async fn get_ack_summary(&self, message_id: &str) -> AckStoreResult<Option<AckSummary>>;
+
+    /// 删除ACK状态
+    async fn delete_ack_status(&self, message_id: &str, user_id: &str) -> AckStoreResult<()>;
+
+    /// 检查ACK是否存在（已压缩的消息摘要存在即视为存在）
+    async fn exists_ack(&self, message_id: &str, user_id: &str) -> AckStoreResult<bool>;
+
+    /// 领取截止时间不晚于 `now` 的待确认ACK（最多 `limit` 条，领取后移出队列）
+    async fn claim_expired_deadlines(
+        &self,
+        now: u64,
+        limit: usize,
+    ) -> AckStoreResult<Vec<ExpiredAckDeadline>>;
+
+    /// 扫描逐用户的ACK状态（最多 `max_records` 条，0 表示不限制；`batch_size` 为单次扫描数量）
+    async fn scan_ack_statuses(
+        &self,
+        max_records: usize,
+        batch_size: usize,
+    ) -> AckStoreResult<Vec<AckStatusInfo>>;
+
+    /// 查询用户仍处于 Pending 状态的ACK（按时间戳升序，只返回时间戳不早于 `since` 的记录；
+    /// 最多 `limit` 条，0 表示不限制）
+    async fn list_pending_acks(
+        &self,
+        user_id: &str,
+        limit: usize,
+        since: u64,
+    ) -> AckStoreResult<Vec<AckStatusInfo>>;
+
+    /// 将消息的逐用户终态ACK合并到消息摘要并删除逐用户记录（返回删除的记录数）
+    ///
+    /// 只合并存储中仍为终态的记录，摘要已存在时累加计数；合并后接收者登记表中不再有待确认用户时一并删除
+    async fn merge_final_acks(
+        &self,
+        message_id: &str,
+        user_ids: &[String],
+        now: u64,
+    ) -> AckStoreResult<usize>;
+
+    /// 登记群聊消息的接收者人数
+    async fn register_group_message(
+        &self,
+        message_id: &str,
+        conversation_id: &str,
+        recipient_count: u64,
+    ) -> AckStoreResult<()>;
+
+    /// 累计群聊消息的送达/已读ACK
+    async fn record_group_ack(
+        &self,
+        message_id: &str,
+        user_id: &str,
+        kind: GroupAckKind,
+        now: u64,
+    ) -> AckStoreResult<GroupAckOutcome>;
+
+    /// 获取群聊消息的ACK聚合摘要
+    async fn get_group_summary(
+        &self,
+        message_id: &str,
+    ) -> AckStoreResult<Option<MessageAckSummary>>;
+
+    /// 获取存储统计信息
+    async fn get_stats(&self) -> AckStoreResult<RedisStats>;
+}
+
+/// 按配置创建ACK状态存储
+pub fn build_ack_store(
+    config: &AckServiceConfig,
+) -> Result<Arc<dyn AckStore>, Box<dyn std::error::Error>> {
+    let deadline_policy = config
+        .timeout_scan
+        .enabled
+        .then(|| config.deadline_policy());
+
+    let store: Arc<dyn AckStore> = match config.store.backend {
+        AckStoreBackend::Memory => {
+            let mut store = MemoryAckStore::new(config.ttl_policy())
+                .with_compaction(config.compaction.enabled)
+                .with_group_ttl(config.group_aggregation.ttl);
+            if let Some(deadline_policy) = deadline_policy {
+                store = store.with_deadline_policy(deadline_policy);
+            }
+            Arc::new(store)
+        }
+        AckStoreBackend::Redis => {
+            let mut store = RedisAckManager::new(&config.redis_url, config.redis_ttl)?
+                .with_ttl_policy(config.ttl_policy())
+                .with_compaction(config.compaction.enabled)
+                .with_group_ttl(config.group_aggregation.ttl)
+                .with_write_pipeline(config.write_pipeline.clone());
+            if let Some(deadline_policy) = deadline_policy {
+                store = store.with_deadline_policy(deadline_policy);
+            }
+            Arc::new(store)
+        }
+        AckStoreBackend::RedisCluster => {
+            let nodes = if config.store.cluster_nodes.is_empty() {
+                vec![config.redis_url.clone()]
+            } else {
+                config.store.cluster_nodes.clone()
+            };
+            let mut store = RedisClusterAckStore::new(&nodes, config.ttl_policy())?
+                .with_compaction(config.compaction.enabled)
+                .with_group_ttl(config.group_aggregation.ttl);
+            if let Some(deadline_policy) = deadline_policy {
+                store = store.with_deadline_policy(deadline_policy);
+            }
+            Arc::new(store)
+        }
+    };
+    Ok(store)
+}
@@ -0,0 +1,338 @@
+//! ACK阶段时间线
+//!
+//! 同一消息+用户的ACK依次经过：发送（推送登记待确认）→ 传输ACK → 服务器ACK → 送达ACK → 已读ACK。
+//! ACK状态存储中后到的ACK会覆盖先到的，阶段之间的耗时需要单独关联：
+//! - 每个阶段只记录首次到达的时间（毫秒）
+//! - 新阶段到达时，记录与之前最近一个已到达阶段之间的耗时（`<起始阶段>_to_<结束阶段>`）
+//! - 已读ACK到达且记录过发送时间时，额外记录端到端耗时 `sent_to_read`，之后时间线结束
+//! - 耗时按租户记录到 `ack_stage_latency_seconds`，用于衡量各租户的"发送→已读" SLA
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dashmap::DashMap;
+
+use crate::ack::config::AckTimelineConfig;
+use crate::ack::metrics::AckMetrics;
+use crate::ack::redis_manager::{AckStatus, AckStatusInfo, AckType};
+
+/// 未指定租户时的指标标签
+const UNKNOWN_TENANT: &str = "unknown";
+
+/// ACK阶段（按先后顺序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AckStage {
+    /// 消息已推送，等待确认
+    Sent,
+    /// 客户端传输层确认
+    Transport,
+    /// 服务器确认
+    Server,
+    /// 已送达
+    Delivered,
+    /// 已读
+    Read,
+}
+
+impl AckStage {
+    pub const ALL: [AckStage; 5] = [
+        AckStage::Sent,
+        AckStage::Transport,
+        AckStage::Server,
+        AckStage::Delivered,
+        AckStage::Read,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AckStage::Sent => "sent",
+            AckStage::Transport => "transport",
+            AckStage::Server => "server",
+            AckStage::Delivered => "delivered",
+            AckStage::Read => "read",
+        }
+    }
+
+    /// 根据ACK状态推断阶段（失败的ACK与存储ACK不构成阶段）
+    ///
+    /// 待确认的服务器ACK表示消息已推送（发送），其余成功的ACK按类型对应阶段
+    pub fn from_ack(ack_info: &AckStatusInfo) -> Option<Self> {
+        if ack_info.status == AckStatus::Failed {
+            return None;
+        }
+        match ack_info.ack_type? {
+            AckType::ServerAck if ack_info.status == AckStatus::Pending => Some(AckStage::Sent),
+            _ if ack_info.status == AckStatus::Pending => None,
+            AckType::TransportAck => Some(AckStage::Transport),
+            AckType::ServerAck => Some(AckStage::Server),
+            AckType::DeliveryAck => Some(AckStage::Delivered),
+            AckType::ReadAck => Some(AckStage::Read),
+            AckType::StorageAck => None,
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// 一条消息对一个用户的ACK时间线
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AckTimeline {
+    /// 消息ID
+    pub message_id: String,
+    /// 用户ID
+    pub user_id: String,
+    /// 租户ID（任一阶段携带租户时记录）
+    pub tenant_id: Option<String>,
+    /// 各阶段首次到达时间（毫秒，按 `AckStage` 顺序）
+    stages: [Option<u64>; 5],
+}
+
+impl AckTimeline {
+    pub fn new(message_id: impl Into<String>, user_id: impl Into<String>) -> Self {
+        Self {
+            message_id: message_id.into(),
+            user_id: user_id.into(),
+            tenant_id: None,
+            stages: [None; 5],
+        }
+    }
+
+    /// 阶段到达时间（毫秒）
+    pub fn stage_at(&self, stage: AckStage) -> Option<u64> {
+        self.stages[stage.index()]
+    }
+
+    /// 两个阶段之间的耗时（毫秒；任一阶段未到达或顺序颠倒时为 None）
+    pub fn latency_ms(&self, from: AckStage, to: AckStage) -> Option<u64> {
+        self.stage_at(to)?.checked_sub(self.stage_at(from)?)
+    }
+
+    /// 发送到已读的端到端耗时（毫秒）
+    pub fn send_to_read_ms(&self) -> Option<u64> {
+        self.latency_ms(AckStage::Sent, AckStage::Read)
+    }
+
+    /// 是否已结束（收到已读ACK）
+    pub fn is_complete(&self) -> bool {
+        self.stage_at(AckStage::Read).is_some()
+    }
+
+    /// 记录阶段到达（重复到达的阶段忽略），返回新产生的阶段耗时 `(起始阶段, 结束阶段, 毫秒)`
+    pub fn record(&mut self, stage: AckStage, at_ms: u64) -> Vec<(AckStage, AckStage, u64)> {
+        if self.stage_at(stage).is_some() {
+            return Vec::new();
+        }
+        self.stages[stage.index()] = Some(at_ms);
+
+        let mut spans = Vec::new();
+        // 与之前最近一个已到达阶段之间的耗时
+        if let Some(previous) = AckStage::ALL[..stage.index()]
+            .iter()
+            .rev()
+            .find(|previous| self.stage_at(**previous).is_some())
+        {
+            if let Some(latency) = self.latency_ms(*previous, stage) {
+                spans.push((*previous, stage, latency));
+            }
+        }
+        // 之后已到达的阶段（乱序到达时）改为从本阶段起算
+        if let Some(next) = AckStage::ALL[stage.index() + 1..]
+            .iter()
+            .find(|next| self.stage_at(**next).is_some())
+        {
+            if let Some(latency) = self.latency_ms(stage, *next) {
+                spans.push((stage, *next, latency));
+            }
+        }
+        // 端到端耗时（已读ACK的上一个阶段就是发送时已包含在内）
+        if stage == AckStage::Read && !spans.iter().any(|(from, _, _)| *from == AckStage::Sent) {
+            if let Some(latency) = self.send_to_read_ms() {
+                spans.push((AckStage::Sent, AckStage::Read, latency));
+            }
+        }
+        spans
+    }
+}
+
+/// ACK时间线跟踪器（进程内）
+pub(crate) struct AckTimelineTracker {
+    timelines: DashMap<String, AckTimeline>,
+    config: AckTimelineConfig,
+    metrics: Arc<AckMetrics>,
+}
+
+impl AckTimelineTracker {
+    pub(crate) fn new(config: AckTimelineConfig, metrics: Arc<AckMetrics>) -> Self {
+        Self {
+            timelines: DashMap::new(),
+            config,
+            metrics,
+        }
+    }
+
+    /// 根据ACK状态记录阶段到达
+    pub(crate) fn observe(&self, ack_info: &AckStatusInfo, tenant_id: Option<&str>) {
+        if let Some(stage) = AckStage::from_ack(ack_info) {
+            self.record(
+                &ack_info.message_id,
+                &ack_info.user_id,
+                stage,
+                tenant_id,
+                now_millis(),
+            );
+        }
+    }
+
+    /// 记录阶段到达并上报阶段耗时，时间线结束后移除
+    pub(crate) fn record(
+        &self,
+        message_id: &str,
+        user_id: &str,
+        stage: AckStage,
+        tenant_id: Option<&str>,
+        at_ms: u64,
+    ) {
+        if !self.config.enabled {
+            return;
+        }
+        let key = timeline_key(message_id, user_id);
+        if !self.timelines.contains_key(&key) && self.timelines.len() >= self.config.max_entries {
+            return;
+        }
+
+        let (spans, tenant, complete) = {
+            let mut timeline = self
+                .timelines
+                .entry(key.clone())
+                .or_insert_with(|| AckTimeline::new(message_id, user_id));
+            if timeline.tenant_id.is_none() {
+                timeline.tenant_id = tenant_id
+                    .filter(|tenant_id| !tenant_id.is_empty())
+                    .map(str::to_string);
+            }
+            let spans = timeline.record(stage, at_ms);
+            (spans, timeline.tenant_id.clone(), timeline.is_complete())
+        };
+
+        let tenant = tenant.as_deref().unwrap_or(UNKNOWN_TENANT);
+        for (from, to, latency_ms) in spans {
+            self.metrics.record_ack_stage_latency(
+                tenant,
+                &format!("{}_to_{}", from.as_str(), to.as_str()),
+                latency_ms as f64 / 1000.0,
+            );
+        }
+        if complete {
+            self.timelines.remove(&key);
+        }
+    }
+
+    /// 获取时间线（已结束或已过期的时间线返回 None）
+    pub(crate) fn get(&self, message_id: &str, user_id: &str) -> Option<AckTimeline> {
+        self.timelines
+            .get(&timeline_key(message_id, user_id))
+            .map(|timeline| timeline.clone())
+    }
+
+    /// 淘汰超过保留时间的未完成时间线，返回淘汰数量
+    pub(crate) fn evict_expired(&self) -> usize {
+        let cutoff = now_millis().saturating_sub(self.config.ttl.saturating_mul(1000));
+        let before = self.timelines.len();
+        self.timelines.retain(|_, timeline| {
+            timeline
+                .stages
+                .iter()
+                .flatten()
+                .max()
+                .is_some_and(|last| *last >= cutoff)
+        });
+        before.saturating_sub(self.timelines.len())
+    }
+}
+
+fn timeline_key(message_id: &str, user_id: &str) -> String {
+    format!("{}:{}", message_id, user_id)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeline_stage_latencies() {
+        let mut timeline = AckTimeline::new("msg_1", "user_1");
+        assert!(timeline.record(AckStage::Sent, 1_000).is_empty());
+        assert_eq!(
+            timeline.record(AckStage::Transport, 1_200),
+            vec![(AckStage::Sent, AckStage::Transport, 200)]
+        );
+        // 重复到达的阶段不重新计时
+        assert!(timeline.record(AckStage::Transport, 1_500).is_empty());
+        // 未收到服务器ACK与送达ACK时，从传输ACK起算
+        assert_eq!(
+            timeline.record(AckStage::Read, 6_000),
+            vec![
+                (AckStage::Transport, AckStage::Read, 4_800),
+                (AckStage::Sent, AckStage::Read, 5_000),
+            ]
+        );
+        assert!(timeline.is_complete());
+        assert_eq!(timeline.send_to_read_ms(), Some(5_000));
+    }
+
+    #[test]
+    fn test_timeline_out_of_order() {
+        let mut timeline = AckTimeline::new("msg_1", "user_1");
+        timeline.record(AckStage::Sent, 1_000);
+        timeline.record(AckStage::Delivered, 1_400);
+        // 服务器ACK晚于送达ACK到达：分别记录发送→服务器与服务器→送达
+        assert_eq!(
+            timeline.record(AckStage::Server, 1_300),
+            vec![
+                (AckStage::Sent, AckStage::Server, 300),
+                (AckStage::Server, AckStage::Delivered, 100),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stage_from_ack() {
+        let ack = |ack_type, status| AckStatusInfo {
+            message_id: "msg_1".to_string(),
+            user_id: "user_1".to_string(),
+            ack_type: Some(ack_type),
+            status,
+            timestamp: 0,
+            importance: crate::ack::redis_manager::ImportanceLevel::High,
+        };
+        assert_eq!(
+            AckStage::from_ack(&ack(AckType::ServerAck, AckStatus::Pending)),
+            Some(AckStage::Sent)
+        );
+        assert_eq!(
+            AckStage::from_ack(&ack(AckType::ServerAck, AckStatus::Received)),
+            Some(AckStage::Server)
+        );
+        assert_eq!(
+            AckStage::from_ack(&ack(AckType::ReadAck, AckStatus::Processed)),
+            Some(AckStage::Read)
+        );
+        assert_eq!(
+            AckStage::from_ack(&ack(AckType::DeliveryAck, AckStatus::Failed)),
+            None
+        );
+        assert_eq!(
+            AckStage::from_ack(&ack(AckType::StorageAck, AckStatus::Processed)),
+            None
+        );
+    }
+}
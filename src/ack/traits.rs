@@ -1,12 +1,17 @@
 //! ACK 管理统一接口
 //! 提供统一的 ACK 能力，供各业务模块使用
 
+use crate::ack::archiver::AckArchiveRecord;
 use crate::ack::redis_manager::AckStatusInfo;
+use crate::ack::watch::{AckWatchFilter, AckWatcher};
 use async_trait::async_trait;
 
 // 重新导出类型，方便外部使用
 pub use crate::ack::redis_manager::{AckStatus, AckType, ImportanceLevel};
 
+/// `AckEvent.metadata` 中携带租户ID的字段（用于按租户记录ACK阶段耗时）
+pub const ACK_TENANT_METADATA_KEY: &str = "tenant_id";
+
 /// ACK 事件
 #[derive(Debug, Clone)]
 pub struct AckEvent {
@@ -28,6 +33,52 @@ pub struct AckTimeoutEvent {
     pub timeout_at: i64,
 }
 
+/// 群聊消息全员已读事件
+#[derive(Debug, Clone)]
+pub struct GroupReadCompleteEvent {
+    pub message_id: String,
+    pub conversation_id: String,
+    pub total_recipients: u64,
+    pub completed_at: i64,
+}
+
+/// ACK 超时处理器
+///
+/// 由 ACK 服务的超时扫描任务调用，可用于触发重推、降级为离线推送或转发到消息队列
+#[async_trait]
+pub trait AckTimeoutHandler: Send + Sync {
+    /// 处理 ACK 超时事件
+    async fn on_timeout(
+        &self,
+        event: AckTimeoutEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// 群聊消息全员已读处理器
+///
+/// 在达成全员已读的那次已读ACK中调用（每条消息只触发一次），可用于通知发送者或更新消息状态
+#[async_trait]
+pub trait GroupReadCompleteHandler: Send + Sync {
+    /// 处理全员已读事件
+    async fn on_read_complete(
+        &self,
+        event: GroupReadCompleteEvent,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// ACK 归档存储
+///
+/// 由归档任务批量调用，写入失败时按配置重试，重试耗尽后丢弃该批记录；
+/// 实现需保证同一记录重复写入时幂等
+#[async_trait]
+pub trait AckArchiveSink: Send + Sync {
+    /// 批量写入归档记录
+    async fn write_batch(
+        &self,
+        records: &[AckArchiveRecord],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
 /// ACK 管理器 Trait
 ///
 /// 提供统一的 ACK 状态管理能力，供各业务模块使用
@@ -62,4 +113,25 @@ pub trait AckManager: Send + Sync {
         message_id: &str,
         user_id: &str,
     ) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// 订阅 ACK 状态变更（只接收订阅之后的变更，替代轮询 `get_ack_status`）
+    fn watch_ack_status(
+        &self,
+        _filter: AckWatchFilter,
+    ) -> Result<AckWatcher, Box<dyn std::error::Error>> {
+        Err("ACK status watch is not supported".into())
+    }
+
+    /// 查询用户已下发但仍未确认（Pending）的消息
+    ///
+    /// 按ACK时间戳升序返回时间戳不早于 `since`（秒）的记录，最多 `limit` 条（0 表示不限制），
+    /// 供推送服务重投或转离线推送
+    async fn list_pending_acks(
+        &self,
+        _user_id: &str,
+        _limit: usize,
+        _since: u64,
+    ) -> Result<Vec<AckStatusInfo>, Box<dyn std::error::Error>> {
+        Err("Pending ACK query is not supported".into())
+    }
 }
@@ -0,0 +1,180 @@
+//! ACK状态订阅
+//!
+//! ACK服务在状态发生变化时（首次记录或状态迁移）通过 tokio broadcast 广播变更事件，
+//! 订阅者按消息、用户、状态过滤后实时接收，无需轮询 `get_ack_status`：
+//! - 没有订阅者时不产生任何开销
+//! - 广播队列有界，消费过慢的订阅者会跳过最早的事件（记录到 `ack_watch_lagged_total`）
+//! - 订阅只覆盖订阅之后的变更，需要当前状态时先查询一次再订阅
+
+use std::sync::Arc;
+
+use tokio::sync::broadcast;
+
+use crate::ack::metrics::AckMetrics;
+use crate::ack::redis_manager::{AckStatus, AckStatusInfo, AckType};
+
+/// ACK状态变更事件
+#[derive(Debug, Clone)]
+pub struct AckStatusChange {
+    /// 变更后的ACK状态
+    pub ack: AckStatusInfo,
+    /// 变更前的状态（本实例首次记录该ACK时为 None）
+    pub previous_status: Option<AckStatus>,
+    /// 变更时间（秒）
+    pub changed_at: u64,
+}
+
+/// ACK状态订阅过滤条件（未设置的条件不过滤）
+#[derive(Debug, Clone, Default)]
+pub struct AckWatchFilter {
+    /// 只接收该消息的变更
+    pub message_id: Option<String>,
+    /// 只接收该用户的变更
+    pub user_id: Option<String>,
+    /// 只接收这些ACK类型的变更
+    pub ack_types: Vec<AckType>,
+    /// 只接收变更为这些状态的事件
+    pub statuses: Vec<AckStatus>,
+}
+
+impl AckWatchFilter {
+    /// 订阅某条消息的全部ACK变更
+    pub fn message(message_id: impl Into<String>) -> Self {
+        Self {
+            message_id: Some(message_id.into()),
+            ..Self::default()
+        }
+    }
+
+    /// 订阅某个用户的全部ACK变更
+    pub fn user(user_id: impl Into<String>) -> Self {
+        Self {
+            user_id: Some(user_id.into()),
+            ..Self::default()
+        }
+    }
+
+    /// 只接收这些ACK类型的变更
+    pub fn with_ack_types(mut self, ack_types: Vec<AckType>) -> Self {
+        self.ack_types = ack_types;
+        self
+    }
+
+    /// 只接收变更为这些状态的事件
+    pub fn with_statuses(mut self, statuses: Vec<AckStatus>) -> Self {
+        self.statuses = statuses;
+        self
+    }
+
+    /// 事件是否满足过滤条件
+    pub fn matches(&self, change: &AckStatusChange) -> bool {
+        let ack = &change.ack;
+        self.message_id
+            .as_ref()
+            .is_none_or(|message_id| *message_id == ack.message_id)
+            && self
+                .user_id
+                .as_ref()
+                .is_none_or(|user_id| *user_id == ack.user_id)
+            && (self.ack_types.is_empty()
+                || ack
+                    .ack_type
+                    .is_some_and(|ack_type| self.ack_types.contains(&ack_type)))
+            && (self.statuses.is_empty() || self.statuses.contains(&ack.status))
+    }
+}
+
+/// ACK状态变更广播器
+pub(crate) struct AckStatusBroadcaster {
+    sender: broadcast::Sender<Arc<AckStatusChange>>,
+}
+
+impl AckStatusBroadcaster {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    /// 是否有订阅者（没有订阅者时调用方可跳过事件构造）
+    pub(crate) fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    /// 广播状态变更（没有订阅者时直接丢弃）
+    pub(crate) fn publish(&self, change: AckStatusChange) {
+        let _ = self.sender.send(Arc::new(change));
+    }
+
+    pub(crate) fn subscribe(&self, filter: AckWatchFilter, metrics: Arc<AckMetrics>) -> AckWatcher {
+        AckWatcher {
+            receiver: self.sender.subscribe(),
+            filter,
+            metrics,
+        }
+    }
+}
+
+/// ACK状态订阅者
+pub struct AckWatcher {
+    receiver: broadcast::Receiver<Arc<AckStatusChange>>,
+    filter: AckWatchFilter,
+    metrics: Arc<AckMetrics>,
+}
+
+impl AckWatcher {
+    /// 等待下一个满足过滤条件的状态变更（ACK服务关闭后返回 None）
+    ///
+    /// 消费过慢时跳过被覆盖的事件并继续接收
+    pub async fn recv(&mut self) -> Option<Arc<AckStatusChange>> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(change) if self.filter.matches(&change) => return Some(change),
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    self.metrics.record_ack_watch_lagged(skipped);
+                    tracing::warn!(skipped, "ACK watcher lagged, skipped status changes");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// 订阅的过滤条件
+    pub fn filter(&self) -> &AckWatchFilter {
+        &self.filter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ack::redis_manager::ImportanceLevel;
+
+    fn change(message_id: &str, user_id: &str, status: AckStatus) -> AckStatusChange {
+        AckStatusChange {
+            ack: AckStatusInfo {
+                message_id: message_id.to_string(),
+                user_id: user_id.to_string(),
+                ack_type: Some(AckType::DeliveryAck),
+                status,
+                timestamp: 1,
+                importance: ImportanceLevel::Medium,
+            },
+            previous_status: None,
+            changed_at: 1,
+        }
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let filter = AckWatchFilter::message("msg_1").with_statuses(vec![AckStatus::Processed]);
+        assert!(filter.matches(&change("msg_1", "user_1", AckStatus::Processed)));
+        assert!(!filter.matches(&change("msg_1", "user_1", AckStatus::Pending)));
+        assert!(!filter.matches(&change("msg_2", "user_1", AckStatus::Processed)));
+
+        let filter = AckWatchFilter::user("user_1").with_ack_types(vec![AckType::StorageAck]);
+        assert!(!filter.matches(&change("msg_1", "user_1", AckStatus::Processed)));
+
+        assert!(AckWatchFilter::default().matches(&change("msg_3", "user_3", AckStatus::Failed)));
+    }
+}
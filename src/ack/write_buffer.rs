@@ -0,0 +1,149 @@
+//! ACK写入缓冲
+//! 单机 Redis 存储逐条写入时每条ACK都是一次往返；启用写入管道后并发的逐条写入先进入有界缓冲，
+//! 后台任务攒批（达到 `max_batch` 或 `flush_interval_ms` 到期）后通过一个 Redis 管道写入，
+//! 再把各条写入的结果（是否完成压缩）返回给等待的调用方
+
+use std::time::Duration;
+
+use redis::aio::MultiplexedConnection;
+use redis::{Client, RedisError, RedisResult};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::interval;
+
+use crate::ack::config::AckWritePipelineConfig;
+use crate::ack::redis_manager::{AckStatusInfo, RedisAckWriter};
+
+/// 缓冲中等待写入的ACK
+struct PendingAckWrite {
+    ack_info: AckStatusInfo,
+    result: oneshot::Sender<RedisResult<bool>>,
+}
+
+/// ACK写入缓冲
+///
+/// 热路径只做非阻塞入队：缓冲已满时不排队，由调用方直接写入
+pub(crate) struct AckWriteBuffer {
+    tx: mpsc::Sender<PendingAckWrite>,
+}
+
+impl AckWriteBuffer {
+    /// 创建写入缓冲并启动后台管道写入任务
+    pub(crate) fn start(
+        client: Client,
+        writer: RedisAckWriter,
+        config: &AckWritePipelineConfig,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel(config.buffer_capacity.max(1));
+        tokio::spawn(Self::run(
+            rx,
+            client,
+            writer,
+            config.max_batch.max(1),
+            Duration::from_millis(config.flush_interval_ms.max(1)),
+        ));
+        Self { tx }
+    }
+
+    /// 提交写入（非阻塞），返回等待写入结果的接收端；缓冲已满或已关闭时返回 None
+    pub(crate) fn submit(
+        &self,
+        ack_info: AckStatusInfo,
+    ) -> Option<oneshot::Receiver<RedisResult<bool>>> {
+        let (result, receiver) = oneshot::channel();
+        match self.tx.try_send(PendingAckWrite { ack_info, result }) {
+            Ok(()) => Some(receiver),
+            Err(TrySendError::Full(pending)) => {
+                tracing::trace!(
+                    message_id = %pending.ack_info.message_id,
+                    user_id = %pending.ack_info.user_id,
+                    "ACK write buffer full, writing directly"
+                );
+                None
+            }
+            Err(TrySendError::Closed(_)) => None,
+        }
+    }
+
+    /// 后台写入任务：攒批后通过管道写入，所有发送端释放后写完剩余ACK退出
+    async fn run(
+        mut rx: mpsc::Receiver<PendingAckWrite>,
+        client: Client,
+        writer: RedisAckWriter,
+        max_batch: usize,
+        flush_interval: Duration,
+    ) {
+        let mut batch = Vec::with_capacity(max_batch);
+        let mut conn = None;
+        let mut ticker = interval(flush_interval);
+
+        loop {
+            tokio::select! {
+                received = rx.recv() => match received {
+                    Some(pending) => {
+                        batch.push(pending);
+                        if batch.len() < max_batch {
+                            continue;
+                        }
+                    }
+                    None => {
+                        if !batch.is_empty() {
+                            Self::flush(&client, &writer, &mut conn, &mut batch).await;
+                        }
+                        break;
+                    }
+                },
+                _ = ticker.tick() => {
+                    if batch.is_empty() {
+                        continue;
+                    }
+                }
+            }
+            Self::flush(&client, &writer, &mut conn, &mut batch).await;
+        }
+    }
+
+    /// 写入一批ACK并通知各调用方；写入失败时丢弃连接，下一批重新建立
+    async fn flush(
+        client: &Client,
+        writer: &RedisAckWriter,
+        conn: &mut Option<MultiplexedConnection>,
+        batch: &mut Vec<PendingAckWrite>,
+    ) {
+        let (ack_infos, results): (Vec<_>, Vec<_>) = batch
+            .drain(..)
+            .map(|pending| (pending.ack_info, pending.result))
+            .unzip();
+
+        let written = match conn {
+            Some(conn) => writer.store_batch(conn, &ack_infos).await,
+            None => match client.get_multiplexed_async_connection().await {
+                Ok(new_conn) => writer.store_batch(conn.insert(new_conn), &ack_infos).await,
+                Err(e) => Err(e),
+            },
+        };
+
+        match written {
+            Ok(compacted) => {
+                for (result, compacted) in results.into_iter().zip(compacted) {
+                    let _ = result.send(Ok(compacted));
+                }
+            }
+            Err(e) => {
+                *conn = None;
+                tracing::warn!(
+                    error = %e,
+                    count = ack_infos.len(),
+                    "Failed to write ACK batch via pipeline"
+                );
+                for result in results {
+                    let _ = result.send(Err(RedisError::from((
+                        e.kind(),
+                        "ACK pipeline write failed",
+                        e.to_string(),
+                    ))));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,8 @@
+//! 认证公共模块
+//!
+//! - `TokenKeyRing`：令牌签名密钥集合，支持多密钥并存与在线轮换
+//!   （JWT 头部携带 `kid`，使用当前生效密钥签发，按 `kid` 选择验证密钥）
+
+pub mod token_keys;
+
+pub use token_keys::{SigningKeySet, TokenKeyRing, spawn_key_file_reload};
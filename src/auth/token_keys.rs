@@ -0,0 +1,434 @@
+//! 令牌签名密钥轮换
+//!
+//! 单一静态密钥在轮换时会让所有已签发令牌失效、用户被迫重新登录。
+//! `TokenKeyRing` 同时持有多把密钥：新令牌使用当前生效密钥签发并在 JWT 头部写入 `kid`，
+//! 验证时按 `kid` 选择密钥，旧密钥在令牌过期前保留即可平滑轮换。
+//! 未携带 `kid` 的历史令牌会依次尝试所有密钥，以及仅用于验证的历史单密钥（原 `token_secret`）。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, decode_header, encode,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// 兼容单密钥配置时使用的密钥ID
+pub const DEFAULT_KEY_ID: &str = "default";
+
+/// 历史单密钥（仅验证）使用的密钥ID
+pub const LEGACY_KEY_ID: &str = "legacy";
+
+/// 密钥文件中的单个密钥
+#[derive(Debug, Deserialize)]
+struct KeyFileEntry {
+    key_id: String,
+    secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyFile {
+    /// 当前用于签发的密钥ID（未设置时使用最后一个密钥）
+    #[serde(default)]
+    active_key_id: Option<String>,
+    #[serde(default)]
+    keys: Vec<KeyFileEntry>,
+}
+
+/// 签名密钥集合（不可变快照）
+///
+/// 密钥文件格式（TOML）：
+///
+/// ```toml
+/// active_key_id = "2025-02"
+///
+/// [[keys]]
+/// key_id = "2025-01"
+/// secret = "old-secret"
+///
+/// [[keys]]
+/// key_id = "2025-02"
+/// secret = "new-secret"
+/// ```
+///
+/// 轮换时先新增密钥（各实例都能验证后）再切换 `active_key_id`，旧密钥在其签发的令牌过期后移除
+#[derive(Clone)]
+pub struct SigningKeySet {
+    keys: HashMap<String, String>,
+    active_key_id: String,
+}
+
+impl std::fmt::Debug for SigningKeySet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出密钥内容
+        let mut key_ids: Vec<_> = self.keys.keys().collect();
+        key_ids.sort();
+        f.debug_struct("SigningKeySet")
+            .field("key_ids", &key_ids)
+            .field("active_key_id", &self.active_key_id)
+            .finish()
+    }
+}
+
+impl SigningKeySet {
+    /// 单密钥（兼容原有 `token_secret` 配置）
+    pub fn single(secret: impl Into<String>) -> Self {
+        Self {
+            keys: HashMap::from([(DEFAULT_KEY_ID.to_string(), secret.into())]),
+            active_key_id: DEFAULT_KEY_ID.to_string(),
+        }
+    }
+
+    /// 从密钥列表构建
+    pub fn new(keys: Vec<(String, String)>, active_key_id: Option<String>) -> Result<Self> {
+        let Some((last_key_id, _)) = keys.last() else {
+            anyhow::bail!("Signing key set must contain at least one key");
+        };
+        let active_key_id = active_key_id.unwrap_or_else(|| last_key_id.clone());
+
+        let mut map = HashMap::new();
+        for (key_id, secret) in keys {
+            if secret.is_empty() {
+                anyhow::bail!("Signing key {} has an empty secret", key_id);
+            }
+            if map.insert(key_id.clone(), secret).is_some() {
+                anyhow::bail!("Duplicate signing key_id: {}", key_id);
+            }
+        }
+        if !map.contains_key(&active_key_id) {
+            anyhow::bail!("Active signing key {} not found", active_key_id);
+        }
+
+        Ok(Self {
+            keys: map,
+            active_key_id,
+        })
+    }
+
+    /// 从密钥文件加载
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read signing key file: {}", path.display()))?;
+        Self::from_toml(&content)
+    }
+
+    /// 从TOML内容加载
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: KeyFile = toml::from_str(content).context("Failed to parse signing key file")?;
+        Self::new(
+            file.keys
+                .into_iter()
+                .map(|entry| (entry.key_id, entry.secret))
+                .collect(),
+            file.active_key_id,
+        )
+    }
+
+    pub fn active_key_id(&self) -> &str {
+        &self.active_key_id
+    }
+
+    /// 当前用于签发的密钥
+    pub fn active_secret(&self) -> &str {
+        &self.keys[&self.active_key_id]
+    }
+
+    /// 按密钥ID查找验证密钥
+    pub fn secret(&self, key_id: &str) -> Option<&str> {
+        self.keys.get(key_id).map(String::as_str)
+    }
+
+    pub fn key_ids(&self) -> impl Iterator<Item = &str> {
+        self.keys.keys().map(String::as_str)
+    }
+}
+
+/// 可在线替换的签名密钥集合
+pub struct TokenKeyRing {
+    keys: RwLock<Arc<SigningKeySet>>,
+    /// 切换到密钥文件前的单密钥：只验证未携带 `kid` 的历史令牌，不用于签发
+    legacy_secret: Option<String>,
+}
+
+impl TokenKeyRing {
+    pub fn new(keys: SigningKeySet) -> Arc<Self> {
+        Arc::new(Self {
+            keys: RwLock::new(Arc::new(keys)),
+            legacy_secret: None,
+        })
+    }
+
+    /// 保留原 `token_secret` 作为仅验证的历史密钥，切换到密钥文件前签发的令牌在过期前仍然有效
+    pub fn with_legacy_secret(keys: SigningKeySet, legacy_secret: impl Into<String>) -> Arc<Self> {
+        let legacy_secret = legacy_secret.into();
+        Arc::new(Self {
+            keys: RwLock::new(Arc::new(keys)),
+            legacy_secret: (!legacy_secret.is_empty()).then_some(legacy_secret),
+        })
+    }
+
+    /// 当前密钥集合快照
+    pub fn snapshot(&self) -> Arc<SigningKeySet> {
+        self.keys
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// 替换密钥集合（配置更新 / 远程下发）
+    pub fn replace(&self, keys: SigningKeySet) {
+        info!(
+            active_key_id = %keys.active_key_id(),
+            key_count = keys.keys.len(),
+            "Token signing keys updated"
+        );
+        *self
+            .keys
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(keys);
+    }
+
+    /// 选择验证令牌的密钥：有 `kid` 时按 `kid` 查找，否则返回全部密钥及历史单密钥（历史令牌）
+    ///
+    /// 返回 `(key_id, secret)` 列表；`kid` 未知时返回错误
+    pub fn verification_keys(&self, token: &str) -> Result<Vec<(String, String)>> {
+        let header = decode_header(token).map_err(|e| anyhow!("Invalid token header: {}", e))?;
+        let keys = self.snapshot();
+        match header.kid {
+            Some(kid) => {
+                let secret = keys
+                    .secret(&kid)
+                    .ok_or_else(|| anyhow!("Unknown token signing key: {}", kid))?;
+                Ok(vec![(kid, secret.to_string())])
+            }
+            None => {
+                // 当前生效密钥优先
+                let mut candidates = vec![(
+                    keys.active_key_id().to_string(),
+                    keys.active_secret().to_string(),
+                )];
+                candidates.extend(
+                    keys.keys
+                        .iter()
+                        .filter(|(key_id, _)| key_id.as_str() != keys.active_key_id())
+                        .map(|(key_id, secret)| (key_id.clone(), secret.clone())),
+                );
+                if let Some(legacy_secret) = &self.legacy_secret {
+                    if !candidates.iter().any(|(_, secret)| secret == legacy_secret) {
+                        candidates.push((LEGACY_KEY_ID.to_string(), legacy_secret.clone()));
+                    }
+                }
+                Ok(candidates)
+            }
+        }
+    }
+
+    /// 使用当前生效密钥签发令牌（HS256，头部写入 `kid`）
+    pub fn sign<C: Serialize>(&self, claims: &C) -> Result<String> {
+        let keys = self.snapshot();
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some(keys.active_key_id().to_string());
+        encode(
+            &header,
+            claims,
+            &EncodingKey::from_secret(keys.active_secret().as_bytes()),
+        )
+        .map_err(|e| anyhow!("Failed to sign token: {}", e))
+    }
+
+    /// 验证令牌并解析 Claims
+    pub fn verify<C: DeserializeOwned>(&self, token: &str, validation: &Validation) -> Result<C> {
+        let mut last_error = None;
+        for (_, secret) in self.verification_keys(token)? {
+            match decode::<C>(token, &DecodingKey::from_secret(secret.as_bytes()), validation) {
+                Ok(data) => return Ok(data.claims),
+                Err(err) => last_error = Some(err),
+            }
+        }
+        Err(anyhow!(
+            "Token validation failed: {}",
+            last_error.map(|e| e.to_string()).unwrap_or_default()
+        ))
+    }
+}
+
+/// 定期重新加载密钥文件，内容变化时替换密钥集合
+///
+/// 加载失败时保留原有密钥并记录告警，不影响在线验证
+pub fn spawn_key_file_reload(
+    ring: Arc<TokenKeyRing>,
+    path: impl Into<PathBuf>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    let path = path.into();
+    tokio::spawn(async move {
+        let mut last_content = tokio::fs::read_to_string(&path).await.ok();
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let content = match tokio::fs::read_to_string(&path).await {
+                Ok(content) => content,
+                Err(err) => {
+                    warn!(path = %path.display(), error = %err, "Failed to read signing key file");
+                    continue;
+                }
+            };
+            if last_content.as_deref() == Some(content.as_str()) {
+                continue;
+            }
+            match SigningKeySet::from_toml(&content) {
+                Ok(keys) => {
+                    ring.replace(keys);
+                    last_content = Some(content);
+                }
+                Err(err) => {
+                    warn!(
+                        path = %path.display(),
+                        error = %err,
+                        "Invalid signing key file, keeping previous keys"
+                    );
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Claims {
+        sub: String,
+        exp: i64,
+    }
+
+    fn claims() -> Claims {
+        Claims {
+            sub: "user-1".to_string(),
+            exp: chrono::Utc::now().timestamp() + 3600,
+        }
+    }
+
+    fn key_set(active: &str) -> SigningKeySet {
+        SigningKeySet::new(
+            vec![
+                ("k1".to_string(), "secret-1".to_string()),
+                ("k2".to_string(), "secret-2".to_string()),
+            ],
+            Some(active.to_string()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tokens_signed_before_rotation_remain_valid() {
+        let ring = TokenKeyRing::new(key_set("k1"));
+        let old_token = ring.sign(&claims()).unwrap();
+
+        ring.replace(key_set("k2"));
+        let new_token = ring.sign(&claims()).unwrap();
+        assert_eq!(decode_header(&new_token).unwrap().kid.as_deref(), Some("k2"));
+
+        let validation = Validation::new(Algorithm::HS256);
+        assert_eq!(
+            ring.verify::<Claims>(&old_token, &validation).unwrap().sub,
+            "user-1"
+        );
+        assert_eq!(
+            ring.verify::<Claims>(&new_token, &validation).unwrap().sub,
+            "user-1"
+        );
+    }
+
+    #[test]
+    fn test_removed_key_is_rejected() {
+        let ring = TokenKeyRing::new(key_set("k1"));
+        let token = ring.sign(&claims()).unwrap();
+
+        ring.replace(SigningKeySet::single("secret-3"));
+        let validation = Validation::new(Algorithm::HS256);
+        assert!(ring.verify::<Claims>(&token, &validation).is_err());
+    }
+
+    #[test]
+    fn test_legacy_token_without_kid() {
+        let token = encode(
+            &Header::new(Algorithm::HS256),
+            &claims(),
+            &EncodingKey::from_secret(b"secret-1"),
+        )
+        .unwrap();
+
+        let ring = TokenKeyRing::new(key_set("k2"));
+        let validation = Validation::new(Algorithm::HS256);
+        assert!(ring.verify::<Claims>(&token, &validation).is_ok());
+    }
+
+    #[test]
+    fn test_legacy_secret_is_verify_only() {
+        let legacy_token = encode(
+            &Header::new(Algorithm::HS256),
+            &claims(),
+            &EncodingKey::from_secret(b"token-secret"),
+        )
+        .unwrap();
+
+        let ring = TokenKeyRing::with_legacy_secret(key_set("k2"), "token-secret");
+        let validation = Validation::new(Algorithm::HS256);
+        assert!(ring.verify::<Claims>(&legacy_token, &validation).is_ok());
+
+        // 新令牌使用密钥文件中的生效密钥签发
+        let token = ring.sign(&claims()).unwrap();
+        assert_eq!(decode_header(&token).unwrap().kid.as_deref(), Some("k2"));
+        assert!(
+            TokenKeyRing::new(SigningKeySet::single("token-secret"))
+                .verify::<Claims>(&token, &validation)
+                .is_err()
+        );
+
+        // 历史密钥不能通过 kid 选中
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some(LEGACY_KEY_ID.to_string());
+        let forged = encode(
+            &header,
+            &claims(),
+            &EncodingKey::from_secret(b"token-secret"),
+        )
+        .unwrap();
+        assert!(ring.verify::<Claims>(&forged, &validation).is_err());
+    }
+
+    #[test]
+    fn test_key_file_validation() {
+        let keys = SigningKeySet::from_toml(
+            r#"
+            [[keys]]
+            key_id = "a"
+            secret = "s1"
+
+            [[keys]]
+            key_id = "b"
+            secret = "s2"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(keys.active_key_id(), "b");
+
+        // 生效密钥不存在
+        assert!(
+            SigningKeySet::from_toml(
+                "active_key_id = \"x\"\n[[keys]]\nkey_id = \"a\"\nsecret = \"s\""
+            )
+            .is_err()
+        );
+        assert!(SigningKeySet::from_toml("keys = []").is_err());
+    }
+}
@@ -0,0 +1,51 @@
+//! 配置值加密工具
+//!
+//! 使用 `FLARE_CONFIG_KEY` / `FLARE_CONFIG_KEY_FILE` 中的配置密钥加密明文，输出可直接写入
+//! 配置文件或配置中心的 `enc:<base64>` 值；未传参数时从标准输入读取明文：
+//!
+//! ```bash
+//! FLARE_CONFIG_KEY=... cargo run --bin encrypt-config --features encryption -- '<明文>'
+//! ```
+
+use std::io::Read;
+use std::process::ExitCode;
+
+use anyhow::{Context, Result, anyhow};
+use flare_im_core::ConfigCipher;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(value) => {
+            println!("{value}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("failed to encrypt config value: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<String> {
+    let plaintext = match std::env::args().nth(1) {
+        Some(plaintext) => plaintext,
+        None => {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .context("failed to read plaintext from stdin")?;
+            input.trim_end_matches(['\r', '\n']).to_string()
+        }
+    };
+    if plaintext.is_empty() {
+        return Err(anyhow!("plaintext is empty"));
+    }
+
+    let cipher = ConfigCipher::from_env()?.ok_or_else(|| {
+        anyhow!("no config key is set (FLARE_CONFIG_KEY or FLARE_CONFIG_KEY_FILE)")
+    })?;
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(cipher.encrypt(&plaintext))
+}
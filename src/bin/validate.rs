@@ -0,0 +1,31 @@
+//! 配置校验工具
+//!
+//! 加载配置（与服务启动时相同的合并、配置中心与密钥解析流程）并列出所有问题，
+//! 存在问题时以非零状态退出，可在发布流水线中提前发现错误配置：
+//!
+//! ```bash
+//! cargo run --bin validate -- config
+//! ```
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let path = std::env::args().nth(1);
+
+    let config = match flare_im_core::load_config_from(path.as_deref()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("failed to load configuration: {err:#}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let report = config.validate();
+    if report.is_ok() {
+        println!("{report}");
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("{report}");
+        ExitCode::FAILURE
+    }
+}
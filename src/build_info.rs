@@ -0,0 +1,497 @@
+//! 构建信息与服务信息查询
+//!
+//! 每个服务启动时安装自身的 [`BuildInfo`]（服务版本、flare-im-core 版本、git 提交、构建时间、
+//! 启用的 feature），用于：
+//! - 统一格式的启动日志
+//! - 服务注册元数据（`build.*` 标签），注册中心即可看到每个实例运行的版本
+//! - gRPC `GetServerInfo` 查询（额外返回当前配置指纹），排障时确认实例实际运行的构建与配置
+//! - gRPC `ListConfigReloads` 查询最近的配置重新加载记录（触发来源、脱敏后的变更明细、失败原因）
+//!
+//! gRPC 接口定义（手写实现，不依赖 flare-proto 代码生成，客户端可按此生成）：
+//!
+//! ```protobuf
+//! syntax = "proto3";
+//! package flare.admin;
+//!
+//! service ServerInfoService {
+//!   rpc GetServerInfo(GetServerInfoRequest) returns (ServerInfo);
+//!   rpc ListConfigReloads(ListConfigReloadsRequest) returns (ListConfigReloadsResponse);
+//! }
+//!
+//! message GetServerInfoRequest {}
+//!
+//! message ServerInfo {
+//!   string service = 1;
+//!   string version = 2;
+//!   string core_version = 3;
+//!   string git_sha = 4;
+//!   string build_time = 5;
+//!   repeated string features = 6;
+//!   string config_fingerprint = 7;
+//!   string started_at = 8;
+//! }
+//!
+//! message ListConfigReloadsRequest {
+//!   uint32 limit = 1; // 0 表示全部
+//! }
+//!
+//! message ListConfigReloadsResponse {
+//!   repeated ConfigReload reloads = 1; // 最新的在前
+//! }
+//!
+//! message ConfigReload {
+//!   uint64 version = 1;
+//!   string reloaded_at = 2;
+//!   string trigger = 3;
+//!   string outcome = 4; // applied / failed
+//!   string error = 5;
+//!   string config_fingerprint = 6;
+//!   repeated ConfigKeyChange changes = 7;
+//! }
+//!
+//! message ConfigKeyChange {
+//!   string key = 1;
+//!   string change = 2; // added / removed / modified
+//!   string old_value = 3;
+//!   string new_value = 4;
+//! }
+//! ```
+//!
+//! # 示例
+//! ```rust,ignore
+//! init_tracing_from_config(None);
+//! flare_im_core::build_info!(CONVERSATION).install();
+//!
+//! Server::builder()
+//!     .add_service(ServerInfoServer::from_global())
+//!     .add_service(conversation_service);
+//! ```
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::sync::OnceLock;
+use std::task::{Context, Poll};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use prost::Message;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::{Body, BoxFuture, Service, StdError, http};
+use tonic::server::{Grpc, NamedService, UnaryService};
+use tonic::{Request, Response, Status};
+
+use crate::config::config_fingerprint;
+
+/// flare-im-core 的 git 提交（由 build.rs 注入）
+pub const GIT_SHA: &str = env!("FLARE_GIT_SHA");
+/// flare-im-core 版本
+pub const CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
+const BUILD_TIMESTAMP: &str = env!("FLARE_BUILD_TIMESTAMP");
+
+/// gRPC 服务名
+pub const SERVER_INFO_SERVICE_NAME: &str = "flare.admin.ServerInfoService";
+const GET_SERVER_INFO_PATH: &str = "/flare.admin.ServerInfoService/GetServerInfo";
+const LIST_CONFIG_RELOADS_PATH: &str = "/flare.admin.ServerInfoService/ListConfigReloads";
+
+static GLOBAL_BUILD_INFO: OnceLock<BuildInfo> = OnceLock::new();
+
+/// 使用调用方 crate 的版本构建 [`BuildInfo`]
+#[macro_export]
+macro_rules! build_info {
+    ($service:expr) => {
+        $crate::build_info::BuildInfo::new($service, env!("CARGO_PKG_VERSION"))
+    };
+}
+
+/// 服务构建信息
+#[derive(Debug, Clone)]
+pub struct BuildInfo {
+    pub service: String,
+    pub version: String,
+    pub core_version: &'static str,
+    pub git_sha: &'static str,
+    pub build_time: String,
+    /// flare-im-core 启用的 feature
+    pub features: Vec<&'static str>,
+    pub started_at: DateTime<Utc>,
+}
+
+impl BuildInfo {
+    pub fn new(service: impl Into<String>, version: impl Into<String>) -> Self {
+        let build_time = BUILD_TIMESTAMP
+            .parse::<i64>()
+            .ok()
+            .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            .map(|ts| ts.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .unwrap_or_else(|| "unknown".to_string());
+
+        Self {
+            service: service.into(),
+            version: version.into(),
+            core_version: CORE_VERSION,
+            git_sha: GIT_SHA,
+            build_time,
+            features: enabled_features(),
+            started_at: Utc::now(),
+        }
+    }
+
+    /// 安装为全局构建信息并输出启动日志（只有第一次调用生效）
+    pub fn install(self) -> &'static BuildInfo {
+        let mut installed = false;
+        let info = GLOBAL_BUILD_INFO.get_or_init(|| {
+            installed = true;
+            self
+        });
+        if installed {
+            info.log_banner();
+        }
+        info
+    }
+
+    /// 全局构建信息（未安装时返回 None）
+    pub fn global() -> Option<&'static BuildInfo> {
+        GLOBAL_BUILD_INFO.get()
+    }
+
+    /// 统一格式的启动日志
+    pub fn log_banner(&self) {
+        tracing::info!(
+            service = %self.service,
+            version = %self.version,
+            core_version = %self.core_version,
+            git_sha = %self.git_sha,
+            build_time = %self.build_time,
+            features = %self.features.join(","),
+            "🚀 Starting {} v{} ({})",
+            self.service,
+            self.version,
+            self.git_sha
+        );
+    }
+
+    /// 服务注册元数据（加入当前配置指纹）
+    pub fn registry_metadata(&self) -> HashMap<String, String> {
+        let mut metadata = HashMap::from([
+            ("build.version".to_string(), self.version.clone()),
+            (
+                "build.core_version".to_string(),
+                self.core_version.to_string(),
+            ),
+            ("build.git_sha".to_string(), self.git_sha.to_string()),
+            ("build.time".to_string(), self.build_time.clone()),
+        ]);
+        if let Some(fingerprint) = config_fingerprint() {
+            metadata.insert("build.config_fingerprint".to_string(), fingerprint);
+        }
+        metadata
+    }
+
+    /// `GetServerInfo` 的响应内容
+    pub fn server_info(&self) -> ServerInfo {
+        ServerInfo {
+            service: self.service.clone(),
+            version: self.version.clone(),
+            core_version: self.core_version.to_string(),
+            git_sha: self.git_sha.to_string(),
+            build_time: self.build_time.clone(),
+            features: self.features.iter().map(|f| f.to_string()).collect(),
+            config_fingerprint: config_fingerprint().unwrap_or_default(),
+            started_at: self.started_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+}
+
+/// flare-im-core 当前启用的 feature
+fn enabled_features() -> Vec<&'static str> {
+    [
+        ("ack", cfg!(feature = "ack")),
+        ("auth", cfg!(feature = "auth")),
+        ("config-center", cfg!(feature = "config-center")),
+        ("config-watch", cfg!(feature = "config-watch")),
+        ("encryption", cfg!(feature = "encryption")),
+        ("metrics", cfg!(feature = "metrics")),
+        ("redis", cfg!(feature = "redis")),
+        ("secrets", cfg!(feature = "secrets")),
+        ("tracing", cfg!(feature = "tracing")),
+        ("webhook", cfg!(feature = "webhook")),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect()
+}
+
+/// `GetServerInfo` 请求
+#[derive(Clone, PartialEq, Message)]
+pub struct GetServerInfoRequest {}
+
+/// `GetServerInfo` 响应
+#[derive(Clone, PartialEq, Message)]
+pub struct ServerInfo {
+    #[prost(string, tag = "1")]
+    pub service: String,
+    #[prost(string, tag = "2")]
+    pub version: String,
+    #[prost(string, tag = "3")]
+    pub core_version: String,
+    #[prost(string, tag = "4")]
+    pub git_sha: String,
+    #[prost(string, tag = "5")]
+    pub build_time: String,
+    #[prost(string, repeated, tag = "6")]
+    pub features: Vec<String>,
+    #[prost(string, tag = "7")]
+    pub config_fingerprint: String,
+    #[prost(string, tag = "8")]
+    pub started_at: String,
+}
+
+/// `ListConfigReloads` 请求
+#[derive(Clone, PartialEq, Message)]
+pub struct ListConfigReloadsRequest {
+    /// 返回条数（0 表示全部）
+    #[prost(uint32, tag = "1")]
+    pub limit: u32,
+}
+
+/// `ListConfigReloads` 响应（最新的在前）
+#[derive(Clone, PartialEq, Message)]
+pub struct ListConfigReloadsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub reloads: Vec<ConfigReload>,
+}
+
+/// 一次配置重新加载
+#[derive(Clone, PartialEq, Message)]
+pub struct ConfigReload {
+    #[prost(uint64, tag = "1")]
+    pub version: u64,
+    #[prost(string, tag = "2")]
+    pub reloaded_at: String,
+    #[prost(string, tag = "3")]
+    pub trigger: String,
+    #[prost(string, tag = "4")]
+    pub outcome: String,
+    #[prost(string, tag = "5")]
+    pub error: String,
+    #[prost(string, tag = "6")]
+    pub config_fingerprint: String,
+    #[prost(message, repeated, tag = "7")]
+    pub changes: Vec<ConfigKeyChange>,
+}
+
+/// 单个配置键的变更（值已脱敏）
+#[derive(Clone, PartialEq, Message)]
+pub struct ConfigKeyChange {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(string, tag = "2")]
+    pub change: String,
+    #[prost(string, tag = "3")]
+    pub old_value: String,
+    #[prost(string, tag = "4")]
+    pub new_value: String,
+}
+
+#[cfg(feature = "config-watch")]
+impl From<crate::config::ConfigReloadRecord> for ConfigReload {
+    fn from(record: crate::config::ConfigReloadRecord) -> Self {
+        let error = match &record.outcome {
+            crate::config::ReloadOutcome::Failed(error) => error.clone(),
+            crate::config::ReloadOutcome::Applied => String::new(),
+        };
+        Self {
+            version: record.version,
+            reloaded_at: record
+                .reloaded_at
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            trigger: record.trigger.to_string(),
+            outcome: record.outcome.as_str().to_string(),
+            error,
+            config_fingerprint: record.fingerprint.unwrap_or_default(),
+            changes: record
+                .changes
+                .into_iter()
+                .map(|change| ConfigKeyChange {
+                    key: change.key,
+                    change: change.kind.as_str().to_string(),
+                    old_value: change.old_value.unwrap_or_default(),
+                    new_value: change.new_value.unwrap_or_default(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 最近的配置重新加载记录（未启用 `config-watch` feature 时为空）
+fn list_config_reloads(limit: u32) -> ListConfigReloadsResponse {
+    #[cfg(feature = "config-watch")]
+    let reloads = crate::config::recent_reloads(limit as usize)
+        .into_iter()
+        .map(ConfigReload::from)
+        .collect();
+    #[cfg(not(feature = "config-watch"))]
+    let reloads = {
+        let _ = limit;
+        Vec::new()
+    };
+    ListConfigReloadsResponse { reloads }
+}
+
+/// `flare.admin.ServerInfoService` gRPC 服务，可直接加入各服务的 tonic `Server`
+#[derive(Debug, Clone)]
+pub struct ServerInfoServer {
+    info: BuildInfo,
+}
+
+impl ServerInfoServer {
+    pub fn new(info: BuildInfo) -> Self {
+        Self { info }
+    }
+
+    /// 使用全局构建信息（未安装时使用 flare-im-core 自身的版本）
+    pub fn from_global() -> Self {
+        let info = BuildInfo::global()
+            .cloned()
+            .unwrap_or_else(|| BuildInfo::new("unknown", CORE_VERSION));
+        Self::new(info)
+    }
+}
+
+impl NamedService for ServerInfoServer {
+    const NAME: &'static str = SERVER_INFO_SERVICE_NAME;
+}
+
+struct GetServerInfoMethod(BuildInfo);
+
+struct ListConfigReloadsMethod;
+
+impl UnaryService<ListConfigReloadsRequest> for ListConfigReloadsMethod {
+    type Response = ListConfigReloadsResponse;
+    type Future = BoxFuture<Response<ListConfigReloadsResponse>, Status>;
+
+    fn call(&mut self, request: Request<ListConfigReloadsRequest>) -> Self::Future {
+        let response = list_config_reloads(request.into_inner().limit);
+        Box::pin(async move { Ok(Response::new(response)) })
+    }
+}
+
+impl UnaryService<GetServerInfoRequest> for GetServerInfoMethod {
+    type Response = ServerInfo;
+    type Future = BoxFuture<Response<ServerInfo>, Status>;
+
+    fn call(&mut self, _request: Request<GetServerInfoRequest>) -> Self::Future {
+        let info = self.0.server_info();
+        Box::pin(async move { Ok(Response::new(info)) })
+    }
+}
+
+impl<B> Service<http::Request<B>> for ServerInfoServer
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<tonic::body::Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        match req.uri().path() {
+            GET_SERVER_INFO_PATH => {
+                let method = GetServerInfoMethod(self.info.clone());
+                Box::pin(async move {
+                    let mut grpc =
+                        Grpc::new(ProstCodec::<ServerInfo, GetServerInfoRequest>::default());
+                    Ok(grpc.unary(method, req).await)
+                })
+            }
+            LIST_CONFIG_RELOADS_PATH => Box::pin(async move {
+                let mut grpc = Grpc::new(ProstCodec::<
+                    ListConfigReloadsResponse,
+                    ListConfigReloadsRequest,
+                >::default());
+                Ok(grpc.unary(ListConfigReloadsMethod, req).await)
+            }),
+            _ => Box::pin(async move { Ok(Status::unimplemented("").into_http()) }),
+        }
+    }
+}
+
+/// protobuf 编解码（与 tonic 代码生成使用的编解码一致）
+struct ProstCodec<E, D>(PhantomData<(E, D)>);
+
+impl<E, D> Default for ProstCodec<E, D> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<E, D> Codec for ProstCodec<E, D>
+where
+    E: Message + Send + 'static,
+    D: Message + Default + Send + 'static,
+{
+    type Encode = E;
+    type Decode = D;
+    type Encoder = ProstEncoder<E>;
+    type Decoder = ProstDecoder<D>;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        ProstEncoder(PhantomData)
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        ProstDecoder(PhantomData)
+    }
+}
+
+struct ProstEncoder<E>(PhantomData<E>);
+
+impl<E: Message> Encoder for ProstEncoder<E> {
+    type Item = E;
+    type Error = Status;
+
+    fn encode(&mut self, item: E, dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        item.encode(dst)
+            .map_err(|e| Status::internal(format!("failed to encode message: {e}")))
+    }
+}
+
+struct ProstDecoder<D>(PhantomData<D>);
+
+impl<D: Message + Default> Decoder for ProstDecoder<D> {
+    type Item = D;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<D>, Status> {
+        D::decode(src)
+            .map(Some)
+            .map_err(|e| Status::internal(format!("failed to decode message: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_info_roundtrip() {
+        let info = BuildInfo::new("flare-test", "1.2.3");
+        assert_eq!(info.core_version, CORE_VERSION);
+        assert!(!info.git_sha.is_empty());
+
+        let metadata = info.registry_metadata();
+        assert_eq!(metadata["build.version"], "1.2.3");
+        assert_eq!(metadata["build.git_sha"], info.git_sha);
+
+        let encoded = info.server_info().encode_to_vec();
+        let decoded = ServerInfo::decode(encoded.as_slice()).unwrap();
+        assert_eq!(decoded.service, "flare-test");
+        assert_eq!(decoded.version, "1.2.3");
+        assert_eq!(decoded.build_time, info.build_time);
+    }
+}
@@ -0,0 +1,43 @@
+//! golden fixture 读取
+
+use std::path::PathBuf;
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/compat")
+        .join(name)
+}
+
+/// 读取 proto golden fixture（十六进制文本），缺失时直接失败
+pub(super) fn load_proto_fixture(name: &str) -> Vec<u8> {
+    let path = fixture_path(name);
+    let content = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "failed to read golden fixture {}: {}; generate it from the baseline flare-proto with \
+             `cargo test -p flare-im-core generate_proto_fixtures -- --ignored` and commit it",
+            path.display(),
+            e
+        )
+    });
+    hex::decode(content.trim())
+        .unwrap_or_else(|e| panic!("invalid hex in fixture {}: {}", path.display(), e))
+}
+
+/// 写入 proto golden fixture，返回是否写入（已存在的 fixture 永不覆盖）
+pub(super) fn write_proto_fixture(name: &str, bytes: &[u8]) -> bool {
+    let path = fixture_path(name);
+    if path.exists() {
+        return false;
+    }
+    std::fs::create_dir_all(path.parent().expect("fixture directory"))
+        .expect("failed to create fixture directory");
+    std::fs::write(&path, format!("{}\n", hex::encode(bytes))).expect("failed to write fixture");
+    true
+}
+
+/// 读取 JSON golden fixture
+pub(super) fn load_json_fixture(name: &str) -> String {
+    let path = fixture_path(name);
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read fixture {}: {}", path.display(), e))
+}
@@ -0,0 +1,89 @@
+//! Hook 载荷兼容性测试
+//!
+//! Hook 类型以 serde JSON 形式发送给 WebHook 并写入死信队列，
+//! 新增字段必须带 `#[serde(default)]`，否则历史载荷无法解码。
+
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::fixtures::load_json_fixture;
+use crate::hooks::{DeliveryEvent, MessageDraft, MessageRecord, RecallEvent};
+
+/// 解码 fixture，并校验重新序列化后与 fixture 等价（字段未丢失、未改名）
+fn decode_fixture<T: Serialize + DeserializeOwned>(name: &str) -> T {
+    let content = load_json_fixture(name);
+    let decoded: T = serde_json::from_str(&content)
+        .unwrap_or_else(|e| panic!("failed to decode fixture {}: {}", name, e));
+    let expected: Value = serde_json::from_str(&content).expect("fixture is not valid JSON");
+    assert_eq!(
+        serde_json::to_value(&decoded).expect("failed to encode"),
+        expected,
+        "fixture {} does not round-trip",
+        name
+    );
+    decoded
+}
+
+fn at(secs: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+#[test]
+fn test_message_draft_compat() {
+    let draft: MessageDraft = decode_fixture("hook_message_draft.json");
+    assert_eq!(draft.message_id.as_deref(), Some("msg-compat-0001"));
+    assert_eq!(draft.client_message_id.as_deref(), Some("client-compat-0001"));
+    assert_eq!(draft.conversation_id.as_deref(), Some("conv-compat-0001"));
+    assert_eq!(draft.payload, b"hello");
+    assert_eq!(draft.headers["x-trace-id"], "trace-compat");
+    assert_eq!(draft.metadata["message_type"], "text");
+    assert_eq!(draft.extra["priority"], 5);
+}
+
+#[test]
+fn test_message_record_compat() {
+    let record: MessageRecord = decode_fixture("hook_message_record.json");
+    assert_eq!(record.message_id, "msg-compat-0001");
+    assert_eq!(record.sender_id, "user-compat-sender");
+    assert_eq!(record.conversation_type.as_deref(), Some("single"));
+    assert_eq!(record.persisted_at, at(1_700_000_000));
+    assert_eq!(record.metadata["seq"], "42");
+}
+
+#[test]
+fn test_delivery_event_compat() {
+    let event: DeliveryEvent = decode_fixture("hook_delivery_event.json");
+    assert_eq!(event.message_id, "msg-compat-0001");
+    assert_eq!(event.user_id, "user-compat-receiver");
+    assert_eq!(event.channel, "websocket");
+    assert_eq!(event.delivered_at, at(1_700_000_001));
+    assert_eq!(event.metadata["device_id"], "device-compat");
+}
+
+#[test]
+fn test_recall_event_compat() {
+    let event: RecallEvent = decode_fixture("hook_recall_event.json");
+    assert_eq!(event.message_id, "msg-compat-0001");
+    assert_eq!(event.operator_id, "user-compat-sender");
+    assert_eq!(event.recalled_at, at(1_700_000_002));
+    assert_eq!(event.metadata["reason"], "typo");
+}
+
+/// 早期载荷没有 map 字段，缺省时应按空处理
+#[test]
+fn test_hook_payload_optional_maps_default() {
+    let draft: MessageDraft = serde_json::from_str(
+        r#"{"message_id":null,"client_message_id":null,"conversation_id":null,"payload":[]}"#,
+    )
+    .expect("failed to decode minimal draft");
+    assert!(draft.headers.is_empty() && draft.metadata.is_empty() && draft.extra.is_empty());
+
+    let event: DeliveryEvent = serde_json::from_str(
+        r#"{"message_id":"m","user_id":"u","channel":"c","delivered_at":{"secs_since_epoch":0,"nanos_since_epoch":0}}"#,
+    )
+    .expect("failed to decode minimal delivery event");
+    assert!(event.metadata.is_empty());
+}
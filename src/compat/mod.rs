@@ -0,0 +1,15 @@
+//! 协议兼容性测试
+//!
+//! 用 golden fixture 固定已持久化数据的编码：存储的消息、Kafka 消息体和 Hook 载荷。
+//! proto 或模型变更导致历史数据无法解码时，对应测试失败。
+//!
+//! - proto fixture（`tests/fixtures/compat/*.hex`）：在基线 proto 上运行
+//!   `cargo test -p flare-im-core generate_proto_fixtures -- --ignored` 生成并提交；读取时缺失直接失败
+//! - Hook 载荷 fixture（`tests/fixtures/compat/*.json`）：手工维护
+//!
+//! 已提交的 fixture 不允许修改。确需不兼容变更时，新增带版本后缀的 fixture 并保留旧文件，
+//! 同时在消费端兼容旧格式。
+
+mod fixtures;
+mod hook_payloads;
+mod proto_messages;
@@ -0,0 +1,235 @@
+//! proto 兼容性测试
+//!
+//! 覆盖写入 Redis/Postgres 的消息和 Kafka 消息体：
+//! - `stored_message.hex`：`common::Message`（消息缓存、推送载荷）
+//! - `stored_message_content.hex`：`common::MessageContent`（Postgres content 列）
+//! - `kafka_store_message_request.hex`：`storage::StoreMessageRequest`（存储主题）
+//! - `kafka_push_message_request.hex`：`push::PushMessageRequest`（推送主题）
+//! - `kafka_push_ack_request.hex`：`flare::push::v1::PushAckRequest`（ACK 主题）
+//!
+//! 样例只使用单元素 map，保证编码结果确定。fixture 由 `generate_proto_fixtures`（默认忽略）
+//! 按基线 proto 生成后提交，读取时缺失直接失败。
+
+use std::collections::HashMap;
+
+use flare_proto::common::message_content::Content;
+use flare_proto::common::{
+    AckStatus, ConversationType, Message, MessageContent, MessageType, SendEnvelopeAck,
+    TenantContext, TextContent,
+};
+use flare_proto::flare::push::v1::PushAckRequest;
+use flare_proto::push::PushMessageRequest;
+use flare_proto::storage::StoreMessageRequest;
+use prost::Message as _;
+
+use super::fixtures::{load_proto_fixture, write_proto_fixture};
+
+const MESSAGE_ID: &str = "msg-compat-0001";
+const CONVERSATION_ID: &str = "conv-compat-0001";
+const SENDER_ID: &str = "user-compat-sender";
+const RECEIVER_ID: &str = "user-compat-receiver";
+const TENANT_ID: &str = "tenant-compat";
+const TEXT: &str = "hello, compat";
+
+fn sample_content() -> MessageContent {
+    MessageContent {
+        content: Some(Content::Text(TextContent {
+            text: TEXT.to_string(),
+            mentions: vec![],
+        })),
+        ..Default::default()
+    }
+}
+
+fn sample_tenant() -> TenantContext {
+    TenantContext {
+        tenant_id: TENANT_ID.to_string(),
+        business_type: "im".to_string(),
+        ..Default::default()
+    }
+}
+
+fn sample_message() -> Message {
+    Message {
+        server_id: MESSAGE_ID.to_string(),
+        conversation_id: CONVERSATION_ID.to_string(),
+        client_msg_id: "client-compat-0001".to_string(),
+        sender_id: SENDER_ID.to_string(),
+        receiver_id: RECEIVER_ID.to_string(),
+        seq: 42,
+        timestamp: Some(prost_types::Timestamp {
+            seconds: 1_700_000_000,
+            nanos: 0,
+        }),
+        conversation_type: ConversationType::Single as i32,
+        message_type: MessageType::Text as i32,
+        content: Some(sample_content()),
+        extra: HashMap::from([("seq".to_string(), "42".to_string())]),
+        tenant: Some(sample_tenant()),
+        ..Default::default()
+    }
+}
+
+fn sample_store_message_request() -> StoreMessageRequest {
+    StoreMessageRequest {
+        conversation_id: CONVERSATION_ID.to_string(),
+        message: Some(sample_message()),
+        sync: false,
+        tenant: Some(sample_tenant()),
+        ..Default::default()
+    }
+}
+
+fn sample_push_message_request() -> PushMessageRequest {
+    PushMessageRequest {
+        user_ids: vec![RECEIVER_ID.to_string()],
+        message: Some(sample_message()),
+        tenant: Some(sample_tenant()),
+        ..Default::default()
+    }
+}
+
+fn sample_push_ack_request() -> PushAckRequest {
+    PushAckRequest {
+        target_user_ids: vec![SENDER_ID.to_string()],
+        ack: Some(SendEnvelopeAck {
+            server_msg_id: MESSAGE_ID.to_string(),
+            seq: 42,
+            status: AckStatus::Success as i32,
+            ..Default::default()
+        }),
+        request_id: "ack-compat-0001".to_string(),
+        ..Default::default()
+    }
+}
+
+/// fixture 文件名与样例编码
+fn proto_fixtures() -> [(&'static str, Vec<u8>); 5] {
+    [
+        ("stored_message.hex", sample_message().encode_to_vec()),
+        (
+            "stored_message_content.hex",
+            sample_content().encode_to_vec(),
+        ),
+        (
+            "kafka_store_message_request.hex",
+            sample_store_message_request().encode_to_vec(),
+        ),
+        (
+            "kafka_push_message_request.hex",
+            sample_push_message_request().encode_to_vec(),
+        ),
+        (
+            "kafka_push_ack_request.hex",
+            sample_push_ack_request().encode_to_vec(),
+        ),
+    ]
+}
+
+fn assert_sample_content(content: Option<&MessageContent>) {
+    match content.and_then(|c| c.content.as_ref()) {
+        Some(Content::Text(text)) => assert_eq!(text.text, TEXT),
+        other => panic!("unexpected message content: {:?}", other),
+    }
+}
+
+fn assert_sample_message(message: &Message) {
+    assert_eq!(message.server_id, MESSAGE_ID);
+    assert_eq!(message.conversation_id, CONVERSATION_ID);
+    assert_eq!(message.client_msg_id, "client-compat-0001");
+    assert_eq!(message.sender_id, SENDER_ID);
+    assert_eq!(message.receiver_id, RECEIVER_ID);
+    assert_eq!(message.seq, 42);
+    assert_eq!(
+        message.timestamp.as_ref().map(|ts| ts.seconds),
+        Some(1_700_000_000)
+    );
+    assert_eq!(message.conversation_type, ConversationType::Single as i32);
+    assert_eq!(message.message_type, MessageType::Text as i32);
+    assert_sample_content(message.content.as_ref());
+    assert_eq!(message.extra.get("seq").map(String::as_str), Some("42"));
+    assert_eq!(
+        message.tenant.as_ref().map(|t| t.tenant_id.as_str()),
+        Some(TENANT_ID)
+    );
+}
+
+/// 解码后再次编码、解码，结果应与首次解码一致
+fn assert_round_trip<M: prost::Message + Default + PartialEq + std::fmt::Debug>(decoded: &M) {
+    let reencoded = M::decode(decoded.encode_to_vec().as_slice()).expect("re-decode failed");
+    assert_eq!(&reencoded, decoded);
+}
+
+#[test]
+fn test_stored_message_compat() {
+    let bytes = load_proto_fixture("stored_message.hex");
+    let message = Message::decode(bytes.as_slice()).expect("failed to decode stored Message");
+    assert_sample_message(&message);
+    assert_round_trip(&message);
+}
+
+#[test]
+fn test_stored_message_content_compat() {
+    let bytes = load_proto_fixture("stored_message_content.hex");
+    let content =
+        MessageContent::decode(bytes.as_slice()).expect("failed to decode stored MessageContent");
+    assert_sample_content(Some(&content));
+    assert_round_trip(&content);
+}
+
+#[test]
+fn test_kafka_store_message_request_compat() {
+    let bytes = load_proto_fixture("kafka_store_message_request.hex");
+    let request = StoreMessageRequest::decode(bytes.as_slice())
+        .expect("failed to decode StoreMessageRequest");
+    assert_eq!(request.conversation_id, CONVERSATION_ID);
+    assert!(!request.sync);
+    assert_sample_message(request.message.as_ref().expect("message is missing"));
+    assert_eq!(
+        request.tenant.as_ref().map(|t| t.tenant_id.as_str()),
+        Some(TENANT_ID)
+    );
+    assert_round_trip(&request);
+}
+
+#[test]
+fn test_kafka_push_message_request_compat() {
+    let bytes = load_proto_fixture("kafka_push_message_request.hex");
+    let request =
+        PushMessageRequest::decode(bytes.as_slice()).expect("failed to decode PushMessageRequest");
+    assert_eq!(request.user_ids, vec![RECEIVER_ID.to_string()]);
+    assert_sample_message(request.message.as_ref().expect("message is missing"));
+    assert_eq!(
+        request.tenant.as_ref().map(|t| t.tenant_id.as_str()),
+        Some(TENANT_ID)
+    );
+    assert_round_trip(&request);
+}
+
+#[test]
+fn test_kafka_push_ack_request_compat() {
+    let bytes = load_proto_fixture("kafka_push_ack_request.hex");
+    let request =
+        PushAckRequest::decode(bytes.as_slice()).expect("failed to decode PushAckRequest");
+    assert_eq!(request.target_user_ids, vec![SENDER_ID.to_string()]);
+    assert_eq!(request.request_id, "ack-compat-0001");
+    let ack = request.ack.as_ref().expect("ack is missing");
+    assert_eq!(ack.server_msg_id, MESSAGE_ID);
+    assert_eq!(ack.seq, 42);
+    assert_eq!(ack.status, AckStatus::Success as i32);
+    assert_round_trip(&request);
+}
+
+/// 按当前 proto 生成缺失的 fixture（只在基线 proto 上运行一次，已存在的 fixture 不覆盖）
+#[test]
+#[ignore = "writes golden fixtures, run explicitly against the baseline proto"]
+fn generate_proto_fixtures() {
+    for (name, bytes) in proto_fixtures() {
+        if write_proto_fixture(name, &bytes) {
+            eprintln!(
+                "generated golden fixture {}, commit it to the repository",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,388 @@
+//! 配置中心后端（`config-center` feature）
+//!
+//! 从 etcd / Nacos 拉取指定前缀下的 TOML 配置片段，按与本地配置目录相同的层级
+//! （base → shared → services → overrides，同层按名称排序）合并：
+//!
+//! - etcd：键 `{prefix}/base`、`{prefix}/services/push_server.toml` 等
+//! - Nacos：dataId `{prefix}.base`、`{prefix}.services.push_server.toml` 等
+//!
+//! 通过环境变量启用：
+//! - `FLARE_CONFIG_CENTER`：`etcd://host1:2379,host2:2379` 或 `nacos://host:8848?namespace=dev&group=FLARE`
+//! - `FLARE_CONFIG_CENTER_PREFIX`：键前缀（默认 etcd 为 `/flare/config`，Nacos 为 `flare`），
+//!   不同服务可使用各自的前缀
+//! - `FLARE_CONFIG_CENTER_TIMEOUT_MS`：拉取超时（默认 3000ms）
+
+use std::env;
+use std::time::Duration;
+
+use anyhow::{Context as AnyhowContext, Result, anyhow};
+use serde::Deserialize;
+use toml::Value;
+use tracing::{debug, info};
+
+use super::interpolate::interpolate_env;
+use super::merge_value;
+
+/// 配置中心地址环境变量
+pub const CONFIG_CENTER_ENV: &str = "FLARE_CONFIG_CENTER";
+/// 配置键前缀环境变量
+pub const CONFIG_CENTER_PREFIX_ENV: &str = "FLARE_CONFIG_CENTER_PREFIX";
+/// 拉取超时环境变量（毫秒）
+pub const CONFIG_CENTER_TIMEOUT_ENV: &str = "FLARE_CONFIG_CENTER_TIMEOUT_MS";
+
+/// 默认拉取超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(3000);
+/// Nacos 默认分组
+const DEFAULT_NACOS_GROUP: &str = "DEFAULT_GROUP";
+/// Nacos 列表接口单页大小
+const NACOS_PAGE_SIZE: usize = 500;
+
+/// 配置片段层级（与配置目录的合并顺序一致）
+const LAYERS: [&str; 3] = ["shared", "services", "overrides"];
+
+/// 配置中心后端
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigCenterBackend {
+    /// etcd（v3 API）
+    Etcd { endpoints: Vec<String> },
+    /// Nacos（v1 Open API）
+    Nacos {
+        server: String,
+        namespace: Option<String>,
+        group: String,
+    },
+}
+
+/// 配置中心配置源
+#[derive(Debug, Clone)]
+pub struct ConfigCenterSource {
+    endpoint: String,
+    backend: ConfigCenterBackend,
+    prefix: String,
+    timeout: Duration,
+}
+
+impl ConfigCenterSource {
+    /// 解析配置中心地址，`prefix` 为 None 时使用后端的默认前缀
+    pub fn parse(endpoint: &str, prefix: Option<&str>) -> Result<Self> {
+        let (scheme, rest) = endpoint
+            .split_once("://")
+            .ok_or_else(|| anyhow!("invalid config center endpoint: {endpoint}"))?;
+        let (address, query) = rest.split_once('?').unwrap_or((rest, ""));
+        if address.is_empty() {
+            return Err(anyhow!("config center endpoint has no address: {endpoint}"));
+        }
+
+        let (backend, default_prefix) = match scheme {
+            "etcd" => {
+                let endpoints = address
+                    .split(',')
+                    .filter(|host| !host.is_empty())
+                    .map(|host| format!("http://{host}"))
+                    .collect();
+                (ConfigCenterBackend::Etcd { endpoints }, "/flare/config")
+            }
+            "nacos" => {
+                let mut namespace = None;
+                let mut group = DEFAULT_NACOS_GROUP.to_string();
+                for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+                    match key {
+                        "namespace" if !value.is_empty() => namespace = Some(value.to_string()),
+                        "group" if !value.is_empty() => group = value.to_string(),
+                        _ => {}
+                    }
+                }
+                let backend = ConfigCenterBackend::Nacos {
+                    server: format!("http://{address}"),
+                    namespace,
+                    group,
+                };
+                (backend, "flare")
+            }
+            other => return Err(anyhow!("unsupported config center backend: {other}")),
+        };
+
+        Ok(Self {
+            endpoint: endpoint.to_string(),
+            backend,
+            prefix: prefix.unwrap_or(default_prefix).to_string(),
+            timeout: DEFAULT_TIMEOUT,
+        })
+    }
+
+    /// 从环境变量读取配置中心（未设置 `FLARE_CONFIG_CENTER` 时返回 None）
+    pub fn from_env() -> Result<Option<Self>> {
+        let endpoint = match env::var(CONFIG_CENTER_ENV) {
+            Ok(endpoint) if !endpoint.trim().is_empty() => endpoint,
+            _ => return Ok(None),
+        };
+        let prefix = env::var(CONFIG_CENTER_PREFIX_ENV)
+            .ok()
+            .filter(|prefix| !prefix.trim().is_empty());
+
+        let mut source = Self::parse(endpoint.trim(), prefix.as_deref())?;
+        if let Some(timeout_ms) = env::var(CONFIG_CENTER_TIMEOUT_ENV)
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+        {
+            source = source.with_timeout(Duration::from_millis(timeout_ms));
+        }
+        Ok(Some(source))
+    }
+
+    /// 设置拉取超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 配置中心地址
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// 配置中心后端
+    pub fn backend(&self) -> &ConfigCenterBackend {
+        &self.backend
+    }
+
+    /// 拉取并合并配置片段
+    pub async fn fetch(&self) -> Result<Value> {
+        let fragments = tokio::time::timeout(self.timeout, self.fetch_fragments())
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "timed out after {}ms fetching configuration from {}",
+                    self.timeout.as_millis(),
+                    self.endpoint
+                )
+            })??;
+
+        info!(
+            endpoint = %self.endpoint,
+            prefix = %self.prefix,
+            fragments = fragments.len(),
+            "Loaded configuration fragments from config center"
+        );
+        merge_fragments(fragments)
+    }
+
+    /// 在独立线程的运行时中拉取配置（可在同步上下文或 tokio 运行时内调用）
+    pub fn fetch_blocking(&self) -> Result<Value> {
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .context("failed to build config center runtime")?
+                        .block_on(self.fetch())
+                })
+                .join()
+                .map_err(|_| anyhow!("config center fetch thread panicked"))?
+        })
+    }
+
+    /// 拉取前缀下的全部片段，返回（相对名称, 内容）
+    async fn fetch_fragments(&self) -> Result<Vec<(String, String)>> {
+        match &self.backend {
+            ConfigCenterBackend::Etcd { endpoints } => self.fetch_etcd(endpoints).await,
+            ConfigCenterBackend::Nacos {
+                server,
+                namespace,
+                group,
+            } => self.fetch_nacos(server, namespace.as_deref(), group).await,
+        }
+    }
+
+    async fn fetch_etcd(&self, endpoints: &[String]) -> Result<Vec<(String, String)>> {
+        use etcd_client::{Client, GetOptions};
+
+        let mut client = Client::connect(endpoints, None)
+            .await
+            .context("failed to connect to etcd")?;
+        let prefix = format!("{}/", self.prefix.trim_end_matches('/'));
+        let resp = client
+            .get(prefix.as_str(), Some(GetOptions::new().with_prefix()))
+            .await
+            .with_context(|| format!("failed to list configuration under {prefix}"))?;
+
+        resp.kvs()
+            .iter()
+            .map(|kv| {
+                let key = kv.key_str().context("etcd key is not valid UTF-8")?;
+                let value = kv
+                    .value_str()
+                    .with_context(|| format!("etcd value of {key} is not valid UTF-8"))?;
+                Ok((key[prefix.len()..].to_string(), value.to_string()))
+            })
+            .collect()
+    }
+
+    async fn fetch_nacos(
+        &self,
+        server: &str,
+        namespace: Option<&str>,
+        group: &str,
+    ) -> Result<Vec<(String, String)>> {
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .context("failed to build Nacos HTTP client")?;
+        let url = format!("{}/nacos/v1/cs/configs", server.trim_end_matches('/'));
+        let prefix = format!("{}.", self.prefix.trim_end_matches('.'));
+        let data_id = format!("{prefix}*");
+        let page_size = NACOS_PAGE_SIZE.to_string();
+
+        let mut fragments = Vec::new();
+        for page_no in 1.. {
+            let page_no = page_no.to_string();
+            let mut query = vec![
+                ("search", "blur"),
+                ("dataId", data_id.as_str()),
+                ("group", group),
+                ("pageNo", page_no.as_str()),
+                ("pageSize", page_size.as_str()),
+            ];
+            if let Some(namespace) = namespace {
+                query.push(("tenant", namespace));
+            }
+
+            let page: NacosConfigPage = client
+                .get(&url)
+                .query(&query)
+                .send()
+                .await
+                .context("failed to query Nacos configuration")?
+                .error_for_status()
+                .context("Nacos returned an error status")?
+                .json()
+                .await
+                .context("invalid Nacos configuration response")?;
+
+            let received = page.page_items.len();
+            fragments.extend(page.page_items.into_iter().filter_map(|item| {
+                item.data_id
+                    .strip_prefix(&prefix)
+                    .map(|name| (name.replacen('.', "/", 1), item.content))
+            }));
+            if received < NACOS_PAGE_SIZE {
+                break;
+            }
+        }
+        Ok(fragments)
+    }
+}
+
+/// Nacos 配置列表分页结果
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NacosConfigPage {
+    #[serde(default)]
+    page_items: Vec<NacosConfigItem>,
+}
+
+/// Nacos 配置项
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NacosConfigItem {
+    data_id: String,
+    #[serde(default)]
+    content: String,
+}
+
+/// 按层级排序并合并配置片段
+///
+/// 片段名称（相对前缀）为 `base` 或 `<shared|services|overrides>/<name>`，
+/// `.toml` 后缀可省略；无法识别的片段会被忽略
+fn merge_fragments(fragments: Vec<(String, String)>) -> Result<Value> {
+    let mut ordered = Vec::with_capacity(fragments.len());
+    for (name, content) in fragments {
+        let name = name.trim_start_matches('/');
+        let stem = name.strip_suffix(".toml").unwrap_or(name);
+        let layer = if stem == "base" {
+            0
+        } else {
+            match stem
+                .split_once('/')
+                .and_then(|(layer, _)| LAYERS.iter().position(|candidate| *candidate == layer))
+            {
+                Some(index) => index + 1,
+                None => {
+                    debug!(fragment = %name, "Skipping unrecognized config center fragment");
+                    continue;
+                }
+            }
+        };
+        ordered.push((layer, name.to_string(), content));
+    }
+    ordered.sort();
+
+    let mut merged = Value::Table(Default::default());
+    for (_, name, content) in ordered {
+        let mut value: Value = toml::from_str(&content)
+            .with_context(|| format!("invalid TOML content in config center fragment {name}"))?;
+        interpolate_env(&mut value)
+            .with_context(|| format!("unresolved placeholder in config center fragment {name}"))?;
+        merge_value(&mut merged, value);
+    }
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_endpoints() {
+        let etcd = ConfigCenterSource::parse("etcd://10.0.0.1:2379,10.0.0.2:2379", None).unwrap();
+        assert_eq!(
+            etcd.backend(),
+            &ConfigCenterBackend::Etcd {
+                endpoints: vec![
+                    "http://10.0.0.1:2379".to_string(),
+                    "http://10.0.0.2:2379".to_string()
+                ],
+            }
+        );
+        assert_eq!(etcd.prefix, "/flare/config");
+
+        let nacos =
+            ConfigCenterSource::parse("nacos://nacos:8848?namespace=dev", Some("push")).unwrap();
+        assert_eq!(
+            nacos.backend(),
+            &ConfigCenterBackend::Nacos {
+                server: "http://nacos:8848".to_string(),
+                namespace: Some("dev".to_string()),
+                group: DEFAULT_NACOS_GROUP.to_string(),
+            }
+        );
+        assert_eq!(nacos.prefix, "push");
+
+        assert!(ConfigCenterSource::parse("consul://consul:8500", None).is_err());
+    }
+
+    #[test]
+    fn test_merge_fragments_follows_directory_order() {
+        let fragments = vec![
+            (
+                "overrides/prod.toml".to_string(),
+                "[redis]\nurl = \"redis://prod\"".to_string(),
+            ),
+            (
+                "services/push_server.toml".to_string(),
+                "[redis]\nurl = \"redis://push\"\npool = 8".to_string(),
+            ),
+            (
+                "base".to_string(),
+                "[redis]\nurl = \"redis://base\"\npool = 4".to_string(),
+            ),
+            ("unknown/ignored".to_string(), "not = [toml".to_string()),
+        ];
+
+        let merged = merge_fragments(fragments).unwrap();
+        let redis = merged.get("redis").unwrap();
+        assert_eq!(redis.get("url").unwrap().as_str(), Some("redis://prod"));
+        assert_eq!(redis.get("pool").unwrap().as_integer(), Some(8));
+    }
+}
@@ -0,0 +1,375 @@
+//! 基础设施客户端构建
+//!
+//! 按配置档名称直接构建 Redis / Kafka / MongoDB / PostgreSQL 客户端，统一处理连接池、TLS 与重试，
+//! 服务无需再各自从 `RedisPoolConfig` / `KafkaClusterConfig` 等拼装客户端：
+//!
+//! | 方法 | 配置表 | 返回 | Feature |
+//! |------|--------|------|---------|
+//! | [`FlareAppConfig::build_redis`] | `[redis.<name>]` | `redis::aio::ConnectionManager` | `redis` |
+//! | [`FlareAppConfig::build_kafka_producer`] | `[kafka.<name>]` | `rdkafka::producer::FutureProducer` | `kafka` |
+//! | [`FlareAppConfig::build_mongo`] | `[mongodb.<name>]` | `mongodb::Database` | `mongodb` |
+//! | [`FlareAppConfig::build_pg_pool`] | `[postgres.<name>]` | `sqlx::PgPool` | `postgres` |
+//!
+//! Redis 断线重连由 `ConnectionManager` 按 `max_retries` 处理；PostgreSQL / MongoDB 首次建连失败时
+//! 按指数退避重试 `max_retries` 次（默认 3 次）。TLS 依赖驱动自身的 feature：Redis 需在服务中开启
+//! `redis` 的 `tokio-rustls-comp`，Kafka 的 `SSL` / `SASL_SSL` 需启用 `kafka-tls`（librdkafka 链接 OpenSSL）。
+
+#[cfg(any(feature = "redis", feature = "postgres", feature = "mongodb", test))]
+use std::time::Duration;
+
+#[cfg(any(
+    feature = "redis",
+    feature = "kafka",
+    feature = "postgres",
+    feature = "mongodb"
+))]
+use anyhow::{Context as _, Result, anyhow};
+
+#[cfg(any(
+    feature = "redis",
+    feature = "kafka",
+    feature = "postgres",
+    feature = "mongodb"
+))]
+use super::FlareAppConfig;
+#[cfg(any(feature = "kafka", test))]
+use super::KafkaClusterConfig;
+#[cfg(any(feature = "redis", test))]
+use super::RedisPoolConfig;
+
+/// 建连失败的默认重试次数
+#[cfg(any(feature = "postgres", feature = "mongodb"))]
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Kafka 生产者默认消息超时（毫秒）
+#[cfg(any(feature = "kafka", test))]
+const DEFAULT_KAFKA_MESSAGE_TIMEOUT_MS: u64 = 5000;
+
+#[cfg(any(
+    feature = "redis",
+    feature = "kafka",
+    feature = "postgres",
+    feature = "mongodb"
+))]
+impl FlareAppConfig {
+    /// 按 `[redis.<name>]` 构建自动重连的 Redis 连接
+    #[cfg(feature = "redis")]
+    pub async fn build_redis(&self, name: &str) -> Result<redis::aio::ConnectionManager> {
+        use redis::IntoConnectionInfo;
+        use redis::aio::ConnectionManagerConfig;
+
+        let profile = self
+            .redis_profile(name)
+            .ok_or_else(|| anyhow!("Redis config '{}' not found", name))?;
+        let mut info = redis_connection_url(profile)
+            .as_str()
+            .into_connection_info()
+            .with_context(|| format!("Invalid Redis url in redis.{}", name))?;
+        if let Some(database) = profile.database {
+            info.redis.db = i64::from(database);
+        }
+        let client = redis::Client::open(info)
+            .with_context(|| format!("Failed to create Redis client for redis.{}", name))?;
+
+        let mut config = ConnectionManagerConfig::new();
+        if let Some(timeout_ms) = profile.connect_timeout_ms {
+            config = config.set_connection_timeout(Duration::from_millis(timeout_ms));
+        }
+        if let Some(timeout_ms) = profile.response_timeout_ms {
+            config = config.set_response_timeout(Duration::from_millis(timeout_ms));
+        }
+        if let Some(max_retries) = profile.max_retries {
+            config = config.set_number_of_retries(max_retries as usize);
+        }
+        client
+            .get_connection_manager_with_config(config)
+            .await
+            .with_context(|| format!("Failed to connect Redis for redis.{}", name))
+    }
+
+    /// 按 `[kafka.<name>]` 构建 Kafka 生产者
+    ///
+    /// `options` 中的配置项最后写入，可覆盖其他字段生成的 librdkafka 配置
+    #[cfg(feature = "kafka")]
+    pub fn build_kafka_producer(&self, name: &str) -> Result<rdkafka::producer::FutureProducer> {
+        let profile = self
+            .kafka_profile(name)
+            .ok_or_else(|| anyhow!("Kafka config '{}' not found", name))?;
+        let mut config = rdkafka::ClientConfig::new();
+        for (key, value) in kafka_producer_settings(profile) {
+            config.set(key, value);
+        }
+        config
+            .create()
+            .with_context(|| format!("Failed to create Kafka producer for kafka.{}", name))
+    }
+
+    /// 按 `[mongodb.<name>]` 构建数据库句柄（未配置 `database` 时使用 URL 中的默认库）
+    #[cfg(feature = "mongodb")]
+    pub async fn build_mongo(&self, name: &str) -> Result<mongodb::Database> {
+        use mongodb::bson::doc;
+        use mongodb::options::{ClientOptions, Tls, TlsOptions};
+
+        let profile = self
+            .mongodb_profile(name)
+            .ok_or_else(|| anyhow!("MongoDB config '{}' not found", name))?;
+        let mut options = ClientOptions::parse(&profile.url)
+            .await
+            .with_context(|| format!("Invalid MongoDB url in mongodb.{}", name))?;
+        if let Some(max_pool_size) = profile.max_pool_size {
+            options.max_pool_size = Some(max_pool_size);
+        }
+        if let Some(min_pool_size) = profile.min_pool_size {
+            options.min_pool_size = Some(min_pool_size);
+        }
+        if let Some(timeout_ms) = profile.connect_timeout_ms {
+            options.connect_timeout = Some(Duration::from_millis(timeout_ms));
+        }
+        if let Some(retry_writes) = profile.retry_writes {
+            options.retry_writes = Some(retry_writes);
+        }
+        match (profile.tls, &profile.tls_ca_file) {
+            (Some(false), _) => options.tls = Some(Tls::Disabled),
+            (Some(true), ca_file) | (None, ca_file @ Some(_)) => {
+                options.tls = Some(Tls::Enabled(
+                    TlsOptions::builder()
+                        .ca_file_path(ca_file.as_ref().map(std::path::PathBuf::from))
+                        .build(),
+                ));
+            }
+            (None, None) => {}
+        }
+
+        let client = mongodb::Client::with_options(options)
+            .with_context(|| format!("Failed to create MongoDB client for mongodb.{}", name))?;
+        let database = match &profile.database {
+            Some(database) => client.database(database),
+            None => client.default_database().ok_or_else(|| {
+                anyhow!(
+                    "mongodb.{} has no database and the url names no default",
+                    name
+                )
+            })?,
+        };
+        // 驱动延迟建连，ping 一次以便在启动阶段暴露连接问题
+        connect_with_retry(
+            "MongoDB",
+            name,
+            profile.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+            || database.run_command(doc! { "ping": 1 }, None),
+        )
+        .await?;
+        Ok(database)
+    }
+
+    /// 按 `[postgres.<name>]` 构建 PostgreSQL 连接池
+    #[cfg(feature = "postgres")]
+    pub async fn build_pg_pool(&self, name: &str) -> Result<sqlx::PgPool> {
+        use sqlx::postgres::{PgConnectOptions, PgPoolOptions, PgSslMode};
+
+        let profile = self
+            .postgres_profile(name)
+            .ok_or_else(|| anyhow!("PostgreSQL config '{}' not found", name))?;
+        let mut connect_options: PgConnectOptions = profile
+            .url
+            .parse()
+            .with_context(|| format!("Invalid PostgreSQL url in postgres.{}", name))?;
+        if let Some(ssl_mode) = &profile.ssl_mode {
+            let ssl_mode: PgSslMode = ssl_mode
+                .parse()
+                .with_context(|| format!("Invalid ssl_mode in postgres.{}", name))?;
+            connect_options = connect_options.ssl_mode(ssl_mode);
+        }
+        if let Some(ssl_root_cert) = &profile.ssl_root_cert {
+            connect_options = connect_options.ssl_root_cert(ssl_root_cert);
+        }
+
+        let mut pool_options = PgPoolOptions::new();
+        if let Some(max_connections) = profile.max_connections {
+            pool_options = pool_options.max_connections(max_connections);
+        }
+        if let Some(min_connections) = profile.min_connections {
+            pool_options = pool_options.min_connections(min_connections);
+        }
+        if let Some(timeout_ms) = profile.acquire_timeout_ms {
+            pool_options = pool_options.acquire_timeout(Duration::from_millis(timeout_ms));
+        }
+        if let Some(timeout_ms) = profile.idle_timeout_ms {
+            pool_options = pool_options.idle_timeout(Duration::from_millis(timeout_ms));
+        }
+
+        connect_with_retry(
+            "PostgreSQL",
+            name,
+            profile.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+            || pool_options.clone().connect_with(connect_options.clone()),
+        )
+        .await
+    }
+}
+
+/// Redis 连接地址（启用 TLS 时 `redis://` 改为 `rediss://`）
+#[cfg(any(feature = "redis", test))]
+fn redis_connection_url(profile: &RedisPoolConfig) -> String {
+    match (profile.tls, profile.url.strip_prefix("redis://")) {
+        (Some(true), Some(rest)) => format!("rediss://{}", rest),
+        _ => profile.url.clone(),
+    }
+}
+
+/// Kafka 生产者的 librdkafka 配置项（按写入顺序，后写入的覆盖先写入的）
+#[cfg(any(feature = "kafka", test))]
+fn kafka_producer_settings(profile: &KafkaClusterConfig) -> Vec<(String, String)> {
+    let mut settings = vec![
+        (
+            "bootstrap.servers".to_string(),
+            profile.bootstrap_servers.clone(),
+        ),
+        (
+            "message.timeout.ms".to_string(),
+            profile
+                .timeout_ms
+                .unwrap_or(DEFAULT_KAFKA_MESSAGE_TIMEOUT_MS)
+                .to_string(),
+        ),
+    ];
+    let mut set = |key: &str, value: Option<String>| {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            settings.push((key.to_string(), value));
+        }
+    };
+    set("client.id", profile.client_id.clone());
+    set("retries", profile.retries.map(|v| v.to_string()));
+    set(
+        "retry.backoff.ms",
+        profile.retry_backoff_ms.map(|v| v.to_string()),
+    );
+    set("security.protocol", profile.security_protocol.clone());
+    if profile.sasl_username.is_some() {
+        set(
+            "sasl.mechanisms",
+            Some(
+                profile
+                    .sasl_mechanism
+                    .clone()
+                    .unwrap_or_else(|| "PLAIN".to_string()),
+            ),
+        );
+    }
+    set("sasl.username", profile.sasl_username.clone());
+    set("sasl.password", profile.sasl_password.clone());
+    set("ssl.ca.location", profile.ssl_ca_location.clone());
+
+    let mut options: Vec<_> = profile.options.iter().collect();
+    options.sort();
+    settings.extend(options.into_iter().map(|(k, v)| (k.clone(), v.clone())));
+    settings
+}
+
+/// 第 `attempt` 次重试前的等待时间（200ms 起指数退避，上限 5 秒）
+#[cfg(any(feature = "postgres", feature = "mongodb", test))]
+fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_millis(200u64.saturating_mul(1 << attempt.min(10)).min(5000))
+}
+
+/// 执行建连，失败时按 [`retry_delay`] 退避重试 `max_retries` 次
+#[cfg(any(feature = "postgres", feature = "mongodb"))]
+async fn connect_with_retry<T, E, F, Fut>(
+    kind: &str,
+    name: &str,
+    max_retries: u32,
+    mut connect: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = std::result::Result<T, E>>,
+    E: std::fmt::Display,
+{
+    let mut attempt = 0;
+    loop {
+        match connect().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < max_retries => {
+                let delay = retry_delay(attempt);
+                tracing::warn!(
+                    profile = %name,
+                    attempt = attempt + 1,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %e,
+                    "Failed to connect {}, retrying",
+                    kind
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                return Err(anyhow!(
+                    "Failed to connect {} for profile '{}' after {} attempt(s): {}",
+                    kind,
+                    name,
+                    attempt + 1,
+                    e
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redis_tls_switches_scheme() {
+        let mut profile = RedisPoolConfig {
+            url: "redis://:pass@cache:6379".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(redis_connection_url(&profile), "redis://:pass@cache:6379");
+        profile.tls = Some(true);
+        assert_eq!(redis_connection_url(&profile), "rediss://:pass@cache:6379");
+        profile.url = "rediss://cache:6380".to_string();
+        assert_eq!(redis_connection_url(&profile), "rediss://cache:6380");
+    }
+
+    #[test]
+    fn test_kafka_settings_apply_security_and_overrides() {
+        let profile = KafkaClusterConfig {
+            bootstrap_servers: "kafka-0:9093,kafka-1:9093".to_string(),
+            client_id: Some("push-server".to_string()),
+            security_protocol: Some("SASL_SSL".to_string()),
+            sasl_username: Some("flare".to_string()),
+            sasl_password: Some("secret".to_string()),
+            ssl_ca_location: Some("/etc/kafka/ca.pem".to_string()),
+            retries: Some(5),
+            options: [("message.timeout.ms".to_string(), "8000".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        let settings = kafka_producer_settings(&profile);
+        let get = |key: &str| {
+            settings
+                .iter()
+                .rev()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("bootstrap.servers"), Some("kafka-0:9093,kafka-1:9093"));
+        assert_eq!(get("security.protocol"), Some("SASL_SSL"));
+        assert_eq!(get("sasl.mechanisms"), Some("PLAIN"));
+        assert_eq!(get("ssl.ca.location"), Some("/etc/kafka/ca.pem"));
+        assert_eq!(get("retries"), Some("5"));
+        assert_eq!(get("retry.backoff.ms"), None);
+        // options 覆盖生成的配置
+        assert_eq!(get("message.timeout.ms"), Some("8000"));
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_to_cap() {
+        assert_eq!(retry_delay(0), Duration::from_millis(200));
+        assert_eq!(retry_delay(2), Duration::from_millis(800));
+        assert_eq!(retry_delay(10), Duration::from_secs(5));
+        assert_eq!(retry_delay(u32::MAX), Duration::from_secs(5));
+    }
+}
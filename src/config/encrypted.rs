@@ -0,0 +1,325 @@
+//! 加密配置值（`encryption` feature）
+//!
+//! 整个值为 `enc:<base64>` 的字符串在 `load_config` 中解密后再反序列化，`sasl_password`、
+//! `secret_key`、`token_secret` 等敏感字段可以密文形式提交到仓库或配置中心：
+//!
+//! ```toml
+//! [kafka.default]
+//! sasl_password = "enc:+kVOQwIGY29uZmlnqBtN..."
+//! ```
+//!
+//! 密文为 [`FieldEncryptor`] 信封（AES-256-GCM，携带密钥ID）的 base64 编码，配置密钥来源：
+//! - `FLARE_CONFIG_KEY`：base64 编码的 32 字节密钥，密钥ID 取 `FLARE_CONFIG_KEY_ID`（默认 `config`）
+//! - `FLARE_CONFIG_KEY_FILE`：本地密钥文件（格式见 [`LocalKeyFileKms`]），加密使用租户 `config`
+//!   的当前密钥，轮换后旧密钥保留即可继续解密历史密文
+//! - 对接外部 KMS：实现 [`KmsProvider`]，在 `load_config` 之前调用 [`ConfigCipher::install_global`]
+//!
+//! 生成密文：`cargo run --bin encrypt-config -- '<明文>'`（或从标准输入读取）
+
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{Context as AnyhowContext, Result, anyhow};
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose};
+use toml::Value;
+
+use crate::encryption::kms::DATA_KEY_LEN;
+use crate::encryption::{DataKey, FieldEncryptor, KmsProvider, LocalKeyFileKms};
+
+/// 加密配置值前缀
+pub const ENCRYPTED_VALUE_PREFIX: &str = "enc:";
+/// 配置密钥（base64 编码的 32 字节）
+pub const CONFIG_KEY_ENV: &str = "FLARE_CONFIG_KEY";
+/// 配置密钥ID
+pub const CONFIG_KEY_ID_ENV: &str = "FLARE_CONFIG_KEY_ID";
+/// 配置密钥文件
+pub const CONFIG_KEY_FILE_ENV: &str = "FLARE_CONFIG_KEY_FILE";
+/// 加密配置值使用的密钥所属租户
+pub const CONFIG_KEY_TENANT: &str = "config";
+
+/// 加密配置值绑定的记录ID（配置值之间不区分记录）
+const CONFIG_RECORD_ID: &str = "";
+
+/// 默认配置密钥ID
+const DEFAULT_CONFIG_KEY_ID: &str = "config";
+
+/// 进程级配置解密器（通过 [`ConfigCipher::install_global`] 安装）
+static GLOBAL_CONFIG_CIPHER: OnceLock<Arc<ConfigCipher>> = OnceLock::new();
+
+/// 配置值加解密
+pub struct ConfigCipher {
+    encryptor: FieldEncryptor,
+}
+
+impl ConfigCipher {
+    pub fn new(kms: Arc<dyn KmsProvider>) -> Self {
+        Self {
+            encryptor: FieldEncryptor::new(kms),
+        }
+    }
+
+    /// 从环境变量创建（`FLARE_CONFIG_KEY` 优先于 `FLARE_CONFIG_KEY_FILE`，均未设置时返回 None）
+    pub fn from_env() -> Result<Option<Self>> {
+        if let Some(key) = env_non_empty(CONFIG_KEY_ENV) {
+            let key_id = env_non_empty(CONFIG_KEY_ID_ENV)
+                .unwrap_or_else(|| DEFAULT_CONFIG_KEY_ID.to_string());
+            let kms = StaticKeyKms::from_base64(key_id, &key)
+                .with_context(|| format!("invalid {}", CONFIG_KEY_ENV))?;
+            return Ok(Some(Self::new(Arc::new(kms))));
+        }
+        if let Some(path) = env_non_empty(CONFIG_KEY_FILE_ENV) {
+            let kms = LocalKeyFileKms::from_file(&path)
+                .with_context(|| format!("invalid {}", CONFIG_KEY_FILE_ENV))?;
+            return Ok(Some(Self::new(Arc::new(kms))));
+        }
+        Ok(None)
+    }
+
+    /// 安装进程级配置解密器（对接外部 KMS），须在 `load_config` 之前调用；
+    /// 已安装时返回 false
+    pub fn install_global(cipher: ConfigCipher) -> bool {
+        GLOBAL_CONFIG_CIPHER.set(Arc::new(cipher)).is_ok()
+    }
+
+    /// 加密明文，返回 `enc:<base64>`
+    pub async fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let envelope = self
+            .encryptor
+            .encrypt(CONFIG_KEY_TENANT, CONFIG_RECORD_ID, plaintext.as_bytes())
+            .await?;
+        Ok(format!(
+            "{}{}",
+            ENCRYPTED_VALUE_PREFIX,
+            general_purpose::STANDARD.encode(envelope)
+        ))
+    }
+
+    /// 解密 `enc:<base64>` 值
+    pub async fn decrypt(&self, value: &str) -> Result<String> {
+        let encoded = value
+            .strip_prefix(ENCRYPTED_VALUE_PREFIX)
+            .ok_or_else(|| anyhow!("value does not start with {}", ENCRYPTED_VALUE_PREFIX))?;
+        let envelope = general_purpose::STANDARD
+            .decode(encoded.trim())
+            .context("invalid base64 in encrypted value")?;
+        // 非信封数据会被 FieldEncryptor 当作历史明文原样返回，这里必须拒绝
+        if !FieldEncryptor::is_encrypted(&envelope) {
+            return Err(anyhow!("encrypted value is not a valid envelope"));
+        }
+        let field = self
+            .encryptor
+            .decrypt(CONFIG_KEY_TENANT, CONFIG_RECORD_ID, &envelope)
+            .await?;
+        String::from_utf8(field.plaintext).context("decrypted value is not valid UTF-8")
+    }
+
+    /// 解密配置中的全部 `enc:` 值（错误信息只包含配置键，不包含值）
+    pub async fn decrypt_value(&self, value: &mut Value) -> Result<usize> {
+        let mut encrypted = Vec::new();
+        collect_encrypted(value, String::new(), &mut encrypted);
+        let mut plaintexts = Vec::with_capacity(encrypted.len());
+        for (path, ciphertext) in &encrypted {
+            plaintexts.push(
+                self.decrypt(ciphertext)
+                    .await
+                    .with_context(|| format!("failed to decrypt {}", display_path(path)))?,
+            );
+        }
+        replace_encrypted(value, &mut plaintexts.into_iter());
+        Ok(encrypted.len())
+    }
+}
+
+/// 解密配置中的 `enc:` 值（在独立线程的运行时中执行，可在 tokio 运行时内调用）
+///
+/// 存在加密值但未配置密钥时返回错误，避免把密文当作凭证使用
+pub(crate) fn decrypt_config_value(value: &mut Value) -> Result<usize> {
+    let mut encrypted = Vec::new();
+    collect_encrypted(value, String::new(), &mut encrypted);
+    let Some((first, _)) = encrypted.first() else {
+        return Ok(0);
+    };
+
+    let cipher = match GLOBAL_CONFIG_CIPHER.get() {
+        Some(cipher) => cipher.clone(),
+        None => Arc::new(ConfigCipher::from_env()?.ok_or_else(|| {
+            anyhow!(
+                "{} is encrypted but no config key is set ({} or {})",
+                display_path(first),
+                CONFIG_KEY_ENV,
+                CONFIG_KEY_FILE_ENV
+            )
+        })?),
+    };
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .context("failed to build config decryption runtime")?
+                    .block_on(cipher.decrypt_value(value))
+            })
+            .join()
+            .map_err(|_| anyhow!("config decryption thread panicked"))?
+    })
+}
+
+/// 按遍历顺序收集 `enc:` 值及其配置键（数组下标以 `[n]` 表示）
+fn collect_encrypted(value: &Value, path: String, found: &mut Vec<(String, String)>) {
+    match value {
+        Value::String(s) if s.starts_with(ENCRYPTED_VALUE_PREFIX) => found.push((path, s.clone())),
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect_encrypted(item, format!("{path}[{index}]"), found);
+            }
+        }
+        Value::Table(table) => {
+            for (key, item) in table {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                collect_encrypted(item, child, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 按与 [`collect_encrypted`] 相同的遍历顺序替换 `enc:` 值
+fn replace_encrypted(value: &mut Value, plaintexts: &mut impl Iterator<Item = String>) {
+    match value {
+        Value::String(s) if s.starts_with(ENCRYPTED_VALUE_PREFIX) => {
+            if let Some(plaintext) = plaintexts.next() {
+                *s = plaintext;
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| replace_encrypted(item, plaintexts)),
+        Value::Table(table) => table
+            .iter_mut()
+            .for_each(|(_, item)| replace_encrypted(item, plaintexts)),
+        _ => {}
+    }
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() { "<root>" } else { path }
+}
+
+fn env_non_empty(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// 单个环境变量密钥
+struct StaticKeyKms {
+    key: DataKey,
+}
+
+impl StaticKeyKms {
+    fn from_base64(key_id: String, encoded: &str) -> Result<Self> {
+        let bytes = general_purpose::STANDARD
+            .decode(encoded.trim())
+            .context("key is not valid base64")?;
+        let key: [u8; DATA_KEY_LEN] = bytes
+            .try_into()
+            .map_err(|_| anyhow!("key must be {} bytes", DATA_KEY_LEN))?;
+        Ok(Self {
+            key: DataKey {
+                key_id,
+                tenant_id: CONFIG_KEY_TENANT.to_string(),
+                key,
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl KmsProvider for StaticKeyKms {
+    async fn active_key(&self, _tenant_id: &str) -> Result<DataKey> {
+        Ok(self.key.clone())
+    }
+
+    async fn key_by_id(&self, key_id: &str) -> Result<DataKey> {
+        if key_id == self.key.key_id {
+            Ok(self.key.clone())
+        } else {
+            Err(anyhow!("unknown config key id {}", key_id))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_V1: &str = "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=";
+    const KEY_V2: &str = "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI=";
+
+    fn cipher(key: &str) -> ConfigCipher {
+        ConfigCipher::new(Arc::new(
+            StaticKeyKms::from_base64("config".to_string(), key).unwrap(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_roundtrip() {
+        let cipher = cipher(KEY_V1);
+        let value = cipher.encrypt("kafka-pass").await.unwrap();
+        assert!(value.starts_with(ENCRYPTED_VALUE_PREFIX));
+        assert!(!value.contains("kafka-pass"));
+        assert_eq!(cipher.decrypt(&value).await.unwrap(), "kafka-pass");
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_value_replaces_nested_fields() {
+        let cipher = cipher(KEY_V1);
+        let password = cipher.encrypt("kafka-pass").await.unwrap();
+        let secret = cipher.encrypt("s3-secret").await.unwrap();
+        let mut value: Value = toml::from_str(&format!(
+            r#"
+            [kafka.default]
+            sasl_username = "flare"
+            sasl_password = "{password}"
+
+            [[object_storage]]
+            secret_key = "{secret}"
+            "#
+        ))
+        .unwrap();
+
+        assert_eq!(cipher.decrypt_value(&mut value).await.unwrap(), 2);
+        assert_eq!(
+            value["kafka"]["default"]["sasl_password"].as_str(),
+            Some("kafka-pass")
+        );
+        assert_eq!(
+            value["kafka"]["default"]["sasl_username"].as_str(),
+            Some("flare")
+        );
+        assert_eq!(
+            value["object_storage"][0]["secret_key"].as_str(),
+            Some("s3-secret")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decrypt_rejects_wrong_key_and_plain_payload() {
+        let value = cipher(KEY_V1).encrypt("kafka-pass").await.unwrap();
+        let mut config: Value = toml::from_str(&format!("token_secret = \"{value}\"")).unwrap();
+        let err = cipher(KEY_V2).decrypt_value(&mut config).await.unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("failed to decrypt token_secret"));
+        assert!(!message.contains(&value));
+
+        let plain = format!(
+            "{}{}",
+            ENCRYPTED_VALUE_PREFIX,
+            general_purpose::STANDARD.encode("x")
+        );
+        assert!(cipher(KEY_V1).decrypt(&plain).await.is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! 配置片段格式
+//!
+//! 配置目录中的片段按扩展名解析为 TOML 值后再做深度合并：
+//! - `.toml`
+//! - `.yaml` / `.yml`（支持锚点与 `<<` 合并键，便于直接复用 Helm values）
+//! - `.json`
+//!
+//! TOML 没有空值，YAML / JSON 中表里的 `null` 视为未设置（不覆盖下层配置），数组中的 `null` 视为错误。
+
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use toml::Value;
+use toml::map::Map;
+
+/// 配置片段格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConfigFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// 按扩展名识别格式（不区分大小写），无法识别时返回 None
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Toml => "TOML",
+            Self::Yaml => "YAML",
+            Self::Json => "JSON",
+        }
+    }
+
+    /// 解析片段内容
+    pub(crate) fn parse(self, content: &str) -> Result<Value> {
+        match self {
+            Self::Toml => Ok(toml::from_str(content)?),
+            Self::Yaml => {
+                // 空文档视为空表
+                if content.trim().is_empty() {
+                    return Ok(Value::Table(Map::new()));
+                }
+                let mut value: serde_yaml::Value = serde_yaml::from_str(content)?;
+                value.apply_merge()?;
+                yaml_to_toml(value)?.ok_or_else(|| anyhow!("document must not be null"))
+            }
+            Self::Json => {
+                let value: serde_json::Value = serde_json::from_str(content)?;
+                json_to_toml(value)?.ok_or_else(|| anyhow!("document must not be null"))
+            }
+        }
+    }
+}
+
+/// YAML 值转换为 TOML 值（`null` 返回 None）
+fn yaml_to_toml(value: serde_yaml::Value) -> Result<Option<Value>> {
+    use serde_yaml::Value as Yaml;
+
+    let converted = match value {
+        Yaml::Null => return Ok(None),
+        Yaml::Bool(b) => Value::Boolean(b),
+        Yaml::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Integer(i)
+            } else if let Some(f) = n.as_f64().filter(|_| !n.is_u64()) {
+                Value::Float(f)
+            } else {
+                return Err(anyhow!("integer {n} is out of range"));
+            }
+        }
+        Yaml::String(s) => Value::String(s),
+        Yaml::Sequence(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| {
+                    yaml_to_toml(item)?.ok_or_else(|| anyhow!("arrays must not contain null"))
+                })
+                .collect::<Result<_>>()?,
+        ),
+        Yaml::Mapping(mapping) => {
+            let mut table = Map::new();
+            for (key, item) in mapping {
+                let key = match key {
+                    Yaml::String(s) => s,
+                    Yaml::Bool(b) => b.to_string(),
+                    Yaml::Number(n) => n.to_string(),
+                    other => return Err(anyhow!("unsupported mapping key {other:?}")),
+                };
+                let item = yaml_to_toml(item).map_err(|e| anyhow!("`{key}`: {e}"))?;
+                if let Some(item) = item {
+                    table.insert(key, item);
+                }
+            }
+            Value::Table(table)
+        }
+        Yaml::Tagged(tagged) => return yaml_to_toml(tagged.value),
+    };
+    Ok(Some(converted))
+}
+
+/// JSON 值转换为 TOML 值（`null` 返回 None）
+fn json_to_toml(value: serde_json::Value) -> Result<Option<Value>> {
+    use serde_json::Value as Json;
+
+    let converted = match value {
+        Json::Null => return Ok(None),
+        Json::Bool(b) => Value::Boolean(b),
+        Json::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Integer(i)
+            } else if let Some(f) = n.as_f64().filter(|_| !n.is_u64()) {
+                Value::Float(f)
+            } else {
+                return Err(anyhow!("integer {n} is out of range"));
+            }
+        }
+        Json::String(s) => Value::String(s),
+        Json::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| {
+                    json_to_toml(item)?.ok_or_else(|| anyhow!("arrays must not contain null"))
+                })
+                .collect::<Result<_>>()?,
+        ),
+        Json::Object(object) => {
+            let mut table = Map::new();
+            for (key, item) in object {
+                let item = json_to_toml(item).map_err(|e| anyhow!("`{key}`: {e}"))?;
+                if let Some(item) = item {
+                    table.insert(key, item);
+                }
+            }
+            Value::Table(table)
+        }
+    };
+    Ok(Some(converted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+[services.push_server]
+kafka = "push"
+max_poll_records = 500
+ratio = 0.5
+enabled = true
+topics = ["a", "b"]
+"#;
+
+    #[test]
+    fn test_formats_parse_to_same_value() {
+        let expected = ConfigFormat::Toml.parse(TOML).unwrap();
+
+        let yaml = r#"
+defaults: &defaults
+  kafka: push
+  max_poll_records: 500
+services:
+  push_server:
+    <<: *defaults
+    ratio: 0.5
+    enabled: true
+    topics: [a, b]
+    hook_config: ~
+"#;
+        assert_eq!(
+            ConfigFormat::Yaml.parse(yaml).unwrap()["services"],
+            expected["services"]
+        );
+
+        let json = r#"{"services": {"push_server": {
+            "kafka": "push", "max_poll_records": 500, "ratio": 0.5,
+            "enabled": true, "topics": ["a", "b"], "hook_config": null
+        }}}"#;
+        assert_eq!(ConfigFormat::Json.parse(json).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_format_detection_and_errors() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("services/push.YML")),
+            Some(ConfigFormat::Yaml)
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("shared/kafka.json")),
+            Some(ConfigFormat::Json)
+        );
+        assert_eq!(ConfigFormat::from_path(Path::new("README.md")), None);
+
+        assert!(
+            ConfigFormat::Yaml
+                .parse("")
+                .unwrap()
+                .as_table()
+                .unwrap()
+                .is_empty()
+        );
+        assert!(ConfigFormat::Json.parse(r#"{"a": [1, null]}"#).is_err());
+        assert!(
+            ConfigFormat::Json
+                .parse(r#"{"a": 18446744073709551615}"#)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! 配置值中的环境变量插值
+//!
+//! 解析 TOML 之后、反序列化之前替换字符串值中的占位符：
+//! - `${ENV_VAR}`：使用环境变量的值，未设置时报错
+//! - `${ENV_VAR:-default}`：环境变量未设置或为空时使用默认值
+//! - `$${`：转义，输出字面量 `${`
+
+use std::env;
+
+use anyhow::{Result, anyhow};
+use toml::Value;
+
+/// 使用进程环境变量替换配置中的占位符
+pub(crate) fn interpolate_env(value: &mut Value) -> Result<()> {
+    interpolate_with(value, &|name| env::var(name).ok())
+}
+
+/// 使用指定的变量查询函数替换配置中的占位符
+fn interpolate_with(value: &mut Value, lookup: &dyn Fn(&str) -> Option<String>) -> Result<()> {
+    match value {
+        Value::String(s) => {
+            if s.contains('$') {
+                *s = interpolate_str(s, lookup)?;
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                interpolate_with(item, lookup)?;
+            }
+        }
+        Value::Table(table) => {
+            for (key, item) in table.iter_mut() {
+                interpolate_with(item, lookup)
+                    .map_err(|e| anyhow!("failed to interpolate `{key}`: {e}"))?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn interpolate_str(input: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find('$') {
+        output.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(escaped) = after.strip_prefix("${") {
+            output.push_str("${");
+            rest = escaped;
+            continue;
+        }
+        let Some(body) = after.strip_prefix('{') else {
+            output.push('$');
+            rest = after;
+            continue;
+        };
+
+        let end = body
+            .find('}')
+            .ok_or_else(|| anyhow!("unterminated placeholder in {input:?}"))?;
+        let (name, default) = match body[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&body[..end], None),
+        };
+        if name.is_empty() {
+            return Err(anyhow!("empty placeholder in {input:?}"));
+        }
+        let resolved = match (lookup(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => default.to_string(),
+            (Some(value), _) => value,
+            (None, Some(default)) => default.to_string(),
+            (None, None) => return Err(anyhow!("environment variable {name} is not set")),
+        };
+        output.push_str(&resolved);
+        rest = &body[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_interpolate_placeholders() {
+        let mut value: Value = toml::from_str(
+            r#"
+            [redis]
+            url = "redis://${REDIS_HOST}:${REDIS_PORT:-6379}/0"
+            password = "${REDIS_PASSWORD:-}"
+            endpoints = ["http://${CONSUL_HOST:-localhost}:8500"]
+            literal = "cost $5, template $${NAME}"
+            port = 6379
+            "#,
+        )
+        .unwrap();
+        let vars = lookup(&[("REDIS_HOST", "10.0.0.1"), ("CONSUL_HOST", "")]);
+        interpolate_with(&mut value, &vars).unwrap();
+
+        let redis = value.get("redis").unwrap();
+        assert_eq!(redis["url"].as_str(), Some("redis://10.0.0.1:6379/0"));
+        assert_eq!(redis["password"].as_str(), Some(""));
+        assert_eq!(
+            redis["endpoints"][0].as_str(),
+            Some("http://localhost:8500")
+        );
+        assert_eq!(redis["literal"].as_str(), Some("cost $5, template ${NAME}"));
+        assert_eq!(redis["port"].as_integer(), Some(6379));
+    }
+
+    #[test]
+    fn test_interpolate_errors() {
+        let vars = lookup(&[]);
+        assert!(interpolate_str("${MISSING}", &vars).is_err());
+        assert!(interpolate_str("${UNTERMINATED", &vars).is_err());
+        assert!(interpolate_str("${:-x}", &vars).is_err());
+    }
+}
@@ -4,16 +4,23 @@
 //! - 根据环境变量选择对象存储配置
 //! - 加载环境特定配置
 //! - 合并配置值
+//! - 监听配置变更（`config-watch` feature）
 
 use std::collections::HashMap;
 use std::env;
-use std::fs;
 use std::path::Path;
 
 use anyhow::{Context as AnyhowContext, Result};
 use toml::Value;
 
-use super::{FlareAppConfig, ObjectStoreConfig};
+#[cfg(feature = "config-watch")]
+use super::watcher::ConfigWatcher;
+use super::{FlareAppConfig, ObjectStoreConfig, overlay};
+
+/// 进程内的配置监听器（`ConfigManager::watch` 首次调用时创建）
+#[cfg(feature = "config-watch")]
+static CONFIG_WATCHER: std::sync::OnceLock<std::sync::Arc<ConfigWatcher>> =
+    std::sync::OnceLock::new();
 
 /// 配置管理器
 pub struct ConfigManager;
@@ -49,7 +56,8 @@ impl ConfigManager {
     /// 获取当前环境名称
     ///
     /// 从环境变量 FLARE_ENV 获取当前环境名称，
-    /// 如果未设置则默认为 "development"
+    /// 如果未设置则默认为 "development"；
+    /// 配置目录加载时据此选择 `overrides/<env>` 覆盖文件与 `environments/<env>` 对象存储配置
     ///
     /// # 返回
     /// 返回当前环境名称
@@ -57,10 +65,46 @@ impl ConfigManager {
         env::var("FLARE_ENV").unwrap_or_else(|_| "development".to_string())
     }
 
+    /// 监听配置变更（配置热加载）
+    ///
+    /// 监听配置目录（`path` 为 None 时依次尝试 `config` 目录与 `config.toml`），
+    /// 变更后重新加载并校验，通过 [`ConfigWatcher::subscribe`] 接收按配置段划分的变更事件。
+    /// 同一进程内多次调用返回同一个监听器；需要在 tokio 运行时中调用
+    ///
+    /// # 示例
+    /// ```ignore
+    /// let watcher = ConfigManager::watch(Some("./config"))?;
+    /// let mut changes = watcher.subscribe();
+    /// while let Ok(event) = changes.recv().await {
+    ///     if event.affects_service("push_server") {
+    ///         let config = PushServerConfig::from_app_config(&event.config);
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "config-watch")]
+    pub fn watch(path: Option<&str>) -> Result<std::sync::Arc<ConfigWatcher>> {
+        if let Some(watcher) = CONFIG_WATCHER.get() {
+            return Ok(watcher.clone());
+        }
+
+        let path = match path {
+            Some(p) => std::path::PathBuf::from(p),
+            None => ["config", "config.toml"]
+                .iter()
+                .map(std::path::PathBuf::from)
+                .find(|p| p.exists())
+                .ok_or_else(|| anyhow::anyhow!("no configuration source to watch"))?,
+        };
+        let watcher = ConfigWatcher::start(path)?;
+        // 并发调用时以先注册的监听器为准
+        Ok(CONFIG_WATCHER.get_or_init(|| watcher).clone())
+    }
+
     /// 根据环境加载特定配置
     ///
-    /// 加载 config/environments/{environment}.toml 文件中的配置，
-    /// 并将其合并到基础配置中
+    /// 加载 config/environments/{environment} 文件中的对象存储配置，并将其合并到基础配置中。
+    /// 环境名称与 `overrides/` 使用同一张等价名称表（`FLARE_ENV=prod` 会选中 `production.toml`）；
+    /// 该文件在配置目录合并之后应用，同名的对象存储配置以它为准
     ///
     /// # 参数
     /// * `base_config` - 基础配置，将被修改以包含环境特定配置
@@ -68,17 +112,27 @@ impl ConfigManager {
     /// # 返回
     /// 成功时返回 Ok(())，失败时返回错误信息
     pub fn load_environment_config(base_config: &mut FlareAppConfig) -> Result<()> {
-        let env = Self::get_environment();
-        let env_config_path = format!("config/environments/{}.toml", env);
+        Self::merge_environment_dir(
+            &mut base_config.object_storage,
+            Path::new("config/environments"),
+            &Self::get_environment(),
+        )
+    }
 
-        if Path::new(&env_config_path).exists() {
-            let env_config_content = fs::read_to_string(&env_config_path)
-                .with_context(|| format!("无法读取环境配置文件: {}", env_config_path))?;
-            let env_config: Value = toml::from_str(&env_config_content)
-                .with_context(|| format!("无效的环境配置格式: {}", env_config_path))?;
+    /// 合并目录中当前环境的配置文件（没有对应文件时不修改）
+    ///
+    /// 与配置目录中的片段一样替换 `${ENV_VAR}` / `${ENV_VAR:-default}` 占位符
+    fn merge_environment_dir(
+        object_storage: &mut HashMap<String, ObjectStoreConfig>,
+        dir: &Path,
+        environment: &str,
+    ) -> Result<()> {
+        if let Some(path) = overlay::find_environment_file(dir, environment)? {
+            let env_config = super::load_fragment_value(&path)
+                .with_context(|| format!("无法加载环境配置文件: {}", path.display()))?;
 
             // 合并环境配置到基础配置中
-            Self::merge_config_values(&mut base_config.object_storage, &env_config);
+            Self::merge_config_values(object_storage, &env_config);
         }
 
         Ok(())
@@ -142,3 +196,99 @@ impl ConfigManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// 测试用的临时配置目录（`name` 区分同一进程中的不同测试）
+    fn temp_config_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("flare-{name}-{}", std::process::id()));
+        fs::create_dir_all(dir.join("environments")).unwrap();
+        fs::create_dir_all(dir.join("overrides")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_environment_file_interpolates_placeholders() {
+        let dir = temp_config_dir("env-interpolate");
+        fs::write(
+            dir.join("environments/production.toml"),
+            r#"
+            [object_storage.default]
+            profile_type = "s3"
+            access_key = "${FLARE_TEST_ENV_CONFIG_ACCESS_KEY}"
+            bucket = "${FLARE_TEST_ENV_CONFIG_BUCKET:-flare-media}"
+            "#,
+        )
+        .unwrap();
+        // SAFETY: 变量名仅在本测试中使用
+        unsafe { env::set_var("FLARE_TEST_ENV_CONFIG_ACCESS_KEY", "AKIA-test") };
+
+        let mut object_storage = HashMap::new();
+        let result = ConfigManager::merge_environment_dir(
+            &mut object_storage,
+            &dir.join("environments"),
+            "production",
+        );
+        fs::remove_dir_all(&dir).unwrap();
+        result.unwrap();
+
+        let store = &object_storage["default"];
+        assert_eq!(store.access_key.as_deref(), Some("AKIA-test"));
+        assert_eq!(store.bucket.as_deref(), Some("flare-media"));
+    }
+
+    #[test]
+    fn test_environment_file_uses_overlay_aliases_and_wins() {
+        let dir = temp_config_dir("env-precedence");
+        fs::write(
+            dir.join("overrides/production.toml"),
+            r#"
+            [object_storage.default]
+            profile_type = "s3"
+            bucket = "from-overrides"
+            region = "us-east-1"
+            "#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("environments/production.toml"),
+            r#"
+            [object_storage.default]
+            profile_type = "s3"
+            bucket = "from-environments"
+            "#,
+        )
+        .unwrap();
+
+        // FLARE_ENV=prod 同时选中 overrides/production 与 environments/production
+        let mut root = Value::Table(Default::default());
+        let overlay = overlay::merge_environment_overlay(&mut root, &dir.join("overrides"), "prod");
+        let mut object_storage: HashMap<String, ObjectStoreConfig> = root
+            .get("object_storage")
+            .cloned()
+            .and_then(|value| value.try_into().ok())
+            .unwrap_or_default();
+        let from_overrides = object_storage
+            .get("default")
+            .and_then(|store| store.bucket.clone());
+        let environment = ConfigManager::merge_environment_dir(
+            &mut object_storage,
+            &dir.join("environments"),
+            "prod",
+        );
+        fs::remove_dir_all(&dir).unwrap();
+        overlay.unwrap();
+        environment.unwrap();
+        assert_eq!(from_overrides.as_deref(), Some("from-overrides"));
+
+        // environments/ 在 overrides/ 之后应用，同名配置整项替换
+        let store = &object_storage["default"];
+        assert_eq!(store.bucket.as_deref(), Some("from-environments"));
+        assert_eq!(store.region, None);
+    }
+}
@@ -8,24 +8,84 @@
 
 // 首先导入需要的模块和类型
 use std::collections::HashMap;
-use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context as AnyhowContext, Result, anyhow};
 use flare_server_core::{Config, RegistryConfig};
 use serde::Deserialize;
-use std::sync::OnceLock;
+use std::sync::{OnceLock, RwLock};
 use toml::Value;
-use tracing::warn;
+use tracing::{info, warn};
 
 // 导入配置管理器模块
 mod manager;
 pub use manager::ConfigManager;
 
+// 配置热加载
+#[cfg(feature = "config-watch")]
+mod watcher;
+#[cfg(feature = "config-watch")]
+pub use watcher::{ConfigChangeEvent, ConfigSection, ConfigWatcher};
+
+// 配置重新加载审计
+#[cfg(feature = "config-watch")]
+mod audit;
+#[cfg(feature = "config-watch")]
+pub use audit::{
+    CONFIG_AUDIT_CAPACITY_ENV, ConfigChange, ConfigChangeKind, ConfigReloadRecord, ReloadOutcome,
+    ReloadTrigger, recent_reloads,
+};
+
+// 配置值中的环境变量插值
+mod interpolate;
+
+// 配置片段格式（TOML / YAML / JSON）
+mod format;
+use format::ConfigFormat;
+
+// 按 FLARE_ENV 选择的环境覆盖配置
+mod overlay;
+
+// 基础设施客户端构建（Redis / Kafka / MongoDB / PostgreSQL）
+mod clients;
+
+// 配置校验（引用、地址格式、端口冲突）
+mod validate;
+pub use validate::{
+    CONFIG_STRICT_ENV, ConfigIssue, ConfigIssueKind, ValidationReport, strict_mode,
+};
+
+// 配置中心（etcd / Nacos）
+#[cfg(feature = "config-center")]
+mod center;
+#[cfg(feature = "config-center")]
+pub use center::{ConfigCenterBackend, ConfigCenterSource};
+
+// 配置密钥解析（Vault / AWS Secrets Manager）
+#[cfg(feature = "secrets")]
+mod secrets;
+#[cfg(feature = "secrets")]
+pub use secrets::{
+    AwsCredentials, AwsSecretsManagerResolver, ResolvedSecret, SecretLease, SecretManager,
+    SecretReference, SecretResolver, VaultResolver,
+};
+
+// 加密配置值（`enc:<base64>`）
+#[cfg(feature = "encryption")]
+mod encrypted;
+#[cfg(feature = "encryption")]
+pub use encrypted::{
+    CONFIG_KEY_ENV, CONFIG_KEY_FILE_ENV, CONFIG_KEY_ID_ENV, CONFIG_KEY_TENANT, ConfigCipher,
+    ENCRYPTED_VALUE_PREFIX,
+};
+
 /// 全局应用配置实例，使用 OnceLock 确保只初始化一次
 static APP_CONFIG: OnceLock<FlareAppConfig> = OnceLock::new();
 
+/// 最近一次成功加载的配置指纹
+static CONFIG_FINGERPRINT: RwLock<Option<String>> = RwLock::new(None);
+
 /// Redis 连接池配置
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct RedisPoolConfig {
@@ -40,6 +100,18 @@ pub struct RedisPoolConfig {
     /// 过期时间（秒）
     #[serde(default)]
     pub ttl_seconds: Option<u64>,
+    /// 是否启用 TLS（`redis://` 地址按 `rediss://` 连接）
+    #[serde(default)]
+    pub tls: Option<bool>,
+    /// 建连超时（毫秒）
+    #[serde(default)]
+    pub connect_timeout_ms: Option<u64>,
+    /// 命令响应超时（毫秒）
+    #[serde(default)]
+    pub response_timeout_ms: Option<u64>,
+    /// 建连/断线重连的最大重试次数
+    #[serde(default)]
+    pub max_retries: Option<u32>,
 }
 
 /// Kafka 集群配置
@@ -59,9 +131,21 @@ pub struct KafkaClusterConfig {
     /// SASL 密码
     #[serde(default)]
     pub sasl_password: Option<String>,
+    /// SASL 机制（如 `PLAIN`、`SCRAM-SHA-512`，配置用户名时默认 `PLAIN`）
+    #[serde(default)]
+    pub sasl_mechanism: Option<String>,
+    /// TLS CA 证书路径（`SSL` / `SASL_SSL` 协议）
+    #[serde(default)]
+    pub ssl_ca_location: Option<String>,
     /// 超时时间（毫秒）
     #[serde(default)]
     pub timeout_ms: Option<u64>,
+    /// 生产者发送失败的重试次数
+    #[serde(default)]
+    pub retries: Option<u32>,
+    /// 生产者重试间隔（毫秒）
+    #[serde(default)]
+    pub retry_backoff_ms: Option<u64>,
     /// 其他选项
     #[serde(default)]
     pub options: HashMap<String, String>,
@@ -78,6 +162,21 @@ pub struct PostgresInstanceConfig {
     /// 最小连接数
     #[serde(default)]
     pub min_connections: Option<u32>,
+    /// 获取连接超时（毫秒）
+    #[serde(default)]
+    pub acquire_timeout_ms: Option<u64>,
+    /// 空闲连接回收时间（毫秒）
+    #[serde(default)]
+    pub idle_timeout_ms: Option<u64>,
+    /// TLS 模式（`disable` / `require` / `verify-full` 等，默认取 URL 中的 `sslmode`）
+    #[serde(default)]
+    pub ssl_mode: Option<String>,
+    /// TLS CA 证书路径
+    #[serde(default)]
+    pub ssl_root_cert: Option<String>,
+    /// 建连失败的最大重试次数
+    #[serde(default)]
+    pub max_retries: Option<u32>,
 }
 
 /// MongoDB 实例配置
@@ -88,6 +187,27 @@ pub struct MongoInstanceConfig {
     /// 数据库名称
     #[serde(default)]
     pub database: Option<String>,
+    /// 连接池最大连接数
+    #[serde(default)]
+    pub max_pool_size: Option<u32>,
+    /// 连接池最小连接数
+    #[serde(default)]
+    pub min_pool_size: Option<u32>,
+    /// 建连超时（毫秒）
+    #[serde(default)]
+    pub connect_timeout_ms: Option<u64>,
+    /// 是否启用 TLS（默认取 URL 中的 `tls` 参数）
+    #[serde(default)]
+    pub tls: Option<bool>,
+    /// TLS CA 证书路径
+    #[serde(default)]
+    pub tls_ca_file: Option<String>,
+    /// 是否重试写操作（默认取驱动默认值）
+    #[serde(default)]
+    pub retry_writes: Option<bool>,
+    /// 建连失败的最大重试次数
+    #[serde(default)]
+    pub max_retries: Option<u32>,
 }
 
 /// 对象存储配置
@@ -199,6 +319,9 @@ pub struct AccessGatewayServiceConfig {
     /// 令牌过期时间（秒）
     #[serde(default)]
     pub token_ttl_seconds: Option<u64>,
+    /// 令牌签名密钥文件（多密钥轮换，配置后优先于 token_secret，文件变更后自动重新加载）
+    #[serde(default)]
+    pub token_keys_file: Option<String>,
     /// 令牌存储
     #[serde(default)]
     pub token_store: Option<String>,
@@ -223,6 +346,12 @@ pub struct AccessGatewayServiceConfig {
     /// 加密密钥（32字节，hex编码或直接字符串，如果启用加密但未设置则使用默认密钥）
     #[serde(default)]
     pub encryption_key: Option<String>,
+    /// 长连接监听分片数（SO_REUSEPORT 多 acceptor，0 表示按CPU核数，未设置或1表示单 acceptor）
+    #[serde(default)]
+    pub listener_shards: Option<usize>,
+    /// 登录安全策略文件（TOML，按租户配置登录异常检测与 step-up，配置后启用）
+    #[serde(default)]
+    pub login_security_config: Option<String>,
 }
 
 /// 核心网关服务配置（业务系统统一入口）
@@ -286,6 +415,9 @@ pub struct CoreGatewayServiceConfig {
     /// JWT Token 过期时间（秒）
     #[serde(default)]
     pub token_ttl_seconds: Option<u64>,
+    /// JWT 签名密钥文件（多密钥轮换，配置后优先于 token_secret）
+    #[serde(default)]
+    pub token_keys_file: Option<String>,
 }
 
 /// 媒体服务配置
@@ -330,6 +462,12 @@ pub struct MediaServiceConfig {
     /// 最大分块大小（字节）
     #[serde(default)]
     pub max_chunk_size_bytes: Option<i64>,
+    /// Hook 配置
+    #[serde(default)]
+    pub hook_config: Option<String>,
+    /// Hook 配置目录
+    #[serde(default)]
+    pub hook_config_dir: Option<String>,
 }
 
 /// 推送代理服务配置
@@ -353,6 +491,18 @@ pub struct PushProxyServiceConfig {
     /// 超时时间（毫秒）
     #[serde(default)]
     pub timeout_ms: Option<u64>,
+    /// 受众标签存储（Redis 配置名称）
+    #[serde(default)]
+    pub audience_redis: Option<String>,
+    /// 受众人群包存储（PostgreSQL 配置名称）
+    #[serde(default)]
+    pub audience_postgres: Option<String>,
+    /// 受众解析后的最大用户数
+    #[serde(default)]
+    pub max_audience_size: Option<usize>,
+    /// 扇出分批大小（每个 Kafka 消息包含的最大用户数）
+    #[serde(default)]
+    pub fanout_batch_size: Option<usize>,
 }
 
 /// 推送服务器服务配置
@@ -394,6 +544,9 @@ pub struct PushServerServiceConfig {
     /// ACK 服务配置（从业务模块配置中读取，不再使用独立的 ack.yaml）
     #[serde(default)]
     pub ack: Option<AckServiceConfigSection>,
+    /// 租户隔离配置（按租户等级划分工作池）
+    #[serde(default)]
+    pub tenant_isolation: Option<TenantIsolationConfigSection>,
 }
 
 /// ACK 服务配置段（集成到业务模块配置中）
@@ -426,6 +579,53 @@ fn default_ack_batch_size() -> usize {
     100
 }
 
+/// 推送租户隔离配置段
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct TenantIsolationConfigSection {
+    /// 是否启用
+    #[serde(default)]
+    pub enabled: bool,
+    /// 所有租户共享的 worker 总数
+    #[serde(default)]
+    pub total_workers: Option<usize>,
+    /// 未配置等级的租户使用的等级
+    #[serde(default)]
+    pub default_tier: Option<String>,
+    /// 租户队列已满时暂存任务的总数上限
+    #[serde(default)]
+    pub max_spilled_tasks: Option<usize>,
+    /// 等级名 -> 等级规格
+    #[serde(default)]
+    pub tiers: HashMap<String, TenantTierConfigSection>,
+    /// 租户 ID -> 等级名
+    #[serde(default)]
+    pub tenants: HashMap<String, String>,
+}
+
+/// 租户等级规格
+#[derive(Debug, Clone, Deserialize)]
+pub struct TenantTierConfigSection {
+    /// 单个租户最多同时占用的 worker 数
+    #[serde(default = "default_tenant_tier_workers")]
+    pub workers: usize,
+    /// 单个租户的待处理队列上限
+    #[serde(default = "default_tenant_tier_queue_capacity")]
+    pub queue_capacity: usize,
+    /// 加权轮询权重
+    #[serde(default = "default_tenant_tier_weight")]
+    pub weight: u32,
+}
+
+fn default_tenant_tier_workers() -> usize {
+    4
+}
+fn default_tenant_tier_queue_capacity() -> usize {
+    1000
+}
+fn default_tenant_tier_weight() -> u32 {
+    1
+}
+
 /// 推送工作服务配置
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct PushWorkerServiceConfig {
@@ -502,6 +702,12 @@ pub struct SignalingOnlineServiceConfig {
     /// 在线状态前缀
     #[serde(default)]
     pub presence_prefix: Option<String>,
+    /// Hook 配置
+    #[serde(default)]
+    pub hook_config: Option<String>,
+    /// Hook 配置目录
+    #[serde(default)]
+    pub hook_config_dir: Option<String>,
 }
 
 /// 信令路由服务配置
@@ -588,6 +794,9 @@ pub struct SessionPolicyConfig {
     /// 是否允许历史同步
     #[serde(default)]
     pub allow_history_sync: Option<bool>,
+    /// 默认回执策略：full / delivery_only / none
+    #[serde(default)]
+    pub receipts: Option<String>,
 }
 
 /// 会话服务配置
@@ -627,6 +836,18 @@ pub struct ConversationServiceConfig {
     /// 默认策略配置
     #[serde(default)]
     pub default_policy: Option<SessionPolicyConfig>,
+    /// Kafka 配置（可选，用于发布用户合并映射事件）
+    #[serde(default)]
+    pub kafka: Option<String>,
+    /// 用户合并映射事件 Topic
+    #[serde(default)]
+    pub user_merge_topic: Option<String>,
+    /// Hook 配置
+    #[serde(default)]
+    pub hook_config: Option<String>,
+    /// Hook 配置目录
+    #[serde(default)]
+    pub hook_config_dir: Option<String>,
 }
 
 /// 日志配置
@@ -834,167 +1055,20 @@ impl FlareAppConfig {
 
     /// 验证配置引用
     ///
-    /// 检查服务配置中引用的基础设施配置是否存在
+    /// 检查服务配置中引用的基础设施配置是否存在（地址格式与端口冲突见 [`FlareAppConfig::validate`]）
     ///
     /// # 返回
-    /// 如果所有引用都有效，返回 Ok(())，否则返回错误信息
+    /// 如果所有引用都有效，返回 Ok(())，否则返回列出全部缺失配置的错误
     pub fn validate_references(&self) -> Result<()> {
-        // 验证接入网关配置
-        if let Some(cfg) = &self.services.access_gateway {
-            if let Some(token_store) = &cfg.token_store {
-                self.redis_profile(token_store).ok_or_else(|| {
-                    anyhow!("Redis config '{}' not found (token_store)", token_store)
-                })?;
-            }
-            if let Some(session_store) = &cfg.session_store {
-                self.redis_profile(session_store).ok_or_else(|| {
-                    anyhow!("Redis config '{}' not found (session_store)", session_store)
-                })?;
-            }
-        }
-
-        // 验证媒体服务配置
-        if let Some(cfg) = &self.services.media {
-            if let Some(metadata_store) = &cfg.metadata_store {
-                self.postgres_profile(metadata_store).ok_or_else(|| {
-                    anyhow!(
-                        "PostgreSQL config '{}' not found (metadata_store)",
-                        metadata_store
-                    )
-                })?;
-            }
-            if let Some(metadata_cache) = &cfg.metadata_cache {
-                self.redis_profile(metadata_cache).ok_or_else(|| {
-                    anyhow!(
-                        "Redis config '{}' not found (metadata_cache)",
-                        metadata_cache
-                    )
-                })?;
-            }
-            if let Some(object_store) = &cfg.object_store {
-                self.object_store_profile(object_store).ok_or_else(|| {
-                    anyhow!(
-                        "Object storage config '{}' not found (object_store)",
-                        object_store
-                    )
-                })?;
-            }
-            if let Some(upload_session_store) = &cfg.upload_session_store {
-                self.redis_profile(upload_session_store).ok_or_else(|| {
-                    anyhow!(
-                        "Redis config '{}' not found (upload_session_store)",
-                        upload_session_store
-                    )
-                })?;
-            }
-        }
-
-        // 验证推送服务配置
-        if let Some(cfg) = &self.services.push_proxy {
-            if let Some(kafka) = &cfg.kafka {
-                self.kafka_profile(kafka)
-                    .ok_or_else(|| anyhow!("Kafka config '{}' not found (push_proxy)", kafka))?;
-            }
-        }
-
-        if let Some(cfg) = &self.services.push_server {
-            if let Some(kafka) = &cfg.kafka {
-                self.kafka_profile(kafka)
-                    .ok_or_else(|| anyhow!("Kafka config '{}' not found (push_server)", kafka))?;
-            }
-            if let Some(redis) = &cfg.redis {
-                self.redis_profile(redis)
-                    .ok_or_else(|| anyhow!("Redis config '{}' not found (push_server)", redis))?;
-            }
-        }
-
-        if let Some(cfg) = &self.services.push_worker {
-            if let Some(kafka) = &cfg.kafka {
-                self.kafka_profile(kafka)
-                    .ok_or_else(|| anyhow!("Kafka config '{}' not found (push_worker)", kafka))?;
-            }
-        }
-
-        // 验证消息编排服务配置
-        if let Some(cfg) = &self.services.message_orchestrator {
-            if let Some(kafka) = &cfg.kafka {
-                self.kafka_profile(kafka).ok_or_else(|| {
-                    anyhow!("Kafka config '{}' not found (message_orchestrator)", kafka)
-                })?;
-            }
-            if let Some(wal_store) = &cfg.wal_store {
-                self.redis_profile(wal_store)
-                    .ok_or_else(|| anyhow!("Redis config '{}' not found (wal_store)", wal_store))?;
-            }
-        }
-
-        // 验证信令在线服务配置
-        if let Some(cfg) = &self.services.signaling_online {
-            if let Some(redis) = &cfg.redis {
-                self.redis_profile(redis).ok_or_else(|| {
-                    anyhow!("Redis config '{}' not found (signaling_online)", redis)
-                })?;
-            }
-        }
-
-        // 验证存储读取服务配置
-        if let Some(cfg) = &self.services.storage_reader {
-            if let Some(mongo) = &cfg.mongo {
-                self.mongodb_profile(mongo).ok_or_else(|| {
-                    anyhow!("MongoDB config '{}' not found (storage_reader)", mongo)
-                })?;
-            }
-            if let Some(redis) = &cfg.redis {
-                self.redis_profile(redis).ok_or_else(|| {
-                    anyhow!("Redis config '{}' not found (storage_reader)", redis)
-                })?;
-            }
-        }
-
-        // 验证存储写入服务配置
-        if let Some(cfg) = &self.services.storage_writer {
-            if let Some(kafka) = &cfg.kafka {
-                self.kafka_profile(kafka).ok_or_else(|| {
-                    anyhow!("Kafka config '{}' not found (storage_writer)", kafka)
-                })?;
-            }
-            if let Some(mongo) = &cfg.mongo {
-                self.mongodb_profile(mongo).ok_or_else(|| {
-                    anyhow!("MongoDB config '{}' not found (storage_writer)", mongo)
-                })?;
-            }
-            if let Some(postgres) = &cfg.postgres {
-                self.postgres_profile(postgres).ok_or_else(|| {
-                    anyhow!(
-                        "PostgreSQL config '{}' not found (storage_writer)",
-                        postgres
-                    )
-                })?;
-            }
-            if let Some(wal_store) = &cfg.wal_store {
-                self.redis_profile(wal_store).ok_or_else(|| {
-                    anyhow!(
-                        "Redis config '{}' not found (storage_writer.wal_store)",
-                        wal_store
-                    )
-                })?;
-            }
-        }
-
-        // 验证会话服务配置
-        if let Some(cfg) = &self.services.conversation {
-            if let Some(redis) = &cfg.redis {
-                self.redis_profile(redis)
-                    .ok_or_else(|| anyhow!("Redis config '{}' not found (conversation)", redis))?;
-            }
-        }
-
-        Ok(())
+        self.reference_report().into_result()
     }
 }
 
 /// 加载配置
 ///
+/// 加载失败时回退到默认配置，配置问题只记录警告；需要在配置有问题时拒绝启动
+/// （如 `FLARE_CONFIG_STRICT=1`）请使用 [`load_config_with_validation`]
+///
 /// # 参数
 /// * `path` - 配置路径，可以是目录或文件。如果为 None，尝试加载 "config" 目录或 "config.toml" 文件
 ///
@@ -1010,37 +1084,49 @@ impl FlareAppConfig {
 /// let config = load_config(Some("config"));
 /// ```
 pub fn load_config(path: Option<&str>) -> &'static FlareAppConfig {
-    // 确定配置文件候选路径
-    let candidates: Vec<PathBuf> = match path {
-        Some(p) => vec![PathBuf::from(p)],
-        None => vec![PathBuf::from("config"), PathBuf::from("config.toml")],
-    };
-
     // 使用 OnceLock 确保配置只初始化一次
     APP_CONFIG.get_or_init(|| {
         // 使用备选方案加载配置
-        let mut cfg = load_with_fallback(&candidates);
+        let mut cfg = load_with_fallback(&config_candidates(path));
         // 加载环境特定配置
         if let Err(e) = manager::ConfigManager::load_environment_config(&mut cfg) {
             warn!("failed to load environment config: {}", e);
         }
-        // 验证配置引用（可选，生产环境建议启用）
+        // 验证配置引用（生产环境建议设置 FLARE_CONFIG_STRICT=1）
         if let Err(e) = cfg.validate_references() {
             warn!("configuration reference validation failed: {}", e);
             // 注意：这里只警告，不失败，允许配置在开发环境中不完整
-            // 生产环境应该确保所有引用都有效
         }
         cfg
     })
 }
 
+/// 加载配置（不缓存、不回退到默认配置）
+///
+/// 任一环节失败都返回错误，供严格模式与 `validate` 工具使用
+pub fn load_config_from(path: Option<&str>) -> Result<FlareAppConfig> {
+    let mut cfg = try_load_config(&config_candidates(path))?;
+    manager::ConfigManager::load_environment_config(&mut cfg)
+        .context("failed to load environment config")?;
+    Ok(cfg)
+}
+
+/// 配置文件候选路径
+fn config_candidates(path: Option<&str>) -> Vec<PathBuf> {
+    match path {
+        Some(p) => vec![PathBuf::from(p)],
+        None => vec![PathBuf::from("config"), PathBuf::from("config.toml")],
+    }
+}
+
 /// 加载并验证配置
 ///
-/// 与 `load_config` 相同，但会严格验证配置引用，如果验证失败会返回错误
+/// `strict` 时不回退到默认配置：加载失败或存在任何配置问题（引用、地址格式与端口冲突）
+/// 都返回错误，由调用方（服务入口）决定是否终止启动；非严格时与 `load_config` 相同
 ///
 /// # 参数
 /// * `path` - 配置路径
-/// * `strict` - 是否严格验证（如果为 true，验证失败会返回错误）
+/// * `strict` - 是否严格验证（服务入口通常传入 [`strict_mode`]，即 `FLARE_CONFIG_STRICT`）
 ///
 /// # 返回
 /// 成功返回配置实例，失败返回错误
@@ -1048,20 +1134,29 @@ pub fn load_config_with_validation(
     path: Option<&str>,
     strict: bool,
 ) -> Result<&'static FlareAppConfig> {
-    // 加载配置
-    let config = load_config(path);
-
-    // 根据 strict 参数决定是否严格验证配置引用
-    if strict {
-        config
-            .validate_references()
-            .with_context(|| "configuration validation failed")?;
-    } else {
+    if !strict {
+        let config = load_config(path);
         if let Err(e) = config.validate_references() {
             warn!("configuration reference validation failed: {}", e);
         }
+        return Ok(config);
     }
 
+    // 已初始化时只校验现有配置，未初始化时严格加载（失败不写入全局配置）
+    let config = match APP_CONFIG.get() {
+        Some(config) => config,
+        None => {
+            let cfg = load_config_from(path)?;
+            cfg.validate()
+                .into_result()
+                .with_context(|| "configuration validation failed")?;
+            return Ok(APP_CONFIG.get_or_init(|| cfg));
+        }
+    };
+    config
+        .validate()
+        .into_result()
+        .with_context(|| "configuration validation failed")?;
     Ok(config)
 }
 
@@ -1072,30 +1167,58 @@ pub fn app_config() -> &'static FlareAppConfig {
 
 /// 使用备选方案加载配置
 ///
-/// 按照候选路径列表依次尝试加载配置，如果都失败则使用默认配置
+/// 按照候选路径列表依次尝试加载配置（配置目录叠加 `FLARE_ENV` 对应的 `overrides/<env>` 覆盖文件），
+/// 并叠加配置中心的配置片段；
+/// 配置中心不可达时仅使用本地配置，都失败则使用默认配置
 fn load_with_fallback(candidates: &[PathBuf]) -> FlareAppConfig {
+    try_load_config(candidates).unwrap_or_else(|err| {
+        warn!("{err:#}, falling back to defaults");
+        default_config()
+    })
+}
+
+/// 按照候选路径列表依次尝试加载配置（叠加配置中心的配置片段），都失败时返回错误
+fn try_load_config(candidates: &[PathBuf]) -> Result<FlareAppConfig> {
+    let remote = load_config_center_value();
+
     // 遍历候选路径列表，尝试加载配置
     for path in candidates {
-        match load_config_from_source(path) {
-            Ok(mut cfg) => {
-                cfg.ensure_defaults();
-                return cfg;
+        let loaded = load_source_value(path).and_then(|mut raw| {
+            if let Some(remote) = &remote {
+                merge_value(&mut raw, remote.clone());
             }
+            value_to_config(raw, &path.display().to_string())
+        });
+        match loaded {
+            Ok(cfg) => return Ok(cfg),
             Err(err) => {
                 warn!("failed to load config from {}: {err}", path.display());
             }
         }
     }
 
-    // 如果所有候选路径都失败，则使用默认配置
-    warn!("no configuration source succeeded, falling back to defaults");
-    default_config()
+    // 本地配置均不可用时，仅使用配置中心的配置
+    if let Some(remote) = remote {
+        match value_to_config(remote, "config center") {
+            Ok(cfg) => return Ok(cfg),
+            Err(err) => warn!("failed to load config from config center: {err}"),
+        }
+    }
+
+    Err(anyhow!(
+        "no configuration source succeeded (tried {})",
+        candidates
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
 }
 
-/// 从源加载配置
+/// 从源加载原始配置
 ///
 /// 根据路径类型（文件或目录）加载配置
-fn load_config_from_source(path: &Path) -> Result<FlareAppConfig> {
+fn load_source_value(path: &Path) -> Result<Value> {
     // 检查配置路径是否存在
     if !path.exists() {
         return Err(anyhow!(
@@ -1111,29 +1234,132 @@ fn load_config_from_source(path: &Path) -> Result<FlareAppConfig> {
 
     // 根据路径类型加载配置
     if metadata.is_dir() {
-        load_config_from_directory(path)
+        load_directory_value(path)
     } else {
-        load_config_from_file(path)
+        load_fragment_value(path)
     }
 }
 
-/// 从文件加载配置
-///
-/// 读取并解析 TOML 配置文件
-fn load_config_from_file(path: &Path) -> Result<FlareAppConfig> {
-    // 读取配置文件内容
-    let content = fs::read_to_string(path)
-        .with_context(|| format!("unable to read config file: {}", Path::new(path).display()))?;
-    // 解析 TOML 格式的配置内容
-    let mut cfg: FlareAppConfig = toml::from_str(&content)
-        .context(format!("invalid config format: {}", Path::new(path).display()))?;
+/// 将合并后的原始配置转换为应用配置（先解析密钥引用、解密加密值）
+fn value_to_config(mut raw: Value, source: &str) -> Result<FlareAppConfig> {
+    let fingerprint = fingerprint_value(&raw);
+    resolve_secret_references(&mut raw).context(format!(
+        "unresolved secret reference after merging {source}"
+    ))?;
+    decrypt_config_values(&mut raw)
+        .context(format!("undecryptable config value after merging {source}"))?;
+    let mut cfg: FlareAppConfig = raw
+        .try_into()
+        .context(format!("invalid configuration after merging {source}"))?;
     // 确保配置有默认值
     cfg.ensure_defaults();
+    record_config_fingerprint(fingerprint);
     Ok(cfg)
 }
 
-/// 从目录加载配置
-fn load_config_from_directory(path: &Path) -> Result<FlareAppConfig> {
+/// 当前配置指纹（合并后、解析密钥引用前的原始配置摘要；尚未加载配置时返回 None）
+///
+/// 指纹不包含密钥内容，可用于比对不同实例是否运行同一份配置
+pub fn config_fingerprint() -> Option<String> {
+    CONFIG_FINGERPRINT.read().unwrap().clone()
+}
+
+/// 计算原始配置的指纹（SHA-256 前 16 位十六进制）
+fn fingerprint_value(raw: &Value) -> String {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::digest(raw.to_string().as_bytes());
+    hex::encode(&digest[..8])
+}
+
+fn record_config_fingerprint(fingerprint: String) {
+    info!(config_fingerprint = %fingerprint, "Configuration loaded");
+    *CONFIG_FINGERPRINT.write().unwrap() = Some(fingerprint);
+}
+
+/// 拉取配置中心的配置（未配置配置中心时返回 `Ok(None)`）
+#[cfg(feature = "config-center")]
+fn fetch_config_center_value() -> Result<Option<Value>> {
+    match ConfigCenterSource::from_env()? {
+        Some(source) => source
+            .fetch_blocking()
+            .with_context(|| format!("config center {} unavailable", source.endpoint()))
+            .map(Some),
+        None => Ok(None),
+    }
+}
+
+/// 未启用 `config-center` feature 时不使用配置中心
+#[cfg(not(feature = "config-center"))]
+fn fetch_config_center_value() -> Result<Option<Value>> {
+    Ok(None)
+}
+
+/// 拉取配置中心的配置，失败时告警并回退到本地配置
+fn load_config_center_value() -> Option<Value> {
+    fetch_config_center_value().unwrap_or_else(|err| {
+        warn!("{err:#}, falling back to local configuration");
+        None
+    })
+}
+
+/// 替换配置中的密钥引用（`vault:kv/path#key` 等），存在租约时在运行时内启动续约
+#[cfg(feature = "secrets")]
+fn resolve_secret_references(raw: &mut Value) -> Result<()> {
+    let manager = SecretManager::global();
+    manager.resolve_value_blocking(raw)?;
+    manager.ensure_lease_renewal();
+    Ok(())
+}
+
+/// 未启用 `secrets` feature 时密钥引用视为配置错误，避免把引用当作明文凭证使用
+#[cfg(not(feature = "secrets"))]
+fn resolve_secret_references(raw: &mut Value) -> Result<()> {
+    match raw {
+        Value::String(s) => match s.split_once(':') {
+            Some((scheme, _)) if ["vault", "aws-sm"].contains(&scheme) => Err(anyhow!(
+                "secret reference {s:?} requires the `secrets` feature"
+            )),
+            _ => Ok(()),
+        },
+        Value::Array(items) => items.iter_mut().try_for_each(resolve_secret_references),
+        Value::Table(table) => table
+            .iter_mut()
+            .try_for_each(|(_, item)| resolve_secret_references(item)),
+        _ => Ok(()),
+    }
+}
+
+/// 解密配置中的 `enc:<base64>` 值
+#[cfg(feature = "encryption")]
+fn decrypt_config_values(raw: &mut Value) -> Result<()> {
+    let decrypted = encrypted::decrypt_config_value(raw)?;
+    if decrypted > 0 {
+        info!(decrypted, "Decrypted encrypted config values");
+    }
+    Ok(())
+}
+
+/// 未启用 `encryption` feature 时加密值视为配置错误，避免把密文当作凭证使用
+#[cfg(not(feature = "encryption"))]
+fn decrypt_config_values(raw: &mut Value) -> Result<()> {
+    match raw {
+        Value::String(s) if s.starts_with("enc:") => Err(anyhow!(
+            "encrypted config value requires the `encryption` feature"
+        )),
+        Value::Array(items) => items.iter_mut().try_for_each(decrypt_config_values),
+        Value::Table(table) => table
+            .iter_mut()
+            .try_for_each(|(_, item)| decrypt_config_values(item)),
+        _ => Ok(()),
+    }
+}
+
+/// 合并目录中的配置片段（base.toml → shared → services → overrides/<FLARE_ENV>）
+///
+/// `overrides` 中只合并当前环境的覆盖文件（见 [`overlay`]），
+/// `shared` / `services` / `overrides` 中的片段可以是 TOML、YAML 或 JSON
+fn load_directory_value(path: &Path) -> Result<Value> {
     let base_file = path.join("base.toml");
     if !base_file.exists() {
         return Err(anyhow!(
@@ -1142,7 +1368,7 @@ fn load_config_from_directory(path: &Path) -> Result<FlareAppConfig> {
         ));
     }
 
-    let mut merged = load_toml_value(&base_file)?;
+    let mut merged = load_fragment_value(&base_file)?;
 
     if !merged.is_table() {
         return Err(anyhow!(
@@ -1153,13 +1379,13 @@ fn load_config_from_directory(path: &Path) -> Result<FlareAppConfig> {
 
     merge_directory(&mut merged, &path.join("shared"))?;
     merge_directory(&mut merged, &path.join("services"))?;
-    merge_directory(&mut merged, &path.join("overrides"))?;
+    overlay::merge_environment_overlay(
+        &mut merged,
+        &path.join("overrides"),
+        &ConfigManager::get_environment(),
+    )?;
 
-    let cfg: FlareAppConfig = merged
-        .try_into()
-        .context(format!("invalid configuration after merging {}", path.display()))?;
-
-    Ok(cfg)
+    Ok(merged)
 }
 
 /// 合并目录中的配置
@@ -1171,32 +1397,32 @@ fn merge_directory(root: &mut Value, dir: &Path) -> Result<()> {
     let mut entries = fs::read_dir(dir)
         .context(format!("unable to read config directory {}", dir.display()))?
         .filter_map(|entry| entry.ok())
-        .filter(|entry| {
-            entry
-                .path()
-                .extension()
-                .and_then(OsStr::to_str)
-                .map(|ext| ext.eq_ignore_ascii_case("toml"))
-                .unwrap_or(false)
-        })
+        .filter(|entry| ConfigFormat::from_path(&entry.path()).is_some())
         .collect::<Vec<_>>();
 
     entries.sort_by_key(|entry| entry.path());
 
     for entry in entries {
-        let value = load_toml_value(&entry.path())?;
+        let value = load_fragment_value(&entry.path())?;
         merge_value(root, value);
     }
 
     Ok(())
 }
 
-/// 加载 TOML 值
-fn load_toml_value(path: &Path) -> Result<Value> {
+/// 加载配置片段（按扩展名解析 TOML / YAML / JSON，其他扩展名按 TOML 解析），
+/// 并替换字符串中的 `${ENV_VAR}` / `${ENV_VAR:-default}` 占位符
+fn load_fragment_value(path: &Path) -> Result<Value> {
+    let format = ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Toml);
     let content = fs::read_to_string(path)
         .context(format!("unable to read config fragment {}", path.display()))?;
-    let value: Value = toml::from_str(&content)
-        .context(format!("invalid TOML content in fragment {}", path.display()))?;
+    let mut value = format.parse(&content).context(format!(
+        "invalid {} content in fragment {}",
+        format.name(),
+        path.display()
+    ))?;
+    interpolate::interpolate_env(&mut value)
+        .context(format!("unresolved placeholder in fragment {}", path.display()))?;
     Ok(value)
 }
 
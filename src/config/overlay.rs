@@ -0,0 +1,141 @@
+//! 环境覆盖配置
+//!
+//! 配置目录的 `overrides/` 中每个文件对应一个环境（`overrides/dev.toml`、`overrides/prod.yaml` 等），
+//! 加载时只合并 `FLARE_ENV`（默认 `development`）对应的文件，同一份配置目录即可用于所有环境，
+//! 无需在部署时复制或替换覆盖文件。
+//!
+//! `dev` / `development`、`prod` / `production`、`stage` / `staging` 视为同一环境；
+//! 同一环境存在多个覆盖文件（如 `prod.toml` 与 `production.yaml`）时加载失败。
+//!
+//! `config/environments/` 中的对象存储配置（见 `ConfigManager::load_environment_config`）
+//! 使用同一张等价名称表选择文件。它在整个配置目录（包括 `overrides/`）合并并反序列化之后应用，
+//! 同名的 `object_storage` 配置以 `environments/` 中的为准（整项替换）。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as AnyhowContext, Result, anyhow};
+use toml::Value;
+use tracing::{debug, info};
+
+use super::format::ConfigFormat;
+use super::{load_fragment_value, merge_value};
+
+/// 同一环境的等价名称
+const ENVIRONMENT_ALIASES: &[&[&str]] = &[
+    &["dev", "development"],
+    &["prod", "production"],
+    &["stage", "staging"],
+];
+
+/// 合并当前环境的覆盖配置（`dir` 不存在或没有对应文件时不修改）
+pub(crate) fn merge_environment_overlay(
+    root: &mut Value,
+    dir: &Path,
+    environment: &str,
+) -> Result<()> {
+    match find_environment_file(dir, environment)? {
+        Some(overlay) => {
+            info!(
+                environment,
+                overlay = %overlay.display(),
+                "Applying environment configuration overlay"
+            );
+            merge_value(root, load_fragment_value(&overlay)?);
+        }
+        None => debug!(
+            environment,
+            dir = %dir.display(),
+            "No configuration overlay for environment"
+        ),
+    }
+    Ok(())
+}
+
+/// 在目录中查找当前环境（含等价名称）对应的配置文件
+///
+/// `dir` 不存在或没有对应文件时返回 None，同一环境存在多个文件时返回错误
+pub(crate) fn find_environment_file(dir: &Path, environment: &str) -> Result<Option<PathBuf>> {
+    if !dir.exists() {
+        return Ok(None);
+    }
+
+    let paths = fs::read_dir(dir)
+        .context(format!("unable to read config directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+
+    let mut overlays = select_overlays(paths, environment);
+    match overlays.len() {
+        0 => Ok(None),
+        1 => Ok(overlays.pop()),
+        _ => Err(anyhow!(
+            "multiple configuration overlays for environment {}: {}",
+            environment,
+            overlays
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// 环境名称及其等价名称（小写）
+fn environment_names(environment: &str) -> Vec<String> {
+    let environment = environment.trim().to_ascii_lowercase();
+    ENVIRONMENT_ALIASES
+        .iter()
+        .find(|aliases| aliases.contains(&environment.as_str()))
+        .map(|aliases| aliases.iter().map(|alias| alias.to_string()).collect())
+        .unwrap_or_else(|| vec![environment])
+}
+
+/// 选出属于该环境的覆盖文件（按文件名排序）
+fn select_overlays(paths: Vec<PathBuf>, environment: &str) -> Vec<PathBuf> {
+    let names = environment_names(environment);
+    let mut overlays: Vec<PathBuf> = paths
+        .into_iter()
+        .filter(|path| ConfigFormat::from_path(path).is_some())
+        .filter(|path| {
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|stem| names.contains(&stem.to_ascii_lowercase()))
+        })
+        .collect();
+    overlays.sort();
+    overlays
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names
+            .iter()
+            .map(|name| Path::new("config/overrides").join(name))
+            .collect()
+    }
+
+    #[test]
+    fn test_select_overlays_by_environment() {
+        let all = paths(&["dev.toml", "prod.yaml", "qa.json", "README.md"]);
+
+        assert_eq!(
+            select_overlays(all.clone(), "development"),
+            paths(&["dev.toml"])
+        );
+        assert_eq!(
+            select_overlays(all.clone(), "Production"),
+            paths(&["prod.yaml"])
+        );
+        assert_eq!(select_overlays(all.clone(), "qa"), paths(&["qa.json"]));
+        assert!(select_overlays(all, "staging").is_empty());
+
+        let ambiguous = paths(&["prod.toml", "production.yaml"]);
+        assert_eq!(select_overlays(ambiguous.clone(), "prod"), ambiguous);
+    }
+}
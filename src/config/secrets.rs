@@ -0,0 +1,978 @@
+//! 配置密钥解析（`secrets` feature）
+//!
+//! 加载配置时，将整个值为密钥引用的字符串替换为密钥内容，引用格式为 `<scheme>:<path>#<key>`：
+//!
+//! - `vault:secret/flare/redis#url`：Vault KV v2（挂载点在 `VAULT_KV_MOUNTS` 中）
+//! - `vault:database/creds/flare#password`：Vault 动态密钥（带租约，后台自动续约）
+//! - `aws-sm:flare/kafka#sasl_password`：AWS Secrets Manager（SecretString 为 JSON 对象时按键取值）
+//!
+//! 省略 `#key` 时密钥必须只有一个字段（AWS 纯文本密钥的字段名为 `value`）。
+//! 同一路径的多个字段共用一次读取，结果在缓存有效期内复用（带租约的密钥以租约到期为准）。
+//!
+//! 通过环境变量启用：
+//! - Vault：`VAULT_ADDR`、`VAULT_TOKEN`，可选 `VAULT_NAMESPACE`、`VAULT_KV_MOUNTS`（默认 `secret,kv`）
+//! - AWS：`AWS_REGION`（或 `AWS_DEFAULT_REGION`）、`AWS_ACCESS_KEY_ID`、`AWS_SECRET_ACCESS_KEY`，
+//!   可选 `AWS_SESSION_TOKEN`、`AWS_SECRETS_MANAGER_ENDPOINT`
+//! - `FLARE_SECRETS_CACHE_TTL_SECS`：无租约密钥的缓存时长（默认 300 秒）
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context as AnyhowContext, Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use toml::Value;
+use tracing::{debug, info, warn};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Vault 引用前缀
+pub const VAULT_SCHEME: &str = "vault";
+/// AWS Secrets Manager 引用前缀
+pub const AWS_SECRETS_MANAGER_SCHEME: &str = "aws-sm";
+/// 内置的引用前缀（未注册对应解析器时引用视为配置错误）
+pub(crate) const BUILTIN_SCHEMES: [&str; 2] = [VAULT_SCHEME, AWS_SECRETS_MANAGER_SCHEME];
+
+/// 缓存时长环境变量（秒）
+pub const SECRETS_CACHE_TTL_ENV: &str = "FLARE_SECRETS_CACHE_TTL_SECS";
+
+/// 默认缓存时长
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(300);
+/// 请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// 租约续约检查间隔
+const RENEWAL_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// 纯文本密钥的字段名
+const PLAIN_SECRET_FIELD: &str = "value";
+
+/// 进程级密钥管理器
+static GLOBAL_SECRET_MANAGER: OnceLock<Arc<SecretManager>> = OnceLock::new();
+
+/// 密钥租约
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretLease {
+    pub id: String,
+    pub duration: Duration,
+    pub renewable: bool,
+}
+
+/// 解析得到的密钥（一个路径下的全部字段）
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedSecret {
+    pub fields: HashMap<String, String>,
+    pub lease: Option<SecretLease>,
+}
+
+/// 密钥引用 `<scheme>:<path>#<key>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretReference {
+    pub scheme: String,
+    pub path: String,
+    pub key: Option<String>,
+}
+
+impl SecretReference {
+    /// 解析引用，前缀不在 `schemes` 中时返回 None（视为普通字符串）
+    pub fn parse(value: &str, schemes: &[&str]) -> Option<Self> {
+        let (scheme, rest) = value.split_once(':')?;
+        if !schemes.contains(&scheme) {
+            return None;
+        }
+        let (path, key) = match rest.split_once('#') {
+            Some((path, key)) => (path, Some(key.to_string())),
+            None => (rest, None),
+        };
+        Some(Self {
+            scheme: scheme.to_string(),
+            path: path.to_string(),
+            key,
+        })
+    }
+
+    /// 缓存键（同一路径的不同字段共用）
+    fn cache_key(&self) -> String {
+        format!("{}:{}", self.scheme, self.path)
+    }
+}
+
+/// 密钥解析器
+///
+/// 内置 Vault 与 AWS Secrets Manager 实现，嵌入方可注册自定义前缀的解析器
+#[async_trait]
+pub trait SecretResolver: Send + Sync {
+    /// 引用前缀（如 `vault`）
+    fn scheme(&self) -> &str;
+
+    /// 读取路径下的密钥
+    async fn resolve(&self, path: &str) -> Result<ResolvedSecret>;
+
+    /// 续约租约，返回新的租约时长
+    async fn renew(&self, lease: &SecretLease) -> Result<Duration> {
+        Err(anyhow!("{} does not support lease renewal", lease.id))
+    }
+}
+
+/// 缓存的密钥
+struct CachedSecret {
+    secret: ResolvedSecret,
+    /// 读取或最近一次续约的时间
+    renewed_at: Instant,
+    expires_at: Instant,
+}
+
+/// 密钥管理器：按前缀分发解析器，缓存密钥并续约租约
+pub struct SecretManager {
+    resolvers: HashMap<String, Arc<dyn SecretResolver>>,
+    cache: Mutex<HashMap<String, CachedSecret>>,
+    cache_ttl: Duration,
+    renewal_started: AtomicBool,
+}
+
+impl Default for SecretManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecretManager {
+    pub fn new() -> Self {
+        Self {
+            resolvers: HashMap::new(),
+            cache: Mutex::new(HashMap::new()),
+            cache_ttl: DEFAULT_CACHE_TTL,
+            renewal_started: AtomicBool::new(false),
+        }
+    }
+
+    /// 根据环境变量注册内置解析器
+    pub fn from_env() -> Self {
+        let mut manager = Self::new();
+        if let Some(ttl) = env::var(SECRETS_CACHE_TTL_ENV)
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+        {
+            manager = manager.with_cache_ttl(Duration::from_secs(ttl));
+        }
+        match VaultResolver::from_env() {
+            Ok(Some(vault)) => manager = manager.with_resolver(Arc::new(vault)),
+            Ok(None) => {}
+            Err(err) => warn!("Vault secret resolver disabled: {err:#}"),
+        }
+        match AwsSecretsManagerResolver::from_env() {
+            Ok(Some(aws)) => manager = manager.with_resolver(Arc::new(aws)),
+            Ok(None) => {}
+            Err(err) => warn!("AWS Secrets Manager resolver disabled: {err:#}"),
+        }
+        manager
+    }
+
+    /// 进程级密钥管理器（配置加载使用，首次访问时从环境变量初始化）
+    pub fn global() -> &'static Arc<SecretManager> {
+        GLOBAL_SECRET_MANAGER.get_or_init(|| Arc::new(Self::from_env()))
+    }
+
+    /// 安装进程级密钥管理器（注册自定义解析器），须在 `load_config` 之前调用；
+    /// 已初始化时返回 false
+    pub fn install_global(manager: SecretManager) -> bool {
+        GLOBAL_SECRET_MANAGER.set(Arc::new(manager)).is_ok()
+    }
+
+    /// 注册解析器（同一前缀后注册的覆盖先注册的）
+    pub fn with_resolver(mut self, resolver: Arc<dyn SecretResolver>) -> Self {
+        self.resolvers
+            .insert(resolver.scheme().to_string(), resolver);
+        self
+    }
+
+    /// 设置无租约密钥的缓存时长
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// 已注册的引用前缀
+    pub fn schemes(&self) -> Vec<&str> {
+        self.resolvers.keys().map(String::as_str).collect()
+    }
+
+    /// 解析单个引用
+    pub async fn resolve(&self, reference: &SecretReference) -> Result<String> {
+        self.fetch(reference).await?;
+        self.lookup(reference)
+    }
+
+    /// 替换配置中的全部密钥引用，返回替换的数量
+    pub async fn resolve_value(&self, value: &mut Value) -> Result<usize> {
+        let mut references = Vec::new();
+        self.collect_references(value, &mut references)?;
+        if references.is_empty() {
+            return Ok(0);
+        }
+        for reference in &references {
+            self.fetch(reference).await?;
+        }
+        self.substitute(value)
+    }
+
+    /// 在独立线程的运行时中替换密钥引用（可在同步上下文或 tokio 运行时内调用）
+    pub fn resolve_value_blocking(&self, value: &mut Value) -> Result<usize> {
+        let mut references = Vec::new();
+        self.collect_references(value, &mut references)?;
+        if references.is_empty() {
+            return Ok(0);
+        }
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .context("failed to build secret resolver runtime")?
+                        .block_on(self.resolve_value(value))
+                })
+                .join()
+                .map_err(|_| anyhow!("secret resolver thread panicked"))?
+        })
+    }
+
+    /// 续约即将到期的租约（已过租约时长的 2/3），无法续约的密钥移出缓存，
+    /// 下次加载配置时重新读取；返回续约成功的数量
+    pub async fn renew_leases(&self) -> usize {
+        let now = Instant::now();
+        let due: Vec<(String, SecretLease)> = {
+            let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            cache
+                .iter()
+                .filter_map(|(key, cached)| {
+                    let lease = cached.secret.lease.as_ref()?;
+                    (now.duration_since(cached.renewed_at) >= lease.duration * 2 / 3)
+                        .then(|| (key.clone(), lease.clone()))
+                })
+                .collect()
+        };
+
+        let mut renewed = 0;
+        for (key, lease) in due {
+            let scheme = key.split_once(':').map(|(scheme, _)| scheme).unwrap_or("");
+            let result = match self.resolvers.get(scheme) {
+                Some(resolver) if lease.renewable => resolver.renew(&lease).await,
+                _ => Err(anyhow!("lease is not renewable")),
+            };
+            let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            match result {
+                Ok(duration) => {
+                    if let Some(cached) = cache.get_mut(&key) {
+                        let now = Instant::now();
+                        cached.renewed_at = now;
+                        cached.expires_at = now + duration;
+                        if let Some(lease) = cached.secret.lease.as_mut() {
+                            lease.duration = duration;
+                        }
+                    }
+                    debug!(secret = %key, ttl_secs = duration.as_secs(), "Secret lease renewed");
+                    renewed += 1;
+                }
+                Err(err) => {
+                    warn!(secret = %key, lease_id = %lease.id, "Failed to renew secret lease: {err:#}");
+                    cache.remove(&key);
+                }
+            }
+        }
+        renewed
+    }
+
+    /// 启动后台租约续约任务
+    pub fn spawn_lease_renewal(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let manager = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RENEWAL_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                manager.renew_leases().await;
+            }
+        })
+    }
+
+    /// 缓存中存在租约且当前处于 tokio 运行时内时启动续约任务（只启动一次）
+    pub fn ensure_lease_renewal(self: &Arc<Self>) {
+        if tokio::runtime::Handle::try_current().is_err() || !self.has_leases() {
+            return;
+        }
+        if !self.renewal_started.swap(true, Ordering::SeqCst) {
+            self.spawn_lease_renewal();
+        }
+    }
+
+    fn has_leases(&self) -> bool {
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .any(|cached| cached.secret.lease.is_some())
+    }
+
+    /// 收集配置中的密钥引用
+    fn collect_references(
+        &self,
+        value: &Value,
+        references: &mut Vec<SecretReference>,
+    ) -> Result<()> {
+        match value {
+            Value::String(s) => {
+                if let Some(reference) = self.parse_reference(s)? {
+                    references.push(reference);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.collect_references(item, references)?;
+                }
+            }
+            Value::Table(table) => {
+                for (key, item) in table {
+                    self.collect_references(item, references)
+                        .map_err(|e| anyhow!("failed to resolve secret `{key}`: {e}"))?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// 用缓存中的密钥替换引用
+    fn substitute(&self, value: &mut Value) -> Result<usize> {
+        match value {
+            Value::String(s) => match self.parse_reference(s)? {
+                Some(reference) => {
+                    *s = self.lookup(&reference)?;
+                    Ok(1)
+                }
+                None => Ok(0),
+            },
+            Value::Array(items) => items.iter_mut().map(|item| self.substitute(item)).sum(),
+            Value::Table(table) => table
+                .iter_mut()
+                .map(|(key, item)| {
+                    self.substitute(item)
+                        .map_err(|e| anyhow!("failed to resolve secret `{key}`: {e}"))
+                })
+                .sum(),
+            _ => Ok(0),
+        }
+    }
+
+    fn parse_reference(&self, value: &str) -> Result<Option<SecretReference>> {
+        if let Some(reference) = SecretReference::parse(value, &self.schemes()) {
+            if reference.path.is_empty() {
+                return Err(anyhow!("secret reference {value:?} has no path"));
+            }
+            return Ok(Some(reference));
+        }
+        match SecretReference::parse(value, &BUILTIN_SCHEMES) {
+            Some(reference) => Err(anyhow!(
+                "no secret resolver configured for `{}` references",
+                reference.scheme
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// 读取引用所在路径的密钥（缓存有效时跳过）
+    async fn fetch(&self, reference: &SecretReference) -> Result<()> {
+        let key = reference.cache_key();
+        {
+            let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            if cache
+                .get(&key)
+                .is_some_and(|cached| cached.expires_at > Instant::now())
+            {
+                return Ok(());
+            }
+        }
+
+        let resolver = self
+            .resolvers
+            .get(&reference.scheme)
+            .ok_or_else(|| anyhow!("no secret resolver for `{}`", reference.scheme))?;
+        let secret = resolver
+            .resolve(&reference.path)
+            .await
+            .with_context(|| format!("failed to read secret {key}"))?;
+
+        let now = Instant::now();
+        let ttl = secret
+            .lease
+            .as_ref()
+            .map(|lease| lease.duration)
+            .unwrap_or(self.cache_ttl);
+        info!(
+            secret = %key,
+            fields = secret.fields.len(),
+            leased = secret.lease.is_some(),
+            "Resolved configuration secret"
+        );
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            key,
+            CachedSecret {
+                secret,
+                renewed_at: now,
+                expires_at: now + ttl,
+            },
+        );
+        Ok(())
+    }
+
+    fn lookup(&self, reference: &SecretReference) -> Result<String> {
+        let key = reference.cache_key();
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let fields = &cache
+            .get(&key)
+            .ok_or_else(|| anyhow!("secret {key} is not loaded"))?
+            .secret
+            .fields;
+        match &reference.key {
+            Some(field) => fields
+                .get(field)
+                .cloned()
+                .ok_or_else(|| anyhow!("secret {key} has no field `{field}`")),
+            None if fields.len() == 1 => Ok(fields.values().next().cloned().unwrap_or_default()),
+            None => Err(anyhow!(
+                "secret {key} has {} fields, specify one with `#<field>`",
+                fields.len()
+            )),
+        }
+    }
+}
+
+/// 将 JSON 对象展开为字段（非字符串值保留其 JSON 文本）
+fn json_fields(data: JsonValue) -> Result<HashMap<String, String>> {
+    match data {
+        JsonValue::Object(map) => Ok(map
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    JsonValue::String(s) => s,
+                    other => other.to_string(),
+                };
+                (key, value)
+            })
+            .collect()),
+        other => Err(anyhow!("secret data is not an object: {other}")),
+    }
+}
+
+/// Vault 解析器（KV v2 与动态密钥）
+pub struct VaultResolver {
+    client: reqwest::Client,
+    addr: String,
+    token: String,
+    namespace: Option<String>,
+    kv_mounts: Vec<String>,
+}
+
+/// Vault 响应
+#[derive(Debug, Deserialize)]
+struct VaultResponse {
+    #[serde(default)]
+    lease_id: String,
+    #[serde(default)]
+    lease_duration: u64,
+    #[serde(default)]
+    renewable: bool,
+    #[serde(default)]
+    data: Option<JsonValue>,
+}
+
+impl VaultResolver {
+    pub fn new(addr: impl Into<String>, token: impl Into<String>) -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .context("failed to build Vault HTTP client")?,
+            addr: addr.into().trim_end_matches('/').to_string(),
+            token: token.into(),
+            namespace: None,
+            kv_mounts: vec!["secret".to_string(), "kv".to_string()],
+        })
+    }
+
+    /// 从环境变量创建（未设置 `VAULT_ADDR` 时返回 None）
+    pub fn from_env() -> Result<Option<Self>> {
+        let addr = match env::var("VAULT_ADDR") {
+            Ok(addr) if !addr.trim().is_empty() => addr,
+            _ => return Ok(None),
+        };
+        let token = env::var("VAULT_TOKEN")
+            .ok()
+            .filter(|token| !token.trim().is_empty())
+            .ok_or_else(|| anyhow!("VAULT_ADDR is set but VAULT_TOKEN is missing"))?;
+
+        let mut resolver = Self::new(addr.trim(), token.trim())?;
+        if let Ok(namespace) = env::var("VAULT_NAMESPACE") {
+            resolver = resolver.with_namespace(namespace);
+        }
+        if let Ok(mounts) = env::var("VAULT_KV_MOUNTS") {
+            resolver = resolver.with_kv_mounts(mounts.split(','));
+        }
+        Ok(Some(resolver))
+    }
+
+    /// 设置 Vault 命名空间（企业版）
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into()).filter(|ns: &String| !ns.trim().is_empty());
+        self
+    }
+
+    /// 设置 KV v2 挂载点（其余路径按动态密钥读取）
+    pub fn with_kv_mounts<I, S>(mut self, mounts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.kv_mounts = mounts
+            .into_iter()
+            .map(|mount| mount.as_ref().trim().trim_matches('/').to_string())
+            .filter(|mount| !mount.is_empty())
+            .collect();
+        self
+    }
+
+    /// 读取地址，KV v2 路径插入 `data/`
+    fn read_url(&self, path: &str) -> (String, bool) {
+        let path = path.trim_matches('/');
+        match path.split_once('/') {
+            Some((mount, rest)) if self.kv_mounts.iter().any(|kv| kv == mount) => {
+                (format!("{}/v1/{mount}/data/{rest}", self.addr), true)
+            }
+            _ => (format!("{}/v1/{path}", self.addr), false),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("X-Vault-Token", &self.token);
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        request
+    }
+}
+
+#[async_trait]
+impl SecretResolver for VaultResolver {
+    fn scheme(&self) -> &str {
+        VAULT_SCHEME
+    }
+
+    async fn resolve(&self, path: &str) -> Result<ResolvedSecret> {
+        let (url, kv) = self.read_url(path);
+        let response: VaultResponse = self
+            .request(reqwest::Method::GET, &url)
+            .send()
+            .await
+            .context("failed to query Vault")?
+            .error_for_status()
+            .context("Vault returned an error status")?
+            .json()
+            .await
+            .context("invalid Vault response")?;
+
+        let mut data = response
+            .data
+            .ok_or_else(|| anyhow!("Vault response has no data"))?;
+        if kv {
+            data = data
+                .get_mut("data")
+                .map(JsonValue::take)
+                .ok_or_else(|| anyhow!("Vault KV response has no data"))?;
+        }
+        let lease =
+            (!response.lease_id.is_empty() && response.lease_duration > 0).then(|| SecretLease {
+                id: response.lease_id,
+                duration: Duration::from_secs(response.lease_duration),
+                renewable: response.renewable,
+            });
+        Ok(ResolvedSecret {
+            fields: json_fields(data)?,
+            lease,
+        })
+    }
+
+    async fn renew(&self, lease: &SecretLease) -> Result<Duration> {
+        let url = format!("{}/v1/sys/leases/renew", self.addr);
+        let response: VaultResponse = self
+            .request(reqwest::Method::PUT, &url)
+            .json(&serde_json::json!({
+                "lease_id": lease.id,
+                "increment": lease.duration.as_secs(),
+            }))
+            .send()
+            .await
+            .context("failed to renew Vault lease")?
+            .error_for_status()
+            .context("Vault returned an error status")?
+            .json()
+            .await
+            .context("invalid Vault response")?;
+        if response.lease_duration == 0 {
+            return Err(anyhow!("Vault lease {} was not extended", lease.id));
+        }
+        Ok(Duration::from_secs(response.lease_duration))
+    }
+}
+
+/// AWS 访问凭证
+#[derive(Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+/// AWS Secrets Manager 解析器（`GetSecretValue`，SigV4 签名）
+pub struct AwsSecretsManagerResolver {
+    client: reqwest::Client,
+    region: String,
+    endpoint: String,
+    host: String,
+    credentials: AwsCredentials,
+}
+
+/// GetSecretValue 响应
+#[derive(Debug, Deserialize)]
+struct GetSecretValueResponse {
+    #[serde(rename = "SecretString", default)]
+    secret_string: Option<String>,
+}
+
+impl AwsSecretsManagerResolver {
+    pub fn new(region: impl Into<String>, credentials: AwsCredentials) -> Result<Self> {
+        let region = region.into();
+        let endpoint = format!("https://secretsmanager.{region}.amazonaws.com/");
+        Self::with_endpoint(region, credentials, &endpoint)
+    }
+
+    /// 使用自定义地址（VPC 终端节点、LocalStack 等）
+    pub fn with_endpoint(
+        region: impl Into<String>,
+        credentials: AwsCredentials,
+        endpoint: &str,
+    ) -> Result<Self> {
+        let url = reqwest::Url::parse(endpoint)
+            .with_context(|| format!("invalid Secrets Manager endpoint {endpoint}"))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(anyhow!("Secrets Manager endpoint has no host: {endpoint}")),
+        };
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .context("failed to build Secrets Manager HTTP client")?,
+            region: region.into(),
+            endpoint: url.to_string(),
+            host,
+            credentials,
+        })
+    }
+
+    /// 从环境变量创建（未设置区域时返回 None）
+    pub fn from_env() -> Result<Option<Self>> {
+        let region = match env::var("AWS_REGION").or_else(|_| env::var("AWS_DEFAULT_REGION")) {
+            Ok(region) if !region.trim().is_empty() => region.trim().to_string(),
+            _ => return Ok(None),
+        };
+        let (Ok(access_key_id), Ok(secret_access_key)) = (
+            env::var("AWS_ACCESS_KEY_ID"),
+            env::var("AWS_SECRET_ACCESS_KEY"),
+        ) else {
+            return Ok(None);
+        };
+        let credentials = AwsCredentials {
+            access_key_id,
+            secret_access_key,
+            session_token: env::var("AWS_SESSION_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+        };
+        match env::var("AWS_SECRETS_MANAGER_ENDPOINT") {
+            Ok(endpoint) if !endpoint.trim().is_empty() => {
+                Self::with_endpoint(region, credentials, endpoint.trim()).map(Some)
+            }
+            _ => Self::new(region, credentials).map(Some),
+        }
+    }
+}
+
+#[async_trait]
+impl SecretResolver for AwsSecretsManagerResolver {
+    fn scheme(&self) -> &str {
+        AWS_SECRETS_MANAGER_SCHEME
+    }
+
+    async fn resolve(&self, path: &str) -> Result<ResolvedSecret> {
+        let body = serde_json::to_vec(&serde_json::json!({ "SecretId": path }))?;
+        let headers = sign_request(
+            &self.credentials,
+            &self.region,
+            &self.host,
+            "secretsmanager.GetSecretValue",
+            &body,
+            Utc::now(),
+        );
+        let mut request = self.client.post(&self.endpoint).body(body);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let response: GetSecretValueResponse = request
+            .send()
+            .await
+            .context("failed to query Secrets Manager")?
+            .error_for_status()
+            .context("Secrets Manager returned an error status")?
+            .json()
+            .await
+            .context("invalid Secrets Manager response")?;
+
+        let secret = response
+            .secret_string
+            .ok_or_else(|| anyhow!("secret {path} has no SecretString"))?;
+        let fields = match serde_json::from_str::<JsonValue>(&secret) {
+            Ok(data @ JsonValue::Object(_)) => json_fields(data)?,
+            _ => HashMap::from([(PLAIN_SECRET_FIELD.to_string(), secret)]),
+        };
+        Ok(ResolvedSecret {
+            fields,
+            lease: None,
+        })
+    }
+}
+
+/// 计算 Secrets Manager 请求的 SigV4 签名头
+fn sign_request(
+    credentials: &AwsCredentials,
+    region: &str,
+    host: &str,
+    target: &str,
+    body: &[u8],
+    now: DateTime<Utc>,
+) -> Vec<(&'static str, String)> {
+    const SERVICE: &str = "secretsmanager";
+    const CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    let mut headers = vec![
+        ("content-type", CONTENT_TYPE.to_string()),
+        ("host", host.to_string()),
+        ("x-amz-date", amz_date.clone()),
+    ];
+    if let Some(token) = &credentials.session_token {
+        headers.push(("x-amz-security-token", token.clone()));
+    }
+    headers.push(("x-amz-target", target.to_string()));
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{value}\n"))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "POST\n/\n\n{canonical_headers}\n{signed_headers}\n{}",
+        hex::encode(Sha256::digest(body))
+    );
+
+    let scope = format!("{date}/{region}/{SERVICE}/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let signing_key = [region, SERVICE, "aws4_request"].iter().fold(
+        hmac_sha256(
+            format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        ),
+        |key, part| hmac_sha256(&key, part.as_bytes()),
+    );
+    let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+    headers.retain(|(name, _)| *name != "host");
+    headers.push((
+        "authorization",
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            credentials.access_key_id
+        ),
+    ));
+    headers
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+
+    /// 内存解析器（记录读取与续约次数）
+    #[derive(Default)]
+    struct MemoryResolver {
+        reads: AtomicUsize,
+        renewals: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl SecretResolver for MemoryResolver {
+        fn scheme(&self) -> &str {
+            VAULT_SCHEME
+        }
+
+        async fn resolve(&self, path: &str) -> Result<ResolvedSecret> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            let (fields, lease) = match path {
+                "secret/flare/kafka" => (
+                    vec![("username", "flare"), ("password", "kafka-pass")],
+                    None,
+                ),
+                "database/creds/flare" => (
+                    vec![("password", "dyn-pass")],
+                    Some(SecretLease {
+                        id: "database/creds/flare/abc".to_string(),
+                        duration: Duration::from_millis(30),
+                        renewable: true,
+                    }),
+                ),
+                other => return Err(anyhow!("secret {other} not found")),
+            };
+            Ok(ResolvedSecret {
+                fields: fields
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                lease,
+            })
+        }
+
+        async fn renew(&self, lease: &SecretLease) -> Result<Duration> {
+            self.renewals.fetch_add(1, Ordering::SeqCst);
+            Ok(lease.duration)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_references_and_renew_leases() {
+        let resolver = Arc::new(MemoryResolver::default());
+        let manager = SecretManager::new().with_resolver(resolver.clone());
+        let mut value: Value = toml::from_str(
+            r#"
+            [kafka.default]
+            bootstrap_servers = "kafka:9092"
+            sasl_username = "vault:secret/flare/kafka#username"
+            sasl_password = "vault:secret/flare/kafka#password"
+
+            [redis.default]
+            url = "redis://localhost:6379"
+
+            [postgres.default]
+            password = "vault:database/creds/flare"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(manager.resolve_value(&mut value).await.unwrap(), 3);
+        assert_eq!(
+            value["kafka"]["default"]["sasl_username"].as_str(),
+            Some("flare")
+        );
+        assert_eq!(
+            value["kafka"]["default"]["sasl_password"].as_str(),
+            Some("kafka-pass")
+        );
+        assert_eq!(
+            value["postgres"]["default"]["password"].as_str(),
+            Some("dyn-pass")
+        );
+        assert_eq!(
+            value["redis"]["default"]["url"].as_str(),
+            Some("redis://localhost:6379")
+        );
+        assert_eq!(resolver.reads.load(Ordering::SeqCst), 2);
+
+        tokio::time::sleep(Duration::from_millis(25)).await;
+        assert_eq!(manager.renew_leases().await, 1);
+        assert_eq!(resolver.renewals.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reference_errors() {
+        let manager = SecretManager::new().with_resolver(Arc::new(MemoryResolver::default()));
+        for reference in [
+            "vault:secret/flare/kafka",
+            "vault:secret/flare/kafka#missing",
+            "vault:secret/flare/unknown#password",
+            "aws-sm:flare/kafka#password",
+        ] {
+            let mut value = Value::String(reference.to_string());
+            assert!(
+                manager.resolve_value(&mut value).await.is_err(),
+                "{reference} should fail"
+            );
+        }
+    }
+
+    #[test]
+    fn test_sign_request_matches_sigv4() {
+        let credentials = AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+        };
+        let now = DateTime::parse_from_rfc3339("2026-10-17T11:15:23Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let headers = sign_request(
+            &credentials,
+            "us-east-1",
+            "secretsmanager.us-east-1.amazonaws.com",
+            "secretsmanager.GetSecretValue",
+            br#"{"SecretId":"flare/kafka"}"#,
+            now,
+        );
+        let authorization = headers
+            .iter()
+            .find(|(name, _)| *name == "authorization")
+            .map(|(_, value)| value.as_str());
+        assert_eq!(
+            authorization,
+            Some(
+                "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20261017/us-east-1/secretsmanager/aws4_request, \
+                 SignedHeaders=content-type;host;x-amz-date;x-amz-target, \
+                 Signature=eb2a478f34605813e16df05403496ee22a9875c4108600b94ac7fdab1c56d0c4"
+            )
+        );
+    }
+}
@@ -0,0 +1,598 @@
+//! 配置校验
+//!
+//! 一次性列出 `services` 表中的所有问题，而不是遇到第一个就停止：
+//! - 引用了不存在的 Redis / Kafka / PostgreSQL / MongoDB / 对象存储配置
+//! - 连接地址格式错误（Redis / PostgreSQL / MongoDB URL、Kafka `host:port` 列表、注册中心地址等）
+//! - 多个服务监听同一地址端口
+//!
+//! 设置 `FLARE_CONFIG_STRICT=1` 时 `load_config` 在存在任何问题时直接终止启动；
+//! 也可以用 `validate` 工具在发布前检查：`cargo run --bin validate -- config`
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::net::IpAddr;
+
+use anyhow::{Result, anyhow};
+use flare_server_core::RegistryConfig;
+
+use super::{FlareAppConfig, ServiceRuntimeConfig};
+
+/// 严格校验模式开关
+pub const CONFIG_STRICT_ENV: &str = "FLARE_CONFIG_STRICT";
+
+/// 是否启用严格校验模式（`FLARE_CONFIG_STRICT=1` / `true`）
+pub fn strict_mode() -> bool {
+    env::var(CONFIG_STRICT_ENV)
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// 配置问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigIssueKind {
+    /// 引用的基础设施配置不存在
+    MissingProfile,
+    /// 连接地址格式错误
+    InvalidEndpoint,
+    /// 端口冲突
+    PortConflict,
+}
+
+impl ConfigIssueKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MissingProfile => "missing-profile",
+            Self::InvalidEndpoint => "invalid-endpoint",
+            Self::PortConflict => "port-conflict",
+        }
+    }
+}
+
+/// 单个配置问题
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub kind: ConfigIssueKind,
+    /// 出问题的配置项（如 `services.push_server.kafka`）
+    pub path: String,
+    /// 问题描述与修复建议
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {}: {}",
+            self.kind.as_str(),
+            self.path,
+            self.message
+        )
+    }
+}
+
+/// 校验结果
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// 存在问题时转换为列出全部问题的错误
+    pub fn into_result(self) -> Result<()> {
+        if self.is_ok() {
+            Ok(())
+        } else {
+            Err(anyhow!("{self}"))
+        }
+    }
+
+    fn push(&mut self, kind: ConfigIssueKind, path: impl Into<String>, message: impl Into<String>) {
+        self.issues.push(ConfigIssue {
+            kind,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return write!(f, "configuration is valid");
+        }
+        write!(f, "{} configuration issue(s) found:", self.issues.len())?;
+        for issue in &self.issues {
+            write!(f, "\n  - {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FlareAppConfig {
+    /// 完整校验：配置引用、连接地址格式与端口冲突
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = self.reference_report();
+        self.check_endpoints(&mut report);
+        self.check_port_conflicts(&mut report);
+        report
+    }
+
+    /// 检查服务配置中引用的基础设施配置是否存在（列出全部缺失项）
+    pub(super) fn reference_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let services = &self.services;
+        let redis = ProfileKind::new("Redis", "redis", &self.redis);
+        let kafka = ProfileKind::new("Kafka", "kafka", &self.kafka);
+        let postgres = ProfileKind::new("PostgreSQL", "postgres", &self.postgres);
+        let mongodb = ProfileKind::new("MongoDB", "mongodb", &self.mongodb);
+        let object_storage =
+            ProfileKind::new("Object storage", "object_storage", &self.object_storage);
+
+        let mut check = |kind: &ProfileKind, service: &str, field: &str, name: &Option<String>| {
+            if let Some(name) = name {
+                kind.check(&mut report, &format!("services.{service}.{field}"), name);
+            }
+        };
+
+        if let Some(cfg) = &services.access_gateway {
+            check(&redis, "access_gateway", "token_store", &cfg.token_store);
+            check(
+                &redis,
+                "access_gateway",
+                "session_store",
+                &cfg.session_store,
+            );
+        }
+        if let Some(cfg) = &services.media {
+            check(&postgres, "media", "metadata_store", &cfg.metadata_store);
+            check(&redis, "media", "metadata_cache", &cfg.metadata_cache);
+            check(&object_storage, "media", "object_store", &cfg.object_store);
+            check(
+                &redis,
+                "media",
+                "upload_session_store",
+                &cfg.upload_session_store,
+            );
+        }
+        if let Some(cfg) = &services.push_proxy {
+            check(&kafka, "push_proxy", "kafka", &cfg.kafka);
+            check(&redis, "push_proxy", "audience_redis", &cfg.audience_redis);
+            check(
+                &postgres,
+                "push_proxy",
+                "audience_postgres",
+                &cfg.audience_postgres,
+            );
+        }
+        if let Some(cfg) = &services.push_server {
+            check(&kafka, "push_server", "kafka", &cfg.kafka);
+            check(&redis, "push_server", "redis", &cfg.redis);
+        }
+        if let Some(cfg) = &services.push_worker {
+            check(&kafka, "push_worker", "kafka", &cfg.kafka);
+        }
+        if let Some(cfg) = &services.message_orchestrator {
+            check(&kafka, "message_orchestrator", "kafka", &cfg.kafka);
+            check(&redis, "message_orchestrator", "wal_store", &cfg.wal_store);
+        }
+        if let Some(cfg) = &services.signaling_online {
+            check(&redis, "signaling_online", "redis", &cfg.redis);
+        }
+        if let Some(cfg) = &services.storage_reader {
+            check(&mongodb, "storage_reader", "mongo", &cfg.mongo);
+            check(&redis, "storage_reader", "redis", &cfg.redis);
+        }
+        if let Some(cfg) = &services.storage_writer {
+            check(&kafka, "storage_writer", "kafka", &cfg.kafka);
+            check(&mongodb, "storage_writer", "mongo", &cfg.mongo);
+            check(&postgres, "storage_writer", "postgres", &cfg.postgres);
+            check(&redis, "storage_writer", "wal_store", &cfg.wal_store);
+        }
+        if let Some(cfg) = &services.conversation {
+            check(&redis, "conversation", "redis", &cfg.redis);
+            check(&postgres, "conversation", "postgres", &cfg.postgres);
+            check(&kafka, "conversation", "kafka", &cfg.kafka);
+        }
+
+        report
+    }
+
+    /// 检查基础设施配置与服务监听地址的格式
+    fn check_endpoints(&self, report: &mut ValidationReport) {
+        for (name, cfg) in sorted(&self.redis) {
+            if let Err(e) = check_url(&cfg.url, &["redis", "rediss", "redis+unix", "unix"]) {
+                report.push(
+                    ConfigIssueKind::InvalidEndpoint,
+                    format!("redis.{name}.url"),
+                    e,
+                );
+            }
+        }
+        for (name, cfg) in sorted(&self.kafka) {
+            let path = format!("kafka.{name}.bootstrap_servers");
+            if cfg.bootstrap_servers.trim().is_empty() {
+                report.push(
+                    ConfigIssueKind::InvalidEndpoint,
+                    path,
+                    "empty broker list, expected `host:port[,host:port...]`",
+                );
+                continue;
+            }
+            for broker in cfg.bootstrap_servers.split(',') {
+                let broker = broker.trim();
+                let broker = broker.split_once("://").map_or(broker, |(_, rest)| rest);
+                if let Err(e) = check_host_port(broker) {
+                    report.push(ConfigIssueKind::InvalidEndpoint, path.clone(), e);
+                }
+            }
+        }
+        for (name, cfg) in sorted(&self.postgres) {
+            if let Err(e) = check_url(&cfg.url, &["postgres", "postgresql"]) {
+                report.push(
+                    ConfigIssueKind::InvalidEndpoint,
+                    format!("postgres.{name}.url"),
+                    e,
+                );
+            }
+        }
+        for (name, cfg) in sorted(&self.mongodb) {
+            if let Err(e) = check_url(&cfg.url, &["mongodb", "mongodb+srv"]) {
+                report.push(
+                    ConfigIssueKind::InvalidEndpoint,
+                    format!("mongodb.{name}.url"),
+                    e,
+                );
+            }
+        }
+        for (name, cfg) in sorted(&self.object_storage) {
+            if let Some(endpoint) = &cfg.endpoint {
+                if let Err(e) = check_url_or_host_port(endpoint, &["http", "https"]) {
+                    report.push(
+                        ConfigIssueKind::InvalidEndpoint,
+                        format!("object_storage.{name}.endpoint"),
+                        e,
+                    );
+                }
+            }
+        }
+
+        if let Some(registry) = &self.core.registry {
+            check_registry(report, "registry", registry);
+        }
+        for (service, runtime) in self.service_runtimes() {
+            if let Some(server) = &runtime.server {
+                if let Some(address) = &server.address {
+                    if let Err(e) = check_host(address) {
+                        report.push(
+                            ConfigIssueKind::InvalidEndpoint,
+                            format!("services.{service}.server.address"),
+                            e,
+                        );
+                    }
+                }
+                if server.port == Some(0) {
+                    report.push(
+                        ConfigIssueKind::InvalidEndpoint,
+                        format!("services.{service}.server.port"),
+                        "port must be between 1 and 65535",
+                    );
+                }
+            }
+            if let Some(registry) = &runtime.registry {
+                check_registry(report, &format!("services.{service}.registry"), registry);
+            }
+        }
+    }
+
+    /// 检查显式配置了端口的服务是否监听同一地址端口
+    fn check_port_conflicts(&self, report: &mut ValidationReport) {
+        let mut bound: Vec<(&str, String, u16)> = Vec::new();
+        for (service, runtime) in self.service_runtimes() {
+            let Some(server) = &runtime.server else {
+                continue;
+            };
+            let Some(port) = server.port.filter(|port| *port != 0) else {
+                continue;
+            };
+            let address = server
+                .address
+                .clone()
+                .filter(|address| !address.is_empty())
+                .unwrap_or_else(|| self.core.server.address.clone());
+
+            for (other, other_address, other_port) in &bound {
+                if *other_port == port && addresses_overlap(&address, other_address) {
+                    report.push(
+                        ConfigIssueKind::PortConflict,
+                        format!("services.{service}.server.port"),
+                        format!(
+                            "{address}:{port} conflicts with services.{other}.server \
+                             ({other_address}:{other_port}), assign a different port"
+                        ),
+                    );
+                }
+            }
+            bound.push((service, address, port));
+        }
+    }
+
+    /// 所有已配置服务的运行时配置（按配置中的服务名）
+    fn service_runtimes(&self) -> Vec<(&'static str, &ServiceRuntimeConfig)> {
+        let s = &self.services;
+        let runtimes = [
+            (
+                "access_gateway",
+                s.access_gateway.as_ref().map(|c| &c.runtime),
+            ),
+            ("core_gateway", s.core_gateway.as_ref().map(|c| &c.runtime)),
+            ("media", s.media.as_ref().map(|c| &c.runtime)),
+            ("push_proxy", s.push_proxy.as_ref().map(|c| &c.runtime)),
+            ("push_server", s.push_server.as_ref().map(|c| &c.runtime)),
+            ("push_worker", s.push_worker.as_ref().map(|c| &c.runtime)),
+            (
+                "message_orchestrator",
+                s.message_orchestrator.as_ref().map(|c| &c.runtime),
+            ),
+            (
+                "signaling_online",
+                s.signaling_online.as_ref().map(|c| &c.runtime),
+            ),
+            (
+                "signaling_route",
+                s.signaling_route.as_ref().map(|c| &c.runtime),
+            ),
+            (
+                "storage_reader",
+                s.storage_reader.as_ref().map(|c| &c.runtime),
+            ),
+            (
+                "storage_writer",
+                s.storage_writer.as_ref().map(|c| &c.runtime),
+            ),
+            ("conversation", s.conversation.as_ref().map(|c| &c.runtime)),
+        ];
+        runtimes
+            .into_iter()
+            .filter_map(|(name, runtime)| runtime.map(|runtime| (name, runtime)))
+            .collect()
+    }
+}
+
+/// 一类基础设施配置（用于生成缺失配置的提示）
+struct ProfileKind<'a> {
+    label: &'static str,
+    table: &'static str,
+    names: Vec<&'a str>,
+}
+
+impl<'a> ProfileKind<'a> {
+    fn new<T>(label: &'static str, table: &'static str, profiles: &'a HashMap<String, T>) -> Self {
+        let mut names: Vec<&str> = profiles.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        Self {
+            label,
+            table,
+            names,
+        }
+    }
+
+    fn check(&self, report: &mut ValidationReport, path: &str, name: &str) {
+        if self.names.contains(&name) {
+            return;
+        }
+        let defined = if self.names.is_empty() {
+            "none defined".to_string()
+        } else {
+            format!("defined: {}", self.names.join(", "))
+        };
+        report.push(
+            ConfigIssueKind::MissingProfile,
+            path,
+            format!(
+                "{} config '{name}' not found, add a [{}.{name}] section or reference an existing one ({defined})",
+                self.label, self.table
+            ),
+        );
+    }
+}
+
+fn sorted<T>(profiles: &HashMap<String, T>) -> Vec<(&String, &T)> {
+    let mut entries: Vec<_> = profiles.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+fn check_registry(report: &mut ValidationReport, path: &str, registry: &RegistryConfig) {
+    if registry.endpoints.is_empty() {
+        report.push(
+            ConfigIssueKind::InvalidEndpoint,
+            format!("{path}.endpoints"),
+            "registry is configured but has no endpoints",
+        );
+    }
+    for endpoint in &registry.endpoints {
+        if let Err(e) = check_url_or_host_port(endpoint, &["http", "https"]) {
+            report.push(
+                ConfigIssueKind::InvalidEndpoint,
+                format!("{path}.endpoints"),
+                e,
+            );
+        }
+    }
+}
+
+/// 检查 `scheme://[user[:password]@]host[:port][/path]` 格式的连接地址
+fn check_url(url: &str, schemes: &[&str]) -> std::result::Result<(), String> {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return Err(format!(
+            "{url:?} is not a URL, expected `{}://host[:port]`",
+            schemes[0]
+        ));
+    };
+    if !schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme)) {
+        return Err(format!(
+            "unsupported scheme `{scheme}` in {url:?}, expected one of: {}",
+            schemes.join(", ")
+        ));
+    }
+    // unix 套接字地址只需要路径
+    if scheme.ends_with("unix") {
+        return if rest.is_empty() {
+            Err(format!("{url:?} is missing the socket path"))
+        } else {
+            Ok(())
+        };
+    }
+
+    let authority = rest.split(['/', '?']).next().unwrap_or_default();
+    let hosts = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, hosts)| hosts);
+    if hosts.is_empty() {
+        return Err(format!("{url:?} is missing the host"));
+    }
+    // MongoDB 允许 `host1:port,host2:port`
+    for host in hosts.split(',') {
+        if host.contains(':') && !host.ends_with(']') {
+            check_host_port(host).map_err(|e| format!("{e} in {url:?}"))?;
+        } else {
+            check_host(host).map_err(|e| format!("{e} in {url:?}"))?;
+        }
+    }
+    Ok(())
+}
+
+fn check_url_or_host_port(endpoint: &str, schemes: &[&str]) -> std::result::Result<(), String> {
+    if endpoint.contains("://") {
+        check_url(endpoint, schemes)
+    } else {
+        check_host_port(endpoint)
+    }
+}
+
+/// 检查 `host:port` / `[ipv6]:port` 格式
+fn check_host_port(endpoint: &str) -> std::result::Result<(), String> {
+    let Some((host, port)) = endpoint.rsplit_once(':') else {
+        return Err(format!(
+            "{endpoint:?} is missing a port, expected `host:port`"
+        ));
+    };
+    check_host(host)?;
+    match port.parse::<u16>() {
+        Ok(port) if port != 0 => Ok(()),
+        _ => Err(format!("invalid port `{port}` in {endpoint:?}")),
+    }
+}
+
+fn check_host(host: &str) -> std::result::Result<(), String> {
+    let host = host.trim();
+    if let Some(ipv6) = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+        return ipv6
+            .parse::<IpAddr>()
+            .map(|_| ())
+            .map_err(|_| format!("invalid IPv6 address `{host}`"));
+    }
+    let valid = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid host `{host}`"))
+    }
+}
+
+fn addresses_overlap(a: &str, b: &str) -> bool {
+    let unspecified = |address: &str| {
+        address
+            .trim_matches(['[', ']'])
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_unspecified())
+    };
+    a == b || unspecified(a) || unspecified(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{KafkaClusterConfig, RedisPoolConfig};
+
+    #[test]
+    fn test_endpoint_formats() {
+        assert!(check_url("redis://:secret@redis.internal:6379/0", &["redis"]).is_ok());
+        assert!(check_url("mongodb://a:27017,b:27017/?replicaSet=rs", &["mongodb"]).is_ok());
+        assert!(check_url("postgres://flare@[::1]:5432/flare", &["postgres"]).is_ok());
+        assert!(check_url("localhost:6379", &["redis"]).is_err());
+        assert!(check_url("http://localhost:6379", &["redis"]).is_err());
+        assert!(check_url("redis://localhost:70000", &["redis"]).is_err());
+
+        assert!(check_host_port("kafka-0.kafka:9092").is_ok());
+        assert!(check_host_port("[::1]:9092").is_ok());
+        assert!(check_host_port("kafka:0").is_err());
+        assert!(check_host_port("kafka").is_err());
+        assert!(check_url_or_host_port("http://consul:8500", &["http"]).is_ok());
+    }
+
+    #[test]
+    fn test_report_lists_every_issue() {
+        let mut config = crate::config::default_config();
+        config.redis.insert(
+            "cache".to_string(),
+            RedisPoolConfig {
+                url: "localhost:6379".to_string(),
+                ..Default::default()
+            },
+        );
+        config.kafka.insert(
+            "push".to_string(),
+            KafkaClusterConfig {
+                bootstrap_servers: "kafka:9092,kafka-1".to_string(),
+                ..Default::default()
+            },
+        );
+        config.services = toml::from_str(
+            r#"
+[push_server]
+kafka = "missing"
+redis = "cache"
+server = { address = "0.0.0.0", port = 50051 }
+
+[push_proxy]
+server = { address = "127.0.0.1", port = 50051 }
+"#,
+        )
+        .unwrap();
+
+        let report = config.validate();
+        let kinds: Vec<_> = report
+            .issues
+            .iter()
+            .map(|i| (i.kind, i.path.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    ConfigIssueKind::MissingProfile,
+                    "services.push_server.kafka"
+                ),
+                (ConfigIssueKind::InvalidEndpoint, "redis.cache.url"),
+                (
+                    ConfigIssueKind::InvalidEndpoint,
+                    "kafka.push.bootstrap_servers"
+                ),
+                (
+                    ConfigIssueKind::PortConflict,
+                    "services.push_server.server.port"
+                ),
+            ]
+        );
+        assert!(report.to_string().contains("defined: push"));
+    }
+}
@@ -0,0 +1,390 @@
+//! 配置热加载
+//!
+//! 监听配置目录（或单个配置文件），变更后重新加载并校验，校验通过时广播按配置段划分的变更事件：
+//! - 文件事件经过防抖合并，编辑器多次写入只触发一次重新加载
+//! - 重新加载失败或配置引用校验失败时保留当前配置，不广播
+//! - 配置中心等外部来源可调用 [`ConfigWatcher::reload_from`] 主动触发
+//! - 每次重新加载按键记录脱敏后的变更明细与失败原因（见 [`recent_reloads`](super::recent_reloads)）
+//!
+//! `load_config` 返回的全局配置不会被替换，需要热更新的服务订阅变更事件并读取事件中的新配置。
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context as AnyhowContext, Result};
+use chrono::Utc;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::{RwLock, broadcast, mpsc};
+use toml::Value;
+use tracing::{debug, info, warn};
+
+use super::audit::{ConfigReloadRecord, ReloadOutcome, ReloadTrigger, diff_values};
+use super::manager::ConfigManager;
+use super::{
+    FlareAppConfig, config_fingerprint, decrypt_config_values, fetch_config_center_value,
+    fingerprint_value, load_config_center_value, load_directory_value, load_fragment_value,
+    merge_value, record_config_fingerprint, resolve_secret_references, strict_mode,
+};
+
+/// 文件事件防抖时间
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 变更事件广播缓冲区大小
+const CHANGE_CHANNEL_CAPACITY: usize = 16;
+
+/// 发生变更的配置段
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigSection {
+    /// 核心配置（service / server / registry 等）
+    Core,
+    Logging,
+    Redis,
+    Kafka,
+    Postgres,
+    Mongodb,
+    ObjectStorage,
+    /// 单个服务配置（`services.<name>`）
+    Service(String),
+}
+
+/// 配置变更事件
+#[derive(Debug, Clone)]
+pub struct ConfigChangeEvent {
+    /// 配置版本（每次成功重新加载递增，初始加载为 0）
+    pub version: u64,
+    /// 发生变更的配置段
+    pub sections: Vec<ConfigSection>,
+    /// 变更后的完整配置
+    pub config: Arc<FlareAppConfig>,
+}
+
+impl ConfigChangeEvent {
+    /// 是否包含指定配置段的变更
+    pub fn affects(&self, section: &ConfigSection) -> bool {
+        self.sections.contains(section)
+    }
+
+    /// 是否包含指定服务配置的变更（如 `push_server`）
+    pub fn affects_service(&self, service: &str) -> bool {
+        self.sections
+            .iter()
+            .any(|section| matches!(section, ConfigSection::Service(name) if name == service))
+    }
+}
+
+struct LoadedConfig {
+    raw: Value,
+    config: Arc<FlareAppConfig>,
+}
+
+/// 配置监听器
+pub struct ConfigWatcher {
+    path: PathBuf,
+    current: RwLock<LoadedConfig>,
+    version: AtomicU64,
+    sender: broadcast::Sender<ConfigChangeEvent>,
+    /// 文件监听句柄（drop 后停止监听）
+    _fs_watcher: RecommendedWatcher,
+}
+
+impl ConfigWatcher {
+    /// 加载配置并开始监听（需要在 tokio 运行时中调用）
+    pub fn start(path: impl Into<PathBuf>) -> Result<Arc<Self>> {
+        let path = path.into();
+        let (raw, config) = load_validated(&path, false)?;
+
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let mut fs_watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+                Ok(event) if !event.kind.is_access() => {
+                    let _ = event_tx.send(event.paths);
+                }
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Config watcher error"),
+            })
+            .context("unable to create config watcher")?;
+        let mode = if path.is_dir() {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        fs_watcher
+            .watch(&path, mode)
+            .with_context(|| format!("unable to watch {}", path.display()))?;
+
+        let (sender, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+        let watcher = Arc::new(Self {
+            path,
+            current: RwLock::new(LoadedConfig {
+                raw,
+                config: Arc::new(config),
+            }),
+            version: AtomicU64::new(0),
+            sender,
+            _fs_watcher: fs_watcher,
+        });
+
+        Self::spawn_reload_loop(Arc::downgrade(&watcher), event_rx);
+        info!(path = %watcher.path.display(), "Config hot-reload enabled");
+        Ok(watcher)
+    }
+
+    /// 订阅配置变更事件
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfigChangeEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 当前生效的配置
+    pub async fn current(&self) -> Arc<FlareAppConfig> {
+        self.current.read().await.config.clone()
+    }
+
+    /// 当前配置版本
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed)
+    }
+
+    /// 手动重新加载配置（见 [`ConfigWatcher::reload_from`]）
+    pub async fn reload(&self) -> Result<Option<ConfigChangeEvent>> {
+        self.reload_from(ReloadTrigger::Manual).await
+    }
+
+    /// 重新加载配置
+    ///
+    /// 校验通过且内容有变化时替换当前配置并广播事件；内容无变化时返回 `Ok(None)`。
+    /// 变更明细与失败原因按 `trigger` 记录到审计日志
+    pub async fn reload_from(&self, trigger: ReloadTrigger) -> Result<Option<ConfigChangeEvent>> {
+        // 配置中心拉取为阻塞调用，放到阻塞线程池执行
+        let path = self.path.clone();
+        let loaded = tokio::task::spawn_blocking(move || load_validated(&path, true))
+            .await
+            .context("configuration reload task failed")
+            .and_then(|loaded| loaded);
+        let (raw, config) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                ConfigReloadRecord {
+                    version: self.version(),
+                    reloaded_at: Utc::now(),
+                    trigger,
+                    outcome: ReloadOutcome::Failed(format!("{e:#}")),
+                    fingerprint: None,
+                    changes: Vec::new(),
+                }
+                .record();
+                return Err(e);
+            }
+        };
+
+        let mut current = self.current.write().await;
+        let sections = diff_sections(&current.raw, &raw);
+        if sections.is_empty() {
+            return Ok(None);
+        }
+        let changes = diff_values(&current.raw, &raw);
+
+        let config = Arc::new(config);
+        *current = LoadedConfig {
+            raw,
+            config: config.clone(),
+        };
+        let event = ConfigChangeEvent {
+            version: self.version.fetch_add(1, Ordering::Relaxed) + 1,
+            sections,
+            config,
+        };
+        drop(current);
+
+        info!(
+            version = event.version,
+            trigger = %trigger,
+            sections = ?event.sections,
+            changed_keys = changes.len(),
+            "Configuration reloaded"
+        );
+        for change in &changes {
+            info!(
+                version = event.version,
+                key = %change.key,
+                change = change.kind.as_str(),
+                old = change.old_value.as_deref().unwrap_or("-"),
+                new = change.new_value.as_deref().unwrap_or("-"),
+                "Configuration key changed"
+            );
+        }
+        ConfigReloadRecord {
+            version: event.version,
+            reloaded_at: Utc::now(),
+            trigger,
+            outcome: ReloadOutcome::Applied,
+            fingerprint: config_fingerprint(),
+            changes,
+        }
+        .record();
+        // 没有订阅者时发送失败，忽略
+        let _ = self.sender.send(event.clone());
+        Ok(Some(event))
+    }
+
+    fn spawn_reload_loop(
+        watcher: std::sync::Weak<Self>,
+        mut events: mpsc::UnboundedReceiver<Vec<PathBuf>>,
+    ) {
+        tokio::spawn(async move {
+            while let Some(mut paths) = events.recv().await {
+                // 防抖：合并窗口内的后续事件
+                tokio::time::sleep(RELOAD_DEBOUNCE).await;
+                while let Ok(more) = events.try_recv() {
+                    paths.extend(more);
+                }
+                paths.sort();
+                paths.dedup();
+
+                let Some(watcher) = watcher.upgrade() else {
+                    break;
+                };
+                match watcher.reload_from(ReloadTrigger::Files(paths)).await {
+                    Ok(Some(_)) => {}
+                    Ok(None) => debug!("Config files touched without effective changes"),
+                    Err(e) => warn!(
+                        error = %e,
+                        "Failed to reload configuration, keeping current configuration"
+                    ),
+                }
+            }
+        });
+    }
+}
+
+/// 加载原始配置（叠加配置中心片段）并转换、校验
+///
+/// `require_center` 为 true 时配置中心不可达视为加载失败（保留当前配置），
+/// 否则告警并仅使用本地配置
+fn load_validated(path: &Path, require_center: bool) -> Result<(Value, FlareAppConfig)> {
+    let mut raw = if path.is_dir() {
+        load_directory_value(path)?
+    } else {
+        load_fragment_value(path)?
+    };
+    let remote = if require_center {
+        fetch_config_center_value()?
+    } else {
+        load_config_center_value()
+    };
+    if let Some(remote) = remote {
+        merge_value(&mut raw, remote);
+    }
+    let fingerprint = fingerprint_value(&raw);
+    resolve_secret_references(&mut raw)
+        .with_context(|| format!("unresolved secret reference in {}", path.display()))?;
+    // 在副本上解密，审计差异中只出现密文
+    let mut decrypted = raw.clone();
+    decrypt_config_values(&mut decrypted)
+        .with_context(|| format!("undecryptable config value in {}", path.display()))?;
+    let mut config: FlareAppConfig = decrypted
+        .try_into()
+        .with_context(|| format!("invalid configuration in {}", path.display()))?;
+    config.ensure_defaults();
+    ConfigManager::load_environment_config(&mut config)?;
+    // 严格模式下热加载同样拒绝存在地址格式或端口问题的配置
+    if strict_mode() {
+        config.validate().into_result()
+    } else {
+        config.validate_references()
+    }
+    .context("configuration validation failed")?;
+    record_config_fingerprint(fingerprint);
+    Ok((raw, config))
+}
+
+/// 比较两份原始配置，返回发生变更的配置段（按服务细分 `services`）
+fn diff_sections(old: &Value, new: &Value) -> Vec<ConfigSection> {
+    let empty = toml::map::Map::new();
+    let old = old.as_table().unwrap_or(&empty);
+    let new = new.as_table().unwrap_or(&empty);
+
+    let mut sections = BTreeSet::new();
+    for key in old.keys().chain(new.keys()) {
+        if old.get(key) == new.get(key) {
+            continue;
+        }
+        let section = match key.as_str() {
+            "logging" => ConfigSection::Logging,
+            "redis" => ConfigSection::Redis,
+            "kafka" => ConfigSection::Kafka,
+            "postgres" => ConfigSection::Postgres,
+            "mongodb" => ConfigSection::Mongodb,
+            "object_storage" => ConfigSection::ObjectStorage,
+            "services" => {
+                let old_services = old.get(key).and_then(Value::as_table).unwrap_or(&empty);
+                let new_services = new.get(key).and_then(Value::as_table).unwrap_or(&empty);
+                for service in old_services.keys().chain(new_services.keys()) {
+                    if old_services.get(service) != new_services.get(service) {
+                        sections.insert(ConfigSection::Service(service.clone()));
+                    }
+                }
+                continue;
+            }
+            _ => ConfigSection::Core,
+        };
+        sections.insert(section);
+    }
+    sections.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_sections() {
+        let old: Value = toml::from_str(
+            r#"
+            [service]
+            name = "flare"
+
+            [redis.default]
+            url = "redis://127.0.0.1:6379"
+
+            [services.push_server]
+            kafka = "push"
+
+            [services.conversation]
+            redis = "default"
+            "#,
+        )
+        .unwrap();
+        let new: Value = toml::from_str(
+            r#"
+            [service]
+            name = "flare"
+
+            [redis.default]
+            url = "redis://10.0.0.1:6379"
+
+            [services.push_server]
+            kafka = "push_v2"
+
+            [services.conversation]
+            redis = "default"
+
+            [services.media]
+            metadata_store = "media"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            diff_sections(&old, &new),
+            vec![
+                ConfigSection::Redis,
+                ConfigSection::Service("media".to_string()),
+                ConfigSection::Service("push_server".to_string()),
+            ]
+        );
+        assert!(diff_sections(&old, &old).is_empty());
+    }
+}
@@ -5,6 +5,7 @@
 use std::net::SocketAddr;
 use uuid::Uuid;
 
+use crate::build_info::BuildInfo;
 use crate::config::FlareAppConfig;
 use flare_server_core::{
     RegistryConfig,
@@ -145,6 +146,14 @@ pub async fn init_from_registry_config(
         instance = instance.with_namespace(&registry_config.namespace);
     }
 
+    // 添加构建信息（版本、git 提交、配置指纹）
+    if let Some(build_info) = BuildInfo::global() {
+        for (key, value) in build_info.registry_metadata() {
+            instance = instance.with_tag(key.clone(), value.clone());
+            instance.metadata.custom.insert(key, value);
+        }
+    }
+
     // 使用 DiscoveryFactory::register_and_discover 创建服务注册和发现
     let (registry, discover, updater) = DiscoveryFactory::register_and_discover(
         backend_type,
@@ -335,7 +344,7 @@ pub async fn register_service_from_registry_config_with_metadata(
     service_type: &str,
     service_address: SocketAddr,
     instance_id: Option<String>,
-    metadata: Option<std::collections::HashMap<String, String>>,
+    mut metadata: Option<std::collections::HashMap<String, String>>,
 ) -> Result<ServiceRegistry, Box<dyn std::error::Error + Send + Sync>> {
     let backend_type = parse_backend_type(&registry_config.registry_type)
         .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> { Box::from(e) })?;
@@ -398,6 +407,13 @@ pub async fn register_service_from_registry_config_with_metadata(
         instance = instance.with_namespace(&registry_config.namespace);
     }
 
+    // 添加构建信息（版本、git 提交、配置指纹），调用方提供的同名元数据优先
+    if let Some(build_info) = BuildInfo::global() {
+        let mut build_metadata = build_info.registry_metadata();
+        build_metadata.extend(metadata.unwrap_or_default());
+        metadata = Some(build_metadata);
+    }
+
     // 添加元数据（如果提供了）
     if let Some(metadata) = metadata {
         for (key, value) in metadata {
@@ -0,0 +1,130 @@
+//! 缓存值加密
+//!
+//! 消息缓存（Redis 热缓存、会话最后一条消息视图）保存完整的消息编码，启用字段加密后
+//! 整体加密，避免缓存成为绕过静态加密的明文副本。
+//!
+//! 缓存值格式：
+//! - 未启用加密：`base64(数据)`
+//! - 启用加密：`enc:{tenant_id}:{base64(信封)}`，租户ID明文保存用于选择数据密钥，信封绑定
+//!   租户与缓存键，改写租户前缀或把值搬到其他缓存键都会导致解密失败
+
+use anyhow::{Context, Result};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+use crate::encryption::FieldEncryptor;
+
+const SEALED_PREFIX: &str = "enc:";
+
+/// 编码缓存值，启用加密时使用租户当前密钥加密并绑定到 `cache_key`
+pub async fn seal_cache_value(
+    encryptor: Option<&FieldEncryptor>,
+    tenant_id: &str,
+    cache_key: &str,
+    data: &[u8],
+) -> Result<String> {
+    let Some(encryptor) = encryptor else {
+        return Ok(BASE64.encode(data));
+    };
+    let sealed = encryptor.encrypt(tenant_id, cache_key, data).await?;
+    Ok(format!(
+        "{}{}:{}",
+        SEALED_PREFIX,
+        tenant_id,
+        BASE64.encode(sealed)
+    ))
+}
+
+/// 解码缓存值（兼容启用加密前写入的明文缓存）
+pub async fn open_cache_value(
+    encryptor: Option<&FieldEncryptor>,
+    cache_key: &str,
+    value: &str,
+) -> Result<Vec<u8>> {
+    let Some(sealed) = value.strip_prefix(SEALED_PREFIX) else {
+        return BASE64.decode(value).context("Failed to decode cache value");
+    };
+    let encryptor = encryptor
+        .ok_or_else(|| anyhow::anyhow!("Encrypted cache value but encryption is not enabled"))?;
+    // base64 不含 `:`，从右侧切分以兼容包含 `:` 的租户ID
+    let (tenant_id, envelope) = sealed
+        .rsplit_once(':')
+        .ok_or_else(|| anyhow::anyhow!("Malformed encrypted cache value"))?;
+    let envelope = BASE64
+        .decode(envelope)
+        .context("Failed to decode encrypted cache value")?;
+    let opened = encryptor.decrypt(tenant_id, cache_key, &envelope).await?;
+    Ok(opened.plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::encryption::LocalKeyFileKms;
+
+    fn encryptor() -> FieldEncryptor {
+        let kms = LocalKeyFileKms::from_toml(
+            r#"
+            [[keys]]
+            key_id = "tenant-a-v1"
+            tenant_id = "tenant-a"
+            key = "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE="
+            active = true
+
+            [[keys]]
+            key_id = "tenant-b-v1"
+            tenant_id = "tenant-b"
+            key = "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI="
+            active = true
+            "#,
+        )
+        .unwrap();
+        FieldEncryptor::new(Arc::new(kms))
+    }
+
+    #[tokio::test]
+    async fn test_cache_value_round_trip_and_binding() {
+        let encryptor = encryptor();
+        let sealed = seal_cache_value(Some(&encryptor), "tenant-a", "cache:msg:c1:m1", b"hello")
+            .await
+            .unwrap();
+        assert!(sealed.starts_with("enc:tenant-a:"));
+        assert!(!sealed.contains(&BASE64.encode(b"hello")));
+        assert_eq!(
+            open_cache_value(Some(&encryptor), "cache:msg:c1:m1", &sealed)
+                .await
+                .unwrap(),
+            b"hello"
+        );
+
+        // 搬到其他缓存键、改写租户前缀或未启用加密时无法读取
+        assert!(
+            open_cache_value(Some(&encryptor), "cache:msg:c1:m2", &sealed)
+                .await
+                .is_err()
+        );
+        let retargeted = sealed.replacen("enc:tenant-a:", "enc:tenant-b:", 1);
+        assert!(
+            open_cache_value(Some(&encryptor), "cache:msg:c1:m1", &retargeted)
+                .await
+                .is_err()
+        );
+        assert!(
+            open_cache_value(None, "cache:msg:c1:m1", &sealed)
+                .await
+                .is_err()
+        );
+
+        // 启用加密前写入的明文缓存仍可读取
+        let plain = seal_cache_value(None, "tenant-a", "cache:msg:c1:m1", b"hello")
+            .await
+            .unwrap();
+        assert_eq!(
+            open_cache_value(Some(&encryptor), "cache:msg:c1:m1", &plain)
+                .await
+                .unwrap(),
+            b"hello"
+        );
+    }
+}
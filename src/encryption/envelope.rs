@@ -0,0 +1,441 @@
+//! 字段加密信封
+//!
+//! 信封格式（二进制）：
+//!
+//! | magic (4) | version (1) | key_id_len (1) | key_id | nonce (12) | ciphertext + tag |
+//!
+//! 版本 2 的 AAD 绑定 key_id、记录所属租户与记录ID（消息为 server_id），租户和记录ID由
+//! 调用方根据记录本身传入，密文被搬到其他租户或其他记录时解密失败；解密时还会校验数据密钥
+//! 属于该租户。版本 1 的 AAD 只有 key_id，仍可解密并标记为需要重新加密。
+//!
+//! 不以 magic 开头的数据视为历史明文，原样返回，保证启用加密前写入的数据仍可读取。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context, Result};
+use rand::RngCore;
+use tokio::sync::RwLock;
+
+use crate::encryption::kms::{DataKey, KmsProvider};
+use crate::metrics::FieldEncryptionMetrics;
+
+static METRICS: once_cell::sync::Lazy<FieldEncryptionMetrics> =
+    once_cell::sync::Lazy::new(FieldEncryptionMetrics::new);
+
+const MAGIC: &[u8; 4] = b"\xFAENC";
+const VERSION: u8 = 2;
+/// AAD 只包含 key_id 的旧版本信封
+const LEGACY_VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+
+/// 解密结果
+#[derive(Debug, Clone)]
+pub struct DecryptedField {
+    pub plaintext: Vec<u8>,
+    /// 加密使用的密钥ID（历史明文为 None）
+    pub key_id: Option<String>,
+    /// 密钥所属租户（历史明文为 None）
+    pub tenant_id: Option<String>,
+    /// 是否需要使用租户当前密钥重新加密（密钥已轮换或旧版本信封）
+    pub needs_reencrypt: bool,
+}
+
+/// 字段加密器
+///
+/// 按租户使用数据密钥进行 AES-256-GCM 加密，密钥ID写入信封用于解密和轮换
+pub struct FieldEncryptor {
+    kms: Arc<dyn KmsProvider>,
+    /// key_id -> 数据密钥（历史密钥不可变，可安全缓存）
+    key_cache: RwLock<HashMap<String, DataKey>>,
+}
+
+impl FieldEncryptor {
+    pub fn new(kms: Arc<dyn KmsProvider>) -> Self {
+        Self {
+            kms,
+            key_cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 使用本地密钥文件创建加密器
+    pub fn from_key_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let kms = crate::encryption::kms::LocalKeyFileKms::from_file(path)?;
+        Ok(Self::new(Arc::new(kms)))
+    }
+
+    /// 判断数据是否为加密信封
+    pub fn is_encrypted(data: &[u8]) -> bool {
+        data.len() > MAGIC.len() + 2
+            && data.starts_with(MAGIC)
+            && matches!(data[MAGIC.len()], VERSION | LEGACY_VERSION)
+    }
+
+    /// 使用租户当前密钥加密，密文绑定到 `tenant_id` 租户下 `record_id` 标识的记录
+    pub async fn encrypt(
+        &self,
+        tenant_id: &str,
+        record_id: &str,
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>> {
+        let data_key = self.kms.active_key(tenant_id).await?;
+        check_key_owner(&data_key, tenant_id)?;
+        seal(&data_key, tenant_id, record_id, plaintext)
+    }
+
+    /// 解密 `tenant_id` 租户下 `record_id` 标识的记录（历史明文原样返回）
+    ///
+    /// 租户和记录ID必须取自记录本身（如消息行的 tenant_id 与 server_id），失败时计入
+    /// `field_decrypt_failures_total` 指标
+    pub async fn decrypt(
+        &self,
+        tenant_id: &str,
+        record_id: &str,
+        data: &[u8],
+    ) -> Result<DecryptedField> {
+        let result = self.open(tenant_id, record_id, data).await;
+        if result.is_err() {
+            METRICS
+                .decrypt_failures_total
+                .with_label_values(&[tenant_id])
+                .inc();
+        }
+        result
+    }
+
+    async fn open(&self, tenant_id: &str, record_id: &str, data: &[u8]) -> Result<DecryptedField> {
+        if !Self::is_encrypted(data) {
+            return Ok(DecryptedField {
+                plaintext: data.to_vec(),
+                key_id: None,
+                tenant_id: None,
+                needs_reencrypt: false,
+            });
+        }
+
+        let legacy = data[MAGIC.len()] == LEGACY_VERSION;
+        let (key_id, nonce, ciphertext) = parse_envelope(data)?;
+        let data_key = self.cached_key(key_id).await?;
+        check_key_owner(&data_key, tenant_id)?;
+        let aad = if legacy {
+            key_id.as_bytes().to_vec()
+        } else {
+            associated_data(key_id, tenant_id, record_id)
+        };
+        let plaintext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key.key))
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| anyhow::anyhow!("Failed to decrypt field with key {}", key_id))?;
+
+        // 旧版本信封需要升级；租户的当前密钥已变化，说明发生过轮换
+        let needs_reencrypt = legacy
+            || match self.kms.active_key(tenant_id).await {
+                Ok(active) => active.key_id != key_id,
+                Err(_) => false,
+            };
+
+        Ok(DecryptedField {
+            plaintext,
+            key_id: Some(key_id.to_string()),
+            tenant_id: Some(data_key.tenant_id),
+            needs_reencrypt,
+        })
+    }
+
+    /// 使用租户当前密钥重新加密已解密的字段（用于密钥轮换后的惰性重加密）
+    pub async fn reencrypt(
+        &self,
+        record_id: &str,
+        field: &DecryptedField,
+    ) -> Result<Option<Vec<u8>>> {
+        match field.tenant_id {
+            Some(ref tenant_id) if field.needs_reencrypt => self
+                .encrypt(tenant_id, record_id, &field.plaintext)
+                .await
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    async fn cached_key(&self, key_id: &str) -> Result<DataKey> {
+        if let Some(key) = self.key_cache.read().await.get(key_id) {
+            return Ok(key.clone());
+        }
+
+        let key = self
+            .kms
+            .key_by_id(key_id)
+            .await
+            .with_context(|| format!("Failed to load data key {}", key_id))?;
+        self.key_cache
+            .write()
+            .await
+            .insert(key_id.to_string(), key.clone());
+        Ok(key)
+    }
+}
+
+/// 数据密钥必须属于记录所属租户
+fn check_key_owner(data_key: &DataKey, tenant_id: &str) -> Result<()> {
+    if data_key.tenant_id != tenant_id {
+        anyhow::bail!(
+            "Data key {} does not belong to tenant {}",
+            data_key.key_id,
+            tenant_id
+        );
+    }
+    Ok(())
+}
+
+/// 版本 2 的 AAD：各字段以 u16 长度前缀拼接，避免字段边界歧义
+fn associated_data(key_id: &str, tenant_id: &str, record_id: &str) -> Vec<u8> {
+    let mut aad = Vec::new();
+    for part in [key_id, tenant_id, record_id] {
+        aad.extend_from_slice(&(part.len() as u16).to_be_bytes());
+        aad.extend_from_slice(part.as_bytes());
+    }
+    aad
+}
+
+fn seal(data_key: &DataKey, tenant_id: &str, record_id: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let key_id = data_key.key_id.as_bytes();
+    if key_id.len() > u8::MAX as usize {
+        anyhow::bail!("Key id too long: {}", data_key.key_id);
+    }
+
+    if tenant_id.len() > u16::MAX as usize || record_id.len() > u16::MAX as usize {
+        anyhow::bail!("Tenant id or record id too long");
+    }
+
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let ciphertext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key.key))
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &associated_data(&data_key.key_id, tenant_id, record_id),
+            },
+        )
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt field with key {}", data_key.key_id))?;
+
+    let mut envelope =
+        Vec::with_capacity(MAGIC.len() + 2 + key_id.len() + NONCE_LEN + ciphertext.len());
+    envelope.extend_from_slice(MAGIC);
+    envelope.push(VERSION);
+    envelope.push(key_id.len() as u8);
+    envelope.extend_from_slice(key_id);
+    envelope.extend_from_slice(&nonce);
+    envelope.extend_from_slice(&ciphertext);
+    Ok(envelope)
+}
+
+fn parse_envelope(data: &[u8]) -> Result<(&str, &[u8], &[u8])> {
+    let header_len = MAGIC.len() + 2;
+    let key_id_len = data[MAGIC.len() + 1] as usize;
+    let key_id_end = header_len + key_id_len;
+    let nonce_end = key_id_end + NONCE_LEN;
+    if data.len() < nonce_end {
+        anyhow::bail!("Truncated encryption envelope");
+    }
+
+    let key_id = std::str::from_utf8(&data[header_len..key_id_end])
+        .context("Invalid key id in encryption envelope")?;
+    Ok((key_id, &data[key_id_end..nonce_end], &data[nonce_end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encryption::kms::LocalKeyFileKms;
+
+    const KEY_V1: &str = "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=";
+    const KEY_V2: &str = "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI=";
+
+    fn encryptor(active_v2: bool) -> FieldEncryptor {
+        let toml = format!(
+            r#"
+            [[keys]]
+            key_id = "tenant-a-v1"
+            tenant_id = "tenant-a"
+            key = "{KEY_V1}"
+            active = {}
+
+            [[keys]]
+            key_id = "tenant-a-v2"
+            tenant_id = "tenant-a"
+            key = "{KEY_V2}"
+            active = {}
+            "#,
+            !active_v2, active_v2
+        );
+        FieldEncryptor::new(Arc::new(LocalKeyFileKms::from_toml(&toml).unwrap()))
+    }
+
+    /// 按版本 1 格式（AAD 只有 key_id）构造信封
+    fn seal_v1(data_key: &DataKey, plaintext: &[u8]) -> Vec<u8> {
+        let key_id = data_key.key_id.as_bytes();
+        let nonce = [7u8; NONCE_LEN];
+        let ciphertext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key.key))
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: key_id,
+                },
+            )
+            .unwrap();
+        let mut envelope = MAGIC.to_vec();
+        envelope.push(LEGACY_VERSION);
+        envelope.push(key_id.len() as u8);
+        envelope.extend_from_slice(key_id);
+        envelope.extend_from_slice(&nonce);
+        envelope.extend_from_slice(&ciphertext);
+        envelope
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_roundtrip() {
+        let encryptor = encryptor(false);
+        let sealed = encryptor
+            .encrypt("tenant-a", "msg-1", b"hello")
+            .await
+            .unwrap();
+        assert!(FieldEncryptor::is_encrypted(&sealed));
+
+        let opened = encryptor
+            .decrypt("tenant-a", "msg-1", &sealed)
+            .await
+            .unwrap();
+        assert_eq!(opened.plaintext, b"hello");
+        assert_eq!(opened.key_id.as_deref(), Some("tenant-a-v1"));
+        assert!(!opened.needs_reencrypt);
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_passthrough() {
+        let encryptor = encryptor(false);
+        let opened = encryptor
+            .decrypt("tenant-a", "msg-1", b"legacy")
+            .await
+            .unwrap();
+        assert_eq!(opened.plaintext, b"legacy");
+        assert!(opened.key_id.is_none());
+        assert!(!opened.needs_reencrypt);
+    }
+
+    #[tokio::test]
+    async fn test_rotation_marks_reencrypt() {
+        let sealed = encryptor(false)
+            .encrypt("tenant-a", "msg-1", b"hello")
+            .await
+            .unwrap();
+
+        // 轮换到 v2 后仍可用 v1 解密，并提示重新加密
+        let rotated = encryptor(true);
+        let opened = rotated.decrypt("tenant-a", "msg-1", &sealed).await.unwrap();
+        assert_eq!(opened.plaintext, b"hello");
+        assert!(opened.needs_reencrypt);
+
+        let resealed = rotated.reencrypt("msg-1", &opened).await.unwrap().unwrap();
+        let opened = rotated
+            .decrypt("tenant-a", "msg-1", &resealed)
+            .await
+            .unwrap();
+        assert_eq!(opened.key_id.as_deref(), Some("tenant-a-v2"));
+        assert!(!opened.needs_reencrypt);
+    }
+
+    #[tokio::test]
+    async fn test_moved_ciphertext_fails() {
+        let encryptor = encryptor(false);
+        let sealed = encryptor
+            .encrypt("tenant-a", "msg-1", b"hello")
+            .await
+            .unwrap();
+        assert!(
+            encryptor
+                .decrypt("tenant-a", "msg-2", &sealed)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_legacy_envelope_marks_reencrypt() {
+        let encryptor = encryptor(false);
+        let data_key = encryptor.cached_key("tenant-a-v1").await.unwrap();
+        let sealed = seal_v1(&data_key, b"hello");
+        assert!(FieldEncryptor::is_encrypted(&sealed));
+
+        let opened = encryptor
+            .decrypt("tenant-a", "msg-1", &sealed)
+            .await
+            .unwrap();
+        assert_eq!(opened.plaintext, b"hello");
+        assert!(opened.needs_reencrypt);
+
+        let resealed = encryptor
+            .reencrypt("msg-1", &opened)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resealed[MAGIC.len()], VERSION);
+        let opened = encryptor
+            .decrypt("tenant-a", "msg-1", &resealed)
+            .await
+            .unwrap();
+        assert!(!opened.needs_reencrypt);
+    }
+
+    #[tokio::test]
+    async fn test_other_tenant_record_fails() {
+        let encryptor = encryptor(false);
+        let sealed = encryptor
+            .encrypt("tenant-a", "msg-1", b"hello")
+            .await
+            .unwrap();
+        // 密文被复制到其他租户的同ID记录：数据密钥不属于该租户
+        let err = encryptor
+            .decrypt("tenant-b", "msg-1", &sealed)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("does not belong to tenant"));
+    }
+
+    #[tokio::test]
+    async fn test_tampered_envelope_fails() {
+        let encryptor = encryptor(false);
+        let mut sealed = encryptor
+            .encrypt("tenant-a", "msg-1", b"hello")
+            .await
+            .unwrap();
+        let last = sealed.len() - 1;
+        sealed[last] ^= 0xFF;
+        assert!(
+            encryptor
+                .decrypt("tenant-a", "msg-1", &sealed)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tenant_fails() {
+        let encryptor = encryptor(false);
+        assert!(
+            encryptor
+                .encrypt("tenant-b", "msg-1", b"hello")
+                .await
+                .is_err()
+        );
+    }
+}
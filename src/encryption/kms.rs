@@ -0,0 +1,176 @@
+//! 数据密钥提供者
+//!
+//! `KmsProvider` 负责按租户提供当前生效的数据密钥，并按密钥ID查找历史密钥（用于解密旧数据）。
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose};
+use serde::Deserialize;
+
+/// 数据密钥长度（AES-256）
+pub const DATA_KEY_LEN: usize = 32;
+
+/// 数据密钥
+#[derive(Clone)]
+pub struct DataKey {
+    /// 密钥ID（全局唯一，写入加密信封）
+    pub key_id: String,
+    /// 所属租户
+    pub tenant_id: String,
+    /// 密钥内容
+    pub key: [u8; DATA_KEY_LEN],
+}
+
+impl std::fmt::Debug for DataKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出密钥内容
+        f.debug_struct("DataKey")
+            .field("key_id", &self.key_id)
+            .field("tenant_id", &self.tenant_id)
+            .finish()
+    }
+}
+
+/// 数据密钥提供者
+///
+/// 实现方可对接云厂商KMS（远程实现应自行缓存，避免每条消息都请求KMS）
+#[async_trait]
+pub trait KmsProvider: Send + Sync {
+    /// 获取租户当前生效的数据密钥（用于加密）
+    async fn active_key(&self, tenant_id: &str) -> Result<DataKey>;
+
+    /// 根据密钥ID获取数据密钥（用于解密）
+    async fn key_by_id(&self, key_id: &str) -> Result<DataKey>;
+}
+
+/// 本地密钥文件中的单个密钥
+#[derive(Debug, Deserialize)]
+struct KeyFileEntry {
+    key_id: String,
+    tenant_id: String,
+    /// Base64编码的32字节密钥
+    key: String,
+    #[serde(default)]
+    active: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyFile {
+    /// 未单独配置密钥的租户使用的密钥所属租户（可选）
+    #[serde(default)]
+    default_tenant: Option<String>,
+    #[serde(default)]
+    keys: Vec<KeyFileEntry>,
+}
+
+/// 基于本地密钥文件的密钥提供者
+///
+/// 密钥文件格式（TOML）：
+///
+/// ```toml
+/// default_tenant = "default"
+///
+/// [[keys]]
+/// key_id = "tenant-a-v1"
+/// tenant_id = "tenant-a"
+/// key = "<base64 32字节>"
+///
+/// [[keys]]
+/// key_id = "tenant-a-v2"
+/// tenant_id = "tenant-a"
+/// key = "<base64 32字节>"
+/// active = true
+/// ```
+///
+/// 轮换密钥时新增密钥并标记为 `active`，旧密钥保留用于解密历史数据
+pub struct LocalKeyFileKms {
+    keys: HashMap<String, DataKey>,
+    /// tenant_id -> 当前生效的 key_id
+    active: HashMap<String, String>,
+    default_tenant: Option<String>,
+}
+
+impl LocalKeyFileKms {
+    /// 从密钥文件加载
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read key file: {}", path.display()))?;
+        Self::from_toml(&content)
+    }
+
+    /// 从TOML内容加载
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: KeyFile = toml::from_str(content).context("Failed to parse key file")?;
+
+        let mut keys = HashMap::new();
+        let mut active = HashMap::new();
+        // 每个租户在文件中的最后一个密钥（未显式标记 active 时使用）
+        let mut latest: HashMap<String, String> = HashMap::new();
+        for entry in file.keys {
+            let bytes = general_purpose::STANDARD
+                .decode(entry.key.trim())
+                .with_context(|| format!("Invalid base64 key: {}", entry.key_id))?;
+            let key: [u8; DATA_KEY_LEN] = bytes.try_into().map_err(|_| {
+                anyhow::anyhow!("Key {} must be {} bytes", entry.key_id, DATA_KEY_LEN)
+            })?;
+
+            if entry.active
+                && let Some(previous) = active.insert(entry.tenant_id.clone(), entry.key_id.clone())
+            {
+                anyhow::bail!(
+                    "Tenant {} has multiple active keys: {} and {}",
+                    entry.tenant_id,
+                    previous,
+                    entry.key_id
+                );
+            }
+
+            latest.insert(entry.tenant_id.clone(), entry.key_id.clone());
+            let data_key = DataKey {
+                key_id: entry.key_id.clone(),
+                tenant_id: entry.tenant_id,
+                key,
+            };
+            if keys.insert(entry.key_id.clone(), data_key).is_some() {
+                anyhow::bail!("Duplicate key_id: {}", entry.key_id);
+            }
+        }
+
+        for (tenant_id, key_id) in latest {
+            active.entry(tenant_id).or_insert(key_id);
+        }
+
+        Ok(Self {
+            keys,
+            active,
+            default_tenant: file.default_tenant,
+        })
+    }
+}
+
+#[async_trait]
+impl KmsProvider for LocalKeyFileKms {
+    async fn active_key(&self, tenant_id: &str) -> Result<DataKey> {
+        let key_id = self
+            .active
+            .get(tenant_id)
+            .or_else(|| {
+                self.default_tenant
+                    .as_ref()
+                    .and_then(|tenant| self.active.get(tenant))
+            })
+            .ok_or_else(|| anyhow::anyhow!("No data key configured for tenant: {}", tenant_id))?;
+        self.key_by_id(key_id).await
+    }
+
+    async fn key_by_id(&self, key_id: &str) -> Result<DataKey> {
+        self.keys
+            .get(key_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown data key: {}", key_id))
+    }
+}
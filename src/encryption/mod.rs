@@ -0,0 +1,15 @@
+//! 字段级静态加密模块
+//! 使用按租户划分的数据密钥对消息内容等敏感字段进行信封加密（AES-256-GCM），
+//! 密钥ID随密文一起保存，支持密钥轮换后惰性重加密
+//!
+//! - `KmsProvider`：数据密钥提供者（可对接外部KMS），内置本地密钥文件实现 `LocalKeyFileKms`
+//! - `FieldEncryptor`：加密/解密字段，历史明文数据原样返回
+//! - `seal_cache_value` / `open_cache_value`：消息缓存值的加密编码
+
+pub mod cache;
+pub mod envelope;
+pub mod kms;
+
+pub use cache::{open_cache_value, seal_cache_value};
+pub use envelope::{DecryptedField, FieldEncryptor};
+pub use kms::{DataKey, KmsProvider, LocalKeyFileKms};
@@ -0,0 +1,350 @@
+//! 进程内事件总线
+//!
+//! 类型化的发布/订阅，用于服务内部组件解耦（例如存储写入完成后通知缓存失效、计数更新）：
+//! - 每个订阅者拥有独立的有界队列和处理任务，慢订阅者不会阻塞发布方或其他订阅者
+//! - `publish` 在订阅者队列已满时丢弃事件并计数，`publish_wait` 等待队列空位（背压），
+//!   总线已关闭时返回错误，调用方据此回退到同步处理而不是静默丢失事件
+//! - 按订阅者统计积压、处理、丢弃与失败数量，启用 `metrics` feature 时同步导出到 Prometheus
+//! - `shutdown` 停止接收新事件，等待订阅者处理完已入队的事件后返回；
+//!   `serve_until` 可直接挂到 `ServiceRuntime::add_spawn_with_shutdown` 上参与优雅停机
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+#[cfg(feature = "metrics")]
+static METRICS: once_cell::sync::Lazy<crate::metrics::EventBusMetrics> =
+    once_cell::sync::Lazy::new(crate::metrics::EventBusMetrics::new);
+
+/// 事件处理器
+#[async_trait]
+pub trait EventHandler<E>: Send + Sync {
+    /// 处理事件（返回错误只记录统计，不影响后续事件）
+    async fn handle(&self, event: &E) -> anyhow::Result<()>;
+}
+
+/// 订阅者统计快照
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriberStats {
+    /// 订阅者名称
+    pub name: String,
+    /// 队列中尚未处理的事件数
+    pub lag: usize,
+    /// 处理成功的事件数
+    pub handled: u64,
+    /// 队列已满被丢弃的事件数
+    pub dropped: u64,
+    /// 处理失败的事件数
+    pub failed: u64,
+}
+
+#[derive(Default)]
+struct SubscriberCounters {
+    handled: AtomicU64,
+    dropped: AtomicU64,
+    failed: AtomicU64,
+}
+
+struct Subscriber<E> {
+    name: String,
+    /// 停机后置空，订阅者任务处理完剩余事件后退出
+    sender: Option<mpsc::Sender<Arc<E>>>,
+    counters: Arc<SubscriberCounters>,
+}
+
+impl<E> Subscriber<E> {
+    fn lag(&self) -> usize {
+        self.sender
+            .as_ref()
+            .map(|sender| sender.max_capacity() - sender.capacity())
+            .unwrap_or(0)
+    }
+}
+
+/// 进程内事件总线
+pub struct EventBus<E> {
+    name: String,
+    subscribers: RwLock<Vec<Subscriber<E>>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+    closed: AtomicBool,
+}
+
+impl<E: Send + Sync + 'static> EventBus<E> {
+    /// 创建事件总线（名称用于日志与指标标签）
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            subscribers: RwLock::new(Vec::new()),
+            tasks: Mutex::new(Vec::new()),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 注册订阅者并启动其处理任务（需在 Tokio 运行时内调用）
+    ///
+    /// `capacity` 为订阅者队列容量，最小为 1
+    pub fn subscribe(
+        &self,
+        name: impl Into<String>,
+        capacity: usize,
+        handler: Arc<dyn EventHandler<E>>,
+    ) {
+        let name = name.into();
+        if self.is_closed() {
+            warn!(bus = %self.name, subscriber = %name, "Event bus is closed, subscription ignored");
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let counters = Arc::new(SubscriberCounters::default());
+        let task = tokio::spawn(run_subscriber(
+            self.name.clone(),
+            name.clone(),
+            receiver,
+            handler,
+            counters.clone(),
+        ));
+
+        self.subscribers.write().unwrap().push(Subscriber {
+            name: name.clone(),
+            sender: Some(sender),
+            counters,
+        });
+        self.tasks.lock().unwrap().push(task);
+        debug!(bus = %self.name, subscriber = %name, capacity, "Event bus subscriber registered");
+    }
+
+    /// 发布事件（不等待），返回成功入队的订阅者数量
+    ///
+    /// 订阅者队列已满时丢弃该订阅者的这条事件并计入 `dropped`
+    pub fn publish(&self, event: E) -> usize {
+        if self.is_closed() {
+            return 0;
+        }
+
+        let event = Arc::new(event);
+        let subscribers = self.subscribers.read().unwrap();
+        let mut accepted = 0;
+        for subscriber in subscribers.iter() {
+            let Some(sender) = &subscriber.sender else {
+                continue;
+            };
+            match sender.try_send(event.clone()) {
+                Ok(()) => {
+                    accepted += 1;
+                    self.record_lag(subscriber);
+                }
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    subscriber.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                    #[cfg(feature = "metrics")]
+                    METRICS
+                        .events_dropped_total
+                        .with_label_values(&[&self.name, &subscriber.name])
+                        .inc();
+                    warn!(
+                        bus = %self.name,
+                        subscriber = %subscriber.name,
+                        "Event bus subscriber queue is full, event dropped"
+                    );
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => {}
+            }
+        }
+        accepted
+    }
+
+    /// 发布事件，订阅者队列已满时等待空位，返回成功入队的订阅者数量
+    ///
+    /// 总线已关闭（或关闭过程中订阅者队列已停止接收）时返回错误
+    pub async fn publish_wait(&self, event: E) -> anyhow::Result<usize> {
+        if self.is_closed() {
+            anyhow::bail!("event bus {} is closed", self.name);
+        }
+
+        let event = Arc::new(event);
+        let senders: Vec<mpsc::Sender<Arc<E>>> = self
+            .subscribers
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|subscriber| subscriber.sender.clone())
+            .collect();
+
+        let mut accepted = 0;
+        for sender in senders {
+            if sender.send(event.clone()).await.is_ok() {
+                accepted += 1;
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        for subscriber in self.subscribers.read().unwrap().iter() {
+            self.record_lag(subscriber);
+        }
+        if self.is_closed() && accepted == 0 {
+            anyhow::bail!("event bus {} is closed", self.name);
+        }
+        Ok(accepted)
+    }
+
+    /// 各订阅者的统计快照
+    pub fn stats(&self) -> Vec<SubscriberStats> {
+        self.subscribers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|subscriber| SubscriberStats {
+                name: subscriber.name.clone(),
+                lag: subscriber.lag(),
+                handled: subscriber.counters.handled.load(Ordering::Relaxed),
+                dropped: subscriber.counters.dropped.load(Ordering::Relaxed),
+                failed: subscriber.counters.failed.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// 关闭总线：不再接收新事件，等待订阅者处理完已入队的事件
+    pub async fn shutdown(&self) {
+        if self.closed.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        for subscriber in self.subscribers.write().unwrap().iter_mut() {
+            subscriber.sender = None;
+        }
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        for task in tasks {
+            if let Err(err) = task.await {
+                warn!(bus = %self.name, error = %err, "Event bus subscriber task aborted");
+            }
+        }
+        info!(bus = %self.name, "Event bus shut down");
+    }
+
+    /// 等待停机信号后关闭总线，签名与 `ServiceRuntime::add_spawn_with_shutdown` 的任务一致
+    pub async fn serve_until<F>(
+        self: Arc<Self>,
+        signal: F,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+    where
+        F: Future,
+    {
+        signal.await;
+        self.shutdown().await;
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    fn record_lag(&self, subscriber: &Subscriber<E>) {
+        #[cfg(feature = "metrics")]
+        METRICS
+            .subscriber_lag
+            .with_label_values(&[&self.name, &subscriber.name])
+            .set(subscriber.lag() as i64);
+    }
+}
+
+async fn run_subscriber<E>(
+    bus: String,
+    name: String,
+    mut receiver: mpsc::Receiver<Arc<E>>,
+    handler: Arc<dyn EventHandler<E>>,
+    counters: Arc<SubscriberCounters>,
+) {
+    while let Some(event) = receiver.recv().await {
+        match handler.handle(&event).await {
+            Ok(()) => {
+                counters.handled.fetch_add(1, Ordering::Relaxed);
+                #[cfg(feature = "metrics")]
+                METRICS
+                    .events_handled_total
+                    .with_label_values(&[&bus, &name])
+                    .inc();
+            }
+            Err(err) => {
+                counters.failed.fetch_add(1, Ordering::Relaxed);
+                #[cfg(feature = "metrics")]
+                METRICS
+                    .handler_failures_total
+                    .with_label_values(&[&bus, &name])
+                    .inc();
+                warn!(bus = %bus, subscriber = %name, error = %err, "Event handler failed");
+            }
+        }
+        #[cfg(feature = "metrics")]
+        METRICS
+            .subscriber_lag
+            .with_label_values(&[&bus, &name])
+            .set(receiver.len() as i64);
+    }
+    debug!(bus = %bus, subscriber = %name, "Event bus subscriber stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<u32>>,
+    }
+
+    #[async_trait]
+    impl EventHandler<u32> for Recorder {
+        async fn handle(&self, event: &u32) -> anyhow::Result<()> {
+            self.events.lock().unwrap().push(*event);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_fans_out_and_drains_on_shutdown() {
+        let bus = EventBus::new("test");
+        let first = Arc::new(Recorder::default());
+        let second = Arc::new(Recorder::default());
+        bus.subscribe("first", 8, first.clone());
+        bus.subscribe("second", 8, second.clone());
+
+        for event in 1..=3 {
+            assert_eq!(bus.publish(event), 2);
+        }
+        bus.shutdown().await;
+
+        assert_eq!(*first.events.lock().unwrap(), vec![1, 2, 3]);
+        assert_eq!(*second.events.lock().unwrap(), vec![1, 2, 3]);
+        assert!(bus.stats().iter().all(|s| s.handled == 3 && s.lag == 0));
+        assert_eq!(bus.publish(4), 0);
+        assert!(bus.publish_wait(5).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_full_subscriber_queue_drops_events() {
+        let bus = EventBus::new("test");
+        let recorder = Arc::new(Recorder::default());
+        bus.subscribe("slow", 1, recorder.clone());
+
+        // 单线程运行时下订阅者任务尚未运行，队列只能容纳一条事件
+        assert_eq!(bus.publish(1), 1);
+        assert_eq!(bus.publish(2), 0);
+        assert_eq!(bus.publish(3), 0);
+        let stats = bus.stats().remove(0);
+        assert_eq!((stats.lag, stats.dropped), (1, 2));
+
+        bus.shutdown().await;
+        assert_eq!(*recorder.events.lock().unwrap(), vec![1]);
+        assert_eq!(bus.stats()[0].handled, 1);
+    }
+}
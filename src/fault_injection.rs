@@ -0,0 +1,397 @@
+//! 故障注入（混沌测试）
+//!
+//! 在关键接缝处按场景配置注入延迟、错误和丢弃，用于确定性地验证 WAL 回放、重试与故障转移路径：
+//! - 接缝：Kafka 发布、Redis 操作、gRPC 客户端调用、Hook 调用
+//! - 场景由规则组成，每条规则按接缝与目标（精确匹配或 `*` 结尾的前缀）匹配调用，
+//!   通过 `skip_first` / `every_nth` / `max_injections` 控制触发的调用序号，`probability` 使用场景种子的伪随机数
+//! - 通过环境变量 `FLARE_FAULT_SCENARIO` 指定场景文件（`.toml` 或 `.json`）启用；
+//!   生产环境（`FLARE_ENV=production` / `prod`）拒绝启用
+//!
+//! 未启用时每个接缝只有一次原子读取的开销
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use anyhow::{Context as AnyhowContext, Result, bail};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::ConfigManager;
+
+/// 场景文件路径的环境变量
+pub const FAULT_SCENARIO_ENV: &str = "FLARE_FAULT_SCENARIO";
+
+/// 故障注入接缝
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultSeam {
+    /// Kafka 发布（目标为 topic）
+    KafkaPublish,
+    /// Redis 操作（目标为操作名，如 `wal.append`）
+    Redis,
+    /// gRPC 客户端调用（目标为 `服务.方法`，如 `conversation.create_conversation`）
+    GrpcClient,
+    /// Hook 调用（目标为 Hook 名称）
+    Hook,
+}
+
+impl FaultSeam {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FaultSeam::KafkaPublish => "kafka_publish",
+            FaultSeam::Redis => "redis",
+            FaultSeam::GrpcClient => "grpc_client",
+            FaultSeam::Hook => "hook",
+        }
+    }
+}
+
+/// 注入的故障类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultKind {
+    /// 延迟后继续执行
+    Latency,
+    /// 返回错误
+    Error,
+    /// 丢弃（由接缝决定语义：发布/写入视为成功但未执行，调用方等不到响应）
+    Drop,
+}
+
+/// 故障规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaultRule {
+    pub seam: FaultSeam,
+    /// 目标（精确匹配或 `*` 结尾的前缀匹配，缺省匹配所有目标）
+    #[serde(default)]
+    pub target: Option<String>,
+    pub fault: FaultKind,
+    /// 延迟时长（毫秒，`latency` 故障使用）
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// 错误信息（`error` 故障使用）
+    #[serde(default)]
+    pub message: Option<String>,
+    /// 前 N 次匹配的调用不注入
+    #[serde(default)]
+    pub skip_first: u64,
+    /// 每 N 次匹配的调用注入一次（0 和 1 表示每次）
+    #[serde(default)]
+    pub every_nth: u64,
+    /// 最多注入次数（0 表示不限制）
+    #[serde(default)]
+    pub max_injections: u64,
+    /// 满足序号条件后的注入概率
+    #[serde(default = "default_probability")]
+    pub probability: f64,
+}
+
+fn default_probability() -> f64 {
+    1.0
+}
+
+impl FaultRule {
+    fn matches(&self, seam: FaultSeam, target: &str) -> bool {
+        if self.seam != seam {
+            return false;
+        }
+        match self.target.as_deref() {
+            None => true,
+            Some(pattern) => match pattern.strip_suffix('*') {
+                Some(prefix) => target.starts_with(prefix),
+                None => target == pattern,
+            },
+        }
+    }
+}
+
+/// 故障场景
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FaultScenario {
+    /// 场景名称（用于日志）
+    #[serde(default)]
+    pub name: String,
+    /// 伪随机数种子（相同种子与调用顺序下注入结果相同）
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub rules: Vec<FaultRule>,
+}
+
+impl FaultScenario {
+    /// 从场景文件加载（按扩展名解析 TOML 或 JSON）
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read fault scenario {}", path.display()))?;
+        let scenario = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content)?
+        } else {
+            toml::from_str(&content)?
+        };
+        Ok(scenario)
+    }
+}
+
+/// 接缝处应执行的动作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FaultAction {
+    /// 正常执行
+    Proceed,
+    /// 丢弃
+    Drop,
+    /// 返回错误
+    Fail(String),
+}
+
+struct RuleState {
+    rule: FaultRule,
+    calls: AtomicU64,
+    injected: AtomicU64,
+}
+
+/// 故障注入器
+pub struct FaultInjector {
+    name: String,
+    rules: Vec<RuleState>,
+    rng: Mutex<StdRng>,
+}
+
+impl FaultInjector {
+    pub fn new(scenario: FaultScenario) -> Self {
+        Self {
+            name: scenario.name,
+            rules: scenario
+                .rules
+                .into_iter()
+                .map(|rule| RuleState {
+                    rule,
+                    calls: AtomicU64::new(0),
+                    injected: AtomicU64::new(0),
+                })
+                .collect(),
+            rng: Mutex::new(StdRng::seed_from_u64(scenario.seed)),
+        }
+    }
+
+    /// 计算一次调用的注入结果（延迟累加，错误/丢弃取第一条触发的规则）
+    pub fn evaluate(&self, seam: FaultSeam, target: &str) -> (Duration, FaultAction) {
+        let mut latency = Duration::ZERO;
+        let mut action = FaultAction::Proceed;
+        for state in &self.rules {
+            let rule = &state.rule;
+            if !rule.matches(seam, target) {
+                continue;
+            }
+            let call = state.calls.fetch_add(1, Ordering::Relaxed);
+            if call < rule.skip_first || (call - rule.skip_first) % rule.every_nth.max(1) != 0 {
+                continue;
+            }
+            if rule.probability < 1.0
+                && !self
+                    .rng
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .gen_bool(rule.probability.clamp(0.0, 1.0))
+            {
+                continue;
+            }
+            if rule.fault != FaultKind::Latency && action != FaultAction::Proceed {
+                continue;
+            }
+            let injected = state.injected.fetch_add(1, Ordering::Relaxed);
+            if rule.max_injections > 0 && injected >= rule.max_injections {
+                continue;
+            }
+
+            warn!(
+                scenario = %self.name,
+                seam = seam.as_str(),
+                target,
+                fault = ?rule.fault,
+                "Injecting fault"
+            );
+            match rule.fault {
+                FaultKind::Latency => latency += Duration::from_millis(rule.latency_ms),
+                FaultKind::Error => {
+                    action = FaultAction::Fail(rule.message.clone().unwrap_or_else(|| {
+                        format!("injected {} fault on {}", seam.as_str(), target)
+                    }))
+                }
+                FaultKind::Drop => action = FaultAction::Drop,
+            }
+        }
+        (latency, action)
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static INJECTOR: RwLock<Option<Arc<FaultInjector>>> = RwLock::new(None);
+
+/// 安装全局故障注入器
+pub fn install(injector: FaultInjector) {
+    *INJECTOR.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(injector));
+    ENABLED.store(true, Ordering::Release);
+}
+
+/// 卸载全局故障注入器
+pub fn uninstall() {
+    ENABLED.store(false, Ordering::Release);
+    *INJECTOR.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// 按 `FLARE_FAULT_SCENARIO` 加载场景并安装（未设置时返回 false）
+pub fn init_from_env() -> Result<bool> {
+    let Ok(path) = std::env::var(FAULT_SCENARIO_ENV) else {
+        return Ok(false);
+    };
+    let environment = ConfigManager::get_environment();
+    if matches!(environment.as_str(), "production" | "prod") {
+        bail!(
+            "fault injection is not allowed in {} environment ({} is set)",
+            environment,
+            FAULT_SCENARIO_ENV
+        );
+    }
+    let scenario = FaultScenario::from_file(&path)?;
+    info!(
+        scenario = %scenario.name,
+        rules = scenario.rules.len(),
+        path = %path,
+        "Fault injection enabled"
+    );
+    install(FaultInjector::new(scenario));
+    Ok(true)
+}
+
+/// 在接缝处执行故障注入：先等待注入的延迟，再返回应执行的动作
+pub async fn inject(seam: FaultSeam, target: &str) -> FaultAction {
+    if !ENABLED.load(Ordering::Acquire) {
+        return FaultAction::Proceed;
+    }
+    let Some(injector) = INJECTOR.read().unwrap_or_else(|e| e.into_inner()).clone() else {
+        return FaultAction::Proceed;
+    };
+    let (latency, action) = injector.evaluate(seam, target);
+    if !latency.is_zero() {
+        tokio::time::sleep(latency).await;
+    }
+    action
+}
+
+/// gRPC 客户端调用前的故障注入（错误与丢弃均转换为 `Unavailable`）
+pub async fn inject_grpc(target: &str) -> std::result::Result<(), tonic::Status> {
+    match inject(FaultSeam::GrpcClient, target).await {
+        FaultAction::Proceed => Ok(()),
+        FaultAction::Drop => Err(tonic::Status::unavailable(format!(
+            "injected drop on {}",
+            target
+        ))),
+        FaultAction::Fail(message) => Err(tonic::Status::unavailable(message)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(seam: FaultSeam, target: Option<&str>, fault: FaultKind) -> FaultRule {
+        FaultRule {
+            seam,
+            target: target.map(str::to_string),
+            fault,
+            latency_ms: 0,
+            message: None,
+            skip_first: 0,
+            every_nth: 0,
+            max_injections: 0,
+            probability: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_rule_schedule_is_deterministic() {
+        let mut error = rule(FaultSeam::KafkaPublish, Some("storage-*"), FaultKind::Error);
+        error.skip_first = 1;
+        error.every_nth = 2;
+        error.max_injections = 2;
+        let injector = FaultInjector::new(FaultScenario {
+            name: "kafka".to_string(),
+            seed: 7,
+            rules: vec![error],
+        });
+
+        let actions: Vec<bool> = (0..8)
+            .map(|_| {
+                injector
+                    .evaluate(FaultSeam::KafkaPublish, "storage-messages")
+                    .1
+                    != FaultAction::Proceed
+            })
+            .collect();
+        assert_eq!(
+            actions,
+            vec![false, true, false, true, false, false, false, false]
+        );
+        assert_eq!(
+            injector.evaluate(FaultSeam::KafkaPublish, "push-tasks").1,
+            FaultAction::Proceed
+        );
+        assert_eq!(
+            injector.evaluate(FaultSeam::Redis, "storage-messages").1,
+            FaultAction::Proceed
+        );
+    }
+
+    #[test]
+    fn test_latency_accumulates_and_first_failure_wins() {
+        let mut latency = rule(FaultSeam::Redis, None, FaultKind::Latency);
+        latency.latency_ms = 50;
+        let mut drop = rule(FaultSeam::Redis, Some("wal.append"), FaultKind::Drop);
+        drop.max_injections = 1;
+        let error = rule(FaultSeam::Redis, Some("wal.append"), FaultKind::Error);
+        let injector = FaultInjector::new(FaultScenario {
+            name: "redis".to_string(),
+            seed: 0,
+            rules: vec![latency, drop, error],
+        });
+
+        let (delay, action) = injector.evaluate(FaultSeam::Redis, "wal.append");
+        assert_eq!(delay, Duration::from_millis(50));
+        assert_eq!(action, FaultAction::Drop);
+        let (_, action) = injector.evaluate(FaultSeam::Redis, "wal.append");
+        assert!(matches!(action, FaultAction::Fail(_)));
+    }
+
+    #[test]
+    fn test_scenario_from_toml() {
+        let scenario: FaultScenario = toml::from_str(
+            r#"
+            name = "wal-replay"
+            seed = 42
+
+            [[rules]]
+            seam = "kafka_publish"
+            fault = "drop"
+            every_nth = 3
+
+            [[rules]]
+            seam = "hook"
+            target = "pre-send-*"
+            fault = "latency"
+            latency_ms = 200
+            probability = 0.5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(scenario.rules.len(), 2);
+        assert_eq!(scenario.rules[0].fault, FaultKind::Drop);
+        assert_eq!(scenario.rules[0].probability, 1.0);
+        assert_eq!(scenario.rules[1].seam, FaultSeam::Hook);
+        assert_eq!(scenario.rules[1].latency_ms, 200);
+    }
+}
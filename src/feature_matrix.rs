@@ -0,0 +1,182 @@
+//! Feature 组合测试
+//!
+//! - 进程内测试：校验当前 feature 组合下的替代路径（trait 对象注入、禁用传输的报错）
+//! - 编译矩阵：对每个 feature 组合执行 `cargo check`，不依赖 CI 配置，本地可直接运行：
+//!   `cargo test -p flare-im-core feature_matrix -- --ignored`
+
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+
+use crate::hooks::adapters::DefaultHookFactory;
+use crate::hooks::{HookDefinition, HookFactory, HookTransportConfig};
+use crate::scheduler::{JobStore, ScheduledJob, SchedulerConfig, TaskScheduler};
+
+/// 需要保证可独立编译的 feature 组合（空字符串表示仅核心模块：hooks / config 等）
+const FEATURE_SETS: &[&str] = &[
+    "",
+    "metrics",
+    "redis",
+    "kafka",
+    "mongodb",
+    "postgres",
+    "webhook",
+    "config-watch",
+    "config-center",
+    "secrets",
+    "auth,encryption",
+    "ack",
+    "full",
+];
+
+/// 内存任务存储（模拟嵌入方注入的自定义存储）
+#[derive(Default)]
+struct MemoryJobStore {
+    jobs: Mutex<HashMap<String, ScheduledJob>>,
+}
+
+#[async_trait]
+impl JobStore for MemoryJobStore {
+    async fn schedule(&self, job: &ScheduledJob) -> anyhow::Result<()> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .insert(job.job_id.clone(), job.clone());
+        Ok(())
+    }
+
+    async fn cancel(&self, job_id: &str) -> anyhow::Result<bool> {
+        Ok(self.jobs.lock().unwrap().remove(job_id).is_some())
+    }
+
+    async fn claim_due(
+        &self,
+        now_ms: i64,
+        _lease_ms: u64,
+        limit: usize,
+    ) -> anyhow::Result<Vec<ScheduledJob>> {
+        Ok(self
+            .jobs
+            .lock()
+            .unwrap()
+            .values_mut()
+            .filter(|job| job.run_at <= now_ms && job.lease_token.is_none())
+            .take(limit)
+            .map(|job| {
+                job.attempts += 1;
+                job.lease_token = Some(format!("lease-{}", job.attempts));
+                job.clone()
+            })
+            .collect())
+    }
+
+    async fn reclaim_expired(&self, _now_ms: i64, _limit: usize) -> anyhow::Result<u64> {
+        Ok(0)
+    }
+
+    async fn complete(&self, job: &ScheduledJob) -> anyhow::Result<bool> {
+        Ok(self.release(job, false))
+    }
+
+    async fn retry(&self, job: &ScheduledJob) -> anyhow::Result<bool> {
+        Ok(self.release(job, true))
+    }
+
+    async fn dead_letter(&self, job: &ScheduledJob) -> anyhow::Result<bool> {
+        Ok(self.release(job, false))
+    }
+}
+
+impl MemoryJobStore {
+    /// 仍持有租约时释放任务：`requeue` 为 true 时重新入队，否则删除
+    fn release(&self, job: &ScheduledJob, requeue: bool) -> bool {
+        let mut jobs = self.jobs.lock().unwrap();
+        let held = jobs.get(&job.job_id).is_some_and(|stored| {
+            stored.lease_token.is_some() && stored.lease_token == job.lease_token
+        });
+        if !held {
+            return false;
+        }
+        if requeue {
+            let mut job = job.clone();
+            job.lease_token = None;
+            jobs.insert(job.job_id.clone(), job);
+        } else {
+            jobs.remove(&job.job_id);
+        }
+        true
+    }
+}
+
+#[tokio::test]
+async fn test_scheduler_accepts_custom_store() {
+    let store = Arc::new(MemoryJobStore::default());
+    let scheduler = TaskScheduler::with_store(store.clone(), SchedulerConfig::default());
+
+    scheduler
+        .schedule_at("job-1", "test", serde_json::Value::Null, 0)
+        .await
+        .unwrap();
+    let claimed = store.claim_due(1, 0, 10).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].attempts, 1);
+    assert!(store.claim_due(1, 0, 10).await.unwrap().is_empty());
+
+    // 租约令牌不匹配时不生效
+    let mut stale = claimed[0].clone();
+    stale.lease_token = Some("lease-stale".to_string());
+    assert!(!store.complete(&stale).await.unwrap());
+    assert!(store.retry(&claimed[0]).await.unwrap());
+    assert_eq!(store.claim_due(1, 0, 10).await.unwrap()[0].attempts, 2);
+    assert!(scheduler.cancel("job-1").await.unwrap());
+    assert!(!scheduler.cancel("job-1").await.unwrap());
+}
+
+#[test]
+fn test_webhook_transport_follows_feature() {
+    let def = HookDefinition {
+        name: "webhook-hook".to_string(),
+        transport: HookTransportConfig::Webhook {
+            endpoint: "http://127.0.0.1:9/hook".to_string(),
+            secret: None,
+            previous_secret: None,
+            headers: HashMap::new(),
+        },
+        ..Default::default()
+    };
+    let factory = DefaultHookFactory::new().unwrap();
+    let result = factory.build_pre_send(&def, &def.selector().unwrap());
+
+    if cfg!(feature = "webhook") {
+        assert!(matches!(result, Ok(Some(_))));
+    } else {
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+#[ignore = "runs cargo check for every feature set, slow"]
+fn test_feature_sets_compile() {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let manifest = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+    // 独立的 target 目录，避免与外层 cargo test 争用构建锁
+    let target_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/target/feature-matrix");
+
+    let failed: Vec<&str> = FEATURE_SETS
+        .iter()
+        .copied()
+        .filter(|features| {
+            let status = Command::new(&cargo)
+                .args(["check", "--lib", "-p", "flare-im-core", "--no-default-features"])
+                .args(["--manifest-path", manifest, "--target-dir", target_dir])
+                .args(["--features", features])
+                .status()
+                .expect("failed to run cargo");
+            !status.success()
+        })
+        .collect();
+
+    assert!(failed.is_empty(), "feature sets failed to compile: {:?}", failed);
+}
@@ -5,4 +5,7 @@
 
 pub mod router;
 
-pub use router::{GatewayRouter, GatewayRouterConfig, GatewayRouterError, GatewayRouterTrait};
+pub use router::{
+    GATEWAY_GENERATION_METADATA, GatewayRouter, GatewayRouterConfig, GatewayRouterError,
+    GatewayRouterTrait,
+};
@@ -25,7 +25,7 @@ use tokio::sync::RwLock;
 use tonic::transport::{Channel, Endpoint};
 use tracing::{debug, info, warn};
 
-use flare_server_core::discovery::{ServiceClient, discover::ServiceDiscover};
+use flare_server_core::discovery::{ServiceClient, ServiceInstance, discover::ServiceDiscover};
 
 /// Gateway Router 错误类型
 #[derive(Debug, thiserror::Error)]
@@ -38,6 +38,12 @@ pub enum GatewayRouterError {
     Other(#[from] anyhow::Error),
 }
 
+/// 服务发现元数据中标识网关实例代际的键
+///
+/// Access Gateway 每次启动注册一个新的代际值；同一 gateway_id 的代际变化说明实例已被替换
+/// （重启、滚动发布），旧连接上的推送不再可靠
+pub const GATEWAY_GENERATION_METADATA: &str = "instance_generation";
+
 /// Gateway Router 配置
 #[derive(Debug, Clone)]
 pub struct GatewayRouterConfig {
@@ -78,11 +84,25 @@ pub trait GatewayRouterTrait: Send + Sync {
         gateway_id: &str,
         request: PushMessageRequest,
     ) -> Result<PushMessageResponse>;
+
+    /// 探测网关是否可达（用于网关短暂不可达后的重投递），默认视为可达
+    async fn probe_gateway(&self, _gateway_id: &str) -> Result<()> {
+        Ok(())
+    }
+
+    /// 检查网关实例是否已被替换（服务发现中的实例代际变化或实例已下线），已替换时丢弃旧连接
+    ///
+    /// 默认视为未替换
+    async fn refresh_gateway(&self, _gateway_id: &str) -> Result<bool> {
+        Ok(false)
+    }
 }
 
-/// 连接池条目（包含客户端和最后使用时间）
+/// 连接池条目（包含客户端、实例代际和最后使用时间）
 struct ConnectionPoolEntry {
     client: AccessGatewayClient<Channel>,
+    /// 建立连接时的实例代际（未上报代际时为实例地址）
+    generation: Option<String>,
     last_used: Instant,
 }
 
@@ -204,6 +224,7 @@ impl GatewayRouter {
 
         // 使用服务发现获取特定 gateway_id 的 Channel
         // 优先使用 ServiceDiscover 根据 instance_id 过滤实例，如果不可用则回退到 ServiceClient 的负载均衡
+        let mut generation = None;
         let channel = if let Some(ref service_discover) = self.service_discover {
             // 使用 ServiceDiscover 获取所有实例，然后根据 instance_id == gateway_id 筛选
             let instances = service_discover.get_instances().await;
@@ -213,6 +234,7 @@ impl GatewayRouter {
 
             match target_instance {
                 Some(instance) => {
+                    generation = Some(instance_generation(instance));
                     // 根据实例地址直接创建 channel
                     let uri = instance.to_grpc_uri();
                     let endpoint = Endpoint::from_shared(uri)
@@ -289,6 +311,7 @@ impl GatewayRouter {
                 gateway_id.to_string(),
                 ConnectionPoolEntry {
                     client: client.clone(),
+                    generation,
                     last_used: Instant::now(),
                 },
             );
@@ -304,8 +327,67 @@ impl GatewayRouter {
     }
 }
 
+/// 实例代际（未上报代际元数据时以实例地址区分）
+fn instance_generation(instance: &ServiceInstance) -> String {
+    instance
+        .metadata
+        .custom
+        .get(GATEWAY_GENERATION_METADATA)
+        .cloned()
+        .unwrap_or_else(|| instance.address.to_string())
+}
+
+/// 判断网关实例是否已被替换
+///
+/// `current` 为服务发现中的当前代际（None 表示实例已下线），`pooled` 为连接池中连接建立时的代际；
+/// 连接池中没有该网关的连接（或连接未记录代际）时无从比较，视为未替换
+fn instance_replaced(current: Option<&str>, pooled: Option<&str>) -> bool {
+    match (current, pooled) {
+        // 实例已从服务发现中下线
+        (None, _) => true,
+        (Some(current), Some(pooled)) => current != pooled,
+        (Some(_), None) => false,
+    }
+}
+
 #[async_trait]
 impl GatewayRouterTrait for GatewayRouter {
+    async fn probe_gateway(&self, gateway_id: &str) -> Result<()> {
+        // 丢弃旧连接，重新建立连接以确认网关实例已恢复
+        self.connection_pool.write().await.remove(gateway_id);
+        self.get_or_create_client(gateway_id).await.map(|_| ())
+    }
+
+    async fn refresh_gateway(&self, gateway_id: &str) -> Result<bool> {
+        // 仅 ServiceDiscover 能按 gateway_id 定位实例
+        let Some(ref service_discover) = self.service_discover else {
+            return Ok(false);
+        };
+
+        let current = service_discover
+            .get_instances()
+            .await
+            .iter()
+            .find(|inst| inst.instance_id == gateway_id)
+            .map(instance_generation);
+
+        let mut pool = self.connection_pool.write().await;
+        let pooled = pool
+            .get(gateway_id)
+            .and_then(|entry| entry.generation.as_deref());
+        let replaced = instance_replaced(current.as_deref(), pooled);
+
+        if replaced {
+            pool.remove(gateway_id);
+            info!(
+                gateway_id = %gateway_id,
+                generation = ?current,
+                "Gateway instance replaced, dropped stale connection"
+            );
+        }
+        Ok(replaced)
+    }
+
     async fn route_push_message(
         &self,
         gateway_id: &str,
@@ -423,3 +505,25 @@ impl GatewayRouterTrait for GatewayRouter {
         Ok(response)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instance_replaced_when_generation_changes() {
+        assert!(instance_replaced(Some("gen-2"), Some("gen-1")));
+        assert!(!instance_replaced(Some("gen-1"), Some("gen-1")));
+    }
+
+    #[test]
+    fn test_instance_replaced_when_deregistered() {
+        assert!(instance_replaced(None, Some("gen-1")));
+        assert!(instance_replaced(None, None));
+    }
+
+    #[test]
+    fn test_instance_not_replaced_without_pooled_generation() {
+        assert!(!instance_replaced(Some("gen-1"), None));
+    }
+}
@@ -256,7 +256,7 @@ fn build_context(
     // 从 Context 中提取 Hook 特定的数据
     // 注意：这里需要访问 HookContextData，但它在 flare-hook-engine 中
     // 为了简化，我们使用 Context 的基本字段
-    use crate::hooks::hook_context_data::get_hook_context_data;
+    use crate::hooks::hook_context_data::{HOOK_BUDGET_ATTRIBUTE, get_hook_context_data};
     
     let hook_data = get_hook_context_data(ctx).cloned().unwrap_or_default();
     let corridor = hook_data
@@ -272,11 +272,29 @@ fn build_context(
             .entry(key.clone())
             .or_insert_with(|| value.clone());
     }
+    // 消息类型与业务类型没有独立的协议字段，通过属性传递给 Hook 引擎（用于选择器匹配）
+    if let Some(message_type) = &hook_data.message_type {
+        attributes
+            .entry("message_type".to_string())
+            .or_insert_with(|| message_type.clone());
+    }
+    if let Some(business_type) = &hook_data.business_type {
+        attributes
+            .entry("business_type".to_string())
+            .or_insert_with(|| business_type.clone());
+    }
     for (key, value) in &hook_data.request_metadata {
         attributes
             .entry(format!("request.{key}"))
             .or_insert_with(|| value.clone());
     }
+    // 剩余请求预算随每次调用重新计算，覆盖上游传入的旧值
+    if let Some(budget) = hook_data.remaining_budget() {
+        attributes.insert(
+            HOOK_BUDGET_ATTRIBUTE.to_string(),
+            budget.as_millis().to_string(),
+        );
+    }
 
     ProtoHookInvocationContext {
         request_context: build_request_context(ctx, &hook_data),
@@ -638,7 +656,7 @@ fn build_recall_event(event: &RecallEvent) -> flare_proto::ProtoHookRecallEvent
         message_id: event.message_id.clone(),
         operator_id: event.operator_id.clone(),
         recalled_at: Some(system_time_to_timestamp(event.recalled_at)),
-        metadata: event.metadata.clone(),
+        metadata: event.wire_metadata(),
     }
 }
 
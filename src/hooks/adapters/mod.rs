@@ -1,4 +1,5 @@
 mod grpc;
+#[cfg(feature = "webhook")]
 mod webhook;
 
 use std::collections::HashMap;
@@ -8,30 +9,46 @@ use crate::error::{ErrorBuilder, ErrorCode, Result};
 
 use super::config::{HookDefinition, HookFactory, HookTransportConfig};
 use super::selector::HookSelector;
-use super::types::{DeliveryHook, PostSendHook, PreSendHook, RecallHook};
+#[cfg(feature = "webhook")]
+use super::signature::WebhookSigner;
+use super::types::{
+    DeliveryHook, HookKind, MediaUploadedHook, PostSendHook, PreSendHook, PresenceChangedHook,
+    RecallHook, SessionCreatedHook, SessionMemberChangedHook,
+};
 
 pub use grpc::GrpcHookFactory;
+#[cfg(feature = "webhook")]
 pub use webhook::WebhookHookFactory;
 
 /// 默认的 Hook 工厂，支持 gRPC / WebHook / 本地实现
 pub struct DefaultHookFactory {
     grpc: GrpcHookFactory,
+    #[cfg(feature = "webhook")]
     webhook: WebhookHookFactory,
     pre_send_locals: HashMap<String, Arc<dyn PreSendHook>>,
     post_send_locals: HashMap<String, Arc<dyn PostSendHook>>,
     delivery_locals: HashMap<String, Arc<dyn DeliveryHook>>,
     recall_locals: HashMap<String, Arc<dyn RecallHook>>,
+    presence_changed_locals: HashMap<String, Arc<dyn PresenceChangedHook>>,
+    session_created_locals: HashMap<String, Arc<dyn SessionCreatedHook>>,
+    session_member_changed_locals: HashMap<String, Arc<dyn SessionMemberChangedHook>>,
+    media_uploaded_locals: HashMap<String, Arc<dyn MediaUploadedHook>>,
 }
 
 impl DefaultHookFactory {
     pub fn new() -> Result<Self> {
         Ok(Self {
             grpc: GrpcHookFactory::new(),
+            #[cfg(feature = "webhook")]
             webhook: WebhookHookFactory::new()?,
             pre_send_locals: HashMap::new(),
             post_send_locals: HashMap::new(),
             delivery_locals: HashMap::new(),
             recall_locals: HashMap::new(),
+            presence_changed_locals: HashMap::new(),
+            session_created_locals: HashMap::new(),
+            session_member_changed_locals: HashMap::new(),
+            media_uploaded_locals: HashMap::new(),
         })
     }
 
@@ -62,6 +79,83 @@ impl DefaultHookFactory {
     pub fn register_recall_local<S: Into<String>>(&mut self, name: S, hook: Arc<dyn RecallHook>) {
         self.recall_locals.insert(name.into(), hook);
     }
+
+    pub fn register_presence_changed_local<S: Into<String>>(
+        &mut self,
+        name: S,
+        hook: Arc<dyn PresenceChangedHook>,
+    ) {
+        self.presence_changed_locals.insert(name.into(), hook);
+    }
+
+    pub fn register_session_created_local<S: Into<String>>(
+        &mut self,
+        name: S,
+        hook: Arc<dyn SessionCreatedHook>,
+    ) {
+        self.session_created_locals.insert(name.into(), hook);
+    }
+
+    pub fn register_session_member_changed_local<S: Into<String>>(
+        &mut self,
+        name: S,
+        hook: Arc<dyn SessionMemberChangedHook>,
+    ) {
+        self.session_member_changed_locals.insert(name.into(), hook);
+    }
+
+    pub fn register_media_uploaded_local<S: Into<String>>(
+        &mut self,
+        name: S,
+        hook: Arc<dyn MediaUploadedHook>,
+    ) {
+        self.media_uploaded_locals.insert(name.into(), hook);
+    }
+
+    /// 构建生命周期类 Hook（在线状态、会话、媒体），仅支持 WebHook 与本地实现
+    fn build_lifecycle<T: ?Sized>(
+        &self,
+        def: &HookDefinition,
+        kind: HookKind,
+        locals: &HashMap<String, Arc<T>>,
+        #[cfg(feature = "webhook")] build_webhook: impl FnOnce(
+            &WebhookHookFactory,
+            &str,
+            Option<WebhookSigner>,
+            HashMap<String, String>,
+        ) -> Arc<T>,
+    ) -> Result<Option<Arc<T>>> {
+        match &def.transport {
+            HookTransportConfig::Grpc { .. } => Err(ErrorBuilder::new(
+                ErrorCode::ConfigurationError,
+                "grpc transport is not supported for lifecycle hooks",
+            )
+            .details(format!("hook={}, kind={}", def.name, kind.as_str()))
+            .build_error()),
+            #[cfg(not(feature = "webhook"))]
+            HookTransportConfig::Webhook { .. } => Err(webhook_disabled(def)),
+            #[cfg(feature = "webhook")]
+            HookTransportConfig::Webhook {
+                endpoint,
+                secret,
+                previous_secret,
+                headers,
+            } => Ok(Some(build_webhook(
+                &self.webhook,
+                endpoint,
+                WebhookSigner::from_secrets(secret.clone(), previous_secret.clone()),
+                headers.clone(),
+            ))),
+            HookTransportConfig::Local { target } => {
+                let hook = locals.get(target).cloned().ok_or_else(|| {
+                    ErrorBuilder::new(ErrorCode::ConfigurationError, "local hook not found")
+                        .details(format!("hook={}, kind={}", def.name, kind.as_str()))
+                        .build_error()
+                })?;
+                Ok(Some(hook))
+            }
+        }
+    }
 }
 
 impl HookFactory for DefaultHookFactory {
@@ -77,14 +171,18 @@ impl HookFactory for DefaultHookFactory {
                 merged.extend(metadata.clone());
                 Ok(Some(self.grpc.build_pre_send(merged, channel)))
             }
+            #[cfg(not(feature = "webhook"))]
+            HookTransportConfig::Webhook { .. } => Err(webhook_disabled(def)),
+            #[cfg(feature = "webhook")]
             HookTransportConfig::Webhook {
                 endpoint,
                 secret,
+                previous_secret,
                 headers,
             } => Ok(Some(self.webhook.build_pre_send(
                 def,
                 endpoint,
-                secret.clone(),
+                WebhookSigner::from_secrets(secret.clone(), previous_secret.clone()),
                 headers.clone(),
             ))),
             HookTransportConfig::Local { target } => {
@@ -113,14 +211,18 @@ impl HookFactory for DefaultHookFactory {
                 merged.extend(metadata.clone());
                 Ok(Some(self.grpc.build_post_send(merged, channel)))
             }
+            #[cfg(not(feature = "webhook"))]
+            HookTransportConfig::Webhook { .. } => Err(webhook_disabled(def)),
+            #[cfg(feature = "webhook")]
             HookTransportConfig::Webhook {
                 endpoint,
                 secret,
+                previous_secret,
                 headers,
             } => Ok(Some(self.webhook.build_post_send(
                 def,
                 endpoint,
-                secret.clone(),
+                WebhookSigner::from_secrets(secret.clone(), previous_secret.clone()),
                 headers.clone(),
             ))),
             HookTransportConfig::Local { target } => {
@@ -149,14 +251,18 @@ impl HookFactory for DefaultHookFactory {
                 merged.extend(metadata.clone());
                 Ok(Some(self.grpc.build_delivery(merged, channel)))
             }
+            #[cfg(not(feature = "webhook"))]
+            HookTransportConfig::Webhook { .. } => Err(webhook_disabled(def)),
+            #[cfg(feature = "webhook")]
             HookTransportConfig::Webhook {
                 endpoint,
                 secret,
+                previous_secret,
                 headers,
             } => Ok(Some(self.webhook.build_delivery(
                 def,
                 endpoint,
-                secret.clone(),
+                WebhookSigner::from_secrets(secret.clone(), previous_secret.clone()),
                 headers.clone(),
             ))),
             HookTransportConfig::Local { target } => {
@@ -185,14 +291,18 @@ impl HookFactory for DefaultHookFactory {
                 merged.extend(metadata.clone());
                 Ok(Some(self.grpc.build_recall(merged, channel)))
             }
+            #[cfg(not(feature = "webhook"))]
+            HookTransportConfig::Webhook { .. } => Err(webhook_disabled(def)),
+            #[cfg(feature = "webhook")]
             HookTransportConfig::Webhook {
                 endpoint,
                 secret,
+                previous_secret,
                 headers,
             } => Ok(Some(self.webhook.build_recall(
                 def,
                 endpoint,
-                secret.clone(),
+                WebhookSigner::from_secrets(secret.clone(), previous_secret.clone()),
                 headers.clone(),
             ))),
             HookTransportConfig::Local { target } => {
@@ -205,4 +315,82 @@ impl HookFactory for DefaultHookFactory {
             }
         }
     }
+
+    fn build_presence_changed(
+        &self,
+        def: &HookDefinition,
+        _selector: &HookSelector,
+    ) -> Result<Option<Arc<dyn PresenceChangedHook>>> {
+        self.build_lifecycle(
+            def,
+            HookKind::PresenceChanged,
+            &self.presence_changed_locals,
+            #[cfg(feature = "webhook")]
+            |webhook, endpoint, secret, headers| {
+                webhook.build_presence_changed(def, endpoint, secret, headers)
+            },
+        )
+    }
+
+    fn build_session_created(
+        &self,
+        def: &HookDefinition,
+        _selector: &HookSelector,
+    ) -> Result<Option<Arc<dyn SessionCreatedHook>>> {
+        self.build_lifecycle(
+            def,
+            HookKind::SessionCreated,
+            &self.session_created_locals,
+            #[cfg(feature = "webhook")]
+            |webhook, endpoint, secret, headers| {
+                webhook.build_session_created(def, endpoint, secret, headers)
+            },
+        )
+    }
+
+    fn build_session_member_changed(
+        &self,
+        def: &HookDefinition,
+        _selector: &HookSelector,
+    ) -> Result<Option<Arc<dyn SessionMemberChangedHook>>> {
+        self.build_lifecycle(
+            def,
+            HookKind::SessionMemberChanged,
+            &self.session_member_changed_locals,
+            #[cfg(feature = "webhook")]
+            |webhook, endpoint, secret, headers| {
+                webhook.build_session_member_changed(def, endpoint, secret, headers)
+            },
+        )
+    }
+
+    fn build_media_uploaded(
+        &self,
+        def: &HookDefinition,
+        _selector: &HookSelector,
+    ) -> Result<Option<Arc<dyn MediaUploadedHook>>> {
+        self.build_lifecycle(
+            def,
+            HookKind::MediaUploaded,
+            &self.media_uploaded_locals,
+            #[cfg(feature = "webhook")]
+            |webhook, endpoint, secret, headers| {
+                webhook.build_media_uploaded(def, endpoint, secret, headers)
+            },
+        )
+    }
+}
+
+/// 未启用 `webhook` feature 时，WebHook 传输在构建阶段即报配置错误
+#[cfg(not(feature = "webhook"))]
+fn webhook_disabled(def: &HookDefinition) -> crate::error::FlareError {
+    ErrorBuilder::new(
+        ErrorCode::ConfigurationError,
+        "webhook hook transport is disabled",
+    )
+    .details(format!(
+        "hook={}, enable the `webhook` feature of flare-im-core",
+        def.name
+    ))
+    .build_error()
 }
@@ -10,9 +10,12 @@ use serde::{Deserialize, Serialize};
 use crate::error::{ErrorBuilder, ErrorCode, Result};
 
 use super::super::config::HookDefinition;
+use super::super::signature::WebhookSigner;
 use super::super::types::{
-    DeliveryEvent, DeliveryHook, HookOutcome, MessageDraft, MessageRecord,
-    PostSendHook, PreSendDecision, PreSendHook, RecallEvent, RecallHook,
+    DeliveryEvent, DeliveryHook, HookKind, HookOutcome, MediaUploadedEvent, MediaUploadedHook,
+    MessageDraft, MessageRecord, PostSendHook, PreSendDecision, PreSendHook, PresenceChangedEvent,
+    PresenceChangedHook, RecallEvent, RecallHook, SessionCreatedEvent, SessionCreatedHook,
+    SessionMemberChangedEvent, SessionMemberChangedHook,
 };
 use flare_server_core::context::Context;
 
@@ -35,13 +38,13 @@ impl WebhookHookFactory {
         &self,
         def: &HookDefinition,
         endpoint: &str,
-        secret: Option<String>,
+        signer: Option<WebhookSigner>,
         headers: HashMap<String, String>,
     ) -> Arc<dyn PreSendHook> {
         Arc::new(WebhookPreSendHook {
             client: self.client.clone(),
             endpoint: endpoint.to_string(),
-            secret,
+            signer,
             headers,
             static_metadata: def.metadata.clone(),
         })
@@ -51,13 +54,13 @@ impl WebhookHookFactory {
         &self,
         def: &HookDefinition,
         endpoint: &str,
-        secret: Option<String>,
+        signer: Option<WebhookSigner>,
         headers: HashMap<String, String>,
     ) -> Arc<dyn PostSendHook> {
         Arc::new(WebhookPostSendHook {
             client: self.client.clone(),
             endpoint: endpoint.to_string(),
-            secret,
+            signer,
             headers,
             static_metadata: def.metadata.clone(),
         })
@@ -67,13 +70,13 @@ impl WebhookHookFactory {
         &self,
         def: &HookDefinition,
         endpoint: &str,
-        secret: Option<String>,
+        signer: Option<WebhookSigner>,
         headers: HashMap<String, String>,
     ) -> Arc<dyn DeliveryHook> {
         Arc::new(WebhookDeliveryHook {
             client: self.client.clone(),
             endpoint: endpoint.to_string(),
-            secret,
+            signer,
             headers,
             static_metadata: def.metadata.clone(),
         })
@@ -83,17 +86,81 @@ impl WebhookHookFactory {
         &self,
         def: &HookDefinition,
         endpoint: &str,
-        secret: Option<String>,
+        signer: Option<WebhookSigner>,
         headers: HashMap<String, String>,
     ) -> Arc<dyn RecallHook> {
         Arc::new(WebhookRecallHook {
             client: self.client.clone(),
             endpoint: endpoint.to_string(),
-            secret,
+            signer,
             headers,
             static_metadata: def.metadata.clone(),
         })
     }
+
+    pub fn build_presence_changed(
+        &self,
+        def: &HookDefinition,
+        endpoint: &str,
+        signer: Option<WebhookSigner>,
+        headers: HashMap<String, String>,
+    ) -> Arc<dyn PresenceChangedHook> {
+        Arc::new(self.build_event(def, endpoint, signer, headers, HookKind::PresenceChanged))
+    }
+
+    pub fn build_session_created(
+        &self,
+        def: &HookDefinition,
+        endpoint: &str,
+        signer: Option<WebhookSigner>,
+        headers: HashMap<String, String>,
+    ) -> Arc<dyn SessionCreatedHook> {
+        Arc::new(self.build_event(def, endpoint, signer, headers, HookKind::SessionCreated))
+    }
+
+    pub fn build_session_member_changed(
+        &self,
+        def: &HookDefinition,
+        endpoint: &str,
+        signer: Option<WebhookSigner>,
+        headers: HashMap<String, String>,
+    ) -> Arc<dyn SessionMemberChangedHook> {
+        Arc::new(self.build_event(
+            def,
+            endpoint,
+            signer,
+            headers,
+            HookKind::SessionMemberChanged,
+        ))
+    }
+
+    pub fn build_media_uploaded(
+        &self,
+        def: &HookDefinition,
+        endpoint: &str,
+        signer: Option<WebhookSigner>,
+        headers: HashMap<String, String>,
+    ) -> Arc<dyn MediaUploadedHook> {
+        Arc::new(self.build_event(def, endpoint, signer, headers, HookKind::MediaUploaded))
+    }
+
+    fn build_event(
+        &self,
+        def: &HookDefinition,
+        endpoint: &str,
+        signer: Option<WebhookSigner>,
+        headers: HashMap<String, String>,
+        kind: HookKind,
+    ) -> WebhookEventHook {
+        WebhookEventHook {
+            client: self.client.clone(),
+            endpoint: endpoint.to_string(),
+            signer,
+            headers,
+            static_metadata: def.metadata.clone(),
+            kind,
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -177,20 +244,23 @@ struct WebhookStatus {
     message: Option<String>,
 }
 
-fn build_headers(
+/// 发送 JSON 请求：追加自定义请求头，配置密钥时按请求体签名（见 [`crate::hooks::signature`]）
+async fn send_signed<T: Serialize + ?Sized>(
     request_builder: reqwest::RequestBuilder,
-    secret: &Option<String>,
+    signer: &Option<WebhookSigner>,
     headers: &HashMap<String, String>,
-) -> reqwest::RequestBuilder {
-    let mut builder = request_builder;
-    builder = builder.header("content-type", "application/json");
-    if let Some(secret) = secret {
-        builder = builder.header("x-flare-signature", secret);
-    }
+    body: &T,
+) -> reqwest::Result<reqwest::Response> {
+    let mut builder = request_builder.json(body);
     for (key, value) in headers {
         builder = builder.header(key, value);
     }
-    builder
+    let (client, request) = builder.build_split();
+    let mut request = request?;
+    if let Some(signer) = signer {
+        signer.sign_request(&mut request);
+    }
+    client.execute(request).await
 }
 
 fn webhook_context(ctx: &Context) -> WebhookContextPayload {
@@ -223,7 +293,7 @@ fn webhook_context(ctx: &Context) -> WebhookContextPayload {
 struct WebhookPreSendHook {
     client: Client,
     endpoint: String,
-    secret: Option<String>,
+    signer: Option<WebhookSigner>,
     headers: HashMap<String, String>,
     static_metadata: HashMap<String, String>,
 }
@@ -238,8 +308,7 @@ impl PreSendHook for WebhookPreSendHook {
         };
 
         let builder = self.client.post(&self.endpoint);
-        let builder = build_headers(builder, &self.secret, &self.headers);
-        let response = builder.json(&request_body).send().await;
+        let response = send_signed(builder, &self.signer, &self.headers, &request_body).await;
 
         match response {
             Ok(resp) => match resp.json::<PreSendWebhookResponse>().await {
@@ -305,7 +374,7 @@ struct PostSendWebhookRequest {
 struct WebhookPostSendHook {
     client: Client,
     endpoint: String,
-    secret: Option<String>,
+    signer: Option<WebhookSigner>,
     headers: HashMap<String, String>,
     static_metadata: HashMap<String, String>,
 }
@@ -326,8 +395,7 @@ impl PostSendHook for WebhookPostSendHook {
         };
 
         let builder = self.client.post(&self.endpoint);
-        let builder = build_headers(builder, &self.secret, &self.headers);
-        match builder.json(&request_body).send().await {
+        match send_signed(builder, &self.signer, &self.headers, &request_body).await {
             Ok(resp) if resp.status().is_success() => HookOutcome::Completed,
             Ok(resp) => {
                 let err =
@@ -358,7 +426,7 @@ struct DeliveryWebhookRequest {
 struct WebhookDeliveryHook {
     client: Client,
     endpoint: String,
-    secret: Option<String>,
+    signer: Option<WebhookSigner>,
     headers: HashMap<String, String>,
     static_metadata: HashMap<String, String>,
 }
@@ -372,8 +440,7 @@ impl DeliveryHook for WebhookDeliveryHook {
             metadata: self.static_metadata.clone(),
         };
         let builder = self.client.post(&self.endpoint);
-        let builder = build_headers(builder, &self.secret, &self.headers);
-        match builder.json(&request_body).send().await {
+        match send_signed(builder, &self.signer, &self.headers, &request_body).await {
             Ok(resp) if resp.status().is_success() => HookOutcome::Completed,
             Ok(resp) => {
                 let err =
@@ -404,7 +471,7 @@ struct RecallWebhookRequest {
 struct WebhookRecallHook {
     client: Client,
     endpoint: String,
-    secret: Option<String>,
+    signer: Option<WebhookSigner>,
     headers: HashMap<String, String>,
     static_metadata: HashMap<String, String>,
 }
@@ -418,9 +485,8 @@ impl RecallHook for WebhookRecallHook {
             metadata: self.static_metadata.clone(),
         };
         let builder = self.client.post(&self.endpoint);
-        let builder = build_headers(builder, &self.secret, &self.headers);
 
-        match builder.json(&request_body).send().await {
+        match send_signed(builder, &self.signer, &self.headers, &request_body).await {
             Ok(resp) if resp.status().is_success() => HookOutcome::Completed,
             Ok(resp) => {
                 let err = ErrorBuilder::new(ErrorCode::ServiceUnavailable, "webhook recall failed")
@@ -437,3 +503,72 @@ impl RecallHook for WebhookRecallHook {
         }
     }
 }
+
+#[derive(Serialize)]
+struct EventWebhookRequest<'a, E> {
+    context: WebhookContextPayload,
+    event_type: &'static str,
+    event: &'a E,
+    metadata: HashMap<String, String>,
+}
+
+/// 生命周期事件 WebHook（在线状态、会话、媒体），请求体中的 `event_type` 区分事件类型
+#[derive(Clone)]
+struct WebhookEventHook {
+    client: Client,
+    endpoint: String,
+    signer: Option<WebhookSigner>,
+    headers: HashMap<String, String>,
+    static_metadata: HashMap<String, String>,
+    kind: HookKind,
+}
+
+impl WebhookEventHook {
+    async fn notify<E: Serialize + Sync>(&self, ctx: &Context, event: &E) -> HookOutcome {
+        let request_body = EventWebhookRequest {
+            context: webhook_context(ctx),
+            event_type: self.kind.as_str(),
+            event,
+            metadata: self.static_metadata.clone(),
+        };
+        let builder = self.client.post(&self.endpoint);
+
+        let details = match send_signed(builder, &self.signer, &self.headers, &request_body).await {
+            Ok(resp) if resp.status().is_success() => return HookOutcome::Completed,
+            Ok(resp) => resp.status().to_string(),
+            Err(err) => err.to_string(),
+        };
+        let err = ErrorBuilder::new(ErrorCode::ServiceUnavailable, "webhook event hook failed")
+            .details(format!("event={}, {details}", self.kind.as_str()))
+            .build_error();
+        HookOutcome::Failed(err)
+    }
+}
+
+#[async_trait]
+impl PresenceChangedHook for WebhookEventHook {
+    async fn handle(&self, ctx: &Context, event: &PresenceChangedEvent) -> HookOutcome {
+        self.notify(ctx, event).await
+    }
+}
+
+#[async_trait]
+impl SessionCreatedHook for WebhookEventHook {
+    async fn handle(&self, ctx: &Context, event: &SessionCreatedEvent) -> HookOutcome {
+        self.notify(ctx, event).await
+    }
+}
+
+#[async_trait]
+impl SessionMemberChangedHook for WebhookEventHook {
+    async fn handle(&self, ctx: &Context, event: &SessionMemberChangedEvent) -> HookOutcome {
+        self.notify(ctx, event).await
+    }
+}
+
+#[async_trait]
+impl MediaUploadedHook for WebhookEventHook {
+    async fn handle(&self, ctx: &Context, event: &MediaUploadedEvent) -> HookOutcome {
+        self.notify(ctx, event).await
+    }
+}
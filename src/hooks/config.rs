@@ -9,9 +9,11 @@ use serde::Deserialize;
 use crate::error::{ErrorBuilder, ErrorCode, Result};
 
 use super::registry::HookRegistry;
-use super::selector::{HookSelector, MatchRule};
+use super::selector::{HookSelector, MatchRule, TagExpr};
 use super::types::{
-    DeliveryHook, HookErrorPolicy, HookKind, HookMetadata, PostSendHook, PreSendHook, RecallHook,
+    DeliveryHook, HookErrorPolicy, HookGroup, HookKind, HookMetadata, MediaUploadedHook,
+    PostSendHook, PreSendHook, PresenceChangedHook, RecallHook, SessionCreatedHook,
+    SessionMemberChangedHook,
 };
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -21,6 +23,10 @@ pub struct HookConfig {
     pub post_send: Vec<HookDefinition>,
     pub delivery: Vec<HookDefinition>,
     pub recall: Vec<HookDefinition>,
+    pub presence_changed: Vec<HookDefinition>,
+    pub session_created: Vec<HookDefinition>,
+    pub session_member_changed: Vec<HookDefinition>,
+    pub media_uploaded: Vec<HookDefinition>,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -29,6 +35,10 @@ pub struct HookSelectorConfig {
     pub tenants: Vec<String>,
     pub conversation_types: Vec<String>,
     pub message_types: Vec<String>,
+    pub business_types: Vec<String>,
+    pub senders: Vec<String>,
+    /// 标签表达式（如 `level=vip|svip`、`!muted`），全部满足才匹配
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -43,6 +53,9 @@ pub enum HookTransportConfig {
         endpoint: String,
         #[serde(default)]
         secret: Option<String>,
+        /// 轮换中的旧密钥（配置后同时使用新旧密钥签名）
+        #[serde(default)]
+        previous_secret: Option<String>,
         #[serde(default)]
         headers: HashMap<String, String>,
     },
@@ -63,6 +76,8 @@ pub struct HookDefinition {
     pub max_retries: u32,
     pub error_policy: HookErrorPolicy,
     pub require_success: bool,
+    /// 显式分组（未配置时根据 priority 推断）
+    pub group: Option<HookGroup>,
     pub selector: HookSelectorConfig,
     pub transport: HookTransportConfig,
     #[serde(default)]
@@ -81,6 +96,7 @@ impl Default for HookDefinition {
             max_retries: 0,
             error_policy: HookErrorPolicy::FailFast,
             require_success: true,
+            group: None,
             selector: HookSelectorConfig::default(),
             transport: HookTransportConfig::Local {
                 target: String::new(),
@@ -91,24 +107,26 @@ impl Default for HookDefinition {
 }
 
 impl HookDefinition {
-    pub fn selector(&self) -> HookSelector {
-        HookSelector {
-            tenants: if self.selector.tenants.is_empty() {
-                MatchRule::Any
-            } else {
-                MatchRule::of(self.selector.tenants.clone())
-            },
-            conversation_types: if self.selector.conversation_types.is_empty() {
-                MatchRule::Any
-            } else {
-                MatchRule::of(self.selector.conversation_types.clone())
-            },
-            message_types: if self.selector.message_types.is_empty() {
-                MatchRule::Any
-            } else {
-                MatchRule::of(self.selector.message_types.clone())
-            },
-        }
+    pub fn selector(&self) -> Result<HookSelector> {
+        let tags = self
+            .selector
+            .tags
+            .iter()
+            .map(|expr| expr.parse::<TagExpr>())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|err| {
+                ErrorBuilder::new(ErrorCode::ConfigurationError, "invalid hook selector")
+                    .details(format!("hook={}, err={err}", self.name))
+                    .build_error()
+            })?;
+        Ok(HookSelector {
+            tenants: MatchRule::of_non_empty(&self.selector.tenants),
+            conversation_types: MatchRule::of_non_empty(&self.selector.conversation_types),
+            message_types: MatchRule::of_non_empty(&self.selector.message_types),
+            business_types: MatchRule::of_non_empty(&self.selector.business_types),
+            senders: MatchRule::of_non_empty(&self.selector.senders),
+            tags,
+        })
     }
 
     pub fn metadata(&self, kind: HookKind) -> HookMetadata {
@@ -121,6 +139,7 @@ impl HookDefinition {
             .with_timeout(Duration::from_millis(self.timeout_ms))
             .with_error_policy(self.error_policy)
             .with_require_success(self.require_success)
+            .with_group(self.group)
     }
 }
 
@@ -148,6 +167,30 @@ pub trait HookFactory: Send + Sync {
         def: &HookDefinition,
         selector: &HookSelector,
     ) -> Result<Option<Arc<dyn RecallHook>>>;
+
+    fn build_presence_changed(
+        &self,
+        def: &HookDefinition,
+        selector: &HookSelector,
+    ) -> Result<Option<Arc<dyn PresenceChangedHook>>>;
+
+    fn build_session_created(
+        &self,
+        def: &HookDefinition,
+        selector: &HookSelector,
+    ) -> Result<Option<Arc<dyn SessionCreatedHook>>>;
+
+    fn build_session_member_changed(
+        &self,
+        def: &HookDefinition,
+        selector: &HookSelector,
+    ) -> Result<Option<Arc<dyn SessionMemberChangedHook>>>;
+
+    fn build_media_uploaded(
+        &self,
+        def: &HookDefinition,
+        selector: &HookSelector,
+    ) -> Result<Option<Arc<dyn MediaUploadedHook>>>;
 }
 
 pub struct HookConfigLoader {
@@ -238,6 +281,11 @@ impl HookConfig {
         self.post_send.extend(other.post_send);
         self.delivery.extend(other.delivery);
         self.recall.extend(other.recall);
+        self.presence_changed.extend(other.presence_changed);
+        self.session_created.extend(other.session_created);
+        self.session_member_changed
+            .extend(other.session_member_changed);
+        self.media_uploaded.extend(other.media_uploaded);
     }
 
     pub async fn install(
@@ -250,7 +298,7 @@ impl HookConfig {
                 tracing::info!(hook = %def.name, "pre-send hook disabled, skip");
                 continue;
             }
-            let selector = def.selector();
+            let selector = def.selector()?;
             if let Some(handler) = factory.build_pre_send(def, &selector)? {
                 registry
                     .register_pre_send(def.metadata(HookKind::PreSend), selector, handler)
@@ -263,7 +311,7 @@ impl HookConfig {
                 tracing::info!(hook = %def.name, "post-send hook disabled, skip");
                 continue;
             }
-            let selector = def.selector();
+            let selector = def.selector()?;
             if let Some(handler) = factory.build_post_send(def, &selector)? {
                 registry
                     .register_post_send(def.metadata(HookKind::PostSend), selector, handler)
@@ -276,7 +324,7 @@ impl HookConfig {
                 tracing::info!(hook = %def.name, "delivery hook disabled, skip");
                 continue;
             }
-            let selector = def.selector();
+            let selector = def.selector()?;
             if let Some(handler) = factory.build_delivery(def, &selector)? {
                 registry
                     .register_delivery(def.metadata(HookKind::Delivery), selector, handler)
@@ -289,7 +337,7 @@ impl HookConfig {
                 tracing::info!(hook = %def.name, "recall hook disabled, skip");
                 continue;
             }
-            let selector = def.selector();
+            let selector = def.selector()?;
             if let Some(handler) = factory.build_recall(def, &selector)? {
                 registry
                     .register_recall(def.metadata(HookKind::Recall), selector, handler)
@@ -297,6 +345,199 @@ impl HookConfig {
             }
         }
 
+        for def in &self.presence_changed {
+            if !def.enabled {
+                tracing::info!(hook = %def.name, "presence-changed hook disabled, skip");
+                continue;
+            }
+            let selector = def.selector()?;
+            if let Some(handler) = factory.build_presence_changed(def, &selector)? {
+                registry
+                    .register_presence_changed(
+                        def.metadata(HookKind::PresenceChanged),
+                        selector,
+                        handler,
+                    )
+                    .await;
+            }
+        }
+
+        for def in &self.session_created {
+            if !def.enabled {
+                tracing::info!(hook = %def.name, "session-created hook disabled, skip");
+                continue;
+            }
+            let selector = def.selector()?;
+            if let Some(handler) = factory.build_session_created(def, &selector)? {
+                registry
+                    .register_session_created(
+                        def.metadata(HookKind::SessionCreated),
+                        selector,
+                        handler,
+                    )
+                    .await;
+            }
+        }
+
+        for def in &self.session_member_changed {
+            if !def.enabled {
+                tracing::info!(hook = %def.name, "session-member-changed hook disabled, skip");
+                continue;
+            }
+            let selector = def.selector()?;
+            if let Some(handler) = factory.build_session_member_changed(def, &selector)? {
+                registry
+                    .register_session_member_changed(
+                        def.metadata(HookKind::SessionMemberChanged),
+                        selector,
+                        handler,
+                    )
+                    .await;
+            }
+        }
+
+        for def in &self.media_uploaded {
+            if !def.enabled {
+                tracing::info!(hook = %def.name, "media-uploaded hook disabled, skip");
+                continue;
+            }
+            let selector = def.selector()?;
+            if let Some(handler) = factory.build_media_uploaded(def, &selector)? {
+                registry
+                    .register_media_uploaded(
+                        def.metadata(HookKind::MediaUploaded),
+                        selector,
+                        handler,
+                    )
+                    .await;
+            }
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::SystemTime;
+
+    use async_trait::async_trait;
+    use flare_server_core::context::Context;
+
+    use super::*;
+    use crate::hooks::adapters::DefaultHookFactory;
+    use crate::hooks::runtime::HookDispatcher;
+    use crate::hooks::types::{HookOutcome, MediaUploadedEvent};
+
+    #[derive(Default)]
+    struct CountingHook {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl MediaUploadedHook for CountingHook {
+        async fn handle(&self, _ctx: &Context, _event: &MediaUploadedEvent) -> HookOutcome {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            HookOutcome::Completed
+        }
+    }
+
+    #[test]
+    fn test_parse_hook_group_from_config() {
+        let config: HookConfig = toml::from_str(
+            r#"
+            [[pre_send]]
+            name = "content-check"
+            group = "validation"
+
+            [[pre_send]]
+            name = "billing"
+            group = "Critical"
+
+            [[pre_send]]
+            name = "analytics"
+            "#,
+        )
+        .unwrap();
+
+        let groups: Vec<_> = config.pre_send.iter().map(|hook| hook.group).collect();
+        assert_eq!(
+            groups,
+            vec![Some(HookGroup::Validation), Some(HookGroup::Critical), None]
+        );
+
+        let invalid = toml::from_str::<HookConfig>(
+            r#"
+            [[pre_send]]
+            name = "content-check"
+            group = "urgent"
+            "#,
+        );
+        assert!(invalid.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_install_lifecycle_hook_from_config() {
+        let config: HookConfig = toml::from_str(
+            r#"
+            [[media_uploaded]]
+            name = "media-audit"
+
+            [media_uploaded.selector]
+            tenants = ["t1"]
+
+            [media_uploaded.transport]
+            type = "local"
+            target = "media_audit"
+            "#,
+        )
+        .unwrap();
+
+        let hook = Arc::new(CountingHook::default());
+        let mut factory = DefaultHookFactory::new().unwrap();
+        factory.register_media_uploaded_local("media_audit", hook.clone());
+        let registry = HookRegistry::new();
+        config
+            .install(Arc::clone(&registry), &factory)
+            .await
+            .unwrap();
+        let dispatcher = HookDispatcher::new(registry);
+
+        let event = MediaUploadedEvent {
+            file_id: "f1".to_string(),
+            uploader_id: "u1".to_string(),
+            file_name: "a.png".to_string(),
+            mime_type: "image/png".to_string(),
+            file_size: 1024,
+            url: "https://cdn.example.com/a.png".to_string(),
+            deduplicated: false,
+            uploaded_at: SystemTime::now(),
+            metadata: HashMap::new(),
+        };
+        dispatcher
+            .media_uploaded(&Context::root().with_tenant_id("t1"), &event)
+            .await
+            .unwrap();
+        // 其他租户不匹配 selector
+        dispatcher
+            .media_uploaded(&Context::root().with_tenant_id("t2"), &event)
+            .await
+            .unwrap();
+        assert_eq!(hook.calls.load(Ordering::SeqCst), 1);
+
+        // 生命周期 Hook 不支持 gRPC 传输
+        let grpc: HookConfig = toml::from_str(
+            r#"
+            [[presence_changed]]
+            name = "presence-sync"
+
+            [presence_changed.transport]
+            type = "grpc"
+            endpoint = "http://127.0.0.1:50051"
+            "#,
+        )
+        .unwrap();
+        assert!(grpc.install(HookRegistry::new(), &factory).await.is_err());
+    }
+}
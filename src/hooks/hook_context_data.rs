@@ -3,7 +3,10 @@
 //! 存储 Hook 特定的上下文信息，这些信息会被存储到 `flare_server_core::Context` 的自定义数据中
 
 use std::collections::HashMap;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
+
+/// 调用方剩余请求预算（毫秒）在 `HookInvocationContext.attributes` 中的键
+pub const HOOK_BUDGET_ATTRIBUTE: &str = "hook_budget_ms";
 
 /// Hook 特定的上下文数据
 ///
@@ -13,11 +16,15 @@ pub struct HookContextData {
     pub conversation_id: Option<String>,
     pub conversation_type: Option<String>,
     pub message_type: Option<String>,
+    /// 业务类型（如客服、营销），用于 Hook 选择器匹配
+    pub business_type: Option<String>,
     pub sender_id: Option<String>,
     pub tags: HashMap<String, String>,
     pub attributes: HashMap<String, String>,
     pub request_metadata: HashMap<String, String>,
     pub occurred_at: Option<SystemTime>,
+    /// 调用方请求的截止时间，Hook 引擎据此跳过剩余的非关键 Hook
+    pub deadline: Option<Instant>,
 }
 
 impl Default for HookContextData {
@@ -26,11 +33,13 @@ impl Default for HookContextData {
             conversation_id: None,
             conversation_type: None,
             message_type: None,
+            business_type: None,
             sender_id: None,
             tags: HashMap::new(),
             attributes: HashMap::new(),
             request_metadata: HashMap::new(),
             occurred_at: None,
+            deadline: None,
         }
     }
 }
@@ -55,6 +64,11 @@ impl HookContextData {
         self
     }
 
+    pub fn with_business_type(mut self, business_type: impl Into<String>) -> Self {
+        self.business_type = Some(business_type.into());
+        self
+    }
+
     pub fn with_sender_id(mut self, sender_id: impl Into<String>) -> Self {
         self.sender_id = Some(sender_id.into());
         self
@@ -79,6 +93,22 @@ impl HookContextData {
         self.occurred_at = Some(SystemTime::now());
         self
     }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// 以剩余预算设置截止时间
+    pub fn with_budget(self, budget: Duration) -> Self {
+        self.with_deadline(Instant::now() + budget)
+    }
+
+    /// 剩余请求预算（未设置截止时间时返回 None，已超时返回 0）
+    pub fn remaining_budget(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
 }
 
 /// 从 `flare_server_core::Context` 中提取 Hook 上下文数据
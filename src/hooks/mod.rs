@@ -10,16 +10,21 @@ pub mod hook_context_data;
 mod registry;
 mod runtime;
 mod selector;
+pub mod signature;
 mod types;
 
 pub use config::{
-    HookConfig, HookConfigLoader, HookDefinition, HookSelectorConfig, HookTransportConfig,
+    HookConfig, HookConfigLoader, HookDefinition, HookFactory, HookSelectorConfig,
+    HookTransportConfig,
 };
 pub use registry::{GlobalHookRegistry, HookRegistry, HookRegistryBuilder, PreSendPlan};
 pub use runtime::HookDispatcher;
-pub use selector::{HookSelector, MatchRule};
+pub use selector::{HookSelector, MatchRule, TagExpr};
+pub use signature::WebhookSigner;
 pub use types::{
-    DeliveryEvent, DeliveryHook, GetConversationParticipantsHook, HookErrorPolicy,
-    HookGroup, HookKind, HookMetadata, MessageDraft, MessageRecord, PostSendHook, PreSendDecision,
-    PreSendHook, RecallEvent, RecallHook,
+    DeliveryEvent, DeliveryHook, GetConversationParticipantsHook, HookErrorPolicy, HookGroup,
+    HookKind, HookMetadata, HookOutcome, MESSAGE_SENT_AT_METADATA_KEY, MediaUploadedEvent,
+    MediaUploadedHook, MessageDraft, MessageRecord, PostSendHook, PreSendDecision, PreSendHook,
+    PresenceChangedEvent, PresenceChangedHook, RecallEvent, RecallHook, SessionCreatedEvent,
+    SessionCreatedHook, SessionMemberChangedEvent, SessionMemberChangedHook,
 };
@@ -1,14 +1,18 @@
+use std::future::Future;
 use std::sync::Arc;
 
 use once_cell::sync::OnceCell;
 use tokio::sync::RwLock;
 
 use crate::error::{ErrorBuilder, ErrorCode, FlareError, Result};
+use crate::fault_injection::{self, FaultAction, FaultSeam};
 
 use super::selector::HookSelector;
 use super::types::{
-    DeliveryEvent, DeliveryHook, HookKind, HookMetadata, HookOutcome, MessageDraft,
-    MessageRecord, PostSendHook, PreSendDecision, PreSendHook, RecallEvent, RecallHook,
+    DeliveryEvent, DeliveryHook, HookKind, HookMetadata, HookOutcome, MediaUploadedEvent,
+    MediaUploadedHook, MessageDraft, MessageRecord, PostSendHook, PreSendDecision, PreSendHook,
+    PresenceChangedEvent, PresenceChangedHook, RecallEvent, RecallHook, SessionCreatedEvent,
+    SessionCreatedHook, SessionMemberChangedEvent, SessionMemberChangedHook,
 };
 use flare_server_core::context::Context;
 
@@ -52,7 +56,11 @@ impl PreSendPlan {
     }
 
     pub async fn execute(&self, ctx: &Context, draft: &mut MessageDraft) -> PreSendDecision {
-        let fut = self.handler.handle(ctx, draft);
+        let fut = with_fault_injection(
+            &self.metadata,
+            self.handler.handle(ctx, draft),
+            |error| PreSendDecision::Reject { error },
+        );
         match tokio::time::timeout(self.metadata.timeout, fut).await {
             Ok(decision) => match decision {
                 PreSendDecision::Continue => PreSendDecision::Continue,
@@ -75,6 +83,21 @@ impl PreSendPlan {
     }
 }
 
+/// Hook 调用的故障注入：错误转换为 Hook 失败，丢弃表现为 Hook 无响应（走超时处理）
+async fn with_fault_injection<T>(
+    metadata: &HookMetadata,
+    fut: impl Future<Output = T>,
+    on_error: impl FnOnce(FlareError) -> T,
+) -> T {
+    match fault_injection::inject(FaultSeam::Hook, &metadata.name).await {
+        FaultAction::Proceed => fut.await,
+        FaultAction::Drop => std::future::pending().await,
+        FaultAction::Fail(message) => {
+            on_error(metadata.build_error(ErrorCode::ServiceUnavailable, &message))
+        }
+    }
+}
+
 fn annotate(err: FlareError, metadata: &HookMetadata) -> FlareError {
     if let Some(localized) = err.as_localized() {
         if localized.details.is_none() {
@@ -93,6 +116,10 @@ pub struct HookRegistry {
     post_send: RwLock<Vec<RegistryEntry<dyn PostSendHook>>>,
     delivery: RwLock<Vec<RegistryEntry<dyn DeliveryHook>>>,
     recall: RwLock<Vec<RegistryEntry<dyn RecallHook>>>,
+    presence_changed: RwLock<Vec<RegistryEntry<dyn PresenceChangedHook>>>,
+    session_created: RwLock<Vec<RegistryEntry<dyn SessionCreatedHook>>>,
+    session_member_changed: RwLock<Vec<RegistryEntry<dyn SessionMemberChangedHook>>>,
+    media_uploaded: RwLock<Vec<RegistryEntry<dyn MediaUploadedHook>>>,
 }
 
 impl HookRegistry {
@@ -164,6 +191,66 @@ impl HookRegistry {
         guard.sort_by(|a, b| a.metadata.priority.cmp(&b.metadata.priority));
     }
 
+    pub async fn register_presence_changed(
+        &self,
+        metadata: HookMetadata,
+        selector: HookSelector,
+        handler: Arc<dyn PresenceChangedHook>,
+    ) {
+        let mut guard = self.presence_changed.write().await;
+        guard.push(RegistryEntry::new(
+            metadata.with_kind(HookKind::PresenceChanged),
+            selector,
+            handler,
+        ));
+        guard.sort_by(|a, b| a.metadata.priority.cmp(&b.metadata.priority));
+    }
+
+    pub async fn register_session_created(
+        &self,
+        metadata: HookMetadata,
+        selector: HookSelector,
+        handler: Arc<dyn SessionCreatedHook>,
+    ) {
+        let mut guard = self.session_created.write().await;
+        guard.push(RegistryEntry::new(
+            metadata.with_kind(HookKind::SessionCreated),
+            selector,
+            handler,
+        ));
+        guard.sort_by(|a, b| a.metadata.priority.cmp(&b.metadata.priority));
+    }
+
+    pub async fn register_session_member_changed(
+        &self,
+        metadata: HookMetadata,
+        selector: HookSelector,
+        handler: Arc<dyn SessionMemberChangedHook>,
+    ) {
+        let mut guard = self.session_member_changed.write().await;
+        guard.push(RegistryEntry::new(
+            metadata.with_kind(HookKind::SessionMemberChanged),
+            selector,
+            handler,
+        ));
+        guard.sort_by(|a, b| a.metadata.priority.cmp(&b.metadata.priority));
+    }
+
+    pub async fn register_media_uploaded(
+        &self,
+        metadata: HookMetadata,
+        selector: HookSelector,
+        handler: Arc<dyn MediaUploadedHook>,
+    ) {
+        let mut guard = self.media_uploaded.write().await;
+        guard.push(RegistryEntry::new(
+            metadata.with_kind(HookKind::MediaUploaded),
+            selector,
+            handler,
+        ));
+        guard.sort_by(|a, b| a.metadata.priority.cmp(&b.metadata.priority));
+    }
+
     pub async fn plan_pre_send(&self, ctx: &Context) -> Vec<PreSendPlan> {
         let guard = self.pre_send.read().await;
         guard
@@ -198,7 +285,11 @@ impl HookRegistry {
     ) -> Result<()> {
         let guard = self.post_send.read().await;
         for entry in guard.iter().filter(|entry| entry.selector.matches(ctx)) {
-            let fut = entry.handler.handle(ctx, record, draft);
+            let fut = with_fault_injection(
+                &entry.metadata,
+                entry.handler.handle(ctx, record, draft),
+                HookOutcome::Failed,
+            );
             let outcome = tokio::time::timeout(entry.metadata.timeout, fut).await;
             let outcome = match outcome {
                 Ok(result) => result,
@@ -224,7 +315,11 @@ impl HookRegistry {
     pub async fn execute_delivery(&self, ctx: &Context, event: &DeliveryEvent) -> Result<()> {
         let guard = self.delivery.read().await;
         for entry in guard.iter().filter(|entry| entry.selector.matches(ctx)) {
-            let fut = entry.handler.handle(ctx, event);
+            let fut = with_fault_injection(
+                &entry.metadata,
+                entry.handler.handle(ctx, event),
+                HookOutcome::Failed,
+            );
             let outcome = tokio::time::timeout(entry.metadata.timeout, fut).await;
             let outcome = match outcome {
                 Ok(result) => result,
@@ -250,7 +345,11 @@ impl HookRegistry {
     pub async fn execute_recall(&self, ctx: &Context, event: &RecallEvent) -> Result<()> {
         let guard = self.recall.read().await;
         for entry in guard.iter().filter(|entry| entry.selector.matches(ctx)) {
-            let fut = entry.handler.handle(ctx, event);
+            let fut = with_fault_injection(
+                &entry.metadata,
+                entry.handler.handle(ctx, event),
+                HookOutcome::Failed,
+            );
             let outcome = tokio::time::timeout(entry.metadata.timeout, fut).await;
             let outcome = match outcome {
                 Ok(result) => result,
@@ -272,6 +371,146 @@ impl HookRegistry {
         }
         Ok(())
     }
+
+    pub async fn execute_presence_changed(
+        &self,
+        ctx: &Context,
+        event: &PresenceChangedEvent,
+    ) -> Result<()> {
+        let guard = self.presence_changed.read().await;
+        for entry in guard.iter().filter(|entry| entry.selector.matches(ctx)) {
+            let fut = with_fault_injection(
+                &entry.metadata,
+                entry.handler.handle(ctx, event),
+                HookOutcome::Failed,
+            );
+            let outcome = tokio::time::timeout(entry.metadata.timeout, fut).await;
+            let outcome = match outcome {
+                Ok(result) => result,
+                Err(_) => {
+                    if entry.metadata.require_success {
+                        return Err(entry.metadata.build_error(
+                            ErrorCode::OperationTimeout,
+                            "presence-changed hook timed out",
+                        ));
+                    } else {
+                        tracing::warn!(
+                            hook = %entry.metadata.name,
+                            "presence-changed hook timeout ignored"
+                        );
+                        HookOutcome::Completed
+                    }
+                }
+            };
+            outcome.into_result(&entry.metadata)?;
+        }
+        Ok(())
+    }
+
+    pub async fn execute_session_created(
+        &self,
+        ctx: &Context,
+        event: &SessionCreatedEvent,
+    ) -> Result<()> {
+        let guard = self.session_created.read().await;
+        for entry in guard.iter().filter(|entry| entry.selector.matches(ctx)) {
+            let fut = with_fault_injection(
+                &entry.metadata,
+                entry.handler.handle(ctx, event),
+                HookOutcome::Failed,
+            );
+            let outcome = tokio::time::timeout(entry.metadata.timeout, fut).await;
+            let outcome = match outcome {
+                Ok(result) => result,
+                Err(_) => {
+                    if entry.metadata.require_success {
+                        return Err(entry.metadata.build_error(
+                            ErrorCode::OperationTimeout,
+                            "session-created hook timed out",
+                        ));
+                    } else {
+                        tracing::warn!(
+                            hook = %entry.metadata.name,
+                            "session-created hook timeout ignored"
+                        );
+                        HookOutcome::Completed
+                    }
+                }
+            };
+            outcome.into_result(&entry.metadata)?;
+        }
+        Ok(())
+    }
+
+    pub async fn execute_session_member_changed(
+        &self,
+        ctx: &Context,
+        event: &SessionMemberChangedEvent,
+    ) -> Result<()> {
+        let guard = self.session_member_changed.read().await;
+        for entry in guard.iter().filter(|entry| entry.selector.matches(ctx)) {
+            let fut = with_fault_injection(
+                &entry.metadata,
+                entry.handler.handle(ctx, event),
+                HookOutcome::Failed,
+            );
+            let outcome = tokio::time::timeout(entry.metadata.timeout, fut).await;
+            let outcome = match outcome {
+                Ok(result) => result,
+                Err(_) => {
+                    if entry.metadata.require_success {
+                        return Err(entry.metadata.build_error(
+                            ErrorCode::OperationTimeout,
+                            "session-member-changed hook timed out",
+                        ));
+                    } else {
+                        tracing::warn!(
+                            hook = %entry.metadata.name,
+                            "session-member-changed hook timeout ignored"
+                        );
+                        HookOutcome::Completed
+                    }
+                }
+            };
+            outcome.into_result(&entry.metadata)?;
+        }
+        Ok(())
+    }
+
+    pub async fn execute_media_uploaded(
+        &self,
+        ctx: &Context,
+        event: &MediaUploadedEvent,
+    ) -> Result<()> {
+        let guard = self.media_uploaded.read().await;
+        for entry in guard.iter().filter(|entry| entry.selector.matches(ctx)) {
+            let fut = with_fault_injection(
+                &entry.metadata,
+                entry.handler.handle(ctx, event),
+                HookOutcome::Failed,
+            );
+            let outcome = tokio::time::timeout(entry.metadata.timeout, fut).await;
+            let outcome = match outcome {
+                Ok(result) => result,
+                Err(_) => {
+                    if entry.metadata.require_success {
+                        return Err(entry.metadata.build_error(
+                            ErrorCode::OperationTimeout,
+                            "media-uploaded hook timed out",
+                        ));
+                    } else {
+                        tracing::warn!(
+                            hook = %entry.metadata.name,
+                            "media-uploaded hook timeout ignored"
+                        );
+                        HookOutcome::Completed
+                    }
+                }
+            };
+            outcome.into_result(&entry.metadata)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Default)]
@@ -2,8 +2,13 @@ use std::sync::Arc;
 
 use crate::error::Result;
 
+use super::adapters::DefaultHookFactory;
+use super::config::HookConfigLoader;
 use super::registry::HookRegistry;
-use super::types::{MessageDraft, MessageRecord, PreSendDecision};
+use super::types::{
+    MediaUploadedEvent, MessageDraft, MessageRecord, PreSendDecision, PresenceChangedEvent,
+    SessionCreatedEvent, SessionMemberChangedEvent,
+};
 use flare_server_core::context::Context;
 
 /// Hook 调度器，封装常用执行入口
@@ -17,6 +22,15 @@ impl HookDispatcher {
         Self { registry }
     }
 
+    /// 加载 Hook 配置并使用默认工厂（gRPC / WebHook / 本地）安装，返回调度器
+    pub async fn load(loader: &HookConfigLoader) -> Result<Self> {
+        let config = loader.load()?;
+        let registry = HookRegistry::new();
+        let factory = DefaultHookFactory::new()?;
+        config.install(Arc::clone(&registry), &factory).await?;
+        Ok(Self::new(registry))
+    }
+
     /// 调用获取会话参与者的Hook
     pub async fn invoke_get_conversation_participants(
         &self,
@@ -58,4 +72,34 @@ impl HookDispatcher {
     ) -> Result<()> {
         self.registry.execute_post_send(ctx, record, draft).await
     }
+
+    /// 执行 PresenceChanged Hook（用户上线/下线）
+    pub async fn presence_changed(
+        &self,
+        ctx: &Context,
+        event: &PresenceChangedEvent,
+    ) -> Result<()> {
+        self.registry.execute_presence_changed(ctx, event).await
+    }
+
+    /// 执行 SessionCreated Hook
+    pub async fn session_created(&self, ctx: &Context, event: &SessionCreatedEvent) -> Result<()> {
+        self.registry.execute_session_created(ctx, event).await
+    }
+
+    /// 执行 SessionMemberChanged Hook
+    pub async fn session_member_changed(
+        &self,
+        ctx: &Context,
+        event: &SessionMemberChangedEvent,
+    ) -> Result<()> {
+        self.registry
+            .execute_session_member_changed(ctx, event)
+            .await
+    }
+
+    /// 执行 MediaUploaded Hook
+    pub async fn media_uploaded(&self, ctx: &Context, event: &MediaUploadedEvent) -> Result<()> {
+        self.registry.execute_media_uploaded(ctx, event).await
+    }
 }
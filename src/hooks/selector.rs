@@ -1,4 +1,6 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
@@ -32,6 +34,15 @@ impl MatchRule {
         }
     }
 
+    /// 空列表表示匹配任意值
+    pub fn of_non_empty(values: &[String]) -> Self {
+        if values.is_empty() {
+            MatchRule::Any
+        } else {
+            MatchRule::of(values.iter().cloned())
+        }
+    }
+
     pub fn matches(&self, value: Option<&str>) -> bool {
         match self {
             MatchRule::Any => true,
@@ -40,6 +51,93 @@ impl MatchRule {
     }
 }
 
+/// 标签表达式
+///
+/// - `key`：存在该标签
+/// - `!key`：不存在该标签
+/// - `key=a|b`：标签值为 a 或 b
+/// - `key!=a|b`：标签不存在，或值不为 a 且不为 b
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum TagExpr {
+    Exists(String),
+    Missing(String),
+    In(String, Vec<String>),
+    NotIn(String, Vec<String>),
+}
+
+impl TagExpr {
+    pub fn matches(&self, tags: &HashMap<String, String>) -> bool {
+        match self {
+            TagExpr::Exists(key) => tags.contains_key(key),
+            TagExpr::Missing(key) => !tags.contains_key(key),
+            TagExpr::In(key, values) => tags.get(key).is_some_and(|v| values.contains(v)),
+            TagExpr::NotIn(key, values) => !tags.get(key).is_some_and(|v| values.contains(v)),
+        }
+    }
+}
+
+impl FromStr for TagExpr {
+    type Err = String;
+
+    fn from_str(raw: &str) -> std::result::Result<Self, Self::Err> {
+        let expr = raw.trim();
+        let parse_values = |values: &str| -> std::result::Result<Vec<String>, String> {
+            let values: Vec<String> = values
+                .split('|')
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect();
+            if values.is_empty() {
+                return Err(format!("tag expression `{raw}` has no values"));
+            }
+            Ok(values)
+        };
+        let parse_key = |key: &str| -> std::result::Result<String, String> {
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(format!("tag expression `{raw}` has no key"));
+            }
+            Ok(key.to_string())
+        };
+
+        if let Some((key, values)) = expr.split_once("!=") {
+            Ok(TagExpr::NotIn(parse_key(key)?, parse_values(values)?))
+        } else if let Some((key, values)) = expr.split_once('=') {
+            Ok(TagExpr::In(parse_key(key)?, parse_values(values)?))
+        } else if let Some(key) = expr.strip_prefix('!') {
+            Ok(TagExpr::Missing(parse_key(key)?))
+        } else {
+            Ok(TagExpr::Exists(parse_key(expr)?))
+        }
+    }
+}
+
+impl fmt::Display for TagExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagExpr::Exists(key) => write!(f, "{key}"),
+            TagExpr::Missing(key) => write!(f, "!{key}"),
+            TagExpr::In(key, values) => write!(f, "{key}={}", values.join("|")),
+            TagExpr::NotIn(key, values) => write!(f, "{key}!={}", values.join("|")),
+        }
+    }
+}
+
+impl TryFrom<String> for TagExpr {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<TagExpr> for String {
+    fn from(expr: TagExpr) -> Self {
+        expr.to_string()
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HookSelector {
     #[serde(default)]
@@ -48,21 +146,95 @@ pub struct HookSelector {
     pub conversation_types: MatchRule,
     #[serde(default)]
     pub message_types: MatchRule,
+    #[serde(default)]
+    pub business_types: MatchRule,
+    /// 发送者（用户ID）
+    #[serde(default)]
+    pub senders: MatchRule,
+    /// 标签表达式（全部满足才匹配）
+    #[serde(default)]
+    pub tags: Vec<TagExpr>,
 }
 
 impl HookSelector {
     pub fn matches(&self, ctx: &Context) -> bool {
         use crate::hooks::hook_context_data::get_hook_context_data;
-        
+
         let tenant_id = ctx.tenant_id().unwrap_or("0").to_string();
         let hook_data = get_hook_context_data(ctx);
-        
+        let empty_tags = HashMap::new();
+        let tags = hook_data.map(|d| &d.tags).unwrap_or(&empty_tags);
+
         self.tenants.matches(Some(tenant_id.as_str()))
-            && self.conversation_types.matches(
-                hook_data.and_then(|d| d.conversation_type.as_deref())
-            )
-            && self.message_types.matches(
-                hook_data.and_then(|d| d.message_type.as_deref())
-            )
+            && self
+                .conversation_types
+                .matches(hook_data.and_then(|d| d.conversation_type.as_deref()))
+            && self
+                .message_types
+                .matches(hook_data.and_then(|d| d.message_type.as_deref()))
+            && self
+                .business_types
+                .matches(hook_data.and_then(|d| d.business_type.as_deref()))
+            && self
+                .senders
+                .matches(hook_data.and_then(|d| d.sender_id.as_deref()))
+            && self.tags.iter().all(|expr| expr.matches(tags))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::hook_context_data::{HookContextData, set_hook_context_data};
+
+    fn tags(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_tag_expr_parse_and_match() {
+        let vip: TagExpr = "level=vip|svip".parse().unwrap();
+        assert_eq!(
+            vip,
+            TagExpr::In("level".into(), vec!["vip".into(), "svip".into()])
+        );
+        assert!(vip.matches(&tags(&[("level", "svip")])));
+        assert!(!vip.matches(&tags(&[("level", "normal")])));
+        assert!(!vip.matches(&tags(&[])));
+
+        let not_bot: TagExpr = "source != bot".parse().unwrap();
+        assert!(not_bot.matches(&tags(&[])));
+        assert!(!not_bot.matches(&tags(&[("source", "bot")])));
+
+        let missing: TagExpr = "!muted".parse().unwrap();
+        assert!(missing.matches(&tags(&[("level", "vip")])));
+        assert!(!missing.matches(&tags(&[("muted", "1")])));
+
+        assert!("=vip".parse::<TagExpr>().is_err());
+        assert!("level=".parse::<TagExpr>().is_err());
+        assert_eq!(String::from(not_bot), "source!=bot");
+    }
+
+    #[test]
+    fn test_selector_matches_business_type_and_tags() {
+        let selector = HookSelector {
+            message_types: MatchRule::of(["text"]),
+            business_types: MatchRule::of(["customer_service"]),
+            tags: vec!["level=vip".parse().unwrap()],
+            ..Default::default()
+        };
+        let data = HookContextData::new()
+            .with_message_type("text")
+            .with_business_type("customer_service")
+            .with_tags(tags(&[("level", "vip")]));
+        let ctx = set_hook_context_data(Context::root().with_tenant_id("t1"), data.clone());
+        assert!(selector.matches(&ctx));
+
+        let other = set_hook_context_data(Context::root(), data.with_business_type("marketing"));
+        assert!(!selector.matches(&other));
+        assert!(!selector.matches(&Context::root()));
     }
 }
@@ -0,0 +1,165 @@
+//! WebHook 请求签名
+//!
+//! 所有 WebHook 传输（`flare-im-core` 内置适配器与 Hook 引擎适配器）使用同一套签名规则：
+//! - `X-Hook-Timestamp`：发送时间（Unix 秒）
+//! - `X-Hook-Signature`：`sha256=hex(HMAC-SHA256(secret, "{timestamp}.{body}"))`
+//!
+//! 密钥轮换期间同时配置新旧两个密钥，签名头携带两个签名（逗号分隔，新密钥在前），
+//! 接收方持有其中任一密钥即可校验通过；轮换完成后移除旧密钥即可。
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 签名请求头
+pub const SIGNATURE_HEADER: &str = "X-Hook-Signature";
+/// 时间戳请求头
+pub const TIMESTAMP_HEADER: &str = "X-Hook-Timestamp";
+/// 签名前缀（签名算法）
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+/// 计算单个密钥的签名：`sha256=hex(HMAC-SHA256(secret, "{timestamp}.{body}"))`
+pub fn sign_payload(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    format!(
+        "{}{}",
+        SIGNATURE_PREFIX,
+        hex::encode(mac(secret, timestamp, body).finalize().into_bytes())
+    )
+}
+
+/// 校验签名头：签名头中任一签名能被任一密钥校验通过即视为有效（常量时间比较）
+///
+/// 时间戳的有效期由接收方自行判断（建议拒绝与当前时间相差超过5分钟的请求）
+pub fn verify_signature(secrets: &[&str], timestamp: u64, body: &[u8], header: &str) -> bool {
+    header
+        .split(',')
+        .filter_map(|signature| signature.trim().strip_prefix(SIGNATURE_PREFIX))
+        .filter_map(|signature| hex::decode(signature).ok())
+        .any(|signature| {
+            secrets.iter().any(|secret| {
+                mac(secret, timestamp, body)
+                    .verify_slice(&signature)
+                    .is_ok()
+            })
+        })
+}
+
+fn mac(secret: &str, timestamp: u64, body: &[u8]) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// WebHook 请求签名器（当前密钥 + 轮换中的旧密钥）
+#[derive(Clone)]
+pub struct WebhookSigner {
+    secret: String,
+    previous_secret: Option<String>,
+}
+
+impl WebhookSigner {
+    /// 根据配置创建签名器，未配置当前密钥时返回 `None`（不签名）
+    pub fn from_secrets(secret: Option<String>, previous_secret: Option<String>) -> Option<Self> {
+        let secret = secret.filter(|s| !s.is_empty())?;
+        Some(Self {
+            previous_secret: previous_secret.filter(|s| !s.is_empty() && *s != secret),
+            secret,
+        })
+    }
+
+    /// 生成签名头的值（轮换期间包含新旧两个签名）
+    pub fn sign(&self, timestamp: u64, body: &[u8]) -> String {
+        let signature = sign_payload(&self.secret, timestamp, body);
+        match &self.previous_secret {
+            Some(previous) => format!("{},{}", signature, sign_payload(previous, timestamp, body)),
+            None => signature,
+        }
+    }
+
+    /// 按请求体为已构建的请求追加 `X-Hook-Timestamp` 与 `X-Hook-Signature` 请求头
+    #[cfg(feature = "webhook")]
+    pub fn sign_request(&self, request: &mut reqwest::Request) {
+        use reqwest::header::HeaderValue;
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let body = request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .unwrap_or_default();
+        let signature = self.sign(timestamp, body);
+        let headers = request.headers_mut();
+        headers.insert(TIMESTAMP_HEADER, HeaderValue::from(timestamp));
+        if let Ok(signature) = HeaderValue::from_str(&signature) {
+            headers.insert(SIGNATURE_HEADER, signature);
+        }
+    }
+}
+
+impl std::fmt::Debug for WebhookSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookSigner")
+            .field("rotating", &self.previous_secret.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &[u8] = br#"{"a":1}"#;
+
+    #[test]
+    fn test_rotation_signature_verifies_with_either_secret() {
+        let signer =
+            WebhookSigner::from_secrets(Some("new".to_string()), Some("old".to_string())).unwrap();
+        let header = signer.sign(1_700_000_000, BODY);
+        assert_eq!(header.split(',').count(), 2);
+
+        assert!(verify_signature(&["new"], 1_700_000_000, BODY, &header));
+        assert!(verify_signature(&["old"], 1_700_000_000, BODY, &header));
+        assert!(!verify_signature(&["other"], 1_700_000_000, BODY, &header));
+        // 时间戳或请求体被篡改时校验失败
+        assert!(!verify_signature(&["new"], 1_700_000_001, BODY, &header));
+        assert!(!verify_signature(&["new"], 1_700_000_000, b"{}", &header));
+    }
+
+    #[test]
+    fn test_signer_requires_current_secret() {
+        assert!(WebhookSigner::from_secrets(None, Some("old".to_string())).is_none());
+        assert!(WebhookSigner::from_secrets(Some(String::new()), None).is_none());
+
+        // 旧密钥与当前密钥相同时只签一次
+        let signer =
+            WebhookSigner::from_secrets(Some("key".to_string()), Some("key".to_string())).unwrap();
+        assert_eq!(signer.sign(1, BODY), sign_payload("key", 1, BODY));
+    }
+
+    #[cfg(feature = "webhook")]
+    #[test]
+    fn test_sign_request_signs_body_with_timestamp() {
+        let signer = WebhookSigner::from_secrets(Some("key".to_string()), None).unwrap();
+        let mut request = reqwest::Client::new()
+            .post("http://localhost/hook")
+            .json(&serde_json::json!({"a": 1}))
+            .build()
+            .unwrap();
+        signer.sign_request(&mut request);
+
+        let timestamp: u64 = request.headers()[TIMESTAMP_HEADER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let header = request.headers()[SIGNATURE_HEADER].to_str().unwrap();
+        assert!(verify_signature(&["key"], timestamp, BODY, header));
+        assert!(!header.contains("key"));
+    }
+}
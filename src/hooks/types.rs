@@ -17,6 +17,29 @@ pub enum HookKind {
     PostSend,
     Delivery,
     Recall,
+    /// 用户上线/下线
+    PresenceChanged,
+    /// 会话创建
+    SessionCreated,
+    /// 会话成员变更
+    SessionMemberChanged,
+    /// 媒体文件上传完成
+    MediaUploaded,
+}
+
+impl HookKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookKind::PreSend => "pre_send",
+            HookKind::PostSend => "post_send",
+            HookKind::Delivery => "delivery",
+            HookKind::Recall => "recall",
+            HookKind::PresenceChanged => "presence_changed",
+            HookKind::SessionCreated => "session_created",
+            HookKind::SessionMemberChanged => "session_member_changed",
+            HookKind::MediaUploaded => "media_uploaded",
+        }
+    }
 }
 
 /// Hook 执行策略
@@ -37,7 +60,10 @@ impl Default for HookErrorPolicy {
 }
 
 /// Hook分组
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// 配置中使用小写名称（`group = "validation"`），反序列化与 [`HookGroup::parse`] 一致（大小写不敏感）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum HookGroup {
     /// 校验类Hook组（串行执行，快速失败）
     Validation,
@@ -56,6 +82,24 @@ impl HookGroup {
             HookGroup::Business
         }
     }
+
+    /// 从配置字符串解析（validation/critical/business，大小写不敏感）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "validation" => Some(HookGroup::Validation),
+            "critical" => Some(HookGroup::Critical),
+            "business" => Some(HookGroup::Business),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookGroup::Validation => "validation",
+            HookGroup::Critical => "critical",
+            HookGroup::Business => "business",
+        }
+    }
 }
 
 impl Default for HookGroup {
@@ -64,6 +108,18 @@ impl Default for HookGroup {
     }
 }
 
+impl<'de> Deserialize<'de> for HookGroup {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        HookGroup::parse(&value).ok_or_else(|| {
+            serde::de::Error::unknown_variant(&value, &["validation", "critical", "business"])
+        })
+    }
+}
+
 // Hook 特定的数据通过 Context 的自定义数据存储（见 HookContextData）
 
 /// 消息草稿（Pre-Send 阶段可修改）
@@ -144,12 +200,113 @@ pub struct DeliveryEvent {
     pub metadata: HashMap<String, String>,
 }
 
+/// 撤回事件中原消息发送时间在 gRPC 元数据中的键（Unix 毫秒）
+///
+/// `HookRecallEvent` 没有发送时间字段，跨进程传递时由转换函数写入/读取该键
+pub const MESSAGE_SENT_AT_METADATA_KEY: &str = "message_sent_at_ms";
+
 /// 撤回事件
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecallEvent {
     pub message_id: String,
     pub operator_id: String,
     pub recalled_at: SystemTime,
+    /// 原消息的发送时间（取自存储的消息时间戳，未知时为 None）
+    #[serde(default)]
+    pub sent_at: Option<SystemTime>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+impl RecallEvent {
+    /// 设置原消息的发送时间
+    pub fn with_sent_at(mut self, sent_at: SystemTime) -> Self {
+        self.sent_at = Some(sent_at);
+        self
+    }
+
+    /// gRPC 传输用的元数据（包含 [`MESSAGE_SENT_AT_METADATA_KEY`]）
+    pub fn wire_metadata(&self) -> HashMap<String, String> {
+        let mut metadata = self.metadata.clone();
+        if let Some(sent_at) = self.sent_at {
+            let millis = sent_at
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            metadata.insert(MESSAGE_SENT_AT_METADATA_KEY.to_string(), millis.to_string());
+        }
+        metadata
+    }
+
+    /// 从 gRPC 元数据中读取原消息的发送时间
+    pub fn sent_at_from_metadata(metadata: &HashMap<String, String>) -> Option<SystemTime> {
+        metadata
+            .get(MESSAGE_SENT_AT_METADATA_KEY)
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(|ms| std::time::UNIX_EPOCH + std::time::Duration::from_millis(ms))
+    }
+}
+
+/// 在线状态变更事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresenceChangedEvent {
+    pub user_id: String,
+    pub device_id: String,
+    pub device_platform: String,
+    pub gateway_id: Option<String>,
+    /// true 表示上线，false 表示下线
+    pub online: bool,
+    pub changed_at: SystemTime,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+/// 会话创建事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCreatedEvent {
+    pub conversation_id: String,
+    pub conversation_type: String,
+    pub business_type: Option<String>,
+    pub creator_id: Option<String>,
+    /// 初始成员
+    #[serde(default)]
+    pub participants: Vec<String>,
+    pub created_at: SystemTime,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+/// 会话成员变更事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMemberChangedEvent {
+    pub conversation_id: String,
+    pub conversation_type: String,
+    pub business_type: Option<String>,
+    pub operator_id: Option<String>,
+    #[serde(default)]
+    pub added: Vec<String>,
+    #[serde(default)]
+    pub removed: Vec<String>,
+    /// 角色被更新的成员
+    #[serde(default)]
+    pub updated: Vec<String>,
+    pub changed_at: SystemTime,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+/// 媒体上传完成事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaUploadedEvent {
+    pub file_id: String,
+    pub uploader_id: String,
+    pub file_name: String,
+    pub mime_type: String,
+    pub file_size: i64,
+    pub url: String,
+    /// 命中秒传（复用已存在的相同文件）
+    pub deduplicated: bool,
+    pub uploaded_at: SystemTime,
     #[serde(default)]
     pub metadata: HashMap<String, String>,
 }
@@ -227,6 +384,30 @@ pub trait RecallHook: Send + Sync {
     async fn handle(&self, ctx: &Context, event: &RecallEvent) -> HookOutcome;
 }
 
+/// PresenceChanged Hook Trait
+#[async_trait]
+pub trait PresenceChangedHook: Send + Sync {
+    async fn handle(&self, ctx: &Context, event: &PresenceChangedEvent) -> HookOutcome;
+}
+
+/// SessionCreated Hook Trait
+#[async_trait]
+pub trait SessionCreatedHook: Send + Sync {
+    async fn handle(&self, ctx: &Context, event: &SessionCreatedEvent) -> HookOutcome;
+}
+
+/// SessionMemberChanged Hook Trait
+#[async_trait]
+pub trait SessionMemberChangedHook: Send + Sync {
+    async fn handle(&self, ctx: &Context, event: &SessionMemberChangedEvent) -> HookOutcome;
+}
+
+/// MediaUploaded Hook Trait
+#[async_trait]
+pub trait MediaUploadedHook: Send + Sync {
+    async fn handle(&self, ctx: &Context, event: &MediaUploadedEvent) -> HookOutcome;
+}
+
 /// GetConversationParticipants Hook Trait
 ///
 /// 业务系统可以通过实现此 Hook 来提供会话参与者列表
@@ -295,6 +476,46 @@ where
     }
 }
 
+#[async_trait]
+impl<T> PresenceChangedHook for Arc<T>
+where
+    T: PresenceChangedHook + ?Sized,
+{
+    async fn handle(&self, ctx: &Context, event: &PresenceChangedEvent) -> HookOutcome {
+        (**self).handle(ctx, event).await
+    }
+}
+
+#[async_trait]
+impl<T> SessionCreatedHook for Arc<T>
+where
+    T: SessionCreatedHook + ?Sized,
+{
+    async fn handle(&self, ctx: &Context, event: &SessionCreatedEvent) -> HookOutcome {
+        (**self).handle(ctx, event).await
+    }
+}
+
+#[async_trait]
+impl<T> SessionMemberChangedHook for Arc<T>
+where
+    T: SessionMemberChangedHook + ?Sized,
+{
+    async fn handle(&self, ctx: &Context, event: &SessionMemberChangedEvent) -> HookOutcome {
+        (**self).handle(ctx, event).await
+    }
+}
+
+#[async_trait]
+impl<T> MediaUploadedHook for Arc<T>
+where
+    T: MediaUploadedHook + ?Sized,
+{
+    async fn handle(&self, ctx: &Context, event: &MediaUploadedEvent) -> HookOutcome {
+        (**self).handle(ctx, event).await
+    }
+}
+
 impl HookOutcome {
     pub fn into_result(self, metadata: &HookMetadata) -> Result<()> {
         match self {
@@ -326,6 +547,8 @@ pub struct HookMetadata {
     pub max_retries: u32,
     pub error_policy: HookErrorPolicy,
     pub require_success: bool,
+    /// 显式分组（None 时根据 priority 推断）
+    pub group: Option<HookGroup>,
 }
 
 impl Default for HookMetadata {
@@ -340,6 +563,7 @@ impl Default for HookMetadata {
             max_retries: 0,
             error_policy: HookErrorPolicy::FailFast,
             require_success: true,
+            group: None,
         }
     }
 }
@@ -385,6 +609,17 @@ impl HookMetadata {
         self
     }
 
+    pub fn with_group(mut self, group: Option<HookGroup>) -> Self {
+        self.group = group;
+        self
+    }
+
+    /// 实际生效的分组：优先使用显式分组，否则根据 priority 推断
+    pub fn group(&self) -> HookGroup {
+        self.group
+            .unwrap_or_else(|| HookGroup::from_priority(self.priority))
+    }
+
     pub fn build_error(&self, code: ErrorCode, message: &str) -> FlareError {
         ErrorBuilder::new(code, message)
             .details(format!("hook={}", self.name))
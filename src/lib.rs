@@ -2,17 +2,33 @@
 //!
 //! 提供统一的配置加载和服务注册发现功能
 
+#[cfg(feature = "ack")]
 pub mod ack;
+#[cfg(feature = "auth")]
+pub mod auth;
+pub mod build_info;
 pub mod config;
 pub mod discovery;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 pub mod error;
+pub mod event_bus;
+pub mod fault_injection;
 pub mod gateway;
 pub mod hooks;
+#[cfg(feature = "metrics")]
 pub mod metrics;
+pub mod pagination;
+pub mod scheduler;
 pub mod service_names;
 pub mod tracing;
 pub mod utils;
 
+#[cfg(test)]
+mod compat;
+#[cfg(test)]
+mod feature_matrix;
+
 // Re-export context utilities for convenience
 pub use utils::context::{
     require_context, extract_context_opt,
@@ -22,17 +38,34 @@ pub use utils::context::{
 };
 
 // 重新导出 ACK 相关类型（AckServiceConfig 通过 ack::AckServiceConfig 访问）
+#[cfg(feature = "ack")]
 pub use ack::{
     AckEvent, AckManager, AckModule, AckStatus, AckTimeoutEvent, AckType, ImportanceLevel,
 };
 
+pub use build_info::{BuildInfo, ServerInfoServer};
 pub use config::{
     AccessGatewayServiceConfig, ConfigManager, FlareAppConfig, KafkaClusterConfig,
     MediaServiceConfig, MessageOrchestratorServiceConfig, MongoInstanceConfig, ObjectStoreConfig,
     PostgresInstanceConfig, RedisPoolConfig, ServiceEndpointConfig, ServiceRuntimeConfig,
     ConversationServiceConfig, SessionPolicyConfig, SignalingOnlineServiceConfig,
     SignalingRouteServiceConfig, StorageReaderServiceConfig, StorageWriterServiceConfig,
-    app_config, load_config, load_config_with_validation,
+    ValidationReport, app_config, config_fingerprint, load_config, load_config_from,
+    load_config_with_validation, strict_mode,
+};
+#[cfg(feature = "config-center")]
+pub use config::{ConfigCenterBackend, ConfigCenterSource};
+#[cfg(feature = "secrets")]
+pub use config::{
+    AwsCredentials, AwsSecretsManagerResolver, ResolvedSecret, SecretLease, SecretManager,
+    SecretReference, SecretResolver, VaultResolver,
+};
+#[cfg(feature = "encryption")]
+pub use config::{ConfigCipher, ENCRYPTED_VALUE_PREFIX};
+#[cfg(feature = "config-watch")]
+pub use config::{
+    ConfigChangeEvent, ConfigReloadRecord, ConfigSection, ConfigWatcher, ReloadTrigger,
+    recent_reloads,
 };
 pub use discovery::{
     BackendType,
@@ -63,8 +96,13 @@ pub use discovery::{
     register_service_from_registry_config,
     register_service_only,
 };
+#[cfg(feature = "encryption")]
+pub use encryption::{DataKey, FieldEncryptor, KmsProvider, LocalKeyFileKms};
 pub use error::*;
+pub use event_bus::{EventBus, EventHandler, SubscriberStats};
 pub use hooks::*;
+pub use pagination::{CursorCodec, PageInfo, PageLimit, PaginationError};
+pub use scheduler::{JobHandler, JobStore, ScheduledJob, SchedulerConfig, TaskScheduler};
 
 pub use gateway::{GatewayRouter, GatewayRouterConfig, GatewayRouterError, GatewayRouterTrait};
 pub use service_names::service_names::*; // 导出所有服务名常量
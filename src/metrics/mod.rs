@@ -2,14 +2,23 @@
 //!
 //! 为各个服务模块提供统一的 Prometheus 指标收集能力。
 
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use once_cell::sync::Lazy;
 use prometheus::{
-    Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry,
 };
 
 /// 全局指标注册表
 pub static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
 
+/// 全局 Exemplar 存储（由 [`gather_metrics_openmetrics`] 导出）
+pub static EXEMPLARS: Lazy<ExemplarStore> = Lazy::new(ExemplarStore::default);
+
 /// 消息编排服务指标
 pub struct MessageOrchestratorMetrics {
     /// 消息发送总数
@@ -141,6 +150,18 @@ pub struct StorageWriterMetrics {
     pub messages_duplicate_total: IntCounter,
     /// 批量处理大小
     pub batch_size: Histogram,
+    /// 一致性校验抽样的消息数
+    pub consistency_checked_total: IntCounter,
+    /// 一致性校验发现的不一致次数（按存储与类型）
+    pub consistency_divergence_total: IntCounterVec,
+    /// 一致性校验回填热缓存的次数
+    pub consistency_repaired_total: IntCounter,
+    /// 租户公平调度的积压消息数（按租户）
+    pub tenant_backlog: IntGaugeVec,
+    /// 租户因写入限速被跳过的次数（按租户）
+    pub tenant_throttled_total: IntCounterVec,
+    /// 因租户过载被暂停的 Kafka 分区数
+    pub paused_partitions: IntGauge,
 }
 
 impl StorageWriterMetrics {
@@ -193,6 +214,51 @@ impl StorageWriterMetrics {
         )
         .expect("Failed to create batch_size metric");
 
+        let consistency_checked_total = IntCounter::new(
+            "storage_consistency_checked_total",
+            "Total number of messages sampled by the consistency verifier",
+        )
+        .expect("Failed to create consistency_checked_total metric");
+
+        let consistency_divergence_total = IntCounterVec::new(
+            Opts::new(
+                "storage_consistency_divergence_total",
+                "Total number of divergences found between message stores",
+            ),
+            &["store", "kind"],
+        )
+        .expect("Failed to create consistency_divergence_total metric");
+
+        let consistency_repaired_total = IntCounter::new(
+            "storage_consistency_repaired_total",
+            "Total number of hot cache entries repaired by the consistency verifier",
+        )
+        .expect("Failed to create consistency_repaired_total metric");
+
+        let tenant_backlog = IntGaugeVec::new(
+            Opts::new(
+                "storage_writer_tenant_backlog",
+                "Number of messages queued by the tenant fairness scheduler",
+            ),
+            &["tenant_id"],
+        )
+        .expect("Failed to create tenant_backlog metric");
+
+        let tenant_throttled_total = IntCounterVec::new(
+            Opts::new(
+                "storage_writer_tenant_throttled_total",
+                "Total number of times a tenant was skipped by the write rate limit",
+            ),
+            &["tenant_id"],
+        )
+        .expect("Failed to create tenant_throttled_total metric");
+
+        let paused_partitions = IntGauge::new(
+            "storage_writer_paused_partitions",
+            "Number of Kafka partitions paused due to tenant overload",
+        )
+        .expect("Failed to create paused_partitions metric");
+
         // 注册指标，忽略重复注册错误（在基准测试中可能会重复创建）
         let _ = REGISTRY.register(Box::new(messages_persisted_total.clone()));
         let _ = REGISTRY.register(Box::new(messages_persisted_duration_seconds.clone()));
@@ -200,6 +266,12 @@ impl StorageWriterMetrics {
         let _ = REGISTRY.register(Box::new(redis_update_duration_seconds.clone()));
         let _ = REGISTRY.register(Box::new(messages_duplicate_total.clone()));
         let _ = REGISTRY.register(Box::new(batch_size.clone()));
+        let _ = REGISTRY.register(Box::new(consistency_checked_total.clone()));
+        let _ = REGISTRY.register(Box::new(consistency_divergence_total.clone()));
+        let _ = REGISTRY.register(Box::new(consistency_repaired_total.clone()));
+        let _ = REGISTRY.register(Box::new(tenant_backlog.clone()));
+        let _ = REGISTRY.register(Box::new(tenant_throttled_total.clone()));
+        let _ = REGISTRY.register(Box::new(paused_partitions.clone()));
 
         Self {
             messages_persisted_total,
@@ -208,6 +280,12 @@ impl StorageWriterMetrics {
             redis_update_duration_seconds,
             messages_duplicate_total,
             batch_size,
+            consistency_checked_total,
+            consistency_divergence_total,
+            consistency_repaired_total,
+            tenant_backlog,
+            tenant_throttled_total,
+            paused_partitions,
         }
     }
 }
@@ -238,6 +316,24 @@ pub struct PushServerMetrics {
     pub ack_received_total: IntCounterVec,
     /// ACK超时次数
     pub ack_timeout_total: IntCounterVec,
+    /// 推送任务消费积压消息数（可作为 K8s HPA 外部指标）
+    pub consumer_lag: IntGauge,
+    /// 推送任务消费积压时长（毫秒）
+    pub consumer_message_age_ms: IntGauge,
+    /// 当前消费容量（进程内并发度或期望实例数）
+    pub consumer_capacity: IntGauge,
+    /// 等待网关恢复的重投递任务数
+    pub gateway_redelivery_buffered: IntGauge,
+    /// 网关重投递任务数（按结果：buffered/redelivered/expired/overflow）
+    pub gateway_redelivery_total: IntCounterVec,
+    /// 网关推送失败后重新解析路由的任务数（按结果：rerouted/offline/unchanged）
+    pub gateway_reroute_total: IntCounterVec,
+    /// 租户扇出延迟（秒，从进入租户队列到推送处理完成）
+    pub tenant_fanout_latency_seconds: HistogramVec,
+    /// 租户工作池待处理任务数
+    pub tenant_queue_depth: IntGaugeVec,
+    /// 租户队列已满导致消费端等待的次数
+    pub tenant_queue_full_total: IntCounterVec,
 }
 
 impl PushServerMetrics {
@@ -312,6 +408,76 @@ impl PushServerMetrics {
         )
         .expect("Failed to create ack_timeout_total metric");
 
+        let consumer_lag = IntGauge::new(
+            "push_consumer_lag",
+            "Number of push task messages waiting to be consumed",
+        )
+        .expect("Failed to create push_consumer_lag metric");
+
+        let consumer_message_age_ms = IntGauge::new(
+            "push_consumer_message_age_ms",
+            "Age of the most recently consumed push task message in milliseconds",
+        )
+        .expect("Failed to create push_consumer_message_age_ms metric");
+
+        let consumer_capacity = IntGauge::new(
+            "push_consumer_capacity",
+            "Current push consumer capacity (worker concurrency or desired instances)",
+        )
+        .expect("Failed to create push_consumer_capacity metric");
+
+        let gateway_redelivery_buffered = IntGauge::new(
+            "push_gateway_redelivery_buffered",
+            "Number of push tasks buffered while waiting for their gateway to recover",
+        )
+        .expect("Failed to create push_gateway_redelivery_buffered metric");
+
+        let gateway_redelivery_total = IntCounterVec::new(
+            Opts::new(
+                "push_gateway_redelivery_total",
+                "Total number of push tasks handled by the gateway redelivery buffer",
+            ),
+            &["outcome"],
+        )
+        .expect("Failed to create push_gateway_redelivery_total metric");
+
+        let gateway_reroute_total = IntCounterVec::new(
+            Opts::new(
+                "push_gateway_reroute_total",
+                "Total number of push tasks re-resolved after a gateway push failure",
+            ),
+            &["outcome"],
+        )
+        .expect("Failed to create push_gateway_reroute_total metric");
+
+        let tenant_fanout_latency_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "push_tenant_fanout_latency_seconds",
+                "Per-tenant fan-out latency from enqueue to push completion in seconds",
+            )
+            .buckets(vec![0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 30.0]),
+            &["tenant_id", "tier"],
+        )
+        .expect("Failed to create push_tenant_fanout_latency_seconds metric");
+
+        let tenant_queue_depth = IntGaugeVec::new(
+            Opts::new(
+                "push_tenant_queue_depth",
+                "Number of push tasks waiting in the tenant worker pool queue",
+            ),
+            &["tenant_id"],
+        )
+        .expect("Failed to create push_tenant_queue_depth metric");
+
+        let tenant_queue_full_total = IntCounterVec::new(
+            Opts::new(
+                "push_tenant_queue_full_total",
+                "Total number of times the consumer waited on a full tenant queue",
+            ),
+            &["tenant_id"],
+        )
+        .expect("Failed to create push_tenant_queue_full_total metric");
+
         // 注册指标，忽略重复注册错误（在基准测试中可能会重复创建）
         let _ = REGISTRY.register(Box::new(push_tasks_processed_total.clone()));
         let _ = REGISTRY.register(Box::new(online_push_success_total.clone()));
@@ -322,6 +488,15 @@ impl PushServerMetrics {
         let _ = REGISTRY.register(Box::new(batch_size.clone()));
         let _ = REGISTRY.register(Box::new(ack_received_total.clone()));
         let _ = REGISTRY.register(Box::new(ack_timeout_total.clone()));
+        let _ = REGISTRY.register(Box::new(consumer_lag.clone()));
+        let _ = REGISTRY.register(Box::new(consumer_message_age_ms.clone()));
+        let _ = REGISTRY.register(Box::new(consumer_capacity.clone()));
+        let _ = REGISTRY.register(Box::new(gateway_redelivery_buffered.clone()));
+        let _ = REGISTRY.register(Box::new(gateway_redelivery_total.clone()));
+        let _ = REGISTRY.register(Box::new(gateway_reroute_total.clone()));
+        let _ = REGISTRY.register(Box::new(tenant_fanout_latency_seconds.clone()));
+        let _ = REGISTRY.register(Box::new(tenant_queue_depth.clone()));
+        let _ = REGISTRY.register(Box::new(tenant_queue_full_total.clone()));
 
         Self {
             push_tasks_processed_total,
@@ -333,6 +508,15 @@ impl PushServerMetrics {
             batch_size,
             ack_received_total,
             ack_timeout_total,
+            consumer_lag,
+            consumer_message_age_ms,
+            consumer_capacity,
+            gateway_redelivery_buffered,
+            gateway_redelivery_total,
+            gateway_reroute_total,
+            tenant_fanout_latency_seconds,
+            tenant_queue_depth,
+            tenant_queue_full_total,
         }
     }
 }
@@ -347,13 +531,13 @@ impl Default for PushServerMetrics {
 pub struct PushWorkerMetrics {
     /// 离线推送成功次数
     pub offline_push_success_total: IntCounterVec,
-    /// 离线推送失败次数
+    /// 离线推送失败次数（failure_reason 为归一化的渠道结果，如 token_invalid、throttled）
     pub offline_push_failure_total: IntCounterVec,
-    /// 推送重试次数
+    /// 推送重试次数（retry_reason 为归一化的渠道结果）
     pub push_retry_total: IntCounterVec,
     /// 推送耗时（秒）
     pub push_duration_seconds: HistogramVec,
-    /// 死信队列消息数
+    /// 死信队列消息数（failure_reason 为归一化的渠道结果）
     pub dlq_messages_total: IntCounterVec,
     /// 批量处理大小
     pub batch_size: Histogram,
@@ -451,6 +635,10 @@ pub struct AccessGatewayMetrics {
     /// 在线状态缓存命中率
     pub online_cache_hit_total: IntCounter,
     pub online_cache_miss_total: IntCounter,
+    /// 已建立的长连接总数（用于计算 accept 速率）
+    pub connections_accepted_total: IntCounter,
+    /// 长连接监听分片数（SO_REUSEPORT acceptor 数量）
+    pub listener_shards: IntGauge,
 }
 
 impl AccessGatewayMetrics {
@@ -514,6 +702,18 @@ impl AccessGatewayMetrics {
         )
         .expect("Failed to create online_cache_miss_total metric");
 
+        let connections_accepted_total = IntCounter::new(
+            "access_gateway_connections_accepted_total",
+            "Total number of accepted long connections",
+        )
+        .expect("Failed to create connections_accepted_total metric");
+
+        let listener_shards = IntGauge::new(
+            "access_gateway_listener_shards",
+            "Number of long connection listener shards",
+        )
+        .expect("Failed to create listener_shards metric");
+
         REGISTRY
             .register(Box::new(connections_active.clone()))
             .unwrap();
@@ -541,6 +741,12 @@ impl AccessGatewayMetrics {
         REGISTRY
             .register(Box::new(online_cache_miss_total.clone()))
             .unwrap();
+        REGISTRY
+            .register(Box::new(connections_accepted_total.clone()))
+            .unwrap();
+        REGISTRY
+            .register(Box::new(listener_shards.clone()))
+            .unwrap();
 
         Self {
             connections_active,
@@ -552,6 +758,8 @@ impl AccessGatewayMetrics {
             push_latency_seconds,
             online_cache_hit_total,
             online_cache_miss_total,
+            connections_accepted_total,
+            listener_shards,
         }
     }
 }
@@ -562,6 +770,260 @@ impl Default for AccessGatewayMetrics {
     }
 }
 
+/// 进程内事件总线指标（按总线、订阅者区分）
+pub struct EventBusMetrics {
+    /// 订阅者队列积压的事件数
+    pub subscriber_lag: IntGaugeVec,
+    /// 订阅者处理成功的事件数
+    pub events_handled_total: IntCounterVec,
+    /// 订阅者队列已满被丢弃的事件数
+    pub events_dropped_total: IntCounterVec,
+    /// 订阅者处理失败的事件数
+    pub handler_failures_total: IntCounterVec,
+}
+
+impl EventBusMetrics {
+    pub fn new() -> Self {
+        let subscriber_lag = IntGaugeVec::new(
+            Opts::new(
+                "event_bus_subscriber_lag",
+                "Number of events queued for an event bus subscriber",
+            ),
+            &["bus", "subscriber"],
+        )
+        .expect("Failed to create event_bus_subscriber_lag metric");
+
+        let events_handled_total = IntCounterVec::new(
+            Opts::new(
+                "event_bus_events_handled_total",
+                "Total number of events successfully handled by event bus subscribers",
+            ),
+            &["bus", "subscriber"],
+        )
+        .expect("Failed to create event_bus_events_handled_total metric");
+
+        let events_dropped_total = IntCounterVec::new(
+            Opts::new(
+                "event_bus_events_dropped_total",
+                "Total number of events dropped because a subscriber queue was full",
+            ),
+            &["bus", "subscriber"],
+        )
+        .expect("Failed to create event_bus_events_dropped_total metric");
+
+        let handler_failures_total = IntCounterVec::new(
+            Opts::new(
+                "event_bus_handler_failures_total",
+                "Total number of events an event bus subscriber failed to handle",
+            ),
+            &["bus", "subscriber"],
+        )
+        .expect("Failed to create event_bus_handler_failures_total metric");
+
+        let _ = REGISTRY.register(Box::new(subscriber_lag.clone()));
+        let _ = REGISTRY.register(Box::new(events_handled_total.clone()));
+        let _ = REGISTRY.register(Box::new(events_dropped_total.clone()));
+        let _ = REGISTRY.register(Box::new(handler_failures_total.clone()));
+
+        Self {
+            subscriber_lag,
+            events_handled_total,
+            events_dropped_total,
+            handler_failures_total,
+        }
+    }
+}
+
+impl Default for EventBusMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 字段加密指标
+pub struct FieldEncryptionMetrics {
+    /// 字段解密失败次数（密文损坏、密钥不属于记录租户或记录不匹配）
+    pub decrypt_failures_total: IntCounterVec,
+}
+
+impl FieldEncryptionMetrics {
+    pub fn new() -> Self {
+        let decrypt_failures_total = IntCounterVec::new(
+            Opts::new(
+                "field_decrypt_failures_total",
+                "Total number of encrypted fields that failed to decrypt",
+            ),
+            &["tenant_id"],
+        )
+        .expect("Failed to create field_decrypt_failures_total metric");
+
+        let _ = REGISTRY.register(Box::new(decrypt_failures_total.clone()));
+
+        Self {
+            decrypt_failures_total,
+        }
+    }
+}
+
+impl Default for FieldEncryptionMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hook 端点健康检查指标
+pub struct HookHealthMetrics {
+    /// Hook 端点健康状态（1 健康，0 降级）
+    pub endpoint_healthy: IntGaugeVec,
+    /// 健康检查失败次数
+    pub probe_failures_total: IntCounterVec,
+    /// 因端点降级被跳过的非必需 Hook 调用次数
+    pub degraded_skipped_total: IntCounterVec,
+}
+
+impl HookHealthMetrics {
+    pub fn new() -> Self {
+        let endpoint_healthy = IntGaugeVec::new(
+            Opts::new(
+                "hook_endpoint_healthy",
+                "Whether a hook endpoint is healthy (1) or degraded (0)",
+            ),
+            &["hook"],
+        )
+        .expect("Failed to create hook_endpoint_healthy metric");
+
+        let probe_failures_total = IntCounterVec::new(
+            Opts::new(
+                "hook_health_probe_failures_total",
+                "Total number of failed hook endpoint health probes",
+            ),
+            &["hook"],
+        )
+        .expect("Failed to create hook_health_probe_failures_total metric");
+
+        let degraded_skipped_total = IntCounterVec::new(
+            Opts::new(
+                "hook_degraded_skipped_total",
+                "Total number of optional hook calls skipped because the endpoint is degraded",
+            ),
+            &["hook"],
+        )
+        .expect("Failed to create hook_degraded_skipped_total metric");
+
+        let _ = REGISTRY.register(Box::new(endpoint_healthy.clone()));
+        let _ = REGISTRY.register(Box::new(probe_failures_total.clone()));
+        let _ = REGISTRY.register(Box::new(degraded_skipped_total.clone()));
+
+        Self {
+            endpoint_healthy,
+            probe_failures_total,
+            degraded_skipped_total,
+        }
+    }
+}
+
+impl Default for HookHealthMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hook 执行指标的标签
+const HOOK_EXECUTION_LABELS: [&str; 5] = ["hook", "kind", "group", "tenant_id", "outcome"];
+
+/// Hook 执行指标（按 Hook 维度统计错误率与耗时）
+pub struct HookExecutionMetrics {
+    /// Hook 执行次数（outcome: continue, reject, error）
+    pub executions_total: IntCounterVec,
+    /// Hook 执行耗时（秒）
+    pub execution_duration_seconds: HistogramVec,
+    /// Hook 执行前等待并发许可的时间（秒）
+    pub queue_wait_seconds: HistogramVec,
+    /// 因排队超时被拒绝的执行次数（scope: tenant, hook, global）
+    pub concurrency_rejected_total: IntCounterVec,
+}
+
+impl HookExecutionMetrics {
+    pub fn new() -> Self {
+        let executions_total = IntCounterVec::new(
+            Opts::new("hook_executions_total", "Total number of hook executions"),
+            &HOOK_EXECUTION_LABELS,
+        )
+        .expect("Failed to create hook_executions_total metric");
+
+        let execution_duration_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "hook_execution_duration_seconds",
+                "Hook execution duration in seconds",
+            )
+            .buckets(vec![0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0]),
+            &HOOK_EXECUTION_LABELS,
+        )
+        .expect("Failed to create hook_execution_duration_seconds metric");
+
+        let queue_wait_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "hook_queue_wait_seconds",
+                "Time hook executions waited for a concurrency permit in seconds",
+            )
+            .buckets(vec![0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0]),
+            &["hook", "kind", "tenant_id"],
+        )
+        .expect("Failed to create hook_queue_wait_seconds metric");
+
+        let concurrency_rejected_total = IntCounterVec::new(
+            Opts::new(
+                "hook_concurrency_rejected_total",
+                "Total number of hook executions rejected by concurrency limits",
+            ),
+            &["hook", "kind", "scope"],
+        )
+        .expect("Failed to create hook_concurrency_rejected_total metric");
+
+        let _ = REGISTRY.register(Box::new(executions_total.clone()));
+        let _ = REGISTRY.register(Box::new(execution_duration_seconds.clone()));
+        let _ = REGISTRY.register(Box::new(queue_wait_seconds.clone()));
+        let _ = REGISTRY.register(Box::new(concurrency_rejected_total.clone()));
+
+        Self {
+            executions_total,
+            execution_duration_seconds,
+            queue_wait_seconds,
+            concurrency_rejected_total,
+        }
+    }
+
+    /// 记录一次 Hook 执行的次数与耗时
+    ///
+    /// 标签顺序为 hook、kind、group、tenant_id、outcome；传入 trace_id 时为耗时记录 exemplar，
+    /// 执行出错时同时为错误计数记录 exemplar，便于从延迟尖刺或错误定位到具体链路
+    pub fn observe_execution(&self, labels: &[&str; 5], seconds: f64, trace_id: Option<&str>) {
+        self.executions_total.with_label_values(labels).inc();
+        self.execution_duration_seconds
+            .with_label_values(labels)
+            .observe(seconds);
+
+        let Some(trace_id) = trace_id.filter(|trace_id| !trace_id.is_empty()) else {
+            return;
+        };
+        let pairs: Vec<(&str, &str)> = HOOK_EXECUTION_LABELS
+            .iter()
+            .copied()
+            .zip(labels.iter().copied())
+            .collect();
+        EXEMPLARS.record("hook_execution_duration_seconds", &pairs, seconds, trace_id);
+        if labels[4] == "error" {
+            EXEMPLARS.record("hook_executions_total", &pairs, 1.0, trace_id);
+        }
+    }
+}
+
+impl Default for HookExecutionMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// 获取 Prometheus 指标导出格式
 pub fn gather_metrics() -> String {
     use prometheus::Encoder;
@@ -571,3 +1033,254 @@ pub fn gather_metrics() -> String {
     encoder.encode(&metric_families, &mut buffer).unwrap();
     String::from_utf8(buffer).unwrap()
 }
+
+/// 获取 OpenMetrics 指标导出格式（带 exemplar）
+///
+/// Prometheus 仅在 OpenMetrics 格式下解析 exemplar，抓取端点需以
+/// `application/openmetrics-text; version=1.0.0` 返回该内容
+pub fn gather_metrics_openmetrics() -> String {
+    EXEMPLARS.annotate(&gather_metrics())
+}
+
+/// Prometheus 文本格式的 Content-Type
+pub const TEXT_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// OpenMetrics 格式的 Content-Type
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// 按抓取请求的 `Accept` 头选择导出格式，返回 `(Content-Type, 内容)`
+///
+/// Prometheus 开启 exemplar 存储时以 `Accept: application/openmetrics-text` 抓取，
+/// 此时返回带 exemplar 的 OpenMetrics 格式，否则返回文本格式
+pub fn gather_metrics_for(accept: Option<&str>) -> (&'static str, String) {
+    if accept.is_some_and(|accept| accept.contains("application/openmetrics-text")) {
+        (OPENMETRICS_CONTENT_TYPE, gather_metrics_openmetrics())
+    } else {
+        (TEXT_CONTENT_TYPE, gather_metrics())
+    }
+}
+
+/// 指标样本关联的追踪样例（OpenMetrics exemplar）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exemplar {
+    pub trace_id: String,
+    pub value: f64,
+    /// Unix 时间戳（秒）
+    pub timestamp: f64,
+}
+
+/// Exemplar 存储
+///
+/// 按指标名与标签保留最近一次样例：直方图样例附加到值所在的第一个桶，计数器样例附加到计数行
+#[derive(Debug, Default)]
+pub struct ExemplarStore {
+    exemplars: RwLock<HashMap<String, Exemplar>>,
+}
+
+impl ExemplarStore {
+    /// 记录样例（`metric` 为直方图或计数器的指标名，不含 `_bucket` 后缀）
+    pub fn record(&self, metric: &str, labels: &[(&str, &str)], value: f64, trace_id: &str) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs_f64())
+            .unwrap_or_default();
+        let exemplar = Exemplar {
+            trace_id: trace_id.to_string(),
+            value,
+            timestamp,
+        };
+        self.exemplars
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(series_key(metric, labels), exemplar);
+    }
+
+    /// 获取指定序列最近一次的样例
+    pub fn get(&self, metric: &str, labels: &[(&str, &str)]) -> Option<Exemplar> {
+        self.exemplars
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&series_key(metric, labels))
+            .cloned()
+    }
+
+    /// 将 Prometheus 文本格式转换为 OpenMetrics 格式并附加样例
+    ///
+    /// 计数器的 `# TYPE` / `# HELP` 使用去掉 `_total` 的指标族名称，样本名补齐 `_total` 后缀
+    pub fn annotate(&self, text: &str) -> String {
+        let exemplars = self.exemplars.read().unwrap_or_else(|e| e.into_inner());
+        let counters: HashSet<&str> = text
+            .lines()
+            .filter_map(|line| line.strip_prefix("# TYPE "))
+            .filter_map(|rest| rest.strip_suffix(" counter"))
+            .collect();
+        let family = |name: &str| name.strip_suffix("_total").unwrap_or(name).to_string();
+
+        let mut out = String::with_capacity(text.len());
+        let mut annotated = HashSet::new();
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix("# TYPE ") {
+                match rest.strip_suffix(" counter") {
+                    Some(name) => {
+                        let _ = writeln!(out, "# TYPE {} counter", family(name));
+                    }
+                    None => {
+                        let _ = writeln!(out, "{line}");
+                    }
+                }
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("# HELP ") {
+                match rest.split_once(' ') {
+                    Some((name, help)) if counters.contains(name) => {
+                        let _ = writeln!(out, "# HELP {} {}", family(name), help);
+                    }
+                    _ => {
+                        let _ = writeln!(out, "{line}");
+                    }
+                }
+                continue;
+            }
+            let Some((series, value)) = line.rsplit_once(' ').filter(|_| !line.starts_with('#'))
+            else {
+                let _ = writeln!(out, "{line}");
+                continue;
+            };
+            let (name, labels) = series.split_at(series.find('{').unwrap_or(series.len()));
+
+            if counters.contains(name) {
+                let exemplar = exemplars.get(series);
+                let _ = write!(out, "{}_total{} {}", family(name), labels, value);
+                if let Some(exemplar) = exemplar {
+                    write_exemplar(&mut out, exemplar);
+                }
+                out.push('\n');
+                continue;
+            }
+
+            let _ = write!(out, "{line}");
+            if let Some(base) = name.strip_suffix("_bucket") {
+                if let Some((key, le)) = split_bucket_labels(base, labels) {
+                    if let Some(exemplar) = exemplars.get(&key) {
+                        if exemplar.value <= le && annotated.insert(key) {
+                            write_exemplar(&mut out, exemplar);
+                        }
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        out.push_str("# EOF\n");
+        out
+    }
+}
+
+/// 序列键：与文本格式一致的 `name{label="value",...}`（标签按名称排序）
+fn series_key(metric: &str, labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return metric.to_string();
+    }
+    let mut labels = labels.to_vec();
+    labels.sort_by_key(|(name, _)| *name);
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('\n', "\\n")
+                .replace('"', "\\\"");
+            format!("{name}=\"{value}\"")
+        })
+        .collect();
+    format!("{}{{{}}}", metric, labels.join(","))
+}
+
+/// 拆分直方图桶的标签，返回去掉 `le` 后的序列键与桶上界（`le` 总是最后一个标签）
+fn split_bucket_labels(base: &str, labels: &str) -> Option<(String, f64)> {
+    let labels = labels.strip_suffix('}')?;
+    let (rest, le) = match labels.rfind(",le=\"") {
+        Some(pos) => (format!("{}}}", &labels[..pos]), &labels[pos + 5..]),
+        None => (String::new(), labels.strip_prefix("{le=\"")?),
+    };
+    let le = le.strip_suffix('"')?.parse().ok()?;
+    Some((format!("{base}{rest}"), le))
+}
+
+fn write_exemplar(out: &mut String, exemplar: &Exemplar) {
+    let _ = write!(
+        out,
+        " # {{trace_id=\"{}\"}} {} {:.3}",
+        exemplar.trace_id, exemplar.value, exemplar.timestamp
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exemplar_annotation() {
+        let store = ExemplarStore::default();
+        let labels = [("outcome", "error"), ("hook", "spam")];
+        store.record("hook_duration_seconds", &labels, 0.3, "trace-1");
+        store.record("hook_total", &labels, 1.0, "trace-1");
+
+        let text = "\
+# HELP hook_duration_seconds Hook duration
+# TYPE hook_duration_seconds histogram
+hook_duration_seconds_bucket{hook=\"spam\",outcome=\"error\",le=\"0.1\"} 0
+hook_duration_seconds_bucket{hook=\"spam\",outcome=\"error\",le=\"0.5\"} 1
+hook_duration_seconds_bucket{hook=\"spam\",outcome=\"error\",le=\"+Inf\"} 1
+hook_duration_seconds_sum{hook=\"spam\",outcome=\"error\"} 0.3
+hook_duration_seconds_count{hook=\"spam\",outcome=\"error\"} 1
+# HELP hook_total Hook executions
+# TYPE hook_total counter
+hook_total{hook=\"spam\",outcome=\"error\"} 1
+";
+        let annotated = store.annotate(text);
+        let lines: Vec<&str> = annotated.lines().collect();
+
+        assert!(!lines[2].contains('#'));
+        assert!(lines[3].starts_with(
+            "hook_duration_seconds_bucket{hook=\"spam\",outcome=\"error\",le=\"0.5\"} 1 # {trace_id=\"trace-1\"} 0.3 "
+        ));
+        assert!(!lines[4].contains('#'));
+        assert_eq!(lines[7], "# HELP hook Hook executions");
+        assert_eq!(lines[8], "# TYPE hook counter");
+        assert!(lines[9].starts_with(
+            "hook_total{hook=\"spam\",outcome=\"error\"} 1 # {trace_id=\"trace-1\"} 1 "
+        ));
+        assert_eq!(lines.last(), Some(&"# EOF"));
+    }
+
+    #[test]
+    fn test_gather_metrics_negotiates_openmetrics() {
+        let counter = IntCounterVec::new(
+            Opts::new("exemplar_scrape_test_total", "Exemplar scrape test"),
+            &["outcome"],
+        )
+        .unwrap();
+        REGISTRY.register(Box::new(counter.clone())).unwrap();
+        counter.with_label_values(&["error"]).inc();
+        EXEMPLARS.record(
+            "exemplar_scrape_test_total",
+            &[("outcome", "error")],
+            1.0,
+            "trace-scrape",
+        );
+
+        let (content_type, body) = gather_metrics_for(Some(
+            "application/openmetrics-text;version=1.0.0,text/plain;version=0.0.4;q=0.5",
+        ));
+        assert_eq!(content_type, OPENMETRICS_CONTENT_TYPE);
+        assert!(body.lines().any(|line| line.starts_with(
+            "exemplar_scrape_test_total{outcome=\"error\"} 1 # {trace_id=\"trace-scrape\"} 1 "
+        )));
+
+        let (content_type, body) = gather_metrics_for(Some("text/plain"));
+        assert_eq!(content_type, TEXT_CONTENT_TYPE);
+        assert!(!body.contains("trace-scrape"));
+        assert_eq!(gather_metrics_for(None).0, TEXT_CONTENT_TYPE);
+    }
+}
@@ -0,0 +1,333 @@
+//! 统一分页原语
+//!
+//! 各查询接口（历史消息、会话搜索、Hook 配置与统计等）共用同一套分页语义，客户端行为一致：
+//! - 游标不透明：`base64url(JSON位置).base64url(签名)`，签名为 `HMAC-SHA256(密钥, "{scope}.{payload}")` 的前16字节；
+//!   客户端只能原样回传，篡改或跨接口复用（`scope` 不同）的游标会被拒绝
+//! - 条数：`limit <= 0` 使用接口默认值，超过上限截断为上限
+//! - `has_more` 为 true 时 `cursor`（下一页游标）非空；`total_size` 为满足条件的总数，无法低成本计算时为 -1
+//!
+//! 签名密钥通过 `FLARE_PAGINATION_SECRET` 配置（多实例需一致），轮换期间将旧密钥配置到
+//! `FLARE_PAGINATION_PREVIOUS_SECRET`，旧密钥签发的游标在轮换完成前仍可使用
+
+use std::sync::OnceLock;
+
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 游标签名密钥环境变量
+pub const PAGINATION_SECRET_ENV: &str = "FLARE_PAGINATION_SECRET";
+/// 轮换中的旧密钥环境变量
+pub const PAGINATION_PREVIOUS_SECRET_ENV: &str = "FLARE_PAGINATION_PREVIOUS_SECRET";
+/// 总数未知时的 `total_size`
+pub const UNKNOWN_TOTAL: i64 = -1;
+
+/// 未配置密钥时的内置密钥（只防误用，不防伪造；生产环境应配置 `FLARE_PAGINATION_SECRET`）
+const DEFAULT_SECRET: &str = "flare-im-pagination-cursor";
+/// 签名截断长度（字节）
+const SIGNATURE_LEN: usize = 16;
+
+/// 分页错误
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum PaginationError {
+    /// 游标格式错误（不是本服务签发的游标）
+    #[error("malformed pagination cursor")]
+    MalformedCursor,
+    /// 签名校验失败（游标被篡改、属于其他接口或密钥已轮换）
+    #[error("invalid pagination cursor signature")]
+    InvalidSignature,
+}
+
+/// 接口的条数限制（默认值 + 上限）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageLimit {
+    /// 未指定条数时的默认值
+    pub default: usize,
+    /// 单页上限
+    pub max: usize,
+}
+
+impl PageLimit {
+    pub const fn new(default: usize, max: usize) -> Self {
+        Self { default, max }
+    }
+
+    /// 规范化请求条数：`<= 0` 使用默认值，超过上限截断为上限
+    pub fn clamp(&self, requested: i64) -> usize {
+        let max = self.max.max(1);
+        if requested <= 0 {
+            self.default.clamp(1, max)
+        } else {
+            usize::try_from(requested).unwrap_or(max).min(max)
+        }
+    }
+}
+
+/// 不透明游标编解码器
+#[derive(Clone)]
+pub struct CursorCodec {
+    secret: Vec<u8>,
+    previous_secret: Option<Vec<u8>>,
+}
+
+impl CursorCodec {
+    /// 使用指定密钥创建编解码器
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secret: secret.into(),
+            previous_secret: None,
+        }
+    }
+
+    /// 设置轮换中的旧密钥（只用于校验，新游标始终使用当前密钥签名）
+    pub fn with_previous_secret(mut self, previous_secret: impl Into<Vec<u8>>) -> Self {
+        let previous_secret = previous_secret.into();
+        if !previous_secret.is_empty() && previous_secret != self.secret {
+            self.previous_secret = Some(previous_secret);
+        }
+        self
+    }
+
+    /// 从环境变量创建编解码器（未配置时使用内置密钥并告警）
+    pub fn from_env() -> Self {
+        let secret = std::env::var(PAGINATION_SECRET_ENV)
+            .ok()
+            .filter(|secret| !secret.is_empty())
+            .unwrap_or_else(|| {
+                tracing::warn!(
+                    "{} is not set, pagination cursors are signed with the built-in key",
+                    PAGINATION_SECRET_ENV
+                );
+                DEFAULT_SECRET.to_string()
+            });
+        let codec = Self::new(secret);
+        match std::env::var(PAGINATION_PREVIOUS_SECRET_ENV) {
+            Ok(previous_secret) => codec.with_previous_secret(previous_secret),
+            Err(_) => codec,
+        }
+    }
+
+    /// 进程内共享的编解码器（首次使用时从环境变量初始化）
+    pub fn global() -> &'static CursorCodec {
+        static CODEC: OnceLock<CursorCodec> = OnceLock::new();
+        CODEC.get_or_init(Self::from_env)
+    }
+
+    /// 将分页位置编码为不透明游标（`scope` 标识接口，防止游标跨接口复用）
+    pub fn encode<T: Serialize>(&self, scope: &str, position: &T) -> String {
+        let payload =
+            BASE64_URL.encode(serde_json::to_vec(position).expect("cursor position serializes"));
+        let signature = self.sign(&self.secret, scope, &payload);
+        format!("{}.{}", payload, BASE64_URL.encode(signature))
+    }
+
+    /// 解码游标（空游标返回 None，表示第一页）
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        scope: &str,
+        cursor: &str,
+    ) -> Result<Option<T>, PaginationError> {
+        if cursor.is_empty() {
+            return Ok(None);
+        }
+        let (payload, signature) = cursor
+            .split_once('.')
+            .ok_or(PaginationError::MalformedCursor)?;
+        let signature = BASE64_URL
+            .decode(signature)
+            .map_err(|_| PaginationError::MalformedCursor)?;
+        let verified = std::iter::once(&self.secret)
+            .chain(self.previous_secret.as_ref())
+            .any(|secret| self.verify(secret, scope, payload, &signature));
+        if !verified {
+            return Err(PaginationError::InvalidSignature);
+        }
+        let payload = BASE64_URL
+            .decode(payload)
+            .map_err(|_| PaginationError::MalformedCursor)?;
+        serde_json::from_slice(&payload)
+            .map(Some)
+            .map_err(|_| PaginationError::MalformedCursor)
+    }
+
+    fn mac(&self, secret: &[u8], scope: &str, payload: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+        mac.update(scope.as_bytes());
+        mac.update(b".");
+        mac.update(payload.as_bytes());
+        mac
+    }
+
+    fn sign(&self, secret: &[u8], scope: &str, payload: &str) -> Vec<u8> {
+        let mut signature = self
+            .mac(secret, scope, payload)
+            .finalize()
+            .into_bytes()
+            .to_vec();
+        signature.truncate(SIGNATURE_LEN);
+        signature
+    }
+
+    fn verify(&self, secret: &[u8], scope: &str, payload: &str, signature: &[u8]) -> bool {
+        signature.len() == SIGNATURE_LEN
+            && self
+                .mac(secret, scope, payload)
+                .verify_truncated_left(signature)
+                .is_ok()
+    }
+}
+
+impl std::fmt::Debug for CursorCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CursorCodec")
+            .field("rotating", &self.previous_secret.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// 一页结果的分页信息
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageInfo {
+    /// 下一页游标（没有下一页时为空）
+    pub next_cursor: String,
+    /// 是否还有下一页
+    pub has_more: bool,
+    /// 满足条件的总数（无法低成本计算时为 None）
+    pub total: Option<i64>,
+}
+
+impl PageInfo {
+    /// 偏移量分页：`offset` 为本页起始位置，`returned` 为本页条数，`total` 为总数
+    ///
+    /// 下一页游标编码下一页的起始偏移量
+    pub fn from_offset(
+        codec: &CursorCodec,
+        scope: &str,
+        offset: u64,
+        returned: usize,
+        total: u64,
+    ) -> Self {
+        let next_offset = offset + returned as u64;
+        let has_more = returned > 0 && next_offset < total;
+        Self {
+            next_cursor: if has_more {
+                codec.encode(scope, &next_offset)
+            } else {
+                String::new()
+            },
+            has_more,
+            total: Some(total as i64),
+        }
+    }
+
+    /// 多取一条判断是否还有下一页：`fetched` 为按 `limit + 1` 查询的结果，超出部分被截断，
+    /// 下一页游标由本页最后一条生成
+    pub fn from_overfetch<T, P: Serialize>(
+        codec: &CursorCodec,
+        scope: &str,
+        fetched: &mut Vec<T>,
+        limit: usize,
+        position: impl FnOnce(&T) -> P,
+    ) -> Self {
+        let has_more = fetched.len() > limit;
+        fetched.truncate(limit);
+        let next_cursor = match fetched.last() {
+            Some(last) if has_more => codec.encode(scope, &position(last)),
+            _ => String::new(),
+        };
+        Self {
+            has_more: !next_cursor.is_empty(),
+            next_cursor,
+            total: None,
+        }
+    }
+
+    /// 写入响应分页信息（`request_cursor` 为本页请求使用的游标，作为 `previous_cursor` 返回）
+    pub fn apply_to(
+        self,
+        pagination: &mut flare_proto::common::Pagination,
+        request_cursor: String,
+        limit: usize,
+    ) {
+        pagination.previous_cursor = request_cursor;
+        pagination.cursor = self.next_cursor;
+        pagination.has_more = self.has_more;
+        pagination.limit = limit as i32;
+        pagination.total_size = self.total.unwrap_or(UNKNOWN_TOTAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_limit_clamp() {
+        let limit = PageLimit::new(20, 100);
+        assert_eq!(limit.clamp(0), 20);
+        assert_eq!(limit.clamp(-5), 20);
+        assert_eq!(limit.clamp(50), 50);
+        assert_eq!(limit.clamp(5000), 100);
+        // 默认值超过上限时以上限为准
+        assert_eq!(PageLimit::new(500, 100).clamp(0), 100);
+    }
+
+    #[test]
+    fn test_cursor_round_trip_and_tamper() {
+        let codec = CursorCodec::new("secret");
+        let cursor = codec.encode("hook.configs", &42u64);
+        assert_eq!(codec.decode::<u64>("hook.configs", &cursor), Ok(Some(42)));
+        assert_eq!(codec.decode::<u64>("hook.configs", ""), Ok(None));
+
+        // 跨接口复用、篡改、其他密钥签发的游标均被拒绝
+        assert_eq!(
+            codec.decode::<u64>("conversation.search", &cursor),
+            Err(PaginationError::InvalidSignature)
+        );
+        let forged = format!(
+            "{}{}",
+            BASE64_URL.encode(b"43"),
+            &cursor[cursor.find('.').unwrap()..]
+        );
+        assert_eq!(
+            codec.decode::<u64>("hook.configs", &forged),
+            Err(PaginationError::InvalidSignature)
+        );
+        assert_eq!(
+            CursorCodec::new("other").decode::<u64>("hook.configs", &cursor),
+            Err(PaginationError::InvalidSignature)
+        );
+        assert_eq!(
+            codec.decode::<u64>("hook.configs", "100"),
+            Err(PaginationError::MalformedCursor)
+        );
+
+        // 轮换期间旧密钥签发的游标仍可使用
+        let rotated = CursorCodec::new("new").with_previous_secret("secret");
+        assert_eq!(rotated.decode::<u64>("hook.configs", &cursor), Ok(Some(42)));
+    }
+
+    #[test]
+    fn test_page_info() {
+        let codec = CursorCodec::new("secret");
+        let page = PageInfo::from_offset(&codec, "scope", 20, 10, 35);
+        assert!(page.has_more);
+        assert_eq!(
+            codec.decode::<u64>("scope", &page.next_cursor),
+            Ok(Some(30))
+        );
+        assert_eq!(page.total, Some(35));
+        assert!(!PageInfo::from_offset(&codec, "scope", 30, 5, 35).has_more);
+
+        let mut fetched = vec![1, 2, 3];
+        let page = PageInfo::from_overfetch(&codec, "scope", &mut fetched, 2, |last| *last);
+        assert_eq!(fetched, vec![1, 2]);
+        assert!(page.has_more);
+        assert_eq!(codec.decode::<i32>("scope", &page.next_cursor), Ok(Some(2)));
+        assert_eq!(page.total, None);
+    }
+}
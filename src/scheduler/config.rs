@@ -0,0 +1,40 @@
+//! 延迟任务调度器配置
+
+use serde::{Deserialize, Serialize};
+
+/// 调度器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    /// Redis URL
+    pub redis_url: String,
+    /// 命名空间（用于隔离不同服务的任务队列）
+    pub namespace: String,
+    /// 到期任务轮询间隔（毫秒）
+    pub poll_interval_ms: u64,
+    /// 任务租约时长（毫秒），租约过期未完成的任务会被重新投递
+    pub lease_ms: u64,
+    /// 每次拉取的最大任务数
+    pub batch_size: usize,
+    /// 默认最大尝试次数（超过后进入死信队列）
+    pub max_attempts: u32,
+    /// 重试退避基数（毫秒），按指数增长
+    pub retry_backoff_ms: u64,
+    /// 重试退避上限（毫秒）
+    pub max_retry_backoff_ms: u64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            redis_url: "redis://127.0.0.1/".to_string(),
+            namespace: "default".to_string(),
+            poll_interval_ms: 500,
+            lease_ms: 30_000, // 30秒
+            batch_size: 100,
+            max_attempts: 5,
+            retry_backoff_ms: 1_000,
+            max_retry_backoff_ms: 300_000, // 5分钟
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! 延迟任务定义
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// 延迟任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    /// 任务ID（全局唯一，重复调度同一ID会覆盖原任务）
+    pub job_id: String,
+    /// 任务类型（用于路由到已注册的处理器，如 `burn_after_read`、`retention_purge`）
+    pub job_type: String,
+    /// 任务负载
+    pub payload: serde_json::Value,
+    /// 计划执行时间（毫秒时间戳）
+    pub run_at: i64,
+    /// 已尝试次数
+    #[serde(default)]
+    pub attempts: u32,
+    /// 最大尝试次数（None 表示使用调度器默认值）
+    #[serde(default)]
+    pub max_attempts: Option<u32>,
+    /// 最近一次失败原因
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// 本次领取的租约令牌（由 `JobStore::claim_due` 设置，不持久化）
+    #[serde(skip)]
+    pub lease_token: Option<String>,
+}
+
+impl ScheduledJob {
+    pub fn new(
+        job_id: impl Into<String>,
+        job_type: impl Into<String>,
+        payload: serde_json::Value,
+        run_at: i64,
+    ) -> Self {
+        Self {
+            job_id: job_id.into(),
+            job_type: job_type.into(),
+            payload,
+            run_at,
+            attempts: 0,
+            max_attempts: None,
+            last_error: None,
+            lease_token: None,
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// 计算下一次重试的延迟（毫秒），指数退避并受上限约束
+    pub fn retry_delay_ms(&self, base_ms: u64, max_ms: u64) -> u64 {
+        let exponent = self.attempts.saturating_sub(1).min(20);
+        base_ms.saturating_mul(1u64 << exponent).min(max_ms)
+    }
+
+    /// 是否已耗尽重试次数
+    pub fn is_exhausted(&self, default_max_attempts: u32) -> bool {
+        self.attempts >= self.max_attempts.unwrap_or(default_max_attempts)
+    }
+
+    /// 领取前已耗尽重试次数（此前的尝试均因租约过期未完成，如处理中进程崩溃）
+    pub fn exceeded_attempts(&self, default_max_attempts: u32) -> bool {
+        self.attempts > self.max_attempts.unwrap_or(default_max_attempts)
+    }
+}
+
+/// 任务处理器
+///
+/// 处理器需要保证幂等：调度器提供至少一次（at-least-once）语义，
+/// 租约过期或进程崩溃时同一任务可能被重复投递。
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    async fn handle(&self, job: &ScheduledJob) -> anyhow::Result<()>;
+}
+
+/// 任务存储
+///
+/// 调度器只依赖该 trait；内置 Redis 实现（`RedisJobStore`，需启用 `redis` feature），
+/// 嵌入式场景可注入自定义实现（`TaskScheduler::with_store`）。
+///
+/// 领取、完成、重试与死信需满足租约语义：领取时持久化尝试次数并签发租约令牌，
+/// 完成/重试/死信只在任务仍由该令牌持有时生效（租约过期被回收后，原处理结果丢弃）。
+#[async_trait]
+pub trait JobStore: Send + Sync {
+    /// 保存任务并加入到期队列（同ID任务会被覆盖，尝试次数以 `job.attempts` 为准）
+    async fn schedule(&self, job: &ScheduledJob) -> anyhow::Result<()>;
+
+    /// 取消任务，返回任务是否存在
+    async fn cancel(&self, job_id: &str) -> anyhow::Result<bool>;
+
+    /// 领取到期任务并加租约：尝试次数加一并持久化，返回的任务携带租约令牌
+    async fn claim_due(
+        &self,
+        now_ms: i64,
+        lease_ms: u64,
+        limit: usize,
+    ) -> anyhow::Result<Vec<ScheduledJob>>;
+
+    /// 回收租约过期的任务（作废租约令牌），返回回收数量
+    async fn reclaim_expired(&self, now_ms: i64, limit: usize) -> anyhow::Result<u64>;
+
+    /// 标记任务完成，返回是否仍持有租约（未持有时不做任何修改）
+    async fn complete(&self, job: &ScheduledJob) -> anyhow::Result<bool>;
+
+    /// 失败任务按 `job.run_at` 重新加入到期队列，返回是否仍持有租约
+    async fn retry(&self, job: &ScheduledJob) -> anyhow::Result<bool>;
+
+    /// 任务进入死信队列，返回是否仍持有租约
+    async fn dead_letter(&self, job: &ScheduledJob) -> anyhow::Result<bool>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        let mut job = ScheduledJob::new("job-1", "test", serde_json::Value::Null, 0);
+
+        job.attempts = 1;
+        assert_eq!(job.retry_delay_ms(1_000, 60_000), 1_000);
+        job.attempts = 2;
+        assert_eq!(job.retry_delay_ms(1_000, 60_000), 2_000);
+        job.attempts = 4;
+        assert_eq!(job.retry_delay_ms(1_000, 60_000), 8_000);
+        job.attempts = 30;
+        assert_eq!(job.retry_delay_ms(1_000, 60_000), 60_000);
+    }
+
+    #[test]
+    fn test_is_exhausted() {
+        let mut job = ScheduledJob::new("job-1", "test", serde_json::Value::Null, 0);
+        job.attempts = 3;
+        assert!(!job.is_exhausted(5));
+        assert!(job.is_exhausted(3));
+
+        let job = job.with_max_attempts(10);
+        assert!(!job.is_exhausted(3));
+        assert!(!job.exceeded_attempts(3));
+    }
+
+    #[test]
+    fn test_lease_token_not_persisted() {
+        let mut job = ScheduledJob::new("job-1", "test", serde_json::Value::Null, 0);
+        job.lease_token = Some("lease-1".to_string());
+        let decoded: ScheduledJob =
+            serde_json::from_str(&serde_json::to_string(&job).unwrap()).unwrap();
+        assert_eq!(decoded.lease_token, None);
+    }
+}
@@ -0,0 +1,16 @@
+//! 延迟任务调度模块
+//! 基于 Redis ZSET 持久化的延迟任务调度器，提供至少一次（at-least-once）执行语义
+//!
+//! 适用场景：阅后即焚、消息保留期清理、定时提醒等需要跨进程重启保留的延迟任务
+
+pub mod config;
+pub mod job;
+#[cfg(feature = "redis")]
+pub mod redis_store;
+pub mod service;
+
+pub use config::SchedulerConfig;
+pub use job::{JobHandler, JobStore, ScheduledJob};
+#[cfg(feature = "redis")]
+pub use redis_store::RedisJobStore;
+pub use service::TaskScheduler;
@@ -0,0 +1,332 @@
+//! 延迟任务Redis存储
+//! 基于 ZSET 实现到期队列与租约队列，任务内容存储在 HASH 中
+//!
+//! Redis键结构：
+//! - `scheduler:{ns}:due`    ZSET，score 为计划执行时间（毫秒）
+//! - `scheduler:{ns}:leases` ZSET，score 为租约过期时间（毫秒）
+//! - `scheduler:{ns}:jobs`   HASH，job_id -> 任务JSON
+//! - `scheduler:{ns}:attempts` HASH，job_id -> 尝试次数（领取时原子加一）
+//! - `scheduler:{ns}:owners` HASH，job_id -> 当前租约令牌
+//! - `scheduler:{ns}:dead`   LIST，超过最大尝试次数的任务JSON
+
+use async_trait::async_trait;
+use redis::{AsyncCommands, Client, RedisError, RedisResult, Script};
+
+use crate::scheduler::job::{JobStore, ScheduledJob};
+
+/// 原子地领取到期任务：从到期队列移动到租约队列，尝试次数加一并记录租约令牌
+///
+/// 返回 `[任务JSON, 尝试次数, ...]`
+const CLAIM_DUE_SCRIPT: &str = r#"
+local ids = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[1], 'LIMIT', 0, ARGV[3])
+local result = {}
+for _, id in ipairs(ids) do
+    redis.call('ZREM', KEYS[1], id)
+    local job = redis.call('HGET', KEYS[3], id)
+    if job then
+        redis.call('ZADD', KEYS[2], ARGV[2], id)
+        redis.call('HSET', KEYS[5], id, ARGV[4])
+        local attempts = redis.call('HINCRBY', KEYS[4], id, 1)
+        table.insert(result, job)
+        table.insert(result, tostring(attempts))
+    end
+end
+return result
+"#;
+
+/// 原子地回收租约过期的任务：从租约队列移回到期队列，作废租约令牌
+const RECLAIM_EXPIRED_SCRIPT: &str = r#"
+local ids = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[1], 'LIMIT', 0, ARGV[2])
+for _, id in ipairs(ids) do
+    redis.call('ZREM', KEYS[1], id)
+    redis.call('HDEL', KEYS[3], id)
+    redis.call('ZADD', KEYS[2], ARGV[1], id)
+end
+return #ids
+"#;
+
+/// 仍持有租约时完成任务
+const COMPLETE_SCRIPT: &str = r#"
+if redis.call('HGET', KEYS[4], ARGV[1]) ~= ARGV[2] then
+    return 0
+end
+redis.call('ZREM', KEYS[1], ARGV[1])
+redis.call('HDEL', KEYS[2], ARGV[1])
+redis.call('HDEL', KEYS[3], ARGV[1])
+redis.call('HDEL', KEYS[4], ARGV[1])
+return 1
+"#;
+
+/// 仍持有租约时重新加入到期队列
+const RETRY_SCRIPT: &str = r#"
+if redis.call('HGET', KEYS[4], ARGV[1]) ~= ARGV[2] then
+    return 0
+end
+redis.call('ZREM', KEYS[1], ARGV[1])
+redis.call('HDEL', KEYS[4], ARGV[1])
+redis.call('HSET', KEYS[2], ARGV[1], ARGV[3])
+redis.call('ZADD', KEYS[3], ARGV[4], ARGV[1])
+return 1
+"#;
+
+/// 仍持有租约时移入死信队列
+const DEAD_LETTER_SCRIPT: &str = r#"
+if redis.call('HGET', KEYS[4], ARGV[1]) ~= ARGV[2] then
+    return 0
+end
+redis.call('ZREM', KEYS[1], ARGV[1])
+redis.call('HDEL', KEYS[2], ARGV[1])
+redis.call('HDEL', KEYS[3], ARGV[1])
+redis.call('HDEL', KEYS[4], ARGV[1])
+redis.call('LPUSH', KEYS[5], ARGV[3])
+return 1
+"#;
+
+/// 延迟任务Redis存储
+pub struct RedisJobStore {
+    /// Redis客户端
+    client: Client,
+    due_key: String,
+    leases_key: String,
+    jobs_key: String,
+    attempts_key: String,
+    owners_key: String,
+    dead_key: String,
+}
+
+impl RedisJobStore {
+    /// 创建新的任务存储
+    pub fn new(redis_url: &str, namespace: &str) -> RedisResult<Self> {
+        let client = Client::open(redis_url)?;
+        Ok(Self {
+            client,
+            due_key: format!("scheduler:{}:due", namespace),
+            leases_key: format!("scheduler:{}:leases", namespace),
+            jobs_key: format!("scheduler:{}:jobs", namespace),
+            attempts_key: format!("scheduler:{}:attempts", namespace),
+            owners_key: format!("scheduler:{}:owners", namespace),
+            dead_key: format!("scheduler:{}:dead", namespace),
+        })
+    }
+
+    /// 保存任务并加入到期队列（同ID任务会被覆盖）
+    pub async fn schedule(&self, job: &ScheduledJob) -> RedisResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let value = encode_job(job)?;
+
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .hset(&self.jobs_key, &job.job_id, value)
+            .ignore()
+            .hset(&self.attempts_key, &job.job_id, job.attempts)
+            .ignore()
+            .hdel(&self.owners_key, &job.job_id)
+            .ignore()
+            .zrem(&self.leases_key, &job.job_id)
+            .ignore()
+            .zadd(&self.due_key, &job.job_id, job.run_at)
+            .ignore();
+        let _: () = pipe.query_async(&mut conn).await?;
+        Ok(())
+    }
+
+    /// 取消任务，返回任务是否存在
+    pub async fn cancel(&self, job_id: &str) -> RedisResult<bool> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .zrem(&self.due_key, job_id)
+            .ignore()
+            .zrem(&self.leases_key, job_id)
+            .ignore()
+            .hdel(&self.attempts_key, job_id)
+            .ignore()
+            .hdel(&self.owners_key, job_id)
+            .ignore()
+            .hdel(&self.jobs_key, job_id);
+        let (removed,): (i64,) = pipe.query_async(&mut conn).await?;
+        Ok(removed > 0)
+    }
+
+    /// 查询任务
+    pub async fn get(&self, job_id: &str) -> RedisResult<Option<ScheduledJob>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let value: Option<String> = conn.hget(&self.jobs_key, job_id).await?;
+        value.map(|data| decode_job(&data)).transpose()
+    }
+
+    /// 领取到期任务并为其加上租约（尝试次数加一，返回的任务携带租约令牌）
+    ///
+    /// # 参数
+    /// - `now_ms`: 当前时间（毫秒）
+    /// - `lease_ms`: 租约时长（毫秒）
+    /// - `limit`: 最多领取的任务数
+    pub async fn claim_due(
+        &self,
+        now_ms: i64,
+        lease_ms: u64,
+        limit: usize,
+    ) -> RedisResult<Vec<ScheduledJob>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let lease_token = uuid::Uuid::new_v4().to_string();
+        let values: Vec<String> = Script::new(CLAIM_DUE_SCRIPT)
+            .key(&self.due_key)
+            .key(&self.leases_key)
+            .key(&self.jobs_key)
+            .key(&self.attempts_key)
+            .key(&self.owners_key)
+            .arg(now_ms)
+            .arg(now_ms + lease_ms as i64)
+            .arg(limit)
+            .arg(&lease_token)
+            .invoke_async(&mut conn)
+            .await?;
+
+        let mut jobs = Vec::with_capacity(values.len() / 2);
+        for pair in values.chunks_exact(2) {
+            match decode_job(&pair[0]) {
+                Ok(mut job) => {
+                    job.attempts = pair[1].parse().unwrap_or(job.attempts);
+                    job.lease_token = Some(lease_token.clone());
+                    jobs.push(job);
+                }
+                Err(e) => {
+                    // 解析失败，记录错误但继续处理其他任务
+                    tracing::warn!(error = %e, "Failed to deserialize scheduled job from Redis");
+                }
+            }
+        }
+        Ok(jobs)
+    }
+
+    /// 回收租约过期的任务，返回回收数量
+    pub async fn reclaim_expired(&self, now_ms: i64, limit: usize) -> RedisResult<u64> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        Script::new(RECLAIM_EXPIRED_SCRIPT)
+            .key(&self.leases_key)
+            .key(&self.due_key)
+            .key(&self.owners_key)
+            .arg(now_ms)
+            .arg(limit)
+            .invoke_async(&mut conn)
+            .await
+    }
+
+    /// 标记任务完成（释放租约并删除任务），返回是否仍持有租约
+    pub async fn complete(&self, job: &ScheduledJob) -> RedisResult<bool> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let completed: i64 = Script::new(COMPLETE_SCRIPT)
+            .key(&self.leases_key)
+            .key(&self.jobs_key)
+            .key(&self.attempts_key)
+            .key(&self.owners_key)
+            .arg(&job.job_id)
+            .arg(job.lease_token.as_deref().unwrap_or_default())
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(completed == 1)
+    }
+
+    /// 失败任务按 `job.run_at` 重新加入到期队列，返回是否仍持有租约
+    pub async fn retry(&self, job: &ScheduledJob) -> RedisResult<bool> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let retried: i64 = Script::new(RETRY_SCRIPT)
+            .key(&self.leases_key)
+            .key(&self.jobs_key)
+            .key(&self.due_key)
+            .key(&self.owners_key)
+            .arg(&job.job_id)
+            .arg(job.lease_token.as_deref().unwrap_or_default())
+            .arg(encode_job(job)?)
+            .arg(job.run_at)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(retried == 1)
+    }
+
+    /// 将任务移入死信队列，返回是否仍持有租约
+    pub async fn dead_letter(&self, job: &ScheduledJob) -> RedisResult<bool> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let moved: i64 = Script::new(DEAD_LETTER_SCRIPT)
+            .key(&self.leases_key)
+            .key(&self.jobs_key)
+            .key(&self.attempts_key)
+            .key(&self.owners_key)
+            .key(&self.dead_key)
+            .arg(&job.job_id)
+            .arg(job.lease_token.as_deref().unwrap_or_default())
+            .arg(encode_job(job)?)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(moved == 1)
+    }
+
+    /// 查询待执行任务数量
+    pub async fn pending_count(&self) -> RedisResult<u64> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        conn.zcard(&self.due_key).await
+    }
+
+    /// 查询死信任务数量
+    pub async fn dead_letter_count(&self) -> RedisResult<u64> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        conn.llen(&self.dead_key).await
+    }
+}
+
+#[async_trait]
+impl JobStore for RedisJobStore {
+    async fn schedule(&self, job: &ScheduledJob) -> anyhow::Result<()> {
+        Ok(RedisJobStore::schedule(self, job).await?)
+    }
+
+    async fn cancel(&self, job_id: &str) -> anyhow::Result<bool> {
+        Ok(RedisJobStore::cancel(self, job_id).await?)
+    }
+
+    async fn claim_due(
+        &self,
+        now_ms: i64,
+        lease_ms: u64,
+        limit: usize,
+    ) -> anyhow::Result<Vec<ScheduledJob>> {
+        Ok(RedisJobStore::claim_due(self, now_ms, lease_ms, limit).await?)
+    }
+
+    async fn reclaim_expired(&self, now_ms: i64, limit: usize) -> anyhow::Result<u64> {
+        Ok(RedisJobStore::reclaim_expired(self, now_ms, limit).await?)
+    }
+
+    async fn complete(&self, job: &ScheduledJob) -> anyhow::Result<bool> {
+        Ok(RedisJobStore::complete(self, job).await?)
+    }
+
+    async fn retry(&self, job: &ScheduledJob) -> anyhow::Result<bool> {
+        Ok(RedisJobStore::retry(self, job).await?)
+    }
+
+    async fn dead_letter(&self, job: &ScheduledJob) -> anyhow::Result<bool> {
+        Ok(RedisJobStore::dead_letter(self, job).await?)
+    }
+}
+
+fn encode_job(job: &ScheduledJob) -> RedisResult<String> {
+    serde_json::to_string(job).map_err(|e| {
+        RedisError::from((
+            redis::ErrorKind::TypeError,
+            "JSON serialization error",
+            e.to_string(),
+        ))
+    })
+}
+
+fn decode_job(data: &str) -> RedisResult<ScheduledJob> {
+    serde_json::from_str(data).map_err(|e| {
+        RedisError::from((
+            redis::ErrorKind::TypeError,
+            "JSON deserialization error",
+            e.to_string(),
+        ))
+    })
+}
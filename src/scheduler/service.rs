@@ -0,0 +1,482 @@
+//! 延迟任务调度服务
+//! 核心功能：任务注册、到期轮询、租约回收、失败重试与死信
+
+use crate::scheduler::config::SchedulerConfig;
+use crate::scheduler::job::{JobHandler, JobStore, ScheduledJob};
+#[cfg(feature = "redis")]
+use crate::scheduler::redis_store::RedisJobStore;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{RwLock, watch};
+use tokio::time::interval;
+use tracing::{debug, error, info, warn};
+
+/// 延迟任务调度器
+///
+/// 任务持久化在 Redis 中，进程重启后不会丢失；多个实例可共享同一命名空间，
+/// 通过租约保证同一时刻只有一个实例处理某个任务。
+pub struct TaskScheduler {
+    /// 任务存储
+    store: Arc<dyn JobStore>,
+    /// 任务处理器（job_type -> handler）
+    handlers: Arc<RwLock<HashMap<String, Arc<dyn JobHandler>>>>,
+    /// 配置
+    config: SchedulerConfig,
+    /// 停机信号（置为 true 后轮询循环退出）
+    shutdown: watch::Sender<bool>,
+}
+
+impl TaskScheduler {
+    /// 创建新的调度器（不会自动启动轮询，需调用 `start`）
+    #[cfg(feature = "redis")]
+    pub fn new(config: SchedulerConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let store = Arc::new(RedisJobStore::new(&config.redis_url, &config.namespace)?);
+        Ok(Self::with_store(store, config))
+    }
+
+    /// 使用自定义任务存储创建调度器
+    pub fn with_store(store: Arc<dyn JobStore>, config: SchedulerConfig) -> Self {
+        Self {
+            store,
+            handlers: Arc::new(RwLock::new(HashMap::new())),
+            config,
+            shutdown: watch::channel(false).0,
+        }
+    }
+
+    /// 注册任务处理器
+    pub async fn register_handler(&self, job_type: impl Into<String>, handler: Arc<dyn JobHandler>) {
+        let job_type = job_type.into();
+        info!(job_type = %job_type, "Registered scheduled job handler");
+        self.handlers.write().await.insert(job_type, handler);
+    }
+
+    /// 在指定时间（毫秒时间戳）执行任务
+    pub async fn schedule_at(
+        &self,
+        job_id: impl Into<String>,
+        job_type: impl Into<String>,
+        payload: serde_json::Value,
+        run_at: i64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let job = ScheduledJob::new(job_id, job_type, payload, run_at);
+        self.schedule(job).await
+    }
+
+    /// 在指定延迟后执行任务
+    pub async fn schedule_after(
+        &self,
+        job_id: impl Into<String>,
+        job_type: impl Into<String>,
+        payload: serde_json::Value,
+        delay: Duration,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let run_at = now_ms() + delay.as_millis() as i64;
+        self.schedule_at(job_id, job_type, payload, run_at).await
+    }
+
+    /// 调度任务（同ID任务会被覆盖）
+    pub async fn schedule(&self, job: ScheduledJob) -> Result<(), Box<dyn std::error::Error>> {
+        self.store.schedule(&job).await?;
+        debug!(job_id = %job.job_id, job_type = %job.job_type, run_at = job.run_at, "Scheduled job");
+        Ok(())
+    }
+
+    /// 取消任务，返回任务是否存在
+    pub async fn cancel(&self, job_id: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(self.store.cancel(job_id).await?)
+    }
+
+    /// 获取任务存储
+    pub fn store(&self) -> Arc<dyn JobStore> {
+        self.store.clone()
+    }
+
+    /// 启动后台轮询任务（调用 `shutdown` 后退出）
+    pub fn start(&self) -> tokio::task::JoinHandle<()> {
+        let store = self.store.clone();
+        let handlers = self.handlers.clone();
+        let config = self.config.clone();
+        let interval_duration = Duration::from_millis(config.poll_interval_ms.max(1));
+        let mut shutdown = self.shutdown.subscribe();
+
+        tokio::spawn(async move {
+            let mut interval = interval(interval_duration);
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = wait_for_shutdown(&mut shutdown) => {
+                        info!("Scheduler polling stopped");
+                        break;
+                    }
+                }
+
+                let now = now_ms();
+
+                // 回收租约过期的任务（实例崩溃或处理超时）
+                match store.reclaim_expired(now, config.batch_size).await {
+                    Ok(0) => {}
+                    Ok(count) => warn!(count, "Reclaimed scheduled jobs with expired lease"),
+                    Err(e) => error!(error = %e, "Failed to reclaim expired scheduled jobs"),
+                }
+
+                let jobs = match store
+                    .claim_due(now, config.lease_ms, config.batch_size)
+                    .await
+                {
+                    Ok(jobs) => jobs,
+                    Err(e) => {
+                        error!(error = %e, "Failed to claim due scheduled jobs");
+                        continue;
+                    }
+                };
+
+                for job in jobs {
+                    let handler = handlers.read().await.get(&job.job_type).cloned();
+                    let store = store.clone();
+                    let config = config.clone();
+
+                    tokio::spawn(async move {
+                        run_job(store, handler, job, &config).await;
+                    });
+                }
+            }
+        })
+    }
+
+    /// 停止轮询：不再领取新任务，已领取的任务继续执行完成
+    pub fn shutdown(&self) {
+        self.shutdown.send_replace(true);
+    }
+}
+
+/// 等待停机信号（调度器被释放但未调用 `shutdown` 时保持轮询）
+async fn wait_for_shutdown(shutdown: &mut watch::Receiver<bool>) {
+    loop {
+        if *shutdown.borrow_and_update() {
+            return;
+        }
+        if shutdown.changed().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+/// 执行单个任务并根据结果完成、重试或进入死信队列
+///
+/// 尝试次数已在领取时持久化；处理超过租约时长视为失败，避免租约过期后与其他实例重复执行。
+/// 完成、重试与死信只在仍持有租约时生效，租约已被回收时丢弃本次结果
+async fn run_job(
+    store: Arc<dyn JobStore>,
+    handler: Option<Arc<dyn JobHandler>>,
+    mut job: ScheduledJob,
+    config: &SchedulerConfig,
+) {
+    // 此前的尝试均未完成（如处理中进程崩溃）且已耗尽次数，不再执行
+    if job.exceeded_attempts(config.max_attempts) {
+        error!(
+            job_id = %job.job_id,
+            job_type = %job.job_type,
+            attempts = job.attempts,
+            "Scheduled job exceeded max attempts after lease expiry, moving to dead letter"
+        );
+        job.last_error
+            .get_or_insert_with(|| "lease expired before completion".to_string());
+        dead_letter(store.as_ref(), &job).await;
+        return;
+    }
+
+    let result = match handler {
+        Some(handler) => {
+            let lease = Duration::from_millis(config.lease_ms);
+            match tokio::time::timeout(lease, handler.handle(&job)).await {
+                Ok(result) => result,
+                Err(_) => Err(anyhow::anyhow!(
+                    "job handler timed out after {}ms (lease duration)",
+                    config.lease_ms
+                )),
+            }
+        }
+        None => Err(anyhow::anyhow!(
+            "no handler registered for job type: {}",
+            job.job_type
+        )),
+    };
+
+    match result {
+        Ok(()) => match store.complete(&job).await {
+            Ok(true) => {}
+            Ok(false) => warn_lease_lost(&job, "complete"),
+            Err(e) => error!(job_id = %job.job_id, error = %e, "Failed to complete scheduled job"),
+        },
+        Err(err) => {
+            job.last_error = Some(err.to_string());
+
+            if job.is_exhausted(config.max_attempts) {
+                error!(
+                    job_id = %job.job_id,
+                    job_type = %job.job_type,
+                    attempts = job.attempts,
+                    error = %err,
+                    "Scheduled job exhausted retries, moving to dead letter"
+                );
+                dead_letter(store.as_ref(), &job).await;
+                return;
+            }
+
+            let delay = job.retry_delay_ms(config.retry_backoff_ms, config.max_retry_backoff_ms);
+            job.run_at = now_ms() + delay as i64;
+            warn!(
+                job_id = %job.job_id,
+                job_type = %job.job_type,
+                attempts = job.attempts,
+                retry_in_ms = delay,
+                error = %err,
+                "Scheduled job failed, will retry"
+            );
+            match store.retry(&job).await {
+                Ok(true) => {}
+                Ok(false) => warn_lease_lost(&job, "retry"),
+                Err(e) => error!(job_id = %job.job_id, error = %e, "Failed to reschedule job"),
+            }
+        }
+    }
+}
+
+async fn dead_letter(store: &dyn JobStore, job: &ScheduledJob) {
+    match store.dead_letter(job).await {
+        Ok(true) => {}
+        Ok(false) => warn_lease_lost(job, "dead_letter"),
+        Err(e) => error!(job_id = %job.job_id, error = %e, "Failed to dead-letter scheduled job"),
+    }
+}
+
+/// 租约已被回收（任务由其他实例重新领取），丢弃本次处理结果
+fn warn_lease_lost(job: &ScheduledJob, action: &str) {
+    warn!(
+        job_id = %job.job_id,
+        job_type = %job.job_type,
+        action,
+        "Scheduled job lease lost, result discarded"
+    );
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// 内存任务存储，`revoke` 模拟租约过期后被其他实例回收
+    #[derive(Default)]
+    struct MemoryJobStore {
+        jobs: Mutex<HashMap<String, ScheduledJob>>,
+        completed: Mutex<Vec<String>>,
+    }
+
+    impl MemoryJobStore {
+        fn revoke(&self, job_id: &str) {
+            if let Some(job) = self.jobs.lock().unwrap().get_mut(job_id) {
+                job.lease_token = None;
+            }
+        }
+
+        fn stored(&self, job_id: &str) -> Option<ScheduledJob> {
+            self.jobs.lock().unwrap().get(job_id).cloned()
+        }
+
+        fn release(&self, job: &ScheduledJob, requeue: bool) -> bool {
+            let mut jobs = self.jobs.lock().unwrap();
+            let held = jobs.get(&job.job_id).is_some_and(|stored| {
+                stored.lease_token.is_some() && stored.lease_token == job.lease_token
+            });
+            if !held {
+                return false;
+            }
+            if requeue {
+                let mut job = job.clone();
+                job.lease_token = None;
+                jobs.insert(job.job_id.clone(), job);
+            } else {
+                jobs.remove(&job.job_id);
+            }
+            true
+        }
+    }
+
+    #[async_trait]
+    impl JobStore for MemoryJobStore {
+        async fn schedule(&self, job: &ScheduledJob) -> anyhow::Result<()> {
+            self.jobs
+                .lock()
+                .unwrap()
+                .insert(job.job_id.clone(), job.clone());
+            Ok(())
+        }
+
+        async fn cancel(&self, job_id: &str) -> anyhow::Result<bool> {
+            Ok(self.jobs.lock().unwrap().remove(job_id).is_some())
+        }
+
+        async fn claim_due(
+            &self,
+            now_ms: i64,
+            _lease_ms: u64,
+            limit: usize,
+        ) -> anyhow::Result<Vec<ScheduledJob>> {
+            Ok(self
+                .jobs
+                .lock()
+                .unwrap()
+                .values_mut()
+                .filter(|job| job.run_at <= now_ms && job.lease_token.is_none())
+                .take(limit)
+                .map(|job| {
+                    job.attempts += 1;
+                    job.lease_token = Some(format!("lease-{}", job.attempts));
+                    job.clone()
+                })
+                .collect())
+        }
+
+        async fn reclaim_expired(&self, _now_ms: i64, _limit: usize) -> anyhow::Result<u64> {
+            Ok(0)
+        }
+
+        async fn complete(&self, job: &ScheduledJob) -> anyhow::Result<bool> {
+            let held = self.release(job, false);
+            if held {
+                self.completed.lock().unwrap().push(job.job_id.clone());
+            }
+            Ok(held)
+        }
+
+        async fn retry(&self, job: &ScheduledJob) -> anyhow::Result<bool> {
+            Ok(self.release(job, true))
+        }
+
+        async fn dead_letter(&self, job: &ScheduledJob) -> anyhow::Result<bool> {
+            Ok(self.release(job, false))
+        }
+    }
+
+    /// 处理过程中租约被回收的处理器
+    struct LeaseLosingHandler {
+        store: Arc<MemoryJobStore>,
+    }
+
+    #[async_trait]
+    impl JobHandler for LeaseLosingHandler {
+        async fn handle(&self, job: &ScheduledJob) -> anyhow::Result<()> {
+            self.store.revoke(&job.job_id);
+            Ok(())
+        }
+    }
+
+    struct OkHandler;
+
+    #[async_trait]
+    impl JobHandler for OkHandler {
+        async fn handle(&self, _job: &ScheduledJob) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// 处理时长超过租约的处理器
+    struct SlowHandler;
+
+    #[async_trait]
+    impl JobHandler for SlowHandler {
+        async fn handle(&self, _job: &ScheduledJob) -> anyhow::Result<()> {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        }
+    }
+
+    async fn claim_one(store: &MemoryJobStore) -> ScheduledJob {
+        let job = ScheduledJob::new("job-1", "test", serde_json::Value::Null, 0);
+        store.schedule(&job).await.unwrap();
+        store.claim_due(1, 0, 10).await.unwrap().remove(0)
+    }
+
+    #[tokio::test]
+    async fn test_lease_lost_mid_run_discards_completion() {
+        let store = Arc::new(MemoryJobStore::default());
+        let job = claim_one(&store).await;
+        let handler: Arc<dyn JobHandler> = Arc::new(LeaseLosingHandler {
+            store: store.clone(),
+        });
+        let config = SchedulerConfig::default();
+
+        run_job(store.clone(), Some(handler), job, &config).await;
+
+        // 未标记完成，任务保留且不再持有租约，可由其他实例重新领取
+        assert!(store.completed.lock().unwrap().is_empty());
+        assert_eq!(store.stored("job-1").unwrap().lease_token, None);
+        let redelivered = store.claim_due(1, 0, 10).await.unwrap();
+        assert_eq!(redelivered[0].attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_handler_timeout_releases_lease_for_retry() {
+        let store = Arc::new(MemoryJobStore::default());
+        let job = claim_one(&store).await;
+        let config = SchedulerConfig {
+            lease_ms: 20,
+            ..Default::default()
+        };
+
+        let handler: Arc<dyn JobHandler> = Arc::new(SlowHandler);
+
+        run_job(store.clone(), Some(handler), job, &config).await;
+
+        assert!(store.completed.lock().unwrap().is_empty());
+        let stored = store.stored("job-1").unwrap();
+        assert_eq!(stored.lease_token, None);
+        assert!(stored.last_error.unwrap().contains("timed out"));
+        assert!(stored.run_at > 0);
+    }
+
+    #[tokio::test]
+    async fn test_zero_poll_interval_runs_jobs_and_stops_on_shutdown() {
+        let store = Arc::new(MemoryJobStore::default());
+        let scheduler = TaskScheduler::with_store(
+            store.clone(),
+            SchedulerConfig {
+                poll_interval_ms: 0,
+                ..Default::default()
+            },
+        );
+        scheduler
+            .register_handler("test", Arc::new(OkHandler))
+            .await;
+        scheduler
+            .schedule_at("job-1", "test", serde_json::Value::Null, 0)
+            .await
+            .unwrap();
+
+        let handle = scheduler.start();
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while store.completed.lock().unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        scheduler.shutdown();
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
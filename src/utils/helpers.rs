@@ -14,27 +14,12 @@ impl ServiceHelper {
     ///
     /// # 参数
     /// * `config_path` - 配置路径
-    /// * `strict` - 是否严格验证配置引用
+    /// * `strict` - 是否严格验证（引用、地址格式与端口冲突）
     ///
     /// # 返回
     /// 返回加载的配置实例
     pub fn load_config(config_path: Option<&str>, strict: bool) -> Result<&'static FlareAppConfig> {
-        let config = crate::config::load_config(config_path);
-
-        // 使用 guard clause 减少嵌套
-        if strict {
-            config
-                .validate_references()
-                .with_context(|| "configuration validation failed")?;
-            return Ok(config);
-        }
-
-        // 非严格模式下，即使验证失败也继续运行，只记录警告日志
-        if let Err(e) = config.validate_references() {
-            tracing::warn!("configuration reference validation failed: {}", e);
-        }
-
-        Ok(config)
+        crate::config::load_config_with_validation(config_path, strict)
     }
 
     /// 从服务配置中解析服务器地址
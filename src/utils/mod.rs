@@ -4,6 +4,7 @@
 
 pub mod context;
 pub mod helpers;
+pub mod translation;
 
 pub use helpers::ServiceHelper;
 
@@ -246,6 +247,80 @@ pub fn embed_seq_in_message(message: &mut flare_proto::common::Message, seq: i64
     message.extra.insert("seq".to_string(), seq.to_string());
 }
 
+/// 消息 extra 中记录发送设备的键（多端同步时用于抑制回显）
+pub const ORIGIN_DEVICE_ID_KEY: &str = "origin_device_id";
+
+/// 从消息的 extra 字段中提取发送设备ID
+///
+/// # 示例
+/// ```
+/// use flare_im_core::utils::{embed_origin_device_id, extract_origin_device_id};
+/// use flare_proto::common::Message;
+///
+/// let mut message = Message::default();
+/// embed_origin_device_id(&mut message, "device-a");
+///
+/// assert_eq!(extract_origin_device_id(&message), Some("device-a"));
+/// ```
+pub fn extract_origin_device_id(message: &flare_proto::common::Message) -> Option<&str> {
+    message
+        .extra
+        .get(ORIGIN_DEVICE_ID_KEY)
+        .map(String::as_str)
+        .filter(|device_id| !device_id.is_empty())
+}
+
+/// 将发送设备ID嵌入到消息的 extra 字段中（已存在时保留原值，空设备ID忽略）
+pub fn embed_origin_device_id(message: &mut flare_proto::common::Message, device_id: &str) {
+    if device_id.is_empty() {
+        return;
+    }
+    message
+        .extra
+        .entry(ORIGIN_DEVICE_ID_KEY.to_string())
+        .or_insert_with(|| device_id.to_string());
+}
+
+/// 同步写入回执频道的标签键（`StoreMessageRequest.tags`）
+///
+/// `sync = true` 时由编排服务设置为本实例订阅的 Redis 频道，Storage Writer 持久化后向该频道发布 [`PersistenceReply`]
+pub const SYNC_REPLY_CHANNEL_TAG: &str = "sync_reply_channel";
+
+/// 同步写入回执（JSON 编码）
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PersistenceReply {
+    pub message_id: String,
+    pub conversation_id: String,
+    pub seq: i64,
+    /// 持久化时间（毫秒）
+    pub persisted_ts: i64,
+    /// 是否为重复消息（已持久化过，未重复写入）
+    pub deduplicated: bool,
+}
+
+/// 判断推送目标是否为消息的发送设备（推送给发送设备即为回显）
+///
+/// # 示例
+/// ```
+/// use flare_im_core::utils::{embed_origin_device_id, is_origin_device};
+/// use flare_proto::common::Message;
+///
+/// let mut message = Message::default();
+/// message.sender_id = "alice".to_string();
+/// embed_origin_device_id(&mut message, "device-a");
+///
+/// assert!(is_origin_device(&message, "alice", "device-a"));
+/// assert!(!is_origin_device(&message, "alice", "device-b"));
+/// assert!(!is_origin_device(&message, "bob", "device-a"));
+/// ```
+pub fn is_origin_device(
+    message: &flare_proto::common::Message,
+    user_id: &str,
+    device_id: &str,
+) -> bool {
+    message.sender_id == user_id && extract_origin_device_id(message) == Some(device_id)
+}
+
 /// 从消息的 extra 字段中提取 seq（从 HashMap 直接提取）
 ///
 /// # 参数
@@ -0,0 +1,224 @@
+//! 消息翻译变体
+//!
+//! 多语言会话中，翻译 Hook 生成的译文以结构化 JSON 存放在消息属性 `translation:<locale>` 中：
+//!
+//! ```json
+//! {"text": "Hello", "source_locale": "zh-CN", "provider": "deepl", "translated_at": 1700000000000}
+//! ```
+//!
+//! 会话服务负责生成与写入（通过 Reader 的 `SetMessageAttributes`），Reader 按请求上下文中的
+//! `locale` 属性（缺省为设备语言）只返回匹配的译文，并在 `extra.translation_locale` 中标明实际匹配的语言。
+//! Reader 启用消息内容加密时，译文属性同样加密存储（密文绑定到租户、消息与属性键）。
+
+use std::collections::HashMap;
+
+use flare_proto::common::RequestContext;
+use serde::{Deserialize, Serialize};
+
+/// 译文属性键前缀
+pub const TRANSLATION_ATTRIBUTE_PREFIX: &str = "translation:";
+
+/// 请求上下文中指定目标语言的属性键（缺省使用设备语言）
+pub const LOCALE_ATTRIBUTE: &str = "locale";
+
+/// Reader 返回消息时在 `extra` 中标明匹配译文语言的字段
+pub const TRANSLATION_LOCALE_EXTRA: &str = "translation_locale";
+
+/// 单个语言的译文
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageTranslation {
+    pub text: String,
+    /// 原文语言（翻译服务识别，可选）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_locale: Option<String>,
+    /// 翻译服务标识（可选）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// 翻译时间（毫秒）
+    #[serde(default)]
+    pub translated_at: i64,
+}
+
+impl MessageTranslation {
+    /// 属性值（JSON）
+    pub fn to_attribute_value(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// 解析属性值，格式错误时返回 None
+    pub fn from_attribute_value(value: &str) -> Option<Self> {
+        serde_json::from_str(value).ok()
+    }
+}
+
+/// 规范化语言标签：`zh_cn` → `zh-CN`，`EN` → `en`
+pub fn normalize_locale(locale: &str) -> Option<String> {
+    let mut parts = locale
+        .trim()
+        .split(['-', '_'])
+        .filter(|part| !part.is_empty());
+    let language = parts.next()?;
+    if !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let mut normalized = language.to_ascii_lowercase();
+    for part in parts {
+        if !part.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        normalized.push('-');
+        match part.len() {
+            // 地区（CN / US）大写，文字（Hans）首字母大写
+            2 => normalized.push_str(&part.to_ascii_uppercase()),
+            4 => {
+                normalized.push_str(&part[..1].to_ascii_uppercase());
+                normalized.push_str(&part[1..].to_ascii_lowercase());
+            }
+            _ => normalized.push_str(part),
+        }
+    }
+    Some(normalized)
+}
+
+/// 译文属性键
+pub fn translation_attribute_key(locale: &str) -> String {
+    let locale = normalize_locale(locale).unwrap_or_else(|| locale.to_string());
+    format!("{TRANSLATION_ATTRIBUTE_PREFIX}{locale}")
+}
+
+/// 请求的目标语言：上下文属性 `locale` 优先，其次为设备语言
+pub fn requested_locale(context: &RequestContext) -> Option<String> {
+    context
+        .attributes
+        .get(LOCALE_ATTRIBUTE)
+        .filter(|locale| !locale.trim().is_empty())
+        .or_else(|| {
+            context
+                .device
+                .as_ref()
+                .map(|device| &device.locale)
+                .filter(|locale| !locale.trim().is_empty())
+        })
+        .and_then(|locale| normalize_locale(locale))
+}
+
+/// 选择与目标语言匹配的译文语言：完全匹配优先，其次语言相同（`zh-TW` 可匹配 `zh`）
+pub fn select_translation_locale(
+    attributes: &HashMap<String, String>,
+    requested: &str,
+) -> Option<String> {
+    let requested = normalize_locale(requested)?;
+    let language = requested.split('-').next().unwrap_or_default();
+
+    let mut fallback: Option<String> = None;
+    for key in attributes.keys() {
+        let Some(locale) = key
+            .strip_prefix(TRANSLATION_ATTRIBUTE_PREFIX)
+            .and_then(normalize_locale)
+        else {
+            continue;
+        };
+        if locale == requested {
+            return Some(key[TRANSLATION_ATTRIBUTE_PREFIX.len()..].to_string());
+        }
+        if locale.split('-').next() == Some(language) {
+            // 多个同语言译文时取最短（最通用）的，保证结果稳定
+            let candidate = key[TRANSLATION_ATTRIBUTE_PREFIX.len()..].to_string();
+            if fallback
+                .as_ref()
+                .is_none_or(|current| (candidate.len(), &candidate) < (current.len(), current))
+            {
+                fallback = Some(candidate);
+            }
+        }
+    }
+    fallback
+}
+
+/// 只保留与目标语言匹配的译文属性，返回匹配的语言（没有匹配时移除所有译文）
+pub fn retain_translation(
+    attributes: &mut HashMap<String, String>,
+    requested: &str,
+) -> Option<String> {
+    let selected = select_translation_locale(attributes, requested);
+    let keep = selected
+        .as_ref()
+        .map(|locale| format!("{TRANSLATION_ATTRIBUTE_PREFIX}{locale}"));
+    attributes.retain(|key, _| {
+        !key.starts_with(TRANSLATION_ATTRIBUTE_PREFIX) || Some(key) == keep.as_ref()
+    });
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(locales: &[&str]) -> HashMap<String, String> {
+        let mut attributes: HashMap<String, String> = locales
+            .iter()
+            .map(|locale| {
+                let translation = MessageTranslation {
+                    text: format!("text-{locale}"),
+                    source_locale: None,
+                    provider: None,
+                    translated_at: 0,
+                };
+                (
+                    format!("{TRANSLATION_ATTRIBUTE_PREFIX}{locale}"),
+                    translation.to_attribute_value(),
+                )
+            })
+            .collect();
+        attributes.insert("pinned".to_string(), "true".to_string());
+        attributes
+    }
+
+    #[test]
+    fn test_locale_normalization() {
+        assert_eq!(normalize_locale("zh_cn").as_deref(), Some("zh-CN"));
+        assert_eq!(
+            normalize_locale("zh-hans-cn").as_deref(),
+            Some("zh-Hans-CN")
+        );
+        assert_eq!(normalize_locale(" EN ").as_deref(), Some("en"));
+        assert_eq!(normalize_locale(""), None);
+        assert_eq!(normalize_locale("e n"), None);
+        assert_eq!(translation_attribute_key("pt_br"), "translation:pt-BR");
+
+        let mut context = RequestContext::default();
+        assert_eq!(requested_locale(&context), None);
+        context
+            .attributes
+            .insert(LOCALE_ATTRIBUTE.to_string(), "ja_jp".to_string());
+        assert_eq!(requested_locale(&context).as_deref(), Some("ja-JP"));
+    }
+
+    #[test]
+    fn test_retain_matching_translation() {
+        let all = attributes(&["en", "zh-CN", "zh-TW"]);
+        assert_eq!(
+            select_translation_locale(&all, "zh_tw").as_deref(),
+            Some("zh-TW")
+        );
+        assert_eq!(
+            select_translation_locale(&all, "en-GB").as_deref(),
+            Some("en")
+        );
+        assert_eq!(select_translation_locale(&all, "fr"), None);
+
+        let mut localized = all.clone();
+        assert_eq!(
+            retain_translation(&mut localized, "zh-CN").as_deref(),
+            Some("zh-CN")
+        );
+        assert_eq!(localized.len(), 2);
+        let translation =
+            MessageTranslation::from_attribute_value(&localized["translation:zh-CN"]).unwrap();
+        assert_eq!(translation.text, "text-zh-CN");
+
+        let mut untranslated = all;
+        assert_eq!(retain_translation(&mut untranslated, "fr"), None);
+        assert_eq!(untranslated.keys().collect::<Vec<_>>(), vec!["pinned"]);
+    }
+}